@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use bastion_core::job_spec::FsAnomalyDetection;
+use serde::{Deserialize, Serialize};
+
+/// Per-job copies of the last entries index live under `<data_dir>/anomaly/`.
+const BASELINE_DIR_NAME: &str = "anomaly";
+
+const MAX_ANOMALY_SAMPLES: usize = 20;
+/// Bytes read from the start of each sampled file.
+const ENTROPY_SAMPLE_BYTES: u64 = 64 * 1024;
+/// Shannon entropy (bits per byte) above which content looks encrypted.
+const HIGH_ENTROPY_BITS: f64 = 7.5;
+/// The entropy heuristic needs at least this many readable samples to trip.
+const MIN_ENTROPY_SAMPLES: u64 = 10;
+
+/// Extensions commonly appended by ransomware to encrypted files.
+const SUSPICIOUS_EXTENSIONS: &[&str] = &[
+    "encrypted",
+    "locked",
+    "crypt",
+    "crypted",
+    "crypto",
+    "cry",
+    "locky",
+    "cerber",
+    "wncry",
+    "wnry",
+    "zepto",
+    "odin",
+    "ryk",
+    "ransom",
+];
+
+/// Formats that are compressed or encrypted by design and would skew the entropy check.
+const HIGH_ENTROPY_EXTENSIONS: &[&str] = &[
+    "7z", "age", "avi", "br", "bz2", "docx", "gif", "gpg", "gz", "heic", "jpeg", "jpg", "mkv",
+    "mov", "mp3", "mp4", "ogg", "pdf", "png", "pptx", "rar", "tgz", "webm", "webp", "xlsx", "xz",
+    "zip", "zst",
+];
+
+/// Outcome of comparing a snapshot with the previous one built for the same job.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FsAnomalyReport {
+    /// Whether any heuristic exceeded its threshold.
+    pub triggered: bool,
+    /// Names of the heuristics that tripped: `mass_change`, `suspicious_extensions`, `entropy`.
+    pub findings: Vec<String>,
+    pub previous_files: u64,
+    pub modified_files: u64,
+    pub removed_files: u64,
+    pub added_files: u64,
+    pub suspicious_extension_files: u64,
+    pub entropy_sampled_files: u64,
+    pub high_entropy_files: u64,
+    pub sample_suspicious: Vec<String>,
+    pub sample_high_entropy: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct IndexEntry {
+    path: String,
+    kind: String,
+    size: u64,
+    #[serde(default)]
+    hash: Option<String>,
+}
+
+fn baseline_path(data_dir: &Path, job_id: &str) -> PathBuf {
+    data_dir
+        .join(BASELINE_DIR_NAME)
+        .join(format!("{job_id}.jsonl.zst"))
+}
+
+fn for_each_file_entry(
+    entries_path: &Path,
+    mut f: impl FnMut(IndexEntry),
+) -> Result<(), anyhow::Error> {
+    let decoder = zstd::Decoder::new(File::open(entries_path)?)?;
+    for line in BufReader::new(decoder).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: IndexEntry = serde_json::from_str(&line)?;
+        if entry.kind == "file" {
+            f(entry);
+        }
+    }
+    Ok(())
+}
+
+fn extension_of(path: &str) -> Option<String> {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let (stem, ext) = name.rsplit_once('.')?;
+    (!stem.is_empty() && !ext.is_empty()).then(|| ext.to_ascii_lowercase())
+}
+
+/// Shannon entropy of `data` in bits per byte.
+fn shannon_entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0u64; 256];
+    for b in data {
+        counts[*b as usize] += 1;
+    }
+    let len = data.len() as f64;
+    counts
+        .iter()
+        .filter(|c| **c > 0)
+        .map(|c| {
+            let p = *c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+fn sample_entropy(path: &Path) -> Option<f64> {
+    let mut buf = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(ENTROPY_SAMPLE_BYTES)
+        .read_to_end(&mut buf)
+        .ok()?;
+    (!buf.is_empty()).then(|| shannon_entropy(&buf))
+}
+
+fn push_sample(samples: &mut Vec<String>, path: &str) {
+    if samples.len() < MAX_ANOMALY_SAMPLES {
+        samples.push(path.to_string());
+    }
+}
+
+/// Compares the new entries index with the job's baseline, then makes it the new baseline.
+///
+/// Returns `None` when the job has no baseline yet. `resolve_source_path` maps an archive path
+/// back to the file on disk for entropy sampling; unreadable files are not counted.
+pub(super) fn check_and_update_baseline(
+    data_dir: &Path,
+    job_id: &str,
+    entries_path: &Path,
+    settings: &FsAnomalyDetection,
+    resolve_source_path: impl Fn(&str) -> Option<PathBuf>,
+) -> Result<Option<FsAnomalyReport>, anyhow::Error> {
+    let baseline = baseline_path(data_dir, job_id);
+    let report = if baseline.is_file() {
+        Some(compare(
+            &baseline,
+            entries_path,
+            settings,
+            resolve_source_path,
+        )?)
+    } else {
+        None
+    };
+
+    if let Some(parent) = baseline.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = baseline.with_extension("zst.partial");
+    std::fs::copy(entries_path, &tmp)?;
+    std::fs::rename(&tmp, &baseline)?;
+
+    Ok(report)
+}
+
+fn compare(
+    baseline: &Path,
+    entries_path: &Path,
+    settings: &FsAnomalyDetection,
+    resolve_source_path: impl Fn(&str) -> Option<PathBuf>,
+) -> Result<FsAnomalyReport, anyhow::Error> {
+    let mut previous = HashMap::<String, (u64, Option<String>)>::new();
+    for_each_file_entry(baseline, |entry| {
+        previous.insert(entry.path, (entry.size, entry.hash));
+    })?;
+
+    let mut report = FsAnomalyReport {
+        previous_files: previous.len() as u64,
+        ..Default::default()
+    };
+    let mut entropy_candidates = Vec::<String>::new();
+    for_each_file_entry(entries_path, |entry| {
+        let changed = match previous.remove(&entry.path) {
+            Some((size, hash)) => {
+                let modified = size != entry.size || (hash.is_some() && hash != entry.hash);
+                if modified {
+                    report.modified_files += 1;
+                }
+                modified
+            }
+            None => {
+                report.added_files += 1;
+                true
+            }
+        };
+        if !changed {
+            return;
+        }
+
+        let ext = extension_of(&entry.path);
+        let suspicious = ext
+            .as_deref()
+            .is_some_and(|ext| SUSPICIOUS_EXTENSIONS.contains(&ext));
+        if suspicious {
+            report.suspicious_extension_files += 1;
+            push_sample(&mut report.sample_suspicious, &entry.path);
+        }
+        let compressed = ext
+            .as_deref()
+            .is_some_and(|ext| HIGH_ENTROPY_EXTENSIONS.contains(&ext));
+        if !compressed
+            && entry.size > 0
+            && (entropy_candidates.len() as u64) < settings.entropy_sample_files
+        {
+            entropy_candidates.push(entry.path);
+        }
+    })?;
+    report.removed_files = previous.len() as u64;
+
+    for path in &entropy_candidates {
+        let Some(entropy) = resolve_source_path(path).and_then(|p| sample_entropy(&p)) else {
+            continue;
+        };
+        report.entropy_sampled_files += 1;
+        if entropy >= HIGH_ENTROPY_BITS {
+            report.high_entropy_files += 1;
+            push_sample(&mut report.sample_high_entropy, path);
+        }
+    }
+
+    let changed = report.modified_files + report.removed_files;
+    if report.previous_files >= settings.min_files
+        && report.previous_files > 0
+        && changed * 100 >= u64::from(settings.changed_percent) * report.previous_files
+    {
+        report.findings.push("mass_change".to_string());
+    }
+    if settings.suspicious_extension_files > 0
+        && report.suspicious_extension_files >= settings.suspicious_extension_files
+    {
+        report.findings.push("suspicious_extensions".to_string());
+    }
+    if report.entropy_sampled_files >= MIN_ENTROPY_SAMPLES
+        && report.high_entropy_files * 100
+            >= u64::from(settings.high_entropy_percent) * report.entropy_sampled_files
+    {
+        report.findings.push("entropy".to_string());
+    }
+    report.triggered = !report.findings.is_empty();
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::Path;
+
+    use bastion_core::job_spec::FsAnomalyDetection;
+    use tempfile::tempdir;
+
+    use super::{check_and_update_baseline, shannon_entropy};
+
+    fn random_bytes(seed: &[u8]) -> Vec<u8> {
+        let mut buf = vec![0u8; 65536];
+        blake3::Hasher::new()
+            .update(seed)
+            .finalize_xof()
+            .fill(&mut buf);
+        buf
+    }
+
+    fn write_index(path: &Path, files: &[(&str, u64, &str)]) {
+        let mut enc = zstd::Encoder::new(std::fs::File::create(path).unwrap(), 3).unwrap();
+        for (p, size, hash) in files {
+            let line = serde_json::json!({
+                "path": p, "kind": "file", "size": size, "hash_alg": "blake3", "hash": hash,
+            });
+            writeln!(enc, "{line}").unwrap();
+        }
+        enc.finish().unwrap();
+    }
+
+    #[test]
+    fn shannon_entropy_separates_text_from_random_bytes() {
+        assert!(shannon_entropy(b"aaaaaaaaaaaaaaaa") < 0.01);
+        assert!(shannon_entropy(&random_bytes(b"seed")) > 7.5);
+    }
+
+    #[test]
+    fn detects_mass_change_and_suspicious_extensions_against_baseline() {
+        let tmp = tempdir().unwrap();
+        let data_dir = tmp.path().join("data");
+        let job_id = "job1";
+        let settings = FsAnomalyDetection {
+            min_files: 4,
+            suspicious_extension_files: 2,
+            entropy_sample_files: 0,
+            ..Default::default()
+        };
+
+        let first = tmp.path().join("first.jsonl.zst");
+        write_index(
+            &first,
+            &[
+                ("a.txt", 1, "h1"),
+                ("b.txt", 1, "h2"),
+                ("c.txt", 1, "h3"),
+                ("d.txt", 1, "h4"),
+            ],
+        );
+        let report =
+            check_and_update_baseline(&data_dir, job_id, &first, &settings, |_| None).unwrap();
+        assert!(report.is_none(), "first snapshot only records a baseline");
+
+        let second = tmp.path().join("second.jsonl.zst");
+        write_index(
+            &second,
+            &[
+                ("a.txt", 1, "h1"),
+                ("b.txt", 1, "changed"),
+                ("c.txt.encrypted", 1, "x"),
+                ("d.txt.encrypted", 1, "y"),
+            ],
+        );
+        let report = check_and_update_baseline(&data_dir, job_id, &second, &settings, |_| None)
+            .unwrap()
+            .expect("report");
+        assert_eq!(report.previous_files, 4);
+        assert_eq!(report.modified_files, 1);
+        assert_eq!(report.removed_files, 2);
+        assert_eq!(report.added_files, 2);
+        assert_eq!(report.suspicious_extension_files, 2);
+        assert!(report.triggered);
+        assert_eq!(
+            report.findings,
+            vec!["mass_change", "suspicious_extensions"]
+        );
+
+        // The second snapshot is now the baseline, so repeating it is quiet.
+        let report = check_and_update_baseline(&data_dir, job_id, &second, &settings, |_| None)
+            .unwrap()
+            .expect("report");
+        assert!(!report.triggered);
+        assert_eq!(report.modified_files + report.added_files, 0);
+    }
+
+    #[test]
+    fn entropy_check_samples_changed_files_from_source() {
+        let tmp = tempdir().unwrap();
+        let data_dir = tmp.path().join("data");
+        let src = tmp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        let settings = FsAnomalyDetection::default();
+
+        let names: Vec<String> = (0..10).map(|i| format!("doc{i}.txt")).collect();
+        let before: Vec<(&str, u64, &str)> = names.iter().map(|n| (n.as_str(), 1, "old")).collect();
+        let after: Vec<(&str, u64, &str)> = names.iter().map(|n| (n.as_str(), 2, "new")).collect();
+        for name in &names {
+            std::fs::write(src.join(name), random_bytes(name.as_bytes())).unwrap();
+        }
+
+        let first = tmp.path().join("first.jsonl.zst");
+        let second = tmp.path().join("second.jsonl.zst");
+        write_index(&first, &before);
+        write_index(&second, &after);
+        check_and_update_baseline(&data_dir, "job", &first, &settings, |_| None).unwrap();
+        let report =
+            check_and_update_baseline(&data_dir, "job", &second, &settings, |p| Some(src.join(p)))
+                .unwrap()
+                .expect("report");
+        assert_eq!(report.entropy_sampled_files, 10);
+        assert_eq!(report.high_entropy_files, 10);
+        assert_eq!(report.findings, vec!["entropy"]);
+    }
+}
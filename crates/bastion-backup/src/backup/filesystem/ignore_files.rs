@@ -0,0 +1,195 @@
+use std::path::Path;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use super::FilesystemBuildIssues;
+
+pub(super) const IGNORE_FILE_NAME: &str = ".bastionignore";
+
+/// `.bastionignore` files found while walking a source tree.
+///
+/// Each file uses `.gitignore` syntax and applies to the directory that holds it and everything
+/// below; deeper files take precedence, so `!pattern` can re-include what a parent excluded.
+pub(super) struct IgnoreFiles {
+    enabled: bool,
+    /// Whether parse warnings and applied files are reported (pre-scan walks the tree again).
+    report: bool,
+    /// Matchers of the directories on the current walk path, outermost first.
+    stack: Vec<Gitignore>,
+    applied: Vec<String>,
+}
+
+impl IgnoreFiles {
+    pub(super) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            report: true,
+            stack: Vec::new(),
+            applied: Vec::new(),
+        }
+    }
+
+    /// Same rules as [`IgnoreFiles::new`], without reporting anything.
+    pub(super) fn for_scan(enabled: bool) -> Self {
+        Self {
+            report: false,
+            ..Self::new(enabled)
+        }
+    }
+
+    /// Ignore files that were loaded, in walk order.
+    pub(super) fn into_applied(self) -> Vec<String> {
+        self.applied
+    }
+
+    /// Starts a new walk at `root`, loading its ignore file if it is a directory.
+    pub(super) fn enter_root(&mut self, root: &Path, issues: &mut FilesystemBuildIssues) {
+        self.stack.clear();
+        if self.enabled && root.is_dir() {
+            self.load_dir(root, issues);
+        }
+    }
+
+    /// Returns whether a walked entry is ignored; kept directories have their ignore file loaded
+    /// before the walk descends into them.
+    pub(super) fn skip_entry(
+        &mut self,
+        entry: &walkdir::DirEntry,
+        issues: &mut FilesystemBuildIssues,
+    ) -> bool {
+        if !self.enabled || entry.depth() == 0 {
+            return false;
+        }
+
+        let path = entry.path();
+        let is_dir = entry.file_type().is_dir();
+        while self
+            .stack
+            .last()
+            .is_some_and(|top| !path.starts_with(top.path()))
+        {
+            self.stack.pop();
+        }
+
+        for matcher in self.stack.iter().rev() {
+            let m = matcher.matched(path, is_dir);
+            if m.is_ignore() {
+                return true;
+            }
+            if m.is_whitelist() {
+                break;
+            }
+        }
+
+        if is_dir {
+            self.load_dir(path, issues);
+        }
+        false
+    }
+
+    fn load_dir(&mut self, dir: &Path, issues: &mut FilesystemBuildIssues) {
+        let file = dir.join(IGNORE_FILE_NAME);
+        if !file.is_file() {
+            return;
+        }
+
+        let mut builder = GitignoreBuilder::new(dir);
+        if let Some(error) = builder.add(&file)
+            && self.report
+        {
+            issues.record_warning(format!("ignore file error: {}: {error}", file.display()));
+        }
+        match builder.build() {
+            Ok(matcher) => {
+                if matcher.is_empty() {
+                    return;
+                }
+                self.stack.push(matcher);
+                if self.report {
+                    self.applied.push(file.display().to_string());
+                }
+            }
+            Err(error) => {
+                if self.report {
+                    issues
+                        .record_warning(format!("ignore file error: {}: {error}", file.display()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+    use walkdir::WalkDir;
+
+    use super::super::FilesystemBuildIssues;
+    use super::{IGNORE_FILE_NAME, IgnoreFiles};
+
+    #[test]
+    fn nested_ignore_files_apply_to_their_subtree() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join("build")).unwrap();
+        std::fs::create_dir_all(root.join("app/logs")).unwrap();
+        std::fs::write(root.join(IGNORE_FILE_NAME), "build/\n*.log\n").unwrap();
+        std::fs::write(root.join("app").join(IGNORE_FILE_NAME), "!keep.log\n").unwrap();
+        for f in [
+            "build/out.bin",
+            "a.log",
+            "app/keep.log",
+            "app/x.log",
+            "app/logs/y.txt",
+        ] {
+            std::fs::write(root.join(f), b"x").unwrap();
+        }
+
+        let mut issues = FilesystemBuildIssues::default();
+        let mut ignore_files = IgnoreFiles::new(true);
+        ignore_files.enter_root(root, &mut issues);
+
+        let mut kept = Vec::new();
+        let mut iter = WalkDir::new(root).sort_by_file_name().into_iter();
+        while let Some(entry) = iter.next() {
+            let entry = entry.unwrap();
+            if ignore_files.skip_entry(&entry, &mut issues) {
+                if entry.file_type().is_dir() {
+                    iter.skip_current_dir();
+                }
+                continue;
+            }
+            if entry.file_type().is_file() {
+                let rel = entry.path().strip_prefix(root).unwrap();
+                kept.push(rel.to_string_lossy().replace('\\', "/"));
+            }
+        }
+
+        assert_eq!(
+            kept,
+            vec![
+                ".bastionignore",
+                "app/.bastionignore",
+                "app/keep.log",
+                "app/logs/y.txt",
+            ]
+        );
+        assert_eq!(issues.warnings_total, 0);
+        assert_eq!(ignore_files.into_applied().len(), 2);
+    }
+
+    #[test]
+    fn disabled_ignore_files_keep_everything() {
+        let tmp = tempdir().unwrap();
+        std::fs::write(tmp.path().join(IGNORE_FILE_NAME), "*\n").unwrap();
+        std::fs::write(tmp.path().join("a.txt"), b"x").unwrap();
+
+        let mut issues = FilesystemBuildIssues::default();
+        let mut ignore_files = IgnoreFiles::new(false);
+        ignore_files.enter_root(tmp.path(), &mut issues);
+        for entry in WalkDir::new(tmp.path()) {
+            assert!(!ignore_files.skip_entry(&entry.unwrap(), &mut issues));
+        }
+        assert!(ignore_files.into_applied().is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use std::fs::OpenOptions;
-use std::io::BufWriter;
+use std::io::{BufWriter, Read};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
@@ -13,13 +13,19 @@ use uuid::Uuid;
 use crate::backup::source_consistency::{SourceConsistencyReportV2, SourceConsistencyTracker};
 use crate::backup::{
     BuildPipelineOptions, COMPLETE_NAME, ENTRIES_INDEX_NAME, LocalArtifact, LocalRunArtifacts,
-    MANIFEST_NAME, PayloadEncryption, stage_dir,
+    MANIFEST_NAME, PartStream, PayloadEncryption, stage_dir,
 };
 use bastion_core::job_spec::FilesystemSource;
 
+pub use anomaly::FsAnomalyReport;
+pub use system_state::SystemStateReport;
+
+mod anomaly;
 mod entries_index;
+mod ignore_files;
 mod raw_tree;
 pub mod source_snapshot;
+mod system_state;
 mod tar;
 mod util;
 
@@ -44,6 +50,9 @@ struct FilesystemBuildProgressCtx<'a> {
     stage: &'static str,
     done: ProgressUnitsV1,
     total: Option<ProgressUnitsV1>,
+    /// Bytes read so far from the file currently being archived; folded into `done` once the
+    /// entry is recorded.
+    in_flight_bytes: u64,
     last_emit: Instant,
     on_progress: &'a dyn Fn(FilesystemBuildProgressUpdate),
 }
@@ -58,6 +67,7 @@ impl<'a> FilesystemBuildProgressCtx<'a> {
             stage,
             done: ProgressUnitsV1::default(),
             total,
+            in_flight_bytes: 0,
             last_emit: Instant::now(),
             on_progress,
         }
@@ -70,12 +80,26 @@ impl<'a> FilesystemBuildProgressCtx<'a> {
         self.last_emit = Instant::now();
         (self.on_progress)(FilesystemBuildProgressUpdate {
             stage: self.stage,
-            done: self.done,
+            done: ProgressUnitsV1 {
+                bytes: self.done.bytes.saturating_add(self.in_flight_bytes),
+                ..self.done
+            },
             total: self.total,
         });
     }
 
+    fn record_file_bytes(&mut self, bytes: u64) {
+        self.in_flight_bytes = self.in_flight_bytes.saturating_add(bytes);
+        self.maybe_emit(false);
+    }
+
+    /// Drops the partial byte count of a file that failed before its entry was recorded.
+    fn discard_file_bytes(&mut self) {
+        self.in_flight_bytes = 0;
+    }
+
     fn record_entry(&mut self, kind: &str, size: u64) {
+        self.in_flight_bytes = 0;
         if kind == "dir" {
             self.done.dirs = self.done.dirs.saturating_add(1);
             self.maybe_emit(false);
@@ -88,6 +112,34 @@ impl<'a> FilesystemBuildProgressCtx<'a> {
     }
 }
 
+/// Reports bytes as they are read, so a single huge file still moves the progress bar.
+struct ProgressReader<'p, 'a, R> {
+    inner: R,
+    progress: Option<&'p mut FilesystemBuildProgressCtx<'a>>,
+}
+
+impl<'p, 'a, R> ProgressReader<'p, 'a, R> {
+    fn new(inner: R, progress: Option<&'p mut FilesystemBuildProgressCtx<'a>>) -> Self {
+        Self { inner, progress }
+    }
+
+    fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ProgressReader<'_, '_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if n > 0
+            && let Some(progress) = self.progress.as_mut()
+        {
+            progress.record_file_bytes(n as u64);
+        }
+        Ok(n)
+    }
+}
+
 fn reborrow_progress<'p, 'a>(
     progress: &'p mut Option<&mut FilesystemBuildProgressCtx<'a>>,
 ) -> Option<&'p mut FilesystemBuildProgressCtx<'a>> {
@@ -104,16 +156,20 @@ pub struct FilesystemBuildIssues {
 
 impl FilesystemBuildIssues {
     fn record_warning(&mut self, msg: impl Into<String>) {
+        let msg = msg.into();
+        super::task_log::line(format!("warning: {msg}"));
         self.warnings_total = self.warnings_total.saturating_add(1);
         if self.sample_warnings.len() < MAX_FS_ISSUE_SAMPLES {
-            self.sample_warnings.push(msg.into());
+            self.sample_warnings.push(msg);
         }
     }
 
     fn record_error(&mut self, msg: impl Into<String>) {
+        let msg = msg.into();
+        super::task_log::line(format!("error: {msg}"));
         self.errors_total = self.errors_total.saturating_add(1);
         if self.sample_errors.len() < MAX_FS_ISSUE_SAMPLES {
-            self.sample_errors.push(msg.into());
+            self.sample_errors.push(msg);
         }
     }
 }
@@ -125,6 +181,13 @@ pub struct FilesystemRunBuild {
     pub consistency: SourceConsistencyReportV2,
     pub source_total: Option<ProgressUnitsV1>,
     pub raw_tree_stats: Option<RawTreeBuildStats>,
+    /// `.bastionignore` files applied while packaging.
+    pub ignore_files: Vec<String>,
+    /// Comparison with the previous snapshot, when anomaly detection is enabled and a baseline
+    /// exists.
+    pub anomaly: Option<FsAnomalyReport>,
+    /// Windows system state stored under `bastion-system-state/`, when requested and captured.
+    pub system_state: Option<SystemStateReport>,
 }
 
 #[derive(Debug, Clone)]
@@ -164,11 +227,14 @@ pub fn build_filesystem_run(
     on_progress: Option<&dyn Fn(FilesystemBuildProgressUpdate)>,
     on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> std::io::Result<()> + Send>>,
     raw_tree_webdav_direct_upload: Option<RawTreeWebdavDirectUploadConfig>,
+    part_stream: Option<Box<dyn PartStream>>,
 ) -> Result<FilesystemRunBuild, anyhow::Error> {
     let BuildPipelineOptions {
         artifact_format,
+        compression,
         encryption,
         part_size_bytes,
+        staging_min_free_bytes,
     } = pipeline;
     let using_paths = source.paths.iter().any(|p| !p.trim().is_empty());
     info!(
@@ -179,10 +245,12 @@ pub fn build_filesystem_run(
         root = %source.root,
         include_rules = source.include.len(),
         exclude_rules = source.exclude.len(),
+        use_ignore_files = source.use_ignore_files,
         symlink_policy = ?source.symlink_policy,
         hardlink_policy = ?source.hardlink_policy,
         error_policy = ?source.error_policy,
         artifact_format = ?artifact_format,
+        compression = compression.algorithm.as_str(),
         encryption = ?encryption,
         part_size_bytes,
         "building filesystem backup artifacts"
@@ -232,6 +300,16 @@ pub fn build_filesystem_run(
         None
     };
 
+    // Captured next to the stage dir so it never mixes with the artifacts themselves.
+    let system_state_dir = stage.parent().unwrap_or(&stage).join("system_state");
+    let system_state =
+        if source.windows_system_state && matches!(artifact_format, ArtifactFormatV1::ArchiveV1) {
+            system_state::capture(&system_state_dir, &mut issues)
+        } else {
+            None
+        };
+
+    let mut ignore_files = ignore_files::IgnoreFiles::new(source.use_ignore_files);
     let mut packaging_progress =
         on_progress.map(|cb| FilesystemBuildProgressCtx::new("packaging", pre_scan_totals, cb));
     if let Some(ctx) = packaging_progress.as_mut() {
@@ -242,26 +320,31 @@ pub fn build_filesystem_run(
         match artifact_format {
             ArtifactFormatV1::ArchiveV1 => (
                 ArtifactFormatV1::ArchiveV1,
-                tar::write_tar_zstd_parts(
+                tar::write_tar_parts(
                     &stage,
                     source,
                     read_mapping,
+                    &compression,
                     encryption,
                     &mut entries_writer,
                     &mut entries_count,
                     part_size_bytes,
+                    staging_min_free_bytes,
                     &mut issues,
+                    &mut ignore_files,
                     &mut consistency,
+                    system_state.as_ref().map(|_| system_state_dir.as_path()),
                     packaging_progress.as_mut(),
                     on_part_finished,
+                    part_stream,
                 )?,
                 None,
                 "pax",
-                "zstd",
+                compression.algorithm.as_str(),
                 part_size_bytes,
             ),
             ArtifactFormatV1::RawTreeV1 => {
-                let _ = on_part_finished;
+                let _ = (on_part_finished, part_stream, staging_min_free_bytes);
                 if !matches!(encryption, PayloadEncryption::None) {
                     anyhow::bail!("raw_tree_v1 does not support payload encryption");
                 }
@@ -275,6 +358,7 @@ pub fn build_filesystem_run(
                         &mut entries_writer,
                         &mut entries_count,
                         &mut issues,
+                        &mut ignore_files,
                         &mut consistency,
                         packaging_progress.as_mut(),
                     )?,
@@ -285,6 +369,7 @@ pub fn build_filesystem_run(
                         &mut entries_writer,
                         &mut entries_count,
                         &mut issues,
+                        &mut ignore_files,
                         &mut consistency,
                         packaging_progress.as_mut(),
                     )?,
@@ -300,6 +385,26 @@ pub fn build_filesystem_run(
             }
         };
     entries_writer.finish()?;
+    let anomaly = match source.anomaly_detection.as_ref() {
+        Some(settings) => match anomaly::check_and_update_baseline(
+            data_dir,
+            job_id,
+            &stage.join(ENTRIES_INDEX_NAME),
+            settings,
+            |archive_path| util::source_path_for_archive_path(source, read_mapping, archive_path),
+        ) {
+            Ok(report) => report,
+            Err(error) => {
+                issues.record_warning(format!("anomaly detection error: {error:#}"));
+                None
+            }
+        },
+        None => None,
+    };
+    let compression_level = match artifact_format {
+        ArtifactFormatV1::ArchiveV1 => compression.effective_level(),
+        ArtifactFormatV1::RawTreeV1 => None,
+    };
     if let Some(ctx) = packaging_progress.as_mut() {
         ctx.maybe_emit(true);
     }
@@ -319,6 +424,7 @@ pub fn build_filesystem_run(
             format: artifact_format,
             tar: tar_kind.to_string(),
             compression: compression_kind.to_string(),
+            compression_level,
             encryption: match encryption {
                 PayloadEncryption::None => "none".to_string(),
                 PayloadEncryption::AgeX25519 { .. } => "age".to_string(),
@@ -327,6 +433,10 @@ pub fn build_filesystem_run(
                 PayloadEncryption::None => None,
                 PayloadEncryption::AgeX25519 { key_name, .. } => Some(key_name.clone()),
             },
+            encryption_recipient: match encryption {
+                PayloadEncryption::None => None,
+                PayloadEncryption::AgeX25519 { recipient, .. } => Some(recipient.clone()),
+            },
             split_bytes,
         },
         artifacts: parts
@@ -342,6 +452,9 @@ pub fn build_filesystem_run(
             name: ENTRIES_INDEX_NAME.to_string(),
             count: entries_count,
         },
+        source: system_state
+            .as_ref()
+            .map(|report| serde_json::json!({ "system_state": report })),
     };
 
     let manifest_path = stage.join(MANIFEST_NAME);
@@ -378,6 +491,9 @@ pub fn build_filesystem_run(
         consistency: consistency.finish(),
         source_total: pre_scan_totals,
         raw_tree_stats,
+        ignore_files: ignore_files.into_applied(),
+        anomaly,
+        system_state,
     })
 }
 
@@ -19,7 +19,11 @@ use crate::backup::source_consistency::{
 use super::FilesystemBuildIssues;
 use super::RawTreeBuildStats;
 use super::entries_index::{EntriesIndexWriter, EntryRecord, write_entry_record};
-use super::util::{archive_prefix_for_path, compile_globset, join_archive_path};
+use super::ignore_files::IgnoreFiles;
+use super::util::{
+    archive_prefix_for_path, compile_exclude_globset, compile_globset, join_archive_path,
+    skipped_by_file_rules,
+};
 
 trait RawTreeDataSink {
     fn ensure_dir(&mut self, archive_path: &str) -> Result<(), anyhow::Error>;
@@ -257,6 +261,7 @@ pub(super) fn write_raw_tree(
     entries_writer: &mut EntriesIndexWriter<'_>,
     entries_count: &mut u64,
     issues: &mut FilesystemBuildIssues,
+    ignore_files: &mut IgnoreFiles,
     consistency: &mut SourceConsistencyTracker,
     mut progress: Option<&mut super::FilesystemBuildProgressCtx<'_>>,
 ) -> Result<RawTreeBuildStats, anyhow::Error> {
@@ -268,6 +273,7 @@ pub(super) fn write_raw_tree(
         entries_writer,
         entries_count,
         issues,
+        ignore_files,
         consistency,
         super::reborrow_progress(&mut progress),
     )
@@ -283,6 +289,7 @@ pub(super) fn write_raw_tree_webdav_direct(
     entries_writer: &mut EntriesIndexWriter<'_>,
     entries_count: &mut u64,
     issues: &mut FilesystemBuildIssues,
+    ignore_files: &mut IgnoreFiles,
     consistency: &mut SourceConsistencyTracker,
     mut progress: Option<&mut super::FilesystemBuildProgressCtx<'_>>,
 ) -> Result<RawTreeBuildStats, anyhow::Error> {
@@ -294,6 +301,7 @@ pub(super) fn write_raw_tree_webdav_direct(
         entries_writer,
         entries_count,
         issues,
+        ignore_files,
         consistency,
         super::reborrow_progress(&mut progress),
     )
@@ -307,10 +315,11 @@ fn write_raw_tree_to_sink(
     entries_writer: &mut EntriesIndexWriter<'_>,
     entries_count: &mut u64,
     issues: &mut FilesystemBuildIssues,
+    ignore_files: &mut IgnoreFiles,
     consistency: &mut SourceConsistencyTracker,
     mut progress: Option<&mut super::FilesystemBuildProgressCtx<'_>>,
 ) -> Result<RawTreeBuildStats, anyhow::Error> {
-    let exclude = compile_globset(&source.exclude)?;
+    let exclude = compile_exclude_globset(source)?;
     let include = compile_globset(&source.include)?;
     let has_includes = !source.include.is_empty();
 
@@ -372,6 +381,7 @@ fn write_raw_tree_to_sink(
                 entries_writer,
                 entries_count,
                 issues,
+                ignore_files,
                 consistency,
                 &mut stats,
                 &mut hardlink_index,
@@ -410,6 +420,7 @@ fn write_raw_tree_to_sink(
             entries_writer,
             entries_count,
             issues,
+            ignore_files,
             consistency,
             &mut stats,
             &mut hardlink_index,
@@ -433,6 +444,7 @@ fn write_legacy_root(
     entries_writer: &mut EntriesIndexWriter<'_>,
     entries_count: &mut u64,
     issues: &mut FilesystemBuildIssues,
+    ignore_files: &mut IgnoreFiles,
     consistency: &mut SourceConsistencyTracker,
     stats: &mut RawTreeBuildStats,
     hardlink_index: &mut HashMap<FileId, String>,
@@ -478,7 +490,9 @@ fn write_legacy_root(
             .is_some_and(|m| m.file_type().is_symlink());
 
         if meta.is_file() {
-            if has_includes && !include.is_match(name) {
+            if (has_includes && !include.is_match(name))
+                || skipped_by_file_rules(source, || Some(meta.clone()))
+            {
                 return Ok(());
             }
             write_file_entry(
@@ -522,6 +536,8 @@ fn write_legacy_root(
         return Ok(());
     }
 
+    ignore_files.enter_root(root, issues);
+
     let mut iter = WalkDir::new(root).follow_links(follow_links).into_iter();
     while let Some(next) = iter.next() {
         let entry = match next {
@@ -572,6 +588,12 @@ fn write_legacy_root(
             }
             continue;
         }
+        if ignore_files.skip_entry(&entry, issues) {
+            if is_dir {
+                iter.skip_current_dir();
+            }
+            continue;
+        }
 
         let is_symlink_path = entry.path_is_symlink();
         if is_symlink_path && source.symlink_policy == FsSymlinkPolicy::Skip {
@@ -583,7 +605,9 @@ fn write_legacy_root(
         }
 
         if entry.file_type().is_file() {
-            if has_includes && !include.is_match(&archive_path) {
+            if (has_includes && !include.is_match(&archive_path))
+                || skipped_by_file_rules(source, || entry.metadata().ok())
+            {
                 continue;
             }
 
@@ -670,6 +694,7 @@ fn write_source_entry(
     entries_writer: &mut EntriesIndexWriter<'_>,
     entries_count: &mut u64,
     issues: &mut FilesystemBuildIssues,
+    ignore_files: &mut IgnoreFiles,
     consistency: &mut SourceConsistencyTracker,
     stats: &mut RawTreeBuildStats,
     hardlink_index: &mut HashMap<FileId, String>,
@@ -721,6 +746,8 @@ fn write_source_entry(
             )?;
         }
 
+        ignore_files.enter_root(fs_path, issues);
+
         let mut iter = WalkDir::new(fs_path).follow_links(follow_links).into_iter();
         while let Some(next) = iter.next() {
             let entry = match next {
@@ -772,6 +799,12 @@ fn write_source_entry(
                 }
                 continue;
             }
+            if ignore_files.skip_entry(&entry, issues) {
+                if is_dir {
+                    iter.skip_current_dir();
+                }
+                continue;
+            }
 
             let is_symlink_path = entry.path_is_symlink();
             if is_symlink_path && source.symlink_policy == FsSymlinkPolicy::Skip {
@@ -783,7 +816,9 @@ fn write_source_entry(
             }
 
             if entry.file_type().is_file() {
-                if has_includes && !include.is_match(&archive_path) {
+                if (has_includes && !include.is_match(&archive_path))
+                    || skipped_by_file_rules(source, || entry.metadata().ok())
+                {
                     continue;
                 }
 
@@ -886,7 +921,9 @@ fn write_source_entry(
         .is_some_and(|m| m.file_type().is_symlink());
 
     if meta.is_file() {
-        if has_includes && !include.is_match(&archive_path) {
+        if (has_includes && !include.is_match(&archive_path))
+            || skipped_by_file_rules(source, || Some(meta.clone()))
+        {
             return Ok(());
         }
         write_file_entry(
@@ -6,7 +6,11 @@ use bastion_core::progress::ProgressUnitsV1;
 use walkdir::WalkDir;
 
 use super::FilesystemBuildIssues;
-use super::util::{archive_prefix_for_path, compile_globset, join_archive_path};
+use super::ignore_files::IgnoreFiles;
+use super::util::{
+    archive_prefix_for_path, compile_exclude_globset, compile_globset, join_archive_path,
+    skipped_by_file_rules,
+};
 
 fn meta_for_policy(
     path: &Path,
@@ -25,10 +29,11 @@ pub(super) fn scan_filesystem_source(
     issues: &mut FilesystemBuildIssues,
     mut progress: Option<&mut super::FilesystemBuildProgressCtx<'_>>,
 ) -> Result<ProgressUnitsV1, anyhow::Error> {
-    let exclude = compile_globset(&source.exclude)?;
+    let exclude = compile_exclude_globset(source)?;
     let include = compile_globset(&source.include)?;
     let has_includes = !source.include.is_empty();
     let follow_links = source.symlink_policy == FsSymlinkPolicy::Follow;
+    let mut ignore_files = IgnoreFiles::for_scan(source.use_ignore_files);
 
     let mut totals = ProgressUnitsV1::default();
 
@@ -72,6 +77,7 @@ pub(super) fn scan_filesystem_source(
                 has_includes,
                 follow_links,
                 issues,
+                &mut ignore_files,
                 &mut totals,
                 super::reborrow_progress(&mut progress),
             )?;
@@ -92,6 +98,7 @@ pub(super) fn scan_filesystem_source(
         has_includes,
         follow_links,
         issues,
+        &mut ignore_files,
         &mut totals,
         super::reborrow_progress(&mut progress),
     )?;
@@ -109,6 +116,7 @@ fn scan_source_path(
     has_includes: bool,
     follow_links: bool,
     issues: &mut FilesystemBuildIssues,
+    ignore_files: &mut IgnoreFiles,
     totals: &mut ProgressUnitsV1,
     mut progress: Option<&mut super::FilesystemBuildProgressCtx<'_>>,
 ) -> Result<(), anyhow::Error> {
@@ -152,6 +160,8 @@ fn scan_source_path(
             }
         }
 
+        ignore_files.enter_root(fs_path, issues);
+
         let mut iter = WalkDir::new(fs_path).follow_links(follow_links).into_iter();
         while let Some(next) = iter.next() {
             let entry = match next {
@@ -203,6 +213,12 @@ fn scan_source_path(
                 }
                 continue;
             }
+            if ignore_files.skip_entry(&entry, issues) {
+                if is_dir {
+                    iter.skip_current_dir();
+                }
+                continue;
+            }
 
             let is_symlink_path = entry.path_is_symlink();
             if is_symlink_path && source.symlink_policy == FsSymlinkPolicy::Skip {
@@ -210,7 +226,9 @@ fn scan_source_path(
             }
 
             if entry.file_type().is_file() {
-                if has_includes && !include.is_match(&archive_path) {
+                if (has_includes && !include.is_match(&archive_path))
+                    || skipped_by_file_rules(source, || entry.metadata().ok())
+                {
                     continue;
                 }
 
@@ -278,7 +296,9 @@ fn scan_source_path(
     }
 
     if meta.is_file() {
-        if has_includes && !include.is_match(&archive_path) {
+        if (has_includes && !include.is_match(&archive_path))
+            || skipped_by_file_rules(source, || Some(meta.clone()))
+        {
             return Ok(());
         }
         totals.files = totals.files.saturating_add(1);
@@ -311,6 +331,7 @@ fn scan_legacy_root(
     has_includes: bool,
     follow_links: bool,
     issues: &mut FilesystemBuildIssues,
+    ignore_files: &mut IgnoreFiles,
     totals: &mut ProgressUnitsV1,
     mut progress: Option<&mut super::FilesystemBuildProgressCtx<'_>>,
 ) -> Result<(), anyhow::Error> {
@@ -345,7 +366,9 @@ fn scan_legacy_root(
         }
 
         if meta.is_file() {
-            if has_includes && !include.is_match(name) {
+            if (has_includes && !include.is_match(name))
+                || skipped_by_file_rules(source, || Some(meta.clone()))
+            {
                 return Ok(());
             }
             totals.files = totals.files.saturating_add(1);
@@ -370,6 +393,8 @@ fn scan_legacy_root(
         return Ok(());
     }
 
+    ignore_files.enter_root(root, issues);
+
     let mut iter = WalkDir::new(root).follow_links(follow_links).into_iter();
     while let Some(next) = iter.next() {
         let entry = match next {
@@ -420,6 +445,12 @@ fn scan_legacy_root(
             }
             continue;
         }
+        if ignore_files.skip_entry(&entry, issues) {
+            if is_dir {
+                iter.skip_current_dir();
+            }
+            continue;
+        }
 
         let is_symlink_path = entry.path_is_symlink();
         if is_symlink_path && source.symlink_policy == FsSymlinkPolicy::Skip {
@@ -427,7 +458,9 @@ fn scan_legacy_root(
         }
 
         if entry.file_type().is_file() {
-            if has_includes && !include.is_match(&archive_path) {
+            if (has_includes && !include.is_match(&archive_path))
+                || skipped_by_file_rules(source, || entry.metadata().ok())
+            {
                 continue;
             }
 
@@ -2,6 +2,7 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use super::FilesystemReadMapping;
+use crate::backup::task_log;
 
 #[derive(Debug, Clone)]
 pub struct SourceSnapshotHandle {
@@ -179,6 +180,9 @@ fn probe_btrfs(root: &Path, settings: &SnapshotSettings) -> ProbeResult {
         .arg("show")
         .arg(root)
         .output();
+    if let Ok(out) = output.as_ref() {
+        task_log::command_output("btrfs subvolume show", out);
+    }
 
     match output {
         Ok(out) if out.status.success() => ProbeResult {
@@ -211,6 +215,7 @@ fn create_btrfs_snapshot(root: &Path, snapshot_root: &Path) -> Result<(), anyhow
         .arg(root)
         .arg(snapshot_root)
         .output()?;
+    task_log::command_output("btrfs subvolume snapshot -r", &out);
     if out.status.success() {
         return Ok(());
     }
@@ -226,6 +231,7 @@ fn delete_btrfs_snapshot(snapshot_root: &Path) -> Result<(), anyhow::Error> {
         .arg("delete")
         .arg(snapshot_root)
         .output()?;
+    task_log::command_output("btrfs subvolume delete", &out);
     if out.status.success() {
         return Ok(());
     }
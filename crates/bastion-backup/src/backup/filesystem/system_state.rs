@@ -0,0 +1,126 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::FilesystemBuildIssues;
+
+/// Archive directory holding the captured system state.
+pub(super) const ARCHIVE_DIR: &str = "bastion-system-state";
+
+/// What was captured, as recorded in the manifest and run summary.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SystemStateReport {
+    /// Captured files, relative to `bastion-system-state/`.
+    pub files: Vec<String>,
+}
+
+/// Captures registry hives, the installed-programs list and the driver list into `dir`.
+///
+/// Every step is best-effort: a failure is recorded as a warning and the backup carries on with
+/// whatever was captured. Returns `None` when nothing could be captured.
+pub(super) fn capture(dir: &Path, issues: &mut FilesystemBuildIssues) -> Option<SystemStateReport> {
+    let files = capture_files(dir, issues);
+    if files.is_empty() {
+        return None;
+    }
+    Some(SystemStateReport { files })
+}
+
+#[cfg(windows)]
+fn capture_files(dir: &Path, issues: &mut FilesystemBuildIssues) -> Vec<String> {
+    use std::process::Command;
+
+    const INSTALLED_PROGRAMS_SCRIPT: &str = "Get-ItemProperty \
+        'HKLM:\\Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\*', \
+        'HKLM:\\Software\\WOW6432Node\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\*' \
+        -ErrorAction SilentlyContinue \
+        | Where-Object { $_.DisplayName } \
+        | Select-Object DisplayName, DisplayVersion, Publisher, InstallDate, InstallLocation \
+        | Sort-Object DisplayName \
+        | ConvertTo-Json -Depth 2";
+
+    let mut files = Vec::new();
+    if let Err(error) = std::fs::create_dir_all(dir) {
+        issues.record_warning(format!(
+            "system state: failed to create {}: {error}",
+            dir.display()
+        ));
+        return files;
+    }
+
+    if let Err(error) = std::fs::create_dir_all(dir.join("registry")) {
+        issues.record_warning(format!(
+            "system state: failed to create registry dir: {error}"
+        ));
+    } else {
+        for hive in ["SOFTWARE", "SYSTEM"] {
+            let rel = format!("registry/{hive}.hiv");
+            let path = dir.join("registry").join(format!("{hive}.hiv"));
+            let mut cmd = Command::new("reg");
+            cmd.arg("save")
+                .arg(format!("HKLM\\{hive}"))
+                .arg(&path)
+                .arg("/y");
+            match run(&mut cmd) {
+                Ok(_) => files.push(rel),
+                Err(error) => {
+                    issues.record_warning(format!("system state: registry hive {hive}: {error}"))
+                }
+            }
+        }
+    }
+
+    let outputs: [(&str, Command); 3] = [
+        ("installed_programs.json", {
+            let mut cmd = Command::new("powershell");
+            cmd.args([
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                INSTALLED_PROGRAMS_SCRIPT,
+            ]);
+            cmd
+        }),
+        ("drivers.csv", {
+            let mut cmd = Command::new("driverquery");
+            cmd.args(["/v", "/fo", "csv"]);
+            cmd
+        }),
+        ("systeminfo.csv", {
+            let mut cmd = Command::new("systeminfo");
+            cmd.args(["/fo", "csv"]);
+            cmd
+        }),
+    ];
+    for (name, mut cmd) in outputs {
+        let result = run(&mut cmd)
+            .and_then(|stdout| std::fs::write(dir.join(name), stdout).map_err(anyhow::Error::from));
+        match result {
+            Ok(()) => files.push(name.to_string()),
+            Err(error) => issues.record_warning(format!("system state: {name}: {error}")),
+        }
+    }
+
+    files
+}
+
+#[cfg(windows)]
+fn run(cmd: &mut std::process::Command) -> Result<Vec<u8>, anyhow::Error> {
+    let program = cmd.get_program().to_string_lossy().to_string();
+    let output = cmd
+        .output()
+        .map_err(|error| anyhow::anyhow!("failed to run {program}: {error}"))?;
+    if output.status.success() {
+        crate::backup::task_log::line(format!("$ {program} ({})", output.status));
+        return Ok(output.stdout);
+    }
+    crate::backup::task_log::command_output(&program, &output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    anyhow::bail!("{program} failed ({}): {}", output.status, stderr.trim());
+}
+
+#[cfg(not(windows))]
+fn capture_files(_dir: &Path, issues: &mut FilesystemBuildIssues) -> Vec<String> {
+    issues.record_warning("windows_system_state is only supported on Windows; skipped");
+    Vec::new()
+}
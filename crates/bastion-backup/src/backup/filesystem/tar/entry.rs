@@ -10,9 +10,10 @@ use crate::backup::hashing_reader::HashingReader;
 use crate::backup::source_consistency::{
     SourceConsistencyTracker, detect_change_reason, fingerprint_for_path_meta,
 };
+use crate::compression::{PayloadWrite, looks_incompressible};
 
-use super::super::FilesystemBuildIssues;
 use super::super::entries_index::{EntriesIndexWriter, EntryRecord, write_entry_record};
+use super::super::{FilesystemBuildIssues, ProgressReader, reborrow_progress};
 
 pub(super) fn source_meta_for_policy(
     path: &Path,
@@ -26,7 +27,7 @@ pub(super) fn source_meta_for_policy(
 }
 
 #[allow(clippy::too_many_arguments)]
-pub(super) fn write_file_entry<W: Write>(
+pub(super) fn write_file_entry<W: PayloadWrite>(
     tar: &mut ::tar::Builder<W>,
     fs_path: &Path,
     archive_path: &str,
@@ -38,14 +39,14 @@ pub(super) fn write_file_entry<W: Write>(
     consistency: &mut SourceConsistencyTracker,
     hardlink_index: &mut HashMap<FileId, HardlinkRecord>,
     seen_archive_paths: &mut HashSet<String>,
-    progress: Option<&mut super::super::FilesystemBuildProgressCtx<'_>>,
+    mut progress: Option<&mut super::super::FilesystemBuildProgressCtx<'_>>,
 ) -> Result<(), anyhow::Error> {
     if seen_archive_paths.contains(archive_path) {
         issues.record_warning(format!("duplicate archive path (file): {archive_path}"));
         return Ok(());
     }
 
-    let file = match File::open(fs_path) {
+    let mut file = match File::open(fs_path) {
         Ok(f) => f,
         Err(error) => {
             let msg = format!("archive error: {archive_path}: {error}");
@@ -124,7 +125,15 @@ pub(super) fn write_file_entry<W: Write>(
     #[cfg(all(test, unix))]
     super::super::test_hooks::run_after_file_open_hook(fs_path, archive_path);
 
-    let mut reader = HashingReader::new(file);
+    if tar.get_ref().adaptive() {
+        // A failed sample only costs compression ratio; the archive write below reports real
+        // read errors.
+        let incompressible = looks_incompressible(fs_path, size, &mut file).unwrap_or(false);
+        tar.get_mut().set_incompressible(incompressible)?;
+    }
+
+    let mut reader =
+        HashingReader::new(ProgressReader::new(file, reborrow_progress(&mut progress)));
 
     let mut header = ::tar::Header::new_gnu();
     header.set_metadata_in_mode(&meta, ::tar::HeaderMode::Complete);
@@ -138,7 +147,10 @@ pub(super) fn write_file_entry<W: Write>(
             return Err(anyhow::Error::new(error).context(format!("archive error: {archive_path}")));
         }
         issues.record_error(msg);
-        let file = reader.into_inner();
+        let file = reader.into_inner().into_inner();
+        if let Some(progress) = progress.as_mut() {
+            progress.discard_file_bytes();
+        }
         let after_handle_fp = file
             .metadata()
             .ok()
@@ -157,7 +169,7 @@ pub(super) fn write_file_entry<W: Write>(
     }
 
     let hash = reader.finalize_hex();
-    let file = reader.into_inner();
+    let file = reader.into_inner().into_inner();
     let after_handle_fp = file
         .metadata()
         .ok()
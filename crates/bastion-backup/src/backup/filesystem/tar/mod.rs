@@ -1,10 +1,11 @@
 use std::io::Write as _;
 use std::path::Path;
 
-use bastion_core::job_spec::FilesystemSource;
+use bastion_core::job_spec::{CompressionV1, FilesystemSource};
 
 use crate::backup::source_consistency::SourceConsistencyTracker;
-use crate::backup::{LocalArtifact, PartWriter, PayloadEncryption};
+use crate::backup::{LocalArtifact, PartStream, PartWriter, PayloadEncryption};
+use crate::compression::CompressWriter;
 
 use super::FilesystemBuildIssues;
 use super::entries_index::EntriesIndexWriter;
@@ -13,33 +14,41 @@ mod entry;
 mod walk;
 
 #[allow(clippy::too_many_arguments)]
-pub(super) fn write_tar_zstd_parts(
+pub(super) fn write_tar_parts(
     stage_dir: &Path,
     source: &FilesystemSource,
     read_mapping: Option<&super::FilesystemReadMapping>,
+    compression: &CompressionV1,
     encryption: &PayloadEncryption,
     entries_writer: &mut EntriesIndexWriter<'_>,
     entries_count: &mut u64,
     part_size_bytes: u64,
+    staging_min_free_bytes: u64,
     issues: &mut FilesystemBuildIssues,
+    ignore_files: &mut super::ignore_files::IgnoreFiles,
     consistency: &mut SourceConsistencyTracker,
+    system_state_dir: Option<&Path>,
     progress: Option<&mut super::FilesystemBuildProgressCtx<'_>>,
     on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> std::io::Result<()> + Send>>,
+    part_stream: Option<Box<dyn PartStream>>,
 ) -> Result<Vec<LocalArtifact>, anyhow::Error> {
     let payload_prefix: &'static str = "payload.part";
     let mut part_writer =
         PartWriter::new(stage_dir.to_path_buf(), part_size_bytes, payload_prefix)?;
+    part_writer.set_min_free_bytes(staging_min_free_bytes);
     if let Some(cb) = on_part_finished {
         part_writer.set_on_part_finished(cb);
     }
+    if let Some(stream) = part_stream {
+        part_writer.set_stream(stream);
+    }
 
     let threads = std::thread::available_parallelism()
         .map(|n| n.get())
         .unwrap_or(1);
     match encryption {
         PayloadEncryption::None => {
-            let mut encoder = zstd::Encoder::new(&mut part_writer, 3)?;
-            encoder.multithread(threads as u32)?;
+            let encoder = CompressWriter::new(&mut part_writer, compression, threads)?;
 
             let mut tar = ::tar::Builder::new(encoder);
             walk::write_tar_entries(
@@ -49,7 +58,9 @@ pub(super) fn write_tar_zstd_parts(
                 entries_writer,
                 entries_count,
                 issues,
+                ignore_files,
                 consistency,
+                system_state_dir,
                 progress,
             )?;
 
@@ -67,8 +78,7 @@ pub(super) fn write_tar_zstd_parts(
             ))?;
             let encrypted = encryptor.wrap_output(&mut part_writer)?;
 
-            let mut encoder = zstd::Encoder::new(encrypted, 3)?;
-            encoder.multithread(threads as u32)?;
+            let encoder = CompressWriter::new(encrypted, compression, threads)?;
 
             let mut tar = ::tar::Builder::new(encoder);
             walk::write_tar_entries(
@@ -78,7 +88,9 @@ pub(super) fn write_tar_zstd_parts(
                 entries_writer,
                 entries_count,
                 issues,
+                ignore_files,
                 consistency,
+                system_state_dir,
                 progress,
             )?;
 
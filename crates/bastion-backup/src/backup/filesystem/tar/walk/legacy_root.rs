@@ -1,18 +1,19 @@
 use std::collections::{HashMap, HashSet};
-use std::io::Write;
 use std::path::Path;
 
 use bastion_core::job_spec::{FilesystemSource, FsErrorPolicy, FsSymlinkPolicy};
 use walkdir::WalkDir;
 
 use super::{
-    EntriesIndexWriter, FileId, FilesystemBuildIssues, HardlinkRecord, source_meta_for_policy,
-    write_dir_entry, write_file_entry, write_symlink_entry,
+    EntriesIndexWriter, FileId, FilesystemBuildIssues, HardlinkRecord, IgnoreFiles,
+    skipped_by_file_rules, source_meta_for_policy, write_dir_entry, write_file_entry,
+    write_symlink_entry,
 };
 use crate::backup::source_consistency::SourceConsistencyTracker;
+use crate::compression::PayloadWrite;
 
 #[allow(clippy::too_many_arguments)]
-pub(super) fn write_legacy_root<W: Write>(
+pub(super) fn write_legacy_root<W: PayloadWrite>(
     tar: &mut ::tar::Builder<W>,
     root: &Path,
     source: &FilesystemSource,
@@ -23,6 +24,7 @@ pub(super) fn write_legacy_root<W: Write>(
     entries_writer: &mut EntriesIndexWriter<'_>,
     entries_count: &mut u64,
     issues: &mut FilesystemBuildIssues,
+    ignore_files: &mut IgnoreFiles,
     consistency: &mut SourceConsistencyTracker,
     hardlink_index: &mut HashMap<FileId, HardlinkRecord>,
     seen_archive_paths: &mut HashSet<String>,
@@ -67,7 +69,9 @@ pub(super) fn write_legacy_root<W: Write>(
             .is_some_and(|m| m.file_type().is_symlink());
 
         if meta.is_file() {
-            if has_includes && !include.is_match(name) {
+            if (has_includes && !include.is_match(name))
+                || skipped_by_file_rules(source, || Some(meta.clone()))
+            {
                 return Ok(());
             }
             write_file_entry(
@@ -110,6 +114,8 @@ pub(super) fn write_legacy_root<W: Write>(
         return Ok(());
     }
 
+    ignore_files.enter_root(root, issues);
+
     let mut iter = WalkDir::new(root).follow_links(follow_links).into_iter();
     while let Some(next) = iter.next() {
         let entry = match next {
@@ -160,6 +166,12 @@ pub(super) fn write_legacy_root<W: Write>(
             }
             continue;
         }
+        if ignore_files.skip_entry(&entry, issues) {
+            if is_dir {
+                iter.skip_current_dir();
+            }
+            continue;
+        }
 
         let is_symlink_path = entry.path_is_symlink();
         if is_symlink_path && source.symlink_policy == FsSymlinkPolicy::Skip {
@@ -171,7 +183,9 @@ pub(super) fn write_legacy_root<W: Write>(
         }
 
         if entry.file_type().is_file() {
-            if has_includes && !include.is_match(&archive_path) {
+            if (has_includes && !include.is_match(&archive_path))
+                || skipped_by_file_rules(source, || entry.metadata().ok())
+            {
                 continue;
             }
 
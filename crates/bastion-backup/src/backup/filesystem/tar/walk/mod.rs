@@ -1,36 +1,42 @@
 use std::collections::{HashMap, HashSet};
-use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use bastion_core::job_spec::{FilesystemSource, FsSymlinkPolicy};
 
 use super::super::FilesystemBuildIssues;
 use super::super::entries_index::EntriesIndexWriter;
+use super::super::ignore_files::IgnoreFiles;
 use super::super::reborrow_progress;
-use super::super::util::{archive_prefix_for_path, compile_globset, join_archive_path};
+use super::super::util::{
+    archive_prefix_for_path, compile_exclude_globset, compile_globset, join_archive_path,
+    skipped_by_file_rules,
+};
 use super::entry::{
     FileId, HardlinkRecord, source_meta_for_policy, write_dir_entry, write_file_entry,
     write_symlink_entry,
 };
 use crate::backup::source_consistency::SourceConsistencyTracker;
+use crate::compression::PayloadWrite;
 
 mod legacy_root;
 mod source_entry;
 
 #[allow(clippy::too_many_arguments)]
-pub(super) fn write_tar_entries<W: Write>(
+pub(super) fn write_tar_entries<W: PayloadWrite>(
     tar: &mut ::tar::Builder<W>,
     source: &FilesystemSource,
     read_mapping: Option<&super::super::FilesystemReadMapping>,
     entries_writer: &mut EntriesIndexWriter<'_>,
     entries_count: &mut u64,
     issues: &mut FilesystemBuildIssues,
+    ignore_files: &mut IgnoreFiles,
     consistency: &mut SourceConsistencyTracker,
+    system_state_dir: Option<&Path>,
     mut progress: Option<&mut super::super::FilesystemBuildProgressCtx<'_>>,
 ) -> Result<(), anyhow::Error> {
     tar.follow_symlinks(source.symlink_policy == FsSymlinkPolicy::Follow);
 
-    let exclude = compile_globset(&source.exclude)?;
+    let exclude = compile_exclude_globset(source)?;
     let include = compile_globset(&source.include)?;
     let has_includes = !source.include.is_empty();
 
@@ -91,6 +97,7 @@ pub(super) fn write_tar_entries<W: Write>(
                 entries_writer,
                 entries_count,
                 issues,
+                ignore_files,
                 consistency,
                 &mut hardlink_index,
                 &mut seen_archive_paths,
@@ -128,6 +135,30 @@ pub(super) fn write_tar_entries<W: Write>(
             entries_writer,
             entries_count,
             issues,
+            ignore_files,
+            consistency,
+            &mut hardlink_index,
+            &mut seen_archive_paths,
+            reborrow_progress(&mut progress),
+        )?;
+    }
+
+    if let Some(dir) = system_state_dir {
+        // Captured outside the source selection, so the selection's filters do not apply.
+        let empty = globset::GlobSet::empty();
+        source_entry::write_source_entry(
+            tar,
+            dir,
+            Path::new(super::super::system_state::ARCHIVE_DIR),
+            source,
+            &empty,
+            &empty,
+            false,
+            false,
+            entries_writer,
+            entries_count,
+            issues,
+            ignore_files,
             consistency,
             &mut hardlink_index,
             &mut seen_archive_paths,
@@ -1,19 +1,19 @@
 use std::collections::{HashMap, HashSet};
-use std::io::Write;
 use std::path::Path;
 
 use bastion_core::job_spec::{FilesystemSource, FsErrorPolicy, FsSymlinkPolicy};
 use walkdir::WalkDir;
 
 use super::{
-    EntriesIndexWriter, FileId, FilesystemBuildIssues, HardlinkRecord, archive_prefix_for_path,
-    join_archive_path, source_meta_for_policy, write_dir_entry, write_file_entry,
-    write_symlink_entry,
+    EntriesIndexWriter, FileId, FilesystemBuildIssues, HardlinkRecord, IgnoreFiles,
+    archive_prefix_for_path, join_archive_path, skipped_by_file_rules, source_meta_for_policy,
+    write_dir_entry, write_file_entry, write_symlink_entry,
 };
 use crate::backup::source_consistency::SourceConsistencyTracker;
+use crate::compression::PayloadWrite;
 
 #[allow(clippy::too_many_arguments)]
-pub(super) fn write_source_entry<W: Write>(
+pub(super) fn write_source_entry<W: PayloadWrite>(
     tar: &mut ::tar::Builder<W>,
     fs_path: &Path,
     archive_path_basis: &Path,
@@ -25,6 +25,7 @@ pub(super) fn write_source_entry<W: Write>(
     entries_writer: &mut EntriesIndexWriter<'_>,
     entries_count: &mut u64,
     issues: &mut FilesystemBuildIssues,
+    ignore_files: &mut IgnoreFiles,
     consistency: &mut SourceConsistencyTracker,
     hardlink_index: &mut HashMap<FileId, HardlinkRecord>,
     seen_archive_paths: &mut HashSet<String>,
@@ -75,6 +76,8 @@ pub(super) fn write_source_entry<W: Write>(
             )?;
         }
 
+        ignore_files.enter_root(fs_path, issues);
+
         let mut iter = WalkDir::new(fs_path).follow_links(follow_links).into_iter();
         while let Some(next) = iter.next() {
             let entry = match next {
@@ -126,6 +129,12 @@ pub(super) fn write_source_entry<W: Write>(
                 }
                 continue;
             }
+            if ignore_files.skip_entry(&entry, issues) {
+                if is_dir {
+                    iter.skip_current_dir();
+                }
+                continue;
+            }
 
             let is_symlink_path = entry.path_is_symlink();
             if is_symlink_path && source.symlink_policy == FsSymlinkPolicy::Skip {
@@ -137,7 +146,9 @@ pub(super) fn write_source_entry<W: Write>(
             }
 
             if entry.file_type().is_file() {
-                if has_includes && !include.is_match(&archive_path) {
+                if (has_includes && !include.is_match(&archive_path))
+                    || skipped_by_file_rules(source, || entry.metadata().ok())
+                {
                     continue;
                 }
 
@@ -227,7 +238,9 @@ pub(super) fn write_source_entry<W: Write>(
         .is_some_and(|m| m.file_type().is_symlink());
 
     if meta.is_file() {
-        if has_includes && !include.is_match(&archive_path) {
+        if (has_includes && !include.is_match(&archive_path))
+            || skipped_by_file_rules(source, || Some(meta.clone()))
+        {
             return Ok(());
         }
         write_file_entry(
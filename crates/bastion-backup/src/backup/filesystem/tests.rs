@@ -8,7 +8,9 @@ use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::backup::{BuildPipelineOptions, PayloadEncryption};
-use bastion_core::job_spec::{FilesystemSource, FsErrorPolicy, FsHardlinkPolicy, FsSymlinkPolicy};
+use bastion_core::job_spec::{
+    CompressionV1, FilesystemSource, FsErrorPolicy, FsHardlinkPolicy, FsSymlinkPolicy,
+};
 use bastion_core::manifest::ArtifactFormatV1;
 use bastion_core::progress::ProgressUnitsV1;
 
@@ -66,6 +68,12 @@ fn filesystem_paths_can_backup_single_file() {
         root: String::new(),
         include: Vec::new(),
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -74,6 +82,7 @@ fn filesystem_paths_can_backup_single_file() {
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
 
     let build = build_filesystem_run(
@@ -84,13 +93,16 @@ fn filesystem_paths_can_backup_single_file() {
         &source,
         BuildPipelineOptions {
             artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1::default(),
             encryption: &PayloadEncryption::None,
             part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
         },
         None,
         None,
         None,
         None,
+        None,
     )
     .unwrap();
     assert_eq!(build.issues.errors_total, 0);
@@ -111,6 +123,54 @@ fn filesystem_paths_can_backup_single_file() {
     assert!(index_paths.contains(&expected));
 }
 
+#[cfg(not(windows))]
+#[test]
+fn windows_system_state_is_skipped_with_a_warning_elsewhere() {
+    let tmp = tempdir().expect("tempdir");
+    let data_dir = tmp.path().join("data");
+    std::fs::create_dir_all(&data_dir).unwrap();
+
+    let src = tmp.path().join("hello.txt");
+    std::fs::write(&src, b"hi").unwrap();
+
+    let source: FilesystemSource = serde_json::from_value(serde_json::json!({
+        "paths": [src.to_string_lossy()],
+        "windows_system_state": true,
+    }))
+    .unwrap();
+
+    let build = build_filesystem_run(
+        &data_dir,
+        &Uuid::new_v4().to_string(),
+        &Uuid::new_v4().to_string(),
+        OffsetDateTime::now_utc(),
+        &source,
+        BuildPipelineOptions {
+            artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1::default(),
+            encryption: &PayloadEncryption::None,
+            part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
+        },
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    assert!(build.system_state.is_none());
+    assert_eq!(build.issues.warnings_total, 1);
+    assert!(build.issues.sample_warnings[0].contains("windows_system_state"));
+
+    let tar_paths = list_tar_paths(&build.artifacts.parts[0].path);
+    assert!(
+        tar_paths
+            .iter()
+            .all(|p| !p.starts_with(super::system_state::ARCHIVE_DIR))
+    );
+}
+
 #[cfg(unix)]
 #[test]
 fn archive_hash_matches_archived_bytes_when_file_is_replaced_after_open() {
@@ -188,6 +248,12 @@ fn archive_hash_matches_archived_bytes_when_file_is_replaced_after_open() {
         root: String::new(),
         include: Vec::new(),
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -196,6 +262,7 @@ fn archive_hash_matches_archived_bytes_when_file_is_replaced_after_open() {
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
 
     let build = build_filesystem_run(
@@ -206,13 +273,16 @@ fn archive_hash_matches_archived_bytes_when_file_is_replaced_after_open() {
         &source,
         BuildPipelineOptions {
             artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1::default(),
             encryption: &PayloadEncryption::None,
             part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
         },
         None,
         None,
         None,
         None,
+        None,
     )
     .unwrap();
     assert_eq!(build.issues.errors_total, 0);
@@ -250,6 +320,12 @@ fn filesystem_paths_can_build_raw_tree_single_file() {
         root: String::new(),
         include: Vec::new(),
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -258,6 +334,7 @@ fn filesystem_paths_can_build_raw_tree_single_file() {
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
 
     let build = build_filesystem_run(
@@ -268,13 +345,16 @@ fn filesystem_paths_can_build_raw_tree_single_file() {
         &source,
         BuildPipelineOptions {
             artifact_format: ArtifactFormatV1::RawTreeV1,
+            compression: CompressionV1::default(),
             encryption: &PayloadEncryption::None,
             part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
         },
         None,
         None,
         None,
         None,
+        None,
     )
     .unwrap();
     assert_eq!(build.issues.errors_total, 0);
@@ -303,6 +383,50 @@ fn filesystem_paths_can_build_raw_tree_single_file() {
     assert!(manifest.artifacts.is_empty());
 }
 
+#[test]
+fn progress_reader_reports_bytes_within_a_single_file() {
+    use std::io::Read as _;
+    use std::sync::Mutex;
+
+    use super::{FilesystemBuildProgressCtx, FilesystemBuildProgressUpdate, ProgressReader};
+
+    let updates = Arc::new(Mutex::new(Vec::<FilesystemBuildProgressUpdate>::new()));
+    let updates_for_cb = updates.clone();
+    let cb = move |u: FilesystemBuildProgressUpdate| updates_for_cb.lock().unwrap().push(u);
+
+    let mut ctx = FilesystemBuildProgressCtx::new("packaging", None, &cb);
+    // Make every read eligible to emit instead of waiting for the throttle interval.
+    ctx.last_emit = std::time::Instant::now() - std::time::Duration::from_secs(60);
+
+    let data = vec![7u8; 3000];
+    let mut buf = [0u8; 1000];
+    ProgressReader::new(&data[..], Some(&mut ctx))
+        .read_exact(&mut buf)
+        .unwrap();
+
+    let got = updates
+        .lock()
+        .unwrap()
+        .last()
+        .cloned()
+        .expect("progress update");
+    assert_eq!(got.stage, "packaging");
+    assert_eq!(got.done.files, 0);
+    assert_eq!(got.done.bytes, 1000);
+
+    // Recording the entry folds the partial count into the totals instead of adding it twice.
+    ctx.record_entry("file", 3000);
+    ctx.maybe_emit(true);
+    let got = updates
+        .lock()
+        .unwrap()
+        .last()
+        .cloned()
+        .expect("progress update");
+    assert_eq!(got.done.files, 1);
+    assert_eq!(got.done.bytes, 3000);
+}
+
 #[test]
 fn filesystem_paths_deduplicates_overlapping_sources() {
     let tmp = tempdir().expect("tempdir");
@@ -325,6 +449,12 @@ fn filesystem_paths_deduplicates_overlapping_sources() {
         root: String::new(),
         include: Vec::new(),
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -333,6 +463,7 @@ fn filesystem_paths_deduplicates_overlapping_sources() {
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
 
     let build = build_filesystem_run(
@@ -343,13 +474,16 @@ fn filesystem_paths_deduplicates_overlapping_sources() {
         &source,
         BuildPipelineOptions {
             artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1::default(),
             encryption: &PayloadEncryption::None,
             part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
         },
         None,
         None,
         None,
         None,
+        None,
     )
     .unwrap();
     assert_eq!(build.issues.errors_total, 0);
@@ -385,6 +519,12 @@ fn legacy_root_can_backup_single_file() {
         root: src.to_string_lossy().to_string(),
         include: Vec::new(),
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -393,6 +533,7 @@ fn legacy_root_can_backup_single_file() {
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
 
     let build = build_filesystem_run(
@@ -403,13 +544,16 @@ fn legacy_root_can_backup_single_file() {
         &source,
         BuildPipelineOptions {
             artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1::default(),
             encryption: &PayloadEncryption::None,
             part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
         },
         None,
         None,
         None,
         None,
+        None,
     )
     .unwrap();
     assert_eq!(build.issues.errors_total, 0);
@@ -444,6 +588,12 @@ fn archive_parts_can_be_deleted_during_packaging() {
         root: String::new(),
         include: Vec::new(),
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -452,6 +602,7 @@ fn archive_parts_can_be_deleted_during_packaging() {
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
 
     let parts_seen = Arc::new(AtomicUsize::new(0));
@@ -487,14 +638,17 @@ fn archive_parts_can_be_deleted_during_packaging() {
         &source,
         BuildPipelineOptions {
             artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1::default(),
             encryption: &PayloadEncryption::None,
             // Force many part rotations so the callback is exercised.
             part_size_bytes: 64,
+            staging_min_free_bytes: 0,
         },
         None,
         None,
         Some(on_part_finished),
         None,
+        None,
     )
     .unwrap();
     assert_eq!(build.issues.errors_total, 0);
@@ -605,6 +759,12 @@ async fn raw_tree_webdav_direct_upload_writes_complete_last_and_hashes_uploaded_
         root: String::new(),
         include: Vec::new(),
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -613,6 +773,7 @@ async fn raw_tree_webdav_direct_upload_writes_complete_last_and_hashes_uploaded_
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
 
     let (base_url, state) = start_dav().await;
@@ -647,13 +808,16 @@ async fn raw_tree_webdav_direct_upload_writes_complete_last_and_hashes_uploaded_
             &source_for_build,
             BuildPipelineOptions {
                 artifact_format: ArtifactFormatV1::RawTreeV1,
+                compression: CompressionV1::default(),
                 encryption: &PayloadEncryption::None,
                 part_size_bytes: 4 * 1024 * 1024,
+                staging_min_free_bytes: 0,
             },
             None,
             None,
             None,
             Some(cfg),
+            None,
         )
     })
     .await
@@ -740,6 +904,12 @@ fn scan_legacy_root_respects_include_patterns_for_files() -> Result<(), anyhow::
         root: root.to_string_lossy().to_string(),
         include: vec!["a.txt".to_string()],
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -748,6 +918,7 @@ fn scan_legacy_root_respects_include_patterns_for_files() -> Result<(), anyhow::
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
 
     let mut issues = FilesystemBuildIssues::default();
@@ -782,6 +953,12 @@ fn scan_legacy_root_excludes_directory_and_skips_descendants() -> Result<(), any
         root: root.to_string_lossy().to_string(),
         include: Vec::new(),
         exclude: vec!["sub".to_string()],
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -790,6 +967,7 @@ fn scan_legacy_root_excludes_directory_and_skips_descendants() -> Result<(), any
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
 
     let mut issues = FilesystemBuildIssues::default();
@@ -826,6 +1004,12 @@ fn scan_legacy_root_symlink_policy_skip_ignores_symlink_entries() -> Result<(),
         root: root.to_string_lossy().to_string(),
         include: Vec::new(),
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -834,6 +1018,7 @@ fn scan_legacy_root_symlink_policy_skip_ignores_symlink_entries() -> Result<(),
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
     let mut issues = FilesystemBuildIssues::default();
     let totals_keep = scan_filesystem_source(&source_keep, None, &mut issues, None)?;
@@ -1,5 +1,7 @@
-use std::path::{Component, Path};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
+use bastion_core::job_spec::FilesystemSource;
 use serde::Serialize;
 
 pub(super) fn compile_globset(patterns: &[String]) -> Result<globset::GlobSet, anyhow::Error> {
@@ -10,6 +12,63 @@ pub(super) fn compile_globset(patterns: &[String]) -> Result<globset::GlobSet, a
     Ok(builder.build()?)
 }
 
+/// Compiles `exclude` together with the patterns of the selected exclude presets.
+pub(super) fn compile_exclude_globset(
+    source: &FilesystemSource,
+) -> Result<globset::GlobSet, anyhow::Error> {
+    let mut patterns = source.exclude.clone();
+    for preset in &source.exclude_presets {
+        patterns.extend(preset.patterns().iter().map(|p| p.to_string()));
+    }
+    compile_globset(&patterns)
+}
+
+/// Applies the size and age rules of the source to a regular file.
+///
+/// `meta` is only called when a rule is set, so sources without rules never pay for the extra
+/// stat. Files whose metadata cannot be read are kept; the archive step reports the error.
+pub(super) fn skipped_by_file_rules(
+    source: &FilesystemSource,
+    meta: impl FnOnce() -> Option<std::fs::Metadata>,
+) -> bool {
+    if source.max_file_size_bytes.is_none()
+        && source.skip_older_than_secs.is_none()
+        && source.skip_newer_than_secs.is_none()
+    {
+        return false;
+    }
+    let Some(meta) = meta() else {
+        return false;
+    };
+    file_rules_skip(source, meta.len(), meta.modified().ok(), SystemTime::now())
+}
+
+fn file_rules_skip(
+    source: &FilesystemSource,
+    size: u64,
+    mtime: Option<SystemTime>,
+    now: SystemTime,
+) -> bool {
+    if source.max_file_size_bytes.is_some_and(|max| size > max) {
+        return true;
+    }
+
+    let Some(mtime) = mtime else {
+        return false;
+    };
+    // Files with an mtime in the future count as brand new.
+    let age = now.duration_since(mtime).unwrap_or(Duration::ZERO);
+    if source
+        .skip_older_than_secs
+        .is_some_and(|secs| age > Duration::from_secs(secs))
+    {
+        return true;
+    }
+    source
+        .skip_newer_than_secs
+        .is_some_and(|secs| age < Duration::from_secs(secs))
+}
+
 pub(super) fn join_archive_path(prefix: &str, rel: &str) -> String {
     if prefix.is_empty() {
         rel.to_string()
@@ -65,6 +124,45 @@ pub(super) fn archive_prefix_for_path(path: &Path) -> Result<String, anyhow::Err
     Ok(components.join("/"))
 }
 
+/// Maps an archive path written for `source` back to the file it was read from.
+pub(super) fn source_path_for_archive_path(
+    source: &FilesystemSource,
+    read_mapping: Option<&super::FilesystemReadMapping>,
+    archive_path: &str,
+) -> Option<PathBuf> {
+    let original = if source.paths.iter().any(|p| !p.trim().is_empty()) {
+        source
+            .paths
+            .iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .find_map(|p| {
+                let path = Path::new(p);
+                let prefix = archive_prefix_for_path(path).ok()?;
+                if archive_path == prefix {
+                    return Some(path.to_path_buf());
+                }
+                let rest = if prefix.is_empty() {
+                    archive_path
+                } else {
+                    archive_path.strip_prefix(&prefix)?.strip_prefix('/')?
+                };
+                Some(path.join(rest))
+            })?
+    } else {
+        let root = Path::new(source.root.trim());
+        if root.is_dir() {
+            root.join(archive_path)
+        } else {
+            root.to_path_buf()
+        }
+    };
+    match read_mapping {
+        Some(mapping) => mapping.map_path(&original).ok(),
+        None => Some(original),
+    }
+}
+
 #[cfg(test)]
 fn hash_file(path: &Path) -> Result<String, anyhow::Error> {
     use std::fs::File;
@@ -92,10 +190,14 @@ pub(super) fn write_json(path: &Path, value: &impl Serialize) -> Result<(), anyh
 
 #[cfg(test)]
 mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use bastion_core::job_spec::{FilesystemSource, FsExcludePreset};
     use tempfile::TempDir;
 
     use super::{
-        archive_prefix_for_path, compile_globset, hash_file, join_archive_path, write_json,
+        archive_prefix_for_path, compile_exclude_globset, compile_globset, file_rules_skip,
+        hash_file, join_archive_path, source_path_for_archive_path, write_json,
     };
 
     #[test]
@@ -117,6 +219,34 @@ mod tests {
         assert_eq!(prefix, "tmp/foo");
     }
 
+    #[test]
+    fn source_path_for_archive_path_reverses_path_prefixes() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("app");
+        let source = source_from(serde_json::json!({ "paths": [dir.to_string_lossy()] }));
+        let prefix = archive_prefix_for_path(&dir).unwrap();
+
+        assert_eq!(
+            source_path_for_archive_path(&source, None, &format!("{prefix}/a/b.txt")),
+            Some(dir.join("a/b.txt"))
+        );
+        assert_eq!(
+            source_path_for_archive_path(&source, None, &prefix),
+            Some(dir.clone())
+        );
+        assert_eq!(
+            source_path_for_archive_path(&source, None, "elsewhere/x"),
+            None
+        );
+
+        std::fs::create_dir_all(&dir).unwrap();
+        let rooted = source_from(serde_json::json!({ "root": dir.to_string_lossy() }));
+        assert_eq!(
+            source_path_for_archive_path(&rooted, None, "a/b.txt"),
+            Some(dir.join("a/b.txt"))
+        );
+    }
+
     #[test]
     fn compile_globset_matches_expected() {
         let set = compile_globset(&["*.txt".to_string(), "dir/**".to_string()]).unwrap();
@@ -126,6 +256,55 @@ mod tests {
         assert!(compile_globset(&["[".to_string()]).is_err());
     }
 
+    fn source_from(value: serde_json::Value) -> FilesystemSource {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn compile_exclude_globset_adds_preset_patterns() {
+        let mut source = source_from(serde_json::json!({ "exclude": ["*.log"] }));
+        source.exclude_presets = vec![FsExcludePreset::NodeModules, FsExcludePreset::Caches];
+
+        let set = compile_exclude_globset(&source).unwrap();
+        assert!(set.is_match("a.log"));
+        assert!(set.is_match("home/u/app/node_modules"));
+        assert!(set.is_match("home/u/app/node_modules/x/index.js"));
+        assert!(set.is_match("home/u/.cache"));
+        assert!(set.is_match("src/__pycache__/m.pyc"));
+        assert!(!set.is_match("home/u/app/src/main.rs"));
+        assert!(!set.is_match("home/u/cache.txt"));
+    }
+
+    #[test]
+    fn file_rules_skip_by_size_and_age() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let days_ago = |d: u64| Some(now - Duration::from_secs(d * 86_400));
+
+        let none = source_from(serde_json::json!({}));
+        assert!(!file_rules_skip(&none, u64::MAX, days_ago(10_000), now));
+
+        let sized = source_from(serde_json::json!({ "max_file_size_bytes": 100 }));
+        assert!(!file_rules_skip(&sized, 100, None, now));
+        assert!(file_rules_skip(&sized, 101, None, now));
+
+        // Keep files modified between 1 and 7 days ago.
+        let aged = source_from(serde_json::json!({
+            "skip_older_than_secs": 7 * 86_400,
+            "skip_newer_than_secs": 86_400
+        }));
+        assert!(file_rules_skip(&aged, 1, days_ago(8), now));
+        assert!(!file_rules_skip(&aged, 1, days_ago(3), now));
+        assert!(file_rules_skip(&aged, 1, Some(now), now));
+        assert!(file_rules_skip(
+            &aged,
+            1,
+            Some(now + Duration::from_secs(60)),
+            now
+        ));
+        // Without an mtime only the size rule applies.
+        assert!(!file_rules_skip(&aged, 1, None, now));
+    }
+
     #[test]
     fn hash_file_matches_blake3_hash() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,688 @@
+//! Minimal blocking IMAP4rev1 client: just enough to list folders and fetch whole messages.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::Engine as _;
+
+use bastion_core::job_spec::{ImapSecurityV1, ImapSource};
+
+const IO_TIMEOUT: Duration = Duration::from_secs(300);
+/// Largest literal accepted from the server (a single message).
+const MAX_LITERAL_BYTES: usize = 512 * 1024 * 1024;
+
+/// How the client authenticates after connecting.
+#[derive(Clone)]
+pub enum ImapLogin {
+    Password {
+        username: String,
+        password: String,
+    },
+    /// `AUTHENTICATE XOAUTH2` with an OAuth2 access token.
+    XOauth2 {
+        username: String,
+        access_token: String,
+    },
+}
+
+impl std::fmt::Debug for ImapLogin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (kind, username) = match self {
+            ImapLogin::Password { username, .. } => ("password", username),
+            ImapLogin::XOauth2 { username, .. } => ("xoauth2", username),
+        };
+        f.debug_struct("ImapLogin")
+            .field("kind", &kind)
+            .field("username", username)
+            .finish_non_exhaustive()
+    }
+}
+
+pub(super) enum Transport {
+    Plain(TcpStream),
+    Tls(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+
+impl Read for Transport {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Transport::Plain(stream) => stream.read(buf),
+            Transport::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Transport {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Transport::Plain(stream) => stream.write(buf),
+            Transport::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Transport::Plain(stream) => stream.flush(),
+            Transport::Tls(stream) => stream.flush(),
+        }
+    }
+}
+
+/// One server response line, with its literals cut out. The `{n}` markers stay in `text` so the
+/// parser can pick the literals up in order.
+#[derive(Debug, Default)]
+pub(super) struct Response {
+    pub(super) text: String,
+    pub(super) literals: Vec<Vec<u8>>,
+}
+
+impl Response {
+    pub(super) fn items(&self) -> Result<Vec<Item>, anyhow::Error> {
+        parse_items(&self.text, &mut self.literals.iter())
+    }
+}
+
+/// A parsed response token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Item {
+    Atom(String),
+    Str(Vec<u8>),
+    List(Vec<Item>),
+}
+
+impl Item {
+    pub(super) fn as_atom(&self) -> Option<&str> {
+        match self {
+            Item::Atom(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Quoted strings, literals and atoms (`NIL` is `None`).
+    pub(super) fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Item::Str(v) => Some(v),
+            Item::Atom(v) if !v.eq_ignore_ascii_case("NIL") => Some(v.as_bytes()),
+            _ => None,
+        }
+    }
+}
+
+/// A selectable folder from `LIST`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ListedFolder {
+    /// Name as sent by the server (modified UTF-7), used in commands.
+    pub(super) raw_name: String,
+    pub(super) delimiter: Option<char>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct SelectedFolder {
+    pub(super) uidvalidity: u32,
+    pub(super) exists: u32,
+}
+
+#[derive(Debug)]
+pub(super) struct FetchedMessage {
+    pub(super) uid: u32,
+    pub(super) flags: Vec<String>,
+    /// `INTERNALDATE` as unix seconds, when the server sent a parseable one.
+    pub(super) internal_date: Option<i64>,
+    pub(super) body: Vec<u8>,
+}
+
+pub(super) struct ImapClient {
+    reader: BufReader<Transport>,
+    next_tag: u32,
+}
+
+impl ImapClient {
+    /// Connects, upgrades with `STARTTLS` when configured, and reads the greeting.
+    pub(super) fn connect(source: &ImapSource) -> Result<Self, anyhow::Error> {
+        let host = source.host.trim();
+        let port = source.effective_port();
+        let tcp = TcpStream::connect((host, port))
+            .map_err(|error| anyhow::anyhow!("failed to connect to {host}:{port}: {error}"))?;
+        tcp.set_read_timeout(Some(IO_TIMEOUT))?;
+        tcp.set_write_timeout(Some(IO_TIMEOUT))?;
+
+        match source.security {
+            ImapSecurityV1::Tls => {
+                let mut client = Self::new(Transport::Tls(Box::new(tls_stream(host, tcp)?)));
+                client.read_greeting()?;
+                Ok(client)
+            }
+            ImapSecurityV1::Starttls => {
+                let mut client = Self::new(Transport::Plain(tcp));
+                client.read_greeting()?;
+                client.command("STARTTLS")?;
+                // Anything the server sent after the OK would be plaintext injected before the
+                // handshake.
+                if !client.reader.buffer().is_empty() {
+                    anyhow::bail!("imap server sent data after STARTTLS");
+                }
+                let Transport::Plain(tcp) = client.reader.into_inner() else {
+                    unreachable!("STARTTLS upgrades a plain connection");
+                };
+                Ok(Self {
+                    reader: BufReader::new(Transport::Tls(Box::new(tls_stream(host, tcp)?))),
+                    next_tag: client.next_tag,
+                })
+            }
+        }
+    }
+
+    pub(super) fn new(transport: Transport) -> Self {
+        Self {
+            reader: BufReader::new(transport),
+            next_tag: 1,
+        }
+    }
+
+    pub(super) fn read_greeting(&mut self) -> Result<(), anyhow::Error> {
+        let greeting = self.read_response()?;
+        if greeting.text.starts_with("* OK") || greeting.text.starts_with("* PREAUTH") {
+            return Ok(());
+        }
+        anyhow::bail!("unexpected imap greeting: {}", greeting.text)
+    }
+
+    pub(super) fn login(&mut self, login: &ImapLogin) -> Result<(), anyhow::Error> {
+        match login {
+            ImapLogin::Password { username, password } => {
+                let command = format!("LOGIN {} {}", quote(username)?, quote(password)?);
+                self.command(&command)
+                    .map(|_| ())
+                    .map_err(|error| anyhow::anyhow!("imap login failed: {error}"))
+            }
+            ImapLogin::XOauth2 {
+                username,
+                access_token,
+            } => {
+                let token = base64::engine::general_purpose::STANDARD.encode(format!(
+                    "user={username}\x01auth=Bearer {access_token}\x01\x01"
+                ));
+                self.command(&format!("AUTHENTICATE XOAUTH2 {token}"))
+                    .map(|_| ())
+                    .map_err(|error| anyhow::anyhow!("imap XOAUTH2 authentication failed: {error}"))
+            }
+        }
+    }
+
+    /// Selectable folders, in server order.
+    pub(super) fn list(&mut self) -> Result<Vec<ListedFolder>, anyhow::Error> {
+        let mut out = Vec::new();
+        for response in self.command("LIST \"\" \"*\"")? {
+            let items = response.items()?;
+            if items.get(1).and_then(Item::as_atom) != Some("LIST") {
+                continue;
+            }
+            let (Some(Item::List(attributes)), Some(delimiter), Some(name)) =
+                (items.get(2), items.get(3), items.get(4))
+            else {
+                anyhow::bail!("malformed LIST response: {}", response.text);
+            };
+            let unselectable = attributes.iter().any(|a| {
+                a.as_atom().is_some_and(|a| {
+                    a.eq_ignore_ascii_case("\\Noselect") || a.eq_ignore_ascii_case("\\NonExistent")
+                })
+            });
+            if unselectable {
+                continue;
+            }
+            let Some(name) = name.as_bytes() else {
+                continue;
+            };
+            out.push(ListedFolder {
+                raw_name: String::from_utf8_lossy(name).to_string(),
+                delimiter: delimiter
+                    .as_bytes()
+                    .and_then(|d| d.first())
+                    .map(|d| char::from(*d)),
+            });
+        }
+        Ok(out)
+    }
+
+    /// Opens a folder read-only.
+    pub(super) fn examine(&mut self, raw_name: &str) -> Result<SelectedFolder, anyhow::Error> {
+        let mut uidvalidity = None;
+        let mut exists = 0;
+        for response in self.command(&format!("EXAMINE {}", quote(raw_name)?))? {
+            if let Some(value) = response_code_number(&response.text, "UIDVALIDITY") {
+                uidvalidity = Some(value);
+            }
+            let items = response.items()?;
+            if items.get(2).and_then(Item::as_atom) == Some("EXISTS")
+                && let Some(count) = items.get(1).and_then(Item::as_atom)
+            {
+                exists = count.parse().unwrap_or(0);
+            }
+        }
+        let Some(uidvalidity) = uidvalidity else {
+            anyhow::bail!("server sent no UIDVALIDITY for {raw_name}");
+        };
+        Ok(SelectedFolder {
+            uidvalidity,
+            exists,
+        })
+    }
+
+    /// UIDs of the messages at or above `from`, sorted.
+    pub(super) fn search_from(&mut self, from: u32) -> Result<Vec<u32>, anyhow::Error> {
+        let mut uids = Vec::new();
+        for response in self.command(&format!("UID SEARCH UID {from}:*"))? {
+            let items = response.items()?;
+            if items.get(1).and_then(Item::as_atom) != Some("SEARCH") {
+                continue;
+            }
+            uids.extend(
+                items[2..]
+                    .iter()
+                    .filter_map(Item::as_atom)
+                    .filter_map(|v| v.parse::<u32>().ok()),
+            );
+        }
+        // `n:*` always matches the last message, even when its UID is below `n`.
+        uids.retain(|uid| *uid >= from);
+        uids.sort_unstable();
+        uids.dedup();
+        Ok(uids)
+    }
+
+    /// Fetches one message without setting `\Seen`. `None` when it was expunged meanwhile.
+    pub(super) fn fetch(&mut self, uid: u32) -> Result<Option<FetchedMessage>, anyhow::Error> {
+        for response in self.command(&format!(
+            "UID FETCH {uid} (UID FLAGS INTERNALDATE BODY.PEEK[])"
+        ))? {
+            let items = response.items()?;
+            if items.get(2).and_then(Item::as_atom) != Some("FETCH") {
+                continue;
+            }
+            let Some(Item::List(attributes)) = items.get(3) else {
+                anyhow::bail!("malformed FETCH response: {}", response.text);
+            };
+            if let Some(message) = parse_fetch(attributes)
+                && message.uid == uid
+            {
+                return Ok(Some(message));
+            }
+        }
+        Ok(None)
+    }
+
+    pub(super) fn logout(mut self) {
+        let _ = self.command("LOGOUT");
+    }
+
+    /// Sends a tagged command and collects the untagged responses until its completion. Fails
+    /// unless the completion is `OK`.
+    pub(super) fn command(&mut self, command: &str) -> Result<Vec<Response>, anyhow::Error> {
+        let tag = format!("a{}", self.next_tag);
+        self.next_tag += 1;
+        let transport = self.reader.get_mut();
+        transport.write_all(format!("{tag} {command}\r\n").as_bytes())?;
+        transport.flush()?;
+
+        let mut untagged = Vec::new();
+        loop {
+            let response = self.read_response()?;
+            if response.text.starts_with('+') {
+                // A continuation here is an authentication challenge carrying an error; an
+                // empty reply makes the server send the tagged failure.
+                let transport = self.reader.get_mut();
+                transport.write_all(b"\r\n")?;
+                transport.flush()?;
+                continue;
+            }
+            if let Some(rest) = response.text.strip_prefix(&tag)
+                && let Some(status) = rest.strip_prefix(' ')
+            {
+                if status.starts_with("OK") {
+                    return Ok(untagged);
+                }
+                anyhow::bail!("{}", status.trim());
+            }
+            untagged.push(response);
+        }
+    }
+
+    fn read_response(&mut self) -> Result<Response, anyhow::Error> {
+        let mut response = Response::default();
+        loop {
+            let mut line = Vec::new();
+            let n = self.reader.read_until(b'\n', &mut line)?;
+            if n == 0 {
+                anyhow::bail!("imap connection closed by the server");
+            }
+            while matches!(line.last(), Some(b'\n' | b'\r')) {
+                line.pop();
+            }
+            let line = String::from_utf8_lossy(&line).to_string();
+            let literal = literal_len(&line);
+            response.text.push_str(&line);
+            let Some(len) = literal else {
+                return Ok(response);
+            };
+            if len > MAX_LITERAL_BYTES {
+                anyhow::bail!("imap literal too large: {len} bytes");
+            }
+            let mut buf = vec![0u8; len];
+            self.reader.read_exact(&mut buf)?;
+            response.literals.push(buf);
+        }
+    }
+}
+
+fn tls_stream(
+    host: &str,
+    tcp: TcpStream,
+) -> Result<rustls::StreamOwned<rustls::ClientConnection, TcpStream>, anyhow::Error> {
+    let roots = rustls::RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()?
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+        .map_err(|error| anyhow::anyhow!("invalid imap host {host:?}: {error}"))?;
+    let connection = rustls::ClientConnection::new(Arc::new(config), server_name)?;
+    Ok(rustls::StreamOwned::new(connection, tcp))
+}
+
+/// `{n}` (or the non-synchronizing `{n+}`) at the end of a line announces an `n`-byte literal.
+fn literal_len(line: &str) -> Option<usize> {
+    let inner = line.strip_suffix('}')?;
+    let open = inner.rfind('{')?;
+    inner[open + 1..].trim_end_matches('+').parse().ok()
+}
+
+fn response_code_number(text: &str, code: &str) -> Option<u32> {
+    let start = text.find(&format!("[{code} "))? + code.len() + 2;
+    let digits: String = text[start..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
+/// Quoted strings can't carry line breaks or NUL; letting them through would end the command
+/// early and smuggle in a second one.
+pub(super) fn quote(value: &str) -> Result<String, anyhow::Error> {
+    if value.contains(['\r', '\n', '\0']) {
+        anyhow::bail!("imap quoted strings must not contain CR, LF or NUL");
+    }
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    Ok(out)
+}
+
+fn parse_items<'a>(
+    text: &str,
+    literals: &mut impl Iterator<Item = &'a Vec<u8>>,
+) -> Result<Vec<Item>, anyhow::Error> {
+    let mut stack: Vec<Vec<Item>> = vec![Vec::new()];
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            ' ' => {}
+            '(' => stack.push(Vec::new()),
+            ')' => {
+                let list = stack.pop().filter(|_| !stack.is_empty());
+                let Some(list) = list else {
+                    anyhow::bail!("unbalanced parenthesis in imap response");
+                };
+                stack.last_mut().expect("outer list").push(Item::List(list));
+            }
+            '"' => {
+                let mut value = String::new();
+                let mut closed = false;
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some((_, escaped)) = chars.next() {
+                                value.push(escaped);
+                            }
+                        }
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        c => value.push(c),
+                    }
+                }
+                if !closed {
+                    anyhow::bail!("unterminated string in imap response");
+                }
+                stack
+                    .last_mut()
+                    .expect("list")
+                    .push(Item::Str(value.into_bytes()));
+            }
+            '{' => {
+                for (_, c) in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                }
+                let Some(literal) = literals.next() else {
+                    anyhow::bail!("imap response references a missing literal");
+                };
+                stack
+                    .last_mut()
+                    .expect("list")
+                    .push(Item::Str(literal.clone()));
+            }
+            _ => {
+                let mut end = start + c.len_utf8();
+                // `BODY[...]` keeps its section (which may contain spaces) in one atom.
+                let mut brackets = usize::from(c == '[');
+                while let Some(&(i, c)) = chars.peek() {
+                    if brackets == 0 && matches!(c, ' ' | '(' | ')') {
+                        break;
+                    }
+                    match c {
+                        '[' => brackets += 1,
+                        ']' => brackets = brackets.saturating_sub(1),
+                        _ => {}
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                stack
+                    .last_mut()
+                    .expect("list")
+                    .push(Item::Atom(text[start..end].to_string()));
+            }
+        }
+    }
+    if stack.len() != 1 {
+        anyhow::bail!("unbalanced parenthesis in imap response");
+    }
+    Ok(stack.pop().unwrap_or_default())
+}
+
+fn parse_fetch(attributes: &[Item]) -> Option<FetchedMessage> {
+    let mut uid = None;
+    let mut flags = Vec::new();
+    let mut internal_date = None;
+    let mut body = None;
+    for pair in attributes.chunks(2) {
+        let [Item::Atom(key), value] = pair else {
+            continue;
+        };
+        match key.to_ascii_uppercase().as_str() {
+            "UID" => uid = value.as_atom().and_then(|v| v.parse::<u32>().ok()),
+            "FLAGS" => {
+                if let Item::List(list) = value {
+                    flags = list
+                        .iter()
+                        .filter_map(Item::as_atom)
+                        .map(str::to_string)
+                        .collect();
+                }
+            }
+            "INTERNALDATE" => {
+                internal_date = value
+                    .as_bytes()
+                    .and_then(|v| parse_internal_date(&String::from_utf8_lossy(v)));
+            }
+            "BODY[]" => body = value.as_bytes().map(<[u8]>::to_vec),
+            _ => {}
+        }
+    }
+    Some(FetchedMessage {
+        uid: uid?,
+        flags,
+        internal_date,
+        body: body?,
+    })
+}
+
+/// Parses `date-time` (`17-Jul-1996 02:44:25 -0700`) into unix seconds.
+fn parse_internal_date(value: &str) -> Option<i64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut parts = value.split_whitespace();
+    let mut date = parts.next()?.split('-');
+    let (day, month, year) = (date.next()?, date.next()?, date.next()?);
+    let mut clock = parts.next()?.split(':');
+    let (hour, minute, second) = (clock.next()?, clock.next()?, clock.next()?);
+    let zone = parts.next()?;
+
+    let month = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(month))?;
+    let month = time::Month::try_from(u8::try_from(month + 1).ok()?).ok()?;
+    let date = time::Date::from_calendar_date(year.parse().ok()?, month, day.parse().ok()?).ok()?;
+    let time = time::Time::from_hms(
+        hour.parse().ok()?,
+        minute.parse().ok()?,
+        second.parse().ok()?,
+    )
+    .ok()?;
+    let (sign, zone) = match zone.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let offset_minutes: i64 =
+        zone.get(..2)?.parse::<i64>().ok()? * 60 + zone.get(2..4)?.parse::<i64>().ok()?;
+    let local = time::PrimitiveDateTime::new(date, time).assume_utc();
+    Some(local.unix_timestamp() - sign * offset_minutes * 60)
+}
+
+/// Decodes a modified UTF-7 folder name (RFC 3501 section 5.1.3). Invalid sequences are kept
+/// as sent.
+pub(super) fn decode_modified_utf7(raw: &str) -> String {
+    let engine = base64::engine::GeneralPurpose::new(
+        &base64::alphabet::STANDARD,
+        base64::engine::GeneralPurposeConfig::new()
+            .with_decode_padding_mode(base64::engine::DecodePaddingMode::RequireNone)
+            .with_decode_allow_trailing_bits(true),
+    );
+    let mut out = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('-') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let encoded = &after[..end];
+        if encoded.is_empty() {
+            out.push('&');
+        } else {
+            let decoded = engine
+                .decode(encoded.replace(',', "/"))
+                .ok()
+                .filter(|bytes| bytes.len() % 2 == 0)
+                .and_then(|bytes| {
+                    let units = bytes
+                        .chunks_exact(2)
+                        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+                    char::decode_utf16(units)
+                        .collect::<Result<String, _>>()
+                        .ok()
+                });
+            match decoded {
+                Some(text) => out.push_str(&text),
+                None => out.push_str(&rest[start..start + end + 2]),
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Item, decode_modified_utf7, literal_len, parse_internal_date, parse_items, quote};
+
+    #[test]
+    fn fetch_responses_keep_literals_and_body_sections() {
+        let body = b"Subject: hi\r\n\r\nhello".to_vec();
+        let literals = [body.clone()];
+        let items = parse_items(
+            "* 3 FETCH (UID 42 FLAGS (\\Seen \\Flagged) INTERNALDATE \"17-Jul-1996 02:44:25 -0700\" BODY[] {20})",
+            &mut literals.iter(),
+        )
+        .unwrap();
+        let Item::List(attributes) = &items[3] else {
+            panic!("expected a list: {items:?}");
+        };
+        let message = super::parse_fetch(attributes).unwrap();
+        assert_eq!(message.uid, 42);
+        assert_eq!(message.flags, vec!["\\Seen", "\\Flagged"]);
+        assert_eq!(message.internal_date, Some(837_596_665));
+        assert_eq!(message.body, body);
+
+        assert_eq!(literal_len("* 3 FETCH (BODY[] {20}"), Some(20));
+        assert_eq!(literal_len("* 3 FETCH (BODY[] {20+}"), Some(20));
+        assert_eq!(literal_len("* OK [UIDVALIDITY 1] {not a literal"), None);
+    }
+
+    #[test]
+    fn internal_dates_apply_the_zone_offset() {
+        assert_eq!(
+            parse_internal_date("01-Jan-2024 00:00:00 +0000"),
+            Some(1_704_067_200)
+        );
+        assert_eq!(
+            parse_internal_date(" 1-Jan-2024 01:30:00 +0130"),
+            Some(1_704_067_200)
+        );
+        assert_eq!(parse_internal_date("garbage"), None);
+    }
+
+    #[test]
+    fn modified_utf7_folder_names_are_decoded() {
+        assert_eq!(decode_modified_utf7("INBOX"), "INBOX");
+        assert_eq!(decode_modified_utf7("Tom &- Jerry"), "Tom & Jerry");
+        assert_eq!(decode_modified_utf7("&ZeVnLIqe-"), "日本語");
+        assert_eq!(
+            decode_modified_utf7("Entw&APw-rfe/&AMQ-rger"),
+            "Entwürfe/Ärger"
+        );
+        assert_eq!(decode_modified_utf7("broken&-&"), "broken&&");
+    }
+
+    #[test]
+    fn quoted_strings_escape_and_reject_line_breaks() {
+        assert_eq!(quote(r#"a"b\c"#).unwrap(), r#""a\"b\\c""#);
+        assert!(quote("pw\r\nA1 DELETE INBOX").is_err());
+        assert!(quote("pw\n").is_err());
+        assert!(quote("pw\0").is_err());
+    }
+}
@@ -0,0 +1,434 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tracing::info;
+
+use crate::backup::{BuildPipelineOptions, LocalRunArtifacts, task_log};
+use bastion_core::job_spec::{
+    FilesystemSource, FsErrorPolicy, FsHardlinkPolicy, FsSymlinkPolicy, ImapCredentialsV1,
+    ImapSource,
+};
+
+mod client;
+
+pub use client::ImapLogin;
+use client::{FetchedMessage, ImapClient, ListedFolder};
+
+/// Per-job UID state, kept next to the staging area between runs.
+const STATE_DIR_NAME: &str = "imap_state";
+/// Separator between a maildir file's unique name and its flags. `:` is not allowed in Windows
+/// file names, so Windows nodes use `;` like most Windows maildir tools.
+#[cfg(not(windows))]
+const INFO_SEPARATOR: char = ':';
+#[cfg(windows)]
+const INFO_SEPARATOR: char = ';';
+
+/// Where each folder's download stopped, keyed by folder name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImapSyncState {
+    #[serde(default)]
+    pub folders: BTreeMap<String, ImapFolderState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImapFolderState {
+    pub uidvalidity: u32,
+    /// Highest UID downloaded so far (`0`: none).
+    pub last_uid: u32,
+}
+
+/// What a run downloaded from one folder, as recorded in the run summary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImapFolderResult {
+    pub name: String,
+    pub uidvalidity: u32,
+    pub last_uid: u32,
+    pub messages: u64,
+    pub bytes: u64,
+    /// `UIDVALIDITY` changed since the last run, so the folder was downloaded again.
+    pub resynced: bool,
+}
+
+#[derive(Debug)]
+pub struct ImapRunBuild {
+    pub artifacts: LocalRunArtifacts,
+    pub folders: Vec<ImapFolderResult>,
+    /// State to persist with [`finish_imap_run`] once the artifacts are stored.
+    pub state: ImapSyncState,
+    pub source_files: u64,
+    pub source_bytes: u64,
+}
+
+impl ImapRunBuild {
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "folders": self.folders,
+            "messages": self.folders.iter().map(|f| f.messages).sum::<u64>(),
+        })
+    }
+}
+
+/// Turns the stored credentials into a login, exchanging the OAuth2 refresh token for an access
+/// token.
+pub async fn resolve_imap_login(
+    credentials: &ImapCredentialsV1,
+) -> Result<ImapLogin, anyhow::Error> {
+    match credentials {
+        ImapCredentialsV1::Password { username, password } => Ok(ImapLogin::Password {
+            username: username.clone(),
+            password: password.clone(),
+        }),
+        ImapCredentialsV1::Oauth2 {
+            username,
+            token_url,
+            client_id,
+            client_secret,
+            refresh_token,
+        } => {
+            #[derive(Deserialize)]
+            struct TokenResponse {
+                access_token: String,
+            }
+
+            let mut form = vec![
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token.as_str()),
+                ("client_id", client_id.as_str()),
+            ];
+            if let Some(secret) = client_secret.as_deref().filter(|v| !v.is_empty()) {
+                form.push(("client_secret", secret));
+            }
+            let res = reqwest::Client::new()
+                .post(token_url.trim())
+                .form(&form)
+                .send()
+                .await
+                .map_err(|error| anyhow::anyhow!("oauth2 token request failed: {error}"))?;
+            let status = res.status();
+            if !status.is_success() {
+                let body = res.text().await.unwrap_or_default();
+                anyhow::bail!(
+                    "oauth2 token request failed: HTTP {status}: {}",
+                    body.chars().take(200).collect::<String>()
+                );
+            }
+            let token = res
+                .json::<TokenResponse>()
+                .await
+                .map_err(|error| anyhow::anyhow!("invalid oauth2 token response: {error}"))?;
+            Ok(ImapLogin::XOauth2 {
+                username: username.clone(),
+                access_token: token.access_token,
+            })
+        }
+    }
+}
+
+/// Downloads the messages added since the last successful run into a maildir-style tree
+/// (`<folder>/cur/<uidvalidity>.<uid>.bastion:2,<flags>`) and packages it.
+///
+/// Messages are fetched with `BODY.PEEK[]`, so the server does not mark them as read. The
+/// returned state only becomes the job's new state through [`finish_imap_run`].
+pub fn build_imap_run(
+    data_dir: &Path,
+    job_id: &str,
+    run_id: &str,
+    started_at: OffsetDateTime,
+    source: &ImapSource,
+    login: &ImapLogin,
+    pipeline: BuildPipelineOptions<'_>,
+) -> Result<ImapRunBuild, anyhow::Error> {
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        host = %source.host.trim(),
+        port = source.effective_port(),
+        security = ?source.security,
+        artifact_format = ?pipeline.artifact_format,
+        "building imap backup artifacts"
+    );
+
+    let run_dir = crate::backup::run_dir(data_dir, run_id);
+    let source_dir = run_dir.join("source");
+    std::fs::create_dir_all(&source_dir)?;
+
+    let previous = load_state(data_dir, job_id)?;
+    let mut client = ImapClient::connect(source)?;
+    client.login(login)?;
+    let (folders, state) = download_folders(&mut client, source, &previous, &source_dir)?;
+    client.logout();
+
+    package(
+        data_dir,
+        job_id,
+        run_id,
+        started_at,
+        pipeline,
+        &source_dir,
+        folders,
+        state,
+    )
+}
+
+/// Makes the run's UID state the job's state. Call only after the artifacts were stored on the
+/// target, so a failed upload downloads the same messages again.
+pub fn finish_imap_run(
+    data_dir: &Path,
+    job_id: &str,
+    build: &ImapRunBuild,
+) -> Result<(), anyhow::Error> {
+    let path = state_path(data_dir, job_id);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.partial");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(&build.state)?)?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+fn state_path(data_dir: &Path, job_id: &str) -> PathBuf {
+    data_dir.join(STATE_DIR_NAME).join(format!("{job_id}.json"))
+}
+
+fn load_state(data_dir: &Path, job_id: &str) -> Result<ImapSyncState, anyhow::Error> {
+    match std::fs::read(state_path(data_dir, job_id)) {
+        Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(ImapSyncState::default()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn download_folders(
+    client: &mut ImapClient,
+    source: &ImapSource,
+    previous: &ImapSyncState,
+    dest: &Path,
+) -> Result<(Vec<ImapFolderResult>, ImapSyncState), anyhow::Error> {
+    let filter = FolderFilter::new(source)?;
+    let mut results = Vec::new();
+    // Folders skipped this time keep their entry, so including them again later does not
+    // download them twice.
+    let mut state = previous.clone();
+
+    for folder in client.list()? {
+        let name = folder_display_name(&folder);
+        if !filter.matches(&name) {
+            continue;
+        }
+        let selected = client.examine(&folder.raw_name)?;
+        let prev = previous.folders.get(&name).copied();
+        let resynced = prev.is_some_and(|p| p.uidvalidity != selected.uidvalidity);
+        let last_uid = prev
+            .filter(|p| p.uidvalidity == selected.uidvalidity)
+            .map(|p| p.last_uid)
+            .unwrap_or(0);
+
+        let mut result = ImapFolderResult {
+            name: name.clone(),
+            uidvalidity: selected.uidvalidity,
+            last_uid,
+            messages: 0,
+            bytes: 0,
+            resynced,
+        };
+        if selected.exists > 0 {
+            let dir = dest.join(folder_archive_path(&folder));
+            for uid in client.search_from(last_uid.saturating_add(1))? {
+                let Some(message) = client.fetch(uid)? else {
+                    continue;
+                };
+                write_message(&dir, selected.uidvalidity, &message)?;
+                result.messages += 1;
+                result.bytes += message.body.len() as u64;
+                result.last_uid = result.last_uid.max(message.uid);
+            }
+        }
+        task_log::line(format!(
+            "imap: {name}: {} new message(s){}",
+            result.messages,
+            if resynced {
+                " (UIDVALIDITY changed, downloaded again)"
+            } else {
+                ""
+            }
+        ));
+        state.folders.insert(
+            name,
+            ImapFolderState {
+                uidvalidity: result.uidvalidity,
+                last_uid: result.last_uid,
+            },
+        );
+        results.push(result);
+    }
+
+    if results.is_empty() {
+        anyhow::bail!("no imap folder matched the include/exclude rules");
+    }
+    Ok((results, state))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn package(
+    data_dir: &Path,
+    job_id: &str,
+    run_id: &str,
+    started_at: OffsetDateTime,
+    pipeline: BuildPipelineOptions<'_>,
+    source_dir: &Path,
+    folders: Vec<ImapFolderResult>,
+    state: ImapSyncState,
+) -> Result<ImapRunBuild, anyhow::Error> {
+    let fs_source = FilesystemSource {
+        pre_scan: true,
+        paths: Vec::new(),
+        root: source_dir.to_string_lossy().to_string(),
+        include: Vec::new(),
+        exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
+        symlink_policy: FsSymlinkPolicy::Keep,
+        hardlink_policy: FsHardlinkPolicy::Copy,
+        error_policy: FsErrorPolicy::FailFast,
+        snapshot_mode: Default::default(),
+        snapshot_provider: None,
+        consistency_policy: Default::default(),
+        consistency_fail_threshold: None,
+        upload_on_consistency_failure: None,
+        windows_system_state: false,
+    };
+    let build = crate::backup::filesystem::build_filesystem_run(
+        data_dir, job_id, run_id, started_at, &fs_source, pipeline, None, None, None, None, None,
+    )?;
+    if build.issues.errors_total > 0 {
+        anyhow::bail!(
+            "unexpected filesystem issues while packaging mail: {}",
+            build.issues.errors_total
+        );
+    }
+
+    let source_files = folders.iter().map(|f| f.messages).sum();
+    let source_bytes = folders.iter().map(|f| f.bytes).sum();
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        folders = folders.len(),
+        messages = source_files,
+        entries_count = build.artifacts.entries_count,
+        "built imap backup artifacts"
+    );
+    Ok(ImapRunBuild {
+        artifacts: build.artifacts,
+        folders,
+        state,
+        source_files,
+        source_bytes,
+    })
+}
+
+struct FolderFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl FolderFilter {
+    fn new(source: &ImapSource) -> Result<Self, anyhow::Error> {
+        let build = |patterns: &[String]| -> Result<GlobSet, anyhow::Error> {
+            let mut set = GlobSetBuilder::new();
+            for pattern in patterns {
+                // `*` stays within one level; `**` crosses levels.
+                set.add(
+                    GlobBuilder::new(pattern.trim())
+                        .literal_separator(true)
+                        .build()?,
+                );
+            }
+            Ok(set.build()?)
+        };
+        Ok(Self {
+            include: (!source.include_folders.is_empty())
+                .then(|| build(&source.include_folders))
+                .transpose()?,
+            exclude: build(&source.exclude_folders)?,
+        })
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.include.as_ref().is_none_or(|set| set.is_match(name)) && !self.exclude.is_match(name)
+    }
+}
+
+fn folder_components(folder: &ListedFolder) -> Vec<String> {
+    let name = client::decode_modified_utf7(&folder.raw_name);
+    match folder.delimiter {
+        Some(delimiter) => name.split(delimiter).map(str::to_string).collect(),
+        None => vec![name],
+    }
+}
+
+/// Folder name with `/` as the hierarchy separator, used for include rules and the state.
+fn folder_display_name(folder: &ListedFolder) -> String {
+    folder_components(folder).join("/")
+}
+
+/// Relative directory of a folder in the tree, with path-unsafe components neutralised.
+fn folder_archive_path(folder: &ListedFolder) -> PathBuf {
+    folder_components(folder)
+        .into_iter()
+        .map(|component| {
+            let component = component.replace(['/', '\\', '\0'], "_");
+            match component.as_str() {
+                "" | "." | ".." => format!("_{component}"),
+                _ => component,
+            }
+        })
+        .collect()
+}
+
+fn maildir_flags(flags: &[String]) -> String {
+    let mut out = flags
+        .iter()
+        .filter_map(|flag| match flag.to_ascii_lowercase().as_str() {
+            "\\draft" => Some('D'),
+            "\\flagged" => Some('F'),
+            "\\answered" => Some('R'),
+            "\\seen" => Some('S'),
+            "\\deleted" => Some('T'),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    out.sort_unstable();
+    out.into_iter().collect()
+}
+
+fn write_message(
+    folder_dir: &Path,
+    uidvalidity: u32,
+    message: &FetchedMessage,
+) -> Result<(), anyhow::Error> {
+    for sub in ["cur", "new", "tmp"] {
+        std::fs::create_dir_all(folder_dir.join(sub))?;
+    }
+    let path = folder_dir.join("cur").join(format!(
+        "{uidvalidity}.{}.bastion{INFO_SEPARATOR}2,{}",
+        message.uid,
+        maildir_flags(&message.flags)
+    ));
+    std::fs::write(&path, &message.body)?;
+    if let Some(internal_date) = message.internal_date {
+        let _ =
+            filetime::set_file_mtime(&path, filetime::FileTime::from_unix_time(internal_date, 0));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,211 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+
+use bastion_core::job_spec::{ImapSecurityV1, ImapSource};
+use tempfile::tempdir;
+
+use super::client::{ImapClient, ListedFolder, Transport};
+use super::{
+    ImapFolderState, ImapLogin, ImapRunBuild, ImapSyncState, download_folders, finish_imap_run,
+    folder_archive_path, load_state, maildir_flags,
+};
+
+/// Serves one scripted IMAP session on a local port.
+fn fake_server() -> TcpStream {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let reader = BufReader::new(stream);
+        writer.write_all(b"* OK fake imap ready\r\n").unwrap();
+        let mut selected = String::new();
+        for line in reader.lines() {
+            let line = line.unwrap();
+            let (tag, command) = line.split_once(' ').unwrap();
+            let reply = match command {
+                c if c.starts_with("LOGIN ") => "".to_string(),
+                "LIST \"\" \"*\"" => concat!(
+                    "* LIST (\\HasNoChildren) \"/\" INBOX\r\n",
+                    "* LIST (\\Noselect \\HasChildren) \"/\" \"[Gmail]\"\r\n",
+                    "* LIST (\\HasNoChildren) \".\" \"Archive.Entw&APw-rfe\"\r\n",
+                    "* LIST (\\HasNoChildren) \"/\" {5}\r\nTrash\r\n",
+                )
+                .to_string(),
+                c if c.starts_with("EXAMINE ") => {
+                    selected = c
+                        .trim_start_matches("EXAMINE ")
+                        .trim_matches('"')
+                        .to_string();
+                    match selected.as_str() {
+                        "INBOX" => "* 2 EXISTS\r\n* OK [UIDVALIDITY 7] UIDs valid\r\n",
+                        "Archive.Entw&APw-rfe" => "* 1 EXISTS\r\n* OK [UIDVALIDITY 9] ok\r\n",
+                        other => panic!("unexpected folder selected: {other}"),
+                    }
+                    .to_string()
+                }
+                "UID SEARCH UID 2:*" if selected == "INBOX" => "* SEARCH 2\r\n".to_string(),
+                "UID SEARCH UID 1:*" => "* SEARCH 5\r\n".to_string(),
+                c if c.starts_with("UID FETCH ") => {
+                    let uid: u32 = c["UID FETCH ".len()..]
+                        .split(' ')
+                        .next()
+                        .unwrap()
+                        .parse()
+                        .unwrap();
+                    let body = format!("Subject: message {uid}\r\n\r\nbody {uid}\r\n");
+                    format!(
+                        "* 1 FETCH (UID {uid} FLAGS (\\Seen \\Answered) INTERNALDATE \"01-Jan-2024 00:00:00 +0000\" BODY[] {{{}}}\r\n{body})\r\n",
+                        body.len()
+                    )
+                }
+                "LOGOUT" => "* BYE\r\n".to_string(),
+                other => panic!("unexpected command: {other}"),
+            };
+            writer
+                .write_all(format!("{reply}{tag} OK done\r\n").as_bytes())
+                .unwrap();
+            if command == "LOGOUT" {
+                break;
+            }
+        }
+    });
+    TcpStream::connect(addr).unwrap()
+}
+
+fn source() -> ImapSource {
+    ImapSource {
+        host: "127.0.0.1".to_string(),
+        port: None,
+        security: ImapSecurityV1::Tls,
+        secret_name: "mail".to_string(),
+        include_folders: Vec::new(),
+        exclude_folders: vec!["Trash".to_string()],
+    }
+}
+
+#[test]
+fn only_messages_above_the_recorded_uid_are_downloaded() {
+    let tmp = tempdir().unwrap();
+    let mut client = ImapClient::new(Transport::Plain(fake_server()));
+    client.read_greeting().unwrap();
+    client
+        .login(&ImapLogin::Password {
+            username: "me".to_string(),
+            password: "secret \"quoted\"".to_string(),
+        })
+        .unwrap();
+
+    let mut previous = ImapSyncState::default();
+    previous.folders.insert(
+        "INBOX".to_string(),
+        ImapFolderState {
+            uidvalidity: 7,
+            last_uid: 1,
+        },
+    );
+    previous.folders.insert(
+        "Archive/Entwürfe".to_string(),
+        ImapFolderState {
+            uidvalidity: 3,
+            last_uid: 40,
+        },
+    );
+    let (folders, state) = download_folders(&mut client, &source(), &previous, tmp.path()).unwrap();
+    client.logout();
+
+    let names = folders.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["INBOX", "Archive/Entwürfe"]);
+    assert_eq!(folders[0].messages, 1);
+    assert!(!folders[0].resynced);
+    assert_eq!(folders[1].messages, 1);
+    assert!(folders[1].resynced, "UIDVALIDITY changed from 3 to 9");
+
+    assert_eq!(
+        state.folders["INBOX"],
+        ImapFolderState {
+            uidvalidity: 7,
+            last_uid: 2
+        }
+    );
+    assert_eq!(state.folders["Archive/Entwürfe"].last_uid, 5);
+
+    let sep = super::INFO_SEPARATOR;
+    let inbox = tmp
+        .path()
+        .join("INBOX/cur")
+        .join(format!("7.2.bastion{sep}2,RS"));
+    assert_eq!(
+        std::fs::read_to_string(&inbox).unwrap(),
+        "Subject: message 2\r\n\r\nbody 2\r\n"
+    );
+    assert!(tmp.path().join("INBOX/new").is_dir());
+    assert!(
+        tmp.path()
+            .join("Archive/Entwürfe/cur")
+            .join(format!("9.5.bastion{sep}2,RS"))
+            .is_file()
+    );
+    assert!(!tmp.path().join("Trash").exists());
+}
+
+#[test]
+fn state_is_persisted_only_by_finish() {
+    let tmp = tempdir().unwrap();
+    let job_id = "8d3f6b1e-0000-4000-8000-000000000001";
+    assert_eq!(
+        load_state(tmp.path(), job_id).unwrap(),
+        ImapSyncState::default()
+    );
+
+    let mut state = ImapSyncState::default();
+    state.folders.insert(
+        "INBOX".to_string(),
+        ImapFolderState {
+            uidvalidity: 1,
+            last_uid: 10,
+        },
+    );
+    let run_dir = tmp.path().join("runs/r1");
+    let build = ImapRunBuild {
+        artifacts: crate::backup::LocalRunArtifacts {
+            run_dir: run_dir.clone(),
+            parts: Vec::new(),
+            entries_index_path: run_dir.join("entries.jsonl.zst"),
+            entries_count: 0,
+            manifest_path: run_dir.join("manifest.json"),
+            complete_path: run_dir.join("complete.json"),
+        },
+        folders: Vec::new(),
+        state: state.clone(),
+        source_files: 0,
+        source_bytes: 0,
+    };
+    finish_imap_run(tmp.path(), job_id, &build).unwrap();
+    assert_eq!(load_state(tmp.path(), job_id).unwrap(), state);
+}
+
+#[test]
+fn folder_paths_and_flags_are_maildir_safe() {
+    let folder = |raw: &str, delimiter: Option<char>| ListedFolder {
+        raw_name: raw.to_string(),
+        delimiter,
+    };
+    assert_eq!(
+        folder_archive_path(&folder("Work/2024.Q1", Some('.'))),
+        std::path::PathBuf::from("Work_2024/Q1")
+    );
+    assert_eq!(
+        folder_archive_path(&folder("Lists/../x", Some('/'))),
+        std::path::PathBuf::from("Lists/_../x")
+    );
+    assert_eq!(
+        maildir_flags(&[
+            "\\Seen".to_string(),
+            "\\Flagged".to_string(),
+            "$Junk".to_string(),
+            "\\Draft".to_string(),
+        ]),
+        "DFS"
+    );
+}
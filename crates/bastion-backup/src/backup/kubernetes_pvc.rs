@@ -0,0 +1,786 @@
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::Serialize;
+use time::OffsetDateTime;
+use tracing::{info, warn};
+
+use crate::backup::{BuildPipelineOptions, LocalArtifact, LocalRunArtifacts, PartStream, task_log};
+use bastion_core::job_spec::{
+    FilesystemSource, FsErrorPolicy, FsHardlinkPolicy, FsSymlinkPolicy, KubernetesPvcModeV1,
+    KubernetesPvcSource,
+};
+use bastion_core::manifest::ManifestV1;
+
+/// Mount path of the snapshot clone inside the reader pod.
+const READER_MOUNT_PATH: &str = "/data";
+/// Resolved from the node's `PATH`; job specs cannot point at a different executable.
+const KUBECTL: &str = "kubectl";
+/// How long to wait for a snapshot to become ready, or a reader pod to start.
+const WAIT_TIMEOUT: &str = "--timeout=600s";
+/// Label put on every object a run creates, so leftovers can be found and removed.
+const RUN_LABEL: &str = "bastion/run";
+
+/// A PVC matched by the label selector, as recorded in the manifest and run summary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedPvc {
+    pub name: String,
+    pub namespace: String,
+    pub volume_name: Option<String>,
+    pub storage_class: Option<String>,
+    pub capacity: Option<String>,
+    /// Directory of the PVC's data inside the archive.
+    pub archive_path: String,
+    /// `VolumeSnapshot` the data was read from (`csi_snapshot` mode).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct KubernetesPvcRunBuild {
+    pub artifacts: LocalRunArtifacts,
+    pub label_selector: String,
+    pub mode: KubernetesPvcModeV1,
+    pub pvcs: Vec<ResolvedPvc>,
+    pub source_files: u64,
+    pub source_bytes: u64,
+}
+
+impl KubernetesPvcRunBuild {
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "label_selector": self.label_selector,
+            "mode": self.mode,
+            "pvcs": self.pvcs,
+        })
+    }
+}
+
+/// Resolves the label selector and archives the data of every matched, bound PVC.
+///
+/// The resolved PVC list is written to the manifest's `source` field, so a snapshot records
+/// which volumes it holds even when the selector matches different PVCs later.
+#[allow(clippy::too_many_arguments)]
+pub fn build_kubernetes_pvc_run(
+    data_dir: &Path,
+    job_id: &str,
+    run_id: &str,
+    started_at: OffsetDateTime,
+    source: &KubernetesPvcSource,
+    pipeline: BuildPipelineOptions<'_>,
+    on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> std::io::Result<()> + Send>>,
+    part_stream: Option<Box<dyn PartStream>>,
+) -> Result<KubernetesPvcRunBuild, anyhow::Error> {
+    build_with_kubectl(
+        &Kubectl::new(KUBECTL),
+        data_dir,
+        job_id,
+        run_id,
+        started_at,
+        source,
+        pipeline,
+        on_part_finished,
+        part_stream,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_with_kubectl(
+    kubectl: &Kubectl,
+    data_dir: &Path,
+    job_id: &str,
+    run_id: &str,
+    started_at: OffsetDateTime,
+    source: &KubernetesPvcSource,
+    pipeline: BuildPipelineOptions<'_>,
+    on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> std::io::Result<()> + Send>>,
+    part_stream: Option<Box<dyn PartStream>>,
+) -> Result<KubernetesPvcRunBuild, anyhow::Error> {
+    let BuildPipelineOptions {
+        artifact_format,
+        compression,
+        encryption,
+        part_size_bytes,
+        staging_min_free_bytes,
+    } = pipeline;
+    let label_selector = source.label_selector.trim().to_string();
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        label_selector = %label_selector,
+        mode = ?source.mode,
+        artifact_format = ?artifact_format,
+        compression = compression.algorithm.as_str(),
+        encryption = ?encryption,
+        part_size_bytes,
+        "building kubernetes pvc backup artifacts"
+    );
+
+    let mut args = vec!["get", "pvc", "-l", &label_selector, "-o", "json"];
+    let namespace = source
+        .namespace
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    if let Some(namespace) = namespace {
+        args.extend(["-n", namespace]);
+    }
+    let pvcs = bound_pvcs(parse_pvc_list(&kubectl.run(&args)?)?);
+    if pvcs.is_empty() {
+        anyhow::bail!("label selector {label_selector:?} matched no bound PVCs");
+    }
+    task_log::line(format!(
+        "kubernetes_pvc: {label_selector} matched {} PVC(s): {}",
+        pvcs.len(),
+        pvcs.iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+
+    let run_dir = crate::backup::run_dir(data_dir, run_id);
+    let source_dir = run_dir.join("source");
+    std::fs::create_dir_all(&source_dir)?;
+
+    let (fs_source, resolved) = match source.mode {
+        KubernetesPvcModeV1::Mounted => {
+            let mount_root = PathBuf::from(source.effective_mount_root());
+            let mut paths = Vec::with_capacity(pvcs.len());
+            let mut resolved = Vec::with_capacity(pvcs.len());
+            for pvc in pvcs {
+                let path = mount_root.join(&pvc.name);
+                if !path.is_dir() {
+                    anyhow::bail!("PVC {} is not mounted at {}", pvc.name, path.display());
+                }
+                resolved.push(pvc.resolved(mounted_archive_path(&path), None));
+                paths.push(path.to_string_lossy().to_string());
+            }
+            (packaging_source(paths, String::new()), resolved)
+        }
+        KubernetesPvcModeV1::CsiSnapshot => {
+            let resolved = copy_from_snapshots(kubectl, source, run_id, &pvcs, &source_dir)?;
+            (
+                packaging_source(Vec::new(), source_dir.to_string_lossy().to_string()),
+                resolved,
+            )
+        }
+    };
+
+    let build = crate::backup::filesystem::build_filesystem_run(
+        data_dir,
+        job_id,
+        run_id,
+        started_at,
+        &fs_source,
+        BuildPipelineOptions {
+            artifact_format,
+            compression,
+            encryption,
+            part_size_bytes,
+            staging_min_free_bytes,
+        },
+        None,
+        None,
+        on_part_finished,
+        None,
+        part_stream,
+    )?;
+    if build.issues.errors_total > 0 {
+        anyhow::bail!(
+            "unexpected filesystem issues while packaging PVC data: {}",
+            build.issues.errors_total
+        );
+    }
+    let artifacts = build.artifacts;
+    let source_total = build.source_total.unwrap_or_default();
+
+    let out = KubernetesPvcRunBuild {
+        artifacts,
+        label_selector,
+        mode: source.mode,
+        pvcs: resolved,
+        source_files: source_total.files,
+        source_bytes: source_total.bytes,
+    };
+    record_manifest_source(
+        &out.artifacts.manifest_path,
+        serde_json::json!({ "kubernetes_pvc": out.summary() }),
+    )?;
+
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        pvcs = out.pvcs.len(),
+        entries_count = out.artifacts.entries_count,
+        parts_count = out.artifacts.parts.len(),
+        "built kubernetes pvc backup artifacts"
+    );
+    Ok(out)
+}
+
+pub(super) fn packaging_source(paths: Vec<String>, root: String) -> FilesystemSource {
+    FilesystemSource {
+        pre_scan: true,
+        paths,
+        root,
+        include: Vec::new(),
+        exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
+        symlink_policy: FsSymlinkPolicy::Keep,
+        hardlink_policy: FsHardlinkPolicy::Copy,
+        error_policy: FsErrorPolicy::FailFast,
+        snapshot_mode: Default::default(),
+        snapshot_provider: None,
+        consistency_policy: Default::default(),
+        consistency_fail_threshold: None,
+        upload_on_consistency_failure: None,
+        windows_system_state: false,
+    }
+}
+
+pub(super) fn record_manifest_source(
+    path: &Path,
+    value: serde_json::Value,
+) -> Result<(), anyhow::Error> {
+    let mut manifest = serde_json::from_slice::<ManifestV1>(&std::fs::read(path)?)?;
+    manifest.source = Some(value);
+    std::fs::write(path, serde_json::to_vec_pretty(&manifest)?)?;
+    Ok(())
+}
+
+/// Archive directory of a mounted PVC: its path without the leading `/`, as packaged from
+/// `paths`.
+fn mounted_archive_path(path: &Path) -> String {
+    path.to_string_lossy()
+        .trim_start_matches('/')
+        .trim_end_matches('/')
+        .to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PvcInfo {
+    name: String,
+    namespace: String,
+    phase: String,
+    volume_name: Option<String>,
+    storage_class: Option<String>,
+    capacity: Option<String>,
+}
+
+impl PvcInfo {
+    fn resolved(self, archive_path: String, snapshot: Option<String>) -> ResolvedPvc {
+        ResolvedPvc {
+            name: self.name,
+            namespace: self.namespace,
+            volume_name: self.volume_name,
+            storage_class: self.storage_class,
+            capacity: self.capacity,
+            archive_path,
+            snapshot,
+        }
+    }
+}
+
+/// Parses `kubectl get pvc -o json`.
+fn parse_pvc_list(output: &str) -> Result<Vec<PvcInfo>, anyhow::Error> {
+    let list: serde_json::Value = serde_json::from_str(output)
+        .map_err(|error| anyhow::anyhow!("invalid kubectl output: {error}"))?;
+    let str_at = |item: &serde_json::Value, pointer: &str| {
+        item.pointer(pointer)
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let mut out = list
+        .get("items")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            Some(PvcInfo {
+                name: str_at(item, "/metadata/name")?,
+                namespace: str_at(item, "/metadata/namespace").unwrap_or_else(|| "default".into()),
+                phase: str_at(item, "/status/phase").unwrap_or_default(),
+                volume_name: str_at(item, "/spec/volumeName"),
+                storage_class: str_at(item, "/spec/storageClassName"),
+                capacity: str_at(item, "/status/capacity/storage")
+                    .or_else(|| str_at(item, "/spec/resources/requests/storage")),
+            })
+        })
+        .collect::<Vec<_>>();
+    out.sort_by(|a, b| (&a.namespace, &a.name).cmp(&(&b.namespace, &b.name)));
+    Ok(out)
+}
+
+fn bound_pvcs(pvcs: Vec<PvcInfo>) -> Vec<PvcInfo> {
+    pvcs.into_iter()
+        .filter(|pvc| {
+            let bound = pvc.phase == "Bound";
+            if !bound {
+                task_log::line(format!(
+                    "kubernetes_pvc: skipping PVC {} ({})",
+                    pvc.name,
+                    if pvc.phase.is_empty() {
+                        "unknown phase"
+                    } else {
+                        pvc.phase.as_str()
+                    }
+                ));
+            }
+            bound
+        })
+        .collect()
+}
+
+/// Snapshots every PVC first (so the volumes are captured at nearly the same moment), then
+/// copies each snapshot into `dest/<pvc>` through a clone PVC and a reader pod. Every object
+/// created here is deleted before returning, also on errors.
+fn copy_from_snapshots(
+    kubectl: &Kubectl,
+    source: &KubernetesPvcSource,
+    run_id: &str,
+    pvcs: &[PvcInfo],
+    dest: &Path,
+) -> Result<Vec<ResolvedPvc>, anyhow::Error> {
+    let objects = pvcs
+        .iter()
+        .enumerate()
+        .map(|(index, pvc)| (pvc, object_name(run_id, index)))
+        .collect::<Vec<_>>();
+
+    let result = snapshot_and_copy(kubectl, source, run_id, &objects, dest);
+
+    for (pvc, name) in &objects {
+        kubectl.delete(
+            &pvc.namespace,
+            &[
+                format!("pod/{name}"),
+                format!("pvc/{name}"),
+                format!("volumesnapshot/{name}"),
+            ],
+        );
+    }
+    result
+}
+
+fn snapshot_and_copy(
+    kubectl: &Kubectl,
+    source: &KubernetesPvcSource,
+    run_id: &str,
+    objects: &[(&PvcInfo, String)],
+    dest: &Path,
+) -> Result<Vec<ResolvedPvc>, anyhow::Error> {
+    for (pvc, name) in objects {
+        kubectl.apply(&volume_snapshot_object(
+            name,
+            run_id,
+            pvc,
+            source.volume_snapshot_class.as_deref(),
+        ))?;
+    }
+    for (pvc, name) in objects {
+        kubectl.run(&[
+            "wait",
+            "-n",
+            &pvc.namespace,
+            "--for=jsonpath={.status.readyToUse}=true",
+            &format!("volumesnapshot/{name}"),
+            WAIT_TIMEOUT,
+        ])?;
+    }
+    task_log::line(format!(
+        "kubernetes_pvc: {} volume snapshot(s) ready",
+        objects.len()
+    ));
+
+    let mut resolved = Vec::with_capacity(objects.len());
+    for (pvc, name) in objects {
+        kubectl.apply(&clone_pvc_object(name, run_id, pvc))?;
+        kubectl.apply(&reader_pod_object(
+            name,
+            run_id,
+            &pvc.namespace,
+            source.effective_reader_image(),
+        ))?;
+        kubectl.run(&[
+            "wait",
+            "-n",
+            &pvc.namespace,
+            "--for=condition=Ready",
+            &format!("pod/{name}"),
+            WAIT_TIMEOUT,
+        ])?;
+        let pvc_dir = dest.join(&pvc.name);
+        std::fs::create_dir_all(&pvc_dir)?;
+        kubectl.copy_out(&pvc.namespace, name, &pvc_dir)?;
+        kubectl.delete(
+            &pvc.namespace,
+            &[format!("pod/{name}"), format!("pvc/{name}")],
+        );
+        resolved.push(
+            (*pvc)
+                .clone()
+                .resolved(pvc.name.clone(), Some(name.clone())),
+        );
+    }
+    Ok(resolved)
+}
+
+/// Names of the objects created for the PVC at `index`: short, DNS-safe and unique per run.
+fn object_name(run_id: &str, index: usize) -> String {
+    let run = run_id
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .take(8)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    format!("bastion-{run}-{index}")
+}
+
+fn object_metadata(name: &str, run_id: &str, namespace: &str) -> serde_json::Value {
+    serde_json::json!({
+        "name": name,
+        "namespace": namespace,
+        "labels": {
+            "app.kubernetes.io/managed-by": "bastion",
+            RUN_LABEL: run_id,
+        },
+    })
+}
+
+fn volume_snapshot_object(
+    name: &str,
+    run_id: &str,
+    pvc: &PvcInfo,
+    class: Option<&str>,
+) -> serde_json::Value {
+    let mut spec = serde_json::json!({
+        "source": { "persistentVolumeClaimName": pvc.name },
+    });
+    if let Some(class) = class.map(str::trim).filter(|v| !v.is_empty()) {
+        spec["volumeSnapshotClassName"] = serde_json::Value::from(class);
+    }
+    serde_json::json!({
+        "apiVersion": "snapshot.storage.k8s.io/v1",
+        "kind": "VolumeSnapshot",
+        "metadata": object_metadata(name, run_id, &pvc.namespace),
+        "spec": spec,
+    })
+}
+
+fn clone_pvc_object(name: &str, run_id: &str, pvc: &PvcInfo) -> serde_json::Value {
+    let mut spec = serde_json::json!({
+        "accessModes": ["ReadWriteOnce"],
+        "resources": { "requests": { "storage": pvc.capacity.as_deref().unwrap_or("1Gi") } },
+        "dataSource": {
+            "name": name,
+            "kind": "VolumeSnapshot",
+            "apiGroup": "snapshot.storage.k8s.io",
+        },
+    });
+    if let Some(class) = &pvc.storage_class {
+        spec["storageClassName"] = serde_json::Value::from(class.as_str());
+    }
+    serde_json::json!({
+        "apiVersion": "v1",
+        "kind": "PersistentVolumeClaim",
+        "metadata": object_metadata(name, run_id, &pvc.namespace),
+        "spec": spec,
+    })
+}
+
+fn reader_pod_object(name: &str, run_id: &str, namespace: &str, image: &str) -> serde_json::Value {
+    serde_json::json!({
+        "apiVersion": "v1",
+        "kind": "Pod",
+        "metadata": object_metadata(name, run_id, namespace),
+        "spec": {
+            "restartPolicy": "Never",
+            "containers": [{
+                "name": "reader",
+                "image": image,
+                "command": ["sleep", "86400"],
+                "volumeMounts": [{ "name": "data", "mountPath": READER_MOUNT_PATH, "readOnly": true }],
+            }],
+            "volumes": [{
+                "name": "data",
+                "persistentVolumeClaim": { "claimName": name, "readOnly": true },
+            }],
+        },
+    })
+}
+
+struct Kubectl {
+    program: String,
+}
+
+impl Kubectl {
+    fn new(program: &str) -> Self {
+        Self {
+            program: program.to_string(),
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String, anyhow::Error> {
+        let output = Command::new(&self.program)
+            .args(args)
+            .output()
+            .map_err(|error| anyhow::anyhow!("failed to run {}: {error}", self.program))?;
+        self.check(args, &output)?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn apply(&self, object: &serde_json::Value) -> Result<(), anyhow::Error> {
+        let args = ["apply", "-f", "-"];
+        let mut child = Command::new(&self.program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|error| anyhow::anyhow!("failed to run {}: {error}", self.program))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(&serde_json::to_vec(object)?)?;
+        }
+        let output = child.wait_with_output()?;
+        self.check(&args, &output)
+    }
+
+    /// Streams the reader pod's mounted volume as a tar archive and unpacks it into `dest`.
+    fn copy_out(&self, namespace: &str, pod: &str, dest: &Path) -> Result<(), anyhow::Error> {
+        let mut child = Command::new(&self.program)
+            .args(["exec", "-n", namespace, pod, "--"])
+            .args(["tar", "-C", READER_MOUNT_PATH, "-cf", "-", "."])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|error| anyhow::anyhow!("failed to run {}: {error}", self.program))?;
+        let unpacked = match child.stdout.take() {
+            Some(stdout) => tar::Archive::new(stdout).unpack(dest),
+            None => Ok(()),
+        };
+        let output = child.wait_with_output()?;
+        self.check(&["exec", pod, "--", "tar"], &output)?;
+        unpacked.map_err(|error| anyhow::anyhow!("failed to unpack data of pod {pod}: {error}"))
+    }
+
+    /// Best-effort removal; failures are logged so they do not hide the run's own error.
+    fn delete(&self, namespace: &str, objects: &[String]) {
+        let mut args = vec![
+            "delete",
+            "-n",
+            namespace,
+            "--ignore-not-found",
+            "--wait=false",
+        ];
+        args.extend(objects.iter().map(String::as_str));
+        if let Err(error) = self.run(&args) {
+            warn!(namespace = %namespace, objects = ?objects, error = %error, "failed to delete kubernetes objects");
+        }
+    }
+
+    fn check(&self, args: &[&str], output: &std::process::Output) -> Result<(), anyhow::Error> {
+        if output.status.success() {
+            // Object listings can be large; only the command line goes to the task log.
+            task_log::line(format!(
+                "$ {} {} ({})",
+                self.program,
+                args.join(" "),
+                output.status
+            ));
+            return Ok(());
+        }
+        task_log::command_output(&self.program, output);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(
+            "kubectl {} failed ({}): {}",
+            args.first().copied().unwrap_or_default(),
+            output.status,
+            stderr.trim()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        PvcInfo, bound_pvcs, clone_pvc_object, mounted_archive_path, object_name, parse_pvc_list,
+        volume_snapshot_object,
+    };
+
+    const PVC_LIST: &str = r#"{
+      "items": [
+        {
+          "metadata": { "name": "gitea-data", "namespace": "apps" },
+          "spec": { "volumeName": "pvc-2", "storageClassName": "longhorn" },
+          "status": { "phase": "Bound", "capacity": { "storage": "10Gi" } }
+        },
+        {
+          "metadata": { "name": "gitea-cache", "namespace": "apps" },
+          "spec": { "resources": { "requests": { "storage": "1Gi" } } },
+          "status": { "phase": "Pending" }
+        },
+        {
+          "metadata": { "name": "gitea-db", "namespace": "apps" },
+          "spec": { "volumeName": "pvc-1" },
+          "status": { "phase": "Bound", "capacity": { "storage": "5Gi" } }
+        }
+      ]
+    }"#;
+
+    fn pvc(name: &str) -> PvcInfo {
+        PvcInfo {
+            name: name.to_string(),
+            namespace: "apps".to_string(),
+            phase: "Bound".to_string(),
+            volume_name: None,
+            storage_class: Some("longhorn".to_string()),
+            capacity: Some("10Gi".to_string()),
+        }
+    }
+
+    #[test]
+    fn pvc_list_is_sorted_and_unbound_claims_are_skipped() {
+        let pvcs = parse_pvc_list(PVC_LIST).unwrap();
+        assert_eq!(
+            pvcs.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            vec!["gitea-cache", "gitea-data", "gitea-db"]
+        );
+        assert_eq!(pvcs[0].capacity.as_deref(), Some("1Gi"));
+        assert_eq!(pvcs[1].storage_class.as_deref(), Some("longhorn"));
+
+        let bound = bound_pvcs(pvcs);
+        assert_eq!(
+            bound.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            vec!["gitea-data", "gitea-db"]
+        );
+        assert!(parse_pvc_list("not json").is_err());
+    }
+
+    #[test]
+    fn snapshot_objects_are_labelled_and_restored_with_the_source_class() {
+        let name = object_name("0c4F-19aa-7d2e", 1);
+        assert_eq!(name, "bastion-0c4f19aa-1");
+
+        let snapshot = volume_snapshot_object(&name, "run-1", &pvc("gitea-data"), Some("csi-snap"));
+        assert_eq!(
+            snapshot["spec"]["source"]["persistentVolumeClaimName"],
+            "gitea-data"
+        );
+        assert_eq!(snapshot["spec"]["volumeSnapshotClassName"], "csi-snap");
+        assert_eq!(snapshot["metadata"]["namespace"], "apps");
+        assert_eq!(snapshot["metadata"]["labels"]["bastion/run"], "run-1");
+        assert!(
+            volume_snapshot_object(&name, "run-1", &pvc("gitea-data"), None)["spec"]
+                .get("volumeSnapshotClassName")
+                .is_none()
+        );
+
+        let clone = clone_pvc_object(&name, "run-1", &pvc("gitea-data"));
+        assert_eq!(clone["spec"]["dataSource"]["name"], name.as_str());
+        assert_eq!(clone["spec"]["storageClassName"], "longhorn");
+        assert_eq!(clone["spec"]["resources"]["requests"]["storage"], "10Gi");
+    }
+
+    #[test]
+    fn mounted_pvcs_are_archived_under_their_mount_path() {
+        assert_eq!(
+            mounted_archive_path(std::path::Path::new("/pvc/gitea-data")),
+            "pvc/gitea-data"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn mounted_mode_records_resolved_pvcs_in_the_manifest() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        use bastion_core::job_spec::{CompressionV1, KubernetesPvcModeV1, KubernetesPvcSource};
+        use bastion_core::manifest::{ArtifactFormatV1, ManifestV1};
+        use time::OffsetDateTime;
+
+        use super::{Kubectl, build_with_kubectl};
+        use crate::backup::{BuildPipelineOptions, PayloadEncryption};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let mount_root = tmp.path().join("pvc");
+        for name in ["gitea-data", "gitea-db"] {
+            std::fs::create_dir_all(mount_root.join(name)).unwrap();
+            std::fs::write(mount_root.join(name).join("file.txt"), name).unwrap();
+        }
+        let list = tmp.path().join("pvcs.json");
+        std::fs::write(&list, PVC_LIST).unwrap();
+
+        // Fake kubectl: answers the PVC listing only.
+        let kubectl = tmp.path().join("kubectl");
+        std::fs::write(
+            &kubectl,
+            format!(
+                "#!/bin/sh\n[ \"$1\" = get ] && [ \"$2\" = pvc ] && exec cat '{}'\nexit 1\n",
+                list.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&kubectl, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let source = KubernetesPvcSource {
+            namespace: Some("apps".to_string()),
+            label_selector: "app=gitea".to_string(),
+            mode: KubernetesPvcModeV1::Mounted,
+            mount_root: Some(mount_root.to_string_lossy().to_string()),
+            volume_snapshot_class: None,
+            reader_image: None,
+        };
+        let encryption = PayloadEncryption::None;
+        let build = build_with_kubectl(
+            &Kubectl::new(&kubectl.to_string_lossy()),
+            &data_dir,
+            &uuid::Uuid::new_v4().to_string(),
+            &uuid::Uuid::new_v4().to_string(),
+            OffsetDateTime::now_utc(),
+            &source,
+            BuildPipelineOptions {
+                artifact_format: ArtifactFormatV1::ArchiveV1,
+                compression: CompressionV1::default(),
+                encryption: &encryption,
+                part_size_bytes: 4 * 1024 * 1024,
+                staging_min_free_bytes: 0,
+            },
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(
+            build
+                .pvcs
+                .iter()
+                .map(|p| p.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["gitea-data", "gitea-db"]
+        );
+        assert_eq!(build.source_files, 2);
+
+        let manifest: ManifestV1 =
+            serde_json::from_slice(&std::fs::read(&build.artifacts.manifest_path).unwrap())
+                .unwrap();
+        let recorded = manifest.source.expect("source recorded");
+        let recorded = &recorded["kubernetes_pvc"];
+        assert_eq!(recorded["label_selector"], "app=gitea");
+        assert_eq!(recorded["mode"], "mounted");
+        assert_eq!(recorded["pvcs"][0]["name"], "gitea-data");
+        assert_eq!(recorded["pvcs"][0]["volume_name"], "pvc-2");
+        assert_eq!(
+            recorded["pvcs"][1]["archive_path"],
+            mounted_archive_path(&mount_root.join("gitea-db")).as_str()
+        );
+    }
+}
@@ -1,7 +1,15 @@
 pub mod filesystem;
+pub mod git;
+pub mod imap;
+pub mod kubernetes_pvc;
+pub mod postgres;
 pub mod source_consistency;
 pub mod sqlite;
+pub mod sqlite_app;
+pub mod staging;
+pub mod task_log;
 pub mod vaultwarden;
+pub mod vm_image;
 
 mod hashing_reader;
 
@@ -12,6 +20,7 @@ use std::path::{Path, PathBuf};
 pub use bastion_core::backup_format::{
     COMPLETE_NAME, ENTRIES_INDEX_NAME, LocalArtifact, LocalRunArtifacts, MANIFEST_NAME,
 };
+use bastion_core::job_spec::CompressionV1;
 use bastion_core::manifest::{ArtifactFormatV1, ArtifactPart, HashAlgorithm};
 
 #[derive(Debug, Clone, Default)]
@@ -27,8 +36,11 @@ pub enum PayloadEncryption {
 #[derive(Debug, Clone)]
 pub struct BuildPipelineOptions<'a> {
     pub artifact_format: ArtifactFormatV1,
+    pub compression: CompressionV1,
     pub encryption: &'a PayloadEncryption,
     pub part_size_bytes: u64,
+    /// Free space the staging disk must keep while parts are written; `0` disables the check.
+    pub staging_min_free_bytes: u64,
 }
 
 pub fn run_dir(data_dir: &Path, run_id: &str) -> PathBuf {
@@ -39,6 +51,16 @@ pub fn stage_dir(data_dir: &Path, run_id: &str) -> PathBuf {
     run_dir(data_dir, run_id).join("staging")
 }
 
+/// Receives payload parts while they are written, so nothing is staged locally.
+///
+/// Parts are delivered one at a time: `begin_part`, any number of `write_part` calls, then
+/// `finish_part`. Errors abort the build.
+pub trait PartStream: Send {
+    fn begin_part(&mut self, name: &str) -> io::Result<()>;
+    fn write_part(&mut self, buf: &[u8]) -> io::Result<()>;
+    fn finish_part(&mut self, part: &ArtifactPart) -> io::Result<()>;
+}
+
 pub struct PartWriter {
     dir: PathBuf,
     part_size: u64,
@@ -47,12 +69,16 @@ pub struct PartWriter {
     current: Option<PartState>,
     parts: Vec<ArtifactPart>,
     on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> io::Result<()> + Send>>,
+    stream: Option<Box<dyn PartStream>>,
+    min_free_bytes: u64,
+    bytes_since_space_check: u64,
 }
 
 #[derive(Debug)]
 struct PartState {
     name: String,
-    file: File,
+    /// `None` while streaming.
+    file: Option<File>,
     hasher: blake3::Hasher,
     size: u64,
 }
@@ -67,6 +93,9 @@ impl PartWriter {
             current: None,
             parts: Vec::new(),
             on_part_finished: None,
+            stream: None,
+            min_free_bytes: 0,
+            bytes_since_space_check: 0,
         })
     }
 
@@ -77,6 +106,25 @@ impl PartWriter {
         self.on_part_finished = Some(cb);
     }
 
+    /// Sends part bytes to `stream` instead of files in the staging directory.
+    pub fn set_stream(&mut self, stream: Box<dyn PartStream>) {
+        self.stream = Some(stream);
+    }
+
+    /// Fails writes with [`staging::StagingDiskFull`] once the staging disk drops below
+    /// `min_free_bytes`. Streamed parts are not checked.
+    pub fn set_min_free_bytes(&mut self, min_free_bytes: u64) {
+        self.min_free_bytes = min_free_bytes;
+    }
+
+    fn check_free_space(&mut self) -> io::Result<()> {
+        self.bytes_since_space_check = 0;
+        if self.stream.is_some() {
+            return Ok(());
+        }
+        staging::check_free_space(&self.dir, self.min_free_bytes).map_err(io::Error::other)
+    }
+
     pub fn finish(mut self) -> Result<Vec<ArtifactPart>, io::Error> {
         self.finish_part()?;
         Ok(self.parts)
@@ -87,11 +135,19 @@ impl PartWriter {
             return Ok(());
         }
 
-        std::fs::create_dir_all(&self.dir)?;
         let name = format!("{}{idx:06}", self.prefix, idx = self.next_index);
         self.next_index += 1;
-        let path = self.dir.join(&name);
-        let file = File::create(path)?;
+        let file = match self.stream.as_mut() {
+            Some(stream) => {
+                stream.begin_part(&name)?;
+                None
+            }
+            None => {
+                std::fs::create_dir_all(&self.dir)?;
+                self.check_free_space()?;
+                Some(File::create(self.dir.join(&name))?)
+            }
+        };
         self.current = Some(PartState {
             name,
             file,
@@ -106,20 +162,35 @@ impl PartWriter {
             return Ok(());
         };
 
-        if state.size == 0 {
+        let hash = state.hasher.finalize().to_hex().to_string();
+        let name = state.name;
+        let size = state.size;
+        let hash_alg = HashAlgorithm::Blake3;
+
+        if let Some(stream) = self.stream.as_mut() {
+            // An empty part still has to be closed on the receiving side, but is not recorded.
+            let part = ArtifactPart {
+                name,
+                size,
+                hash_alg,
+                hash,
+            };
+            stream.finish_part(&part)?;
+            if size > 0 {
+                self.parts.push(part);
+            }
+            return Ok(());
+        }
+
+        if size == 0 {
             return Ok(());
         }
 
         // Close the part before notifying external consumers so they can safely read it.
         // We also flush to ensure the final size and contents are visible.
-        let mut file = state.file;
-        file.flush()?;
-        drop(file);
-
-        let hash = state.hasher.finalize().to_hex().to_string();
-        let name = state.name;
-        let size = state.size;
-        let hash_alg = HashAlgorithm::Blake3;
+        if let Some(mut file) = state.file {
+            file.flush()?;
+        }
 
         if let Some(cb) = self.on_part_finished.as_ref() {
             cb(LocalArtifact {
@@ -164,21 +235,97 @@ impl Write for PartWriter {
             }
 
             let n = remaining.min(buf.len() - total_written);
-            state
-                .file
-                .write_all(&buf[total_written..total_written + n])?;
-            state.hasher.update(&buf[total_written..total_written + n]);
+            let chunk = &buf[total_written..total_written + n];
+            match (state.file.as_mut(), self.stream.as_mut()) {
+                (Some(file), _) => file.write_all(chunk)?,
+                (None, Some(stream)) => stream.write_part(chunk)?,
+                (None, None) => return Err(io::Error::other("part has no output")),
+            }
+            state.hasher.update(chunk);
             state.size = state.size.saturating_add(n as u64);
             total_written += n;
+
+            self.bytes_since_space_check = self.bytes_since_space_check.saturating_add(n as u64);
+            if self.bytes_since_space_check >= staging::FREE_SPACE_CHECK_INTERVAL_BYTES {
+                self.check_free_space()?;
+            }
         }
 
         Ok(buf.len())
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        if let Some(state) = self.current.as_mut() {
-            state.file.flush()?;
+        if let Some(file) = self.current.as_mut().and_then(|state| state.file.as_mut()) {
+            file.flush()?;
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+    use std::sync::{Arc, Mutex};
+
+    use bastion_core::manifest::ArtifactPart;
+
+    use super::{PartStream, PartWriter};
+
+    #[derive(Default)]
+    struct Recorded {
+        parts: Vec<(String, Vec<u8>)>,
+        finished: Vec<ArtifactPart>,
+    }
+
+    struct RecordingStream(Arc<Mutex<Recorded>>);
+
+    impl PartStream for RecordingStream {
+        fn begin_part(&mut self, name: &str) -> std::io::Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .parts
+                .push((name.to_string(), Vec::new()));
+            Ok(())
+        }
+
+        fn write_part(&mut self, buf: &[u8]) -> std::io::Result<()> {
+            let mut recorded = self.0.lock().unwrap();
+            recorded.parts.last_mut().unwrap().1.extend_from_slice(buf);
+            Ok(())
+        }
+
+        fn finish_part(&mut self, part: &ArtifactPart) -> std::io::Result<()> {
+            self.0.lock().unwrap().finished.push(part.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn streaming_part_writer_splits_parts_without_local_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("staging");
+        let recorded = Arc::new(Mutex::new(Recorded::default()));
+
+        let mut writer = PartWriter::new(dir.clone(), 4, "payload.part").unwrap();
+        writer.set_stream(Box::new(RecordingStream(recorded.clone())));
+        writer.write_all(b"0123456789").unwrap();
+        let parts = writer.finish().unwrap();
+
+        assert!(!dir.exists());
+        assert_eq!(
+            parts.iter().map(|p| p.size).collect::<Vec<_>>(),
+            vec![4, 4, 2]
+        );
+
+        let recorded = recorded.lock().unwrap();
+        let bytes = recorded
+            .parts
+            .iter()
+            .map(|(_, bytes)| bytes.as_slice())
+            .collect::<Vec<_>>();
+        assert_eq!(bytes, vec![&b"0123"[..], b"4567", b"89"]);
+        assert_eq!(recorded.parts[0].0, "payload.part000001");
+        assert_eq!(recorded.finished, parts);
+    }
+}
@@ -0,0 +1,353 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use time::OffsetDateTime;
+use tracing::{info, warn};
+
+use crate::backup::{BuildPipelineOptions, LocalArtifact, LocalRunArtifacts, PartStream, task_log};
+use bastion_core::job_spec::{
+    FilesystemSource, FsErrorPolicy, FsHardlinkPolicy, FsSymlinkPolicy, PostgresSource,
+};
+
+/// Directory of the base backup (a plain-format `PGDATA`) inside the archive.
+pub const BASE_DIR: &str = "base";
+/// Directory of the shipped WAL segments inside the archive.
+pub const WAL_DIR: &str = "wal";
+/// Spool file recording when the last base backup was uploaded (unix seconds).
+const LAST_BASE_BACKUP_FILE: &str = ".bastion-last-base-backup";
+/// Resolved from the node's `PATH`; job specs cannot point at a different executable.
+const PG_BASEBACKUP: &str = "pg_basebackup";
+
+#[derive(Debug)]
+pub struct PostgresRunBuild {
+    pub artifacts: LocalRunArtifacts,
+    /// `true` when the archive contains a base backup under `base/`.
+    pub base_backup: bool,
+    pub base_backup_label: Option<String>,
+    /// WAL segment (and history/backup label) file names packaged under `wal/`, sorted.
+    pub wal_segments: Vec<String>,
+    pub source_files: u64,
+    pub source_bytes: u64,
+}
+
+/// Packages new WAL segments from the spool directory, plus a fresh base backup when the last one
+/// is older than the configured interval.
+///
+/// Shipped segments stay in the spool until [`finish_postgres_run`] is called after the upload.
+#[allow(clippy::too_many_arguments)]
+pub fn build_postgres_run(
+    data_dir: &Path,
+    job_id: &str,
+    run_id: &str,
+    started_at: OffsetDateTime,
+    source: &PostgresSource,
+    pipeline: BuildPipelineOptions<'_>,
+    on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> std::io::Result<()> + Send>>,
+    part_stream: Option<Box<dyn PartStream>>,
+) -> Result<PostgresRunBuild, anyhow::Error> {
+    let BuildPipelineOptions {
+        artifact_format,
+        compression,
+        encryption,
+        part_size_bytes,
+        staging_min_free_bytes,
+    } = pipeline;
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        wal_spool_dir = %source.wal_spool_dir,
+        artifact_format = ?artifact_format,
+        compression = compression.algorithm.as_str(),
+        encryption = ?encryption,
+        part_size_bytes,
+        "building postgres backup artifacts"
+    );
+
+    let spool = PathBuf::from(source.wal_spool_dir.trim());
+    if !spool.is_dir() {
+        anyhow::bail!(
+            "postgres WAL spool directory not found: {}",
+            spool.display()
+        );
+    }
+
+    let run_dir = crate::backup::run_dir(data_dir, run_id);
+    let source_dir = run_dir.join("source");
+    std::fs::create_dir_all(&source_dir)?;
+
+    let base_backup = needs_base_backup(
+        read_last_base_backup(&spool),
+        started_at.unix_timestamp(),
+        source.effective_base_backup_interval_hours(),
+    );
+    let base_backup_label = if base_backup {
+        let label = format!("bastion-{run_id}");
+        run_pg_basebackup(source, &source_dir.join(BASE_DIR), &label)?;
+        Some(label)
+    } else {
+        None
+    };
+
+    // Collected after the base backup, so the segments it needs are part of the same run.
+    let wal_segments = list_spool_segments(&spool)?;
+    let wal_dir = source_dir.join(WAL_DIR);
+    std::fs::create_dir_all(&wal_dir)?;
+    for name in &wal_segments {
+        std::fs::copy(spool.join(name), wal_dir.join(name))?;
+    }
+    task_log::line(format!(
+        "postgres: {} WAL file(s) to ship{}",
+        wal_segments.len(),
+        if base_backup {
+            ", with a base backup"
+        } else {
+            ""
+        }
+    ));
+
+    let fs_source = FilesystemSource {
+        pre_scan: true,
+        paths: Vec::new(),
+        root: source_dir.to_string_lossy().to_string(),
+        include: Vec::new(),
+        exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
+        symlink_policy: FsSymlinkPolicy::Keep,
+        hardlink_policy: FsHardlinkPolicy::Copy,
+        error_policy: FsErrorPolicy::FailFast,
+        snapshot_mode: Default::default(),
+        snapshot_provider: None,
+        consistency_policy: Default::default(),
+        consistency_fail_threshold: None,
+        upload_on_consistency_failure: None,
+        windows_system_state: false,
+    };
+    let build = crate::backup::filesystem::build_filesystem_run(
+        data_dir,
+        job_id,
+        run_id,
+        started_at,
+        &fs_source,
+        BuildPipelineOptions {
+            artifact_format,
+            compression,
+            encryption,
+            part_size_bytes,
+            staging_min_free_bytes,
+        },
+        None,
+        None,
+        on_part_finished,
+        None,
+        part_stream,
+    )?;
+    if build.issues.errors_total > 0 {
+        anyhow::bail!(
+            "unexpected filesystem issues while packaging postgres backup: {}",
+            build.issues.errors_total
+        );
+    }
+    let artifacts = build.artifacts;
+
+    let (source_files, source_bytes) = dir_totals(&source_dir)?;
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        base_backup,
+        wal_segments = wal_segments.len(),
+        entries_count = artifacts.entries_count,
+        parts_count = artifacts.parts.len(),
+        parts_bytes = artifacts.parts.iter().map(|p| p.size).sum::<u64>(),
+        "built postgres backup artifacts"
+    );
+
+    Ok(PostgresRunBuild {
+        artifacts,
+        base_backup,
+        base_backup_label,
+        wal_segments,
+        source_files,
+        source_bytes,
+    })
+}
+
+/// Removes the shipped segments from the spool and records the base backup time. Call only after
+/// the run's artifacts were stored on the target.
+pub fn finish_postgres_run(
+    source: &PostgresSource,
+    build: &PostgresRunBuild,
+    now: i64,
+) -> Result<(), anyhow::Error> {
+    let spool = PathBuf::from(source.wal_spool_dir.trim());
+    for name in &build.wal_segments {
+        match std::fs::remove_file(spool.join(name)) {
+            Ok(()) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => {
+                warn!(segment = %name, error = %error, "failed to remove shipped WAL segment");
+            }
+        }
+    }
+    if build.base_backup {
+        std::fs::write(spool.join(LAST_BASE_BACKUP_FILE), now.to_string())?;
+    }
+    Ok(())
+}
+
+fn needs_base_backup(last: Option<i64>, now: i64, interval_hours: u32) -> bool {
+    match last {
+        Some(last) => now.saturating_sub(last) >= i64::from(interval_hours) * 3600,
+        None => true,
+    }
+}
+
+fn read_last_base_backup(spool: &Path) -> Option<i64> {
+    std::fs::read_to_string(spool.join(LAST_BASE_BACKUP_FILE))
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+}
+
+/// Lists the files `archive_command` left in the spool. Hidden files (ours, or partial copies
+/// written under a temporary name) are skipped.
+fn list_spool_segments(spool: &Path) -> Result<Vec<String>, std::io::Error> {
+    let mut out = Vec::new();
+    for entry in std::fs::read_dir(spool)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        out.push(name);
+    }
+    out.sort();
+    Ok(out)
+}
+
+fn run_pg_basebackup(
+    source: &PostgresSource,
+    dest: &Path,
+    label: &str,
+) -> Result<(), anyhow::Error> {
+    let output = Command::new(PG_BASEBACKUP)
+        .arg("--pgdata")
+        .arg(dest)
+        .args(["--format=plain", "--wal-method=none", "--checkpoint=fast"])
+        .arg(format!("--label={label}"))
+        .arg("--no-password")
+        .arg("--dbname")
+        .arg(source.connection.trim())
+        .output()
+        .map_err(|error| anyhow::anyhow!("failed to run {PG_BASEBACKUP}: {error}"))?;
+    task_log::command_output(PG_BASEBACKUP, &output);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(
+            "pg_basebackup failed ({}): {}",
+            output.status,
+            stderr.lines().last().unwrap_or_default().trim()
+        );
+    }
+    Ok(())
+}
+
+fn dir_totals(root: &Path) -> Result<(u64, u64), anyhow::Error> {
+    let mut files = 0u64;
+    let mut bytes = 0u64;
+    for entry in walkdir::WalkDir::new(root) {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            files += 1;
+            bytes = bytes.saturating_add(entry.metadata()?.len());
+        }
+    }
+    Ok((files, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use bastion_core::job_spec::{CompressionV1, PostgresSource};
+    use bastion_core::manifest::ArtifactFormatV1;
+    use tempfile::tempdir;
+    use time::OffsetDateTime;
+    use uuid::Uuid;
+
+    use super::{
+        LAST_BASE_BACKUP_FILE, build_postgres_run, finish_postgres_run, needs_base_backup,
+    };
+    use crate::backup::{BuildPipelineOptions, PayloadEncryption};
+
+    #[test]
+    fn base_backup_is_due_after_the_interval() {
+        assert!(needs_base_backup(None, 1_000, 24));
+        assert!(!needs_base_backup(Some(1_000), 1_000 + 3600, 24));
+        assert!(needs_base_backup(Some(1_000), 1_000 + 24 * 3600, 24));
+    }
+
+    #[test]
+    fn wal_only_run_ships_spool_segments_and_clears_them_after_upload() {
+        let tmp = tempdir().unwrap();
+        let data_dir = tmp.path().join("data");
+        let spool = tmp.path().join("spool");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::create_dir_all(&spool).unwrap();
+
+        let now = OffsetDateTime::now_utc();
+        fs::write(
+            spool.join(LAST_BASE_BACKUP_FILE),
+            now.unix_timestamp().to_string(),
+        )
+        .unwrap();
+        fs::write(spool.join("000000010000000000000002"), b"wal-2").unwrap();
+        fs::write(spool.join("000000010000000000000001"), b"wal-1").unwrap();
+        fs::write(spool.join(".000000010000000000000003.tmp"), b"partial").unwrap();
+
+        let source = PostgresSource {
+            connection: "host=/nonexistent".to_string(),
+            wal_spool_dir: spool.to_string_lossy().to_string(),
+            base_backup_interval_hours: None,
+        };
+        let encryption = PayloadEncryption::None;
+        let build = build_postgres_run(
+            &data_dir,
+            &Uuid::new_v4().to_string(),
+            &Uuid::new_v4().to_string(),
+            now,
+            &source,
+            BuildPipelineOptions {
+                artifact_format: ArtifactFormatV1::ArchiveV1,
+                compression: CompressionV1::default(),
+                encryption: &encryption,
+                part_size_bytes: 4 * 1024 * 1024,
+                staging_min_free_bytes: 0,
+            },
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert!(!build.base_backup);
+        assert_eq!(
+            build.wal_segments,
+            vec![
+                "000000010000000000000001".to_string(),
+                "000000010000000000000002".to_string()
+            ]
+        );
+        assert_eq!(build.source_files, 2);
+        assert!(build.artifacts.manifest_path.exists());
+
+        finish_postgres_run(&source, &build, now.unix_timestamp()).unwrap();
+        assert!(!spool.join("000000010000000000000001").exists());
+        assert!(spool.join(".000000010000000000000003.tmp").exists());
+    }
+}
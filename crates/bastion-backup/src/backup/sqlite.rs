@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
@@ -5,7 +6,7 @@ use rusqlite::{Connection, OpenFlags};
 use time::OffsetDateTime;
 use tracing::{info, warn};
 
-use crate::backup::{BuildPipelineOptions, LocalArtifact, LocalRunArtifacts};
+use crate::backup::{BuildPipelineOptions, LocalArtifact, LocalRunArtifacts, PartStream};
 use bastion_core::job_spec::{
     FilesystemSource, FsErrorPolicy, FsHardlinkPolicy, FsSymlinkPolicy, SqliteSource,
 };
@@ -23,13 +24,52 @@ pub struct IntegrityCheck {
 #[derive(Debug)]
 pub struct SqliteRunArtifacts {
     pub artifacts: LocalRunArtifacts,
+    /// First database's snapshot (kept for single-database summaries).
     pub snapshot_name: String,
     #[allow(dead_code)]
     pub snapshot_path: PathBuf,
     pub snapshot_size: u64,
     pub integrity_check: Option<IntegrityCheck>,
+    /// One entry per database, in job order.
+    pub databases: Vec<SqliteDatabaseSnapshot>,
 }
 
+#[derive(Debug, Clone)]
+pub struct SqliteDatabaseSnapshot {
+    pub path: String,
+    pub snapshot_name: String,
+    pub snapshot_size: u64,
+    pub integrity_check: Option<IntegrityCheck>,
+}
+
+impl SqliteRunArtifacts {
+    pub fn snapshots_size(&self) -> u64 {
+        self.databases.iter().map(|db| db.snapshot_size).sum()
+    }
+
+    /// Per-database entries for the run summary (`summary.sqlite.databases`).
+    pub fn databases_summary(&self) -> serde_json::Value {
+        serde_json::Value::Array(
+            self.databases
+                .iter()
+                .map(|db| {
+                    serde_json::json!({
+                        "path": db.path,
+                        "snapshot_name": db.snapshot_name,
+                        "snapshot_size": db.snapshot_size,
+                        "integrity_check": db.integrity_check.as_ref().map(|check| serde_json::json!({
+                            "ok": check.ok,
+                            "truncated": check.truncated,
+                            "lines": check.lines,
+                        })),
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn build_sqlite_run(
     data_dir: &Path,
     job_id: &str,
@@ -38,49 +78,68 @@ pub fn build_sqlite_run(
     source: &SqliteSource,
     pipeline: BuildPipelineOptions<'_>,
     on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> std::io::Result<()> + Send>>,
+    part_stream: Option<Box<dyn PartStream>>,
 ) -> Result<SqliteRunArtifacts, anyhow::Error> {
     let BuildPipelineOptions {
         artifact_format,
+        compression,
         encryption,
         part_size_bytes,
+        staging_min_free_bytes,
     } = pipeline;
+    let databases = source.database_list();
     info!(
         job_id = %job_id,
         run_id = %run_id,
-        source_path = %source.path,
-        integrity_check = source.integrity_check,
+        databases = databases.len(),
         artifact_format = ?artifact_format,
+        compression = compression.algorithm.as_str(),
         encryption = ?encryption,
         part_size_bytes,
         "building sqlite backup artifacts"
     );
+    if databases.is_empty() {
+        anyhow::bail!("sqlite.source.path is required");
+    }
 
     let run_dir = crate::backup::run_dir(data_dir, run_id);
     let source_dir = run_dir.join("source");
     std::fs::create_dir_all(&source_dir)?;
 
-    let snapshot_name = snapshot_name(&source.path);
-    let snapshot_path = source_dir.join(&snapshot_name);
+    let mut used_names = HashSet::new();
+    let mut snapshots = Vec::with_capacity(databases.len());
+    for db in &databases {
+        let snapshot_name = unique_snapshot_name(&snapshot_name(&db.path), &mut used_names);
+        let snapshot_path = source_dir.join(&snapshot_name);
 
-    create_snapshot(&source.path, &snapshot_path)?;
+        create_snapshot(&db.path, &snapshot_path)?;
 
-    let snapshot_size = std::fs::metadata(&snapshot_path)?.len();
+        let snapshot_size = std::fs::metadata(&snapshot_path)?.len();
 
-    let integrity_check = if source.integrity_check {
-        let check = integrity_check(&snapshot_path)?;
-        if !check.ok {
-            warn!(
-                job_id = %job_id,
-                run_id = %run_id,
-                snapshot_path = %snapshot_path.display(),
-                truncated = check.truncated,
-                "sqlite integrity_check reported problems"
-            );
-        }
-        Some(check)
-    } else {
-        None
-    };
+        let integrity_check = if db.integrity_check {
+            let check = integrity_check(&snapshot_path)?;
+            if !check.ok {
+                warn!(
+                    job_id = %job_id,
+                    run_id = %run_id,
+                    source_path = %db.path,
+                    snapshot_path = %snapshot_path.display(),
+                    truncated = check.truncated,
+                    "sqlite integrity_check reported problems"
+                );
+            }
+            Some(check)
+        } else {
+            None
+        };
+
+        snapshots.push(SqliteDatabaseSnapshot {
+            path: db.path.clone(),
+            snapshot_name,
+            snapshot_size,
+            integrity_check,
+        });
+    }
 
     let fs_source = FilesystemSource {
         pre_scan: true,
@@ -88,6 +147,12 @@ pub fn build_sqlite_run(
         root: source_dir.to_string_lossy().to_string(),
         include: Vec::new(),
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -96,6 +161,7 @@ pub fn build_sqlite_run(
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
     let build = crate::backup::filesystem::build_filesystem_run(
         data_dir,
@@ -105,13 +171,16 @@ pub fn build_sqlite_run(
         &fs_source,
         BuildPipelineOptions {
             artifact_format,
+            compression,
             encryption,
             part_size_bytes,
+            staging_min_free_bytes,
         },
         None,
         None,
         on_part_finished,
         None,
+        part_stream,
     )?;
     if build.issues.errors_total > 0 {
         anyhow::bail!(
@@ -121,11 +190,12 @@ pub fn build_sqlite_run(
     }
     let artifacts = build.artifacts;
 
+    let first = snapshots[0].clone();
     info!(
         job_id = %job_id,
         run_id = %run_id,
-        snapshot_name = %snapshot_name,
-        snapshot_size,
+        databases = snapshots.len(),
+        snapshots_size = snapshots.iter().map(|db| db.snapshot_size).sum::<u64>(),
         entries_count = artifacts.entries_count,
         parts_count = artifacts.parts.len(),
         parts_bytes = artifacts.parts.iter().map(|p| p.size).sum::<u64>(),
@@ -134,13 +204,34 @@ pub fn build_sqlite_run(
 
     Ok(SqliteRunArtifacts {
         artifacts,
-        snapshot_name,
-        snapshot_path,
-        snapshot_size,
-        integrity_check,
+        snapshot_path: source_dir.join(&first.snapshot_name),
+        snapshot_name: first.snapshot_name,
+        snapshot_size: first.snapshot_size,
+        integrity_check: first.integrity_check,
+        databases: snapshots,
     })
 }
 
+/// Keeps snapshot file names unique when several databases share a file name
+/// (`app.db`, `app-2.db`, ...).
+fn unique_snapshot_name(name: &str, used: &mut HashSet<String>) -> String {
+    if used.insert(name.to_string()) {
+        return name.to_string();
+    }
+    let (stem, ext) = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{ext}")),
+        _ => (name, String::new()),
+    };
+    let mut n = 2;
+    loop {
+        let candidate = format!("{stem}-{n}{ext}");
+        if used.insert(candidate.clone()) {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
 fn snapshot_name(source_path: &str) -> String {
     Path::new(source_path)
         .file_name()
@@ -164,7 +255,7 @@ pub(crate) fn create_snapshot(
     Ok(())
 }
 
-fn integrity_check(snapshot_path: &Path) -> Result<IntegrityCheck, anyhow::Error> {
+pub(crate) fn integrity_check(snapshot_path: &Path) -> Result<IntegrityCheck, anyhow::Error> {
     let conn = Connection::open_with_flags(
         snapshot_path,
         OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
@@ -196,7 +287,7 @@ fn integrity_check(snapshot_path: &Path) -> Result<IntegrityCheck, anyhow::Error
 mod tests {
     use super::{build_sqlite_run, integrity_check};
     use crate::backup::{BuildPipelineOptions, PayloadEncryption};
-    use bastion_core::job_spec::SqliteSource;
+    use bastion_core::job_spec::{CompressionV1, SqliteDatabaseV1, SqliteSource};
     use bastion_core::manifest::ArtifactFormatV1;
     use rusqlite::Connection;
     use tempfile::tempdir;
@@ -234,6 +325,7 @@ mod tests {
         let source = SqliteSource {
             path: source_db.to_string_lossy().to_string(),
             integrity_check: true,
+            databases: Vec::new(),
         };
 
         let encryption = PayloadEncryption::None;
@@ -245,10 +337,13 @@ mod tests {
             &source,
             BuildPipelineOptions {
                 artifact_format: ArtifactFormatV1::ArchiveV1,
+                compression: CompressionV1::default(),
                 encryption: &encryption,
                 part_size_bytes: 4 * 1024 * 1024,
+                staging_min_free_bytes: 0,
             },
             None,
+            None,
         )
         .unwrap();
 
@@ -264,4 +359,72 @@ mod tests {
         assert!(result.artifacts.manifest_path.exists());
         assert!(result.artifacts.complete_path.exists());
     }
+
+    #[test]
+    fn build_sqlite_run_packages_every_database_in_one_archive() {
+        let tmp = tempdir().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let mut paths = Vec::new();
+        for dir in ["a", "b"] {
+            std::fs::create_dir_all(tmp.path().join(dir)).unwrap();
+            let path = tmp.path().join(dir).join("app.db");
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch("CREATE TABLE t(x INTEGER); INSERT INTO t VALUES(1);")
+                .unwrap();
+            drop(conn);
+            paths.push(path.to_string_lossy().to_string());
+        }
+
+        let source = SqliteSource {
+            path: String::new(),
+            integrity_check: false,
+            databases: vec![
+                SqliteDatabaseV1 {
+                    path: paths[0].clone(),
+                    integrity_check: true,
+                },
+                SqliteDatabaseV1 {
+                    path: paths[1].clone(),
+                    integrity_check: false,
+                },
+            ],
+        };
+
+        let encryption = PayloadEncryption::None;
+        let result = build_sqlite_run(
+            &data_dir,
+            &Uuid::new_v4().to_string(),
+            &Uuid::new_v4().to_string(),
+            OffsetDateTime::now_utc(),
+            &source,
+            BuildPipelineOptions {
+                artifact_format: ArtifactFormatV1::ArchiveV1,
+                compression: CompressionV1::default(),
+                encryption: &encryption,
+                part_size_bytes: 4 * 1024 * 1024,
+                staging_min_free_bytes: 0,
+            },
+            None,
+            None,
+        )
+        .unwrap();
+
+        let names: Vec<&str> = result
+            .databases
+            .iter()
+            .map(|db| db.snapshot_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["app.db", "app-2.db"]);
+        assert_eq!(result.artifacts.entries_count, 2);
+        assert!(
+            result.databases[0]
+                .integrity_check
+                .as_ref()
+                .is_some_and(|r| r.ok)
+        );
+        assert!(result.databases[1].integrity_check.is_none());
+        assert_eq!(result.snapshot_name, "app.db");
+    }
 }
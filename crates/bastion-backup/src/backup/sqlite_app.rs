@@ -0,0 +1,300 @@
+use std::fs::OpenOptions;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use bastion_core::job_spec::SqliteAppSource;
+use bastion_core::manifest::{ArtifactFormatV1, EntryIndexRef, ManifestV1, PipelineSettings};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::backup::source_consistency::{SourceConsistencyReportV2, SourceConsistencyTracker};
+use crate::backup::sqlite::IntegrityCheck;
+use crate::backup::{
+    BuildPipelineOptions, COMPLETE_NAME, ENTRIES_INDEX_NAME, LocalArtifact, LocalRunArtifacts,
+    MANIFEST_NAME, PartStream, PayloadEncryption, stage_dir, task_log,
+};
+
+const MAX_SOURCE_CONSISTENCY_SAMPLES: usize = 50;
+
+#[derive(Debug)]
+pub struct SqliteAppRunBuild {
+    pub artifacts: LocalRunArtifacts,
+    pub consistency: SourceConsistencyReportV2,
+    pub snapshot_size: u64,
+    pub integrity_check: Option<IntegrityCheck>,
+}
+
+/// Packages an application data directory in one archive, with its SQLite database replaced by an
+/// online-backup snapshot.
+#[allow(clippy::too_many_arguments)]
+pub fn build_sqlite_app_run(
+    data_dir: &std::path::Path,
+    job_id: &str,
+    run_id: &str,
+    started_at: OffsetDateTime,
+    source: &SqliteAppSource,
+    pipeline: BuildPipelineOptions<'_>,
+    on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> std::io::Result<()> + Send>>,
+    part_stream: Option<Box<dyn PartStream>>,
+) -> Result<SqliteAppRunBuild, anyhow::Error> {
+    let BuildPipelineOptions {
+        artifact_format,
+        compression,
+        encryption,
+        part_size_bytes,
+        staging_min_free_bytes,
+    } = pipeline;
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        app_data_dir = %source.data_dir,
+        db_path = %source.db_path,
+        artifact_format = ?artifact_format,
+        compression = compression.algorithm.as_str(),
+        encryption = ?encryption,
+        part_size_bytes,
+        "building sqlite_app backup artifacts"
+    );
+
+    if artifact_format != ArtifactFormatV1::ArchiveV1 {
+        anyhow::bail!("sqlite_app backups currently support only archive_v1 artifact format");
+    }
+
+    let root = PathBuf::from(source.data_dir.trim());
+    if root.as_os_str().is_empty() {
+        anyhow::bail!("sqlite_app.source.data_dir is required");
+    }
+    let db_rel = source
+        .db_path
+        .trim()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .to_string();
+    if db_rel.is_empty() {
+        anyhow::bail!("sqlite_app.source.db_path is required");
+    }
+
+    let stage = stage_dir(data_dir, run_id);
+    std::fs::create_dir_all(&stage)?;
+
+    let entries_path = stage.join(ENTRIES_INDEX_NAME);
+    let entries_file = OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .write(true)
+        .open(entries_path)?;
+    let entries_writer = BufWriter::new(entries_file);
+    let mut entries_writer = zstd::Encoder::new(entries_writer, 3)?;
+    let mut entries_count = 0u64;
+
+    let mut consistency = SourceConsistencyTracker::new(MAX_SOURCE_CONSISTENCY_SAMPLES);
+
+    let run_dir = crate::backup::run_dir(data_dir, run_id);
+    let source_dir = run_dir.join("source");
+    std::fs::create_dir_all(&source_dir)?;
+
+    let source_db_path = root.join(&db_rel);
+    if !source_db_path.is_file() {
+        anyhow::bail!(
+            "sqlite_app database not found: {}",
+            source_db_path.display()
+        );
+    }
+    let snapshot_path = source_dir.join("db.sqlite3");
+    crate::backup::sqlite::create_snapshot(&source_db_path.to_string_lossy(), &snapshot_path)?;
+    let snapshot_size = std::fs::metadata(&snapshot_path)
+        .map(|m| m.len())
+        .unwrap_or(0);
+    task_log::line(format!(
+        "sqlite_app: snapshot of {db_rel} written ({snapshot_size} bytes)"
+    ));
+
+    let integrity_check = if source.integrity_check {
+        let check = crate::backup::sqlite::integrity_check(&snapshot_path)?;
+        if !check.ok {
+            warn!(
+                job_id = %job_id,
+                run_id = %run_id,
+                db_path = %db_rel,
+                truncated = check.truncated,
+                "sqlite integrity_check reported problems"
+            );
+            for line in &check.lines {
+                task_log::line(format!("sqlite_app: integrity_check: {line}"));
+            }
+        }
+        Some(check)
+    } else {
+        None
+    };
+
+    let parts = crate::backup::vaultwarden::tar::write_tar_parts(
+        &stage,
+        &root,
+        &db_rel,
+        &snapshot_path,
+        &compression,
+        encryption,
+        &mut entries_writer,
+        &mut entries_count,
+        part_size_bytes,
+        staging_min_free_bytes,
+        &mut consistency,
+        on_part_finished,
+        part_stream,
+    )?;
+    entries_writer.finish()?;
+
+    let ended_at = OffsetDateTime::now_utc();
+
+    let manifest = ManifestV1 {
+        format_version: ManifestV1::FORMAT_VERSION,
+        job_id: Uuid::parse_str(job_id)?,
+        run_id: Uuid::parse_str(run_id)?,
+        started_at: started_at.format(&Rfc3339)?,
+        ended_at: ended_at.format(&Rfc3339)?,
+        pipeline: PipelineSettings {
+            format: artifact_format,
+            tar: "pax".to_string(),
+            compression: compression.algorithm.as_str().to_string(),
+            compression_level: compression.effective_level(),
+            encryption: match encryption {
+                PayloadEncryption::None => "none".to_string(),
+                PayloadEncryption::AgeX25519 { .. } => "age".to_string(),
+            },
+            encryption_key: match encryption {
+                PayloadEncryption::None => None,
+                PayloadEncryption::AgeX25519 { key_name, .. } => Some(key_name.clone()),
+            },
+            encryption_recipient: match encryption {
+                PayloadEncryption::None => None,
+                PayloadEncryption::AgeX25519 { recipient, .. } => Some(recipient.clone()),
+            },
+            split_bytes: part_size_bytes,
+        },
+        artifacts: parts
+            .iter()
+            .map(|p| bastion_core::manifest::ArtifactPart {
+                name: p.name.clone(),
+                size: p.size,
+                hash_alg: p.hash_alg.clone(),
+                hash: p.hash.clone(),
+            })
+            .collect(),
+        entry_index: EntryIndexRef {
+            name: ENTRIES_INDEX_NAME.to_string(),
+            count: entries_count,
+        },
+        source: None,
+    };
+
+    let manifest_path = stage.join(MANIFEST_NAME);
+    let complete_path = stage.join(COMPLETE_NAME);
+
+    crate::backup::vaultwarden::io::write_json(&manifest_path, &manifest)?;
+    crate::backup::vaultwarden::io::write_json(&complete_path, &serde_json::json!({}))?;
+
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        entries_count,
+        parts_count = parts.len(),
+        parts_bytes = parts.iter().map(|p| p.size).sum::<u64>(),
+        snapshot_size,
+        "built sqlite_app backup artifacts"
+    );
+
+    Ok(SqliteAppRunBuild {
+        artifacts: LocalRunArtifacts {
+            run_dir: stage.parent().unwrap_or(&stage).to_path_buf(),
+            parts,
+            entries_index_path: stage.join(ENTRIES_INDEX_NAME),
+            entries_count,
+            manifest_path,
+            complete_path,
+        },
+        consistency: consistency.finish(),
+        snapshot_size,
+        integrity_check,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use bastion_core::job_spec::{CompressionV1, SqliteAppSource};
+    use bastion_core::manifest::ArtifactFormatV1;
+    use rusqlite::Connection;
+    use tempfile::tempdir;
+    use time::OffsetDateTime;
+    use uuid::Uuid;
+
+    use super::build_sqlite_app_run;
+    use crate::backup::{BuildPipelineOptions, PayloadEncryption};
+
+    #[test]
+    fn sqlite_app_run_replaces_the_live_database_with_a_snapshot() {
+        let tmp = tempdir().unwrap();
+        let data_dir = tmp.path().join("data");
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let app_dir = tmp.path().join("gitea");
+        fs::create_dir_all(app_dir.join("data").join("repos")).unwrap();
+        fs::write(app_dir.join("data").join("repos").join("HEAD"), b"ref").unwrap();
+        fs::write(app_dir.join("app.ini"), b"[database]").unwrap();
+
+        let db_path = app_dir.join("data").join("gitea.db");
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch("CREATE TABLE repo(x INTEGER); INSERT INTO repo VALUES(1);")
+            .unwrap();
+        drop(conn);
+        fs::write(app_dir.join("data").join("gitea.db-wal"), b"ignored").unwrap();
+
+        let source = SqliteAppSource {
+            data_dir: app_dir.to_string_lossy().to_string(),
+            db_path: "data/gitea.db".to_string(),
+            integrity_check: true,
+            consistency_policy: Default::default(),
+            consistency_fail_threshold: None,
+            upload_on_consistency_failure: None,
+        };
+        let encryption = PayloadEncryption::None;
+        let build = build_sqlite_app_run(
+            &data_dir,
+            &Uuid::new_v4().to_string(),
+            &Uuid::new_v4().to_string(),
+            OffsetDateTime::now_utc(),
+            &source,
+            BuildPipelineOptions {
+                artifact_format: ArtifactFormatV1::ArchiveV1,
+                compression: CompressionV1::default(),
+                encryption: &encryption,
+                part_size_bytes: 4 * 1024 * 1024,
+                staging_min_free_bytes: 0,
+            },
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(build.consistency.is_empty());
+        assert!(build.snapshot_size > 0);
+        assert!(build.integrity_check.as_ref().is_some_and(|c| c.ok));
+
+        let raw = fs::read(&build.artifacts.entries_index_path).unwrap();
+        let decoded = zstd::decode_all(std::io::Cursor::new(raw)).unwrap();
+        let paths: Vec<String> = decoded
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice::<serde_json::Value>(line).unwrap())
+            .filter_map(|v| v.get("path").and_then(|p| p.as_str()).map(str::to_string))
+            .collect();
+
+        assert!(paths.contains(&"app.ini".to_string()));
+        assert!(paths.contains(&"data/repos/HEAD".to_string()));
+        assert_eq!(paths.iter().filter(|p| *p == "data/gitea.db").count(), 1);
+        assert!(!paths.contains(&"data/gitea.db-wal".to_string()));
+    }
+}
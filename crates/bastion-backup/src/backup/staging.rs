@@ -0,0 +1,156 @@
+//! Where runs are staged before upload, and the free-space guard that protects that disk.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bastion_core::run_failure::RunFailedWithSummary;
+
+use crate::restore::available_space;
+
+pub const STAGING_DISK_FULL: &str = "staging_disk_full";
+
+/// Bytes written between two free-space checks while packaging.
+pub(crate) const FREE_SPACE_CHECK_INTERVAL_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Root directory for `runs/<run_id>` staging and the free space it must keep.
+#[derive(Debug, Clone)]
+pub struct StagingArea {
+    pub root: PathBuf,
+    /// `0` disables the guard.
+    pub min_free_bytes: u64,
+}
+
+impl StagingArea {
+    pub fn new(root: PathBuf, min_free_bytes: u64) -> Self {
+        Self {
+            root,
+            min_free_bytes,
+        }
+    }
+
+    pub fn check_free_space(&self) -> Result<(), StagingDiskFull> {
+        check_free_space(&self.root, self.min_free_bytes)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StagingDiskFull {
+    pub dir: PathBuf,
+    pub available_bytes: u64,
+    pub min_free_bytes: u64,
+}
+
+impl std::fmt::Display for StagingDiskFull {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "staging disk full: {} bytes free under {}, at least {} required",
+            self.available_bytes,
+            self.dir.display(),
+            self.min_free_bytes
+        )
+    }
+}
+
+impl std::error::Error for StagingDiskFull {}
+
+/// Fails when the filesystem holding `dir` has less than `min_free_bytes` available.
+///
+/// Platforms without a free-space query always pass.
+pub fn check_free_space(dir: &Path, min_free_bytes: u64) -> Result<(), StagingDiskFull> {
+    if min_free_bytes == 0 {
+        return Ok(());
+    }
+    match available_space(dir) {
+        Some(available_bytes) if available_bytes < min_free_bytes => Err(StagingDiskFull {
+            dir: dir.to_path_buf(),
+            available_bytes,
+            min_free_bytes,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Whether a build error means the staging disk ran out of space (guard tripped or `ENOSPC`).
+pub fn is_staging_disk_full(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if cause.is::<StagingDiskFull>() {
+            return true;
+        }
+        let Some(io_error) = cause.downcast_ref::<io::Error>() else {
+            return false;
+        };
+        io_error.kind() == io::ErrorKind::StorageFull
+            || io_error
+                .get_ref()
+                .is_some_and(|inner| inner.is::<StagingDiskFull>())
+    })
+}
+
+/// Turns out-of-space packaging failures into a `staging_disk_full` run failure.
+///
+/// Other errors are returned unchanged.
+pub fn classify_build_error(error: anyhow::Error, staging: &StagingArea) -> anyhow::Error {
+    if !is_staging_disk_full(&error) {
+        return error;
+    }
+    let message = format!("{error:#}");
+    anyhow::Error::new(RunFailedWithSummary::new(
+        STAGING_DISK_FULL,
+        message.clone(),
+        serde_json::json!({
+            "error_code": STAGING_DISK_FULL,
+            "staging_dir": staging.root.to_string_lossy(),
+            "available_bytes": available_space(&staging.root),
+            "min_free_bytes": staging.min_free_bytes,
+            "error": message,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        STAGING_DISK_FULL, StagingArea, StagingDiskFull, check_free_space, classify_build_error,
+        is_staging_disk_full,
+    };
+
+    use bastion_core::run_failure::RunFailedWithSummary;
+
+    #[test]
+    fn free_space_guard_is_disabled_at_zero_and_trips_above_available() {
+        let tmp = tempfile::tempdir().unwrap();
+        check_free_space(tmp.path(), 0).unwrap();
+
+        #[cfg(unix)]
+        {
+            let err = check_free_space(tmp.path(), u64::MAX).unwrap_err();
+            assert_eq!(err.min_free_bytes, u64::MAX);
+        }
+    }
+
+    #[test]
+    fn out_of_space_errors_map_to_staging_disk_full() {
+        let tmp = tempfile::tempdir().unwrap();
+        let staging = StagingArea::new(tmp.path().to_path_buf(), 1024);
+
+        let guard = std::io::Error::other(StagingDiskFull {
+            dir: tmp.path().to_path_buf(),
+            available_bytes: 1,
+            min_free_bytes: 1024,
+        });
+        let enospc = std::io::Error::from(std::io::ErrorKind::StorageFull);
+        for error in [anyhow::Error::new(guard), anyhow::Error::new(enospc)] {
+            assert!(is_staging_disk_full(&error));
+            let mapped = classify_build_error(error, &staging);
+            let run_error = mapped
+                .downcast_ref::<RunFailedWithSummary>()
+                .expect("run failure");
+            assert_eq!(run_error.code, STAGING_DISK_FULL);
+        }
+
+        let other = anyhow::anyhow!("permission denied");
+        let mapped = classify_build_error(other, &staging);
+        assert!(mapped.downcast_ref::<RunFailedWithSummary>().is_none());
+    }
+}
@@ -0,0 +1,66 @@
+//! Raw task log lines (walker issues, external tool output) for the current backup build.
+//!
+//! Builders run on a blocking thread; the caller installs a sink for that thread with
+//! [`with_sink`] and drains the receiver. Lines are dropped when the sink is full, so logging
+//! never slows down or fails a build.
+
+use std::cell::RefCell;
+
+use tokio::sync::mpsc::Sender;
+
+thread_local! {
+    static SINK: RefCell<Option<Sender<String>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with `sink` receiving the task log lines written on this thread.
+pub fn with_sink<R>(sink: Option<Sender<String>>, f: impl FnOnce() -> R) -> R {
+    let prev = SINK.with(|cell| cell.replace(sink));
+    let result = f();
+    SINK.with(|cell| *cell.borrow_mut() = prev);
+    result
+}
+
+/// Writes one line to the task log, if a sink is installed on this thread.
+pub fn line(msg: impl Into<String>) {
+    SINK.with(|cell| {
+        if let Some(sink) = cell.borrow().as_ref() {
+            let _ = sink.try_send(msg.into());
+        }
+    });
+}
+
+/// Writes a command's stdout and stderr, one log line per output line.
+pub fn command_output(command: &str, output: &std::process::Output) {
+    line(format!("$ {command} ({})", output.status));
+    for (stream, bytes) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+        for text in String::from_utf8_lossy(bytes).lines() {
+            if !text.trim().is_empty() {
+                line(format!("[{stream}] {text}"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{line, with_sink};
+
+    #[test]
+    fn lines_reach_the_installed_sink_only() {
+        line("before");
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(2);
+        let out = with_sink(Some(tx), || {
+            line("one");
+            line("two");
+            line("dropped when full");
+            7
+        });
+        assert_eq!(out, 7);
+        line("after");
+
+        assert_eq!(rx.try_recv().ok().as_deref(), Some("one"));
+        assert_eq!(rx.try_recv().ok().as_deref(), Some("two"));
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -6,13 +6,13 @@ use bastion_core::job_spec::VaultwardenSource;
 use bastion_core::manifest::{ArtifactFormatV1, EntryIndexRef, ManifestV1, PipelineSettings};
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
-use tracing::info;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 use crate::backup::source_consistency::{SourceConsistencyReportV2, SourceConsistencyTracker};
 use crate::backup::{
     BuildPipelineOptions, COMPLETE_NAME, ENTRIES_INDEX_NAME, LocalArtifact, LocalRunArtifacts,
-    MANIFEST_NAME, PayloadEncryption, stage_dir,
+    MANIFEST_NAME, PartStream, PayloadEncryption, stage_dir,
 };
 
 const MAX_SOURCE_CONSISTENCY_SAMPLES: usize = 50;
@@ -21,8 +21,10 @@ const MAX_SOURCE_CONSISTENCY_SAMPLES: usize = 50;
 pub struct VaultwardenRunBuild {
     pub artifacts: LocalRunArtifacts,
     pub consistency: SourceConsistencyReportV2,
+    pub integrity: super::VaultwardenIntegrityReport,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn build_vaultwarden_run(
     data_dir: &Path,
     job_id: &str,
@@ -31,17 +33,21 @@ pub fn build_vaultwarden_run(
     source: &VaultwardenSource,
     pipeline: BuildPipelineOptions<'_>,
     on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> std::io::Result<()> + Send>>,
+    part_stream: Option<Box<dyn PartStream>>,
 ) -> Result<VaultwardenRunBuild, anyhow::Error> {
     let BuildPipelineOptions {
         artifact_format,
+        compression,
         encryption,
         part_size_bytes,
+        staging_min_free_bytes,
     } = pipeline;
     info!(
         job_id = %job_id,
         run_id = %run_id,
         vw_data_dir = %source.data_dir,
         artifact_format = ?artifact_format,
+        compression = compression.algorithm.as_str(),
         encryption = ?encryption,
         part_size_bytes,
         "building vaultwarden backup artifacts"
@@ -82,16 +88,34 @@ pub fn build_vaultwarden_run(
         .map(|m| m.len())
         .unwrap_or(0);
 
-    let parts = super::tar::write_tar_zstd_parts(
+    let integrity = super::integrity::check_integrity(&root, &snapshot_path)?;
+    if integrity.total() > 0 {
+        warn!(
+            job_id = %job_id,
+            run_id = %run_id,
+            attachments_missing = integrity.attachments_missing,
+            attachments_size_mismatch = integrity.attachments_size_mismatch,
+            orphan_attachment_files = integrity.orphan_attachment_files,
+            send_files_missing = integrity.send_files_missing,
+            orphan_send_files = integrity.orphan_send_files,
+            "vaultwarden attachment/send files do not match the database"
+        );
+    }
+
+    let parts = super::tar::write_tar_parts(
         &stage,
         &root,
+        "db.sqlite3",
         &snapshot_path,
+        &compression,
         encryption,
         &mut entries_writer,
         &mut entries_count,
         part_size_bytes,
+        staging_min_free_bytes,
         &mut consistency,
         on_part_finished,
+        part_stream,
     )?;
     entries_writer.finish()?;
 
@@ -109,7 +133,8 @@ pub fn build_vaultwarden_run(
         pipeline: PipelineSettings {
             format: artifact_format,
             tar: "pax".to_string(),
-            compression: "zstd".to_string(),
+            compression: compression.algorithm.as_str().to_string(),
+            compression_level: compression.effective_level(),
             encryption: match encryption {
                 PayloadEncryption::None => "none".to_string(),
                 PayloadEncryption::AgeX25519 { .. } => "age".to_string(),
@@ -118,6 +143,10 @@ pub fn build_vaultwarden_run(
                 PayloadEncryption::None => None,
                 PayloadEncryption::AgeX25519 { key_name, .. } => Some(key_name.clone()),
             },
+            encryption_recipient: match encryption {
+                PayloadEncryption::None => None,
+                PayloadEncryption::AgeX25519 { recipient, .. } => Some(recipient.clone()),
+            },
             split_bytes: part_size_bytes,
         },
         artifacts: parts
@@ -133,6 +162,7 @@ pub fn build_vaultwarden_run(
             name: ENTRIES_INDEX_NAME.to_string(),
             count: entries_count,
         },
+        source: None,
     };
 
     let manifest_path = stage.join(MANIFEST_NAME);
@@ -163,5 +193,6 @@ pub fn build_vaultwarden_run(
             complete_path,
         },
         consistency: consistency.finish(),
+        integrity,
     })
 }
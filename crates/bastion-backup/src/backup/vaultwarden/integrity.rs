@@ -0,0 +1,200 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use rusqlite::{Connection, OpenFlags, OptionalExtension as _};
+use serde::{Deserialize, Serialize};
+
+use crate::backup::task_log;
+
+const MAX_SAMPLES: usize = 20;
+/// Vaultwarden `sends.atype` for file sends.
+const SEND_TYPE_FILE: i64 = 1;
+
+/// Cross-check of the attachment and send files against the database snapshot.
+///
+/// Vaultwarden stores attachments at `attachments/<cipher_uuid>/<attachment_id>` and file sends at
+/// `sends/<send_uuid>/<file_id>`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VaultwardenIntegrityReport {
+    /// `false` when the database has no Vaultwarden schema (nothing was cross-checked).
+    pub checked: bool,
+    pub config_json: bool,
+    pub sends_dir: bool,
+    pub attachments_total: u64,
+    pub attachments_missing: u64,
+    pub attachments_size_mismatch: u64,
+    pub orphan_attachment_files: u64,
+    pub send_files_total: u64,
+    pub send_files_missing: u64,
+    pub orphan_send_files: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sample_missing: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sample_size_mismatch: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sample_orphans: Vec<String>,
+}
+
+impl VaultwardenIntegrityReport {
+    /// Number of discrepancies found.
+    pub fn total(&self) -> u64 {
+        self.attachments_missing
+            .saturating_add(self.attachments_size_mismatch)
+            .saturating_add(self.orphan_attachment_files)
+            .saturating_add(self.send_files_missing)
+            .saturating_add(self.orphan_send_files)
+    }
+
+    fn record_missing(&mut self, path: String) {
+        task_log::line(format!("vaultwarden: missing file {path}"));
+        push_sample(&mut self.sample_missing, path);
+    }
+
+    fn record_orphan(&mut self, path: String) {
+        task_log::line(format!("vaultwarden: orphan file {path}"));
+        push_sample(&mut self.sample_orphans, path);
+    }
+}
+
+fn push_sample(samples: &mut Vec<String>, value: String) {
+    if samples.len() < MAX_SAMPLES {
+        samples.push(value);
+    }
+}
+
+pub(super) fn check_integrity(
+    root: &Path,
+    snapshot_db: &Path,
+) -> Result<VaultwardenIntegrityReport, anyhow::Error> {
+    let mut report = VaultwardenIntegrityReport {
+        config_json: root.join("config.json").is_file(),
+        sends_dir: root.join("sends").is_dir(),
+        ..Default::default()
+    };
+
+    let conn = Connection::open_with_flags(
+        snapshot_db,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    let has_attachments = table_exists(&conn, "attachments")?;
+    let has_sends = table_exists(&conn, "sends")?;
+    report.checked = has_attachments || has_sends;
+
+    if has_attachments {
+        let mut expected = HashSet::new();
+        let mut stmt = conn.prepare(
+            "SELECT id, cipher_uuid, file_size FROM attachments ORDER BY cipher_uuid, id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<i64>>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (id, cipher_uuid, file_size) = row?;
+            report.attachments_total += 1;
+            let rel = format!("attachments/{cipher_uuid}/{id}");
+            match std::fs::metadata(root.join(&rel)) {
+                Ok(meta) if meta.is_file() => {
+                    if let Some(expected_size) = file_size
+                        && expected_size >= 0
+                        && meta.len() != expected_size as u64
+                    {
+                        report.attachments_size_mismatch += 1;
+                        task_log::line(format!(
+                            "vaultwarden: size mismatch {rel}: db {expected_size}, file {}",
+                            meta.len()
+                        ));
+                        push_sample(&mut report.sample_size_mismatch, rel.clone());
+                    }
+                }
+                _ => {
+                    report.attachments_missing += 1;
+                    report.record_missing(rel.clone());
+                }
+            }
+            expected.insert(rel);
+        }
+
+        for rel in list_two_level_files(root, "attachments")? {
+            if !expected.contains(&rel) {
+                report.orphan_attachment_files += 1;
+                report.record_orphan(rel);
+            }
+        }
+    }
+
+    if has_sends {
+        let mut expected = HashSet::new();
+        let mut stmt =
+            conn.prepare("SELECT uuid, data FROM sends WHERE atype = ? ORDER BY uuid")?;
+        let rows = stmt.query_map([SEND_TYPE_FILE], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (uuid, data) = row?;
+            let Some(file_id) = serde_json::from_str::<serde_json::Value>(&data)
+                .ok()
+                .and_then(|v| v.get("id").and_then(|id| id.as_str()).map(str::to_string))
+            else {
+                continue;
+            };
+            report.send_files_total += 1;
+            let rel = format!("sends/{uuid}/{file_id}");
+            if !root.join(&rel).is_file() {
+                report.send_files_missing += 1;
+                report.record_missing(rel.clone());
+            }
+            expected.insert(rel);
+        }
+
+        for rel in list_two_level_files(root, "sends")? {
+            if !expected.contains(&rel) {
+                report.orphan_send_files += 1;
+                report.record_orphan(rel);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn table_exists(conn: &Connection, name: &str) -> Result<bool, rusqlite::Error> {
+    conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?",
+        [name],
+        |_| Ok(()),
+    )
+    .optional()
+    .map(|v| v.is_some())
+}
+
+/// Lists `<dir>/<a>/<b>` files as relative paths.
+fn list_two_level_files(root: &Path, dir: &str) -> Result<Vec<String>, std::io::Error> {
+    let mut out = Vec::new();
+    let top = match std::fs::read_dir(root.join(dir)) {
+        Ok(v) => v,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(out),
+        Err(error) => return Err(error),
+    };
+    for parent in top {
+        let parent = parent?;
+        if !parent.file_type()?.is_dir() {
+            continue;
+        }
+        let parent_name = parent.file_name().to_string_lossy().to_string();
+        for child in std::fs::read_dir(parent.path())? {
+            let child = child?;
+            if child.file_type()?.is_file() {
+                out.push(format!(
+                    "{dir}/{parent_name}/{}",
+                    child.file_name().to_string_lossy()
+                ));
+            }
+        }
+    }
+    out.sort();
+    Ok(out)
+}
@@ -2,7 +2,7 @@ use std::path::Path;
 
 use serde::Serialize;
 
-pub(super) fn write_json(path: &Path, value: &impl Serialize) -> Result<(), anyhow::Error> {
+pub(crate) fn write_json(path: &Path, value: &impl Serialize) -> Result<(), anyhow::Error> {
     let bytes = serde_json::to_vec_pretty(value)?;
     std::fs::write(path, bytes)?;
     Ok(())
@@ -1,9 +1,11 @@
 mod builder;
 mod hash;
-mod io;
-mod tar;
+mod integrity;
+pub(crate) mod io;
+pub(crate) mod tar;
 
 pub use builder::{VaultwardenRunBuild, build_vaultwarden_run};
+pub use integrity::VaultwardenIntegrityReport;
 
 #[cfg(test)]
 mod tests;
@@ -2,6 +2,7 @@ use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
+use bastion_core::job_spec::CompressionV1;
 use bastion_core::manifest::HashAlgorithm;
 use serde::Serialize;
 use walkdir::WalkDir;
@@ -10,7 +11,8 @@ use crate::backup::hashing_reader::HashingReader;
 use crate::backup::source_consistency::{
     SourceConsistencyTracker, detect_change_reason, fingerprint_for_path_meta,
 };
-use crate::backup::{LocalArtifact, PartWriter, PayloadEncryption};
+use crate::backup::{LocalArtifact, PartStream, PartWriter, PayloadEncryption};
+use crate::compression::CompressWriter;
 
 #[derive(Debug, Serialize)]
 struct EntryRecord {
@@ -22,36 +24,46 @@ struct EntryRecord {
 }
 
 #[allow(clippy::too_many_arguments)]
-pub(super) fn write_tar_zstd_parts(
+/// Packages `root` into tar parts, replacing the live database at `db_rel` (and its `-wal`, `-shm`
+/// and `-journal` files) with `snapshot_path`.
+pub(crate) fn write_tar_parts(
     stage_dir: &Path,
     root: &Path,
+    db_rel: &str,
     snapshot_path: &Path,
+    compression: &CompressionV1,
     encryption: &PayloadEncryption,
     entries_writer: &mut zstd::Encoder<'_, BufWriter<File>>,
     entries_count: &mut u64,
     part_size_bytes: u64,
+    staging_min_free_bytes: u64,
     consistency: &mut SourceConsistencyTracker,
     on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> std::io::Result<()> + Send>>,
+    part_stream: Option<Box<dyn PartStream>>,
 ) -> Result<Vec<LocalArtifact>, anyhow::Error> {
     let payload_prefix: &'static str = "payload.part";
     let mut part_writer =
         PartWriter::new(stage_dir.to_path_buf(), part_size_bytes, payload_prefix)?;
+    part_writer.set_min_free_bytes(staging_min_free_bytes);
     if let Some(cb) = on_part_finished {
         part_writer.set_on_part_finished(cb);
     }
+    if let Some(stream) = part_stream {
+        part_writer.set_stream(stream);
+    }
 
     let threads = std::thread::available_parallelism()
         .map(|n| n.get())
         .unwrap_or(1);
     match encryption {
         PayloadEncryption::None => {
-            let mut encoder = zstd::Encoder::new(&mut part_writer, 3)?;
-            encoder.multithread(threads as u32)?;
+            let encoder = CompressWriter::new(&mut part_writer, compression, threads)?;
 
             let mut tar = tar::Builder::new(encoder);
-            write_vaultwarden_tar_entries(
+            write_app_tar_entries(
                 &mut tar,
                 root,
+                db_rel,
                 snapshot_path,
                 entries_writer,
                 entries_count,
@@ -72,13 +84,13 @@ pub(super) fn write_tar_zstd_parts(
             ))?;
             let encrypted = encryptor.wrap_output(&mut part_writer)?;
 
-            let mut encoder = zstd::Encoder::new(encrypted, 3)?;
-            encoder.multithread(threads as u32)?;
+            let encoder = CompressWriter::new(encrypted, compression, threads)?;
 
             let mut tar = tar::Builder::new(encoder);
-            write_vaultwarden_tar_entries(
+            write_app_tar_entries(
                 &mut tar,
                 root,
+                db_rel,
                 snapshot_path,
                 entries_writer,
                 entries_count,
@@ -108,9 +120,10 @@ pub(super) fn write_tar_zstd_parts(
     Ok(local_parts)
 }
 
-fn write_vaultwarden_tar_entries<W: Write>(
+fn write_app_tar_entries<W: Write>(
     tar: &mut tar::Builder<W>,
     root: &Path,
+    db_rel: &str,
     snapshot_path: &Path,
     entries_writer: &mut zstd::Encoder<'_, BufWriter<File>>,
     entries_count: &mut u64,
@@ -129,10 +142,9 @@ fn write_vaultwarden_tar_entries<W: Write>(
         }
 
         if entry.file_type().is_file()
-            && matches!(
-                rel_str.as_str(),
-                "db.sqlite3" | "db.sqlite3-wal" | "db.sqlite3-shm" | "db.sqlite3-journal"
-            )
+            && rel_str
+                .strip_prefix(db_rel)
+                .is_some_and(|suffix| matches!(suffix, "" | "-wal" | "-shm" | "-journal"))
         {
             continue;
         }
@@ -231,7 +243,7 @@ fn write_vaultwarden_tar_entries<W: Write>(
         *entries_count += 1;
     }
 
-    // Add the SQLite snapshot as db.sqlite3 at the root of the archive.
+    // Add the SQLite snapshot in place of the live database.
     let snapshot_file = File::open(snapshot_path)?;
     let snapshot_meta = snapshot_file.metadata()?;
     let snapshot_size = snapshot_meta.len();
@@ -242,11 +254,11 @@ fn write_vaultwarden_tar_entries<W: Write>(
     header.set_entry_type(tar::EntryType::Regular);
     header.set_size(snapshot_size);
     header.set_cksum();
-    tar.append_data(&mut header, Path::new("db.sqlite3"), &mut snapshot_reader)?;
+    tar.append_data(&mut header, Path::new(db_rel), &mut snapshot_reader)?;
     let snapshot_hash = snapshot_reader.finalize_hex();
 
     let record = EntryRecord {
-        path: "db.sqlite3".to_string(),
+        path: db_rel.to_string(),
         kind: "file".to_string(),
         size: snapshot_size,
         hash_alg: Some(HashAlgorithm::Blake3),
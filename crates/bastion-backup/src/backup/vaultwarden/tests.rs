@@ -1,6 +1,6 @@
 use super::build_vaultwarden_run;
 use crate::backup::{BuildPipelineOptions, PayloadEncryption};
-use bastion_core::job_spec::VaultwardenSource;
+use bastion_core::job_spec::{CompressionV1, VaultwardenSource};
 use bastion_core::manifest::ArtifactFormatV1;
 use rusqlite::Connection;
 use std::fs;
@@ -54,10 +54,13 @@ fn vaultwarden_run_includes_snapshot_and_files() {
         &source,
         BuildPipelineOptions {
             artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1::default(),
             encryption: &encryption,
             part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
         },
         None,
+        None,
     )
     .unwrap();
     assert!(build.consistency.is_empty());
@@ -109,10 +112,13 @@ fn vaultwarden_run_rejects_non_archive_v1_format() {
         &source,
         BuildPipelineOptions {
             artifact_format: ArtifactFormatV1::RawTreeV1,
+            compression: CompressionV1::default(),
             encryption: &encryption,
             part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
         },
         None,
+        None,
     )
     .expect_err("expected format validation error");
     assert!(err.to_string().contains("support only archive_v1"));
@@ -142,10 +148,13 @@ fn vaultwarden_run_requires_source_data_dir() {
         &source,
         BuildPipelineOptions {
             artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1::default(),
             encryption: &encryption,
             part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
         },
         None,
+        None,
     )
     .expect_err("expected missing data_dir error");
     assert!(
@@ -153,3 +162,87 @@ fn vaultwarden_run_requires_source_data_dir() {
             .contains("vaultwarden.source.data_dir is required")
     );
 }
+
+#[test]
+fn vaultwarden_run_reports_attachment_and_send_discrepancies() {
+    let tmp = tempdir().unwrap();
+    let data_dir = tmp.path().join("data");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    let vw_dir = tmp.path().join("vw");
+    fs::create_dir_all(vw_dir.join("attachments").join("c1")).unwrap();
+    fs::create_dir_all(vw_dir.join("attachments").join("c2")).unwrap();
+    fs::create_dir_all(vw_dir.join("sends").join("s1")).unwrap();
+    fs::write(vw_dir.join("attachments").join("c1").join("a1"), b"12345").unwrap();
+    fs::write(vw_dir.join("attachments").join("c1").join("a2"), b"123").unwrap();
+    fs::write(vw_dir.join("attachments").join("c2").join("stray"), b"x").unwrap();
+    fs::write(vw_dir.join("sends").join("s1").join("f1"), b"send").unwrap();
+    fs::write(vw_dir.join("config.json"), b"{}").unwrap();
+
+    let conn = Connection::open(vw_dir.join("db.sqlite3")).unwrap();
+    conn.execute_batch(
+        r#"
+        CREATE TABLE attachments (id TEXT PRIMARY KEY, cipher_uuid TEXT NOT NULL, file_name TEXT NOT NULL, file_size INTEGER NOT NULL);
+        INSERT INTO attachments VALUES ('a1', 'c1', 'ok.bin', 5);
+        INSERT INTO attachments VALUES ('a2', 'c1', 'resized.bin', 10);
+        INSERT INTO attachments VALUES ('a3', 'c3', 'gone.bin', 1);
+        CREATE TABLE sends (uuid TEXT PRIMARY KEY, atype INTEGER NOT NULL, data TEXT NOT NULL);
+        INSERT INTO sends VALUES ('s1', 1, '{"id":"f1","size":4}');
+        INSERT INTO sends VALUES ('s2', 1, '{"id":"f2","size":4}');
+        INSERT INTO sends VALUES ('s3', 0, '{"text":"hello"}');
+        "#,
+    )
+    .unwrap();
+    drop(conn);
+
+    let source = VaultwardenSource {
+        data_dir: vw_dir.to_string_lossy().to_string(),
+        consistency_policy: Default::default(),
+        consistency_fail_threshold: None,
+        upload_on_consistency_failure: None,
+    };
+    let encryption = PayloadEncryption::None;
+    let build = build_vaultwarden_run(
+        &data_dir,
+        &Uuid::new_v4().to_string(),
+        &Uuid::new_v4().to_string(),
+        OffsetDateTime::now_utc(),
+        &source,
+        BuildPipelineOptions {
+            artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1::default(),
+            encryption: &encryption,
+            part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
+        },
+        None,
+        None,
+    )
+    .unwrap();
+
+    let integrity = build.integrity;
+    assert!(integrity.checked);
+    assert!(integrity.config_json);
+    assert!(integrity.sends_dir);
+    assert_eq!(integrity.attachments_total, 3);
+    assert_eq!(integrity.attachments_missing, 1);
+    assert_eq!(integrity.attachments_size_mismatch, 1);
+    assert_eq!(integrity.orphan_attachment_files, 1);
+    assert_eq!(integrity.send_files_total, 2);
+    assert_eq!(integrity.send_files_missing, 1);
+    assert_eq!(integrity.orphan_send_files, 0);
+    assert_eq!(integrity.total(), 4);
+    assert_eq!(
+        integrity.sample_missing,
+        vec!["attachments/c3/a3".to_string(), "sends/s2/f2".to_string()]
+    );
+    assert_eq!(integrity.sample_size_mismatch, vec!["attachments/c1/a2"]);
+    assert_eq!(integrity.sample_orphans, vec!["attachments/c2/stray"]);
+
+    let paths: Vec<String> = read_entries(&build.artifacts.entries_index_path)
+        .iter()
+        .filter_map(|v| v.get("path").and_then(|p| p.as_str()).map(str::to_string))
+        .collect();
+    assert!(paths.contains(&"config.json".to_string()));
+    assert!(paths.contains(&"sends/s1/f1".to_string()));
+}
@@ -0,0 +1,578 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+use time::OffsetDateTime;
+use tracing::{info, warn};
+
+use crate::backup::{BuildPipelineOptions, LocalArtifact, LocalRunArtifacts, PartStream, task_log};
+use bastion_core::job_spec::{
+    FilesystemSource, FsErrorPolicy, FsHardlinkPolicy, FsSymlinkPolicy, VmImageSource,
+};
+
+/// Domain definition (`virsh dumpxml --inactive`) inside the archive.
+pub const DOMAIN_XML_NAME: &str = "domain.xml";
+/// Directory of the disk images inside the archive.
+pub const DISKS_DIR: &str = "disks";
+/// Resolved from the node's `PATH`; job specs cannot point at a different executable.
+const VIRSH: &str = "virsh";
+
+/// A file-backed disk of the domain, as listed by `virsh domblklist --details`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DomainDisk {
+    target: String,
+    source: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VmDiskImage {
+    pub target: String,
+    pub source: String,
+    /// Path of the image inside the archive (`disks/<target>.<ext>`).
+    pub archive_name: String,
+    pub size: u64,
+}
+
+#[derive(Debug)]
+pub struct VmImageRunBuild {
+    pub artifacts: LocalRunArtifacts,
+    pub domain: String,
+    /// Domain state when the run started (`running`, `shut off`, ...).
+    pub state: String,
+    /// `true` when the disks were read from a live external snapshot.
+    pub live_snapshot: bool,
+    pub quiesced: bool,
+    pub disks: Vec<VmDiskImage>,
+}
+
+impl VmImageRunBuild {
+    pub fn images_size(&self) -> u64 {
+        self.disks.iter().map(|d| d.size).sum()
+    }
+
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "domain": self.domain,
+            "state": self.state,
+            "live_snapshot": self.live_snapshot,
+            "quiesced": self.quiesced,
+            "disks": self.disks,
+        })
+    }
+}
+
+/// Archives the domain definition and its disk images.
+///
+/// Images are linked into the staging directory rather than copied, so they are read once, straight
+/// into the pipeline. For a running domain they are read while an external snapshot holds new
+/// writes; the overlays are committed back before this returns, also when packaging fails.
+#[allow(clippy::too_many_arguments)]
+pub fn build_vm_image_run(
+    data_dir: &Path,
+    job_id: &str,
+    run_id: &str,
+    started_at: OffsetDateTime,
+    source: &VmImageSource,
+    pipeline: BuildPipelineOptions<'_>,
+    on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> std::io::Result<()> + Send>>,
+    part_stream: Option<Box<dyn PartStream>>,
+) -> Result<VmImageRunBuild, anyhow::Error> {
+    build_with_virsh(
+        &Virsh::new(VIRSH, source),
+        data_dir,
+        job_id,
+        run_id,
+        started_at,
+        source,
+        pipeline,
+        on_part_finished,
+        part_stream,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_with_virsh(
+    virsh: &Virsh,
+    data_dir: &Path,
+    job_id: &str,
+    run_id: &str,
+    started_at: OffsetDateTime,
+    source: &VmImageSource,
+    pipeline: BuildPipelineOptions<'_>,
+    on_part_finished: Option<Box<dyn Fn(LocalArtifact) -> std::io::Result<()> + Send>>,
+    part_stream: Option<Box<dyn PartStream>>,
+) -> Result<VmImageRunBuild, anyhow::Error> {
+    let BuildPipelineOptions {
+        artifact_format,
+        compression,
+        encryption,
+        part_size_bytes,
+        staging_min_free_bytes,
+    } = pipeline;
+    let domain = source.domain.trim().to_string();
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        domain = %domain,
+        artifact_format = ?artifact_format,
+        compression = compression.algorithm.as_str(),
+        encryption = ?encryption,
+        part_size_bytes,
+        "building vm image backup artifacts"
+    );
+
+    let state = virsh.run(&["domstate", &domain])?.trim().to_string();
+    let live = state != "shut off";
+    let blklist = virsh.run(&["domblklist", &domain, "--details"])?;
+    let disks = select_disks(parse_domblklist(&blklist), &source.disks)?;
+
+    let run_dir = crate::backup::run_dir(data_dir, run_id);
+    let source_dir = run_dir.join("source");
+    let disks_dir = source_dir.join(DISKS_DIR);
+    std::fs::create_dir_all(&disks_dir)?;
+    std::fs::write(
+        source_dir.join(DOMAIN_XML_NAME),
+        virsh.run(&["dumpxml", &domain, "--inactive"])?,
+    )?;
+
+    let mut images = Vec::with_capacity(disks.len());
+    for disk in &disks {
+        let archive_name = image_archive_name(disk);
+        link_image(Path::new(&disk.source), &source_dir.join(&archive_name))?;
+        images.push(VmDiskImage {
+            target: disk.target.clone(),
+            source: disk.source.clone(),
+            size: std::fs::metadata(&disk.source)?.len(),
+            archive_name,
+        });
+    }
+
+    let overlay_suffix = format!("bastion-{}", &run_id[..run_id.len().min(8)]);
+    if live {
+        let args = snapshot_args(
+            &domain,
+            &disks,
+            &domblklist_targets(&blklist),
+            &overlay_suffix,
+            source.quiesce,
+        );
+        virsh.run(&args.iter().map(String::as_str).collect::<Vec<_>>())?;
+        task_log::line(format!(
+            "vm_image: took a disk-only snapshot of {domain} ({} disk(s))",
+            disks.len()
+        ));
+    }
+
+    let fs_source = FilesystemSource {
+        pre_scan: true,
+        paths: Vec::new(),
+        root: source_dir.to_string_lossy().to_string(),
+        include: Vec::new(),
+        exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
+        symlink_policy: FsSymlinkPolicy::Follow,
+        hardlink_policy: FsHardlinkPolicy::Copy,
+        error_policy: FsErrorPolicy::FailFast,
+        snapshot_mode: Default::default(),
+        snapshot_provider: None,
+        consistency_policy: Default::default(),
+        consistency_fail_threshold: None,
+        upload_on_consistency_failure: None,
+        windows_system_state: false,
+    };
+    let build = crate::backup::filesystem::build_filesystem_run(
+        data_dir,
+        job_id,
+        run_id,
+        started_at,
+        &fs_source,
+        BuildPipelineOptions {
+            artifact_format,
+            compression,
+            encryption,
+            part_size_bytes,
+            staging_min_free_bytes,
+        },
+        None,
+        None,
+        on_part_finished,
+        None,
+        part_stream,
+    );
+
+    let committed = if live {
+        commit_overlays(virsh, &domain, &disks, &overlay_suffix)
+    } else {
+        Ok(())
+    };
+    let build = build?;
+    committed?;
+    if build.issues.errors_total > 0 {
+        anyhow::bail!(
+            "unexpected filesystem issues while packaging vm images: {}",
+            build.issues.errors_total
+        );
+    }
+    let artifacts = build.artifacts;
+
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        domain = %domain,
+        live_snapshot = live,
+        disks = images.len(),
+        images_size = images.iter().map(|d| d.size).sum::<u64>(),
+        entries_count = artifacts.entries_count,
+        parts_count = artifacts.parts.len(),
+        "built vm image backup artifacts"
+    );
+
+    Ok(VmImageRunBuild {
+        artifacts,
+        domain,
+        state,
+        live_snapshot: live,
+        quiesced: live && source.quiesce,
+        disks: images,
+    })
+}
+
+struct Virsh {
+    program: String,
+    connect_uri: Option<String>,
+}
+
+impl Virsh {
+    fn new(program: &str, source: &VmImageSource) -> Self {
+        Self {
+            program: program.to_string(),
+            connect_uri: source
+                .connect_uri
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string),
+        }
+    }
+
+    fn run(&self, args: &[&str]) -> Result<String, anyhow::Error> {
+        let mut cmd = Command::new(&self.program);
+        if let Some(uri) = &self.connect_uri {
+            cmd.args(["--connect", uri]);
+        }
+        let output = cmd
+            .args(args)
+            .output()
+            .map_err(|error| anyhow::anyhow!("failed to run {}: {error}", self.program))?;
+        task_log::command_output(&self.program, &output);
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!(
+                "virsh {} failed ({}): {}",
+                args.first().copied().unwrap_or_default(),
+                output.status,
+                stderr.trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+/// Parses `virsh domblklist --details`, keeping file-backed disks (not cdroms or block devices).
+fn parse_domblklist(output: &str) -> Vec<DomainDisk> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut cols = line.split_whitespace();
+            let (kind, device, target) = (cols.next()?, cols.next()?, cols.next()?);
+            let source = cols.collect::<Vec<_>>().join(" ");
+            (kind == "file" && device == "disk" && !source.is_empty() && source != "-").then(|| {
+                DomainDisk {
+                    target: target.to_string(),
+                    source,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Every disk target in `virsh domblklist --details`, whatever its type.
+fn domblklist_targets(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut cols = line.split_whitespace();
+            let (kind, _device, target) = (cols.next()?, cols.next()?, cols.next()?);
+            matches!(kind, "file" | "block" | "network" | "volume" | "dir")
+                .then(|| target.to_string())
+        })
+        .collect()
+}
+
+fn select_disks(all: Vec<DomainDisk>, wanted: &[String]) -> Result<Vec<DomainDisk>, anyhow::Error> {
+    if wanted.is_empty() {
+        if all.is_empty() {
+            anyhow::bail!("domain has no file-backed disks");
+        }
+        return Ok(all);
+    }
+    wanted
+        .iter()
+        .map(|target| {
+            all.iter()
+                .find(|d| d.target == target.trim())
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("domain has no file-backed disk {target}"))
+        })
+        .collect()
+}
+
+fn image_archive_name(disk: &DomainDisk) -> String {
+    let ext = Path::new(&disk.source)
+        .extension()
+        .and_then(|e| e.to_str())
+        .filter(|e| !e.is_empty() && e.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or("img");
+    format!("{DISKS_DIR}/{}.{ext}", disk.target)
+}
+
+/// `snapshot-create-as` arguments for an external, disk-only snapshot that leaves no libvirt
+/// metadata behind. Every other disk of the domain (`all_targets`) is excluded from the snapshot.
+fn snapshot_args(
+    domain: &str,
+    disks: &[DomainDisk],
+    all_targets: &[String],
+    overlay_suffix: &str,
+    quiesce: bool,
+) -> Vec<String> {
+    let mut args = vec![
+        "snapshot-create-as".to_string(),
+        "--domain".to_string(),
+        domain.to_string(),
+        "--name".to_string(),
+        overlay_suffix.to_string(),
+        "--disk-only".to_string(),
+        "--atomic".to_string(),
+        "--no-metadata".to_string(),
+    ];
+    if quiesce {
+        args.push("--quiesce".to_string());
+    }
+    for disk in disks {
+        args.push("--diskspec".to_string());
+        args.push(format!(
+            "{},snapshot=external,file={}",
+            disk.target,
+            overlay_path(disk, overlay_suffix).display()
+        ));
+    }
+    for target in all_targets {
+        if !disks.iter().any(|d| &d.target == target) {
+            args.push("--diskspec".to_string());
+            args.push(format!("{target},snapshot=no"));
+        }
+    }
+    args
+}
+
+fn overlay_path(disk: &DomainDisk, overlay_suffix: &str) -> PathBuf {
+    PathBuf::from(format!("{}.{overlay_suffix}", disk.source))
+}
+
+/// Merges each overlay back into its image and pivots the domain onto it, then removes the
+/// overlay. Every disk is attempted; the first error is returned.
+fn commit_overlays(
+    virsh: &Virsh,
+    domain: &str,
+    disks: &[DomainDisk],
+    overlay_suffix: &str,
+) -> Result<(), anyhow::Error> {
+    let mut first_error = None;
+    for disk in disks {
+        let result = virsh
+            .run(&[
+                "blockcommit",
+                domain,
+                &disk.target,
+                "--active",
+                "--pivot",
+                "--wait",
+            ])
+            .map(|_| {
+                let overlay = overlay_path(disk, overlay_suffix);
+                if let Err(error) = std::fs::remove_file(&overlay) {
+                    warn!(overlay = %overlay.display(), error = %error, "failed to remove snapshot overlay");
+                }
+            });
+        if let Err(error) = result {
+            warn!(domain = %domain, disk = %disk.target, error = %error, "blockcommit failed");
+            first_error.get_or_insert(error);
+        }
+    }
+    match first_error {
+        Some(error) => Err(error.context(format!(
+            "failed to merge snapshot overlays of {domain}; the domain still writes to *.{overlay_suffix}"
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+fn link_image(image: &Path, link: &Path) -> Result<(), anyhow::Error> {
+    if !image.is_file() {
+        anyhow::bail!("disk image not found: {}", image.display());
+    }
+    std::os::unix::fs::symlink(image, link)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn link_image(_image: &Path, _link: &Path) -> Result<(), anyhow::Error> {
+    anyhow::bail!("vm_image backups are only supported on unix hosts")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DomainDisk, domblklist_targets, image_archive_name, parse_domblklist, select_disks,
+        snapshot_args,
+    };
+    #[cfg(unix)]
+    use super::{Virsh, build_with_virsh};
+
+    const DOMBLKLIST: &str = " Type   Device   Target   Source
+------------------------------------------------------------
+ file   disk     vda      /var/lib/libvirt/images/web01.qcow2
+ file   disk     vdb      /var/lib/libvirt/images/web01 data.raw
+ block  disk     vdc      /dev/vg0/web01-scratch
+ file   cdrom    sda      -
+";
+
+    fn disk(target: &str, source: &str) -> DomainDisk {
+        DomainDisk {
+            target: target.to_string(),
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn domblklist_keeps_file_backed_disks() {
+        assert_eq!(
+            parse_domblklist(DOMBLKLIST),
+            vec![
+                disk("vda", "/var/lib/libvirt/images/web01.qcow2"),
+                disk("vdb", "/var/lib/libvirt/images/web01 data.raw"),
+            ]
+        );
+    }
+
+    #[test]
+    fn disks_are_selected_by_target_and_named_by_format() {
+        let all = parse_domblklist(DOMBLKLIST);
+        let picked = select_disks(all.clone(), &["vdb".to_string()]).unwrap();
+        assert_eq!(
+            picked,
+            vec![disk("vdb", "/var/lib/libvirt/images/web01 data.raw")]
+        );
+        assert!(select_disks(all, &["vdc".to_string()]).is_err());
+
+        assert_eq!(
+            image_archive_name(&disk("vda", "/images/web01.qcow2")),
+            "disks/vda.qcow2"
+        );
+        assert_eq!(
+            image_archive_name(&disk("vdb", "/images/web01")),
+            "disks/vdb.img"
+        );
+    }
+
+    #[test]
+    fn snapshot_args_put_an_overlay_next_to_each_image() {
+        let args = snapshot_args(
+            "web01",
+            &[disk("vda", "/images/web01.qcow2")],
+            &domblklist_targets(DOMBLKLIST),
+            "bastion-1234",
+            true,
+        );
+        assert!(args.contains(&"--disk-only".to_string()));
+        assert!(args.contains(&"--no-metadata".to_string()));
+        assert!(args.contains(&"--quiesce".to_string()));
+        assert!(
+            args.contains(
+                &"vda,snapshot=external,file=/images/web01.qcow2.bastion-1234".to_string()
+            )
+        );
+        for excluded in ["vdb", "vdc", "sda"] {
+            assert!(args.contains(&format!("{excluded},snapshot=no")));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shut_off_domain_is_archived_without_a_snapshot() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        use bastion_core::job_spec::{CompressionV1, VmImageSource};
+        use bastion_core::manifest::ArtifactFormatV1;
+        use time::OffsetDateTime;
+
+        use crate::backup::{BuildPipelineOptions, PayloadEncryption};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let data_dir = tmp.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let image = tmp.path().join("web01.qcow2");
+        std::fs::write(&image, b"qcow2-image").unwrap();
+
+        // Fake virsh: answers the read-only queries and fails on anything else (no snapshot).
+        let virsh = tmp.path().join("virsh");
+        std::fs::write(
+            &virsh,
+            format!(
+                "#!/bin/sh\ncase \"$1\" in\n  domstate) echo 'shut off' ;;\n  domblklist) printf ' Type Device Target Source\\n file disk vda {}\\n' ;;\n  dumpxml) echo '<domain><name>web01</name></domain>' ;;\n  *) exit 1 ;;\nesac\n",
+                image.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&virsh, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let source = VmImageSource {
+            domain: "web01".to_string(),
+            disks: Vec::new(),
+            quiesce: false,
+            connect_uri: None,
+        };
+        let encryption = PayloadEncryption::None;
+        let build = build_with_virsh(
+            &Virsh::new(&virsh.to_string_lossy(), &source),
+            &data_dir,
+            &uuid::Uuid::new_v4().to_string(),
+            &uuid::Uuid::new_v4().to_string(),
+            OffsetDateTime::now_utc(),
+            &source,
+            BuildPipelineOptions {
+                artifact_format: ArtifactFormatV1::ArchiveV1,
+                compression: CompressionV1::default(),
+                encryption: &encryption,
+                part_size_bytes: 4 * 1024 * 1024,
+                staging_min_free_bytes: 0,
+            },
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert!(!build.live_snapshot);
+        assert_eq!(build.state, "shut off");
+        assert_eq!(build.disks.len(), 1);
+        assert_eq!(build.disks[0].archive_name, "disks/vda.qcow2");
+        assert_eq!(build.images_size(), 11);
+        assert!(build.artifacts.manifest_path.exists());
+    }
+}
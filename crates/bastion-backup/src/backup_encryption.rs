@@ -1,4 +1,6 @@
-use age::secrecy::ExposeSecret as _;
+use age::secrecy::{ExposeSecret as _, SecretString};
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use tracing::{debug, info};
 
@@ -9,6 +11,24 @@ use bastion_storage::secrets::SecretsCrypto;
 use bastion_storage::secrets_repo;
 
 pub const BACKUP_AGE_IDENTITY_KIND: &str = "backup_age_identity";
+/// Identities replaced by a key rotation, named `{key_name}@{recipient}`.
+pub const BACKUP_AGE_RETIRED_IDENTITY_KIND: &str = "backup_age_identity_retired";
+/// Backup keys whose private identity is wrapped with a user passphrase (age scrypt).
+///
+/// The Hub stores only the public recipient and the wrapped identity; the passphrase is
+/// supplied per restore/verify operation and never persisted.
+pub const BACKUP_AGE_PASSPHRASE_KEY_KIND: &str = "backup_age_passphrase_key";
+
+/// scrypt work factor used when wrapping passphrase-protected identities (age default).
+const PASSPHRASE_WORK_FACTOR: u8 = 18;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PassphraseKeyRecord {
+    v: u32,
+    recipient: String,
+    /// age scrypt ciphertext of the x25519 identity, base64 encoded.
+    wrapped_identity: String,
+}
 
 pub async fn get_age_identity(
     db: &SqlitePool,
@@ -102,6 +122,243 @@ pub async fn distribute_age_identity_to_node(
     Ok(())
 }
 
+fn age_recipient_of(identity: &str) -> Result<String, anyhow::Error> {
+    use std::str::FromStr as _;
+
+    let identity =
+        age::x25519::Identity::from_str(identity.trim()).map_err(|e| anyhow::anyhow!(e))?;
+    Ok(identity.to_public().to_string())
+}
+
+fn retired_identity_name(key_name: &str, recipient: &str) -> String {
+    format!("{key_name}@{recipient}")
+}
+
+#[derive(Debug, Clone)]
+pub struct RotatedAgeIdentity {
+    pub recipient: String,
+    pub previous_recipient: String,
+}
+
+/// Replaces the identity of `key_name` with a newly generated one.
+///
+/// Future runs encrypt to the new recipient. The previous identity is kept under
+/// [`BACKUP_AGE_RETIRED_IDENTITY_KIND`] so snapshots it encrypted stay restorable.
+pub async fn rotate_age_identity(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    key_name: &str,
+) -> Result<RotatedAgeIdentity, anyhow::Error> {
+    let key_name = key_name.trim();
+    let previous = get_age_identity(db, secrets, key_name)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("missing backup age identity: {}", key_name))?;
+    let previous_recipient = age_recipient_of(&previous)?;
+
+    secrets_repo::upsert_secret(
+        db,
+        secrets,
+        HUB_NODE_ID,
+        BACKUP_AGE_RETIRED_IDENTITY_KIND,
+        &retired_identity_name(key_name, &previous_recipient),
+        previous.as_bytes(),
+    )
+    .await?;
+
+    let identity = age::x25519::Identity::generate();
+    let identity_str = identity.to_string();
+    secrets_repo::upsert_secret(
+        db,
+        secrets,
+        HUB_NODE_ID,
+        BACKUP_AGE_IDENTITY_KIND,
+        key_name,
+        identity_str.expose_secret().as_bytes(),
+    )
+    .await?;
+
+    let recipient = identity.to_public().to_string();
+    info!(
+        key_name = %key_name,
+        recipient = %recipient,
+        previous_recipient = %previous_recipient,
+        "rotated backup age identity"
+    );
+    Ok(RotatedAgeIdentity {
+        recipient,
+        previous_recipient,
+    })
+}
+
+/// Returns every identity (current and retired) known for `key_name`.
+///
+/// When `recipient` is given, only the identity matching it is returned.
+pub async fn list_age_identities(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    key_name: &str,
+    recipient: Option<&str>,
+) -> Result<Vec<String>, anyhow::Error> {
+    let key_name = key_name.trim();
+    let mut identities = Vec::new();
+    if let Some(current) = get_age_identity(db, secrets, key_name).await? {
+        identities.push(current);
+    }
+
+    let prefix = format!("{key_name}@");
+    for secret in
+        secrets_repo::list_secrets(db, HUB_NODE_ID, BACKUP_AGE_RETIRED_IDENTITY_KIND).await?
+    {
+        if !secret.name.starts_with(&prefix) {
+            continue;
+        }
+        if let Some(bytes) = secrets_repo::get_secret(
+            db,
+            secrets,
+            HUB_NODE_ID,
+            BACKUP_AGE_RETIRED_IDENTITY_KIND,
+            &secret.name,
+        )
+        .await?
+        {
+            identities.push(String::from_utf8(bytes)?.trim().to_string());
+        }
+    }
+
+    if let Some(recipient) = recipient.map(str::trim).filter(|v| !v.is_empty()) {
+        let mut matching = Vec::new();
+        for identity in identities {
+            if age_recipient_of(&identity)? == recipient {
+                matching.push(identity);
+            }
+        }
+        return Ok(matching);
+    }
+    Ok(identities)
+}
+
+async fn get_passphrase_key_record(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    key_name: &str,
+) -> Result<Option<PassphraseKeyRecord>, anyhow::Error> {
+    let key_name = key_name.trim();
+    if key_name.is_empty() {
+        return Ok(None);
+    }
+
+    let Some(bytes) = secrets_repo::get_secret(
+        db,
+        secrets,
+        HUB_NODE_ID,
+        BACKUP_AGE_PASSPHRASE_KEY_KIND,
+        key_name,
+    )
+    .await?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(serde_json::from_slice(&bytes)?))
+}
+
+pub async fn create_age_passphrase_key(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    key_name: &str,
+    passphrase: &str,
+) -> Result<String, anyhow::Error> {
+    create_age_passphrase_key_with_work_factor(
+        db,
+        secrets,
+        key_name,
+        passphrase,
+        PASSPHRASE_WORK_FACTOR,
+    )
+    .await
+}
+
+pub(crate) async fn create_age_passphrase_key_with_work_factor(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    key_name: &str,
+    passphrase: &str,
+    work_factor: u8,
+) -> Result<String, anyhow::Error> {
+    let key_name = key_name.trim();
+    if key_name.is_empty() {
+        anyhow::bail!("backup key_name is empty");
+    }
+    if passphrase.is_empty() {
+        anyhow::bail!("backup key passphrase is empty");
+    }
+    for kind in [BACKUP_AGE_IDENTITY_KIND, BACKUP_AGE_PASSPHRASE_KEY_KIND] {
+        if secrets_repo::secret_exists(db, HUB_NODE_ID, kind, key_name).await? {
+            anyhow::bail!("backup key already exists: {}", key_name);
+        }
+    }
+
+    let identity = age::x25519::Identity::generate();
+    let recipient = identity.to_public().to_string();
+    // scrypt is deliberately slow; keep it off the async runtime.
+    let passphrase = SecretString::from(passphrase.to_string());
+    let plain_identity = identity.to_string();
+    let wrapped = tokio::task::spawn_blocking(move || {
+        let mut wrap_recipient = age::scrypt::Recipient::new(passphrase);
+        wrap_recipient.set_work_factor(work_factor);
+        age::encrypt(&wrap_recipient, plain_identity.expose_secret().as_bytes())
+    })
+    .await??;
+
+    let record = PassphraseKeyRecord {
+        v: 1,
+        recipient: recipient.clone(),
+        wrapped_identity: base64::engine::general_purpose::STANDARD.encode(wrapped),
+    };
+    secrets_repo::upsert_secret(
+        db,
+        secrets,
+        HUB_NODE_ID,
+        BACKUP_AGE_PASSPHRASE_KEY_KIND,
+        key_name,
+        &serde_json::to_vec(&record)?,
+    )
+    .await?;
+
+    info!(key_name = %key_name, "created passphrase-protected backup key");
+    Ok(recipient)
+}
+
+pub async fn get_age_passphrase_recipient(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    key_name: &str,
+) -> Result<Option<String>, anyhow::Error> {
+    Ok(get_passphrase_key_record(db, secrets, key_name)
+        .await?
+        .map(|record| record.recipient))
+}
+
+/// Unwraps a passphrase-protected identity in memory; returns `Ok(None)` if no such key exists.
+pub async fn unlock_age_passphrase_key(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    key_name: &str,
+    passphrase: &str,
+) -> Result<Option<String>, anyhow::Error> {
+    let Some(record) = get_passphrase_key_record(db, secrets, key_name).await? else {
+        return Ok(None);
+    };
+
+    let wrapped = base64::engine::general_purpose::STANDARD.decode(record.wrapped_identity)?;
+    let passphrase = SecretString::from(passphrase.to_string());
+    let plain = tokio::task::spawn_blocking(move || {
+        age::decrypt(&age::scrypt::Identity::new(passphrase), &wrapped)
+    })
+    .await?
+    .map_err(|_| anyhow::anyhow!("invalid passphrase for backup key: {}", key_name.trim()))?;
+    Ok(Some(String::from_utf8(plain)?.trim().to_string()))
+}
+
 pub async fn ensure_payload_encryption(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
@@ -128,6 +385,22 @@ pub async fn ensure_payload_encryption(
                 key_name: key_name.trim().to_string(),
             })
         }
+        job_spec::EncryptionV1::AgePassphrase { key_name } => {
+            // Passphrase keys are never generated implicitly: the passphrase is not available here.
+            let recipient = get_age_passphrase_recipient(db, secrets, key_name)
+                .await?
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "missing passphrase-protected backup key: {}",
+                        key_name.trim()
+                    )
+                })?;
+            debug!(key_name = %key_name.trim(), "resolved passphrase payload encryption");
+            Ok(PayloadEncryption::AgeX25519 {
+                recipient,
+                key_name: key_name.trim().to_string(),
+            })
+        }
     }
 }
 
@@ -141,8 +414,9 @@ mod tests {
     use bastion_storage::{db, secrets::SecretsCrypto, secrets_repo};
 
     use super::{
-        BACKUP_AGE_IDENTITY_KIND, distribute_age_identity_to_node, ensure_age_identity,
-        ensure_payload_encryption, get_age_identity,
+        BACKUP_AGE_IDENTITY_KIND, create_age_passphrase_key_with_work_factor,
+        distribute_age_identity_to_node, ensure_age_identity, ensure_payload_encryption,
+        get_age_identity, unlock_age_passphrase_key,
     };
 
     #[tokio::test]
@@ -317,4 +591,82 @@ mod tests {
                 .is_err()
         );
     }
+
+    #[tokio::test]
+    async fn passphrase_key_encrypts_to_recipient_and_unlocks_with_passphrase() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+        let crypto = SecretsCrypto::load_or_create(tmp.path()).unwrap();
+
+        let recipient =
+            create_age_passphrase_key_with_work_factor(&pool, &crypto, "offline", "hunter22", 2)
+                .await
+                .unwrap();
+
+        let pipeline = bastion_core::job_spec::PipelineV1 {
+            format: ArtifactFormatV1::ArchiveV1,
+            encryption: bastion_core::job_spec::EncryptionV1::AgePassphrase {
+                key_name: "offline".to_string(),
+            },
+            ..Default::default()
+        };
+        match ensure_payload_encryption(&pool, &crypto, &pipeline)
+            .await
+            .unwrap()
+        {
+            crate::backup::PayloadEncryption::AgeX25519 {
+                recipient: got,
+                key_name,
+            } => {
+                assert_eq!(got, recipient);
+                assert_eq!(key_name, "offline");
+            }
+            _ => panic!("unexpected payload encryption variant"),
+        }
+
+        // No plain identity is stored for the key.
+        assert!(
+            get_age_identity(&pool, &crypto, "offline")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let identity = unlock_age_passphrase_key(&pool, &crypto, "offline", "hunter22")
+            .await
+            .unwrap()
+            .expect("key exists");
+        let identity = age::x25519::Identity::from_str(&identity).unwrap();
+        assert_eq!(identity.to_public().to_string(), recipient);
+
+        let err = unlock_age_passphrase_key(&pool, &crypto, "offline", "wrong")
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("invalid passphrase"));
+
+        assert!(
+            create_age_passphrase_key_with_work_factor(&pool, &crypto, "offline", "x", 2)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn ensure_payload_encryption_passphrase_requires_existing_key() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+        let crypto = SecretsCrypto::load_or_create(tmp.path()).unwrap();
+
+        let pipeline = bastion_core::job_spec::PipelineV1 {
+            format: ArtifactFormatV1::ArchiveV1,
+            encryption: bastion_core::job_spec::EncryptionV1::AgePassphrase {
+                key_name: "offline".to_string(),
+            },
+            ..Default::default()
+        };
+        let err = ensure_payload_encryption(&pool, &crypto, &pipeline)
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("missing passphrase-protected backup key"));
+    }
 }
@@ -0,0 +1,344 @@
+use std::io::{self, Read, Seek, Write};
+use std::path::Path;
+
+use bastion_core::job_spec::{CompressionAlgorithmV1, CompressionV1};
+
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const LZ4_FRAME_MAGIC: &[u8] = &[0x04, 0x22, 0x4d, 0x18];
+const XZ_MAGIC: &[u8] = &[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00];
+
+/// zstd level used for files detected as already compressed (adaptive mode).
+const ZSTD_STORE_LEVEL: i32 = -7;
+/// Bytes sampled from files without a known extension when estimating entropy.
+const ENTROPY_SAMPLE_BYTES: usize = 64 * 1024;
+/// Files smaller than this are never sampled; the cost outweighs the gain.
+const ENTROPY_SAMPLE_MIN_FILE_BYTES: u64 = 256 * 1024;
+/// Shannon entropy (bits per byte) above which a sample is considered incompressible.
+const INCOMPRESSIBLE_ENTROPY_BITS: f64 = 7.5;
+
+const INCOMPRESSIBLE_EXTENSIONS: &[&str] = &[
+    "7z", "aac", "apk", "avi", "avif", "br", "bz2", "deb", "docx", "epub", "flac", "gif", "gz",
+    "heic", "jar", "jpeg", "jpg", "lz4", "m4a", "m4v", "mkv", "mov", "mp3", "mp4", "odt", "ogg",
+    "opus", "png", "pptx", "rar", "rpm", "tgz", "txz", "webm", "webp", "woff2", "xlsx", "xz",
+    "zip", "zst",
+];
+
+/// Tar payload sink that can switch compression between files.
+pub(crate) trait PayloadWrite: Write {
+    /// Whether the sink wants per-file incompressibility hints.
+    fn adaptive(&self) -> bool;
+
+    /// Called before a file's data is written.
+    fn set_incompressible(&mut self, incompressible: bool) -> io::Result<()>;
+}
+
+/// zstd writer that starts a new frame with a near-store level around runs of incompressible
+/// files. Concatenated frames decode as a single stream.
+pub(crate) struct ZstdWriter<W: Write> {
+    encoder: Option<zstd::Encoder<'static, W>>,
+    level: i32,
+    threads: u32,
+    adaptive: bool,
+    storing: bool,
+}
+
+impl<W: Write> ZstdWriter<W> {
+    fn new(inner: W, level: i32, threads: u32, adaptive: bool) -> io::Result<Self> {
+        Ok(Self {
+            encoder: Some(Self::encoder(inner, level, threads)?),
+            level,
+            threads,
+            adaptive,
+            storing: false,
+        })
+    }
+
+    fn encoder(inner: W, level: i32, threads: u32) -> io::Result<zstd::Encoder<'static, W>> {
+        let mut encoder = zstd::Encoder::new(inner, level)?;
+        encoder.multithread(threads)?;
+        Ok(encoder)
+    }
+
+    fn encoder_mut(&mut self) -> io::Result<&mut zstd::Encoder<'static, W>> {
+        self.encoder
+            .as_mut()
+            .ok_or_else(|| io::Error::other("zstd encoder is closed"))
+    }
+
+    fn switch_level(&mut self, storing: bool) -> io::Result<()> {
+        let encoder = self
+            .encoder
+            .take()
+            .ok_or_else(|| io::Error::other("zstd encoder is closed"))?;
+        let inner = encoder.finish()?;
+        let level = if storing {
+            ZSTD_STORE_LEVEL
+        } else {
+            self.level
+        };
+        self.encoder = Some(Self::encoder(inner, level, self.threads)?);
+        self.storing = storing;
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<W> {
+        match self.encoder.take() {
+            Some(encoder) => encoder.finish(),
+            None => Err(io::Error::other("zstd encoder is closed")),
+        }
+    }
+}
+
+/// Payload compressor for `archive_v1` tar streams.
+pub(crate) enum CompressWriter<W: Write> {
+    Zstd(ZstdWriter<W>),
+    Lz4(lz4_flex::frame::FrameEncoder<W>),
+    Xz(xz2::write::XzEncoder<W>),
+    None(W),
+}
+
+impl<W: Write> CompressWriter<W> {
+    pub(crate) fn new(inner: W, compression: &CompressionV1, threads: usize) -> io::Result<Self> {
+        let level = compression.effective_level();
+        Ok(match compression.algorithm {
+            CompressionAlgorithmV1::Zstd => Self::Zstd(ZstdWriter::new(
+                inner,
+                level.unwrap_or(3),
+                threads as u32,
+                compression.adaptive,
+            )?),
+            CompressionAlgorithmV1::Lz4 => Self::Lz4(lz4_flex::frame::FrameEncoder::new(inner)),
+            CompressionAlgorithmV1::Xz => {
+                let level = level.unwrap_or(6).clamp(0, 9) as u32;
+                Self::Xz(xz2::write::XzEncoder::new(inner, level))
+            }
+            CompressionAlgorithmV1::None => Self::None(inner),
+        })
+    }
+
+    pub(crate) fn finish(self) -> io::Result<W> {
+        match self {
+            Self::Zstd(writer) => writer.finish(),
+            Self::Lz4(encoder) => encoder.finish().map_err(io::Error::other),
+            Self::Xz(encoder) => encoder.finish(),
+            Self::None(inner) => Ok(inner),
+        }
+    }
+}
+
+impl<W: Write> Write for CompressWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Zstd(writer) => writer.encoder_mut()?.write(buf),
+            Self::Lz4(encoder) => encoder.write(buf),
+            Self::Xz(encoder) => encoder.write(buf),
+            Self::None(inner) => inner.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Zstd(writer) => writer.encoder_mut()?.flush(),
+            Self::Lz4(encoder) => encoder.flush(),
+            Self::Xz(encoder) => encoder.flush(),
+            Self::None(inner) => inner.flush(),
+        }
+    }
+}
+
+impl<W: Write> PayloadWrite for CompressWriter<W> {
+    fn adaptive(&self) -> bool {
+        matches!(self, Self::Zstd(writer) if writer.adaptive)
+    }
+
+    fn set_incompressible(&mut self, incompressible: bool) -> io::Result<()> {
+        match self {
+            Self::Zstd(writer) if writer.adaptive && writer.storing != incompressible => {
+                writer.switch_level(incompressible)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Guesses whether a file is already compressed, by extension first and then by sampling the
+/// head of larger files. The reader is rewound before returning.
+pub(crate) fn looks_incompressible<R: Read + Seek>(
+    path: &Path,
+    size: u64,
+    reader: &mut R,
+) -> io::Result<bool> {
+    let known_extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .is_some_and(|ext| INCOMPRESSIBLE_EXTENSIONS.contains(&ext.as_str()));
+    if known_extension {
+        return Ok(true);
+    }
+    if size < ENTROPY_SAMPLE_MIN_FILE_BYTES {
+        return Ok(false);
+    }
+
+    let mut sample = Vec::with_capacity(ENTROPY_SAMPLE_BYTES);
+    reader
+        .by_ref()
+        .take(ENTROPY_SAMPLE_BYTES as u64)
+        .read_to_end(&mut sample)?;
+    reader.rewind()?;
+    Ok(shannon_entropy(&sample) >= INCOMPRESSIBLE_ENTROPY_BITS)
+}
+
+fn shannon_entropy(sample: &[u8]) -> f64 {
+    if sample.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0u64; 256];
+    for b in sample {
+        counts[*b as usize] += 1;
+    }
+    let len = sample.len() as f64;
+    counts
+        .iter()
+        .filter(|c| **c > 0)
+        .map(|c| {
+            let p = *c as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Wraps a (decrypted) payload stream with the matching decompressor.
+///
+/// The algorithm is detected from the stream's magic bytes, so older zstd snapshots and
+/// snapshots written with any configured algorithm restore through the same path. A stream
+/// without a known magic is treated as an uncompressed tar.
+pub(crate) fn decompress_reader<'a>(
+    mut reader: Box<dyn Read + 'a>,
+) -> Result<Box<dyn Read + 'a>, anyhow::Error> {
+    let mut head = Vec::with_capacity(XZ_MAGIC.len());
+    let mut buf = [0u8; 6];
+    while head.len() < XZ_MAGIC.len() {
+        let n = reader.read(&mut buf[..XZ_MAGIC.len() - head.len()])?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+
+    let algorithm = if head.starts_with(ZSTD_MAGIC) {
+        CompressionAlgorithmV1::Zstd
+    } else if head.starts_with(LZ4_FRAME_MAGIC) {
+        CompressionAlgorithmV1::Lz4
+    } else if head.starts_with(XZ_MAGIC) {
+        CompressionAlgorithmV1::Xz
+    } else {
+        CompressionAlgorithmV1::None
+    };
+
+    let reader: Box<dyn Read + 'a> = Box::new(io::Cursor::new(head).chain(reader));
+    Ok(match algorithm {
+        CompressionAlgorithmV1::Zstd => Box::new(zstd::Decoder::new(reader)?),
+        CompressionAlgorithmV1::Lz4 => Box::new(lz4_flex::frame::FrameDecoder::new(reader)),
+        CompressionAlgorithmV1::Xz => Box::new(xz2::read::XzDecoder::new(reader)),
+        CompressionAlgorithmV1::None => reader,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read as _, Write as _};
+
+    use bastion_core::job_spec::{CompressionAlgorithmV1, CompressionV1};
+
+    use super::{CompressWriter, PayloadWrite as _, decompress_reader, looks_incompressible};
+
+    #[test]
+    fn every_algorithm_round_trips_through_sniffed_decoder() {
+        let payload = b"hello bastion ".repeat(1024);
+        for algorithm in [
+            CompressionAlgorithmV1::Zstd,
+            CompressionAlgorithmV1::Lz4,
+            CompressionAlgorithmV1::Xz,
+            CompressionAlgorithmV1::None,
+        ] {
+            let compression = CompressionV1 {
+                algorithm,
+                ..Default::default()
+            };
+            let mut writer = CompressWriter::new(Vec::new(), &compression, 1).unwrap();
+            writer.write_all(&payload).unwrap();
+            let encoded = writer.finish().unwrap();
+
+            let mut decoded = Vec::new();
+            decompress_reader(Box::new(std::io::Cursor::new(encoded)))
+                .unwrap()
+                .read_to_end(&mut decoded)
+                .unwrap();
+            assert_eq!(decoded, payload, "{}", algorithm.as_str());
+        }
+    }
+
+    #[test]
+    fn adaptive_zstd_switches_frames_and_still_decodes() {
+        let compression = CompressionV1 {
+            adaptive: true,
+            ..Default::default()
+        };
+        let mut writer = CompressWriter::new(Vec::new(), &compression, 1).unwrap();
+        assert!(writer.adaptive());
+        writer.write_all(b"text ").unwrap();
+        writer.set_incompressible(true).unwrap();
+        writer.write_all(b"media ").unwrap();
+        writer.set_incompressible(false).unwrap();
+        writer.write_all(b"text").unwrap();
+        let encoded = writer.finish().unwrap();
+
+        let mut decoded = Vec::new();
+        decompress_reader(Box::new(std::io::Cursor::new(encoded)))
+            .unwrap()
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, b"text media text");
+    }
+
+    #[test]
+    fn incompressible_detection_uses_extension_and_entropy() {
+        let mut empty = std::io::Cursor::new(Vec::new());
+        assert!(looks_incompressible(std::path::Path::new("a/photo.JPG"), 10, &mut empty).unwrap());
+        assert!(!looks_incompressible(std::path::Path::new("notes.txt"), 10, &mut empty).unwrap());
+
+        let text = b"the quick brown fox ".repeat(20_000);
+        let mut reader = std::io::Cursor::new(text.clone());
+        assert!(
+            !looks_incompressible(
+                std::path::Path::new("big.log"),
+                text.len() as u64,
+                &mut reader
+            )
+            .unwrap()
+        );
+        assert_eq!(reader.position(), 0);
+
+        // xorshift output is close to 8 bits of entropy per byte.
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let noise = (0..512 * 1024)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect::<Vec<_>>();
+        let mut reader = std::io::Cursor::new(noise.clone());
+        assert!(
+            looks_incompressible(
+                std::path::Path::new("blob.bin"),
+                noise.len() as u64,
+                &mut reader
+            )
+            .unwrap()
+        );
+        assert_eq!(reader.position(), 0);
+    }
+}
@@ -1,5 +1,8 @@
 pub mod backup;
 pub mod backup_encryption;
 pub mod restore;
+pub mod snapshot_export;
+
+mod compression;
 
 pub use backup::*;
@@ -8,7 +8,7 @@ use tracing::debug;
 use bastion_core::job_spec;
 use bastion_driver_api::{OpenReaderRequest, TargetRunReader};
 use bastion_driver_registry::builtins;
-use bastion_driver_registry::target_runtime::{self, WebdavRuntimeAuth};
+use bastion_driver_registry::target_runtime;
 use bastion_storage::runs_repo;
 use bastion_storage::secrets::SecretsCrypto;
 use bastion_storage::secrets_repo;
@@ -48,6 +48,12 @@ fn target_ref(spec: &job_spec::JobSpecV1) -> &job_spec::TargetV1 {
         job_spec::JobSpecV1::Filesystem { target, .. } => target,
         job_spec::JobSpecV1::Sqlite { target, .. } => target,
         job_spec::JobSpecV1::Vaultwarden { target, .. } => target,
+        job_spec::JobSpecV1::SqliteApp { target, .. } => target,
+        job_spec::JobSpecV1::Postgres { target, .. } => target,
+        job_spec::JobSpecV1::VmImage { target, .. } => target,
+        job_spec::JobSpecV1::KubernetesPvc { target, .. } => target,
+        job_spec::JobSpecV1::Imap { target, .. } => target,
+        job_spec::JobSpecV1::Git { target, .. } => target,
     }
 }
 
@@ -83,27 +89,18 @@ async fn resolve_target_config_for_reader(
     node_id: &str,
     target: &job_spec::TargetV1,
 ) -> Result<(bastion_driver_api::DriverId, serde_json::Value), anyhow::Error> {
-    let webdav_auth = match target {
-        job_spec::TargetV1::Webdav { secret_name, .. } => {
-            let secret_name = secret_name.trim();
-            if secret_name.is_empty() {
-                anyhow::bail!("webdav.secret_name is required");
-            }
-
-            let cred_bytes = secrets_repo::get_secret(db, secrets, node_id, "webdav", secret_name)
+    let credentials = match target_runtime::credential_secret_for_job_target(target)? {
+        Some(secret) => Some(
+            secrets_repo::get_secret(db, secrets, node_id, secret.kind, secret.name)
                 .await?
-                .ok_or_else(|| anyhow::anyhow!("missing webdav secret: {secret_name}"))?;
-            let credentials = bastion_targets::WebdavCredentials::from_json(&cred_bytes)?;
-            Some(WebdavRuntimeAuth {
-                username: credentials.username,
-                password: credentials.password,
-                secret_name: Some(secret_name.to_string()),
-            })
-        }
-        job_spec::TargetV1::LocalDir { .. } => None,
+                .ok_or_else(|| {
+                    anyhow::anyhow!("missing {} secret: {}", secret.kind, secret.name)
+                })?,
+        ),
+        None => None,
     };
 
-    target_runtime::runtime_input_for_job_target(target, webdav_auth.as_ref())
+    target_runtime::runtime_input_for_job_target(target, credentials.as_deref())
         .map_err(anyhow::Error::new)
 }
 
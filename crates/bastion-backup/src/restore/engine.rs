@@ -98,20 +98,9 @@ impl<'a, S: RestoreSink> RestoreEngine<'a, S> {
         self.check_canceled()?;
 
         let payload: Box<dyn Read> = payload;
-        let reader: Box<dyn Read> = match self.decryption.clone() {
-            PayloadDecryption::None => payload,
-            PayloadDecryption::AgeX25519 { identity } => {
-                use std::str::FromStr as _;
-
-                let identity = age::x25519::Identity::from_str(identity.trim())
-                    .map_err(|e| anyhow::anyhow!(e))?;
-                let decryptor = age::Decryptor::new(payload)?;
-                let reader = decryptor.decrypt(std::iter::once(&identity as &dyn age::Identity))?;
-                Box::new(reader)
-            }
-        };
+        let reader = self.decryption.wrap_reader(payload)?;
 
-        let decoder = zstd::Decoder::new(reader)?;
+        let decoder = crate::compression::decompress_reader(reader)?;
         let mut archive = tar::Archive::new(decoder);
         archive.set_unpack_xattrs(false);
         archive.set_preserve_mtime(true);
@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::BufRead;
+use std::path::Path;
+
+use sqlx::SqlitePool;
+
+use bastion_storage::secrets::SecretsCrypto;
+
+use super::super::RestoreSelection;
+use super::super::access;
+use super::super::selection::{NormalizedRestoreSelection, normalize_restore_selection};
+use super::RestoreEstimate;
+use super::fetch_entries_index;
+use super::types::EntryRecord;
+
+/// Sums the entries a restore of `selection` (or the whole snapshot) would write.
+pub async fn estimate_run_restore(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    data_dir: &Path,
+    run_id: &str,
+    selection: Option<&RestoreSelection>,
+) -> Result<RestoreEstimate, anyhow::Error> {
+    let selection = selection.map(normalize_restore_selection).transpose()?;
+
+    let access::ResolvedRunAccess { access, .. } =
+        access::resolve_success_run_access(db, secrets, run_id).await?;
+
+    let cache_dir = data_dir.join("cache").join("entries").join(run_id);
+    tokio::fs::create_dir_all(&cache_dir).await?;
+    let entries_path = fetch_entries_index(&access, &cache_dir).await?;
+
+    tokio::task::spawn_blocking(move || {
+        estimate_from_entries_index(&entries_path, selection.as_ref())
+    })
+    .await?
+}
+
+pub(in crate::restore) fn estimate_from_entries_index(
+    entries_path: &Path,
+    selection: Option<&NormalizedRestoreSelection>,
+) -> Result<RestoreEstimate, anyhow::Error> {
+    let file = File::open(entries_path)?;
+    let decoder = zstd::Decoder::new(file)?;
+    let reader = std::io::BufReader::new(decoder);
+
+    let mut estimate = RestoreEstimate::default();
+    let mut hardlink_groups = HashSet::<String>::new();
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let rec: EntryRecord = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if let Some(selection) = selection
+            && !selection.matches(&rec.path)
+        {
+            continue;
+        }
+
+        match rec.kind.as_str() {
+            "dir" => estimate.dirs += 1,
+            "symlink" => estimate.symlinks += 1,
+            _ => {
+                estimate.files += 1;
+                let first_link = match rec.hardlink_group {
+                    Some(group) => hardlink_groups.insert(group),
+                    None => true,
+                };
+                if first_link {
+                    estimate.bytes = estimate.bytes.saturating_add(rec.size);
+                }
+            }
+        }
+    }
+
+    Ok(estimate)
+}
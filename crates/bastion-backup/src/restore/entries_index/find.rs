@@ -0,0 +1,68 @@
+use std::fs::File;
+use std::io::BufRead;
+use std::path::Path;
+
+use sqlx::SqlitePool;
+
+use bastion_storage::secrets::SecretsCrypto;
+
+use super::super::access;
+use super::RunEntryVersion;
+use super::fetch_entries_index;
+use super::types::EntryRecord;
+
+/// Looks up a single path in a successful run's entries index.
+///
+/// Returns `Ok(None)` when the snapshot does not contain the path.
+pub async fn find_run_entry(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    data_dir: &Path,
+    run_id: &str,
+    path: &str,
+) -> Result<Option<RunEntryVersion>, anyhow::Error> {
+    let access::ResolvedRunAccess { access, .. } =
+        access::resolve_success_run_access(db, secrets, run_id).await?;
+
+    let cache_dir = data_dir.join("cache").join("entries").join(run_id);
+    tokio::fs::create_dir_all(&cache_dir).await?;
+    let entries_path = fetch_entries_index(&access, &cache_dir).await?;
+
+    let path = path.trim().trim_matches('/').to_string();
+    tokio::task::spawn_blocking(move || find_entry_in_entries_index(&entries_path, &path)).await?
+}
+
+pub(in crate::restore) fn find_entry_in_entries_index(
+    entries_path: &Path,
+    path: &str,
+) -> Result<Option<RunEntryVersion>, anyhow::Error> {
+    let file = File::open(entries_path)?;
+    let decoder = zstd::Decoder::new(file)?;
+    let reader = std::io::BufReader::new(decoder);
+
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let rec: EntryRecord = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if rec.path != path {
+            continue;
+        }
+
+        return Ok(Some(RunEntryVersion {
+            path: rec.path,
+            kind: rec.kind,
+            size: rec.size,
+            mtime: rec.mtime,
+            hash_alg: rec.hash_alg,
+            hash: rec.hash,
+        }));
+    }
+
+    Ok(None)
+}
@@ -1,11 +1,22 @@
+mod estimate;
 mod fetch;
+mod find;
 mod list;
 mod types;
 
+pub use estimate::estimate_run_restore;
+pub use find::find_run_entry;
 pub use list::{list_run_entries_children, list_run_entries_children_with_options};
-pub use types::{ListRunEntriesChildrenOptions, RunEntriesChild, RunEntriesChildrenResponse};
+pub use types::{
+    ListRunEntriesChildrenOptions, RestoreEstimate, RunEntriesChild, RunEntriesChildrenResponse,
+    RunEntryVersion,
+};
 
+#[cfg(test)]
+pub(super) use estimate::estimate_from_entries_index;
 pub(super) use fetch::fetch_entries_index;
+#[cfg(test)]
+pub(super) use find::find_entry_in_entries_index;
 pub(super) use types::EntryRecord;
 
 pub(super) type ListChildrenFromEntriesIndexOptions = types::ListChildrenFromEntriesIndexOptions;
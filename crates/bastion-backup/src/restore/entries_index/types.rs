@@ -25,6 +25,27 @@ pub struct RunEntriesChild {
     pub size: u64,
 }
 
+/// One path as recorded in a snapshot's entries index.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunEntryVersion {
+    pub path: String,
+    pub kind: String,
+    pub size: u64,
+    pub mtime: Option<u64>,
+    pub hash_alg: Option<HashAlgorithm>,
+    pub hash: Option<String>,
+}
+
+/// What a restore would write, summed from a snapshot's entries index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RestoreEstimate {
+    pub files: u64,
+    pub dirs: u64,
+    pub symlinks: u64,
+    /// File bytes; hard-linked files count once per link group.
+    pub bytes: u64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct RunEntriesChildrenResponse {
     pub prefix: String,
@@ -1,8 +1,10 @@
 use std::io::Read;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use bastion_core::backup_format::MANIFEST_NAME;
+use bastion_core::manifest::{ArtifactFormatV1, ManifestV1};
 use bastion_core::progress::ProgressUnitsV1;
 use bastion_targets::{WebdavClient, WebdavCredentials};
 use url::Url;
@@ -20,10 +22,15 @@ pub mod sources;
 mod unpack;
 mod verify;
 pub use entries_index::{
-    ListRunEntriesChildrenOptions, RunEntriesChild, RunEntriesChildrenResponse,
-    list_run_entries_children, list_run_entries_children_with_options,
+    ListRunEntriesChildrenOptions, RestoreEstimate, RunEntriesChild, RunEntriesChildrenResponse,
+    RunEntryVersion, estimate_run_restore, find_run_entry, list_run_entries_children,
+    list_run_entries_children_with_options,
 };
-pub use operations::{spawn_restore_operation, spawn_verify_operation};
+pub use operations::{
+    spawn_postgres_pitr_operation, spawn_reencrypt_operation, spawn_restore_operation,
+    spawn_verify_operation,
+};
+pub use path::available_space;
 
 #[derive(Debug, Clone, Copy)]
 pub enum ConflictPolicy {
@@ -55,6 +62,30 @@ impl std::str::FromStr for ConflictPolicy {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerifyMode {
+    /// Restore the whole snapshot into a temporary directory and check every file.
+    #[default]
+    Full,
+    /// Decrypt, decompress and unpack the whole snapshot in memory, hashing every file instead
+    /// of writing it; needs no scratch space but skips SQLite integrity checks.
+    Stream,
+    /// Check the manifest, stored part sizes and the hashes of a random subset of parts.
+    Sample { percent: u8 },
+}
+
+impl VerifyMode {
+    pub const DEFAULT_SAMPLE_PERCENT: u8 = 10;
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Stream => "stream",
+            Self::Sample { .. } => "sample",
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct RestoreSelection {
     #[serde(default)]
@@ -66,7 +97,37 @@ pub struct RestoreSelection {
 #[derive(Debug, Clone)]
 pub enum PayloadDecryption {
     None,
-    AgeX25519 { identity: String },
+    /// One or more age identities, one per line (a rotated key may need a retired identity).
+    AgeX25519 {
+        identity: String,
+    },
+}
+
+impl PayloadDecryption {
+    pub(crate) fn wrap_reader<'a>(
+        &self,
+        payload: Box<dyn Read + 'a>,
+    ) -> Result<Box<dyn Read + 'a>, anyhow::Error> {
+        match self {
+            Self::None => Ok(payload),
+            Self::AgeX25519 { identity } => {
+                use std::str::FromStr as _;
+
+                let identities = identity
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(|line| {
+                        age::x25519::Identity::from_str(line).map_err(|e| anyhow::anyhow!(e))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let decryptor = age::Decryptor::new(payload)?;
+                let reader =
+                    decryptor.decrypt(identities.iter().map(|i| i as &dyn age::Identity))?;
+                Ok(Box::new(reader))
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -102,6 +163,74 @@ pub fn restore_to_local_fs(
     Ok(())
 }
 
+/// Restores a run directory on local disk (e.g. an unpacked snapshot export) without a Hub or
+/// database. Parts are verified against the manifest while they are read.
+pub fn restore_run_dir_to_local_fs(
+    run_dir: &Path,
+    destination_dir: PathBuf,
+    conflict: ConflictPolicy,
+    decryption: PayloadDecryption,
+    selection: Option<&RestoreSelection>,
+    on_progress: Option<&dyn Fn(ProgressUnitsV1)>,
+) -> Result<ManifestV1, anyhow::Error> {
+    use sources::ArtifactSource as _;
+
+    let manifest =
+        serde_json::from_slice::<ManifestV1>(&std::fs::read(run_dir.join(MANIFEST_NAME))?)?;
+    if manifest.pipeline.encryption == "age" && matches!(decryption, PayloadDecryption::None) {
+        anyhow::bail!(
+            "payload is encrypted with backup key {}; an age identity is required",
+            manifest.pipeline.encryption_key.as_deref().unwrap_or("?")
+        );
+    }
+
+    let source = sources::LocalDirSource::new(run_dir.to_path_buf());
+    match manifest.pipeline.format {
+        ArtifactFormatV1::ArchiveV1 => {
+            let payload = source.open_payload_reader(&manifest, run_dir)?;
+            restore_to_local_fs(
+                payload,
+                destination_dir,
+                conflict,
+                decryption,
+                selection,
+                on_progress,
+            )?;
+        }
+        ArtifactFormatV1::RawTreeV1 => {
+            let entries_index_path = run_dir.join(&manifest.entry_index.name);
+            raw_tree::restore_raw_tree_to_local_fs(
+                &source,
+                &entries_index_path,
+                run_dir,
+                &destination_dir,
+                conflict,
+                selection,
+                on_progress,
+            )?;
+        }
+    }
+    Ok(manifest)
+}
+
+/// Downloads one stored artifact file of a successful run (e.g. `manifest.json`, a payload part,
+/// or a `raw_tree_v1` data file) to `dest`. Returns the number of bytes written.
+pub async fn fetch_run_artifact_to_file(
+    db: &sqlx::SqlitePool,
+    secrets: &bastion_storage::secrets::SecretsCrypto,
+    run_id: &str,
+    artifact_path: &str,
+    dest: PathBuf,
+) -> Result<u64, anyhow::Error> {
+    let access::ResolvedRunAccess { access, .. } =
+        access::resolve_success_run_access(db, secrets, run_id).await?;
+    let size = access
+        .reader()
+        .get_to_file(artifact_path.to_string(), dest, None, 3)
+        .await?;
+    Ok(size)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn restore_to_webdav(
     payload: Box<dyn Read + Send>,
@@ -7,9 +7,11 @@ use tracing::warn;
 use bastion_storage::operations_repo;
 use bastion_storage::secrets::SecretsCrypto;
 
-use super::{ConflictPolicy, RestoreDestination, RestoreSelection};
+use super::{ConflictPolicy, RestoreDestination, RestoreSelection, VerifyMode};
 
+mod postgres_pitr;
 mod progress;
+mod reencrypt;
 mod restore;
 mod util;
 mod verify;
@@ -75,6 +77,7 @@ pub async fn spawn_restore_operation(
     destination: RestoreDestination,
     conflict: ConflictPolicy,
     selection: Option<RestoreSelection>,
+    passphrase: Option<String>,
     cancel_token: CancellationToken,
     on_finish: Option<Box<dyn FnOnce() + Send + 'static>>,
 ) {
@@ -98,6 +101,7 @@ pub async fn spawn_restore_operation(
             &destination,
             conflict,
             selection,
+            passphrase.as_deref(),
             &cancel_token,
         )
         .await
@@ -119,12 +123,15 @@ pub async fn spawn_restore_operation(
     });
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn spawn_verify_operation(
     db: SqlitePool,
     secrets: std::sync::Arc<SecretsCrypto>,
     data_dir: PathBuf,
     op_id: String,
     run_id: String,
+    mode: VerifyMode,
+    passphrase: Option<String>,
     cancel_token: CancellationToken,
     on_finish: Option<Box<dyn FnOnce() + Send + 'static>>,
 ) {
@@ -139,9 +146,22 @@ pub async fn spawn_verify_operation(
         }
         let _finish_guard = FinishGuard(on_finish);
 
-        if let Err(error) =
-            verify::verify_operation(&db, &secrets, &data_dir, &op_id, &run_id, &cancel_token).await
-        {
+        let ctx = verify::VerifyOperationCtx {
+            db: &db,
+            secrets: &secrets,
+            data_dir: &data_dir,
+            op_id: &op_id,
+            run_id: &run_id,
+            cancel_token: &cancel_token,
+        };
+        let result = match mode {
+            VerifyMode::Full | VerifyMode::Stream => {
+                verify::verify_operation(ctx, mode == VerifyMode::Stream, passphrase.as_deref())
+                    .await
+            }
+            VerifyMode::Sample { percent } => verify::verify_sample_operation(ctx, percent).await,
+        };
+        if let Err(error) = result {
             if error.downcast_ref::<OperationCanceled>().is_some() {
                 cancel_operation(&db, &data_dir, &op_id).await;
                 return;
@@ -152,3 +172,97 @@ pub async fn spawn_verify_operation(
         }
     });
 }
+
+/// Re-encrypts the given runs to the current recipient of `key_name` (after a key rotation).
+#[allow(clippy::too_many_arguments)]
+pub async fn spawn_reencrypt_operation(
+    db: SqlitePool,
+    secrets: std::sync::Arc<SecretsCrypto>,
+    data_dir: PathBuf,
+    op_id: String,
+    key_name: String,
+    run_ids: Vec<String>,
+    cancel_token: CancellationToken,
+    on_finish: Option<Box<dyn FnOnce() + Send + 'static>>,
+) {
+    tokio::spawn(async move {
+        struct FinishGuard(Option<Box<dyn FnOnce() + Send + 'static>>);
+        impl Drop for FinishGuard {
+            fn drop(&mut self) {
+                if let Some(cb) = self.0.take() {
+                    cb();
+                }
+            }
+        }
+        let _finish_guard = FinishGuard(on_finish);
+
+        if let Err(error) = reencrypt::reencrypt_operation(
+            &db,
+            &secrets,
+            &op_id,
+            &key_name,
+            &run_ids,
+            &cancel_token,
+        )
+        .await
+        {
+            if error.downcast_ref::<OperationCanceled>().is_some() {
+                cancel_operation(&db, &data_dir, &op_id).await;
+                return;
+            }
+            warn!(op_id = %op_id, key_name = %key_name, error = %error, "re-encrypt operation failed");
+            let msg = format!("{error:#}");
+            fail_operation(&db, &data_dir, &op_id, &msg).await;
+        }
+    });
+}
+
+/// Rebuilds a PostgreSQL data directory from a `postgres` job's base backup and WAL runs, set up
+/// to recover to `target_time`.
+#[allow(clippy::too_many_arguments)]
+pub async fn spawn_postgres_pitr_operation(
+    db: SqlitePool,
+    secrets: std::sync::Arc<SecretsCrypto>,
+    data_dir: PathBuf,
+    op_id: String,
+    job_id: String,
+    target_time: time::OffsetDateTime,
+    directory: PathBuf,
+    passphrase: Option<String>,
+    cancel_token: CancellationToken,
+    on_finish: Option<Box<dyn FnOnce() + Send + 'static>>,
+) {
+    tokio::spawn(async move {
+        struct FinishGuard(Option<Box<dyn FnOnce() + Send + 'static>>);
+        impl Drop for FinishGuard {
+            fn drop(&mut self) {
+                if let Some(cb) = self.0.take() {
+                    cb();
+                }
+            }
+        }
+        let _finish_guard = FinishGuard(on_finish);
+
+        if let Err(error) = postgres_pitr::postgres_pitr_operation(
+            &db,
+            &secrets,
+            &data_dir,
+            &op_id,
+            &job_id,
+            target_time,
+            &directory,
+            passphrase.as_deref(),
+            &cancel_token,
+        )
+        .await
+        {
+            if error.downcast_ref::<OperationCanceled>().is_some() {
+                cancel_operation(&db, &data_dir, &op_id).await;
+                return;
+            }
+            warn!(op_id = %op_id, job_id = %job_id, error = %error, "postgres point-in-time restore failed");
+            let msg = format!("{error:#}");
+            fail_operation(&db, &data_dir, &op_id, &msg).await;
+        }
+    });
+}
@@ -0,0 +1,374 @@
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use bastion_core::manifest::ArtifactFormatV1;
+use bastion_core::progress::{ProgressKindV1, ProgressUnitsV1};
+use bastion_storage::operations_repo;
+use bastion_storage::runs_repo::{self, Run, RunListFilter, RunStatus};
+use bastion_storage::secrets::SecretsCrypto;
+
+use super::super::engine::RestoreEngine;
+use super::super::sinks::LocalFsSink;
+use super::super::sources::{ArtifactSource, DriverSource, RunArtifactSource};
+use super::super::{ConflictPolicy, RestoreSelection, access};
+use super::progress::{OperationProgressUpdate, spawn_operation_progress_writer};
+use crate::backup::postgres::{BASE_DIR, WAL_DIR};
+
+/// Upper bound on the runs considered when building the restore chain.
+const PITR_RUNS_LIMIT: u32 = 5000;
+
+/// Runs to restore for a point-in-time recovery, oldest first.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct PitrChain {
+    /// The run holding the base backup (its `wal/` is restored as well).
+    pub(super) base_run_id: String,
+    /// Later runs whose `wal/` directory is restored on top.
+    pub(super) wal_run_ids: Vec<String>,
+}
+
+/// Picks the newest successful base backup that finished at or before `target`, then every later
+/// successful run up to and including the first one that started after `target` (it ships the WAL
+/// written up to that moment).
+pub(super) fn plan_pitr_chain(runs: &[Run], target: i64) -> Result<PitrChain, anyhow::Error> {
+    let mut runs = runs
+        .iter()
+        .filter(|run| run.status == RunStatus::Success)
+        .collect::<Vec<_>>();
+    runs.sort_by(|a, b| a.started_at.cmp(&b.started_at).then(a.id.cmp(&b.id)));
+
+    let base_idx = runs
+        .iter()
+        .rposition(|run| has_base_backup(run) && run.ended_at.is_some_and(|ended| ended <= target))
+        .ok_or_else(|| {
+            anyhow::anyhow!("no successful base backup finished at or before the target time")
+        })?;
+
+    let mut wal_run_ids = Vec::new();
+    let mut covered = false;
+    for run in &runs[base_idx + 1..] {
+        wal_run_ids.push(run.id.clone());
+        if run.started_at > target {
+            covered = true;
+            break;
+        }
+    }
+    if !covered {
+        anyhow::bail!("no successful run has shipped the WAL up to the target time yet");
+    }
+
+    Ok(PitrChain {
+        base_run_id: runs[base_idx].id.clone(),
+        wal_run_ids,
+    })
+}
+
+fn has_base_backup(run: &Run) -> bool {
+    run.summary
+        .as_ref()
+        .and_then(|v| v.get("postgres"))
+        .and_then(|v| v.get("base_backup"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Turns the restored `base/` directory into a recovery target: replay WAL from `wal/` and promote
+/// once `target` is reached.
+pub(super) fn write_recovery_config(
+    directory: &Path,
+    target: OffsetDateTime,
+) -> Result<(), anyhow::Error> {
+    let base = directory.join(BASE_DIR);
+    let wal = directory.join(WAL_DIR);
+    if !base.is_dir() {
+        anyhow::bail!("restored base backup not found: {}", base.display());
+    }
+
+    let target =
+        target
+            .to_offset(time::UtcOffset::UTC)
+            .format(time::macros::format_description!(
+                "[year]-[month]-[day] [hour]:[minute]:[second]+00"
+            ))?;
+    let wal = wal.to_string_lossy().replace('\'', "''");
+
+    std::fs::write(base.join("recovery.signal"), b"")?;
+    let mut conf = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(base.join("postgresql.auto.conf"))?;
+    writeln!(conf)?;
+    writeln!(conf, "# Added by bastion point-in-time restore")?;
+    writeln!(conf, "restore_command = 'cp \"{wal}/%f\" \"%p\"'")?;
+    writeln!(conf, "recovery_target_time = '{target}'")?;
+    writeln!(conf, "recovery_target_action = 'promote'")?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn postgres_pitr_operation(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    data_dir: &Path,
+    op_id: &str,
+    job_id: &str,
+    target_time: OffsetDateTime,
+    directory: &Path,
+    passphrase: Option<&str>,
+    cancel_token: &CancellationToken,
+) -> Result<(), anyhow::Error> {
+    super::check_operation_canceled(op_id, cancel_token)?;
+    info!(
+        op_id = %op_id,
+        job_id = %job_id,
+        target_time = target_time.unix_timestamp(),
+        directory = %directory.display(),
+        "postgres point-in-time restore started"
+    );
+    operations_repo::append_event(db, op_id, "info", "start", "start", None).await?;
+    let progress_tx =
+        spawn_operation_progress_writer(db.clone(), op_id.to_string(), ProgressKindV1::Restore);
+
+    let runs = runs_repo::list_runs_for_job_filtered(
+        db,
+        job_id,
+        &RunListFilter {
+            status: Some(RunStatus::Success),
+            ..Default::default()
+        },
+        PITR_RUNS_LIMIT,
+    )
+    .await?;
+    let chain = plan_pitr_chain(&runs, target_time.unix_timestamp())?;
+    operations_repo::append_event(
+        db,
+        op_id,
+        "info",
+        "plan",
+        "plan",
+        Some(serde_json::json!({
+            "base_run_id": chain.base_run_id,
+            "wal_runs": chain.wal_run_ids.len(),
+        })),
+    )
+    .await?;
+
+    let op_dir = super::util::operation_dir(data_dir, op_id);
+    let mut done = ProgressUnitsV1::default();
+    let steps = std::iter::once((chain.base_run_id.clone(), None)).chain(
+        chain.wal_run_ids.iter().map(|id| {
+            (
+                id.clone(),
+                Some(RestoreSelection {
+                    files: Vec::new(),
+                    dirs: vec![WAL_DIR.to_string()],
+                }),
+            )
+        }),
+    );
+    for (run_id, selection) in steps {
+        super::check_operation_canceled(op_id, cancel_token)?;
+        operations_repo::append_event(
+            db,
+            op_id,
+            "info",
+            "restore",
+            "restore",
+            Some(serde_json::json!({ "run_id": run_id })),
+        )
+        .await?;
+        let restored = restore_run(
+            db,
+            secrets,
+            &op_dir.join("staging").join(&run_id),
+            op_id,
+            &run_id,
+            directory.to_path_buf(),
+            selection,
+            passphrase,
+            cancel_token,
+            done,
+            &progress_tx,
+        )
+        .await?;
+        done = restored;
+    }
+
+    super::check_operation_canceled(op_id, cancel_token)?;
+    write_recovery_config(directory, target_time)?;
+
+    operations_repo::append_event(db, op_id, "info", "complete", "complete", None).await?;
+    operations_repo::complete_operation(
+        db,
+        op_id,
+        operations_repo::OperationStatus::Success,
+        Some(serde_json::json!({
+            "destination": { "type": "local_fs", "directory": directory.to_string_lossy().to_string() },
+            "postgres_pitr": {
+                "target_time": target_time.unix_timestamp(),
+                "base_run_id": chain.base_run_id,
+                "wal_run_ids": chain.wal_run_ids,
+            },
+        })),
+        None,
+    )
+    .await?;
+    let _ = tokio::fs::remove_dir_all(&op_dir).await;
+
+    info!(op_id = %op_id, job_id = %job_id, "postgres point-in-time restore completed");
+    Ok(())
+}
+
+/// Restores one run of the chain into `directory` and returns the cumulative progress.
+#[allow(clippy::too_many_arguments)]
+async fn restore_run(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    staging_dir: &Path,
+    op_id: &str,
+    run_id: &str,
+    directory: PathBuf,
+    selection: Option<RestoreSelection>,
+    passphrase: Option<&str>,
+    cancel_token: &CancellationToken,
+    done_before: ProgressUnitsV1,
+    progress_tx: &tokio::sync::watch::Sender<Option<OperationProgressUpdate>>,
+) -> Result<ProgressUnitsV1, anyhow::Error> {
+    let access::ResolvedRunAccess { access, .. } =
+        access::resolve_success_run_access(db, secrets, run_id).await?;
+    tokio::fs::create_dir_all(staging_dir).await?;
+
+    let handle = tokio::runtime::Handle::current();
+    let source = RunArtifactSource::Driver(DriverSource::new(handle, access.reader()));
+    let manifest = source.read_manifest().await?;
+    if manifest.pipeline.format != ArtifactFormatV1::ArchiveV1 {
+        anyhow::bail!("run {run_id} is not an archive_v1 backup");
+    }
+    let decryption =
+        super::util::resolve_payload_decryption(db, secrets, &manifest, passphrase).await?;
+    super::check_operation_canceled(op_id, cancel_token)?;
+
+    // The base run must land in an empty directory; later runs only add WAL files.
+    let conflict = if selection.is_none() {
+        ConflictPolicy::Fail
+    } else {
+        ConflictPolicy::Skip
+    };
+    let staging_dir = staging_dir.to_path_buf();
+    let progress_tx = progress_tx.clone();
+    let op_id = op_id.to_string();
+    let cancel_token = cancel_token.clone();
+    tokio::task::spawn_blocking(move || {
+        let last = std::sync::Mutex::new(done_before);
+        let on_progress = |done: ProgressUnitsV1| {
+            let total = ProgressUnitsV1 {
+                files: done_before.files.saturating_add(done.files),
+                dirs: done_before.dirs.saturating_add(done.dirs),
+                bytes: done_before.bytes.saturating_add(done.bytes),
+            };
+            if let Ok(mut guard) = last.lock() {
+                *guard = total;
+            }
+            let _ = progress_tx.send(Some(OperationProgressUpdate {
+                stage: "restore",
+                done: total,
+                total: None,
+            }));
+        };
+        let cancel_check = || super::check_operation_canceled(&op_id, &cancel_token);
+        let payload = source.open_payload_reader(&manifest, &staging_dir)?;
+        let mut sink = LocalFsSink::new(directory, conflict);
+        let mut engine = RestoreEngine::new_with_cancel(
+            &mut sink,
+            decryption,
+            selection.as_ref(),
+            Some(&on_progress),
+            Some(&cancel_check),
+        )?;
+        engine.restore(payload)?;
+        drop(engine);
+        let done = last
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Ok::<_, anyhow::Error>(done)
+    })
+    .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+    use time::OffsetDateTime;
+
+    use bastion_storage::runs_repo::{Run, RunStatus};
+
+    use super::{PitrChain, plan_pitr_chain, write_recovery_config};
+
+    fn run(id: &str, status: RunStatus, started_at: i64, base_backup: bool) -> Run {
+        Run {
+            id: id.to_string(),
+            job_id: "job1".to_string(),
+            status,
+            started_at,
+            ended_at: Some(started_at + 10),
+            cancel_requested_at: None,
+            cancel_requested_by_user_id: None,
+            cancel_reason: None,
+            progress: None,
+            summary: Some(serde_json::json!({ "postgres": { "base_backup": base_backup } })),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn pitr_chain_starts_at_latest_base_before_target_and_covers_it() {
+        let runs = vec![
+            run("b1", RunStatus::Success, 100, true),
+            run("w1", RunStatus::Success, 200, false),
+            run("b2", RunStatus::Success, 300, true),
+            run("w2", RunStatus::Failed, 400, false),
+            run("w3", RunStatus::Success, 500, false),
+            run("w4", RunStatus::Success, 600, false),
+            run("w5", RunStatus::Success, 700, false),
+        ];
+        assert_eq!(
+            plan_pitr_chain(&runs, 550).unwrap(),
+            PitrChain {
+                base_run_id: "b2".to_string(),
+                wal_run_ids: vec!["w3".to_string(), "w4".to_string()],
+            }
+        );
+        assert_eq!(plan_pitr_chain(&runs, 250).unwrap().base_run_id, "b1");
+
+        assert!(plan_pitr_chain(&runs, 50).is_err());
+        assert!(plan_pitr_chain(&runs, 750).is_err());
+    }
+
+    #[test]
+    fn recovery_config_targets_the_requested_time() {
+        let tmp = tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("base")).unwrap();
+        std::fs::write(
+            tmp.path().join("base/postgresql.auto.conf"),
+            "port = 5432\n",
+        )
+        .unwrap();
+
+        let target = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+        write_recovery_config(tmp.path(), target).unwrap();
+
+        assert!(tmp.path().join("base/recovery.signal").exists());
+        let conf = std::fs::read_to_string(tmp.path().join("base/postgresql.auto.conf")).unwrap();
+        assert!(conf.starts_with("port = 5432\n"));
+        assert!(conf.contains("recovery_target_time = '2023-11-14 22:13:20+00'"));
+        assert!(conf.contains("recovery_target_action = 'promote'"));
+        assert!(conf.contains(&format!(
+            "restore_command = 'cp \"{}/%f\" \"%p\"'",
+            tmp.path().join("wal").display()
+        )));
+    }
+}
@@ -0,0 +1,459 @@
+use std::io::{Read as _, Write as _};
+use std::path::Path;
+
+use sqlx::SqlitePool;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use bastion_core::backup_format::MANIFEST_NAME;
+use bastion_core::manifest::{ArtifactFormatV1, ArtifactPart, ManifestV1};
+use bastion_core::progress::{ProgressKindV1, ProgressUnitsV1};
+use bastion_storage::operations_repo;
+use bastion_storage::secrets::SecretsCrypto;
+
+use super::super::sources::{ArtifactSource as _, LocalDirSource};
+use super::super::{PayloadDecryption, access};
+use super::progress::{OperationProgressUpdate, spawn_operation_progress_writer};
+use crate::backup::PartWriter;
+
+const PAYLOAD_PART_PREFIX: &str = "payload.part";
+/// Scratch directory inside the run directory, so the final moves are same-filesystem renames.
+const REENCRYPT_WORK_DIR: &str = ".reencrypt";
+
+#[derive(Debug)]
+enum RunOutcome {
+    Reencrypted { bytes: u64 },
+    Skipped { reason: &'static str },
+}
+
+pub(super) async fn reencrypt_operation(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    op_id: &str,
+    key_name: &str,
+    run_ids: &[String],
+    cancel_token: &CancellationToken,
+) -> Result<(), anyhow::Error> {
+    super::check_operation_canceled(op_id, cancel_token)?;
+    info!(
+        op_id = %op_id,
+        key_name = %key_name,
+        runs = run_ids.len(),
+        "re-encrypt operation started"
+    );
+    operations_repo::append_event(
+        db,
+        op_id,
+        "info",
+        "start",
+        "start",
+        Some(serde_json::json!({ "key_name": key_name, "runs": run_ids.len() })),
+    )
+    .await?;
+    let progress_tx =
+        spawn_operation_progress_writer(db.clone(), op_id.to_string(), ProgressKindV1::Reencrypt);
+
+    let recipient = crate::backup_encryption::get_age_identity(db, secrets, key_name)
+        .await?
+        .map(|identity| {
+            use std::str::FromStr as _;
+
+            age::x25519::Identity::from_str(identity.trim())
+                .map(|identity| identity.to_public().to_string())
+                .map_err(|e| anyhow::anyhow!(e))
+        })
+        .transpose()?
+        .ok_or_else(|| anyhow::anyhow!("missing backup age identity: {}", key_name))?;
+
+    let total = ProgressUnitsV1 {
+        files: run_ids.len() as u64,
+        ..ProgressUnitsV1::default()
+    };
+    let mut done = ProgressUnitsV1::default();
+    let (mut reencrypted, mut skipped, mut failed) = (0u64, 0u64, 0u64);
+    for run_id in run_ids {
+        super::check_operation_canceled(op_id, cancel_token)?;
+
+        match reencrypt_run(
+            db,
+            secrets,
+            key_name,
+            &recipient,
+            run_id,
+            op_id,
+            cancel_token,
+        )
+        .await
+        {
+            Ok(RunOutcome::Reencrypted { bytes }) => {
+                reencrypted += 1;
+                done.bytes = done.bytes.saturating_add(bytes);
+                operations_repo::append_event(
+                    db,
+                    op_id,
+                    "info",
+                    "run",
+                    "reencrypted",
+                    Some(serde_json::json!({ "run_id": run_id, "bytes": bytes })),
+                )
+                .await?;
+            }
+            Ok(RunOutcome::Skipped { reason }) => {
+                skipped += 1;
+                operations_repo::append_event(
+                    db,
+                    op_id,
+                    "info",
+                    "run",
+                    "skipped",
+                    Some(serde_json::json!({ "run_id": run_id, "reason": reason })),
+                )
+                .await?;
+            }
+            Err(error) => {
+                if error.downcast_ref::<super::OperationCanceled>().is_some() {
+                    return Err(error);
+                }
+                failed += 1;
+                warn!(op_id = %op_id, run_id = %run_id, error = %error, "re-encrypt run failed");
+                operations_repo::append_event(
+                    db,
+                    op_id,
+                    "error",
+                    "run",
+                    "failed",
+                    Some(serde_json::json!({ "run_id": run_id, "error": format!("{error:#}") })),
+                )
+                .await?;
+            }
+        }
+
+        done.files = done.files.saturating_add(1);
+        let _ = progress_tx.send(Some(OperationProgressUpdate {
+            stage: "reencrypt",
+            done,
+            total: Some(total),
+        }));
+    }
+
+    let summary = serde_json::json!({
+        "key_name": key_name,
+        "recipient": recipient,
+        "runs_total": run_ids.len(),
+        "reencrypted": reencrypted,
+        "skipped": skipped,
+        "failed": failed,
+    });
+    operations_repo::complete_operation(
+        db,
+        op_id,
+        if failed == 0 {
+            operations_repo::OperationStatus::Success
+        } else {
+            operations_repo::OperationStatus::Failed
+        },
+        Some(summary),
+        None,
+    )
+    .await?;
+
+    info!(
+        op_id = %op_id,
+        key_name = %key_name,
+        reencrypted,
+        skipped,
+        failed,
+        "re-encrypt operation completed"
+    );
+    Ok(())
+}
+
+async fn reencrypt_run(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    key_name: &str,
+    recipient: &str,
+    run_id: &str,
+    op_id: &str,
+    cancel_token: &CancellationToken,
+) -> Result<RunOutcome, anyhow::Error> {
+    let access::ResolvedRunAccess { access, .. } =
+        access::resolve_success_run_access(db, secrets, run_id).await?;
+    // Rewriting parts in place needs direct filesystem access to the run directory.
+    let Some(run_dir) = access.local_run_dir() else {
+        return Ok(RunOutcome::Skipped {
+            reason: "unsupported_target",
+        });
+    };
+
+    let manifest = LocalDirSource::new(run_dir.clone()).read_manifest().await?;
+    if manifest.pipeline.format != ArtifactFormatV1::ArchiveV1
+        || manifest.pipeline.encryption != "age"
+        || manifest.pipeline.encryption_key.as_deref().map(str::trim) != Some(key_name)
+    {
+        return Ok(RunOutcome::Skipped {
+            reason: "other_key",
+        });
+    }
+    if manifest.pipeline.encryption_recipient.as_deref() == Some(recipient) {
+        return Ok(RunOutcome::Skipped {
+            reason: "up_to_date",
+        });
+    }
+
+    let identities = crate::backup_encryption::list_age_identities(
+        db,
+        secrets,
+        key_name,
+        manifest.pipeline.encryption_recipient.as_deref(),
+    )
+    .await?;
+    if identities.is_empty() {
+        anyhow::bail!(
+            "no identity of backup key {} matches this snapshot",
+            key_name
+        );
+    }
+    let decryption = PayloadDecryption::AgeX25519 {
+        identity: identities.join("\n"),
+    };
+
+    let recipient = recipient.to_string();
+    let op_id = op_id.to_string();
+    let cancel_token = cancel_token.clone();
+    tokio::task::spawn_blocking(move || {
+        let cancel_check = || super::check_operation_canceled(&op_id, &cancel_token);
+        reencrypt_run_dir(
+            &run_dir,
+            &manifest,
+            &decryption,
+            &recipient,
+            Some(&cancel_check),
+        )
+    })
+    .await?
+    .map(|manifest| RunOutcome::Reencrypted {
+        bytes: manifest.artifacts.iter().map(|p| p.size).sum(),
+    })
+}
+
+/// Rewrites the payload parts of `run_dir` encrypted to `recipient` and updates the manifest.
+///
+/// Old parts are moved aside before the new ones are put in place and only deleted once the
+/// new manifest is written, so an interrupted run can be recovered by hand.
+fn reencrypt_run_dir(
+    run_dir: &Path,
+    manifest: &ManifestV1,
+    decryption: &PayloadDecryption,
+    recipient: &str,
+    cancel_check: Option<&dyn Fn() -> Result<(), anyhow::Error>>,
+) -> Result<ManifestV1, anyhow::Error> {
+    let check = || cancel_check.map_or(Ok(()), |check| check());
+
+    let work_dir = run_dir.join(REENCRYPT_WORK_DIR);
+    if work_dir.exists() {
+        // May hold the old parts of an interrupted re-encryption; never delete it blindly.
+        anyhow::bail!(
+            "{} exists (interrupted re-encryption?); inspect and remove it first",
+            work_dir.display()
+        );
+    }
+    let new_dir = work_dir.join("new");
+    let old_dir = work_dir.join("old");
+    std::fs::create_dir_all(&new_dir)?;
+    std::fs::create_dir_all(&old_dir)?;
+
+    let result = write_reencrypted_parts(
+        run_dir, manifest, decryption, recipient, &work_dir, &new_dir, &check,
+    );
+    let parts = match result {
+        Ok(parts) => parts,
+        Err(error) => {
+            let _ = std::fs::remove_dir_all(&work_dir);
+            return Err(error);
+        }
+    };
+    check()?;
+
+    let mut updated = manifest.clone();
+    updated.pipeline.encryption_recipient = Some(recipient.to_string());
+    updated.artifacts = parts;
+
+    let manifest_tmp = work_dir.join(MANIFEST_NAME);
+    std::fs::write(&manifest_tmp, serde_json::to_vec_pretty(&updated)?)?;
+
+    for part in &manifest.artifacts {
+        std::fs::rename(run_dir.join(&part.name), old_dir.join(&part.name))?;
+    }
+    for part in &updated.artifacts {
+        std::fs::rename(new_dir.join(&part.name), run_dir.join(&part.name))?;
+    }
+    std::fs::rename(&manifest_tmp, run_dir.join(MANIFEST_NAME))?;
+    std::fs::remove_dir_all(&work_dir)?;
+
+    Ok(updated)
+}
+
+fn write_reencrypted_parts(
+    run_dir: &Path,
+    manifest: &ManifestV1,
+    decryption: &PayloadDecryption,
+    recipient: &str,
+    work_dir: &Path,
+    new_dir: &Path,
+    check: &dyn Fn() -> Result<(), anyhow::Error>,
+) -> Result<Vec<ArtifactPart>, anyhow::Error> {
+    use std::str::FromStr as _;
+
+    let recipient = age::x25519::Recipient::from_str(recipient).map_err(|e| anyhow::anyhow!(e))?;
+    let payload = LocalDirSource::new(run_dir.to_path_buf())
+        .open_payload_reader(manifest, &work_dir.join("staging"))?;
+    let mut plain = decryption.wrap_reader(payload)?;
+
+    let mut part_writer = PartWriter::new(
+        new_dir.to_path_buf(),
+        manifest.pipeline.split_bytes,
+        PAYLOAD_PART_PREFIX,
+    )?;
+    let encryptor =
+        age::Encryptor::with_recipients(std::iter::once(&recipient as &dyn age::Recipient))?;
+    let mut encrypted = encryptor.wrap_output(&mut part_writer)?;
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        check()?;
+        let n = plain.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        encrypted.write_all(&buf[..n])?;
+    }
+    encrypted.finish()?;
+    Ok(part_writer.finish()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read as _, Write as _};
+
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    use bastion_core::backup_format::MANIFEST_NAME;
+    use bastion_core::manifest::{ArtifactFormatV1, EntryIndexRef, ManifestV1, PipelineSettings};
+
+    use super::{PAYLOAD_PART_PREFIX, reencrypt_run_dir};
+    use crate::backup::PartWriter;
+    use crate::restore::PayloadDecryption;
+
+    fn write_encrypted_run(run_dir: &std::path::Path, recipient: &str, data: &[u8]) -> ManifestV1 {
+        use std::str::FromStr as _;
+
+        let recipient_parsed = age::x25519::Recipient::from_str(recipient).unwrap();
+        let mut part_writer =
+            PartWriter::new(run_dir.to_path_buf(), 1024, PAYLOAD_PART_PREFIX).expect("part writer");
+        let encryptor = age::Encryptor::with_recipients(std::iter::once(
+            &recipient_parsed as &dyn age::Recipient,
+        ))
+        .unwrap();
+        let mut encrypted = encryptor.wrap_output(&mut part_writer).unwrap();
+        encrypted.write_all(data).unwrap();
+        encrypted.finish().unwrap();
+        let parts = part_writer.finish().unwrap();
+
+        let manifest = ManifestV1 {
+            format_version: ManifestV1::FORMAT_VERSION,
+            job_id: Uuid::nil(),
+            run_id: Uuid::nil(),
+            started_at: "2026-02-02T00:00:00Z".to_string(),
+            ended_at: "2026-02-02T00:00:01Z".to_string(),
+            pipeline: PipelineSettings {
+                format: ArtifactFormatV1::ArchiveV1,
+                tar: "pax".to_string(),
+                compression: "zstd".to_string(),
+                compression_level: None,
+                encryption: "age".to_string(),
+                encryption_key: Some("primary".to_string()),
+                encryption_recipient: None,
+                split_bytes: 1024,
+            },
+            artifacts: parts,
+            entry_index: EntryIndexRef {
+                name: "entries.jsonl.zst".to_string(),
+                count: 0,
+            },
+            source: None,
+        };
+        std::fs::write(
+            run_dir.join(MANIFEST_NAME),
+            serde_json::to_vec_pretty(&manifest).unwrap(),
+        )
+        .unwrap();
+        manifest
+    }
+
+    #[test]
+    fn reencrypt_run_dir_rewrites_parts_for_new_recipient() {
+        use age::secrecy::ExposeSecret as _;
+
+        let tmp = TempDir::new().unwrap();
+        let run_dir = tmp.path().join("run");
+        std::fs::create_dir_all(&run_dir).unwrap();
+
+        let old_identity = age::x25519::Identity::generate();
+        let new_identity = age::x25519::Identity::generate();
+        let data = (0..10_000u32)
+            .flat_map(|i| i.to_le_bytes())
+            .collect::<Vec<_>>();
+        let manifest = write_encrypted_run(&run_dir, &old_identity.to_public().to_string(), &data);
+        assert!(manifest.artifacts.len() > 1);
+
+        let new_recipient = new_identity.to_public().to_string();
+        let updated = reencrypt_run_dir(
+            &run_dir,
+            &manifest,
+            &PayloadDecryption::AgeX25519 {
+                identity: old_identity.to_string().expose_secret().to_string(),
+            },
+            &new_recipient,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            updated.pipeline.encryption_recipient.as_deref(),
+            Some(new_recipient.as_str())
+        );
+        assert!(!run_dir.join(super::REENCRYPT_WORK_DIR).exists());
+
+        let stored: ManifestV1 =
+            serde_json::from_slice(&std::fs::read(run_dir.join(MANIFEST_NAME)).unwrap()).unwrap();
+        assert_eq!(stored, updated);
+
+        let mut payload = Vec::new();
+        for part in &stored.artifacts {
+            payload.extend(std::fs::read(run_dir.join(&part.name)).unwrap());
+        }
+        let decryption = PayloadDecryption::AgeX25519 {
+            identity: new_identity.to_string().expose_secret().to_string(),
+        };
+        let mut plain = Vec::new();
+        decryption
+            .wrap_reader(Box::new(std::io::Cursor::new(payload)))
+            .unwrap()
+            .read_to_end(&mut plain)
+            .unwrap();
+        assert_eq!(plain, data);
+
+        // The old identity no longer decrypts the snapshot.
+        let mut payload = Vec::new();
+        for part in &stored.artifacts {
+            payload.extend(std::fs::read(run_dir.join(&part.name)).unwrap());
+        }
+        let old = PayloadDecryption::AgeX25519 {
+            identity: old_identity.to_string().expose_secret().to_string(),
+        };
+        assert!(
+            old.wrap_reader(Box::new(std::io::Cursor::new(payload)))
+                .is_err()
+        );
+    }
+}
@@ -31,6 +31,7 @@ pub(super) async fn restore_operation(
     destination: &RestoreDestination,
     conflict: ConflictPolicy,
     selection: Option<RestoreSelection>,
+    passphrase: Option<&str>,
     cancel_token: &CancellationToken,
 ) -> Result<(), anyhow::Error> {
     super::check_operation_canceled(op_id, cancel_token)?;
@@ -84,7 +85,8 @@ pub(super) async fn restore_operation(
     )
     .await?;
 
-    let decryption = super::util::resolve_payload_decryption(db, secrets, &manifest).await?;
+    let decryption =
+        super::util::resolve_payload_decryption(db, secrets, &manifest, passphrase).await?;
     super::check_operation_canceled(op_id, cancel_token)?;
 
     enum ResolvedDestination {
@@ -191,14 +193,25 @@ pub(super) async fn restore_operation(
                 match resolved_destination {
                     ResolvedDestination::LocalFs { directory } => {
                         let mut sink = LocalFsSink::new(directory.clone(), conflict);
-                        let mut engine = RestoreEngine::new_with_cancel(
+                        let restored = RestoreEngine::new_with_cancel(
                             &mut sink,
                             decryption,
                             selection.as_ref(),
                             Some(&on_progress),
                             Some(&cancel_check),
-                        )?;
-                        engine.restore(payload)?;
+                        )
+                        .and_then(|mut engine| engine.restore(payload));
+                        if let Err(error) = &restored
+                            && error.downcast_ref::<super::OperationCanceled>().is_some()
+                        {
+                            let removed = sink.remove_created();
+                            info!(
+                                op_id = %op_id_for_cancel,
+                                removed,
+                                "removed partial restore output after cancel"
+                            );
+                        }
+                        restored?;
                         Ok::<_, anyhow::Error>(serde_json::json!({
                             "destination": { "type": "local_fs", "directory": directory.to_string_lossy().to_string() },
                             "conflict_policy": conflict.as_str(),
@@ -11,6 +11,7 @@ pub(in crate::restore::operations) async fn resolve_payload_decryption(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
     manifest: &ManifestV1,
+    passphrase: Option<&str>,
 ) -> Result<PayloadDecryption, anyhow::Error> {
     match manifest.pipeline.encryption.as_str() {
         "none" => Ok(PayloadDecryption::None),
@@ -23,9 +24,39 @@ pub(in crate::restore::operations) async fn resolve_payload_decryption(
                 .filter(|v| !v.is_empty())
                 .ok_or_else(|| anyhow::anyhow!("missing manifest.pipeline.encryption_key"))?;
 
-            let identity = crate::backup_encryption::get_age_identity(db, secrets, key_name)
-                .await?
-                .ok_or_else(|| anyhow::anyhow!("missing backup age identity: {}", key_name))?;
+            // Snapshots written before a rotation need the retired identity. Older manifests do
+            // not record the recipient, so every known identity of the key is tried.
+            let identities = crate::backup_encryption::list_age_identities(
+                db,
+                secrets,
+                key_name,
+                manifest.pipeline.encryption_recipient.as_deref(),
+            )
+            .await?;
+            if !identities.is_empty() {
+                return Ok(PayloadDecryption::AgeX25519 {
+                    identity: identities.join("\n"),
+                });
+            }
+
+            let passphrase_key =
+                crate::backup_encryption::get_age_passphrase_recipient(db, secrets, key_name)
+                    .await?
+                    .is_some();
+            if !passphrase_key {
+                anyhow::bail!("missing backup age identity: {}", key_name);
+            }
+            let passphrase = passphrase.filter(|v| !v.is_empty()).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "backup key {} is passphrase-protected; a passphrase is required",
+                    key_name
+                )
+            })?;
+            let identity = crate::backup_encryption::unlock_age_passphrase_key(
+                db, secrets, key_name, passphrase,
+            )
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("missing backup age identity: {}", key_name))?;
             Ok(PayloadDecryption::AgeX25519 { identity })
         }
         other => anyhow::bail!("unsupported manifest.pipeline.encryption: {}", other),
@@ -47,7 +78,9 @@ mod tests {
     use bastion_storage::{db, secrets::SecretsCrypto};
 
     use super::resolve_payload_decryption;
-    use crate::backup_encryption::ensure_age_identity;
+    use crate::backup_encryption::{
+        create_age_passphrase_key_with_work_factor, ensure_age_identity, rotate_age_identity,
+    };
     use crate::restore::PayloadDecryption;
 
     fn manifest_with_encryption(encryption: &str, encryption_key: Option<&str>) -> ManifestV1 {
@@ -61,8 +94,10 @@ mod tests {
                 format: ArtifactFormatV1::ArchiveV1,
                 tar: "pax".to_string(),
                 compression: "zstd".to_string(),
+                compression_level: None,
                 encryption: encryption.to_string(),
                 encryption_key: encryption_key.map(|v| v.to_string()),
+                encryption_recipient: None,
                 split_bytes: 1,
             },
             artifacts: Vec::new(),
@@ -70,6 +105,7 @@ mod tests {
                 name: "entries.jsonl.zst".to_string(),
                 count: 0,
             },
+            source: None,
         }
     }
 
@@ -80,7 +116,7 @@ mod tests {
         let crypto = SecretsCrypto::load_or_create(tmp.path()).unwrap();
 
         let manifest = manifest_with_encryption("none", None);
-        let dec = resolve_payload_decryption(&pool, &crypto, &manifest)
+        let dec = resolve_payload_decryption(&pool, &crypto, &manifest, None)
             .await
             .unwrap();
         assert!(matches!(dec, PayloadDecryption::None));
@@ -93,7 +129,7 @@ mod tests {
         let crypto = SecretsCrypto::load_or_create(tmp.path()).unwrap();
 
         let manifest = manifest_with_encryption("age", None);
-        let err = resolve_payload_decryption(&pool, &crypto, &manifest)
+        let err = resolve_payload_decryption(&pool, &crypto, &manifest, None)
             .await
             .expect_err("expected error");
         assert!(format!("{err:#}").contains("missing manifest.pipeline.encryption_key"));
@@ -106,7 +142,7 @@ mod tests {
         let crypto = SecretsCrypto::load_or_create(tmp.path()).unwrap();
 
         let manifest = manifest_with_encryption("age", Some("primary"));
-        let err = resolve_payload_decryption(&pool, &crypto, &manifest)
+        let err = resolve_payload_decryption(&pool, &crypto, &manifest, None)
             .await
             .expect_err("expected error");
         assert!(format!("{err:#}").contains("missing backup age identity"));
@@ -123,7 +159,7 @@ mod tests {
             .unwrap();
 
         let manifest = manifest_with_encryption("age", Some("primary"));
-        let dec = resolve_payload_decryption(&pool, &crypto, &manifest)
+        let dec = resolve_payload_decryption(&pool, &crypto, &manifest, None)
             .await
             .unwrap();
         match dec {
@@ -138,6 +174,80 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn resolve_payload_decryption_picks_identity_by_recorded_recipient() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+        let crypto = SecretsCrypto::load_or_create(tmp.path()).unwrap();
+
+        let old_identity = ensure_age_identity(&pool, &crypto, "primary")
+            .await
+            .unwrap();
+        let rotated = rotate_age_identity(&pool, &crypto, "primary")
+            .await
+            .unwrap();
+        assert_ne!(rotated.recipient, rotated.previous_recipient);
+
+        let mut manifest = manifest_with_encryption("age", Some("primary"));
+        manifest.pipeline.encryption_recipient = Some(rotated.previous_recipient.clone());
+        match resolve_payload_decryption(&pool, &crypto, &manifest, None)
+            .await
+            .unwrap()
+        {
+            PayloadDecryption::AgeX25519 { identity } => {
+                assert_eq!(identity.trim(), old_identity.trim());
+            }
+            _ => panic!("unexpected decryption variant"),
+        }
+
+        // Without a recorded recipient both the current and the retired identity are tried.
+        manifest.pipeline.encryption_recipient = None;
+        match resolve_payload_decryption(&pool, &crypto, &manifest, None)
+            .await
+            .unwrap()
+        {
+            PayloadDecryption::AgeX25519 { identity } => {
+                assert_eq!(identity.lines().count(), 2);
+                assert!(identity.lines().any(|line| line == old_identity.trim()));
+            }
+            _ => panic!("unexpected decryption variant"),
+        }
+    }
+
+    #[tokio::test]
+    async fn resolve_payload_decryption_passphrase_key_requires_passphrase() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+        let crypto = SecretsCrypto::load_or_create(tmp.path()).unwrap();
+
+        let recipient =
+            create_age_passphrase_key_with_work_factor(&pool, &crypto, "offline", "hunter22", 2)
+                .await
+                .unwrap();
+
+        let manifest = manifest_with_encryption("age", Some("offline"));
+        let err = resolve_payload_decryption(&pool, &crypto, &manifest, None)
+            .await
+            .expect_err("expected error");
+        assert!(format!("{err:#}").contains("a passphrase is required"));
+
+        let err = resolve_payload_decryption(&pool, &crypto, &manifest, Some("wrong"))
+            .await
+            .expect_err("expected error");
+        assert!(format!("{err:#}").contains("invalid passphrase"));
+
+        let dec = resolve_payload_decryption(&pool, &crypto, &manifest, Some("hunter22"))
+            .await
+            .unwrap();
+        match dec {
+            PayloadDecryption::AgeX25519 { identity } => {
+                let identity = age::x25519::Identity::from_str(identity.trim()).unwrap();
+                assert_eq!(identity.to_public().to_string(), recipient);
+            }
+            _ => panic!("unexpected decryption variant"),
+        }
+    }
+
     #[tokio::test]
     async fn resolve_payload_decryption_rejects_unknown_encryption() {
         let tmp = TempDir::new().unwrap();
@@ -146,7 +256,7 @@ mod tests {
 
         let manifest = manifest_with_encryption("weird", None);
         assert!(
-            resolve_payload_decryption(&pool, &crypto, &manifest)
+            resolve_payload_decryption(&pool, &crypto, &manifest, None)
                 .await
                 .is_err()
         );
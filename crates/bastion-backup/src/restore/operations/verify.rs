@@ -4,27 +4,45 @@ use sqlx::SqlitePool;
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 
+use bastion_core::backup_format::ENTRIES_INDEX_NAME;
 use bastion_core::progress::{ProgressKindV1, ProgressUnitsV1};
 use bastion_storage::operations_repo;
 use bastion_storage::secrets::SecretsCrypto;
 
 use super::super::engine::RestoreEngine;
-use super::super::sinks::LocalFsSink;
+use super::super::sinks::{HashingSink, LocalFsSink};
 use super::super::sources::{ArtifactSource, DriverSource, RunArtifactSource};
 use super::super::{ConflictPolicy, access, verify};
 use super::progress::{OperationProgressUpdate, spawn_operation_progress_writer};
 
+/// What every verify mode needs to find the run and report on its operation.
+#[derive(Clone, Copy)]
+pub(super) struct VerifyOperationCtx<'a> {
+    pub(super) db: &'a SqlitePool,
+    pub(super) secrets: &'a SecretsCrypto,
+    pub(super) data_dir: &'a Path,
+    pub(super) op_id: &'a str,
+    pub(super) run_id: &'a str,
+    pub(super) cancel_token: &'a CancellationToken,
+}
+
 pub(super) async fn verify_operation(
-    db: &SqlitePool,
-    secrets: &SecretsCrypto,
-    data_dir: &Path,
-    op_id: &str,
-    run_id: &str,
-    cancel_token: &CancellationToken,
+    ctx: VerifyOperationCtx<'_>,
+    stream: bool,
+    passphrase: Option<&str>,
 ) -> Result<(), anyhow::Error> {
+    let VerifyOperationCtx {
+        db,
+        secrets,
+        data_dir,
+        op_id,
+        run_id,
+        cancel_token,
+    } = ctx;
     super::check_operation_canceled(op_id, cancel_token)?;
-    info!(op_id = %op_id, run_id = %run_id, "verify operation started");
-    operations_repo::append_event(db, op_id, "info", "start", "start", None).await?;
+    info!(op_id = %op_id, run_id = %run_id, stream, "verify operation started");
+    let start_fields = stream.then(|| serde_json::json!({ "mode": "stream" }));
+    operations_repo::append_event(db, op_id, "info", "start", "start", start_fields).await?;
     let progress_tx =
         spawn_operation_progress_writer(db.clone(), op_id.to_string(), ProgressKindV1::Verify);
     super::check_operation_canceled(op_id, cancel_token)?;
@@ -56,7 +74,8 @@ pub(super) async fn verify_operation(
     )
     .await?;
 
-    let decryption = super::util::resolve_payload_decryption(db, secrets, &manifest).await?;
+    let decryption =
+        super::util::resolve_payload_decryption(db, secrets, &manifest, passphrase).await?;
     super::check_operation_canceled(op_id, cancel_token)?;
 
     info!(
@@ -69,7 +88,9 @@ pub(super) async fn verify_operation(
 
     operations_repo::append_event(db, op_id, "info", "restore", "restore", None).await?;
     let temp_restore_dir = op_dir.join("restore");
-    tokio::fs::create_dir_all(&temp_restore_dir).await?;
+    if !stream {
+        tokio::fs::create_dir_all(&temp_restore_dir).await?;
+    }
 
     let record_count = manifest.entry_index.count;
     let sqlite_paths = verify::sqlite_paths_for_verify(&run);
@@ -102,6 +123,33 @@ pub(super) async fn verify_operation(
         cancel_check()?;
 
         let payload = source.open_payload_reader(&manifest, &staging_dir)?;
+        if stream {
+            // Same pipeline as a restore, but files are hashed and dropped instead of written.
+            let mut sink = HashingSink::default();
+            let mut engine = RestoreEngine::new_with_cancel(
+                &mut sink,
+                decryption,
+                None,
+                Some(&on_restore_progress),
+                Some(&cancel_check),
+            )?;
+            engine.restore(payload)?;
+
+            cancel_check()?;
+            let verify = verify::verify_streamed_with_cancel_check(
+                &entries_path,
+                &sink.into_files(),
+                record_count,
+                Some(&on_verify_progress),
+                Some(&cancel_check),
+            )?;
+            let sqlite_results = verify::SqliteVerifyResult {
+                ok: true,
+                details: serde_json::json!({ "skipped": true, "reason": "stream" }),
+            };
+            return Ok::<_, anyhow::Error>((verify, sqlite_results));
+        }
+
         let mut sink = LocalFsSink::new(temp_restore_dir.clone(), ConflictPolicy::Overwrite);
         let mut engine = RestoreEngine::new_with_cancel(
             &mut sink,
@@ -157,6 +205,7 @@ pub(super) async fn verify_operation(
 
     let summary = serde_json::json!({
         "ok": verify.ok && sqlite_results.ok,
+        "mode": if stream { "stream" } else { "full" },
         "files_total": verify.files_total,
         "files_ok": verify.files_ok,
         "files_failed": verify.files_failed,
@@ -187,3 +236,236 @@ pub(super) async fn verify_operation(
     );
     Ok(())
 }
+
+/// Quick verify: checks the manifest, the stored size of every part and the hash of a random
+/// `percent` of parts, without restoring any files.
+pub(super) async fn verify_sample_operation(
+    ctx: VerifyOperationCtx<'_>,
+    percent: u8,
+) -> Result<(), anyhow::Error> {
+    let VerifyOperationCtx {
+        db,
+        secrets,
+        data_dir,
+        op_id,
+        run_id,
+        cancel_token,
+    } = ctx;
+    super::check_operation_canceled(op_id, cancel_token)?;
+    info!(op_id = %op_id, run_id = %run_id, percent, "sample verify operation started");
+    operations_repo::append_event(
+        db,
+        op_id,
+        "info",
+        "start",
+        "start",
+        Some(serde_json::json!({ "mode": "sample", "percent": percent })),
+    )
+    .await?;
+    let progress_tx =
+        spawn_operation_progress_writer(db.clone(), op_id.to_string(), ProgressKindV1::Verify);
+
+    let access::ResolvedRunAccess { access, .. } =
+        access::resolve_success_run_access(db, secrets, run_id).await?;
+    super::check_operation_canceled(op_id, cancel_token)?;
+
+    let op_dir = super::util::operation_dir(data_dir, op_id);
+    let staging_dir = op_dir.join("staging");
+    tokio::fs::create_dir_all(&staging_dir).await?;
+
+    let reader = access.reader();
+    let handle = tokio::runtime::Handle::current();
+    let source = RunArtifactSource::Driver(DriverSource::new(handle, reader.clone()));
+    let manifest = source.read_manifest().await?;
+    operations_repo::append_event(
+        db,
+        op_id,
+        "info",
+        "manifest",
+        "manifest",
+        Some(serde_json::json!({
+            "artifacts": manifest.artifacts.len(),
+            "entries_count": manifest.entry_index.count,
+        })),
+    )
+    .await?;
+
+    let mut errors = Vec::<String>::new();
+    if reader
+        .head_size(ENTRIES_INDEX_NAME.to_string())
+        .await?
+        .is_none()
+    {
+        errors.push(format!("missing {ENTRIES_INDEX_NAME}"));
+    }
+
+    let mut sizes_failed = 0u64;
+    for part in &manifest.artifacts {
+        super::check_operation_canceled(op_id, cancel_token)?;
+        let problem = match reader.head_size(part.name.clone()).await? {
+            Some(size) if size == part.size => continue,
+            Some(size) => format!(
+                "part size mismatch for {}: expected {}, got {}",
+                part.name, part.size, size
+            ),
+            None => format!("missing part: {}", part.name),
+        };
+        sizes_failed += 1;
+        if errors.len() < 10 {
+            errors.push(problem);
+        }
+    }
+
+    let sampled = select_sample_parts(
+        manifest.artifacts.len(),
+        percent,
+        uuid::Uuid::new_v4().as_u64_pair().0,
+    );
+    let total = ProgressUnitsV1 {
+        files: sampled.len() as u64,
+        dirs: 0,
+        bytes: sampled.iter().map(|&i| manifest.artifacts[i].size).sum(),
+    };
+    operations_repo::append_event(
+        db,
+        op_id,
+        "info",
+        "sample",
+        "sample",
+        Some(serde_json::json!({
+            "parts_total": manifest.artifacts.len(),
+            "parts_sampled": sampled.len(),
+        })),
+    )
+    .await?;
+
+    let parts_total = manifest.artifacts.len();
+    let parts_sampled = sampled.len() as u64;
+    let op_id_for_cancel = op_id.to_string();
+    let cancel_token_for_blocking = cancel_token.clone();
+    let (hashes_failed, hash_errors) = tokio::task::spawn_blocking(move || {
+        let cancel_check =
+            || super::check_operation_canceled(&op_id_for_cancel, &cancel_token_for_blocking);
+        let mut done = ProgressUnitsV1::default();
+        let mut failed = 0u64;
+        let mut errors = Vec::<String>::new();
+        for index in sampled {
+            cancel_check()?;
+            let part = manifest.artifacts[index].clone();
+            let mut single = manifest.clone();
+            single.artifacts = vec![part.clone()];
+            // The parts reader checks size and hash as the bytes go by.
+            let checked =
+                source
+                    .open_payload_reader(&single, &staging_dir)
+                    .and_then(|mut payload| {
+                        std::io::copy(&mut payload, &mut std::io::sink())?;
+                        Ok(())
+                    });
+            if let Err(error) = checked {
+                failed += 1;
+                if errors.len() < 10 {
+                    errors.push(format!("{}: {error:#}", part.name));
+                }
+            }
+            done.files += 1;
+            done.bytes = done.bytes.saturating_add(part.size);
+            let _ = progress_tx.send(Some(OperationProgressUpdate {
+                stage: "verify",
+                done,
+                total: Some(total),
+            }));
+        }
+        Ok::<_, anyhow::Error>((failed, errors))
+    })
+    .await??;
+    super::check_operation_canceled(op_id, cancel_token)?;
+
+    for error in hash_errors {
+        if errors.len() < 10 {
+            errors.push(error);
+        }
+    }
+    let ok = errors.is_empty() && sizes_failed == 0 && hashes_failed == 0;
+    let summary = serde_json::json!({
+        "ok": ok,
+        "mode": "sample",
+        "percent": percent,
+        "parts_total": parts_total,
+        "parts_sampled": parts_sampled,
+        "sizes_failed": sizes_failed,
+        "hashes_failed": hashes_failed,
+        "sample_errors": errors,
+    });
+    operations_repo::append_event(
+        db,
+        op_id,
+        if ok { "info" } else { "error" },
+        "verify",
+        "verify",
+        Some(summary.clone()),
+    )
+    .await?;
+    operations_repo::complete_operation(
+        db,
+        op_id,
+        if ok {
+            operations_repo::OperationStatus::Success
+        } else {
+            operations_repo::OperationStatus::Failed
+        },
+        Some(summary),
+        None,
+    )
+    .await?;
+
+    let _ = tokio::fs::remove_dir_all(&op_dir).await;
+    info!(op_id = %op_id, run_id = %run_id, ok, "sample verify operation completed");
+    Ok(())
+}
+
+/// Picks `percent` of `count` parts (at least one) in ascending order.
+fn select_sample_parts(count: usize, percent: u8, seed: u64) -> Vec<usize> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let percent = usize::from(percent.clamp(1, 100));
+    let want = count.saturating_mul(percent).div_ceil(100).clamp(1, count);
+
+    // splitmix64 drives a partial Fisher-Yates shuffle.
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    };
+    let mut indices: Vec<usize> = (0..count).collect();
+    for i in 0..want {
+        let j = i + (next() % (count - i) as u64) as usize;
+        indices.swap(i, j);
+    }
+    indices.truncate(want);
+    indices.sort_unstable();
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::select_sample_parts;
+
+    #[test]
+    fn select_sample_parts_picks_requested_share() {
+        assert!(select_sample_parts(0, 10, 1).is_empty());
+        assert_eq!(select_sample_parts(5, 1, 7).len(), 1);
+        assert_eq!(select_sample_parts(20, 10, 7).len(), 2);
+        assert_eq!(select_sample_parts(7, 100, 7), (0..7).collect::<Vec<_>>());
+
+        let picked = select_sample_parts(100, 25, 42);
+        assert_eq!(picked.len(), 25);
+        assert!(picked.windows(2).all(|w| w[0] < w[1]));
+        assert!(picked.iter().all(|&i| i < 100));
+        assert_ne!(picked, select_sample_parts(100, 25, 43));
+    }
+}
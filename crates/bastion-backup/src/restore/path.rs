@@ -24,6 +24,26 @@ pub(super) fn archive_path_for_match(rel: &Path) -> Option<String> {
     Some(parts.join("/"))
 }
 
+/// Bytes available to unprivileged writers on the filesystem holding `dir`.
+///
+/// `dir` may not exist yet; its nearest existing ancestor is checked instead.
+pub fn available_space(dir: &Path) -> Option<u64> {
+    let existing = dir.ancestors().find(|p| p.exists())?;
+    filesystem_available_bytes(existing)
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)] // field widths differ between platforms
+fn filesystem_available_bytes(path: &Path) -> Option<u64> {
+    let stat = nix::sys::statvfs::statvfs(path).ok()?;
+    Some((stat.blocks_available() as u64).saturating_mul(stat.fragment_size() as u64))
+}
+
+#[cfg(not(unix))]
+fn filesystem_available_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::archive_path_for_match;
@@ -57,4 +77,13 @@ mod tests {
     fn archive_path_for_match_rejects_absolute_paths() {
         assert!(archive_path_for_match(Path::new("/etc/passwd")).is_none());
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn available_space_checks_nearest_existing_ancestor() {
+        let tmp = tempfile::tempdir().unwrap();
+        let missing = tmp.path().join("not").join("created");
+        assert!(!missing.exists());
+        assert!(super::available_space(&missing).is_some());
+    }
 }
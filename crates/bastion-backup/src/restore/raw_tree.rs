@@ -15,7 +15,6 @@ use super::selection;
 use super::sinks::{RestoreSink, WebdavSink, remove_existing_path};
 use super::sources::ArtifactSource;
 
-#[allow(dead_code)]
 pub(super) fn restore_raw_tree_to_local_fs(
     source: &dyn ArtifactSource,
     entries_index_path: &Path,
@@ -153,9 +152,19 @@ pub(super) fn restore_raw_tree_to_local_fs_with_cancel_check(
                     apply_fs_metadata_best_effort(&dest_path, &rec, FsEntryKind::File);
                     Some((false, rec.size))
                 } else {
-                    let mut reader =
-                        source.open_raw_tree_file_reader(&archive_path, rec.size, staging_dir)?;
-                    write_file_atomic(&dest_path, &mut reader, rec.size)?;
+                    // Local targets: clone the stored file (reflink on btrfs/XFS/APFS), falling
+                    // back to a streamed copy when the filesystem can't.
+                    let cloned = source
+                        .raw_tree_file_local_path(&archive_path)
+                        .is_some_and(|src| clone_file_atomic(&src, &dest_path, rec.size));
+                    if !cloned {
+                        let mut reader = source.open_raw_tree_file_reader(
+                            &archive_path,
+                            rec.size,
+                            staging_dir,
+                        )?;
+                        write_file_atomic(&dest_path, &mut reader, rec.size)?;
+                    }
                     apply_fs_metadata_best_effort(&dest_path, &rec, FsEntryKind::File);
 
                     if let Some(group) = rec.hardlink_group.as_deref() {
@@ -457,6 +466,45 @@ fn write_file_atomic(
     Ok(())
 }
 
+/// Copies `src` to `dest_path` via a `.partial` file using the platform's fastest file copy.
+///
+/// `std::fs::copy` uses `copy_file_range` on Linux (which reflinks on btrfs/XFS) and
+/// `fclonefileat` on macOS. Returns `false`, leaving nothing behind, if the copy fails or the
+/// size does not match; callers then stream the file instead.
+pub(super) fn clone_file_atomic(src: &Path, dest_path: &Path, expected_size: u64) -> bool {
+    let Some(file_name) = dest_path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    if !std::fs::metadata(src).is_ok_and(|meta| meta.is_file() && meta.len() == expected_size) {
+        return false;
+    }
+
+    let tmp = dest_path.with_file_name(format!("{file_name}.partial"));
+    let _ = std::fs::remove_file(&tmp);
+    let result = std::fs::copy(src, &tmp).and_then(|copied| {
+        if copied != expected_size {
+            return Err(std::io::Error::other(format!(
+                "expected {expected_size} bytes, copied {copied}"
+            )));
+        }
+        let _ = std::fs::remove_file(dest_path);
+        std::fs::rename(&tmp, dest_path)
+    });
+    match result {
+        Ok(()) => true,
+        Err(error) => {
+            tracing::debug!(
+                src = %src.display(),
+                dest = %dest_path.display(),
+                error = %error,
+                "raw-tree file clone failed; falling back to a streamed copy"
+            );
+            let _ = std::fs::remove_file(&tmp);
+            false
+        }
+    }
+}
+
 fn try_hard_link(src: &Path, dst: &Path) -> Result<(), std::io::Error> {
     std::fs::hard_link(src, dst)
 }
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::Read;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -24,11 +24,38 @@ pub(super) trait RestoreSink {
 pub(super) struct LocalFsSink {
     base_dir: PathBuf,
     conflict: ConflictPolicy,
+    /// Paths that did not exist before this restore, in the order they were written.
+    created: Vec<PathBuf>,
 }
 
 impl LocalFsSink {
     pub(super) fn new(base_dir: PathBuf, conflict: ConflictPolicy) -> Self {
-        Self { base_dir, conflict }
+        Self {
+            base_dir,
+            conflict,
+            created: Vec::new(),
+        }
+    }
+
+    /// Removes what this restore created, e.g. after a cancel. Paths that existed beforehand are
+    /// left alone (with conflict=overwrite they keep the restored content); directories are only
+    /// removed when empty. Returns the number of removed paths.
+    pub(super) fn remove_created(&mut self) -> usize {
+        let mut removed = 0;
+        for path in self.created.drain(..).rev() {
+            let Ok(meta) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+            let result = if meta.is_dir() {
+                std::fs::remove_dir(&path)
+            } else {
+                std::fs::remove_file(&path)
+            };
+            if result.is_ok() {
+                removed += 1;
+            }
+        }
+        removed
     }
 }
 
@@ -44,6 +71,7 @@ impl RestoreSink for LocalFsSink {
         rel_path: &Path,
     ) -> Result<(), anyhow::Error> {
         let dest_path = self.base_dir.join(rel_path);
+        let existed = std::fs::symlink_metadata(&dest_path).is_ok();
 
         match self.conflict {
             ConflictPolicy::Overwrite => {
@@ -69,6 +97,53 @@ impl RestoreSink for LocalFsSink {
         if !unpacked {
             anyhow::bail!("invalid tar entry path: {}", rel_path.display());
         }
+        if !existed {
+            self.created.push(dest_path);
+        }
+        Ok(())
+    }
+}
+
+/// Discards restored data, keeping the size and BLAKE3 hash of every regular file by archive path.
+///
+/// Runs the full decrypt, decompress and untar pipeline without scratch space (stream verify).
+#[derive(Default)]
+pub(super) struct HashingSink {
+    files: HashMap<String, (u64, String)>,
+}
+
+impl HashingSink {
+    pub(super) fn into_files(self) -> HashMap<String, (u64, String)> {
+        self.files
+    }
+}
+
+impl RestoreSink for HashingSink {
+    fn prepare(&mut self) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    fn apply_entry<R: Read>(
+        &mut self,
+        entry: &mut tar::Entry<R>,
+        rel_path: &Path,
+    ) -> Result<(), anyhow::Error> {
+        let path = super::path::archive_path_for_match(rel_path)
+            .ok_or_else(|| anyhow::anyhow!("invalid tar entry path: {}", rel_path.display()))?;
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_file() {
+            let mut hasher = blake3::Hasher::new();
+            let size = std::io::copy(entry, &mut hasher)?;
+            self.files
+                .insert(path, (size, hasher.finalize().to_hex().to_string()));
+        } else if entry_type.is_hard_link()
+            && let Some(target) = entry.link_name()?
+            && let Some(target) = super::path::archive_path_for_match(&target)
+            && let Some(file) = self.files.get(&target).cloned()
+        {
+            // A restored hardlink is a full copy of the file it points at.
+            self.files.insert(path, file);
+        }
         Ok(())
     }
 }
@@ -85,7 +160,50 @@ pub(super) fn remove_existing_path(path: &Path) -> Result<(), anyhow::Error> {
 
 #[cfg(test)]
 mod tests {
-    use super::remove_existing_path;
+    use std::path::Path;
+
+    use super::{LocalFsSink, RestoreSink as _, remove_existing_path};
+    use crate::restore::ConflictPolicy;
+
+    #[test]
+    fn local_fs_sink_remove_created_keeps_preexisting_paths() -> Result<(), anyhow::Error> {
+        let tmp = tempfile::TempDir::new()?;
+        let dest = tmp.path().join("dest");
+        std::fs::create_dir_all(&dest)?;
+        std::fs::write(dest.join("keep.txt"), b"old")?;
+
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, data) in [("keep.txt", &b"new"[..]), ("dir/new.txt", &b"hi"[..])] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, path, data)?;
+        }
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Directory);
+        header.set_size(0);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, "empty", std::io::empty())?;
+        let bytes = builder.into_inner()?;
+
+        let mut sink = LocalFsSink::new(dest.clone(), ConflictPolicy::Skip);
+        sink.prepare()?;
+        let mut archive = tar::Archive::new(std::io::Cursor::new(bytes));
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let rel = entry.path()?.into_owned();
+            sink.apply_entry(&mut entry, Path::new(&rel))?;
+        }
+        assert!(dest.join("dir/new.txt").exists());
+
+        assert_eq!(sink.remove_created(), 2);
+        assert_eq!(std::fs::read(dest.join("keep.txt"))?, b"old");
+        assert!(!dest.join("dir/new.txt").exists());
+        assert!(!dest.join("empty").exists());
+        Ok(())
+    }
 
     #[test]
     fn remove_existing_path_removes_file() -> Result<(), anyhow::Error> {
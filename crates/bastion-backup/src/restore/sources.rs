@@ -34,6 +34,13 @@ pub trait ArtifactSource: Send {
         expected_size: u64,
         staging_dir: &Path,
     ) -> Result<Box<dyn Read + Send>, anyhow::Error>;
+
+    /// Local path of a raw-tree data file, when the source can expose one directly.
+    ///
+    /// Local restores use it to clone the file instead of streaming its bytes.
+    fn raw_tree_file_local_path(&self, _archive_path: &str) -> Option<PathBuf> {
+        None
+    }
 }
 
 pub struct DriverSource {
@@ -103,6 +110,14 @@ impl ArtifactSource for RunArtifactSource {
             }
         }
     }
+
+    fn raw_tree_file_local_path(&self, archive_path: &str) -> Option<PathBuf> {
+        match self {
+            Self::Driver(s) => s.raw_tree_file_local_path(archive_path),
+            Self::Local(s) => s.raw_tree_file_local_path(archive_path),
+            Self::Webdav(s) => s.raw_tree_file_local_path(archive_path),
+        }
+    }
 }
 
 impl ArtifactSource for DriverSource {
@@ -167,7 +182,12 @@ impl ArtifactSource for DriverSource {
             )));
         }
 
-        std::fs::create_dir_all(staging_dir)?;
+        // Targets with range reads are streamed straight into the unpack pipeline, so restores
+        // don't need scratch space for whole parts.
+        let streaming = self.reader.supports_range_reads();
+        if !streaming {
+            std::fs::create_dir_all(staging_dir)?;
+        }
         Ok(Box::new(VerifiedPartsReader::new_driver(
             self.handle.clone(),
             self.reader.clone(),
@@ -179,9 +199,15 @@ impl ArtifactSource for DriverSource {
                     expected_size: p.size,
                     expected_hash_alg: p.hash_alg.clone(),
                     expected_hash: p.hash.clone(),
-                    source: PartSource::Driver {
-                        artifact: p.name.clone(),
-                        dest: staging_dir.join(&p.name),
+                    source: if streaming {
+                        PartSource::DriverStream {
+                            artifact: p.name.clone(),
+                        }
+                    } else {
+                        PartSource::Driver {
+                            artifact: p.name.clone(),
+                            dest: staging_dir.join(&p.name),
+                        }
                     },
                 })
                 .collect(),
@@ -303,6 +329,10 @@ impl ArtifactSource for LocalDirSource {
         }
         Ok(Box::new(std::fs::File::open(path)?))
     }
+
+    fn raw_tree_file_local_path(&self, archive_path: &str) -> Option<PathBuf> {
+        Some(raw_tree_data_path(&self.run_dir, archive_path))
+    }
 }
 
 pub struct WebdavSource {
@@ -358,10 +388,8 @@ impl ArtifactSource for WebdavSource {
     fn open_payload_reader(
         &self,
         manifest: &ManifestV1,
-        staging_dir: &Path,
+        _staging_dir: &Path,
     ) -> Result<Box<dyn Read + Send>, anyhow::Error> {
-        std::fs::create_dir_all(staging_dir)?;
-
         Ok(Box::new(VerifiedPartsReader::new_webdav(
             self.handle.clone(),
             self.client.clone(),
@@ -375,10 +403,7 @@ impl ArtifactSource for WebdavSource {
                         expected_size: p.size,
                         expected_hash_alg: p.hash_alg.clone(),
                         expected_hash: p.hash.clone(),
-                        source: PartSource::Webdav {
-                            url,
-                            dest: staging_dir.join(&p.name),
-                        },
+                        source: PartSource::Webdav { url },
                     })
                 })
                 .collect::<Result<Vec<_>, _>>()?,
@@ -463,12 +488,134 @@ fn raw_tree_data_url(run_url: &Url, archive_path: &str) -> Result<Url, anyhow::E
 
 #[cfg(test)]
 mod tests {
-    use super::{raw_tree_data_artifact_path, raw_tree_data_path, raw_tree_data_url};
+    use super::{
+        PartSource, PartSpec, RangedPartReader, RangedPartTarget, VerifiedPartsReader,
+        raw_tree_data_artifact_path, raw_tree_data_path, raw_tree_data_url,
+    };
 
-    use std::path::Path;
+    use std::io::Read as _;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
 
+    use bastion_core::manifest::HashAlgorithm;
+    use bastion_driver_api::{DriverError, DriverFuture, TargetRunReader};
     use url::Url;
 
+    struct MemoryRunReader {
+        data: Vec<u8>,
+        ranges: Mutex<Vec<(u64, u64)>>,
+    }
+
+    impl TargetRunReader for MemoryRunReader {
+        fn target_kind(&self) -> &str {
+            "memory"
+        }
+
+        fn describe_location(&self) -> String {
+            "memory".to_string()
+        }
+
+        fn complete_exists(&self) -> DriverFuture<Result<bool, DriverError>> {
+            Box::pin(async { Ok(true) })
+        }
+
+        fn read_bytes(&self, _artifact_path: String) -> DriverFuture<Result<Vec<u8>, DriverError>> {
+            Box::pin(async { Err(DriverError::unsupported("not staged")) })
+        }
+
+        fn head_size(
+            &self,
+            _artifact_path: String,
+        ) -> DriverFuture<Result<Option<u64>, DriverError>> {
+            let len = self.data.len() as u64;
+            Box::pin(async move { Ok(Some(len)) })
+        }
+
+        fn get_to_file(
+            &self,
+            _artifact_path: String,
+            _dest: PathBuf,
+            _expected_size: Option<u64>,
+            _retries: usize,
+        ) -> DriverFuture<Result<u64, DriverError>> {
+            Box::pin(async { Err(DriverError::unsupported("not staged")) })
+        }
+
+        fn supports_range_reads(&self) -> bool {
+            true
+        }
+
+        fn read_range(
+            &self,
+            _artifact_path: String,
+            offset: u64,
+            len: u64,
+            _retries: usize,
+        ) -> DriverFuture<Result<Vec<u8>, DriverError>> {
+            self.ranges.lock().unwrap().push((offset, len));
+            let start = offset as usize;
+            let end = std::cmp::min(start + len as usize, self.data.len());
+            let bytes = self.data[start..end].to_vec();
+            Box::pin(async move { Ok(bytes) })
+        }
+    }
+
+    #[test]
+    fn streamed_parts_are_read_in_ranges_and_verified() {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let data = b"hello streaming restore".to_vec();
+        let reader = Arc::new(MemoryRunReader {
+            data: data.clone(),
+            ranges: Mutex::new(Vec::new()),
+        });
+
+        let mut ranged = RangedPartReader::new(
+            rt.handle().clone(),
+            RangedPartTarget::Driver {
+                reader: reader.clone(),
+                artifact: "payload.part000001".to_string(),
+            },
+            data.len() as u64,
+        );
+        ranged.chunk_bytes = 8;
+        let mut out = Vec::new();
+        ranged.read_to_end(&mut out).unwrap();
+        assert_eq!(out, data);
+        assert_eq!(
+            *reader.ranges.lock().unwrap(),
+            vec![(0, 8), (8, 8), (16, 7)]
+        );
+
+        let spec = |hash: String| PartSpec {
+            name: "payload.part000001".to_string(),
+            expected_size: data.len() as u64,
+            expected_hash_alg: HashAlgorithm::Blake3,
+            expected_hash: hash,
+            source: PartSource::DriverStream {
+                artifact: "payload.part000001".to_string(),
+            },
+        };
+
+        let mut parts = VerifiedPartsReader::new_driver(
+            rt.handle().clone(),
+            reader.clone(),
+            vec![spec(blake3::hash(&data).to_hex().to_string())],
+        );
+        let mut out = Vec::new();
+        parts.read_to_end(&mut out).unwrap();
+        assert_eq!(out, data);
+
+        let mut parts = VerifiedPartsReader::new_driver(
+            rt.handle().clone(),
+            reader,
+            vec![spec("bogus".to_string())],
+        );
+        let err = parts
+            .read_to_end(&mut Vec::new())
+            .expect_err("hash mismatch");
+        assert!(err.to_string().contains("part hash mismatch"), "{err}");
+    }
+
     #[test]
     fn raw_tree_data_path_appends_segments_and_skips_empty_parts() {
         let run_dir = Path::new("/tmp/run");
@@ -508,11 +655,16 @@ mod tests {
     }
 }
 
+/// Size of each ranged read when streaming parts from a remote target.
+const STREAM_CHUNK_BYTES: u64 = 8 * 1024 * 1024;
+const STREAM_READ_ATTEMPTS: u32 = 3;
+
 #[derive(Debug, Clone)]
 enum PartSource {
     Local { path: PathBuf },
-    Webdav { url: Url, dest: PathBuf },
+    Webdav { url: Url },
     Driver { artifact: String, dest: PathBuf },
+    DriverStream { artifact: String },
 }
 
 #[derive(Debug, Clone)]
@@ -524,10 +676,9 @@ struct PartSpec {
     source: PartSource,
 }
 
-#[derive(Debug)]
 struct ActivePart {
     index: usize,
-    file: std::fs::File,
+    file: Box<dyn Read + Send>,
     hasher: blake3::Hasher,
     read_bytes: u64,
     expected_size: u64,
@@ -601,10 +752,10 @@ impl VerifiedPartsReader {
                         ),
                     ));
                 }
-                (std::fs::File::open(&path)?, None)
+                let file: Box<dyn Read + Send> = Box::new(std::fs::File::open(&path)?);
+                (file, None)
             }
-            PartSource::Webdav { url, dest } => {
-                let expected_size = spec.expected_size;
+            PartSource::Webdav { url } => {
                 let handle = self
                     .handle
                     .as_ref()
@@ -615,17 +766,30 @@ impl VerifiedPartsReader {
                     .as_ref()
                     .ok_or_else(|| io::Error::other("missing webdav client"))?
                     .clone();
-
-                if let Ok(meta) = std::fs::metadata(&dest)
-                    && meta.len() == expected_size
-                {
-                    (std::fs::File::open(&dest)?, Some(dest))
-                } else {
-                    handle
-                        .block_on(client.get_to_file(&url, &dest, Some(expected_size), 3))
-                        .map_err(|e| io::Error::other(e.to_string()))?;
-                    (std::fs::File::open(&dest)?, Some(dest))
-                }
+                let file: Box<dyn Read + Send> = Box::new(RangedPartReader::new(
+                    handle,
+                    RangedPartTarget::Webdav { client, url },
+                    spec.expected_size,
+                ));
+                (file, None)
+            }
+            PartSource::DriverStream { artifact } => {
+                let handle = self
+                    .handle
+                    .as_ref()
+                    .ok_or_else(|| io::Error::other("missing tokio handle"))?
+                    .clone();
+                let reader = self
+                    .driver_reader
+                    .as_ref()
+                    .ok_or_else(|| io::Error::other("missing driver reader"))?
+                    .clone();
+                let file: Box<dyn Read + Send> = Box::new(RangedPartReader::new(
+                    handle,
+                    RangedPartTarget::Driver { reader, artifact },
+                    spec.expected_size,
+                ));
+                (file, None)
             }
             PartSource::Driver { artifact, dest } => {
                 let expected_size = spec.expected_size;
@@ -640,11 +804,7 @@ impl VerifiedPartsReader {
                     .ok_or_else(|| io::Error::other("missing driver reader"))?
                     .clone();
 
-                if let Ok(meta) = std::fs::metadata(&dest)
-                    && meta.len() == expected_size
-                {
-                    (std::fs::File::open(&dest)?, Some(dest))
-                } else {
+                if !std::fs::metadata(&dest).is_ok_and(|meta| meta.len() == expected_size) {
                     handle
                         .block_on(reader.get_to_file(
                             artifact,
@@ -653,8 +813,9 @@ impl VerifiedPartsReader {
                             3,
                         ))
                         .map_err(|e| io::Error::other(e.to_string()))?;
-                    (std::fs::File::open(&dest)?, Some(dest))
                 }
+                let file: Box<dyn Read + Send> = Box::new(std::fs::File::open(&dest)?);
+                (file, Some(dest))
             }
         };
 
@@ -762,3 +923,90 @@ impl Read for VerifiedPartsReader {
         }
     }
 }
+
+enum RangedPartTarget {
+    Webdav {
+        client: WebdavClient,
+        url: Url,
+    },
+    Driver {
+        reader: Arc<dyn TargetRunReader>,
+        artifact: String,
+    },
+}
+
+/// Reads a remote part in fixed-size ranges, keeping at most one chunk in memory.
+struct RangedPartReader {
+    handle: Handle,
+    target: RangedPartTarget,
+    size: u64,
+    offset: u64,
+    chunk_bytes: u64,
+    chunk: Vec<u8>,
+    chunk_pos: usize,
+}
+
+impl RangedPartReader {
+    fn new(handle: Handle, target: RangedPartTarget, size: u64) -> Self {
+        Self {
+            handle,
+            target,
+            size,
+            offset: 0,
+            chunk_bytes: STREAM_CHUNK_BYTES,
+            chunk: Vec::new(),
+            chunk_pos: 0,
+        }
+    }
+
+    fn fetch_next_chunk(&mut self) -> io::Result<()> {
+        let len = std::cmp::min(self.chunk_bytes, self.size - self.offset);
+        let offset = self.offset;
+        let bytes = match &self.target {
+            RangedPartTarget::Webdav { client, url } => self
+                .handle
+                .block_on(client.get_range(url, offset, len, STREAM_READ_ATTEMPTS))
+                .map_err(|e| io::Error::other(format!("{e:#}")))?,
+            RangedPartTarget::Driver { reader, artifact } => self
+                .handle
+                .block_on(reader.read_range(
+                    artifact.clone(),
+                    offset,
+                    len,
+                    STREAM_READ_ATTEMPTS as usize,
+                ))
+                .map_err(|e| io::Error::other(e.to_string()))?,
+        };
+        if bytes.len() as u64 != len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!(
+                    "ranged read returned {} bytes, expected {len} at offset {offset}",
+                    bytes.len()
+                ),
+            ));
+        }
+        self.offset += len;
+        self.chunk = bytes;
+        self.chunk_pos = 0;
+        Ok(())
+    }
+}
+
+impl Read for RangedPartReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.chunk_pos >= self.chunk.len() {
+            if self.offset >= self.size {
+                return Ok(0);
+            }
+            self.fetch_next_chunk()?;
+        }
+        let n = std::cmp::min(buf.len(), self.chunk.len() - self.chunk_pos);
+        buf[..n].copy_from_slice(&self.chunk[self.chunk_pos..self.chunk_pos + n]);
+        self.chunk_pos += n;
+        Ok(n)
+    }
+}
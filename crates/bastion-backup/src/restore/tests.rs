@@ -6,13 +6,19 @@ use tempfile::tempdir;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
-use super::entries_index::{ListChildrenFromEntriesIndexOptions, list_children_from_entries_index};
+use super::entries_index::{
+    ListChildrenFromEntriesIndexOptions, RestoreEstimate, estimate_from_entries_index,
+    find_entry_in_entries_index, list_children_from_entries_index,
+};
+use super::selection::normalize_restore_selection;
 use super::unpack::{
     PayloadDecryption, restore_from_parts, restore_from_parts_with_cancel_check, safe_join,
 };
 use super::{ConflictPolicy, RestoreSelection};
 use crate::backup::{BuildPipelineOptions, PayloadEncryption};
-use bastion_core::job_spec::{FilesystemSource, FsErrorPolicy, FsHardlinkPolicy, FsSymlinkPolicy};
+use bastion_core::job_spec::{
+    CompressionV1, FilesystemSource, FsErrorPolicy, FsHardlinkPolicy, FsSymlinkPolicy,
+};
 use bastion_core::manifest::ArtifactFormatV1;
 
 #[test]
@@ -183,6 +189,12 @@ fn restore_raw_tree_to_local_fs_copies_files() {
         root: src_root.to_string_lossy().to_string(),
         include: Vec::new(),
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -191,6 +203,7 @@ fn restore_raw_tree_to_local_fs_copies_files() {
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
 
     let build = crate::backup::filesystem::build_filesystem_run(
@@ -201,13 +214,16 @@ fn restore_raw_tree_to_local_fs_copies_files() {
         &source,
         BuildPipelineOptions {
             artifact_format: ArtifactFormatV1::RawTreeV1,
+            compression: CompressionV1::default(),
             encryption: &PayloadEncryption::None,
             part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
         },
         None,
         None,
         None,
         None,
+        None,
     )
     .unwrap();
     assert_eq!(build.issues.errors_total, 0);
@@ -245,6 +261,23 @@ fn restore_raw_tree_to_local_fs_copies_files() {
     assert_eq!(out, b"hi");
 }
 
+#[test]
+fn clone_file_atomic_replaces_destination_and_rejects_size_mismatch() {
+    let tmp = tempdir().unwrap();
+    let src = tmp.path().join("stored.bin");
+    std::fs::write(&src, b"stored-bytes").unwrap();
+    let dest = tmp.path().join("out.bin");
+    std::fs::write(&dest, b"old").unwrap();
+
+    assert!(super::raw_tree::clone_file_atomic(&src, &dest, 12));
+    assert_eq!(std::fs::read(&dest).unwrap(), b"stored-bytes");
+
+    let other = tmp.path().join("other.bin");
+    assert!(!super::raw_tree::clone_file_atomic(&src, &other, 5));
+    assert!(!other.exists());
+    assert!(!tmp.path().join("other.bin.partial").exists());
+}
+
 #[test]
 fn restore_from_parts_conflict_skip_keeps_existing_files() {
     let tmp = tempdir().unwrap();
@@ -340,6 +373,12 @@ fn restore_from_parts_extracts_tar_zstd_age() {
         root: src_root.to_string_lossy().to_string(),
         include: Vec::new(),
         exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
         symlink_policy: FsSymlinkPolicy::Keep,
         hardlink_policy: FsHardlinkPolicy::Copy,
         error_policy: FsErrorPolicy::FailFast,
@@ -348,6 +387,7 @@ fn restore_from_parts_extracts_tar_zstd_age() {
         consistency_policy: Default::default(),
         consistency_fail_threshold: None,
         upload_on_consistency_failure: None,
+        windows_system_state: false,
     };
 
     let build = crate::backup::filesystem::build_filesystem_run(
@@ -358,13 +398,16 @@ fn restore_from_parts_extracts_tar_zstd_age() {
         &source,
         BuildPipelineOptions {
             artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1::default(),
             encryption: &encryption,
             part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
         },
         None,
         None,
         None,
         None,
+        None,
     )
     .unwrap();
     assert_eq!(build.issues.errors_total, 0);
@@ -398,6 +441,280 @@ fn restore_from_parts_extracts_tar_zstd_age() {
     assert_eq!(out, b"hi");
 }
 
+#[test]
+fn restore_run_dir_to_local_fs_decrypts_with_identity_only() {
+    use age::secrecy::ExposeSecret as _;
+
+    let tmp = tempdir().unwrap();
+    let data_dir = tmp.path().join("data");
+    std::fs::create_dir_all(&data_dir).unwrap();
+
+    let src_root = tmp.path().join("src");
+    std::fs::create_dir_all(&src_root).unwrap();
+    std::fs::write(src_root.join("hello.txt"), b"hi").unwrap();
+
+    let identity = age::x25519::Identity::generate();
+    let encryption = PayloadEncryption::AgeX25519 {
+        recipient: identity.to_public().to_string(),
+        key_name: "k".to_string(),
+    };
+
+    let job_id = Uuid::new_v4().to_string();
+    let run_id = Uuid::new_v4().to_string();
+    let source = FilesystemSource {
+        pre_scan: true,
+        paths: Vec::new(),
+        root: src_root.to_string_lossy().to_string(),
+        include: Vec::new(),
+        exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
+        symlink_policy: FsSymlinkPolicy::Keep,
+        hardlink_policy: FsHardlinkPolicy::Copy,
+        error_policy: FsErrorPolicy::FailFast,
+        snapshot_mode: Default::default(),
+        snapshot_provider: None,
+        consistency_policy: Default::default(),
+        consistency_fail_threshold: None,
+        upload_on_consistency_failure: None,
+        windows_system_state: false,
+    };
+
+    let build = crate::backup::filesystem::build_filesystem_run(
+        &data_dir,
+        &job_id,
+        &run_id,
+        OffsetDateTime::now_utc(),
+        &source,
+        BuildPipelineOptions {
+            artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1::default(),
+            encryption: &encryption,
+            part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
+        },
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let target_base = tmp.path().join("target");
+    std::fs::create_dir_all(&target_base).unwrap();
+    let run_dir = bastion_targets::local_dir::store_run(
+        &target_base,
+        &job_id,
+        &run_id,
+        &build.artifacts,
+        None,
+    )
+    .unwrap();
+
+    let dest = tmp.path().join("out_offline");
+    let error = super::restore_run_dir_to_local_fs(
+        &run_dir,
+        dest.clone(),
+        ConflictPolicy::Fail,
+        PayloadDecryption::None,
+        None,
+        None,
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("age identity is required"));
+
+    let manifest = super::restore_run_dir_to_local_fs(
+        &run_dir,
+        dest.clone(),
+        ConflictPolicy::Fail,
+        PayloadDecryption::AgeX25519 {
+            identity: identity.to_string().expose_secret().to_string(),
+        },
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(manifest.pipeline.encryption_key.as_deref(), Some("k"));
+    assert_eq!(std::fs::read(dest.join("hello.txt")).unwrap(), b"hi");
+}
+
+#[test]
+fn restore_from_parts_handles_configured_compression() {
+    use bastion_core::job_spec::CompressionAlgorithmV1;
+
+    let tmp = tempdir().unwrap();
+    let data_dir = tmp.path().join("data");
+    let src_root = tmp.path().join("src");
+    std::fs::create_dir_all(&src_root).unwrap();
+    std::fs::write(src_root.join("hello.txt"), b"hi").unwrap();
+
+    let source = FilesystemSource {
+        pre_scan: false,
+        paths: Vec::new(),
+        root: src_root.to_string_lossy().to_string(),
+        include: Vec::new(),
+        exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
+        symlink_policy: FsSymlinkPolicy::Keep,
+        hardlink_policy: FsHardlinkPolicy::Copy,
+        error_policy: FsErrorPolicy::FailFast,
+        snapshot_mode: Default::default(),
+        snapshot_provider: None,
+        consistency_policy: Default::default(),
+        consistency_fail_threshold: None,
+        upload_on_consistency_failure: None,
+        windows_system_state: false,
+    };
+
+    for (algorithm, level) in [
+        (CompressionAlgorithmV1::Lz4, None),
+        (CompressionAlgorithmV1::Xz, Some(1)),
+        (CompressionAlgorithmV1::None, None),
+    ] {
+        let job_id = Uuid::new_v4().to_string();
+        let run_id = Uuid::new_v4().to_string();
+        let build = crate::backup::filesystem::build_filesystem_run(
+            &data_dir,
+            &job_id,
+            &run_id,
+            OffsetDateTime::now_utc(),
+            &source,
+            BuildPipelineOptions {
+                artifact_format: ArtifactFormatV1::ArchiveV1,
+                compression: CompressionV1 {
+                    algorithm,
+                    level,
+                    ..Default::default()
+                },
+                encryption: &PayloadEncryption::None,
+                part_size_bytes: 4 * 1024 * 1024,
+                staging_min_free_bytes: 0,
+            },
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let manifest_bytes = std::fs::read(&build.artifacts.manifest_path).unwrap();
+        let manifest =
+            serde_json::from_slice::<bastion_core::manifest::ManifestV1>(&manifest_bytes).unwrap();
+        assert_eq!(manifest.pipeline.compression, algorithm.as_str());
+        assert_eq!(manifest.pipeline.compression_level, level);
+
+        let part_paths = build
+            .artifacts
+            .parts
+            .iter()
+            .map(|p| p.path.clone())
+            .collect::<Vec<_>>();
+        let dest = tmp.path().join(format!("out_{}", algorithm.as_str()));
+        restore_from_parts(
+            &part_paths,
+            &dest,
+            ConflictPolicy::Overwrite,
+            PayloadDecryption::None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(std::fs::read(dest.join("hello.txt")).unwrap(), b"hi");
+    }
+}
+
+#[test]
+fn restore_from_parts_handles_adaptive_zstd() {
+    let tmp = tempdir().unwrap();
+    let data_dir = tmp.path().join("data");
+    let src_root = tmp.path().join("src");
+    std::fs::create_dir_all(&src_root).unwrap();
+    std::fs::write(src_root.join("a.txt"), b"alpha").unwrap();
+    std::fs::write(src_root.join("photo.jpg"), b"not really a jpeg").unwrap();
+    std::fs::write(src_root.join("z.txt"), b"omega").unwrap();
+
+    let source = FilesystemSource {
+        pre_scan: false,
+        paths: Vec::new(),
+        root: src_root.to_string_lossy().to_string(),
+        include: Vec::new(),
+        exclude: Vec::new(),
+        exclude_presets: Vec::new(),
+        use_ignore_files: false,
+        max_file_size_bytes: None,
+        skip_older_than_secs: None,
+        skip_newer_than_secs: None,
+        anomaly_detection: None,
+        symlink_policy: FsSymlinkPolicy::Keep,
+        hardlink_policy: FsHardlinkPolicy::Copy,
+        error_policy: FsErrorPolicy::FailFast,
+        snapshot_mode: Default::default(),
+        snapshot_provider: None,
+        consistency_policy: Default::default(),
+        consistency_fail_threshold: None,
+        upload_on_consistency_failure: None,
+        windows_system_state: false,
+    };
+
+    let job_id = Uuid::new_v4().to_string();
+    let run_id = Uuid::new_v4().to_string();
+    let build = crate::backup::filesystem::build_filesystem_run(
+        &data_dir,
+        &job_id,
+        &run_id,
+        OffsetDateTime::now_utc(),
+        &source,
+        BuildPipelineOptions {
+            artifact_format: ArtifactFormatV1::ArchiveV1,
+            compression: CompressionV1 {
+                adaptive: true,
+                ..Default::default()
+            },
+            encryption: &PayloadEncryption::None,
+            part_size_bytes: 4 * 1024 * 1024,
+            staging_min_free_bytes: 0,
+        },
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let part_paths = build
+        .artifacts
+        .parts
+        .iter()
+        .map(|p| p.path.clone())
+        .collect::<Vec<_>>();
+    let dest = tmp.path().join("out");
+    restore_from_parts(
+        &part_paths,
+        &dest,
+        ConflictPolicy::Overwrite,
+        PayloadDecryption::None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(dest.join("a.txt")).unwrap(), b"alpha");
+    assert_eq!(
+        std::fs::read(dest.join("photo.jpg")).unwrap(),
+        b"not really a jpeg"
+    );
+    assert_eq!(std::fs::read(dest.join("z.txt")).unwrap(), b"omega");
+}
+
 #[test]
 fn entries_children_lists_unique_children() {
     #[derive(serde::Serialize)]
@@ -632,3 +949,154 @@ fn entries_children_lists_unique_children() {
     .unwrap();
     assert_eq!(root_file_first.entries[0].path, ".env");
 }
+
+#[test]
+fn entries_find_returns_exact_path_metadata() {
+    #[derive(serde::Serialize)]
+    struct Rec<'a> {
+        path: &'a str,
+        kind: &'a str,
+        size: u64,
+        hash_alg: Option<&'a str>,
+        hash: Option<&'a str>,
+        mtime: Option<u64>,
+    }
+
+    let tmp = tempdir().unwrap();
+    let entries_path = tmp.path().join("entries.jsonl.zst");
+
+    let file = File::create(&entries_path).unwrap();
+    let mut enc = zstd::Encoder::new(file, 3).unwrap();
+    for rec in [
+        Rec {
+            path: "etc",
+            kind: "dir",
+            size: 0,
+            hash_alg: None,
+            hash: None,
+            mtime: Some(10),
+        },
+        Rec {
+            path: "etc/hosts",
+            kind: "file",
+            size: 2,
+            hash_alg: Some("blake3"),
+            hash: Some("x"),
+            mtime: Some(20),
+        },
+        Rec {
+            path: "etc/hosts.bak",
+            kind: "file",
+            size: 3,
+            hash_alg: Some("blake3"),
+            hash: Some("y"),
+            mtime: Some(30),
+        },
+    ] {
+        let line = serde_json::to_vec(&rec).unwrap();
+        enc.write_all(&line).unwrap();
+        enc.write_all(b"\n").unwrap();
+    }
+    enc.finish().unwrap();
+
+    let hosts = find_entry_in_entries_index(&entries_path, "etc/hosts")
+        .unwrap()
+        .unwrap();
+    assert_eq!(hosts.kind, "file");
+    assert_eq!(hosts.size, 2);
+    assert_eq!(hosts.mtime, Some(20));
+    assert_eq!(hosts.hash.as_deref(), Some("x"));
+
+    assert!(
+        find_entry_in_entries_index(&entries_path, "etc/missing")
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn entries_estimate_sums_selection_and_counts_hardlinks_once() {
+    #[derive(serde::Serialize)]
+    struct Rec<'a> {
+        path: &'a str,
+        kind: &'a str,
+        size: u64,
+        hardlink_group: Option<&'a str>,
+    }
+
+    let tmp = tempdir().unwrap();
+    let entries_path = tmp.path().join("entries.jsonl.zst");
+
+    let file = File::create(&entries_path).unwrap();
+    let mut enc = zstd::Encoder::new(file, 3).unwrap();
+    for rec in [
+        Rec {
+            path: "etc",
+            kind: "dir",
+            size: 0,
+            hardlink_group: None,
+        },
+        Rec {
+            path: "etc/hosts",
+            kind: "file",
+            size: 2,
+            hardlink_group: None,
+        },
+        Rec {
+            path: "etc/hosts.link",
+            kind: "file",
+            size: 2,
+            hardlink_group: None,
+        },
+        Rec {
+            path: "var/a",
+            kind: "file",
+            size: 100,
+            hardlink_group: Some("g1"),
+        },
+        Rec {
+            path: "var/b",
+            kind: "file",
+            size: 100,
+            hardlink_group: Some("g1"),
+        },
+        Rec {
+            path: "var/current",
+            kind: "symlink",
+            size: 0,
+            hardlink_group: None,
+        },
+    ] {
+        let line = serde_json::to_vec(&rec).unwrap();
+        enc.write_all(&line).unwrap();
+        enc.write_all(b"\n").unwrap();
+    }
+    enc.finish().unwrap();
+
+    let all = estimate_from_entries_index(&entries_path, None).unwrap();
+    assert_eq!(
+        all,
+        RestoreEstimate {
+            files: 4,
+            dirs: 1,
+            symlinks: 1,
+            bytes: 104,
+        }
+    );
+
+    let selection = normalize_restore_selection(&RestoreSelection {
+        files: vec!["etc/hosts".to_string()],
+        dirs: vec!["var".to_string()],
+    })
+    .unwrap();
+    let selected = estimate_from_entries_index(&entries_path, Some(&selection)).unwrap();
+    assert_eq!(
+        selected,
+        RestoreEstimate {
+            files: 3,
+            dirs: 0,
+            symlinks: 1,
+            bytes: 102,
+        }
+    );
+}
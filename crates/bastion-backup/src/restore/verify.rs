@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
@@ -32,6 +33,81 @@ pub(super) fn verify_restored_with_cancel_check(
     expected_count: u64,
     on_progress: Option<&dyn Fn(ProgressUnitsV1)>,
     on_cancel_check: Option<&dyn Fn() -> Result<(), anyhow::Error>>,
+) -> Result<VerifyResult, anyhow::Error> {
+    verify_entries(
+        entries_path,
+        &mut RestoredDir(restore_dir),
+        expected_count,
+        on_progress,
+        on_cancel_check,
+    )
+}
+
+/// Checks files hashed by a [`super::sinks::HashingSink`] (archive path -> size, BLAKE3 hash)
+/// against the entries index.
+pub(super) fn verify_streamed_with_cancel_check(
+    entries_path: &Path,
+    files: &HashMap<String, (u64, String)>,
+    expected_count: u64,
+    on_progress: Option<&dyn Fn(ProgressUnitsV1)>,
+    on_cancel_check: Option<&dyn Fn() -> Result<(), anyhow::Error>>,
+) -> Result<VerifyResult, anyhow::Error> {
+    verify_entries(
+        entries_path,
+        &mut StreamedFiles(files),
+        expected_count,
+        on_progress,
+        on_cancel_check,
+    )
+}
+
+/// Where the restored files of a verify can be looked up.
+trait RestoredFiles {
+    fn size(&mut self, path: &str) -> Result<Option<u64>, anyhow::Error>;
+    fn blake3(&mut self, path: &str) -> Result<String, anyhow::Error>;
+}
+
+struct RestoredDir<'a>(&'a Path);
+
+impl RestoredDir<'_> {
+    fn join(&self, path: &str) -> Result<PathBuf, anyhow::Error> {
+        let rel = PathBuf::from(path.replace('\\', "/"));
+        unpack::safe_join(self.0, &rel)
+            .ok_or_else(|| anyhow::anyhow!("invalid restored path: {}", path))
+    }
+}
+
+impl RestoredFiles for RestoredDir<'_> {
+    fn size(&mut self, path: &str) -> Result<Option<u64>, anyhow::Error> {
+        Ok(std::fs::metadata(self.join(path)?).ok().map(|m| m.len()))
+    }
+
+    fn blake3(&mut self, path: &str) -> Result<String, anyhow::Error> {
+        parts::hash_file_blake3(&self.join(path)?)
+    }
+}
+
+struct StreamedFiles<'a>(&'a HashMap<String, (u64, String)>);
+
+impl RestoredFiles for StreamedFiles<'_> {
+    fn size(&mut self, path: &str) -> Result<Option<u64>, anyhow::Error> {
+        Ok(self.0.get(path).map(|(size, _)| *size))
+    }
+
+    fn blake3(&mut self, path: &str) -> Result<String, anyhow::Error> {
+        self.0
+            .get(path)
+            .map(|(_, hash)| hash.clone())
+            .ok_or_else(|| anyhow::anyhow!("missing file: {}", path))
+    }
+}
+
+fn verify_entries(
+    entries_path: &Path,
+    restored: &mut dyn RestoredFiles,
+    expected_count: u64,
+    on_progress: Option<&dyn Fn(ProgressUnitsV1)>,
+    on_cancel_check: Option<&dyn Fn() -> Result<(), anyhow::Error>>,
 ) -> Result<VerifyResult, anyhow::Error> {
     const VERIFY_PROGRESS_MIN_INTERVAL: Duration = Duration::from_secs(1);
 
@@ -70,20 +146,14 @@ pub(super) fn verify_restored_with_cancel_check(
             progress_last_emit = Instant::now();
             cb(progress_done);
         }
-        let rel = PathBuf::from(rec.path.replace('\\', "/"));
-        let path = unpack::safe_join(restore_dir, &rel)
-            .ok_or_else(|| anyhow::anyhow!("invalid restored path: {}", rec.path))?;
-        let meta = match std::fs::metadata(&path) {
-            Ok(m) => m,
-            Err(_) => {
-                files_failed += 1;
-                if errors.len() < 10 {
-                    errors.push(format!("missing file: {}", rec.path));
-                }
-                continue;
+        let Some(size) = restored.size(&rec.path)? else {
+            files_failed += 1;
+            if errors.len() < 10 {
+                errors.push(format!("missing file: {}", rec.path));
             }
+            continue;
         };
-        if meta.len() != rec.size {
+        if size != rec.size {
             files_failed += 1;
             if errors.len() < 10 {
                 errors.push(format!("size mismatch: {}", rec.path));
@@ -96,7 +166,7 @@ pub(super) fn verify_restored_with_cancel_check(
                 if let Some(check) = on_cancel_check {
                     check()?;
                 }
-                let computed = parts::hash_file_blake3(&path)?;
+                let computed = restored.blake3(&rec.path)?;
                 if computed != expected_hash {
                     files_failed += 1;
                     if errors.len() < 10 {
@@ -143,18 +213,37 @@ pub(super) fn sqlite_paths_for_verify(run: &runs_repo::Run) -> Vec<String> {
         return Vec::new();
     };
 
-    if let Some(name) = summary
-        .get("sqlite")
-        .and_then(|v| v.get("snapshot_name"))
-        .and_then(|n| n.as_str())
-    {
-        return vec![name.to_string()];
+    if let Some(sqlite) = summary.get("sqlite") {
+        let names: Vec<String> = sqlite
+            .get("databases")
+            .and_then(|v| v.as_array())
+            .map(|dbs| {
+                dbs.iter()
+                    .filter_map(|db| db.get("snapshot_name").and_then(|n| n.as_str()))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !names.is_empty() {
+            return names;
+        }
+        if let Some(name) = sqlite.get("snapshot_name").and_then(|n| n.as_str()) {
+            return vec![name.to_string()];
+        }
     }
 
     if summary.get("vaultwarden").is_some() {
         return vec!["db.sqlite3".to_string()];
     }
 
+    if let Some(db_path) = summary
+        .get("sqlite_app")
+        .and_then(|v| v.get("db_path"))
+        .and_then(|n| n.as_str())
+    {
+        return vec![db_path.to_string()];
+    }
+
     Vec::new()
 }
 
@@ -264,6 +353,7 @@ mod tests {
     use super::{
         sqlite_paths_for_verify, verify_restored, verify_restored_with_cancel_check,
         verify_sqlite_files, verify_sqlite_files_with_cancel_check,
+        verify_streamed_with_cancel_check,
     };
 
     fn run_with_summary(summary: Option<serde_json::Value>) -> Run {
@@ -307,6 +397,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sqlite_paths_for_verify_uses_sqlite_app_db_path() {
+        let run = run_with_summary(Some(serde_json::json!({
+            "sqlite_app": { "data_dir": "/var/lib/gitea", "db_path": "data/gitea.db" }
+        })));
+        assert_eq!(
+            sqlite_paths_for_verify(&run),
+            vec!["data/gitea.db".to_string()]
+        );
+    }
+
     #[test]
     fn sqlite_paths_for_verify_prefers_sqlite_over_vaultwarden() {
         let run = run_with_summary(Some(serde_json::json!({
@@ -316,6 +417,23 @@ mod tests {
         assert_eq!(sqlite_paths_for_verify(&run), vec!["my.db".to_string()]);
     }
 
+    #[test]
+    fn sqlite_paths_for_verify_lists_every_database_snapshot() {
+        let run = run_with_summary(Some(serde_json::json!({
+            "sqlite": {
+                "snapshot_name": "app.db",
+                "databases": [
+                    { "path": "/a/app.db", "snapshot_name": "app.db" },
+                    { "path": "/b/app.db", "snapshot_name": "app-2.db" }
+                ]
+            }
+        })));
+        assert_eq!(
+            sqlite_paths_for_verify(&run),
+            vec!["app.db".to_string(), "app-2.db".to_string()]
+        );
+    }
+
     #[test]
     fn verify_sqlite_files_skips_when_no_paths() {
         let tmp = TempDir::new().unwrap();
@@ -396,6 +514,84 @@ mod tests {
         assert!(res.sample_errors.is_empty());
     }
 
+    #[test]
+    fn verify_streamed_checks_hashed_tar_entries() {
+        use super::super::sinks::{HashingSink, RestoreSink as _};
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "dir/file.txt", &b"hello"[..])
+            .unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Link);
+        header.set_size(0);
+        header.set_cksum();
+        builder
+            .append_link(&mut header, "dir/link.txt", "dir/file.txt")
+            .unwrap();
+        let bytes = builder.into_inner().unwrap();
+
+        let mut sink = HashingSink::default();
+        let mut archive = tar::Archive::new(std::io::Cursor::new(bytes));
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let rel = entry.path().unwrap().into_owned();
+            sink.apply_entry(&mut entry, &rel).unwrap();
+        }
+        let files = sink.into_files();
+
+        let hash = blake3::hash(b"hello").to_hex().to_string();
+        let entry = |path: &str, hash: &str| {
+            serde_json::json!({
+                "path": path,
+                "kind": "file",
+                "size": 5,
+                "hash_alg": "blake3",
+                "hash": hash,
+            })
+        };
+        let tmp = TempDir::new().unwrap();
+        let entries_path = tmp.path().join("entries_index.jsonl.zst");
+        let lines = format!(
+            "{}\n{}\n",
+            entry("dir/file.txt", &hash),
+            entry("dir/link.txt", &hash)
+        );
+        std::fs::write(
+            &entries_path,
+            zstd::encode_all(lines.as_bytes(), 0).unwrap(),
+        )
+        .unwrap();
+        let res = verify_streamed_with_cancel_check(&entries_path, &files, 2, None, None).unwrap();
+        assert!(res.ok);
+        assert_eq!(res.files_ok, 2);
+
+        let lines = format!(
+            "{}\n{}\n",
+            entry("dir/file.txt", "bad"),
+            entry("dir/missing.txt", &hash)
+        );
+        std::fs::write(
+            &entries_path,
+            zstd::encode_all(lines.as_bytes(), 0).unwrap(),
+        )
+        .unwrap();
+        let res = verify_streamed_with_cancel_check(&entries_path, &files, 2, None, None).unwrap();
+        assert!(!res.ok);
+        assert_eq!(res.files_failed, 2);
+        assert_eq!(
+            res.sample_errors,
+            vec![
+                "hash mismatch: dir/file.txt".to_string(),
+                "missing file: dir/missing.txt".to_string()
+            ]
+        );
+    }
+
     #[test]
     fn verify_restored_reports_missing_file() {
         let tmp = TempDir::new().unwrap();
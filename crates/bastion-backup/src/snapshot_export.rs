@@ -0,0 +1,406 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use uuid::Uuid;
+
+use bastion_core::backup_format::{COMPLETE_NAME, MANIFEST_NAME};
+use bastion_core::manifest::{ArtifactFormatV1, HashAlgorithm, ManifestV1};
+
+/// First entry of every export archive.
+pub const EXPORT_METADATA_NAME: &str = "bastion-export.json";
+
+/// Describes a portable snapshot export.
+///
+/// The archive is a plain tar: this metadata first, then every file of the run directory under
+/// `<job_id>/<run_id>/`, so extracting it by hand also yields a valid local directory target.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotExportMetadata {
+    pub format_version: u32,
+    pub job_id: Uuid,
+    pub run_id: Uuid,
+    pub exported_at: String,
+    pub artifact_format: ArtifactFormatV1,
+    pub encryption: SnapshotExportEncryption,
+    pub files: Vec<SnapshotExportFile>,
+}
+
+/// What is needed to decrypt the payload once the export is restored somewhere else.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotExportEncryption {
+    /// `none` or `age`.
+    pub kind: String,
+    /// Backup encryption key name on the exporting Hub; restores need the matching age identity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipient: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotExportFile {
+    /// Path relative to the run directory, `/`-separated.
+    pub path: String,
+    pub size: u64,
+}
+
+impl SnapshotExportMetadata {
+    pub const FORMAT_VERSION: u32 = 1;
+
+    pub fn new(
+        manifest: &ManifestV1,
+        files: Vec<SnapshotExportFile>,
+    ) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            format_version: Self::FORMAT_VERSION,
+            job_id: manifest.job_id,
+            run_id: manifest.run_id,
+            exported_at: OffsetDateTime::now_utc().format(&Rfc3339)?,
+            artifact_format: manifest.pipeline.format.clone(),
+            encryption: SnapshotExportEncryption {
+                kind: manifest.pipeline.encryption.clone(),
+                key_name: manifest.pipeline.encryption_key.clone(),
+                recipient: manifest.pipeline.encryption_recipient.clone(),
+            },
+            files,
+        })
+    }
+
+    fn run_prefix(&self) -> PathBuf {
+        PathBuf::from(self.job_id.to_string()).join(self.run_id.to_string())
+    }
+
+    fn validate(&self) -> Result<(), anyhow::Error> {
+        if self.format_version != Self::FORMAT_VERSION {
+            anyhow::bail!(
+                "unsupported snapshot export format version: {}",
+                self.format_version
+            );
+        }
+        for file in &self.files {
+            validate_relative_path(&file.path)?;
+        }
+        for required in [MANIFEST_NAME, COMPLETE_NAME] {
+            if !self.files.iter().any(|f| f.path == required) {
+                anyhow::bail!("snapshot export is missing {required}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes an export archive; files must be appended in the order listed in the metadata.
+pub struct SnapshotExportWriter<W: Write> {
+    builder: tar::Builder<W>,
+    prefix: PathBuf,
+}
+
+impl<W: Write> SnapshotExportWriter<W> {
+    pub fn new(out: W, metadata: &SnapshotExportMetadata) -> Result<Self, anyhow::Error> {
+        let mut builder = tar::Builder::new(out);
+        let bytes = serde_json::to_vec_pretty(metadata)?;
+        let mut header = file_header(bytes.len() as u64);
+        builder.append_data(&mut header, EXPORT_METADATA_NAME, bytes.as_slice())?;
+        Ok(Self {
+            builder,
+            prefix: metadata.run_prefix(),
+        })
+    }
+
+    pub fn append(&mut self, path: &str, size: u64, data: impl Read) -> Result<(), anyhow::Error> {
+        validate_relative_path(path)?;
+        let mut header = file_header(size);
+        self.builder
+            .append_data(&mut header, self.prefix.join(path), data)
+            .with_context(|| format!("failed to add {path} to the export"))?;
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<W, anyhow::Error> {
+        Ok(self.builder.into_inner()?)
+    }
+}
+
+fn file_header(size: u64) -> tar::Header {
+    let mut header = tar::Header::new_gnu();
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_mtime(OffsetDateTime::now_utc().unix_timestamp().max(0) as u64);
+    header.set_cksum();
+    header
+}
+
+/// Lists every regular file of a local run directory, sorted by path.
+pub fn list_run_dir_files(run_dir: &Path) -> Result<Vec<SnapshotExportFile>, anyhow::Error> {
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(run_dir).sort_by_file_name() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(run_dir)?;
+        files.push(SnapshotExportFile {
+            path: rel.to_string_lossy().replace('\\', "/"),
+            size: entry.metadata()?.len(),
+        });
+    }
+    Ok(files)
+}
+
+/// Exports a complete run directory on local disk.
+pub fn export_local_run_dir(
+    run_dir: &Path,
+    out: impl Write,
+) -> Result<SnapshotExportMetadata, anyhow::Error> {
+    if !run_dir.join(COMPLETE_NAME).is_file() {
+        anyhow::bail!(
+            "run is incomplete (no {COMPLETE_NAME}): {}",
+            run_dir.display()
+        );
+    }
+    let manifest_bytes = std::fs::read(run_dir.join(MANIFEST_NAME))
+        .with_context(|| format!("failed to read {MANIFEST_NAME}"))?;
+    let manifest = serde_json::from_slice::<ManifestV1>(&manifest_bytes)?;
+
+    let metadata = SnapshotExportMetadata::new(&manifest, list_run_dir_files(run_dir)?)?;
+    let mut writer = SnapshotExportWriter::new(out, &metadata)?;
+    for file in &metadata.files {
+        let data = File::open(run_dir.join(&file.path))?;
+        writer.append(&file.path, file.size, data)?;
+    }
+    writer.finish()?;
+    Ok(metadata)
+}
+
+/// Restores an export archive into a local directory target (`<base_dir>/<job_id>/<run_id>/`).
+///
+/// Files are extracted to a hidden staging directory and only moved into place after sizes and
+/// part hashes match, so a damaged archive never leaves a half-imported run behind.
+pub fn import_snapshot_export(
+    archive: impl Read,
+    base_dir: &Path,
+) -> Result<SnapshotExportMetadata, anyhow::Error> {
+    let mut archive = tar::Archive::new(archive);
+    let mut entries = archive.entries()?;
+
+    let mut bytes = Vec::new();
+    {
+        let mut first = entries
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("snapshot export is empty"))??;
+        if first.path()?.as_ref() != Path::new(EXPORT_METADATA_NAME) {
+            anyhow::bail!("not a snapshot export: missing {EXPORT_METADATA_NAME}");
+        }
+        first.read_to_end(&mut bytes)?;
+    }
+    let metadata = serde_json::from_slice::<SnapshotExportMetadata>(&bytes)
+        .with_context(|| format!("invalid {EXPORT_METADATA_NAME}"))?;
+    metadata.validate()?;
+
+    let job_dir = base_dir.join(metadata.job_id.to_string());
+    let run_dir = job_dir.join(metadata.run_id.to_string());
+    if run_dir.exists() {
+        anyhow::bail!("run directory already exists: {}", run_dir.display());
+    }
+    let staging = job_dir.join(format!(".{}.importing", metadata.run_id));
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::create_dir_all(&staging)?;
+
+    let result = extract_and_verify(entries, &metadata, &staging);
+    if let Err(error) = result {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(error);
+    }
+    std::fs::rename(&staging, &run_dir)?;
+    Ok(metadata)
+}
+
+fn extract_and_verify<R: Read>(
+    entries: tar::Entries<'_, R>,
+    metadata: &SnapshotExportMetadata,
+    staging: &Path,
+) -> Result<(), anyhow::Error> {
+    let prefix = metadata.run_prefix();
+    let mut expected = metadata
+        .files
+        .iter()
+        .map(|f| (f.path.as_str(), f.size))
+        .collect::<BTreeMap<_, _>>();
+
+    for entry in entries {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let rel = path
+            .strip_prefix(&prefix)
+            .map_err(|_| anyhow::anyhow!("unexpected entry in export: {}", path.display()))?;
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        let Some(size) = expected.remove(rel.as_str()) else {
+            anyhow::bail!("unexpected entry in export: {rel}");
+        };
+        if entry.header().entry_type() != tar::EntryType::Regular || entry.size() != size {
+            anyhow::bail!("export entry does not match its metadata: {rel}");
+        }
+
+        let dest = staging.join(&rel);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(&dest)?;
+        std::io::copy(&mut entry, &mut file)?;
+        file.flush()?;
+    }
+    if let Some(missing) = expected.keys().next() {
+        anyhow::bail!("snapshot export is truncated: missing {missing}");
+    }
+
+    let manifest =
+        serde_json::from_slice::<ManifestV1>(&std::fs::read(staging.join(MANIFEST_NAME))?)?;
+    if manifest.job_id != metadata.job_id || manifest.run_id != metadata.run_id {
+        anyhow::bail!("manifest ids do not match the export metadata");
+    }
+    if !staging.join(&manifest.entry_index.name).is_file() {
+        anyhow::bail!("snapshot export is missing {}", manifest.entry_index.name);
+    }
+    for part in &manifest.artifacts {
+        let path = staging.join(&part.name);
+        let size = std::fs::metadata(&path)
+            .with_context(|| format!("snapshot export is missing {}", part.name))?
+            .len();
+        if size != part.size {
+            anyhow::bail!("size mismatch: {}", part.name);
+        }
+        if part.hash_alg == HashAlgorithm::Blake3 {
+            let mut hasher = blake3::Hasher::new();
+            std::io::copy(&mut File::open(&path)?, &mut hasher)?;
+            if hasher.finalize().to_hex().as_str() != part.hash {
+                anyhow::bail!("hash mismatch: {}", part.name);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_relative_path(path: &str) -> Result<(), anyhow::Error> {
+    let ok = !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+    if !ok {
+        anyhow::bail!("invalid path in snapshot export: {path}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tempfile::tempdir;
+    use uuid::Uuid;
+
+    use bastion_core::backup_format::{COMPLETE_NAME, ENTRIES_INDEX_NAME, MANIFEST_NAME};
+    use bastion_core::manifest::{
+        ArtifactFormatV1, ArtifactPart, EntryIndexRef, HashAlgorithm, ManifestV1, PipelineSettings,
+    };
+
+    use super::{export_local_run_dir, import_snapshot_export};
+
+    fn write_run(run_dir: &Path, payload: &[u8]) -> ManifestV1 {
+        std::fs::create_dir_all(run_dir).unwrap();
+        std::fs::write(run_dir.join("payload.part000001"), payload).unwrap();
+        std::fs::write(run_dir.join(ENTRIES_INDEX_NAME), b"entries").unwrap();
+        let manifest = ManifestV1 {
+            format_version: ManifestV1::FORMAT_VERSION,
+            job_id: Uuid::new_v4(),
+            run_id: Uuid::new_v4(),
+            started_at: "2026-01-02T03:04:05Z".to_string(),
+            ended_at: "2026-01-02T03:05:05Z".to_string(),
+            pipeline: PipelineSettings {
+                format: ArtifactFormatV1::ArchiveV1,
+                tar: "pax".to_string(),
+                compression: "zstd".to_string(),
+                compression_level: None,
+                encryption: "age".to_string(),
+                encryption_key: Some("default".to_string()),
+                encryption_recipient: None,
+                split_bytes: 1024,
+            },
+            artifacts: vec![ArtifactPart {
+                name: "payload.part000001".to_string(),
+                size: payload.len() as u64,
+                hash_alg: HashAlgorithm::Blake3,
+                hash: blake3::hash(payload).to_hex().to_string(),
+            }],
+            entry_index: EntryIndexRef {
+                name: ENTRIES_INDEX_NAME.to_string(),
+                count: 1,
+            },
+            source: None,
+        };
+        std::fs::write(
+            run_dir.join(MANIFEST_NAME),
+            serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(run_dir.join(COMPLETE_NAME), b"{}").unwrap();
+        manifest
+    }
+
+    #[test]
+    fn export_round_trips_into_a_local_target() {
+        let tmp = tempdir().unwrap();
+        let run_dir = tmp.path().join("src");
+        let manifest = write_run(&run_dir, b"payload bytes");
+
+        let mut archive = Vec::new();
+        let metadata = export_local_run_dir(&run_dir, &mut archive).unwrap();
+        assert_eq!(metadata.run_id, manifest.run_id);
+        assert_eq!(metadata.encryption.key_name.as_deref(), Some("default"));
+        assert_eq!(metadata.files.len(), 4);
+
+        let target = tmp.path().join("target");
+        import_snapshot_export(archive.as_slice(), &target).unwrap();
+        let imported = target
+            .join(manifest.job_id.to_string())
+            .join(manifest.run_id.to_string());
+        for name in [
+            MANIFEST_NAME,
+            COMPLETE_NAME,
+            ENTRIES_INDEX_NAME,
+            "payload.part000001",
+        ] {
+            assert_eq!(
+                std::fs::read(imported.join(name)).unwrap(),
+                std::fs::read(run_dir.join(name)).unwrap()
+            );
+        }
+
+        let again = import_snapshot_export(archive.as_slice(), &target).unwrap_err();
+        assert!(again.to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn import_rejects_corrupted_parts_without_leaving_files() {
+        let tmp = tempdir().unwrap();
+        let run_dir = tmp.path().join("src");
+        let manifest = write_run(&run_dir, b"payload bytes");
+        std::fs::write(run_dir.join("payload.part000001"), b"PAYLOAD BYTES").unwrap();
+
+        let mut archive = Vec::new();
+        export_local_run_dir(&run_dir, &mut archive).unwrap();
+
+        let target = tmp.path().join("target");
+        let error = import_snapshot_export(archive.as_slice(), &target).unwrap_err();
+        assert!(error.to_string().contains("hash mismatch"));
+        let job_dir = target.join(manifest.job_id.to_string());
+        assert_eq!(std::fs::read_dir(job_dir).unwrap().count(), 0);
+    }
+}
@@ -14,5 +14,14 @@ pub struct Config {
     pub hub_timezone: String,
     pub run_retention_days: i64,
     pub incomplete_cleanup_days: i64,
+    /// Run staging root; `None` stages under `data_dir`.
+    pub staging_dir: Option<PathBuf>,
+    pub staging_min_free_bytes: u64,
+    /// Vite dev server origin the UI is proxied to instead of serving `ui/dist` (dev only).
+    pub ui_dev_proxy: Option<String>,
+    /// `/api` requests per minute allowed from one client IP; 0 disables the limit.
+    pub api_rate_limit_per_ip: u32,
+    /// `/api` requests per minute allowed for one session or API token; 0 disables the limit.
+    pub api_rate_limit_per_session: u32,
     pub trusted_proxies: Vec<IpNet>,
 }
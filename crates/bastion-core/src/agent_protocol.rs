@@ -1,10 +1,126 @@
 use serde::{Deserialize, Serialize};
 
-use crate::job_spec::{FilesystemSource, PipelineWebdavV1, SqliteSource, VaultwardenSource};
+use crate::job_spec::{
+    CompressionV1, FilesystemSource, GitSource, ImapCredentialsV1, ImapSource, KubernetesPvcSource,
+    PayloadStagingV1, PipelineWebdavV1, PostgresSource, SqliteAppSource, SqliteSource,
+    VaultwardenSource, VmImageSource,
+};
 use crate::manifest::ArtifactFormatV1;
 
+/// Message envelope version (`v`). Peers drop messages with a different `v`, so it only changes
+/// for breaking changes; additive features are negotiated through [`ProtocolCapabilitiesV1`].
 pub const PROTOCOL_VERSION: u32 = 1;
 
+/// Protocol revision negotiated in `hello`/`hello_ack`. Revision 1 agents don't negotiate.
+pub const PROTOCOL_REVISION: u32 = 2;
+
+pub const TASK_TYPE_BACKUP: &str = "backup";
+pub const TASK_TYPE_RESTORE: &str = "restore";
+pub const TASK_TYPE_SNAPSHOT_DELETE: &str = "snapshot_delete";
+
+pub const FEATURE_ARTIFACT_STREAM: &str = "artifact_stream";
+pub const FEATURE_DIAGNOSTICS: &str = "diagnostics";
+pub const FEATURE_FS_LIST: &str = "fs_list";
+pub const FEATURE_WEBDAV_LIST: &str = "webdav_list";
+pub const FEATURE_TASK_RESULT_ACK: &str = "task_result_ack";
+
+pub const COMPRESSION_ZSTD: &str = crate::agent_stream::WS_COMPRESSION_ZSTD;
+
+/// What one side of the agent connection supports; the negotiated set is the intersection.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+pub struct ProtocolCapabilitiesV1 {
+    pub revision: u32,
+    #[serde(default)]
+    pub task_types: Vec<String>,
+    #[serde(default)]
+    pub features: Vec<String>,
+    #[serde(default)]
+    pub compression: Vec<String>,
+}
+
+impl ProtocolCapabilitiesV1 {
+    /// Everything this build supports.
+    pub fn current() -> Self {
+        Self {
+            revision: PROTOCOL_REVISION,
+            task_types: strings(&[
+                TASK_TYPE_BACKUP,
+                TASK_TYPE_RESTORE,
+                TASK_TYPE_SNAPSHOT_DELETE,
+            ]),
+            features: strings(&[
+                FEATURE_ARTIFACT_STREAM,
+                FEATURE_DIAGNOSTICS,
+                FEATURE_FS_LIST,
+                FEATURE_TASK_RESULT_ACK,
+                FEATURE_WEBDAV_LIST,
+            ]),
+            compression: strings(&[COMPRESSION_ZSTD]),
+        }
+    }
+
+    /// What a revision 1 peer (one that sends no capability set) is known to support.
+    pub fn legacy() -> Self {
+        Self {
+            revision: 1,
+            task_types: strings(&[
+                TASK_TYPE_BACKUP,
+                TASK_TYPE_RESTORE,
+                TASK_TYPE_SNAPSHOT_DELETE,
+            ]),
+            features: strings(&[
+                FEATURE_ARTIFACT_STREAM,
+                FEATURE_DIAGNOSTICS,
+                FEATURE_FS_LIST,
+                FEATURE_WEBDAV_LIST,
+            ]),
+            compression: strings(&[COMPRESSION_ZSTD]),
+        }
+    }
+
+    /// The capabilities both sides support.
+    pub fn intersect(&self, other: &Self) -> Self {
+        fn common(a: &[String], b: &[String]) -> Vec<String> {
+            let mut out = a
+                .iter()
+                .filter(|v| b.contains(v))
+                .cloned()
+                .collect::<Vec<_>>();
+            out.sort();
+            out.dedup();
+            out
+        }
+
+        Self {
+            revision: self.revision.min(other.revision),
+            task_types: common(&self.task_types, &other.task_types),
+            features: common(&self.features, &other.features),
+            compression: common(&self.compression, &other.compression),
+        }
+    }
+
+    pub fn supports_task_type(&self, task_type: &str) -> bool {
+        self.task_types.iter().any(|v| v == task_type)
+    }
+
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.iter().any(|v| v == feature)
+    }
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+/// The `type` and `v` of a message; readable even when the rest of it doesn't parse, so a peer
+/// can tell a version mismatch from garbage.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct MessageEnvelopeV1 {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub v: u32,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct FsDirEntryV1 {
     pub name: String,
@@ -31,9 +147,13 @@ pub struct PipelineResolvedV1 {
     #[serde(default)]
     pub format: ArtifactFormatV1,
     #[serde(default)]
+    pub compression: CompressionV1,
+    #[serde(default)]
     pub encryption: EncryptionResolvedV1,
     #[serde(default)]
     pub webdav: PipelineWebdavV1,
+    #[serde(default, skip_serializing_if = "PayloadStagingV1::is_local")]
+    pub staging: PayloadStagingV1,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -44,10 +164,17 @@ pub enum TargetResolvedV1 {
         username: String,
         password: String,
         part_size_bytes: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        secret_name: Option<String>,
+        /// Staged replacement credential; the executor tries it first and reports when it works.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pending: Option<PendingWebdavCredentialV1>,
     },
     LocalDir {
         base_dir: String,
         part_size_bytes: u64,
+        #[serde(default)]
+        link_unchanged: bool,
     },
 }
 
@@ -75,6 +202,50 @@ pub enum JobSpecResolvedV1 {
         source: VaultwardenSource,
         target: TargetResolvedV1,
     },
+    SqliteApp {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineResolvedV1,
+        source: SqliteAppSource,
+        target: TargetResolvedV1,
+    },
+    Postgres {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineResolvedV1,
+        source: PostgresSource,
+        target: TargetResolvedV1,
+    },
+    VmImage {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineResolvedV1,
+        source: VmImageSource,
+        target: TargetResolvedV1,
+    },
+    KubernetesPvc {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineResolvedV1,
+        source: KubernetesPvcSource,
+        target: TargetResolvedV1,
+    },
+    Imap {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineResolvedV1,
+        source: ImapSource,
+        /// Resolved from the node's `imap` secret named by `source.secret_name`.
+        credentials: ImapCredentialsV1,
+        target: TargetResolvedV1,
+    },
+    Git {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineResolvedV1,
+        source: GitSource,
+        target: TargetResolvedV1,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
@@ -221,12 +392,22 @@ pub struct JobConfigV1 {
     pub spec: JobSpecResolvedV1,
 }
 
+/// A WebDAV credential staged for rotation, identified by when it was staged.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct PendingWebdavCredentialV1 {
+    pub username: String,
+    pub password: String,
+    pub staged_at: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WebdavSecretV1 {
     pub name: String,
     pub username: String,
     pub password: String,
     pub updated_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending: Option<PendingWebdavCredentialV1>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -236,9 +417,72 @@ pub struct BackupAgeIdentitySecretV1 {
     pub updated_at: i64,
 }
 
+/// A Hub-hosted agent binary the agent should download, verify and switch to.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AgentUpdateV1 {
+    pub version: String,
+    /// `<os>-<arch>` (Rust `std::env::consts` names), e.g. `linux-x86_64`.
+    pub platform: String,
+    /// Hub-relative download path; fetched with the agent key.
+    pub url: String,
+    pub size: u64,
+    /// BLAKE3 hex digest of the binary.
+    pub hash: String,
+    /// Base64-encoded Ed25519 signature over [`AgentUpdateV1::signed_manifest`].
+    pub signature: String,
+}
+
+impl AgentUpdateV1 {
+    /// The bytes the release key signs. Binding version and platform (not just the binary)
+    /// keeps a Hub from replaying an old signed build under a newer version or another platform.
+    ///
+    /// ```text
+    /// bastion-agent-update-v1
+    /// version=<version>
+    /// platform=<os>-<arch>
+    /// size=<bytes>
+    /// blake3=<lowercase hex>
+    /// ```
+    pub fn signed_manifest(&self) -> String {
+        format!(
+            "bastion-agent-update-v1\nversion={}\nplatform={}\nsize={}\nblake3={}\n",
+            self.version,
+            self.platform,
+            self.size,
+            self.hash.trim().to_ascii_lowercase()
+        )
+    }
+}
+
+/// Parses a release version `MAJOR.MINOR.PATCH[-pre]` (optionally prefixed with `v`) into a
+/// comparable key. Pre-releases sort before the release they precede.
+pub fn parse_release_version(raw: &str) -> Option<(u64, u64, u64, bool)> {
+    let raw = raw.trim();
+    let raw = raw.strip_prefix('v').unwrap_or(raw);
+    let (core, pre) = match raw.split_once('-') {
+        Some((core, pre)) if !pre.is_empty() => (core, true),
+        Some(_) => return None,
+        None => (raw, false),
+    };
+
+    let mut parts = core.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch, !pre))
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum HubToAgentMessageV1 {
+    /// Answers `hello` with the capabilities both sides support.
+    HelloAck {
+        v: u32,
+        protocol: ProtocolCapabilitiesV1,
+    },
     Task {
         v: u32,
         task_id: String,
@@ -285,6 +529,9 @@ pub enum HubToAgentMessageV1 {
         size_min_bytes: Option<u64>,
         #[serde(default)]
         size_max_bytes: Option<u64>,
+        /// Opaque id of the Hub session that asked; only its own older listing is superseded.
+        #[serde(default)]
+        requester: Option<String>,
     },
     WebdavList {
         v: u32,
@@ -312,6 +559,9 @@ pub enum HubToAgentMessageV1 {
         size_min_bytes: Option<u64>,
         #[serde(default)]
         size_max_bytes: Option<u64>,
+        /// Opaque id of the Hub session that asked; only its own older listing is superseded.
+        #[serde(default)]
+        requester: Option<String>,
     },
     ConfigSnapshot {
         v: u32,
@@ -346,9 +596,82 @@ pub enum HubToAgentMessageV1 {
         v: u32,
         req: ArtifactStreamCloseV1,
     },
+    UpdateAvailable {
+        v: u32,
+        update: AgentUpdateV1,
+    },
     Pong {
         v: u32,
     },
+    /// The Hub stored the result of `task_id` (or already had it, when `duplicate`); the agent
+    /// no longer needs to keep it for replay.
+    TaskResultAck {
+        v: u32,
+        task_id: String,
+        #[serde(default)]
+        duplicate: bool,
+    },
+    /// Runs the fixed set of pre-flight checks for the agent's managed jobs (or just `job_id`).
+    Diagnostics {
+        v: u32,
+        request_id: String,
+        #[serde(default)]
+        job_id: Option<String>,
+    },
+    /// The Hub is draining before it stops; the connection will drop within `drain_timeout_secs`.
+    /// Agents keep running their tasks and reconnect once the Hub is back.
+    HubShutdown {
+        v: u32,
+        drain_timeout_secs: u64,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DiskUsageV1 {
+    /// `staging` (the agent data dir, where runs are staged) or `source` (a job source path).
+    pub role: String,
+    pub path: String,
+    pub total_bytes: u64,
+    /// Space available to the agent user.
+    pub available_bytes: u64,
+}
+
+/// Point-in-time host sample reported by an agent. Fields the platform can't provide are omitted.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct HostMetricsV1 {
+    pub collected_at: i64,
+    #[serde(default)]
+    pub load_avg: Option<[f64; 3]>,
+    #[serde(default)]
+    pub mem_total_bytes: Option<u64>,
+    #[serde(default)]
+    pub mem_available_bytes: Option<u64>,
+    #[serde(default)]
+    pub disks: Vec<DiskUsageV1>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct DiagnosticCheckV1 {
+    /// `job_config`, `staging_space`, `staging_writable`, `source_access` or `target_reachable`.
+    pub check: String,
+    /// Set for checks that belong to one job (sources and targets).
+    #[serde(default)]
+    pub job_id: Option<String>,
+    /// The path or URL that was checked.
+    pub subject: String,
+    /// `ok`, `warn` or `fail`.
+    pub status: String,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub available_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct DiagnosticsReportV1 {
+    pub collected_at: i64,
+    #[serde(default)]
+    pub checks: Vec<DiagnosticCheckV1>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -363,6 +686,11 @@ pub enum AgentToHubMessageV1 {
         info: serde_json::Value,
         #[serde(default)]
         capabilities: serde_json::Value,
+        #[serde(default)]
+        metrics: Option<HostMetricsV1>,
+        /// Missing for revision 1 agents.
+        #[serde(default)]
+        protocol: Option<ProtocolCapabilitiesV1>,
     },
     ConfigAck {
         v: u32,
@@ -370,6 +698,8 @@ pub enum AgentToHubMessageV1 {
     },
     Ping {
         v: u32,
+        #[serde(default)]
+        metrics: Option<HostMetricsV1>,
     },
     Ack {
         v: u32,
@@ -450,6 +780,14 @@ pub enum AgentToHubMessageV1 {
         #[serde(default)]
         error: Option<String>,
     },
+    DiagnosticsResult {
+        v: u32,
+        request_id: String,
+        #[serde(default)]
+        report: Option<DiagnosticsReportV1>,
+        #[serde(default)]
+        error: Option<String>,
+    },
     ArtifactStreamOpen {
         v: u32,
         req: ArtifactStreamOpenV1,
@@ -466,13 +804,23 @@ pub enum AgentToHubMessageV1 {
         v: u32,
         req: ArtifactStreamCloseV1,
     },
+    /// Announces a raw task log stream for a run; the log follows as artifact chunk frames with
+    /// `stream_id`, and a frame with the EOF flag closes it.
+    RunLogOpen {
+        v: u32,
+        run_id: String,
+        stream_id: String,
+    },
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        AgentToHubMessageV1, DriverRefV1, HubToAgentMessageV1, PROTOCOL_VERSION,
-        SnapshotDeleteTaskV1, TargetDriverCapabilitiesV1,
+        AgentToHubMessageV1, AgentUpdateV1, DiagnosticCheckV1, DiagnosticsReportV1, DiskUsageV1,
+        DriverRefV1, FEATURE_TASK_RESULT_ACK, HostMetricsV1, HubToAgentMessageV1,
+        MessageEnvelopeV1, PROTOCOL_REVISION, PROTOCOL_VERSION, PendingWebdavCredentialV1,
+        ProtocolCapabilitiesV1, SnapshotDeleteTaskV1, TASK_TYPE_RESTORE,
+        TargetDriverCapabilitiesV1, TargetResolvedV1, parse_release_version,
     };
 
     #[test]
@@ -573,10 +921,12 @@ mod tests {
                     source: crate::job_spec::SqliteSource {
                         path: "/tmp/db.sqlite3".to_string(),
                         integrity_check: false,
+                        databases: Vec::new(),
                     },
                     target: super::TargetResolvedV1::LocalDir {
                         base_dir: "/tmp/out".to_string(),
                         part_size_bytes: 4096,
+                        link_unchanged: false,
                     },
                 },
                 source_driver: Some(DriverRefV1 {
@@ -627,6 +977,92 @@ mod tests {
         );
     }
 
+    #[test]
+    fn capabilities_negotiate_to_the_common_subset() {
+        let hub = ProtocolCapabilitiesV1::current();
+        let agent = ProtocolCapabilitiesV1 {
+            revision: 3,
+            task_types: vec!["backup".to_string(), "verify".to_string()],
+            features: vec![FEATURE_TASK_RESULT_ACK.to_string()],
+            compression: Vec::new(),
+        };
+
+        let negotiated = hub.intersect(&agent);
+        assert_eq!(negotiated.revision, PROTOCOL_REVISION);
+        assert_eq!(negotiated.task_types, vec!["backup".to_string()]);
+        assert!(negotiated.has_feature(FEATURE_TASK_RESULT_ACK));
+        assert!(!negotiated.supports_task_type(TASK_TYPE_RESTORE));
+        assert!(negotiated.compression.is_empty());
+
+        let legacy = hub.intersect(&ProtocolCapabilitiesV1::legacy());
+        assert_eq!(legacy.revision, 1);
+        assert!(!legacy.has_feature(FEATURE_TASK_RESULT_ACK));
+        assert!(legacy.supports_task_type(TASK_TYPE_RESTORE));
+    }
+
+    #[test]
+    fn envelope_reads_type_and_version_of_unknown_messages() {
+        let envelope = serde_json::from_str::<MessageEnvelopeV1>(
+            r#"{"type":"something_new","v":7,"payload":{"x":1}}"#,
+        )
+        .expect("envelope");
+        assert_eq!(envelope.kind, "something_new");
+        assert_eq!(envelope.v, 7);
+
+        let hello = serde_json::from_value::<AgentToHubMessageV1>(serde_json::json!({
+            "type": "hello",
+            "v": PROTOCOL_VERSION,
+            "agent_id": "a1",
+        }))
+        .expect("revision 1 hello");
+        assert!(matches!(
+            hello,
+            AgentToHubMessageV1::Hello { protocol: None, .. }
+        ));
+    }
+
+    #[test]
+    fn task_result_ack_defaults_to_first_delivery() {
+        let decoded = serde_json::from_value::<HubToAgentMessageV1>(serde_json::json!({
+            "type": "task_result_ack",
+            "v": PROTOCOL_VERSION,
+            "task_id": "task-1",
+        }))
+        .expect("deserialize");
+        match decoded {
+            HubToAgentMessageV1::TaskResultAck {
+                v,
+                task_id,
+                duplicate,
+            } => {
+                assert_eq!(v, PROTOCOL_VERSION);
+                assert_eq!(task_id, "task-1");
+                assert!(!duplicate);
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn hub_shutdown_serializes_with_snake_case_type() {
+        let msg = HubToAgentMessageV1::HubShutdown {
+            v: PROTOCOL_VERSION,
+            drain_timeout_secs: 60,
+        };
+
+        let json = serde_json::to_value(&msg).expect("serialize");
+        assert_eq!(json["type"], "hub_shutdown");
+        assert_eq!(json["drain_timeout_secs"], 60);
+        let decoded = serde_json::from_value::<HubToAgentMessageV1>(json).expect("deserialize");
+        assert!(matches!(
+            decoded,
+            HubToAgentMessageV1::HubShutdown {
+                drain_timeout_secs: 60,
+                ..
+            }
+        ));
+    }
+
     #[test]
     fn cancel_run_task_round_trip() {
         let msg = HubToAgentMessageV1::CancelRunTask {
@@ -662,4 +1098,204 @@ mod tests {
             other => panic!("unexpected message: {other:?}"),
         }
     }
+
+    #[test]
+    fn release_versions_compare_numerically_and_prereleases_sort_first() {
+        assert!(parse_release_version("0.10.0") > parse_release_version("0.9.9"));
+        assert!(parse_release_version("v1.2.3") == parse_release_version("1.2.3"));
+        assert!(parse_release_version("1.0.0-rc.1") < parse_release_version("1.0.0"));
+        assert_eq!(parse_release_version("1.2"), None);
+        assert_eq!(parse_release_version("1.2.3.4"), None);
+        assert_eq!(parse_release_version("1.2.x"), None);
+    }
+
+    #[test]
+    fn update_manifest_covers_version_and_platform() {
+        let update = AgentUpdateV1 {
+            version: "0.2.0".to_string(),
+            platform: "linux-x86_64".to_string(),
+            url: "/agent/releases/0.2.0/linux-x86_64".to_string(),
+            size: 42,
+            hash: "ABC".to_string(),
+            signature: String::new(),
+        };
+        assert_eq!(
+            update.signed_manifest(),
+            "bastion-agent-update-v1\nversion=0.2.0\nplatform=linux-x86_64\nsize=42\nblake3=abc\n"
+        );
+    }
+
+    #[test]
+    fn update_available_round_trip() {
+        let update = AgentUpdateV1 {
+            version: "0.2.0".to_string(),
+            platform: "linux-x86_64".to_string(),
+            url: "/agent/releases/0.2.0/linux-x86_64".to_string(),
+            size: 42,
+            hash: "abc".to_string(),
+            signature: "c2ln".to_string(),
+        };
+        let msg = HubToAgentMessageV1::UpdateAvailable {
+            v: PROTOCOL_VERSION,
+            update: update.clone(),
+        };
+
+        let json = serde_json::to_string(&msg).expect("serialize");
+        assert!(json.contains("\"type\":\"update_available\""));
+        let decoded = serde_json::from_str::<HubToAgentMessageV1>(&json).expect("deserialize");
+        match decoded {
+            HubToAgentMessageV1::UpdateAvailable { v, update: decoded } => {
+                assert_eq!(v, PROTOCOL_VERSION);
+                assert_eq!(decoded, update);
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn ping_metrics_are_optional_and_round_trip() {
+        let legacy = serde_json::json!({ "type": "ping", "v": PROTOCOL_VERSION });
+        let decoded = serde_json::from_value::<AgentToHubMessageV1>(legacy).expect("deserialize");
+        assert!(matches!(
+            decoded,
+            AgentToHubMessageV1::Ping { metrics: None, .. }
+        ));
+
+        let metrics = HostMetricsV1 {
+            collected_at: 10,
+            load_avg: Some([0.5, 0.25, 0.125]),
+            mem_total_bytes: Some(1024),
+            mem_available_bytes: Some(512),
+            disks: vec![DiskUsageV1 {
+                role: "staging".to_string(),
+                path: "/var/lib/bastion".to_string(),
+                total_bytes: 100,
+                available_bytes: 40,
+            }],
+        };
+        let msg = AgentToHubMessageV1::Ping {
+            v: PROTOCOL_VERSION,
+            metrics: Some(metrics.clone()),
+        };
+        let json = serde_json::to_string(&msg).expect("serialize");
+        match serde_json::from_str::<AgentToHubMessageV1>(&json).expect("deserialize") {
+            AgentToHubMessageV1::Ping {
+                metrics: decoded, ..
+            } => {
+                assert_eq!(decoded, Some(metrics));
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn diagnostics_round_trip() {
+        let request = serde_json::json!({
+            "type": "diagnostics",
+            "v": PROTOCOL_VERSION,
+            "request_id": "req1"
+        });
+        match serde_json::from_value::<HubToAgentMessageV1>(request).expect("deserialize") {
+            HubToAgentMessageV1::Diagnostics {
+                request_id, job_id, ..
+            } => {
+                assert_eq!(request_id, "req1");
+                assert_eq!(job_id, None);
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+
+        let report = DiagnosticsReportV1 {
+            collected_at: 10,
+            checks: vec![DiagnosticCheckV1 {
+                check: "source_access".to_string(),
+                job_id: Some("job1".to_string()),
+                subject: "/srv/data".to_string(),
+                status: "fail".to_string(),
+                message: Some("permission denied".to_string()),
+                available_bytes: None,
+            }],
+        };
+        let msg = AgentToHubMessageV1::DiagnosticsResult {
+            v: PROTOCOL_VERSION,
+            request_id: "req1".to_string(),
+            report: Some(report.clone()),
+            error: None,
+        };
+        let json = serde_json::to_string(&msg).expect("serialize");
+        match serde_json::from_str::<AgentToHubMessageV1>(&json).expect("deserialize") {
+            AgentToHubMessageV1::DiagnosticsResult {
+                report: decoded, ..
+            } => assert_eq!(decoded, Some(report)),
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn run_log_open_round_trip() {
+        let msg = AgentToHubMessageV1::RunLogOpen {
+            v: PROTOCOL_VERSION,
+            run_id: "r1".to_string(),
+            stream_id: "00000000-0000-0000-0000-000000000001".to_string(),
+        };
+
+        let json = serde_json::to_value(&msg).expect("serialize");
+        assert_eq!(json["type"], "run_log_open");
+        match serde_json::from_value::<AgentToHubMessageV1>(json).expect("deserialize") {
+            AgentToHubMessageV1::RunLogOpen {
+                v,
+                run_id,
+                stream_id,
+            } => {
+                assert_eq!(v, PROTOCOL_VERSION);
+                assert_eq!(run_id, "r1");
+                assert_eq!(stream_id, "00000000-0000-0000-0000-000000000001");
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn webdav_target_pending_credential_is_optional() {
+        let legacy = serde_json::json!({
+            "type": "webdav",
+            "base_url": "https://nas.example/backup/",
+            "username": "u",
+            "password": "old",
+            "part_size_bytes": 1024
+        });
+        match serde_json::from_value::<TargetResolvedV1>(legacy).expect("deserialize") {
+            TargetResolvedV1::Webdav {
+                secret_name,
+                pending,
+                ..
+            } => {
+                assert_eq!(secret_name, None);
+                assert_eq!(pending, None);
+            }
+            other => panic!("unexpected target: {other:?}"),
+        }
+
+        let pending = PendingWebdavCredentialV1 {
+            username: "u".to_string(),
+            password: "new".to_string(),
+            staged_at: 7,
+        };
+        let target = TargetResolvedV1::Webdav {
+            base_url: "https://nas.example/backup/".to_string(),
+            username: "u".to_string(),
+            password: "old".to_string(),
+            part_size_bytes: 1024,
+            secret_name: Some("nas".to_string()),
+            pending: Some(pending.clone()),
+        };
+        let json = serde_json::to_value(&target).expect("serialize");
+        assert_eq!(json["pending"]["staged_at"], 7);
+        match serde_json::from_value::<TargetResolvedV1>(json).expect("deserialize") {
+            TargetResolvedV1::Webdav {
+                pending: decoded, ..
+            } => assert_eq!(decoded, Some(pending)),
+            other => panic!("unexpected target: {other:?}"),
+        }
+    }
 }
@@ -1,7 +1,19 @@
+use std::io::Read as _;
+
 use uuid::Uuid;
 
 pub const ARTIFACT_CHUNK_FRAME_V1_HEADER_LEN: usize = 17; // 16 bytes UUID + 1 byte flags
 
+/// WebSocket upgrade header used to negotiate compressed JSON messages. The agent sends the
+/// codecs it accepts; the Hub echoes the one it picked.
+pub const WS_COMPRESSION_HEADER: &str = "x-bastion-ws-compression";
+pub const WS_COMPRESSION_ZSTD: &str = "zstd";
+
+/// Text messages shorter than this are not worth compressing (pings, acks).
+pub const COMPRESSED_TEXT_FRAME_MIN_BYTES: usize = 1024;
+const COMPRESSED_TEXT_FRAME_MAX_DECODED_BYTES: u64 = 64 * 1024 * 1024;
+const COMPRESSED_TEXT_FRAME_ZSTD_LEVEL: i32 = 3;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct ArtifactChunkFrameV1Flags {
     pub eof: bool,
@@ -54,6 +66,52 @@ pub fn decode_artifact_chunk_frame_v1(
     })
 }
 
+/// Returns true when a comma-separated `WS_COMPRESSION_HEADER` value offers zstd.
+pub fn ws_compression_offers_zstd(header: Option<&str>) -> bool {
+    header.is_some_and(|v| {
+        v.split(',')
+            .any(|codec| codec.trim().eq_ignore_ascii_case(WS_COMPRESSION_ZSTD))
+    })
+}
+
+/// Compressed JSON messages reuse the chunk frame layout with the nil stream id, which is never
+/// allocated for artifact streams. The payload is a zstd frame of the UTF-8 message text.
+///
+/// Returns `None` when the text is too small or does not shrink.
+pub fn encode_compressed_text_frame_v1(text: &str) -> Option<Vec<u8>> {
+    if text.len() < COMPRESSED_TEXT_FRAME_MIN_BYTES {
+        return None;
+    }
+    let compressed =
+        zstd::bulk::compress(text.as_bytes(), COMPRESSED_TEXT_FRAME_ZSTD_LEVEL).ok()?;
+    if compressed.len() + ARTIFACT_CHUNK_FRAME_V1_HEADER_LEN >= text.len() {
+        return None;
+    }
+    Some(encode_artifact_chunk_frame_v1(
+        &Uuid::nil(),
+        ArtifactChunkFrameV1Flags { eof: true },
+        &compressed,
+    ))
+}
+
+/// Decodes a frame produced by [`encode_compressed_text_frame_v1`]. Returns `Ok(None)` for
+/// regular artifact chunk frames.
+pub fn decode_compressed_text_frame_v1(bytes: &[u8]) -> Result<Option<String>, anyhow::Error> {
+    let decoded = decode_artifact_chunk_frame_v1(bytes)?;
+    if !decoded.stream_id.is_nil() {
+        return Ok(None);
+    }
+
+    let mut text = Vec::new();
+    zstd::stream::read::Decoder::new(decoded.payload)?
+        .take(COMPRESSED_TEXT_FRAME_MAX_DECODED_BYTES + 1)
+        .read_to_end(&mut text)?;
+    if text.len() as u64 > COMPRESSED_TEXT_FRAME_MAX_DECODED_BYTES {
+        anyhow::bail!("compressed message too large");
+    }
+    Ok(Some(String::from_utf8(text)?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,4 +163,37 @@ mod tests {
             .expect("expected error");
         assert!(err.to_string().contains("too short"));
     }
+
+    #[test]
+    fn compressed_text_frames_round_trip_and_skip_small_messages() -> Result<(), anyhow::Error> {
+        assert!(encode_compressed_text_frame_v1(r#"{"type":"ping","v":1}"#).is_none());
+
+        let events: Vec<String> = (0..200)
+            .map(|i| {
+                format!(r#"{{"seq":{i},"level":"info","kind":"upload","message":"uploaded part"}}"#)
+            })
+            .collect();
+        let text = format!("[{}]", events.join(","));
+        let frame = encode_compressed_text_frame_v1(&text).expect("compressible");
+        assert!(frame.len() < text.len() / 4);
+
+        assert_eq!(decode_compressed_text_frame_v1(&frame)?, Some(text));
+
+        // Regular chunk frames are left to the artifact stream handler.
+        let chunk = encode_artifact_chunk_frame_v1(
+            &Uuid::new_v4(),
+            ArtifactChunkFrameV1Flags { eof: false },
+            b"bytes",
+        );
+        assert_eq!(decode_compressed_text_frame_v1(&chunk)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn zstd_offer_is_parsed_from_codec_list() {
+        assert!(ws_compression_offers_zstd(Some("zstd")));
+        assert!(ws_compression_offers_zstd(Some("br, ZSTD")));
+        assert!(!ws_compression_offers_zstd(Some("deflate")));
+        assert!(!ws_compression_offers_zstd(None));
+    }
 }
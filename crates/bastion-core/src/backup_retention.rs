@@ -7,6 +7,14 @@ pub struct RetentionSnapshot {
     pub run_id: String,
     pub ended_at: i64,
     pub pinned: bool,
+    /// Retention lock expiry (unix seconds); the snapshot is kept until then.
+    pub locked_until: Option<i64>,
+}
+
+impl RetentionSnapshot {
+    pub fn is_locked(&self, now: i64) -> bool {
+        self.locked_until.is_some_and(|until| until > now)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -50,9 +58,9 @@ pub fn select_retention(
 
     let mut keep_ids = HashSet::<&str>::new();
 
-    // pinned is always kept.
+    // pinned and retention-locked snapshots are always kept.
     for s in snapshots {
-        if s.pinned {
+        if s.pinned || s.is_locked(now) {
             keep_ids.insert(&s.run_id);
         }
     }
@@ -105,6 +113,9 @@ pub fn select_retention(
         if s.pinned {
             reasons.push("pinned");
         }
+        if s.is_locked(now) {
+            reasons.push("locked");
+        }
         if keep_last_ids.contains(s.run_id.as_str()) {
             reasons.push("keep_last");
         }
@@ -148,16 +159,19 @@ mod tests {
                 run_id: "a".to_string(),
                 ended_at: now - 10,
                 pinned: false,
+                locked_until: None,
             },
             RetentionSnapshot {
                 run_id: "b".to_string(),
                 ended_at: now - 20,
                 pinned: false,
+                locked_until: None,
             },
             RetentionSnapshot {
                 run_id: "c".to_string(),
                 ended_at: now - 400_000,
                 pinned: false,
+                locked_until: None,
             },
         ];
 
@@ -167,6 +181,7 @@ mod tests {
             keep_days: Some(1),
             max_delete_per_tick: 50,
             max_delete_per_day: 200,
+            lock_days: None,
         };
         let sel = select_retention(&policy, now, &snaps);
 
@@ -195,16 +210,19 @@ mod tests {
                 run_id: "a".to_string(),
                 ended_at: now - 10,
                 pinned: false,
+                locked_until: None,
             },
             RetentionSnapshot {
                 run_id: "b".to_string(),
                 ended_at: now - 20,
                 pinned: true,
+                locked_until: None,
             },
             RetentionSnapshot {
                 run_id: "c".to_string(),
                 ended_at: now - 30,
                 pinned: false,
+                locked_until: None,
             },
         ];
 
@@ -214,6 +232,7 @@ mod tests {
             keep_days: None,
             max_delete_per_tick: 50,
             max_delete_per_day: 200,
+            lock_days: None,
         };
         let sel = select_retention(&policy, now, &snaps);
 
@@ -224,4 +243,52 @@ mod tests {
         );
         assert!(!sel.delete.iter().any(|d| d.run_id == "b"));
     }
+
+    #[test]
+    fn locked_is_kept_until_lock_expires() {
+        let now = 1_000_000;
+        let snaps = vec![
+            RetentionSnapshot {
+                run_id: "a".to_string(),
+                ended_at: now - 10,
+                pinned: false,
+                locked_until: None,
+            },
+            RetentionSnapshot {
+                run_id: "b".to_string(),
+                ended_at: now - 20,
+                pinned: false,
+                locked_until: Some(now + 60),
+            },
+            RetentionSnapshot {
+                run_id: "c".to_string(),
+                ended_at: now - 30,
+                pinned: false,
+                locked_until: Some(now),
+            },
+        ];
+
+        let policy = RetentionPolicyV1 {
+            enabled: true,
+            keep_last: Some(1),
+            keep_days: None,
+            max_delete_per_tick: 50,
+            max_delete_per_day: 200,
+            lock_days: None,
+        };
+        let sel = select_retention(&policy, now, &snaps);
+
+        assert!(
+            sel.keep
+                .iter()
+                .any(|d| d.run_id == "b" && d.reasons == vec!["locked"])
+        );
+        assert_eq!(
+            sel.delete
+                .iter()
+                .map(|d| d.run_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["c"]
+        );
+    }
 }
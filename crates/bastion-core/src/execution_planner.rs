@@ -75,6 +75,7 @@ pub enum ExecutionPlanModeV1 {
     StagedUpload,
     RollingUpload,
     RawTreeWebdavDirect,
+    StreamingUpload,
 }
 
 impl ExecutionPlanModeV1 {
@@ -83,6 +84,7 @@ impl ExecutionPlanModeV1 {
             Self::StagedUpload => "staged_upload",
             Self::RollingUpload => "rolling_upload",
             Self::RawTreeWebdavDirect => "raw_tree_webdav_direct",
+            Self::StreamingUpload => "streaming_upload",
         }
     }
 }
@@ -145,6 +147,8 @@ pub struct ExecutionPlannerInputV1 {
     pub direct_upload_preference: DirectUploadPreferenceV1,
     pub consistency_policy: Option<ConsistencyPolicyV1>,
     pub upload_on_consistency_failure: Option<bool>,
+    /// The job asked for `pipeline.staging=stream` (payload parts never touch local disk).
+    pub stream_payload: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -158,6 +162,9 @@ pub struct ExecutionPlanV1 {
     pub enable_raw_tree_webdav_direct_upload: bool,
     #[serde(default)]
     pub link_stage_data_to_local_target: bool,
+    /// Payload parts go straight to the target; implies `allow_rolling_upload`.
+    #[serde(default)]
+    pub stream_payload_parts: bool,
     #[serde(default)]
     pub required_target_capabilities: PlannerTargetCapabilitiesV1,
 }
@@ -189,6 +196,7 @@ impl ExecutionPlanV1 {
             "allow_rolling_upload": self.allow_rolling_upload,
             "enable_raw_tree_webdav_direct_upload": self.enable_raw_tree_webdav_direct_upload,
             "link_stage_data_to_local_target": self.link_stage_data_to_local_target,
+            "stream_payload_parts": self.stream_payload_parts,
         })
     }
 }
@@ -306,6 +314,7 @@ pub fn plan_execution(
             allow_rolling_upload: false,
             enable_raw_tree_webdav_direct_upload: true,
             link_stage_data_to_local_target: false,
+            stream_payload_parts: false,
             required_target_capabilities: PlannerTargetCapabilitiesV1 {
                 supports_raw_tree_direct_upload: true,
                 ..Default::default()
@@ -327,8 +336,14 @@ pub fn plan_execution(
         };
     }
 
+    // Streaming has the same constraints as rolling uploads: parts reach the target before the
+    // build finishes. When they don't hold, the run falls back to staging locally.
+    let streaming_enabled = rolling_enabled && input.stream_payload;
+
     Ok(ExecutionPlanV1 {
-        mode: if rolling_enabled {
+        mode: if streaming_enabled {
+            ExecutionPlanModeV1::StreamingUpload
+        } else if rolling_enabled {
             ExecutionPlanModeV1::RollingUpload
         } else {
             ExecutionPlanModeV1::StagedUpload
@@ -338,6 +353,7 @@ pub fn plan_execution(
         enable_raw_tree_webdav_direct_upload: false,
         link_stage_data_to_local_target: input.artifact_format == ArtifactFormatV1::RawTreeV1
             && input.target_driver.kind == "local_dir",
+        stream_payload_parts: streaming_enabled,
         required_target_capabilities: PlannerTargetCapabilitiesV1 {
             supports_archive_rolling_upload: rolling_enabled,
             ..Default::default()
@@ -386,6 +402,7 @@ mod tests {
             direct_upload_preference: DirectUploadPreferenceV1::Auto,
             consistency_policy: Some(ConsistencyPolicyV1::Warn),
             upload_on_consistency_failure: Some(true),
+            stream_payload: false,
         }
     }
 
@@ -455,6 +472,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn planner_streams_archive_parts_when_requested() {
+        let mut input = filesystem_webdav_input();
+        input.artifact_format = ArtifactFormatV1::ArchiveV1;
+        input.direct_upload_preference = DirectUploadPreferenceV1::Off;
+        input.stream_payload = true;
+
+        let plan = plan_execution(&input).expect("plan");
+        assert_eq!(plan.mode, ExecutionPlanModeV1::StreamingUpload);
+        assert!(plan.stream_payload_parts);
+        assert!(plan.allow_rolling_upload);
+
+        // A strict consistency policy needs the whole payload before anything is uploaded.
+        input.consistency_policy = Some(ConsistencyPolicyV1::Fail);
+        input.upload_on_consistency_failure = Some(false);
+        let plan = plan_execution(&input).expect("plan");
+        assert_eq!(plan.mode, ExecutionPlanModeV1::StagedUpload);
+        assert!(!plan.stream_payload_parts);
+    }
+
     #[test]
     fn planner_marks_local_dir_raw_tree_link_mode() {
         let mut input = filesystem_webdav_input();
@@ -18,6 +18,68 @@ pub enum EncryptionV1 {
     AgeX25519 {
         key_name: String,
     },
+    /// Encrypts to a backup key whose identity is wrapped with a user passphrase (age scrypt).
+    AgePassphrase {
+        key_name: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionAlgorithmV1 {
+    #[default]
+    Zstd,
+    Lz4,
+    Xz,
+    None,
+}
+
+impl CompressionAlgorithmV1 {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Zstd => "zstd",
+            Self::Lz4 => "lz4",
+            Self::Xz => "xz",
+            Self::None => "none",
+        }
+    }
+
+    /// Valid `level` range, or `None` when the algorithm has no levels.
+    pub fn level_range(self) -> Option<std::ops::RangeInclusive<i32>> {
+        match self {
+            Self::Zstd => Some(1..=22),
+            Self::Xz => Some(0..=9),
+            Self::Lz4 | Self::None => None,
+        }
+    }
+
+    pub fn default_level(self) -> Option<i32> {
+        match self {
+            Self::Zstd => Some(3),
+            Self::Xz => Some(6),
+            Self::Lz4 | Self::None => None,
+        }
+    }
+}
+
+/// Payload compression for `archive_v1` (the entries index is always zstd).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CompressionV1 {
+    #[serde(default)]
+    pub algorithm: CompressionAlgorithmV1,
+    /// Algorithm-specific level; `None` uses the algorithm default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<i32>,
+    /// Store already-compressed files (media, archives) with a near-store zstd level instead of
+    /// recompressing them. Applies to filesystem archives; requires zstd.
+    #[serde(default)]
+    pub adaptive: bool,
+}
+
+impl CompressionV1 {
+    pub fn effective_level(&self) -> Option<i32> {
+        self.level.or(self.algorithm.default_level())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -25,9 +87,30 @@ pub struct PipelineV1 {
     #[serde(default)]
     pub format: ArtifactFormatV1,
     #[serde(default)]
+    pub compression: CompressionV1,
+    #[serde(default)]
     pub encryption: EncryptionV1,
     #[serde(default)]
     pub webdav: PipelineWebdavV1,
+    #[serde(default, skip_serializing_if = "PayloadStagingV1::is_local")]
+    pub staging: PayloadStagingV1,
+}
+
+/// Where archive payload parts are written before they reach the target.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadStagingV1 {
+    /// Parts are written to the staging directory first (staged or rolling upload).
+    #[default]
+    Local,
+    /// Agents stream parts straight to the target; requires `archive_v1`. Hub runs stage locally.
+    Stream,
+}
+
+impl PayloadStagingV1 {
+    pub fn is_local(&self) -> bool {
+        *self == Self::Local
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
@@ -110,6 +193,19 @@ impl Default for WebdavRawTreeDirectSettingsV1 {
 pub struct PipelineWebdavV1 {
     #[serde(default)]
     pub raw_tree_direct: WebdavRawTreeDirectSettingsV1,
+    /// Archive parts uploaded in parallel during rolling uploads (default 1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part_upload_concurrency: Option<u32>,
+}
+
+impl PipelineWebdavV1 {
+    pub const MAX_PART_UPLOAD_CONCURRENCY: u32 = 16;
+
+    pub fn effective_part_upload_concurrency(&self) -> usize {
+        self.part_upload_concurrency
+            .unwrap_or(1)
+            .clamp(1, Self::MAX_PART_UPLOAD_CONCURRENCY) as usize
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
@@ -120,6 +216,43 @@ pub enum NotificationsModeV1 {
     Custom,
 }
 
+/// When a finished run should produce notifications.
+///
+/// "Previous run" means the most recent earlier run of the same job that ended in success or
+/// failure; canceled runs are ignored.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyOnV1 {
+    /// Every finished run.
+    All,
+    /// Every failed run.
+    Failure,
+    /// A failed run whose previous run succeeded (or that has no previous run).
+    FirstFailure,
+    /// A successful run whose previous run failed.
+    Recovery,
+}
+
+/// External "dead man's switch" URLs (e.g. Healthchecks.io) the Hub pings with a GET request.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct PingUrlsV1 {
+    /// Pinged when the run starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_url: Option<String>,
+    /// Pinged when the run succeeds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success_url: Option<String>,
+    /// Pinged when the run fails.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_url: Option<String>,
+}
+
+impl PingUrlsV1 {
+    pub fn is_empty(&self) -> bool {
+        self.start_url.is_none() && self.success_url.is_none() && self.failure_url.is_none()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct NotificationsV1 {
     #[serde(default)]
@@ -128,6 +261,31 @@ pub struct NotificationsV1 {
     pub wecom_bot: Vec<String>,
     #[serde(default)]
     pub email: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slack: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub discord: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sms: Vec<String>,
+    /// A run notifies when any rule matches. Empty means `all`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notify_on: Vec<NotifyOnV1>,
+    #[serde(default, skip_serializing_if = "PingUrlsV1::is_empty")]
+    pub ping: PingUrlsV1,
+}
+
+impl NotificationsV1 {
+    /// Destinations picked for `channel` in custom mode.
+    pub fn destinations_for(&self, channel: &str) -> &[String] {
+        match channel {
+            "wecom_bot" => &self.wecom_bot,
+            "email" => &self.email,
+            "slack" => &self.slack,
+            "discord" => &self.discord,
+            "sms" => &self.sms,
+            _ => &[],
+        }
+    }
 }
 
 fn default_max_delete_per_tick() -> u32 {
@@ -155,6 +313,10 @@ pub struct RetentionPolicyV1 {
     /// Safety valve: limit how many snapshots retention can enqueue per day (UTC).
     #[serde(default = "default_max_delete_per_day")]
     pub max_delete_per_day: u32,
+    /// Lock new snapshots against deletion for D days after they finish. Applies even when
+    /// `enabled` is false. `None`/`0` means disabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_days: Option<u32>,
 }
 
 impl Default for RetentionPolicyV1 {
@@ -165,6 +327,7 @@ impl Default for RetentionPolicyV1 {
             keep_days: None,
             max_delete_per_tick: default_max_delete_per_tick(),
             max_delete_per_day: default_max_delete_per_day(),
+            lock_days: None,
         }
     }
 }
@@ -178,6 +341,85 @@ pub enum FsSymlinkPolicy {
     Skip,
 }
 
+/// Built-in exclude rule sets for directories that are rarely worth backing up.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FsExcludePreset {
+    /// `node_modules` directories.
+    NodeModules,
+    /// `.cache` and `__pycache__` directories.
+    Caches,
+}
+
+impl FsExcludePreset {
+    /// Glob patterns added to `exclude` when the preset is selected.
+    pub fn patterns(self) -> &'static [&'static str] {
+        match self {
+            Self::NodeModules => &["**/node_modules", "**/node_modules/**"],
+            Self::Caches => &[
+                "**/.cache",
+                "**/.cache/**",
+                "**/__pycache__",
+                "**/__pycache__/**",
+            ],
+        }
+    }
+}
+
+fn default_anomaly_changed_percent() -> u32 {
+    50
+}
+
+fn default_anomaly_min_files() -> u64 {
+    100
+}
+
+fn default_anomaly_suspicious_extension_files() -> u64 {
+    10
+}
+
+fn default_anomaly_entropy_sample_files() -> u64 {
+    200
+}
+
+fn default_anomaly_high_entropy_percent() -> u32 {
+    50
+}
+
+/// Thresholds for the snapshot-to-snapshot anomaly heuristics.
+///
+/// Each heuristic raises its own finding; a snapshot is flagged when any of them trips.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct FsAnomalyDetection {
+    /// Share of previously backed-up files that were modified or removed.
+    #[serde(default = "default_anomaly_changed_percent")]
+    pub changed_percent: u32,
+    /// The changed-share heuristic is skipped while the previous snapshot has fewer files.
+    #[serde(default = "default_anomaly_min_files")]
+    pub min_files: u64,
+    /// New files with ransomware-style extensions (`.encrypted`, `.locked`, ...).
+    #[serde(default = "default_anomaly_suspicious_extension_files")]
+    pub suspicious_extension_files: u64,
+    /// Changed files whose content is sampled for entropy; `0` disables the entropy check.
+    #[serde(default = "default_anomaly_entropy_sample_files")]
+    pub entropy_sample_files: u64,
+    /// Share of sampled files that look encrypted (near-random content).
+    #[serde(default = "default_anomaly_high_entropy_percent")]
+    pub high_entropy_percent: u32,
+}
+
+impl Default for FsAnomalyDetection {
+    fn default() -> Self {
+        Self {
+            changed_percent: default_anomaly_changed_percent(),
+            min_files: default_anomaly_min_files(),
+            suspicious_extension_files: default_anomaly_suspicious_extension_files(),
+            entropy_sample_files: default_anomaly_entropy_sample_files(),
+            high_entropy_percent: default_anomaly_high_entropy_percent(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum FsHardlinkPolicy {
@@ -235,6 +477,23 @@ pub struct FilesystemSource {
     pub include: Vec<String>,
     #[serde(default)]
     pub exclude: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_presets: Vec<FsExcludePreset>,
+    /// Honor `.bastionignore` files (`.gitignore` syntax) found in the source tree.
+    #[serde(default)]
+    pub use_ignore_files: bool,
+    /// Files larger than this are skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size_bytes: Option<u64>,
+    /// Files whose mtime is older than this many seconds are skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_older_than_secs: Option<u64>,
+    /// Files whose mtime is newer than this many seconds are skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_newer_than_secs: Option<u64>,
+    /// Compare each snapshot to the previous one and warn on ransomware-like changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anomaly_detection: Option<FsAnomalyDetection>,
     #[serde(default)]
     pub symlink_policy: FsSymlinkPolicy,
     #[serde(default)]
@@ -251,10 +510,46 @@ pub struct FilesystemSource {
     pub consistency_fail_threshold: Option<u64>,
     #[serde(default)]
     pub upload_on_consistency_failure: Option<bool>,
+    /// Windows only: also store exported registry hives, the installed-programs list and the
+    /// driver list under `bastion-system-state/` in the archive.
+    #[serde(default)]
+    pub windows_system_state: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SqliteSource {
+    /// Single database path (the original form). Combined with `databases` when both are set.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub path: String,
+    /// Integrity check toggle for `path`.
+    #[serde(default)]
+    pub integrity_check: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub databases: Vec<SqliteDatabaseV1>,
+}
+
+impl SqliteSource {
+    /// Databases backed up by the job: `path` (when set) followed by `databases`.
+    pub fn database_list(&self) -> Vec<SqliteDatabaseV1> {
+        let mut out = Vec::with_capacity(self.databases.len() + 1);
+        if !self.path.trim().is_empty() {
+            out.push(SqliteDatabaseV1 {
+                path: self.path.clone(),
+                integrity_check: self.integrity_check,
+            });
+        }
+        out.extend(
+            self.databases
+                .iter()
+                .filter(|db| !db.path.trim().is_empty())
+                .cloned(),
+        );
+        out
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SqliteDatabaseV1 {
     pub path: String,
     #[serde(default)]
     pub integrity_check: bool,
@@ -271,6 +566,224 @@ pub struct VaultwardenSource {
     pub upload_on_consistency_failure: Option<bool>,
 }
 
+/// An application stored as a data directory plus a SQLite database (Gitea, Nextcloud with
+/// SQLite, ...).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SqliteAppSource {
+    pub data_dir: String,
+    /// Database path relative to `data_dir`.
+    pub db_path: String,
+    #[serde(default)]
+    pub integrity_check: bool,
+    #[serde(default)]
+    pub consistency_policy: ConsistencyPolicyV1,
+    #[serde(default)]
+    pub consistency_fail_threshold: Option<u64>,
+    #[serde(default)]
+    pub upload_on_consistency_failure: Option<bool>,
+}
+
+/// A PostgreSQL cluster backed up as periodic base backups plus the WAL segments its
+/// `archive_command` copies into `wal_spool_dir`, so it can be restored to a point in time.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PostgresSource {
+    /// libpq connection string passed to `pg_basebackup -d`.
+    pub connection: String,
+    /// Directory the cluster's `archive_command` copies finished WAL segments into. Shipped
+    /// segments are removed from it after a successful upload.
+    pub wal_spool_dir: String,
+    /// A run takes a new base backup when the last one is older than this (default 24).
+    #[serde(default)]
+    pub base_backup_interval_hours: Option<u32>,
+}
+
+impl PostgresSource {
+    pub const DEFAULT_BASE_BACKUP_INTERVAL_HOURS: u32 = 24;
+
+    pub fn effective_base_backup_interval_hours(&self) -> u32 {
+        self.base_backup_interval_hours
+            .filter(|v| *v > 0)
+            .unwrap_or(Self::DEFAULT_BASE_BACKUP_INTERVAL_HOURS)
+    }
+}
+
+/// A libvirt domain whose file-backed disks (qcow2, raw, ...) are archived as images.
+///
+/// Running domains get a short-lived external disk-only snapshot: the original images are read
+/// while writes go to overlays, which are merged back with `blockcommit` afterwards.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct VmImageSource {
+    /// libvirt domain name or UUID.
+    pub domain: String,
+    /// Disk targets to include (for example `vda`); empty means every file-backed disk.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disks: Vec<String>,
+    /// Freeze guest filesystems through the QEMU guest agent while the snapshot is taken.
+    #[serde(default)]
+    pub quiesce: bool,
+    /// libvirt connection URI (default: `virsh`'s default, usually `qemu:///system` as root).
+    #[serde(default)]
+    pub connect_uri: Option<String>,
+}
+
+/// How the data of each matched PVC is read.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum KubernetesPvcModeV1 {
+    /// Each PVC is mounted into the node's pod at `<mount_root>/<pvc name>`.
+    #[default]
+    Mounted,
+    /// Each PVC is copied from a CSI `VolumeSnapshot` through a short-lived reader pod.
+    CsiSnapshot,
+}
+
+/// PersistentVolumeClaims selected by label, resolved when the run starts.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct KubernetesPvcSource {
+    /// Namespace of the PVCs (default: the namespace of the node's service account / context).
+    #[serde(default)]
+    pub namespace: Option<String>,
+    /// Label selector, as accepted by `kubectl get -l` (for example `app=gitea`).
+    pub label_selector: String,
+    #[serde(default)]
+    pub mode: KubernetesPvcModeV1,
+    /// `mounted` mode: directory containing one mount per PVC (default: `/pvc`).
+    #[serde(default)]
+    pub mount_root: Option<String>,
+    /// `csi_snapshot` mode: `VolumeSnapshotClass` to use (default: the cluster default).
+    #[serde(default)]
+    pub volume_snapshot_class: Option<String>,
+    /// `csi_snapshot` mode: image of the reader pod; it must provide `tar` and `sleep`.
+    #[serde(default)]
+    pub reader_image: Option<String>,
+}
+
+impl KubernetesPvcSource {
+    pub const DEFAULT_MOUNT_ROOT: &'static str = "/pvc";
+    pub const DEFAULT_READER_IMAGE: &'static str = "busybox:1.36";
+
+    pub fn effective_mount_root(&self) -> &str {
+        self.mount_root
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .unwrap_or(Self::DEFAULT_MOUNT_ROOT)
+    }
+
+    pub fn effective_reader_image(&self) -> &str {
+        self.reader_image
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .unwrap_or(Self::DEFAULT_READER_IMAGE)
+    }
+}
+
+/// How the IMAP connection is secured.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImapSecurityV1 {
+    /// TLS from the first byte (IMAPS, port 993).
+    #[default]
+    Tls,
+    /// Plain connection upgraded with `STARTTLS` (port 143).
+    Starttls,
+}
+
+/// IMAP mailboxes downloaded incrementally into a maildir-style tree.
+///
+/// Each run fetches only the messages above the UID recorded for a folder by the previous
+/// successful run; a changed `UIDVALIDITY` downloads the folder again.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImapSource {
+    pub host: String,
+    /// Default: 993 for `tls`, 143 for `starttls`.
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub security: ImapSecurityV1,
+    /// Name of the node's `imap` secret holding the account credentials.
+    pub secret_name: String,
+    /// Folder name globs to back up (for example `INBOX` or `Archive/*`); empty means all.
+    /// Names use `/` as the hierarchy separator, whatever the server's delimiter.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include_folders: Vec<String>,
+    /// Folder name globs to skip (for example `Trash` or `Junk`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_folders: Vec<String>,
+}
+
+impl ImapSource {
+    pub const DEFAULT_TLS_PORT: u16 = 993;
+    pub const DEFAULT_STARTTLS_PORT: u16 = 143;
+
+    pub fn effective_port(&self) -> u16 {
+        self.port.filter(|v| *v > 0).unwrap_or(match self.security {
+            ImapSecurityV1::Tls => Self::DEFAULT_TLS_PORT,
+            ImapSecurityV1::Starttls => Self::DEFAULT_STARTTLS_PORT,
+        })
+    }
+}
+
+/// Payload of an `imap` secret.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "auth", rename_all = "snake_case")]
+pub enum ImapCredentialsV1 {
+    /// `LOGIN` with a password (or an app password).
+    Password { username: String, password: String },
+    /// `AUTHENTICATE XOAUTH2` with an access token obtained from the refresh token at run time.
+    Oauth2 {
+        username: String,
+        token_url: String,
+        client_id: String,
+        #[serde(default)]
+        client_secret: Option<String>,
+        refresh_token: String,
+    },
+}
+
+impl ImapCredentialsV1 {
+    pub fn username(&self) -> &str {
+        match self {
+            ImapCredentialsV1::Password { username, .. }
+            | ImapCredentialsV1::Oauth2 { username, .. } => username,
+        }
+    }
+}
+
+/// One remote repository mirrored by a `git` job.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitRepositoryV1 {
+    /// Anything `git clone` accepts (`https://…`, `ssh://…`, `git@host:owner/repo.git`).
+    pub url: String,
+    /// Directory name of the bare mirror inside the artifact (default: derived from the URL).
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl GitRepositoryV1 {
+    /// `name`, or the last path segment of the URL without `.git`.
+    pub fn effective_name(&self) -> String {
+        if let Some(name) = self.name.as_deref().map(str::trim)
+            && !name.is_empty()
+        {
+            return name.to_string();
+        }
+        let url = self.url.trim().trim_end_matches('/');
+        let last = url.rsplit(['/', ':']).next().unwrap_or(url);
+        last.strip_suffix(".git").unwrap_or(last).to_string()
+    }
+}
+
+/// Remote git repositories kept as bare mirrors on the node and packaged on every run.
+///
+/// The mirrors persist between runs, so each run only fetches new objects. Authentication
+/// uses the node's own SSH keys and git credential helpers.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitSource {
+    pub repositories: Vec<GitRepositoryV1>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TargetV1 {
@@ -284,6 +797,9 @@ pub enum TargetV1 {
         base_dir: String,
         #[serde(default = "default_part_size_bytes")]
         part_size_bytes: u64,
+        /// Hardlink unchanged raw-tree files against the job's previous snapshot.
+        #[serde(default)]
+        link_unchanged: bool,
     },
 }
 
@@ -336,6 +852,72 @@ pub enum JobSpecV1 {
         source: VaultwardenSource,
         target: TargetV1,
     },
+    SqliteApp {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineV1,
+        #[serde(default)]
+        notifications: NotificationsV1,
+        #[serde(default)]
+        retention: RetentionPolicyV1,
+        source: SqliteAppSource,
+        target: TargetV1,
+    },
+    Postgres {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineV1,
+        #[serde(default)]
+        notifications: NotificationsV1,
+        #[serde(default)]
+        retention: RetentionPolicyV1,
+        source: PostgresSource,
+        target: TargetV1,
+    },
+    VmImage {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineV1,
+        #[serde(default)]
+        notifications: NotificationsV1,
+        #[serde(default)]
+        retention: RetentionPolicyV1,
+        source: VmImageSource,
+        target: TargetV1,
+    },
+    KubernetesPvc {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineV1,
+        #[serde(default)]
+        notifications: NotificationsV1,
+        #[serde(default)]
+        retention: RetentionPolicyV1,
+        source: KubernetesPvcSource,
+        target: TargetV1,
+    },
+    Imap {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineV1,
+        #[serde(default)]
+        notifications: NotificationsV1,
+        #[serde(default)]
+        retention: RetentionPolicyV1,
+        source: ImapSource,
+        target: TargetV1,
+    },
+    Git {
+        v: u32,
+        #[serde(default)]
+        pipeline: PipelineV1,
+        #[serde(default)]
+        notifications: NotificationsV1,
+        #[serde(default)]
+        retention: RetentionPolicyV1,
+        source: GitSource,
+        target: TargetV1,
+    },
 }
 
 impl JobSpecV1 {
@@ -344,6 +926,12 @@ impl JobSpecV1 {
             JobSpecV1::Filesystem { notifications, .. } => notifications,
             JobSpecV1::Sqlite { notifications, .. } => notifications,
             JobSpecV1::Vaultwarden { notifications, .. } => notifications,
+            JobSpecV1::SqliteApp { notifications, .. } => notifications,
+            JobSpecV1::Postgres { notifications, .. } => notifications,
+            JobSpecV1::VmImage { notifications, .. } => notifications,
+            JobSpecV1::KubernetesPvc { notifications, .. } => notifications,
+            JobSpecV1::Imap { notifications, .. } => notifications,
+            JobSpecV1::Git { notifications, .. } => notifications,
         }
     }
 
@@ -352,6 +940,12 @@ impl JobSpecV1 {
             JobSpecV1::Filesystem { retention, .. } => retention,
             JobSpecV1::Sqlite { retention, .. } => retention,
             JobSpecV1::Vaultwarden { retention, .. } => retention,
+            JobSpecV1::SqliteApp { retention, .. } => retention,
+            JobSpecV1::Postgres { retention, .. } => retention,
+            JobSpecV1::VmImage { retention, .. } => retention,
+            JobSpecV1::KubernetesPvc { retention, .. } => retention,
+            JobSpecV1::Imap { retention, .. } => retention,
+            JobSpecV1::Git { retention, .. } => retention,
         }
     }
 }
@@ -370,6 +964,11 @@ mod tests {
         assert_eq!(src.root, "");
         assert!(src.include.is_empty());
         assert!(src.exclude.is_empty());
+        assert!(src.exclude_presets.is_empty());
+        assert!(!src.use_ignore_files);
+        assert_eq!(src.max_file_size_bytes, None);
+        assert_eq!(src.skip_older_than_secs, None);
+        assert_eq!(src.skip_newer_than_secs, None);
         assert_eq!(src.symlink_policy, FsSymlinkPolicy::Keep);
         assert_eq!(src.hardlink_policy, FsHardlinkPolicy::Copy);
         assert_eq!(src.error_policy, FsErrorPolicy::FailFast);
@@ -415,6 +1014,23 @@ mod tests {
         assert_eq!(n.mode, NotificationsModeV1::Inherit);
         assert!(n.wecom_bot.is_empty());
         assert!(n.email.is_empty());
+        assert!(n.slack.is_empty());
+        assert!(n.discord.is_empty());
+        assert!(n.sms.is_empty());
+        assert!(n.notify_on.is_empty());
+        assert!(n.ping.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn notify_on_rules_parse_snake_case() -> Result<(), anyhow::Error> {
+        let n: NotificationsV1 = serde_json::from_value(serde_json::json!({
+            "notify_on": ["first_failure", "recovery"]
+        }))?;
+        assert_eq!(
+            n.notify_on,
+            vec![NotifyOnV1::FirstFailure, NotifyOnV1::Recovery]
+        );
         Ok(())
     }
 
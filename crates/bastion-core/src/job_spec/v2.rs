@@ -4,8 +4,9 @@ use anyhow::Context;
 use serde::{Deserialize, Serialize};
 
 use super::types::{
-    FilesystemSource, JobSpecV1, NotificationsV1, PipelineV1, RetentionPolicyV1, SqliteSource,
-    TargetV1, VaultwardenSource,
+    FilesystemSource, GitSource, ImapSource, JobSpecV1, KubernetesPvcSource, NotificationsV1,
+    PipelineV1, PostgresSource, RetentionPolicyV1, SqliteAppSource, SqliteSource, TargetV1,
+    VaultwardenSource, VmImageSource,
 };
 
 pub const JOB_SPEC_VERSION_V2: u32 = 2;
@@ -13,6 +14,12 @@ pub const JOB_SPEC_VERSION_V2: u32 = 2;
 pub const SOURCE_KIND_FILESYSTEM: &str = "filesystem";
 pub const SOURCE_KIND_SQLITE: &str = "sqlite";
 pub const SOURCE_KIND_VAULTWARDEN: &str = "vaultwarden";
+pub const SOURCE_KIND_SQLITE_APP: &str = "sqlite_app";
+pub const SOURCE_KIND_POSTGRES: &str = "postgres";
+pub const SOURCE_KIND_VM_IMAGE: &str = "vm_image";
+pub const SOURCE_KIND_KUBERNETES_PVC: &str = "kubernetes_pvc";
+pub const SOURCE_KIND_IMAP: &str = "imap";
+pub const SOURCE_KIND_GIT: &str = "git";
 
 pub const TARGET_KIND_WEBDAV: &str = "webdav";
 pub const TARGET_KIND_LOCAL_DIR: &str = "local_dir";
@@ -85,6 +92,8 @@ struct LocalDirTargetConfigV2 {
     base_dir: String,
     #[serde(default = "default_target_part_size_bytes")]
     part_size_bytes: u64,
+    #[serde(default)]
+    link_unchanged: bool,
 }
 
 pub fn parse_canonical_value(spec: &serde_json::Value) -> Result<JobSpecV2, anyhow::Error> {
@@ -174,6 +183,126 @@ pub fn translate_v1_to_v2(spec: &JobSpecV1) -> Result<JobSpecV2, anyhow::Error>
             },
             target: translate_target_v1_to_v2(target)?,
         }),
+        JobSpecV1::SqliteApp {
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+            ..
+        } => Ok(JobSpecV2 {
+            v: JOB_SPEC_VERSION_V2,
+            pipeline: pipeline.clone(),
+            notifications: notifications.clone(),
+            retention: retention.clone(),
+            source: SourceEnvelopeV2 {
+                driver_type: SOURCE_KIND_SQLITE_APP.to_string(),
+                version: 1,
+                config: serde_json::to_value(source)
+                    .context("failed to encode sqlite_app source config")?,
+            },
+            target: translate_target_v1_to_v2(target)?,
+        }),
+        JobSpecV1::Postgres {
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+            ..
+        } => Ok(JobSpecV2 {
+            v: JOB_SPEC_VERSION_V2,
+            pipeline: pipeline.clone(),
+            notifications: notifications.clone(),
+            retention: retention.clone(),
+            source: SourceEnvelopeV2 {
+                driver_type: SOURCE_KIND_POSTGRES.to_string(),
+                version: 1,
+                config: serde_json::to_value(source)
+                    .context("failed to encode postgres source config")?,
+            },
+            target: translate_target_v1_to_v2(target)?,
+        }),
+        JobSpecV1::VmImage {
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+            ..
+        } => Ok(JobSpecV2 {
+            v: JOB_SPEC_VERSION_V2,
+            pipeline: pipeline.clone(),
+            notifications: notifications.clone(),
+            retention: retention.clone(),
+            source: SourceEnvelopeV2 {
+                driver_type: SOURCE_KIND_VM_IMAGE.to_string(),
+                version: 1,
+                config: serde_json::to_value(source)
+                    .context("failed to encode vm_image source config")?,
+            },
+            target: translate_target_v1_to_v2(target)?,
+        }),
+        JobSpecV1::KubernetesPvc {
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+            ..
+        } => Ok(JobSpecV2 {
+            v: JOB_SPEC_VERSION_V2,
+            pipeline: pipeline.clone(),
+            notifications: notifications.clone(),
+            retention: retention.clone(),
+            source: SourceEnvelopeV2 {
+                driver_type: SOURCE_KIND_KUBERNETES_PVC.to_string(),
+                version: 1,
+                config: serde_json::to_value(source)
+                    .context("failed to encode kubernetes_pvc source config")?,
+            },
+            target: translate_target_v1_to_v2(target)?,
+        }),
+        JobSpecV1::Imap {
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+            ..
+        } => Ok(JobSpecV2 {
+            v: JOB_SPEC_VERSION_V2,
+            pipeline: pipeline.clone(),
+            notifications: notifications.clone(),
+            retention: retention.clone(),
+            source: SourceEnvelopeV2 {
+                driver_type: SOURCE_KIND_IMAP.to_string(),
+                version: 1,
+                config: serde_json::to_value(source)
+                    .context("failed to encode imap source config")?,
+            },
+            target: translate_target_v1_to_v2(target)?,
+        }),
+        JobSpecV1::Git {
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+            ..
+        } => Ok(JobSpecV2 {
+            v: JOB_SPEC_VERSION_V2,
+            pipeline: pipeline.clone(),
+            notifications: notifications.clone(),
+            retention: retention.clone(),
+            source: SourceEnvelopeV2 {
+                driver_type: SOURCE_KIND_GIT.to_string(),
+                version: 1,
+                config: serde_json::to_value(source)
+                    .context("failed to encode git source config")?,
+            },
+            target: translate_target_v1_to_v2(target)?,
+        }),
     }
 }
 
@@ -206,12 +335,14 @@ fn translate_target_v1_to_v2(target: &TargetV1) -> Result<TargetEnvelopeV2, anyh
         TargetV1::LocalDir {
             base_dir,
             part_size_bytes,
+            link_unchanged,
         } => Ok(TargetEnvelopeV2 {
             driver_type: TARGET_KIND_LOCAL_DIR.to_string(),
             version: 1,
             config: serde_json::json!({
                 "base_dir": base_dir,
                 "part_size_bytes": part_size_bytes,
+                "link_unchanged": link_unchanged,
             }),
             auth_refs: BTreeMap::new(),
         }),
@@ -251,6 +382,54 @@ pub fn translate_v2_to_v1(spec: &JobSpecV2) -> Result<JobSpecV1, anyhow::Error>
             source,
             target,
         }),
+        SourceConfigV1::SqliteApp(source) => Ok(JobSpecV1::SqliteApp {
+            v: 1,
+            pipeline: spec.pipeline.clone(),
+            notifications: spec.notifications.clone(),
+            retention: spec.retention.clone(),
+            source,
+            target,
+        }),
+        SourceConfigV1::Postgres(source) => Ok(JobSpecV1::Postgres {
+            v: 1,
+            pipeline: spec.pipeline.clone(),
+            notifications: spec.notifications.clone(),
+            retention: spec.retention.clone(),
+            source,
+            target,
+        }),
+        SourceConfigV1::VmImage(source) => Ok(JobSpecV1::VmImage {
+            v: 1,
+            pipeline: spec.pipeline.clone(),
+            notifications: spec.notifications.clone(),
+            retention: spec.retention.clone(),
+            source,
+            target,
+        }),
+        SourceConfigV1::KubernetesPvc(source) => Ok(JobSpecV1::KubernetesPvc {
+            v: 1,
+            pipeline: spec.pipeline.clone(),
+            notifications: spec.notifications.clone(),
+            retention: spec.retention.clone(),
+            source,
+            target,
+        }),
+        SourceConfigV1::Imap(source) => Ok(JobSpecV1::Imap {
+            v: 1,
+            pipeline: spec.pipeline.clone(),
+            notifications: spec.notifications.clone(),
+            retention: spec.retention.clone(),
+            source,
+            target,
+        }),
+        SourceConfigV1::Git(source) => Ok(JobSpecV1::Git {
+            v: 1,
+            pipeline: spec.pipeline.clone(),
+            notifications: spec.notifications.clone(),
+            retention: spec.retention.clone(),
+            source,
+            target,
+        }),
     }
 }
 
@@ -258,6 +437,12 @@ enum SourceConfigV1 {
     Filesystem(FilesystemSource),
     Sqlite(SqliteSource),
     Vaultwarden(VaultwardenSource),
+    SqliteApp(SqliteAppSource),
+    Postgres(PostgresSource),
+    VmImage(VmImageSource),
+    KubernetesPvc(KubernetesPvcSource),
+    Imap(ImapSource),
+    Git(GitSource),
 }
 
 fn translate_source_v2_to_v1(source: &SourceEnvelopeV2) -> Result<SourceConfigV1, anyhow::Error> {
@@ -284,6 +469,36 @@ fn translate_source_v2_to_v1(source: &SourceEnvelopeV2) -> Result<SourceConfigV1
                 .context("invalid vaultwarden source config")?;
             Ok(SourceConfigV1::Vaultwarden(config))
         }
+        (SOURCE_KIND_SQLITE_APP, 1) => {
+            let config = serde_json::from_value::<SqliteAppSource>(source.config.clone())
+                .context("invalid sqlite_app source config")?;
+            Ok(SourceConfigV1::SqliteApp(config))
+        }
+        (SOURCE_KIND_POSTGRES, 1) => {
+            let config = serde_json::from_value::<PostgresSource>(source.config.clone())
+                .context("invalid postgres source config")?;
+            Ok(SourceConfigV1::Postgres(config))
+        }
+        (SOURCE_KIND_VM_IMAGE, 1) => {
+            let config = serde_json::from_value::<VmImageSource>(source.config.clone())
+                .context("invalid vm_image source config")?;
+            Ok(SourceConfigV1::VmImage(config))
+        }
+        (SOURCE_KIND_KUBERNETES_PVC, 1) => {
+            let config = serde_json::from_value::<KubernetesPvcSource>(source.config.clone())
+                .context("invalid kubernetes_pvc source config")?;
+            Ok(SourceConfigV1::KubernetesPvc(config))
+        }
+        (SOURCE_KIND_IMAP, 1) => {
+            let config = serde_json::from_value::<ImapSource>(source.config.clone())
+                .context("invalid imap source config")?;
+            Ok(SourceConfigV1::Imap(config))
+        }
+        (SOURCE_KIND_GIT, 1) => {
+            let config = serde_json::from_value::<GitSource>(source.config.clone())
+                .context("invalid git source config")?;
+            Ok(SourceConfigV1::Git(config))
+        }
         (kind, version) => anyhow::bail!("unsupported source driver: {kind}@{version}"),
     }
 }
@@ -383,6 +598,7 @@ fn translate_local_dir_target_v2_to_v1(
     Ok(TargetV1::LocalDir {
         base_dir: config.base_dir,
         part_size_bytes: config.part_size_bytes,
+        link_unchanged: config.link_unchanged,
     })
 }
 
@@ -403,6 +619,12 @@ mod tests {
                 root: "".to_string(),
                 include: vec![],
                 exclude: vec![],
+                exclude_presets: Vec::new(),
+                use_ignore_files: false,
+                max_file_size_bytes: None,
+                skip_older_than_secs: None,
+                skip_newer_than_secs: None,
+                anomaly_detection: None,
                 symlink_policy: Default::default(),
                 hardlink_policy: Default::default(),
                 error_policy: Default::default(),
@@ -411,6 +633,7 @@ mod tests {
                 consistency_policy: Default::default(),
                 consistency_fail_threshold: None,
                 upload_on_consistency_failure: None,
+                windows_system_state: false,
             },
             target: TargetV1::Webdav {
                 base_url: "https://example.invalid/backup".to_string(),
@@ -530,4 +753,28 @@ mod tests {
         assert_eq!(canonical.source.driver_type, SOURCE_KIND_VAULTWARDEN);
         assert_eq!(canonical.target.driver_type, TARGET_KIND_LOCAL_DIR);
     }
+
+    #[test]
+    fn local_dir_link_unchanged_round_trips_through_v2() {
+        let legacy = serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "root": "/" },
+            "target": { "type": "local_dir", "base_dir": "/tmp/out", "link_unchanged": true }
+        });
+
+        let canonical = parse_canonical_value(&legacy).expect("parse");
+        assert_eq!(canonical.target.config["link_unchanged"], true);
+
+        let payload = serde_json::to_value(&canonical).expect("serialize");
+        let reparsed = parse_canonical_value(&payload).expect("reparse");
+        let translated = translate_v2_to_v1(&reparsed).expect("translate");
+        let JobSpecV1::Filesystem { target, .. } = translated else {
+            panic!("expected filesystem spec");
+        };
+        let TargetV1::LocalDir { link_unchanged, .. } = target else {
+            panic!("expected local_dir target");
+        };
+        assert!(link_unchanged);
+    }
 }
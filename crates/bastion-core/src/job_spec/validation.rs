@@ -3,8 +3,11 @@ use url::Url;
 
 use super::JOB_SPEC_VERSION;
 use super::types::{
-    EncryptionV1, FilesystemSource, JobSpecV1, NotificationsModeV1, NotificationsV1, PipelineV1,
-    RetentionPolicyV1, TargetV1, VaultwardenSource, WebdavRawTreeDirectModeV1,
+    CompressionAlgorithmV1, CompressionV1, EncryptionV1, FilesystemSource, GitSource, ImapSource,
+    JobSpecV1, KubernetesPvcModeV1, KubernetesPvcSource, NotificationsModeV1, NotificationsV1,
+    PayloadStagingV1, PipelineV1, PipelineWebdavV1, PostgresSource, RetentionPolicyV1,
+    SqliteAppSource, SqliteSource, TargetV1, VaultwardenSource, VmImageSource,
+    WebdavRawTreeDirectModeV1,
 };
 use super::v2::{self, JOB_SPEC_VERSION_V2, JobSpecV2};
 use crate::manifest::ArtifactFormatV1;
@@ -41,6 +44,9 @@ pub fn validate(spec: &JobSpecV1) -> Result<(), anyhow::Error> {
             validate_version(*v)?;
             validate_pipeline(pipeline)?;
             validate_webdav_raw_tree_direct_filesystem(pipeline, source, target)?;
+            if source.windows_system_state && pipeline.format != ArtifactFormatV1::ArchiveV1 {
+                anyhow::bail!("windows_system_state requires the archive_v1 artifact format");
+            }
             validate_notifications(notifications)?;
             validate_retention(retention)?;
             validate_filesystem_source(source)?;
@@ -59,9 +65,7 @@ pub fn validate(spec: &JobSpecV1) -> Result<(), anyhow::Error> {
             validate_webdav_raw_tree_direct_unsupported("sqlite", pipeline)?;
             validate_notifications(notifications)?;
             validate_retention(retention)?;
-            if source.path.trim().is_empty() {
-                anyhow::bail!("sqlite.source.path is required");
-            }
+            validate_sqlite_source(source)?;
             validate_target(target)?;
         }
         JobSpecV1::Vaultwarden {
@@ -80,6 +84,112 @@ pub fn validate(spec: &JobSpecV1) -> Result<(), anyhow::Error> {
             validate_vaultwarden_source(source)?;
             validate_target(target)?;
         }
+        JobSpecV1::SqliteApp {
+            v,
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+        } => {
+            validate_version(*v)?;
+            validate_pipeline(pipeline)?;
+            validate_webdav_raw_tree_direct_unsupported("sqlite_app", pipeline)?;
+            validate_notifications(notifications)?;
+            validate_retention(retention)?;
+            validate_sqlite_app_source(source)?;
+            validate_target(target)?;
+        }
+        JobSpecV1::Postgres {
+            v,
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+        } => {
+            validate_version(*v)?;
+            validate_pipeline(pipeline)?;
+            if pipeline.format != ArtifactFormatV1::ArchiveV1 {
+                anyhow::bail!("postgres jobs support only the archive_v1 artifact format");
+            }
+            validate_notifications(notifications)?;
+            validate_retention(retention)?;
+            validate_postgres_source(source)?;
+            validate_target(target)?;
+        }
+        JobSpecV1::VmImage {
+            v,
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+        } => {
+            validate_version(*v)?;
+            validate_pipeline(pipeline)?;
+            if pipeline.format != ArtifactFormatV1::ArchiveV1 {
+                anyhow::bail!("vm_image jobs support only the archive_v1 artifact format");
+            }
+            validate_notifications(notifications)?;
+            validate_retention(retention)?;
+            validate_vm_image_source(source)?;
+            validate_target(target)?;
+        }
+        JobSpecV1::KubernetesPvc {
+            v,
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+        } => {
+            validate_version(*v)?;
+            validate_pipeline(pipeline)?;
+            if pipeline.format != ArtifactFormatV1::ArchiveV1 {
+                anyhow::bail!("kubernetes_pvc jobs support only the archive_v1 artifact format");
+            }
+            validate_notifications(notifications)?;
+            validate_retention(retention)?;
+            validate_kubernetes_pvc_source(source)?;
+            validate_target(target)?;
+        }
+        JobSpecV1::Imap {
+            v,
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+        } => {
+            validate_version(*v)?;
+            validate_pipeline(pipeline)?;
+            if pipeline.format != ArtifactFormatV1::RawTreeV1 {
+                anyhow::bail!("imap jobs support only the raw_tree_v1 artifact format");
+            }
+            validate_notifications(notifications)?;
+            validate_retention(retention)?;
+            validate_imap_source(source)?;
+            validate_target(target)?;
+        }
+        JobSpecV1::Git {
+            v,
+            pipeline,
+            notifications,
+            retention,
+            source,
+            target,
+        } => {
+            validate_version(*v)?;
+            validate_pipeline(pipeline)?;
+            if pipeline.format != ArtifactFormatV1::ArchiveV1 {
+                anyhow::bail!("git jobs support only the archive_v1 artifact format");
+            }
+            validate_notifications(notifications)?;
+            validate_retention(retention)?;
+            validate_git_source(source)?;
+            validate_target(target)?;
+        }
     }
 
     Ok(())
@@ -130,10 +240,46 @@ fn validate_pipeline(pipeline: &PipelineV1) -> Result<(), anyhow::Error> {
     {
         anyhow::bail!("pipeline.encryption is not supported when pipeline.format is raw_tree_v1");
     }
+    if pipeline.format == ArtifactFormatV1::RawTreeV1
+        && pipeline.compression != CompressionV1::default()
+    {
+        anyhow::bail!("pipeline.compression is not supported when pipeline.format is raw_tree_v1");
+    }
+
+    let compression = &pipeline.compression;
+    match (compression.algorithm.level_range(), compression.level) {
+        (None, Some(_)) => anyhow::bail!(
+            "pipeline.compression.level is not supported for {}",
+            compression.algorithm.as_str()
+        ),
+        (Some(range), Some(level)) if !range.contains(&level) => anyhow::bail!(
+            "pipeline.compression.level must be between {} and {} for {}",
+            range.start(),
+            range.end(),
+            compression.algorithm.as_str()
+        ),
+        _ => {}
+    }
+    if compression.adaptive && compression.algorithm != CompressionAlgorithmV1::Zstd {
+        anyhow::bail!("pipeline.compression.adaptive requires zstd");
+    }
+    if pipeline.staging == PayloadStagingV1::Stream
+        && pipeline.format != ArtifactFormatV1::ArchiveV1
+    {
+        anyhow::bail!("pipeline.staging=stream requires pipeline.format=archive_v1");
+    }
+    if let Some(concurrency) = pipeline.webdav.part_upload_concurrency
+        && !(1..=PipelineWebdavV1::MAX_PART_UPLOAD_CONCURRENCY).contains(&concurrency)
+    {
+        anyhow::bail!(
+            "pipeline.webdav.part_upload_concurrency must be between 1 and {}",
+            PipelineWebdavV1::MAX_PART_UPLOAD_CONCURRENCY
+        );
+    }
 
     match &pipeline.encryption {
         EncryptionV1::None => {}
-        EncryptionV1::AgeX25519 { key_name } => {
+        EncryptionV1::AgeX25519 { key_name } | EncryptionV1::AgePassphrase { key_name } => {
             if key_name.trim().is_empty() {
                 anyhow::bail!("pipeline.encryption.key_name is required");
             }
@@ -247,12 +393,31 @@ fn validate_notifications(notifications: &NotificationsV1) -> Result<(), anyhow:
             .wecom_bot
             .iter()
             .chain(notifications.email.iter())
+            .chain(notifications.slack.iter())
+            .chain(notifications.discord.iter())
+            .chain(notifications.sms.iter())
         {
             if name.trim().is_empty() {
                 anyhow::bail!("notifications destination name is required");
             }
         }
     }
+
+    let ping = &notifications.ping;
+    for (field, value) in [
+        ("start_url", &ping.start_url),
+        ("success_url", &ping.success_url),
+        ("failure_url", &ping.failure_url),
+    ] {
+        let Some(value) = value else {
+            continue;
+        };
+        let url = Url::parse(value.trim())
+            .map_err(|_| anyhow::anyhow!("notifications.ping.{field} is not a valid url"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            anyhow::bail!("notifications.ping.{field} must be http(s)");
+        }
+    }
     Ok(())
 }
 
@@ -278,6 +443,24 @@ fn validate_filesystem_source(source: &FilesystemSource) -> Result<(), anyhow::E
     }
     validate_globs(&source.include)?;
     validate_globs(&source.exclude)?;
+    if source.max_file_size_bytes == Some(0) {
+        anyhow::bail!("filesystem.source.max_file_size_bytes must be > 0");
+    }
+    if let (Some(older), Some(newer)) = (source.skip_older_than_secs, source.skip_newer_than_secs)
+        && newer >= older
+    {
+        anyhow::bail!(
+            "filesystem.source.skip_newer_than_secs must be less than skip_older_than_secs"
+        );
+    }
+    if let Some(anomaly) = source.anomaly_detection
+        && (anomaly.changed_percent == 0
+            || anomaly.changed_percent > 100
+            || anomaly.high_entropy_percent == 0
+            || anomaly.high_entropy_percent > 100)
+    {
+        anyhow::bail!("filesystem.source.anomaly_detection percentages must be within 1..=100");
+    }
     Ok(())
 }
 
@@ -288,6 +471,130 @@ fn validate_vaultwarden_source(source: &VaultwardenSource) -> Result<(), anyhow:
     Ok(())
 }
 
+fn validate_sqlite_source(source: &SqliteSource) -> Result<(), anyhow::Error> {
+    let databases = source.database_list();
+    if databases.is_empty() {
+        anyhow::bail!("sqlite.source.path is required");
+    }
+    let mut seen = std::collections::HashSet::new();
+    for db in &databases {
+        if !seen.insert(db.path.trim()) {
+            anyhow::bail!(
+                "sqlite.source.databases contains a duplicate path: {}",
+                db.path.trim()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn validate_sqlite_app_source(source: &SqliteAppSource) -> Result<(), anyhow::Error> {
+    if source.data_dir.trim().is_empty() {
+        anyhow::bail!("sqlite_app.source.data_dir is required");
+    }
+    let db_path = source.db_path.trim();
+    if db_path.is_empty() {
+        anyhow::bail!("sqlite_app.source.db_path is required");
+    }
+    let path = std::path::Path::new(db_path);
+    if path.is_absolute()
+        || db_path.starts_with('/')
+        || db_path.starts_with('\\')
+        || path
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        anyhow::bail!("sqlite_app.source.db_path must be a relative path inside data_dir");
+    }
+    Ok(())
+}
+
+fn validate_postgres_source(source: &PostgresSource) -> Result<(), anyhow::Error> {
+    if source.connection.trim().is_empty() {
+        anyhow::bail!("postgres.source.connection is required");
+    }
+    if source.wal_spool_dir.trim().is_empty() {
+        anyhow::bail!("postgres.source.wal_spool_dir is required");
+    }
+    Ok(())
+}
+
+fn validate_vm_image_source(source: &VmImageSource) -> Result<(), anyhow::Error> {
+    if source.domain.trim().is_empty() {
+        anyhow::bail!("vm_image.source.domain is required");
+    }
+    let mut seen = std::collections::HashSet::new();
+    for disk in &source.disks {
+        let disk = disk.trim();
+        if disk.is_empty() || disk.contains(['/', '\\', ',']) {
+            anyhow::bail!("vm_image.source.disks contains an invalid disk target: {disk:?}");
+        }
+        if !seen.insert(disk) {
+            anyhow::bail!("vm_image.source.disks lists {disk} more than once");
+        }
+    }
+    Ok(())
+}
+
+fn validate_kubernetes_pvc_source(source: &KubernetesPvcSource) -> Result<(), anyhow::Error> {
+    if source.label_selector.trim().is_empty() {
+        anyhow::bail!("kubernetes_pvc.source.label_selector is required");
+    }
+    if source.mode == KubernetesPvcModeV1::Mounted
+        && !std::path::Path::new(source.effective_mount_root()).is_absolute()
+    {
+        anyhow::bail!("kubernetes_pvc.source.mount_root must be an absolute path");
+    }
+    Ok(())
+}
+
+fn validate_imap_source(source: &ImapSource) -> Result<(), anyhow::Error> {
+    if source.host.trim().is_empty() {
+        anyhow::bail!("imap.source.host is required");
+    }
+    if source.secret_name.trim().is_empty() {
+        anyhow::bail!("imap.source.secret_name is required");
+    }
+    for pattern in source.include_folders.iter().chain(&source.exclude_folders) {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            anyhow::bail!("imap.source folder patterns must not be empty");
+        }
+        Glob::new(pattern)
+            .map_err(|error| anyhow::anyhow!("invalid imap folder pattern {pattern:?}: {error}"))?;
+    }
+    Ok(())
+}
+
+fn validate_git_source(source: &GitSource) -> Result<(), anyhow::Error> {
+    if source.repositories.is_empty() {
+        anyhow::bail!("git.source.repositories must not be empty");
+    }
+    let mut names = std::collections::HashSet::new();
+    for repo in &source.repositories {
+        if repo.url.trim().is_empty() {
+            anyhow::bail!("git.source.repositories[].url is required");
+        }
+        let name = repo.effective_name();
+        if name.is_empty()
+            || name == "."
+            || name == ".."
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            anyhow::bail!(
+                "invalid git repository name {name:?} for {}: use letters, digits, '-', '_' or '.'",
+                repo.url.trim()
+            );
+        }
+        if !names.insert(name.clone()) {
+            anyhow::bail!("duplicate git repository name {name:?}; set an explicit name");
+        }
+    }
+    Ok(())
+}
+
 fn validate_target(target: &TargetV1) -> Result<(), anyhow::Error> {
     match target {
         TargetV1::Webdav {
@@ -312,6 +619,7 @@ fn validate_target(target: &TargetV1) -> Result<(), anyhow::Error> {
         TargetV1::LocalDir {
             base_dir,
             part_size_bytes,
+            ..
         } => {
             if base_dir.trim().is_empty() {
                 anyhow::bail!("target.base_dir is required");
@@ -347,6 +655,322 @@ mod tests {
         );
     }
 
+    #[test]
+    fn streaming_staging_requires_archive_format() {
+        let spec = serde_json::json!({
+          "v": 1,
+          "type": "filesystem",
+          "pipeline": { "format": "raw_tree_v1", "staging": "stream" },
+          "source": { "paths": ["/"] },
+          "target": { "type": "local_dir", "base_dir": "/tmp" }
+        });
+        let err = validate_value(&spec).expect_err("invalid");
+        assert!(
+            err.to_string().contains("pipeline.staging"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn filesystem_age_window_must_not_be_empty() {
+        let spec = serde_json::json!({
+          "v": 1,
+          "type": "filesystem",
+          "source": {
+            "paths": ["/"],
+            "exclude_presets": ["node_modules"],
+            "skip_older_than_secs": 86400,
+            "skip_newer_than_secs": 86400
+          },
+          "target": { "type": "local_dir", "base_dir": "/tmp" }
+        });
+        let err = validate_value(&spec).expect_err("invalid");
+        assert!(
+            err.to_string().contains("skip_newer_than_secs"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn filesystem_anomaly_percentages_are_bounded() {
+        let spec = serde_json::json!({
+          "v": 1,
+          "type": "filesystem",
+          "source": {
+            "paths": ["/"],
+            "anomaly_detection": { "changed_percent": 150 }
+          },
+          "target": { "type": "local_dir", "base_dir": "/tmp" }
+        });
+        let err = validate_value(&spec).expect_err("invalid");
+        assert!(
+            err.to_string().contains("anomaly_detection"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn sqlite_app_db_path_must_stay_inside_data_dir() {
+        let spec = |db_path: &str| {
+            serde_json::json!({
+              "v": 1,
+              "type": "sqlite_app",
+              "source": { "data_dir": "/var/lib/gitea", "db_path": db_path },
+              "target": { "type": "local_dir", "base_dir": "/tmp" }
+            })
+        };
+        validate_value(&spec("data/gitea.db")).expect("valid");
+        for bad in [
+            "",
+            "/var/lib/gitea/data/gitea.db",
+            "../gitea.db",
+            "data/../../x.db",
+        ] {
+            let err = validate_value(&spec(bad)).expect_err("invalid");
+            assert!(
+                err.to_string().contains("sqlite_app.source.db_path"),
+                "unexpected error: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn sqlite_accepts_a_database_list_without_duplicates() {
+        let spec = |source: serde_json::Value| {
+            serde_json::json!({
+              "v": 1,
+              "type": "sqlite",
+              "source": source,
+              "target": { "type": "local_dir", "base_dir": "/tmp" }
+            })
+        };
+        validate_value(&spec(serde_json::json!({
+            "databases": [
+                { "path": "/srv/app/main.db", "integrity_check": true },
+                { "path": "/srv/app/cache.db" }
+            ]
+        })))
+        .expect("valid");
+
+        let err =
+            validate_value(&spec(serde_json::json!({ "databases": [] }))).expect_err("invalid");
+        assert!(
+            err.to_string().contains("sqlite.source.path"),
+            "unexpected error: {err}"
+        );
+
+        let err = validate_value(&spec(serde_json::json!({
+            "path": "/srv/app/main.db",
+            "databases": [{ "path": "/srv/app/main.db" }]
+        })))
+        .expect_err("invalid");
+        assert!(
+            err.to_string().contains("duplicate"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn postgres_requires_connection_spool_dir_and_archive_format() {
+        let spec = |source: serde_json::Value, format: &str| {
+            serde_json::json!({
+              "v": 1,
+              "type": "postgres",
+              "pipeline": { "format": format },
+              "source": source,
+              "target": { "type": "local_dir", "base_dir": "/tmp" }
+            })
+        };
+        let source = serde_json::json!({
+            "connection": "host=/var/run/postgresql user=postgres",
+            "wal_spool_dir": "/var/lib/postgresql/wal-spool"
+        });
+        validate_value(&spec(source.clone(), "archive_v1")).expect("valid");
+
+        let err = validate_value(&spec(source, "raw_tree_v1")).expect_err("invalid");
+        assert!(
+            err.to_string().contains("archive_v1"),
+            "unexpected error: {err}"
+        );
+
+        let err = validate_value(&spec(
+            serde_json::json!({ "connection": "host=db", "wal_spool_dir": " " }),
+            "archive_v1",
+        ))
+        .expect_err("invalid");
+        assert!(
+            err.to_string().contains("postgres.source.wal_spool_dir"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn vm_image_requires_domain_and_distinct_disk_targets() {
+        let spec = |source: serde_json::Value| {
+            serde_json::json!({
+              "v": 1,
+              "type": "vm_image",
+              "source": source,
+              "target": { "type": "local_dir", "base_dir": "/tmp" }
+            })
+        };
+        validate_value(&spec(
+            serde_json::json!({ "domain": "web01", "disks": ["vda", "vdb"] }),
+        ))
+        .expect("valid");
+
+        let err = validate_value(&spec(serde_json::json!({ "domain": " " }))).expect_err("invalid");
+        assert!(
+            err.to_string().contains("vm_image.source.domain"),
+            "unexpected error: {err}"
+        );
+
+        let err = validate_value(&spec(
+            serde_json::json!({ "domain": "web01", "disks": ["vda", "vda"] }),
+        ))
+        .expect_err("invalid");
+        assert!(
+            err.to_string().contains("more than once"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn kubernetes_pvc_requires_label_selector_and_absolute_mount_root() {
+        let spec = |source: serde_json::Value| {
+            serde_json::json!({
+              "v": 1,
+              "type": "kubernetes_pvc",
+              "source": source,
+              "target": { "type": "local_dir", "base_dir": "/tmp" }
+            })
+        };
+        validate_value(&spec(serde_json::json!({ "label_selector": "app=gitea" }))).expect("valid");
+        validate_value(&spec(serde_json::json!({
+          "label_selector": "app=gitea",
+          "mode": "csi_snapshot",
+          "mount_root": "relative"
+        })))
+        .expect("mount_root is ignored for csi_snapshot");
+
+        let err = validate_value(&spec(serde_json::json!({ "label_selector": "" })))
+            .expect_err("invalid");
+        assert!(
+            err.to_string().contains("label_selector"),
+            "unexpected error: {err}"
+        );
+
+        let err = validate_value(&spec(serde_json::json!({
+          "label_selector": "app=gitea",
+          "mount_root": "pvc"
+        })))
+        .expect_err("invalid");
+        assert!(
+            err.to_string().contains("absolute"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn imap_requires_raw_tree_format_and_valid_folder_patterns() {
+        let spec = |format: &str, source: serde_json::Value| {
+            serde_json::json!({
+              "v": 1,
+              "type": "imap",
+              "pipeline": { "format": format },
+              "source": source,
+              "target": { "type": "local_dir", "base_dir": "/tmp" }
+            })
+        };
+        let source = serde_json::json!({
+          "host": "imap.example.com",
+          "secret_name": "mail",
+          "include_folders": ["INBOX", "Archive/*"]
+        });
+        validate_value(&spec("raw_tree_v1", source.clone())).expect("valid");
+
+        let err = validate_value(&spec("archive_v1", source)).expect_err("invalid");
+        assert!(
+            err.to_string().contains("raw_tree_v1"),
+            "unexpected error: {err}"
+        );
+
+        let err = validate_value(&spec(
+            "raw_tree_v1",
+            serde_json::json!({
+              "host": "imap.example.com",
+              "secret_name": "mail",
+              "exclude_folders": ["Trash["]
+            }),
+        ))
+        .expect_err("invalid");
+        assert!(
+            err.to_string().contains("folder pattern"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn windows_system_state_requires_archive_format() {
+        let spec = |format: &str| {
+            serde_json::json!({
+              "v": 1,
+              "type": "filesystem",
+              "pipeline": { "format": format },
+              "source": { "paths": ["C:\\Users"], "windows_system_state": true },
+              "target": { "type": "local_dir", "base_dir": "/tmp" }
+            })
+        };
+        validate_value(&spec("archive_v1")).expect("valid");
+        let err = validate_value(&spec("raw_tree_v1")).expect_err("invalid");
+        assert!(
+            err.to_string().contains("windows_system_state"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn git_requires_unique_repository_names() {
+        let spec = |repositories: serde_json::Value| {
+            serde_json::json!({
+              "v": 1,
+              "type": "git",
+              "source": { "repositories": repositories },
+              "target": { "type": "local_dir", "base_dir": "/tmp" }
+            })
+        };
+        validate_value(&spec(serde_json::json!([
+          { "url": "https://github.com/acme/app.git" },
+          { "url": "git@git.example.com:ops/app.git", "name": "ops-app" }
+        ])))
+        .expect("valid");
+
+        let err = validate_value(&spec(serde_json::json!([]))).expect_err("invalid");
+        assert!(
+            err.to_string().contains("repositories"),
+            "unexpected error: {err}"
+        );
+
+        let err = validate_value(&spec(serde_json::json!([
+          { "url": "https://github.com/acme/app.git" },
+          { "url": "git@git.example.com:ops/app.git" }
+        ])))
+        .expect_err("invalid");
+        assert!(
+            err.to_string().contains("duplicate"),
+            "unexpected error: {err}"
+        );
+
+        let err = validate_value(&spec(serde_json::json!([
+          { "url": "https://github.com/acme/app.git", "name": "../app" }
+        ])))
+        .expect_err("invalid");
+        assert!(
+            err.to_string().contains("invalid git repository name"),
+            "unexpected error: {err}"
+        );
+    }
+
     #[test]
     fn webdav_raw_tree_direct_requires_webdav_target_and_raw_tree_format() {
         let spec = serde_json::json!({
@@ -571,4 +1195,84 @@ mod tests {
             "unexpected error: {err}"
         );
     }
+
+    #[test]
+    fn notifications_ping_urls_must_be_http() {
+        let spec = serde_json::json!({
+          "v": 1,
+          "type": "filesystem",
+          "source": { "paths": ["/"] },
+          "target": { "type": "local_dir", "base_dir": "/tmp" },
+          "notifications": {
+            "ping": {
+              "start_url": "https://hc-ping.com/abc/start",
+              "failure_url": "ftp://example.invalid/fail"
+            }
+          }
+        });
+        let err = validate_value(&spec).expect_err("invalid");
+        assert!(
+            err.to_string().contains("notifications.ping.failure_url"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn compression_level_is_validated_per_algorithm() {
+        let spec_with = |compression: serde_json::Value| {
+            serde_json::json!({
+              "v": 1,
+              "type": "filesystem",
+              "pipeline": { "compression": compression },
+              "source": { "paths": ["/"] },
+              "target": { "type": "local_dir", "base_dir": "/tmp" }
+            })
+        };
+
+        validate_value(&spec_with(
+            serde_json::json!({ "algorithm": "zstd", "level": 19 }),
+        ))
+        .expect("valid");
+        validate_value(&spec_with(serde_json::json!({ "algorithm": "xz" }))).expect("valid");
+
+        let err = validate_value(&spec_with(
+            serde_json::json!({ "algorithm": "zstd", "level": 23 }),
+        ))
+        .expect_err("invalid");
+        assert!(
+            err.to_string().contains("between 1 and 22"),
+            "unexpected error: {err}"
+        );
+
+        let err = validate_value(&spec_with(
+            serde_json::json!({ "algorithm": "lz4", "level": 1 }),
+        ))
+        .expect_err("invalid");
+        assert!(
+            err.to_string().contains("not supported for lz4"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn webdav_part_upload_concurrency_is_bounded() {
+        let spec_with = |concurrency: u32| {
+            serde_json::json!({
+              "v": 1,
+              "type": "filesystem",
+              "pipeline": { "webdav": { "part_upload_concurrency": concurrency } },
+              "source": { "paths": ["/"] },
+              "target": { "type": "webdav", "base_url": "https://dav.example/backup", "secret_name": "dav" }
+            })
+        };
+
+        validate_value(&spec_with(4)).expect("valid");
+        for invalid in [0, 17] {
+            let err = validate_value(&spec_with(invalid)).expect_err("invalid");
+            assert!(
+                err.to_string().contains("part_upload_concurrency"),
+                "unexpected error: {err}"
+            );
+        }
+    }
 }
@@ -36,9 +36,15 @@ pub struct PipelineSettings {
     pub format: ArtifactFormatV1,
     pub tar: String,
     pub compression: String,
+    /// Compression level used for the payload; absent for algorithms without levels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression_level: Option<i32>,
     pub encryption: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub encryption_key: Option<String>,
+    /// age recipient the payload was encrypted to; identifies the key version after rotations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_recipient: Option<String>,
     pub split_bytes: u64,
 }
 
@@ -52,6 +58,9 @@ pub struct ManifestV1 {
     pub pipeline: PipelineSettings,
     pub artifacts: Vec<ArtifactPart>,
     pub entry_index: EntryIndexRef,
+    /// Source details resolved at run time (for example the PVCs a label selector matched).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<serde_json::Value>,
 }
 
 impl ManifestV1 {
@@ -79,8 +88,10 @@ mod tests {
                 format: ArtifactFormatV1::ArchiveV1,
                 tar: "pax".to_string(),
                 compression: "zstd".to_string(),
+                compression_level: None,
                 encryption: "none".to_string(),
                 encryption_key: None,
+                encryption_recipient: None,
                 split_bytes: 268_435_456,
             },
             artifacts: vec![ArtifactPart {
@@ -93,6 +104,7 @@ mod tests {
                 name: "entries.jsonl.zst".to_string(),
                 count: 42,
             },
+            source: None,
         };
 
         let json = serde_json::to_value(&manifest).expect("serialize");
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use serde::{Deserialize, Serialize};
 
 pub const PROGRESS_SNAPSHOT_EVENT_KIND_V1: &str = "progress_snapshot";
@@ -8,6 +10,7 @@ pub enum ProgressKindV1 {
     Backup,
     Restore,
     Verify,
+    Reencrypt,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -34,6 +37,71 @@ pub struct ProgressSnapshotV1 {
     pub detail: Option<serde_json::Value>,
 }
 
+/// Seconds of history behind `rate_bps`: long enough to smooth out bursty writes, short enough to
+/// follow a real change in throughput.
+pub const PROGRESS_RATE_WINDOW_SECS: i64 = 30;
+
+/// Rolling-average rate and ETA for one progress stream.
+///
+/// Feed it every snapshot of a run or operation. History restarts when the stage changes or
+/// `done` goes backwards, so each stage gets its own rate.
+#[derive(Debug, Default, Clone)]
+pub struct ProgressRateEstimator {
+    stage: Option<String>,
+    /// `(ts, done_bytes)`, oldest first; the front is the baseline of the window.
+    samples: VecDeque<(i64, u64)>,
+}
+
+impl ProgressRateEstimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `done_bytes` at `now_ts` and returns `(rate_bps, eta_seconds)`.
+    ///
+    /// The rate is `None` until the stage has made progress over at least one second; the ETA
+    /// additionally needs a total that is not yet reached.
+    pub fn observe(
+        &mut self,
+        stage: &str,
+        now_ts: i64,
+        done_bytes: u64,
+        total_bytes: Option<u64>,
+    ) -> (Option<u64>, Option<u64>) {
+        let restart = self.stage.as_deref() != Some(stage)
+            || self
+                .samples
+                .back()
+                .is_some_and(|&(ts, done)| now_ts < ts || done_bytes < done);
+        if restart {
+            self.stage = Some(stage.to_string());
+            self.samples.clear();
+        }
+
+        self.samples.push_back((now_ts, done_bytes));
+        let window_start = now_ts.saturating_sub(PROGRESS_RATE_WINDOW_SECS);
+        // Keep the newest sample at or before the window start as the baseline.
+        while self.samples.len() > 2 && self.samples[1].0 <= window_start {
+            self.samples.pop_front();
+        }
+
+        let Some(&(first_ts, first_done)) = self.samples.front() else {
+            return (None, None);
+        };
+        let dt = now_ts.saturating_sub(first_ts);
+        let delta = done_bytes.saturating_sub(first_done);
+        if dt <= 0 || delta == 0 {
+            return (None, None);
+        }
+
+        let rate = delta.saturating_div(dt as u64).max(1);
+        let eta = total_bytes
+            .filter(|total| *total > done_bytes)
+            .map(|total| total.saturating_sub(done_bytes).saturating_div(rate));
+        (Some(rate), eta)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -73,4 +141,33 @@ mod tests {
         assert!(!obj.contains_key("detail"));
         Ok(())
     }
+
+    #[test]
+    fn rate_estimator_averages_over_the_window_and_resets_per_stage() {
+        let mut est = ProgressRateEstimator::new();
+
+        assert_eq!(est.observe("upload", 1000, 0, Some(1000)), (None, None));
+        assert_eq!(est.observe("upload", 1000, 10, Some(1000)), (None, None));
+        assert_eq!(
+            est.observe("upload", 1010, 100, Some(1000)),
+            (Some(10), Some(90))
+        );
+        // A stall lowers the average instead of dropping the estimate.
+        assert_eq!(
+            est.observe("upload", 1020, 100, Some(1000)),
+            (Some(5), Some(180))
+        );
+        // Samples older than the window stop counting: the baseline is now (1020, 100).
+        assert_eq!(
+            est.observe("upload", 1060, 500, Some(1000)),
+            (Some(10), Some(50))
+        );
+        // Reaching the total leaves no ETA.
+        assert_eq!(est.observe("upload", 1070, 1000, Some(1000)).1, None);
+
+        assert_eq!(est.observe("verify", 1071, 1000, None), (None, None));
+        assert_eq!(est.observe("verify", 1072, 1200, None), (Some(200), None));
+        // Going backwards restarts the history.
+        assert_eq!(est.observe("verify", 1073, 50, None), (None, None));
+    }
 }
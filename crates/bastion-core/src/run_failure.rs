@@ -1,8 +1,186 @@
 use std::fmt;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+/// Catalog of run failure codes stored in `runs.error` and surfaced in the API, notifications,
+/// and stats. Detailed, source-specific codes stay in the run summary (`summary.error_code`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunErrorCode {
+    /// The backup target could not be reached (DNS, routing, refused or reset connections).
+    NetworkTarget,
+    /// The backup target rejected the credentials or the account lacks permission.
+    TargetAuth,
+    /// Source files or directories could not be read.
+    SourcePermission,
+    /// The source changed during the backup beyond the configured policy.
+    SourceConsistency,
+    /// The staging disk, target storage, or quota is full.
+    DiskFull,
+    /// The backup encryption key (age identity) is not available.
+    EncryptionKeyMissing,
+    /// An operation timed out.
+    Timeout,
+    /// The run was canceled.
+    Canceled,
+    /// The job, target, or driver configuration is invalid or unsupported.
+    Config,
+    /// Anything not covered above.
+    Unknown,
+}
+
+impl RunErrorCode {
+    pub const ALL: [Self; 10] = [
+        Self::NetworkTarget,
+        Self::TargetAuth,
+        Self::SourcePermission,
+        Self::SourceConsistency,
+        Self::DiskFull,
+        Self::EncryptionKeyMissing,
+        Self::Timeout,
+        Self::Canceled,
+        Self::Config,
+        Self::Unknown,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NetworkTarget => "network_target",
+            Self::TargetAuth => "target_auth",
+            Self::SourcePermission => "source_permission",
+            Self::SourceConsistency => "source_consistency",
+            Self::DiskFull => "disk_full",
+            Self::EncryptionKeyMissing => "encryption_key_missing",
+            Self::Timeout => "timeout",
+            Self::Canceled => "canceled",
+            Self::Config => "config",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Short English description used in notifications.
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::NetworkTarget => "backup target unreachable",
+            Self::TargetAuth => "backup target rejected the credentials",
+            Self::SourcePermission => "source files could not be read",
+            Self::SourceConsistency => "source changed during backup",
+            Self::DiskFull => "disk or quota full",
+            Self::EncryptionKeyMissing => "backup encryption key missing",
+            Self::Timeout => "operation timed out",
+            Self::Canceled => "canceled",
+            Self::Config => "invalid configuration",
+            Self::Unknown => "unclassified error",
+        }
+    }
+
+    /// Parses a catalog code, or maps a detailed/legacy code (`fs_issues`, `staging_disk_full`,
+    /// `agent_failed`, ...) onto the catalog.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim();
+        if let Some(v) = Self::ALL.into_iter().find(|v| v.as_str() == code) {
+            return Some(v);
+        }
+        match code {
+            "fs_issues" => Some(Self::SourcePermission),
+            "staging_disk_full" => Some(Self::DiskFull),
+            "snapshot_unavailable" | "unsupported_driver" | "driver_capability_mismatch" => {
+                Some(Self::Config)
+            }
+            "run_failed" | "agent_failed" => Some(Self::Unknown),
+            _ => None,
+        }
+    }
+
+    /// Classifies an error by walking its cause chain.
+    pub fn classify<'a>(
+        chain: impl IntoIterator<Item = &'a (dyn std::error::Error + 'static)>,
+    ) -> Self {
+        let mut text = String::new();
+        for cause in chain {
+            if let Some(failed) = cause.downcast_ref::<RunFailedWithSummary>() {
+                return failed.error_code;
+            }
+            if let Some(io_error) = cause.downcast_ref::<io::Error>() {
+                match io_error.kind() {
+                    io::ErrorKind::PermissionDenied => return Self::SourcePermission,
+                    io::ErrorKind::StorageFull | io::ErrorKind::QuotaExceeded => {
+                        return Self::DiskFull;
+                    }
+                    io::ErrorKind::TimedOut => return Self::Timeout,
+                    _ => {}
+                }
+            }
+            text.push_str(&cause.to_string().to_lowercase());
+            text.push_str(" | ");
+        }
+        Self::classify_text(&text)
+    }
+
+    /// Classifies a free-form error message (for example an agent's task error string).
+    pub fn classify_text(text: &str) -> Self {
+        let text = text.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|n| text.contains(n));
+
+        if has(&[
+            "missing backup age identity",
+            "missing backup age recipient",
+        ]) {
+            return Self::EncryptionKeyMissing;
+        }
+        if has(&[
+            "staging disk full",
+            "insufficient storage",
+            "no space left",
+            "disk full",
+            "quota exceeded",
+            "quota has been exceeded",
+            "http 507",
+        ]) {
+            return Self::DiskFull;
+        }
+        if has(&["http 401", "http 403", "unauthorized", "forbidden"]) {
+            return Self::TargetAuth;
+        }
+        if has(&["timed out", "timeout"]) {
+            return Self::Timeout;
+        }
+        if has(&[
+            "client error (connect)",
+            "tcp connect error",
+            "connection refused",
+            "network is unreachable",
+            "no route to host",
+            "host is unreachable",
+            "name or service not known",
+            "temporary failure in name resolution",
+            "failed to lookup address information",
+            "connection reset",
+            "connection aborted",
+            "broken pipe",
+        ]) {
+            return Self::NetworkTarget;
+        }
+        if has(&["permission denied", "access is denied"]) {
+            return Self::SourcePermission;
+        }
+        Self::Unknown
+    }
+}
+
+impl fmt::Display for RunErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct RunFailedWithSummary {
+    /// Detailed, source-specific code (also written to `summary.error_code`).
     pub code: &'static str,
+    /// Catalog code stored on the run.
+    pub error_code: RunErrorCode,
     pub summary: serde_json::Value,
     pub message: String,
 }
@@ -11,6 +189,7 @@ impl RunFailedWithSummary {
     pub fn new(code: &'static str, message: impl Into<String>, summary: serde_json::Value) -> Self {
         Self {
             code,
+            error_code: RunErrorCode::from_code(code).unwrap_or(RunErrorCode::Unknown),
             summary,
             message: message.into(),
         }
@@ -27,7 +206,7 @@ impl std::error::Error for RunFailedWithSummary {}
 
 #[cfg(test)]
 mod tests {
-    use super::RunFailedWithSummary;
+    use super::{RunErrorCode, RunFailedWithSummary};
 
     #[test]
     fn display_uses_message_and_anyhow_downcast_works() {
@@ -41,7 +220,58 @@ mod tests {
             .downcast_ref::<RunFailedWithSummary>()
             .expect("downcast");
         assert_eq!(down.code, "code");
+        assert_eq!(down.error_code, RunErrorCode::Unknown);
         assert_eq!(down.message, "boom");
         assert_eq!(down.summary, serde_json::json!({ "k": "v" }));
     }
+
+    #[test]
+    fn detailed_codes_map_onto_the_catalog() {
+        for code in RunErrorCode::ALL {
+            assert_eq!(RunErrorCode::from_code(code.as_str()), Some(code));
+        }
+        assert_eq!(
+            RunErrorCode::from_code("staging_disk_full"),
+            Some(RunErrorCode::DiskFull)
+        );
+        assert_eq!(
+            RunErrorCode::from_code("agent_failed"),
+            Some(RunErrorCode::Unknown)
+        );
+        assert_eq!(RunErrorCode::from_code("something_else"), None);
+
+        let rf = RunFailedWithSummary::new("source_consistency", "boom", serde_json::json!({}));
+        assert_eq!(rf.error_code, RunErrorCode::SourceConsistency);
+    }
+
+    #[test]
+    fn classify_walks_the_cause_chain() {
+        let err = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+            .context("read /srv/data");
+        assert_eq!(
+            RunErrorCode::classify(err.chain()),
+            RunErrorCode::SourcePermission
+        );
+
+        let err = anyhow::anyhow!("tcp connect error: Connection refused").context("upload part");
+        assert_eq!(
+            RunErrorCode::classify(err.chain()),
+            RunErrorCode::NetworkTarget
+        );
+
+        let err = anyhow::anyhow!("missing backup age identity: default");
+        assert_eq!(
+            RunErrorCode::classify(err.chain()),
+            RunErrorCode::EncryptionKeyMissing
+        );
+
+        let soft = RunFailedWithSummary::new("staging_disk_full", "full", serde_json::json!({}));
+        let err = anyhow::Error::new(soft).context("package");
+        assert_eq!(RunErrorCode::classify(err.chain()), RunErrorCode::DiskFull);
+
+        assert_eq!(
+            RunErrorCode::classify_text("something odd"),
+            RunErrorCode::Unknown
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use std::fmt::{Display, Formatter};
 use std::future::Future;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
 
@@ -53,6 +53,17 @@ pub enum DriverErrorKind {
 pub struct DriverError {
     pub kind: DriverErrorKind,
     pub message: String,
+    /// The HTTP response behind the error, for drivers that talk HTTP.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<DriverHttpError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DriverHttpError {
+    pub status: u16,
+    pub message: String,
+    #[serde(default)]
+    pub retry_after_secs: Option<u64>,
 }
 
 impl DriverError {
@@ -60,9 +71,15 @@ impl DriverError {
         Self {
             kind,
             message: message.into(),
+            http: None,
         }
     }
 
+    pub fn with_http(mut self, http: DriverHttpError) -> Self {
+        self.http = Some(http);
+        self
+    }
+
     pub fn unsupported(message: impl Into<String>) -> Self {
         Self::new(DriverErrorKind::Unsupported, message)
     }
@@ -168,6 +185,22 @@ pub trait TargetRunReader: Send + Sync {
         expected_size: Option<u64>,
         retries: usize,
     ) -> DriverFuture<Result<u64, DriverError>>;
+
+    /// Whether `read_range` can stream artifacts without staging them to local files.
+    fn supports_range_reads(&self) -> bool {
+        false
+    }
+
+    fn read_range(
+        &self,
+        artifact_path: String,
+        offset: u64,
+        len: u64,
+        retries: usize,
+    ) -> DriverFuture<Result<Vec<u8>, DriverError>> {
+        let _ = (artifact_path, offset, len, retries);
+        Box::pin(async { Err(DriverError::unsupported("range reads are not supported")) })
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -219,10 +252,73 @@ pub struct CleanupRunRequest {
     pub target_snapshot: serde_json::Value,
 }
 
+pub type TargetStoreFuture<'a, T> =
+    Pin<Box<dyn Future<Output = Result<T, DriverError>> + Send + 'a>>;
+
+/// A child of a listed target directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetEntry {
+    pub name: String,
+    pub is_dir: bool,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetStat {
+    pub is_dir: bool,
+    pub size: u64,
+}
+
+/// Object-level access to a backup target, for work that is not tied to one run (listing runs,
+/// deleting snapshots, scanning for orphans).
+///
+/// Paths are `/`-separated and relative to the target root, e.g. `<job_id>/<run_id>/manifest.json`.
+/// Paths naming a directory end with `/`.
+pub trait TargetStore: Send + Sync {
+    /// Target type as stored in run snapshots (`local_dir`, `webdav`, ...).
+    fn kind(&self) -> &str;
+
+    /// Human-readable location of `path`, safe to show in reports (no credentials).
+    fn describe(&self, path: &str) -> String;
+
+    /// Whether the data lives on the filesystem of the node that wrote it, so only that node can
+    /// reach it.
+    fn node_local(&self) -> bool {
+        false
+    }
+
+    /// Uploads the local file `src` to `path`, creating parent directories. Returns the bytes
+    /// stored.
+    fn store_part<'a>(&'a self, path: &'a str, src: &'a Path) -> TargetStoreFuture<'a, u64>;
+
+    /// Reads `len` bytes of `path` starting at `offset`; shorter at the end of the file.
+    fn read_range<'a>(
+        &'a self,
+        path: &'a str,
+        offset: u64,
+        len: u64,
+    ) -> TargetStoreFuture<'a, Vec<u8>>;
+
+    /// Lists the children of directory `dir`, sorted by name. A missing directory is empty.
+    fn list<'a>(&'a self, dir: &'a str) -> TargetStoreFuture<'a, Vec<TargetEntry>>;
+
+    /// Deletes a file or a whole directory tree. Returns `false` when nothing was there.
+    fn delete<'a>(&'a self, path: &'a str) -> TargetStoreFuture<'a, bool>;
+
+    /// Returns `None` when `path` does not exist.
+    fn stat<'a>(&'a self, path: &'a str) -> TargetStoreFuture<'a, Option<TargetStat>>;
+}
+
 pub trait TargetDriver: Send + Sync {
     fn id(&self) -> &DriverId;
     fn capabilities(&self) -> TargetDriverCapabilities;
 
+    /// Secret kind holding this target's credentials, when it needs any. Callers load the secret
+    /// named by the job and hand its fields to the driver along with the target config.
+    fn credential_secret_kind(&self) -> Option<&'static str> {
+        None
+    }
+
     fn store_run(
         &self,
         request: StoreRunRequest,
@@ -246,6 +342,14 @@ pub trait TargetDriver: Send + Sync {
         &self,
         target_config: &serde_json::Value,
     ) -> Result<serde_json::Value, DriverError>;
+
+    /// Opens object-level access from a runtime target config (credentials included).
+    fn open_store(
+        &self,
+        _target_config: &serde_json::Value,
+    ) -> Result<Arc<dyn TargetStore>, DriverError> {
+        Err(DriverError::unsupported("open_store is not implemented"))
+    }
 }
 
 #[cfg(test)]
@@ -8,9 +8,11 @@ use bastion_core::backup_format::{COMPLETE_NAME, ENTRIES_INDEX_NAME, MANIFEST_NA
 use bastion_driver_api::{
     DriverError, DriverErrorKind, DriverFuture, DriverId, OpenReaderRequest, StoreRunProgress,
     StoreRunRequest, TargetDriver, TargetDriverCapabilities, TargetRequestLimits, TargetRunReader,
+    TargetStore,
 };
 
 use crate::DriverRegistry;
+use crate::target_store::{LocalDirTargetStore, WebdavTargetStore};
 
 pub const BUILTIN_DRIVER_VERSION: u32 = 1;
 pub const TARGET_KIND_LOCAL_DIR: &str = "local_dir";
@@ -47,6 +49,8 @@ pub fn target_registry() -> &'static DriverRegistry {
 #[derive(Debug, Deserialize)]
 struct LocalDirTargetConfig {
     base_dir: String,
+    #[serde(default)]
+    link_unchanged: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -184,9 +188,10 @@ impl TargetDriver for LocalDirTargetDriver {
             let artifacts = request.artifacts;
             let progress = request.on_progress;
             let base_dir = base_dir.to_string();
+            let link_unchanged = cfg.link_unchanged;
 
-            let run_dir = tokio::task::spawn_blocking(move || {
-                if let Some(cb) = progress.as_ref() {
+            let (run_dir, linked) = tokio::task::spawn_blocking(move || {
+                let stored = if let Some(cb) = progress.as_ref() {
                     let adapter = |p: bastion_targets::StoreRunProgress| {
                         cb(StoreRunProgress {
                             bytes_done: p.bytes_done,
@@ -208,16 +213,51 @@ impl TargetDriver for LocalDirTargetDriver {
                         &artifacts,
                         None,
                     )
-                }
+                };
+
+                stored.map(|run_dir| {
+                    // Linking is an optimization: a failure leaves a complete run with plain copies.
+                    let linked = if link_unchanged {
+                        bastion_targets::local_dir::link_unchanged_from_previous(
+                            Path::new(&base_dir),
+                            &job_id,
+                            &run_id,
+                        )
+                        .unwrap_or_else(|error| {
+                            tracing::warn!(
+                                run_id = %run_id,
+                                error = %error,
+                                "failed to link unchanged raw-tree files"
+                            );
+                            None
+                        })
+                    } else {
+                        None
+                    };
+                    (run_dir, linked)
+                })
             })
             .await
             .map_err(|error| DriverError::unknown(format!("local_dir store join error: {error}")))?
             .map_err(|error| DriverError::io(error.to_string()))?;
 
-            Ok(serde_json::json!({
+            let mut summary = serde_json::json!({
                 "type": TARGET_KIND_LOCAL_DIR,
                 "run_dir": run_dir.to_string_lossy().to_string()
-            }))
+            });
+            if let Some(linked) = linked
+                && let Some(obj) = summary.as_object_mut()
+            {
+                obj.insert(
+                    "linked_unchanged".to_string(),
+                    serde_json::json!({
+                        "previous_run_id": linked.previous_run_id,
+                        "files": linked.linked_files,
+                        "bytes": linked.linked_bytes,
+                    }),
+                );
+            }
+            Ok(summary)
         })
     }
 
@@ -299,6 +339,13 @@ impl TargetDriver for LocalDirTargetDriver {
             "base_dir": cfg.base_dir,
         }))
     }
+
+    fn open_store(
+        &self,
+        target_config: &serde_json::Value,
+    ) -> Result<Arc<dyn TargetStore>, DriverError> {
+        Ok(Arc::new(LocalDirTargetStore::from_config(target_config)?))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -400,6 +447,29 @@ impl TargetRunReader for WebdavRunReader {
                 .map_err(map_webdav_anyhow_to_driver_error)
         })
     }
+
+    fn supports_range_reads(&self) -> bool {
+        true
+    }
+
+    fn read_range(
+        &self,
+        artifact_path: String,
+        offset: u64,
+        len: u64,
+        retries: usize,
+    ) -> DriverFuture<Result<Vec<u8>, DriverError>> {
+        let client = self.client.clone();
+        let url = self.artifact_url(&artifact_path);
+        Box::pin(async move {
+            let url = url?;
+            let retries = u32::try_from(retries).unwrap_or(u32::MAX);
+            client
+                .get_range(&url, offset, len, retries)
+                .await
+                .map_err(map_webdav_anyhow_to_driver_error)
+        })
+    }
 }
 
 struct WebdavTargetDriver {
@@ -419,6 +489,10 @@ impl TargetDriver for WebdavTargetDriver {
         &self.id
     }
 
+    fn credential_secret_kind(&self) -> Option<&'static str> {
+        Some(TARGET_KIND_WEBDAV)
+    }
+
     fn capabilities(&self) -> TargetDriverCapabilities {
         TargetDriverCapabilities {
             supports_archive_rolling_upload: true,
@@ -614,9 +688,16 @@ impl TargetDriver for WebdavTargetDriver {
         }
         Ok(out)
     }
+
+    fn open_store(
+        &self,
+        target_config: &serde_json::Value,
+    ) -> Result<Arc<dyn TargetStore>, DriverError> {
+        Ok(Arc::new(WebdavTargetStore::from_config(target_config)?))
+    }
 }
 
-fn map_webdav_anyhow_to_driver_error<E>(error: E) -> DriverError
+pub(crate) fn map_webdav_anyhow_to_driver_error<E>(error: E) -> DriverError
 where
     E: std::fmt::Display,
 {
@@ -724,7 +805,7 @@ fn to_webdav_limits(limits: TargetRequestLimits) -> bastion_targets::WebdavReque
     }
 }
 
-fn redact_run_url(run_url: &Url) -> String {
+pub(crate) fn redact_run_url(run_url: &Url) -> String {
     let mut out = run_url.clone();
     let _ = out.set_username("");
     let _ = out.set_password(None);
@@ -4,11 +4,12 @@ use std::sync::Arc;
 use bastion_driver_api::{
     CleanupRunRequest, CleanupRunStatus, DriverError, DriverErrorKind, DriverId, OpenReaderRequest,
     SourceDriver, SourceDriverCapabilities, StoreRunRequest, TargetDriver,
-    TargetDriverCapabilities, TargetRunReader,
+    TargetDriverCapabilities, TargetRunReader, TargetStore,
 };
 
 pub mod builtins;
 pub mod target_runtime;
+mod target_store;
 
 #[derive(Default)]
 pub struct DriverRegistry {
@@ -140,6 +141,34 @@ impl DriverRegistry {
             .ok_or_else(|| DriverError::unsupported(format!("unsupported target driver: {id}")))
     }
 
+    /// Newest registered driver for a target type as recorded in run snapshots (`type`).
+    pub fn target_driver_id_for_kind(&self, kind: &str) -> Result<DriverId, DriverError> {
+        self.target_drivers
+            .values()
+            .map(|driver| driver.id())
+            .filter(|id| id.kind == kind)
+            .max_by_key(|id| id.version)
+            .cloned()
+            .ok_or_else(|| DriverError::unsupported(format!("unsupported target type: {kind}")))
+    }
+
+    pub fn credential_secret_kind(
+        &self,
+        id: &DriverId,
+    ) -> Result<Option<&'static str>, DriverError> {
+        let driver = self.resolve_target_driver(id)?;
+        Ok(driver.credential_secret_kind())
+    }
+
+    pub fn open_store(
+        &self,
+        id: &DriverId,
+        target_config: &serde_json::Value,
+    ) -> Result<Arc<dyn TargetStore>, DriverError> {
+        let driver = self.resolve_target_driver(id)?;
+        driver.open_store(target_config)
+    }
+
     pub fn open_writer(
         &self,
         id: &DriverId,
@@ -1,14 +1,16 @@
 use bastion_core::agent_protocol::TargetResolvedV1;
 use bastion_core::job_spec;
 use bastion_driver_api::{DriverError, DriverId};
+use bastion_targets::WebdavCredentials;
 
 use crate::builtins;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct WebdavRuntimeAuth {
-    pub username: String,
-    pub password: String,
-    pub secret_name: Option<String>,
+/// The secret a job target reads its credentials from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetCredentialSecret<'a> {
+    /// Secret kind, as declared by the target driver.
+    pub kind: &'static str,
+    pub name: &'a str,
 }
 
 pub fn driver_id_for_job_target(target: &job_spec::TargetV1) -> DriverId {
@@ -18,42 +20,65 @@ pub fn driver_id_for_job_target(target: &job_spec::TargetV1) -> DriverId {
     }
 }
 
+/// Which secret (if any) must be loaded on the job's node before opening its target.
+pub fn credential_secret_for_job_target(
+    target: &job_spec::TargetV1,
+) -> Result<Option<TargetCredentialSecret<'_>>, DriverError> {
+    let id = driver_id_for_job_target(target);
+    let Some(kind) = builtins::target_registry().credential_secret_kind(&id)? else {
+        return Ok(None);
+    };
+    let name = match target {
+        job_spec::TargetV1::Webdav { secret_name, .. } => secret_name.as_str(),
+        job_spec::TargetV1::LocalDir { .. } => "",
+    };
+    let name = require_non_empty(name, &format!("{}.secret_name", id.kind))?;
+    Ok(Some(TargetCredentialSecret { kind, name }))
+}
+
+/// Runtime config for a job target. `credentials` is the payload of the secret named by
+/// [`credential_secret_for_job_target`], required when that returns one.
 pub fn runtime_input_for_job_target(
     target: &job_spec::TargetV1,
-    webdav_auth: Option<&WebdavRuntimeAuth>,
+    credentials: Option<&[u8]>,
 ) -> Result<(DriverId, serde_json::Value), DriverError> {
     match target {
-        job_spec::TargetV1::LocalDir { base_dir, .. } => {
+        job_spec::TargetV1::LocalDir {
+            base_dir,
+            link_unchanged,
+            ..
+        } => {
             let base_dir = require_non_empty(base_dir, "local_dir.base_dir")?;
             Ok((
                 builtins::local_dir_driver_id(),
-                serde_json::json!({ "base_dir": base_dir }),
+                serde_json::json!({ "base_dir": base_dir, "link_unchanged": link_unchanged }),
             ))
         }
-        job_spec::TargetV1::Webdav { base_url, .. } => {
+        job_spec::TargetV1::Webdav {
+            base_url,
+            secret_name,
+            ..
+        } => {
             let base_url = require_non_empty(base_url, "webdav.base_url")?;
-            let auth = webdav_auth.ok_or_else(|| {
+            let credentials = credentials.ok_or_else(|| {
                 DriverError::auth("webdav credentials are required for runtime target config")
             })?;
-            let username = require_non_empty(&auth.username, "webdav.username")?;
-            let password = require_non_empty(&auth.password, "webdav.password")?;
-
-            let mut out = serde_json::json!({
-                "base_url": base_url,
-                "username": username,
-                "password": password,
-            });
-            if let Some(secret_name) = auth.secret_name.as_deref() {
-                let secret_name = require_non_empty(secret_name, "webdav.secret_name")?;
-                if let Some(obj) = out.as_object_mut() {
-                    obj.insert(
-                        "secret_name".to_string(),
-                        serde_json::Value::String(secret_name.to_string()),
-                    );
-                }
-            }
-
-            Ok((builtins::webdav_driver_id(), out))
+            let credentials = WebdavCredentials::from_json(credentials).map_err(|error| {
+                DriverError::config(format!("invalid webdav credentials: {error}"))
+            })?;
+            let username = require_non_empty(&credentials.username, "webdav.username")?;
+            let password = require_non_empty(&credentials.password, "webdav.password")?;
+            let secret_name = require_non_empty(secret_name, "webdav.secret_name")?;
+
+            Ok((
+                builtins::webdav_driver_id(),
+                serde_json::json!({
+                    "base_url": base_url,
+                    "username": username,
+                    "password": password,
+                    "secret_name": secret_name,
+                }),
+            ))
         }
     }
 }
@@ -91,11 +116,15 @@ pub fn runtime_input_for_resolved_target(
     target: &TargetResolvedV1,
 ) -> Result<(DriverId, serde_json::Value), DriverError> {
     match target {
-        TargetResolvedV1::LocalDir { base_dir, .. } => {
+        TargetResolvedV1::LocalDir {
+            base_dir,
+            link_unchanged,
+            ..
+        } => {
             let base_dir = require_non_empty(base_dir, "local_dir.base_dir")?;
             Ok((
                 builtins::local_dir_driver_id(),
-                serde_json::json!({ "base_dir": base_dir }),
+                serde_json::json!({ "base_dir": base_dir, "link_unchanged": link_unchanged }),
             ))
         }
         TargetResolvedV1::Webdav {
@@ -130,8 +159,9 @@ fn require_non_empty<'a>(value: &'a str, field: &str) -> Result<&'a str, DriverE
 #[cfg(test)]
 mod tests {
     use super::{
-        WebdavRuntimeAuth, driver_id_for_job_target, runtime_input_for_job_target,
-        runtime_input_for_resolved_target, snapshot_input_for_job_target,
+        TargetCredentialSecret, credential_secret_for_job_target, driver_id_for_job_target,
+        runtime_input_for_job_target, runtime_input_for_resolved_target,
+        snapshot_input_for_job_target,
     };
 
     use bastion_core::agent_protocol::TargetResolvedV1;
@@ -147,6 +177,7 @@ mod tests {
         let local = job_spec::TargetV1::LocalDir {
             base_dir: "/tmp".to_string(),
             part_size_bytes: 1,
+            link_unchanged: false,
         };
 
         assert_eq!(driver_id_for_job_target(&webdav).kind, "webdav");
@@ -165,6 +196,40 @@ mod tests {
         assert_eq!(err.kind, bastion_driver_api::DriverErrorKind::Auth);
     }
 
+    #[test]
+    fn credential_secret_comes_from_the_target_driver() {
+        let webdav = job_spec::TargetV1::Webdav {
+            base_url: "https://example.com/base/".to_string(),
+            secret_name: " primary ".to_string(),
+            part_size_bytes: 1,
+        };
+        assert_eq!(
+            credential_secret_for_job_target(&webdav).expect("secret"),
+            Some(TargetCredentialSecret {
+                kind: "webdav",
+                name: "primary",
+            })
+        );
+
+        let unnamed = job_spec::TargetV1::Webdav {
+            base_url: "https://example.com/base/".to_string(),
+            secret_name: " ".to_string(),
+            part_size_bytes: 1,
+        };
+        let err = credential_secret_for_job_target(&unnamed).expect_err("name required");
+        assert_eq!(err.message, "webdav.secret_name is required");
+
+        let local = job_spec::TargetV1::LocalDir {
+            base_dir: "/tmp".to_string(),
+            part_size_bytes: 1,
+            link_unchanged: false,
+        };
+        assert_eq!(
+            credential_secret_for_job_target(&local).expect("none"),
+            None
+        );
+    }
+
     #[test]
     fn runtime_input_for_job_target_webdav_includes_secret_name() {
         let target = job_spec::TargetV1::Webdav {
@@ -173,15 +238,9 @@ mod tests {
             part_size_bytes: 1,
         };
 
-        let (_id, cfg) = runtime_input_for_job_target(
-            &target,
-            Some(&WebdavRuntimeAuth {
-                username: "u".to_string(),
-                password: "p".to_string(),
-                secret_name: Some("primary".to_string()),
-            }),
-        )
-        .expect("runtime input");
+        let (_id, cfg) =
+            runtime_input_for_job_target(&target, Some(br#"{"username":"u","password":"p"}"#))
+                .expect("runtime input");
 
         assert_eq!(cfg["base_url"], "https://example.com/base/");
         assert_eq!(cfg["username"], "u");
@@ -196,6 +255,8 @@ mod tests {
             username: "u".to_string(),
             password: "p".to_string(),
             part_size_bytes: 1,
+            secret_name: None,
+            pending: None,
         };
 
         let (_id, cfg) = runtime_input_for_resolved_target(&target).expect("resolved input");
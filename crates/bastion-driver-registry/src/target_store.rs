@@ -0,0 +1,400 @@
+use std::path::{Path, PathBuf};
+
+use url::Url;
+
+use bastion_driver_api::{
+    DriverError, DriverHttpError, TargetEntry, TargetStat, TargetStore, TargetStoreFuture,
+};
+use bastion_targets::{WebdavClient, WebdavCredentials, WebdavHttpError};
+
+use crate::builtins::{
+    TARGET_KIND_LOCAL_DIR, TARGET_KIND_WEBDAV, map_webdav_anyhow_to_driver_error, redact_run_url,
+};
+
+fn config_str<'a>(config: &'a serde_json::Value, field: &str) -> Result<&'a str, DriverError> {
+    config
+        .get(field)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| DriverError::config(format!("target config is missing {field}")))
+}
+
+fn invalid_path(path: &str) -> DriverError {
+    DriverError::config(format!("invalid target path: {path}"))
+}
+
+fn io_error(error: std::io::Error) -> DriverError {
+    DriverError::io(error.to_string())
+}
+
+/// [`TargetStore`] over a directory on this node.
+#[derive(Debug, Clone)]
+pub(crate) struct LocalDirTargetStore {
+    base_dir: PathBuf,
+}
+
+impl LocalDirTargetStore {
+    pub(crate) fn new(base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            base_dir: base_dir.into(),
+        }
+    }
+
+    pub(crate) fn from_config(config: &serde_json::Value) -> Result<Self, DriverError> {
+        Ok(Self::new(config_str(config, "base_dir")?))
+    }
+
+    fn resolve(&self, path: &str) -> Result<PathBuf, DriverError> {
+        let mut out = self.base_dir.clone();
+        for segment in path.split('/').filter(|s| !s.is_empty()) {
+            if segment == "." || segment == ".." || segment.contains('\\') {
+                return Err(invalid_path(path));
+            }
+            out.push(segment);
+        }
+        Ok(out)
+    }
+}
+
+impl TargetStore for LocalDirTargetStore {
+    fn kind(&self) -> &str {
+        TARGET_KIND_LOCAL_DIR
+    }
+
+    fn describe(&self, path: &str) -> String {
+        match self.resolve(path) {
+            Ok(path) => path.display().to_string(),
+            Err(_) => self.base_dir.display().to_string(),
+        }
+    }
+
+    fn node_local(&self) -> bool {
+        true
+    }
+
+    fn store_part<'a>(&'a self, path: &'a str, src: &'a Path) -> TargetStoreFuture<'a, u64> {
+        Box::pin(async move {
+            let dst = self.resolve(path)?;
+            let src = src.to_path_buf();
+            tokio::task::spawn_blocking(move || -> Result<u64, DriverError> {
+                let size = std::fs::metadata(&src).map_err(io_error)?.len();
+                if let Some(parent) = dst.parent() {
+                    std::fs::create_dir_all(parent).map_err(io_error)?;
+                }
+                bastion_targets::local_dir::copy_if_needed(&src, &dst, size)
+                    .map_err(|error| DriverError::io(format!("{error:#}")))?;
+                Ok(size)
+            })
+            .await
+            .map_err(|error| DriverError::unknown(format!("local_dir store join error: {error}")))?
+        })
+    }
+
+    fn read_range<'a>(
+        &'a self,
+        path: &'a str,
+        offset: u64,
+        len: u64,
+    ) -> TargetStoreFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            use tokio::io::{AsyncReadExt as _, AsyncSeekExt as _};
+
+            let mut file = tokio::fs::File::open(self.resolve(path)?)
+                .await
+                .map_err(io_error)?;
+            file.seek(std::io::SeekFrom::Start(offset))
+                .await
+                .map_err(io_error)?;
+            let mut out = Vec::new();
+            file.take(len)
+                .read_to_end(&mut out)
+                .await
+                .map_err(io_error)?;
+            Ok(out)
+        })
+    }
+
+    fn list<'a>(&'a self, dir: &'a str) -> TargetStoreFuture<'a, Vec<TargetEntry>> {
+        Box::pin(async move {
+            let mut entries = match tokio::fs::read_dir(self.resolve(dir)?).await {
+                Ok(v) => v,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                    return Ok(Vec::new());
+                }
+                Err(error) => return Err(io_error(error)),
+            };
+            let mut out = Vec::new();
+            while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+                let meta = entry.metadata().await.map_err(io_error)?;
+                out.push(TargetEntry {
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    is_dir: meta.is_dir(),
+                    size: meta.is_file().then_some(meta.len()),
+                });
+            }
+            out.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(out)
+        })
+    }
+
+    fn delete<'a>(&'a self, path: &'a str) -> TargetStoreFuture<'a, bool> {
+        Box::pin(async move {
+            let path = self.resolve(path)?;
+            let meta = match tokio::fs::symlink_metadata(&path).await {
+                Ok(v) => v,
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+                Err(error) => return Err(io_error(error)),
+            };
+            if meta.is_dir() {
+                tokio::fs::remove_dir_all(&path).await.map_err(io_error)?;
+            } else {
+                tokio::fs::remove_file(&path).await.map_err(io_error)?;
+            }
+            Ok(true)
+        })
+    }
+
+    fn stat<'a>(&'a self, path: &'a str) -> TargetStoreFuture<'a, Option<TargetStat>> {
+        Box::pin(async move {
+            match tokio::fs::metadata(self.resolve(path)?).await {
+                Ok(meta) => Ok(Some(TargetStat {
+                    is_dir: meta.is_dir(),
+                    size: meta.len(),
+                })),
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(error) => Err(io_error(error)),
+            }
+        })
+    }
+}
+
+/// [`TargetStore`] over a WebDAV collection.
+#[derive(Debug, Clone)]
+pub(crate) struct WebdavTargetStore {
+    client: WebdavClient,
+    base_url: Url,
+}
+
+impl WebdavTargetStore {
+    /// `base_url` from the target config; `username`/`password` from the resolved WebDAV secret.
+    pub(crate) fn from_config(config: &serde_json::Value) -> Result<Self, DriverError> {
+        let credentials = WebdavCredentials {
+            username: config_str(config, "username")?.to_string(),
+            password: config
+                .get("password")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+        };
+        let mut base_url = Url::parse(config_str(config, "base_url")?)
+            .map_err(|error| DriverError::config(format!("invalid webdav.base_url: {error}")))?;
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        let client =
+            WebdavClient::new(base_url.clone(), credentials).map_err(map_webdav_store_error)?;
+        Ok(Self { client, base_url })
+    }
+
+    fn url(&self, path: &str) -> Result<Url, DriverError> {
+        if path.split('/').any(|s| s == "." || s == "..") {
+            return Err(invalid_path(path));
+        }
+        self.base_url
+            .join(path.trim_start_matches('/'))
+            .map_err(|_| invalid_path(path))
+    }
+}
+
+fn webdav_http_error(error: &anyhow::Error) -> Option<&WebdavHttpError> {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<WebdavHttpError>())
+}
+
+/// Like [`map_webdav_anyhow_to_driver_error`], keeping the whole context chain and the HTTP
+/// response so callers can classify failures and honor `Retry-After`.
+fn map_webdav_store_error(error: anyhow::Error) -> DriverError {
+    let http = webdav_http_error(&error).map(|http| DriverHttpError {
+        status: http.status.as_u16(),
+        message: http.message.clone(),
+        retry_after_secs: http.retry_after.map(|value| value.as_secs()),
+    });
+    let mut out = map_webdav_anyhow_to_driver_error(format!("{error:#}"));
+    out.http = http;
+    out
+}
+
+fn is_not_found(error: &anyhow::Error) -> bool {
+    webdav_http_error(error).is_some_and(|http| http.status.as_u16() == 404)
+}
+
+impl TargetStore for WebdavTargetStore {
+    fn kind(&self) -> &str {
+        TARGET_KIND_WEBDAV
+    }
+
+    fn describe(&self, path: &str) -> String {
+        match self.url(path) {
+            Ok(url) => redact_run_url(&url),
+            Err(_) => redact_run_url(&self.base_url),
+        }
+    }
+
+    fn store_part<'a>(&'a self, path: &'a str, src: &'a Path) -> TargetStoreFuture<'a, u64> {
+        Box::pin(async move {
+            let url = self.url(path)?;
+            let mut parent = String::new();
+            let segments = path
+                .split('/')
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>();
+            for segment in segments.iter().take(segments.len().saturating_sub(1)) {
+                parent.push_str(segment);
+                parent.push('/');
+                self.client
+                    .ensure_collection(&self.url(&parent)?)
+                    .await
+                    .map_err(map_webdav_store_error)?;
+            }
+            let size = tokio::fs::metadata(src).await.map_err(io_error)?.len();
+            self.client
+                .put_file(&url, src, size)
+                .await
+                .map_err(map_webdav_store_error)?;
+            Ok(size)
+        })
+    }
+
+    fn read_range<'a>(
+        &'a self,
+        path: &'a str,
+        offset: u64,
+        len: u64,
+    ) -> TargetStoreFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            self.client
+                .get_range(&self.url(path)?, offset, len, 3)
+                .await
+                .map_err(map_webdav_store_error)
+        })
+    }
+
+    fn list<'a>(&'a self, dir: &'a str) -> TargetStoreFuture<'a, Vec<TargetEntry>> {
+        Box::pin(async move {
+            let mut url = self.url(dir)?;
+            if !url.path().ends_with('/') {
+                url.set_path(&format!("{}/", url.path()));
+            }
+            let entries = match self.client.propfind_depth1(&url).await {
+                Ok(v) => v,
+                Err(error) if is_not_found(&error) => return Ok(Vec::new()),
+                Err(error) => return Err(map_webdav_store_error(error)),
+            };
+            let mut out = entries
+                .into_iter()
+                .map(|entry| TargetEntry {
+                    is_dir: entry.kind == "dir",
+                    size: entry.size,
+                    name: entry.name,
+                })
+                .collect::<Vec<_>>();
+            out.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(out)
+        })
+    }
+
+    fn delete<'a>(&'a self, path: &'a str) -> TargetStoreFuture<'a, bool> {
+        Box::pin(async move {
+            self.client
+                .delete(&self.url(path)?)
+                .await
+                .map_err(map_webdav_store_error)
+        })
+    }
+
+    fn stat<'a>(&'a self, path: &'a str) -> TargetStoreFuture<'a, Option<TargetStat>> {
+        Box::pin(async move {
+            if path.ends_with('/') {
+                return match self.client.propfind_depth1(&self.url(path)?).await {
+                    Ok(_) => Ok(Some(TargetStat {
+                        is_dir: true,
+                        size: 0,
+                    })),
+                    Err(error) if is_not_found(&error) => Ok(None),
+                    Err(error) => Err(map_webdav_store_error(error)),
+                };
+            }
+            Ok(self
+                .client
+                .head_size(&self.url(path)?)
+                .await
+                .map_err(map_webdav_store_error)?
+                .map(|size| TargetStat {
+                    is_dir: false,
+                    size,
+                }))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builtins;
+
+    #[tokio::test]
+    async fn local_dir_store_stores_reads_lists_and_deletes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = tmp.path().join("base");
+        let registry = builtins::target_registry();
+        let id = registry
+            .target_driver_id_for_kind("local_dir")
+            .expect("local_dir driver");
+        let target = registry
+            .open_store(
+                &id,
+                &serde_json::json!({ "type": "local_dir", "base_dir": base.to_string_lossy() }),
+            )
+            .unwrap();
+        assert_eq!(target.kind(), "local_dir");
+        assert!(target.node_local());
+
+        let src = tmp.path().join("part");
+        std::fs::write(&src, b"hello world").unwrap();
+        assert_eq!(
+            target
+                .store_part("job/run/payload.part000001", &src)
+                .await
+                .unwrap(),
+            11
+        );
+
+        let bytes = target
+            .read_range("job/run/payload.part000001", 6, 100)
+            .await
+            .unwrap();
+        assert_eq!(bytes, b"world");
+
+        let entries = target.list("job/").await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "run");
+        assert!(entries[0].is_dir);
+        assert!(target.list("missing/").await.unwrap().is_empty());
+
+        let stat = target
+            .stat("job/run/payload.part000001")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stat.size, 11);
+        assert!(!stat.is_dir);
+
+        assert!(target.delete("job/run/").await.unwrap());
+        assert!(!target.delete("job/run/").await.unwrap());
+        assert!(target.stat("job/run/").await.unwrap().is_none());
+
+        let err = target.stat("job/../etc/").await.expect_err("escape");
+        assert!(err.message.contains("invalid target path"));
+    }
+}
@@ -9,6 +9,8 @@ use bastion_storage::secrets::SecretsCrypto;
 use bastion_storage::secrets_repo;
 use bastion_targets::WebdavCredentials;
 
+use crate::webdav_rotation;
+
 pub async fn resolve_job_spec_for_agent(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
@@ -55,9 +57,106 @@ pub async fn resolve_job_spec_for_agent(
             source,
             target: resolve_target_for_agent(db, secrets, node_id, target).await?,
         }),
+        job_spec::JobSpecV1::SqliteApp {
+            v,
+            pipeline,
+            notifications: _,
+            source,
+            target,
+            ..
+        } => Ok(JobSpecResolvedV1::SqliteApp {
+            v,
+            pipeline: resolve_pipeline_for_agent(db, secrets, &pipeline).await?,
+            source,
+            target: resolve_target_for_agent(db, secrets, node_id, target).await?,
+        }),
+        job_spec::JobSpecV1::Postgres {
+            v,
+            pipeline,
+            notifications: _,
+            source,
+            target,
+            ..
+        } => Ok(JobSpecResolvedV1::Postgres {
+            v,
+            pipeline: resolve_pipeline_for_agent(db, secrets, &pipeline).await?,
+            source,
+            target: resolve_target_for_agent(db, secrets, node_id, target).await?,
+        }),
+        job_spec::JobSpecV1::VmImage {
+            v,
+            pipeline,
+            notifications: _,
+            source,
+            target,
+            ..
+        } => Ok(JobSpecResolvedV1::VmImage {
+            v,
+            pipeline: resolve_pipeline_for_agent(db, secrets, &pipeline).await?,
+            source,
+            target: resolve_target_for_agent(db, secrets, node_id, target).await?,
+        }),
+        job_spec::JobSpecV1::KubernetesPvc {
+            v,
+            pipeline,
+            notifications: _,
+            source,
+            target,
+            ..
+        } => Ok(JobSpecResolvedV1::KubernetesPvc {
+            v,
+            pipeline: resolve_pipeline_for_agent(db, secrets, &pipeline).await?,
+            source,
+            target: resolve_target_for_agent(db, secrets, node_id, target).await?,
+        }),
+        job_spec::JobSpecV1::Git {
+            v,
+            pipeline,
+            notifications: _,
+            source,
+            target,
+            ..
+        } => Ok(JobSpecResolvedV1::Git {
+            v,
+            pipeline: resolve_pipeline_for_agent(db, secrets, &pipeline).await?,
+            source,
+            target: resolve_target_for_agent(db, secrets, node_id, target).await?,
+        }),
+        job_spec::JobSpecV1::Imap {
+            v,
+            pipeline,
+            notifications: _,
+            source,
+            target,
+            ..
+        } => {
+            let credentials =
+                load_imap_credentials(db, secrets, node_id, &source.secret_name).await?;
+            Ok(JobSpecResolvedV1::Imap {
+                v,
+                pipeline: resolve_pipeline_for_agent(db, secrets, &pipeline).await?,
+                source,
+                credentials,
+                target: resolve_target_for_agent(db, secrets, node_id, target).await?,
+            })
+        }
     }
 }
 
+/// Loads the node-scoped `imap` secret referenced by an IMAP job.
+pub(crate) async fn load_imap_credentials(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    node_id: &str,
+    secret_name: &str,
+) -> Result<job_spec::ImapCredentialsV1, anyhow::Error> {
+    let secret_name = secret_name.trim();
+    let bytes = secrets_repo::get_secret(db, secrets, node_id, "imap", secret_name)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("missing imap secret: {secret_name}"))?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
 async fn resolve_pipeline_for_agent(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
@@ -77,8 +176,10 @@ async fn resolve_pipeline_for_agent(
     };
     Ok(PipelineResolvedV1 {
         format,
+        compression: pipeline.compression,
         encryption,
         webdav: pipeline.webdav.clone(),
+        staging: pipeline.staging,
     })
 }
 
@@ -98,19 +199,24 @@ async fn resolve_target_for_agent(
                 .await?
                 .ok_or_else(|| anyhow::anyhow!("missing webdav secret: {secret_name}"))?;
             let credentials = WebdavCredentials::from_json(&cred_bytes)?;
+            let pending = webdav_rotation::load_pending(db, secrets, node_id, &secret_name).await?;
             Ok(TargetResolvedV1::Webdav {
                 base_url,
                 username: credentials.username,
                 password: credentials.password,
                 part_size_bytes,
+                secret_name: Some(secret_name),
+                pending,
             })
         }
         job_spec::TargetV1::LocalDir {
             base_dir,
             part_size_bytes,
+            link_unchanged,
         } => Ok(TargetResolvedV1::LocalDir {
             base_dir,
             part_size_bytes,
+            link_unchanged,
         }),
     }
 }
@@ -155,6 +261,12 @@ mod tests {
                 root: "/".to_string(),
                 include: vec![],
                 exclude: vec![],
+                exclude_presets: Vec::new(),
+                use_ignore_files: false,
+                max_file_size_bytes: None,
+                skip_older_than_secs: None,
+                skip_newer_than_secs: None,
+                anomaly_detection: None,
                 symlink_policy: Default::default(),
                 hardlink_policy: Default::default(),
                 error_policy: Default::default(),
@@ -163,6 +275,7 @@ mod tests {
                 consistency_policy: Default::default(),
                 consistency_fail_threshold: None,
                 upload_on_consistency_failure: None,
+                windows_system_state: false,
             },
             target: job_spec::TargetV1::Webdav {
                 base_url: "http://example.com/backup".to_string(),
@@ -183,6 +296,7 @@ mod tests {
             username,
             password,
             part_size_bytes,
+            ..
         } = target
         else {
             panic!("expected webdav target");
@@ -210,6 +324,12 @@ mod tests {
                 root: "/".to_string(),
                 include: vec![],
                 exclude: vec![],
+                exclude_presets: Vec::new(),
+                use_ignore_files: false,
+                max_file_size_bytes: None,
+                skip_older_than_secs: None,
+                skip_newer_than_secs: None,
+                anomaly_detection: None,
                 symlink_policy: Default::default(),
                 hardlink_policy: Default::default(),
                 error_policy: Default::default(),
@@ -218,10 +338,12 @@ mod tests {
                 consistency_policy: Default::default(),
                 consistency_fail_threshold: None,
                 upload_on_consistency_failure: None,
+                windows_system_state: false,
             },
             target: job_spec::TargetV1::LocalDir {
                 base_dir: "/tmp".to_string(),
                 part_size_bytes: 2048,
+                link_unchanged: false,
             },
         };
 
@@ -235,6 +357,7 @@ mod tests {
         let TargetResolvedV1::LocalDir {
             base_dir,
             part_size_bytes,
+            ..
         } = target
         else {
             panic!("expected local_dir target");
@@ -6,8 +6,8 @@ use tokio::sync::{Mutex, RwLock, mpsc, oneshot};
 
 use bastion_core::agent;
 use bastion_core::agent_protocol::{
-    ArtifactStreamOpenResultV1, ArtifactStreamOpenV1, ArtifactStreamPullV1, FsDirEntryV1,
-    HubToAgentMessageV1, PROTOCOL_VERSION,
+    ArtifactStreamOpenResultV1, ArtifactStreamOpenV1, ArtifactStreamPullV1, DiagnosticsReportV1,
+    FsDirEntryV1, HubToAgentMessageV1, PROTOCOL_VERSION,
 };
 use uuid::Uuid;
 
@@ -21,6 +21,10 @@ type WebdavListResult = Result<WebdavListPage, WebdavListRemoteError>;
 type WebdavListSender = oneshot::Sender<WebdavListResult>;
 type PendingWebdavList = HashMap<WebdavListKey, WebdavListSender>;
 
+type DiagnosticsKey = (String, String); // (agent_id, request_id)
+type PendingDiagnostics =
+    HashMap<DiagnosticsKey, oneshot::Sender<Result<DiagnosticsReportV1, String>>>;
+
 type ArtifactStreamKey = (String, Uuid); // (agent_id, stream_id)
 type PendingArtifactOpen = HashMap<ArtifactStreamKey, oneshot::Sender<ArtifactStreamOpenResultV1>>;
 type PendingArtifactChunk =
@@ -44,6 +48,8 @@ pub struct FsListOptions {
     pub sort_dir: Option<String>,
     pub size_min_bytes: Option<u64>,
     pub size_max_bytes: Option<u64>,
+    /// Forwarded to the agent so a newer listing only cancels one from the same requester.
+    pub requester: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -80,6 +86,8 @@ pub struct WebdavListOptions {
     pub sort_dir: Option<String>,
     pub size_min_bytes: Option<u64>,
     pub size_max_bytes: Option<u64>,
+    /// Forwarded to the agent so a newer listing only cancels one from the same requester.
+    pub requester: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -114,6 +122,7 @@ pub struct AgentManager {
     inner: Arc<RwLock<HashMap<String, AgentConnection>>>,
     pending_fs_list: Arc<Mutex<PendingFsList>>,
     pending_webdav_list: Arc<Mutex<PendingWebdavList>>,
+    pending_diagnostics: Arc<Mutex<PendingDiagnostics>>,
     pending_artifact_open: Arc<Mutex<PendingArtifactOpen>>,
     pending_artifact_chunk: Arc<Mutex<PendingArtifactChunk>>,
 }
@@ -149,6 +158,11 @@ impl AgentManager {
             }));
         }
 
+        let mut pending_diagnostics = self.pending_diagnostics.lock().await;
+        for (_key, tx) in pending_diagnostics.extract_if(|key, _| key.0.as_str() == agent_id) {
+            let _ = tx.send(Err("agent disconnected".to_string()));
+        }
+
         let mut pending_open = self.pending_artifact_open.lock().await;
         for ((_id, stream_id), tx) in pending_open.extract_if(|key, _| key.0.as_str() == agent_id) {
             let _ = tx.send(ArtifactStreamOpenResultV1 {
@@ -193,6 +207,33 @@ impl AgentManager {
         self.send(agent_id, Message::Text(text.into())).await
     }
 
+    /// Sends `value` to every connected agent; returns how many accepted it.
+    pub async fn broadcast_json<T: serde::Serialize>(
+        &self,
+        value: &T,
+    ) -> Result<usize, anyhow::Error> {
+        let text = serde_json::to_string(value)?;
+        let senders = self
+            .inner
+            .read()
+            .await
+            .values()
+            .map(|v| v.sender.clone())
+            .collect::<Vec<_>>();
+
+        let mut sent = 0;
+        for sender in senders {
+            if sender
+                .send(Message::Text(text.clone().into()))
+                .await
+                .is_ok()
+            {
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+
     pub async fn send_config_snapshot_json<T: serde::Serialize>(
         &self,
         agent_id: &str,
@@ -257,6 +298,7 @@ impl AgentManager {
             sort_dir: opts.sort_dir,
             size_min_bytes: opts.size_min_bytes,
             size_max_bytes: opts.size_max_bytes,
+            requester: opts.requester,
         };
         if let Err(error) = self.send_json(agent_id, &msg).await {
             let _ = self
@@ -303,6 +345,7 @@ impl AgentManager {
                     sort_dir: None,
                     size_min_bytes: None,
                     size_max_bytes: None,
+                    requester: None,
                 },
                 timeout,
             )
@@ -350,6 +393,7 @@ impl AgentManager {
             sort_dir: opts.sort_dir,
             size_min_bytes: opts.size_min_bytes,
             size_max_bytes: opts.size_max_bytes,
+            requester: opts.requester,
         };
         if let Err(error) = self.send_json(agent_id, &msg).await {
             let _ = self
@@ -388,6 +432,52 @@ impl AgentManager {
         }
     }
 
+    pub async fn run_diagnostics(
+        &self,
+        agent_id: &str,
+        job_id: Option<String>,
+        timeout: std::time::Duration,
+    ) -> Result<DiagnosticsReportV1, anyhow::Error> {
+        let request_id = agent::generate_token_b64_urlsafe(16);
+        let key = (agent_id.to_string(), request_id.clone());
+        let (tx, rx) = oneshot::channel::<Result<DiagnosticsReportV1, String>>();
+        self.pending_diagnostics
+            .lock()
+            .await
+            .insert(key.clone(), tx);
+
+        let msg = HubToAgentMessageV1::Diagnostics {
+            v: PROTOCOL_VERSION,
+            request_id,
+            job_id,
+        };
+        if let Err(error) = self.send_json(agent_id, &msg).await {
+            let _ = self.pending_diagnostics.lock().await.remove(&key);
+            return Err(error);
+        }
+
+        let result = tokio::time::timeout(timeout, rx)
+            .await
+            .map_err(|_| anyhow::anyhow!("agent diagnostics timeout"))?
+            .map_err(|_| anyhow::anyhow!("agent diagnostics channel closed"))?;
+
+        let _ = self.pending_diagnostics.lock().await.remove(&key);
+        result.map_err(anyhow::Error::msg)
+    }
+
+    pub async fn complete_diagnostics(
+        &self,
+        agent_id: &str,
+        request_id: &str,
+        result: Result<DiagnosticsReportV1, String>,
+    ) {
+        let key = (agent_id.to_string(), request_id.to_string());
+        let tx = self.pending_diagnostics.lock().await.remove(&key);
+        if let Some(tx) = tx {
+            let _ = tx.send(result);
+        }
+    }
+
     pub async fn artifact_stream_open(
         &self,
         agent_id: &str,
@@ -513,6 +603,7 @@ mod tests {
                         sort_dir: None,
                         size_min_bytes: None,
                         size_max_bytes: None,
+                        requester: None,
                     },
                     Duration::from_secs(30),
                 )
@@ -557,4 +648,54 @@ mod tests {
         assert_eq!(res.stream_id, stream_id);
         assert_eq!(res.error.as_deref(), Some("agent disconnected"));
     }
+
+    #[tokio::test]
+    async fn broadcast_reaches_every_connected_agent() {
+        let manager = AgentManager::default();
+        let (sender_1, mut receiver_1) = mpsc::channel(8);
+        let (sender_2, mut receiver_2) = mpsc::channel(8);
+        manager.register("agent1".to_string(), sender_1).await;
+        manager.register("agent2".to_string(), sender_2).await;
+
+        let sent = manager
+            .broadcast_json(&serde_json::json!({"type": "hub_shutdown"}))
+            .await
+            .expect("broadcast");
+        assert_eq!(sent, 2);
+        assert!(receiver_1.recv().await.is_some());
+        assert!(receiver_2.recv().await.is_some());
+    }
+
+    #[tokio::test]
+    async fn diagnostics_result_is_routed_by_request_id() {
+        let manager = AgentManager::default();
+        let (sender, mut receiver) = mpsc::channel(8);
+        manager.register("agent1".to_string(), sender).await;
+
+        let manager_task = manager.clone();
+        let task = tokio::spawn(async move {
+            manager_task
+                .run_diagnostics("agent1", Some("job1".to_string()), Duration::from_secs(30))
+                .await
+        });
+
+        let sent = match receiver.recv().await.expect("sent request") {
+            axum::extract::ws::Message::Text(text) => {
+                serde_json::from_str::<serde_json::Value>(text.as_str()).expect("json")
+            }
+            other => panic!("unexpected message: {other:?}"),
+        };
+        assert_eq!(sent["type"], "diagnostics");
+        assert_eq!(sent["job_id"], "job1");
+        let request_id = sent["request_id"].as_str().expect("request_id");
+
+        let report = super::DiagnosticsReportV1 {
+            collected_at: 1,
+            checks: Vec::new(),
+        };
+        manager
+            .complete_diagnostics("agent1", request_id, Ok(report.clone()))
+            .await;
+        assert_eq!(task.await.expect("task join").expect("report"), report);
+    }
 }
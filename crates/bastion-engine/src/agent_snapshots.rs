@@ -9,6 +9,7 @@ use sqlx::SqlitePool;
 
 use crate::agent_job_resolver;
 use crate::agent_manager::AgentManager;
+use crate::webdav_rotation;
 use bastion_storage::agents_repo;
 use bastion_storage::jobs_repo;
 use bastion_storage::secrets::SecretsCrypto;
@@ -20,6 +21,30 @@ struct WebdavSecretPayload {
     password: String,
 }
 
+/// Reads one secret for a node snapshot. An entry that cannot be read (e.g. an unreachable or
+/// revoked external reference) is left out so the rest of the snapshot still reaches the node.
+async fn load_snapshot_secret(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    node_id: &str,
+    kind: &str,
+    name: &str,
+) -> Option<Vec<u8>> {
+    match secrets_repo::get_secret(db, secrets, node_id, kind, name).await {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            tracing::warn!(
+                node_id = %node_id,
+                secret_kind = kind,
+                secret_name = %name,
+                error = %format!("{error:#}"),
+                "skipping secret in node snapshot"
+            );
+            None
+        }
+    }
+}
+
 pub async fn send_node_secrets_snapshot(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
@@ -30,17 +55,18 @@ pub async fn send_node_secrets_snapshot(
 
     let mut webdav = Vec::with_capacity(list.len());
     for entry in list {
-        let Some(bytes) =
-            secrets_repo::get_secret(db, secrets, node_id, "webdav", &entry.name).await?
+        let Some(bytes) = load_snapshot_secret(db, secrets, node_id, "webdav", &entry.name).await
         else {
             continue;
         };
         let payload: WebdavSecretPayload = serde_json::from_slice(&bytes)?;
+        let pending = webdav_rotation::load_pending(db, secrets, node_id, &entry.name).await?;
         webdav.push(WebdavSecretV1 {
             name: entry.name,
             username: payload.username,
             password: payload.password,
             updated_at: entry.updated_at,
+            pending,
         });
     }
 
@@ -48,8 +74,7 @@ pub async fn send_node_secrets_snapshot(
     let mut backup_age_identities = Vec::with_capacity(list.len());
     for entry in list {
         let Some(bytes) =
-            secrets_repo::get_secret(db, secrets, node_id, "backup_age_identity", &entry.name)
-                .await?
+            load_snapshot_secret(db, secrets, node_id, "backup_age_identity", &entry.name).await
         else {
             continue;
         };
@@ -188,13 +213,19 @@ mod tests {
     use sqlx::Row;
     use tempfile::TempDir;
 
+    use axum::extract::ws::Message;
+    use bastion_core::agent_protocol::HubToAgentMessageV1;
     use bastion_core::{agent, job_spec};
     use bastion_storage::jobs_repo::OverlapPolicy;
     use bastion_storage::secrets::SecretsCrypto;
-    use bastion_storage::{db, jobs_repo};
+    use bastion_storage::{db, jobs_repo, secrets_repo};
 
-    use super::{SendConfigSnapshotOutcome, send_node_config_snapshot_with_outcome};
+    use super::{
+        SendConfigSnapshotOutcome, send_node_config_snapshot_with_outcome,
+        send_node_secrets_snapshot,
+    };
     use crate::agent_manager::AgentManager;
+    use crate::webdav_rotation;
 
     #[tokio::test]
     async fn offline_send_sets_desired_snapshot_and_returns_pending_offline() {
@@ -224,6 +255,12 @@ mod tests {
                 root: "/".to_string(),
                 include: vec![],
                 exclude: vec![],
+                exclude_presets: Vec::new(),
+                use_ignore_files: false,
+                max_file_size_bytes: None,
+                skip_older_than_secs: None,
+                skip_newer_than_secs: None,
+                anomaly_detection: None,
                 symlink_policy: Default::default(),
                 hardlink_policy: Default::default(),
                 error_policy: Default::default(),
@@ -232,10 +269,12 @@ mod tests {
                 consistency_policy: Default::default(),
                 consistency_fail_threshold: None,
                 upload_on_consistency_failure: None,
+                windows_system_state: false,
             },
             target: job_spec::TargetV1::LocalDir {
                 base_dir: "/tmp".to_string(),
                 part_size_bytes: 2048,
+                link_unchanged: false,
             },
         };
 
@@ -274,4 +313,46 @@ mod tests {
                 .is_none()
         );
     }
+
+    #[tokio::test]
+    async fn secrets_snapshot_carries_staged_webdav_credential() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        let crypto = SecretsCrypto::load_or_create(temp.path()).expect("crypto");
+
+        let agent_id = "agent1";
+        secrets_repo::upsert_secret(
+            &pool,
+            &crypto,
+            agent_id,
+            webdav_rotation::WEBDAV_KIND,
+            "main",
+            br#"{"username":"old","password":"old-pw"}"#,
+        )
+        .await
+        .expect("active secret");
+        webdav_rotation::stage_pending(&pool, &crypto, agent_id, "main", "new", "new-pw")
+            .await
+            .expect("stage");
+
+        let agent_manager = AgentManager::default();
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<Message>(4);
+        agent_manager.register(agent_id.to_string(), tx).await;
+        send_node_secrets_snapshot(&pool, &crypto, &agent_manager, agent_id)
+            .await
+            .expect("send");
+
+        let Some(Message::Text(text)) = rx.recv().await else {
+            panic!("expected a text message");
+        };
+        let msg: HubToAgentMessageV1 = serde_json::from_str(&text).expect("message json");
+        let HubToAgentMessageV1::SecretsSnapshot { webdav, .. } = msg else {
+            panic!("expected a secrets snapshot");
+        };
+        assert_eq!(webdav.len(), 1);
+        assert_eq!(webdav[0].username, "old");
+        let pending = webdav[0].pending.as_ref().expect("pending credential");
+        assert_eq!(pending.username, "new");
+        assert_eq!(pending.password, "new-pw");
+    }
 }
@@ -106,6 +106,29 @@ struct JobDeployFailure {
     message: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JobSyncOutcome {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+impl JobSyncOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::Unchanged => "unchanged",
+        }
+    }
+}
+
+struct JobSyncResult {
+    outcome: JobSyncOutcome,
+    job_name: String,
+    config_send_error: Option<String>,
+}
+
 async fn distribute_webdav_secret_to_agent(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
@@ -162,30 +185,15 @@ async fn disambiguate_job_name(
     }
 }
 
-async fn deploy_job_to_agent(
+/// Checks that `source_job`'s spec is valid and resolvable on `target_agent_id`.
+async fn validate_job_for_agent(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
-    agent_manager: &AgentManager,
     target_agent_id: &str,
     source_job: &jobs_repo::Job,
-    name_template: &str,
-) -> Result<Option<String>, JobDeployFailure> {
+) -> Result<(), JobDeployFailure> {
     use bastion_core::job_spec;
 
-    let planned_base = render_name_template(name_template, &source_job.name, target_agent_id);
-    if planned_base.is_empty() {
-        return Err(JobDeployFailure {
-            kind: "invalid_payload",
-            message: "name_template produced empty name".to_string(),
-        });
-    }
-    let planned_name = disambiguate_job_name(db, target_agent_id, &planned_base)
-        .await
-        .map_err(|e| JobDeployFailure {
-            kind: "internal_error",
-            message: e.to_string(),
-        })?;
-
     let spec = job_spec::parse_value(&source_job.spec).map_err(|e| JobDeployFailure {
         kind: "invalid_payload",
         message: e.to_string(),
@@ -201,8 +209,31 @@ async fn deploy_job_to_agent(
             kind: "validation_failed",
             message: e.to_string(),
         })?;
+    Ok(())
+}
+
+/// Creates a copy of `source_job` on `target_agent_id`, linked back to the source.
+async fn create_job_copy(
+    db: &SqlitePool,
+    target_agent_id: &str,
+    source_job: &jobs_repo::Job,
+    name_template: &str,
+) -> Result<jobs_repo::Job, JobDeployFailure> {
+    let planned_base = render_name_template(name_template, &source_job.name, target_agent_id);
+    if planned_base.is_empty() {
+        return Err(JobDeployFailure {
+            kind: "invalid_payload",
+            message: "name_template produced empty name".to_string(),
+        });
+    }
+    let planned_name = disambiguate_job_name(db, target_agent_id, &planned_base)
+        .await
+        .map_err(|e| JobDeployFailure {
+            kind: "internal_error",
+            message: e.to_string(),
+        })?;
 
-    let _created = jobs_repo::create_job(
+    let created = jobs_repo::create_job(
         db,
         &planned_name,
         Some(target_agent_id),
@@ -216,6 +247,52 @@ async fn deploy_job_to_agent(
         kind: "create_failed",
         message: e.to_string(),
     })?;
+    if source_job.schedule_jitter_seconds > 0 {
+        jobs_repo::set_job_schedule_jitter(db, &created.id, source_job.schedule_jitter_seconds)
+            .await
+            .map_err(|e| JobDeployFailure {
+                kind: "create_failed",
+                message: e.to_string(),
+            })?;
+    }
+    if source_job.max_success_interval_seconds > 0 {
+        jobs_repo::set_job_max_success_interval(
+            db,
+            &created.id,
+            source_job.max_success_interval_seconds,
+        )
+        .await
+        .map_err(|e| JobDeployFailure {
+            kind: "create_failed",
+            message: e.to_string(),
+        })?;
+    }
+    jobs_repo::set_job_template(db, &created.id, Some(&source_job.id))
+        .await
+        .map_err(|e| JobDeployFailure {
+            kind: "create_failed",
+            message: e.to_string(),
+        })?;
+
+    Ok(created)
+}
+
+async fn deploy_job_to_agent(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    agent_manager: &AgentManager,
+    target_agent_id: &str,
+    source_job: &jobs_repo::Job,
+    name_template: &str,
+) -> Result<Option<String>, JobDeployFailure> {
+    if render_name_template(name_template, &source_job.name, target_agent_id).is_empty() {
+        return Err(JobDeployFailure {
+            kind: "invalid_payload",
+            message: "name_template produced empty name".to_string(),
+        });
+    }
+    validate_job_for_agent(db, secrets, target_agent_id, source_job).await?;
+    create_job_copy(db, target_agent_id, source_job, name_template).await?;
 
     match send_node_config_snapshot_with_outcome(db, secrets, agent_manager, target_agent_id).await
     {
@@ -224,6 +301,101 @@ async fn deploy_job_to_agent(
     }
 }
 
+/// Whether a job instantiated from `source_job` already carries its spec and schedule settings.
+pub fn job_matches_template(job: &jobs_repo::Job, source_job: &jobs_repo::Job) -> bool {
+    job.spec == source_job.spec
+        && job.schedule == source_job.schedule
+        && job.schedule_timezone == source_job.schedule_timezone
+        && job.overlap_policy == source_job.overlap_policy
+        && job.schedule_jitter_seconds == source_job.schedule_jitter_seconds
+        && job.max_success_interval_seconds == source_job.max_success_interval_seconds
+}
+
+/// Creates the agent's instance of `source_job`, or brings an existing one back in line with it.
+///
+/// Instances keep their own name once created, so renaming one on a node survives re-syncs.
+async fn sync_job_to_agent(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    agent_manager: &AgentManager,
+    target_agent_id: &str,
+    source_job: &jobs_repo::Job,
+    name_template: &str,
+) -> Result<JobSyncResult, JobDeployFailure> {
+    let existing = jobs_repo::find_template_instance(db, &source_job.id, target_agent_id)
+        .await
+        .map_err(|e| JobDeployFailure {
+            kind: "internal_error",
+            message: e.to_string(),
+        })?;
+
+    if let Some(job) = existing.as_ref()
+        && job_matches_template(job, source_job)
+    {
+        return Ok(JobSyncResult {
+            outcome: JobSyncOutcome::Unchanged,
+            job_name: job.name.clone(),
+            config_send_error: None,
+        });
+    }
+
+    validate_job_for_agent(db, secrets, target_agent_id, source_job).await?;
+
+    let (outcome, job_name) = match existing {
+        Some(job) => {
+            jobs_repo::update_job(
+                db,
+                jobs_repo::UpdateJobParams {
+                    job_id: &job.id,
+                    name: &job.name,
+                    agent_id: Some(target_agent_id),
+                    schedule: source_job.schedule.as_deref(),
+                    schedule_timezone: Some(&source_job.schedule_timezone),
+                    overlap_policy: source_job.overlap_policy,
+                    spec: source_job.spec.clone(),
+                },
+            )
+            .await
+            .map_err(|e| JobDeployFailure {
+                kind: "update_failed",
+                message: e.to_string(),
+            })?;
+            jobs_repo::set_job_schedule_jitter(db, &job.id, source_job.schedule_jitter_seconds)
+                .await
+                .map_err(|e| JobDeployFailure {
+                    kind: "update_failed",
+                    message: e.to_string(),
+                })?;
+            jobs_repo::set_job_max_success_interval(
+                db,
+                &job.id,
+                source_job.max_success_interval_seconds,
+            )
+            .await
+            .map_err(|e| JobDeployFailure {
+                kind: "update_failed",
+                message: e.to_string(),
+            })?;
+            (JobSyncOutcome::Updated, job.name)
+        }
+        None => {
+            let created = create_job_copy(db, target_agent_id, source_job, name_template).await?;
+            (JobSyncOutcome::Created, created.name)
+        }
+    };
+
+    let config_send_error =
+        send_node_config_snapshot_with_outcome(db, secrets, agent_manager, target_agent_id)
+            .await
+            .err()
+            .map(|error| error.to_string());
+    Ok(JobSyncResult {
+        outcome,
+        job_name,
+        config_send_error,
+    })
+}
+
 async fn process_item(
     db: SqlitePool,
     secrets: Arc<SecretsCrypto>,
@@ -391,7 +563,7 @@ async fn process_item(
                 }
             }
         }
-        "job_deploy" => {
+        "job_deploy" | "job_sync" => {
             let payload: JobDeployPayload = match serde_json::from_str(&item.payload_json) {
                 Ok(v) => v,
                 Err(error) => {
@@ -459,6 +631,52 @@ async fn process_item(
                 return;
             }
 
+            if item.kind == "job_sync" {
+                match sync_job_to_agent(
+                    &db,
+                    secrets.as_ref(),
+                    &agent_manager,
+                    &item.agent_id,
+                    &source_job,
+                    template,
+                )
+                .await
+                {
+                    Ok(result) => {
+                        let (note_kind, note) = match result.config_send_error {
+                            Some(error) => (
+                                "config_send_failed",
+                                format!(
+                                    "{} {}; config snapshot not sent: {error}",
+                                    result.outcome.as_str(),
+                                    result.job_name
+                                ),
+                            ),
+                            None => (result.outcome.as_str(), result.job_name),
+                        };
+                        let _ = bulk_operations_repo::mark_item_succeeded_with_note(
+                            &db,
+                            &item.op_id,
+                            &item.agent_id,
+                            note_kind,
+                            &note,
+                        )
+                        .await;
+                    }
+                    Err(failure) => {
+                        let _ = bulk_operations_repo::mark_item_failed(
+                            &db,
+                            &item.op_id,
+                            &item.agent_id,
+                            failure.kind,
+                            &failure.message,
+                        )
+                        .await;
+                    }
+                }
+                return;
+            }
+
             match deploy_job_to_agent(
                 &db,
                 secrets.as_ref(),
@@ -563,6 +781,12 @@ mod tests {
                 root: "/".to_string(),
                 include: vec![],
                 exclude: vec![],
+                exclude_presets: Vec::new(),
+                use_ignore_files: false,
+                max_file_size_bytes: None,
+                skip_older_than_secs: None,
+                skip_newer_than_secs: None,
+                anomaly_detection: None,
                 symlink_policy: Default::default(),
                 hardlink_policy: Default::default(),
                 error_policy: Default::default(),
@@ -571,6 +795,7 @@ mod tests {
                 consistency_policy: Default::default(),
                 consistency_fail_threshold: None,
                 upload_on_consistency_failure: None,
+                windows_system_state: false,
             },
             target: job_spec::TargetV1::Webdav {
                 base_url: "http://example.com/backup".to_string(),
@@ -856,4 +1081,95 @@ mod tests {
         assert_eq!(jobs.len(), 2);
         assert!(jobs.iter().any(|j| j.name == "Backup (agent1) #2"));
     }
+
+    #[tokio::test]
+    async fn job_sync_creates_then_updates_idempotently() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        let crypto = SecretsCrypto::load_or_create(temp.path()).expect("crypto");
+        let secrets = Arc::new(crypto);
+        let agent_manager = AgentManager::default();
+        let user_id = create_user_id(&pool).await;
+
+        insert_agent(&pool, "agent1").await;
+        insert_webdav_secret(&pool, secrets.as_ref(), "agent1", "primary").await;
+
+        let source_job = jobs_repo::create_job(
+            &pool,
+            "Backup",
+            None,
+            Some("0 0 * * *"),
+            Some("UTC"),
+            jobs_repo::OverlapPolicy::Reject,
+            example_webdav_job_spec("primary"),
+        )
+        .await
+        .expect("create source job");
+
+        let run_sync = |pool: sqlx::SqlitePool, secrets: Arc<SecretsCrypto>| {
+            let agent_manager = agent_manager.clone();
+            let source_job_id = source_job.id.clone();
+            async move {
+                let op_id = bulk_operations_repo::create_operation(
+                    &pool,
+                    user_id,
+                    "job_sync",
+                    &serde_json::json!({"node_ids":["agent1"]}),
+                    &serde_json::json!({
+                        "source_job_id": source_job_id,
+                        "name_template": "{name} ({node})"
+                    }),
+                    &["agent1".to_string()],
+                )
+                .await
+                .expect("create op");
+                for item in bulk_operations_repo::claim_next_items(&pool, 10)
+                    .await
+                    .expect("claim")
+                {
+                    super::process_item(pool.clone(), secrets.clone(), agent_manager.clone(), item)
+                        .await;
+                }
+                let op = bulk_operations_repo::get_operation(&pool, &op_id)
+                    .await
+                    .expect("get op")
+                    .expect("op exists");
+                let item = op.items.into_iter().next().expect("item");
+                assert_eq!(item.status, "success");
+                item.last_error_kind.expect("outcome")
+            }
+        };
+
+        assert_eq!(run_sync(pool.clone(), secrets.clone()).await, "created");
+        let jobs = jobs_repo::list_jobs_for_agent(&pool, "agent1")
+            .await
+            .expect("list");
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].name, "Backup (agent1)");
+
+        assert_eq!(run_sync(pool.clone(), secrets.clone()).await, "unchanged");
+
+        jobs_repo::update_job(
+            &pool,
+            jobs_repo::UpdateJobParams {
+                job_id: &source_job.id,
+                name: "Backup",
+                agent_id: None,
+                schedule: Some("30 1 * * *"),
+                schedule_timezone: Some("UTC"),
+                overlap_policy: jobs_repo::OverlapPolicy::Queue,
+                spec: example_webdav_job_spec("primary"),
+            },
+        )
+        .await
+        .expect("update source job");
+
+        assert_eq!(run_sync(pool.clone(), secrets.clone()).await, "updated");
+        let jobs = jobs_repo::list_jobs_for_agent(&pool, "agent1")
+            .await
+            .expect("list");
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].schedule.as_deref(), Some("30 1 * * *"));
+        assert_eq!(jobs[0].overlap_policy, jobs_repo::OverlapPolicy::Queue);
+    }
 }
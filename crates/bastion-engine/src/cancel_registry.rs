@@ -22,6 +22,15 @@ impl CancelRegistry {
         Self::cancel_token(&self.run_tokens, run_id)
     }
 
+    /// Cancels every registered run; returns how many there were.
+    pub fn cancel_all_runs(&self) -> usize {
+        let guard = lock_or_recover(&self.run_tokens);
+        for token in guard.values() {
+            token.cancel();
+        }
+        guard.len()
+    }
+
     pub fn register_operation(&self, op_id: &str) -> CancellationToken {
         Self::register_token(&self.operation_tokens, op_id)
     }
@@ -90,6 +99,19 @@ mod tests {
         assert!(!registry.cancel_run("run-1"));
     }
 
+    #[test]
+    fn cancel_all_runs_leaves_operations_alone() {
+        let registry = CancelRegistry::default();
+        let run_1 = registry.register_run("run-1");
+        let run_2 = registry.register_run("run-2");
+        let op = registry.register_operation("op-1");
+
+        assert_eq!(registry.cancel_all_runs(), 2);
+        assert!(run_1.is_cancelled());
+        assert!(run_2.is_cancelled());
+        assert!(!op.is_cancelled());
+    }
+
     #[test]
     fn operation_registration_cancel_and_unregistration_work() {
         let registry = CancelRegistry::default();
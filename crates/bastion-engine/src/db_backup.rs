@@ -0,0 +1,283 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use time::format_description::BorrowedFormatItem;
+use time::macros::format_description;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use bastion_storage::db;
+
+use crate::supervision::spawn_supervised;
+
+const CHECK_INTERVAL_SECS: u64 = 60 * 60;
+const BACKUP_INTERVAL_SECS: i64 = 24 * 60 * 60;
+
+/// Directory (under the data directory) that holds database snapshots.
+pub const BACKUPS_DIR: &str = "backups";
+/// Number of database snapshots kept; older ones are removed after each new snapshot.
+pub const BACKUP_KEEP: usize = 7;
+
+const BACKUP_PREFIX: &str = "bastion-";
+const BACKUP_SUFFIX: &str = ".db";
+const BACKUP_STAMP: &[BorrowedFormatItem<'static>] =
+    format_description!("[year][month][day]T[hour][minute][second]Z");
+
+/// Serializes snapshots between the background loop and on-demand API calls.
+static BACKUP_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DbBackupFile {
+    pub name: String,
+    pub size_bytes: u64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DbStatus {
+    pub db_size_bytes: u64,
+    pub wal_size_bytes: u64,
+    pub backups_dir: String,
+    pub backup_keep: usize,
+    pub backup_interval_seconds: i64,
+    pub backups: Vec<DbBackupFile>,
+}
+
+pub struct DbBackupArgs {
+    pub db: SqlitePool,
+    pub data_dir: PathBuf,
+    pub shutdown: CancellationToken,
+}
+
+pub fn spawn(args: DbBackupArgs) {
+    let shutdown = args.shutdown.clone();
+    spawn_supervised("db_backup.loop", shutdown, run_loop(args));
+}
+
+async fn run_loop(args: DbBackupArgs) {
+    loop {
+        if args.shutdown.is_cancelled() {
+            break;
+        }
+
+        match checkpoint_and_optimize(&args.db).await {
+            Ok(checkpoint) if checkpoint.busy => {
+                debug!(
+                    log_frames = checkpoint.log_frames,
+                    checkpointed_frames = checkpoint.checkpointed_frames,
+                    "wal checkpoint could not complete; will retry"
+                );
+            }
+            Ok(_) => {}
+            Err(error) => warn!(error = %error, "wal checkpoint failed"),
+        }
+
+        let now = OffsetDateTime::now_utc();
+        match backup_due(&args.data_dir, now.unix_timestamp()).await {
+            Ok(true) => match create_backup(&args.db, &args.data_dir, now).await {
+                Ok(backup) => info!(
+                    name = %backup.name,
+                    size_bytes = backup.size_bytes,
+                    "database snapshot written"
+                ),
+                Err(error) => warn!(error = %error, "database snapshot failed"),
+            },
+            Ok(false) => {}
+            Err(error) => warn!(error = %error, "failed to list database snapshots"),
+        }
+
+        tokio::select! {
+            _ = args.shutdown.cancelled() => break,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {}
+        }
+    }
+}
+
+pub async fn checkpoint_and_optimize(db: &SqlitePool) -> Result<db::WalCheckpoint, anyhow::Error> {
+    let checkpoint = db::wal_checkpoint(db).await?;
+    db::optimize(db).await?;
+    Ok(checkpoint)
+}
+
+async fn backup_due(data_dir: &Path, now: i64) -> Result<bool, anyhow::Error> {
+    let backups = list_backups(data_dir).await?;
+    Ok(backups
+        .first()
+        .is_none_or(|latest| now.saturating_sub(latest.created_at) >= BACKUP_INTERVAL_SECS))
+}
+
+/// Writes a `VACUUM INTO` snapshot of the Hub database to `<data_dir>/backups` and removes the
+/// oldest snapshots beyond [`BACKUP_KEEP`].
+pub async fn create_backup(
+    db: &SqlitePool,
+    data_dir: &Path,
+    now: OffsetDateTime,
+) -> Result<DbBackupFile, anyhow::Error> {
+    let _guard = BACKUP_LOCK.lock().await;
+
+    let dir = data_dir.join(BACKUPS_DIR);
+    tokio::fs::create_dir_all(&dir).await?;
+
+    let stamp = now.format(BACKUP_STAMP)?;
+    let name = format!("{BACKUP_PREFIX}{stamp}{BACKUP_SUFFIX}");
+    let path = dir.join(&name);
+    if tokio::fs::try_exists(&path).await? {
+        anyhow::bail!("database snapshot {name} already exists");
+    }
+
+    // VACUUM INTO refuses to overwrite, so a stale partial file from a crash is removed first.
+    let tmp = dir.join(format!("{name}.partial"));
+    let _ = tokio::fs::remove_file(&tmp).await;
+    if let Err(error) = db::vacuum_into(db, &tmp).await {
+        let _ = tokio::fs::remove_file(&tmp).await;
+        return Err(error);
+    }
+
+    // The snapshot holds encrypted secrets and password hashes.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        tokio::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600)).await?;
+    }
+    tokio::fs::rename(&tmp, &path).await?;
+
+    let size_bytes = tokio::fs::metadata(&path).await?.len();
+    rotate(data_dir, BACKUP_KEEP).await?;
+
+    Ok(DbBackupFile {
+        name,
+        size_bytes,
+        created_at: now.unix_timestamp(),
+    })
+}
+
+/// Lists database snapshots, newest first.
+pub async fn list_backups(data_dir: &Path) -> Result<Vec<DbBackupFile>, anyhow::Error> {
+    let dir = data_dir.join(BACKUPS_DIR);
+    let mut rd = match tokio::fs::read_dir(&dir).await {
+        Ok(v) => v,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error.into()),
+    };
+
+    let mut out = Vec::new();
+    while let Some(entry) = rd.next_entry().await? {
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let Some(created_at) = parse_backup_name(&name) else {
+            continue;
+        };
+        let meta = entry.metadata().await?;
+        if !meta.is_file() {
+            continue;
+        }
+        out.push(DbBackupFile {
+            name,
+            size_bytes: meta.len(),
+            created_at,
+        });
+    }
+    out.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(out)
+}
+
+pub async fn status(data_dir: &Path) -> Result<DbStatus, anyhow::Error> {
+    let size_of = |name: &str| {
+        let path = data_dir.join(name);
+        async move {
+            tokio::fs::metadata(path)
+                .await
+                .map(|m| m.len())
+                .unwrap_or(0)
+        }
+    };
+
+    Ok(DbStatus {
+        db_size_bytes: size_of("bastion.db").await,
+        wal_size_bytes: size_of("bastion.db-wal").await,
+        backups_dir: data_dir.join(BACKUPS_DIR).display().to_string(),
+        backup_keep: BACKUP_KEEP,
+        backup_interval_seconds: BACKUP_INTERVAL_SECS,
+        backups: list_backups(data_dir).await?,
+    })
+}
+
+fn parse_backup_name(name: &str) -> Option<i64> {
+    let stamp = name
+        .strip_prefix(BACKUP_PREFIX)?
+        .strip_suffix(BACKUP_SUFFIX)?;
+    let parsed = time::PrimitiveDateTime::parse(stamp, BACKUP_STAMP).ok()?;
+    Some(parsed.assume_utc().unix_timestamp())
+}
+
+async fn rotate(data_dir: &Path, keep: usize) -> Result<(), anyhow::Error> {
+    let dir = data_dir.join(BACKUPS_DIR);
+    for old in list_backups(data_dir).await?.into_iter().skip(keep) {
+        tokio::fs::remove_file(dir.join(&old.name)).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+    use time::OffsetDateTime;
+
+    use bastion_storage::{auth, db};
+
+    use super::{BACKUP_KEEP, BACKUPS_DIR, backup_due, create_backup, list_backups};
+
+    #[tokio::test]
+    async fn snapshots_are_restorable_and_rotated() {
+        let data = TempDir::new().expect("tempdir");
+        let pool = db::init(data.path()).await.expect("db init");
+        auth::create_user(&pool, "admin", "password")
+            .await
+            .expect("create user");
+
+        let start = OffsetDateTime::from_unix_timestamp(1_700_000_000).expect("ts");
+        assert!(
+            backup_due(data.path(), start.unix_timestamp())
+                .await
+                .expect("due")
+        );
+
+        let first = create_backup(&pool, data.path(), start)
+            .await
+            .expect("backup");
+        assert!(
+            !backup_due(data.path(), start.unix_timestamp() + 60)
+                .await
+                .expect("due")
+        );
+
+        // A snapshot opens as a regular Hub database.
+        let restored = TempDir::new().expect("tempdir");
+        std::fs::copy(
+            data.path().join(BACKUPS_DIR).join(&first.name),
+            restored.path().join("bastion.db"),
+        )
+        .expect("copy");
+        let restored_pool = db::init(restored.path()).await.expect("open snapshot");
+        assert!(
+            auth::find_user_by_username(&restored_pool, "admin")
+                .await
+                .expect("find user")
+                .is_some()
+        );
+
+        for day in 1..=(BACKUP_KEEP as i64 + 2) {
+            let now = start + time::Duration::days(day);
+            create_backup(&pool, data.path(), now)
+                .await
+                .expect("backup");
+        }
+        let backups = list_backups(data.path()).await.expect("list");
+        assert_eq!(backups.len(), BACKUP_KEEP);
+        assert!(backups.iter().all(|b| b.name != first.name));
+        assert!(backups[0].created_at > backups[1].created_at);
+    }
+}
@@ -0,0 +1,376 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use bastion_storage::runs_repo::{self, RunStatus};
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{db, secrets_repo};
+
+const MAX_INTEGRITY_ERRORS: u32 = 20;
+const MAX_DETAILS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub id: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// What the operator can do about a `warn`/`fail` finding.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+}
+
+impl DoctorCheck {
+    fn ok(id: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            status: CheckStatus::Ok,
+            message: message.into(),
+            hint: None,
+            details: Vec::new(),
+        }
+    }
+
+    fn finding(
+        id: &'static str,
+        status: CheckStatus,
+        message: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        Self {
+            id,
+            status,
+            message: message.into(),
+            hint: Some(hint.into()),
+            details: Vec::new(),
+        }
+    }
+
+    fn with_details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub ok: bool,
+    pub checks: Vec<DoctorCheck>,
+}
+
+/// Checks the Hub data directory: database integrity, the master keyring, that every stored
+/// secret still decrypts, and leftover staging directories.
+pub async fn check_data_dir(db: &SqlitePool, data_dir: &Path, staging_root: &Path) -> DoctorReport {
+    let mut checks = vec![check_database(db).await];
+
+    let (keyring_check, crypto) = check_master_key(data_dir);
+    checks.push(keyring_check);
+    checks.push(check_secrets(db, crypto.as_ref()).await);
+    checks.push(check_staging(db, staging_root).await);
+
+    let ok = checks.iter().all(|c| c.status != CheckStatus::Fail);
+    DoctorReport { ok, checks }
+}
+
+pub async fn check_database(db: &SqlitePool) -> DoctorCheck {
+    match db::integrity_check(db, MAX_INTEGRITY_ERRORS).await {
+        Ok(problems) if problems.is_empty() => {
+            DoctorCheck::ok("db_integrity", "database integrity check passed")
+        }
+        Ok(problems) => DoctorCheck::finding(
+            "db_integrity",
+            CheckStatus::Fail,
+            format!(
+                "database integrity check reported {} problem(s)",
+                problems.len()
+            ),
+            "Stop the Hub, keep a copy of bastion.db, and restore it from a backup.",
+        )
+        .with_details(problems),
+        Err(error) => DoctorCheck::finding(
+            "db_integrity",
+            CheckStatus::Fail,
+            format!("database integrity check failed: {error}"),
+            "Make sure bastion.db is readable and not locked by another process.",
+        ),
+    }
+}
+
+/// Loads `master.key` without creating it, so a missing keyring is reported instead of replaced.
+pub fn check_master_key(data_dir: &Path) -> (DoctorCheck, Option<SecretsCrypto>) {
+    if !data_dir.join("master.key").exists() {
+        let check = DoctorCheck::finding(
+            "master_key",
+            CheckStatus::Warn,
+            "master.key not found",
+            "A new keyring is created on the next Hub start. If this data directory had secrets, import a keypack first (`bastion keypack import`).",
+        );
+        return (check, None);
+    }
+
+    match SecretsCrypto::load_or_create(data_dir) {
+        Ok(crypto) => {
+            let check = DoctorCheck::ok(
+                "master_key",
+                format!("master.key loaded (active key id {})", crypto.active_kid()),
+            );
+            (check, Some(crypto))
+        }
+        Err(error) => {
+            let check = DoctorCheck::finding(
+                "master_key",
+                CheckStatus::Fail,
+                format!("master.key could not be loaded: {error}"),
+                "Restore master.key from a backup or import a keypack (`bastion keypack import`).",
+            );
+            (check, None)
+        }
+    }
+}
+
+pub async fn check_secrets(db: &SqlitePool, crypto: Option<&SecretsCrypto>) -> DoctorCheck {
+    let keys = match secrets_repo::list_encrypted_secret_keys(db).await {
+        Ok(v) => v,
+        Err(error) => {
+            return DoctorCheck::finding(
+                "secrets_decrypt",
+                CheckStatus::Fail,
+                format!("failed to list secrets: {error}"),
+                "Check the database integrity finding first.",
+            );
+        }
+    };
+    if keys.is_empty() {
+        return DoctorCheck::ok("secrets_decrypt", "no stored secrets");
+    }
+    let Some(crypto) = crypto else {
+        return DoctorCheck::finding(
+            "secrets_decrypt",
+            CheckStatus::Fail,
+            format!(
+                "{} stored secret(s) cannot be decrypted without master.key",
+                keys.len()
+            ),
+            "Restore master.key from a backup or import a keypack (`bastion keypack import`).",
+        );
+    };
+
+    let mut failed = Vec::new();
+    for (node_id, kind, name) in &keys {
+        if let Err(error) = secrets_repo::get_secret(db, crypto, node_id, kind, name).await {
+            failed.push(format!("{node_id}/{kind}/{name}: {error}"));
+        }
+    }
+
+    if failed.is_empty() {
+        return DoctorCheck::ok(
+            "secrets_decrypt",
+            format!("all {} stored secret(s) decrypt", keys.len()),
+        );
+    }
+    let message = format!(
+        "{} of {} stored secret(s) do not decrypt",
+        failed.len(),
+        keys.len()
+    );
+    failed.truncate(MAX_DETAILS);
+    DoctorCheck::finding(
+        "secrets_decrypt",
+        CheckStatus::Fail,
+        message,
+        "These secrets were encrypted with a key that is not in master.key. Import the matching keypack, or re-enter the secrets.",
+    )
+    .with_details(failed)
+}
+
+/// Reports run staging directories that no queued or running run owns.
+pub async fn check_staging(db: &SqlitePool, staging_root: &Path) -> DoctorCheck {
+    let runs_dir = staging_root.join("runs");
+    let entries = match list_dirs(&runs_dir).await {
+        Ok(v) => v,
+        Err(error) => {
+            return DoctorCheck::finding(
+                "staging_leftovers",
+                CheckStatus::Warn,
+                format!("failed to list {}: {error}", runs_dir.display()),
+                "Check permissions on the staging directory.",
+            );
+        }
+    };
+
+    let mut leftovers = Vec::new();
+    for (run_id, path) in entries {
+        let active = match runs_repo::get_run(db, &run_id).await {
+            Ok(Some(run)) => matches!(run.status, RunStatus::Queued | RunStatus::Running),
+            Ok(None) => false,
+            // When in doubt, never suggest deleting a directory.
+            Err(_) => true,
+        };
+        if !active {
+            leftovers.push(path);
+        }
+    }
+
+    if leftovers.is_empty() {
+        return DoctorCheck::ok("staging_leftovers", "no leftover staging directories");
+    }
+
+    let bytes = {
+        let paths = leftovers.clone();
+        tokio::task::spawn_blocking(move || paths.iter().map(|p| dir_size(p)).sum::<u64>())
+            .await
+            .unwrap_or(0)
+    };
+    let message = format!(
+        "{} leftover staging director{} ({bytes} bytes) under {}",
+        leftovers.len(),
+        if leftovers.len() == 1 { "y" } else { "ies" },
+        runs_dir.display()
+    );
+    let details = leftovers
+        .iter()
+        .take(MAX_DETAILS)
+        .map(|p| p.display().to_string())
+        .collect();
+    DoctorCheck::finding(
+        "staging_leftovers",
+        CheckStatus::Warn,
+        message,
+        "No queued or running run uses these directories; they are safe to delete.",
+    )
+    .with_details(details)
+}
+
+async fn list_dirs(dir: &Path) -> Result<Vec<(String, PathBuf)>, std::io::Error> {
+    let mut out = Vec::new();
+    let mut rd = match tokio::fs::read_dir(dir).await {
+        Ok(v) => v,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(out),
+        Err(error) => return Err(error),
+    };
+    while let Some(entry) = rd.next_entry().await? {
+        if !entry.file_type().await?.is_dir() {
+            continue;
+        }
+        if let Some(name) = entry.file_name().to_str() {
+            out.push((name.to_string(), entry.path()));
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use bastion_storage::runs_repo::{self, RunStatus};
+    use bastion_storage::secrets::SecretsCrypto;
+    use bastion_storage::{db, jobs_repo, secrets_repo};
+
+    use super::{CheckStatus, check_data_dir};
+
+    #[tokio::test]
+    async fn reports_undecryptable_secrets_and_staging_leftovers() {
+        let data = TempDir::new().expect("tempdir");
+        let pool = db::init(data.path()).await.expect("db init");
+
+        let report = check_data_dir(&pool, data.path(), data.path()).await;
+        assert!(report.ok);
+        let master_key = report
+            .checks
+            .iter()
+            .find(|c| c.id == "master_key")
+            .expect("master_key check");
+        assert_eq!(master_key.status, CheckStatus::Warn);
+        // Doctor never creates a keyring on its own.
+        assert!(!data.path().join("master.key").exists());
+
+        let crypto = SecretsCrypto::load_or_create(data.path()).expect("secrets");
+        secrets_repo::upsert_secret(&pool, &crypto, "hub", "webdav", "primary", b"secret")
+            .await
+            .expect("upsert");
+        // A secret from another keyring no longer decrypts.
+        let other = TempDir::new().expect("tempdir");
+        let foreign = SecretsCrypto::load_or_create(other.path()).expect("secrets");
+        secrets_repo::upsert_secret(&pool, &foreign, "hub", "webdav", "foreign", b"secret")
+            .await
+            .expect("upsert");
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "job",
+            None,
+            None,
+            Some("UTC"),
+            jobs_repo::OverlapPolicy::Queue,
+            serde_json::json!({
+              "v": 1,
+              "type": "filesystem",
+              "source": { "paths": ["/tmp"] },
+              "target": { "type": "local_dir", "base_dir": "/tmp" }
+            }),
+        )
+        .await
+        .expect("job");
+        let running =
+            runs_repo::create_run(&pool, &job.id, RunStatus::Running, 1, None, None, None)
+                .await
+                .expect("run");
+        let failed =
+            runs_repo::create_run(&pool, &job.id, RunStatus::Failed, 1, Some(2), None, None)
+                .await
+                .expect("run");
+        for run_id in [&running.id, &failed.id, &"gone".to_string()] {
+            let dir = data.path().join("runs").join(run_id);
+            std::fs::create_dir_all(&dir).expect("mkdir");
+            std::fs::write(dir.join("payload.part000000"), b"data").expect("write");
+        }
+
+        let report = check_data_dir(&pool, data.path(), data.path()).await;
+        assert!(!report.ok);
+        let by_id = |id: &str| {
+            report
+                .checks
+                .iter()
+                .find(|c| c.id == id)
+                .unwrap_or_else(|| panic!("{id} check"))
+        };
+        assert_eq!(by_id("db_integrity").status, CheckStatus::Ok);
+        assert_eq!(by_id("master_key").status, CheckStatus::Ok);
+
+        let secrets = by_id("secrets_decrypt");
+        assert_eq!(secrets.status, CheckStatus::Fail);
+        assert_eq!(secrets.details.len(), 1);
+        assert!(secrets.details[0].starts_with("hub/webdav/foreign"));
+
+        let staging = by_id("staging_leftovers");
+        assert_eq!(staging.status, CheckStatus::Warn);
+        assert_eq!(staging.details.len(), 2);
+        assert!(!staging.details.iter().any(|d| d.ends_with(&running.id)));
+    }
+}
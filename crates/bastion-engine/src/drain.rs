@@ -0,0 +1,212 @@
+//! Graceful shutdown: stop claiming runs, let in-flight ones finish, then stop the Hub.
+//!
+//! Hub-local runs still going when the drain timeout expires are interrupted and put back in the
+//! queue, so the next start picks them up again instead of recording them as canceled.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use bastion_core::agent_protocol::{HubToAgentMessageV1, PROTOCOL_VERSION};
+
+use crate::agent_manager::AgentManager;
+use crate::cancel_registry::global_cancel_registry;
+
+/// How long interrupted runs get to requeue themselves after the drain timeout.
+const INTERRUPT_GRACE: Duration = Duration::from_secs(10);
+
+#[derive(Clone)]
+pub struct ShutdownDrain {
+    draining: CancellationToken,
+    expired: CancellationToken,
+    in_flight: Arc<watch::Sender<usize>>,
+}
+
+impl Default for ShutdownDrain {
+    fn default() -> Self {
+        Self {
+            draining: CancellationToken::new(),
+            expired: CancellationToken::new(),
+            in_flight: Arc::new(watch::Sender::new(0)),
+        }
+    }
+}
+
+impl ShutdownDrain {
+    pub fn is_draining(&self) -> bool {
+        self.draining.is_cancelled()
+    }
+
+    pub async fn started(&self) {
+        self.draining.cancelled().await;
+    }
+
+    /// The drain timeout passed; waits on in-flight runs should give up.
+    pub fn is_expired(&self) -> bool {
+        self.expired.is_cancelled()
+    }
+
+    pub async fn expired(&self) {
+        self.expired.cancelled().await;
+    }
+
+    pub fn in_flight(&self) -> usize {
+        *self.in_flight.borrow()
+    }
+
+    /// Counts a run as in flight until the guard is dropped.
+    pub fn track_run(&self) -> InFlightRun {
+        self.in_flight.send_modify(|n| *n += 1);
+        InFlightRun {
+            in_flight: self.in_flight.clone(),
+        }
+    }
+
+    async fn wait_idle(&self) {
+        let mut rx = self.in_flight.subscribe();
+        let _ = rx.wait_for(|n| *n == 0).await;
+    }
+}
+
+pub struct InFlightRun {
+    in_flight: Arc<watch::Sender<usize>>,
+}
+
+impl Drop for InFlightRun {
+    fn drop(&mut self) {
+        self.in_flight.send_modify(|n| *n = n.saturating_sub(1));
+    }
+}
+
+/// Upper bound on how long [`run`] takes with `timeout`.
+pub fn max_duration(timeout: Duration) -> Duration {
+    timeout + INTERRUPT_GRACE
+}
+
+pub struct DrainArgs {
+    pub drain: ShutdownDrain,
+    pub agent_manager: AgentManager,
+    pub timeout: Duration,
+    pub shutdown: CancellationToken,
+}
+
+/// Drains the Hub and then cancels `shutdown`.
+pub async fn run(args: DrainArgs) {
+    let DrainArgs {
+        drain,
+        agent_manager,
+        timeout,
+        shutdown,
+    } = args;
+
+    drain.draining.cancel();
+    info!(
+        in_flight = drain.in_flight(),
+        timeout_seconds = timeout.as_secs(),
+        "draining before shutdown"
+    );
+
+    match agent_manager
+        .broadcast_json(&HubToAgentMessageV1::HubShutdown {
+            v: PROTOCOL_VERSION,
+            drain_timeout_secs: timeout.as_secs(),
+        })
+        .await
+    {
+        Ok(agents) => info!(agents, "notified agents of hub shutdown"),
+        Err(error) => warn!(error = %error, "failed to notify agents of hub shutdown"),
+    }
+
+    if tokio::time::timeout(timeout, drain.wait_idle())
+        .await
+        .is_err()
+    {
+        warn!(
+            in_flight = drain.in_flight(),
+            "drain timed out; interrupting in-flight runs"
+        );
+        drain.expired.cancel();
+        global_cancel_registry().cancel_all_runs();
+        if tokio::time::timeout(INTERRUPT_GRACE, drain.wait_idle())
+            .await
+            .is_err()
+        {
+            warn!(
+                in_flight = drain.in_flight(),
+                "in-flight runs did not stop in time"
+            );
+        }
+    }
+
+    info!("drain finished");
+    shutdown.cancel();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio_util::sync::CancellationToken;
+
+    use super::{DrainArgs, ShutdownDrain, run};
+    use crate::agent_manager::AgentManager;
+
+    #[tokio::test]
+    async fn drain_waits_for_in_flight_runs_before_shutdown() {
+        let drain = ShutdownDrain::default();
+        let shutdown = CancellationToken::new();
+        let in_flight = drain.track_run();
+
+        let task = tokio::spawn(run(DrainArgs {
+            drain: drain.clone(),
+            agent_manager: AgentManager::default(),
+            timeout: Duration::from_secs(30),
+            shutdown: shutdown.clone(),
+        }));
+
+        tokio::time::timeout(Duration::from_secs(1), drain.started())
+            .await
+            .expect("draining");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!shutdown.is_cancelled());
+
+        drop(in_flight);
+        tokio::time::timeout(Duration::from_secs(1), shutdown.cancelled())
+            .await
+            .expect("shutdown cancelled");
+        assert!(!drain.is_expired());
+        task.await.expect("drain join");
+    }
+
+    #[tokio::test]
+    async fn drain_interrupts_runs_after_timeout() {
+        let drain = ShutdownDrain::default();
+        let shutdown = CancellationToken::new();
+        let stuck = drain.track_run();
+        let interrupted = drain.clone();
+        tokio::spawn(async move {
+            // Stands in for a run that requeues itself once it is interrupted.
+            interrupted.expired().await;
+            drop(stuck);
+        });
+
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            run(DrainArgs {
+                drain: drain.clone(),
+                agent_manager: AgentManager::default(),
+                timeout: Duration::from_millis(50),
+                shutdown: shutdown.clone(),
+            }),
+        )
+        .await
+        .expect("drain finished");
+
+        assert!(drain.is_expired());
+        assert!(shutdown.is_cancelled());
+        assert_eq!(drain.in_flight(), 0);
+    }
+}
@@ -0,0 +1,223 @@
+//! Warm standby: a second Hub keeps a recent copy of the primary's database under
+//! `<data_dir>/standby/` and can be promoted to take over.
+//!
+//! The standby process only follows; it does not open the copy, run schedulers or accept agents.
+//! Promotion swaps the copy in as `bastion.db`, after which the Hub starts normally.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sqlx::Connection as _;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+
+/// Directory (under the data directory) the standby writes to.
+pub const STANDBY_DIR: &str = "standby";
+
+/// Response header carrying the primary's keyring fingerprint next to a database snapshot.
+pub const KEYRING_FINGERPRINT_HEADER: &str = "x-bastion-keyring-fingerprint";
+
+const SNAPSHOT_FILE: &str = "bastion.db";
+const STATE_FILE: &str = "state.json";
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+const DB_FILES: [&str; 3] = ["bastion.db", "bastion.db-wal", "bastion.db-shm"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandbyState {
+    pub primary_url: String,
+    #[serde(default)]
+    pub last_sync_at: Option<i64>,
+    #[serde(default)]
+    pub snapshot_bytes: Option<u64>,
+    /// Whether the local `master.key` matches the primary's; secrets in the copy can't be
+    /// decrypted otherwise.
+    #[serde(default)]
+    pub keyring_matches: Option<bool>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub last_error_at: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Promotion {
+    /// Where the database this Hub had before promotion was moved, if it had one.
+    pub replaced_dir: Option<PathBuf>,
+    pub last_sync_at: Option<i64>,
+}
+
+pub fn snapshot_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(STANDBY_DIR).join(SNAPSHOT_FILE)
+}
+
+pub async fn read_state(data_dir: &Path) -> Option<StandbyState> {
+    let bytes = tokio::fs::read(data_dir.join(STANDBY_DIR).join(STATE_FILE))
+        .await
+        .ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+pub async fn write_state(data_dir: &Path, state: &StandbyState) -> Result<(), anyhow::Error> {
+    let dir = data_dir.join(STANDBY_DIR);
+    tokio::fs::create_dir_all(&dir).await?;
+    let tmp = dir.join(format!("{STATE_FILE}.partial"));
+    tokio::fs::write(&tmp, serde_json::to_vec_pretty(state)?).await?;
+    tokio::fs::rename(&tmp, dir.join(STATE_FILE)).await?;
+    Ok(())
+}
+
+/// Compares the local keyring with the fingerprint the primary sent.
+pub fn keyring_matches(data_dir: &Path, primary_fingerprint: &str) -> bool {
+    bastion_storage::secrets::keyring_fingerprint(data_dir)
+        .is_ok_and(|local| local == primary_fingerprint)
+}
+
+/// Checks a downloaded snapshot and moves it into place as the standby copy. Returns its size.
+pub async fn install_snapshot(data_dir: &Path, downloaded: &Path) -> Result<u64, anyhow::Error> {
+    let mut header = [0u8; 16];
+    {
+        use tokio::io::AsyncReadExt as _;
+        let mut file = tokio::fs::File::open(downloaded).await?;
+        file.read_exact(&mut header)
+            .await
+            .map_err(|_| anyhow::anyhow!("snapshot is not a SQLite database"))?;
+    }
+    if header != SQLITE_HEADER {
+        anyhow::bail!("snapshot is not a SQLite database");
+    }
+
+    let options = SqliteConnectOptions::new().filename(downloaded);
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+    let check = sqlx::query_scalar::<_, String>("PRAGMA quick_check")
+        .fetch_all(&mut conn)
+        .await;
+    conn.close().await?;
+    let check = check?;
+    if check != ["ok"] {
+        anyhow::bail!("snapshot failed its integrity check: {}", check.join("; "));
+    }
+
+    // The copy holds encrypted secrets and password hashes.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        tokio::fs::set_permissions(downloaded, std::fs::Permissions::from_mode(0o600)).await?;
+    }
+    let dest = snapshot_path(data_dir);
+    tokio::fs::create_dir_all(data_dir.join(STANDBY_DIR)).await?;
+    tokio::fs::rename(downloaded, &dest).await?;
+    Ok(tokio::fs::metadata(&dest).await?.len())
+}
+
+/// Makes the standby copy this Hub's database. The previous database (if any) is moved to
+/// `<data_dir>/standby/replaced-<unix time>/`. The Hub must not be running.
+pub async fn promote(data_dir: &Path, force: bool) -> Result<Promotion, anyhow::Error> {
+    let snapshot = snapshot_path(data_dir);
+    if !tokio::fs::try_exists(&snapshot).await? {
+        anyhow::bail!(
+            "no standby copy at {}; the standby has not synced yet",
+            snapshot.display()
+        );
+    }
+
+    let state = read_state(data_dir).await;
+    if !force {
+        if bastion_storage::secrets::keyring_fingerprint(data_dir).is_err() {
+            anyhow::bail!(
+                "no master.key in {}; import the primary's keypack first",
+                data_dir.display()
+            );
+        }
+        if state.as_ref().and_then(|s| s.keyring_matches) == Some(false) {
+            anyhow::bail!("master.key does not match the primary's; import the primary's keypack");
+        }
+    }
+
+    let mut replaced_dir = None;
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    for name in DB_FILES {
+        let path = data_dir.join(name);
+        if !tokio::fs::try_exists(&path).await? {
+            continue;
+        }
+        let dir = data_dir.join(STANDBY_DIR).join(format!("replaced-{now}"));
+        tokio::fs::create_dir_all(&dir).await?;
+        tokio::fs::rename(&path, dir.join(name)).await?;
+        replaced_dir = Some(dir);
+    }
+
+    tokio::fs::rename(&snapshot, data_dir.join(SNAPSHOT_FILE)).await?;
+    let _ = tokio::fs::remove_file(data_dir.join(STANDBY_DIR).join(STATE_FILE)).await;
+
+    Ok(Promotion {
+        replaced_dir,
+        last_sync_at: state.and_then(|s| s.last_sync_at),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use bastion_storage::{auth, db};
+
+    use super::{StandbyState, install_snapshot, promote, snapshot_path, write_state};
+
+    #[tokio::test]
+    async fn snapshots_are_checked_and_promoted_over_the_old_database() {
+        let primary = TempDir::new().expect("tempdir");
+        let pool = db::init(primary.path()).await.expect("db init");
+        auth::create_user(&pool, "admin", "password")
+            .await
+            .expect("create user");
+        let exported = primary.path().join("export.db");
+        db::vacuum_into(&pool, &exported).await.expect("vacuum");
+
+        let standby = TempDir::new().expect("tempdir");
+        let garbage = standby.path().join("garbage.db");
+        std::fs::write(&garbage, b"not a database at all").expect("write");
+        assert!(install_snapshot(standby.path(), &garbage).await.is_err());
+
+        let downloaded = standby.path().join("download.db");
+        std::fs::copy(&exported, &downloaded).expect("copy");
+        let size = install_snapshot(standby.path(), &downloaded)
+            .await
+            .expect("install");
+        assert!(size > 0);
+        assert!(snapshot_path(standby.path()).exists());
+
+        // Without the primary's keyring, promotion is refused.
+        let err = promote(standby.path(), false).await.unwrap_err();
+        assert!(err.to_string().contains("master.key"));
+
+        drop(db::init(standby.path()).await.expect("old standby db"));
+        write_state(
+            standby.path(),
+            &StandbyState {
+                primary_url: "https://primary.example.com/".to_string(),
+                last_sync_at: Some(1_700_000_000),
+                keyring_matches: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("state");
+        let promotion = promote(standby.path(), true).await.expect("promote");
+        assert_eq!(promotion.last_sync_at, Some(1_700_000_000));
+        assert!(
+            promotion
+                .replaced_dir
+                .expect("old db moved")
+                .join("bastion.db")
+                .exists()
+        );
+        assert!(!snapshot_path(standby.path()).exists());
+
+        let promoted = db::init(standby.path()).await.expect("open promoted");
+        assert!(
+            auth::find_user_by_username(&promoted, "admin")
+                .await
+                .expect("find user")
+                .is_some()
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+use url::Url;
+
+use bastion_core::HUB_NODE_ID;
+use bastion_storage::keypack_escrow_repo::{self, KeypackEscrowTarget};
+use bastion_storage::secrets::{self, SecretsCrypto};
+use bastion_storage::secrets_repo;
+use bastion_targets::{WebdavClient, WebdavCredentials};
+
+use crate::supervision::spawn_supervised;
+
+const CHECK_INTERVAL_SECS: u64 = 10 * 60;
+
+/// Directory (under the escrow target) that holds escrowed keypacks.
+pub const ESCROW_DIR: &str = "bastion-keypack";
+
+/// Serializes escrow runs between the background loop and on-demand API calls.
+static ESCROW_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscrowOutcome {
+    /// Escrow is turned off.
+    Disabled,
+    /// The current keyring was already escrowed.
+    UpToDate,
+    /// A new keypack was written to the target.
+    Escrowed,
+}
+
+pub struct KeypackEscrowArgs {
+    pub db: SqlitePool,
+    pub secrets: Arc<SecretsCrypto>,
+    pub data_dir: PathBuf,
+    pub shutdown: CancellationToken,
+}
+
+pub fn spawn(args: KeypackEscrowArgs) {
+    let shutdown = args.shutdown.clone();
+    spawn_supervised("keypack_escrow.loop", shutdown, run_loop(args));
+}
+
+async fn run_loop(args: KeypackEscrowArgs) {
+    loop {
+        if args.shutdown.is_cancelled() {
+            break;
+        }
+
+        // `bastion keypack rotate`/`import` edit master.key on disk, so polling the fingerprint
+        // also catches changes made while the Hub is running.
+        match escrow_keypack(&args.db, &args.secrets, &args.data_dir, false).await {
+            Ok(EscrowOutcome::Escrowed) => info!("keypack escrowed"),
+            Ok(_) => {}
+            Err(error) => warn!(error = %error, "keypack escrow failed"),
+        }
+
+        tokio::select! {
+            _ = args.shutdown.cancelled() => break,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {}
+        }
+    }
+}
+
+/// Pushes a password-protected keypack to the escrow target when the keyring changed since the
+/// last successful escrow (or always, with `force`). The outcome is recorded in the escrow status.
+pub async fn escrow_keypack(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    data_dir: &Path,
+    force: bool,
+) -> Result<EscrowOutcome, anyhow::Error> {
+    let _guard = ESCROW_LOCK.lock().await;
+
+    let settings = keypack_escrow_repo::get_settings(db).await?;
+    if !settings.enabled {
+        return Ok(EscrowOutcome::Disabled);
+    }
+
+    let fingerprint = {
+        let data_dir = data_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || secrets::keyring_fingerprint(&data_dir)).await??
+    };
+    let mut status = keypack_escrow_repo::get_status(db).await?;
+    if !force && status.escrowed_fingerprint.as_deref() == Some(fingerprint.as_str()) {
+        return Ok(EscrowOutcome::UpToDate);
+    }
+
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    status.last_attempt_at = Some(now);
+    let result = match &settings.target {
+        Some(target) => push_keypack(db, secrets, data_dir, target, &fingerprint).await,
+        None => Err(anyhow::anyhow!("no escrow target configured")),
+    };
+    match &result {
+        Ok(location) => {
+            status.escrowed_fingerprint = Some(fingerprint);
+            status.last_success_at = Some(now);
+            status.last_error = None;
+            status.location = Some(location.clone());
+        }
+        Err(error) => status.last_error = Some(format!("{error:#}")),
+    }
+    keypack_escrow_repo::upsert_status(db, &status).await?;
+
+    result.map(|_| EscrowOutcome::Escrowed)
+}
+
+/// Escrowed keypacks are named after the keyring fingerprint, so replacing the keyring never
+/// overwrites the keypack of an older one.
+fn escrow_file_name(fingerprint: &str) -> String {
+    let short = fingerprint.get(..12).unwrap_or(fingerprint);
+    format!("bastion-keypack-{short}.json")
+}
+
+async fn push_keypack(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    data_dir: &Path,
+    target: &KeypackEscrowTarget,
+    fingerprint: &str,
+) -> Result<String, anyhow::Error> {
+    let password = secrets_repo::get_secret(
+        db,
+        secrets,
+        HUB_NODE_ID,
+        keypack_escrow_repo::PASSWORD_SECRET_KIND,
+        keypack_escrow_repo::PASSWORD_SECRET_NAME,
+    )
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("escrow keypack password is not set"))?;
+    let password = String::from_utf8(password)?;
+
+    let bytes = {
+        let data_dir = data_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || secrets::export_keypack_bytes(&data_dir, &password))
+            .await??
+    };
+    let file_name = escrow_file_name(fingerprint);
+
+    match target {
+        KeypackEscrowTarget::LocalDir { base_dir } => {
+            let dir = Path::new(base_dir).join(ESCROW_DIR);
+            let path = dir.join(&file_name);
+            let location = path.display().to_string();
+            tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
+                std::fs::create_dir_all(&dir)?;
+                let tmp = dir.join(format!("{file_name}.partial"));
+                std::fs::write(&tmp, &bytes)?;
+                std::fs::rename(&tmp, &path)?;
+                Ok(())
+            })
+            .await??;
+            Ok(location)
+        }
+        KeypackEscrowTarget::Webdav {
+            base_url,
+            secret_name,
+        } => {
+            let cred_bytes =
+                secrets_repo::get_secret(db, secrets, HUB_NODE_ID, "webdav", secret_name)
+                    .await?
+                    .ok_or_else(|| anyhow::anyhow!("missing webdav secret: {secret_name}"))?;
+            let credentials = WebdavCredentials::from_json(&cred_bytes)?;
+
+            let mut base = Url::parse(base_url.trim())?;
+            if !base.path().ends_with('/') {
+                base.set_path(&format!("{}/", base.path()));
+            }
+            let client = WebdavClient::new(base.clone(), credentials)?;
+            let dir_url = base.join(&format!("{ESCROW_DIR}/"))?;
+            client.ensure_collection(&dir_url).await?;
+            let file_url = dir_url.join(&file_name)?;
+            client
+                .put_bytes(&file_url, bytes, "application/json")
+                .await?;
+            Ok(file_url.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use bastion_core::HUB_NODE_ID;
+    use bastion_storage::keypack_escrow_repo::{self, KeypackEscrowSettings, KeypackEscrowTarget};
+    use bastion_storage::secrets::{self, SecretsCrypto};
+    use bastion_storage::{db, secrets_repo};
+
+    use super::{ESCROW_DIR, EscrowOutcome, escrow_keypack};
+
+    #[tokio::test]
+    async fn escrows_local_dir_once_per_keyring() {
+        let data = TempDir::new().expect("tempdir");
+        let escrow = TempDir::new().expect("tempdir");
+        let pool = db::init(data.path()).await.expect("db init");
+        let crypto = SecretsCrypto::load_or_create(data.path()).expect("secrets");
+
+        assert_eq!(
+            escrow_keypack(&pool, &crypto, data.path(), false)
+                .await
+                .expect("disabled"),
+            EscrowOutcome::Disabled
+        );
+
+        secrets_repo::upsert_secret(
+            &pool,
+            &crypto,
+            HUB_NODE_ID,
+            keypack_escrow_repo::PASSWORD_SECRET_KIND,
+            keypack_escrow_repo::PASSWORD_SECRET_NAME,
+            b"escrow password",
+        )
+        .await
+        .expect("password");
+        keypack_escrow_repo::upsert_settings(
+            &pool,
+            &KeypackEscrowSettings {
+                enabled: true,
+                target: Some(KeypackEscrowTarget::LocalDir {
+                    base_dir: escrow.path().display().to_string(),
+                }),
+            },
+        )
+        .await
+        .expect("settings");
+
+        assert_eq!(
+            escrow_keypack(&pool, &crypto, data.path(), false)
+                .await
+                .expect("escrow"),
+            EscrowOutcome::Escrowed
+        );
+        assert_eq!(
+            escrow_keypack(&pool, &crypto, data.path(), false)
+                .await
+                .expect("escrow"),
+            EscrowOutcome::UpToDate
+        );
+
+        let status = keypack_escrow_repo::get_status(&pool)
+            .await
+            .expect("status");
+        assert!(status.last_error.is_none());
+        let location = status.location.expect("location");
+        let bytes = std::fs::read(&location).expect("escrowed keypack");
+
+        let restored = TempDir::new().expect("tempdir");
+        secrets::import_keypack_bytes(restored.path(), &bytes, "escrow password", false)
+            .expect("import escrowed keypack");
+
+        // A rotated keyring gets its own file next to the previous one.
+        secrets::rotate_master_key(data.path()).expect("rotate");
+        assert_eq!(
+            escrow_keypack(&pool, &crypto, data.path(), false)
+                .await
+                .expect("escrow"),
+            EscrowOutcome::Escrowed
+        );
+        let files = std::fs::read_dir(escrow.path().join(ESCROW_DIR))
+            .expect("escrow dir")
+            .count();
+        assert_eq!(files, 2);
+    }
+}
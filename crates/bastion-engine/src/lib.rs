@@ -3,10 +3,21 @@ pub mod agent_manager;
 pub mod agent_snapshots;
 pub mod bulk_operations;
 pub mod cancel_registry;
+pub mod db_backup;
+pub mod doctor;
+pub mod drain;
 pub mod error_envelope;
+pub mod hub_standby;
+pub mod keypack_escrow;
 pub mod maintenance;
 pub mod notifications;
+pub mod orphan_gc;
 pub mod run_events;
 pub mod run_events_bus;
+pub mod run_stats;
 pub mod scheduler;
+pub mod snapshot_target;
+pub mod stale_backups;
+pub mod storage_usage;
 mod supervision;
+pub mod webdav_rotation;
@@ -3,15 +3,22 @@ use time::OffsetDateTime;
 use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
+use bastion_storage::{agent_connections_repo, download_links_repo};
+
 use crate::supervision::spawn_supervised;
 
 const LOGIN_THROTTLE_RETENTION_DAYS: i64 = 30;
+const AGENT_CONNECTIONS_RETENTION_DAYS: i64 = 90;
+// Expired download links are kept for a while so their audit trail stays visible.
+const DOWNLOAD_LINKS_RETENTION_DAYS: i64 = 30;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct DbPruneStats {
     pub sessions_deleted: u64,
     pub enrollment_tokens_deleted: u64,
     pub login_throttle_deleted: u64,
+    pub agent_connections_deleted: u64,
+    pub download_links_deleted: u64,
 }
 
 pub fn spawn(db: SqlitePool, shutdown: CancellationToken) {
@@ -30,12 +37,16 @@ async fn run_loop(db: SqlitePool, shutdown: CancellationToken) {
                 let total = stats
                     .sessions_deleted
                     .saturating_add(stats.enrollment_tokens_deleted)
-                    .saturating_add(stats.login_throttle_deleted);
+                    .saturating_add(stats.login_throttle_deleted)
+                    .saturating_add(stats.agent_connections_deleted)
+                    .saturating_add(stats.download_links_deleted);
                 if total > 0 {
                     info!(
                         sessions_deleted = stats.sessions_deleted,
                         enrollment_tokens_deleted = stats.enrollment_tokens_deleted,
                         login_throttle_deleted = stats.login_throttle_deleted,
+                        agent_connections_deleted = stats.agent_connections_deleted,
+                        download_links_deleted = stats.download_links_deleted,
                         "database maintenance pruned rows"
                     );
                 }
@@ -73,6 +84,18 @@ async fn prune_with_now(db: &SqlitePool, now: i64) -> Result<DbPruneStats, anyho
         .execute(db)
         .await?;
     stats.login_throttle_deleted = result.rows_affected();
+    let result = sqlx::query("DELETE FROM login_user_throttle WHERE last_failed_at < ?")
+        .bind(cutoff)
+        .execute(db)
+        .await?;
+    stats.login_throttle_deleted += result.rows_affected();
+
+    let cutoff = now.saturating_sub(AGENT_CONNECTIONS_RETENTION_DAYS.saturating_mul(24 * 60 * 60));
+    stats.agent_connections_deleted =
+        agent_connections_repo::prune_disconnected_before(db, cutoff).await?;
+
+    let cutoff = now.saturating_sub(DOWNLOAD_LINKS_RETENTION_DAYS.saturating_mul(24 * 60 * 60));
+    stats.download_links_deleted = download_links_repo::prune_expired_before(db, cutoff).await?;
 
     Ok(stats)
 }
@@ -143,10 +166,42 @@ mod tests {
         .await
         .expect("insert login throttle");
 
+        // Old agent connection session.
+        sqlx::query("INSERT INTO agents (id, key_hash, created_at) VALUES ('a', X'00', 0)")
+            .execute(&pool)
+            .await
+            .expect("insert agent");
+        let old_connection = now
+            .saturating_sub(super::AGENT_CONNECTIONS_RETENTION_DAYS.saturating_mul(24 * 60 * 60))
+            .saturating_sub(1);
+        sqlx::query(
+            "INSERT INTO agent_connections (agent_id, connected_at, disconnected_at) VALUES ('a', ?, ?)",
+        )
+        .bind(old_connection - 10)
+        .bind(old_connection)
+        .execute(&pool)
+        .await
+        .expect("insert agent connection");
+
+        // Download link that expired before the retention window.
+        let old_link = now
+            .saturating_sub(super::DOWNLOAD_LINKS_RETENTION_DAYS.saturating_mul(24 * 60 * 60))
+            .saturating_sub(1);
+        sqlx::query(
+            "INSERT INTO download_links (id, token_hash, kind, subject_id, path, created_at, expires_at) VALUES ('l1', X'01', 'restored_file', 'op1', '/tmp/a', ?, ?)",
+        )
+        .bind(old_link - 10)
+        .bind(old_link)
+        .execute(&pool)
+        .await
+        .expect("insert download link");
+
         let stats = prune_with_now(&pool, now).await.expect("prune");
         assert_eq!(stats.sessions_deleted, 1);
         assert_eq!(stats.enrollment_tokens_deleted, 1);
         assert_eq!(stats.login_throttle_deleted, 1);
+        assert_eq!(stats.agent_connections_deleted, 1);
+        assert_eq!(stats.download_links_deleted, 1);
     }
 
     #[tokio::test]
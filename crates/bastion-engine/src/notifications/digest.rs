@@ -0,0 +1,557 @@
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike as _, Days, NaiveDate, TimeZone as _};
+use chrono_tz::Tz;
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use bastion_notify::{MessageTemplates, builtin_channels};
+use bastion_storage::notification_destinations_repo;
+use bastion_storage::notification_digests_repo::{self, DigestRow, DigestRunRow};
+use bastion_storage::notifications_settings_repo::{
+    self, NotificationsDigestMode, NotificationsDigestSettings, NotificationsSettings,
+};
+use bastion_storage::secrets::SecretsCrypto;
+
+use crate::supervision::spawn_supervised;
+
+use super::send::{SendOutcome, load_destination};
+use super::template::{error_label, format_bytes, format_duration};
+
+const TICK_SECS: u64 = 60;
+/// A period that ended longer ago than this (e.g. while the Hub was down) is skipped.
+const CATCH_UP_SECS: i64 = 6 * 60 * 60;
+const MAX_ATTEMPTS: i64 = 5;
+const BACKOFF_BASE_SECONDS: i64 = 60;
+const BACKOFF_MAX_SECONDS: i64 = 60 * 60;
+
+pub struct DigestArgs {
+    pub db: SqlitePool,
+    pub secrets: Arc<SecretsCrypto>,
+    pub hub_timezone: String,
+    pub shutdown: CancellationToken,
+}
+
+pub fn spawn_digest(args: DigestArgs) {
+    let shutdown = args.shutdown.clone();
+    spawn_supervised("notifications.digest.loop", shutdown, run_loop(args));
+}
+
+async fn run_loop(args: DigestArgs) {
+    let tz = args.hub_timezone.parse::<Tz>().unwrap_or_else(|_| {
+        warn!(timezone = %args.hub_timezone, "invalid hub timezone; digests use UTC");
+        Tz::UTC
+    });
+
+    loop {
+        if args.shutdown.is_cancelled() {
+            break;
+        }
+
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        if let Err(error) = tick(&args.db, &args.secrets, tz, now).await {
+            warn!(error = %error, "notification digest tick failed");
+        }
+
+        tokio::select! {
+            _ = args.shutdown.cancelled() => break,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(TICK_SECS)) => {}
+        }
+    }
+}
+
+async fn tick(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    tz: Tz,
+    now: i64,
+) -> Result<(), anyhow::Error> {
+    let settings = notifications_settings_repo::get_or_default(db).await?;
+    if settings.enabled
+        && let Some((period_start, period_end)) = latest_period(tz, &settings.digest, now)
+        && now.saturating_sub(period_end) <= CATCH_UP_SECS
+        && !notification_digests_repo::period_enqueued(db, period_end).await?
+    {
+        let inserted = enqueue_period(db, &settings, period_start, period_end, now).await?;
+        debug!(
+            period_start,
+            period_end, inserted, "notification digest enqueued"
+        );
+    }
+
+    while let Some(digest) = notification_digests_repo::claim_next_due(db, now).await? {
+        let attempts = digest.attempts.saturating_add(1);
+        match send_digest(db, secrets, tz, &settings, &digest).await {
+            Ok(SendOutcome::Sent) => {
+                notification_digests_repo::finish(db, &digest.id, "sent", attempts, None, now)
+                    .await?;
+            }
+            Ok(SendOutcome::Canceled { reason }) => {
+                notification_digests_repo::finish(
+                    db,
+                    &digest.id,
+                    "canceled",
+                    digest.attempts,
+                    Some(&reason),
+                    now,
+                )
+                .await?;
+            }
+            Err(error) => {
+                let error = format!("{error:#}");
+                if attempts >= MAX_ATTEMPTS {
+                    warn!(id = %digest.id, attempts, error = %error, "notification digest failed permanently");
+                    notification_digests_repo::finish(
+                        db,
+                        &digest.id,
+                        "failed",
+                        attempts,
+                        Some(&error),
+                        now,
+                    )
+                    .await?;
+                } else {
+                    let delay = backoff_seconds(attempts);
+                    warn!(id = %digest.id, attempts, delay, error = %error, "notification digest failed; will retry");
+                    notification_digests_repo::reschedule(
+                        db,
+                        &digest.id,
+                        attempts,
+                        now.saturating_add(delay),
+                        &error,
+                        now,
+                    )
+                    .await?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The most recent digest period that has ended at or before `now`, as `(start, end)`.
+///
+/// Periods end at `hour:00` in the hub timezone, every day or on `weekday`; `None` in per-run
+/// mode.
+fn latest_period(tz: Tz, digest: &NotificationsDigestSettings, now: i64) -> Option<(i64, i64)> {
+    let step = match digest.mode {
+        NotificationsDigestMode::PerRun => return None,
+        NotificationsDigestMode::Daily => 1,
+        NotificationsDigestMode::Weekly => 7,
+    };
+
+    let mut date = DateTime::from_timestamp(now, 0)?
+        .with_timezone(&tz)
+        .date_naive();
+    if digest.mode == NotificationsDigestMode::Weekly {
+        let current = date.weekday().number_from_monday();
+        let target = u32::from(digest.weekday.clamp(1, 7));
+        date = date.checked_sub_days(Days::new(u64::from((current + 7 - target) % 7)))?;
+    }
+
+    let mut end = local_hour(tz, date, digest.hour)?;
+    if end > now {
+        date = date.checked_sub_days(Days::new(step))?;
+        end = local_hour(tz, date, digest.hour)?;
+    }
+    let start = local_hour(tz, date.checked_sub_days(Days::new(step))?, digest.hour)?;
+    Some((start, end))
+}
+
+fn local_hour(tz: Tz, date: NaiveDate, hour: u8) -> Option<i64> {
+    let hour = u32::from(hour.min(23));
+    let naive = date.and_hms_opt(hour, 0, 0)?;
+    // A DST gap skips the hour: fall back to the first valid instant after it.
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(naive + chrono::Duration::hours(1)))
+                .earliest()
+        })
+        .map(|t| t.timestamp())
+}
+
+async fn enqueue_period(
+    db: &SqlitePool,
+    settings: &NotificationsSettings,
+    period_start: i64,
+    period_end: i64,
+    now: i64,
+) -> Result<i64, anyhow::Error> {
+    let channels = builtin_channels()
+        .destination_kinds()
+        .into_iter()
+        .filter(|(channel, _)| settings.channels.is_enabled(channel))
+        .collect::<Vec<_>>();
+    let destinations = notification_destinations_repo::list_destinations(db, &channels).await?;
+
+    let mut inserted = 0_i64;
+    for (channel, _) in &channels {
+        let names = destinations
+            .iter()
+            .filter(|d| d.enabled && d.channel == *channel)
+            .map(|d| d.name.clone())
+            .collect::<Vec<_>>();
+        inserted += notification_digests_repo::enqueue_for_period(
+            db,
+            period_start,
+            period_end,
+            channel,
+            &names,
+            now,
+        )
+        .await?;
+    }
+    Ok(inserted)
+}
+
+async fn send_digest(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    tz: Tz,
+    settings: &NotificationsSettings,
+    digest: &DigestRow,
+) -> Result<SendOutcome, anyhow::Error> {
+    let destination = match load_destination(
+        db,
+        secrets,
+        settings,
+        &digest.channel,
+        &digest.secret_name,
+    )
+    .await?
+    {
+        Ok(destination) => destination,
+        Err(outcome) => return Ok(outcome),
+    };
+
+    let runs =
+        notification_digests_repo::list_period_runs(db, digest.period_start, digest.period_end)
+            .await?;
+    let status = if runs.iter().any(|r| r.status == "failed") {
+        "failed"
+    } else {
+        "success"
+    };
+    if !destination
+        .channel
+        .accepts_run_status(&destination.config, status)
+    {
+        return Ok(SendOutcome::Canceled {
+            reason: format!("canceled: destination skips {status} runs"),
+        });
+    }
+    let message = render_digest(
+        tz,
+        settings.digest.mode,
+        digest.period_start,
+        digest.period_end,
+        &runs,
+    );
+    let rendered = destination.channel.render(&destination.config, &message)?;
+    destination
+        .channel
+        .send(&destination.config, &rendered)
+        .await?;
+
+    info!(
+        channel = %digest.channel,
+        secret_name = %digest.secret_name,
+        runs = runs.len(),
+        "notification digest sent"
+    );
+    Ok(SendOutcome::Sent)
+}
+
+struct DigestMessage {
+    email_subject: String,
+    email_body: String,
+    wecom_markdown: String,
+}
+
+/// Digests are fixed texts; per-destination templates do not apply to them.
+impl MessageTemplates for DigestMessage {
+    fn render(&self, slot: &str, _template_override: Option<&str>) -> String {
+        match slot {
+            "email_subject" => self.email_subject.clone(),
+            "email_body" => self.email_body.clone(),
+            _ => self.wecom_markdown.clone(),
+        }
+    }
+}
+
+struct DigestLine {
+    job_name: String,
+    status: String,
+    started_at: String,
+    duration: String,
+    size: String,
+    error: Option<String>,
+}
+
+fn render_digest(
+    tz: Tz,
+    mode: NotificationsDigestMode,
+    period_start: i64,
+    period_end: i64,
+    runs: &[DigestRunRow],
+) -> DigestMessage {
+    let kind = match mode {
+        NotificationsDigestMode::Weekly => "weekly",
+        _ => "daily",
+    };
+    let succeeded = runs.iter().filter(|r| r.status == "success").count();
+    let failed = runs.iter().filter(|r| r.status == "failed").count();
+    let counts = format!(
+        "{} runs, {succeeded} succeeded, {failed} failed",
+        runs.len()
+    );
+    let period = format!(
+        "{} - {}",
+        format_local(tz, period_start),
+        format_local(tz, period_end)
+    );
+
+    let lines = runs
+        .iter()
+        .map(|run| DigestLine {
+            job_name: run.job_name.clone(),
+            status: status_text(&run.status).to_string(),
+            started_at: format_local(tz, run.started_at),
+            duration: format_duration(run.ended_at.saturating_sub(run.started_at).max(0) as u64),
+            size: run
+                .summary_json
+                .as_deref()
+                .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+                .and_then(|v| v.pointer("/metrics/transfer_total_bytes")?.as_u64())
+                .map(format_bytes)
+                .unwrap_or_else(|| "-".to_string()),
+            error: run
+                .error
+                .as_deref()
+                .map(str::trim)
+                .filter(|e| !e.is_empty())
+                .map(error_label),
+        })
+        .collect::<Vec<_>>();
+
+    let email_subject = if failed > 0 {
+        format!("Bastion {kind} digest - {failed} failed of {}", runs.len())
+    } else {
+        format!("Bastion {kind} digest - {} runs", runs.len())
+    };
+
+    let mut email_body = format!("Bastion {kind} digest\n\nPeriod: {period}\nRuns: {counts}\n\n");
+    if lines.is_empty() {
+        email_body.push_str("No runs finished in this period.\n");
+    } else {
+        let job_width = lines
+            .iter()
+            .map(|l| l.job_name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max("Job".len());
+        let _ = writeln!(
+            email_body,
+            "{:<job_width$}  {:<9}  {:<16}  {:>11}  {:>10}",
+            "Job", "Status", "Started", "Duration", "Size"
+        );
+        for line in &lines {
+            let _ = writeln!(
+                email_body,
+                "{:<job_width$}  {:<9}  {:<16}  {:>11}  {:>10}",
+                line.job_name, line.status, line.started_at, line.duration, line.size
+            );
+        }
+        let errors = lines
+            .iter()
+            .filter_map(|l| Some((l, l.error.as_deref()?)))
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            email_body.push_str("\nErrors:\n");
+            for (line, error) in errors {
+                let _ = writeln!(
+                    email_body,
+                    "- {} ({}): {error}",
+                    line.job_name, line.started_at
+                );
+            }
+        }
+    }
+
+    let mut wecom_markdown =
+        format!("**Bastion {kind} digest**\n> Period: {period}\n> Runs: {counts}\n");
+    if lines.is_empty() {
+        wecom_markdown.push_str("No runs finished in this period.\n");
+    }
+    for line in &lines {
+        let _ = write!(
+            wecom_markdown,
+            "\n{} · {} · {} · {} · {}",
+            line.job_name, line.status, line.started_at, line.duration, line.size
+        );
+        if let Some(error) = &line.error {
+            let _ = write!(wecom_markdown, "\n> Error: {error}");
+        }
+    }
+
+    DigestMessage {
+        email_subject,
+        email_body,
+        wecom_markdown,
+    }
+}
+
+fn status_text(status: &str) -> &str {
+    match status {
+        "success" => "Succeeded",
+        "failed" => "Failed",
+        "rejected" => "Rejected",
+        "canceled" => "Canceled",
+        other => other,
+    }
+}
+
+fn format_local(tz: Tz, ts: i64) -> String {
+    DateTime::from_timestamp(ts, 0)
+        .map(|t| t.with_timezone(&tz).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+fn backoff_seconds(attempts: i64) -> i64 {
+    let shift = (attempts.saturating_sub(1)).clamp(0, 20) as u32;
+    let exp = 1_i64.checked_shl(shift).unwrap_or(i64::MAX);
+    BACKOFF_BASE_SECONDS
+        .saturating_mul(exp)
+        .clamp(BACKOFF_BASE_SECONDS, BACKOFF_MAX_SECONDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::Tz;
+
+    use bastion_storage::notification_digests_repo::DigestRunRow;
+    use bastion_storage::notifications_settings_repo::{
+        NotificationsDigestMode, NotificationsDigestSettings,
+    };
+
+    use super::{latest_period, render_digest};
+
+    const HOUR: i64 = 60 * 60;
+    const DAY: i64 = 24 * HOUR;
+    // 2024-01-31 00:00:00 UTC (Wednesday).
+    const WED: i64 = 1_706_659_200;
+
+    fn digest(mode: NotificationsDigestMode, hour: u8, weekday: u8) -> NotificationsDigestSettings {
+        NotificationsDigestSettings {
+            mode,
+            hour,
+            weekday,
+        }
+    }
+
+    #[test]
+    fn latest_period_follows_mode_hour_and_weekday() {
+        let daily = digest(NotificationsDigestMode::Daily, 8, 1);
+        assert_eq!(
+            latest_period(Tz::UTC, &daily, WED + 9 * HOUR),
+            Some((WED + 8 * HOUR - DAY, WED + 8 * HOUR))
+        );
+        assert_eq!(
+            latest_period(Tz::UTC, &daily, WED + 7 * HOUR),
+            Some((WED + 8 * HOUR - 2 * DAY, WED + 8 * HOUR - DAY))
+        );
+        assert_eq!(
+            latest_period(Tz::UTC, &daily, WED + 8 * HOUR),
+            Some((WED + 8 * HOUR - DAY, WED + 8 * HOUR))
+        );
+
+        // Monday 2024-01-29 08:00 UTC.
+        let monday = WED - 2 * DAY + 8 * HOUR;
+        let weekly = digest(NotificationsDigestMode::Weekly, 8, 1);
+        assert_eq!(
+            latest_period(Tz::UTC, &weekly, WED),
+            Some((monday - 7 * DAY, monday))
+        );
+        assert_eq!(
+            latest_period(Tz::UTC, &weekly, monday - 1),
+            Some((monday - 14 * DAY, monday - 7 * DAY))
+        );
+
+        // 08:00 in Shanghai is 00:00 UTC.
+        assert_eq!(
+            latest_period(chrono_tz::Asia::Shanghai, &daily, WED + HOUR),
+            Some((WED - DAY, WED))
+        );
+
+        let per_run = digest(NotificationsDigestMode::PerRun, 8, 1);
+        assert_eq!(latest_period(Tz::UTC, &per_run, WED), None);
+    }
+
+    #[test]
+    fn render_digest_lists_runs_with_durations_sizes_and_errors() {
+        let run =
+            |id: &str, job: &str, status: &str, error: Option<&str>, summary: Option<&str>| {
+                DigestRunRow {
+                    run_id: id.to_string(),
+                    job_id: job.to_string(),
+                    job_name: job.to_string(),
+                    status: status.to_string(),
+                    started_at: WED,
+                    ended_at: WED + 125,
+                    error: error.map(str::to_string),
+                    summary_json: summary.map(str::to_string),
+                }
+            };
+        let runs = vec![
+            run(
+                "r1",
+                "nightly",
+                "success",
+                None,
+                Some(r#"{"metrics":{"transfer_total_bytes":2048}}"#),
+            ),
+            run("r2", "db", "failed", Some("target unreachable"), None),
+        ];
+
+        let message = render_digest(
+            Tz::UTC,
+            NotificationsDigestMode::Daily,
+            WED - DAY,
+            WED,
+            &runs,
+        );
+        assert_eq!(
+            message.email_subject,
+            "Bastion daily digest - 1 failed of 2"
+        );
+        assert!(message.email_body.contains("2 runs, 1 succeeded, 1 failed"));
+        assert!(message.email_body.contains("nightly"));
+        assert!(message.email_body.contains("2m 5s"));
+        assert!(message.email_body.contains("2.0 KiB"));
+        assert!(
+            message
+                .email_body
+                .contains("- db (2024-01-31 00:00): target unreachable")
+        );
+        assert!(
+            message
+                .wecom_markdown
+                .contains("> Error: target unreachable")
+        );
+
+        let empty = render_digest(
+            Tz::UTC,
+            NotificationsDigestMode::Weekly,
+            WED - 7 * DAY,
+            WED,
+            &[],
+        );
+        assert_eq!(empty.email_subject, "Bastion weekly digest - 0 runs");
+        assert!(empty.email_body.contains("No runs finished"));
+    }
+}
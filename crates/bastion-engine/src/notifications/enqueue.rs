@@ -3,9 +3,13 @@ use std::collections::HashSet;
 use sqlx::SqlitePool;
 
 use bastion_core::job_spec;
+use bastion_notify::builtin_channels;
 use bastion_storage::notification_destinations_repo;
 use bastion_storage::notifications_repo;
-use bastion_storage::notifications_settings_repo;
+use bastion_storage::notifications_settings_repo::{self, NotificationsDigestMode};
+use bastion_storage::runs_repo::{self, RunStatus};
+
+use super::template::triggered_anomaly;
 
 pub async fn enqueue_for_run_spec(
     db: &SqlitePool,
@@ -17,64 +21,147 @@ pub async fn enqueue_for_run_spec(
         return Ok(false);
     }
 
-    let all = notification_destinations_repo::list_destinations(db).await?;
-
-    let mut enabled_wecom = Vec::new();
-    let mut enabled_email = Vec::new();
-    for d in &all {
-        if !d.enabled {
-            continue;
+    // Anomaly warnings are high priority: they are sent right away regardless of digest mode
+    // and notify_on rules.
+    let run = runs_repo::get_run(db, run_id).await?;
+    let anomaly = run
+        .as_ref()
+        .and_then(|r| r.summary.as_ref())
+        .is_some_and(|summary| triggered_anomaly(summary).is_some());
+    if !anomaly {
+        // In digest mode runs are reported in the periodic summary instead.
+        if settings.digest.mode != NotificationsDigestMode::PerRun {
+            return Ok(false);
         }
-        match d.channel.as_str() {
-            notifications_repo::CHANNEL_WECOM_BOT => enabled_wecom.push(d.name.clone()),
-            notifications_repo::CHANNEL_EMAIL => enabled_email.push(d.name.clone()),
-            _ => {}
+        if !run_matches_rules(db, &spec.notifications().notify_on, run.as_ref()).await? {
+            return Ok(false);
         }
     }
 
-    let mut selected_wecom: Vec<String> = Vec::new();
-    let mut selected_email: Vec<String> = Vec::new();
-    match spec.notifications().mode {
-        job_spec::NotificationsModeV1::Inherit => {
-            selected_wecom = enabled_wecom;
-            selected_email = enabled_email;
-        }
-        job_spec::NotificationsModeV1::Custom => {
-            let wecom_set: HashSet<&str> = enabled_wecom.iter().map(|s| s.as_str()).collect();
-            let email_set: HashSet<&str> = enabled_email.iter().map(|s| s.as_str()).collect();
-
-            for name in &spec.notifications().wecom_bot {
-                if wecom_set.contains(name.as_str()) {
-                    selected_wecom.push(name.clone());
-                }
-            }
-            for name in &spec.notifications().email {
-                if email_set.contains(name.as_str()) {
-                    selected_email.push(name.clone());
-                }
+    let channels = builtin_channels()
+        .destination_kinds()
+        .into_iter()
+        .filter(|(channel, _)| settings.channels.is_enabled(channel))
+        .collect::<Vec<_>>();
+    let destinations = notification_destinations_repo::list_destinations(db, &channels).await?;
+
+    let mut inserted = 0_i64;
+    for (channel, _) in &channels {
+        let enabled = destinations
+            .iter()
+            .filter(|d| d.enabled && d.channel == *channel)
+            .map(|d| d.name.clone())
+            .collect::<Vec<_>>();
+        let selected = match spec.notifications().mode {
+            job_spec::NotificationsModeV1::Inherit => enabled,
+            job_spec::NotificationsModeV1::Custom => {
+                let enabled: HashSet<&str> = enabled.iter().map(|s| s.as_str()).collect();
+                spec.notifications()
+                    .destinations_for(channel)
+                    .iter()
+                    .filter(|name| enabled.contains(name.as_str()))
+                    .cloned()
+                    .collect()
             }
+        };
+        if selected.is_empty() {
+            continue;
         }
+        inserted += notifications_repo::enqueue_for_run(db, run_id, channel, &selected).await?;
     }
 
-    let mut inserted = 0_i64;
-    if settings.channels.wecom_bot.enabled && !selected_wecom.is_empty() {
-        inserted += notifications_repo::enqueue_for_run(
-            db,
-            run_id,
-            notifications_repo::CHANNEL_WECOM_BOT,
-            &selected_wecom,
-        )
-        .await?;
+    Ok(inserted > 0)
+}
+
+async fn run_matches_rules(
+    db: &SqlitePool,
+    rules: &[job_spec::NotifyOnV1],
+    run: Option<&runs_repo::Run>,
+) -> Result<bool, anyhow::Error> {
+    if rules.is_empty() || rules.contains(&job_spec::NotifyOnV1::All) {
+        return Ok(true);
     }
-    if settings.channels.email.enabled && !selected_email.is_empty() {
-        inserted += notifications_repo::enqueue_for_run(
-            db,
-            run_id,
-            notifications_repo::CHANNEL_EMAIL,
-            &selected_email,
-        )
-        .await?;
+
+    let Some(run) = run else {
+        return Ok(false);
+    };
+    let previous = runs_repo::get_previous_finished_run_status(db, &run.id).await?;
+    Ok(rules
+        .iter()
+        .any(|rule| rule_matches(*rule, run.status, previous)))
+}
+
+fn rule_matches(
+    rule: job_spec::NotifyOnV1,
+    current: RunStatus,
+    previous: Option<RunStatus>,
+) -> bool {
+    match rule {
+        job_spec::NotifyOnV1::All => true,
+        job_spec::NotifyOnV1::Failure => current == RunStatus::Failed,
+        job_spec::NotifyOnV1::FirstFailure => {
+            current == RunStatus::Failed && previous != Some(RunStatus::Failed)
+        }
+        job_spec::NotifyOnV1::Recovery => {
+            current == RunStatus::Success && previous == Some(RunStatus::Failed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bastion_core::job_spec::NotifyOnV1;
+    use bastion_storage::runs_repo::RunStatus;
+
+    use super::rule_matches;
+
+    #[test]
+    fn failure_rules_follow_previous_outcome() {
+        assert!(rule_matches(
+            NotifyOnV1::Failure,
+            RunStatus::Failed,
+            Some(RunStatus::Failed)
+        ));
+        assert!(!rule_matches(NotifyOnV1::Failure, RunStatus::Success, None));
+
+        assert!(rule_matches(
+            NotifyOnV1::FirstFailure,
+            RunStatus::Failed,
+            None
+        ));
+        assert!(rule_matches(
+            NotifyOnV1::FirstFailure,
+            RunStatus::Failed,
+            Some(RunStatus::Success)
+        ));
+        assert!(!rule_matches(
+            NotifyOnV1::FirstFailure,
+            RunStatus::Failed,
+            Some(RunStatus::Failed)
+        ));
     }
 
-    Ok(inserted > 0)
+    #[test]
+    fn recovery_requires_success_after_failure() {
+        assert!(rule_matches(
+            NotifyOnV1::Recovery,
+            RunStatus::Success,
+            Some(RunStatus::Failed)
+        ));
+        assert!(!rule_matches(
+            NotifyOnV1::Recovery,
+            RunStatus::Success,
+            Some(RunStatus::Success)
+        ));
+        assert!(!rule_matches(
+            NotifyOnV1::Recovery,
+            RunStatus::Success,
+            None
+        ));
+        assert!(!rule_matches(
+            NotifyOnV1::Recovery,
+            RunStatus::Canceled,
+            Some(RunStatus::Failed)
+        ));
+    }
 }
@@ -70,10 +70,9 @@ fn classify_notification_error(error: &str) -> (&'static str, &'static str, &'st
 }
 
 fn notification_transport(channel: &str) -> bastion_core::error_envelope::ErrorTransportV1 {
-    match channel {
-        notifications_repo::CHANNEL_WECOM_BOT => transport("http").with_provider("wecom_bot"),
-        notifications_repo::CHANNEL_EMAIL => transport("smtp").with_provider("email"),
-        _ => transport("internal").with_provider(channel.to_string()),
+    match bastion_notify::builtin_channels().get(channel) {
+        Some(channel) => transport(channel.protocol()).with_provider(channel.id()),
+        None => transport("internal").with_provider(channel.to_string()),
     }
 }
 
@@ -1,7 +1,12 @@
+mod digest;
 mod enqueue;
 mod r#loop;
+mod ping;
 mod send;
 mod template;
 
+pub use digest::{DigestArgs, spawn_digest};
 pub use enqueue::enqueue_for_run_spec;
 pub use r#loop::spawn;
+pub use ping::{ping_run_end, ping_run_start};
+pub use template::{EmailPreview, render_email_preview};
@@ -0,0 +1,205 @@
+use sqlx::SqlitePool;
+use tracing::warn;
+
+use bastion_core::job_spec;
+use bastion_storage::runs_repo::{self, RunStatus};
+
+use crate::run_events;
+use crate::run_events_bus::RunEventsBus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunPing {
+    Start,
+    Success,
+    Failure,
+}
+
+impl RunPing {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Success => "success",
+            Self::Failure => "failure",
+        }
+    }
+
+    fn for_status(status: RunStatus) -> Option<Self> {
+        match status {
+            RunStatus::Success => Some(Self::Success),
+            RunStatus::Failed => Some(Self::Failure),
+            _ => None,
+        }
+    }
+
+    fn url(self, ping: &job_spec::PingUrlsV1) -> Option<&str> {
+        match self {
+            Self::Start => ping.start_url.as_deref(),
+            Self::Success => ping.success_url.as_deref(),
+            Self::Failure => ping.failure_url.as_deref(),
+        }
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+    }
+}
+
+/// Pings the job's `start_url`, if configured.
+pub async fn ping_run_start(
+    db: &SqlitePool,
+    run_events_bus: &RunEventsBus,
+    spec: &job_spec::JobSpecV1,
+    run_id: &str,
+) {
+    send(db, run_events_bus, spec, run_id, RunPing::Start).await;
+}
+
+/// Pings the job's `success_url` or `failure_url` according to the run's final status.
+///
+/// Canceled and rejected runs are not reported.
+pub async fn ping_run_end(
+    db: &SqlitePool,
+    run_events_bus: &RunEventsBus,
+    spec: &job_spec::JobSpecV1,
+    run_id: &str,
+) {
+    if spec.notifications().ping.is_empty() {
+        return;
+    }
+
+    let status = match runs_repo::get_run(db, run_id).await {
+        Ok(Some(run)) => run.status,
+        Ok(None) => return,
+        Err(error) => {
+            warn!(run_id = %run_id, error = %error, "failed to load run for ping");
+            return;
+        }
+    };
+    let Some(kind) = RunPing::for_status(status) else {
+        return;
+    };
+    send(db, run_events_bus, spec, run_id, kind).await;
+}
+
+async fn send(
+    db: &SqlitePool,
+    run_events_bus: &RunEventsBus,
+    spec: &job_spec::JobSpecV1,
+    run_id: &str,
+    kind: RunPing,
+) {
+    let Some(url) = kind.url(&spec.notifications().ping) else {
+        return;
+    };
+
+    // A failed ping must never fail the run; surface it as a run event instead.
+    // The URL is left out of the event since ping URLs usually embed the check's secret id.
+    let Err(error) = bastion_notify::ping::send_ping(url).await else {
+        return;
+    };
+    warn!(run_id = %run_id, ping = kind.as_str(), error = %error, "ping failed");
+
+    let message = format!("{} ping failed: {error}", kind.as_str());
+    let fields = serde_json::json!({
+        "ping": kind.as_str(),
+        "error": error.to_string(),
+    });
+    if let Err(error) = run_events::append_and_broadcast(
+        db,
+        run_events_bus,
+        run_id,
+        "warn",
+        "ping_failed",
+        &message,
+        Some(fields),
+    )
+    .await
+    {
+        warn!(run_id = %run_id, error = %error, "failed to write ping_failed event");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bastion_core::job_spec::{self, PingUrlsV1};
+    use bastion_storage::db;
+    use bastion_storage::jobs_repo::{self, OverlapPolicy};
+    use bastion_storage::runs_repo::{self, RunStatus};
+
+    use crate::run_events_bus::RunEventsBus;
+
+    use super::{RunPing, ping_run_start};
+
+    #[test]
+    fn end_ping_follows_run_status() {
+        assert_eq!(
+            RunPing::for_status(RunStatus::Success),
+            Some(RunPing::Success)
+        );
+        assert_eq!(
+            RunPing::for_status(RunStatus::Failed),
+            Some(RunPing::Failure)
+        );
+        assert_eq!(RunPing::for_status(RunStatus::Canceled), None);
+    }
+
+    #[test]
+    fn blank_urls_are_skipped() {
+        let ping = PingUrlsV1 {
+            start_url: Some("  ".to_string()),
+            success_url: Some("https://hc-ping.com/abc".to_string()),
+            failure_url: None,
+        };
+        assert_eq!(RunPing::Start.url(&ping), None);
+        assert_eq!(RunPing::Success.url(&ping), Some("https://hc-ping.com/abc"));
+        assert_eq!(RunPing::Failure.url(&ping), None);
+    }
+
+    #[tokio::test]
+    async fn failed_ping_event_does_not_contain_the_url() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+        let bus = RunEventsBus::new();
+
+        // Nothing listens on the port once the listener is dropped, so the ping fails.
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let url = format!("http://127.0.0.1:{port}/ping/secret-check-id");
+        let spec = job_spec::parse_value(&serde_json::json!({
+          "v": 1,
+          "type": "filesystem",
+          "source": { "paths": ["/"] },
+          "target": { "type": "local_dir", "base_dir": "/tmp" },
+          "notifications": { "ping": { "start_url": url } }
+        }))
+        .unwrap();
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "job",
+            None,
+            None,
+            Some("UTC"),
+            OverlapPolicy::Queue,
+            serde_json::json!({"v": 1, "type": "filesystem"}),
+        )
+        .await
+        .unwrap();
+        let run = runs_repo::create_run(&pool, &job.id, RunStatus::Running, 1, None, None, None)
+            .await
+            .unwrap();
+
+        ping_run_start(&pool, &bus, &spec, &run.id).await;
+
+        let events = runs_repo::list_run_events(&pool, &run.id, 10)
+            .await
+            .unwrap();
+        let event = events
+            .iter()
+            .find(|e| e.kind == "ping_failed")
+            .expect("ping_failed event");
+        let recorded = serde_json::to_string(event).unwrap();
+        assert!(!recorded.contains("secret-check-id"), "{recorded}");
+    }
+}
@@ -4,21 +4,92 @@ use tracing::info;
 use bastion_core::HUB_NODE_ID;
 use bastion_storage::notification_destinations_repo;
 use bastion_storage::notifications_repo;
-use bastion_storage::notifications_settings_repo;
+use bastion_storage::notifications_settings_repo::{
+    self, NotificationsSettings, NotificationsTemplates,
+};
 use bastion_storage::secrets::SecretsCrypto;
 use bastion_storage::secrets_repo;
 
 use crate::run_events;
 use crate::run_events_bus::RunEventsBus;
-use bastion_notify::{smtp, wecom};
+use bastion_notify::{MessageTemplates, NotificationChannel, builtin_channels};
 
-use super::template::{build_context, render_template};
+use super::template::{TemplateContext, build_context, render_template};
 
 pub(super) enum SendOutcome {
     Sent,
     Canceled { reason: String },
 }
 
+/// A destination resolved to its channel and secret payload.
+pub(super) struct Destination {
+    pub(super) channel: &'static dyn NotificationChannel,
+    pub(super) config: serde_json::Value,
+}
+
+fn canceled(reason: &str) -> Result<Destination, SendOutcome> {
+    Err(SendOutcome::Canceled {
+        reason: reason.to_string(),
+    })
+}
+
+/// Resolves the destination a message should go to.
+///
+/// Returns `Err(SendOutcome::Canceled)` when notifications, the channel or the destination are
+/// disabled, or the destination was deleted.
+pub(super) async fn load_destination(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    settings: &NotificationsSettings,
+    channel: &str,
+    secret_name: &str,
+) -> Result<Result<Destination, SendOutcome>, anyhow::Error> {
+    if !settings.enabled {
+        return Ok(canceled("canceled: notifications disabled"));
+    }
+
+    let channel = builtin_channels()
+        .get(channel)
+        .ok_or_else(|| anyhow::anyhow!("unsupported notification channel: {channel}"))?
+        .as_ref();
+    if !settings.channels.is_enabled(channel.id()) {
+        return Ok(canceled("canceled: channel disabled"));
+    }
+    if !notification_destinations_repo::is_enabled(db, channel.secret_kind(), secret_name).await? {
+        return Ok(canceled("canceled: destination disabled"));
+    }
+
+    let secret_bytes =
+        secrets_repo::get_secret(db, secrets, HUB_NODE_ID, channel.secret_kind(), secret_name)
+            .await?;
+    let Some(secret_bytes) = secret_bytes else {
+        return Ok(canceled("canceled: destination deleted"));
+    };
+
+    Ok(Ok(Destination {
+        channel,
+        config: serde_json::from_slice(&secret_bytes)?,
+    }))
+}
+
+/// Hub-wide templates rendered against a run.
+struct RunTemplates<'a> {
+    templates: &'a NotificationsTemplates,
+    ctx: &'a TemplateContext,
+}
+
+impl MessageTemplates for RunTemplates<'_> {
+    fn render(&self, slot: &str, template_override: Option<&str>) -> String {
+        let template = template_override.unwrap_or(match slot {
+            "wecom_markdown" => self.templates.wecom_markdown.as_str(),
+            "email_subject" => self.templates.email_subject.as_str(),
+            "email_body" => self.templates.email_body.as_str(),
+            _ => "",
+        });
+        render_template(template, self.ctx)
+    }
+}
+
 pub(super) async fn send_one(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
@@ -26,136 +97,60 @@ pub(super) async fn send_one(
     notification: &notifications_repo::NotificationRow,
 ) -> Result<SendOutcome, anyhow::Error> {
     let settings = notifications_settings_repo::get_or_default(db).await?;
-    if !settings.enabled {
+    let destination = match load_destination(
+        db,
+        secrets,
+        &settings,
+        &notification.channel,
+        &notification.secret_name,
+    )
+    .await?
+    {
+        Ok(destination) => destination,
+        Err(outcome) => return Ok(outcome),
+    };
+
+    let ctx = build_context(db, &notification.run_id).await?;
+    if !destination
+        .channel
+        .accepts_run_status(&destination.config, ctx.status())
+    {
         return Ok(SendOutcome::Canceled {
-            reason: "canceled: notifications disabled".to_string(),
+            reason: format!("canceled: destination skips {} runs", ctx.status()),
         });
     }
-
-    match notification.channel.as_str() {
-        notifications_repo::CHANNEL_WECOM_BOT => {
-            if !settings.channels.wecom_bot.enabled {
-                return Ok(SendOutcome::Canceled {
-                    reason: "canceled: channel disabled".to_string(),
-                });
-            }
-            if !notification_destinations_repo::is_enabled(
-                db,
-                notifications_repo::CHANNEL_WECOM_BOT,
-                &notification.secret_name,
-            )
-            .await?
-            {
-                return Ok(SendOutcome::Canceled {
-                    reason: "canceled: destination disabled".to_string(),
-                });
-            }
-
-            let secret_bytes = secrets_repo::get_secret(
-                db,
-                secrets,
-                HUB_NODE_ID,
-                "wecom_bot",
-                &notification.secret_name,
-            )
-            .await?;
-            let Some(secret_bytes) = secret_bytes else {
-                return Ok(SendOutcome::Canceled {
-                    reason: "canceled: destination deleted".to_string(),
-                });
-            };
-            #[derive(serde::Deserialize)]
-            struct Payload {
-                webhook_url: String,
-            }
-            let payload: Payload = serde_json::from_slice(&secret_bytes)?;
-
-            let ctx = build_context(db, &notification.run_id).await?;
-            let content = render_template(&settings.templates.wecom_markdown, &ctx);
-            wecom::send_markdown(&payload.webhook_url, &content).await?;
-
-            let _ = run_events::append_and_broadcast(
-                db,
-                run_events_bus,
-                &notification.run_id,
-                "info",
-                "notify_sent",
-                "notify_sent",
-                Some(serde_json::json!({
-                    "channel": notification.channel,
-                    "secret_name": notification.secret_name,
-                })),
-            )
-            .await;
-
-            info!(
-                run_id = %notification.run_id,
-                secret_name = %notification.secret_name,
-                "wecom notification sent"
-            );
-
-            Ok(SendOutcome::Sent)
-        }
-        notifications_repo::CHANNEL_EMAIL => {
-            if !settings.channels.email.enabled {
-                return Ok(SendOutcome::Canceled {
-                    reason: "canceled: channel disabled".to_string(),
-                });
-            }
-            if !notification_destinations_repo::is_enabled(
-                db,
-                notifications_repo::CHANNEL_EMAIL,
-                &notification.secret_name,
-            )
-            .await?
-            {
-                return Ok(SendOutcome::Canceled {
-                    reason: "canceled: destination disabled".to_string(),
-                });
-            }
-
-            let secret_bytes = secrets_repo::get_secret(
-                db,
-                secrets,
-                HUB_NODE_ID,
-                "smtp",
-                &notification.secret_name,
-            )
-            .await?;
-            let Some(secret_bytes) = secret_bytes else {
-                return Ok(SendOutcome::Canceled {
-                    reason: "canceled: destination deleted".to_string(),
-                });
-            };
-            let payload: smtp::SmtpSecretPayload = serde_json::from_slice(&secret_bytes)?;
-
-            let ctx = build_context(db, &notification.run_id).await?;
-            let subject = render_template(&settings.templates.email_subject, &ctx);
-            let body = render_template(&settings.templates.email_body, &ctx);
-            smtp::send_plain_text(&payload, &subject, &body).await?;
-
-            let _ = run_events::append_and_broadcast(
-                db,
-                run_events_bus,
-                &notification.run_id,
-                "info",
-                "notify_sent",
-                "notify_sent",
-                Some(serde_json::json!({
-                    "channel": notification.channel,
-                    "secret_name": notification.secret_name,
-                })),
-            )
-            .await;
-
-            info!(
-                run_id = %notification.run_id,
-                secret_name = %notification.secret_name,
-                "email notification sent"
-            );
-
-            Ok(SendOutcome::Sent)
-        }
-        other => anyhow::bail!("unsupported notification channel: {other}"),
-    }
+    let message = destination.channel.render(
+        &destination.config,
+        &RunTemplates {
+            templates: &settings.templates,
+            ctx: &ctx,
+        },
+    )?;
+    destination
+        .channel
+        .send(&destination.config, &message)
+        .await?;
+
+    let _ = run_events::append_and_broadcast(
+        db,
+        run_events_bus,
+        &notification.run_id,
+        "info",
+        "notify_sent",
+        "notify_sent",
+        Some(serde_json::json!({
+            "channel": notification.channel,
+            "secret_name": notification.secret_name,
+        })),
+    )
+    .await;
+
+    info!(
+        run_id = %notification.run_id,
+        channel = %notification.channel,
+        secret_name = %notification.secret_name,
+        "notification sent"
+    );
+
+    Ok(SendOutcome::Sent)
 }
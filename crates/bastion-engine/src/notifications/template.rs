@@ -3,6 +3,9 @@ use sqlx::SqlitePool;
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
 
+use bastion_core::run_failure::RunErrorCode;
+use bastion_storage::runs_repo;
+
 pub(super) struct TemplateContext {
     title: String,
     job_id: String,
@@ -12,6 +15,10 @@ pub(super) struct TemplateContext {
     status_text: String,
     started_at: String,
     ended_at: String,
+    duration: String,
+    duration_seconds: String,
+    bytes: String,
+    source_bytes: String,
     target_type: String,
     target_location: String,
     target: String,
@@ -23,6 +30,97 @@ pub(super) struct TemplateContext {
     target_line_email: String,
     consistency_line_email: String,
     error_line_email: String,
+    bytes_delta: String,
+    files_delta: String,
+    duration_delta: String,
+    delta_line_wecom: String,
+    delta_line_email: String,
+    anomaly_line_wecom: String,
+    anomaly_line_email: String,
+}
+
+impl TemplateContext {
+    /// Run status (`success`, `failed`, ...), `unknown` when the run is gone.
+    pub(super) fn status(&self) -> &str {
+        &self.status
+    }
+}
+
+/// Size, file count and duration of a run, as far as its summary reports them.
+#[derive(Debug, Default, Clone, Copy)]
+struct RunMetrics {
+    transfer_bytes: Option<u64>,
+    files: Option<u64>,
+    duration_secs: Option<u64>,
+}
+
+impl RunMetrics {
+    fn from_run(
+        summary: Option<&serde_json::Value>,
+        started_at: i64,
+        ended_at: Option<i64>,
+    ) -> Self {
+        let metrics = summary.and_then(|v| v.get("metrics"));
+        Self {
+            transfer_bytes: metrics
+                .and_then(|m| m.get("transfer_total_bytes"))
+                .and_then(|v| v.as_u64()),
+            files: metrics
+                .and_then(|m| m.get("source_total"))
+                .and_then(|t| t.get("files"))
+                .and_then(|v| v.as_u64()),
+            duration_secs: ended_at
+                .map(|ended_at| ended_at.saturating_sub(started_at).max(0) as u64),
+        }
+    }
+}
+
+/// Changes against the previous successful run; `None` where either run lacks the value.
+#[derive(Debug, Default)]
+struct RunDelta {
+    bytes: Option<String>,
+    files: Option<String>,
+    duration: Option<String>,
+}
+
+impl RunDelta {
+    fn between(current: RunMetrics, previous: RunMetrics) -> Self {
+        fn pair(cur: Option<u64>, prev: Option<u64>) -> Option<(u64, u64)> {
+            Some((cur?, prev?))
+        }
+
+        Self {
+            bytes: pair(current.transfer_bytes, previous.transfer_bytes).map(|(cur, prev)| {
+                let sign = if cur >= prev { "+" } else { "-" };
+                let mut out = format!("{sign}{}", format_bytes(cur.abs_diff(prev)));
+                if prev > 0 {
+                    let pct = (cur as f64 - prev as f64) / prev as f64 * 100.0;
+                    out.push_str(&format!(" ({pct:+.1}%)"));
+                }
+                out
+            }),
+            files: pair(current.files, previous.files).map(|(cur, prev)| {
+                let sign = if cur >= prev { "+" } else { "-" };
+                format!("{sign}{}", cur.abs_diff(prev))
+            }),
+            duration: pair(current.duration_secs, previous.duration_secs).map(|(cur, prev)| {
+                let sign = if cur >= prev { "+" } else { "-" };
+                format!("{sign}{}", format_duration(cur.abs_diff(prev)))
+            }),
+        }
+    }
+
+    fn line(&self) -> Option<String> {
+        let parts = [
+            self.bytes.as_deref().map(|v| format!("size {v}")),
+            self.files.as_deref().map(|v| format!("files {v}")),
+            self.duration.as_deref().map(|v| format!("duration {v}")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        (!parts.is_empty()).then(|| format!("Change vs last success: {}", parts.join(", ")))
+    }
 }
 
 pub(super) async fn build_context(
@@ -46,6 +144,10 @@ pub(super) async fn build_context(
             status_text: "Unknown".to_string(),
             started_at: "-".to_string(),
             ended_at: "-".to_string(),
+            duration: "-".to_string(),
+            duration_seconds: "-".to_string(),
+            bytes: "-".to_string(),
+            source_bytes: "-".to_string(),
             target_type: "-".to_string(),
             target_location: "-".to_string(),
             target: "-".to_string(),
@@ -57,6 +159,13 @@ pub(super) async fn build_context(
             target_line_email: String::new(),
             consistency_line_email: String::new(),
             error_line_email: String::new(),
+            bytes_delta: "-".to_string(),
+            files_delta: "-".to_string(),
+            duration_delta: "-".to_string(),
+            delta_line_wecom: String::new(),
+            delta_line_email: String::new(),
+            anomaly_line_wecom: String::new(),
+            anomaly_line_email: String::new(),
         });
     };
 
@@ -73,7 +182,7 @@ pub(super) async fn build_context(
         .await?
         .unwrap_or_else(|| job_id.clone());
 
-    let (title, status_text) = match status.as_str() {
+    let (mut title, status_text) = match status.as_str() {
         "success" => (
             "Bastion backup succeeded".to_string(),
             "Succeeded".to_string(),
@@ -95,6 +204,7 @@ pub(super) async fn build_context(
 
     let started_at_str = format_ts(started_at);
     let ended_at_str = ended_at.map(format_ts).unwrap_or_else(|| "-".to_string());
+    let duration_secs = ended_at.map(|ended_at| ended_at.saturating_sub(started_at).max(0) as u64);
 
     let mut target_type = "-".to_string();
     let mut target_location = "-".to_string();
@@ -118,6 +228,14 @@ pub(super) async fn build_context(
             .to_string();
     }
 
+    let metrics = summary_value.as_ref().and_then(|v| v.get("metrics"));
+    let current_metrics = RunMetrics::from_run(summary_value.as_ref(), started_at, ended_at);
+    let transfer_bytes = current_metrics.transfer_bytes;
+    let source_bytes = metrics
+        .and_then(|m| m.get("source_total"))
+        .and_then(|t| t.get("bytes"))
+        .and_then(|v| v.as_u64());
+
     let target = if target_type != "-" && target_location != "-" {
         format!("{target_type} {target_location}")
     } else if target_type != "-" {
@@ -137,7 +255,7 @@ pub(super) async fn build_context(
         String::new()
     };
     let error_line_wecom = if !error.is_empty() {
-        format!("> Error: {error}\n")
+        format!("> Error: {}\n", error_label(&error))
     } else {
         String::new()
     };
@@ -168,7 +286,7 @@ pub(super) async fn build_context(
         String::new()
     };
     let error_line_email = if !error.is_empty() {
-        format!("Error: {error}\n")
+        format!("Error: {}\n", error_label(&error))
     } else {
         String::new()
     };
@@ -187,6 +305,24 @@ pub(super) async fn build_context(
         String::new()
     };
 
+    let delta = match runs_repo::get_previous_successful_run(db, run_id).await? {
+        Some(previous) => RunDelta::between(
+            current_metrics,
+            RunMetrics::from_run(
+                previous.summary.as_ref(),
+                previous.started_at,
+                previous.ended_at,
+            ),
+        ),
+        None => RunDelta::default(),
+    };
+    let delta_line = delta.line();
+
+    let anomaly_line = summary_value.as_ref().and_then(anomaly_line_from_summary);
+    if anomaly_line.is_some() {
+        title = "Bastion backup anomaly detected".to_string();
+    }
+
     Ok(TemplateContext {
         title,
         job_id,
@@ -196,6 +332,18 @@ pub(super) async fn build_context(
         status_text,
         started_at: started_at_str,
         ended_at: ended_at_str,
+        duration: duration_secs
+            .map(format_duration)
+            .unwrap_or_else(|| "-".to_string()),
+        duration_seconds: duration_secs
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| "-".to_string()),
+        bytes: transfer_bytes
+            .map(format_bytes)
+            .unwrap_or_else(|| "-".to_string()),
+        source_bytes: source_bytes
+            .map(format_bytes)
+            .unwrap_or_else(|| "-".to_string()),
         target_type,
         target_location,
         target,
@@ -207,49 +355,193 @@ pub(super) async fn build_context(
         target_line_email,
         consistency_line_email,
         error_line_email,
+        bytes_delta: delta.bytes.unwrap_or_else(|| "-".to_string()),
+        files_delta: delta.files.unwrap_or_else(|| "-".to_string()),
+        duration_delta: delta.duration.unwrap_or_else(|| "-".to_string()),
+        delta_line_wecom: delta_line
+            .as_deref()
+            .map(|line| format!("> {line}\n"))
+            .unwrap_or_default(),
+        delta_line_email: delta_line
+            .map(|line| format!("{line}\n"))
+            .unwrap_or_default(),
+        anomaly_line_wecom: anomaly_line
+            .as_deref()
+            .map(|line| format!("> **{line}**\n"))
+            .unwrap_or_default(),
+        anomaly_line_email: anomaly_line
+            .map(|line| format!("{line}\n"))
+            .unwrap_or_default(),
     })
 }
 
-pub(super) fn render_template(template: &str, ctx: &TemplateContext) -> String {
-    let consistency_changed_total = ctx.consistency_changed_total.to_string();
-    let pairs = [
-        ("{{title}}", ctx.title.as_str()),
-        ("{{job_id}}", ctx.job_id.as_str()),
-        ("{{job_name}}", ctx.job_name.as_str()),
-        ("{{run_id}}", ctx.run_id.as_str()),
-        ("{{status}}", ctx.status.as_str()),
-        ("{{status_text}}", ctx.status_text.as_str()),
-        ("{{started_at}}", ctx.started_at.as_str()),
-        ("{{ended_at}}", ctx.ended_at.as_str()),
-        ("{{target_type}}", ctx.target_type.as_str()),
-        ("{{target_location}}", ctx.target_location.as_str()),
-        ("{{target}}", ctx.target.as_str()),
-        (
-            "{{consistency_changed_total}}",
-            consistency_changed_total.as_str(),
-        ),
-        ("{{error}}", ctx.error.as_str()),
-        ("{{target_line_wecom}}", ctx.target_line_wecom.as_str()),
+fn template_values(ctx: &TemplateContext) -> [(&'static str, String); 30] {
+    [
+        ("title", ctx.title.clone()),
+        ("job_id", ctx.job_id.clone()),
+        ("job_name", ctx.job_name.clone()),
+        ("run_id", ctx.run_id.clone()),
+        ("status", ctx.status.clone()),
+        ("status_text", ctx.status_text.clone()),
+        ("started_at", ctx.started_at.clone()),
+        ("ended_at", ctx.ended_at.clone()),
+        ("duration", ctx.duration.clone()),
+        ("duration_seconds", ctx.duration_seconds.clone()),
+        ("bytes", ctx.bytes.clone()),
+        ("source_bytes", ctx.source_bytes.clone()),
+        ("target_type", ctx.target_type.clone()),
+        ("target_location", ctx.target_location.clone()),
+        ("target", ctx.target.clone()),
         (
-            "{{consistency_line_wecom}}",
-            ctx.consistency_line_wecom.as_str(),
+            "consistency_changed_total",
+            ctx.consistency_changed_total.to_string(),
         ),
-        ("{{error_line_wecom}}", ctx.error_line_wecom.as_str()),
-        ("{{target_line_email}}", ctx.target_line_email.as_str()),
-        (
-            "{{consistency_line_email}}",
-            ctx.consistency_line_email.as_str(),
-        ),
-        ("{{error_line_email}}", ctx.error_line_email.as_str()),
-    ];
+        ("error", ctx.error.clone()),
+        ("target_line_wecom", ctx.target_line_wecom.clone()),
+        ("consistency_line_wecom", ctx.consistency_line_wecom.clone()),
+        ("error_line_wecom", ctx.error_line_wecom.clone()),
+        ("target_line_email", ctx.target_line_email.clone()),
+        ("consistency_line_email", ctx.consistency_line_email.clone()),
+        ("error_line_email", ctx.error_line_email.clone()),
+        ("bytes_delta", ctx.bytes_delta.clone()),
+        ("files_delta", ctx.files_delta.clone()),
+        ("duration_delta", ctx.duration_delta.clone()),
+        ("delta_line_wecom", ctx.delta_line_wecom.clone()),
+        ("delta_line_email", ctx.delta_line_email.clone()),
+        ("anomaly_line_wecom", ctx.anomaly_line_wecom.clone()),
+        ("anomaly_line_email", ctx.anomaly_line_email.clone()),
+    ]
+}
 
+pub(super) fn render_template(template: &str, ctx: &TemplateContext) -> String {
     let mut out = template.to_string();
-    for (k, v) in pairs {
-        out = out.replace(k, v);
+    for (k, v) in template_values(ctx) {
+        out = out.replace(&format!("{{{{{k}}}}}"), &v);
     }
     out
 }
 
+/// Lists `{{name}}` placeholders that are not template variables (they are left verbatim).
+fn unknown_variables(template: &str, ctx: &TemplateContext) -> Vec<String> {
+    let known = template_values(ctx).map(|(k, _)| k);
+    let mut out = Vec::<String>::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        let name = &after[..end];
+        if !known.contains(&name) && !out.iter().any(|v| v == name) {
+            out.push(name.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+    out
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EmailPreview {
+    pub subject: String,
+    pub body: String,
+    pub unknown_variables: Vec<String>,
+}
+
+/// Renders email templates against a real run (when `run_id` is given) or a sample context.
+pub async fn render_email_preview(
+    db: &SqlitePool,
+    subject_template: &str,
+    body_template: &str,
+    run_id: Option<&str>,
+) -> Result<EmailPreview, anyhow::Error> {
+    let ctx = match run_id {
+        Some(run_id) => build_context(db, run_id).await?,
+        None => sample_context(),
+    };
+
+    let mut unknown = unknown_variables(subject_template, &ctx);
+    for name in unknown_variables(body_template, &ctx) {
+        if !unknown.contains(&name) {
+            unknown.push(name);
+        }
+    }
+
+    Ok(EmailPreview {
+        subject: render_template(subject_template, &ctx),
+        body: render_template(body_template, &ctx),
+        unknown_variables: unknown,
+    })
+}
+
+fn sample_context() -> TemplateContext {
+    TemplateContext {
+        title: "Bastion backup succeeded".to_string(),
+        job_id: "00000000-0000-0000-0000-000000000000".to_string(),
+        job_name: "Example job".to_string(),
+        run_id: "00000000-0000-0000-0000-000000000001".to_string(),
+        status: "success".to_string(),
+        status_text: "Succeeded".to_string(),
+        started_at: format_ts(1_700_000_000),
+        ended_at: format_ts(1_700_000_125),
+        duration: format_duration(125),
+        duration_seconds: "125".to_string(),
+        bytes: format_bytes(52_428_800),
+        source_bytes: format_bytes(209_715_200),
+        target_type: "webdav".to_string(),
+        target_location: "https://dav.example.com/backups/run".to_string(),
+        target: "webdav https://dav.example.com/backups/run".to_string(),
+        consistency_changed_total: 0,
+        error: String::new(),
+        target_line_wecom: "> Target: webdav https://dav.example.com/backups/run\n".to_string(),
+        consistency_line_wecom: String::new(),
+        error_line_wecom: String::new(),
+        target_line_email: "Target: webdav https://dav.example.com/backups/run\n".to_string(),
+        consistency_line_email: String::new(),
+        error_line_email: String::new(),
+        bytes_delta: "+2.0 MiB (+4.2%)".to_string(),
+        files_delta: "+12".to_string(),
+        duration_delta: "+5s".to_string(),
+        delta_line_wecom:
+            "> Change vs last success: size +2.0 MiB (+4.2%), files +12, duration +5s\n".to_string(),
+        delta_line_email:
+            "Change vs last success: size +2.0 MiB (+4.2%), files +12, duration +5s\n".to_string(),
+        anomaly_line_wecom: String::new(),
+        anomaly_line_email: String::new(),
+    }
+}
+
+/// The filesystem anomaly report of a run, when its heuristics tripped.
+pub(super) fn triggered_anomaly(summary: &serde_json::Value) -> Option<&serde_json::Value> {
+    summary
+        .get("filesystem")
+        .and_then(|v| v.get("anomaly"))
+        .filter(|a| a.get("triggered").and_then(|v| v.as_bool()) == Some(true))
+}
+
+fn anomaly_line_from_summary(summary: &serde_json::Value) -> Option<String> {
+    let anomaly = triggered_anomaly(summary)?;
+    let count = |key: &str| anomaly.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let findings = anomaly
+        .get("findings")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_default();
+    Some(format!(
+        "Possible ransomware activity ({findings}): modified {}, removed {}, suspicious extensions {}, high entropy {}/{}",
+        count("modified_files"),
+        count("removed_files"),
+        count("suspicious_extension_files"),
+        count("high_entropy_files"),
+        count("entropy_sampled_files"),
+    ))
+}
+
 fn consistency_changed_total_from_summary(summary: &serde_json::Value) -> u64 {
     fn report_total(report: &serde_json::Value) -> u64 {
         let Some(obj) = report.as_object() else {
@@ -289,8 +581,47 @@ fn consistency_changed_total_from_summary(summary: &serde_json::Value) -> u64 {
         .and_then(|v| v.get("consistency"))
         .map(report_total)
         .unwrap_or(0);
+    let app_total = summary
+        .get("sqlite_app")
+        .and_then(|v| v.get("consistency"))
+        .map(report_total)
+        .unwrap_or(0);
+
+    fs_total.saturating_add(vw_total).saturating_add(app_total)
+}
+
+/// Renders a run error for people: catalog codes get their description, anything else is shown
+/// as stored.
+pub(super) fn error_label(error: &str) -> String {
+    match RunErrorCode::from_code(error) {
+        Some(code) if code.as_str() == error => format!("{} ({code})", code.description()),
+        _ => error.to_string(),
+    }
+}
 
-    fs_total.saturating_add(vw_total)
+pub(super) fn format_duration(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{h}h {m}m {s}s")
+    } else if m > 0 {
+        format!("{m}m {s}s")
+    } else {
+        format!("{s}s")
+    }
+}
+
+pub(super) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
 }
 
 fn format_ts(ts: i64) -> String {
@@ -307,6 +638,15 @@ mod tests {
     use bastion_storage::jobs_repo::OverlapPolicy;
     use bastion_storage::runs_repo::RunStatus;
 
+    #[test]
+    fn error_label_describes_catalog_codes() {
+        assert_eq!(
+            error_label("network_target"),
+            "backup target unreachable (network_target)"
+        );
+        assert_eq!(error_label("boom"), "boom");
+    }
+
     #[test]
     fn format_ts_renders_rfc3339_and_falls_back_on_invalid_values() {
         assert_eq!(format_ts(0), "1970-01-01T00:00:00Z");
@@ -324,6 +664,10 @@ mod tests {
             status_text: "st".to_string(),
             started_at: "sa".to_string(),
             ended_at: "ea".to_string(),
+            duration: "d".to_string(),
+            duration_seconds: "ds".to_string(),
+            bytes: "b".to_string(),
+            source_bytes: "sb".to_string(),
             target_type: "tt".to_string(),
             target_location: "tl".to_string(),
             target: "tgt".to_string(),
@@ -335,10 +679,60 @@ mod tests {
             target_line_email: "Target: tgt\n".to_string(),
             consistency_line_email: "Source changed during backup: 0\n".to_string(),
             error_line_email: "Error: err\n".to_string(),
+            bytes_delta: "bd".to_string(),
+            files_delta: "fd".to_string(),
+            duration_delta: "dd".to_string(),
+            delta_line_wecom: String::new(),
+            delta_line_email: String::new(),
+            anomaly_line_wecom: String::new(),
+            anomaly_line_email: String::new(),
         };
 
         let out = render_template("{{title}} {{job_id}} {{run_id}} {{unknown}}", &ctx);
         assert_eq!(out, "t j r {{unknown}}");
+
+        let out = render_template("{{job_name}} {{duration}} {{bytes}}", &ctx);
+        assert_eq!(out, "jn d b");
+
+        let out = render_template("{{bytes_delta}} {{files_delta}} {{duration_delta}}", &ctx);
+        assert_eq!(out, "bd fd dd");
+        assert_eq!(
+            unknown_variables("{{job_name}} {{nope}} {{nope}} {{", &ctx),
+            vec!["nope".to_string()]
+        );
+    }
+
+    #[test]
+    fn anomaly_line_requires_triggered_report() {
+        let quiet = serde_json::json!({ "filesystem": { "anomaly": { "triggered": false } } });
+        assert_eq!(anomaly_line_from_summary(&quiet), None);
+        assert_eq!(anomaly_line_from_summary(&serde_json::json!({})), None);
+
+        let flagged = serde_json::json!({ "filesystem": { "anomaly": {
+            "triggered": true,
+            "findings": ["mass_change", "entropy"],
+            "modified_files": 90,
+            "removed_files": 2,
+            "suspicious_extension_files": 0,
+            "high_entropy_files": 40,
+            "entropy_sampled_files": 50,
+        } } });
+        assert_eq!(
+            anomaly_line_from_summary(&flagged).as_deref(),
+            Some(
+                "Possible ransomware activity (mass_change, entropy): modified 90, removed 2, suspicious extensions 0, high entropy 40/50"
+            )
+        );
+    }
+
+    #[test]
+    fn format_duration_and_bytes_are_human_readable() {
+        assert_eq!(format_duration(5), "5s");
+        assert_eq!(format_duration(125), "2m 5s");
+        assert_eq!(format_duration(3_725), "1h 2m 5s");
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(52_428_800), "50.0 MiB");
     }
 
     async fn init_test_db() -> Result<(tempfile::TempDir, SqlitePool), anyhow::Error> {
@@ -416,6 +810,9 @@ mod tests {
         assert_eq!(ctx.status_text, "Succeeded");
         assert_eq!(ctx.started_at, "1970-01-01T00:00:00Z");
         assert_eq!(ctx.ended_at, "1970-01-01T00:00:01Z");
+        assert_eq!(ctx.duration, "1s");
+        assert_eq!(ctx.duration_seconds, "1");
+        assert_eq!(ctx.bytes, "-");
 
         assert_eq!(ctx.target_type, "webdav");
         assert_eq!(ctx.target_location, "https://example.invalid/runs/123");
@@ -501,4 +898,78 @@ mod tests {
         );
         Ok(())
     }
+
+    #[tokio::test]
+    async fn build_context_compares_with_previous_successful_run() -> Result<(), anyhow::Error> {
+        let (_dir, db) = init_test_db().await?;
+
+        let job = bastion_storage::jobs_repo::create_job(
+            &db,
+            "myjob",
+            None,
+            None,
+            None,
+            OverlapPolicy::Reject,
+            serde_json::json!({}),
+        )
+        .await?;
+
+        let summary = |bytes: u64, files: u64| {
+            serde_json::json!({
+                "metrics": {
+                    "transfer_total_bytes": bytes,
+                    "source_total": { "files": files, "dirs": 1, "bytes": bytes }
+                }
+            })
+        };
+        let first = bastion_storage::runs_repo::create_run(
+            &db,
+            &job.id,
+            RunStatus::Success,
+            0,
+            Some(100),
+            Some(summary(1024 * 1024, 100)),
+            None,
+        )
+        .await?;
+        let ctx = build_context(&db, &first.id).await?;
+        assert_eq!(ctx.bytes_delta, "-");
+        assert_eq!(ctx.delta_line_email, "");
+
+        // A failed run in between is not the baseline.
+        bastion_storage::runs_repo::create_run(
+            &db,
+            &job.id,
+            RunStatus::Failed,
+            200,
+            Some(210),
+            Some(summary(1, 1)),
+            Some("boom"),
+        )
+        .await?;
+        let second = bastion_storage::runs_repo::create_run(
+            &db,
+            &job.id,
+            RunStatus::Success,
+            300,
+            Some(460),
+            Some(summary(3 * 512 * 1024, 90)),
+            None,
+        )
+        .await?;
+
+        let ctx = build_context(&db, &second.id).await?;
+        assert_eq!(ctx.bytes_delta, "+512.0 KiB (+50.0%)");
+        assert_eq!(ctx.files_delta, "-10");
+        assert_eq!(ctx.duration_delta, "+1m 0s");
+        assert_eq!(
+            ctx.delta_line_email,
+            "Change vs last success: size +512.0 KiB (+50.0%), files -10, duration +1m 0s\n"
+        );
+        assert_eq!(
+            ctx.delta_line_wecom,
+            "> Change vs last success: size +512.0 KiB (+50.0%), files -10, duration +1m 0s\n"
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,456 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tracing::{info, warn};
+
+use bastion_core::HUB_NODE_ID;
+use bastion_core::job_spec::{self, JobSpecV1};
+use bastion_driver_api::TargetStore;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{jobs_repo, run_artifacts_repo};
+
+use crate::snapshot_target;
+
+/// Why a directory on a target was reported as orphaned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanReason {
+    /// Run directory of a known job, but the DB has no such run (crashed/purged run).
+    UnknownRun,
+    /// The run exists but its snapshot was recorded as deleted.
+    ArtifactDeleted,
+    /// Job directory for a job this Hub does not know. Reported only: the target may be shared.
+    UnknownJob,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanDir {
+    pub node_id: String,
+    pub target_type: String,
+    pub job_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    pub location: String,
+    pub reason: OrphanReason,
+    pub deletable: bool,
+    pub deleted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetScan {
+    pub node_id: String,
+    pub target_type: String,
+    pub location: String,
+    pub jobs_scanned: u32,
+    /// Set when the target cannot be reached from the Hub (e.g. an Agent-local directory).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OrphanGcReport {
+    pub targets: Vec<TargetScan>,
+    pub orphans: Vec<OrphanDir>,
+    pub deleted_total: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OrphanGcOptions {
+    /// Only scan this job's directories (unknown job directories are not reported then).
+    pub job_id: Option<String>,
+    /// Delete deletable orphans after scanning.
+    pub delete: bool,
+    /// When deleting, only delete orphans of these runs.
+    pub run_ids: Option<Vec<String>>,
+}
+
+/// A target as recorded in run snapshots: its type plus the redacted config (no credentials).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct TargetKey {
+    node_id: String,
+    target_type: String,
+    config: String,
+}
+
+impl TargetKey {
+    fn from_snapshot(snapshot: &serde_json::Value) -> Option<Self> {
+        let node_id = snapshot.get("node_id")?.as_str()?.to_string();
+        let target = snapshot.get("target")?;
+        Some(TargetKey {
+            node_id,
+            target_type: target.get("type")?.as_str()?.to_string(),
+            config: target.to_string(),
+        })
+    }
+
+    fn from_spec(node_id: &str, spec: &JobSpecV1) -> Option<Self> {
+        Self::from_snapshot(&crate::scheduler::build_run_target_snapshot(node_id, spec).ok()?)
+    }
+}
+
+/// Bastion names job and run directories after UUIDs; anything else on the target is left alone.
+fn is_bastion_id(name: &str) -> bool {
+    uuid::Uuid::parse_str(name).is_ok()
+}
+
+/// Compares job/run directories on every target the Hub knows about against the runs and
+/// snapshots in the DB, and optionally deletes the orphaned run directories.
+pub async fn reconcile(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    options: &OrphanGcOptions,
+) -> Result<OrphanGcReport, anyhow::Error> {
+    let jobs = jobs_repo::list_jobs_including_archived(db).await?;
+    let known_jobs = jobs.iter().map(|j| j.id.clone()).collect::<HashSet<_>>();
+
+    let mut targets: BTreeMap<TargetKey, BTreeSet<String>> = BTreeMap::new();
+    for job in &jobs {
+        if options.job_id.as_ref().is_some_and(|id| id != &job.id) {
+            continue;
+        }
+        let Ok(spec) = job_spec::parse_value(&job.spec) else {
+            continue;
+        };
+        let node_id = job.agent_id.as_deref().unwrap_or(HUB_NODE_ID);
+        let Some(key) = TargetKey::from_spec(node_id, &spec) else {
+            continue;
+        };
+        targets.entry(key).or_default().insert(job.id.clone());
+    }
+    for (job_id, snapshot) in run_artifacts_repo::list_distinct_targets(db).await? {
+        if options.job_id.as_ref().is_some_and(|id| id != &job_id) {
+            continue;
+        }
+        if let Some(key) = TargetKey::from_snapshot(&snapshot) {
+            targets.entry(key).or_default().insert(job_id);
+        }
+    }
+
+    let mut report = OrphanGcReport::default();
+    for (key, job_ids) in targets {
+        let mut scan = TargetScan {
+            node_id: key.node_id.clone(),
+            target_type: key.target_type.clone(),
+            location: String::new(),
+            jobs_scanned: 0,
+            skipped: None,
+            error: None,
+        };
+
+        let result = match open_key(db, secrets, &key).await {
+            Ok(target) => {
+                scan.location = target.describe("");
+                // Agent-local directories are only reachable from the Agent itself.
+                if target.node_local() && key.node_id != HUB_NODE_ID {
+                    scan.skipped = Some(format!("agent_{}", key.target_type));
+                    report.targets.push(scan);
+                    continue;
+                }
+                scan_target(
+                    db,
+                    target.as_ref(),
+                    &key.node_id,
+                    &job_ids,
+                    &known_jobs,
+                    options,
+                    &mut scan,
+                )
+                .await
+            }
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(orphans) => report.orphans.extend(orphans),
+            Err(error) => {
+                warn!(location = %scan.location, error = %error, "orphan scan failed for target");
+                scan.error = Some(format!("{error:#}"));
+            }
+        }
+        report.targets.push(scan);
+    }
+
+    report.deleted_total = report.orphans.iter().filter(|o| o.deleted).count() as u32;
+    if report.deleted_total > 0 {
+        info!(
+            deleted = report.deleted_total,
+            "deleted orphaned run directories"
+        );
+    }
+    Ok(report)
+}
+
+/// Classifies a run directory of a known job; `None` means the DB still accounts for it.
+fn classify_run_dir(
+    known_runs: &HashMap<String, Option<String>>,
+    run_id: &str,
+) -> Option<OrphanReason> {
+    match known_runs.get(run_id) {
+        None => Some(OrphanReason::UnknownRun),
+        Some(Some(status)) if status == "deleted" => Some(OrphanReason::ArtifactDeleted),
+        Some(_) => None,
+    }
+}
+
+fn should_delete(options: &OrphanGcOptions, orphan: &OrphanDir) -> bool {
+    if !options.delete || !orphan.deletable {
+        return false;
+    }
+    match (&options.run_ids, &orphan.run_id) {
+        (Some(only), Some(run_id)) => only.contains(run_id),
+        (None, _) => true,
+        (Some(_), None) => false,
+    }
+}
+
+async fn known_runs_for_job(
+    db: &SqlitePool,
+    job_id: &str,
+) -> Result<HashMap<String, Option<String>>, anyhow::Error> {
+    Ok(run_artifacts_repo::list_known_runs_for_job(db, job_id)
+        .await?
+        .into_iter()
+        .collect())
+}
+
+async fn open_key(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    key: &TargetKey,
+) -> Result<Arc<dyn TargetStore>, anyhow::Error> {
+    let config = serde_json::from_str::<serde_json::Value>(&key.config)?;
+    snapshot_target::open(db, secrets, &key.node_id, &config).await
+}
+
+async fn list_subdirs(target: &dyn TargetStore, dir: &str) -> Result<Vec<String>, anyhow::Error> {
+    Ok(target
+        .list(dir)
+        .await?
+        .into_iter()
+        .filter(|entry| entry.is_dir)
+        .map(|entry| entry.name.trim_end_matches('/').to_string())
+        .collect())
+}
+
+async fn scan_target(
+    db: &SqlitePool,
+    target: &dyn TargetStore,
+    node_id: &str,
+    job_ids: &BTreeSet<String>,
+    known_jobs: &HashSet<String>,
+    options: &OrphanGcOptions,
+    scan: &mut TargetScan,
+) -> Result<Vec<OrphanDir>, anyhow::Error> {
+    let mut orphans = Vec::new();
+
+    for job_id in job_ids {
+        scan.jobs_scanned += 1;
+        let known_runs = known_runs_for_job(db, job_id).await?;
+
+        for run_id in list_subdirs(target, &format!("{job_id}/")).await? {
+            if !is_bastion_id(&run_id) {
+                continue;
+            }
+            let Some(reason) = classify_run_dir(&known_runs, &run_id) else {
+                continue;
+            };
+            let run_dir = snapshot_target::run_dir_path(job_id, &run_id);
+            let mut orphan = OrphanDir {
+                node_id: node_id.to_string(),
+                target_type: target.kind().to_string(),
+                job_id: job_id.clone(),
+                run_id: Some(run_id.clone()),
+                location: target.describe(&run_dir),
+                reason,
+                deletable: snapshot_target::is_deletable_run_dir(target, &run_dir).await?,
+                deleted: false,
+                error: None,
+            };
+            if should_delete(options, &orphan) {
+                match target.delete(&run_dir).await {
+                    Ok(_) => orphan.deleted = true,
+                    Err(error) => orphan.error = Some(format!("{error:#}")),
+                }
+            }
+            orphans.push(orphan);
+        }
+    }
+
+    if options.job_id.is_none() {
+        for name in list_subdirs(target, "").await? {
+            if !is_bastion_id(&name) || known_jobs.contains(&name) {
+                continue;
+            }
+            let job_dir = format!("{name}/");
+            let holds_runs = list_subdirs(target, &job_dir)
+                .await?
+                .iter()
+                .any(|run_id| is_bastion_id(run_id));
+            if !holds_runs {
+                continue;
+            }
+            orphans.push(OrphanDir {
+                node_id: node_id.to_string(),
+                target_type: target.kind().to_string(),
+                location: target.describe(&job_dir),
+                job_id: name,
+                run_id: None,
+                reason: OrphanReason::UnknownJob,
+                deletable: false,
+                deleted: false,
+                error: None,
+            });
+        }
+    }
+
+    Ok(orphans)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use bastion_storage::jobs_repo::{self, OverlapPolicy};
+    use bastion_storage::runs_repo::{self, RunStatus};
+    use bastion_storage::secrets::SecretsCrypto;
+    use bastion_storage::{db, run_artifacts_repo};
+
+    use super::{OrphanGcOptions, OrphanReason, reconcile};
+
+    fn write_run_dir(base: &std::path::Path, job_id: &str, run_id: &str) -> std::path::PathBuf {
+        let dir = base.join(job_id).join(run_id);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("payload.part000001"), b"x").unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn reports_and_deletes_orphaned_local_run_dirs() {
+        let data = TempDir::new().unwrap();
+        let target = TempDir::new().unwrap();
+        let pool = db::init(data.path()).await.unwrap();
+        let crypto = SecretsCrypto::load_or_create(data.path()).unwrap();
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "job",
+            None,
+            None,
+            Some("UTC"),
+            OverlapPolicy::Queue,
+            serde_json::json!({
+              "v": 1,
+              "type": "filesystem",
+              "source": { "paths": ["/tmp"] },
+              "target": { "type": "local_dir", "base_dir": target.path().display().to_string() }
+            }),
+        )
+        .await
+        .unwrap();
+
+        let live = runs_repo::create_run(&pool, &job.id, RunStatus::Success, 1, None, None, None)
+            .await
+            .unwrap();
+        let gone = runs_repo::create_run(&pool, &job.id, RunStatus::Success, 1, None, None, None)
+            .await
+            .unwrap();
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        for run_id in [&live.id, &gone.id] {
+            sqlx::query(
+                r#"
+                INSERT INTO run_artifacts (
+                  run_id, job_id, node_id, target_type, target_snapshot_json,
+                  artifact_format, status, started_at, ended_at, created_at, updated_at
+                ) VALUES (?, ?, 'hub', 'local_dir', ?, 'archive_v1', 'present', 1, 2, ?, ?)
+                "#,
+            )
+            .bind(run_id)
+            .bind(&job.id)
+            .bind(
+                serde_json::json!({
+                    "node_id": "hub",
+                    "target": { "type": "local_dir", "base_dir": target.path().display().to_string() }
+                })
+                .to_string(),
+            )
+            .bind(now)
+            .bind(now)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        run_artifacts_repo::mark_run_artifact_deleted(&pool, &gone.id, now)
+            .await
+            .unwrap();
+
+        let unknown_run = uuid::Uuid::new_v4().to_string();
+        let unknown_job = uuid::Uuid::new_v4().to_string();
+        let live_dir = write_run_dir(target.path(), &job.id, &live.id);
+        let gone_dir = write_run_dir(target.path(), &job.id, &gone.id);
+        let unknown_run_dir = write_run_dir(target.path(), &job.id, &unknown_run);
+        let unknown_job_dir = write_run_dir(target.path(), &unknown_job, &unknown_run);
+        // Non-Bastion directories next to ours are ignored.
+        std::fs::create_dir_all(target.path().join(&job.id).join("notes")).unwrap();
+
+        let report = reconcile(&pool, &crypto, &OrphanGcOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(report.deleted_total, 0);
+        assert_eq!(report.orphans.len(), 3);
+        let reason_of = |job_id: &str, run_id: Option<&str>| {
+            report
+                .orphans
+                .iter()
+                .find(|o| o.job_id == job_id && o.run_id.as_deref() == run_id)
+                .map(|o| o.reason)
+        };
+        assert_eq!(
+            reason_of(&job.id, Some(&gone.id)),
+            Some(OrphanReason::ArtifactDeleted)
+        );
+        assert_eq!(
+            reason_of(&job.id, Some(&unknown_run)),
+            Some(OrphanReason::UnknownRun)
+        );
+        assert_eq!(
+            reason_of(&unknown_job, None),
+            Some(OrphanReason::UnknownJob)
+        );
+
+        let report = reconcile(
+            &pool,
+            &crypto,
+            &OrphanGcOptions {
+                delete: true,
+                run_ids: Some(vec![unknown_run.clone()]),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.deleted_total, 1);
+        assert!(!unknown_run_dir.exists());
+        assert!(gone_dir.exists());
+
+        let report = reconcile(
+            &pool,
+            &crypto,
+            &OrphanGcOptions {
+                delete: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.deleted_total, 1);
+        assert!(!gone_dir.exists());
+        assert!(live_dir.exists());
+        // Unknown job directories are never deleted automatically.
+        assert!(unknown_job_dir.exists());
+    }
+}
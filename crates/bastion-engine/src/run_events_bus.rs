@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -8,6 +8,9 @@ use tokio::sync::broadcast;
 use bastion_storage::runs_repo::RunEvent;
 
 const DEFAULT_CAPACITY: usize = 1024;
+/// Larger than the broadcast capacity so a subscriber that lagged out of the channel can still
+/// resume from the buffer.
+const DEFAULT_REPLAY_CAPACITY: usize = 4096;
 const DEFAULT_IDLE_TTL_SECONDS: i64 = 10 * 60;
 const DEFAULT_PRUNE_EVERY_OPS: u64 = 128;
 
@@ -15,11 +18,57 @@ const DEFAULT_PRUNE_EVERY_OPS: u64 = 128;
 struct BusEntry {
     tx: broadcast::Sender<RunEvent>,
     last_used_at: i64,
+    /// The most recently published events of the run, at most `replay_capacity`.
+    recent: VecDeque<RunEvent>,
+}
+
+impl BusEntry {
+    fn new(capacity: usize, now: i64) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self {
+            tx,
+            last_used_at: now,
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Buffered events after `after_seq`, ordered by seq. `None` when the buffer does not hold
+    /// every one of them (aged out, or published before the Hub started).
+    fn replay_after(&self, after_seq: i64) -> Option<Vec<RunEvent>> {
+        let mut events = self
+            .recent
+            .iter()
+            .filter(|event| event.seq > after_seq)
+            .cloned()
+            .collect::<Vec<_>>();
+        events.sort_by_key(|event| event.seq);
+
+        let contiguous = events
+            .iter()
+            .zip(after_seq.saturating_add(1)..)
+            .all(|(event, expected)| event.seq == expected);
+        let reaches_back = match events.first() {
+            Some(_) => true,
+            // Nothing newer: only trust that when the buffer has seen `after_seq` itself.
+            None => self.recent.iter().any(|event| event.seq == after_seq),
+        };
+        (contiguous && reaches_back).then_some(events)
+    }
+}
+
+/// A subscription that resumes after a known seq.
+#[derive(Debug)]
+pub struct RunEventsSubscription {
+    pub rx: broadcast::Receiver<RunEvent>,
+    /// Events after the requested seq that were published before subscribing, ordered by seq.
+    /// `None` when the replay window does not cover them and the caller must read the database.
+    pub replay: Option<Vec<RunEvent>>,
 }
 
 #[derive(Debug)]
 pub struct RunEventsBus {
     capacity: usize,
+    replay_capacity: usize,
     idle_ttl_seconds: i64,
     prune_every_ops: u64,
     ops: AtomicU64,
@@ -39,11 +88,14 @@ impl RunEventsBus {
             DEFAULT_IDLE_TTL_SECONDS,
             DEFAULT_PRUNE_EVERY_OPS,
         )
+        .with_replay_capacity(DEFAULT_REPLAY_CAPACITY)
     }
 
     pub fn new_with_options(capacity: usize, idle_ttl_seconds: i64, prune_every_ops: u64) -> Self {
+        let capacity = capacity.max(1);
         Self {
-            capacity: capacity.max(1),
+            capacity,
+            replay_capacity: capacity,
             idle_ttl_seconds: idle_ttl_seconds.max(0),
             prune_every_ops: prune_every_ops.max(1),
             ops: AtomicU64::new(0),
@@ -51,26 +103,48 @@ impl RunEventsBus {
         }
     }
 
+    /// Number of recent events kept per run for [`RunEventsBus::subscribe_after`].
+    pub fn with_replay_capacity(mut self, replay_capacity: usize) -> Self {
+        self.replay_capacity = replay_capacity.max(1);
+        self
+    }
+
     pub fn subscribe(&self, run_id: &str) -> broadcast::Receiver<RunEvent> {
-        let now = OffsetDateTime::now_utc().unix_timestamp();
-        let mut inner = match self.inner.lock() {
+        self.with_entry(run_id, |entry| entry.tx.subscribe())
+    }
+
+    /// Subscribes and returns the buffered events after `after_seq` in one step, so no event is
+    /// lost between the replay and the live stream.
+    pub fn subscribe_after(&self, run_id: &str, after_seq: i64) -> RunEventsSubscription {
+        self.with_entry(run_id, |entry| RunEventsSubscription {
+            rx: entry.tx.subscribe(),
+            replay: entry.replay_after(after_seq),
+        })
+    }
+
+    /// Buffered events after `after_seq`, for a subscriber that lagged behind the live stream.
+    /// `None` when the replay window no longer covers them.
+    pub fn replay_after(&self, run_id: &str, after_seq: i64) -> Option<Vec<RunEvent>> {
+        let inner = match self.inner.lock() {
             Ok(guard) => guard,
             Err(poisoned) => poisoned.into_inner(),
         };
-        self.maybe_prune_locked(&mut inner, now);
+        inner.get(run_id)?.replay_after(after_seq)
+    }
 
-        let entry = inner.entry(run_id.to_string()).or_insert_with(|| {
-            let (tx, _) = broadcast::channel(self.capacity);
-            BusEntry {
-                tx,
-                last_used_at: now,
+    pub fn publish(&self, event: &RunEvent) {
+        self.with_entry(&event.run_id, |entry| {
+            if entry.recent.len() >= self.replay_capacity {
+                entry.recent.pop_front();
             }
+            entry.recent.push_back(event.clone());
+            // Never blocks: a subscriber that falls more than `capacity` behind sees `Lagged` and
+            // resumes from the replay buffer (or the database).
+            let _ = entry.tx.send(event.clone());
         });
-        entry.last_used_at = now;
-        entry.tx.subscribe()
     }
 
-    pub fn publish(&self, event: &RunEvent) {
+    fn with_entry<T>(&self, run_id: &str, f: impl FnOnce(&mut BusEntry) -> T) -> T {
         let now = OffsetDateTime::now_utc().unix_timestamp();
         let mut inner = match self.inner.lock() {
             Ok(guard) => guard,
@@ -78,16 +152,11 @@ impl RunEventsBus {
         };
         self.maybe_prune_locked(&mut inner, now);
 
-        let entry = inner.entry(event.run_id.clone()).or_insert_with(|| {
-            let (tx, _) = broadcast::channel(self.capacity);
-            BusEntry {
-                tx,
-                last_used_at: now,
-            }
-        });
-
+        let entry = inner
+            .entry(run_id.to_string())
+            .or_insert_with(|| BusEntry::new(self.capacity, now));
         entry.last_used_at = now;
-        let _ = entry.tx.send(event.clone());
+        f(entry)
     }
 
     fn maybe_prune_locked(&self, inner: &mut HashMap<String, BusEntry>, now: i64) {
@@ -111,6 +180,18 @@ mod tests {
     use super::RunEvent;
     use super::RunEventsBus;
 
+    fn event(seq: i64) -> RunEvent {
+        RunEvent {
+            run_id: "run1".to_string(),
+            seq,
+            ts: 100,
+            level: "info".to_string(),
+            kind: "test".to_string(),
+            message: format!("event {seq}"),
+            fields: None,
+        }
+    }
+
     #[tokio::test]
     async fn publish_delivers_to_subscribers_after_mutex_poisoning() {
         let bus = RunEventsBus::new_with_options(8, 60, 1);
@@ -157,4 +238,46 @@ mod tests {
         assert_eq!(got.seq, 1);
         assert_eq!(got.message, "hello");
     }
+
+    #[tokio::test]
+    async fn subscribe_after_replays_buffered_events() {
+        let bus = RunEventsBus::new_with_options(8, 60, 1).with_replay_capacity(3);
+        for seq in 1..=4 {
+            bus.publish(&event(seq));
+        }
+
+        let mut sub = bus.subscribe_after("run1", 2);
+        let replay = sub.replay.expect("covered by the replay window");
+        assert_eq!(replay.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![3, 4]);
+
+        // Seq 1 aged out of the window, so resuming after 0 needs the database.
+        assert!(bus.subscribe_after("run1", 0).replay.is_none());
+        // Caught up with the newest event.
+        assert_eq!(
+            bus.replay_after("run1", 4).map(|v| v.is_empty()),
+            Some(true)
+        );
+        // Nothing known about this run yet.
+        assert!(bus.subscribe_after("run2", 0).replay.is_none());
+
+        bus.publish(&event(5));
+        assert_eq!(sub.rx.recv().await.expect("recv").seq, 5);
+    }
+
+    #[tokio::test]
+    async fn lagged_subscribers_resume_from_the_replay_buffer() {
+        let bus = RunEventsBus::new_with_options(2, 60, 1).with_replay_capacity(16);
+        let mut rx = bus.subscribe("run1");
+        for seq in 1..=6 {
+            bus.publish(&event(seq));
+        }
+
+        assert!(matches!(
+            rx.recv().await,
+            Err(tokio::sync::broadcast::error::RecvError::Lagged(_))
+        ));
+        let replay = bus.replay_after("run1", 0).expect("covered");
+        assert_eq!(replay.len(), 6);
+        assert_eq!(replay[5].message, "event 6");
+    }
 }
@@ -0,0 +1,33 @@
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use bastion_storage::run_stats_repo;
+
+use crate::supervision::spawn_supervised;
+
+const REFRESH_INTERVAL_SECS: u64 = 15 * 60;
+
+pub fn spawn(db: SqlitePool, shutdown: CancellationToken) {
+    spawn_supervised("run_stats.loop", shutdown.clone(), run_loop(db, shutdown));
+}
+
+async fn run_loop(db: SqlitePool, shutdown: CancellationToken) {
+    loop {
+        if shutdown.is_cancelled() {
+            break;
+        }
+
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        match run_stats_repo::refresh_run_stats(&db, now).await {
+            Ok(jobs) => debug!(jobs, "run stats refreshed"),
+            Err(error) => warn!(error = %error, "run stats refresh failed"),
+        }
+
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(REFRESH_INTERVAL_SECS)) => {}
+        }
+    }
+}
@@ -5,15 +5,14 @@ use sqlx::SqlitePool;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
-use url::Url;
 
 use bastion_core::HUB_NODE_ID;
 use bastion_core::agent_protocol::{HubToAgentMessageV1, PROTOCOL_VERSION, SnapshotDeleteTaskV1};
+use bastion_driver_api::{DriverError, DriverHttpError, TargetStore};
 use bastion_storage::artifact_delete_repo;
 use bastion_storage::run_artifacts_repo;
+use bastion_storage::runs_repo;
 use bastion_storage::secrets::SecretsCrypto;
-use bastion_storage::secrets_repo;
-use bastion_targets::{WebdavClient, WebdavCredentials, WebdavHttpError};
 
 use crate::agent_manager::AgentManager;
 use crate::error_envelope::{
@@ -30,6 +29,8 @@ const RUNNING_TTL_SECS: i64 = 30 * 60;
 const MAX_ATTEMPTS: i64 = 20;
 const MAX_AGE_SECS: i64 = 30 * 24 * 60 * 60;
 
+const RETENTION_LOCK_KIND: &str = "retention_lock";
+
 pub(super) async fn run_artifact_delete_loop(
     db: SqlitePool,
     secrets: Arc<SecretsCrypto>,
@@ -61,9 +62,16 @@ pub(super) async fn run_artifact_delete_loop(
                 retrying = stats.retrying,
                 blocked = stats.blocked,
                 abandoned = stats.abandoned,
+                purged_runs = stats.purged_runs,
                 "artifact delete tick"
             );
         }
+        if !stats.blocked_purges.is_empty() {
+            warn!(
+                run_ids = ?stats.blocked_purges,
+                "run purge blocked by a failed snapshot delete; purge again to retry"
+            );
+        }
 
         let sleep = match compute_sleep(&db, now, &stats).await {
             Ok(v) => v,
@@ -90,12 +98,15 @@ struct TickStats {
     retrying: u64,
     blocked: u64,
     abandoned: u64,
+    purged_runs: u64,
+    /// Purge-requested runs whose snapshot delete gave up; reported when a tick marks a failure.
+    blocked_purges: Vec<String>,
     hit_process_limit: bool,
 }
 
 impl TickStats {
     fn any_activity(&self) -> bool {
-        self.recovered_running > 0 || self.processed > 0
+        self.recovered_running > 0 || self.processed > 0 || self.purged_runs > 0
     }
 }
 
@@ -107,6 +118,13 @@ async fn tick(
 ) -> Result<TickStats, anyhow::Error> {
     let recovered_running = recover_stuck_running(db, now).await?;
     let (pstats, hit_limit) = process_due_tasks(db, secrets, agent_manager, now).await?;
+    // Runs with a purge request go away once their artifacts are confirmed deleted.
+    let purged_runs = runs_repo::purge_requested_runs(db).await?;
+    let blocked_purges = if pstats.blocked > 0 || pstats.abandoned > 0 {
+        runs_repo::list_blocked_run_purges(db).await?
+    } else {
+        Vec::new()
+    };
 
     Ok(TickStats {
         recovered_running,
@@ -116,6 +134,8 @@ async fn tick(
         retrying: pstats.retrying,
         blocked: pstats.blocked,
         abandoned: pstats.abandoned,
+        purged_runs: purged_runs.len() as u64,
+        blocked_purges,
         hit_process_limit: hit_limit,
     })
 }
@@ -230,19 +250,7 @@ async fn process_due_tasks(
 #[derive(Debug, serde::Deserialize)]
 struct RunTargetSnapshot {
     node_id: String,
-    target: RunTarget,
-}
-
-#[derive(Debug, serde::Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-enum RunTarget {
-    Webdav {
-        base_url: String,
-        secret_name: String,
-    },
-    LocalDir {
-        base_dir: String,
-    },
+    target: serde_json::Value,
 }
 
 async fn process_task(
@@ -255,6 +263,16 @@ async fn process_task(
 ) -> Result<(), anyhow::Error> {
     let attempt_started = std::time::Instant::now();
 
+    if let Some(locked_until) = run_artifacts_repo::get_run_artifact(db, &task.run_id)
+        .await?
+        .and_then(|artifact| artifact.locked_until)
+        .filter(|until| *until > now)
+    {
+        block_for_retention_lock(db, task, locked_until, now).await?;
+        stats.blocked = stats.blocked.saturating_add(1);
+        return Ok(());
+    }
+
     let _ = artifact_delete_repo::append_event(
         db,
         &task.run_id,
@@ -268,49 +286,36 @@ async fn process_task(
 
     let parsed = serde_json::from_value::<RunTargetSnapshot>(task.target_snapshot.clone());
     let result = match parsed {
-        Ok(parsed) => match parsed.target {
-            RunTarget::LocalDir { base_dir } => {
-                if parsed.node_id == HUB_NODE_ID {
-                    let base_dir = base_dir.clone();
-                    let job_id = task.job_id.clone();
-                    let run_id = task.run_id.clone();
-                    match tokio::task::spawn_blocking(move || {
-                        delete_local_dir_snapshot(&base_dir, &job_id, &run_id)
-                    })
-                    .await
-                    {
-                        Ok(v) => v,
-                        Err(error) => DeleteResult::Failed {
-                            kind: ErrorKind::Unknown,
-                            error: anyhow::anyhow!("join error: {error}"),
-                        },
-                    }
-                } else {
-                    let msg = HubToAgentMessageV1::SnapshotDeleteTask {
-                        v: PROTOCOL_VERSION,
-                        task: SnapshotDeleteTaskV1 {
-                            run_id: task.run_id.clone(),
-                            job_id: task.job_id.clone(),
-                            base_dir: base_dir.clone(),
-                        },
-                    };
-                    match agent_manager.send_json(&parsed.node_id, &msg).await {
-                        Ok(()) => DeleteResult::Dispatched,
-                        Err(error) => DeleteResult::Failed {
-                            kind: ErrorKind::Network,
-                            error,
-                        },
-                    }
-                }
+        // Agent-local directories are deleted by the Agent that owns them.
+        Ok(parsed) if parsed.node_id != HUB_NODE_ID && parsed.target["type"] == "local_dir" => {
+            let msg = HubToAgentMessageV1::SnapshotDeleteTask {
+                v: PROTOCOL_VERSION,
+                task: SnapshotDeleteTaskV1 {
+                    run_id: task.run_id.clone(),
+                    job_id: task.job_id.clone(),
+                    base_dir: parsed.target["base_dir"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                },
+            };
+            match agent_manager.send_json(&parsed.node_id, &msg).await {
+                Ok(()) => DeleteResult::Dispatched,
+                Err(error) => DeleteResult::Failed {
+                    kind: ErrorKind::Network,
+                    error,
+                },
             }
-            RunTarget::Webdav {
-                base_url,
-                secret_name,
-            } => {
-                delete_webdav_snapshot(db, secrets, &parsed.node_id, &base_url, &secret_name, task)
-                    .await
+        }
+        Ok(parsed) => {
+            match crate::snapshot_target::open(db, secrets, &parsed.node_id, &parsed.target).await {
+                Ok(target) => delete_snapshot(target.as_ref(), &task.job_id, &task.run_id).await,
+                Err(error) => DeleteResult::Failed {
+                    kind: classify_error(&error),
+                    error,
+                },
             }
-        },
+        }
         Err(error) => DeleteResult::Failed {
             kind: ErrorKind::Config,
             error: anyhow::anyhow!("invalid target snapshot: {error}"),
@@ -491,6 +496,58 @@ async fn process_task(
     Ok(())
 }
 
+/// Parks a task until the snapshot's retention lock expires; the lock is not an error, so the
+/// task is retried exactly when it lifts.
+async fn block_for_retention_lock(
+    db: &SqlitePool,
+    task: &artifact_delete_repo::ArtifactDeleteTaskRow,
+    locked_until: i64,
+    now: i64,
+) -> Result<(), anyhow::Error> {
+    let until = time::OffsetDateTime::from_unix_timestamp(locked_until)
+        .ok()
+        .and_then(|t| {
+            t.format(&time::format_description::well_known::Rfc3339)
+                .ok()
+        })
+        .unwrap_or_else(|| locked_until.to_string());
+    let message = format!("blocked by retention lock until {until}");
+
+    artifact_delete_repo::mark_blocked(
+        db,
+        &task.run_id,
+        locked_until,
+        RETENTION_LOCK_KIND,
+        &message,
+        now,
+    )
+    .await?;
+    let _ = artifact_delete_repo::append_event(
+        db,
+        &task.run_id,
+        "warn",
+        "blocked",
+        &message,
+        Some(serde_json::json!({
+            "reason": RETENTION_LOCK_KIND,
+            "locked_until": locked_until,
+            "next_attempt_at": locked_until,
+        })),
+        now,
+    )
+    .await;
+    let _ = run_artifacts_repo::mark_run_artifact_deleting_with_error(
+        db,
+        &task.run_id,
+        RETENTION_LOCK_KIND,
+        &message,
+        now,
+        now,
+    )
+    .await;
+    Ok(())
+}
+
 fn should_abandon(task: &artifact_delete_repo::ArtifactDeleteTaskRow, now: i64) -> bool {
     if task.attempts >= MAX_ATTEMPTS {
         return true;
@@ -606,14 +663,12 @@ fn retriable_for_error_kind(kind: ErrorKind) -> bool {
 fn webdav_http_meta(error: &anyhow::Error) -> Option<(u16, String, Option<u64>)> {
     error
         .chain()
-        .find_map(|cause| cause.downcast_ref::<WebdavHttpError>())
-        .map(|http| {
-            (
-                http.status.as_u16(),
-                http.message.clone(),
-                http.retry_after.map(|value| value.as_secs()),
-            )
-        })
+        .find_map(|cause| http_error(cause))
+        .map(|http| (http.status, http.message.clone(), http.retry_after_secs))
+}
+
+fn http_error<'a>(cause: &'a (dyn std::error::Error + 'static)) -> Option<&'a DriverHttpError> {
+    cause.downcast_ref::<DriverError>()?.http.as_ref()
 }
 
 fn transport_for_delete_error(
@@ -720,105 +775,51 @@ enum DeleteResult {
     },
 }
 
-fn delete_local_dir_snapshot(base_dir: &str, job_id: &str, run_id: &str) -> DeleteResult {
-    use bastion_backup::{COMPLETE_NAME, ENTRIES_INDEX_NAME, MANIFEST_NAME};
-
-    let run_dir = std::path::Path::new(base_dir).join(job_id).join(run_id);
-    if !run_dir.exists() {
-        return DeleteResult::NotFound {
-            message: "local snapshot dir missing; nothing to delete",
-        };
-    }
-
-    let mut looks_like_bastion = false;
-    if run_dir.join(COMPLETE_NAME).exists()
-        || run_dir.join(MANIFEST_NAME).exists()
-        || run_dir.join(ENTRIES_INDEX_NAME).exists()
-    {
-        looks_like_bastion = true;
-    } else if let Ok(entries) = std::fs::read_dir(&run_dir) {
-        for entry in entries.flatten() {
-            let name = entry.file_name();
-            let name = name.to_string_lossy();
-            if name.starts_with("payload.part") || name.ends_with(".partial") {
-                looks_like_bastion = true;
-                break;
-            }
+async fn delete_snapshot(target: &dyn TargetStore, job_id: &str, run_id: &str) -> DeleteResult {
+    let run_dir = crate::snapshot_target::run_dir_path(job_id, run_id);
+    let deletable = match target.stat(&run_dir).await {
+        Ok(None) => {
+            return DeleteResult::NotFound {
+                message: "snapshot dir missing; nothing to delete",
+            };
+        }
+        Ok(Some(_)) => crate::snapshot_target::is_deletable_run_dir(target, &run_dir).await,
+        Err(error) => Err(anyhow::Error::new(error)),
+    };
+    match deletable {
+        Ok(true) => {}
+        Ok(false) => {
+            return DeleteResult::Failed {
+                kind: ErrorKind::Config,
+                error: anyhow::anyhow!("snapshot dir did not look like bastion data"),
+            };
+        }
+        Err(error) => {
+            return DeleteResult::Failed {
+                kind: classify_error(&error),
+                error,
+            };
         }
     }
-    if !looks_like_bastion {
-        return DeleteResult::Failed {
-            kind: ErrorKind::Config,
-            error: anyhow::anyhow!("local snapshot dir did not look like bastion data"),
-        };
-    }
-
-    match std::fs::remove_dir_all(&run_dir) {
-        Ok(()) => DeleteResult::Deleted,
-        Err(error) => DeleteResult::Failed {
-            kind: ErrorKind::Unknown,
-            error: anyhow::Error::from(error),
-        },
-    }
-}
 
-async fn delete_webdav_snapshot(
-    db: &SqlitePool,
-    secrets: &SecretsCrypto,
-    node_id: &str,
-    base_url: &str,
-    secret_name: &str,
-    task: &artifact_delete_repo::ArtifactDeleteTaskRow,
-) -> DeleteResult {
-    match delete_webdav_snapshot_inner(db, secrets, node_id, base_url, secret_name, task).await {
-        Ok(v) => v,
-        Err(error) => DeleteResult::Failed {
-            kind: classify_error(&error),
-            error,
+    match target.delete(&run_dir).await {
+        Ok(true) => DeleteResult::Deleted,
+        Ok(false) => DeleteResult::NotFound {
+            message: "snapshot dir missing; nothing to delete",
         },
-    }
-}
-
-async fn delete_webdav_snapshot_inner(
-    db: &SqlitePool,
-    secrets: &SecretsCrypto,
-    node_id: &str,
-    base_url: &str,
-    secret_name: &str,
-    task: &artifact_delete_repo::ArtifactDeleteTaskRow,
-) -> Result<DeleteResult, anyhow::Error> {
-    let cred_bytes = secrets_repo::get_secret(db, secrets, node_id, "webdav", secret_name)
-        .await?
-        .ok_or_else(|| anyhow::anyhow!("missing webdav secret: {secret_name}"))?;
-    let credentials = WebdavCredentials::from_json(&cred_bytes)?;
-
-    let mut base_url = Url::parse(base_url)?;
-    if !base_url.path().ends_with('/') {
-        base_url.set_path(&format!("{}/", base_url.path()));
-    }
-
-    let client = WebdavClient::new(base_url.clone(), credentials)?;
-    let job_url = base_url.join(&format!("{}/", task.job_id))?;
-    let run_url = job_url.join(&format!("{}/", task.run_id))?;
-
-    match client.delete(&run_url).await {
-        Ok(true) => Ok(DeleteResult::Deleted),
-        Ok(false) => Ok(DeleteResult::NotFound {
-            message: "remote snapshot dir missing; nothing to delete",
-        }),
-        Err(error) => Ok(DeleteResult::Failed {
-            kind: classify_error(&error),
-            error,
-        }),
+        Err(error) => {
+            let error = anyhow::Error::new(error);
+            DeleteResult::Failed {
+                kind: classify_error(&error),
+                error,
+            }
+        }
     }
 }
 
 fn classify_error(error: &anyhow::Error) -> ErrorKind {
-    if let Some(http) = error
-        .chain()
-        .find_map(|cause| cause.downcast_ref::<WebdavHttpError>())
-    {
-        return match http.status.as_u16() {
+    if let Some(http) = error.chain().find_map(|cause| http_error(cause)) {
+        return match http.status {
             401 | 403 => ErrorKind::Auth,
             408 | 429 | 502 | 503 | 504 => ErrorKind::Network,
             500..=599 => ErrorKind::Network,
@@ -876,8 +877,9 @@ mod tests {
     use tempfile::TempDir;
 
     use super::{
-        ErrorKind, backoff_seconds, build_failed_event_fields, classify_error,
-        delete_local_dir_snapshot, hint_for_error_kind, sanitize_error_string,
+        ErrorKind, RETENTION_LOCK_KIND, backoff_seconds, block_for_retention_lock,
+        build_failed_event_fields, classify_error, delete_snapshot, hint_for_error_kind,
+        sanitize_error_string,
     };
 
     fn dummy_task(
@@ -895,6 +897,74 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn retention_lock_parks_task_until_lock_expires() {
+        use bastion_storage::{db, jobs_repo, runs_repo};
+
+        let tmp = TempDir::new().expect("tmp");
+        let pool = db::init(tmp.path()).await.expect("db init");
+        let job = jobs_repo::create_job(
+            &pool,
+            "job",
+            None,
+            None,
+            Some("UTC"),
+            jobs_repo::OverlapPolicy::Queue,
+            serde_json::json!({
+                "v": 1,
+                "type": "filesystem",
+                "source": { "root": "/" },
+                "target": { "type": "local_dir", "base_dir": "/tmp" }
+            }),
+        )
+        .await
+        .expect("job");
+        let run = runs_repo::create_run(
+            &pool,
+            &job.id,
+            runs_repo::RunStatus::Success,
+            1,
+            Some(2),
+            None,
+            None,
+        )
+        .await
+        .expect("run");
+
+        let now = 1_000;
+        artifact_delete_repo::upsert_task_if_missing(
+            &pool,
+            &run.id,
+            &job.id,
+            "hub",
+            "local_dir",
+            "{}",
+            now,
+        )
+        .await
+        .expect("task");
+
+        let mut task = dummy_task(artifact_delete_repo::DeleteTargetType::LocalDir, "hub");
+        task.run_id = run.id.clone();
+        task.job_id = job.id.clone();
+        block_for_retention_lock(&pool, &task, now + 3_600, now)
+            .await
+            .expect("block");
+
+        let got = artifact_delete_repo::get_task(&pool, &run.id)
+            .await
+            .expect("get")
+            .expect("task");
+        assert_eq!(got.status, "blocked");
+        assert_eq!(got.next_attempt_at, now + 3_600);
+        assert_eq!(got.last_error_kind.as_deref(), Some(RETENTION_LOCK_KIND));
+        assert!(
+            got.last_error
+                .as_deref()
+                .is_some_and(|e| e.starts_with("blocked by retention lock until "))
+        );
+    }
+
     #[test]
     fn sanitize_error_string_trims_and_single_lines() {
         let s = " hello\nworld\r\n ";
@@ -909,24 +979,31 @@ mod tests {
         assert!(a2 >= a1);
     }
 
-    #[test]
-    fn local_delete_requires_bastion_markers() {
+    #[tokio::test]
+    async fn local_delete_requires_bastion_markers() {
         let tmp = TempDir::new().expect("tmp");
         let base = tmp.path();
         let job_id = "job";
         let run_id = "run";
         let dir = base.join(job_id).join(run_id);
         std::fs::create_dir_all(&dir).expect("mkdir");
+        let registry = bastion_driver_registry::builtins::target_registry();
+        let target = registry
+            .open_store(
+                &bastion_driver_registry::builtins::local_dir_driver_id(),
+                &serde_json::json!({ "base_dir": base.display().to_string() }),
+            )
+            .expect("open local_dir store");
 
         // Not a bastion dir -> config failure
-        match delete_local_dir_snapshot(base.to_str().unwrap(), job_id, run_id) {
+        match delete_snapshot(target.as_ref(), job_id, run_id).await {
             super::DeleteResult::Failed { kind, .. } => assert!(matches!(kind, ErrorKind::Config)),
             other => panic!("unexpected result: {other:?}"),
         }
 
         // Add marker -> delete succeeds
         std::fs::write(dir.join(bastion_backup::COMPLETE_NAME), b"{}").expect("write");
-        match delete_local_dir_snapshot(base.to_str().unwrap(), job_id, run_id) {
+        match delete_snapshot(target.as_ref(), job_id, run_id).await {
             super::DeleteResult::Deleted => {}
             other => panic!("unexpected result: {other:?}"),
         }
@@ -11,7 +11,7 @@ use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, warn};
 
-use bastion_storage::jobs_repo;
+use bastion_storage::{hub_runtime_config_repo, jobs_repo};
 
 use crate::agent_manager::AgentManager;
 use crate::run_events_bus::RunEventsBus;
@@ -38,6 +38,24 @@ pub(super) fn validate_cron(expr: &str) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+/// Returns the next `count` fire times strictly after `after`, matching what the scheduler enqueues
+/// (DST folds fire once).
+pub(super) fn next_fire_times(
+    expr: &str,
+    tz: Tz,
+    after: DateTime<Utc>,
+    count: usize,
+) -> Result<Vec<DateTime<Utc>>, anyhow::Error> {
+    let schedule = Schedule::from_str(&normalize_cron(expr)?)?;
+    let after_local = tz.from_utc_datetime(&after.naive_utc());
+    Ok(schedule
+        .after(&after_local)
+        .filter(|candidate| allow_due_for_local_minute(tz, *candidate))
+        .take(count)
+        .map(|candidate| candidate.with_timezone(&Utc))
+        .collect())
+}
+
 fn allow_due_for_local_minute(tz: Tz, local_minute_start: DateTime<Tz>) -> bool {
     // DST fold: local wall time occurs twice. Run once by choosing the first occurrence (earlier offset).
     match tz.from_local_datetime(&local_minute_start.naive_local()) {
@@ -118,6 +136,7 @@ pub(super) async fn run_cron_loop(
         if should_evaluate_due {
             if minute != last_minute {
                 last_minute = minute;
+                let stagger_seconds = load_stagger_seconds(&db).await;
                 for job in &jobs {
                     let Some(expr) = job.schedule.as_deref() else {
                         continue;
@@ -153,6 +172,25 @@ pub(super) async fn run_cron_loop(
                     match cron_matches_minute_cached(expr, local_minute_start, &mut schedule_cache)
                     {
                         Ok(true) => {
+                            let delay = stagger_offset_seconds(&job.id, stagger_seconds)
+                                + random_jitter_seconds(job.schedule_jitter_seconds);
+                            if delay > 0 {
+                                debug!(
+                                    job_id = %job.id,
+                                    delay_seconds = delay,
+                                    "cron due; enqueue delayed by stagger/jitter"
+                                );
+                                spawn_delayed_enqueue(
+                                    db.clone(),
+                                    run_events_bus.clone(),
+                                    run_queue_notify.clone(),
+                                    job.id.clone(),
+                                    std::time::Duration::from_secs(delay),
+                                    shutdown.clone(),
+                                );
+                                continue;
+                            }
+
                             debug!(job_id = %job.id, "cron due; enqueue run");
                             if let Err(error) = enqueue_run(
                                 &db,
@@ -211,6 +249,73 @@ pub(super) async fn run_cron_loop(
     }
 }
 
+async fn load_stagger_seconds(db: &SqlitePool) -> u32 {
+    match hub_runtime_config_repo::get(db).await {
+        Ok(saved) => saved.and_then(|c| c.schedule_stagger_seconds).unwrap_or(0),
+        Err(error) => {
+            warn!(error = %error, "failed to load schedule stagger window; not staggering");
+            0
+        }
+    }
+}
+
+/// Stable offset in `0..window_seconds` so jobs sharing a cron expression fire spread out, but
+/// each job keeps the same slot across restarts.
+fn stagger_offset_seconds(job_id: &str, window_seconds: u32) -> u64 {
+    if window_seconds == 0 {
+        return 0;
+    }
+    // FNV-1a; unlike std's DefaultHasher it is stable across Rust releases.
+    let hash = job_id.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    hash % u64::from(window_seconds)
+}
+
+fn random_jitter_seconds(max_seconds: u32) -> u64 {
+    if max_seconds == 0 {
+        return 0;
+    }
+    (uuid::Uuid::new_v4().as_u128() % (u128::from(max_seconds) + 1)) as u64
+}
+
+fn spawn_delayed_enqueue(
+    db: SqlitePool,
+    run_events_bus: Arc<RunEventsBus>,
+    run_queue_notify: Arc<Notify>,
+    job_id: String,
+    delay: std::time::Duration,
+    shutdown: CancellationToken,
+) {
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = shutdown.cancelled() => return,
+            _ = tokio::time::sleep(delay) => {}
+        }
+
+        // The job may have been unscheduled, archived or deleted while waiting.
+        let job = match jobs_repo::get_job(&db, &job_id).await {
+            Ok(Some(job)) if job.archived_at.is_none() && job.schedule.is_some() => job,
+            Ok(_) => return,
+            Err(error) => {
+                warn!(job_id = %job_id, error = %error, "failed to load job for delayed schedule");
+                return;
+            }
+        };
+        if let Err(error) = enqueue_run(
+            &db,
+            run_events_bus.as_ref(),
+            run_queue_notify.as_ref(),
+            &job,
+            "schedule",
+        )
+        .await
+        {
+            warn!(job_id = %job_id, error = %error, "failed to enqueue scheduled run");
+        }
+    });
+}
+
 fn cron_matches_minute_cached<Tz1: chrono::TimeZone>(
     expr: &str,
     minute_start: DateTime<Tz1>,
@@ -282,7 +387,10 @@ mod tests {
     use chrono::{NaiveDate, TimeZone as _};
     use chrono_tz::America::New_York;
 
-    use super::{allow_due_for_local_minute, normalize_cron};
+    use super::{
+        allow_due_for_local_minute, next_fire_times, normalize_cron, random_jitter_seconds,
+        stagger_offset_seconds,
+    };
 
     #[test]
     fn normalize_cron_rejects_nonzero_seconds() {
@@ -306,4 +414,47 @@ mod tests {
             other => panic!("expected ambiguous local time, got: {other:?}"),
         }
     }
+
+    #[test]
+    fn next_fire_times_follow_timezone_and_skip_dst_fold_repeat() {
+        let after = chrono::Utc
+            .with_ymd_and_hms(2026, 10, 31, 12, 0, 0)
+            .unwrap();
+        let times = next_fire_times("30 1 * * *", New_York, after, 3).unwrap();
+        let local = times
+            .iter()
+            .map(|t| t.with_timezone(&New_York).to_rfc3339())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            local,
+            vec![
+                "2026-11-01T01:30:00-04:00",
+                "2026-11-02T01:30:00-05:00",
+                "2026-11-03T01:30:00-05:00",
+            ]
+        );
+
+        assert!(next_fire_times("not a cron", New_York, after, 3).is_err());
+    }
+
+    #[test]
+    fn stagger_offset_is_stable_and_within_window() {
+        assert_eq!(stagger_offset_seconds("job-a", 0), 0);
+        let a = stagger_offset_seconds("job-a", 600);
+        assert!(a < 600);
+        assert_eq!(a, stagger_offset_seconds("job-a", 600));
+
+        let offsets = (0..50)
+            .map(|i| stagger_offset_seconds(&format!("job-{i}"), 600))
+            .collect::<std::collections::HashSet<_>>();
+        assert!(offsets.len() > 10, "offsets should spread: {offsets:?}");
+    }
+
+    #[test]
+    fn random_jitter_stays_within_bound() {
+        assert_eq!(random_jitter_seconds(0), 0);
+        for _ in 0..100 {
+            assert!(random_jitter_seconds(30) <= 30);
+        }
+    }
 }
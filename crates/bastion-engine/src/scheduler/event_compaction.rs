@@ -0,0 +1,205 @@
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use bastion_storage::hub_runtime_config_repo;
+use bastion_storage::runs_repo;
+
+const LOOP_INTERVAL_SECS: u64 = 60 * 60; // hourly
+/// Finished runs are left alone for a while so late events (for example from a reconnecting
+/// Agent) are compacted together with the rest.
+const COMPACT_AFTER_SECS: i64 = 60 * 60;
+const BATCH_RUNS: u32 = 100;
+/// Upper bound on runs compacted (and runs pruned) per tick; the backlog continues next tick.
+const MAX_RUNS_PER_TICK: u64 = 5_000;
+
+#[derive(Debug, Default)]
+struct TickStats {
+    runs_compacted: u64,
+    progress_removed: u64,
+    truncated: u64,
+    runs_pruned: u64,
+    events_pruned: u64,
+}
+
+impl TickStats {
+    fn any_activity(&self) -> bool {
+        self.progress_removed > 0 || self.truncated > 0 || self.events_pruned > 0
+    }
+}
+
+pub(super) async fn run_event_compaction_loop(db: SqlitePool, shutdown: CancellationToken) {
+    loop {
+        if shutdown.is_cancelled() {
+            break;
+        }
+
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+
+        match tick(&db, now).await {
+            Ok(stats) => {
+                if stats.any_activity() {
+                    info!(
+                        runs_compacted = stats.runs_compacted,
+                        progress_removed = stats.progress_removed,
+                        truncated = stats.truncated,
+                        runs_pruned = stats.runs_pruned,
+                        events_pruned = stats.events_pruned,
+                        "run event compaction tick"
+                    );
+                }
+            }
+            Err(error) => {
+                warn!(error = %error, "run event compaction tick failed");
+            }
+        }
+
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(LOOP_INTERVAL_SECS)) => {}
+        }
+    }
+}
+
+async fn tick(db: &SqlitePool, now: i64) -> Result<TickStats, anyhow::Error> {
+    let mut stats = TickStats::default();
+
+    // Read on every tick so runtime config changes apply without a restart.
+    let config = hub_runtime_config_repo::get(db).await?.unwrap_or_default();
+    let max_events = config
+        .max_events_per_run
+        .unwrap_or(runs_repo::DEFAULT_MAX_EVENTS_PER_RUN);
+
+    let compact_before = now.saturating_sub(COMPACT_AFTER_SECS);
+    while stats.runs_compacted < MAX_RUNS_PER_TICK {
+        let run_ids =
+            runs_repo::list_runs_pending_event_compaction(db, compact_before, BATCH_RUNS).await?;
+        if run_ids.is_empty() {
+            break;
+        }
+        for run_id in &run_ids {
+            let compaction = runs_repo::compact_run_events(db, run_id, max_events, now).await?;
+            stats.runs_compacted += 1;
+            stats.progress_removed += compaction.progress_removed;
+            stats.truncated += compaction.truncated;
+        }
+    }
+
+    if let Some(days) = config.event_retention_days.filter(|d| *d > 0) {
+        let cutoff = now.saturating_sub(i64::from(days).saturating_mul(24 * 60 * 60));
+        while stats.runs_pruned < MAX_RUNS_PER_TICK {
+            let (runs, events) =
+                runs_repo::prune_run_events_ended_before(db, cutoff, now, BATCH_RUNS).await?;
+            stats.runs_pruned += runs;
+            stats.events_pruned += events;
+            if runs < u64::from(BATCH_RUNS) {
+                break;
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use bastion_storage::runs_repo::{self, RunStatus};
+    use bastion_storage::{db, hub_runtime_config_repo, jobs_repo};
+
+    use super::{COMPACT_AFTER_SECS, tick};
+
+    #[tokio::test]
+    async fn tick_compacts_finished_runs_and_applies_event_retention() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "job",
+            None,
+            None,
+            Some("UTC"),
+            jobs_repo::OverlapPolicy::Queue,
+            serde_json::json!({"v": 1, "type": "filesystem"}),
+        )
+        .await
+        .unwrap();
+
+        let now = 1_706_659_200;
+        let day = 24 * 60 * 60;
+        let old = runs_repo::create_run(
+            &pool,
+            &job.id,
+            RunStatus::Success,
+            now - 40 * day,
+            Some(now - 40 * day),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        // Finished too recently to be compacted.
+        let recent = runs_repo::create_run(
+            &pool,
+            &job.id,
+            RunStatus::Success,
+            now - 60,
+            Some(now - COMPACT_AFTER_SECS / 2),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        for run_id in [&old.id, &recent.id] {
+            for _ in 0..3 {
+                runs_repo::append_run_event(
+                    &pool,
+                    run_id,
+                    "info",
+                    "progress_snapshot",
+                    "upload",
+                    None,
+                )
+                .await
+                .unwrap();
+            }
+        }
+
+        let stats = tick(&pool, now).await.unwrap();
+        assert_eq!(stats.runs_compacted, 1);
+        assert_eq!(stats.progress_removed, 2);
+        assert_eq!(stats.events_pruned, 0);
+        assert_eq!(
+            runs_repo::list_run_events(&pool, &recent.id, 10)
+                .await
+                .unwrap()
+                .len(),
+            3
+        );
+
+        hub_runtime_config_repo::upsert(
+            &pool,
+            &hub_runtime_config_repo::HubRuntimeConfig {
+                event_retention_days: Some(30),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let stats = tick(&pool, now).await.unwrap();
+        assert_eq!(stats.runs_compacted, 0);
+        assert_eq!(stats.runs_pruned, 1);
+        assert_eq!(stats.events_pruned, 1);
+        assert!(
+            runs_repo::list_run_events(&pool, &old.id, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(runs_repo::get_run(&pool, &old.id).await.unwrap().is_some());
+    }
+}
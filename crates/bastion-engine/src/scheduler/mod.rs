@@ -4,14 +4,17 @@ use sqlx::SqlitePool;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 
+use bastion_backup::staging::StagingArea;
 use bastion_storage::secrets::SecretsCrypto;
 
 use crate::agent_manager::AgentManager;
+use crate::drain::ShutdownDrain;
 use crate::run_events_bus::RunEventsBus;
 use crate::supervision::spawn_supervised;
 
 mod artifact_delete;
 mod cron;
+mod event_compaction;
 mod incomplete_cleanup;
 mod queue;
 mod retention;
@@ -19,9 +22,11 @@ mod snapshot_retention;
 mod target_snapshot;
 mod worker;
 
+pub use target_snapshot::build_run_target_snapshot;
+
 pub struct SchedulerArgs {
     pub db: SqlitePool,
-    pub data_dir: std::path::PathBuf,
+    pub staging: StagingArea,
     pub secrets: Arc<SecretsCrypto>,
     pub agent_manager: AgentManager,
     pub run_retention_days: i64,
@@ -32,13 +37,14 @@ pub struct SchedulerArgs {
     pub artifact_delete_notify: Arc<Notify>,
     pub jobs_notify: Arc<Notify>,
     pub notifications_notify: Arc<Notify>,
+    pub drain: ShutdownDrain,
     pub shutdown: CancellationToken,
 }
 
 pub fn spawn(args: SchedulerArgs) {
     let SchedulerArgs {
         db,
-        data_dir,
+        staging,
         secrets,
         agent_manager,
         run_retention_days,
@@ -49,6 +55,7 @@ pub fn spawn(args: SchedulerArgs) {
         artifact_delete_notify,
         jobs_notify,
         notifications_notify,
+        drain,
         shutdown,
     } = args;
 
@@ -72,12 +79,13 @@ pub fn spawn(args: SchedulerArgs) {
         shutdown.clone(),
         worker::run_worker_loop(worker::WorkerLoopArgs {
             db: db.clone(),
-            data_dir,
+            staging,
             secrets: secrets.clone(),
             agent_manager: agent_manager_worker,
             run_events_bus: run_events_bus.clone(),
             run_queue_notify: run_queue_notify.clone(),
             notifications_notify: notifications_notify.clone(),
+            drain,
             shutdown: shutdown.clone(),
         }),
     );
@@ -88,6 +96,12 @@ pub fn spawn(args: SchedulerArgs) {
         retention::run_retention_loop(db.clone(), run_retention_days, shutdown.clone()),
     );
 
+    spawn_supervised(
+        "scheduler.event_compaction_loop",
+        shutdown.clone(),
+        event_compaction::run_event_compaction_loop(db.clone(), shutdown.clone()),
+    );
+
     spawn_supervised(
         "scheduler.snapshot_retention_loop",
         shutdown.clone(),
@@ -129,6 +143,20 @@ pub fn validate_cron(expr: &str) -> Result<(), anyhow::Error> {
     cron::validate_cron(expr)
 }
 
+/// Next `count` times (after `after`) at which the scheduler would enqueue a run for `expr`
+/// evaluated in `timezone`.
+pub fn preview_cron(
+    expr: &str,
+    timezone: &str,
+    after: chrono::DateTime<chrono::Utc>,
+    count: usize,
+) -> Result<Vec<chrono::DateTime<chrono::Utc>>, anyhow::Error> {
+    let tz = timezone
+        .parse::<chrono_tz::Tz>()
+        .map_err(|_| anyhow::anyhow!("invalid timezone: {timezone}"))?;
+    cron::next_fire_times(expr, tz, after, count)
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::TempDir;
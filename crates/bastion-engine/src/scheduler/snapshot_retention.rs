@@ -9,11 +9,14 @@ use tracing::{info, warn};
 use bastion_core::backup_retention::{RetentionSnapshot, select_retention};
 use bastion_core::job_spec;
 use bastion_storage::artifact_delete_repo;
+use bastion_storage::hub_runtime_config_repo;
 use bastion_storage::jobs_repo;
-use bastion_storage::run_artifacts_repo;
+use bastion_storage::run_artifacts_repo::{self, RunArtifact};
 
 const LOOP_INTERVAL_SECS: u64 = 60 * 60; // hourly
 const RETENTION_SCAN_LIMIT: u64 = 20_000;
+/// Upper bound on Hub-wide artifact retention deletes queued per tick.
+const ARTIFACT_RETENTION_MAX_PER_TICK: u64 = 500;
 
 fn day_start_utc(ts: i64) -> i64 {
     ts.saturating_div(24 * 60 * 60).saturating_mul(24 * 60 * 60)
@@ -25,11 +28,12 @@ struct TickStats {
     jobs_enabled: u64,
     enqueued: u64,
     skipped_due_to_limits: u64,
+    artifact_retention_enqueued: u64,
 }
 
 impl TickStats {
     fn any_activity(&self) -> bool {
-        self.enqueued > 0 || self.skipped_due_to_limits > 0
+        self.enqueued > 0 || self.skipped_due_to_limits > 0 || self.artifact_retention_enqueued > 0
     }
 }
 
@@ -53,6 +57,7 @@ pub(super) async fn run_snapshot_retention_loop(
                         jobs_enabled = stats.jobs_enabled,
                         enqueued = stats.enqueued,
                         skipped_due_to_limits = stats.skipped_due_to_limits,
+                        artifact_retention_enqueued = stats.artifact_retention_enqueued,
                         "snapshot retention tick"
                     );
                 }
@@ -104,6 +109,7 @@ async fn tick(db: &SqlitePool, notify: &Notify, now: i64) -> Result<TickStats, a
                 run_id: r.run_id.clone(),
                 ended_at: r.ended_at,
                 pinned: r.pinned_at.is_some(),
+                locked_until: r.locked_until,
             })
             .collect::<Vec<_>>();
 
@@ -146,39 +152,15 @@ async fn tick(db: &SqlitePool, notify: &Notify, now: i64) -> Result<TickStats, a
                 continue;
             }
 
-            let snapshot_json = serde_json::to_string(&artifact.target_snapshot)?;
-
-            let inserted = artifact_delete_repo::upsert_task_if_missing(
-                db,
-                &artifact.run_id,
-                &artifact.job_id,
-                &artifact.node_id,
-                &artifact.target_type,
-                &snapshot_json,
-                now,
-            )
-            .await?;
-
-            if inserted {
+            let fields = serde_json::json!({
+                "job_id": job.id,
+                "keep_last": retention.keep_last,
+                "keep_days": retention.keep_days
+            });
+            if enqueue_delete(db, &artifact, "retention_queued", fields, now).await? {
                 any_enqueued = true;
                 stats.enqueued = stats.enqueued.saturating_add(1);
-                let _ = artifact_delete_repo::append_event(
-                    db,
-                    &artifact.run_id,
-                    "info",
-                    "retention_queued",
-                    "retention delete queued",
-                    Some(serde_json::json!({
-                        "job_id": job.id,
-                        "keep_last": retention.keep_last,
-                        "keep_days": retention.keep_days
-                    })),
-                    now,
-                )
-                .await;
             }
-
-            let _ = run_artifacts_repo::mark_run_artifact_deleting(db, &artifact.run_id, now).await;
         }
 
         if any_enqueued {
@@ -186,16 +168,95 @@ async fn tick(db: &SqlitePool, notify: &Notify, now: i64) -> Result<TickStats, a
         }
     }
 
+    stats.artifact_retention_enqueued = artifact_retention_tick(db, now).await?;
+    if stats.artifact_retention_enqueued > 0 {
+        notify.notify_one();
+    }
+
     Ok(stats)
 }
 
+/// Hub-wide artifact retention: deletes stored snapshot data `artifact_retention_days` after the
+/// run ended, independent of per-job keep rules. Run records stay until run retention prunes them.
+///
+/// Uses its own event kind so it does not eat into the per-job `max_delete_per_day` budget.
+async fn artifact_retention_tick(db: &SqlitePool, now: i64) -> Result<u64, anyhow::Error> {
+    let days = hub_runtime_config_repo::get(db)
+        .await?
+        .and_then(|c| c.artifact_retention_days)
+        .filter(|d| *d > 0);
+    let Some(days) = days else {
+        return Ok(0);
+    };
+
+    let cutoff = now.saturating_sub(i64::from(days).saturating_mul(24 * 60 * 60));
+    let expired = run_artifacts_repo::list_expired_artifacts(
+        db,
+        cutoff,
+        now,
+        ARTIFACT_RETENTION_MAX_PER_TICK,
+    )
+    .await?;
+
+    let mut enqueued = 0_u64;
+    for artifact in expired {
+        let fields = serde_json::json!({
+            "job_id": artifact.job_id,
+            "artifact_retention_days": days
+        });
+        if enqueue_delete(db, &artifact, "artifact_retention_queued", fields, now).await? {
+            enqueued = enqueued.saturating_add(1);
+        }
+    }
+    Ok(enqueued)
+}
+
+/// Queues a delete task for a snapshot and marks it as deleting. Returns whether a new task was
+/// created.
+async fn enqueue_delete(
+    db: &SqlitePool,
+    artifact: &RunArtifact,
+    event_kind: &str,
+    fields: serde_json::Value,
+    now: i64,
+) -> Result<bool, anyhow::Error> {
+    let snapshot_json = serde_json::to_string(&artifact.target_snapshot)?;
+
+    let inserted = artifact_delete_repo::upsert_task_if_missing(
+        db,
+        &artifact.run_id,
+        &artifact.job_id,
+        &artifact.node_id,
+        &artifact.target_type,
+        &snapshot_json,
+        now,
+    )
+    .await?;
+
+    if inserted {
+        let _ = artifact_delete_repo::append_event(
+            db,
+            &artifact.run_id,
+            "info",
+            event_kind,
+            "retention delete queued",
+            Some(fields),
+            now,
+        )
+        .await;
+    }
+
+    let _ = run_artifacts_repo::mark_run_artifact_deleting(db, &artifact.run_id, now).await;
+    Ok(inserted)
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::TempDir;
 
-    use bastion_storage::db;
     use bastion_storage::jobs_repo::{self, OverlapPolicy};
     use bastion_storage::runs_repo::{self, RunStatus};
+    use bastion_storage::{db, hub_runtime_config_repo, run_artifacts_repo};
     use sqlx::Row;
 
     use super::tick;
@@ -382,4 +443,105 @@ mod tests {
         let cnt = rows.get::<i64, _>("cnt");
         assert_eq!(cnt, 1);
     }
+
+    #[tokio::test]
+    async fn tick_applies_hub_artifact_retention_to_jobs_without_keep_rules() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "job",
+            None,
+            None,
+            Some("UTC"),
+            OverlapPolicy::Queue,
+            serde_json::json!({
+              "v": 1,
+              "type": "filesystem",
+              "source": { "paths": ["/tmp"] },
+              "target": { "type": "local_dir", "base_dir": "/tmp" }
+            }),
+        )
+        .await
+        .unwrap();
+
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let day = 24 * 60 * 60;
+
+        let run_recent =
+            runs_repo::create_run(&pool, &job.id, RunStatus::Success, 1, None, None, None)
+                .await
+                .unwrap();
+        let run_expired =
+            runs_repo::create_run(&pool, &job.id, RunStatus::Success, 1, None, None, None)
+                .await
+                .unwrap();
+        let run_locked =
+            runs_repo::create_run(&pool, &job.id, RunStatus::Success, 1, None, None, None)
+                .await
+                .unwrap();
+
+        for (run_id, ended_at, locked_until) in [
+            (&run_recent.id, now - day, None),
+            (&run_expired.id, now - 10 * day, None),
+            (&run_locked.id, now - 10 * day, Some(now + day)),
+        ] {
+            sqlx::query(
+                r#"
+                INSERT INTO run_artifacts (
+                  run_id, job_id, node_id, target_type, target_snapshot_json,
+                  artifact_format, status, started_at, ended_at, locked_until,
+                  created_at, updated_at
+                ) VALUES (?, ?, 'hub', 'local_dir', ?, 'archive_v1', 'present', ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(run_id)
+            .bind(&job.id)
+            .bind(serde_json::json!({ "node_id": "hub", "target": { "type": "local_dir", "base_dir": "/tmp" } }).to_string())
+            .bind(ended_at - 100)
+            .bind(ended_at)
+            .bind(locked_until)
+            .bind(now)
+            .bind(now)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let notify = tokio::sync::Notify::new();
+        let stats = tick(&pool, &notify, now).await.unwrap();
+        assert_eq!(stats.artifact_retention_enqueued, 0);
+
+        hub_runtime_config_repo::upsert(
+            &pool,
+            &hub_runtime_config_repo::HubRuntimeConfig {
+                artifact_retention_days: Some(7),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let stats = tick(&pool, &notify, now).await.unwrap();
+        assert_eq!(stats.enqueued, 0);
+        assert_eq!(stats.artifact_retention_enqueued, 1);
+
+        let expired = run_artifacts_repo::get_run_artifact(&pool, &run_expired.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(expired.status, "deleting");
+        for run_id in [&run_recent.id, &run_locked.id] {
+            let kept = run_artifacts_repo::get_run_artifact(&pool, run_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(kept.status, "present");
+        }
+
+        // Idempotent: the next tick does not queue it again.
+        let stats = tick(&pool, &notify, now).await.unwrap();
+        assert_eq!(stats.artifact_retention_enqueued, 0);
+    }
 }
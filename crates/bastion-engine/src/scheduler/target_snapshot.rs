@@ -2,7 +2,8 @@ use bastion_core::job_spec;
 use bastion_driver_registry::builtins;
 use bastion_driver_registry::target_runtime;
 
-pub(super) fn build_run_target_snapshot(
+/// Redacted `{ node_id, target }` snapshot recorded on a run for later restores and cleanup.
+pub fn build_run_target_snapshot(
     node_id: &str,
     spec: &job_spec::JobSpecV1,
 ) -> Result<serde_json::Value, anyhow::Error> {
@@ -23,6 +24,12 @@ fn extract_target(spec: &job_spec::JobSpecV1) -> &job_spec::TargetV1 {
         job_spec::JobSpecV1::Filesystem { target, .. } => target,
         job_spec::JobSpecV1::Sqlite { target, .. } => target,
         job_spec::JobSpecV1::Vaultwarden { target, .. } => target,
+        job_spec::JobSpecV1::SqliteApp { target, .. } => target,
+        job_spec::JobSpecV1::Postgres { target, .. } => target,
+        job_spec::JobSpecV1::VmImage { target, .. } => target,
+        job_spec::JobSpecV1::KubernetesPvc { target, .. } => target,
+        job_spec::JobSpecV1::Imap { target, .. } => target,
+        job_spec::JobSpecV1::Git { target, .. } => target,
     }
 }
 
@@ -300,6 +300,12 @@ mod tests {
                 root: "".to_string(),
                 include: vec![],
                 exclude: vec![],
+                exclude_presets: Vec::new(),
+                use_ignore_files: false,
+                max_file_size_bytes: None,
+                skip_older_than_secs: None,
+                skip_newer_than_secs: None,
+                anomaly_detection: None,
                 symlink_policy: Default::default(),
                 hardlink_policy: Default::default(),
                 error_policy: Default::default(),
@@ -308,10 +314,12 @@ mod tests {
                 consistency_policy: Default::default(),
                 consistency_fail_threshold: None,
                 upload_on_consistency_failure: None,
+                windows_system_state: false,
             },
             target: job_spec::TargetV1::LocalDir {
                 base_dir: "/tmp/out".to_string(),
                 part_size_bytes: 1024 * 1024,
+                link_unchanged: false,
             },
         };
 
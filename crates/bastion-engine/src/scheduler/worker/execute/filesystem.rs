@@ -7,6 +7,7 @@ use tokio_util::sync::CancellationToken;
 use bastion_core::job_spec;
 use bastion_core::progress::{ProgressKindV1, ProgressUnitsV1};
 use bastion_core::run_failure::RunFailedWithSummary;
+use bastion_driver_registry::target_runtime;
 use bastion_storage::jobs_repo;
 use bastion_storage::secrets::SecretsCrypto;
 
@@ -16,6 +17,7 @@ use crate::run_events_bus::RunEventsBus;
 
 use bastion_backup as backup;
 use bastion_backup::backup_encryption;
+use bastion_backup::staging::{self, StagingArea};
 
 use super::planner::plan_filesystem_execution;
 use super::progress::{RUN_PROGRESS_MIN_INTERVAL, RunProgressUpdate, spawn_run_progress_writer};
@@ -201,7 +203,7 @@ pub(super) async fn execute_filesystem_run(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
     run_events_bus: &RunEventsBus,
-    data_dir: &std::path::Path,
+    staging: &StagingArea,
     job: &jobs_repo::Job,
     run_id: &str,
     started_at: OffsetDateTime,
@@ -211,11 +213,15 @@ pub(super) async fn execute_filesystem_run(
     target: job_spec::TargetV1,
 ) -> Result<serde_json::Value, anyhow::Error> {
     check_run_canceled(run_id, cancel_token)?;
+    staging
+        .check_free_space()
+        .map_err(|error| staging::classify_build_error(error.into(), staging))?;
     let progress_tx =
         spawn_run_progress_writer(db.clone(), run_id.to_string(), ProgressKindV1::Backup);
     let snapshot_settings = backup::filesystem::source_snapshot::SnapshotSettings::capture();
 
-    let data_dir = data_dir.to_path_buf();
+    let staging_root = staging.root.clone();
+    let staging_min_free_bytes = staging.min_free_bytes;
     let job_id = job.id.clone();
     let run_id_owned = run_id.to_string();
 
@@ -284,7 +290,7 @@ pub(super) async fn execute_filesystem_run(
                 )
                 .await;
 
-                let run_dir = backup::run_dir(&data_dir, &run_id_owned);
+                let run_dir = backup::run_dir(&staging_root, &run_id_owned);
                 let snapshot_settings = snapshot_settings.clone();
                 let attempt = tokio::task::spawn_blocking(move || {
                     backup::filesystem::source_snapshot::attempt_source_snapshot(
@@ -418,6 +424,8 @@ pub(super) async fn execute_filesystem_run(
     let consistency_fail_threshold = source.consistency_fail_threshold.unwrap_or(0);
     let upload_on_consistency_failure = source.upload_on_consistency_failure.unwrap_or(false);
     let artifact_format = pipeline.format.clone();
+    let compression = pipeline.compression;
+    let part_upload_concurrency = pipeline.webdav.effective_part_upload_concurrency();
     let webdav_direct = pipeline.webdav.raw_tree_direct.clone();
     let webdav_limits = webdav_direct
         .limits
@@ -471,6 +479,7 @@ pub(super) async fn execute_filesystem_run(
             &job.id,
             run_id,
             artifact_format.clone(),
+            part_upload_concurrency,
         )
         .await?
     } else {
@@ -480,28 +489,23 @@ pub(super) async fn execute_filesystem_run(
     let mut raw_tree_webdav_direct_upload: Option<
         backup::filesystem::RawTreeWebdavDirectUploadConfig,
     > = None;
+    let upload_target = if planned.plan.enable_raw_tree_webdav_direct_upload
+        || planned.plan.link_stage_data_to_local_target
+    {
+        rolling_archive::resolve_hub_upload_target(db, secrets, &target).await?
+    } else {
+        None
+    };
     if planned.plan.enable_raw_tree_webdav_direct_upload
-        && let job_spec::TargetV1::Webdav {
+        && let Some(rolling_archive::HubUploadTarget::Webdav {
             base_url,
-            secret_name,
-            ..
-        } = &target
+            credentials,
+        }) = &upload_target
     {
-        let cred_bytes = bastion_storage::secrets_repo::get_secret(
-            db,
-            secrets,
-            bastion_core::HUB_NODE_ID,
-            "webdav",
-            secret_name,
-        )
-        .await?
-        .ok_or_else(|| anyhow::anyhow!("missing webdav secret: {secret_name}"))?;
-        let credentials = bastion_targets::WebdavCredentials::from_json(&cred_bytes)?;
-
         raw_tree_webdav_direct_upload = Some(backup::filesystem::RawTreeWebdavDirectUploadConfig {
             handle: tokio::runtime::Handle::current(),
             base_url: base_url.clone(),
-            credentials,
+            credentials: credentials.clone(),
             max_attempts: 3,
             resume_by_size: webdav_direct.resume_by_size,
             limits: webdav_limits.clone(),
@@ -513,13 +517,13 @@ pub(super) async fn execute_filesystem_run(
     // staging `data/` dir to the final target run dir (best-effort; falls back to normal staging).
     let mut direct_target_run_dir: Option<std::path::PathBuf> = None;
     if planned.plan.link_stage_data_to_local_target
-        && let job_spec::TargetV1::LocalDir { base_dir, .. } = &target
+        && let Some(rolling_archive::HubUploadTarget::LocalDir { base_dir }) = &upload_target
     {
         let target_run_dir = std::path::Path::new(base_dir)
             .join(&job_id)
             .join(&run_id_owned);
         let target_data_dir = target_run_dir.join("data");
-        let stage_data_dir = backup::stage_dir(&data_dir, &run_id_owned).join("data");
+        let stage_data_dir = backup::stage_dir(&staging_root, &run_id_owned).join("data");
 
         if let Err(error) = std::fs::create_dir_all(&target_data_dir) {
             let fields = direct_data_path_unavailable_event_fields(
@@ -538,7 +542,7 @@ pub(super) async fn execute_filesystem_run(
             )
             .await;
         } else {
-            let _ = std::fs::create_dir_all(backup::stage_dir(&data_dir, &run_id_owned));
+            let _ = std::fs::create_dir_all(backup::stage_dir(&staging_root, &run_id_owned));
             if let Ok(meta) = std::fs::symlink_metadata(&stage_data_dir) {
                 let _ = if meta.is_dir() {
                     std::fs::remove_dir_all(&stage_data_dir)
@@ -603,20 +607,23 @@ pub(super) async fn execute_filesystem_run(
             }));
         };
         backup::filesystem::build_filesystem_run(
-            &data_dir,
+            &staging_root,
             &job_id_for_build,
             &run_id_for_build,
             started_at,
             &source,
             backup::BuildPipelineOptions {
                 artifact_format,
+                compression,
                 encryption: &encryption,
                 part_size_bytes: part_size,
+                staging_min_free_bytes,
             },
             read_mapping_for_build.as_ref(),
             Some(&on_progress),
             on_part_finished,
             raw_tree_webdav_direct_upload_for_build,
+            None,
         )
     })
     .await?;
@@ -672,7 +679,9 @@ pub(super) async fn execute_filesystem_run(
 
     let build = match (build_res, uploader_res) {
         (Ok(build), Ok(())) => build,
-        (Err(build_error), Ok(())) => return Err(build_error),
+        (Err(build_error), Ok(())) => {
+            return Err(staging::classify_build_error(build_error, staging));
+        }
         (Ok(_), Err(upload_error)) => return Err(upload_error),
         (Err(build_error), Err(upload_error)) => {
             return Err(rolling_archive::merge_packaging_and_uploader_errors(
@@ -707,6 +716,32 @@ pub(super) async fn execute_filesystem_run(
         .await;
     }
 
+    if !build.ignore_files.is_empty() {
+        let _ = run_events::append_and_broadcast(
+            db,
+            run_events_bus,
+            run_id,
+            "info",
+            "fs_ignore_files",
+            "applied ignore files",
+            Some(serde_json::json!({ "files": &build.ignore_files })),
+        )
+        .await;
+    }
+
+    if let Some(anomaly) = build.anomaly.as_ref().filter(|a| a.triggered) {
+        let _ = run_events::append_and_broadcast(
+            db,
+            run_events_bus,
+            run_id,
+            "warn",
+            "fs_anomaly",
+            "possible ransomware activity: snapshot differs sharply from the previous one",
+            Some(serde_json::to_value(anomaly)?),
+        )
+        .await;
+    }
+
     let consistency_total = build.consistency.total();
     let consistency_failed =
         consistency_policy.should_fail(consistency_total, consistency_fail_threshold);
@@ -729,6 +764,8 @@ pub(super) async fn execute_filesystem_run(
 
     let source_total = build.source_total;
     let raw_tree_stats = build.raw_tree_stats;
+    let anomaly = build.anomaly;
+    let system_state = build.system_state;
     let issues = build.issues;
     let consistency = if consistency_policy == job_spec::ConsistencyPolicyV1::Ignore {
         Default::default()
@@ -755,10 +792,7 @@ pub(super) async fn execute_filesystem_run(
 
     if consistency_failed && !upload_on_consistency_failure {
         let target_summary = serde_json::json!({
-            "type": match target {
-                job_spec::TargetV1::Webdav { .. } => "webdav",
-                job_spec::TargetV1::LocalDir { .. } => "local_dir",
-            }
+            "type": target_runtime::driver_id_for_job_target(&target).kind,
         });
 
         let metrics = {
@@ -784,6 +818,8 @@ pub(super) async fn execute_filesystem_run(
                 "errors_total": issues.errors_total,
                 "snapshot": snapshot_summary.clone(),
                 "consistency": consistency,
+                "anomaly": anomaly,
+                "system_state": system_state,
             },
             "planner": planner_summary.clone(),
         });
@@ -880,11 +916,6 @@ pub(super) async fn execute_filesystem_run(
         })
     };
 
-    let webdav_limits_for_store = if matches!(target, job_spec::TargetV1::Webdav { .. }) {
-        webdav_limits.clone()
-    } else {
-        None
-    };
     let target_summary = match super::super::target_store::store_run_artifacts_to_target(
         db,
         secrets,
@@ -892,7 +923,7 @@ pub(super) async fn execute_filesystem_run(
         run_id,
         &target,
         &artifacts,
-        webdav_limits_for_store,
+        webdav_limits.clone(),
         Some(upload_cb),
     )
     .await
@@ -940,6 +971,8 @@ pub(super) async fn execute_filesystem_run(
             "errors_total": issues.errors_total,
             "snapshot": snapshot_summary.clone(),
             "consistency": consistency,
+            "anomaly": anomaly,
+            "system_state": system_state,
         },
         "planner": planner_summary,
     });
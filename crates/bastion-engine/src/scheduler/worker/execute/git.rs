@@ -0,0 +1,249 @@
+use std::time::Instant;
+
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+
+use bastion_core::job_spec;
+use bastion_core::progress::{ProgressKindV1, ProgressUnitsV1};
+use bastion_storage::jobs_repo;
+use bastion_storage::secrets::SecretsCrypto;
+
+use crate::run_events;
+use crate::run_events_bus::RunEventsBus;
+
+use bastion_backup as backup;
+use bastion_backup::backup_encryption;
+use bastion_backup::staging::{self, StagingArea};
+
+use super::check_run_canceled;
+use super::planner::plan_git_execution;
+use super::progress::{RUN_PROGRESS_MIN_INTERVAL, RunProgressUpdate, spawn_run_progress_writer};
+use super::rolling_archive;
+
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn execute_git_run(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    run_events_bus: &RunEventsBus,
+    staging: &StagingArea,
+    job: &jobs_repo::Job,
+    run_id: &str,
+    started_at: OffsetDateTime,
+    cancel_token: &CancellationToken,
+    pipeline: job_spec::PipelineV1,
+    source: job_spec::GitSource,
+    target: job_spec::TargetV1,
+) -> Result<serde_json::Value, anyhow::Error> {
+    check_run_canceled(run_id, cancel_token)?;
+    staging
+        .check_free_space()
+        .map_err(|error| staging::classify_build_error(error.into(), staging))?;
+    let progress_tx =
+        spawn_run_progress_writer(db.clone(), run_id.to_string(), ProgressKindV1::Backup);
+    let _ = progress_tx.send(Some(RunProgressUpdate {
+        stage: "snapshot",
+        done: ProgressUnitsV1::default(),
+        total: None,
+        detail: None,
+    }));
+
+    run_events::append_and_broadcast(
+        db,
+        run_events_bus,
+        run_id,
+        "info",
+        "snapshot",
+        "snapshot",
+        None,
+    )
+    .await?;
+
+    let staging_root = staging.root.clone();
+    let staging_min_free_bytes = staging.min_free_bytes;
+    let job_id = job.id.clone();
+    let run_id_owned = run_id.to_string();
+    let part_size = target.part_size_bytes();
+    let artifact_format = pipeline.format.clone();
+    let compression = pipeline.compression;
+    let part_upload_concurrency = pipeline.webdav.effective_part_upload_concurrency();
+    let encryption = backup_encryption::ensure_payload_encryption(db, secrets, &pipeline).await?;
+
+    let planned = plan_git_execution(&pipeline, &target)
+        .map_err(|error| anyhow::anyhow!("execution planning failed: {error}"))?;
+    let planner_fields = planned
+        .plan
+        .observability_fields(&planned.source_driver, &planned.target_driver);
+    let planner_summary = planned
+        .plan
+        .summary_payload(&planned.source_driver, &planned.target_driver);
+    run_events::append_and_broadcast(
+        db,
+        run_events_bus,
+        run_id,
+        "info",
+        "planning",
+        "planning",
+        Some(planner_fields),
+    )
+    .await?;
+
+    let (on_part_finished, parts_uploader) = if planned.plan.allow_rolling_upload {
+        rolling_archive::prepare_archive_part_uploader(
+            db,
+            secrets,
+            &target,
+            &job.id,
+            run_id,
+            artifact_format.clone(),
+            part_upload_concurrency,
+        )
+        .await?
+    } else {
+        (None, None)
+    };
+
+    let build_res = tokio::task::spawn_blocking(move || {
+        backup::git::build_git_run(
+            &staging_root,
+            &job_id,
+            &run_id_owned,
+            started_at,
+            &source,
+            backup::BuildPipelineOptions {
+                artifact_format,
+                compression,
+                encryption: &encryption,
+                part_size_bytes: part_size,
+                staging_min_free_bytes,
+            },
+            on_part_finished,
+            None,
+        )
+    })
+    .await?;
+    check_run_canceled(run_id, cancel_token)?;
+    let uploader_res = rolling_archive::join_parts_uploader(parts_uploader).await;
+    let build = match (build_res, uploader_res) {
+        (Ok(build), Ok(())) => build,
+        (Err(build_error), Ok(())) => {
+            return Err(staging::classify_build_error(build_error, staging));
+        }
+        (Ok(_), Err(upload_error)) => return Err(upload_error),
+        (Err(build_error), Err(upload_error)) => {
+            return Err(rolling_archive::merge_packaging_and_uploader_errors(
+                build_error,
+                upload_error,
+            ));
+        }
+    };
+
+    run_events::append_and_broadcast(
+        db,
+        run_events_bus,
+        run_id,
+        "info",
+        "git",
+        "repositories",
+        Some(serde_json::json!({
+            "repositories": build.repositories.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(),
+        })),
+    )
+    .await?;
+
+    run_events::append_and_broadcast(db, run_events_bus, run_id, "info", "upload", "upload", None)
+        .await?;
+
+    let parts_bytes: u64 = build.artifacts.parts.iter().map(|p| p.size).sum();
+    let entries_size = std::fs::metadata(&build.artifacts.entries_index_path)?.len();
+    let manifest_size = std::fs::metadata(&build.artifacts.manifest_path)?.len();
+    let complete_size = std::fs::metadata(&build.artifacts.complete_path)?.len();
+    let transfer_total_bytes = parts_bytes
+        .saturating_add(entries_size)
+        .saturating_add(manifest_size)
+        .saturating_add(complete_size);
+
+    struct UploadThrottle {
+        last_emit: Instant,
+        last_done: u64,
+        last_total: Option<u64>,
+    }
+
+    let upload_throttle = std::sync::Arc::new(std::sync::Mutex::new(UploadThrottle {
+        last_emit: Instant::now()
+            .checked_sub(RUN_PROGRESS_MIN_INTERVAL)
+            .unwrap_or_else(Instant::now),
+        last_done: 0,
+        last_total: None,
+    }));
+    let progress_tx_upload = progress_tx.clone();
+    let upload_cb: std::sync::Arc<dyn Fn(bastion_targets::StoreRunProgress) + Send + Sync> = {
+        let upload_throttle = upload_throttle.clone();
+        std::sync::Arc::new(move |p: bastion_targets::StoreRunProgress| {
+            let now = Instant::now();
+            let mut guard = match upload_throttle.lock() {
+                Ok(g) => g,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+
+            let total_bytes = Some(transfer_total_bytes);
+            let done_bytes = p.bytes_done;
+            let finished = total_bytes.is_some_and(|t| done_bytes >= t);
+            let should_emit =
+                finished || now.duration_since(guard.last_emit) >= RUN_PROGRESS_MIN_INTERVAL;
+            if !should_emit {
+                return;
+            }
+            if done_bytes == guard.last_done && total_bytes == guard.last_total {
+                return;
+            }
+
+            guard.last_emit = now;
+            guard.last_done = done_bytes;
+            guard.last_total = total_bytes;
+
+            let _ = progress_tx_upload.send(Some(RunProgressUpdate {
+                stage: "upload",
+                done: ProgressUnitsV1 {
+                    files: 0,
+                    dirs: 0,
+                    bytes: done_bytes,
+                },
+                total: total_bytes.map(|bytes| ProgressUnitsV1 {
+                    files: 0,
+                    dirs: 0,
+                    bytes,
+                }),
+                detail: None,
+            }));
+        })
+    };
+
+    let target_summary = super::super::target_store::store_run_artifacts_to_target(
+        db,
+        secrets,
+        &job.id,
+        run_id,
+        &target,
+        &build.artifacts,
+        None,
+        Some(upload_cb),
+    )
+    .await?;
+    check_run_canceled(run_id, cancel_token)?;
+
+    let _ = tokio::fs::remove_dir_all(&build.artifacts.run_dir).await;
+
+    Ok(serde_json::json!({
+        "target": target_summary,
+        "artifact_format": pipeline.format,
+        "entries_count": build.artifacts.entries_count,
+        "parts": build.artifacts.parts.len(),
+        "metrics": {
+            "source_total": { "files": build.source_files, "dirs": 0, "bytes": build.source_bytes },
+            "transfer_total_bytes": transfer_total_bytes,
+        },
+        "git": build.summary(),
+        "planner": planner_summary,
+    }))
+}
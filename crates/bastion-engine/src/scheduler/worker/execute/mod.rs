@@ -2,6 +2,7 @@ use sqlx::SqlitePool;
 use time::OffsetDateTime;
 use tokio_util::sync::CancellationToken;
 
+use bastion_backup::staging::StagingArea;
 use bastion_core::error_envelope::ErrorEnvelopeV1;
 use bastion_core::job_spec;
 use bastion_storage::jobs_repo;
@@ -11,11 +12,17 @@ use crate::error_envelope::{envelope, origin, retriable, retriable_with_reason,
 use crate::run_events_bus::RunEventsBus;
 
 mod filesystem;
+mod git;
+mod imap;
+mod kubernetes_pvc;
 mod planner;
+mod postgres;
 mod progress;
 mod rolling_archive;
 mod sqlite;
+mod sqlite_app;
 mod vaultwarden;
+mod vm_image;
 
 #[derive(Debug)]
 pub(super) struct RunCanceled {
@@ -75,7 +82,7 @@ pub(super) struct ExecuteRunArgs<'a> {
     pub(super) db: &'a SqlitePool,
     pub(super) secrets: &'a SecretsCrypto,
     pub(super) run_events_bus: &'a RunEventsBus,
-    pub(super) data_dir: &'a std::path::Path,
+    pub(super) staging: &'a StagingArea,
     pub(super) job: &'a jobs_repo::Job,
     pub(super) run_id: &'a str,
     pub(super) started_at: OffsetDateTime,
@@ -90,7 +97,7 @@ pub(super) async fn execute_run(
         db,
         secrets,
         run_events_bus,
-        data_dir,
+        staging,
         job,
         run_id,
         started_at,
@@ -109,7 +116,7 @@ pub(super) async fn execute_run(
                 db,
                 secrets,
                 run_events_bus,
-                data_dir,
+                staging,
                 job,
                 run_id,
                 started_at,
@@ -130,7 +137,7 @@ pub(super) async fn execute_run(
                 db,
                 secrets,
                 run_events_bus,
-                data_dir,
+                staging,
                 job,
                 run_id,
                 started_at,
@@ -151,7 +158,133 @@ pub(super) async fn execute_run(
                 db,
                 secrets,
                 run_events_bus,
-                data_dir,
+                staging,
+                job,
+                run_id,
+                started_at,
+                &cancel_token,
+                pipeline,
+                source,
+                target,
+            )
+            .await
+        }
+        job_spec::JobSpecV1::SqliteApp {
+            pipeline,
+            source,
+            target,
+            ..
+        } => {
+            sqlite_app::execute_sqlite_app_run(
+                db,
+                secrets,
+                run_events_bus,
+                staging,
+                job,
+                run_id,
+                started_at,
+                &cancel_token,
+                pipeline,
+                source,
+                target,
+            )
+            .await
+        }
+        job_spec::JobSpecV1::Postgres {
+            pipeline,
+            source,
+            target,
+            ..
+        } => {
+            postgres::execute_postgres_run(
+                db,
+                secrets,
+                run_events_bus,
+                staging,
+                job,
+                run_id,
+                started_at,
+                &cancel_token,
+                pipeline,
+                source,
+                target,
+            )
+            .await
+        }
+        job_spec::JobSpecV1::VmImage {
+            pipeline,
+            source,
+            target,
+            ..
+        } => {
+            vm_image::execute_vm_image_run(
+                db,
+                secrets,
+                run_events_bus,
+                staging,
+                job,
+                run_id,
+                started_at,
+                &cancel_token,
+                pipeline,
+                source,
+                target,
+            )
+            .await
+        }
+        job_spec::JobSpecV1::Git {
+            pipeline,
+            source,
+            target,
+            ..
+        } => {
+            git::execute_git_run(
+                db,
+                secrets,
+                run_events_bus,
+                staging,
+                job,
+                run_id,
+                started_at,
+                &cancel_token,
+                pipeline,
+                source,
+                target,
+            )
+            .await
+        }
+        job_spec::JobSpecV1::KubernetesPvc {
+            pipeline,
+            source,
+            target,
+            ..
+        } => {
+            kubernetes_pvc::execute_kubernetes_pvc_run(
+                db,
+                secrets,
+                run_events_bus,
+                staging,
+                job,
+                run_id,
+                started_at,
+                &cancel_token,
+                pipeline,
+                source,
+                target,
+            )
+            .await
+        }
+        job_spec::JobSpecV1::Imap {
+            pipeline,
+            source,
+            target,
+            ..
+        } => {
+            imap::execute_imap_run(
+                db,
+                secrets,
+                run_events_bus,
+                staging,
                 job,
                 run_id,
                 started_at,
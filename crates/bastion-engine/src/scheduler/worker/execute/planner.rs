@@ -66,6 +66,103 @@ pub(super) fn plan_vaultwarden_execution(
     )
 }
 
+pub(super) fn plan_sqlite_app_execution(
+    pipeline: &job_spec::PipelineV1,
+    source: &job_spec::SqliteAppSource,
+    target: &job_spec::TargetV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("sqlite_app", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        Some(source.consistency_policy),
+        source.upload_on_consistency_failure,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
+pub(super) fn plan_postgres_execution(
+    pipeline: &job_spec::PipelineV1,
+    target: &job_spec::TargetV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("postgres", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        None,
+        None,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
+pub(super) fn plan_vm_image_execution(
+    pipeline: &job_spec::PipelineV1,
+    target: &job_spec::TargetV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("vm_image", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        None,
+        None,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
+pub(super) fn plan_kubernetes_pvc_execution(
+    pipeline: &job_spec::PipelineV1,
+    target: &job_spec::TargetV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("kubernetes_pvc", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        None,
+        None,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
+pub(super) fn plan_git_execution(
+    pipeline: &job_spec::PipelineV1,
+    target: &job_spec::TargetV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("git", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        None,
+        None,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
+pub(super) fn plan_imap_execution(
+    pipeline: &job_spec::PipelineV1,
+    target: &job_spec::TargetV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("imap", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        None,
+        None,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
 fn plan_for_target(
     source_driver: PlannerDriverRefV1,
     source_capabilities: PlannerSourceCapabilitiesV1,
@@ -87,6 +184,8 @@ fn plan_for_target(
         direct_upload_preference,
         consistency_policy,
         upload_on_consistency_failure,
+        // Hub runs always stage locally; streaming is an agent-side mode.
+        stream_payload: false,
     })?;
 
     Ok(PlannedExecution {
@@ -130,6 +229,12 @@ mod tests {
             root: String::new(),
             include: vec![],
             exclude: vec![],
+            exclude_presets: Vec::new(),
+            use_ignore_files: false,
+            max_file_size_bytes: None,
+            skip_older_than_secs: None,
+            skip_newer_than_secs: None,
+            anomaly_detection: None,
             symlink_policy: Default::default(),
             hardlink_policy: Default::default(),
             error_policy: Default::default(),
@@ -138,6 +243,7 @@ mod tests {
             consistency_policy: job_spec::ConsistencyPolicyV1::Warn,
             consistency_fail_threshold: Some(0),
             upload_on_consistency_failure: Some(true),
+            windows_system_state: false,
         };
         let target = job_spec::TargetV1::Webdav {
             base_url: "https://example.com/base/".to_string(),
@@ -151,6 +257,7 @@ mod tests {
                     mode: job_spec::WebdavRawTreeDirectModeV1::Auto,
                     ..Default::default()
                 },
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -164,6 +271,7 @@ mod tests {
         let target = job_spec::TargetV1::LocalDir {
             base_dir: "/tmp/out".to_string(),
             part_size_bytes: 123,
+            link_unchanged: false,
         };
         let pipeline = job_spec::PipelineV1 {
             format: ArtifactFormatV1::ArchiveV1,
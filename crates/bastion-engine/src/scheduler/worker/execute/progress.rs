@@ -3,7 +3,9 @@ use std::time::{Duration, Instant};
 use sqlx::SqlitePool;
 use time::OffsetDateTime;
 
-use bastion_core::progress::{ProgressKindV1, ProgressSnapshotV1, ProgressUnitsV1};
+use bastion_core::progress::{
+    ProgressKindV1, ProgressRateEstimator, ProgressSnapshotV1, ProgressUnitsV1,
+};
 use bastion_storage::runs_repo;
 
 pub(super) const RUN_PROGRESS_MIN_INTERVAL: Duration = Duration::from_secs(1);
@@ -28,7 +30,7 @@ pub(super) fn spawn_run_progress_writer(
             .checked_sub(RUN_PROGRESS_MIN_INTERVAL)
             .unwrap_or_else(Instant::now);
         let mut last_stage: Option<&'static str> = None;
-        let mut last_ts: Option<i64> = None;
+        let mut rate = ProgressRateEstimator::new();
         let mut last_done_bytes: u64 = 0;
         let mut last_total_bytes: Option<u64> = None;
         let mut last_update: Option<RunProgressUpdate> = None;
@@ -50,34 +52,15 @@ pub(super) fn spawn_run_progress_writer(
                 continue;
             }
 
-            let (rate_bps, eta_seconds) = if stage_changed {
-                (None, None)
-            } else {
-                let dt = last_ts.map(|ts| now_ts.saturating_sub(ts)).unwrap_or(0);
-                let delta = update.done.bytes.saturating_sub(last_done_bytes);
-                let rate = if dt > 0 && delta > 0 {
-                    Some(delta.saturating_div(dt as u64).max(1))
-                } else {
-                    None
-                };
-
-                let eta = match (rate, update.total.as_ref()) {
-                    (Some(rate), Some(total)) if rate > 0 && total.bytes > update.done.bytes => {
-                        Some(
-                            total
-                                .bytes
-                                .saturating_sub(update.done.bytes)
-                                .saturating_div(rate),
-                        )
-                    }
-                    _ => None,
-                };
-                (rate, eta)
-            };
+            let (rate_bps, eta_seconds) = rate.observe(
+                update.stage,
+                now_ts,
+                update.done.bytes,
+                update.total.as_ref().map(|t| t.bytes),
+            );
 
             last_emit = Instant::now();
             last_stage = Some(update.stage);
-            last_ts = Some(now_ts);
             last_done_bytes = update.done.bytes;
             last_total_bytes = update.total.as_ref().map(|t| t.bytes);
 
@@ -116,29 +99,12 @@ pub(super) fn spawn_run_progress_writer(
 
         let now_ts = OffsetDateTime::now_utc().unix_timestamp();
 
-        let stage_changed = last_stage != Some(update.stage);
-        let (rate_bps, eta_seconds) = if stage_changed {
-            (None, None)
-        } else {
-            let dt = last_ts.map(|ts| now_ts.saturating_sub(ts)).unwrap_or(0);
-            let delta = update.done.bytes.saturating_sub(last_done_bytes);
-            let rate = if dt > 0 && delta > 0 {
-                Some(delta.saturating_div(dt as u64).max(1))
-            } else {
-                None
-            };
-
-            let eta = match (rate, update.total.as_ref()) {
-                (Some(rate), Some(total)) if rate > 0 && total.bytes > update.done.bytes => Some(
-                    total
-                        .bytes
-                        .saturating_sub(update.done.bytes)
-                        .saturating_div(rate),
-                ),
-                _ => None,
-            };
-            (rate, eta)
-        };
+        let (rate_bps, eta_seconds) = rate.observe(
+            update.stage,
+            now_ts,
+            update.done.bytes,
+            update.total.as_ref().map(|t| t.bytes),
+        );
 
         let snapshot = ProgressSnapshotV1 {
             v: 1,
@@ -1,13 +1,14 @@
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+use serde::Deserialize;
 use sqlx::SqlitePool;
 
-use bastion_core::HUB_NODE_ID;
 use bastion_core::job_spec;
 use bastion_core::manifest::ArtifactFormatV1;
+use bastion_driver_registry::builtins;
+use bastion_driver_registry::target_runtime;
 use bastion_storage::secrets::SecretsCrypto;
-use bastion_storage::secrets_repo;
 use bastion_targets::WebdavCredentials;
 
 use bastion_backup as backup;
@@ -163,11 +164,67 @@ pub(super) async fn join_parts_uploader(
     }
 }
 
+/// A job target as the Hub writes to it directly, resolved through the driver registry.
+pub(super) enum HubUploadTarget {
+    Webdav {
+        base_url: String,
+        credentials: WebdavCredentials,
+    },
+    LocalDir {
+        base_dir: String,
+    },
+}
+
+#[derive(Deserialize)]
+struct WebdavUploadConfig {
+    base_url: String,
+    username: String,
+    password: String,
+}
+
+#[derive(Deserialize)]
+struct LocalDirUploadConfig {
+    base_dir: String,
+}
+
+/// Resolves the job target's runtime config. `None` for drivers the Hub has no direct writer for;
+/// those store the finished run through the driver instead.
+pub(super) async fn resolve_hub_upload_target(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    target: &job_spec::TargetV1,
+) -> Result<Option<HubUploadTarget>, anyhow::Error> {
+    let (driver_id, target_config) =
+        super::super::target_store::resolve_target_config_for_hub(db, secrets, target).await?;
+
+    Ok(match driver_id.kind.as_str() {
+        builtins::TARGET_KIND_WEBDAV => {
+            let config = serde_json::from_value::<WebdavUploadConfig>(target_config)?;
+            Some(HubUploadTarget::Webdav {
+                base_url: config.base_url,
+                credentials: WebdavCredentials {
+                    username: config.username,
+                    password: config.password,
+                },
+            })
+        }
+        builtins::TARGET_KIND_LOCAL_DIR => {
+            let config = serde_json::from_value::<LocalDirUploadConfig>(target_config)?;
+            Some(HubUploadTarget::LocalDir {
+                base_dir: config.base_dir,
+            })
+        }
+        _ => None,
+    })
+}
+
 /// Prepare a rolling uploader for archive_v1 part files.
 ///
 /// Returns:
 /// - on_part_finished hook to pass into the archive builder
 /// - a join handle that completes once all received parts have been stored (and local files deleted)
+///
+/// `part_upload_concurrency` bounds the parts in flight for WebDAV targets; local_dir stays sequential.
 pub(super) async fn prepare_archive_part_uploader(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
@@ -175,6 +232,7 @@ pub(super) async fn prepare_archive_part_uploader(
     job_id: &str,
     run_id: &str,
     artifact_format: ArtifactFormatV1,
+    part_upload_concurrency: usize,
 ) -> Result<
     (
         Option<Box<dyn Fn(backup::LocalArtifact) -> std::io::Result<()> + Send>>,
@@ -185,24 +243,24 @@ pub(super) async fn prepare_archive_part_uploader(
     if artifact_format != ArtifactFormatV1::ArchiveV1 {
         return Ok((None, None));
     }
+    let driver_id = target_runtime::driver_id_for_job_target(target);
+    let capabilities = builtins::target_registry().target_capabilities(&driver_id)?;
+    if !capabilities.supports_archive_rolling_upload {
+        return Ok((None, None));
+    }
+    let Some(upload_target) = resolve_hub_upload_target(db, secrets, target).await? else {
+        return Ok((None, None));
+    };
 
     let (tx, rx) = tokio::sync::mpsc::channel::<backup::LocalArtifact>(1);
     let uploader_failure: Arc<Mutex<Option<RollingUploaderFailureSnapshot>>> =
         Arc::new(Mutex::new(None));
 
-    let handle: tokio::task::JoinHandle<Result<(), anyhow::Error>> = match target {
-        job_spec::TargetV1::Webdav {
+    let handle: tokio::task::JoinHandle<Result<(), anyhow::Error>> = match upload_target {
+        HubUploadTarget::Webdav {
             base_url,
-            secret_name,
-            ..
+            credentials,
         } => {
-            let cred_bytes =
-                secrets_repo::get_secret(db, secrets, HUB_NODE_ID, "webdav", secret_name)
-                    .await?
-                    .ok_or_else(|| anyhow::anyhow!("missing webdav secret: {secret_name}"))?;
-            let credentials = WebdavCredentials::from_json(&cred_bytes)?;
-
-            let base_url = base_url.to_string();
             let job_id = job_id.to_string();
             let run_id = run_id.to_string();
             let uploader_failure_for_task = uploader_failure.clone();
@@ -213,6 +271,7 @@ pub(super) async fn prepare_archive_part_uploader(
                     credentials,
                     &job_id,
                     &run_id,
+                    part_upload_concurrency,
                     rx,
                 )
                 .await
@@ -230,8 +289,7 @@ pub(super) async fn prepare_archive_part_uploader(
                 }
             })
         }
-        job_spec::TargetV1::LocalDir { base_dir, .. } => {
-            let base_dir = base_dir.to_string();
+        HubUploadTarget::LocalDir { base_dir } => {
             let job_id = job_id.to_string();
             let run_id = run_id.to_string();
             let uploader_failure_for_task = uploader_failure.clone();
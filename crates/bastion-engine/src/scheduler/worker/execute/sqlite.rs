@@ -16,6 +16,7 @@ use crate::run_events_bus::RunEventsBus;
 
 use bastion_backup as backup;
 use bastion_backup::backup_encryption;
+use bastion_backup::staging::{self, StagingArea};
 
 use super::planner::plan_sqlite_execution;
 use super::progress::{RUN_PROGRESS_MIN_INTERVAL, RunProgressUpdate, spawn_run_progress_writer};
@@ -57,7 +58,7 @@ pub(super) async fn execute_sqlite_run(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
     run_events_bus: &RunEventsBus,
-    data_dir: &std::path::Path,
+    staging: &StagingArea,
     job: &jobs_repo::Job,
     run_id: &str,
     started_at: OffsetDateTime,
@@ -67,6 +68,9 @@ pub(super) async fn execute_sqlite_run(
     target: job_spec::TargetV1,
 ) -> Result<serde_json::Value, anyhow::Error> {
     check_run_canceled(run_id, cancel_token)?;
+    staging
+        .check_free_space()
+        .map_err(|error| staging::classify_build_error(error.into(), staging))?;
     let progress_tx =
         spawn_run_progress_writer(db.clone(), run_id.to_string(), ProgressKindV1::Backup);
     let _ = progress_tx.send(Some(RunProgressUpdate {
@@ -87,12 +91,19 @@ pub(super) async fn execute_sqlite_run(
     )
     .await?;
 
-    let sqlite_path = source.path.clone();
-    let data_dir = data_dir.to_path_buf();
+    let sqlite_path = source
+        .database_list()
+        .first()
+        .map(|db| db.path.clone())
+        .unwrap_or_default();
+    let staging_root = staging.root.clone();
+    let staging_min_free_bytes = staging.min_free_bytes;
     let job_id = job.id.clone();
     let run_id_owned = run_id.to_string();
     let part_size = target.part_size_bytes();
     let artifact_format = pipeline.format.clone();
+    let compression = pipeline.compression;
+    let part_upload_concurrency = pipeline.webdav.effective_part_upload_concurrency();
     let encryption = backup_encryption::ensure_payload_encryption(db, secrets, &pipeline).await?;
 
     let planned = plan_sqlite_execution(&pipeline, &target)
@@ -122,6 +133,7 @@ pub(super) async fn execute_sqlite_run(
             &job.id,
             run_id,
             artifact_format.clone(),
+            part_upload_concurrency,
         )
         .await?
     } else {
@@ -130,17 +142,20 @@ pub(super) async fn execute_sqlite_run(
 
     let build_res = tokio::task::spawn_blocking(move || {
         backup::sqlite::build_sqlite_run(
-            &data_dir,
+            &staging_root,
             &job_id,
             &run_id_owned,
             started_at,
             &source,
             backup::BuildPipelineOptions {
                 artifact_format,
+                compression,
                 encryption: &encryption,
                 part_size_bytes: part_size,
+                staging_min_free_bytes,
             },
             on_part_finished,
+            None,
         )
     })
     .await?;
@@ -148,7 +163,9 @@ pub(super) async fn execute_sqlite_run(
     let uploader_res = rolling_archive::join_parts_uploader(parts_uploader).await;
     let build = match (build_res, uploader_res) {
         (Ok(build), Ok(())) => build,
-        (Err(build_error), Ok(())) => return Err(build_error),
+        (Err(build_error), Ok(())) => {
+            return Err(staging::classify_build_error(build_error, staging));
+        }
         (Ok(_), Err(upload_error)) => return Err(upload_error),
         (Err(build_error), Err(upload_error)) => {
             return Err(rolling_archive::merge_packaging_and_uploader_errors(
@@ -158,8 +175,14 @@ pub(super) async fn execute_sqlite_run(
         }
     };
 
-    if let Some(check) = build.integrity_check.as_ref() {
-        let data = sqlite_integrity_check_event_fields(check.ok, check.truncated, &check.lines);
+    for sqlite_db in &build.databases {
+        let Some(check) = sqlite_db.integrity_check.as_ref() else {
+            continue;
+        };
+        let mut data = sqlite_integrity_check_event_fields(check.ok, check.truncated, &check.lines);
+        if let Some(obj) = data.as_object_mut() {
+            obj.insert("path".to_string(), serde_json::json!(sqlite_db.path));
+        }
         let _ = run_events::append_and_broadcast(
             db,
             run_events_bus,
@@ -173,7 +196,11 @@ pub(super) async fn execute_sqlite_run(
 
         if !check.ok {
             let first = check.lines.first().cloned().unwrap_or_default();
-            anyhow::bail!("sqlite integrity_check failed: {}", first);
+            anyhow::bail!(
+                "sqlite integrity_check failed for {}: {}",
+                sqlite_db.path,
+                first
+            );
         }
     }
 
@@ -185,7 +212,7 @@ pub(super) async fn execute_sqlite_run(
     let manifest_size = std::fs::metadata(&build.artifacts.manifest_path)?.len();
     let complete_size = std::fs::metadata(&build.artifacts.complete_path)?.len();
     let raw_tree_data_bytes = if pipeline.format == ArtifactFormatV1::RawTreeV1 {
-        build.snapshot_size
+        build.snapshots_size()
     } else {
         0
     };
@@ -272,12 +299,17 @@ pub(super) async fn execute_sqlite_run(
         "entries_count": build.artifacts.entries_count,
         "parts": build.artifacts.parts.len(),
         "metrics": {
-            "source_total": { "files": 1, "dirs": 0, "bytes": build.snapshot_size },
+            "source_total": {
+                "files": build.databases.len(),
+                "dirs": 0,
+                "bytes": build.snapshots_size(),
+            },
             "transfer_total_bytes": transfer_total_bytes,
         },
         "sqlite": {
             "path": sqlite_path,
             "snapshot_name": build.snapshot_name,
+            "databases": build.databases_summary(),
         },
         "planner": planner_summary,
     }))
@@ -7,6 +7,7 @@ use tokio_util::sync::CancellationToken;
 use bastion_core::job_spec;
 use bastion_core::progress::{ProgressKindV1, ProgressUnitsV1};
 use bastion_core::run_failure::RunFailedWithSummary;
+use bastion_driver_registry::target_runtime;
 use bastion_storage::jobs_repo;
 use bastion_storage::secrets::SecretsCrypto;
 
@@ -16,6 +17,7 @@ use crate::run_events_bus::RunEventsBus;
 
 use bastion_backup as backup;
 use bastion_backup::backup_encryption;
+use bastion_backup::staging::{self, StagingArea};
 
 use super::planner::plan_vaultwarden_execution;
 use super::progress::{RUN_PROGRESS_MIN_INTERVAL, RunProgressUpdate, spawn_run_progress_writer};
@@ -54,7 +56,7 @@ pub(super) async fn execute_vaultwarden_run(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
     run_events_bus: &RunEventsBus,
-    data_dir: &std::path::Path,
+    staging: &StagingArea,
     job: &jobs_repo::Job,
     run_id: &str,
     started_at: OffsetDateTime,
@@ -64,6 +66,9 @@ pub(super) async fn execute_vaultwarden_run(
     target: job_spec::TargetV1,
 ) -> Result<serde_json::Value, anyhow::Error> {
     check_run_canceled(run_id, cancel_token)?;
+    staging
+        .check_free_space()
+        .map_err(|error| staging::classify_build_error(error.into(), staging))?;
     let progress_tx =
         spawn_run_progress_writer(db.clone(), run_id.to_string(), ProgressKindV1::Backup);
     let _ = progress_tx.send(Some(RunProgressUpdate {
@@ -84,7 +89,8 @@ pub(super) async fn execute_vaultwarden_run(
     )
     .await?;
 
-    let data_dir = data_dir.to_path_buf();
+    let staging_root = staging.root.clone();
+    let staging_min_free_bytes = staging.min_free_bytes;
     let job_id = job.id.clone();
     let run_id_owned = run_id.to_string();
     let vw_data_dir = source.data_dir.clone();
@@ -93,6 +99,8 @@ pub(super) async fn execute_vaultwarden_run(
     let upload_on_consistency_failure = source.upload_on_consistency_failure.unwrap_or(false);
     let part_size = target.part_size_bytes();
     let artifact_format = pipeline.format.clone();
+    let compression = pipeline.compression;
+    let part_upload_concurrency = pipeline.webdav.effective_part_upload_concurrency();
     let encryption = backup_encryption::ensure_payload_encryption(db, secrets, &pipeline).await?;
 
     let planned = plan_vaultwarden_execution(&pipeline, &source, &target)
@@ -122,6 +130,7 @@ pub(super) async fn execute_vaultwarden_run(
             &job.id,
             run_id,
             artifact_format.clone(),
+            part_upload_concurrency,
         )
         .await?
     } else {
@@ -130,17 +139,20 @@ pub(super) async fn execute_vaultwarden_run(
 
     let build_res = tokio::task::spawn_blocking(move || {
         backup::vaultwarden::build_vaultwarden_run(
-            &data_dir,
+            &staging_root,
             &job_id,
             &run_id_owned,
             started_at,
             &source,
             backup::BuildPipelineOptions {
                 artifact_format,
+                compression,
                 encryption: &encryption,
                 part_size_bytes: part_size,
+                staging_min_free_bytes,
             },
             on_part_finished,
+            None,
         )
     })
     .await?;
@@ -148,7 +160,9 @@ pub(super) async fn execute_vaultwarden_run(
     let uploader_res = rolling_archive::join_parts_uploader(parts_uploader).await;
     let build = match (build_res, uploader_res) {
         (Ok(build), Ok(())) => build,
-        (Err(build_error), Ok(())) => return Err(build_error),
+        (Err(build_error), Ok(())) => {
+            return Err(staging::classify_build_error(build_error, staging));
+        }
         (Ok(_), Err(upload_error)) => return Err(upload_error),
         (Err(build_error), Err(upload_error)) => {
             return Err(rolling_archive::merge_packaging_and_uploader_errors(
@@ -165,8 +179,22 @@ pub(super) async fn execute_vaultwarden_run(
     } else {
         build.consistency
     };
+    let integrity = build.integrity;
     let artifacts = build.artifacts;
 
+    if integrity.total() > 0 {
+        let _ = run_events::append_and_broadcast(
+            db,
+            run_events_bus,
+            run_id,
+            "warn",
+            "vaultwarden_integrity",
+            "vaultwarden attachment/send files do not match the database",
+            Some(serde_json::to_value(&integrity)?),
+        )
+        .await;
+    }
+
     if consistency_policy.should_emit_warnings() && consistency_total > 0 {
         let consistency_fields = serde_json::to_value(&consistency)?;
         let _ = run_events::append_and_broadcast(
@@ -194,10 +222,7 @@ pub(super) async fn execute_vaultwarden_run(
 
     if consistency_failed && !upload_on_consistency_failure {
         let target_summary = serde_json::json!({
-            "type": match target {
-                job_spec::TargetV1::Webdav { .. } => "webdav",
-                job_spec::TargetV1::LocalDir { .. } => "local_dir",
-            }
+            "type": target_runtime::driver_id_for_job_target(&target).kind,
         });
 
         let summary = serde_json::json!({
@@ -212,6 +237,7 @@ pub(super) async fn execute_vaultwarden_run(
                 "data_dir": vw_data_dir,
                 "db": "db.sqlite3",
                 "consistency": consistency,
+                "integrity": integrity,
             },
             "planner": planner_summary.clone(),
         });
@@ -313,6 +339,7 @@ pub(super) async fn execute_vaultwarden_run(
             "data_dir": vw_data_dir,
             "db": "db.sqlite3",
             "consistency": consistency,
+            "integrity": integrity,
         },
         "planner": planner_summary,
     });
@@ -138,6 +138,7 @@ pub(super) async fn dispatch_and_wait(
         };
         if current.status != RunStatus::Running {
             notifications::enqueue_for_run_spec(ctx, &spec, &run.id).await;
+            notifications::ping_run_end(ctx, &spec, &run.id).await;
             info!(run_id = %run.id, "run completed (agent)");
             break;
         }
@@ -182,7 +183,18 @@ pub(super) async fn dispatch_and_wait(
             break;
         }
 
-        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        tokio::select! {
+            _ = ctx.drain.expired() => {
+                // The agent keeps going and reports the result once the Hub is back.
+                info!(
+                    run_id = %run.id,
+                    agent_id = %agent_id,
+                    "hub shutting down; leaving agent run in flight"
+                );
+                break;
+            }
+            _ = tokio::time::sleep(std::time::Duration::from_secs(5)) => {}
+        }
     }
 }
 
@@ -13,6 +13,7 @@ pub(super) async fn claim_next_queued_run_or_wait(
             warn!(error = %error, "failed to claim queued run");
             tokio::select! {
                 _ = ctx.shutdown.cancelled() => {}
+                _ = ctx.drain.started() => {}
                 _ = ctx.run_queue_notify.notified() => {}
                 _ = tokio::time::sleep(std::time::Duration::from_secs(1)) => {}
             }
@@ -31,3 +32,10 @@ pub(super) async fn claim_next_queued_run_or_wait(
 
     Some(run)
 }
+
+/// Puts a run claimed just as the drain started back in the queue.
+pub(super) async fn release_claimed_run(ctx: &WorkerLoopCtx<'_>, run: &runs_repo::Run) {
+    if let Err(error) = runs_repo::requeue_run(ctx.db, &run.id).await {
+        warn!(run_id = %run.id, error = %error, "failed to requeue run claimed during drain");
+    }
+}
@@ -2,7 +2,7 @@ use time::OffsetDateTime;
 use tracing::{debug, info, warn};
 
 use bastion_core::job_spec;
-use bastion_core::run_failure::RunFailedWithSummary;
+use bastion_core::run_failure::{RunErrorCode, RunFailedWithSummary};
 use bastion_storage::jobs_repo;
 use bastion_storage::run_artifacts_repo;
 use bastion_storage::runs_repo::{self, RunStatus};
@@ -14,6 +14,7 @@ use crate::error_envelope::{
     retriable_with_reason_retry_after, text_ref_with_params, transport, with_context_param,
 };
 use crate::run_events;
+use crate::webdav_rotation;
 
 use super::super::execute::{ExecuteRunArgs, RunCanceled, execute_run};
 use super::WorkerLoopCtx;
@@ -57,6 +58,12 @@ fn fallback_error_hint(error: &anyhow::Error) -> (String, String) {
             "check WebDAV account permissions for target path (403)".to_string(),
         );
     }
+    if text.contains("staging disk full") {
+        return (
+            "storage_full".to_string(),
+            "the staging disk is low on free space; free space, move --staging-dir to a larger disk or lower --staging-min-free-mb".to_string(),
+        );
+    }
     if text.contains("insufficient storage")
         || text.contains("no space left")
         || text.contains("disk full")
@@ -150,6 +157,13 @@ fn build_failed_event_fields(
         "error_code".to_string(),
         serde_json::Value::String(soft.map(|v| v.code).unwrap_or("run_failed").to_string()),
     );
+    let run_error_code = soft
+        .map(|v| v.error_code)
+        .unwrap_or_else(|| RunErrorCode::classify(error.chain()));
+    fields.insert(
+        "run_error_code".to_string(),
+        serde_json::Value::String(run_error_code.as_str().to_string()),
+    );
 
     let chain: Vec<String> = error.chain().take(12).map(ToString::to_string).collect();
     fields.insert("error_chain".to_string(), serde_json::json!(chain));
@@ -284,6 +298,39 @@ fn build_failed_event_fields(
     serde_json::Value::Object(fields)
 }
 
+/// A run canceled by the shutdown drain (rather than by a user) goes back to the queue so the
+/// next Hub start runs it again.
+async fn requeue_if_interrupted_by_drain(ctx: &WorkerLoopCtx<'_>, run_id: &str) -> bool {
+    if !ctx.drain.is_expired() {
+        return false;
+    }
+    match runs_repo::get_run(ctx.db, run_id).await {
+        Ok(Some(current)) if current.cancel_requested_at.is_none() => {}
+        Ok(_) => return false,
+        Err(error) => {
+            warn!(run_id = %run_id, error = %error, "failed to load run interrupted by drain");
+            return false;
+        }
+    }
+
+    if let Err(error) = runs_repo::requeue_run(ctx.db, run_id).await {
+        warn!(run_id = %run_id, error = %error, "failed to requeue run interrupted by drain");
+        return false;
+    }
+    let _ = run_events::append_and_broadcast(
+        ctx.db,
+        ctx.run_events_bus,
+        run_id,
+        "warn",
+        "requeued",
+        "interrupted by hub shutdown; requeued",
+        None,
+    )
+    .await;
+    info!(run_id = %run_id, "run interrupted by shutdown; requeued");
+    true
+}
+
 pub(super) async fn execute_and_complete(
     ctx: &WorkerLoopCtx<'_>,
     job: &jobs_repo::Job,
@@ -304,11 +351,34 @@ pub(super) async fn execute_and_complete(
         run_id: run.id.clone(),
     };
 
+    let target = match &spec {
+        job_spec::JobSpecV1::Filesystem { target, .. }
+        | job_spec::JobSpecV1::Sqlite { target, .. }
+        | job_spec::JobSpecV1::Vaultwarden { target, .. }
+        | job_spec::JobSpecV1::SqliteApp { target, .. }
+        | job_spec::JobSpecV1::Postgres { target, .. }
+        | job_spec::JobSpecV1::VmImage { target, .. }
+        | job_spec::JobSpecV1::KubernetesPvc { target, .. }
+        | job_spec::JobSpecV1::Imap { target, .. }
+        | job_spec::JobSpecV1::Git { target, .. } => target,
+    };
+    if let Err(error) = webdav_rotation::verify_pending_for_hub_run(
+        ctx.db,
+        ctx.secrets,
+        ctx.run_events_bus,
+        &run.id,
+        target,
+    )
+    .await
+    {
+        warn!(run_id = %run.id, error = %error, "failed to verify pending webdav credential");
+    }
+
     match execute_run(ExecuteRunArgs {
         db: ctx.db,
         secrets: ctx.secrets,
         run_events_bus: ctx.run_events_bus,
-        data_dir: ctx.data_dir,
+        staging: ctx.staging,
         job,
         run_id: &run.id,
         started_at,
@@ -386,11 +456,15 @@ pub(super) async fn execute_and_complete(
                 )
                 .await;
                 notifications::enqueue_for_run_spec(ctx, &spec, &run.id).await;
+                notifications::ping_run_end(ctx, &spec, &run.id).await;
                 debug!(run_id = %run.id, ended_at, "run completed");
             }
         }
         Err(error) => {
             let canceled = error.downcast_ref::<RunCanceled>().is_some();
+            if canceled && requeue_if_interrupted_by_drain(ctx, &run.id).await {
+                return;
+            }
             if canceled {
                 info!(run_id = %run.id, "run canceled");
             } else {
@@ -409,9 +483,10 @@ pub(super) async fn execute_and_complete(
                 soft.map(|e| e.summary.clone())
             };
             let error_code = if canceled {
-                Some("canceled")
+                RunErrorCode::Canceled
             } else {
-                Some(soft.map(|e| e.code).unwrap_or("run_failed"))
+                soft.map(|e| e.error_code)
+                    .unwrap_or_else(|| RunErrorCode::classify(error.chain()))
             };
 
             let completed = match runs_repo::complete_run(
@@ -419,7 +494,7 @@ pub(super) async fn execute_and_complete(
                 &run.id,
                 requested_status,
                 summary,
-                error_code,
+                Some(error_code.as_str()),
             )
             .await
             {
@@ -471,6 +546,7 @@ pub(super) async fn execute_and_complete(
             )
             .await;
             notifications::enqueue_for_run_spec(ctx, &spec, &run.id).await;
+            notifications::ping_run_end(ctx, &spec, &run.id).await;
         }
     }
 }
@@ -493,6 +569,16 @@ mod tests {
             Some("source_consistency")
         );
         assert!(obj.get("error_chain").is_some());
+
+        assert_eq!(
+            obj.get("run_error_code").and_then(|v| v.as_str()),
+            Some("source_consistency")
+        );
+        let fields = build_failed_event_fields(&anyhow::anyhow!("connection refused"), None);
+        assert_eq!(
+            fields.get("run_error_code").and_then(|v| v.as_str()),
+            Some("network_target")
+        );
     }
 
     #[test]
@@ -1,12 +1,12 @@
-use std::path::Path;
-
 use sqlx::SqlitePool;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 
+use bastion_backup::staging::StagingArea;
 use bastion_storage::secrets::SecretsCrypto;
 
 use crate::agent_manager::AgentManager;
+use crate::drain::ShutdownDrain;
 use crate::run_events_bus::RunEventsBus;
 
 use super::WorkerLoopArgs;
@@ -19,35 +19,38 @@ mod process;
 
 struct WorkerLoopCtx<'a> {
     db: &'a SqlitePool,
-    data_dir: &'a Path,
+    staging: &'a StagingArea,
     secrets: &'a SecretsCrypto,
     agent_manager: &'a AgentManager,
     run_events_bus: &'a RunEventsBus,
     run_queue_notify: &'a Notify,
     notifications_notify: &'a Notify,
+    drain: &'a ShutdownDrain,
     shutdown: &'a CancellationToken,
 }
 
 pub(super) async fn run_worker_loop(args: WorkerLoopArgs) {
     let WorkerLoopArgs {
         db,
-        data_dir,
+        staging,
         secrets,
         agent_manager,
         run_events_bus,
         run_queue_notify,
         notifications_notify,
+        drain,
         shutdown,
     } = args;
 
     let ctx = WorkerLoopCtx {
         db: &db,
-        data_dir: data_dir.as_path(),
+        staging: &staging,
         secrets: secrets.as_ref(),
         agent_manager: &agent_manager,
         run_events_bus: run_events_bus.as_ref(),
         run_queue_notify: run_queue_notify.as_ref(),
         notifications_notify: notifications_notify.as_ref(),
+        drain: &drain,
         shutdown: &shutdown,
     };
 
@@ -55,11 +58,21 @@ pub(super) async fn run_worker_loop(args: WorkerLoopArgs) {
         if shutdown.is_cancelled() {
             break;
         }
+        if drain.is_draining() {
+            // Stay up (supervision treats an early return as a crash) but take no more runs.
+            shutdown.cancelled().await;
+            break;
+        }
 
         let Some(run) = claim::claim_next_queued_run_or_wait(&ctx).await else {
             continue;
         };
 
+        let _in_flight = drain.track_run();
+        if drain.is_draining() {
+            claim::release_claimed_run(&ctx, &run).await;
+            continue;
+        }
         process::process_run(&ctx, run).await;
     }
 }
@@ -21,3 +21,19 @@ pub(super) async fn enqueue_for_run_spec(
         }
     }
 }
+
+pub(super) async fn ping_run_start(
+    ctx: &WorkerLoopCtx<'_>,
+    spec: &job_spec::JobSpecV1,
+    run_id: &str,
+) {
+    crate::notifications::ping_run_start(ctx.db, ctx.run_events_bus, spec, run_id).await;
+}
+
+pub(super) async fn ping_run_end(
+    ctx: &WorkerLoopCtx<'_>,
+    spec: &job_spec::JobSpecV1,
+    run_id: &str,
+) {
+    crate::notifications::ping_run_end(ctx.db, ctx.run_events_bus, spec, run_id).await;
+}
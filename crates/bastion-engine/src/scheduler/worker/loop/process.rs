@@ -94,6 +94,8 @@ pub(super) async fn process_run(ctx: &WorkerLoopCtx<'_>, run: runs_repo::Run) {
         }
     }
 
+    super::notifications::ping_run_start(ctx, &spec, &run.id).await;
+
     let started_at = OffsetDateTime::from_unix_timestamp(run.started_at)
         .unwrap_or_else(|_| OffsetDateTime::now_utc());
 
@@ -4,9 +4,11 @@ use sqlx::SqlitePool;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 
+use bastion_backup::staging::StagingArea;
 use bastion_storage::secrets::SecretsCrypto;
 
 use crate::agent_manager::AgentManager;
+use crate::drain::ShutdownDrain;
 use crate::run_events_bus::RunEventsBus;
 
 mod dispatch;
@@ -16,12 +18,13 @@ mod target_store;
 
 pub(super) struct WorkerLoopArgs {
     pub(super) db: SqlitePool,
-    pub(super) data_dir: std::path::PathBuf,
+    pub(super) staging: StagingArea,
     pub(super) secrets: Arc<SecretsCrypto>,
     pub(super) agent_manager: AgentManager,
     pub(super) run_events_bus: Arc<RunEventsBus>,
     pub(super) run_queue_notify: Arc<Notify>,
     pub(super) notifications_notify: Arc<Notify>,
+    pub(super) drain: ShutdownDrain,
     pub(super) shutdown: CancellationToken,
 }
 
@@ -6,10 +6,9 @@ use bastion_core::HUB_NODE_ID;
 use bastion_core::job_spec;
 use bastion_driver_api::{StoreRunProgress, StoreRunRequest, TargetRequestLimits};
 use bastion_driver_registry::builtins;
-use bastion_driver_registry::target_runtime::{self, WebdavRuntimeAuth};
+use bastion_driver_registry::target_runtime;
 use bastion_storage::secrets::SecretsCrypto;
 use bastion_storage::secrets_repo;
-use bastion_targets::WebdavCredentials;
 
 use bastion_backup as backup;
 use bastion_targets as targets;
@@ -27,33 +26,23 @@ fn to_driver_limits(limits: targets::WebdavRequestLimits) -> TargetRequestLimits
     }
 }
 
-async fn resolve_target_config_for_hub(
+pub(super) async fn resolve_target_config_for_hub(
     db: &SqlitePool,
     secrets: &SecretsCrypto,
     target: &job_spec::TargetV1,
 ) -> Result<(bastion_driver_api::DriverId, serde_json::Value), anyhow::Error> {
-    let webdav_auth = match target {
-        job_spec::TargetV1::Webdav { secret_name, .. } => {
-            let secret_name = secret_name.trim();
-            if secret_name.is_empty() {
-                anyhow::bail!("webdav.secret_name is required");
-            }
-
-            let cred_bytes =
-                secrets_repo::get_secret(db, secrets, HUB_NODE_ID, "webdav", secret_name)
-                    .await?
-                    .ok_or_else(|| anyhow::anyhow!("missing webdav secret: {secret_name}"))?;
-            let credentials = WebdavCredentials::from_json(&cred_bytes)?;
-            Some(WebdavRuntimeAuth {
-                username: credentials.username,
-                password: credentials.password,
-                secret_name: Some(secret_name.to_string()),
-            })
-        }
-        job_spec::TargetV1::LocalDir { .. } => None,
+    let credentials = match target_runtime::credential_secret_for_job_target(target)? {
+        Some(secret) => Some(
+            secrets_repo::get_secret(db, secrets, HUB_NODE_ID, secret.kind, secret.name)
+                .await?
+                .ok_or_else(|| {
+                    anyhow::anyhow!("missing {} secret: {}", secret.kind, secret.name)
+                })?,
+        ),
+        None => None,
     };
 
-    target_runtime::runtime_input_for_job_target(target, webdav_auth.as_ref())
+    target_runtime::runtime_input_for_job_target(target, credentials.as_deref())
         .map_err(anyhow::Error::new)
 }
 
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use sqlx::SqlitePool;
+
+use bastion_backup::{COMPLETE_NAME, ENTRIES_INDEX_NAME, MANIFEST_NAME};
+use bastion_driver_api::TargetStore;
+use bastion_driver_registry::builtins;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::secrets_repo;
+
+/// Opens the target recorded in a run snapshot (`snapshot["target"]`) as seen from the Hub.
+///
+/// When the target driver reads credentials from a secret, the secret named by `secret_name` is
+/// loaded for `node_id` and its fields (e.g. WebDAV `username`/`password`) are added to the config.
+pub async fn open(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    node_id: &str,
+    target: &serde_json::Value,
+) -> Result<Arc<dyn TargetStore>, anyhow::Error> {
+    let mut config = target.clone();
+    let kind = config
+        .get("type")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow::anyhow!("target config is missing type"))?
+        .to_string();
+    let registry = builtins::target_registry();
+    let driver_id = registry.target_driver_id_for_kind(&kind)?;
+
+    if let Some(secret_kind) = registry.credential_secret_kind(&driver_id)? {
+        let secret_name = config
+            .get("secret_name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("{kind}.secret_name is required"))?
+            .to_string();
+        let bytes = secrets_repo::get_secret(db, secrets, node_id, secret_kind, &secret_name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("missing {kind} secret: {secret_name}"))?;
+        let secret = serde_json::from_slice::<serde_json::Map<String, serde_json::Value>>(&bytes)?;
+        if let Some(obj) = config.as_object_mut() {
+            for (key, value) in secret {
+                obj.entry(key).or_insert(value);
+            }
+        }
+    }
+
+    Ok(registry.open_store(&driver_id, &config)?)
+}
+
+/// Target path of a run directory, e.g. `<job_id>/<run_id>/`.
+pub fn run_dir_path(job_id: &str, run_id: &str) -> String {
+    format!("{job_id}/{run_id}/")
+}
+
+/// Whether a run directory may be deleted as a whole.
+///
+/// Remote targets are scoped to their base URL, so a run collection is deleted as is. A node-local
+/// directory must hold Bastion snapshot data (complete or partial) so a misconfigured `base_dir`
+/// never removes unrelated files.
+pub async fn is_deletable_run_dir(
+    target: &dyn TargetStore,
+    run_dir: &str,
+) -> Result<bool, anyhow::Error> {
+    if !target.node_local() {
+        return Ok(true);
+    }
+    Ok(target.list(run_dir).await?.iter().any(|entry| {
+        let name = entry.name.as_str();
+        name == COMPLETE_NAME
+            || name == MANIFEST_NAME
+            || name == ENTRIES_INDEX_NAME
+            || name.starts_with("payload.part")
+            || name.ends_with(".partial")
+    }))
+}
@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use bastion_core::job_spec;
+use bastion_storage::runs_repo::{self, RunStatus};
+use bastion_storage::{jobs_repo, stale_backups_repo};
+
+use crate::run_events;
+use crate::run_events_bus::RunEventsBus;
+use crate::supervision::spawn_supervised;
+
+const CHECK_INTERVAL_SECS: u64 = 5 * 60;
+
+/// Error code of the failed run recorded when a job misses its freshness interval.
+pub const BACKUP_STALE: &str = "backup_stale";
+
+pub struct StaleBackupsArgs {
+    pub db: SqlitePool,
+    pub run_events_bus: Arc<RunEventsBus>,
+    pub notifications_notify: Arc<Notify>,
+    pub shutdown: CancellationToken,
+}
+
+pub fn spawn(args: StaleBackupsArgs) {
+    let shutdown = args.shutdown.clone();
+    spawn_supervised("stale_backups.loop", shutdown, run_loop(args));
+}
+
+async fn run_loop(args: StaleBackupsArgs) {
+    loop {
+        if args.shutdown.is_cancelled() {
+            break;
+        }
+
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        match check_once(
+            &args.db,
+            &args.run_events_bus,
+            &args.notifications_notify,
+            now,
+        )
+        .await
+        {
+            Ok(alerted) => debug!(alerted, "stale backup check finished"),
+            Err(error) => warn!(error = %error, "stale backup check failed"),
+        }
+
+        tokio::select! {
+            _ = args.shutdown.cancelled() => break,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {}
+        }
+    }
+}
+
+/// Alerts once for every job that newly became stale; returns how many alerts were raised.
+///
+/// Each alert is recorded as a failed run with error `backup_stale`, so it shows up in the job's
+/// history and goes through the job's notification rules like any other failure.
+async fn check_once(
+    db: &SqlitePool,
+    run_events_bus: &RunEventsBus,
+    notifications_notify: &Notify,
+    now: i64,
+) -> Result<u64, anyhow::Error> {
+    let stale = stale_backups_repo::list_stale_jobs(db, now).await?;
+    let stale_ids = stale
+        .iter()
+        .map(|job| job.job_id.as_str())
+        .collect::<Vec<_>>();
+    stale_backups_repo::resolve_alerts_except(db, &stale_ids).await?;
+
+    let mut alerted = 0_u64;
+    for job in &stale {
+        if job.alerted_at.is_some()
+            || !stale_backups_repo::claim_alert(db, &job.job_id, job.last_success_at, now).await?
+        {
+            continue;
+        }
+
+        let message = match job.last_success_at {
+            Some(at) => format!(
+                "no successful run for {}s (expected within {}s, last success at {at})",
+                now - at,
+                job.max_success_interval_seconds
+            ),
+            None => format!(
+                "no successful run yet (expected within {}s)",
+                job.max_success_interval_seconds
+            ),
+        };
+        let summary = serde_json::json!({
+            "error_code": BACKUP_STALE,
+            "last_success_at": job.last_success_at,
+            "max_success_interval_seconds": job.max_success_interval_seconds,
+            "overdue_seconds": job.overdue_seconds,
+        });
+        let run = runs_repo::create_run(
+            db,
+            &job.job_id,
+            RunStatus::Failed,
+            now,
+            Some(now),
+            Some(summary.clone()),
+            Some(BACKUP_STALE),
+        )
+        .await?;
+        stale_backups_repo::set_alert_run(db, &job.job_id, &run.id).await?;
+        run_events::append_and_broadcast(
+            db,
+            run_events_bus,
+            &run.id,
+            "error",
+            BACKUP_STALE,
+            &message,
+            Some(summary),
+        )
+        .await?;
+        warn!(job_id = %job.job_id, run_id = %run.id, overdue_seconds = job.overdue_seconds, "backup is stale");
+        alerted += 1;
+
+        let Some(spec) = jobs_repo::get_job(db, &job.job_id)
+            .await?
+            .and_then(|job| job_spec::parse_value(&job.spec).ok())
+        else {
+            continue;
+        };
+        match crate::notifications::enqueue_for_run_spec(db, &spec, &run.id).await {
+            Ok(true) => notifications_notify.notify_one(),
+            Ok(false) => {}
+            Err(error) => {
+                warn!(run_id = %run.id, error = %error, "failed to enqueue stale backup notifications");
+            }
+        }
+    }
+
+    Ok(alerted)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::Notify;
+
+    use bastion_storage::jobs_repo::{self, OverlapPolicy};
+    use bastion_storage::runs_repo::{self, RunStatus};
+    use bastion_storage::{db, stale_backups_repo};
+
+    use crate::run_events_bus::RunEventsBus;
+
+    use super::{BACKUP_STALE, check_once};
+
+    #[tokio::test]
+    async fn stale_job_records_one_failed_run_per_episode() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+        let bus = RunEventsBus::new();
+        let notify = Notify::new();
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "job",
+            None,
+            None,
+            Some("UTC"),
+            OverlapPolicy::Queue,
+            serde_json::json!({"v": 1, "type": "filesystem"}),
+        )
+        .await
+        .unwrap();
+        jobs_repo::set_job_max_success_interval(&pool, &job.id, 3600)
+            .await
+            .unwrap();
+
+        let now = job.created_at + 7200;
+        assert_eq!(check_once(&pool, &bus, &notify, now).await.unwrap(), 1);
+        assert_eq!(check_once(&pool, &bus, &notify, now + 60).await.unwrap(), 0);
+
+        let runs = runs_repo::list_runs_for_job(&pool, &job.id, 10)
+            .await
+            .unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, RunStatus::Failed);
+        assert_eq!(runs[0].error.as_deref(), Some(BACKUP_STALE));
+
+        let stale = stale_backups_repo::list_stale_jobs(&pool, now)
+            .await
+            .unwrap();
+        assert_eq!(stale[0].alert_run_id.as_deref(), Some(runs[0].id.as_str()));
+
+        // A success closes the episode; the next miss alerts again.
+        runs_repo::create_run(
+            &pool,
+            &job.id,
+            RunStatus::Success,
+            now + 100,
+            Some(now + 120),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            check_once(&pool, &bus, &notify, now + 200).await.unwrap(),
+            0
+        );
+        assert!(
+            stale_backups_repo::list_stale_jobs(&pool, now + 200)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            check_once(&pool, &bus, &notify, now + 120 + 3601)
+                .await
+                .unwrap(),
+            1
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use bastion_storage::storage_usage_repo;
+
+use crate::supervision::spawn_supervised;
+
+const REFRESH_INTERVAL_SECS: u64 = 15 * 60;
+
+pub fn spawn(db: SqlitePool, shutdown: CancellationToken) {
+    spawn_supervised(
+        "storage_usage.loop",
+        shutdown.clone(),
+        run_loop(db, shutdown),
+    );
+}
+
+async fn run_loop(db: SqlitePool, shutdown: CancellationToken) {
+    loop {
+        if shutdown.is_cancelled() {
+            break;
+        }
+
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        match storage_usage_repo::refresh_storage_usage(&db, now).await {
+            Ok(rows) => debug!(rows, "storage usage refreshed"),
+            Err(error) => warn!(error = %error, "storage usage refresh failed"),
+        }
+
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(REFRESH_INTERVAL_SECS)) => {}
+        }
+    }
+}
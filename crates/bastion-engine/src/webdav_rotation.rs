@@ -0,0 +1,273 @@
+//! Staged WebDAV credential rotation.
+//!
+//! A replacement credential is stored next to the active one (kind `webdav_pending`, same node
+//! and name). Runs try it first; the first run that authenticates with it promotes it to the
+//! active secret. Until then the active credential keeps working for runs already in flight.
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tracing::warn;
+
+use bastion_core::HUB_NODE_ID;
+use bastion_core::agent_protocol::PendingWebdavCredentialV1;
+use bastion_core::job_spec;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::secrets_repo;
+use bastion_targets::WebdavCredentials;
+
+use crate::agent_manager::AgentManager;
+use crate::agent_snapshots;
+use crate::run_events;
+use crate::run_events_bus::RunEventsBus;
+
+pub const WEBDAV_KIND: &str = "webdav";
+pub const WEBDAV_PENDING_KIND: &str = "webdav_pending";
+
+/// Run event emitted when a staged credential authenticated and was promoted.
+pub const EVENT_CREDENTIAL_VERIFIED: &str = "webdav_credential_verified";
+/// Run event emitted when a staged credential was rejected; the run falls back to the active one.
+pub const EVENT_CREDENTIAL_REJECTED: &str = "webdav_credential_rejected";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ActivePayload {
+    username: String,
+    password: String,
+}
+
+/// Stores `username`/`password` as the pending credential, replacing any earlier one.
+pub async fn stage_pending(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    node_id: &str,
+    name: &str,
+    username: &str,
+    password: &str,
+) -> Result<PendingWebdavCredentialV1, anyhow::Error> {
+    let pending = PendingWebdavCredentialV1 {
+        username: username.to_string(),
+        password: password.to_string(),
+        staged_at: time::OffsetDateTime::now_utc().unix_timestamp(),
+    };
+    let bytes = serde_json::to_vec(&pending)?;
+    secrets_repo::upsert_secret(db, secrets, node_id, WEBDAV_PENDING_KIND, name, &bytes).await?;
+    Ok(pending)
+}
+
+pub async fn load_pending(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    node_id: &str,
+    name: &str,
+) -> Result<Option<PendingWebdavCredentialV1>, anyhow::Error> {
+    let Some(bytes) =
+        secrets_repo::get_secret(db, secrets, node_id, WEBDAV_PENDING_KIND, name).await?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(serde_json::from_slice(&bytes)?))
+}
+
+pub async fn discard_pending(
+    db: &SqlitePool,
+    node_id: &str,
+    name: &str,
+) -> Result<bool, anyhow::Error> {
+    secrets_repo::delete_secret(db, node_id, WEBDAV_PENDING_KIND, name).await
+}
+
+/// Replaces the active credential with the pending one and clears the pending slot.
+///
+/// With `staged_at`, only that exact staged credential is promoted: a report about an older
+/// credential that has since been replaced again is ignored. Returns whether anything changed.
+pub async fn promote_pending(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    node_id: &str,
+    name: &str,
+    staged_at: Option<i64>,
+) -> Result<bool, anyhow::Error> {
+    let Some(pending) = load_pending(db, secrets, node_id, name).await? else {
+        return Ok(false);
+    };
+    if staged_at.is_some_and(|v| v != pending.staged_at) {
+        return Ok(false);
+    }
+
+    let active = serde_json::to_vec(&ActivePayload {
+        username: pending.username,
+        password: pending.password,
+    })?;
+    secrets_repo::upsert_secret(db, secrets, node_id, WEBDAV_KIND, name, &active).await?;
+    discard_pending(db, node_id, name).await?;
+    Ok(true)
+}
+
+/// Tries the pending credential for a Hub-executed run's WebDAV target.
+///
+/// On success the credential is promoted before the run resolves its target, so the run itself
+/// uses it. On failure the run continues with the active credential and a warning is recorded.
+pub async fn verify_pending_for_hub_run(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    bus: &RunEventsBus,
+    run_id: &str,
+    target: &job_spec::TargetV1,
+) -> Result<(), anyhow::Error> {
+    let job_spec::TargetV1::Webdav {
+        base_url,
+        secret_name,
+        ..
+    } = target
+    else {
+        return Ok(());
+    };
+    let Some(pending) = load_pending(db, secrets, HUB_NODE_ID, secret_name).await? else {
+        return Ok(());
+    };
+
+    let fields = serde_json::json!({
+        "secret_name": secret_name,
+        "staged_at": pending.staged_at,
+    });
+    let credentials = WebdavCredentials {
+        username: pending.username.clone(),
+        password: pending.password.clone(),
+    };
+    match bastion_targets::webdav::check_credentials(base_url, credentials).await {
+        Ok(()) => {
+            promote_pending(
+                db,
+                secrets,
+                HUB_NODE_ID,
+                secret_name,
+                Some(pending.staged_at),
+            )
+            .await?;
+            run_events::append_and_broadcast(
+                db,
+                bus,
+                run_id,
+                "info",
+                EVENT_CREDENTIAL_VERIFIED,
+                "pending webdav credential verified and promoted",
+                Some(fields),
+            )
+            .await?;
+        }
+        Err(error) => {
+            let mut fields = fields;
+            fields["error"] = serde_json::json!(format!("{error:#}"));
+            run_events::append_and_broadcast(
+                db,
+                bus,
+                run_id,
+                "warn",
+                EVENT_CREDENTIAL_REJECTED,
+                "pending webdav credential rejected; using the active credential",
+                Some(fields),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Promotes a credential an agent reported as working, then resyncs that agent.
+pub async fn promote_from_agent_event(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    agent_manager: &AgentManager,
+    node_id: &str,
+    fields: Option<&serde_json::Value>,
+) -> Result<bool, anyhow::Error> {
+    let Some(fields) = fields else {
+        return Ok(false);
+    };
+    let Some(secret_name) = fields.get("secret_name").and_then(|v| v.as_str()) else {
+        return Ok(false);
+    };
+    let Some(staged_at) = fields.get("staged_at").and_then(|v| v.as_i64()) else {
+        return Ok(false);
+    };
+
+    if !promote_pending(db, secrets, node_id, secret_name, Some(staged_at)).await? {
+        return Ok(false);
+    }
+
+    if let Err(error) =
+        agent_snapshots::send_node_secrets_snapshot(db, secrets, agent_manager, node_id).await
+    {
+        warn!(node_id = %node_id, error = %error, "failed to send secrets snapshot after credential promotion");
+    }
+    if let Err(error) =
+        agent_snapshots::send_node_config_snapshot(db, secrets, agent_manager, node_id).await
+    {
+        warn!(node_id = %node_id, error = %error, "failed to send config snapshot after credential promotion");
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use bastion_storage::db;
+    use bastion_storage::secrets::SecretsCrypto;
+    use bastion_storage::secrets_repo;
+
+    use super::{WEBDAV_KIND, load_pending, promote_pending, stage_pending};
+
+    #[tokio::test]
+    async fn promote_replaces_active_credential_and_clears_pending() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        let crypto = SecretsCrypto::load_or_create(temp.path()).expect("crypto");
+
+        secrets_repo::upsert_secret(
+            &pool,
+            &crypto,
+            "agent1",
+            WEBDAV_KIND,
+            "nas",
+            br#"{"username":"u","password":"old"}"#,
+        )
+        .await
+        .expect("upsert");
+
+        let pending = stage_pending(&pool, &crypto, "agent1", "nas", "u", "new")
+            .await
+            .expect("stage");
+
+        // A report about some other staged credential must not promote this one.
+        assert!(
+            !promote_pending(&pool, &crypto, "agent1", "nas", Some(pending.staged_at - 1))
+                .await
+                .expect("promote stale")
+        );
+        assert!(
+            load_pending(&pool, &crypto, "agent1", "nas")
+                .await
+                .expect("load")
+                .is_some()
+        );
+
+        assert!(
+            promote_pending(&pool, &crypto, "agent1", "nas", Some(pending.staged_at))
+                .await
+                .expect("promote")
+        );
+        assert!(
+            load_pending(&pool, &crypto, "agent1", "nas")
+                .await
+                .expect("load")
+                .is_none()
+        );
+
+        let active = secrets_repo::get_secret(&pool, &crypto, "agent1", WEBDAV_KIND, "nas")
+            .await
+            .expect("get")
+            .expect("active");
+        let active: serde_json::Value = serde_json::from_slice(&active).expect("json");
+        assert_eq!(active["password"], "new");
+    }
+}
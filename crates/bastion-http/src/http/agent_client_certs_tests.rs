@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_core::agent;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+fn escaped_pem(pem: &str) -> String {
+    percent_encoding::utf8_percent_encode(pem, percent_encoding::NON_ALPHANUMERIC).to_string()
+}
+
+#[tokio::test]
+async fn required_client_cert_is_pinned_and_rotated_with_the_key() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let enroll_token = agent::generate_token_b64_urlsafe(32);
+    sqlx::query(
+        "INSERT INTO enrollment_tokens (token_hash, created_at, expires_at, remaining_uses) VALUES (?, 0, ?, NULL)",
+    )
+    .bind(agent::sha256_urlsafe_token(&enroll_token).expect("hash"))
+    .bind(i64::MAX)
+    .execute(&pool)
+    .await
+    .expect("insert token");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!("{}/agent/enroll", base_url(addr)))
+        .json(&serde_json::json!({ "token": enroll_token, "name": "edge" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let enrolled: serde_json::Value = resp.json().await.expect("json");
+    let agent_id = enrolled["agent_id"].as_str().expect("agent_id").to_string();
+    let agent_key = enrolled["agent_key"]
+        .as_str()
+        .expect("agent_key")
+        .to_string();
+    let cert_pem = enrolled["client_cert"]["cert_pem"]
+        .as_str()
+        .expect("cert_pem")
+        .to_string();
+    assert!(
+        enrolled["client_cert"]["key_pem"]
+            .as_str()
+            .is_some_and(|v| v.contains("PRIVATE KEY"))
+    );
+
+    // An agent-authenticated route: 404 means authentication passed.
+    let release = |key: String, cert: Option<String>| {
+        let mut req = client
+            .get(format!(
+                "{}/agent/releases/9.9.9/linux-x86_64",
+                base_url(addr)
+            ))
+            .bearer_auth(key);
+        if let Some(cert) = cert {
+            req = req.header("x-client-cert", escaped_pem(&cert));
+        }
+        req.send()
+    };
+    let set_required = |required: bool| {
+        client
+            .put(format!(
+                "{}/api/agents/{}/client-cert",
+                base_url(addr),
+                agent_id
+            ))
+            .header("cookie", format!("bastion_session={}", session.id))
+            .header("x-csrf-token", session.csrf_token.clone())
+            .json(&serde_json::json!({ "required": required }))
+            .send()
+    };
+
+    let resp = release(agent_key.clone(), None).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = set_required(true).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let resp = release(agent_key.clone(), None).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("client_cert_required"));
+
+    let resp = release(agent_key.clone(), Some(cert_pem.clone()))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = client
+        .post(format!(
+            "{}/api/agents/{}/rotate-key",
+            base_url(addr),
+            agent_id
+        ))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .header("x-csrf-token", session.csrf_token.clone())
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let rotated: serde_json::Value = resp.json().await.expect("json");
+    let new_key = rotated["agent_key"]
+        .as_str()
+        .expect("agent_key")
+        .to_string();
+    let new_cert = rotated["client_cert"]["cert_pem"]
+        .as_str()
+        .expect("cert_pem")
+        .to_string();
+
+    // The old certificate no longer matches the pinned fingerprint.
+    let resp = release(new_key.clone(), Some(cert_pem))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = release(new_key.clone(), Some(new_cert))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = client
+        .get(format!("{}/api/agents/{}", base_url(addr), agent_id))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    let detail: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(detail["client_cert_required"].as_bool(), Some(true));
+    assert_eq!(
+        detail["client_cert_sha256"].as_str(),
+        rotated["client_cert"]["sha256"].as_str()
+    );
+
+    server.abort();
+}
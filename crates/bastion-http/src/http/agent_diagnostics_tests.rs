@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use futures_util::{SinkExt, StreamExt};
+use tempfile::TempDir;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+async fn insert_agent(pool: &sqlx::SqlitePool, agent_id: &str) -> (String, String) {
+    let agent_key = bastion_core::agent::generate_token_b64_urlsafe(32);
+    let hash = bastion_core::agent::sha256_urlsafe_token(&agent_key).expect("hash");
+    sqlx::query("INSERT INTO agents (id, name, key_hash, created_at) VALUES (?, NULL, ?, ?)")
+        .bind(agent_id)
+        .bind(hash)
+        .bind(1_i64)
+        .execute(pool)
+        .await
+        .expect("insert agent");
+    (agent_id.to_string(), agent_key)
+}
+
+async fn connect_agent_ws(
+    addr: std::net::SocketAddr,
+    agent_key: &str,
+) -> tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>> {
+    let mut req = format!("ws://{addr}/agent/ws")
+        .into_client_request()
+        .expect("ws request");
+    req.headers_mut().insert(
+        "authorization",
+        format!("Bearer {agent_key}")
+            .parse()
+            .expect("authorization"),
+    );
+
+    let (socket, _) = tokio_tungstenite::connect_async(req)
+        .await
+        .expect("ws connect");
+    socket
+}
+
+#[tokio::test]
+async fn agent_diagnostics_round_trip_through_agent_ws() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let (agent_id, agent_key) = insert_agent(&pool, "agent1").await;
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let diagnostics_url = format!("{}/api/agents/{}/diagnostics", base_url(addr), agent_id);
+
+    let resp = client
+        .post(&diagnostics_url)
+        .header("cookie", format!("bastion_session={}", session.id))
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("agent_offline"));
+
+    let resp = client
+        .post(&diagnostics_url)
+        .header("cookie", format!("bastion_session={}", session.id))
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({ "job_id": "missing" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("invalid_job_id"));
+
+    let mut socket = connect_agent_ws(addr, &agent_key).await;
+    let fake_agent = tokio::spawn(async move {
+        while let Some(Ok(msg)) = socket.next().await {
+            let WsMessage::Text(text) = msg else {
+                continue;
+            };
+            let value: serde_json::Value = serde_json::from_str(&text).expect("json");
+            if value["type"] != "diagnostics" {
+                continue;
+            }
+            let reply = serde_json::json!({
+                "type": "diagnostics_result",
+                "v": bastion_core::agent_protocol::PROTOCOL_VERSION,
+                "request_id": value["request_id"],
+                "report": {
+                    "collected_at": 1,
+                    "checks": [{
+                        "check": "staging_space",
+                        "subject": "/var/lib/bastion",
+                        "status": "ok",
+                        "available_bytes": 1024
+                    }]
+                }
+            });
+            socket
+                .send(WsMessage::Text(reply.to_string().into()))
+                .await
+                .expect("send reply");
+            return socket;
+        }
+        panic!("agent socket closed before diagnostics request");
+    });
+
+    let mut body = serde_json::Value::Null;
+    for _ in 0..50 {
+        let resp = client
+            .post(&diagnostics_url)
+            .header("cookie", format!("bastion_session={}", session.id))
+            .header("x-csrf-token", session.csrf_token.clone())
+            .json(&serde_json::json!({}))
+            .send()
+            .await
+            .expect("request");
+        if resp.status() == StatusCode::CONFLICT {
+            // The hub registers the socket asynchronously after the upgrade.
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            continue;
+        }
+        assert_eq!(resp.status(), StatusCode::OK);
+        body = resp.json().await.expect("json");
+        break;
+    }
+
+    assert_eq!(body["collected_at"].as_i64(), Some(1));
+    assert_eq!(body["checks"][0]["check"].as_str(), Some("staging_space"));
+    assert_eq!(body["checks"][0]["status"].as_str(), Some("ok"));
+
+    let _socket = fake_agent.await.expect("fake agent");
+    server.abort();
+}
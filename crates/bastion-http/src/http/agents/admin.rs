@@ -8,12 +8,16 @@ use tower_cookies::Cookies;
 use bastion_engine::agent_snapshots::{
     SendConfigSnapshotOutcome, send_node_config_snapshot_with_outcome,
 };
+use bastion_storage::agent_host_metrics_repo;
 use bastion_storage::agent_labels_repo;
 use bastion_storage::agents_repo;
 
 use super::super::shared::{require_csrf, require_session};
 use super::super::{AppError, AppState};
+use super::client_certs::{ClientCertBundle, issue_client_cert};
 use super::labels::{LabelsMode, normalize_labels, parse_labels_mode};
+use super::protocol::AgentProtocolStatus;
+use super::updates::AgentBuildInfo;
 
 #[derive(Debug, Serialize)]
 pub(in crate::http) struct AgentListItem {
@@ -29,6 +33,10 @@ pub(in crate::http) struct AgentListItem {
     last_config_sync_attempt_at: Option<i64>,
     last_config_sync_error_kind: Option<String>,
     last_config_sync_error: Option<String>,
+    version: Option<String>,
+    update_policy: String,
+    update_pinned_version: Option<String>,
+    last_update_offered_version: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -300,6 +308,7 @@ pub(in crate::http) async fn list_agents(
               a.id, a.name, a.revoked_at, a.last_seen_at,
               a.desired_config_snapshot_id, a.applied_config_snapshot_id,
               a.last_config_sync_attempt_at, a.last_config_sync_error_kind, a.last_config_sync_error,
+              a.capabilities_json, a.update_policy, a.update_pinned_version, a.last_update_offered_version,
               al.label
             FROM agents a
             LEFT JOIN agent_labels al ON al.agent_id = a.id
@@ -352,6 +361,14 @@ pub(in crate::http) async fn list_agents(
                         .get::<Option<i64>, _>("last_config_sync_attempt_at"),
                     last_config_sync_error_kind,
                     last_config_sync_error: row.get::<Option<String>, _>("last_config_sync_error"),
+                    version: AgentBuildInfo::from_hello_json(
+                        row.get::<Option<String>, _>("capabilities_json").as_deref(),
+                    )
+                    .version,
+                    update_policy: row.get::<String, _>("update_policy"),
+                    update_pinned_version: row.get::<Option<String>, _>("update_pinned_version"),
+                    last_update_offered_version: row
+                        .get::<Option<String>, _>("last_update_offered_version"),
                 });
                 current_id = Some(id);
             }
@@ -396,6 +413,20 @@ pub(in crate::http) struct AgentDetail {
     last_config_sync_error_kind: Option<String>,
     last_config_sync_error: Option<String>,
     last_config_sync_error_at: Option<i64>,
+
+    version: Option<String>,
+    update_policy: String,
+    update_pinned_version: Option<String>,
+    last_update_offered_version: Option<String>,
+    last_update_offered_at: Option<i64>,
+    /// Negotiated protocol and anything the agent and Hub can't do together.
+    protocol: Option<AgentProtocolStatus>,
+
+    host_metrics: Option<agent_host_metrics_repo::AgentHostMetrics>,
+
+    client_cert_sha256: Option<String>,
+    client_cert_expires_at: Option<i64>,
+    client_cert_required: bool,
 }
 
 pub(in crate::http) async fn get_agent(
@@ -412,7 +443,9 @@ pub(in crate::http) async fn get_agent(
           id, name, revoked_at, created_at, last_seen_at, capabilities_json,
           desired_config_snapshot_id, desired_config_snapshot_at,
           applied_config_snapshot_id, applied_config_snapshot_at,
-          last_config_sync_attempt_at, last_config_sync_error_kind, last_config_sync_error, last_config_sync_error_at
+          last_config_sync_attempt_at, last_config_sync_error_kind, last_config_sync_error, last_config_sync_error_at,
+          update_policy, update_pinned_version, last_update_offered_version, last_update_offered_at,
+          client_cert_sha256, client_cert_expires_at, client_cert_required
         FROM agents
         WHERE id = ?
         LIMIT 1
@@ -442,6 +475,10 @@ pub(in crate::http) async fn get_agent(
     .to_string();
 
     let labels = agent_labels_repo::list_labels_for_agent(&state.db, &agent_id).await?;
+    let capabilities_json = row.get::<Option<String>, _>("capabilities_json");
+    let build = AgentBuildInfo::from_hello_json(capabilities_json.as_deref());
+    let protocol = AgentProtocolStatus::from_hello_json(capabilities_json.as_deref());
+    let host_metrics = agent_host_metrics_repo::get_latest(&state.db, &agent_id).await?;
 
     Ok(Json(AgentDetail {
         id: row.get::<String, _>("id"),
@@ -450,7 +487,7 @@ pub(in crate::http) async fn get_agent(
         created_at: row.get::<i64, _>("created_at"),
         last_seen_at,
         online,
-        capabilities_json: row.get::<Option<String>, _>("capabilities_json"),
+        capabilities_json,
         labels,
         desired_config_snapshot_id,
         desired_config_snapshot_at: row.get::<Option<i64>, _>("desired_config_snapshot_at"),
@@ -461,6 +498,16 @@ pub(in crate::http) async fn get_agent(
         last_config_sync_error_kind,
         last_config_sync_error: row.get::<Option<String>, _>("last_config_sync_error"),
         last_config_sync_error_at: row.get::<Option<i64>, _>("last_config_sync_error_at"),
+        version: build.version,
+        update_policy: row.get::<String, _>("update_policy"),
+        update_pinned_version: row.get::<Option<String>, _>("update_pinned_version"),
+        last_update_offered_version: row.get::<Option<String>, _>("last_update_offered_version"),
+        last_update_offered_at: row.get::<Option<i64>, _>("last_update_offered_at"),
+        protocol,
+        host_metrics,
+        client_cert_sha256: row.get::<Option<String>, _>("client_cert_sha256"),
+        client_cert_expires_at: row.get::<Option<i64>, _>("client_cert_expires_at"),
+        client_cert_required: row.get::<i64, _>("client_cert_required") != 0,
     }))
 }
 
@@ -487,6 +534,7 @@ pub(in crate::http) async fn revoke_agent(
 pub(in crate::http) struct RotateAgentKeyResponse {
     agent_id: String,
     agent_key: String,
+    client_cert: ClientCertBundle,
 }
 
 pub(in crate::http) async fn rotate_agent_key(
@@ -502,9 +550,20 @@ pub(in crate::http) async fn rotate_agent_key(
         .await?
         .ok_or_else(|| AppError::not_found("agent_not_found", "Agent not found"))?;
 
+    // The previous certificate stops matching as soon as the new fingerprint is pinned.
+    let client_cert = issue_client_cert(&state.config.data_dir, &agent_id)?;
+    agents_repo::set_client_cert(
+        &state.db,
+        &agent_id,
+        &client_cert.sha256,
+        client_cert.expires_at,
+    )
+    .await?;
+
     Ok(Json(RotateAgentKeyResponse {
         agent_id,
         agent_key,
+        client_cert,
     }))
 }
 
@@ -1,9 +1,11 @@
 use axum::http::HeaderMap;
-use sqlx::{Row, SqlitePool};
+use sqlx::Row;
 
 use bastion_core::agent;
 
-use super::super::AppError;
+use super::super::shared::ClientConnInfo;
+use super::super::{AppError, AppState};
+use super::client_certs::presented_client_cert_sha256;
 
 fn bearer_token(headers: &HeaderMap) -> Option<String> {
     let header = headers.get("authorization")?.to_str().ok()?;
@@ -11,19 +13,24 @@ fn bearer_token(headers: &HeaderMap) -> Option<String> {
     Some(token.trim().to_string())
 }
 
+/// Authenticates an agent by bearer key and, when the agent requires it, by the pinned client
+/// certificate presented to the Hub's TLS listener or forwarded by a trusted proxy.
 pub(super) async fn authenticate_agent(
-    db: &SqlitePool,
+    state: &AppState,
     headers: &HeaderMap,
+    client: &ClientConnInfo,
 ) -> Result<String, AppError> {
     let agent_key = bearer_token(headers)
         .ok_or_else(|| AppError::unauthorized("unauthorized", "Unauthorized"))?;
     let key_hash = agent::sha256_urlsafe_token(&agent_key)
         .map_err(|_| AppError::unauthorized("unauthorized", "Unauthorized"))?;
 
-    let row = sqlx::query("SELECT id, revoked_at FROM agents WHERE key_hash = ? LIMIT 1")
-        .bind(key_hash)
-        .fetch_optional(db)
-        .await?;
+    let row = sqlx::query(
+        "SELECT id, revoked_at, client_cert_sha256, client_cert_expires_at, client_cert_required FROM agents WHERE key_hash = ? LIMIT 1",
+    )
+    .bind(key_hash)
+    .fetch_optional(&state.db)
+    .await?;
 
     let Some(row) = row else {
         return Err(AppError::unauthorized("unauthorized", "Unauthorized"));
@@ -32,5 +39,20 @@ pub(super) async fn authenticate_agent(
         return Err(AppError::unauthorized("revoked", "Agent revoked"));
     }
 
+    if row.get::<i64, _>("client_cert_required") != 0 {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let expected = row.get::<Option<String>, _>("client_cert_sha256");
+        let expired = row
+            .get::<Option<i64>, _>("client_cert_expires_at")
+            .is_some_and(|expires_at| expires_at <= now);
+        let presented = presented_client_cert_sha256(client, headers);
+        if expected.is_none() || expired || presented != expected {
+            return Err(AppError::unauthorized(
+                "client_cert_required",
+                "Valid client certificate required",
+            ));
+        }
+    }
+
     Ok(row.get::<String, _>("id"))
 }
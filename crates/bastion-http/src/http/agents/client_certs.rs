@@ -0,0 +1,276 @@
+use std::path::Path;
+
+use axum::Json;
+use axum::extract::Path as UrlPath;
+use axum::http::{HeaderMap, StatusCode};
+use base64::Engine as _;
+use percent_encoding::percent_decode_str;
+use rcgen::{
+    BasicConstraints, CertificateParams, DistinguishedName, DnType, ExtendedKeyUsagePurpose, IsCa,
+    KeyPair, KeyUsagePurpose,
+};
+use rustls_pki_types::CertificateDer;
+use rustls_pki_types::pem::PemObject as _;
+use serde::{Deserialize, Serialize};
+use sha2::Digest as _;
+use tower_cookies::Cookies;
+
+use bastion_storage::agents_repo;
+
+use super::super::shared::{ClientConnInfo, require_csrf, require_session};
+use super::super::{AppError, AppState};
+
+const AGENT_CA_DIR: &str = "agent-ca";
+const CA_CERT_FILE: &str = "ca.crt.pem";
+const CA_KEY_FILE: &str = "ca.key.pem";
+const CA_VALIDITY_DAYS: i64 = 3650;
+const CLIENT_CERT_VALIDITY_DAYS: i64 = 365;
+// Tolerate small clock differences between the Hub and agents/proxies.
+const CLIENT_CERT_BACKDATE_SECS: i64 = 5 * 60;
+
+/// Client certificate forwarded by a TLS-terminating proxy: URL-encoded PEM (nginx
+/// `$ssl_client_escaped_cert`) or base64 DER (Traefik-style).
+pub(in crate::http) const CLIENT_CERT_HEADER: &str = "x-client-cert";
+
+// Serializes CA creation so concurrent enrollments don't race on the key files.
+static CA_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// DER of the client certificate an agent presented in the Hub's own TLS handshake, already
+/// verified against the agent CA. The Hub's TLS listener adds it as a request extension.
+#[derive(Debug, Clone)]
+pub struct TlsPeerCertificate(pub Vec<u8>);
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct ClientCertBundle {
+    pub(in crate::http) cert_pem: String,
+    pub(in crate::http) key_pem: String,
+    pub(in crate::http) ca_pem: String,
+    pub(in crate::http) sha256: String,
+    pub(in crate::http) expires_at: i64,
+}
+
+struct AgentCa {
+    cert: rcgen::Certificate,
+    key: KeyPair,
+    cert_pem: String,
+}
+
+pub(in crate::http) fn agent_ca_cert_path(data_dir: &Path) -> std::path::PathBuf {
+    data_dir.join(AGENT_CA_DIR).join(CA_CERT_FILE)
+}
+
+fn write_private_file(path: &Path, contents: &str) -> Result<(), anyhow::Error> {
+    let tmp = path.with_extension("partial");
+    let _ = std::fs::remove_file(&tmp);
+    std::fs::write(&tmp, contents)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
+    }
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn load_or_create_ca(data_dir: &Path) -> Result<AgentCa, anyhow::Error> {
+    let _guard = CA_LOCK
+        .lock()
+        .map_err(|_| anyhow::anyhow!("agent CA lock poisoned"))?;
+
+    let dir = data_dir.join(AGENT_CA_DIR);
+    let cert_path = agent_ca_cert_path(data_dir);
+    let key_path = dir.join(CA_KEY_FILE);
+
+    if cert_path.exists() || key_path.exists() {
+        let cert_pem = std::fs::read_to_string(&cert_path)?;
+        let key = KeyPair::from_pem(&std::fs::read_to_string(&key_path)?)?;
+        // rcgen signs with an in-memory issuer; re-signing the stored params with the stored
+        // key yields the same subject and key identifier as the certificate on disk.
+        let cert = CertificateParams::from_ca_cert_pem(&cert_pem)?.self_signed(&key)?;
+        return Ok(AgentCa {
+            cert,
+            key,
+            cert_pem,
+        });
+    }
+
+    let now = time::OffsetDateTime::now_utc();
+    let mut params = CertificateParams::new(Vec::<String>::new())?;
+    params.distinguished_name = DistinguishedName::new();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "Bastion agent CA");
+    params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    params.not_before = now - time::Duration::seconds(CLIENT_CERT_BACKDATE_SECS);
+    params.not_after = now + time::Duration::days(CA_VALIDITY_DAYS);
+
+    let key = KeyPair::generate()?;
+    let cert = params.self_signed(&key)?;
+    let cert_pem = cert.pem();
+
+    std::fs::create_dir_all(&dir)?;
+    write_private_file(&key_path, &key.serialize_pem())?;
+    std::fs::write(&cert_path, &cert_pem)?;
+
+    Ok(AgentCa {
+        cert,
+        key,
+        cert_pem,
+    })
+}
+
+/// PEM of the agent CA, creating the CA if needed, so the Hub's TLS listener can verify agent
+/// client certificates.
+pub fn agent_ca_cert_pem(data_dir: &Path) -> Result<String, anyhow::Error> {
+    Ok(load_or_create_ca(data_dir)?.cert_pem)
+}
+
+fn sha256_hex(der: &[u8]) -> String {
+    hex::encode(sha2::Sha256::digest(der))
+}
+
+/// Issues a client certificate (CN = agent id) signed by the Hub's agent CA, creating the CA on
+/// first use.
+pub(in crate::http) fn issue_client_cert(
+    data_dir: &Path,
+    agent_id: &str,
+) -> Result<ClientCertBundle, anyhow::Error> {
+    let ca = load_or_create_ca(data_dir)?;
+
+    let now = time::OffsetDateTime::now_utc();
+    let not_after = now + time::Duration::days(CLIENT_CERT_VALIDITY_DAYS);
+    let mut params = CertificateParams::new(Vec::<String>::new())?;
+    params.distinguished_name = DistinguishedName::new();
+    params.distinguished_name.push(DnType::CommonName, agent_id);
+    params.is_ca = IsCa::ExplicitNoCa;
+    params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+    params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+    params.not_before = now - time::Duration::seconds(CLIENT_CERT_BACKDATE_SECS);
+    params.not_after = not_after;
+
+    let key = KeyPair::generate()?;
+    let cert = params.signed_by(&key, &ca.cert, &ca.key)?;
+
+    Ok(ClientCertBundle {
+        sha256: sha256_hex(cert.der()),
+        cert_pem: cert.pem(),
+        key_pem: key.serialize_pem(),
+        ca_pem: ca.cert_pem,
+        expires_at: not_after.unix_timestamp(),
+    })
+}
+
+fn client_cert_sha256_from_header(raw: &str) -> Option<String> {
+    let decoded = percent_decode_str(raw.trim()).decode_utf8().ok()?;
+    let decoded = decoded.trim();
+    if decoded.starts_with("-----BEGIN") {
+        let der = CertificateDer::from_pem_slice(decoded.as_bytes()).ok()?;
+        return Some(sha256_hex(der.as_ref()));
+    }
+    let der = base64::engine::general_purpose::STANDARD
+        .decode(decoded)
+        .ok()?;
+    Some(sha256_hex(&der))
+}
+
+/// SHA-256 fingerprint of the client certificate presented to the Hub's TLS listener or, failing
+/// that, to a trusted proxy.
+pub(in crate::http) fn presented_client_cert_sha256(
+    client: &ClientConnInfo,
+    headers: &HeaderMap,
+) -> Option<String> {
+    if let Some(der) = &client.tls_peer_cert {
+        return Some(sha256_hex(der));
+    }
+    if !client.trusted_proxy {
+        return None;
+    }
+    let raw = headers.get(CLIENT_CERT_HEADER)?.to_str().ok()?;
+    client_cert_sha256_from_header(raw)
+}
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct SetClientCertPolicyRequest {
+    required: bool,
+}
+
+pub(in crate::http) async fn set_agent_client_cert_policy(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    UrlPath(agent_id): UrlPath<String>,
+    Json(req): Json<SetClientCertPolicyRequest>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    let cert = agents_repo::get_client_cert(&state.db, &agent_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("agent_not_found", "Agent not found"))?;
+    if req.required && cert.sha256.is_none() {
+        return Err(AppError::conflict(
+            "client_cert_missing",
+            "Agent has no client certificate; rotate its key to issue one",
+        ));
+    }
+
+    agents_repo::set_client_cert_required(&state.db, &agent_id, req.required).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine as _;
+    use rustls_pki_types::CertificateDer;
+    use rustls_pki_types::pem::PemObject as _;
+
+    use super::{
+        agent_ca_cert_path, client_cert_sha256_from_header, issue_client_cert, sha256_hex,
+    };
+
+    #[test]
+    fn issued_certs_share_one_ca_and_have_distinct_fingerprints() {
+        let temp = tempfile::tempdir().expect("tempdir");
+
+        let first = issue_client_cert(temp.path(), "agent1").expect("issue 1");
+        let ca_pem = std::fs::read_to_string(agent_ca_cert_path(temp.path())).expect("ca");
+        assert_eq!(first.ca_pem, ca_pem);
+
+        let second = issue_client_cert(temp.path(), "agent1").expect("issue 2");
+        assert_eq!(second.ca_pem, ca_pem);
+        assert_ne!(first.sha256, second.sha256);
+
+        let der = CertificateDer::from_pem_slice(first.cert_pem.as_bytes()).expect("der");
+        assert_eq!(sha256_hex(der.as_ref()), first.sha256);
+    }
+
+    #[test]
+    fn forwarded_cert_accepts_escaped_pem_and_base64_der() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let issued = issue_client_cert(temp.path(), "agent1").expect("issue");
+
+        let escaped: String = percent_encoding::utf8_percent_encode(
+            &issued.cert_pem,
+            percent_encoding::NON_ALPHANUMERIC,
+        )
+        .to_string();
+        assert_eq!(
+            client_cert_sha256_from_header(&escaped).as_deref(),
+            Some(issued.sha256.as_str())
+        );
+
+        let der = CertificateDer::from_pem_slice(issued.cert_pem.as_bytes()).expect("der");
+        let b64 = base64::engine::general_purpose::STANDARD.encode(der.as_ref());
+        assert_eq!(
+            client_cert_sha256_from_header(&b64).as_deref(),
+            Some(issued.sha256.as_str())
+        );
+
+        assert_eq!(client_cert_sha256_from_header("not a cert!"), None);
+    }
+}
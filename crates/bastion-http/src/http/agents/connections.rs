@@ -0,0 +1,57 @@
+use axum::Json;
+use axum::extract::{Path, Query};
+use serde::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+use bastion_storage::agent_connections_repo::{self, AgentConnection};
+
+use super::super::shared::require_session;
+use super::super::{AppError, AppState};
+
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct ListAgentConnectionsQuery {
+    /// Only sessions still open at or after this unix timestamp.
+    #[serde(default)]
+    since: Option<i64>,
+    #[serde(default)]
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct ListAgentConnectionsResponse {
+    agent_id: String,
+    items: Vec<AgentConnection>,
+}
+
+pub(in crate::http) async fn list_agent_connections(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(agent_id): Path<String>,
+    Query(query): Query<ListAgentConnectionsQuery>,
+) -> Result<Json<ListAgentConnectionsResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(AppError::bad_request("invalid_limit", "Invalid limit")
+            .with_reason("out_of_range")
+            .with_field("limit")
+            .with_param("max", MAX_LIMIT));
+    }
+
+    let exists = sqlx::query_scalar::<_, i64>("SELECT 1 FROM agents WHERE id = ? LIMIT 1")
+        .bind(&agent_id)
+        .fetch_optional(&state.db)
+        .await?
+        .is_some();
+    if !exists {
+        return Err(AppError::not_found("agent_not_found", "Agent not found"));
+    }
+
+    let items =
+        agent_connections_repo::list_for_agent(&state.db, &agent_id, query.since, limit).await?;
+    Ok(Json(ListAgentConnectionsResponse { agent_id, items }))
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use axum::Json;
+use axum::extract::Path;
+use axum::http::HeaderMap;
+use serde::Deserialize;
+use tower_cookies::Cookies;
+
+use bastion_core::agent_protocol::DiagnosticsReportV1;
+use bastion_engine::agent_snapshots::send_node_config_snapshot_with_outcome;
+use bastion_storage::jobs_repo;
+
+use super::super::shared::{require_csrf, require_session};
+use super::super::{AppError, AppState};
+
+// Each WebDAV target probe may take up to 15s on the agent; jobs are checked one after another.
+const DIAGNOSTICS_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[derive(Debug, Default, Deserialize)]
+pub(in crate::http) struct RunAgentDiagnosticsRequest {
+    #[serde(default)]
+    job_id: Option<String>,
+}
+
+pub(in crate::http) async fn run_agent_diagnostics(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(agent_id): Path<String>,
+    Json(req): Json<RunAgentDiagnosticsRequest>,
+) -> Result<Json<DiagnosticsReportV1>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    let exists = sqlx::query("SELECT 1 FROM agents WHERE id = ? LIMIT 1")
+        .bind(&agent_id)
+        .fetch_optional(&state.db)
+        .await?;
+    if exists.is_none() {
+        return Err(AppError::not_found("agent_not_found", "Agent not found"));
+    }
+
+    let job_id = req
+        .job_id
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    if let Some(job_id) = job_id.as_deref() {
+        let job = jobs_repo::get_job(&state.db, job_id).await?;
+        if job.is_none_or(|job| job.agent_id.as_deref() != Some(agent_id.as_str())) {
+            return Err(AppError::bad_request(
+                "invalid_job_id",
+                "Job is not assigned to this agent",
+            )
+            .with_reason("not_found")
+            .with_field("job_id"));
+        }
+    }
+
+    if !state.agent_manager.is_connected(&agent_id).await {
+        return Err(AppError::conflict("agent_offline", "Agent is offline"));
+    }
+
+    // The agent applies config snapshots in order, so pushing one first means a job created
+    // moments ago is part of the config the checks run against.
+    send_node_config_snapshot_with_outcome(
+        &state.db,
+        &state.secrets,
+        &state.agent_manager,
+        &agent_id,
+    )
+    .await?;
+
+    let report = state
+        .agent_manager
+        .run_diagnostics(&agent_id, job_id, DIAGNOSTICS_TIMEOUT)
+        .await
+        .map_err(|error| {
+            AppError::bad_request(
+                "agent_diagnostics_failed",
+                format!("Agent diagnostics failed: {error}"),
+            )
+            .with_reason("transport_error")
+        })?;
+
+    Ok(Json(report))
+}
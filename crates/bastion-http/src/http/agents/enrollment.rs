@@ -9,12 +9,16 @@ use bastion_core::agent;
 
 use super::super::shared::{require_csrf, require_session};
 use super::super::{AppError, AppState};
+use super::client_certs::{ClientCertBundle, issue_client_cert};
 
 #[derive(Debug, Deserialize)]
 pub(in crate::http) struct CreateEnrollmentTokenRequest {
     #[serde(default = "default_enroll_ttl_seconds")]
     ttl_seconds: i64,
     remaining_uses: Option<i64>,
+    /// Agent name baked into the generated install command.
+    #[serde(default)]
+    name: Option<String>,
 }
 
 fn default_enroll_ttl_seconds() -> i64 {
@@ -26,6 +30,11 @@ pub(in crate::http) struct CreateEnrollmentTokenResponse {
     token: String,
     expires_at: i64,
     remaining_uses: Option<i64>,
+    /// The following are only set once a public base URL is configured.
+    hub_url: Option<String>,
+    install_command: Option<String>,
+    /// Compact JSON (`hub_url` + `token`) for rendering as a QR code.
+    qr_payload: Option<String>,
 }
 
 pub(in crate::http) async fn create_enrollment_token(
@@ -53,13 +62,50 @@ pub(in crate::http) async fn create_enrollment_token(
     .execute(&state.db)
     .await?;
 
+    let hub_url = state.hub_runtime_config.public_base_url.clone();
+    let name = req.name.as_deref().map(str::trim).filter(|v| !v.is_empty());
+    let install_command = hub_url
+        .as_deref()
+        .map(|hub_url| install_command(hub_url, &token, name));
+    let qr_payload = hub_url.as_deref().map(|hub_url| {
+        serde_json::json!({ "v": 1, "hub_url": hub_url, "token": token }).to_string()
+    });
+
     Ok(Json(CreateEnrollmentTokenResponse {
         token,
         expires_at,
         remaining_uses: req.remaining_uses,
+        hub_url,
+        install_command,
+        qr_payload,
     }))
 }
 
+fn install_command(hub_url: &str, token: &str, name: Option<&str>) -> String {
+    let mut command = format!(
+        "bastion agent --hub-url {} --enroll-token {}",
+        shell_quote(hub_url),
+        shell_quote(token)
+    );
+    if let Some(name) = name {
+        command.push_str(&format!(" --name {}", shell_quote(name)));
+    }
+    command
+}
+
+/// POSIX single-quoting, skipped for values made only of URL/token-safe characters.
+fn shell_quote(value: &str) -> String {
+    let plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.:/@%+=,".contains(c));
+    if plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub(in crate::http) struct AgentEnrollRequest {
     token: String,
@@ -70,6 +116,7 @@ pub(in crate::http) struct AgentEnrollRequest {
 pub(in crate::http) struct AgentEnrollResponse {
     agent_id: String,
     agent_key: String,
+    client_cert: ClientCertBundle,
 }
 
 pub(in crate::http) async fn agent_enroll(
@@ -137,19 +184,42 @@ pub(in crate::http) async fn agent_enroll(
     let agent_id = Uuid::new_v4().to_string();
     let agent_key = agent::generate_token_b64_urlsafe(32);
     let agent_key_hash = agent::sha256_urlsafe_token(&agent_key)?;
+    let client_cert = issue_client_cert(&state.config.data_dir, &agent_id)?;
 
-    sqlx::query("INSERT INTO agents (id, name, key_hash, created_at) VALUES (?, ?, ?, ?)")
-        .bind(&agent_id)
-        .bind(req.name)
-        .bind(agent_key_hash)
-        .bind(now)
-        .execute(&mut *tx)
-        .await?;
+    sqlx::query(
+        "INSERT INTO agents (id, name, key_hash, created_at, client_cert_sha256, client_cert_expires_at) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&agent_id)
+    .bind(req.name)
+    .bind(agent_key_hash)
+    .bind(now)
+    .bind(&client_cert.sha256)
+    .bind(client_cert.expires_at)
+    .execute(&mut *tx)
+    .await?;
 
     tx.commit().await?;
 
     Ok(Json(AgentEnrollResponse {
         agent_id,
         agent_key,
+        client_cert,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::install_command;
+
+    #[test]
+    fn install_command_quotes_only_when_needed() {
+        assert_eq!(
+            install_command("https://backup.example.com/", "abc-_123", None),
+            "bastion agent --hub-url https://backup.example.com/ --enroll-token abc-_123"
+        );
+        assert_eq!(
+            install_command("https://backup.example.com/", "abc", Some("Bob's NAS")),
+            "bastion agent --hub-url https://backup.example.com/ --enroll-token abc --name 'Bob'\\''s NAS'"
+        );
+    }
+}
@@ -1,12 +1,17 @@
+use axum::Extension;
 use axum::Json;
 use axum::http::{HeaderMap, StatusCode};
 use serde::Deserialize;
 use sqlx::Row;
 
 use bastion_core::job_spec;
+use bastion_core::run_failure::RunErrorCode;
 use bastion_engine::notifications;
-use bastion_storage::runs_repo;
+use bastion_engine::scheduler;
+use bastion_engine::webdav_rotation;
+use bastion_storage::{run_artifacts_repo, runs_repo};
 
+use super::super::shared::ClientConnInfo;
 use super::super::{AppError, AppState};
 use super::agent_auth::authenticate_agent;
 
@@ -49,8 +54,44 @@ struct AgentIngestRunEvent {
     fields: Option<serde_json::Value>,
 }
 
+/// Offline runs whose upload is still deferred report a placeholder target summary.
+fn summary_target_deferred(summary: Option<&serde_json::Value>) -> bool {
+    summary
+        .and_then(|v| v.get("target"))
+        .and_then(|v| v.get("upload_deferred"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Records the target snapshot and artifact row of a successful offline run, like online runs
+/// get when the Hub dispatches them. Existing snapshots are kept.
+async fn record_run_snapshot(
+    state: &AppState,
+    agent_id: &str,
+    run_id: &str,
+    spec: &job_spec::JobSpecV1,
+) {
+    let snapshot = match scheduler::build_run_target_snapshot(agent_id, spec) {
+        Ok(v) => v,
+        Err(error) => {
+            tracing::warn!(run_id = %run_id, error = %error, "failed to build target snapshot for ingested run");
+            return;
+        }
+    };
+    if let Err(error) = runs_repo::set_run_target_snapshot(&state.db, run_id, snapshot).await {
+        tracing::warn!(run_id = %run_id, error = %error, "failed to record target snapshot for ingested run");
+        return;
+    }
+    if let Err(error) =
+        run_artifacts_repo::upsert_run_artifact_from_successful_run(&state.db, run_id).await
+    {
+        tracing::warn!(run_id = %run_id, error = %error, "failed to record snapshot for ingested run");
+    }
+}
+
 pub(in crate::http) async fn agent_ingest_runs(
     state: axum::extract::State<AppState>,
+    Extension(client): Extension<ClientConnInfo>,
     headers: HeaderMap,
     Json(req): Json<AgentIngestRunRequest>,
 ) -> Result<StatusCode, AppError> {
@@ -73,7 +114,7 @@ pub(in crate::http) async fn agent_ingest_runs(
     const MAX_EVENT_MESSAGE_LEN: usize = 4096;
     const MAX_ERROR_LEN: usize = 4096;
 
-    let agent_id = authenticate_agent(&state.db, &headers).await?;
+    let agent_id = authenticate_agent(&state, &headers, &client).await?;
 
     let run = req.run;
     if run.id.trim().is_empty() {
@@ -146,10 +187,11 @@ pub(in crate::http) async fn agent_ingest_runs(
     }
     let spec_json = row.get::<String, _>("spec_json");
 
-    if let Some(row) = sqlx::query("SELECT job_id FROM runs WHERE id = ? LIMIT 1")
+    let existing = sqlx::query("SELECT job_id FROM runs WHERE id = ? LIMIT 1")
         .bind(&run.id)
         .fetch_optional(&state.db)
-        .await?
+        .await?;
+    if let Some(row) = &existing
         && row.get::<String, _>("job_id") != run.job_id
     {
         return Err(invalid_run_id_error(
@@ -164,6 +206,16 @@ pub(in crate::http) async fn agent_ingest_runs(
         AgentIngestRunStatus::Rejected => runs_repo::RunStatus::Rejected,
     };
 
+    // Offline runs from older agents carry free-form codes such as `run_failed`.
+    let error = match status {
+        runs_repo::RunStatus::Failed => run.error.as_deref().map(|code| {
+            RunErrorCode::from_code(code)
+                .unwrap_or(RunErrorCode::Unknown)
+                .as_str()
+        }),
+        _ => run.error.as_deref(),
+    };
+
     let summary_json = run
         .summary
         .as_ref()
@@ -191,7 +243,7 @@ pub(in crate::http) async fn agent_ingest_runs(
     .bind(run.started_at)
     .bind(run.ended_at)
     .bind(summary_json)
-    .bind(run.error.as_deref())
+    .bind(error)
     .execute(&mut *tx)
     .await?;
 
@@ -230,6 +282,18 @@ pub(in crate::http) async fn agent_ingest_runs(
 
     for ev in &inserted_events {
         state.run_events_bus.publish(ev);
+        if ev.kind.trim() == webdav_rotation::EVENT_CREDENTIAL_VERIFIED
+            && let Err(error) = webdav_rotation::promote_from_agent_event(
+                &state.db,
+                &state.secrets,
+                &state.agent_manager,
+                &agent_id,
+                ev.fields.as_ref(),
+            )
+            .await
+        {
+            tracing::warn!(agent_id = %agent_id, run_id = %run.id, error = %error, "failed to promote pending webdav credential");
+        }
     }
 
     // Enqueue notifications after ingestion (may be delayed while offline).
@@ -237,6 +301,11 @@ pub(in crate::http) async fn agent_ingest_runs(
         && let Ok(spec) = job_spec::parse_value(&spec_value)
         && job_spec::validate(&spec).is_ok()
     {
+        if status == runs_repo::RunStatus::Success && !summary_target_deferred(run.summary.as_ref())
+        {
+            record_run_snapshot(&state, &agent_id, &run.id, &spec).await;
+        }
+
         match notifications::enqueue_for_run_spec(&state.db, &spec, &run.id).await {
             Ok(true) => state.notifications_notify.notify_one(),
             Ok(false) => {}
@@ -244,6 +313,10 @@ pub(in crate::http) async fn agent_ingest_runs(
                 tracing::warn!(run_id = %run.id, error = %error, "failed to enqueue notifications for ingested run");
             }
         }
+        // Re-ingesting a run (agent retry) must not ping twice.
+        if existing.is_none() {
+            notifications::ping_run_end(&state.db, &state.run_events_bus, &spec, &run.id).await;
+        }
     }
 
     tracing::info!(
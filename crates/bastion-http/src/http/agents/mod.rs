@@ -1,13 +1,22 @@
 mod admin;
 mod agent_auth;
+mod client_certs;
+mod connections;
+mod diagnostics;
 mod enrollment;
 mod ingest;
 mod labels;
+mod protocol;
 mod snapshots;
 mod stage_events;
+mod updates;
 mod ws;
 
 pub(super) use admin::{get_agent, list_agents, revoke_agent, rotate_agent_key, sync_config_now};
+pub(super) use client_certs::set_agent_client_cert_policy;
+pub use client_certs::{TlsPeerCertificate, agent_ca_cert_pem};
+pub(super) use connections::list_agent_connections;
+pub(super) use diagnostics::run_agent_diagnostics;
 pub(super) use enrollment::{agent_enroll, create_enrollment_token};
 pub(super) use ingest::agent_ingest_runs;
 pub(in crate::http) use labels::{LabelsMode, normalize_labels, parse_labels_mode};
@@ -15,4 +24,5 @@ pub(super) use labels::{
     add_agent_labels, list_agent_labels_index, remove_agent_labels, set_agent_labels,
 };
 pub(super) use snapshots::send_node_config_snapshot;
+pub(super) use updates::{agent_release_download, set_agent_update_policy, trigger_agent_update};
 pub(super) use ws::agent_ws;
@@ -0,0 +1,156 @@
+//! Protocol compatibility between the Hub and an agent, derived from the agent's stored `hello`.
+
+use serde::Serialize;
+
+use bastion_core::agent_protocol::{PROTOCOL_REVISION, PROTOCOL_VERSION, ProtocolCapabilitiesV1};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(super) struct ProtocolIncompatibility {
+    code: &'static str,
+    message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(super) struct AgentProtocolStatus {
+    /// Message envelope version (`v`) the agent speaks.
+    message_version: u32,
+    /// Revision the agent advertised (1 when it doesn't negotiate).
+    agent_revision: u32,
+    hub_revision: u32,
+    /// Capabilities both sides support.
+    negotiated: ProtocolCapabilitiesV1,
+    incompatibilities: Vec<ProtocolIncompatibility>,
+}
+
+impl AgentProtocolStatus {
+    /// `None` until the agent has connected once.
+    pub(super) fn from_hello_json(raw: Option<&str>) -> Option<Self> {
+        let hello = serde_json::from_str::<serde_json::Value>(raw?).ok()?;
+        let message_version = u32::try_from(hello.get("v")?.as_u64()?).ok()?;
+        let advertised = hello
+            .get("protocol")
+            .filter(|v| !v.is_null())
+            .and_then(|v| serde_json::from_value::<ProtocolCapabilitiesV1>(v.clone()).ok());
+
+        let hub = ProtocolCapabilitiesV1::current();
+        let agent = advertised
+            .clone()
+            .unwrap_or_else(ProtocolCapabilitiesV1::legacy);
+        let negotiated = hub.intersect(&agent);
+
+        let mut incompatibilities = Vec::new();
+        if message_version != PROTOCOL_VERSION {
+            let upgrade = if message_version > PROTOCOL_VERSION {
+                "Hub"
+            } else {
+                "agent"
+            };
+            incompatibilities.push(ProtocolIncompatibility {
+                code: "message_version_mismatch",
+                message: format!(
+                    "Agent uses message version {message_version}, the Hub uses {PROTOCOL_VERSION}; their messages are ignored until the {upgrade} is upgraded"
+                ),
+            });
+        }
+        if advertised.is_none() {
+            let missing = missing(&hub.features, &negotiated.features);
+            incompatibilities.push(ProtocolIncompatibility {
+                code: "legacy_protocol",
+                message: format!(
+                    "Agent predates capability negotiation; upgrade it to enable: {}",
+                    missing.join(", ")
+                ),
+            });
+        } else if agent.revision > hub.revision {
+            incompatibilities.push(ProtocolIncompatibility {
+                code: "hub_outdated",
+                message: format!(
+                    "Agent speaks protocol revision {}, the Hub only {}; upgrade the Hub to use its newer features",
+                    agent.revision, hub.revision
+                ),
+            });
+        }
+        for task_type in missing(&hub.task_types, &negotiated.task_types) {
+            incompatibilities.push(ProtocolIncompatibility {
+                code: "task_type_unsupported",
+                message: format!("Agent does not run {task_type} tasks"),
+            });
+        }
+
+        Some(Self {
+            message_version,
+            agent_revision: agent.revision,
+            hub_revision: PROTOCOL_REVISION,
+            negotiated,
+            incompatibilities,
+        })
+    }
+}
+
+fn missing<'a>(all: &'a [String], negotiated: &[String]) -> Vec<&'a str> {
+    all.iter()
+        .filter(|v| !negotiated.contains(v))
+        .map(String::as_str)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bastion_core::agent_protocol::{
+        FEATURE_TASK_RESULT_ACK, PROTOCOL_REVISION, PROTOCOL_VERSION, ProtocolCapabilitiesV1,
+    };
+
+    use super::AgentProtocolStatus;
+
+    fn codes(status: &AgentProtocolStatus) -> Vec<&'static str> {
+        status.incompatibilities.iter().map(|i| i.code).collect()
+    }
+
+    #[test]
+    fn current_agents_have_no_incompatibilities() {
+        let hello = serde_json::json!({
+            "type": "hello",
+            "v": PROTOCOL_VERSION,
+            "agent_id": "a1",
+            "protocol": ProtocolCapabilitiesV1::current(),
+        });
+        let status = AgentProtocolStatus::from_hello_json(Some(&hello.to_string())).unwrap();
+        assert_eq!(status.agent_revision, PROTOCOL_REVISION);
+        assert_eq!(status.negotiated, ProtocolCapabilitiesV1::current());
+        assert!(status.incompatibilities.is_empty());
+    }
+
+    #[test]
+    fn legacy_and_mismatched_agents_are_reported() {
+        let legacy =
+            serde_json::json!({ "type": "hello", "v": PROTOCOL_VERSION, "agent_id": "a1" });
+        let status = AgentProtocolStatus::from_hello_json(Some(&legacy.to_string())).unwrap();
+        assert_eq!(status.agent_revision, 1);
+        assert!(!status.negotiated.has_feature(FEATURE_TASK_RESULT_ACK));
+        assert_eq!(codes(&status), ["legacy_protocol"]);
+
+        let newer = serde_json::json!({
+            "type": "hello",
+            "v": PROTOCOL_VERSION + 1,
+            "agent_id": "a1",
+            "protocol": {
+                "revision": PROTOCOL_REVISION + 1,
+                "task_types": ["backup"],
+                "features": [],
+                "compression": ["zstd"],
+            },
+        });
+        let status = AgentProtocolStatus::from_hello_json(Some(&newer.to_string())).unwrap();
+        assert_eq!(
+            codes(&status),
+            [
+                "message_version_mismatch",
+                "hub_outdated",
+                "task_type_unsupported",
+                "task_type_unsupported"
+            ]
+        );
+
+        assert!(AgentProtocolStatus::from_hello_json(None).is_none());
+    }
+}
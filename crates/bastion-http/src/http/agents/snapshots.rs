@@ -40,6 +40,12 @@ mod tests {
                 root: "/".to_string(),
                 include: vec![],
                 exclude: vec![],
+                exclude_presets: Vec::new(),
+                use_ignore_files: false,
+                max_file_size_bytes: None,
+                skip_older_than_secs: None,
+                skip_newer_than_secs: None,
+                anomaly_detection: None,
                 symlink_policy: Default::default(),
                 hardlink_policy: Default::default(),
                 error_policy: Default::default(),
@@ -48,10 +54,12 @@ mod tests {
                 consistency_policy: Default::default(),
                 consistency_fail_threshold: None,
                 upload_on_consistency_failure: None,
+                windows_system_state: false,
             },
             target: TargetResolvedV1::LocalDir {
                 base_dir: "/tmp".to_string(),
                 part_size_bytes: 1024,
+                link_unchanged: false,
             },
         };
 
@@ -0,0 +1,576 @@
+use std::path::{Path as FsPath, PathBuf};
+
+use axum::Extension;
+use axum::Json;
+use axum::body::Body;
+use axum::extract::Path;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use tokio_util::io::ReaderStream;
+use tower_cookies::Cookies;
+
+use bastion_core::agent_protocol::{
+    AgentUpdateV1, HubToAgentMessageV1, PROTOCOL_VERSION, parse_release_version,
+};
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::agents_repo::{
+    self, UPDATE_POLICY_AUTO, UPDATE_POLICY_MANUAL, UPDATE_POLICY_PINNED,
+};
+
+use super::super::shared::{ClientConnInfo, require_csrf, require_session};
+use super::super::{AppError, AppState};
+use super::agent_auth::authenticate_agent;
+
+/// Layout: `<data_dir>/agent-releases/<version>/<os>-<arch>/bastion[.exe]` plus a detached
+/// `bastion[.exe].sig` (base64 Ed25519 signature). Binaries are signed offline; the Hub only
+/// hosts them and never holds the signing key.
+const AGENT_RELEASES_DIR: &str = "agent-releases";
+const SIGNATURE_SUFFIX: &str = ".sig";
+
+/// Agents advertise this in `capabilities.control` when they have an update key configured.
+pub(in crate::http) const SELF_UPDATE_CAPABILITY: &str = "self_update";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AgentRelease {
+    version: String,
+    platform: String,
+    size: u64,
+    hash: String,
+    signature: String,
+}
+
+/// Version/platform facts extracted from the agent's stored Hello payload.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(in crate::http) struct AgentBuildInfo {
+    pub(in crate::http) version: Option<String>,
+    pub(in crate::http) platform: Option<String>,
+    pub(in crate::http) self_update: bool,
+}
+
+impl AgentBuildInfo {
+    pub(in crate::http) fn from_hello_json(raw: Option<&str>) -> Self {
+        let Some(hello) = raw.and_then(|v| serde_json::from_str::<serde_json::Value>(v).ok())
+        else {
+            return Self::default();
+        };
+
+        let info = &hello["info"];
+        let version = info["version"].as_str().map(str::to_string);
+        let platform = match (info["os"].as_str(), info["arch"].as_str()) {
+            (Some(os), Some(arch)) => Some(format!("{os}-{arch}")),
+            _ => None,
+        };
+        let self_update = hello["capabilities"]["control"]
+            .as_array()
+            .is_some_and(|v| v.iter().any(|c| c.as_str() == Some(SELF_UPDATE_CAPABILITY)));
+
+        Self {
+            version,
+            platform,
+            self_update,
+        }
+    }
+}
+
+fn is_safe_segment(value: &str) -> bool {
+    !value.is_empty()
+        && value != "."
+        && value != ".."
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+}
+
+fn binary_name(platform: &str) -> &'static str {
+    if platform.starts_with("windows-") {
+        "bastion.exe"
+    } else {
+        "bastion"
+    }
+}
+
+fn release_binary_path(data_dir: &FsPath, version: &str, platform: &str) -> Option<PathBuf> {
+    if !is_safe_segment(version) || !is_safe_segment(platform) {
+        return None;
+    }
+    Some(
+        data_dir
+            .join(AGENT_RELEASES_DIR)
+            .join(version)
+            .join(platform)
+            .join(binary_name(platform)),
+    )
+}
+
+async fn load_release(
+    data_dir: &FsPath,
+    version: &str,
+    platform: &str,
+) -> Result<Option<AgentRelease>, anyhow::Error> {
+    let Some(path) = release_binary_path(data_dir, version, platform) else {
+        return Ok(None);
+    };
+    let mut sig_path = path.clone().into_os_string();
+    sig_path.push(SIGNATURE_SUFFIX);
+
+    let signature = match tokio::fs::read_to_string(&sig_path).await {
+        Ok(v) => v.trim().to_string(),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    let size = match tokio::fs::metadata(&path).await {
+        Ok(meta) if meta.is_file() => meta.len(),
+        Ok(_) => return Ok(None),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+
+    let hash = tokio::task::spawn_blocking(move || -> Result<String, anyhow::Error> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update_reader(std::fs::File::open(path)?)?;
+        Ok(hasher.finalize().to_hex().to_string())
+    })
+    .await??;
+
+    Ok(Some(AgentRelease {
+        version: version.to_string(),
+        platform: platform.to_string(),
+        size,
+        hash,
+        signature,
+    }))
+}
+
+/// Newest hosted version that ships a signed binary for `platform`.
+async fn latest_release_version(
+    data_dir: &FsPath,
+    platform: &str,
+) -> Result<Option<String>, anyhow::Error> {
+    let mut entries = match tokio::fs::read_dir(data_dir.join(AGENT_RELEASES_DIR)).await {
+        Ok(v) => v,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+
+    let mut best: Option<((u64, u64, u64, bool), String)> = None;
+    while let Some(entry) = entries.next_entry().await? {
+        let Ok(version) = entry.file_name().into_string() else {
+            continue;
+        };
+        let Some(parsed) = parse_release_version(&version) else {
+            continue;
+        };
+        if best.as_ref().is_some_and(|(b, _)| *b >= parsed) {
+            continue;
+        }
+        let Some(binary) = release_binary_path(data_dir, &version, platform) else {
+            continue;
+        };
+        let mut sig = binary.clone().into_os_string();
+        sig.push(SIGNATURE_SUFFIX);
+        if tokio::fs::try_exists(&binary).await? && tokio::fs::try_exists(&sig).await? {
+            best = Some((parsed, version));
+        }
+    }
+
+    Ok(best.map(|(_, v)| v))
+}
+
+/// Which version (if any) the Hub should offer an agent on its own initiative.
+fn select_offer_version(
+    policy: &str,
+    pinned_version: Option<&str>,
+    current_version: &str,
+    latest_version: Option<&str>,
+) -> Option<String> {
+    match policy {
+        UPDATE_POLICY_AUTO => {
+            let latest = latest_version?;
+            let newer = parse_release_version(latest)? > parse_release_version(current_version)?;
+            newer.then(|| latest.to_string())
+        }
+        UPDATE_POLICY_PINNED => {
+            let pinned = pinned_version?;
+            (pinned != current_version).then(|| pinned.to_string())
+        }
+        _ => None,
+    }
+}
+
+async fn send_update_offer(
+    db: &SqlitePool,
+    agent_manager: &AgentManager,
+    agent_id: &str,
+    release: &AgentRelease,
+) -> Result<(), anyhow::Error> {
+    let msg = HubToAgentMessageV1::UpdateAvailable {
+        v: PROTOCOL_VERSION,
+        update: AgentUpdateV1 {
+            version: release.version.clone(),
+            platform: release.platform.clone(),
+            url: format!("/agent/releases/{}/{}", release.version, release.platform),
+            size: release.size,
+            hash: release.hash.clone(),
+            signature: release.signature.clone(),
+        },
+    };
+    agent_manager.send_json(agent_id, &msg).await?;
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    agents_repo::record_update_offered(db, agent_id, &release.version, now).await?;
+    tracing::info!(
+        agent_id = %agent_id,
+        version = %release.version,
+        platform = %release.platform,
+        "agent update offered"
+    );
+    Ok(())
+}
+
+/// Offers an update to a freshly connected agent when its policy calls for one.
+pub(in crate::http) async fn offer_update_if_due(
+    db: &SqlitePool,
+    agent_manager: &AgentManager,
+    data_dir: &FsPath,
+    agent_id: &str,
+    build: &AgentBuildInfo,
+) -> Result<(), anyhow::Error> {
+    if !build.self_update {
+        return Ok(());
+    }
+    let (Some(current), Some(platform)) = (build.version.as_deref(), build.platform.as_deref())
+    else {
+        return Ok(());
+    };
+    let Some(policy) = agents_repo::get_update_policy(db, agent_id).await? else {
+        return Ok(());
+    };
+    if policy.policy == UPDATE_POLICY_MANUAL {
+        return Ok(());
+    }
+
+    let latest = if policy.policy == UPDATE_POLICY_AUTO {
+        latest_release_version(data_dir, platform).await?
+    } else {
+        None
+    };
+    let Some(version) = select_offer_version(
+        &policy.policy,
+        policy.pinned_version.as_deref(),
+        current,
+        latest.as_deref(),
+    ) else {
+        return Ok(());
+    };
+
+    match load_release(data_dir, &version, platform).await? {
+        Some(release) => send_update_offer(db, agent_manager, agent_id, &release).await,
+        None => {
+            tracing::warn!(
+                agent_id = %agent_id,
+                version = %version,
+                platform = %platform,
+                "no signed agent release hosted for the requested version"
+            );
+            Ok(())
+        }
+    }
+}
+
+async fn load_agent_build_info(
+    db: &SqlitePool,
+    agent_id: &str,
+) -> Result<Option<AgentBuildInfo>, AppError> {
+    let row = sqlx::query("SELECT capabilities_json FROM agents WHERE id = ? LIMIT 1")
+        .bind(agent_id)
+        .fetch_optional(db)
+        .await?;
+    Ok(row.map(|row| {
+        AgentBuildInfo::from_hello_json(
+            row.get::<Option<String>, _>("capabilities_json").as_deref(),
+        )
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct SetAgentUpdatePolicyRequest {
+    policy: String,
+    #[serde(default)]
+    pinned_version: Option<String>,
+}
+
+pub(in crate::http) async fn set_agent_update_policy(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(agent_id): Path<String>,
+    Json(req): Json<SetAgentUpdatePolicyRequest>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    let policy = req.policy.trim();
+    let pinned_version = match policy {
+        UPDATE_POLICY_AUTO | UPDATE_POLICY_MANUAL => None,
+        UPDATE_POLICY_PINNED => {
+            let pinned = req
+                .pinned_version
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| parse_release_version(v).is_some() && is_safe_segment(v))
+                .ok_or_else(|| {
+                    AppError::bad_request("invalid_pinned_version", "Invalid pinned version")
+                        .with_reason("invalid_format")
+                        .with_field("pinned_version")
+                })?;
+            Some(pinned)
+        }
+        _ => {
+            return Err(
+                AppError::bad_request("invalid_update_policy", "Invalid update policy")
+                    .with_reason("unsupported_value")
+                    .with_field("policy"),
+            );
+        }
+    };
+
+    if !agents_repo::set_update_policy(&state.db, &agent_id, policy, pinned_version).await? {
+        return Err(AppError::not_found("agent_not_found", "Agent not found"));
+    }
+
+    if state.agent_manager.is_connected(&agent_id).await
+        && let Some(build) = load_agent_build_info(&state.db, &agent_id).await?
+        && let Err(error) = offer_update_if_due(
+            &state.db,
+            &state.agent_manager,
+            &state.config.data_dir,
+            &agent_id,
+            &build,
+        )
+        .await
+    {
+        tracing::warn!(agent_id = %agent_id, error = %error, "failed to offer agent update");
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct TriggerAgentUpdateRequest {
+    /// Defaults to the newest hosted release for the agent's platform.
+    #[serde(default)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct TriggerAgentUpdateResponse {
+    outcome: String,
+    version: String,
+}
+
+pub(in crate::http) async fn trigger_agent_update(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(agent_id): Path<String>,
+    Json(req): Json<TriggerAgentUpdateRequest>,
+) -> Result<Json<TriggerAgentUpdateResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    let build = load_agent_build_info(&state.db, &agent_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("agent_not_found", "Agent not found"))?;
+    if !state.agent_manager.is_connected(&agent_id).await {
+        return Err(AppError::conflict("agent_offline", "Agent is offline"));
+    }
+    let (Some(current), Some(platform)) = (build.version.as_deref(), build.platform.as_deref())
+    else {
+        return Err(AppError::conflict(
+            "agent_platform_unknown",
+            "Agent has not reported its version and platform yet",
+        ));
+    };
+    if !build.self_update {
+        return Err(AppError::conflict(
+            "agent_self_update_unsupported",
+            "Agent has no update signing key configured",
+        ));
+    }
+
+    let version = match req
+        .version
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        Some(v) => v.to_string(),
+        None => latest_release_version(&state.config.data_dir, platform)
+            .await?
+            .ok_or_else(|| {
+                AppError::not_found("agent_release_not_found", "No agent release is hosted")
+            })?,
+    };
+    if version == current {
+        return Ok(Json(TriggerAgentUpdateResponse {
+            outcome: "up_to_date".to_string(),
+            version,
+        }));
+    }
+
+    let release = load_release(&state.config.data_dir, &version, platform)
+        .await?
+        .ok_or_else(|| {
+            AppError::not_found("agent_release_not_found", "Agent release not found")
+                .with_field("version")
+        })?;
+    send_update_offer(&state.db, &state.agent_manager, &agent_id, &release).await?;
+
+    Ok(Json(TriggerAgentUpdateResponse {
+        outcome: "sent".to_string(),
+        version,
+    }))
+}
+
+/// Agent-authenticated download of a hosted release binary.
+pub(in crate::http) async fn agent_release_download(
+    state: axum::extract::State<AppState>,
+    Extension(client): Extension<ClientConnInfo>,
+    headers: HeaderMap,
+    Path((version, platform)): Path<(String, String)>,
+) -> Result<Response, AppError> {
+    let agent_id = authenticate_agent(&state, &headers, &client).await?;
+
+    let path = release_binary_path(&state.config.data_dir, &version, &platform)
+        .ok_or_else(|| AppError::not_found("agent_release_not_found", "Agent release not found"))?;
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Err(AppError::not_found(
+                "agent_release_not_found",
+                "Agent release not found",
+            ));
+        }
+        Err(error) => return Err(error.into()),
+    };
+    let len = file.metadata().await?.len();
+    tracing::info!(agent_id = %agent_id, version = %version, platform = %platform, "agent release download");
+
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/octet-stream")
+        .header(header::CONTENT_LENGTH, len.to_string())
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from_stream(ReaderStream::new(file)))?;
+    Ok(resp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AgentBuildInfo, UPDATE_POLICY_AUTO, UPDATE_POLICY_MANUAL, UPDATE_POLICY_PINNED,
+        latest_release_version, load_release, select_offer_version,
+    };
+
+    #[test]
+    fn offer_follows_policy() {
+        assert_eq!(
+            select_offer_version(UPDATE_POLICY_AUTO, None, "0.1.0", Some("0.2.0")).as_deref(),
+            Some("0.2.0")
+        );
+        assert_eq!(
+            select_offer_version(UPDATE_POLICY_AUTO, None, "0.2.0", Some("0.1.0")),
+            None
+        );
+        assert_eq!(
+            select_offer_version(UPDATE_POLICY_MANUAL, None, "0.1.0", Some("0.2.0")),
+            None
+        );
+        // Pinning may also roll an agent back.
+        assert_eq!(
+            select_offer_version(UPDATE_POLICY_PINNED, Some("0.1.0"), "0.2.0", None).as_deref(),
+            Some("0.1.0")
+        );
+        assert_eq!(
+            select_offer_version(UPDATE_POLICY_PINNED, Some("0.2.0"), "0.2.0", None),
+            None
+        );
+    }
+
+    #[test]
+    fn build_info_reads_hello_payload() {
+        let hello = serde_json::json!({
+            "type": "hello",
+            "v": 1,
+            "agent_id": "a",
+            "info": { "version": "0.1.0", "os": "linux", "arch": "x86_64" },
+            "capabilities": { "control": ["fs_list", "self_update"] },
+        })
+        .to_string();
+
+        let info = AgentBuildInfo::from_hello_json(Some(&hello));
+        assert_eq!(info.version.as_deref(), Some("0.1.0"));
+        assert_eq!(info.platform.as_deref(), Some("linux-x86_64"));
+        assert!(info.self_update);
+        assert_eq!(
+            AgentBuildInfo::from_hello_json(None),
+            AgentBuildInfo::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn releases_require_a_signature_and_pick_the_newest() {
+        let temp = tempfile::TempDir::new().expect("tempdir");
+        let write_release = |version: &str, signed: bool| {
+            let dir = temp
+                .path()
+                .join("agent-releases")
+                .join(version)
+                .join("linux-x86_64");
+            std::fs::create_dir_all(&dir).expect("mkdir");
+            std::fs::write(dir.join("bastion"), version.as_bytes()).expect("write");
+            if signed {
+                std::fs::write(dir.join("bastion.sig"), "c2ln\n").expect("write sig");
+            }
+        };
+        write_release("0.1.0", true);
+        write_release("0.3.0", false);
+        write_release("0.2.0", true);
+
+        assert_eq!(
+            latest_release_version(temp.path(), "linux-x86_64")
+                .await
+                .expect("latest")
+                .as_deref(),
+            Some("0.2.0")
+        );
+        assert_eq!(
+            latest_release_version(temp.path(), "windows-x86_64")
+                .await
+                .expect("latest"),
+            None
+        );
+
+        let release = load_release(temp.path(), "0.2.0", "linux-x86_64")
+            .await
+            .expect("load")
+            .expect("release");
+        assert_eq!(release.size, 5);
+        assert_eq!(release.signature, "c2ln");
+        assert_eq!(release.hash, blake3::hash(b"0.2.0").to_hex().to_string());
+        assert!(
+            load_release(temp.path(), "0.3.0", "linux-x86_64")
+                .await
+                .expect("load")
+                .is_none()
+        );
+        assert!(
+            load_release(temp.path(), "..", "linux-x86_64")
+                .await
+                .expect("load")
+                .is_none()
+        );
+    }
+}
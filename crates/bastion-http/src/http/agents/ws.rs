@@ -4,9 +4,9 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
-use axum::extract::ConnectInfo;
+use axum::Extension;
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
-use axum::http::HeaderMap;
+use axum::http::{HeaderMap, HeaderValue};
 use axum::response::Response;
 use futures_util::{SinkExt, StreamExt};
 use sqlx::SqlitePool;
@@ -14,29 +14,39 @@ use tokio::sync::Notify;
 use uuid::Uuid;
 
 use bastion_core::agent_protocol::{
-    AgentToHubMessageV1, ArtifactStreamOpenResultV1, HubToAgentMessageV1, PROTOCOL_VERSION,
+    AgentToHubMessageV1, ArtifactStreamOpenResultV1, FEATURE_TASK_RESULT_ACK, HostMetricsV1,
+    HubToAgentMessageV1, MessageEnvelopeV1, PROTOCOL_VERSION, ProtocolCapabilitiesV1,
 };
 use bastion_core::agent_stream::{
-    ArtifactChunkFrameV1Flags, decode_artifact_chunk_frame_v1, encode_artifact_chunk_frame_v1,
+    ArtifactChunkFrameV1Flags, WS_COMPRESSION_HEADER, WS_COMPRESSION_ZSTD,
+    decode_artifact_chunk_frame_v1, decode_compressed_text_frame_v1,
+    encode_artifact_chunk_frame_v1, encode_compressed_text_frame_v1, ws_compression_offers_zstd,
 };
 use bastion_core::error_envelope::{
     ErrorEnvelopeV1, ErrorOriginV1, ErrorRetriableV1, ErrorTransportV1, LocalizedTextRefV1,
 };
+use bastion_core::run_failure::RunErrorCode;
 use bastion_engine::agent_manager::AgentManager;
 use bastion_engine::run_events;
 use bastion_engine::run_events_bus::RunEventsBus;
+use bastion_engine::webdav_rotation;
+use bastion_storage::agent_connections_repo;
+use bastion_storage::agent_host_metrics_repo;
 use bastion_storage::agent_tasks_repo;
 use bastion_storage::agents_repo;
 use bastion_storage::artifact_delete_repo;
 use bastion_storage::operations_repo;
 use bastion_storage::run_artifacts_repo;
+use bastion_storage::run_logs_repo;
 use bastion_storage::runs_repo;
 use bastion_storage::secrets::SecretsCrypto;
 
+use super::super::shared::ClientConnInfo;
 use super::super::{AppError, AppState};
 use super::agent_auth::authenticate_agent;
 use super::snapshots::{send_node_config_snapshot, send_node_secrets_snapshot};
 use super::stage_events;
+use super::updates::{AgentBuildInfo, offer_update_if_due};
 
 mod artifact_stream;
 mod artifact_stream_authz;
@@ -52,10 +62,10 @@ const ARTIFACT_STREAM_AUTH_ERROR: &str = "artifact stream authorization failed";
 pub(in crate::http) async fn agent_ws(
     state: axum::extract::State<AppState>,
     headers: HeaderMap,
-    ConnectInfo(peer): ConnectInfo<std::net::SocketAddr>,
+    Extension(client): Extension<ClientConnInfo>,
     ws: WebSocketUpgrade,
 ) -> Result<Response, AppError> {
-    let agent_id = authenticate_agent(&state.db, &headers).await?;
+    let agent_id = authenticate_agent(&state, &headers, &client).await?;
 
     let data_dir = state.config.data_dir.clone();
     let db = state.db.clone();
@@ -63,32 +73,47 @@ pub(in crate::http) async fn agent_ws(
     let agent_manager = state.agent_manager.clone();
     let run_events_bus = state.run_events_bus.clone();
     let artifact_delete_notify = state.artifact_delete_notify.clone();
-    Ok(ws.on_upgrade(move |socket| {
+    let compress = ws_compression_offers_zstd(
+        headers
+            .get(WS_COMPRESSION_HEADER)
+            .and_then(|v| v.to_str().ok()),
+    );
+    let mut response = ws.on_upgrade(move |socket| {
         handle_agent_socket(
             AgentSocketContext {
                 data_dir,
                 db,
                 agent_id,
-                peer_ip: peer.ip(),
+                client_ip: client.ip,
                 secrets,
                 agent_manager,
                 run_events_bus,
                 artifact_delete_notify,
+                compress,
             },
             socket,
         )
-    }))
+    });
+    if compress {
+        response.headers_mut().insert(
+            WS_COMPRESSION_HEADER,
+            HeaderValue::from_static(WS_COMPRESSION_ZSTD),
+        );
+    }
+    Ok(response)
 }
 
 struct AgentSocketContext {
     data_dir: PathBuf,
     db: SqlitePool,
     agent_id: String,
-    peer_ip: std::net::IpAddr,
+    client_ip: std::net::IpAddr,
     secrets: Arc<SecretsCrypto>,
     agent_manager: AgentManager,
     run_events_bus: Arc<RunEventsBus>,
     artifact_delete_notify: Arc<Notify>,
+    /// Agent negotiated zstd-compressed JSON messages.
+    compress: bool,
 }
 
 async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
@@ -96,11 +121,12 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
         data_dir,
         db,
         agent_id,
-        peer_ip,
+        client_ip,
         secrets,
         agent_manager,
         run_events_bus,
         artifact_delete_notify,
+        compress,
     } = ctx;
     let now = time::OffsetDateTime::now_utc().unix_timestamp();
     if let Err(error) = sqlx::query("UPDATE agents SET last_seen_at = ? WHERE id = ?")
@@ -113,7 +139,22 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
     }
     let mut last_seen_persisted_at = now;
 
-    tracing::info!(agent_id = %agent_id, peer_ip = %peer_ip, "agent connected");
+    let connection_id = match agent_connections_repo::record_connected(
+        &db,
+        &agent_id,
+        Some(&client_ip.to_string()),
+        now,
+    )
+    .await
+    {
+        Ok(id) => Some(id),
+        Err(error) => {
+            tracing::warn!(agent_id = %agent_id, error = %error, "failed to record agent connection");
+            None
+        }
+    };
+
+    tracing::info!(agent_id = %agent_id, client_ip = %client_ip, "agent connected");
 
     let (mut sender, mut receiver) = socket.split();
     let (tx, mut rx) = tokio::sync::mpsc::channel::<Message>(AGENT_WS_OUTBOX_CAPACITY);
@@ -123,6 +164,9 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
     // Keyed by stream_id (UUID).
     let mut hub_streams: HashMap<Uuid, artifact_stream::HubArtifactStream> = HashMap::new();
 
+    // Raw task log streams pushed by this agent (stream_id -> run_id).
+    let mut run_log_streams: HashMap<Uuid, String> = HashMap::new();
+
     // Best-effort stage tracking for progress snapshots (run_id -> last stage kind).
     let mut run_stage_cache: HashMap<String, String> = HashMap::new();
 
@@ -185,13 +229,39 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
     let agent_id_send = agent_id.clone();
     let send_task = tokio::spawn(async move {
         while let Some(msg) = rx.recv().await {
+            let msg = match msg {
+                Message::Text(text) if compress => match encode_compressed_text_frame_v1(&text) {
+                    Some(frame) => Message::Binary(frame.into()),
+                    None => Message::Text(text),
+                },
+                other => other,
+            };
             if sender.send(msg).await.is_err() {
                 break;
             }
         }
     });
 
+    let mut disconnect_reason = agent_connections_repo::DISCONNECT_REASON_CONNECTION_LOST;
+    // Until the agent's hello says otherwise, assume a revision 1 agent.
+    let mut negotiated = ProtocolCapabilitiesV1::legacy();
+    let mut version_mismatch_logged = false;
     while let Some(Ok(msg)) = receiver.next().await {
+        let msg = match msg {
+            Message::Binary(bytes) => match decode_compressed_text_frame_v1(&bytes) {
+                Ok(Some(text)) => Message::Text(text.into()),
+                Ok(None) => Message::Binary(bytes),
+                Err(error) => {
+                    tracing::warn!(
+                        agent_id = %agent_id,
+                        error = %error,
+                        "invalid binary frame from agent"
+                    );
+                    continue;
+                }
+            },
+            other => other,
+        };
         match msg {
             Message::Text(text) => {
                 let text = text.to_string();
@@ -215,13 +285,66 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
                     }
                 }
 
+                if let Ok(envelope) = serde_json::from_str::<MessageEnvelopeV1>(&text)
+                    && envelope.v != PROTOCOL_VERSION
+                {
+                    if !version_mismatch_logged {
+                        version_mismatch_logged = true;
+                        tracing::warn!(
+                            agent_id = %agent_id,
+                            agent_version = envelope.v,
+                            hub_version = PROTOCOL_VERSION,
+                            kind = %envelope.kind,
+                            "ignoring agent messages with a different protocol version"
+                        );
+                    }
+                    // Keep the hello so `/api/agents/{id}` can report the mismatch.
+                    if envelope.kind == "hello" {
+                        let _ = sqlx::query(
+                            "UPDATE agents SET capabilities_json = ?, last_seen_at = ? WHERE id = ?",
+                        )
+                        .bind(&text)
+                        .bind(now)
+                        .bind(&agent_id)
+                        .execute(&db)
+                        .await;
+                    }
+                    continue;
+                }
+
                 match serde_json::from_str::<AgentToHubMessageV1>(&text) {
-                    Ok(AgentToHubMessageV1::Ping { v }) if v == PROTOCOL_VERSION => {
+                    Ok(AgentToHubMessageV1::Ping { v, metrics }) if v == PROTOCOL_VERSION => {
+                        if let Some(metrics) = metrics {
+                            store_host_metrics(&db, &agent_id, &metrics, now).await;
+                        }
                         let _ = agent_manager
                             .send_json(&agent_id, &HubToAgentMessageV1::Pong { v })
                             .await;
                     }
-                    Ok(AgentToHubMessageV1::Hello { v, .. }) if v == PROTOCOL_VERSION => {
+                    Ok(AgentToHubMessageV1::Hello {
+                        v,
+                        metrics,
+                        protocol,
+                        ..
+                    }) if v == PROTOCOL_VERSION => {
+                        negotiated = ProtocolCapabilitiesV1::current().intersect(
+                            protocol
+                                .as_ref()
+                                .unwrap_or(&ProtocolCapabilitiesV1::legacy()),
+                        );
+                        // Revision 1 agents don't know `hello_ack` and would only log it.
+                        if protocol.is_some() {
+                            let _ = agent_manager
+                                .send_json(
+                                    &agent_id,
+                                    &HubToAgentMessageV1::HelloAck {
+                                        v,
+                                        protocol: negotiated.clone(),
+                                    },
+                                )
+                                .await;
+                        }
+
                         // Store full hello payload for debugging/capabilities display.
                         let _ = sqlx::query(
                             "UPDATE agents SET capabilities_json = ?, last_seen_at = ? WHERE id = ?",
@@ -231,6 +354,9 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
                         .bind(&agent_id)
                         .execute(&db)
                         .await;
+                        if let Some(metrics) = metrics {
+                            store_host_metrics(&db, &agent_id, &metrics, now).await;
+                        }
 
                         if let Err(error) =
                             send_node_secrets_snapshot(&db, &secrets, &agent_manager, &agent_id)
@@ -253,6 +379,18 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
                                 "failed to send config snapshot"
                             );
                         }
+
+                        let build = AgentBuildInfo::from_hello_json(Some(&text));
+                        if let Err(error) =
+                            offer_update_if_due(&db, &agent_manager, &data_dir, &agent_id, &build)
+                                .await
+                        {
+                            tracing::warn!(
+                                agent_id = %agent_id,
+                                error = %error,
+                                "failed to offer agent update"
+                            );
+                        }
                     }
                     Ok(AgentToHubMessageV1::ConfigAck { v, snapshot_id })
                         if v == PROTOCOL_VERSION =>
@@ -301,6 +439,24 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
                             continue;
                         }
 
+                        if kind.trim() == webdav_rotation::EVENT_CREDENTIAL_VERIFIED
+                            && let Err(error) = webdav_rotation::promote_from_agent_event(
+                                &db,
+                                &secrets,
+                                &agent_manager,
+                                &agent_id,
+                                fields.as_ref(),
+                            )
+                            .await
+                        {
+                            tracing::warn!(
+                                agent_id = %agent_id,
+                                run_id = %run_id,
+                                error = %error,
+                                "failed to promote pending webdav credential"
+                            );
+                        }
+
                         let _ = run_events::append_and_broadcast(
                             &db,
                             &run_events_bus,
@@ -320,8 +476,30 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
                         summary,
                         error,
                     }) if v == PROTOCOL_VERSION => {
+                        // Agents replay cached results after a reconnect; only the first one counts.
+                        let delivery = agent_tasks_repo::record_task_result(
+                            &db,
+                            &task_id,
+                            summary.as_ref(),
+                            error.as_deref(),
+                        )
+                        .await;
+                        let duplicate = matches!(
+                            delivery,
+                            Ok(agent_tasks_repo::TaskResultDelivery::Duplicate)
+                        );
+                        if duplicate {
+                            tracing::debug!(
+                                agent_id = %agent_id,
+                                task_id = %task_id,
+                                run_id = %run_id,
+                                "ignoring replayed task result"
+                            );
+                        }
+
                         let run = runs_repo::get_run(&db, &run_id).await.ok().flatten();
-                        if let Some(run) = run
+                        if !duplicate
+                            && let Some(run) = run
                             && run.status == runs_repo::RunStatus::Running
                         {
                             let (run_status, err_code) = match status.trim() {
@@ -330,80 +508,91 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
                                 _ => {
                                     let code = summary
                                         .as_ref()
-                                        .and_then(|v| v.get("error_code"))
-                                        .and_then(|v| v.as_str())
-                                        .filter(|v| !v.trim().is_empty())
-                                        .unwrap_or("agent_failed");
-                                    (runs_repo::RunStatus::Failed, Some(code))
+                                        .and_then(run_error_code_from_summary)
+                                        .unwrap_or_else(|| {
+                                            RunErrorCode::classify_text(
+                                                error.as_deref().unwrap_or_default(),
+                                            )
+                                        });
+                                    (runs_repo::RunStatus::Failed, Some(code.as_str()))
                                 }
                             };
 
-                            let _ = runs_repo::complete_run(
+                            let completed = runs_repo::complete_run(
                                 &db,
                                 &run_id,
                                 run_status,
                                 summary.clone(),
                                 err_code,
                             )
-                            .await;
-                            let final_status = runs_repo::get_run(&db, &run_id)
-                                .await
-                                .ok()
-                                .flatten()
-                                .map(|r| r.status)
-                                .unwrap_or(run_status);
-                            if final_status == runs_repo::RunStatus::Success {
-                                let _ =
-                                    run_artifacts_repo::upsert_run_artifact_from_successful_run(
-                                        &db, &run_id,
-                                    )
-                                    .await;
-                            }
-                            let event_level = if matches!(
-                                final_status,
-                                runs_repo::RunStatus::Success | runs_repo::RunStatus::Canceled
-                            ) {
-                                "info"
-                            } else {
-                                "error"
-                            };
-                            let event_kind = if final_status == runs_repo::RunStatus::Success {
-                                "complete"
-                            } else if final_status == runs_repo::RunStatus::Canceled {
-                                "canceled"
-                            } else {
-                                "failed"
-                            };
-                            let event_fields = if final_status == runs_repo::RunStatus::Failed {
-                                Some(agent_task_result_failure_fields(
-                                    &agent_id,
-                                    &task_id,
+                            .await
+                            .unwrap_or(false);
+                            // A concurrent delivery may have finished the run first.
+                            if completed {
+                                let final_status = runs_repo::get_run(&db, &run_id)
+                                    .await
+                                    .ok()
+                                    .flatten()
+                                    .map(|r| r.status)
+                                    .unwrap_or(run_status);
+                                if final_status == runs_repo::RunStatus::Success {
+                                    let _ =
+                                        run_artifacts_repo::upsert_run_artifact_from_successful_run(
+                                            &db, &run_id,
+                                        )
+                                        .await;
+                                }
+                                let event_level = if matches!(
+                                    final_status,
+                                    runs_repo::RunStatus::Success | runs_repo::RunStatus::Canceled
+                                ) {
+                                    "info"
+                                } else {
+                                    "error"
+                                };
+                                let event_kind = if final_status == runs_repo::RunStatus::Success {
+                                    "complete"
+                                } else if final_status == runs_repo::RunStatus::Canceled {
+                                    "canceled"
+                                } else {
+                                    "failed"
+                                };
+                                let event_fields = if final_status == runs_repo::RunStatus::Failed {
+                                    Some(agent_task_result_failure_fields(
+                                        &agent_id,
+                                        &task_id,
+                                        &run_id,
+                                        summary.as_ref(),
+                                        error.as_deref(),
+                                    ))
+                                } else {
+                                    Some(serde_json::json!({ "agent_id": agent_id.clone() }))
+                                };
+                                let _ = run_events::append_and_broadcast(
+                                    &db,
+                                    &run_events_bus,
                                     &run_id,
-                                    summary.as_ref(),
-                                    error.as_deref(),
-                                ))
-                            } else {
-                                Some(serde_json::json!({ "agent_id": agent_id.clone() }))
-                            };
-                            let _ = run_events::append_and_broadcast(
-                                &db,
-                                &run_events_bus,
-                                &run_id,
-                                event_level,
-                                event_kind,
-                                event_kind,
-                                event_fields,
-                            )
-                            .await;
+                                    event_level,
+                                    event_kind,
+                                    event_kind,
+                                    event_fields,
+                                )
+                                .await;
+                            }
                         }
 
-                        let _ = agent_tasks_repo::complete_task(
-                            &db,
-                            &task_id,
-                            summary.as_ref(),
-                            error.as_deref(),
-                        )
-                        .await;
+                        if delivery.is_ok() && negotiated.has_feature(FEATURE_TASK_RESULT_ACK) {
+                            let _ = agent_manager
+                                .send_json(
+                                    &agent_id,
+                                    &HubToAgentMessageV1::TaskResultAck {
+                                        v: PROTOCOL_VERSION,
+                                        task_id,
+                                        duplicate,
+                                    },
+                                )
+                                .await;
+                        }
                     }
                     Ok(AgentToHubMessageV1::SnapshotDeleteEvent {
                         v,
@@ -641,6 +830,22 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
                             .complete_webdav_list(&agent_id, &request_id, result)
                             .await;
                     }
+                    Ok(AgentToHubMessageV1::DiagnosticsResult {
+                        v,
+                        request_id,
+                        report,
+                        error,
+                    }) if v == PROTOCOL_VERSION => {
+                        let result =
+                            match (report, error) {
+                                (Some(report), None) => Ok(report),
+                                (_, error) => Err(error
+                                    .unwrap_or_else(|| "missing diagnostics report".to_string())),
+                            };
+                        agent_manager
+                            .complete_diagnostics(&agent_id, &request_id, result)
+                            .await;
+                    }
                     Ok(AgentToHubMessageV1::OperationEvent { v, event })
                         if v == PROTOCOL_VERSION =>
                     {
@@ -666,48 +871,75 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
                     Ok(AgentToHubMessageV1::OperationResult { v, result })
                         if v == PROTOCOL_VERSION =>
                     {
-                        let requested_status = match result.status.trim() {
-                            "success" => operations_repo::OperationStatus::Success,
-                            "canceled" => operations_repo::OperationStatus::Canceled,
-                            _ => operations_repo::OperationStatus::Failed,
-                        };
-
-                        let completed = operations_repo::complete_operation(
+                        let delivery = agent_tasks_repo::record_task_result(
                             &db,
                             &result.op_id,
-                            requested_status,
-                            result.summary.clone(),
+                            result.summary.as_ref(),
                             result.error.as_deref(),
                         )
-                        .await
-                        .unwrap_or(false);
+                        .await;
+                        let duplicate = matches!(
+                            delivery,
+                            Ok(agent_tasks_repo::TaskResultDelivery::Duplicate)
+                        );
 
-                        let final_status = operations_repo::get_operation(&db, &result.op_id)
-                            .await
-                            .ok()
-                            .flatten()
-                            .map(|op| op.status)
-                            .unwrap_or(requested_status);
+                        if duplicate {
+                            tracing::debug!(
+                                agent_id = %agent_id,
+                                op_id = %result.op_id,
+                                "ignoring replayed operation result"
+                            );
+                        } else {
+                            let requested_status = match result.status.trim() {
+                                "success" => operations_repo::OperationStatus::Success,
+                                "canceled" => operations_repo::OperationStatus::Canceled,
+                                _ => operations_repo::OperationStatus::Failed,
+                            };
 
-                        if completed && final_status == operations_repo::OperationStatus::Canceled {
-                            let _ = operations_repo::append_event(
+                            let completed = operations_repo::complete_operation(
                                 &db,
                                 &result.op_id,
-                                "info",
-                                "canceled",
-                                "canceled",
-                                Some(serde_json::json!({ "agent_id": agent_id.clone() })),
+                                requested_status,
+                                result.summary.clone(),
+                                result.error.as_deref(),
                             )
-                            .await;
+                            .await
+                            .unwrap_or(false);
+
+                            let final_status = operations_repo::get_operation(&db, &result.op_id)
+                                .await
+                                .ok()
+                                .flatten()
+                                .map(|op| op.status)
+                                .unwrap_or(requested_status);
+
+                            if completed
+                                && final_status == operations_repo::OperationStatus::Canceled
+                            {
+                                let _ = operations_repo::append_event(
+                                    &db,
+                                    &result.op_id,
+                                    "info",
+                                    "canceled",
+                                    "canceled",
+                                    Some(serde_json::json!({ "agent_id": agent_id.clone() })),
+                                )
+                                .await;
+                            }
                         }
 
-                        let _ = agent_tasks_repo::complete_task(
-                            &db,
-                            &result.op_id,
-                            result.summary.as_ref(),
-                            result.error.as_deref(),
-                        )
-                        .await;
+                        if delivery.is_ok() && negotiated.has_feature(FEATURE_TASK_RESULT_ACK) {
+                            let _ = agent_manager
+                                .send_json(
+                                    &agent_id,
+                                    &HubToAgentMessageV1::TaskResultAck {
+                                        v: PROTOCOL_VERSION,
+                                        task_id: result.op_id,
+                                        duplicate,
+                                    },
+                                )
+                                .await;
+                        }
                     }
                     Ok(AgentToHubMessageV1::ArtifactStreamOpenResult { v, res })
                         if v == PROTOCOL_VERSION =>
@@ -862,11 +1094,61 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
                             let _ = tokio::fs::remove_dir_all(dir).await;
                         }
                     }
+                    Ok(AgentToHubMessageV1::RunLogOpen {
+                        v,
+                        run_id,
+                        stream_id,
+                    }) if v == PROTOCOL_VERSION => {
+                        let Ok(stream_id) = Uuid::parse_str(stream_id.trim()) else {
+                            continue;
+                        };
+                        match artifact_stream_authz::authorize_agent_run_log_open(
+                            &db, &agent_id, &run_id,
+                        )
+                        .await
+                        {
+                            Ok(()) => {
+                                run_log_streams.insert(stream_id, run_id.trim().to_string());
+                            }
+                            Err(error) => {
+                                tracing::warn!(
+                                    agent_id = %agent_id,
+                                    run_id = %run_id,
+                                    error = %error,
+                                    "run log stream rejected"
+                                );
+                            }
+                        }
+                    }
                     _ => {}
                 }
             }
             Message::Binary(bytes) => {
                 if let Ok(decoded) = decode_artifact_chunk_frame_v1(&bytes) {
+                    if let Some(run_id) = run_log_streams.get(&decoded.stream_id) {
+                        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+                        if let Err(error) = run_logs_repo::append_run_log_chunk(
+                            &db,
+                            run_id,
+                            decoded.payload,
+                            run_logs_repo::MAX_RUN_LOG_BYTES,
+                            now,
+                        )
+                        .await
+                        {
+                            tracing::warn!(
+                                agent_id = %agent_id,
+                                run_id = %run_id,
+                                error = %error,
+                                "failed to store run log chunk"
+                            );
+                        }
+                        if decoded.flags.eof {
+                            run_log_streams.remove(&decoded.stream_id);
+                        }
+                        continue;
+                    }
+
                     agent_manager
                         .complete_artifact_stream_chunk(
                             &agent_id,
@@ -879,7 +1161,10 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
                         .await;
                 }
             }
-            Message::Close(_) => break,
+            Message::Close(_) => {
+                disconnect_reason = agent_connections_repo::DISCONNECT_REASON_CLOSED;
+                break;
+            }
             _ => {}
         }
     }
@@ -887,13 +1172,33 @@ async fn handle_agent_socket(ctx: AgentSocketContext, socket: WebSocket) {
     agent_manager.unregister(&agent_id_send).await;
     send_task.abort();
 
-    tracing::info!(agent_id = %agent_id, "agent disconnected");
+    if let Some(connection_id) = connection_id {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        if let Err(error) =
+            agent_connections_repo::record_disconnected(&db, connection_id, disconnect_reason, now)
+                .await
+        {
+            tracing::warn!(agent_id = %agent_id, error = %error, "failed to record agent disconnect");
+        }
+    }
+
+    tracing::info!(agent_id = %agent_id, reason = disconnect_reason, "agent disconnected");
 }
 
 fn should_persist_agent_last_seen(last_persisted_at: i64, now: i64) -> bool {
     now.saturating_sub(last_persisted_at) >= AGENT_LAST_SEEN_MIN_UPDATE_SECS
 }
 
+async fn store_host_metrics(db: &SqlitePool, agent_id: &str, metrics: &HostMetricsV1, now: i64) {
+    if let Err(error) = agent_host_metrics_repo::upsert_latest(db, agent_id, metrics, now).await {
+        tracing::warn!(
+            agent_id = %agent_id,
+            error = %error,
+            "failed to store agent host metrics"
+        );
+    }
+}
+
 fn localized_text(key: &'static str) -> LocalizedTextRefV1 {
     LocalizedTextRefV1::new(key)
 }
@@ -954,6 +1259,18 @@ fn task_result_is_retriable(code: &str) -> bool {
     )
 }
 
+/// Agents classify a failure from its cause chain (`run_error_code`); older agents only send
+/// the detailed `error_code`, which is mapped onto the catalog when possible.
+fn run_error_code_from_summary(summary: &serde_json::Value) -> Option<RunErrorCode> {
+    let code = |key: &str| {
+        summary
+            .get(key)
+            .and_then(|v| v.as_str())
+            .and_then(RunErrorCode::from_code)
+    };
+    code("run_error_code").or_else(|| code("error_code").filter(|v| *v != RunErrorCode::Unknown))
+}
+
 fn agent_task_result_failure_fields(
     agent_id: &str,
     task_id: &str,
@@ -1148,9 +1465,12 @@ fn delete_jitter_seconds(run_id: &str, attempts: i64, max_jitter: i64) -> i64 {
 
 #[cfg(test)]
 mod tests {
+    use bastion_core::run_failure::RunErrorCode;
+
     use super::{
         AGENT_LAST_SEEN_MIN_UPDATE_SECS, agent_task_result_failure_fields,
-        should_persist_agent_last_seen, snapshot_delete_failure_fields,
+        run_error_code_from_summary, should_persist_agent_last_seen,
+        snapshot_delete_failure_fields,
     };
 
     #[test]
@@ -1171,6 +1491,28 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn run_error_code_prefers_agent_classification_then_detailed_code() {
+        let summary = serde_json::json!({
+            "error_code": "run_failed",
+            "run_error_code": "network_target",
+        });
+        assert_eq!(
+            run_error_code_from_summary(&summary),
+            Some(RunErrorCode::NetworkTarget)
+        );
+
+        let summary = serde_json::json!({ "error_code": "fs_issues" });
+        assert_eq!(
+            run_error_code_from_summary(&summary),
+            Some(RunErrorCode::SourcePermission)
+        );
+
+        // A generic detailed code leaves room for classifying the error text.
+        let summary = serde_json::json!({ "error_code": "agent_failed" });
+        assert_eq!(run_error_code_from_summary(&summary), None);
+    }
+
     #[test]
     fn task_result_failure_fields_include_error_envelope_context() {
         let fields = agent_task_result_failure_fields(
@@ -57,6 +57,29 @@ pub(super) async fn authorize_agent_artifact_stream_open(
     Ok(())
 }
 
+/// A run log stream is only accepted for a run with an open task assigned to this agent.
+pub(super) async fn authorize_agent_run_log_open(
+    db: &SqlitePool,
+    agent_id: &str,
+    run_id: &str,
+) -> Result<(), anyhow::Error> {
+    let run_id = run_id.trim();
+    if run_id.is_empty() {
+        anyhow::bail!("run_id is required");
+    }
+
+    sqlx::query(
+        "SELECT 1 FROM agent_tasks WHERE agent_id = ? AND run_id = ? AND completed_at IS NULL LIMIT 1",
+    )
+    .bind(agent_id)
+    .bind(run_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("open task not found for agent"))?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::TempDir;
@@ -64,7 +87,7 @@ mod tests {
     use bastion_core::agent_protocol::{HubToAgentMessageV1, PROTOCOL_VERSION, RestoreTaskV1};
     use bastion_storage::db;
 
-    use super::authorize_agent_artifact_stream_open;
+    use super::{authorize_agent_artifact_stream_open, authorize_agent_run_log_open};
 
     fn restore_task_payload(op_id: &str, run_id: &str) -> serde_json::Value {
         serde_json::to_value(HubToAgentMessageV1::RestoreTask {
@@ -146,4 +169,39 @@ mod tests {
             .await
             .expect("must allow matching task");
     }
+
+    #[tokio::test]
+    async fn run_log_open_requires_an_open_task_for_the_agent() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+
+        sqlx::query(
+            "INSERT INTO agent_tasks (id, agent_id, run_id, status, payload_json, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind("run-1")
+        .bind("agent-a")
+        .bind("run-1")
+        .bind("sent")
+        .bind("{}")
+        .bind(1_i64)
+        .bind(1_i64)
+        .execute(&pool)
+        .await
+        .expect("insert task");
+
+        authorize_agent_run_log_open(&pool, "agent-a", "run-1")
+            .await
+            .expect("must allow the assigned agent");
+        authorize_agent_run_log_open(&pool, "agent-b", "run-1")
+            .await
+            .expect_err("must deny other agents");
+
+        sqlx::query("UPDATE agent_tasks SET completed_at = 2 WHERE id = 'run-1'")
+            .execute(&pool)
+            .await
+            .expect("complete task");
+        authorize_agent_run_log_open(&pool, "agent-a", "run-1")
+            .await
+            .expect_err("must deny completed tasks");
+    }
 }
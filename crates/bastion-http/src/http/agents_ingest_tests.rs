@@ -1,16 +1,21 @@
 use std::sync::Arc;
 
 use axum::http::StatusCode;
-use futures_util::SinkExt;
+use futures_util::{SinkExt, StreamExt};
 use tempfile::TempDir;
 use tokio_tungstenite::tungstenite::Message as WsMessage;
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 
 use bastion_config::Config;
-use bastion_core::agent_protocol::{AgentToHubMessageV1, OperationResultV1, PROTOCOL_VERSION};
+use bastion_core::agent_protocol::{
+    AgentToHubMessageV1, HubToAgentMessageV1, OperationResultV1, PROTOCOL_VERSION,
+    ProtocolCapabilitiesV1,
+};
 use bastion_engine::agent_manager::AgentManager;
 use bastion_storage::secrets::SecretsCrypto;
-use bastion_storage::{agent_tasks_repo, db, jobs_repo, operations_repo, runs_repo};
+use bastion_storage::{
+    agent_tasks_repo, db, jobs_repo, operations_repo, run_artifacts_repo, runs_repo,
+};
 
 async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
@@ -29,6 +34,11 @@ fn test_config(temp: &TempDir) -> Arc<Config> {
         hub_timezone: "UTC".to_string(),
         run_retention_days: 180,
         incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
         trusted_proxies: vec![
             "127.0.0.1/32".parse().expect("proxy"),
             "::1/128".parse().expect("proxy"),
@@ -189,6 +199,106 @@ async fn agent_ingest_runs_inserts_run_and_events_and_dedupes() {
     server.abort();
 }
 
+#[tokio::test]
+async fn agent_ingest_runs_records_snapshot_unless_upload_deferred() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let (agent_id, agent_key) = insert_agent(&pool, "agent1").await;
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "job1",
+        Some(&agent_id),
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "root": "/data" },
+            "target": { "type": "local_dir", "base_dir": "/tmp", "part_size_bytes": 1048576 }
+        }),
+    )
+    .await
+    .expect("create job");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    for (run_id, target) in [
+        (
+            "deferred",
+            serde_json::json!({ "type": "deferred", "upload_deferred": true, "error": "offline" }),
+        ),
+        (
+            "uploaded",
+            serde_json::json!({ "type": "local_dir", "run_dir": "/tmp/job/uploaded" }),
+        ),
+    ] {
+        let resp = client
+            .post(format!("{}/agent/runs/ingest", base_url(addr)))
+            .header("authorization", format!("Bearer {agent_key}"))
+            .json(&serde_json::json!({
+                "run": {
+                    "id": run_id,
+                    "job_id": job.id,
+                    "status": "success",
+                    "started_at": 100,
+                    "ended_at": 120,
+                    "summary": { "executed_offline": true, "target": target },
+                    "events": []
+                }
+            }))
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    }
+
+    assert!(
+        run_artifacts_repo::get_run_artifact(&pool, "deferred")
+            .await
+            .expect("get artifact")
+            .is_none()
+    );
+    let artifact = run_artifacts_repo::get_run_artifact(&pool, "uploaded")
+        .await
+        .expect("get artifact")
+        .expect("artifact exists");
+    assert_eq!(artifact.job_id, job.id);
+    assert_eq!(artifact.node_id, agent_id);
+    assert_eq!(artifact.target_type, "local_dir");
+
+    server.abort();
+}
+
 #[tokio::test]
 async fn agent_ingest_runs_requires_auth() {
     let temp = TempDir::new().expect("tempdir");
@@ -1114,6 +1224,147 @@ async fn agent_ws_task_result_canceled_marks_cancel_requested_run_canceled() {
     server.abort();
 }
 
+#[tokio::test]
+async fn agent_ws_replayed_task_result_is_acked_and_ignored() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let (agent_id, agent_key) = insert_agent(&pool, "agent1").await;
+    let job = jobs_repo::create_job(
+        &pool,
+        "job1",
+        Some(&agent_id),
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create job");
+    let run = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Running,
+        100,
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    agent_tasks_repo::upsert_task(
+        &pool,
+        &run.id,
+        &agent_id,
+        &run.id,
+        "sent",
+        &serde_json::json!({}),
+    )
+    .await
+    .expect("upsert task");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let mut socket = connect_agent_ws(addr, &agent_key).await;
+    // Acks are only sent to agents that negotiated them.
+    let hello = AgentToHubMessageV1::Hello {
+        v: PROTOCOL_VERSION,
+        agent_id: agent_id.clone(),
+        name: None,
+        info: serde_json::Value::Null,
+        capabilities: serde_json::Value::Null,
+        metrics: None,
+        protocol: Some(ProtocolCapabilitiesV1::current()),
+    };
+    socket
+        .send(WsMessage::Text(
+            serde_json::to_string(&hello).expect("json").into(),
+        ))
+        .await
+        .expect("send hello");
+
+    let mut acks = Vec::new();
+    for status in ["success", "failed"] {
+        let msg = AgentToHubMessageV1::TaskResult {
+            v: PROTOCOL_VERSION,
+            task_id: run.id.clone(),
+            run_id: run.id.clone(),
+            status: status.to_string(),
+            summary: None,
+            error: None,
+        };
+        socket
+            .send(WsMessage::Text(
+                serde_json::to_string(&msg).expect("json").into(),
+            ))
+            .await
+            .expect("send");
+
+        let ack = tokio::time::timeout(std::time::Duration::from_secs(2), async {
+            loop {
+                let Some(Ok(WsMessage::Text(text))) = socket.next().await else {
+                    continue;
+                };
+                if let Ok(HubToAgentMessageV1::TaskResultAck {
+                    task_id, duplicate, ..
+                }) = serde_json::from_str::<HubToAgentMessageV1>(text.as_str())
+                {
+                    break (task_id, duplicate);
+                }
+            }
+        })
+        .await
+        .expect("wait ack");
+        acks.push(ack);
+    }
+    assert_eq!(acks, [(run.id.clone(), false), (run.id.clone(), true)]);
+
+    let run_row = runs_repo::get_run(&pool, &run.id)
+        .await
+        .expect("get run")
+        .expect("run exists");
+    assert_eq!(run_row.status, runs_repo::RunStatus::Success);
+    let task_row = agent_tasks_repo::get_task(&pool, &run.id)
+        .await
+        .expect("get task")
+        .expect("task exists");
+    assert_eq!(task_row.result_deliveries, 2);
+
+    let events = runs_repo::list_run_events(&pool, &run.id, 50)
+        .await
+        .expect("list events");
+    assert_eq!(events.iter().filter(|e| e.kind == "complete").count(), 1);
+    assert!(!events.iter().any(|e| e.kind == "failed"));
+
+    let _ = socket.close(None).await;
+    server.abort();
+}
+
 #[tokio::test]
 async fn agent_ws_operation_result_canceled_marks_cancel_requested_operation_canceled() {
     let temp = TempDir::new().expect("tempdir");
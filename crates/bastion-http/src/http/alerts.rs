@@ -0,0 +1,35 @@
+use axum::Json;
+use serde::Serialize;
+use tower_cookies::Cookies;
+
+use bastion_storage::stale_backups_repo::{self, StaleJob};
+
+use super::shared::{require_session, visible_job_ids};
+use super::{AppError, AppState};
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct StaleBackupsResponse {
+    checked_at: i64,
+    items: Vec<StaleJob>,
+}
+
+/// Jobs whose latest successful run is older than their `max_success_interval_seconds`.
+///
+/// Computed on request, so it can run ahead of the monitor loop that raises alerts.
+pub(in crate::http) async fn list_stale_backups(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<StaleBackupsResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    let visible = visible_job_ids(&state, &session).await?;
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let mut items = stale_backups_repo::list_stale_jobs(&state.db, now).await?;
+    if let Some(visible) = &visible {
+        items.retain(|item| visible.contains(&item.job_id));
+    }
+    Ok(Json(StaleBackupsResponse {
+        checked_at: now,
+        items,
+    }))
+}
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db, jobs_repo, runs_repo};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn api_token_authenticates_requests_without_cookie_or_csrf() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let token = auth::create_api_token(&pool, user.id, "ci", None)
+        .await
+        .expect("create token");
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "job",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "root": "/" },
+            "target": { "type": "local_dir", "base_dir": "/tmp" }
+        }),
+    )
+    .await
+    .expect("create job");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .get(format!("{}/api/jobs", base_url(addr)))
+        .bearer_auth(&token.token)
+        .send()
+        .await
+        .expect("list jobs");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = client
+        .post(format!("{}/api/jobs/{}/run", base_url(addr), job.id))
+        .bearer_auth(&token.token)
+        .send()
+        .await
+        .expect("run job");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    let run_id = body["run_id"].as_str().expect("run_id");
+    let run = runs_repo::get_run(&pool, run_id)
+        .await
+        .expect("get run")
+        .expect("run exists");
+    assert_eq!(run.status, runs_repo::RunStatus::Queued);
+
+    let resp = client
+        .get(format!("{}/api/jobs", base_url(addr)))
+        .bearer_auth("bst_invalid")
+        .send()
+        .await
+        .expect("bad token");
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("invalid_api_token"));
+
+    auth::delete_api_token(&pool, user.id, &token.row.id)
+        .await
+        .expect("delete token");
+    let resp = client
+        .get(format!("{}/api/jobs", base_url(addr)))
+        .bearer_auth(&token.token)
+        .send()
+        .await
+        .expect("revoked token");
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    server.abort();
+}
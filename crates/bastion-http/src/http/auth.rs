@@ -1,16 +1,17 @@
+use axum::Extension;
 use axum::Json;
-use axum::extract::ConnectInfo;
+use axum::extract::Path;
 use axum::http::{HeaderMap, StatusCode};
 use serde::Serialize;
 use tower_cookies::Cookies;
 use tower_cookies::cookie::Cookie;
 
 use super::shared;
-use super::shared::require_csrf;
+use super::shared::{require_csrf, require_session};
 use super::{AppError, AppState};
-use bastion_storage::auth;
+use bastion_storage::{audit_repo, auth, hub_runtime_config_repo, job_access_repo};
 
-const MIN_SETUP_PASSWORD_LEN: usize = 12;
+const MIN_PASSWORD_LEN: usize = 12;
 
 fn validate_username(username: &str) -> Result<&str, AppError> {
     let username = username.trim();
@@ -24,15 +25,15 @@ fn validate_username(username: &str) -> Result<&str, AppError> {
     Ok(username)
 }
 
-fn validate_setup_password(password: &str) -> Result<(), AppError> {
-    if password.len() < MIN_SETUP_PASSWORD_LEN {
+fn validate_new_password(password: &str, field: &'static str) -> Result<(), AppError> {
+    if password.len() < MIN_PASSWORD_LEN {
         return Err(AppError::bad_request(
             "invalid_password",
-            format!("Password must be at least {MIN_SETUP_PASSWORD_LEN} characters"),
+            format!("Password must be at least {MIN_PASSWORD_LEN} characters"),
         )
         .with_reason("min_length")
-        .with_field("password")
-        .with_param("min_length", MIN_SETUP_PASSWORD_LEN));
+        .with_field(field)
+        .with_param("min_length", MIN_PASSWORD_LEN));
     }
     Ok(())
 }
@@ -62,7 +63,7 @@ pub(super) async fn setup_initialize(
     Json(req): Json<SetupInitializeRequest>,
 ) -> Result<StatusCode, AppError> {
     let username = validate_username(&req.username)?;
-    validate_setup_password(&req.password)?;
+    validate_new_password(&req.password, "password")?;
 
     match auth::create_first_user(&state.db, username, &req.password).await? {
         auth::CreateFirstUserResult::Created => {}
@@ -93,13 +94,13 @@ pub(super) async fn login(
     state: axum::extract::State<AppState>,
     cookies: Cookies,
     headers: HeaderMap,
-    ConnectInfo(peer): ConnectInfo<std::net::SocketAddr>,
+    Extension(client): Extension<shared::ClientConnInfo>,
     Json(req): Json<LoginRequest>,
 ) -> Result<Json<LoginResponse>, AppError> {
     let username = validate_username(&req.username)?;
 
     let now = time::OffsetDateTime::now_utc().unix_timestamp();
-    let client_ip = shared::effective_client_ip(&state, &headers, peer.ip());
+    let client_ip = client.ip;
     let client_ip_str = client_ip.to_string();
 
     if let Some(retry_after) =
@@ -114,8 +115,20 @@ pub(super) async fn login(
         .with_param("retry_after_seconds", retry_after));
     }
 
+    if let Some(retry_after) =
+        auth::login_user_throttle_retry_after_seconds(&state.db, username, now).await?
+    {
+        tracing::warn!(client_ip = %client_ip, username, retry_after, "login locked for username");
+        return Err(AppError::too_many_requests(
+            "rate_limited",
+            format!("Too many login attempts. Retry after {retry_after}s."),
+        )
+        .with_reason("locked")
+        .with_param("retry_after_seconds", retry_after));
+    }
+
     let Some(user) = auth::find_user_by_username(&state.db, username).await? else {
-        let _ = auth::record_login_failure(&state.db, &client_ip_str, now).await;
+        record_login_failure(&state, &client_ip_str, username, now).await;
         tracing::debug!(client_ip = %client_ip, "login failed: user not found");
         return Err(AppError::unauthorized(
             "invalid_credentials",
@@ -124,7 +137,7 @@ pub(super) async fn login(
     };
 
     if !auth::verify_password(&user.password_hash, &req.password)? {
-        let _ = auth::record_login_failure(&state.db, &client_ip_str, now).await;
+        record_login_failure(&state, &client_ip_str, username, now).await;
         tracing::debug!(client_ip = %client_ip, user_id = user.id, "login failed: bad password");
         return Err(AppError::unauthorized(
             "invalid_credentials",
@@ -133,9 +146,11 @@ pub(super) async fn login(
     }
 
     let _ = auth::clear_login_throttle(&state.db, &client_ip_str).await;
+    let _ = auth::clear_login_user_throttle(&state.db, username).await;
 
-    let session = auth::create_session(&state.db, user.id).await?;
-    shared::set_session_cookie(&state, &headers, peer.ip(), &cookies, &session.id)?;
+    let options = session_options(&state, &headers, &client_ip_str).await?;
+    let session = auth::create_session_with(&state.db, user.id, &options).await?;
+    shared::set_session_cookie(&client, &cookies, &session.id)?;
 
     tracing::info!(client_ip = %client_ip, user_id = user.id, "login succeeded");
     Ok(Json(LoginResponse {
@@ -143,6 +158,218 @@ pub(super) async fn login(
     }))
 }
 
+const MAX_USER_AGENT_LEN: usize = 256;
+
+/// Lifetimes come from the saved runtime config, so changes apply to sessions created afterwards.
+async fn session_options(
+    state: &AppState,
+    headers: &HeaderMap,
+    client_ip: &str,
+) -> Result<auth::SessionOptions, anyhow::Error> {
+    let config = hub_runtime_config_repo::get(&state.db)
+        .await?
+        .unwrap_or_default();
+    let user_agent = headers
+        .get(axum::http::header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| v.chars().take(MAX_USER_AGENT_LEN).collect());
+
+    Ok(auth::SessionOptions {
+        client_ip: Some(client_ip.to_string()),
+        user_agent,
+        max_age_seconds: config
+            .session_max_age_hours
+            .map(|h| i64::from(h) * 60 * 60)
+            .unwrap_or(auth::DEFAULT_SESSION_MAX_AGE_SECONDS),
+        idle_timeout_seconds: config
+            .session_idle_timeout_minutes
+            .map(|m| i64::from(m) * 60),
+    })
+}
+
+async fn record_login_failure(state: &AppState, client_ip: &str, username: &str, now: i64) {
+    let _ = auth::record_login_failure(&state.db, client_ip, now).await;
+    if let Ok(Some(locked_until)) = auth::record_login_user_failure(&state.db, username, now).await
+    {
+        tracing::warn!(
+            client_ip,
+            username,
+            locked_until,
+            "username locked after failed logins"
+        );
+    }
+}
+
+async fn require_admin_for_login_locks(
+    state: &AppState,
+    session: &auth::SessionRow,
+) -> Result<(), AppError> {
+    if !job_access_repo::is_admin(&state.db, session.user_id).await? {
+        return Err(AppError::forbidden(
+            "admin_required",
+            "Only admins can view or lift login lockouts",
+        ));
+    }
+    Ok(())
+}
+
+pub(super) async fn list_login_locks(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<auth::LoginUserLock>>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_admin_for_login_locks(&state, &session).await?;
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    Ok(Json(auth::list_login_user_locks(&state.db, now).await?))
+}
+
+/// Lifts a username lockout and resets its escalation.
+pub(super) async fn unlock_login(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<shared::ClientConnInfo>,
+    Path(username): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_admin_for_login_locks(&state, &session).await?;
+
+    if !auth::clear_login_user_throttle(&state.db, &username).await? {
+        return Err(AppError::not_found(
+            "login_lock_not_found",
+            "No failed logins recorded for this username",
+        ));
+    }
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+    audit_repo::append(
+        &state.db,
+        now,
+        audit_repo::ACTION_LOGIN_UNLOCKED,
+        Some(session.user_id),
+        Some(&client_ip),
+        Some(&username),
+    )
+    .await?;
+    tracing::info!(user_id = session.user_id, username = %username, "login lock cleared");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ChangePasswordRequest {
+    current_password: String,
+    new_password: String,
+}
+
+/// Changes the caller's password and signs out their other browser sessions.
+pub(super) async fn change_password(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<shared::ClientConnInfo>,
+    Json(req): Json<ChangePasswordRequest>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    let Some(user) = auth::find_user_by_id(&state.db, session.user_id).await? else {
+        return Err(AppError::unauthorized("unauthorized", "Unauthorized"));
+    };
+    // Wrong current passwords count like failed logins, so a stolen session can't be used to
+    // guess the password.
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+    if let Some(retry_after) =
+        auth::login_user_throttle_retry_after_seconds(&state.db, &user.username, now).await?
+    {
+        return Err(AppError::too_many_requests(
+            "rate_limited",
+            format!("Too many failed attempts. Retry after {retry_after}s."),
+        )
+        .with_reason("locked")
+        .with_param("retry_after_seconds", retry_after));
+    }
+    if !auth::verify_password(&user.password_hash, &req.current_password)? {
+        record_login_failure(&state, &client_ip, &user.username, now).await;
+        return Err(AppError::bad_request(
+            "invalid_current_password",
+            "Current password is incorrect",
+        )
+        .with_field("current_password"));
+    }
+    validate_new_password(&req.new_password, "new_password")?;
+
+    auth::set_password(&state.db, user.id, &req.new_password, Some(&session.id)).await?;
+
+    audit_repo::append(
+        &state.db,
+        now,
+        audit_repo::ACTION_PASSWORD_CHANGED,
+        Some(user.id),
+        Some(&client_ip),
+        None,
+    )
+    .await?;
+    tracing::info!(user_id = user.id, "password changed");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub(super) struct ResetPasswordRequest {
+    new_password: String,
+}
+
+/// Sets another user's password, signs out their browser sessions and lifts any login lock.
+pub(super) async fn reset_password(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<shared::ClientConnInfo>,
+    Path(username): Path<String>,
+    Json(req): Json<ResetPasswordRequest>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    if !job_access_repo::is_admin(&state.db, session.user_id).await? {
+        return Err(AppError::forbidden(
+            "admin_required",
+            "Only admins can reset other users' passwords",
+        ));
+    }
+
+    let Some(user) = auth::find_user_by_username(&state.db, username.trim()).await? else {
+        return Err(AppError::not_found("user_not_found", "User not found"));
+    };
+    if user.id == session.user_id {
+        return Err(AppError::bad_request(
+            "use_change_password",
+            "Use change-password to change your own password",
+        ));
+    }
+    validate_new_password(&req.new_password, "new_password")?;
+
+    auth::set_password(&state.db, user.id, &req.new_password, None).await?;
+    let _ = auth::clear_login_user_throttle(&state.db, &user.username).await;
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+    audit_repo::append(
+        &state.db,
+        now,
+        audit_repo::ACTION_PASSWORD_RESET,
+        Some(session.user_id),
+        Some(&client_ip),
+        Some(&user.username),
+    )
+    .await?;
+    tracing::info!(user_id = session.user_id, username = %user.username, "password reset");
+    Ok(StatusCode::NO_CONTENT)
+}
+
 pub(super) async fn logout(
     state: axum::extract::State<AppState>,
     cookies: Cookies,
@@ -17,6 +17,11 @@ fn test_config(temp: &TempDir) -> Arc<Config> {
         hub_timezone: "UTC".to_string(),
         run_retention_days: 180,
         incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
         trusted_proxies: vec![
             "127.0.0.1/32".parse().expect("proxy"),
             "::1/128".parse().expect("proxy"),
@@ -182,3 +187,411 @@ async fn setup_initialize_is_atomic_under_concurrency() {
 
     server.abort();
 }
+
+#[tokio::test]
+async fn username_lockout_spans_client_ips_until_an_admin_unlocks_it() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_first_user(&pool, "admin", &user_password)
+        .await
+        .expect("create admin");
+    auth::create_user(&pool, "alice", &user_password)
+        .await
+        .expect("create user");
+    let admin = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find admin")
+        .expect("admin");
+    let session = auth::create_session(&pool, admin.id)
+        .await
+        .expect("session");
+
+    let (addr, server) = start_server(&temp, pool).await;
+    let client = reqwest::Client::new();
+    let login = |ip: String, password: String| {
+        client
+            .post(format!("{}/api/auth/login", base_url(addr)))
+            .header("x-forwarded-for", ip)
+            .json(&serde_json::json!({ "username": "alice", "password": password }))
+            .send()
+    };
+
+    // Each attempt comes from a different client IP, so only the username counter trips.
+    for i in 0..5 {
+        let resp = login(format!("203.0.113.{i}"), "wrong".to_string())
+            .await
+            .expect("request");
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+    let resp = login("198.51.100.1".to_string(), user_password.clone())
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["details"]["reason"], "locked");
+
+    let cookie = format!("bastion_session={}", session.id);
+    let locks: serde_json::Value = client
+        .get(format!("{}/api/auth/login-locks", base_url(addr)))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("json");
+    assert_eq!(locks[0]["username"], "alice");
+
+    let resp = client
+        .delete(format!("{}/api/auth/login-locks/alice", base_url(addr)))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let resp = login("198.51.100.1".to_string(), user_password.clone())
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn login_locks_require_an_admin() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_first_user(&pool, "admin", &user_password)
+        .await
+        .expect("create admin");
+    auth::create_user(&pool, "alice", &user_password)
+        .await
+        .expect("create user");
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    for _ in 0..5 {
+        auth::record_login_user_failure(&pool, "admin", now)
+            .await
+            .expect("record failure");
+    }
+    let alice = auth::find_user_by_username(&pool, "alice")
+        .await
+        .expect("find alice")
+        .expect("alice");
+    let session = auth::create_session(&pool, alice.id)
+        .await
+        .expect("session");
+
+    let (addr, server) = start_server(&temp, pool.clone()).await;
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+
+    let resp = client
+        .get(format!("{}/api/auth/login-locks", base_url(addr)))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "admin_required");
+
+    let resp = client
+        .delete(format!("{}/api/auth/login-locks/admin", base_url(addr)))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    assert_eq!(
+        auth::list_login_user_locks(&pool, now)
+            .await
+            .expect("locks")
+            .len(),
+        1
+    );
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn sessions_are_listed_with_client_details_and_can_be_revoked() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &password)
+        .await
+        .expect("create user");
+    let admin = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find admin")
+        .expect("admin");
+    let other = auth::create_session(&pool, admin.id)
+        .await
+        .expect("session");
+    bastion_storage::hub_runtime_config_repo::upsert(
+        &pool,
+        &bastion_storage::hub_runtime_config_repo::HubRuntimeConfig {
+            session_max_age_hours: Some(2),
+            session_idle_timeout_minutes: Some(30),
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("runtime config");
+
+    let (addr, server) = start_server(&temp, pool).await;
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!("{}/api/auth/login", base_url(addr)))
+        .header("x-forwarded-for", "203.0.113.9")
+        .header(
+            "user-agent",
+            "Mozilla/5.0 (X11; Linux x86_64) Firefox/140.0",
+        )
+        .json(&serde_json::json!({ "username": "admin", "password": password }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let cookie = resp
+        .headers()
+        .get("set-cookie")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .expect("session cookie")
+        .to_string();
+    let body: serde_json::Value = resp.json().await.expect("json");
+    let csrf = body["csrf_token"].as_str().expect("csrf").to_string();
+
+    let sessions: serde_json::Value = client
+        .get(format!("{}/api/sessions", base_url(addr)))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("json");
+    let sessions = sessions.as_array().expect("array");
+    assert_eq!(sessions.len(), 2);
+    let current = sessions
+        .iter()
+        .find(|s| s["current"] == true)
+        .expect("current session");
+    assert_eq!(current["client_ip"], "203.0.113.9");
+    assert_eq!(
+        current["user_agent"],
+        "Mozilla/5.0 (X11; Linux x86_64) Firefox/140.0"
+    );
+    assert_eq!(
+        current["expires_at"].as_i64().unwrap() - current["created_at"].as_i64().unwrap(),
+        2 * 60 * 60
+    );
+    assert_eq!(
+        current["idle_expires_at"].as_i64().unwrap() - current["last_used_at"].as_i64().unwrap(),
+        30 * 60
+    );
+    assert!(
+        sessions
+            .iter()
+            .all(|s| s["id"].as_str() != Some(other.id.as_str())),
+        "the cookie value is never exposed"
+    );
+
+    let resp = client
+        .delete(format!("{}/api/sessions/does-not-exist", base_url(addr)))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", &csrf)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp: serde_json::Value = client
+        .post(format!("{}/api/sessions/revoke-others", base_url(addr)))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", &csrf)
+        .send()
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("json");
+    assert_eq!(resp["revoked"], 1);
+
+    let resp = client
+        .get(format!("{}/api/sessions", base_url(addr)))
+        .header("cookie", format!("bastion_session={}", other.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn passwords_can_be_changed_and_reset_for_other_users() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    auth::create_first_user(&pool, "admin", "admin-password-1")
+        .await
+        .expect("create admin");
+    auth::create_user(&pool, "alice", "alice-password-1")
+        .await
+        .expect("create alice");
+    let admin = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find admin")
+        .expect("admin");
+    let session = auth::create_session(&pool, admin.id)
+        .await
+        .expect("session");
+    let other = auth::create_session(&pool, admin.id)
+        .await
+        .expect("other session");
+
+    let (addr, server) = start_server(&temp, pool.clone()).await;
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+    let post = |path: &str, body: serde_json::Value| {
+        client
+            .post(format!("{}{path}", base_url(addr)))
+            .header("cookie", &cookie)
+            .header("x-csrf-token", &session.csrf_token)
+            .json(&body)
+            .send()
+    };
+
+    let resp = post(
+        "/api/auth/change-password",
+        serde_json::json!({ "current_password": "wrong", "new_password": "admin-password-2" }),
+    )
+    .await
+    .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_current_password");
+
+    let resp = post(
+        "/api/auth/change-password",
+        serde_json::json!({ "current_password": "admin-password-1", "new_password": "short" }),
+    )
+    .await
+    .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = post(
+        "/api/auth/change-password",
+        serde_json::json!({ "current_password": "admin-password-1", "new_password": "admin-password-2" }),
+    )
+    .await
+    .expect("request");
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    assert!(
+        auth::get_session(&pool, &session.id)
+            .await
+            .unwrap()
+            .is_some()
+    );
+    assert!(auth::get_session(&pool, &other.id).await.unwrap().is_none());
+
+    let resp = post(
+        "/api/users/admin/reset-password",
+        serde_json::json!({ "new_password": "admin-password-3" }),
+    )
+    .await
+    .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = post(
+        "/api/users/alice/reset-password",
+        serde_json::json!({ "new_password": "alice-password-2" }),
+    )
+    .await
+    .expect("request");
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let login = |username: &str, password: &str| {
+        client
+            .post(format!("{}/api/auth/login", base_url(addr)))
+            .json(&serde_json::json!({ "username": username, "password": password }))
+            .send()
+    };
+    assert_eq!(
+        login("alice", "alice-password-1")
+            .await
+            .expect("request")
+            .status(),
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        login("alice", "alice-password-2")
+            .await
+            .expect("request")
+            .status(),
+        StatusCode::OK
+    );
+    assert_eq!(
+        login("admin", "admin-password-2")
+            .await
+            .expect("request")
+            .status(),
+        StatusCode::OK
+    );
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn password_reset_requires_an_admin() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    auth::create_first_user(&pool, "admin", "admin-password-1")
+        .await
+        .expect("create admin");
+    auth::create_user(&pool, "alice", "alice-password-1")
+        .await
+        .expect("create alice");
+    let alice = auth::find_user_by_username(&pool, "alice")
+        .await
+        .expect("find alice")
+        .expect("alice");
+    let session = auth::create_session(&pool, alice.id)
+        .await
+        .expect("session");
+
+    let (addr, server) = start_server(&temp, pool.clone()).await;
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!("{}/api/users/admin/reset-password", base_url(addr)))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .header("x-csrf-token", &session.csrf_token)
+        .json(&serde_json::json!({ "new_password": "taken-over-password" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "admin_required");
+
+    let resp = client
+        .post(format!("{}/api/auth/login", base_url(addr)))
+        .json(&serde_json::json!({ "username": "admin", "password": "admin-password-1" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    server.abort();
+}
@@ -73,6 +73,7 @@ fn validate_kind(kind: &str) -> Result<&str, AppError> {
         | "agent_labels_remove"
         | "sync_config_now"
         | "job_deploy"
+        | "job_sync"
         | "webdav_secret_distribute" => Ok(kind),
         _ => Err(invalid_kind_error()),
     }
@@ -211,7 +212,7 @@ pub(in crate::http) async fn create_bulk_operation(
             serde_json::json!({ "labels": payload_labels })
         }
         "sync_config_now" => serde_json::json!({}),
-        "job_deploy" => {
+        "job_deploy" | "job_sync" => {
             let Some(payload) = req.payload else {
                 return Err(invalid_payload_error(
                     "required",
@@ -326,6 +327,11 @@ pub(in crate::http) struct JobDeployPreviewItem {
     planned_name: String,
     valid: bool,
     error: Option<String>,
+    /// `create`, `update` or `unchanged`; only set for `job_sync` previews.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    existing_job_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -343,6 +349,12 @@ pub(in crate::http) enum BulkOperationPreviewResponse {
         name_template: String,
         items: Vec<JobDeployPreviewItem>,
     },
+    #[serde(rename = "job_sync")]
+    JobSync {
+        source_job_id: String,
+        name_template: String,
+        items: Vec<JobDeployPreviewItem>,
+    },
 }
 
 pub(in crate::http) async fn preview_bulk_operation(
@@ -443,9 +455,12 @@ pub(in crate::http) async fn preview_bulk_operation(
                 items,
             }))
         }
-        "job_deploy" => {
+        "job_deploy" | "job_sync" => {
             use bastion_core::job_spec;
             use bastion_engine::agent_job_resolver;
+            use bastion_engine::bulk_operations::job_matches_template;
+
+            let sync = kind == "job_sync";
 
             let parsed: JobDeployPayloadRequest =
                 serde_json::from_value(payload).map_err(|_| {
@@ -512,6 +527,39 @@ pub(in crate::http) async fn preview_bulk_operation(
                     .trim()
                     .to_string();
 
+                // A sync keeps the agent's existing instance (and its name) instead of copying.
+                let instance = if sync {
+                    jobs_repo::find_template_instance(&state.db, &source.id, &agent_id).await?
+                } else {
+                    None
+                };
+                if let Some(instance) = instance {
+                    let unchanged = job_matches_template(&instance, &source);
+                    let validation = if unchanged {
+                        Ok(())
+                    } else {
+                        agent_job_resolver::resolve_job_spec_for_agent(
+                            &state.db,
+                            state.secrets.as_ref(),
+                            &agent_id,
+                            spec.clone(),
+                        )
+                        .await
+                        .map(|_| ())
+                    };
+                    items.push(JobDeployPreviewItem {
+                        agent_id: agent_id.clone(),
+                        agent_name: names.get(&agent_id).cloned().unwrap_or(None),
+                        planned_name: instance.name,
+                        valid: validation.is_ok(),
+                        error: validation.err().map(|error| error.to_string()),
+                        action: Some(if unchanged { "unchanged" } else { "update" }),
+                        existing_job_id: Some(instance.id),
+                    });
+                    continue;
+                }
+                let action = sync.then_some("create");
+
                 if planned_base.is_empty() {
                     items.push(JobDeployPreviewItem {
                         agent_id: agent_id.clone(),
@@ -519,6 +567,8 @@ pub(in crate::http) async fn preview_bulk_operation(
                         planned_name: planned_base,
                         valid: false,
                         error: Some("name_template produced empty name".to_string()),
+                        action,
+                        existing_job_id: None,
                     });
                     continue;
                 }
@@ -556,6 +606,8 @@ pub(in crate::http) async fn preview_bulk_operation(
                         planned_name,
                         valid: true,
                         error: None,
+                        action,
+                        existing_job_id: None,
                     }),
                     Err(error) => items.push(JobDeployPreviewItem {
                         agent_id: agent_id.clone(),
@@ -563,14 +615,24 @@ pub(in crate::http) async fn preview_bulk_operation(
                         planned_name,
                         valid: false,
                         error: Some(error.to_string()),
+                        action,
+                        existing_job_id: None,
                     }),
                 }
             }
 
-            Ok(Json(BulkOperationPreviewResponse::JobDeploy {
-                source_job_id,
-                name_template,
-                items,
+            Ok(Json(if sync {
+                BulkOperationPreviewResponse::JobSync {
+                    source_job_id,
+                    name_template,
+                    items,
+                }
+            } else {
+                BulkOperationPreviewResponse::JobDeploy {
+                    source_job_id,
+                    name_template,
+                    items,
+                }
             }))
         }
         _ => Err(AppError::bad_request(
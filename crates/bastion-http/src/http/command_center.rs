@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use axum::Json;
 use axum::extract::Query;
 use serde::{Deserialize, Serialize};
@@ -5,7 +7,7 @@ use sqlx::Row;
 use tower_cookies::Cookies;
 use url::form_urlencoded::Serializer;
 
-use super::shared::require_session;
+use super::shared::{require_session, visible_job_ids};
 use super::{AppError, AppState};
 
 #[derive(Debug, Deserialize)]
@@ -196,6 +198,32 @@ impl RequestedScope {
     }
 }
 
+/// The requested scope, narrowed to the jobs the viewer may see.
+#[derive(Debug)]
+struct ViewScope {
+    requested: RequestedScope,
+    /// `None` for admins.
+    visible_jobs: Option<HashSet<String>>,
+}
+
+impl ViewScope {
+    fn as_str(&self) -> String {
+        self.requested.as_str()
+    }
+
+    fn includes_agent(&self, agent_id: Option<&str>) -> bool {
+        self.requested.includes_agent(agent_id)
+    }
+
+    fn includes(&self, agent_id: Option<&str>, job_id: &str) -> bool {
+        self.requested.includes_agent(agent_id)
+            && self
+                .visible_jobs
+                .as_ref()
+                .is_none_or(|visible| visible.contains(job_id))
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct ResolvedRange {
     preset: &'static str,
@@ -326,10 +354,13 @@ pub(super) async fn get_command_center(
     cookies: Cookies,
     Query(query): Query<CommandCenterQuery>,
 ) -> Result<Json<CommandCenterResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
 
     let now_ts = time::OffsetDateTime::now_utc().unix_timestamp();
-    let requested_scope = RequestedScope::parse(query.scope.as_deref())?;
+    let requested_scope = ViewScope {
+        requested: RequestedScope::parse(query.scope.as_deref())?,
+        visible_jobs: visible_job_ids(&state, &session).await?,
+    };
     let resolved_range = ResolvedRange::parse(query.range.as_deref(), now_ts)?;
 
     let attention =
@@ -416,7 +447,7 @@ fn recovery_readiness_or_degraded(result: anyhow::Result<RecoveryReadiness>) ->
 
 async fn build_attention(
     state: &AppState,
-    scope: &RequestedScope,
+    scope: &ViewScope,
     range: ResolvedRange,
 ) -> anyhow::Result<CommandCenterSection<CommandCenterItem>> {
     let failed_runs = load_failed_runs(state, range).await?;
@@ -426,7 +457,7 @@ async fn build_attention(
     let mut items = Vec::new();
 
     for row in failed_runs {
-        if !scope.includes_agent(row.agent_id.as_deref()) {
+        if !scope.includes(row.agent_id.as_deref(), &row.job_id) {
             continue;
         }
 
@@ -471,7 +502,7 @@ async fn build_attention(
     }
 
     for row in failed_notifications {
-        if !scope.includes_agent(row.agent_id.as_deref()) {
+        if !scope.includes(row.agent_id.as_deref(), &row.job_id) {
             continue;
         }
 
@@ -580,7 +611,7 @@ async fn build_attention(
 
 async fn build_critical_activity(
     state: &AppState,
-    scope: &RequestedScope,
+    scope: &ViewScope,
     range: ResolvedRange,
 ) -> anyhow::Result<CommandCenterSection<CommandCenterItem>> {
     let recent_runs = load_recent_runs(state, range).await?;
@@ -589,7 +620,7 @@ async fn build_critical_activity(
     let mut items = Vec::new();
 
     for row in recent_runs {
-        if !scope.includes_agent(row.agent_id.as_deref()) {
+        if !scope.includes(row.agent_id.as_deref(), &row.job_id) {
             continue;
         }
 
@@ -636,7 +667,7 @@ async fn build_critical_activity(
     }
 
     for row in recent_operations {
-        if !scope.includes_agent(row.agent_id.as_deref()) {
+        if !scope.includes(row.agent_id.as_deref(), &row.job_id) {
             continue;
         }
 
@@ -693,7 +724,7 @@ async fn build_critical_activity(
 
 async fn build_watchlist(
     state: &AppState,
-    scope: &RequestedScope,
+    scope: &ViewScope,
     range: ResolvedRange,
 ) -> anyhow::Result<CommandCenterSection<CommandCenterItem>> {
     let recent_runs = load_recent_runs(state, range).await?;
@@ -703,7 +734,7 @@ async fn build_watchlist(
     let mut items = Vec::new();
 
     for row in recent_runs {
-        if !scope.includes_agent(row.agent_id.as_deref()) {
+        if !scope.includes(row.agent_id.as_deref(), &row.job_id) {
             continue;
         }
         if row.status != "running" && row.status != "queued" {
@@ -737,7 +768,7 @@ async fn build_watchlist(
     }
 
     for row in recent_operations {
-        if !scope.includes_agent(row.agent_id.as_deref()) {
+        if !scope.includes(row.agent_id.as_deref(), &row.job_id) {
             continue;
         }
         if row.status != "running" {
@@ -776,7 +807,7 @@ async fn build_watchlist(
     }
 
     for row in queued_notifications {
-        if !scope.includes_agent(row.agent_id.as_deref()) {
+        if !scope.includes(row.agent_id.as_deref(), &row.job_id) {
             continue;
         }
 
@@ -807,7 +838,7 @@ async fn build_watchlist(
         });
     }
 
-    items.sort_by(|left, right| right.occurred_at.cmp(&left.occurred_at));
+    items.sort_by_key(|item| std::cmp::Reverse(item.occurred_at));
     items.truncate(6);
 
     Ok(section_from_items(items))
@@ -815,7 +846,7 @@ async fn build_watchlist(
 
 async fn build_recovery_readiness(
     state: &AppState,
-    scope: &RequestedScope,
+    scope: &ViewScope,
 ) -> anyhow::Result<RecoveryReadiness> {
     let active_jobs = load_active_jobs(state).await?;
     let backup_coverage = load_backup_job_signals(state).await?;
@@ -825,7 +856,7 @@ async fn build_recovery_readiness(
 
     let scoped_jobs: Vec<_> = active_jobs
         .into_iter()
-        .filter(|row| scope.includes_agent(row.agent_id.as_deref()))
+        .filter(|row| scope.includes(row.agent_id.as_deref(), &row.job_id))
         .collect();
     let active_jobs_count = scoped_jobs.len() as i64;
 
@@ -873,10 +904,10 @@ async fn build_recovery_readiness(
 
     let latest_backup = latest_backup
         .into_iter()
-        .find(|row| scope.includes_agent(row.agent_id.as_deref()));
+        .find(|row| scope.includes(row.agent_id.as_deref(), &row.job_id));
     let latest_verify = latest_verify
         .into_iter()
-        .find(|row| scope.includes_agent(row.agent_id.as_deref()));
+        .find(|row| scope.includes(row.agent_id.as_deref(), &row.job_id));
 
     let mut blockers = Vec::new();
 
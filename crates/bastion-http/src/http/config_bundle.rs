@@ -0,0 +1,174 @@
+use std::collections::BTreeSet;
+
+use axum::Extension;
+use axum::Json;
+use axum::body::Body;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::Response;
+use serde::Deserialize;
+use tower_cookies::Cookies;
+
+use bastion_core::HUB_NODE_ID;
+use bastion_storage::config_bundle::{self, ImportSummary};
+use bastion_storage::{audit_repo, auth, job_access_repo};
+
+use super::agents::send_node_config_snapshot;
+use super::secrets::validate_keypack_password;
+use super::shared::{ClientConnInfo, reauthenticate, require_csrf, require_session};
+use super::{AppError, AppState};
+
+async fn require_admin(state: &AppState, session: &auth::SessionRow) -> Result<(), AppError> {
+    if !job_access_repo::is_admin(&state.db, session.user_id).await? {
+        return Err(AppError::forbidden(
+            "admin_required",
+            "Only admins can export or import the Hub configuration",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub(in crate::http) struct ExportConfigRequest {
+    current_password: String,
+    /// Seals every locally stored secret into the bundle with this password.
+    #[serde(default)]
+    password: Option<String>,
+}
+
+pub(in crate::http) async fn export_config(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Json(req): Json<ExportConfigRequest>,
+) -> Result<Response, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_admin(&state, &session).await?;
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+    reauthenticate(&state, &session, &client_ip, &req.current_password, now).await?;
+    let password = req.password.filter(|v| !v.is_empty());
+    if let Some(password) = password.as_deref() {
+        validate_keypack_password(password)?;
+    }
+
+    let bundle =
+        config_bundle::collect(&state.db, state.secrets.as_ref(), password.is_some()).await?;
+    let jobs = bundle.jobs.len();
+    let secrets = bundle.secrets.len();
+    // Argon2 key derivation is deliberately slow; keep it off the async workers.
+    let bytes =
+        tokio::task::spawn_blocking(move || config_bundle::encode(&bundle, password.as_deref()))
+            .await??;
+
+    let detail = format!("jobs={jobs} secrets={secrets}");
+    audit_repo::append(
+        &state.db,
+        now,
+        audit_repo::ACTION_CONFIG_EXPORTED,
+        Some(session.user_id),
+        Some(&client_ip),
+        Some(&detail),
+    )
+    .await?;
+    tracing::warn!(client_ip = %client_ip, user_id = session.user_id, jobs, secrets, "config exported");
+
+    let filename = format!("bastion-config-{now}.json");
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(
+            header::CONTENT_DISPOSITION,
+            HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+                .unwrap_or_else(|_| HeaderValue::from_static("attachment")),
+        )
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from(bytes))?;
+    Ok(resp)
+}
+
+#[derive(Deserialize)]
+pub(in crate::http) struct ImportConfigRequest {
+    current_password: String,
+    bundle: serde_json::Value,
+    /// Required when the bundle contains secrets.
+    #[serde(default)]
+    password: Option<String>,
+}
+
+pub(in crate::http) async fn import_config(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Json(req): Json<ImportConfigRequest>,
+) -> Result<Json<ImportSummary>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_admin(&state, &session).await?;
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+    reauthenticate(&state, &session, &client_ip, &req.current_password, now).await?;
+
+    let bytes = serde_json::to_vec(&req.bundle)?;
+    let password = req.password.filter(|v| !v.is_empty());
+    let bundle =
+        tokio::task::spawn_blocking(move || config_bundle::decode(&bytes, password.as_deref()))
+            .await?
+            .map_err(|error| {
+                AppError::bad_request(
+                    "invalid_bundle",
+                    format!("Config bundle could not be read: {error}"),
+                )
+                .with_field("bundle")
+            })?;
+
+    let summary = config_bundle::apply(&state.db, state.secrets.as_ref(), &bundle).await?;
+    for job_id in &summary.jobs_created {
+        job_access_repo::set_owner(&state.db, job_id, Some(session.user_id)).await?;
+    }
+
+    let detail = format!(
+        "jobs_created={} jobs_skipped={} secrets_imported={}",
+        summary.jobs_created.len(),
+        summary.jobs_skipped.len(),
+        summary.secrets_imported
+    );
+    audit_repo::append(
+        &state.db,
+        now,
+        audit_repo::ACTION_CONFIG_IMPORTED,
+        Some(session.user_id),
+        Some(&client_ip),
+        Some(&detail),
+    )
+    .await?;
+    tracing::warn!(client_ip = %client_ip, user_id = session.user_id, %detail, "config imported");
+
+    state.jobs_notify.notify_one();
+    let agents: BTreeSet<&str> = bundle
+        .jobs
+        .iter()
+        .filter_map(|job| job.agent_id.as_deref())
+        .chain(bundle.secrets.iter().map(|secret| secret.node_id.as_str()))
+        .filter(|node_id| *node_id != HUB_NODE_ID)
+        .collect();
+    for agent_id in agents {
+        if state.agent_manager.is_connected(agent_id).await
+            && let Err(error) = send_node_config_snapshot(
+                &state.db,
+                state.secrets.as_ref(),
+                &state.agent_manager,
+                agent_id,
+            )
+            .await
+        {
+            tracing::warn!(agent_id = %agent_id, error = %error, "failed to send agent config snapshot");
+        }
+    }
+
+    Ok(Json(summary))
+}
@@ -0,0 +1,207 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{audit_repo, auth, db, job_access_repo, jobs_repo};
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+async fn start_server(
+    temp: &TempDir,
+    pool: sqlx::SqlitePool,
+) -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
+    let config = test_config(temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool,
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    (addr, server)
+}
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+async fn login(pool: &sqlx::SqlitePool, username: &str) -> auth::SessionRow {
+    let user = auth::find_user_by_username(pool, username)
+        .await
+        .expect("find user")
+        .expect("user exists");
+    auth::create_session(pool, user.id)
+        .await
+        .expect("create session")
+}
+
+#[tokio::test]
+async fn config_bundle_moves_jobs_and_secrets_between_hubs() {
+    let src_temp = TempDir::new().expect("tempdir");
+    let src = db::init(src_temp.path()).await.expect("db init");
+    auth::create_first_user(&src, "admin", "pw")
+        .await
+        .expect("admin");
+    auth::create_user(&src, "alice", "pw").await.expect("alice");
+    jobs_repo::create_job(
+        &src,
+        "nightly",
+        None,
+        Some("0 2 * * *"),
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "root": "/" },
+            "target": { "type": "local_dir", "base_dir": "/tmp" }
+        }),
+    )
+    .await
+    .expect("create job");
+    let (src_addr, src_server) = start_server(&src_temp, src.clone()).await;
+    let client = reqwest::Client::new();
+
+    let admin = login(&src, "admin").await;
+    let alice = login(&src, "alice").await;
+    let resp = client
+        .post(format!("{}/api/config/export", base_url(src_addr)))
+        .header("cookie", format!("bastion_session={}", alice.id))
+        .header("x-csrf-token", alice.csrf_token.clone())
+        .json(&serde_json::json!({ "current_password": "pw" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = client
+        .post(format!("{}/api/config/export", base_url(src_addr)))
+        .header("cookie", format!("bastion_session={}", admin.id))
+        .header("x-csrf-token", admin.csrf_token.clone())
+        .json(&serde_json::json!({ "current_password": "wrong", "password": "bundle-password" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_credentials");
+
+    let resp = client
+        .post(format!("{}/api/config/export", base_url(src_addr)))
+        .header("cookie", format!("bastion_session={}", admin.id))
+        .header("x-csrf-token", admin.csrf_token.clone())
+        .json(&serde_json::json!({ "current_password": "pw", "password": "bundle-password" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()
+            .get("cache-control")
+            .and_then(|v| v.to_str().ok()),
+        Some("no-store")
+    );
+    let bundle: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(bundle["jobs"][0]["name"], "nightly");
+    assert!(bundle["secrets"].is_object());
+
+    let exported = audit_repo::list_recent(&src, &[audit_repo::ACTION_CONFIG_EXPORTED], 10)
+        .await
+        .expect("audit");
+    assert_eq!(exported.len(), 1);
+    src_server.abort();
+
+    let dst_temp = TempDir::new().expect("tempdir");
+    let dst = db::init(dst_temp.path()).await.expect("db init");
+    auth::create_first_user(&dst, "admin", "pw")
+        .await
+        .expect("admin");
+    let (dst_addr, dst_server) = start_server(&dst_temp, dst.clone()).await;
+    let admin = login(&dst, "admin").await;
+
+    let resp = client
+        .post(format!("{}/api/config/import", base_url(dst_addr)))
+        .header("cookie", format!("bastion_session={}", admin.id))
+        .header("x-csrf-token", admin.csrf_token.clone())
+        .json(&serde_json::json!({ "current_password": "pw", "bundle": bundle }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_bundle");
+
+    let resp = client
+        .post(format!("{}/api/config/import", base_url(dst_addr)))
+        .header("cookie", format!("bastion_session={}", admin.id))
+        .header("x-csrf-token", admin.csrf_token.clone())
+        .json(&serde_json::json!({
+            "current_password": "pw",
+            "bundle": bundle,
+            "password": "bundle-password",
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let summary: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(summary["jobs_created"].as_array().map(Vec::len), Some(1));
+
+    let jobs = jobs_repo::list_jobs(&dst).await.expect("jobs");
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].name, "nightly");
+    assert_eq!(jobs[0].schedule.as_deref(), Some("0 2 * * *"));
+    let access = job_access_repo::get_access(&dst, &jobs[0].id)
+        .await
+        .expect("access")
+        .expect("job access");
+    assert_eq!(access.owner_user_id, Some(admin.user_id));
+
+    dst_server.abort();
+}
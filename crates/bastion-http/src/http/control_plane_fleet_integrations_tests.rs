@@ -28,6 +28,11 @@ fn test_config(temp: &TempDir) -> Arc<Config> {
         hub_timezone: "UTC".to_string(),
         run_retention_days: 180,
         incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
         trusted_proxies: vec![
             "127.0.0.1/32".parse().expect("proxy"),
             "::1/128".parse().expect("proxy"),
@@ -750,3 +755,141 @@ async fn integrations_detail_endpoints_expose_storage_usage_and_distribution_sco
 
     server.abort();
 }
+
+#[tokio::test]
+async fn runtime_config_reports_values_reloaded_from_config_file() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    let session = seed_admin_session(&pool).await;
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let hub_runtime_config = HubRuntimeConfigMeta::default();
+    let reloaded = hub_runtime_config.reloaded.clone();
+    let app = super::router(app_state(config, pool, secrets, hub_runtime_config));
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    async fn get_config(
+        client: &reqwest::Client,
+        addr: std::net::SocketAddr,
+        session_id: &str,
+    ) -> serde_json::Value {
+        let resp = client
+            .get(format!(
+                "{}/api/settings/hub-runtime-config",
+                base_url(addr)
+            ))
+            .header("cookie", format!("bastion_session={session_id}"))
+            .send()
+            .await
+            .expect("get request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        resp.json::<serde_json::Value>().await.expect("json")
+    }
+
+    let body = get_config(&client, addr, &session.id).await;
+    assert_eq!(
+        body["effective"]["trusted_proxies"],
+        serde_json::json!(["127.0.0.1/32", "::1/128"])
+    );
+    assert_eq!(
+        body["fields"]["log_filter"]["editable"].as_bool(),
+        Some(true)
+    );
+
+    reloaded.set_trusted_proxies(
+        vec!["10.0.0.0/8".parse().expect("proxy")],
+        ConfigValueSource::File,
+    );
+    reloaded.set_log_filter("debug".to_string(), ConfigValueSource::File);
+
+    let body = get_config(&client, addr, &session.id).await;
+    assert_eq!(
+        body["effective"]["trusted_proxies"],
+        serde_json::json!(["10.0.0.0/8"])
+    );
+    assert_eq!(
+        body["fields"]["trusted_proxies"]["source"].as_str(),
+        Some("file")
+    );
+    assert_eq!(body["effective"]["log_filter"].as_str(), Some("debug"));
+    assert_eq!(
+        body["fields"]["log_filter"]["source"].as_str(),
+        Some("file")
+    );
+    assert_eq!(
+        body["fields"]["log_filter"]["editable"].as_bool(),
+        Some(false)
+    );
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn enrollment_tokens_include_install_command_and_qr_payload_when_public_url_is_set() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    let session = seed_admin_session(&pool).await;
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(app_state(
+        config,
+        pool.clone(),
+        secrets,
+        HubRuntimeConfigMeta {
+            public_base_url: Some("https://backup.example.com".to_string()),
+            ..HubRuntimeConfigMeta::default()
+        },
+    ));
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!("{}/api/agents/enrollment-tokens", base_url(addr)))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({ "ttl_seconds": 600, "name": "edge 1" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    let token = body["token"].as_str().expect("token");
+
+    assert_eq!(body["hub_url"].as_str(), Some("https://backup.example.com"));
+    assert_eq!(
+        body["install_command"].as_str(),
+        Some(
+            format!(
+                "bastion agent --hub-url https://backup.example.com --enroll-token {token} --name 'edge 1'"
+            )
+            .as_str()
+        )
+    );
+    let qr: serde_json::Value =
+        serde_json::from_str(body["qr_payload"].as_str().expect("qr payload")).expect("qr json");
+    assert_eq!(qr["hub_url"].as_str(), Some("https://backup.example.com"));
+    assert_eq!(qr["token"].as_str(), Some(token));
+
+    server.abort();
+}
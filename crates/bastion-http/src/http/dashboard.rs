@@ -2,12 +2,13 @@ use std::collections::HashMap;
 
 use axum::Json;
 use serde::Serialize;
-use sqlx::Row;
+use sqlx::{QueryBuilder, Row, Sqlite};
 use tower_cookies::Cookies;
 
+use bastion_storage::job_access_repo;
 use bastion_storage::runs_repo;
 
-use super::shared::require_session;
+use super::shared::{job_list_viewer, require_session};
 use super::{AppError, AppState};
 
 #[derive(Debug, Serialize)]
@@ -82,11 +83,29 @@ pub(in crate::http) struct DashboardRecentRun {
     executed_offline: bool,
 }
 
+/// Limits `job_id_column` to jobs `viewer` can see, joined with `keyword` (`WHERE`/`AND`).
+fn push_visible_job_filter(
+    qb: &mut QueryBuilder<'_, Sqlite>,
+    viewer: Option<i64>,
+    keyword: &str,
+    job_id_column: &str,
+) {
+    let Some(user_id) = viewer else {
+        return;
+    };
+    qb.push(format!(
+        " {keyword} {job_id_column} IN (SELECT vj.id FROM jobs vj WHERE "
+    ));
+    job_access_repo::push_visible_jobs_condition(qb, "vj", user_id);
+    qb.push(")");
+}
+
 pub(in crate::http) async fn get_overview(
     state: axum::extract::State<AppState>,
     cookies: Cookies,
 ) -> Result<Json<DashboardOverviewResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    let viewer = job_list_viewer(&state, &session).await?;
 
     let now = time::OffsetDateTime::now_utc();
     let now_ts = now.unix_timestamp();
@@ -120,37 +139,35 @@ pub(in crate::http) async fn get_overview(
     let agents_offline = agents_active.saturating_sub(agents_online);
 
     // Jobs.
-    let row = sqlx::query(
+    let mut qb = QueryBuilder::<Sqlite>::new(
         r#"
         SELECT
           COALESCE(SUM(CASE WHEN archived_at IS NULL THEN 1 ELSE 0 END), 0) AS active,
           COALESCE(SUM(CASE WHEN archived_at IS NOT NULL THEN 1 ELSE 0 END), 0) AS archived
         FROM jobs
         "#,
-    )
-    .fetch_one(&state.db)
-    .await?;
+    );
+    push_visible_job_filter(&mut qb, viewer, "WHERE", "id");
+    let row = qb.build().fetch_one(&state.db).await?;
 
     let jobs_active = row.get::<i64, _>("active");
     let jobs_archived = row.get::<i64, _>("archived");
 
     // Runs (live + last 24h).
-    let row = sqlx::query(
+    let mut qb = QueryBuilder::<Sqlite>::new(
         r#"
         SELECT
           COALESCE(SUM(CASE WHEN status = 'running' THEN 1 ELSE 0 END), 0) AS running,
           COALESCE(SUM(CASE WHEN status = 'queued' THEN 1 ELSE 0 END), 0) AS queued,
-          COALESCE(SUM(CASE WHEN ended_at IS NOT NULL AND ended_at >= ? AND status = 'success' THEN 1 ELSE 0 END), 0) AS success_24h,
-          COALESCE(SUM(CASE WHEN ended_at IS NOT NULL AND ended_at >= ? AND status = 'failed' THEN 1 ELSE 0 END), 0) AS failed_24h,
-          COALESCE(SUM(CASE WHEN ended_at IS NOT NULL AND ended_at >= ? AND status = 'rejected' THEN 1 ELSE 0 END), 0) AS rejected_24h
-        FROM runs
-        "#,
-    )
-    .bind(cutoff_24h)
-    .bind(cutoff_24h)
-    .bind(cutoff_24h)
-    .fetch_one(&state.db)
-    .await?;
+          COALESCE(SUM(CASE WHEN ended_at >= c.cutoff AND status = 'success' THEN 1 ELSE 0 END), 0) AS success_24h,
+          COALESCE(SUM(CASE WHEN ended_at >= c.cutoff AND status = 'failed' THEN 1 ELSE 0 END), 0) AS failed_24h,
+          COALESCE(SUM(CASE WHEN ended_at >= c.cutoff AND status = 'rejected' THEN 1 ELSE 0 END), 0) AS rejected_24h
+        FROM runs, (SELECT "#,
+    );
+    qb.push_bind(cutoff_24h);
+    qb.push(" AS cutoff) c");
+    push_visible_job_filter(&mut qb, viewer, "WHERE", "job_id");
+    let row = qb.build().fetch_one(&state.db).await?;
 
     let runs_running = row.get::<i64, _>("running");
     let runs_queued = row.get::<i64, _>("queued");
@@ -159,7 +176,7 @@ pub(in crate::http) async fn get_overview(
     let runs_rejected_24h = row.get::<i64, _>("rejected_24h");
 
     // Notifications queue (exclude sent).
-    let row = sqlx::query(
+    let mut qb = QueryBuilder::<Sqlite>::new(
         r#"
         SELECT
           COALESCE(SUM(CASE WHEN status = 'queued' THEN 1 ELSE 0 END), 0) AS queued,
@@ -169,9 +186,13 @@ pub(in crate::http) async fn get_overview(
         FROM notifications
         WHERE status IN ('queued', 'sending', 'failed', 'canceled')
         "#,
-    )
-    .fetch_one(&state.db)
-    .await?;
+    );
+    if viewer.is_some() {
+        qb.push(" AND run_id IN (SELECT vr.id FROM runs vr");
+        push_visible_job_filter(&mut qb, viewer, "WHERE", "vr.job_id");
+        qb.push(")");
+    }
+    let row = qb.build().fetch_one(&state.db).await?;
 
     let notifications_queued = row.get::<i64, _>("queued");
     let notifications_sending = row.get::<i64, _>("sending");
@@ -183,7 +204,7 @@ pub(in crate::http) async fn get_overview(
     let start = today - time::Duration::days(6);
     let start_ts = start.midnight().assume_utc().unix_timestamp();
 
-    let rows = sqlx::query(
+    let mut qb = QueryBuilder::<Sqlite>::new(
         r#"
         SELECT
           date(datetime(ended_at, 'unixepoch')) AS day,
@@ -191,15 +212,13 @@ pub(in crate::http) async fn get_overview(
           SUM(CASE WHEN status = 'failed' THEN 1 ELSE 0 END) AS failed
         FROM runs
         WHERE ended_at IS NOT NULL
-          AND ended_at >= ?
           AND status IN ('success', 'failed')
-        GROUP BY day
-        ORDER BY day ASC
-        "#,
-    )
-    .bind(start_ts)
-    .fetch_all(&state.db)
-    .await?;
+          AND ended_at >= "#,
+    );
+    qb.push_bind(start_ts);
+    push_visible_job_filter(&mut qb, viewer, "AND", "job_id");
+    qb.push(" GROUP BY day ORDER BY day ASC");
+    let rows = qb.build().fetch_all(&state.db).await?;
 
     let mut map: HashMap<String, (i64, i64)> = HashMap::new();
     for row in rows {
@@ -223,7 +242,7 @@ pub(in crate::http) async fn get_overview(
     }
 
     // Recent runs.
-    let rows = sqlx::query(
+    let mut qb = QueryBuilder::<Sqlite>::new(
         r#"
         SELECT
           r.id AS run_id,
@@ -239,12 +258,14 @@ pub(in crate::http) async fn get_overview(
         FROM runs r
         JOIN jobs j ON j.id = r.job_id
         LEFT JOIN agents a ON a.id = j.agent_id
-        ORDER BY r.started_at DESC
-        LIMIT 20
         "#,
-    )
-    .fetch_all(&state.db)
-    .await?;
+    );
+    if let Some(user_id) = viewer {
+        qb.push(" WHERE ");
+        job_access_repo::push_visible_jobs_condition(&mut qb, "j", user_id);
+    }
+    qb.push(" ORDER BY r.started_at DESC LIMIT 20");
+    let rows = qb.build().fetch_all(&state.db).await?;
 
     let mut recent_runs: Vec<DashboardRecentRun> = Vec::with_capacity(rows.len());
     for row in rows {
@@ -1,11 +1,11 @@
+use axum::Extension;
 use axum::body::Body;
-use axum::extract::{ConnectInfo, State};
 use axum::http::{HeaderMap, Method, StatusCode, Uri};
 use axum::response::{IntoResponse, Response};
 use tower_cookies::Cookies;
 use tower_cookies::cookie::{Cookie, SameSite, time::Duration};
 
-use super::{AppState, shared};
+use super::shared;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DocsLocale {
@@ -106,15 +106,7 @@ fn docs_locale_from_path(path: &str) -> DocsLocale {
     }
 }
 
-fn maybe_set_locale_cookie(
-    state: &AppState,
-    headers: &HeaderMap,
-    peer_ip: std::net::IpAddr,
-    cookies: &Cookies,
-    locale: DocsLocale,
-) {
-    let is_secure = shared::request_is_https(state, headers, peer_ip);
-
+fn maybe_set_locale_cookie(client: &shared::ClientConnInfo, cookies: &Cookies, locale: DocsLocale) {
     let mut cookie = Cookie::new(
         shared::LOCALE_COOKIE_NAME,
         locale.cookie_value().to_string(),
@@ -122,7 +114,7 @@ fn maybe_set_locale_cookie(
     cookie.set_http_only(false);
     cookie.set_same_site(SameSite::Lax);
     cookie.set_path("/");
-    cookie.set_secure(is_secure);
+    cookie.set_secure(client.https);
     cookie.set_max_age(Duration::days(365));
 
     cookies.add(cookie);
@@ -135,10 +127,9 @@ pub(super) async fn docs_redirect(uri: Uri, headers: HeaderMap, cookies: Cookies
 }
 
 pub(super) async fn docs_fallback(
-    State(state): State<AppState>,
     cookies: Cookies,
     headers: HeaderMap,
-    ConnectInfo(peer): ConnectInfo<std::net::SocketAddr>,
+    Extension(client): Extension<shared::ClientConnInfo>,
     method: Method,
     uri: Uri,
 ) -> Response {
@@ -184,7 +175,7 @@ pub(super) async fn docs_fallback(
             if let Some(bytes) = load_docs_asset_bytes(served_path) {
                 let mut resp = serve_embed(method.clone(), served_path, bytes, &headers);
                 if served_path.ends_with(".html") {
-                    maybe_set_locale_cookie(&state, &headers, peer.ip(), &cookies, request_locale);
+                    maybe_set_locale_cookie(&client, &cookies, request_locale);
                 }
                 if is_fallback_404 && resp.status() == StatusCode::OK {
                     *resp.status_mut() = StatusCode::NOT_FOUND;
@@ -198,7 +189,7 @@ pub(super) async fn docs_fallback(
             if let Some(resp) = serve_fs(method.clone(), served_path, &headers).await {
                 let mut resp = resp;
                 if served_path.ends_with(".html") {
-                    maybe_set_locale_cookie(&state, &headers, peer.ip(), &cookies, request_locale);
+                    maybe_set_locale_cookie(&client, &cookies, request_locale);
                 }
                 if is_fallback_404 && resp.status() == StatusCode::OK {
                     *resp.status_mut() = StatusCode::NOT_FOUND;
@@ -465,6 +456,11 @@ mod tests {
             hub_timezone: "UTC".to_string(),
             run_retention_days: 180,
             incomplete_cleanup_days: 7,
+            staging_dir: None,
+            staging_min_free_bytes: 0,
+            ui_dev_proxy: None,
+            api_rate_limit_per_ip: 0,
+            api_rate_limit_per_session: 0,
             trusted_proxies: vec![
                 "127.0.0.1/32".parse().expect("proxy"),
                 "::1/128".parse().expect("proxy"),
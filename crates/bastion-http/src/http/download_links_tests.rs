@@ -0,0 +1,247 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db};
+use bastion_storage::{jobs_repo, operations_repo, runs_repo};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+async fn insert_hub_restore(pool: &sqlx::SqlitePool, directory: &std::path::Path) -> String {
+    let job = jobs_repo::create_job(
+        pool,
+        "job1",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "root": "/data" },
+            "target": { "type": "local_dir", "base_dir": "/tmp" }
+        }),
+    )
+    .await
+    .expect("create job");
+    runs_repo::create_run_with_id(
+        pool,
+        "run1",
+        &job.id,
+        runs_repo::RunStatus::Success,
+        100,
+        Some(120),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+
+    let op = operations_repo::create_operation(
+        pool,
+        operations_repo::OperationKind::Restore,
+        Some(("run", "run1")),
+    )
+    .await
+    .expect("create operation");
+    operations_repo::append_event(
+        pool,
+        &op.id,
+        "info",
+        "requested",
+        "requested",
+        Some(serde_json::json!({
+            "run_id": "run1",
+            "destination": {
+                "type": "local_fs",
+                "node_id": "hub",
+                "directory": directory.to_string_lossy(),
+            },
+            "executor": { "node_id": "hub" },
+            "conflict_policy": "overwrite",
+        })),
+    )
+    .await
+    .expect("append event");
+    operations_repo::complete_operation(
+        pool,
+        &op.id,
+        operations_repo::OperationStatus::Success,
+        None,
+        None,
+    )
+    .await
+    .expect("complete operation");
+    op.id
+}
+
+#[tokio::test]
+async fn restored_file_link_downloads_without_session_and_is_audited() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let restore_dir = temp.path().join("restored");
+    std::fs::create_dir_all(restore_dir.join("docs")).expect("mkdir");
+    std::fs::write(restore_dir.join("docs").join("report.txt"), b"hello").expect("write");
+    let op_id = insert_hub_restore(&pool, &restore_dir).await;
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let create = |path: &'static str| {
+        client
+            .post(format!(
+                "{}/api/operations/{}/download-links",
+                base_url(addr),
+                op_id
+            ))
+            .header("cookie", format!("bastion_session={}", session.id))
+            .header("x-csrf-token", session.csrf_token.clone())
+            .json(&serde_json::json!({ "path": path, "expires_in_seconds": 600 }))
+            .send()
+    };
+
+    let resp = create("../restored/docs/report.txt")
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let resp = create("docs/missing.txt").await.expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = create("docs/report.txt").await.expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let created: serde_json::Value = resp.json().await.expect("json");
+    let link_id = created["id"].as_str().expect("id").to_string();
+    let url = created["url"].as_str().expect("url").to_string();
+    assert!(url.starts_with("/api/download/"));
+
+    // No cookie: the token alone grants access.
+    let resp = client
+        .get(format!("{}{}", base_url(addr), url))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()
+            .get("content-disposition")
+            .and_then(|v| v.to_str().ok()),
+        Some("attachment; filename=\"report.txt\"")
+    );
+    assert_eq!(resp.bytes().await.expect("body").as_ref(), b"hello");
+
+    let resp = client
+        .post(format!(
+            "{}/api/download-links/{}/revoke",
+            base_url(addr),
+            link_id
+        ))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .header("x-csrf-token", session.csrf_token.clone())
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = client
+        .get(format!("{}{}", base_url(addr), url))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = client
+        .get(format!(
+            "{}/api/download-links/{}/audit",
+            base_url(addr),
+            link_id
+        ))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["link"]["download_count"].as_i64(), Some(1));
+    let actions: Vec<&str> = body["items"]
+        .as_array()
+        .expect("items")
+        .iter()
+        .filter_map(|v| v["action"].as_str())
+        .collect();
+    assert_eq!(actions, vec!["created", "downloaded", "revoked"]);
+
+    server.abort();
+}
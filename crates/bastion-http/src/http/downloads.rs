@@ -0,0 +1,517 @@
+use std::path::{Path as FsPath, PathBuf};
+
+use axum::Extension;
+use axum::Json;
+use axum::body::Body;
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::Response;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio_util::io::ReaderStream;
+use tower_cookies::Cookies;
+
+use bastion_backup::restore;
+use bastion_core::{HUB_NODE_ID, agent};
+use bastion_storage::download_links_repo::{
+    self, DownloadLink, DownloadLinkAuditEntry, NewDownloadLink,
+};
+use bastion_storage::job_access_repo::{self, JobPermission};
+use bastion_storage::{auth, operations_repo, runs_repo};
+
+use super::shared::{
+    ClientConnInfo, job_list_viewer, require_csrf, require_operation_permission,
+    require_run_permission, require_session,
+};
+use super::{AppError, AppState};
+
+const DEFAULT_EXPIRES_IN_SECONDS: i64 = 60 * 60;
+const MIN_EXPIRES_IN_SECONDS: i64 = 60;
+const MAX_EXPIRES_IN_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+const DEFAULT_LIST_LIMIT: u32 = 100;
+const MAX_LIST_LIMIT: u32 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub(super) struct CreateSnapshotDownloadLinkRequest {
+    /// Artifact path inside the snapshot, e.g. `manifest.json` or `payload.part000001`.
+    artifact: String,
+    #[serde(default)]
+    expires_in_seconds: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct CreateRestoredFileDownloadLinkRequest {
+    /// File path relative to the restore destination directory.
+    path: String,
+    #[serde(default)]
+    expires_in_seconds: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct CreateDownloadLinkResponse {
+    id: String,
+    /// Relative URL that can be fetched without a session; shown only once.
+    url: String,
+    expires_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ListDownloadLinksQuery {
+    #[serde(default)]
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct ListDownloadLinksResponse {
+    items: Vec<DownloadLink>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct DownloadLinkAuditResponse {
+    link: DownloadLink,
+    items: Vec<DownloadLinkAuditEntry>,
+}
+
+fn resolve_expires_in(expires_in_seconds: Option<i64>) -> Result<i64, AppError> {
+    let value = expires_in_seconds.unwrap_or(DEFAULT_EXPIRES_IN_SECONDS);
+    if !(MIN_EXPIRES_IN_SECONDS..=MAX_EXPIRES_IN_SECONDS).contains(&value) {
+        return Err(
+            AppError::bad_request("invalid_expires_in", "Invalid link expiry")
+                .with_reason("out_of_range")
+                .with_field("expires_in_seconds")
+                .with_param("min", MIN_EXPIRES_IN_SECONDS)
+                .with_param("max", MAX_EXPIRES_IN_SECONDS),
+        );
+    }
+    Ok(value)
+}
+
+/// Normalizes a relative `a/b/c` path, rejecting absolute paths and `..` segments.
+fn normalize_relative_path(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    if raw.is_empty() || raw.starts_with('/') || raw.contains('\\') {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+    for segment in raw.split('/') {
+        match segment {
+            "" | "." => continue,
+            ".." => return None,
+            s if s.contains(':') => return None,
+            s => parts.push(s),
+        }
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    Some(parts.join("/"))
+}
+
+fn invalid_path_error(field: &'static str, message: &'static str) -> AppError {
+    AppError::bad_request("invalid_path", message)
+        .with_reason("invalid_value")
+        .with_field(field)
+}
+
+async fn create_link(
+    state: &AppState,
+    client: &ClientConnInfo,
+    user_id: i64,
+    kind: &str,
+    subject_id: &str,
+    path: &str,
+    expires_in_seconds: i64,
+) -> Result<CreateDownloadLinkResponse, AppError> {
+    let token = agent::generate_token_b64_urlsafe(32);
+    let token_hash = agent::sha256_urlsafe_token(&token)?;
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+
+    let link = download_links_repo::create_link(
+        &state.db,
+        NewDownloadLink {
+            token_hash: &token_hash,
+            kind,
+            subject_id,
+            path,
+            created_by_user_id: Some(user_id),
+            created_at: now,
+            expires_at: now + expires_in_seconds,
+        },
+        Some(&client_ip),
+    )
+    .await?;
+
+    tracing::info!(
+        link_id = %link.id,
+        kind = %kind,
+        subject_id = %subject_id,
+        user_id,
+        expires_at = link.expires_at,
+        "download link created"
+    );
+
+    Ok(CreateDownloadLinkResponse {
+        id: link.id,
+        url: format!("/api/download/{token}"),
+        expires_at: link.expires_at,
+    })
+}
+
+pub(super) async fn create_snapshot_download_link(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Path(run_id): Path<String>,
+    Json(req): Json<CreateSnapshotDownloadLinkRequest>,
+) -> Result<Json<CreateDownloadLinkResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_run_permission(&state, &session, &run_id, JobPermission::Trigger).await?;
+
+    let expires_in = resolve_expires_in(req.expires_in_seconds)?;
+    let artifact = normalize_relative_path(&req.artifact)
+        .ok_or_else(|| invalid_path_error("artifact", "Invalid artifact path"))?;
+
+    let run = runs_repo::get_run(&state.db, &run_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("run_not_found", "Run not found"))?;
+    if run.status != runs_repo::RunStatus::Success {
+        return Err(AppError::bad_request(
+            "invalid_run",
+            "Run is not successful",
+        ));
+    }
+
+    let resp = create_link(
+        &state,
+        &client,
+        session.user_id,
+        download_links_repo::KIND_SNAPSHOT_ARTIFACT,
+        &run.id,
+        &artifact,
+        expires_in,
+    )
+    .await?;
+    Ok(Json(resp))
+}
+
+/// Returns the Hub-local directory a successful restore operation wrote to, if any.
+async fn hub_restore_directory(state: &AppState, op_id: &str) -> Result<Option<PathBuf>, AppError> {
+    let events = operations_repo::list_events(&state.db, op_id, 1).await?;
+    let Some(fields) = events
+        .into_iter()
+        .find(|ev| ev.kind == "requested")
+        .and_then(|ev| ev.fields)
+    else {
+        return Ok(None);
+    };
+
+    let destination = &fields["destination"];
+    let executor_node_id = fields["executor"]["node_id"].as_str().unwrap_or_default();
+    if destination["type"].as_str() != Some("local_fs") || executor_node_id != HUB_NODE_ID {
+        return Ok(None);
+    }
+
+    Ok(destination["directory"]
+        .as_str()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from))
+}
+
+pub(super) async fn create_restored_file_download_link(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Path(op_id): Path<String>,
+    Json(req): Json<CreateRestoredFileDownloadLinkRequest>,
+) -> Result<Json<CreateDownloadLinkResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_operation_permission(&state, &session, &op_id, JobPermission::Trigger).await?;
+
+    let expires_in = resolve_expires_in(req.expires_in_seconds)?;
+    let rel = normalize_relative_path(&req.path)
+        .ok_or_else(|| invalid_path_error("path", "Invalid file path"))?;
+
+    let op = operations_repo::get_operation(&state.db, &op_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("operation_not_found", "Operation not found"))?;
+    if op.kind != operations_repo::OperationKind::Restore {
+        return Err(
+            AppError::bad_request("invalid_operation", "Operation is not a restore")
+                .with_reason("not_restore"),
+        );
+    }
+    if op.status != operations_repo::OperationStatus::Success {
+        return Err(AppError::bad_request(
+            "invalid_operation",
+            "Restore has not completed successfully",
+        )
+        .with_reason("not_successful"));
+    }
+
+    let Some(directory) = hub_restore_directory(&state, &op.id).await? else {
+        return Err(AppError::bad_request(
+            "unsupported_destination",
+            "Only restores to the Hub's local filesystem can be downloaded",
+        ));
+    };
+
+    let file_path = resolve_restored_file(&directory, &rel)
+        .await
+        .ok_or_else(|| invalid_path_error("path", "File not found in restore destination"))?;
+
+    let resp = create_link(
+        &state,
+        &client,
+        session.user_id,
+        download_links_repo::KIND_RESTORED_FILE,
+        &op.id,
+        &file_path.to_string_lossy(),
+        expires_in,
+    )
+    .await?;
+    Ok(Json(resp))
+}
+
+/// Resolves `rel` under `directory`, requiring a regular file that stays inside it after
+/// following symlinks.
+async fn resolve_restored_file(directory: &FsPath, rel: &str) -> Option<PathBuf> {
+    let root = tokio::fs::canonicalize(directory).await.ok()?;
+    let mut candidate = root.clone();
+    for segment in rel.split('/') {
+        candidate.push(segment);
+    }
+    let resolved = tokio::fs::canonicalize(&candidate).await.ok()?;
+    if !resolved.starts_with(&root) {
+        return None;
+    }
+    let meta = tokio::fs::metadata(&resolved).await.ok()?;
+    meta.is_file().then_some(resolved)
+}
+
+/// Links are scoped like the run or restore they point at; hidden ones look missing.
+async fn require_link_permission(
+    state: &AppState,
+    session: &auth::SessionRow,
+    link: &DownloadLink,
+    needed: JobPermission,
+) -> Result<(), AppError> {
+    if job_list_viewer(state, session).await?.is_none() {
+        return Ok(());
+    }
+    let permission = if link.kind == download_links_repo::KIND_SNAPSHOT_ARTIFACT {
+        job_access_repo::permission_for_run(&state.db, &link.subject_id, session.user_id).await?
+    } else {
+        job_access_repo::permission_for_operation(&state.db, &link.subject_id, session.user_id)
+            .await?
+    };
+    match permission {
+        None => Err(AppError::not_found(
+            "download_link_not_found",
+            "Download link not found",
+        )),
+        Some(p) if p < needed => Err(AppError::forbidden(
+            "job_permission_required",
+            "Not allowed for this job",
+        )
+        .with_param("required", needed.as_str())),
+        Some(_) => Ok(()),
+    }
+}
+
+pub(super) async fn list_download_links(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Query(query): Query<ListDownloadLinksQuery>,
+) -> Result<Json<ListDownloadLinksResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    let viewer = job_list_viewer(&state, &session).await?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    if limit == 0 || limit > MAX_LIST_LIMIT {
+        return Err(AppError::bad_request("invalid_limit", "Invalid limit")
+            .with_reason("out_of_range")
+            .with_field("limit")
+            .with_param("max", MAX_LIST_LIMIT));
+    }
+
+    let items = download_links_repo::list_links(&state.db, limit, viewer).await?;
+    Ok(Json(ListDownloadLinksResponse { items }))
+}
+
+pub(super) async fn get_download_link_audit(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(link_id): Path<String>,
+) -> Result<Json<DownloadLinkAuditResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+
+    let link = download_links_repo::get_link(&state.db, &link_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("download_link_not_found", "Download link not found"))?;
+    require_link_permission(&state, &session, &link, JobPermission::View).await?;
+    let items = download_links_repo::list_audit(&state.db, &link.id).await?;
+    Ok(Json(DownloadLinkAuditResponse { link, items }))
+}
+
+pub(super) async fn revoke_download_link(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Path(link_id): Path<String>,
+) -> Result<Json<DownloadLink>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    let link = download_links_repo::get_link(&state.db, &link_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("download_link_not_found", "Download link not found"))?;
+    require_link_permission(&state, &session, &link, JobPermission::Trigger).await?;
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+    let _ = download_links_repo::revoke_link(
+        &state.db,
+        &link_id,
+        Some(session.user_id),
+        Some(&client_ip),
+        now,
+    )
+    .await?;
+
+    let link = download_links_repo::get_link(&state.db, &link_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("download_link_not_found", "Download link not found"))?;
+    Ok(Json(link))
+}
+
+/// Removes a temporary download file once the response body is dropped.
+pub(in crate::http) struct TempFileGuard(pub(in crate::http) PathBuf);
+
+impl Drop for TempFileGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn attachment_header(path: &str) -> HeaderValue {
+    let name = path
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| (c.is_ascii_graphic() && *c != '"' && *c != '\\') || *c == ' ')
+        .collect::<String>();
+    let name = if name.trim().is_empty() {
+        "download".to_string()
+    } else {
+        name
+    };
+    HeaderValue::from_str(&format!("attachment; filename=\"{name}\""))
+        .unwrap_or_else(|_| HeaderValue::from_static("attachment"))
+}
+
+/// Serves a pre-signed download. No session is required: the token is the credential.
+pub(super) async fn download(
+    state: axum::extract::State<AppState>,
+    Extension(client): Extension<ClientConnInfo>,
+    Path(token): Path<String>,
+) -> Result<Response, AppError> {
+    let not_found = || {
+        AppError::not_found(
+            "download_link_not_found",
+            "Download link not found or expired",
+        )
+    };
+
+    let token_hash = agent::sha256_urlsafe_token(&token).map_err(|_| not_found())?;
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let link = download_links_repo::find_active_by_token_hash(&state.db, &token_hash, now)
+        .await?
+        .ok_or_else(not_found)?;
+
+    let (file, guard) = match link.kind.as_str() {
+        download_links_repo::KIND_RESTORED_FILE => {
+            let file = tokio::fs::File::open(&link.path).await.map_err(|_| {
+                AppError::not_found(
+                    "download_source_missing",
+                    "Downloaded file no longer exists",
+                )
+            })?;
+            (file, None)
+        }
+        download_links_repo::KIND_SNAPSHOT_ARTIFACT => {
+            let dir = state.config.data_dir.join("tmp").join("downloads");
+            tokio::fs::create_dir_all(&dir).await?;
+            let dest = dir.join(uuid::Uuid::new_v4().to_string());
+            let guard = TempFileGuard(dest.clone());
+            if let Err(error) = restore::fetch_run_artifact_to_file(
+                &state.db,
+                &state.secrets,
+                &link.subject_id,
+                &link.path,
+                dest.clone(),
+            )
+            .await
+            {
+                tracing::warn!(
+                    link_id = %link.id,
+                    run_id = %link.subject_id,
+                    error = %error,
+                    "failed to fetch snapshot artifact for download"
+                );
+                return Err(AppError::not_found(
+                    "download_source_missing",
+                    "Snapshot artifact is not available",
+                ));
+            }
+            let file = tokio::fs::File::open(&dest).await?;
+            (file, Some(guard))
+        }
+        _ => return Err(not_found()),
+    };
+    let len = file.metadata().await?.len();
+
+    let client_ip = client.ip.to_string();
+    download_links_repo::record_download(&state.db, &link.id, Some(&client_ip), now).await?;
+    tracing::info!(link_id = %link.id, client_ip = %client_ip, "download link used");
+
+    let stream = ReaderStream::new(file).map(move |chunk| {
+        let _ = &guard;
+        chunk
+    });
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/octet-stream")
+        .header(header::CONTENT_LENGTH, len.to_string())
+        .header(header::CONTENT_DISPOSITION, attachment_header(&link.path))
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from_stream(stream))?;
+    Ok(resp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_relative_path;
+
+    #[test]
+    fn relative_paths_reject_traversal_and_absolute_paths() {
+        assert_eq!(
+            normalize_relative_path("./a//b/c.txt").as_deref(),
+            Some("a/b/c.txt")
+        );
+        assert_eq!(normalize_relative_path("../etc/passwd"), None);
+        assert_eq!(normalize_relative_path("a/../../b"), None);
+        assert_eq!(normalize_relative_path("/etc/passwd"), None);
+        assert_eq!(normalize_relative_path("C:/Windows"), None);
+        assert_eq!(normalize_relative_path("a\\b"), None);
+        assert_eq!(normalize_relative_path(" "), None);
+    }
+}
@@ -2,6 +2,7 @@ use std::future::Future;
 
 use axum::Json;
 use axum::http::StatusCode;
+use axum::http::header::{HeaderValue, RETRY_AFTER};
 use axum::response::{IntoResponse, Response};
 use serde::Serialize;
 
@@ -115,6 +116,19 @@ impl AppError {
         }
     }
 
+    pub(in crate::http) fn service_unavailable(
+        code: &'static str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            code,
+            message: message.into(),
+            details: None,
+            debug_details: None,
+        }
+    }
+
     fn ensure_details_object(&mut self) -> &mut serde_json::Map<String, serde_json::Value> {
         let replace = !matches!(self.details, Some(serde_json::Value::Object(_)));
         if replace {
@@ -331,13 +345,28 @@ impl AppError {
             details: Option<serde_json::Value>,
         }
 
+        let retry_after = (self.status == StatusCode::TOO_MANY_REQUESTS)
+            .then(|| {
+                self.details
+                    .as_ref()?
+                    .get("params")?
+                    .get("retry_after_seconds")?
+                    .as_u64()
+            })
+            .flatten();
         let details = merge_response_details(self.details, self.debug_details, options);
         let body = Json(Body {
             error: self.code,
             message: self.message,
             details,
         });
-        (self.status, body).into_response()
+        let mut response = (self.status, body).into_response();
+        if let Some(seconds) = retry_after {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response
     }
 }
 
@@ -398,6 +427,27 @@ mod tests {
         assert_eq!(details["violations"][0]["params"]["min_length"], 12);
     }
 
+    #[test]
+    fn rate_limited_errors_carry_retry_after() {
+        let resp = AppError::too_many_requests("rate_limited", "slow down")
+            .with_param("retry_after_seconds", 7)
+            .into_response();
+        assert_eq!(resp.status(), axum::http::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            resp.headers().get(axum::http::header::RETRY_AFTER),
+            Some(&axum::http::HeaderValue::from(7u64))
+        );
+
+        let resp = AppError::bad_request("invalid", "nope")
+            .with_param("retry_after_seconds", 7)
+            .into_response();
+        assert!(
+            resp.headers()
+                .get(axum::http::header::RETRY_AFTER)
+                .is_none()
+        );
+    }
+
     async fn render_internal_error_details(
         options: AppErrorRenderOptions,
     ) -> Option<serde_json::Value> {
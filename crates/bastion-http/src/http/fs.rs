@@ -15,7 +15,7 @@ use super::list_paging::{
     invalid_cursor_error, invalid_sort_by_error, invalid_sort_dir_error, parse_sort_by,
     parse_sort_dir, rank_kind,
 };
-use super::shared::require_session;
+use super::shared::{list_requester_id, require_session};
 use super::{AppError, AppState};
 
 #[derive(Debug, Deserialize)]
@@ -87,7 +87,7 @@ pub(super) async fn fs_list(
     Path(node_id): Path<String>,
     Query(query): Query<FsListQuery>,
 ) -> Result<Json<FsListResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
 
     let path = query.path.trim();
     if path.is_empty() {
@@ -141,6 +141,7 @@ pub(super) async fn fs_list(
                 sort_dir: query.sort_dir,
                 size_min_bytes: query.size_min_bytes,
                 size_max_bytes: query.size_max_bytes,
+                requester: Some(list_requester_id(&session)),
             },
             Duration::from_secs(5),
         )
@@ -187,9 +188,8 @@ fn map_agent_fs_list_error(path: &str, error: anyhow::Error) -> AppError {
 
         let (mapped_code, mapped_from_legacy_message) = match remote_code.as_str() {
             "permission_denied" | "path_not_found" | "not_directory" | "invalid_cursor"
-            | "invalid_path" | "invalid_sort_by" | "invalid_sort_dir" => {
-                (remote_code.as_str(), false)
-            }
+            | "invalid_path" | "invalid_sort_by" | "invalid_sort_dir" | "rate_limited"
+            | "superseded" => (remote_code.as_str(), false),
             _ => match classify_legacy_remote_fs_error(&message) {
                 Some(mapped) => (mapped, true),
                 None => ("agent_fs_list_failed", false),
@@ -204,6 +204,14 @@ fn map_agent_fs_list_error(path: &str, error: anyhow::Error) -> AppError {
             "invalid_path" => invalid_path_error("required", "path is required"),
             "invalid_sort_by" => invalid_sort_by_error("unsupported_value", "invalid sort_by"),
             "invalid_sort_dir" => invalid_sort_dir_error("unsupported_value", "invalid sort_dir"),
+            "rate_limited" => AppError::too_many_requests(
+                "agent_list_rate_limited",
+                "Agent is throttling directory listings; retry shortly",
+            ),
+            "superseded" => AppError::conflict(
+                "agent_list_superseded",
+                "Listing was superseded by a newer request",
+            ),
             _ => agent_fs_list_failed_error(
                 "remote_error",
                 format!("Agent filesystem list failed: {message}"),
@@ -592,6 +600,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn map_agent_fs_list_error_rate_limited_maps_to_429() {
+        let err = bastion_engine::agent_manager::FsListRemoteError {
+            code: "rate_limited".to_string(),
+            message: "too many list requests; retry shortly".to_string(),
+            details: None,
+        };
+        let app = map_agent_fs_list_error("/tmp", anyhow::Error::new(err));
+        assert_eq!(app.code(), "agent_list_rate_limited");
+        assert_eq!(app.status(), axum::http::StatusCode::TOO_MANY_REQUESTS);
+    }
+
     #[test]
     fn map_agent_fs_list_error_unknown_code_falls_back_generic() {
         let err = bastion_engine::agent_manager::FsListRemoteError {
@@ -790,7 +810,7 @@ mod tests {
             let combined = page1
                 .entries
                 .into_iter()
-                .chain(page2.entries.into_iter())
+                .chain(page2.entries)
                 .collect::<Vec<_>>();
             assert_eq!(&combined[..], &full.entries[..combined.len()]);
         }
@@ -7,6 +7,7 @@ use sqlx::Row;
 use tower_cookies::Cookies;
 
 use bastion_core::HUB_NODE_ID;
+use bastion_storage::notification_destinations_repo;
 
 use super::shared::require_session;
 use super::{AppError, AppState};
@@ -67,6 +68,9 @@ pub(in crate::http) struct StorageHealthSummary {
 pub(in crate::http) struct StorageIntegrationItem {
     name: String,
     updated_at: i64,
+    /// When a replacement credential was staged; cleared once a run promotes it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pending_staged_at: Option<i64>,
     usage_total: i64,
     usage: Vec<StorageUsageRef>,
     health: StorageHealthSummary,
@@ -310,11 +314,12 @@ async fn load_storage_summary_for_node(
 async fn load_notifications_summary(
     db: &sqlx::SqlitePool,
 ) -> Result<NotificationsSummary, anyhow::Error> {
-    let destinations_total = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM secrets WHERE node_id = 'hub' AND kind IN ('wecom_bot', 'smtp')",
+    let destinations_total = notification_destinations_repo::list_destinations(
+        db,
+        &bastion_notify::builtin_channels().destination_kinds(),
     )
-    .fetch_one(db)
-    .await?;
+    .await?
+    .len() as i64;
     let recent_failures_total =
         sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM notifications WHERE status = 'failed'")
             .fetch_one(db)
@@ -339,7 +344,14 @@ async fn load_storage_details(
     let summary = load_storage_summary_for_node(db, node_id).await?;
 
     let secret_rows = sqlx::query(
-        "SELECT name, updated_at FROM secrets WHERE node_id = ? AND kind = 'webdav' ORDER BY name ASC",
+        r#"
+        SELECT s.name, s.updated_at, p.updated_at AS pending_staged_at
+        FROM secrets s
+        LEFT JOIN secrets p
+          ON p.node_id = s.node_id AND p.kind = 'webdav_pending' AND p.name = s.name
+        WHERE s.node_id = ? AND s.kind = 'webdav'
+        ORDER BY s.name ASC
+        "#,
     )
     .bind(node_id)
     .fetch_all(db)
@@ -404,6 +416,7 @@ async fn load_storage_details(
             StorageIntegrationItem {
                 name,
                 updated_at: row.get::<i64, _>("updated_at"),
+                pending_staged_at: row.get::<Option<i64>, _>("pending_staged_at"),
                 usage_total: usage_total as i64,
                 usage,
                 health: StorageHealthSummary {
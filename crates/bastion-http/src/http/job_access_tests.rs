@@ -0,0 +1,492 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db, download_links_repo, job_access_repo, jobs_repo, runs_repo};
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+async fn start_server(
+    temp: &TempDir,
+    pool: sqlx::SqlitePool,
+) -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
+    let config = test_config(temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool,
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    (addr, server)
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn users_only_see_and_restore_jobs_they_were_granted() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    auth::create_first_user(&pool, "admin", "pw")
+        .await
+        .expect("admin");
+    auth::create_user(&pool, "alice", "pw")
+        .await
+        .expect("alice");
+    auth::create_user(&pool, "bob", "pw").await.expect("bob");
+    let session = |name: &'static str| {
+        let pool = pool.clone();
+        async move {
+            let user = auth::find_user_by_username(&pool, name)
+                .await
+                .expect("find user")
+                .expect("user exists");
+            let session = auth::create_session(&pool, user.id)
+                .await
+                .expect("create session");
+            (user.id, session)
+        }
+    };
+    let (_, admin) = session("admin").await;
+    let (alice_id, alice) = session("alice").await;
+    let (_, bob) = session("bob").await;
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "alice-job",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "root": "/" },
+            "target": { "type": "local_dir", "base_dir": "/tmp" }
+        }),
+    )
+    .await
+    .expect("create job");
+    job_access_repo::set_owner(&pool, &job.id, Some(alice_id))
+        .await
+        .expect("set owner");
+    let run = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Success,
+        1,
+        Some(2),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+
+    let (addr, server) = start_server(&temp, pool.clone()).await;
+    let client = reqwest::Client::new();
+    let get = |session: &auth::SessionRow, path: String| {
+        client
+            .get(format!("{}{path}", base_url(addr)))
+            .header("cookie", format!("bastion_session={}", session.id))
+            .send()
+    };
+    let restore = |session: &auth::SessionRow| {
+        client
+            .post(format!("{}/api/runs/{}/restore", base_url(addr), run.id))
+            .header("cookie", format!("bastion_session={}", session.id))
+            .header("x-csrf-token", session.csrf_token.clone())
+            .json(&serde_json::json!({
+                "destination": { "type": "local_fs", "node_id": "hub", "directory": "/restore" },
+                "conflict_policy": "overwrite"
+            }))
+            .send()
+    };
+
+    let resp = get(&bob, "/api/jobs".to_string()).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert!(body["items"].as_array().expect("items").is_empty());
+    let resp = get(&bob, format!("/api/jobs/{}", job.id))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = get(&bob, format!("/api/runs/{}", run.id))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = restore(&bob).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    for session in [&alice, &admin] {
+        let resp = get(session, format!("/api/jobs/{}", job.id))
+            .await
+            .expect("request");
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    // A view grant shows the job but doesn't allow restoring from it.
+    let resp = client
+        .put(format!("{}/api/jobs/{}/access", base_url(addr), job.id))
+        .header("cookie", format!("bastion_session={}", alice.id))
+        .header("x-csrf-token", alice.csrf_token.clone())
+        .json(&serde_json::json!({
+            "owner": "alice",
+            "grants": [{ "username": "bob", "permission": "view" }]
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["owner_username"].as_str(), Some("alice"));
+    assert_eq!(body["grants"][0]["permission"].as_str(), Some("view"));
+
+    let resp = get(&bob, format!("/api/runs/{}", run.id))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = restore(&bob).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("job_permission_required"));
+    assert_eq!(
+        body["details"]["params"]["required"].as_str(),
+        Some("trigger")
+    );
+
+    let resp = client
+        .put(format!("{}/api/jobs/{}/access", base_url(addr), job.id))
+        .header("cookie", format!("bastion_session={}", bob.id))
+        .header("x-csrf-token", bob.csrf_token.clone())
+        .json(&serde_json::json!({ "owner": "bob", "grants": [] }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("job_owner_required"));
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn job_stats_and_download_links_follow_job_access() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    auth::create_first_user(&pool, "admin", "pw")
+        .await
+        .expect("admin");
+    auth::create_user(&pool, "alice", "pw")
+        .await
+        .expect("alice");
+    auth::create_user(&pool, "bob", "pw").await.expect("bob");
+    let session = |name: &'static str| {
+        let pool = pool.clone();
+        async move {
+            let user = auth::find_user_by_username(&pool, name)
+                .await
+                .expect("find user")
+                .expect("user exists");
+            auth::create_session(&pool, user.id)
+                .await
+                .expect("create session")
+        }
+    };
+    let admin = session("admin").await;
+    let alice = session("alice").await;
+    let bob = session("bob").await;
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "alice-job",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "root": "/" },
+            "target": { "type": "local_dir", "base_dir": "/tmp" }
+        }),
+    )
+    .await
+    .expect("create job");
+    job_access_repo::set_owner(&pool, &job.id, Some(alice.user_id))
+        .await
+        .expect("set owner");
+    let run = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Success,
+        1,
+        Some(2),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    let link = download_links_repo::create_link(
+        &pool,
+        download_links_repo::NewDownloadLink {
+            token_hash: &[1, 2, 3],
+            kind: download_links_repo::KIND_SNAPSHOT_ARTIFACT,
+            subject_id: &run.id,
+            path: "manifest.json",
+            created_by_user_id: Some(alice.user_id),
+            created_at: 1,
+            expires_at: i64::MAX,
+        },
+        None,
+    )
+    .await
+    .expect("create link");
+
+    let (addr, server) = start_server(&temp, pool.clone()).await;
+    let client = reqwest::Client::new();
+    let get = |session: &auth::SessionRow, path: String| {
+        client
+            .get(format!("{}{path}", base_url(addr)))
+            .header("cookie", format!("bastion_session={}", session.id))
+            .send()
+    };
+
+    let resp = get(&bob, format!("/api/stats/jobs/{}", job.id))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = get(&bob, "/api/download-links".to_string())
+        .await
+        .expect("request");
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert!(body["items"].as_array().expect("items").is_empty());
+    let resp = get(&bob, format!("/api/download-links/{}/audit", link.id))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = client
+        .post(format!(
+            "{}/api/download-links/{}/revoke",
+            base_url(addr),
+            link.id
+        ))
+        .header("cookie", format!("bastion_session={}", bob.id))
+        .header("x-csrf-token", bob.csrf_token.clone())
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    for session in [&alice, &admin] {
+        let resp = get(session, format!("/api/stats/jobs/{}", job.id))
+            .await
+            .expect("request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = get(session, "/api/download-links".to_string())
+            .await
+            .expect("request");
+        let body: serde_json::Value = resp.json().await.expect("json");
+        assert_eq!(body["items"][0]["id"].as_str(), Some(link.id.as_str()));
+        let resp = get(session, format!("/api/download-links/{}/audit", link.id))
+            .await
+            .expect("request");
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn aggregate_views_only_cover_visible_jobs() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    auth::create_first_user(&pool, "admin", "pw")
+        .await
+        .expect("admin");
+    auth::create_user(&pool, "alice", "pw")
+        .await
+        .expect("alice");
+    auth::create_user(&pool, "bob", "pw").await.expect("bob");
+    let session = |name: &'static str| {
+        let pool = pool.clone();
+        async move {
+            let user = auth::find_user_by_username(&pool, name)
+                .await
+                .expect("find user")
+                .expect("user exists");
+            auth::create_session(&pool, user.id)
+                .await
+                .expect("create session")
+        }
+    };
+    let admin = session("admin").await;
+    let alice = session("alice").await;
+    let bob = session("bob").await;
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "alice-job",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "root": "/" },
+            "target": { "type": "local_dir", "base_dir": "/tmp" }
+        }),
+    )
+    .await
+    .expect("create job");
+    job_access_repo::set_owner(&pool, &job.id, Some(alice.user_id))
+        .await
+        .expect("set owner");
+    // Overdue for its freshness interval, with a recent failed run.
+    sqlx::query("UPDATE jobs SET max_success_interval_seconds = 60, created_at = 1 WHERE id = ?")
+        .bind(&job.id)
+        .execute(&pool)
+        .await
+        .expect("make stale");
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let run = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Failed,
+        now - 10,
+        Some(now - 5),
+        None,
+        Some("network_target"),
+    )
+    .await
+    .expect("create run");
+
+    let (addr, server) = start_server(&temp, pool.clone()).await;
+    let client = reqwest::Client::new();
+    let body = |session: &auth::SessionRow, path: &'static str| {
+        let request = client
+            .get(format!("{}{path}", base_url(addr)))
+            .header("cookie", format!("bastion_session={}", session.id))
+            .send();
+        async move {
+            let resp = request.await.expect("request");
+            assert_eq!(resp.status(), StatusCode::OK, "{path}");
+            resp.json::<serde_json::Value>().await.expect("json")
+        }
+    };
+
+    for (viewer, session, visible) in [
+        ("admin", &admin, true),
+        ("alice", &alice, true),
+        ("bob", &bob, false),
+    ] {
+        let contains_job = |value: &serde_json::Value| value.to_string().contains(&job.id);
+
+        let stats = body(session, "/api/stats?refresh=true").await;
+        assert_eq!(contains_job(&stats["jobs"]), visible, "{viewer} stats");
+        assert_eq!(
+            stats["totals"]["runs_failed"].as_u64(),
+            Some(u64::from(visible)),
+            "{viewer} stats totals"
+        );
+
+        let stale = body(session, "/api/alerts/stale-backups").await;
+        assert_eq!(contains_job(&stale["items"]), visible, "{viewer} stale");
+
+        let dashboard = body(session, "/api/dashboard/overview").await;
+        assert_eq!(
+            dashboard["stats"]["jobs"]["active"].as_i64(),
+            Some(i64::from(visible)),
+            "{viewer} dashboard jobs"
+        );
+        assert_eq!(
+            dashboard["stats"]["runs"]["failed_24h"].as_i64(),
+            Some(i64::from(visible)),
+            "{viewer} dashboard runs"
+        );
+        assert_eq!(
+            contains_job(&dashboard["recent_runs"]),
+            visible,
+            "{viewer} dashboard recent runs"
+        );
+
+        let command_center = body(session, "/api/command-center").await;
+        assert_eq!(
+            command_center.to_string().contains(&run.id),
+            visible,
+            "{viewer} command center"
+        );
+
+        let storage = body(session, "/api/storage/usage?refresh=true").await;
+        if !visible {
+            assert!(storage["jobs"].as_array().expect("jobs").is_empty());
+        }
+    }
+
+    let preview = |session: &auth::SessionRow| {
+        client
+            .post(format!(
+                "{}/api/notifications/templates/email/preview",
+                base_url(addr)
+            ))
+            .header("cookie", format!("bastion_session={}", session.id))
+            .header("x-csrf-token", session.csrf_token.clone())
+            .json(&serde_json::json!({ "run_id": run.id }))
+            .send()
+    };
+    let resp = preview(&bob).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = preview(&alice).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    server.abort();
+}
@@ -0,0 +1,141 @@
+use axum::Extension;
+use axum::Json;
+use axum::extract::Path;
+use axum::http::HeaderMap;
+use serde::Deserialize;
+use tower_cookies::Cookies;
+
+use bastion_storage::audit_repo;
+use bastion_storage::auth::{self, SessionRow};
+use bastion_storage::job_access_repo::{self, JobAccess, JobPermission};
+
+use super::super::shared::{ClientConnInfo, require_csrf, require_job_permission, require_session};
+use super::super::{AppError, AppState};
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct JobGrantRequest {
+    username: String,
+    permission: JobPermission,
+}
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct PutJobAccessRequest {
+    /// `None` leaves the job unowned, shared with every user.
+    #[serde(default)]
+    owner: Option<String>,
+    #[serde(default)]
+    grants: Vec<JobGrantRequest>,
+}
+
+async fn load_access(state: &AppState, job_id: &str) -> Result<JobAccess, AppError> {
+    job_access_repo::get_access(&state.db, job_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("job_not_found", "Job not found"))
+}
+
+fn unknown_user(field: &'static str) -> AppError {
+    AppError::bad_request("user_not_found", "User not found")
+        .with_reason("unknown_user")
+        .with_field(field)
+}
+
+async fn find_user_id(state: &AppState, username: &str) -> Result<Option<i64>, AppError> {
+    Ok(auth::find_user_by_username(&state.db, username.trim())
+        .await?
+        .map(|user| user.id))
+}
+
+/// Only admins and the owner may hand out access; an unowned job is everyone's.
+async fn require_access_manager(
+    state: &AppState,
+    session: &SessionRow,
+    access: &JobAccess,
+) -> Result<(), AppError> {
+    if access
+        .owner_user_id
+        .is_none_or(|owner| owner == session.user_id)
+        || job_access_repo::is_admin(&state.db, session.user_id).await?
+    {
+        return Ok(());
+    }
+    Err(
+        AppError::forbidden("job_owner_required", "Only the job owner can change access")
+            .with_reason("not_owner"),
+    )
+}
+
+pub(in crate::http) async fn get_job_access(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(job_id): Path<String>,
+) -> Result<Json<JobAccess>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
+    Ok(Json(load_access(&state, &job_id).await?))
+}
+
+pub(in crate::http) async fn put_job_access(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Path(job_id): Path<String>,
+    Json(req): Json<PutJobAccessRequest>,
+) -> Result<Json<JobAccess>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
+    let access = load_access(&state, &job_id).await?;
+    require_access_manager(&state, &session, &access).await?;
+
+    let owner = match req.owner.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => Some(
+            find_user_id(&state, name)
+                .await?
+                .ok_or_else(|| unknown_user("owner"))?,
+        ),
+        _ => None,
+    };
+    let mut grants: Vec<(i64, JobPermission)> = Vec::with_capacity(req.grants.len());
+    for (index, grant) in req.grants.iter().enumerate() {
+        let user_id = find_user_id(&state, &grant.username)
+            .await?
+            .ok_or_else(|| unknown_user("grants").with_param("index", index))?;
+        if grants.iter().any(|(id, _)| *id == user_id) {
+            return Err(
+                AppError::bad_request("duplicate_grant", "User is listed more than once")
+                    .with_reason("duplicate")
+                    .with_field("grants")
+                    .with_param("index", index),
+            );
+        }
+        if Some(user_id) != owner {
+            grants.push((user_id, grant.permission));
+        }
+    }
+
+    if !job_access_repo::replace_access(&state.db, &job_id, owner, &grants).await? {
+        return Err(AppError::not_found("job_not_found", "Job not found"));
+    }
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+    audit_repo::append(
+        &state.db,
+        now,
+        audit_repo::ACTION_JOB_ACCESS_CHANGED,
+        Some(session.user_id),
+        Some(&client_ip),
+        Some(&job_id),
+    )
+    .await?;
+    tracing::info!(
+        user_id = session.user_id,
+        job_id = %job_id,
+        owner_user_id = ?owner,
+        grants = grants.len(),
+        "job access changed"
+    );
+
+    Ok(Json(load_access(&state, &job_id).await?))
+}
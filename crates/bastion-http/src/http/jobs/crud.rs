@@ -9,12 +9,15 @@ use std::str::FromStr;
 use tower_cookies::Cookies;
 
 use bastion_storage::hub_runtime_config_repo;
+use bastion_storage::job_access_repo::{self, JobPermission};
 use bastion_storage::jobs_repo;
 use bastion_storage::runs_repo;
 use bastion_storage::{artifact_delete_repo, run_artifacts_repo};
 
 use super::super::agents::send_node_config_snapshot;
-use super::super::shared::{require_csrf, require_session};
+use super::super::shared::{
+    job_list_viewer, require_csrf, require_job_permission, require_session,
+};
 use super::super::{AppError, AppState};
 use super::validation::{validate_job_spec, validate_job_target_scope};
 use bastion_engine::scheduler;
@@ -66,6 +69,40 @@ fn validate_schedule(schedule: Option<&str>) -> Result<(), AppError> {
     Ok(())
 }
 
+const MAX_SCHEDULE_JITTER_SECONDS: u32 = 3600;
+
+fn validate_schedule_jitter(value: Option<u32>) -> Result<(), AppError> {
+    if let Some(v) = value
+        && v > MAX_SCHEDULE_JITTER_SECONDS
+    {
+        return Err(AppError::bad_request(
+            "invalid_schedule_jitter",
+            format!("schedule_jitter_seconds must be <= {MAX_SCHEDULE_JITTER_SECONDS}"),
+        )
+        .with_reason("max_exceeded")
+        .with_field("schedule_jitter_seconds")
+        .with_param("max", MAX_SCHEDULE_JITTER_SECONDS));
+    }
+    Ok(())
+}
+
+const MAX_SUCCESS_INTERVAL_SECONDS: u32 = 90 * 24 * 60 * 60;
+
+fn validate_max_success_interval(value: Option<u32>) -> Result<(), AppError> {
+    if let Some(v) = value
+        && v > MAX_SUCCESS_INTERVAL_SECONDS
+    {
+        return Err(AppError::bad_request(
+            "invalid_max_success_interval",
+            format!("max_success_interval_seconds must be <= {MAX_SUCCESS_INTERVAL_SECONDS}"),
+        )
+        .with_reason("max_exceeded")
+        .with_field("max_success_interval_seconds")
+        .with_param("max", MAX_SUCCESS_INTERVAL_SECONDS));
+    }
+    Ok(())
+}
+
 fn normalize_timezone(value: Option<&str>, default: &str) -> Result<String, AppError> {
     let v = value
         .map(str::trim)
@@ -123,6 +160,11 @@ pub(in crate::http) struct CreateJobRequest {
     agent_id: Option<String>,
     schedule: Option<String>,
     schedule_timezone: Option<String>,
+    #[serde(default)]
+    schedule_jitter_seconds: Option<u32>,
+    /// Expected max interval between successful runs (`0` disables freshness monitoring).
+    #[serde(default)]
+    max_success_interval_seconds: Option<u32>,
     overlap_policy: jobs_repo::OverlapPolicy,
     spec: serde_json::Value,
 }
@@ -133,6 +175,11 @@ pub(in crate::http) struct UpdateJobRequest {
     agent_id: Option<String>,
     schedule: Option<String>,
     schedule_timezone: Option<String>,
+    #[serde(default)]
+    schedule_jitter_seconds: Option<u32>,
+    /// Expected max interval between successful runs (`0` disables freshness monitoring).
+    #[serde(default)]
+    max_success_interval_seconds: Option<u32>,
     overlap_policy: jobs_repo::OverlapPolicy,
     spec: serde_json::Value,
 }
@@ -530,6 +577,7 @@ fn push_jobs_list_filters(
     search: Option<&str>,
     latest_status_filter: JobLatestStatusFilter,
     schedule_mode_filter: JobScheduleModeFilter,
+    viewer: Option<i64>,
 ) {
     let mut has_where = false;
     let mut push_next = |qb: &mut QueryBuilder<sqlx::Sqlite>| {
@@ -541,6 +589,11 @@ fn push_jobs_list_filters(
         }
     };
 
+    if let Some(user_id) = viewer {
+        push_next(qb);
+        job_access_repo::push_visible_jobs_condition(qb, "j", user_id);
+    }
+
     if !include_archived {
         push_next(qb);
         qb.push("j.archived_at IS NULL");
@@ -600,7 +653,8 @@ pub(in crate::http) async fn list_jobs(
     cookies: Cookies,
     Query(q): Query<ListJobsQuery>,
 ) -> Result<Json<ListJobsResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    let viewer = job_list_viewer(&state, &session).await?;
 
     let include_archived = q.include_archived.unwrap_or(false);
     let node_filter = parse_scope_or_node_filter(q.scope.as_deref(), q.node_id.as_deref());
@@ -635,6 +689,7 @@ pub(in crate::http) async fn list_jobs(
         search.as_deref(),
         latest_status_filter,
         schedule_mode_filter,
+        viewer,
     );
 
     let total_row = total_qb.build().fetch_one(&state.db).await?;
@@ -686,6 +741,7 @@ pub(in crate::http) async fn list_jobs(
         search.as_deref(),
         latest_status_filter,
         schedule_mode_filter,
+        viewer,
     );
 
     match sort {
@@ -749,7 +805,8 @@ pub(in crate::http) async fn list_jobs_workspace(
     cookies: Cookies,
     Query(q): Query<ListJobsQuery>,
 ) -> Result<Json<JobsWorkspaceListResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    let viewer = job_list_viewer(&state, &session).await?;
 
     let include_archived = q.include_archived.unwrap_or(false);
     let node_filter = parse_scope_or_node_filter(q.scope.as_deref(), q.node_id.as_deref());
@@ -784,6 +841,7 @@ pub(in crate::http) async fn list_jobs_workspace(
         search.as_deref(),
         latest_status_filter,
         schedule_mode_filter,
+        viewer,
     );
 
     let total_row = total_qb.build().fetch_one(&state.db).await?;
@@ -850,6 +908,7 @@ pub(in crate::http) async fn list_jobs_workspace(
         search.as_deref(),
         latest_status_filter,
         schedule_mode_filter,
+        viewer,
     );
 
     match sort {
@@ -965,6 +1024,8 @@ pub(in crate::http) async fn create_job(
     validate_job_spec(&req.spec)?;
     validate_job_target_scope(&state.db, agent_id.as_deref(), &req.spec).await?;
     validate_schedule(schedule.as_deref())?;
+    validate_schedule_jitter(req.schedule_jitter_seconds)?;
+    validate_max_success_interval(req.max_success_interval_seconds)?;
 
     // New jobs inherit the Hub default retention, unless explicitly set by the request.
     if let Some(spec) = req.spec.as_object_mut()
@@ -983,7 +1044,7 @@ pub(in crate::http) async fn create_job(
         }
     }
 
-    let job = jobs_repo::create_job(
+    let mut job = jobs_repo::create_job(
         &state.db,
         name,
         agent_id.as_deref(),
@@ -993,6 +1054,15 @@ pub(in crate::http) async fn create_job(
         req.spec,
     )
     .await?;
+    job_access_repo::set_owner(&state.db, &job.id, Some(session.user_id)).await?;
+    if let Some(jitter) = req.schedule_jitter_seconds.filter(|v| *v > 0) {
+        jobs_repo::set_job_schedule_jitter(&state.db, &job.id, jitter).await?;
+        job.schedule_jitter_seconds = jitter;
+    }
+    if let Some(interval) = req.max_success_interval_seconds.filter(|v| *v > 0) {
+        jobs_repo::set_job_max_success_interval(&state.db, &job.id, interval).await?;
+        job.max_success_interval_seconds = interval;
+    }
 
     tracing::info!(
         job_id = %job.id,
@@ -1017,7 +1087,8 @@ pub(in crate::http) async fn get_job(
     cookies: Cookies,
     Path(job_id): Path<String>,
 ) -> Result<Json<jobs_repo::Job>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
     let job = jobs_repo::get_job(&state.db, &job_id)
         .await?
         .ok_or_else(|| AppError::not_found("job_not_found", "Job not found"))?;
@@ -1029,7 +1100,8 @@ pub(in crate::http) async fn get_job_workspace(
     cookies: Cookies,
     Path(job_id): Path<String>,
 ) -> Result<Json<JobWorkspaceDetailResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
     let job = jobs_repo::get_job(&state.db, &job_id)
         .await?
         .ok_or_else(|| AppError::not_found("job_not_found", "Job not found"))?;
@@ -1106,6 +1178,7 @@ pub(in crate::http) async fn update_job(
 ) -> Result<Json<jobs_repo::Job>, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let previous = jobs_repo::get_job(&state.db, &job_id)
         .await?
@@ -1115,8 +1188,11 @@ pub(in crate::http) async fn update_job(
     let name = require_job_name(&req.name)?;
 
     let schedule = normalize_optional_string(req.schedule.as_deref());
-    let schedule_timezone =
-        normalize_timezone(req.schedule_timezone.as_deref(), &state.config.hub_timezone)?;
+    // An omitted timezone keeps the job's own override instead of falling back to the Hub timezone.
+    let schedule_timezone = normalize_timezone(
+        req.schedule_timezone.as_deref(),
+        &previous.schedule_timezone,
+    )?;
 
     let agent_id = normalize_optional_string(req.agent_id.as_deref());
     validate_agent_id(&state.db, agent_id.as_deref()).await?;
@@ -1124,6 +1200,8 @@ pub(in crate::http) async fn update_job(
     validate_job_spec(&req.spec)?;
     validate_job_target_scope(&state.db, agent_id.as_deref(), &req.spec).await?;
     validate_schedule(schedule.as_deref())?;
+    validate_schedule_jitter(req.schedule_jitter_seconds)?;
+    validate_max_success_interval(req.max_success_interval_seconds)?;
 
     let updated = jobs_repo::update_job(
         &state.db,
@@ -1141,6 +1219,13 @@ pub(in crate::http) async fn update_job(
     if !updated {
         return Err(AppError::not_found("job_not_found", "Job not found"));
     }
+    // An omitted jitter keeps the current value, like the timezone.
+    if let Some(jitter) = req.schedule_jitter_seconds {
+        jobs_repo::set_job_schedule_jitter(&state.db, &job_id, jitter).await?;
+    }
+    if let Some(interval) = req.max_success_interval_seconds {
+        jobs_repo::set_job_max_success_interval(&state.db, &job_id, interval).await?;
+    }
 
     let job = jobs_repo::get_job(&state.db, &job_id)
         .await?
@@ -1182,6 +1267,7 @@ pub(in crate::http) async fn delete_job(
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let previous = jobs_repo::get_job(&state.db, &job_id)
         .await?
@@ -1217,6 +1303,7 @@ pub(in crate::http) async fn archive_job(
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let job = jobs_repo::get_job(&state.db, &job_id)
         .await?
@@ -1348,6 +1435,7 @@ pub(in crate::http) async fn unarchive_job(
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let job = jobs_repo::get_job(&state.db, &job_id)
         .await?
@@ -0,0 +1,144 @@
+use axum::Json;
+use axum::extract::{Path, Query};
+use serde::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+use bastion_backup::restore;
+use bastion_storage::job_access_repo::JobPermission;
+use bastion_storage::jobs_repo;
+use bastion_storage::run_artifacts_repo;
+
+use super::super::shared::{require_job_permission, require_session};
+use super::super::{AppError, AppState};
+
+const FILE_HISTORY_DEFAULT_LIMIT: u64 = 50;
+const FILE_HISTORY_MAX_LIMIT: u64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct FileHistoryQuery {
+    path: Option<String>,
+    /// Maximum number of snapshots (newest first) to scan.
+    limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct FileHistoryVersion {
+    run_id: String,
+    ended_at: i64,
+    #[serde(flatten)]
+    entry: restore::RunEntryVersion,
+    /// False when kind, size, mtime and hash all match the next older version.
+    changed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct FileHistoryUnavailable {
+    run_id: String,
+    error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct FileHistoryResponse {
+    path: String,
+    scanned: usize,
+    versions: Vec<FileHistoryVersion>,
+    unavailable: Vec<FileHistoryUnavailable>,
+}
+
+fn same_content(a: &restore::RunEntryVersion, b: &restore::RunEntryVersion) -> bool {
+    a.kind == b.kind && a.size == b.size && a.mtime == b.mtime && a.hash == b.hash
+}
+
+pub(in crate::http) async fn get_job_file_history(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(job_id): Path<String>,
+    Query(query): Query<FileHistoryQuery>,
+) -> Result<Json<FileHistoryResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
+
+    let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
+    if !job_exists {
+        return Err(AppError::not_found("job_not_found", "Job not found"));
+    }
+
+    let path = query
+        .path
+        .as_deref()
+        .map(|v| v.trim().trim_matches('/'))
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| {
+            AppError::bad_request("invalid_path", "path is required")
+                .with_reason("required")
+                .with_field("path")
+        })?
+        .to_string();
+
+    let limit = match query.limit {
+        None => FILE_HISTORY_DEFAULT_LIMIT,
+        Some(0) => {
+            return Err(
+                AppError::bad_request("invalid_limit", "limit must be positive")
+                    .with_reason("invalid_value")
+                    .with_field("limit"),
+            );
+        }
+        Some(value) if value > FILE_HISTORY_MAX_LIMIT => {
+            return Err(
+                AppError::bad_request("invalid_limit", "limit must not exceed 200")
+                    .with_reason("too_large")
+                    .with_field("limit"),
+            );
+        }
+        Some(value) => value,
+    };
+
+    let artifacts = run_artifacts_repo::list_run_artifacts_for_job(
+        &state.db,
+        &job_id,
+        0,
+        limit,
+        Some("present"),
+    )
+    .await?;
+
+    // Snapshots whose index cannot be read are reported instead of failing the whole scan.
+    let mut versions = Vec::new();
+    let mut unavailable = Vec::new();
+    for artifact in &artifacts {
+        match restore::find_run_entry(
+            &state.db,
+            state.secrets.as_ref(),
+            &state.config.data_dir,
+            &artifact.run_id,
+            &path,
+        )
+        .await
+        {
+            Ok(Some(entry)) => versions.push(FileHistoryVersion {
+                run_id: artifact.run_id.clone(),
+                ended_at: artifact.ended_at,
+                entry,
+                changed: true,
+            }),
+            Ok(None) => {}
+            Err(error) => unavailable.push(FileHistoryUnavailable {
+                run_id: artifact.run_id.clone(),
+                error: format!("{error:#}"),
+            }),
+        }
+    }
+
+    // Versions are newest first; compare each one with the next older version.
+    for i in 0..versions.len().saturating_sub(1) {
+        versions[i].changed = !same_content(&versions[i].entry, &versions[i + 1].entry);
+    }
+
+    Ok(Json(FileHistoryResponse {
+        path,
+        scanned: artifacts.len(),
+        versions,
+        unavailable,
+    }))
+}
@@ -1,20 +1,29 @@
+mod access;
 mod crud;
+mod files;
 mod retention;
 mod runs;
+mod schedule;
 mod snapshots;
 mod validation;
 mod ws;
 
+pub(super) use access::{get_job_access, put_job_access};
 pub(super) use crud::{archive_job, unarchive_job};
 pub(super) use crud::{
     create_job, delete_job, get_job, get_job_workspace, list_jobs, list_jobs_workspace, update_job,
 };
+pub(super) use files::get_job_file_history;
 pub(super) use retention::{
     apply_job_retention, get_job_retention, preview_job_retention, put_job_retention,
 };
-pub(super) use runs::{list_job_runs, list_run_events, trigger_job_run};
+pub(super) use runs::{
+    download_run_log, export_run_events, list_job_runs, list_run_events, search_job_runs,
+    trigger_job_run,
+};
+pub(super) use schedule::{list_upcoming_schedule, preview_job_schedule};
 pub(super) use snapshots::{
-    delete_job_snapshot, delete_job_snapshots_bulk, get_job_snapshot,
+    delete_job_snapshot, delete_job_snapshots_bulk, enqueue_snapshot_delete, get_job_snapshot,
     get_job_snapshot_delete_events, get_job_snapshot_delete_task, ignore_job_snapshot_delete_task,
     list_job_snapshots, pin_job_snapshot, retry_job_snapshot_delete_now, unpin_job_snapshot,
 };
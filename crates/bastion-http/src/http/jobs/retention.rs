@@ -10,10 +10,11 @@ use tower_cookies::Cookies;
 use bastion_core::backup_retention::{RetentionSnapshot, select_retention};
 use bastion_core::job_spec;
 use bastion_storage::artifact_delete_repo;
+use bastion_storage::job_access_repo::JobPermission;
 use bastion_storage::jobs_repo;
 use bastion_storage::run_artifacts_repo;
 
-use super::super::shared::{require_csrf, require_session};
+use super::super::shared::{require_csrf, require_job_permission, require_session};
 use super::super::{AppError, AppState};
 
 const RETENTION_SCAN_LIMIT: u64 = 20_000;
@@ -43,7 +44,8 @@ pub(in crate::http) async fn get_job_retention(
     cookies: Cookies,
     Path(job_id): Path<String>,
 ) -> Result<Json<job_spec::RetentionPolicyV1>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
 
     let job = jobs_repo::get_job(&state.db, &job_id)
         .await?
@@ -64,6 +66,7 @@ pub(in crate::http) async fn put_job_retention(
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let job = jobs_repo::get_job(&state.db, &job_id)
         .await?
@@ -197,6 +200,7 @@ async fn compute_preview(
             run_id: r.run_id.clone(),
             ended_at: r.ended_at,
             pinned: r.pinned_at.is_some(),
+            locked_until: r.locked_until,
         })
         .collect::<Vec<_>>();
 
@@ -263,7 +267,8 @@ pub(in crate::http) async fn preview_job_retention(
     Path(job_id): Path<String>,
     Json(req): Json<RetentionPreviewRequest>,
 ) -> Result<Json<RetentionPreviewResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
 
     let (job, saved) = load_retention_job(&state, &job_id).await?;
     let retention = req.retention.unwrap_or(saved);
@@ -292,6 +297,7 @@ pub(in crate::http) async fn apply_job_retention(
 ) -> Result<Json<RetentionApplyResponse>, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let (job, saved) = load_retention_job(&state, &job_id).await?;
     let retention = req.retention.unwrap_or(saved);
@@ -316,6 +322,7 @@ pub(in crate::http) async fn apply_job_retention(
             run_id: r.run_id.clone(),
             ended_at: r.ended_at,
             pinned: r.pinned_at.is_some(),
+            locked_until: r.locked_until,
         })
         .collect::<Vec<_>>();
 
@@ -1,14 +1,23 @@
 use axum::Json;
-use axum::extract::Path;
-use axum::http::HeaderMap;
-use serde::Serialize;
+use axum::body::{Body, Bytes};
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
 use sqlx::Row;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
 use tower_cookies::Cookies;
 
+use bastion_storage::job_access_repo::JobPermission;
 use bastion_storage::jobs_repo;
+use bastion_storage::run_logs_repo;
 use bastion_storage::runs_repo;
 
-use super::super::shared::{require_csrf, require_session};
+use super::super::runs::resolve_run_cursor;
+use super::super::shared::{
+    require_csrf, require_job_permission, require_run_permission, require_session,
+};
 use super::super::{AppError, AppState};
 use bastion_engine::run_events;
 
@@ -28,6 +37,7 @@ pub(in crate::http) async fn trigger_job_run(
 ) -> Result<Json<TriggerRunResponse>, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Trigger).await?;
 
     let job = jobs_repo::get_job(&state.db, &job_id)
         .await?
@@ -205,26 +215,92 @@ fn consistency_digest_from_summary(summary: Option<&serde_json::Value>) -> Consi
         .and_then(|v| v.get("consistency"))
         .map(|v| consistency_digest_from_report(Some(v)))
         .unwrap_or_default();
+    let app = summary
+        .get("sqlite_app")
+        .and_then(|v| v.get("consistency"))
+        .map(|v| consistency_digest_from_report(Some(v)))
+        .unwrap_or_default();
 
     ConsistencyDigest {
-        total: fs.total.saturating_add(vw.total),
-        signal_total: fs.signal_total.saturating_add(vw.signal_total),
+        total: fs.total.saturating_add(vw.total).saturating_add(app.total),
+        signal_total: fs
+            .signal_total
+            .saturating_add(vw.signal_total)
+            .saturating_add(app.signal_total),
     }
 }
 
+const JOB_RUNS_DEFAULT_LIMIT: u32 = 50;
+const JOB_RUNS_MAX_LIMIT: u32 = 200;
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct ListJobRunsQuery {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    from: Option<i64>,
+    #[serde(default)]
+    to: Option<i64>,
+    /// Id of the last run of the previous page.
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
+    limit: Option<u32>,
+}
+
 pub(in crate::http) async fn list_job_runs(
     state: axum::extract::State<AppState>,
     cookies: Cookies,
     Path(job_id): Path<String>,
+    Query(query): Query<ListJobRunsQuery>,
 ) -> Result<Json<Vec<RunListItem>>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
 
     let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
     if !job_exists {
         return Err(AppError::not_found("job_not_found", "Job not found"));
     }
 
-    let runs = runs_repo::list_runs_for_job(&state.db, &job_id, 50).await?;
+    let limit = match query.limit {
+        None => JOB_RUNS_DEFAULT_LIMIT,
+        Some(0) => {
+            return Err(
+                AppError::bad_request("invalid_limit", "limit must be positive")
+                    .with_reason("invalid_value")
+                    .with_field("limit"),
+            );
+        }
+        Some(value) if value > JOB_RUNS_MAX_LIMIT => {
+            return Err(
+                AppError::bad_request("invalid_limit", "limit must not exceed 200")
+                    .with_reason("too_large")
+                    .with_field("limit"),
+            );
+        }
+        Some(value) => value,
+    };
+    let status = query
+        .status
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && *v != "all")
+        .map(|v| {
+            v.parse::<runs_repo::RunStatus>().map_err(|_| {
+                AppError::bad_request("invalid_status", "invalid status")
+                    .with_reason("unsupported_value")
+                    .with_field("status")
+            })
+        })
+        .transpose()?;
+    let filter = runs_repo::RunListFilter {
+        status,
+        started_from: query.from,
+        started_to: query.to,
+        before: resolve_run_cursor(&state.db, query.cursor.as_deref(), Some(&job_id)).await?,
+    };
+
+    let runs = runs_repo::list_runs_for_job_filtered(&state.db, &job_id, &filter, limit).await?;
 
     let mut fallback_run_ids: Vec<String> = Vec::new();
     for r in &runs {
@@ -292,12 +368,263 @@ pub(in crate::http) async fn list_job_runs(
     ))
 }
 
+const RUN_SEARCH_MAX_HITS: u32 = 500;
+const RUN_SEARCH_EXCERPTS_PER_RUN: usize = 3;
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct SearchJobRunsQuery {
+    #[serde(default)]
+    q: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct RunSearchExcerpt {
+    seq: i64,
+    ts: i64,
+    level: String,
+    kind: String,
+    message: String,
+    excerpt: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct RunSearchResult {
+    run_id: String,
+    status: runs_repo::RunStatus,
+    started_at: i64,
+    match_count: usize,
+    excerpts: Vec<RunSearchExcerpt>,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct SearchJobRunsResponse {
+    q: String,
+    runs: Vec<RunSearchResult>,
+    /// True when the match cap was hit and older runs may be missing.
+    truncated: bool,
+}
+
+pub(in crate::http) async fn search_job_runs(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(job_id): Path<String>,
+    Query(query): Query<SearchJobRunsQuery>,
+) -> Result<Json<SearchJobRunsResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
+
+    let q = query.q.as_deref().map(str::trim).unwrap_or_default();
+    if q.is_empty() {
+        return Err(AppError::bad_request("invalid_query", "q is required")
+            .with_reason("required")
+            .with_field("q"));
+    }
+    if jobs_repo::get_job(&state.db, &job_id).await?.is_none() {
+        return Err(AppError::not_found("job_not_found", "Job not found"));
+    }
+
+    let hits = runs_repo::search_job_run_events(&state.db, &job_id, q, RUN_SEARCH_MAX_HITS).await?;
+    let truncated = hits.len() >= RUN_SEARCH_MAX_HITS as usize;
+
+    // Hits arrive grouped by run (newest first), so consecutive hits belong to the same run.
+    let mut runs: Vec<RunSearchResult> = Vec::new();
+    for hit in hits {
+        if runs.last().is_none_or(|r| r.run_id != hit.run_id) {
+            runs.push(RunSearchResult {
+                run_id: hit.run_id.clone(),
+                status: hit.run_status,
+                started_at: hit.run_started_at,
+                match_count: 0,
+                excerpts: Vec::new(),
+            });
+        }
+        let Some(result) = runs.last_mut() else {
+            continue;
+        };
+        result.match_count += 1;
+        if result.excerpts.len() < RUN_SEARCH_EXCERPTS_PER_RUN {
+            result.excerpts.push(RunSearchExcerpt {
+                seq: hit.seq,
+                ts: hit.ts,
+                level: hit.level,
+                kind: hit.kind,
+                message: hit.message,
+                excerpt: hit.excerpt,
+            });
+        }
+    }
+
+    Ok(Json(SearchJobRunsResponse {
+        q: q.to_string(),
+        runs,
+        truncated,
+    }))
+}
+
 pub(in crate::http) async fn list_run_events(
     state: axum::extract::State<AppState>,
     cookies: Cookies,
     Path(run_id): Path<String>,
 ) -> Result<Json<Vec<runs_repo::RunEvent>>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_run_permission(&state, &session, &run_id, JobPermission::View).await?;
     let events = runs_repo::list_run_events(&state.db, &run_id, 500).await?;
     Ok(Json(events))
 }
+
+const RUN_EVENTS_EXPORT_PAGE_SIZE: u32 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RunEventsExportFormat {
+    Ndjson,
+    Txt,
+}
+
+impl RunEventsExportFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Ndjson => "application/x-ndjson",
+            Self::Txt => "text/plain; charset=utf-8",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Ndjson => "ndjson",
+            Self::Txt => "txt",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct ExportRunEventsQuery {
+    #[serde(default)]
+    format: Option<String>,
+}
+
+pub(in crate::http) async fn export_run_events(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(run_id): Path<String>,
+    Query(query): Query<ExportRunEventsQuery>,
+) -> Result<Response, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_run_permission(&state, &session, &run_id, JobPermission::View).await?;
+
+    let format = match query.format.as_deref().map(str::trim).unwrap_or("ndjson") {
+        "ndjson" => RunEventsExportFormat::Ndjson,
+        "txt" => RunEventsExportFormat::Txt,
+        _ => {
+            return Err(
+                AppError::bad_request("invalid_format", "format must be ndjson or txt")
+                    .with_reason("unsupported_value")
+                    .with_field("format"),
+            );
+        }
+    };
+
+    let run = runs_repo::get_run(&state.db, &run_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("run_not_found", "Run not found"))?;
+
+    // Page through the events so large runs are streamed instead of buffered.
+    let stream = futures_util::stream::try_unfold(
+        (state.db.clone(), run.id.clone(), Some(0_i64)),
+        move |(db, run_id, after_seq)| async move {
+            let Some(after_seq) = after_seq else {
+                return Ok::<_, anyhow::Error>(None);
+            };
+            let events = runs_repo::list_run_events_after_seq(
+                &db,
+                &run_id,
+                after_seq,
+                RUN_EVENTS_EXPORT_PAGE_SIZE,
+            )
+            .await?;
+            let Some(last_seq) = events.last().map(|e| e.seq) else {
+                return Ok(None);
+            };
+            let next = (events.len() as u32 >= RUN_EVENTS_EXPORT_PAGE_SIZE).then_some(last_seq);
+
+            let mut chunk = String::new();
+            for event in &events {
+                chunk.push_str(&format_exported_run_event(format, event)?);
+                chunk.push('\n');
+            }
+            Ok(Some((Bytes::from(chunk), (db, run_id, next))))
+        },
+    );
+
+    let disposition = format!(
+        "attachment; filename=\"run-{}-events.{}\"",
+        run.id,
+        format.extension()
+    );
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            HeaderValue::from_str(&disposition)
+                .unwrap_or_else(|_| HeaderValue::from_static("attachment")),
+        )
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from_stream(stream))?;
+    Ok(resp)
+}
+
+/// Downloads the raw task log an agent streamed for the run.
+pub(in crate::http) async fn download_run_log(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(run_id): Path<String>,
+) -> Result<Response, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_run_permission(&state, &session, &run_id, JobPermission::View).await?;
+
+    let run = runs_repo::get_run(&state.db, &run_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("run_not_found", "Run not found"))?;
+    let log = run_logs_repo::get_run_log(&state.db, &run.id)
+        .await?
+        .ok_or_else(|| AppError::not_found("run_log_not_found", "Run log not found"))?;
+
+    let disposition = format!("attachment; filename=\"run-{}.log\"", run.id);
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            HeaderValue::from_str(&disposition)
+                .unwrap_or_else(|_| HeaderValue::from_static("attachment")),
+        )
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from(log))?;
+    Ok(resp)
+}
+
+fn format_exported_run_event(
+    format: RunEventsExportFormat,
+    event: &runs_repo::RunEvent,
+) -> Result<String, anyhow::Error> {
+    match format {
+        RunEventsExportFormat::Ndjson => Ok(serde_json::to_string(event)?),
+        RunEventsExportFormat::Txt => {
+            let ts = OffsetDateTime::from_unix_timestamp(event.ts)
+                .ok()
+                .and_then(|t| t.format(&Rfc3339).ok())
+                .unwrap_or_else(|| event.ts.to_string());
+            let mut line = format!(
+                "{ts} {:<5} [{}] {}",
+                event.level.to_uppercase(),
+                event.kind,
+                event.message
+            );
+            if let Some(fields) = event.fields.as_ref().filter(|v| !v.is_null()) {
+                line.push(' ');
+                line.push_str(&serde_json::to_string(fields)?);
+            }
+            Ok(line)
+        }
+    }
+}
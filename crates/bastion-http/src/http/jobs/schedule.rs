@@ -0,0 +1,182 @@
+use axum::Json;
+use axum::extract::{Path, Query};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+use bastion_engine::scheduler;
+use bastion_storage::job_access_repo::{self, JobPermission};
+use bastion_storage::jobs_repo;
+
+use super::super::shared::{job_list_viewer, require_job_permission, require_session};
+use super::super::{AppError, AppState};
+
+const DEFAULT_PREVIEW_COUNT: u32 = 20;
+const MAX_PREVIEW_COUNT: u32 = 200;
+
+fn invalid_count_error(message: impl Into<String>) -> AppError {
+    AppError::bad_request("invalid_count", message)
+        .with_reason("out_of_range")
+        .with_field("count")
+}
+
+fn resolve_count(count: Option<u32>) -> Result<usize, AppError> {
+    let count = count.unwrap_or(DEFAULT_PREVIEW_COUNT);
+    if count == 0 || count > MAX_PREVIEW_COUNT {
+        return Err(invalid_count_error(format!(
+            "count must be between 1 and {MAX_PREVIEW_COUNT}"
+        )));
+    }
+    Ok(count as usize)
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct ScheduleFireTime {
+    at: i64,
+    /// RFC 3339 timestamp in the schedule's timezone.
+    local: String,
+}
+
+fn fire_time(at: DateTime<Utc>, tz: chrono_tz::Tz) -> ScheduleFireTime {
+    ScheduleFireTime {
+        at: at.timestamp(),
+        local: at.with_timezone(&tz).to_rfc3339(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct SchedulePreviewQuery {
+    count: Option<u32>,
+    /// Unsaved cron expression to preview instead of the stored one.
+    schedule: Option<String>,
+    /// Unsaved timezone to preview instead of the stored one.
+    timezone: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct SchedulePreviewResponse {
+    schedule: Option<String>,
+    timezone: String,
+    fire_times: Vec<ScheduleFireTime>,
+}
+
+pub(in crate::http) async fn preview_job_schedule(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(job_id): Path<String>,
+    Query(query): Query<SchedulePreviewQuery>,
+) -> Result<Json<SchedulePreviewResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
+    let count = resolve_count(query.count)?;
+
+    let job = jobs_repo::get_job(&state.db, &job_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("job_not_found", "Job not found"))?;
+
+    let schedule = match query.schedule.as_deref().map(str::trim) {
+        Some(v) => Some(v.to_string()).filter(|v| !v.is_empty()),
+        None => job.schedule.clone(),
+    };
+    let timezone = query
+        .timezone
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .unwrap_or(&job.schedule_timezone)
+        .to_string();
+    let tz = timezone.parse::<chrono_tz::Tz>().map_err(|_| {
+        AppError::bad_request("invalid_timezone", "Invalid schedule timezone")
+            .with_reason("invalid_format")
+            .with_field("timezone")
+    })?;
+
+    let fire_times = match schedule.as_deref() {
+        Some(expr) => scheduler::preview_cron(expr, &timezone, Utc::now(), count)
+            .map_err(|_| {
+                AppError::bad_request("invalid_schedule", "Invalid cron schedule")
+                    .with_reason("invalid_format")
+                    .with_field("schedule")
+            })?
+            .into_iter()
+            .map(|at| fire_time(at, tz))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(Json(SchedulePreviewResponse {
+        schedule,
+        timezone,
+        fire_times,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct UpcomingScheduleQuery {
+    count: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct UpcomingScheduleItem {
+    job_id: String,
+    job_name: String,
+    agent_id: Option<String>,
+    schedule: String,
+    timezone: String,
+    #[serde(flatten)]
+    fire_time: ScheduleFireTime,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct UpcomingScheduleResponse {
+    items: Vec<UpcomingScheduleItem>,
+}
+
+pub(in crate::http) async fn list_upcoming_schedule(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Query(query): Query<UpcomingScheduleQuery>,
+) -> Result<Json<UpcomingScheduleResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    let count = resolve_count(query.count)?;
+    let now = Utc::now();
+    let visible = match job_list_viewer(&state, &session).await? {
+        Some(user_id) => Some(job_access_repo::visible_job_ids(&state.db, user_id).await?),
+        None => None,
+    };
+
+    // Each job contributes at most `count` fire times, so the merged head is exact.
+    let mut items = Vec::new();
+    for job in jobs_repo::list_jobs(&state.db).await? {
+        if visible.as_ref().is_some_and(|ids| !ids.contains(&job.id)) {
+            continue;
+        }
+        let Some(expr) = job.schedule.as_deref() else {
+            continue;
+        };
+        let Ok(tz) = job.schedule_timezone.parse::<chrono_tz::Tz>() else {
+            continue;
+        };
+        let Ok(times) = scheduler::preview_cron(expr, &job.schedule_timezone, now, count) else {
+            continue;
+        };
+        items.extend(times.into_iter().map(|at| UpcomingScheduleItem {
+            job_id: job.id.clone(),
+            job_name: job.name.clone(),
+            agent_id: job.agent_id.clone(),
+            schedule: expr.to_string(),
+            timezone: job.schedule_timezone.clone(),
+            fire_time: fire_time(at, tz),
+        }));
+    }
+
+    items.sort_by(|a, b| {
+        a.fire_time
+            .at
+            .cmp(&b.fire_time.at)
+            .then_with(|| a.job_name.cmp(&b.job_name))
+    });
+    items.truncate(count);
+
+    Ok(Json(UpcomingScheduleResponse { items }))
+}
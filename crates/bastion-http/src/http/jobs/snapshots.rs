@@ -10,10 +10,11 @@ use time::OffsetDateTime;
 use tower_cookies::Cookies;
 
 use bastion_storage::artifact_delete_repo;
+use bastion_storage::job_access_repo::JobPermission;
 use bastion_storage::jobs_repo;
 use bastion_storage::run_artifacts_repo;
 
-use super::super::shared::{require_csrf, require_session};
+use super::super::shared::{require_csrf, require_job_permission, require_session};
 use super::super::{AppError, AppState};
 
 fn invalid_snapshot_error(message: impl Into<String>) -> AppError {
@@ -54,6 +55,10 @@ pub(in crate::http) struct RunArtifactResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pinned_by_user_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    locked_until: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     source_files: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     source_dirs: Option<u64>,
@@ -85,6 +90,8 @@ impl From<run_artifacts_repo::RunArtifact> for RunArtifactResponse {
             ended_at: v.ended_at,
             pinned_at: v.pinned_at,
             pinned_by_user_id: v.pinned_by_user_id,
+            locked_until: v.locked_until,
+            deleted_at: v.deleted_at,
             source_files: v.source_files,
             source_dirs: v.source_dirs,
             source_bytes: v.source_bytes,
@@ -164,7 +171,8 @@ pub(in crate::http) async fn list_job_snapshots(
     Path(job_id): Path<String>,
     Query(query): Query<ListJobSnapshotsQuery>,
 ) -> Result<Json<ListJobSnapshotsResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
 
     let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
     if !job_exists {
@@ -236,7 +244,8 @@ pub(in crate::http) async fn get_job_snapshot(
     cookies: Cookies,
     Path((job_id, run_id)): Path<(String, String)>,
 ) -> Result<Json<RunArtifactResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
 
     let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
     if !job_exists {
@@ -265,6 +274,7 @@ pub(in crate::http) async fn pin_job_snapshot(
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
     if !job_exists {
@@ -301,6 +311,7 @@ pub(in crate::http) async fn unpin_job_snapshot(
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
     if !job_exists {
@@ -347,7 +358,7 @@ fn normalize_reason(reason: Option<&str>) -> Option<String> {
     Some(format!("{}…", &reason[..MAX_LEN]))
 }
 
-async fn enqueue_snapshot_delete(
+pub(in crate::http) async fn enqueue_snapshot_delete(
     state: &AppState,
     user_id: i64,
     job_id: &str,
@@ -418,6 +429,7 @@ pub(in crate::http) async fn delete_job_snapshot(
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
     if !job_exists {
@@ -445,6 +457,7 @@ pub(in crate::http) async fn delete_job_snapshots_bulk(
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
     if !job_exists {
@@ -483,7 +496,8 @@ pub(in crate::http) async fn get_job_snapshot_delete_task(
     cookies: Cookies,
     Path((job_id, run_id)): Path<(String, String)>,
 ) -> Result<Json<artifact_delete_repo::ArtifactDeleteTaskDetail>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
 
     let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
     if !job_exists {
@@ -511,7 +525,8 @@ pub(in crate::http) async fn get_job_snapshot_delete_events(
     cookies: Cookies,
     Path((job_id, run_id)): Path<(String, String)>,
 ) -> Result<Json<Vec<artifact_delete_repo::ArtifactDeleteEvent>>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
 
     let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
     if !job_exists {
@@ -540,6 +555,7 @@ pub(in crate::http) async fn retry_job_snapshot_delete_now(
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
     if !job_exists {
@@ -598,6 +614,7 @@ pub(in crate::http) async fn ignore_job_snapshot_delete_task(
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Edit).await?;
 
     let job_exists = jobs_repo::get_job(&state.db, &job_id).await?.is_some();
     if !job_exists {
@@ -2,6 +2,7 @@ use sqlx::SqlitePool;
 
 use bastion_core::HUB_NODE_ID;
 use bastion_core::job_spec;
+use bastion_driver_registry::target_runtime;
 use bastion_storage::secrets_repo;
 
 use super::super::AppError;
@@ -17,6 +18,15 @@ pub(super) fn validate_job_spec(spec: &serde_json::Value) -> Result<(), AppError
         .map_err(|error| invalid_spec_error(format!("Invalid job spec: {error}")))
 }
 
+/// Error code for a missing or unknown target credential. WebDAV keeps the code clients already
+/// handle; other credential-backed targets share a generic one.
+fn invalid_target_secret_code(kind: &str) -> &'static str {
+    match kind {
+        "webdav" => "invalid_webdav_secret",
+        _ => "invalid_target_secret",
+    }
+}
+
 pub(super) async fn validate_job_target_scope(
     db: &SqlitePool,
     agent_id: Option<&str>,
@@ -31,27 +41,47 @@ pub(super) async fn validate_job_target_scope(
         job_spec::JobSpecV1::Filesystem { target, .. } => target,
         job_spec::JobSpecV1::Sqlite { target, .. } => target,
         job_spec::JobSpecV1::Vaultwarden { target, .. } => target,
+        job_spec::JobSpecV1::SqliteApp { target, .. } => target,
+        job_spec::JobSpecV1::Postgres { target, .. } => target,
+        job_spec::JobSpecV1::VmImage { target, .. } => target,
+        job_spec::JobSpecV1::KubernetesPvc { target, .. } => target,
+        job_spec::JobSpecV1::Imap { target, .. } => target,
+        job_spec::JobSpecV1::Git { target, .. } => target,
     };
 
-    if let job_spec::TargetV1::Webdav { secret_name, .. } = target {
-        let secret_name = secret_name.trim();
-        if secret_name.is_empty() {
+    let target_kind = target_runtime::driver_id_for_job_target(target).kind;
+    match target_runtime::credential_secret_for_job_target(target) {
+        Ok(Some(secret)) => {
+            let exists = secrets_repo::secret_exists(db, node_id, secret.kind, secret.name).await?;
+            if !exists {
+                return Err(AppError::bad_request(
+                    invalid_target_secret_code(secret.kind),
+                    "Target credential not found",
+                )
+                .with_reason("not_found")
+                .with_field("spec.target.secret_name"));
+            }
+        }
+        Ok(None) => {}
+        Err(_) => {
             return Err(AppError::bad_request(
-                "invalid_webdav_secret",
-                "WebDAV credential name is required",
+                invalid_target_secret_code(&target_kind),
+                "Target credential name is required",
             )
             .with_reason("required")
             .with_field("spec.target.secret_name"));
         }
+    }
 
-        let exists = secrets_repo::secret_exists(db, node_id, "webdav", secret_name).await?;
+    if let job_spec::JobSpecV1::Imap { source, .. } = &parsed {
+        let secret_name = source.secret_name.trim();
+        let exists = secrets_repo::secret_exists(db, node_id, "imap", secret_name).await?;
         if !exists {
-            return Err(AppError::bad_request(
-                "invalid_webdav_secret",
-                "WebDAV credential not found",
-            )
-            .with_reason("not_found")
-            .with_field("spec.target.secret_name"));
+            return Err(
+                AppError::bad_request("invalid_imap_secret", "IMAP credential not found")
+                    .with_reason("not_found")
+                    .with_field("spec.source.secret_name"),
+            );
         }
     }
 
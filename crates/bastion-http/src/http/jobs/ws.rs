@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use axum::extract::ConnectInfo;
+use axum::Extension;
 use axum::extract::Path;
 use axum::extract::Query;
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
@@ -11,9 +11,13 @@ use sqlx::SqlitePool;
 use tower_cookies::Cookies;
 
 use bastion_engine::run_events_bus::RunEventsBus;
+use bastion_storage::job_access_repo::JobPermission;
 use bastion_storage::runs_repo;
 
-use super::super::shared::{is_trusted_proxy, request_is_https, require_session};
+use super::super::shared::{
+    ClientConnInfo, require_run_permission, require_session, require_ws_same_origin,
+};
+use super::super::ws_liveness::WsLiveness;
 use super::super::{AppError, AppState};
 
 #[derive(Debug, Deserialize)]
@@ -26,13 +30,14 @@ pub(in crate::http) async fn run_events_ws(
     state: axum::extract::State<AppState>,
     cookies: Cookies,
     headers: HeaderMap,
-    ConnectInfo(peer): ConnectInfo<std::net::SocketAddr>,
+    Extension(client): Extension<ClientConnInfo>,
     Query(query): Query<RunEventsWsQuery>,
     Path(run_id): Path<String>,
     ws: WebSocketUpgrade,
 ) -> Result<Response, AppError> {
-    let _session = require_session(&state, &cookies).await?;
-    require_ws_same_origin(&state, &headers, peer.ip())?;
+    let session = require_session(&state, &cookies).await?;
+    require_ws_same_origin(&client, &headers)?;
+    require_run_permission(&state, &session, &run_id, JobPermission::View).await?;
 
     let run_exists = runs_repo::get_run(&state.db, &run_id).await?.is_some();
     if !run_exists {
@@ -47,111 +52,6 @@ pub(in crate::http) async fn run_events_ws(
     }))
 }
 
-fn require_ws_same_origin(
-    state: &AppState,
-    headers: &HeaderMap,
-    peer_ip: std::net::IpAddr,
-) -> Result<(), AppError> {
-    let origin = headers
-        .get(axum::http::header::ORIGIN)
-        .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| AppError::unauthorized("invalid_origin", "Invalid origin"))?;
-
-    let origin = match url::Url::parse(origin) {
-        Ok(url) => url,
-        Err(_) => return Err(AppError::unauthorized("invalid_origin", "Invalid origin")),
-    };
-
-    let trusted_peer = is_trusted_proxy(state, peer_ip);
-    let expected_scheme = if trusted_peer {
-        headers
-            .get("x-forwarded-proto")
-            .and_then(|v| v.to_str().ok())
-            .and_then(|v| v.split(",").next())
-            .map(str::trim)
-            .filter(|v| !v.is_empty())
-            .and_then(normalize_origin_scheme)
-            .unwrap_or_else(|| {
-                if request_is_https(state, headers, peer_ip) {
-                    "https".to_string()
-                } else {
-                    "http".to_string()
-                }
-            })
-    } else if request_is_https(state, headers, peer_ip) {
-        "https".to_string()
-    } else {
-        "http".to_string()
-    };
-
-    let expected_authority_raw = if trusted_peer {
-        headers
-            .get("x-forwarded-host")
-            .and_then(|v| v.to_str().ok())
-            .and_then(|v| v.split(",").next())
-            .map(str::trim)
-            .filter(|v| !v.is_empty())
-            .or_else(|| {
-                headers
-                    .get(axum::http::header::HOST)
-                    .and_then(|v| v.to_str().ok())
-                    .map(str::trim)
-                    .filter(|v| !v.is_empty())
-            })
-    } else {
-        headers
-            .get(axum::http::header::HOST)
-            .and_then(|v| v.to_str().ok())
-            .map(str::trim)
-            .filter(|v| !v.is_empty())
-    }
-    .ok_or_else(|| AppError::unauthorized("invalid_origin", "Invalid origin"))?;
-
-    let expected_authority = expected_authority_raw
-        .parse::<axum::http::uri::Authority>()
-        .map_err(|_| AppError::unauthorized("invalid_origin", "Invalid origin"))?;
-
-    let expected_host = expected_authority.host().to_ascii_lowercase();
-    let expected_port = expected_authority
-        .port_u16()
-        .unwrap_or_else(|| default_port_for_scheme(&expected_scheme));
-
-    let origin_scheme = normalize_origin_scheme(origin.scheme())
-        .ok_or_else(|| AppError::unauthorized("invalid_origin", "Invalid origin"))?;
-    let origin_host = origin
-        .host_str()
-        .map(|v| v.to_ascii_lowercase())
-        .ok_or_else(|| AppError::unauthorized("invalid_origin", "Invalid origin"))?;
-    let origin_port = origin
-        .port_or_known_default()
-        .unwrap_or_else(|| default_port_for_scheme(&origin_scheme));
-
-    if origin_scheme != expected_scheme
-        || origin_host != expected_host
-        || origin_port != expected_port
-    {
-        return Err(AppError::unauthorized("invalid_origin", "Invalid origin"));
-    }
-
-    Ok(())
-}
-
-fn normalize_origin_scheme(raw: &str) -> Option<String> {
-    match raw.trim().to_ascii_lowercase().as_str() {
-        "http" | "ws" => Some("http".to_string()),
-        "https" | "wss" => Some("https".to_string()),
-        _ => None,
-    }
-}
-
-fn default_port_for_scheme(scheme: &str) -> u16 {
-    if scheme.eq_ignore_ascii_case("https") {
-        443
-    } else {
-        80
-    }
-}
-
 async fn handle_run_events_socket(
     db: SqlitePool,
     run_id: String,
@@ -162,40 +62,28 @@ async fn handle_run_events_socket(
     let mut last_seq = after_seq.max(0);
     let mut idle_after_end = 0u32;
 
-    let mut rx = run_events_bus.subscribe(&run_id);
-
-    // Catch up from SQLite after the requested sequence.
-    loop {
-        let events = match runs_repo::list_run_events_after_seq(&db, &run_id, last_seq, 200).await {
-            Ok(v) => v,
-            Err(_) => return,
-        };
-        if events.is_empty() {
-            break;
-        }
-        idle_after_end = 0;
-        for event in events {
-            last_seq = last_seq.max(event.seq);
-            let payload = match serde_json::to_string(&event) {
-                Ok(s) => s,
-                Err(_) => continue,
-            };
-            if socket.send(Message::Text(payload.into())).await.is_err() {
-                return;
-            }
-        }
+    // Resume from the bus's replay window when it covers `after_seq`; otherwise catch up from
+    // SQLite.
+    let subscription = run_events_bus.subscribe_after(&run_id, last_seq);
+    let mut rx = subscription.rx;
+    let sent = match subscription.replay {
+        Some(events) => send_events(&mut socket, events, &mut last_seq).await,
+        None => catch_up_from_db(&db, &run_id, &mut socket, &mut last_seq).await,
+    };
+    if !sent {
+        return;
     }
 
-    let mut status_interval = tokio::time::interval(std::time::Duration::from_secs(3));
-    status_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
-    status_interval.tick().await; // discard immediate tick
+    // Events are pushed by the bus; the run's status is only read on liveness ticks, to close the
+    // socket once the run has ended and gone quiet.
+    let mut liveness = WsLiveness::new();
 
     loop {
         tokio::select! {
             msg = socket.recv() => {
                 match msg {
                     Some(Ok(Message::Close(_))) | None => break,
-                    Some(Ok(_)) => {}
+                    Some(Ok(_)) => liveness.seen(),
                     Some(Err(_)) => break,
                 }
             }
@@ -216,38 +104,32 @@ async fn handle_run_events_socket(
                         }
                     }
                     Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
-                        // The client fell behind; resync from SQLite after the last confirmed seq.
-                        loop {
-                            let events = match runs_repo::list_run_events_after_seq(&db, &run_id, last_seq, 200).await {
-                                Ok(v) => v,
-                                Err(_) => return,
-                            };
-                            if events.is_empty() {
-                                break;
-                            }
-                            idle_after_end = 0;
-                            for event in events {
-                                last_seq = last_seq.max(event.seq);
-                                let payload = match serde_json::to_string(&event) {
-                                    Ok(s) => s,
-                                    Err(_) => continue,
-                                };
-                                if socket.send(Message::Text(payload.into())).await.is_err() {
-                                    return;
-                                }
-                            }
+                        // The client fell behind the live stream; resume after the last sent seq.
+                        idle_after_end = 0;
+                        let sent = match run_events_bus.replay_after(&run_id, last_seq) {
+                            Some(events) => send_events(&mut socket, events, &mut last_seq).await,
+                            None => catch_up_from_db(&db, &run_id, &mut socket, &mut last_seq).await,
+                        };
+                        if !sent {
+                            return;
                         }
                     }
                     Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
                 }
             }
-            _ = status_interval.tick() => {
+            alive = liveness.tick() => {
+                if !alive {
+                    break;
+                }
+                if socket.send(Message::Ping(Default::default())).await.is_err() {
+                    return;
+                }
                 match runs_repo::get_run(&db, &run_id).await {
                     Ok(Some(run)) => {
                         let ended = !matches!(run.status, runs_repo::RunStatus::Queued | runs_repo::RunStatus::Running);
                         if ended {
                             idle_after_end += 1;
-                            if idle_after_end >= 10 {
+                            if idle_after_end >= 2 {
                                 break;
                             }
                         } else {
@@ -260,3 +142,47 @@ async fn handle_run_events_socket(
         }
     }
 }
+
+/// Sends events newer than `last_seq`. Returns `false` once the socket is gone.
+async fn send_events(
+    socket: &mut WebSocket,
+    events: Vec<runs_repo::RunEvent>,
+    last_seq: &mut i64,
+) -> bool {
+    for event in events {
+        if event.seq <= *last_seq {
+            continue;
+        }
+        *last_seq = event.seq;
+        let payload = match serde_json::to_string(&event) {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+        if socket.send(Message::Text(payload.into())).await.is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Sends every stored event after `last_seq`. Returns `false` once the socket is gone or the
+/// database fails.
+async fn catch_up_from_db(
+    db: &SqlitePool,
+    run_id: &str,
+    socket: &mut WebSocket,
+    last_seq: &mut i64,
+) -> bool {
+    loop {
+        let events = match runs_repo::list_run_events_after_seq(db, run_id, *last_seq, 200).await {
+            Ok(v) => v,
+            Err(_) => return false,
+        };
+        if events.is_empty() {
+            return true;
+        }
+        if !send_events(socket, events, last_seq).await {
+            return false;
+        }
+    }
+}
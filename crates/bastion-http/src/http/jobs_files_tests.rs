@@ -0,0 +1,272 @@
+use std::io::Write as _;
+use std::path::Path;
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db, jobs_repo, run_artifacts_repo, runs_repo};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+async fn create_snapshot(pool: &sqlx::SqlitePool, job_id: &str, ended_at: i64) -> String {
+    let run = runs_repo::create_run(
+        pool,
+        job_id,
+        runs_repo::RunStatus::Queued,
+        ended_at - 1,
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    runs_repo::set_run_target_snapshot(
+        pool,
+        &run.id,
+        serde_json::json!({
+            "node_id": "hub",
+            "target": { "type": "local_dir", "base_dir": "/tmp" }
+        }),
+    )
+    .await
+    .expect("set snapshot");
+    runs_repo::complete_run(
+        pool,
+        &run.id,
+        runs_repo::RunStatus::Success,
+        Some(serde_json::json!({ "artifact_format": "archive_v1" })),
+        None,
+    )
+    .await
+    .expect("complete run");
+    sqlx::query("UPDATE runs SET ended_at = ? WHERE id = ?")
+        .bind(ended_at)
+        .bind(&run.id)
+        .execute(pool)
+        .await
+        .expect("set ended_at");
+    run_artifacts_repo::upsert_run_artifact_from_successful_run(pool, &run.id)
+        .await
+        .expect("index");
+    run.id
+}
+
+fn write_run_dir(run_dir: &Path, entries: &[serde_json::Value]) {
+    std::fs::create_dir_all(run_dir).expect("run dir");
+    std::fs::write(run_dir.join("complete.json"), b"{}").expect("complete");
+
+    let file = std::fs::File::create(run_dir.join("entries.jsonl.zst")).expect("entries");
+    let mut enc = zstd::Encoder::new(file, 3).expect("encoder");
+    for entry in entries {
+        enc.write_all(&serde_json::to_vec(entry).expect("json"))
+            .expect("write");
+        enc.write_all(b"\n").expect("write");
+    }
+    enc.finish().expect("finish");
+}
+
+#[tokio::test]
+async fn file_history_lists_versions_across_snapshots() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let base_dir = temp.path().join("artifacts");
+    let job = jobs_repo::create_job(
+        &pool,
+        "job",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "paths": ["/"] },
+            "target": { "type": "local_dir", "base_dir": base_dir.to_string_lossy().to_string() }
+        }),
+    )
+    .await
+    .expect("create job");
+
+    let hosts = |size: u64, mtime: u64, hash: &str| {
+        serde_json::json!({
+            "path": "etc/hosts",
+            "kind": "file",
+            "size": size,
+            "mtime": mtime,
+            "hash_alg": "blake3",
+            "hash": hash,
+        })
+    };
+    let other = serde_json::json!({ "path": "etc/passwd", "kind": "file", "size": 1 });
+
+    let oldest = create_snapshot(&pool, &job.id, 100).await;
+    write_run_dir(&base_dir.join(&job.id).join(&oldest), &[hosts(1, 10, "a")]);
+    let unchanged = create_snapshot(&pool, &job.id, 200).await;
+    write_run_dir(
+        &base_dir.join(&job.id).join(&unchanged),
+        &[other.clone(), hosts(1, 10, "a")],
+    );
+    let without_file = create_snapshot(&pool, &job.id, 300).await;
+    write_run_dir(&base_dir.join(&job.id).join(&without_file), &[other]);
+    let newest = create_snapshot(&pool, &job.id, 400).await;
+    write_run_dir(&base_dir.join(&job.id).join(&newest), &[hosts(2, 20, "b")]);
+    // No run directory at all: reported as unavailable instead of failing the scan.
+    let missing = create_snapshot(&pool, &job.id, 500).await;
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+
+    let resp = client
+        .get(format!(
+            "{}/api/jobs/{}/files/history",
+            base_url(addr),
+            job.id
+        ))
+        .query(&[("path", "/etc/hosts")])
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["path"], "etc/hosts");
+    assert_eq!(body["scanned"], 5);
+
+    let versions = body["versions"].as_array().expect("versions");
+    let run_ids = versions
+        .iter()
+        .map(|v| v["run_id"].as_str().expect("run_id"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        run_ids,
+        vec![newest.as_str(), unchanged.as_str(), oldest.as_str()]
+    );
+    assert_eq!(versions[0]["size"], 2);
+    assert_eq!(versions[0]["mtime"], 20);
+    assert_eq!(versions[0]["hash"], "b");
+    assert_eq!(versions[0]["ended_at"], 400);
+    assert_eq!(versions[0]["changed"], true);
+    assert_eq!(versions[1]["changed"], false);
+    assert_eq!(versions[2]["changed"], true);
+
+    let unavailable = body["unavailable"].as_array().expect("unavailable");
+    assert_eq!(unavailable.len(), 1);
+    assert_eq!(unavailable[0]["run_id"], missing.as_str());
+
+    let resp = client
+        .get(format!(
+            "{}/api/jobs/{}/files/history",
+            base_url(addr),
+            job.id
+        ))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_path");
+
+    let resp = client
+        .get(format!(
+            "{}/api/jobs/{}/files/history?path=etc/hosts&limit=500",
+            base_url(addr),
+            job.id
+        ))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_limit");
+
+    let resp = client
+        .get(format!(
+            "{}/api/jobs/{}/files/history?path=etc/hosts",
+            base_url(addr),
+            uuid::Uuid::new_v4()
+        ))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    server.abort();
+}
@@ -25,6 +25,11 @@ fn test_config(temp: &TempDir) -> Arc<Config> {
         hub_timezone: "UTC".to_string(),
         run_retention_days: 180,
         incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
         trusted_proxies: vec![
             "127.0.0.1/32".parse().expect("proxy"),
             "::1/128".parse().expect("proxy"),
@@ -499,3 +504,249 @@ async fn list_job_runs_includes_issues_digests() {
 
     server.abort();
 }
+
+#[tokio::test]
+async fn list_job_runs_filters_and_pages_with_cursor() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "job1",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create job");
+    for (started_at, status) in [
+        (1000, runs_repo::RunStatus::Success),
+        (2000, runs_repo::RunStatus::Failed),
+        (3000, runs_repo::RunStatus::Success),
+        (4000, runs_repo::RunStatus::Success),
+    ] {
+        runs_repo::create_run(
+            &pool,
+            &job.id,
+            status,
+            started_at,
+            Some(started_at + 1),
+            None,
+            None,
+        )
+        .await
+        .expect("create run");
+    }
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let list = |query: String| {
+        let client = client.clone();
+        let url = format!("{}/api/jobs/{}/runs?{query}", base_url(addr), job.id);
+        let cookie = format!("bastion_session={}", session.id);
+        async move {
+            client
+                .get(url)
+                .header("cookie", cookie)
+                .send()
+                .await
+                .expect("request")
+        }
+    };
+    let started = |body: &serde_json::Value| {
+        body.as_array()
+            .expect("array")
+            .iter()
+            .map(|item| item["started_at"].as_i64().unwrap_or_default())
+            .collect::<Vec<_>>()
+    };
+
+    let resp = list("status=success&limit=2".to_string()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let page1: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(started(&page1), vec![4000, 3000]);
+
+    let cursor = page1[1]["id"].as_str().expect("id").to_string();
+    let resp = list(format!("status=success&limit=2&cursor={cursor}")).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let page2: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(started(&page2), vec![1000]);
+
+    let resp = list("from=1500&to=3500".to_string()).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let ranged: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(started(&ranged), vec![3000, 2000]);
+
+    let resp = list("cursor=missing".to_string()).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("invalid_cursor"));
+
+    let resp = list("limit=500".to_string()).await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("invalid_limit"));
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn search_job_runs_groups_matching_events_by_run() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "job1",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create job");
+    let run = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Failed,
+        1000,
+        Some(1001),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    for path in ["/a", "/b"] {
+        runs_repo::append_run_event(
+            &pool,
+            &run.id,
+            "error",
+            "fs_issues",
+            &format!("permission denied: {path}"),
+            None,
+        )
+        .await
+        .expect("append event");
+    }
+    runs_repo::append_run_event(&pool, &run.id, "info", "complete", "done", None)
+        .await
+        .expect("append event");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .get(format!(
+            "{}/api/jobs/{}/runs/search",
+            base_url(addr),
+            job.id
+        ))
+        .query(&[("q", "permission denied")])
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    let runs = body["runs"].as_array().expect("runs");
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0]["run_id"].as_str(), Some(run.id.as_str()));
+    assert_eq!(runs[0]["status"].as_str(), Some("failed"));
+    assert_eq!(runs[0]["match_count"].as_u64(), Some(2));
+    assert_eq!(runs[0]["excerpts"][0]["seq"].as_i64(), Some(1));
+    assert_eq!(body["truncated"].as_bool(), Some(false));
+
+    let resp = client
+        .get(format!(
+            "{}/api/jobs/{}/runs/search",
+            base_url(addr),
+            job.id
+        ))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    server.abort();
+}
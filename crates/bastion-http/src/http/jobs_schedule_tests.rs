@@ -0,0 +1,486 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db, jobs_repo};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn schedule_preview_and_upcoming_list_fire_times() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let hourly = jobs_repo::create_job(
+        &pool,
+        "hourly",
+        None,
+        Some("0 * * * *"),
+        Some("Asia/Shanghai"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create job");
+    let _every_15m = jobs_repo::create_job(
+        &pool,
+        "every-15m",
+        None,
+        Some("*/15 * * * *"),
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create job");
+    let _manual = jobs_repo::create_job(
+        &pool,
+        "manual",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create job");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+
+    let resp = client
+        .get(format!(
+            "{}/api/jobs/{}/schedule/preview?count=5",
+            base_url(addr),
+            hourly.id
+        ))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["timezone"], "Asia/Shanghai");
+    let times = body["fire_times"].as_array().expect("fire_times");
+    assert_eq!(times.len(), 5);
+    for pair in times.windows(2) {
+        let a = pair[0]["at"].as_i64().expect("at");
+        let b = pair[1]["at"].as_i64().expect("at");
+        assert_eq!(b - a, 3600);
+    }
+    let local = times[0]["local"].as_str().expect("local");
+    assert!(local.ends_with(":00:00+08:00"), "{local}");
+
+    // Unsaved overrides are previewed without touching the stored job.
+    let resp = client
+        .get(format!(
+            "{}/api/jobs/{}/schedule/preview",
+            base_url(addr),
+            hourly.id
+        ))
+        .query(&[
+            ("schedule", "0 3 * * *"),
+            ("timezone", "UTC"),
+            ("count", "2"),
+        ])
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["schedule"], "0 3 * * *");
+    let times = body["fire_times"].as_array().expect("fire_times");
+    assert_eq!(times.len(), 2);
+    assert_eq!(
+        times[1]["at"].as_i64().unwrap() - times[0]["at"].as_i64().unwrap(),
+        86_400
+    );
+
+    let resp = client
+        .get(format!(
+            "{}/api/jobs/{}/schedule/preview",
+            base_url(addr),
+            hourly.id
+        ))
+        .query(&[("schedule", "not a cron")])
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_schedule");
+
+    let resp = client
+        .get(format!(
+            "{}/api/jobs/{}/schedule/preview?count=0",
+            base_url(addr),
+            hourly.id
+        ))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_count");
+
+    let resp = client
+        .get(format!("{}/api/schedule/upcoming?count=8", base_url(addr)))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    let items = body["items"].as_array().expect("items");
+    assert_eq!(items.len(), 8);
+    let ats = items
+        .iter()
+        .map(|item| item["at"].as_i64().expect("at"))
+        .collect::<Vec<_>>();
+    assert!(ats.windows(2).all(|w| w[0] <= w[1]));
+    assert!(items.iter().all(|item| item["job_name"] != "manual"));
+    // Eight fire times span at least two hours, so both scheduled jobs appear.
+    assert!(
+        items
+            .iter()
+            .any(|item| item["job_id"] == hourly.id.as_str())
+    );
+    assert!(items.iter().any(|item| item["job_name"] == "every-15m"));
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn job_schedule_timezone_is_validated_and_kept_on_update() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+    let spec = serde_json::json!({
+        "v": 1,
+        "type": "filesystem",
+        "source": { "root": "/" },
+        "target": { "type": "local_dir", "base_dir": "/tmp" }
+    });
+
+    let resp = client
+        .post(format!("{}/api/jobs", base_url(addr)))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({
+            "name": "bad-tz",
+            "schedule": "0 2 * * *",
+            "schedule_timezone": "Mars/Olympus_Mons",
+            "overlap_policy": "queue",
+            "spec": spec,
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_timezone");
+
+    let resp = client
+        .post(format!("{}/api/jobs", base_url(addr)))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({
+            "name": "tokyo",
+            "schedule": "0 2 * * *",
+            "schedule_timezone": "Asia/Tokyo",
+            "overlap_policy": "queue",
+            "spec": spec,
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let job: serde_json::Value = resp.json().await.expect("json");
+    let job_id = job["id"].as_str().expect("id").to_string();
+    assert_eq!(job["schedule_timezone"], "Asia/Tokyo");
+
+    // Omitting the timezone on update keeps the job override rather than resetting to the Hub's.
+    let resp = client
+        .put(format!("{}/api/jobs/{}", base_url(addr), job_id))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({
+            "name": "tokyo",
+            "schedule": "0 3 * * *",
+            "overlap_policy": "queue",
+            "spec": spec,
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let job: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(job["schedule_timezone"], "Asia/Tokyo");
+
+    let resp = client
+        .get(format!(
+            "{}/api/jobs/{}/schedule/preview?count=1",
+            base_url(addr),
+            job_id
+        ))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["timezone"], "Asia/Tokyo");
+    let local = body["fire_times"][0]["local"].as_str().expect("local");
+    assert!(local.ends_with("T03:00:00+09:00"), "{local}");
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn job_schedule_jitter_is_validated_and_kept_on_update() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+    let spec = serde_json::json!({
+        "v": 1,
+        "type": "filesystem",
+        "source": { "root": "/" },
+        "target": { "type": "local_dir", "base_dir": "/tmp" }
+    });
+
+    let resp = client
+        .post(format!("{}/api/jobs", base_url(addr)))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({
+            "name": "too-much-jitter",
+            "schedule": "0 2 * * *",
+            "schedule_jitter_seconds": 7200,
+            "overlap_policy": "queue",
+            "spec": spec,
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_schedule_jitter");
+
+    let resp = client
+        .post(format!("{}/api/jobs", base_url(addr)))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({
+            "name": "nightly",
+            "schedule": "0 2 * * *",
+            "schedule_jitter_seconds": 300,
+            "overlap_policy": "queue",
+            "spec": spec,
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let job: serde_json::Value = resp.json().await.expect("json");
+    let job_id = job["id"].as_str().expect("id").to_string();
+    assert_eq!(job["schedule_jitter_seconds"], 300);
+
+    let resp = client
+        .put(format!("{}/api/jobs/{}", base_url(addr), job_id))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({
+            "name": "nightly",
+            "schedule": "0 3 * * *",
+            "overlap_policy": "queue",
+            "spec": spec,
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let job: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(job["schedule_jitter_seconds"], 300);
+
+    let resp = client
+        .put(format!("{}/api/jobs/{}", base_url(addr), job_id))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({
+            "name": "nightly",
+            "schedule": "0 3 * * *",
+            "schedule_jitter_seconds": 0,
+            "overlap_policy": "queue",
+            "spec": spec,
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let job: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(job["schedule_jitter_seconds"], 0);
+
+    server.abort();
+}
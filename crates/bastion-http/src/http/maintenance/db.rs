@@ -0,0 +1,97 @@
+use axum::Json;
+use axum::body::Body;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::Response;
+use futures_util::StreamExt;
+use time::OffsetDateTime;
+use tokio_util::io::ReaderStream;
+use tower_cookies::Cookies;
+
+use bastion_engine::db_backup::{self, DbBackupFile, DbStatus};
+use bastion_engine::hub_standby::KEYRING_FINGERPRINT_HEADER;
+use bastion_storage::db::{self, WalCheckpoint};
+use bastion_storage::job_access_repo;
+
+use super::super::downloads::TempFileGuard;
+use super::super::shared::{require_csrf, require_session};
+use super::super::{AppError, AppState};
+
+pub(in crate::http) async fn get_db_status(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<DbStatus>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    let status = db_backup::status(&state.config.data_dir).await?;
+    Ok(Json(status))
+}
+
+pub(in crate::http) async fn checkpoint_db(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+) -> Result<Json<WalCheckpoint>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    let checkpoint = db_backup::checkpoint_and_optimize(&state.db).await?;
+    Ok(Json(checkpoint))
+}
+
+pub(in crate::http) async fn backup_db(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+) -> Result<Json<DbBackupFile>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    let backup =
+        db_backup::create_backup(&state.db, &state.config.data_dir, OffsetDateTime::now_utc())
+            .await?;
+    tracing::info!(
+        name = %backup.name,
+        size_bytes = backup.size_bytes,
+        "database snapshot written on request"
+    );
+    Ok(Json(backup))
+}
+
+/// Streams a consistent copy of the Hub database; warm standbys pull this with an admin's API
+/// token. The copy holds every job, user and encrypted secret, so only admins may fetch it.
+pub(in crate::http) async fn download_db_snapshot(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Response, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    if !job_access_repo::is_admin(&state.db, session.user_id).await? {
+        return Err(AppError::forbidden(
+            "admin_required",
+            "Only admins can download database snapshots",
+        ));
+    }
+
+    let dir = state.config.data_dir.join("tmp").join("db-snapshots");
+    tokio::fs::create_dir_all(&dir).await?;
+    let dest = dir.join(format!("{}.db", uuid::Uuid::new_v4()));
+    let guard = TempFileGuard(dest.clone());
+    db::vacuum_into(&state.db, &dest).await?;
+
+    let file = tokio::fs::File::open(&dest).await?;
+    let len = file.metadata().await?.len();
+    let fingerprint =
+        bastion_storage::secrets::keyring_fingerprint(&state.config.data_dir).unwrap_or_default();
+    tracing::debug!(size_bytes = len, "database snapshot streamed");
+
+    let stream = ReaderStream::new(file).map(move |chunk| {
+        let _ = &guard;
+        chunk
+    });
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/vnd.sqlite3")
+        .header(header::CONTENT_LENGTH, len.to_string())
+        .header(header::CACHE_CONTROL, "no-store")
+        .header(KEYRING_FINGERPRINT_HEADER, fingerprint)
+        .body(Body::from_stream(stream))?;
+    Ok(resp)
+}
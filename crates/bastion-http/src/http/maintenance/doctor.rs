@@ -0,0 +1,22 @@
+use axum::Json;
+use tower_cookies::Cookies;
+
+use bastion_engine::doctor::{self, DoctorReport};
+
+use super::super::shared::require_session;
+use super::super::{AppError, AppState};
+
+pub(in crate::http) async fn run_doctor(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<DoctorReport>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+
+    let staging_root = state
+        .config
+        .staging_dir
+        .clone()
+        .unwrap_or_else(|| state.config.data_dir.clone());
+    let report = doctor::check_data_dir(&state.db, &state.config.data_dir, &staging_root).await;
+    Ok(Json(report))
+}
@@ -1,6 +1,12 @@
+mod db;
+mod doctor;
 mod incomplete_cleanup;
+mod orphans;
 
+pub(super) use db::{backup_db, checkpoint_db, download_db_snapshot, get_db_status};
+pub(super) use doctor::run_doctor;
 pub(super) use incomplete_cleanup::{
     get_incomplete_cleanup_task, ignore_incomplete_cleanup_task, list_incomplete_cleanup_tasks,
     retry_incomplete_cleanup_task_now, unignore_incomplete_cleanup_task,
 };
+pub(super) use orphans::scan_orphans;
@@ -0,0 +1,93 @@
+use axum::Extension;
+use axum::Json;
+use axum::http::HeaderMap;
+use serde::Deserialize;
+use tower_cookies::Cookies;
+
+use bastion_engine::orphan_gc::{self, OrphanGcOptions, OrphanGcReport};
+use bastion_storage::{audit_repo, jobs_repo};
+
+use super::super::shared::{ClientConnInfo, require_csrf, require_session};
+use super::super::{AppError, AppState};
+
+const MAX_RUN_IDS: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct ScanOrphansRequest {
+    #[serde(default)]
+    job_id: Option<String>,
+    #[serde(default)]
+    delete: bool,
+    #[serde(default)]
+    run_ids: Option<Vec<String>>,
+}
+
+pub(in crate::http) async fn scan_orphans(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Json(req): Json<ScanOrphansRequest>,
+) -> Result<Json<OrphanGcReport>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    let job_id = req
+        .job_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string);
+    if let Some(job_id) = job_id.as_deref()
+        && jobs_repo::get_job(&state.db, job_id).await?.is_none()
+    {
+        return Err(AppError::not_found("job_not_found", "Job not found"));
+    }
+    if let Some(run_ids) = req.run_ids.as_ref()
+        && (run_ids.is_empty() || run_ids.len() > MAX_RUN_IDS)
+    {
+        return Err(
+            AppError::bad_request("invalid_run_ids", "run_ids must list 1..=1000 runs")
+                .with_reason("out_of_range")
+                .with_field("run_ids")
+                .with_param("min", 1)
+                .with_param("max", MAX_RUN_IDS),
+        );
+    }
+
+    let options = OrphanGcOptions {
+        job_id,
+        delete: req.delete,
+        run_ids: req.run_ids,
+    };
+    let report = orphan_gc::reconcile(&state.db, &state.secrets, &options).await?;
+
+    if report.deleted_total > 0 {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let client_ip = client.ip.to_string();
+        let deleted = report
+            .orphans
+            .iter()
+            .filter(|o| o.deleted)
+            .map(|o| o.location.as_str())
+            .collect::<Vec<_>>();
+        let detail = serde_json::json!({ "deleted": deleted }).to_string();
+        audit_repo::append(
+            &state.db,
+            now,
+            audit_repo::ACTION_ORPHANS_DELETED,
+            Some(session.user_id),
+            Some(&client_ip),
+            Some(&detail),
+        )
+        .await?;
+        tracing::warn!(
+            client_ip = %client_ip,
+            user_id = session.user_id,
+            deleted = report.deleted_total,
+            "orphaned run directories deleted"
+        );
+    }
+
+    Ok(Json(report))
+}
@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+async fn start_server(
+    temp: &TempDir,
+    pool: sqlx::SqlitePool,
+) -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
+    let config = test_config(temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool,
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    (addr, server)
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn db_backup_and_checkpoint_endpoints() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_first_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let (addr, server) = start_server(&temp, pool.clone()).await;
+
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+
+    let resp = client
+        .get(format!("{}/api/maintenance/db", base_url(addr)))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert!(body["db_size_bytes"].as_u64().unwrap_or_default() > 0);
+    assert_eq!(body["backups"].as_array().map(Vec::len), Some(0));
+
+    // Mutations need the CSRF token.
+    let resp = client
+        .post(format!("{}/api/maintenance/db/backup", base_url(addr)))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = client
+        .post(format!("{}/api/maintenance/db/backup", base_url(addr)))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let backup: serde_json::Value = resp.json().await.expect("json");
+    let name = backup["name"].as_str().expect("name").to_string();
+    assert!(
+        temp.path()
+            .join(bastion_engine::db_backup::BACKUPS_DIR)
+            .join(&name)
+            .is_file()
+    );
+
+    let resp = client
+        .post(format!("{}/api/maintenance/db/checkpoint", base_url(addr)))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert!(body["busy"].is_boolean());
+
+    let resp = client
+        .get(format!("{}/api/maintenance/db", base_url(addr)))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["backups"][0]["name"], name.as_str());
+
+    // Standbys pull a full copy along with the keyring fingerprint.
+    let resp = client
+        .get(format!("{}/api/maintenance/db/snapshot", base_url(addr)))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()[bastion_engine::hub_standby::KEYRING_FINGERPRINT_HEADER]
+            .to_str()
+            .expect("header"),
+        bastion_storage::secrets::keyring_fingerprint(temp.path()).expect("fingerprint")
+    );
+    let bytes = resp.bytes().await.expect("body");
+    assert!(bytes.starts_with(b"SQLite format 3\0"));
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn db_snapshot_requires_an_admin() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_first_user(&pool, "admin", &user_password)
+        .await
+        .expect("create admin");
+    auth::create_user(&pool, "alice", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "alice")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let (addr, server) = start_server(&temp, pool.clone()).await;
+
+    let resp = reqwest::Client::new()
+        .get(format!("{}/api/maintenance/db/snapshot", base_url(addr)))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "admin_required");
+
+    server.abort();
+}
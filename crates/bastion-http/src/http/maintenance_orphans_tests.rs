@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{audit_repo, auth, db, jobs_repo};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn orphan_scan_reports_then_deletes_unknown_run_dirs() {
+    let temp = TempDir::new().expect("tempdir");
+    let target = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "job",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "paths": ["/tmp"] },
+            "target": { "type": "local_dir", "base_dir": target.path().display().to_string() }
+        }),
+    )
+    .await
+    .expect("create job");
+
+    let orphan_run = uuid::Uuid::new_v4().to_string();
+    let orphan_dir = target.path().join(&job.id).join(&orphan_run);
+    std::fs::create_dir_all(&orphan_dir).expect("mkdir");
+    std::fs::write(orphan_dir.join("payload.part000001"), b"x").expect("write");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+    let url = format!("{}/api/maintenance/orphans/scan", base_url(addr));
+
+    let resp = client
+        .post(&url)
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({ "job_id": uuid::Uuid::new_v4().to_string() }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = client
+        .post(&url)
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({}))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["deleted_total"], 0);
+    assert_eq!(body["orphans"][0]["run_id"], orphan_run.as_str());
+    assert_eq!(body["orphans"][0]["reason"], "unknown_run");
+    assert_eq!(body["orphans"][0]["deletable"], true);
+    assert!(orphan_dir.exists());
+
+    let resp = client
+        .post(&url)
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({ "job_id": job.id, "delete": true, "run_ids": [orphan_run] }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["deleted_total"], 1);
+    assert!(!orphan_dir.exists());
+
+    let entries = audit_repo::list_recent(&pool, &[audit_repo::ACTION_ORPHANS_DELETED], 10)
+        .await
+        .expect("audit");
+    assert_eq!(entries.len(), 1);
+
+    server.abort();
+}
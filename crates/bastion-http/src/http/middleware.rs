@@ -1,12 +1,21 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::Extension;
 use axum::extract::ConnectInfo;
 use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::http::header::{AUTHORIZATION, COOKIE};
 use axum::middleware::Next;
 use axum::response::IntoResponse;
 use axum::response::Response;
+use tower_cookies::Cookies;
 
 use super::error::{self, AppErrorRenderOptions};
+use super::rate_limit::ApiRateLimiter;
 use super::shared;
-use super::{AppError, AppState};
+use super::{AppError, AppState, TlsPeerCertificate};
+use bastion_storage::auth;
 
 pub(super) async fn bind_error_render_options_middleware(
     state: axum::extract::State<AppState>,
@@ -22,9 +31,26 @@ pub(super) async fn bind_error_render_options_middleware(
     .await
 }
 
-pub(super) async fn require_secure_middleware(
+/// Resolves the real client IP, scheme and host once and attaches them as
+/// [`shared::ClientConnInfo`] for handlers, cookies, origin checks and audit logging.
+pub(super) async fn client_conn_info_middleware(
     state: axum::extract::State<AppState>,
     ConnectInfo(peer): ConnectInfo<std::net::SocketAddr>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let mut client = shared::ClientConnInfo::resolve(&state, req.headers(), peer.ip());
+    client.tls_peer_cert = req
+        .extensions()
+        .get::<TlsPeerCertificate>()
+        .map(|cert| cert.0.clone());
+    req.extensions_mut().insert(client);
+    next.run(req).await
+}
+
+pub(super) async fn require_secure_middleware(
+    state: axum::extract::State<AppState>,
+    Extension(client): Extension<shared::ClientConnInfo>,
     req: Request,
     next: Next,
 ) -> Response {
@@ -38,16 +64,126 @@ pub(super) async fn require_secure_middleware(
         return next.run(req).await;
     }
 
-    let peer_ip = peer.ip();
-    let https = shared::request_is_https(&state, req.headers(), peer_ip);
-    let allow = https || state.config.insecure_http || peer_ip.is_loopback();
+    let allow = client.https || state.config.insecure_http || client.peer_ip.is_loopback();
     if allow {
         return next.run(req).await;
     }
 
     AppError::bad_request(
         "insecure_not_allowed",
-        "HTTPS required (reverse proxy) or start with --insecure-http",
+        "HTTPS required (reverse proxy or --tls-cert/--acme-domain) or start with --insecure-http",
+    )
+    .into_response()
+}
+
+fn is_rate_limited_path(path: &str) -> bool {
+    path.starts_with("/api/") && !matches!(path, "/api/health" | "/api/ready")
+}
+
+fn rate_limited_response(
+    client: &shared::ClientConnInfo,
+    path: &str,
+    retry_after: u64,
+) -> Response {
+    tracing::warn!(client_ip = %client.ip, path, retry_after, "api rate limited");
+    AppError::too_many_requests(
+        "rate_limited",
+        format!("Too many requests. Retry after {retry_after}s."),
     )
+    .with_reason("api_rate_limit")
+    .with_param("retry_after_seconds", retry_after)
     .into_response()
 }
+
+/// Applies the per-IP request limit to `/api` (health probes excepted). Runs before
+/// [`api_token_middleware`], so guessing tokens is throttled like any other request.
+pub(super) async fn ip_rate_limit_middleware(
+    axum::extract::State(limiter): axum::extract::State<Arc<ApiRateLimiter>>,
+    Extension(client): Extension<shared::ClientConnInfo>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let path = req.uri().path();
+    if !is_rate_limited_path(path) {
+        return next.run(req).await;
+    }
+    if let Err(retry_after) = limiter.check_ip(client.ip, Instant::now()) {
+        return rate_limited_response(&client, path, retry_after);
+    }
+    next.run(req).await
+}
+
+/// Applies the per-session request limit to `/api` (health probes excepted). API tokens count
+/// against their backing session, which [`api_token_middleware`] has already put in the cookie.
+/// Only sessions that exist and are still valid get their own bucket.
+pub(super) async fn session_rate_limit_middleware(
+    axum::extract::State((state, limiter)): axum::extract::State<(AppState, Arc<ApiRateLimiter>)>,
+    Extension(client): Extension<shared::ClientConnInfo>,
+    cookies: Cookies,
+    req: Request,
+    next: Next,
+) -> Response {
+    let path = req.uri().path();
+    if !is_rate_limited_path(path) {
+        return next.run(req).await;
+    }
+
+    let session_id = match cookies.get(shared::SESSION_COOKIE_NAME) {
+        Some(cookie) if limiter.limits_sessions() => {
+            match auth::get_session(&state.db, cookie.value()).await {
+                Ok(session) => session.map(|session| session.id),
+                Err(error) => return AppError::from(error).into_response(),
+            }
+        }
+        _ => None,
+    };
+    if let Err(retry_after) =
+        limiter.check_session(client.ip, session_id.as_deref(), Instant::now())
+    {
+        return rate_limited_response(&client, path, retry_after);
+    }
+    next.run(req).await
+}
+
+/// Authenticates `/api` requests carrying `Authorization: Bearer bst_...` by presenting the API
+/// token's backing session as the session cookie and CSRF header, so handlers need no token
+/// awareness. Must run outside the cookie manager layer.
+pub(super) async fn api_token_middleware(
+    state: axum::extract::State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    if !req.uri().path().starts_with("/api/") {
+        return next.run(req).await;
+    }
+    let token = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| v.trim().to_string());
+    let Some(token) = token.filter(|v| v.starts_with(auth::API_TOKEN_PREFIX)) else {
+        return next.run(req).await;
+    };
+
+    let session = match auth::resolve_api_token(&state.db, &token).await {
+        Ok(Some(session)) => session,
+        Ok(None) => {
+            return AppError::unauthorized("invalid_api_token", "Invalid API token")
+                .into_response();
+        }
+        Err(error) => return AppError::from(error).into_response(),
+    };
+
+    let (Ok(cookie), Ok(csrf)) = (
+        HeaderValue::from_str(&format!("{}={}", shared::SESSION_COOKIE_NAME, session.id)),
+        HeaderValue::from_str(&session.csrf_token),
+    ) else {
+        return AppError::unauthorized("invalid_api_token", "Invalid API token").into_response();
+    };
+    let headers = req.headers_mut();
+    headers.remove(AUTHORIZATION);
+    headers.insert(COOKIE, cookie);
+    headers.insert(shared::CSRF_HEADER, csrf);
+    next.run(req).await
+}
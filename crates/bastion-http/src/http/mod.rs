@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use axum::extract::DefaultBodyLimit;
 use axum::http::StatusCode;
-use axum::routing::{get, post, put};
+use axum::routing::{delete, get, post, put};
 use axum::{Json, Router};
 use serde::Serialize;
 use sqlx::SqlitePool;
@@ -19,12 +19,15 @@ use bastion_engine::run_events_bus::RunEventsBus;
 use bastion_storage::secrets::SecretsCrypto;
 
 mod agents;
+mod alerts;
 mod auth;
 mod bulk_operations;
 mod command_center;
+mod config_bundle;
 mod control_plane;
 mod dashboard;
 mod docs;
+mod downloads;
 mod error;
 mod fleet;
 mod fs;
@@ -35,14 +38,24 @@ mod maintenance;
 mod middleware;
 mod notifications;
 mod operations;
+mod operations_ws;
+mod rate_limit;
 mod runs;
 mod secrets;
+mod sessions;
 mod settings;
 mod shared;
+mod standby;
+mod stats;
+mod storage;
 mod ui;
+mod ui_dev_proxy;
 mod webdav;
+mod ws_liveness;
 
+pub use agents::{TlsPeerCertificate, agent_ca_cert_pem};
 use error::AppError;
+pub use standby::standby_router;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -50,6 +63,7 @@ pub enum ConfigValueSource {
     Cli,
     Env,
     EnvRustLog,
+    File,
     Db,
     Default,
 }
@@ -87,6 +101,46 @@ pub struct HubRuntimeConfigMeta {
     pub sources: HubRuntimeConfigSources,
     pub logging: HubRuntimeLoggingEffective,
     pub public_base_url: Option<String>,
+    /// The Hub terminates TLS itself, so every request it serves arrived over HTTPS.
+    pub tls_enabled: bool,
+    /// Values re-read from the config file on SIGHUP; they replace the startup values above.
+    pub reloaded: Arc<HubReloadedConfig>,
+}
+
+/// Settings the Hub can change without a restart (log filter, trusted proxies).
+#[derive(Debug, Default)]
+pub struct HubReloadedConfig {
+    trusted_proxies: std::sync::RwLock<Option<(Vec<ipnet::IpNet>, ConfigValueSource)>>,
+    log_filter: std::sync::RwLock<Option<(String, ConfigValueSource)>>,
+}
+
+impl HubReloadedConfig {
+    pub fn set_trusted_proxies(&self, proxies: Vec<ipnet::IpNet>, source: ConfigValueSource) {
+        if let Ok(mut guard) = self.trusted_proxies.write() {
+            *guard = Some((proxies, source));
+        }
+    }
+
+    pub fn set_log_filter(&self, filter: String, source: ConfigValueSource) {
+        if let Ok(mut guard) = self.log_filter.write() {
+            *guard = Some((filter, source));
+        }
+    }
+
+    /// `None` until a reload replaced the startup trusted proxies.
+    fn trusts_proxy(&self, ip: std::net::IpAddr) -> Option<bool> {
+        let guard = self.trusted_proxies.read().ok()?;
+        let (proxies, _) = guard.as_ref()?;
+        Some(proxies.iter().any(|net| net.contains(&ip)))
+    }
+
+    fn trusted_proxies(&self) -> Option<(Vec<ipnet::IpNet>, ConfigValueSource)> {
+        self.trusted_proxies.read().ok()?.clone()
+    }
+
+    fn log_filter(&self) -> Option<(String, ConfigValueSource)> {
+        self.log_filter.read().ok()?.clone()
+    }
 }
 
 impl Default for HubRuntimeConfigSources {
@@ -240,11 +294,28 @@ pub fn router(state: AppState) -> Router {
         .route("/api/health", get(health))
         .route("/api/ready", get(ready))
         .route("/api/system", get(system_status))
+        .route("/api/system/keypack/export", post(secrets::export_keypack))
+        .route("/api/system/keypack/import", post(secrets::import_keypack))
+        .route("/api/system/keypack/audit", get(secrets::keypack_audit))
+        .route("/api/config/export", post(config_bundle::export_config))
+        .route("/api/config/import", post(config_bundle::import_config))
+        .route(
+            "/api/system/keypack/escrow",
+            get(secrets::get_keypack_escrow).put(secrets::update_keypack_escrow),
+        )
+        .route(
+            "/api/system/keypack/escrow/run",
+            post(secrets::run_keypack_escrow),
+        )
         .route(
             "/api/control-plane/public-metadata",
             get(control_plane::get_public_metadata),
         )
         .route("/api/dashboard/overview", get(dashboard::get_overview))
+        .route("/api/storage/usage", get(storage::get_storage_usage))
+        .route("/api/alerts/stale-backups", get(alerts::list_stale_backups))
+        .route("/api/stats", get(stats::get_run_stats))
+        .route("/api/stats/jobs/{id}", get(stats::get_job_run_stats))
         .route(
             "/api/command-center",
             get(command_center::get_command_center),
@@ -271,7 +342,23 @@ pub fn router(state: AppState) -> Router {
         .route("/api/setup/initialize", post(auth::setup_initialize))
         .route("/api/auth/login", post(auth::login))
         .route("/api/auth/logout", post(auth::logout))
+        .route("/api/auth/change-password", post(auth::change_password))
+        .route(
+            "/api/users/{username}/reset-password",
+            post(auth::reset_password),
+        )
+        .route("/api/auth/login-locks", get(auth::list_login_locks))
+        .route(
+            "/api/auth/login-locks/{username}",
+            delete(auth::unlock_login),
+        )
         .route("/api/session", get(auth::session))
+        .route("/api/sessions", get(sessions::list_sessions))
+        .route(
+            "/api/sessions/revoke-others",
+            post(sessions::revoke_other_sessions),
+        )
+        .route("/api/sessions/{id}", delete(sessions::revoke_session))
         .route("/api/secrets/webdav", get(secrets::list_webdav_secrets))
         .route(
             "/api/secrets/webdav/{name}",
@@ -279,6 +366,18 @@ pub fn router(state: AppState) -> Router {
                 .put(secrets::upsert_webdav_secret)
                 .delete(secrets::delete_webdav_secret),
         )
+        .route(
+            "/api/secrets/webdav/{name}/references",
+            get(secrets::webdav_secret_references),
+        )
+        .route(
+            "/api/secrets/webdav/{name}/pending",
+            put(secrets::stage_webdav_pending).delete(secrets::discard_webdav_pending),
+        )
+        .route(
+            "/api/secrets/webdav/{name}/pending/promote",
+            post(secrets::promote_webdav_pending),
+        )
         .route(
             "/api/nodes/{node_id}/secrets/webdav",
             get(secrets::list_webdav_secrets_node),
@@ -289,11 +388,48 @@ pub fn router(state: AppState) -> Router {
                 .put(secrets::upsert_webdav_secret_node)
                 .delete(secrets::delete_webdav_secret_node),
         )
+        .route(
+            "/api/nodes/{node_id}/secrets/webdav/{name}/references",
+            get(secrets::webdav_secret_references_node),
+        )
+        .route(
+            "/api/nodes/{node_id}/secrets/webdav/{name}/pending",
+            put(secrets::stage_webdav_pending_node).delete(secrets::discard_webdav_pending_node),
+        )
+        .route(
+            "/api/nodes/{node_id}/secrets/webdav/{name}/pending/promote",
+            post(secrets::promote_webdav_pending_node),
+        )
         .route("/api/nodes/{node_id}/fs/list", get(fs::fs_list))
         .route(
             "/api/nodes/{node_id}/webdav/list",
             get(webdav::webdav_list).post(webdav::webdav_list_post),
         )
+        .route("/api/secrets/imap", get(secrets::list_imap_secrets))
+        .route(
+            "/api/secrets/imap/{name}",
+            get(secrets::get_imap_secret)
+                .put(secrets::upsert_imap_secret)
+                .delete(secrets::delete_imap_secret),
+        )
+        .route(
+            "/api/secrets/imap/{name}/references",
+            get(secrets::imap_secret_references),
+        )
+        .route(
+            "/api/nodes/{node_id}/secrets/imap",
+            get(secrets::list_imap_secrets_node),
+        )
+        .route(
+            "/api/nodes/{node_id}/secrets/imap/{name}",
+            get(secrets::get_imap_secret_node)
+                .put(secrets::upsert_imap_secret_node)
+                .delete(secrets::delete_imap_secret_node),
+        )
+        .route(
+            "/api/nodes/{node_id}/secrets/imap/{name}/references",
+            get(secrets::imap_secret_references_node),
+        )
         .route(
             "/api/secrets/wecom-bot",
             get(secrets::list_wecom_bot_secrets),
@@ -304,6 +440,63 @@ pub fn router(state: AppState) -> Router {
                 .put(secrets::upsert_wecom_bot_secret)
                 .delete(secrets::delete_wecom_bot_secret),
         )
+        .route(
+            "/api/secrets/wecom-bot/{name}/references",
+            get(secrets::wecom_bot_secret_references),
+        )
+        .route("/api/secrets/slack", get(secrets::list_slack_secrets))
+        .route(
+            "/api/secrets/slack/{name}",
+            get(secrets::get_slack_secret)
+                .put(secrets::upsert_slack_secret)
+                .delete(secrets::delete_slack_secret),
+        )
+        .route(
+            "/api/secrets/slack/{name}/references",
+            get(secrets::slack_secret_references),
+        )
+        .route("/api/secrets/discord", get(secrets::list_discord_secrets))
+        .route(
+            "/api/secrets/discord/{name}",
+            get(secrets::get_discord_secret)
+                .put(secrets::upsert_discord_secret)
+                .delete(secrets::delete_discord_secret),
+        )
+        .route(
+            "/api/secrets/discord/{name}/references",
+            get(secrets::discord_secret_references),
+        )
+        .route("/api/secrets/sms", get(secrets::list_sms_secrets))
+        .route(
+            "/api/secrets/sms/{name}",
+            get(secrets::get_sms_secret)
+                .put(secrets::upsert_sms_secret)
+                .delete(secrets::delete_sms_secret),
+        )
+        .route(
+            "/api/secrets/sms/{name}/references",
+            get(secrets::sms_secret_references),
+        )
+        .route(
+            "/api/secrets/backup-passphrase-keys",
+            get(secrets::list_backup_passphrase_keys),
+        )
+        .route(
+            "/api/secrets/backup-passphrase-keys/{name}",
+            get(secrets::get_backup_passphrase_key).post(secrets::create_backup_passphrase_key),
+        )
+        .route(
+            "/api/secrets/backup-keys/{name}/rotate",
+            post(secrets::rotate_backup_key),
+        )
+        .route(
+            "/api/secrets/providers/health",
+            get(secrets::external_providers_health),
+        )
+        .route(
+            "/api/secrets/external/{node_id}/{kind}/{name}",
+            get(secrets::get_external_secret).put(secrets::upsert_external_secret),
+        )
         .route("/api/secrets/smtp", get(secrets::list_smtp_secrets))
         .route(
             "/api/secrets/smtp/{name}",
@@ -311,18 +504,42 @@ pub fn router(state: AppState) -> Router {
                 .put(secrets::upsert_smtp_secret)
                 .delete(secrets::delete_smtp_secret),
         )
+        .route(
+            "/api/secrets/smtp/{name}/references",
+            get(secrets::smtp_secret_references),
+        )
         .route("/api/agents", get(agents::list_agents))
         .route("/api/agents/labels", get(agents::list_agent_labels_index))
         .route("/api/agents/{id}", get(agents::get_agent))
         .route("/api/agents/{id}/revoke", post(agents::revoke_agent))
+        .route(
+            "/api/agents/{id}/connections",
+            get(agents::list_agent_connections),
+        )
         .route(
             "/api/agents/{id}/rotate-key",
             post(agents::rotate_agent_key),
         )
+        .route(
+            "/api/agents/{id}/client-cert",
+            put(agents::set_agent_client_cert_policy),
+        )
         .route(
             "/api/agents/{id}/sync-config-now",
             post(agents::sync_config_now),
         )
+        .route(
+            "/api/agents/{id}/update-policy",
+            put(agents::set_agent_update_policy),
+        )
+        .route(
+            "/api/agents/{id}/update",
+            post(agents::trigger_agent_update),
+        )
+        .route(
+            "/api/agents/{id}/diagnostics",
+            post(agents::run_agent_diagnostics),
+        )
         .route("/api/agents/{id}/labels", put(agents::set_agent_labels))
         .route(
             "/api/agents/{id}/labels/add",
@@ -365,10 +582,28 @@ pub fn router(state: AppState) -> Router {
                 .delete(jobs::delete_job),
         )
         .route("/api/jobs/{id}/workspace", get(jobs::get_job_workspace))
+        .route(
+            "/api/jobs/{id}/access",
+            get(jobs::get_job_access).put(jobs::put_job_access),
+        )
         .route("/api/jobs/{id}/archive", post(jobs::archive_job))
         .route("/api/jobs/{id}/unarchive", post(jobs::unarchive_job))
         .route("/api/jobs/{id}/run", post(jobs::trigger_job_run))
+        .route(
+            "/api/jobs/{id}/postgres/pitr",
+            post(operations::start_postgres_pitr),
+        )
         .route("/api/jobs/{id}/runs", get(jobs::list_job_runs))
+        .route("/api/jobs/{id}/runs/search", get(jobs::search_job_runs))
+        .route(
+            "/api/jobs/{id}/files/history",
+            get(jobs::get_job_file_history),
+        )
+        .route(
+            "/api/jobs/{id}/schedule/preview",
+            get(jobs::preview_job_schedule),
+        )
+        .route("/api/schedule/upcoming", get(jobs::list_upcoming_schedule))
         .route(
             "/api/jobs/{id}/retention",
             get(jobs::get_job_retention).put(jobs::put_job_retention),
@@ -422,7 +657,10 @@ pub fn router(state: AppState) -> Router {
         .route("/api/runs/{id}", get(runs::get_run))
         .route("/api/runs/{id}/workspace", get(runs::get_run_workspace))
         .route("/api/runs/{id}/cancel", post(runs::cancel_run))
+        .route("/api/runs/{id}/purge", post(runs::purge_run))
         .route("/api/runs/{id}/events", get(jobs::list_run_events))
+        .route("/api/runs/{id}/events/export", get(jobs::export_run_events))
+        .route("/api/runs/{id}/log", get(jobs::download_run_log))
         .route(
             "/api/runs/{id}/event-console",
             get(runs::list_run_event_console),
@@ -433,8 +671,31 @@ pub fn router(state: AppState) -> Router {
             "/api/runs/{id}/operations",
             get(operations::list_run_operations),
         )
+        .route(
+            "/api/runs/{id}/download-links",
+            post(downloads::create_snapshot_download_link),
+        )
         .route("/api/runs/{id}/restore", post(operations::start_restore))
+        .route(
+            "/api/runs/{id}/restore/preview",
+            post(operations::preview_restore),
+        )
         .route("/api/runs/{id}/verify", post(operations::start_verify))
+        .route("/api/maintenance/db", get(maintenance::get_db_status))
+        .route(
+            "/api/maintenance/db/checkpoint",
+            post(maintenance::checkpoint_db),
+        )
+        .route("/api/maintenance/db/backup", post(maintenance::backup_db))
+        .route(
+            "/api/maintenance/db/snapshot",
+            get(maintenance::download_db_snapshot),
+        )
+        .route("/api/maintenance/doctor", get(maintenance::run_doctor))
+        .route(
+            "/api/maintenance/orphans/scan",
+            post(maintenance::scan_orphans),
+        )
         .route(
             "/api/maintenance/incomplete-cleanup",
             get(maintenance::list_incomplete_cleanup_tasks),
@@ -459,6 +720,10 @@ pub fn router(state: AppState) -> Router {
             "/api/notifications/settings",
             get(notifications::get_settings).put(notifications::put_settings),
         )
+        .route(
+            "/api/notifications/templates/email/preview",
+            post(notifications::preview_email_template),
+        )
         .route(
             "/api/notifications/destinations",
             get(notifications::list_destinations),
@@ -481,6 +746,24 @@ pub fn router(state: AppState) -> Router {
             post(notifications::cancel),
         )
         .route("/api/operations/{id}", get(operations::get_operation))
+        .route(
+            "/api/operations/{id}/download-links",
+            post(downloads::create_restored_file_download_link),
+        )
+        .route("/api/download-links", get(downloads::list_download_links))
+        .route(
+            "/api/download-links/{id}/audit",
+            get(downloads::get_download_link_audit),
+        )
+        .route(
+            "/api/download-links/{id}/revoke",
+            post(downloads::revoke_download_link),
+        )
+        .route("/api/download/{token}", get(downloads::download))
+        .route(
+            "/api/operations/{id}/approve",
+            post(operations::approve_operation),
+        )
         .route(
             "/api/operations/{id}/cancel",
             post(operations::cancel_operation),
@@ -489,12 +772,20 @@ pub fn router(state: AppState) -> Router {
             "/api/operations/{id}/events",
             get(operations::list_operation_events),
         )
+        .route(
+            "/api/operations/{id}/events/ws",
+            get(operations_ws::operation_events_ws),
+        )
         .layer(DefaultBodyLimit::max(API_BODY_LIMIT_BYTES));
 
     let agent_router = Router::new()
         .route("/agent/enroll", post(agents::agent_enroll))
         .route("/agent/runs/ingest", post(agents::agent_ingest_runs))
         .route("/agent/ws", get(agents::agent_ws))
+        .route(
+            "/agent/releases/{version}/{platform}",
+            get(agents::agent_release_download),
+        )
         .layer(DefaultBodyLimit::max(AGENT_BODY_LIMIT_BYTES));
 
     let docs_router = Router::new()
@@ -502,9 +793,21 @@ pub fn router(state: AppState) -> Router {
         .route("/docs/", get(docs::docs_fallback))
         .route("/docs/{*path}", get(docs::docs_fallback));
 
-    api_router
+    let ui_dev_proxy = state
+        .config
+        .ui_dev_proxy
+        .as_deref()
+        .map(ui_dev_proxy::UiDevProxy::new);
+
+    let rate_limiter = Arc::new(rate_limit::ApiRateLimiter::from_config(&state.config));
+
+    let router = api_router
         .merge(agent_router)
         .merge(docs_router)
+        .layer(axum::middleware::from_fn_with_state(
+            (state.clone(), rate_limiter.clone()),
+            middleware::session_rate_limit_middleware,
+        ))
         .layer(axum::middleware::from_fn_with_state(
             state.clone(),
             middleware::require_secure_middleware,
@@ -514,16 +817,40 @@ pub fn router(state: AppState) -> Router {
             middleware::bind_error_render_options_middleware,
         ))
         .layer(CookieManagerLayer::new())
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::api_token_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            rate_limiter,
+            middleware::ip_rate_limit_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::client_conn_info_middleware,
+        ))
         .layer(trace_layer)
         .layer(PropagateRequestIdLayer::new(request_id_header.clone()))
         .layer(SetRequestIdLayer::new(request_id_header, MakeRequestUuid))
-        .with_state(state)
-        .fallback(ui::ui_fallback)
+        .with_state(state);
+    match ui_dev_proxy {
+        Some(proxy) => {
+            tracing::warn!(target = %proxy.base(), "serving the UI through the dev proxy");
+            router.fallback(move |req: axum::extract::Request| proxy.clone().handle(req))
+        }
+        None => router.fallback(ui::ui_fallback),
+    }
 }
 
 #[cfg(test)]
 mod ws_tests;
 
+#[cfg(test)]
+mod agent_client_certs_tests;
+
+#[cfg(test)]
+mod download_links_tests;
+
 #[cfg(test)]
 mod error_feedback_tests;
 
@@ -557,6 +884,9 @@ mod snapshots_tests;
 #[cfg(test)]
 mod jobs_archive_tests;
 
+#[cfg(test)]
+mod api_tokens_tests;
+
 #[cfg(test)]
 mod retention_tests;
 
@@ -569,6 +899,24 @@ mod jobs_workspace_tests;
 #[cfg(test)]
 mod dashboard_tests;
 
+#[cfg(test)]
+mod storage_usage_tests;
+
+#[cfg(test)]
+mod stats_tests;
+
+#[cfg(test)]
+mod stale_backups_tests;
+
+#[cfg(test)]
+mod notifications_templates_tests;
+
+#[cfg(test)]
+mod agent_connections_tests;
+
+#[cfg(test)]
+mod agent_diagnostics_tests;
+
 #[cfg(test)]
 mod command_center_tests;
 
@@ -577,3 +925,39 @@ mod auth_tests;
 
 #[cfg(test)]
 mod control_plane_fleet_integrations_tests;
+
+#[cfg(test)]
+mod jobs_schedule_tests;
+
+#[cfg(test)]
+mod jobs_files_tests;
+
+#[cfg(test)]
+mod secrets_backup_keys_tests;
+
+#[cfg(test)]
+mod secrets_references_tests;
+
+#[cfg(test)]
+mod secrets_external_tests;
+#[cfg(test)]
+mod secrets_webdav_rotation_tests;
+
+#[cfg(test)]
+mod secrets_keypack_tests;
+
+#[cfg(test)]
+mod config_bundle_tests;
+
+#[cfg(test)]
+mod job_access_tests;
+#[cfg(test)]
+mod maintenance_db_tests;
+#[cfg(test)]
+mod maintenance_doctor_tests;
+#[cfg(test)]
+mod maintenance_orphans_tests;
+#[cfg(test)]
+mod rate_limit_tests;
+#[cfg(test)]
+mod restore_approvals_tests;
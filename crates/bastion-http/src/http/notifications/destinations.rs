@@ -6,14 +6,14 @@ use time::OffsetDateTime;
 use tower_cookies::Cookies;
 
 use bastion_core::HUB_NODE_ID;
-use bastion_notify::{smtp, wecom};
+use bastion_notify::{MessageTemplates, builtin_channels};
 use bastion_storage::notification_destinations_repo;
 use bastion_storage::notifications_repo;
 use bastion_storage::secrets_repo;
 
 use super::super::shared::{require_csrf, require_session};
 use super::super::{AppError, AppState};
-use super::validation::{destination_exists, invalid_channel_error, require_supported_channel};
+use super::validation::{destination_exists, require_supported_channel};
 
 fn invalid_name_error(message: impl Into<String>) -> AppError {
     AppError::bad_request("invalid_name", message)
@@ -26,7 +26,11 @@ pub(in crate::http) async fn list_destinations(
     cookies: Cookies,
 ) -> Result<Json<Vec<DestinationListItem>>, AppError> {
     let _session = require_session(&state, &cookies).await?;
-    let items = notification_destinations_repo::list_destinations(&state.db).await?;
+    let items = notification_destinations_repo::list_destinations(
+        &state.db,
+        &builtin_channels().destination_kinds(),
+    )
+    .await?;
     Ok(Json(
         items
             .into_iter()
@@ -63,7 +67,7 @@ pub(in crate::http) async fn set_destination_enabled(
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
 
-    require_supported_channel(&channel)?;
+    let notification_channel = require_supported_channel(&channel)?;
     if name.trim().is_empty() {
         return Err(invalid_name_error("Destination name is required"));
     }
@@ -75,8 +79,13 @@ pub(in crate::http) async fn set_destination_enabled(
         ));
     }
 
-    notification_destinations_repo::set_enabled(&state.db, &channel, name.trim(), req.enabled)
-        .await?;
+    notification_destinations_repo::set_enabled(
+        &state.db,
+        notification_channel.secret_kind(),
+        name.trim(),
+        req.enabled,
+    )
+    .await?;
 
     let now = OffsetDateTime::now_utc().unix_timestamp();
     if !req.enabled {
@@ -109,7 +118,7 @@ pub(in crate::http) async fn test_destination(
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
 
-    require_supported_channel(&channel)?;
+    let notification_channel = require_supported_channel(&channel)?;
     if name.trim().is_empty() {
         return Err(invalid_name_error("Destination name is required"));
     }
@@ -120,56 +129,47 @@ pub(in crate::http) async fn test_destination(
         .map(|t| t.to_string())
         .unwrap_or_else(|| now.to_string());
 
-    match channel.as_str() {
-        notifications_repo::CHANNEL_WECOM_BOT => {
-            let secret = secrets_repo::get_secret(
-                &state.db,
-                &state.secrets,
-                HUB_NODE_ID,
-                "wecom_bot",
-                name.trim(),
-            )
-            .await?
-            .ok_or_else(|| AppError::not_found("destination_not_found", "Destination not found"))?;
-            #[derive(Deserialize)]
-            struct Payload {
-                webhook_url: String,
-            }
-            let payload: Payload = serde_json::from_slice(&secret)?;
-            let content = format!(
-                "**Bastion test notification**\n> Destination: {}\n> Time: {}\n",
-                name.trim(),
-                ts
-            );
-            wecom::send_markdown(&payload.webhook_url, &content).await?;
-        }
-        notifications_repo::CHANNEL_EMAIL => {
-            let secret = secrets_repo::get_secret(
-                &state.db,
-                &state.secrets,
-                HUB_NODE_ID,
-                "smtp",
-                name.trim(),
-            )
-            .await?
-            .ok_or_else(|| AppError::not_found("destination_not_found", "Destination not found"))?;
-            let payload: smtp::SmtpSecretPayload = serde_json::from_slice(&secret)?;
-            let subject = "Bastion test notification".to_string();
-            let body = format!(
-                "Bastion test notification\n\nDestination: {}\nTime: {}\n",
-                name.trim(),
-                ts
-            );
-            smtp::send_plain_text(&payload, &subject, &body).await?;
-        }
-        _ => {
-            return Err(invalid_channel_error(
-                &channel,
-                "Unsupported notification channel",
-            ));
-        }
-    }
+    let secret = secrets_repo::get_secret(
+        &state.db,
+        &state.secrets,
+        HUB_NODE_ID,
+        notification_channel.secret_kind(),
+        name.trim(),
+    )
+    .await?
+    .ok_or_else(|| AppError::not_found("destination_not_found", "Destination not found"))?;
+    let destination: serde_json::Value = serde_json::from_slice(&secret)?;
+    let message = notification_channel.render(
+        &destination,
+        &TestMessage {
+            destination: name.trim(),
+            time: &ts,
+        },
+    )?;
+    notification_channel.send(&destination, &message).await?;
 
     tracing::info!(channel = %channel, destination = %name.trim(), "test notification sent");
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// Fixed test texts; per-destination templates do not apply to them.
+struct TestMessage<'a> {
+    destination: &'a str,
+    time: &'a str,
+}
+
+impl MessageTemplates for TestMessage<'_> {
+    fn render(&self, slot: &str, _template_override: Option<&str>) -> String {
+        match slot {
+            "email_subject" => "Bastion test notification".to_string(),
+            "email_body" => format!(
+                "Bastion test notification\n\nDestination: {}\nTime: {}\n",
+                self.destination, self.time
+            ),
+            _ => format!(
+                "**Bastion test notification**\n> Destination: {}\n> Time: {}\n",
+                self.destination, self.time
+            ),
+        }
+    }
+}
@@ -1,8 +1,11 @@
 mod destinations;
 mod queue;
 mod settings;
+mod templates;
 mod validation;
 
 pub(super) use destinations::{list_destinations, set_destination_enabled, test_destination};
 pub(super) use queue::{cancel, list_queue, retry_now};
 pub(super) use settings::{get_settings, put_settings};
+pub(super) use templates::preview_email_template;
+pub(super) use validation::destination_error;
@@ -12,7 +12,7 @@ use bastion_storage::notifications_settings_repo;
 
 use super::super::shared::{require_csrf, require_session};
 use super::super::{AppError, AppState};
-use super::validation::{destination_exists, invalid_channel_error, require_supported_channel};
+use super::validation::{destination_exists, require_supported_channel};
 
 fn invalid_page_size_error(reason: &'static str, message: impl Into<String>) -> AppError {
     AppError::bad_request("invalid_page_size", message)
@@ -279,29 +279,12 @@ pub(in crate::http) async fn retry_now(
             "Notifications are disabled",
         ));
     }
-    match row.channel.as_str() {
-        notifications_repo::CHANNEL_WECOM_BOT => {
-            if !settings.channels.wecom_bot.enabled {
-                return Err(AppError::conflict(
-                    "channel_disabled",
-                    "Channel is disabled",
-                ));
-            }
-        }
-        notifications_repo::CHANNEL_EMAIL => {
-            if !settings.channels.email.enabled {
-                return Err(AppError::conflict(
-                    "channel_disabled",
-                    "Channel is disabled",
-                ));
-            }
-        }
-        _ => {
-            return Err(invalid_channel_error(
-                row.channel.as_str(),
-                "Unsupported notification channel",
-            ));
-        }
+    let channel = require_supported_channel(&row.channel)?;
+    if !settings.channels.is_enabled(channel.id()) {
+        return Err(AppError::conflict(
+            "channel_disabled",
+            "Channel is disabled",
+        ));
     }
 
     // Ensure destination exists and is enabled (retry-now should not bypass disabled destinations).
@@ -311,8 +294,12 @@ pub(in crate::http) async fn retry_now(
             "Destination has been deleted",
         ));
     }
-    if !notification_destinations_repo::is_enabled(&state.db, &row.channel, &row.secret_name)
-        .await?
+    if !notification_destinations_repo::is_enabled(
+        &state.db,
+        channel.secret_kind(),
+        &row.secret_name,
+    )
+    .await?
     {
         return Err(AppError::conflict(
             "destination_disabled",
@@ -59,6 +59,23 @@ pub(in crate::http) async fn put_settings(
         ));
     }
 
+    if req.digest.hour > 23 {
+        return Err(AppError::bad_request(
+            "invalid_digest",
+            "Digest hour must be between 0 and 23",
+        )
+        .with_reason("hour_out_of_range")
+        .with_field("digest.hour"));
+    }
+    if !(1..=7).contains(&req.digest.weekday) {
+        return Err(AppError::bad_request(
+            "invalid_digest",
+            "Digest weekday must be between 1 and 7",
+        )
+        .with_reason("weekday_out_of_range")
+        .with_field("digest.weekday"));
+    }
+
     let now = OffsetDateTime::now_utc().unix_timestamp();
     if !req.enabled {
         let _ = notifications_repo::cancel_all_queued(
@@ -68,19 +85,13 @@ pub(in crate::http) async fn put_settings(
         )
         .await?;
     } else {
-        if !req.channels.wecom_bot.enabled {
-            let _ = notifications_repo::cancel_queued_for_channel(
-                &state.db,
-                notifications_repo::CHANNEL_WECOM_BOT,
-                "canceled: channel disabled",
-                now,
-            )
-            .await?;
-        }
-        if !req.channels.email.enabled {
+        for channel in bastion_notify::builtin_channels().channels() {
+            if req.channels.is_enabled(channel.id()) {
+                continue;
+            }
             let _ = notifications_repo::cancel_queued_for_channel(
                 &state.db,
-                notifications_repo::CHANNEL_EMAIL,
+                channel.id(),
                 "canceled: channel disabled",
                 now,
             )
@@ -93,6 +104,10 @@ pub(in crate::http) async fn put_settings(
         enabled = req.enabled,
         wecom_enabled = req.channels.wecom_bot.enabled,
         email_enabled = req.channels.email.enabled,
+        slack_enabled = req.channels.slack.enabled,
+        discord_enabled = req.channels.discord.enabled,
+        sms_enabled = req.channels.sms.enabled,
+        digest_mode = ?req.digest.mode,
         "notification settings updated"
     );
     Ok(StatusCode::NO_CONTENT)
@@ -0,0 +1,80 @@
+use axum::Json;
+use axum::http::HeaderMap;
+use serde::Deserialize;
+use tower_cookies::Cookies;
+
+use bastion_core::HUB_NODE_ID;
+use bastion_engine::notifications::{EmailPreview, render_email_preview};
+use bastion_notify::smtp;
+use bastion_storage::job_access_repo::JobPermission;
+use bastion_storage::notifications_settings_repo;
+use bastion_storage::secrets_repo;
+
+use super::super::shared::{require_csrf, require_run_permission, require_session};
+use super::super::{AppError, AppState};
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct PreviewEmailTemplateRequest {
+    /// SMTP destination whose templates are used when none are given explicitly.
+    #[serde(default)]
+    destination: Option<String>,
+    #[serde(default)]
+    subject_template: Option<String>,
+    #[serde(default)]
+    body_template: Option<String>,
+    /// Render against this run; a built-in sample context is used otherwise.
+    #[serde(default)]
+    run_id: Option<String>,
+}
+
+pub(in crate::http) async fn preview_email_template(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Json(req): Json<PreviewEmailTemplateRequest>,
+) -> Result<Json<EmailPreview>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    let settings = notifications_settings_repo::get_or_default(&state.db).await?;
+    let mut subject_template = settings.templates.email_subject;
+    let mut body_template = settings.templates.email_body;
+
+    if let Some(name) = req
+        .destination
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+    {
+        let secret = secrets_repo::get_secret(&state.db, &state.secrets, HUB_NODE_ID, "smtp", name)
+            .await?
+            .ok_or_else(|| AppError::not_found("destination_not_found", "Destination not found"))?;
+        let payload: smtp::SmtpSecretPayload = serde_json::from_slice(&secret)?;
+        if let Some(v) = payload.subject_template {
+            subject_template = v;
+        }
+        if let Some(v) = payload.body_template {
+            body_template = v;
+        }
+    }
+
+    if let Some(v) = req.subject_template.filter(|v| !v.trim().is_empty()) {
+        subject_template = v;
+    }
+    if let Some(v) = req.body_template.filter(|v| !v.trim().is_empty()) {
+        body_template = v;
+    }
+
+    let run_id = req
+        .run_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty());
+    if let Some(run_id) = run_id {
+        require_run_permission(&state, &session, run_id, JobPermission::View).await?;
+    }
+
+    let preview =
+        render_email_preview(&state.db, &subject_template, &body_template, run_id).await?;
+    Ok(Json(preview))
+}
@@ -1,8 +1,7 @@
 use sqlx::SqlitePool;
 
 use bastion_core::HUB_NODE_ID;
-use bastion_storage::notification_destinations_repo;
-use bastion_storage::notifications_repo;
+use bastion_notify::{DestinationError, NotificationChannel, builtin_channels};
 
 use super::super::AppError;
 
@@ -13,16 +12,24 @@ pub(super) fn invalid_channel_error(channel: &str, message: impl Into<String>) -
         .with_param("value", channel)
 }
 
-pub(super) fn require_supported_channel(channel: &str) -> Result<(), AppError> {
-    if channel == notifications_repo::CHANNEL_WECOM_BOT
-        || channel == notifications_repo::CHANNEL_EMAIL
-    {
-        return Ok(());
+pub(super) fn require_supported_channel(
+    channel: &str,
+) -> Result<&'static dyn NotificationChannel, AppError> {
+    builtin_channels()
+        .get(channel)
+        .map(|c| c.as_ref())
+        .ok_or_else(|| invalid_channel_error(channel, "Unsupported notification channel"))
+}
+
+/// Maps a rejected destination config onto a field error.
+pub(in crate::http) fn destination_error(error: DestinationError) -> AppError {
+    let mut out = AppError::bad_request(error.code, error.message)
+        .with_reason(error.reason)
+        .with_field(error.field);
+    for (name, value) in error.params {
+        out = out.with_param(name, value);
     }
-    Err(invalid_channel_error(
-        channel,
-        "Unsupported notification channel",
-    ))
+    out
 }
 
 pub(super) async fn destination_exists(
@@ -30,13 +37,13 @@ pub(super) async fn destination_exists(
     channel: &str,
     name: &str,
 ) -> Result<bool, anyhow::Error> {
-    let Some(kind) = notification_destinations_repo::secret_kind_for_channel(channel) else {
+    let Some(channel) = builtin_channels().get(channel) else {
         return Ok(false);
     };
     let row =
         sqlx::query("SELECT 1 FROM secrets WHERE node_id = ? AND kind = ? AND name = ? LIMIT 1")
             .bind(HUB_NODE_ID)
-            .bind(kind)
+            .bind(channel.secret_kind())
             .bind(name)
             .fetch_optional(db)
             .await?;
@@ -25,6 +25,11 @@ async fn ready_is_ok_and_allowed_insecure() {
         hub_timezone: "UTC".to_string(),
         run_retention_days: 180,
         incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
         trusted_proxies: Vec::new(),
     });
     let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
@@ -1,23 +1,30 @@
-use axum::Json;
-use axum::extract::Path;
+use axum::extract::{Path, Query};
 use axum::http::HeaderMap;
+use axum::{Extension, Json};
 use serde::{Deserialize, Serialize};
 use tower_cookies::Cookies;
 
-use super::shared::{require_csrf, require_session};
+use super::shared::{
+    ClientConnInfo, require_csrf, require_job_permission, require_operation_permission,
+    require_run_permission, require_session,
+};
 use super::{AppError, AppState};
 use bastion_backup::backup_encryption;
 use bastion_backup::restore;
 use bastion_core::HUB_NODE_ID;
 use bastion_core::agent_protocol::{
-    HubToAgentMessageV1, PROTOCOL_VERSION, RestoreSelectionV1, RestoreTaskV1,
+    HubToAgentMessageV1, PROTOCOL_VERSION, RestoreDestinationV1, RestoreSelectionV1, RestoreTaskV1,
 };
 use bastion_core::job_spec;
 use bastion_engine::agent_snapshots;
 use bastion_engine::cancel_registry::global_cancel_registry;
 use bastion_storage::agent_tasks_repo;
+use bastion_storage::audit_repo;
+use bastion_storage::hub_runtime_config_repo;
+use bastion_storage::job_access_repo::{self, JobPermission};
 use bastion_storage::jobs_repo;
 use bastion_storage::operations_repo;
+use bastion_storage::restore_approvals_repo;
 use bastion_storage::runs_repo;
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -39,7 +46,7 @@ pub(super) struct RestoreExecutor {
     node_id: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub(super) struct StartRestoreRequest {
     destination: RestoreDestination,
     #[serde(default)]
@@ -47,11 +54,70 @@ pub(super) struct StartRestoreRequest {
     conflict_policy: String,
     #[serde(default)]
     selection: Option<restore::RestoreSelection>,
+    /// Unlocks a passphrase-protected backup key for this operation only; never stored.
+    #[serde(default, skip_serializing)]
+    passphrase: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct ApproveOperationRequest {
+    /// Unlocks a passphrase-protected backup key for the approved restore; never stored.
+    #[serde(default)]
+    passphrase: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct StartPostgresPitrRequest {
+    /// Point in time to recover to (unix seconds).
+    target_time: i64,
+    /// Empty directory on the Hub that receives the rebuilt data directory.
+    directory: String,
+    /// Unlocks a passphrase-protected backup key for this operation only; never stored.
+    #[serde(default)]
+    passphrase: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+pub(super) struct StartVerifyRequest {
+    /// Unlocks a passphrase-protected backup key for this operation only; never stored.
+    #[serde(default)]
+    passphrase: Option<String>,
+    /// `full` (default), `stream` or `sample`.
+    #[serde(default)]
+    mode: Option<String>,
+    /// Share of parts whose hashes are checked in `sample` mode (1-100).
+    #[serde(default)]
+    sample_percent: Option<u8>,
+}
+
+pub(super) fn parse_verify_mode(req: &StartVerifyRequest) -> Result<restore::VerifyMode, AppError> {
+    match req.mode.as_deref().map(str::trim).unwrap_or("full") {
+        "" | "full" => Ok(restore::VerifyMode::Full),
+        "stream" => Ok(restore::VerifyMode::Stream),
+        "sample" => {
+            let percent = req
+                .sample_percent
+                .unwrap_or(restore::VerifyMode::DEFAULT_SAMPLE_PERCENT);
+            if !(1..=100).contains(&percent) {
+                return Err(AppError::bad_request(
+                    "invalid_sample_percent",
+                    "sample_percent must be between 1 and 100",
+                )
+                .with_field("sample_percent"));
+            }
+            Ok(restore::VerifyMode::Sample { percent })
+        }
+        _ => Err(
+            AppError::bad_request("invalid_verify_mode", "Invalid verify mode").with_field("mode"),
+        ),
+    }
 }
 
 #[derive(Debug, Serialize)]
 pub(super) struct StartOperationResponse {
     op_id: String,
+    /// `pending_approval` when the restore waits for a second admin.
+    status: operations_repo::OperationStatus,
 }
 
 pub(super) fn invalid_destination_error(
@@ -88,22 +154,10 @@ fn dispatch_failed_error(reason: &'static str, message: impl Into<String>) -> Ap
         .with_field("executor.node_id")
 }
 
-pub(super) async fn start_restore(
-    state: axum::extract::State<AppState>,
-    cookies: Cookies,
-    headers: HeaderMap,
-    Path(run_id): Path<String>,
-    Json(req): Json<StartRestoreRequest>,
-) -> Result<Json<StartOperationResponse>, AppError> {
-    let session = require_session(&state, &cookies).await?;
-    require_csrf(&headers, &session)?;
-
-    let conflict = req
-        .conflict_policy
-        .parse::<restore::ConflictPolicy>()
-        .map_err(|_| invalid_conflict_policy_error("Invalid conflict policy"))?;
-
-    if let Some(selection) = req.selection.as_ref()
+fn validate_restore_selection(
+    selection: Option<&restore::RestoreSelection>,
+) -> Result<(), AppError> {
+    if let Some(selection) = selection
         && selection
             .files
             .iter()
@@ -115,8 +169,11 @@ pub(super) async fn start_restore(
             "restore selection is empty",
         ));
     }
+    Ok(())
+}
 
-    let run = runs_repo::get_run(&state.db, &run_id)
+async fn require_success_run(state: &AppState, run_id: &str) -> Result<runs_repo::Run, AppError> {
+    let run = runs_repo::get_run(&state.db, run_id)
         .await?
         .ok_or_else(|| AppError::not_found("run_not_found", "Run not found"))?;
     if run.status != runs_repo::RunStatus::Success {
@@ -125,6 +182,119 @@ pub(super) async fn start_restore(
             "Run is not successful",
         ));
     }
+    Ok(run)
+}
+
+#[derive(Default, Deserialize)]
+pub(super) struct RestorePreviewRequest {
+    #[serde(default)]
+    destination: Option<RestoreDestination>,
+    #[serde(default)]
+    selection: Option<restore::RestoreSelection>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct RestorePreviewResponse {
+    #[serde(flatten)]
+    estimate: restore::RestoreEstimate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    space: Option<RestorePreviewSpace>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct RestorePreviewSpace {
+    /// `checked`, or why the check was skipped: `remote_node`, `webdav` or `unavailable`.
+    pub(super) status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) available_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) sufficient: Option<bool>,
+}
+
+/// Free space can only be read for directories on the Hub itself.
+pub(super) fn destination_space(
+    destination: &RestoreDestination,
+    bytes: u64,
+) -> RestorePreviewSpace {
+    let skipped = |status| RestorePreviewSpace {
+        status,
+        available_bytes: None,
+        sufficient: None,
+    };
+    match destination {
+        RestoreDestination::Webdav { .. } => skipped("webdav"),
+        RestoreDestination::LocalFs { node_id, .. } if node_id.trim() != HUB_NODE_ID => {
+            skipped("remote_node")
+        }
+        RestoreDestination::LocalFs { directory, .. } => {
+            match restore::available_space(std::path::Path::new(directory.trim())) {
+                Some(available) => RestorePreviewSpace {
+                    status: "checked",
+                    available_bytes: Some(available),
+                    sufficient: Some(available >= bytes),
+                },
+                None => skipped("unavailable"),
+            }
+        }
+    }
+}
+
+pub(super) async fn preview_restore(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(run_id): Path<String>,
+    req: Option<Json<RestorePreviewRequest>>,
+) -> Result<Json<RestorePreviewResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_run_permission(&state, &session, &run_id, JobPermission::Trigger).await?;
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+
+    validate_restore_selection(req.selection.as_ref())?;
+    let run = require_success_run(&state, &run_id).await?;
+
+    let estimate = restore::estimate_run_restore(
+        &state.db,
+        state.secrets.as_ref(),
+        &state.config.data_dir,
+        &run.id,
+        req.selection.as_ref(),
+    )
+    .await
+    .map_err(|error| {
+        AppError::bad_request(
+            "restore_preview_failed",
+            format!("Restore preview failed: {error}"),
+        )
+    })?;
+    let space = req
+        .destination
+        .as_ref()
+        .map(|destination| destination_space(destination, estimate.bytes));
+
+    Ok(Json(RestorePreviewResponse { estimate, space }))
+}
+
+/// A restore request that passed validation, ready to start.
+struct PreparedRestore {
+    job: jobs_repo::Job,
+    conflict: restore::ConflictPolicy,
+    executor_node_id: String,
+    destination_for_hub: restore::RestoreDestination,
+    destination_for_agent: RestoreDestinationV1,
+}
+
+async fn prepare_restore(
+    state: &AppState,
+    run_id: &str,
+    req: &StartRestoreRequest,
+) -> Result<PreparedRestore, AppError> {
+    let conflict = req
+        .conflict_policy
+        .parse::<restore::ConflictPolicy>()
+        .map_err(|_| invalid_conflict_policy_error("Invalid conflict policy"))?;
+
+    validate_restore_selection(req.selection.as_ref())?;
+    let run = require_success_run(state, run_id).await?;
 
     let job = jobs_repo::get_job(&state.db, &run.job_id)
         .await?
@@ -180,7 +350,7 @@ pub(super) async fn start_restore(
                 restore::RestoreDestination::LocalFs {
                     directory: std::path::PathBuf::from(directory),
                 },
-                bastion_core::agent_protocol::RestoreDestinationV1::LocalFs {
+                RestoreDestinationV1::LocalFs {
                     directory: directory.to_string(),
                 },
             )
@@ -222,7 +392,7 @@ pub(super) async fn start_restore(
                     secret_name: secret_name.to_string(),
                     prefix: prefix.to_string(),
                 },
-                bastion_core::agent_protocol::RestoreDestinationV1::Webdav {
+                RestoreDestinationV1::Webdav {
                     base_url: base_url.to_string(),
                     secret_name: secret_name.to_string(),
                     prefix: prefix.to_string(),
@@ -239,23 +409,45 @@ pub(super) async fn start_restore(
         .filter(|v| !v.is_empty())
         .unwrap_or(default_executor_node_id);
 
-    let op = operations_repo::create_operation(
-        &state.db,
-        operations_repo::OperationKind::Restore,
-        Some(("run", run_id.as_str())),
-    )
-    .await?;
+    Ok(PreparedRestore {
+        job,
+        conflict,
+        executor_node_id,
+        destination_for_hub,
+        destination_for_agent,
+    })
+}
+
+/// Identifies where a restore writes, so destinations a job restored to before can be recognized.
+fn destination_key(destination: &RestoreDestination) -> String {
+    match destination {
+        RestoreDestination::LocalFs { node_id, directory } => {
+            format!("local_fs:{}:{}", node_id.trim(), directory.trim())
+        }
+        RestoreDestination::Webdav {
+            base_url, prefix, ..
+        } => format!("webdav:{}:{}", base_url.trim(), prefix.trim()),
+    }
+}
+
+async fn append_restore_requested_event(
+    state: &AppState,
+    op_id: &str,
+    run_id: &str,
+    req: &StartRestoreRequest,
+    prepared: &PreparedRestore,
+) {
     let _ = operations_repo::append_event(
         &state.db,
-        &op.id,
+        op_id,
         "info",
         "requested",
         "requested",
         Some(serde_json::json!({
-            "run_id": run_id.clone(),
+            "run_id": run_id,
             "destination": serde_json::to_value(&req.destination).ok(),
-            "executor": serde_json::json!({ "node_id": executor_node_id }),
-            "conflict_policy": conflict.as_str(),
+            "executor": serde_json::json!({ "node_id": prepared.executor_node_id }),
+            "conflict_policy": prepared.conflict.as_str(),
             "selection": req.selection.as_ref().map(|s| serde_json::json!({
                 "files": s.files.len(),
                 "dirs": s.dirs.len(),
@@ -263,10 +455,34 @@ pub(super) async fn start_restore(
         })),
     )
     .await;
+}
+
+async fn restore_approval_required(state: &AppState) -> Result<bool, AppError> {
+    Ok(hub_runtime_config_repo::get(&state.db)
+        .await?
+        .is_some_and(|config| config.restore_approval_required))
+}
+
+/// Dispatches an accepted restore to its executor (an agent or the Hub itself). Marks the
+/// operation failed if that isn't possible.
+async fn dispatch_restore(
+    state: &AppState,
+    op_id: &str,
+    run_id: &str,
+    selection: Option<restore::RestoreSelection>,
+    passphrase: Option<String>,
+    prepared: PreparedRestore,
+) -> Result<(), AppError> {
+    let PreparedRestore {
+        job,
+        conflict,
+        executor_node_id,
+        destination_for_hub,
+        destination_for_agent,
+    } = prepared;
 
     // Validate WebDAV destination secret in the selected executor scope before dispatch/spawn.
-    if let bastion_core::agent_protocol::RestoreDestinationV1::Webdav { secret_name, .. } =
-        &destination_for_agent
+    if let RestoreDestinationV1::Webdav { secret_name, .. } = &destination_for_agent
         && bastion_storage::secrets_repo::get_secret(
             &state.db,
             &state.secrets,
@@ -279,7 +495,7 @@ pub(super) async fn start_restore(
     {
         let _ = operations_repo::complete_operation(
             &state.db,
-            &op.id,
+            op_id,
             operations_repo::OperationStatus::Failed,
             None,
             Some("missing webdav secret"),
@@ -295,7 +511,7 @@ pub(super) async fn start_restore(
         if !state.agent_manager.is_connected(&executor_node_id).await {
             let _ = operations_repo::complete_operation(
                 &state.db,
-                &op.id,
+                op_id,
                 operations_repo::OperationStatus::Failed,
                 None,
                 Some("destination agent is not connected"),
@@ -315,7 +531,7 @@ pub(super) async fn start_restore(
                 let msg = format!("{error:#}");
                 let _ = operations_repo::complete_operation(
                     &state.db,
-                    &op.id,
+                    op_id,
                     operations_repo::OperationStatus::Failed,
                     None,
                     Some(&msg),
@@ -328,7 +544,7 @@ pub(super) async fn start_restore(
             let msg = format!("{error:#}");
             let _ = operations_repo::complete_operation(
                 &state.db,
-                &op.id,
+                op_id,
                 operations_repo::OperationStatus::Failed,
                 None,
                 Some(&msg),
@@ -341,7 +557,30 @@ pub(super) async fn start_restore(
             job_spec::JobSpecV1::Filesystem { pipeline, .. } => pipeline,
             job_spec::JobSpecV1::Sqlite { pipeline, .. } => pipeline,
             job_spec::JobSpecV1::Vaultwarden { pipeline, .. } => pipeline,
+            job_spec::JobSpecV1::SqliteApp { pipeline, .. } => pipeline,
+            job_spec::JobSpecV1::Postgres { pipeline, .. } => pipeline,
+            job_spec::JobSpecV1::VmImage { pipeline, .. } => pipeline,
+            job_spec::JobSpecV1::KubernetesPvc { pipeline, .. } => pipeline,
+            job_spec::JobSpecV1::Imap { pipeline, .. } => pipeline,
+            job_spec::JobSpecV1::Git { pipeline, .. } => pipeline,
         };
+        if matches!(
+            pipeline.encryption,
+            job_spec::EncryptionV1::AgePassphrase { .. }
+        ) {
+            let msg = "passphrase-protected snapshots can only be restored on the Hub";
+            let _ = operations_repo::complete_operation(
+                &state.db,
+                op_id,
+                operations_repo::OperationStatus::Failed,
+                None,
+                Some(msg),
+            )
+            .await;
+            return Err(AppError::bad_request("unsupported_executor", msg)
+                .with_reason("passphrase_key")
+                .with_field("executor.node_id"));
+        }
         if let job_spec::EncryptionV1::AgeX25519 { key_name } = &pipeline.encryption {
             let key_name = key_name.trim();
             if !key_name.is_empty() {
@@ -356,7 +595,7 @@ pub(super) async fn start_restore(
                     let msg = format!("{error:#}");
                     let _ = operations_repo::complete_operation(
                         &state.db,
-                        &op.id,
+                        op_id,
                         operations_repo::OperationStatus::Failed,
                         None,
                         Some(&msg),
@@ -367,7 +606,7 @@ pub(super) async fn start_restore(
 
                 let _ = operations_repo::append_event(
                     &state.db,
-                    &op.id,
+                    op_id,
                     "info",
                     "age_identity",
                     "age_identity",
@@ -391,7 +630,7 @@ pub(super) async fn start_restore(
                     let msg = format!("{error:#}");
                     let _ = operations_repo::complete_operation(
                         &state.db,
-                        &op.id,
+                        op_id,
                         operations_repo::OperationStatus::Failed,
                         None,
                         Some(&msg),
@@ -403,30 +642,30 @@ pub(super) async fn start_restore(
         }
 
         let task = RestoreTaskV1 {
-            op_id: op.id.clone(),
-            run_id: run_id.clone(),
-            destination: Some(destination_for_agent),
-            destination_dir: match &req.destination {
-                RestoreDestination::LocalFs { directory, .. } => directory.trim().to_string(),
-                RestoreDestination::Webdav { .. } => String::new(),
+            op_id: op_id.to_string(),
+            run_id: run_id.to_string(),
+            destination_dir: match &destination_for_agent {
+                RestoreDestinationV1::LocalFs { directory } => directory.clone(),
+                RestoreDestinationV1::Webdav { .. } => String::new(),
             },
+            destination: Some(destination_for_agent),
             conflict_policy: conflict.as_str().to_string(),
-            selection: req.selection.as_ref().map(|s| RestoreSelectionV1 {
+            selection: selection.as_ref().map(|s| RestoreSelectionV1 {
                 files: s.files.clone(),
                 dirs: s.dirs.clone(),
             }),
         };
         let msg = HubToAgentMessageV1::RestoreTask {
             v: PROTOCOL_VERSION,
-            task_id: op.id.clone(),
+            task_id: op_id.to_string(),
             task: Box::new(task),
         };
         let payload = serde_json::to_value(&msg)?;
         if let Err(error) = agent_tasks_repo::upsert_task(
             &state.db,
-            &op.id,
+            op_id,
             &executor_node_id,
-            &run_id,
+            run_id,
             "sent",
             &payload,
         )
@@ -435,7 +674,7 @@ pub(super) async fn start_restore(
             let msg = format!("{error:#}");
             let _ = operations_repo::complete_operation(
                 &state.db,
-                &op.id,
+                op_id,
                 operations_repo::OperationStatus::Failed,
                 None,
                 Some(&msg),
@@ -453,7 +692,7 @@ pub(super) async fn start_restore(
                 // Best-effort: mark operation failed so it doesn't remain stuck in running state.
                 // Ignore errors from completion, since we are already returning an error.
                 let db = state.db.clone();
-                let op_id = op.id.clone();
+                let op_id = op_id.to_string();
                 tokio::spawn(async move {
                     let _ = operations_repo::complete_operation(
                         &db,
@@ -468,27 +707,28 @@ pub(super) async fn start_restore(
             })?;
 
         tracing::info!(
-            op_id = %op.id,
+            op_id = %op_id,
             run_id = %run_id,
             executor_node_id = %executor_node_id,
             conflict = %conflict.as_str(),
             "restore dispatched to agent"
         );
-        return Ok(Json(StartOperationResponse { op_id: op.id }));
+        return Ok(());
     }
 
     restore::spawn_restore_operation(
         state.db.clone(),
         state.secrets.clone(),
         state.config.data_dir.clone(),
-        op.id.clone(),
-        run_id.clone(),
+        op_id.to_string(),
+        run_id.to_string(),
         destination_for_hub,
         conflict,
-        req.selection,
-        global_cancel_registry().register_operation(&op.id),
+        selection,
+        passphrase,
+        global_cancel_registry().register_operation(op_id),
         Some(Box::new({
-            let op_id = op.id.clone();
+            let op_id = op_id.to_string();
             move || {
                 global_cancel_registry().unregister_operation(&op_id);
             }
@@ -497,33 +737,299 @@ pub(super) async fn start_restore(
     .await;
 
     tracing::info!(
-        op_id = %op.id,
+        op_id = %op_id,
         run_id = %run_id,
         executor_node_id = %executor_node_id,
         conflict = %conflict.as_str(),
         "restore requested"
     );
-    Ok(Json(StartOperationResponse { op_id: op.id }))
+    Ok(())
 }
 
-pub(super) async fn start_verify(
+pub(super) async fn start_restore(
     state: axum::extract::State<AppState>,
     cookies: Cookies,
     headers: HeaderMap,
     Path(run_id): Path<String>,
+    Json(req): Json<StartRestoreRequest>,
 ) -> Result<Json<StartOperationResponse>, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_run_permission(&state, &session, &run_id, JobPermission::Trigger).await?;
 
-    let run = runs_repo::get_run(&state.db, &run_id)
+    let prepared = prepare_restore(&state, &run_id, &req).await?;
+    let destination_key = destination_key(&req.destination);
+    if restore_approval_required(&state).await?
+        && !restore_approvals_repo::is_known_destination(
+            &state.db,
+            &prepared.job.id,
+            &destination_key,
+        )
         .await?
-        .ok_or_else(|| AppError::not_found("run_not_found", "Run not found"))?;
-    if run.status != runs_repo::RunStatus::Success {
+    {
+        // The passphrase is not serialized; the approver supplies it if needed.
+        let op = restore_approvals_repo::create_pending(
+            &state.db,
+            &run_id,
+            &prepared.job.id,
+            &destination_key,
+            &serde_json::to_value(&req)?,
+            session.user_id,
+        )
+        .await?;
+        append_restore_requested_event(&state, &op.id, &run_id, &req, &prepared).await;
+        let _ = operations_repo::append_event(
+            &state.db,
+            &op.id,
+            "info",
+            "approval_required",
+            "waiting for a second admin to approve the new destination",
+            None,
+        )
+        .await;
+        tracing::info!(
+            op_id = %op.id,
+            run_id = %run_id,
+            user_id = session.user_id,
+            "restore waiting for approval"
+        );
+        return Ok(Json(StartOperationResponse {
+            op_id: op.id,
+            status: operations_repo::OperationStatus::PendingApproval,
+        }));
+    }
+
+    let op = operations_repo::create_operation(
+        &state.db,
+        operations_repo::OperationKind::Restore,
+        Some(("run", run_id.as_str())),
+    )
+    .await?;
+    append_restore_requested_event(&state, &op.id, &run_id, &req, &prepared).await;
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    restore_approvals_repo::remember_destination(
+        &state.db,
+        &prepared.job.id,
+        &destination_key,
+        now,
+    )
+    .await?;
+    dispatch_restore(
+        &state,
+        &op.id,
+        &run_id,
+        req.selection,
+        req.passphrase,
+        prepared,
+    )
+    .await?;
+
+    Ok(Json(StartOperationResponse {
+        op_id: op.id,
+        status: operations_repo::OperationStatus::Running,
+    }))
+}
+
+pub(super) async fn approve_operation(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Path(op_id): Path<String>,
+    req: Option<Json<ApproveOperationRequest>>,
+) -> Result<Json<OperationResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_operation_permission(&state, &session, &op_id, JobPermission::View).await?;
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+
+    if !job_access_repo::is_admin(&state.db, session.user_id).await? {
+        return Err(AppError::forbidden(
+            "admin_required",
+            "Only admins can approve restores",
+        ));
+    }
+    let Some(pending) = restore_approvals_repo::get_pending(&state.db, &op_id).await? else {
+        return Err(AppError::conflict(
+            "operation_not_pending",
+            "Operation is not waiting for approval",
+        ));
+    };
+    if pending.requested_by_user_id == Some(session.user_id) {
+        return Err(AppError::forbidden(
+            "second_admin_required",
+            "A restore must be approved by someone other than the user who requested it",
+        )
+        .with_reason("same_user"));
+    }
+    let restore_req = serde_json::from_value::<StartRestoreRequest>(pending.request.clone())?;
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    if !restore_approvals_repo::approve(&state.db, &op_id, session.user_id, now).await? {
+        return Err(AppError::conflict(
+            "operation_not_pending",
+            "Operation is not waiting for approval",
+        ));
+    }
+    let _ = operations_repo::append_event(
+        &state.db,
+        &op_id,
+        "info",
+        "approved",
+        "approved",
+        Some(serde_json::json!({ "approved_by_user_id": session.user_id })),
+    )
+    .await;
+    let client_ip = client.ip.to_string();
+    audit_repo::append(
+        &state.db,
+        now,
+        audit_repo::ACTION_RESTORE_APPROVED,
+        Some(session.user_id),
+        Some(&client_ip),
+        Some(&op_id),
+    )
+    .await?;
+    tracing::info!(op_id = %op_id, user_id = session.user_id, "restore approved");
+
+    // The run or job may have changed while the request waited.
+    let prepared = match prepare_restore(&state, &pending.run_id, &restore_req).await {
+        Ok(prepared) => prepared,
+        Err(error) => {
+            let _ = operations_repo::complete_operation(
+                &state.db,
+                &op_id,
+                operations_repo::OperationStatus::Failed,
+                None,
+                Some("restore request is no longer valid"),
+            )
+            .await;
+            return Err(error);
+        }
+    };
+    restore_approvals_repo::remember_destination(
+        &state.db,
+        &pending.job_id,
+        &pending.destination_key,
+        now,
+    )
+    .await?;
+    dispatch_restore(
+        &state,
+        &op_id,
+        &pending.run_id,
+        restore_req.selection,
+        req.passphrase,
+        prepared,
+    )
+    .await?;
+
+    let op = operations_repo::get_operation(&state.db, &op_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("operation_not_found", "Operation not found"))?;
+    Ok(Json(op.into()))
+}
+
+pub(super) async fn start_postgres_pitr(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(job_id): Path<String>,
+    Json(req): Json<StartPostgresPitrRequest>,
+) -> Result<Json<StartOperationResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_job_permission(&state, &session, &job_id, JobPermission::Trigger).await?;
+
+    let job = jobs_repo::get_job(&state.db, &job_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("job_not_found", "Job not found"))?;
+    if !matches!(
+        job_spec::parse_value(&job.spec),
+        Ok(job_spec::JobSpecV1::Postgres { .. })
+    ) {
         return Err(AppError::bad_request(
-            "invalid_run",
-            "Run is not successful",
+            "invalid_job_type",
+            "point-in-time restore is only available for postgres jobs",
         ));
     }
+    let target_time = time::OffsetDateTime::from_unix_timestamp(req.target_time).map_err(|_| {
+        AppError::bad_request("invalid_target_time", "target_time is out of range")
+            .with_field("target_time")
+    })?;
+    let directory = req.directory.trim();
+    if directory.is_empty() {
+        return Err(invalid_destination_error(
+            "required_directory",
+            "directory",
+            "directory is required",
+        ));
+    }
+
+    let op = operations_repo::create_operation(
+        &state.db,
+        operations_repo::OperationKind::Restore,
+        Some(("job", job_id.as_str())),
+    )
+    .await?;
+    let _ = operations_repo::append_event(
+        &state.db,
+        &op.id,
+        "info",
+        "requested",
+        "requested",
+        Some(serde_json::json!({
+            "job_id": job_id.clone(),
+            "target_time": req.target_time,
+            "directory": directory,
+        })),
+    )
+    .await;
+
+    restore::spawn_postgres_pitr_operation(
+        state.db.clone(),
+        state.secrets.clone(),
+        state.config.data_dir.clone(),
+        op.id.clone(),
+        job_id.clone(),
+        target_time,
+        std::path::PathBuf::from(directory),
+        req.passphrase,
+        global_cancel_registry().register_operation(&op.id),
+        Some(Box::new({
+            let op_id = op.id.clone();
+            move || {
+                global_cancel_registry().unregister_operation(&op_id);
+            }
+        })),
+    )
+    .await;
+
+    tracing::info!(
+        op_id = %op.id,
+        job_id = %job_id,
+        target_time = req.target_time,
+        "postgres point-in-time restore requested"
+    );
+    Ok(Json(StartOperationResponse {
+        op_id: op.id,
+        status: operations_repo::OperationStatus::Running,
+    }))
+}
+
+pub(super) async fn start_verify(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(run_id): Path<String>,
+    req: Option<Json<StartVerifyRequest>>,
+) -> Result<Json<StartOperationResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_run_permission(&state, &session, &run_id, JobPermission::Trigger).await?;
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let mode = parse_verify_mode(&req)?;
+    require_success_run(&state, &run_id).await?;
 
     let op = operations_repo::create_operation(
         &state.db,
@@ -537,7 +1043,7 @@ pub(super) async fn start_verify(
         "info",
         "requested",
         "requested",
-        Some(serde_json::json!({ "run_id": run_id.clone() })),
+        Some(serde_json::json!({ "run_id": run_id.clone(), "mode": mode.as_str() })),
     )
     .await;
 
@@ -547,6 +1053,8 @@ pub(super) async fn start_verify(
         state.config.data_dir.clone(),
         op.id.clone(),
         run_id.clone(),
+        mode,
+        req.passphrase,
         global_cancel_registry().register_operation(&op.id),
         Some(Box::new({
             let op_id = op.id.clone();
@@ -557,8 +1065,11 @@ pub(super) async fn start_verify(
     )
     .await;
 
-    tracing::info!(op_id = %op.id, run_id = %run_id, "verify requested");
-    Ok(Json(StartOperationResponse { op_id: op.id }))
+    tracing::info!(op_id = %op.id, run_id = %run_id, mode = mode.as_str(), "verify requested");
+    Ok(Json(StartOperationResponse {
+        op_id: op.id,
+        status: operations_repo::OperationStatus::Running,
+    }))
 }
 
 #[derive(Debug, Serialize)]
@@ -605,20 +1116,35 @@ pub(super) async fn get_operation(
     cookies: Cookies,
     Path(op_id): Path<String>,
 ) -> Result<Json<OperationResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_operation_permission(&state, &session, &op_id, JobPermission::View).await?;
     let op = operations_repo::get_operation(&state.db, &op_id)
         .await?
         .ok_or_else(|| AppError::not_found("operation_not_found", "Operation not found"))?;
     Ok(Json(op.into()))
 }
 
+#[derive(Debug, Deserialize)]
+pub(super) struct ListOperationEventsQuery {
+    #[serde(default)]
+    after_seq: Option<i64>,
+}
+
 pub(super) async fn list_operation_events(
     state: axum::extract::State<AppState>,
     cookies: Cookies,
     Path(op_id): Path<String>,
+    Query(query): Query<ListOperationEventsQuery>,
 ) -> Result<Json<Vec<operations_repo::OperationEvent>>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
-    let events = operations_repo::list_events(&state.db, &op_id, 500).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_operation_permission(&state, &session, &op_id, JobPermission::View).await?;
+    let events = operations_repo::list_events_after(
+        &state.db,
+        &op_id,
+        query.after_seq.unwrap_or(0).max(0),
+        500,
+    )
+    .await?;
     Ok(Json(events))
 }
 
@@ -627,7 +1153,8 @@ pub(super) async fn list_run_operations(
     cookies: Cookies,
     Path(run_id): Path<String>,
 ) -> Result<Json<Vec<OperationResponse>>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_run_permission(&state, &session, &run_id, JobPermission::View).await?;
 
     let run = runs_repo::get_run(&state.db, &run_id)
         .await?
@@ -653,11 +1180,40 @@ pub(super) async fn cancel_operation(
 ) -> Result<Json<OperationResponse>, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_operation_permission(&state, &session, &op_id, JobPermission::Trigger).await?;
 
     let before = operations_repo::get_operation(&state.db, &op_id)
         .await?
         .ok_or_else(|| AppError::not_found("operation_not_found", "Operation not found"))?;
 
+    // Nothing runs before approval, so a pending restore ends right away.
+    if before.status == operations_repo::OperationStatus::PendingApproval {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        if restore_approvals_repo::cancel_pending(
+            &state.db,
+            &op_id,
+            session.user_id,
+            req.reason.as_deref(),
+            now,
+        )
+        .await?
+        {
+            let _ = operations_repo::append_event(
+                &state.db,
+                &op_id,
+                "info",
+                "canceled",
+                "canceled before approval",
+                None,
+            )
+            .await;
+        }
+        let op = operations_repo::get_operation(&state.db, &op_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("operation_not_found", "Operation not found"))?;
+        return Ok(Json(op.into()));
+    }
+
     let op = operations_repo::request_operation_cancel(
         &state.db,
         &op_id,
@@ -27,6 +27,11 @@ fn test_config(temp: &TempDir) -> Arc<Config> {
         hub_timezone: "UTC".to_string(),
         run_retention_days: 180,
         incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
         trusted_proxies: vec![
             "127.0.0.1/32".parse().expect("proxy"),
             "::1/128".parse().expect("proxy"),
@@ -384,3 +389,158 @@ async fn cancel_operation_dispatches_agent_cancel_message_when_task_exists() {
 
     server.abort();
 }
+
+#[tokio::test]
+async fn postgres_pitr_is_rejected_for_other_job_types() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "job1",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create job");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .post(format!(
+            "{}/api/jobs/{}/postgres/pitr",
+            base_url(addr),
+            job.id
+        ))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({ "target_time": 1000, "directory": "/tmp/restore" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("invalid_job_type"));
+
+    server.abort();
+}
+
+#[test]
+fn parse_verify_mode_accepts_sample_with_bounded_percent() {
+    use super::operations::{StartVerifyRequest, parse_verify_mode};
+    use bastion_backup::restore::VerifyMode;
+
+    let req = |mode: Option<&str>, sample_percent: Option<u8>| {
+        serde_json::from_value::<StartVerifyRequest>(serde_json::json!({
+            "mode": mode,
+            "sample_percent": sample_percent,
+        }))
+        .unwrap()
+    };
+
+    assert_eq!(
+        parse_verify_mode(&req(None, None)).ok(),
+        Some(VerifyMode::Full)
+    );
+    assert_eq!(
+        parse_verify_mode(&req(Some("sample"), None)).ok(),
+        Some(VerifyMode::Sample {
+            percent: VerifyMode::DEFAULT_SAMPLE_PERCENT
+        })
+    );
+    assert_eq!(
+        parse_verify_mode(&req(Some("sample"), Some(25))).ok(),
+        Some(VerifyMode::Sample { percent: 25 })
+    );
+    assert_eq!(
+        parse_verify_mode(&req(Some("stream"), None)).ok(),
+        Some(VerifyMode::Stream)
+    );
+    assert!(parse_verify_mode(&req(Some("sample"), Some(0))).is_err());
+    assert!(parse_verify_mode(&req(Some("sample"), Some(101))).is_err());
+    assert!(parse_verify_mode(&req(Some("quick"), None)).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn restore_preview_space_is_checked_only_for_hub_directories() {
+    let temp = TempDir::new().expect("tempdir");
+    let hub_dir: super::operations::RestoreDestination =
+        serde_json::from_value(serde_json::json!({
+            "type": "local_fs",
+            "node_id": bastion_core::HUB_NODE_ID,
+            "directory": temp.path().join("restore").to_string_lossy(),
+        }))
+        .expect("hub destination");
+
+    let space = super::operations::destination_space(&hub_dir, 1);
+    assert_eq!(space.status, "checked");
+    assert!(space.available_bytes.is_some());
+    assert_eq!(space.sufficient, Some(true));
+    let space = super::operations::destination_space(&hub_dir, u64::MAX);
+    assert_eq!(space.sufficient, Some(false));
+
+    let agent_dir: super::operations::RestoreDestination =
+        serde_json::from_value(serde_json::json!({
+            "type": "local_fs",
+            "node_id": "agent1",
+            "directory": "/restore",
+        }))
+        .expect("agent destination");
+    let space = super::operations::destination_space(&agent_dir, 1);
+    assert_eq!(space.status, "remote_node");
+    assert!(space.sufficient.is_none());
+
+    let webdav: super::operations::RestoreDestination = serde_json::from_value(serde_json::json!({
+        "type": "webdav",
+        "base_url": "https://dav.example.com/",
+        "secret_name": "dav",
+        "prefix": "restore",
+    }))
+    .expect("webdav destination");
+    assert_eq!(
+        super::operations::destination_space(&webdav, 1).status,
+        "webdav"
+    );
+}
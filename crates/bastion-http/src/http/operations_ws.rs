@@ -0,0 +1,164 @@
+use axum::Extension;
+use axum::extract::Path;
+use axum::extract::Query;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::http::HeaderMap;
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tower_cookies::Cookies;
+
+use bastion_storage::job_access_repo::JobPermission;
+use bastion_storage::operations_repo;
+
+use super::shared::{
+    ClientConnInfo, require_operation_permission, require_session, require_ws_same_origin,
+};
+use super::ws_liveness::WsLiveness;
+use super::{AppError, AppState};
+
+// Operation events are written from the backup crate (restore/verify workers) straight to SQLite,
+// so the socket tails the table instead of subscribing to an in-process bus.
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+#[derive(Debug, Deserialize)]
+pub(super) struct OperationEventsWsQuery {
+    #[serde(default, alias = "after_seq")]
+    after: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OperationWsMessage<'a> {
+    Event {
+        event: &'a operations_repo::OperationEvent,
+    },
+    /// Sent on connect and whenever status or progress changes.
+    Snapshot {
+        status: &'a str,
+        progress: &'a Option<serde_json::Value>,
+        summary: &'a Option<serde_json::Value>,
+        error: &'a Option<String>,
+    },
+}
+
+pub(super) async fn operation_events_ws(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Query(query): Query<OperationEventsWsQuery>,
+    Path(op_id): Path<String>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_ws_same_origin(&client, &headers)?;
+    require_operation_permission(&state, &session, &op_id, JobPermission::View).await?;
+
+    let op_exists = operations_repo::get_operation(&state.db, &op_id)
+        .await?
+        .is_some();
+    if !op_exists {
+        return Err(AppError::not_found(
+            "operation_not_found",
+            "Operation not found",
+        ));
+    }
+
+    let after_seq = query.after.unwrap_or(0).max(0);
+    let db = state.db.clone();
+    Ok(ws.on_upgrade(move |socket| handle_operation_events_socket(db, op_id, after_seq, socket)))
+}
+
+async fn handle_operation_events_socket(
+    db: SqlitePool,
+    op_id: String,
+    after_seq: i64,
+    mut socket: WebSocket,
+) {
+    let mut last_seq = after_seq.max(0);
+    let mut last_snapshot: Option<String> = None;
+
+    // The first tick fires immediately and doubles as the catch-up pass.
+    let mut poll_interval = tokio::time::interval(POLL_INTERVAL);
+    poll_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let mut liveness = WsLiveness::new();
+
+    loop {
+        tokio::select! {
+            msg = socket.recv() => {
+                match msg {
+                    Some(Ok(Message::Close(_))) | None => return,
+                    Some(Ok(_)) => liveness.seen(),
+                    Some(Err(_)) => return,
+                }
+            }
+            alive = liveness.tick() => {
+                if !alive || socket.send(Message::Ping(Default::default())).await.is_err() {
+                    return;
+                }
+            }
+            _ = poll_interval.tick() => {
+                // Load the operation before its events: a terminal status then guarantees every
+                // event written before completion is included in this pass.
+                let op = match operations_repo::get_operation(&db, &op_id).await {
+                    Ok(Some(op)) => op,
+                    Ok(None) | Err(_) => return,
+                };
+
+                if !send_events_after(&db, &op_id, &mut last_seq, &mut socket).await {
+                    return;
+                }
+
+                let snapshot = OperationWsMessage::Snapshot {
+                    status: op.status.as_str(),
+                    progress: &op.progress,
+                    summary: &op.summary,
+                    error: &op.error,
+                };
+                let Ok(payload) = serde_json::to_string(&snapshot) else {
+                    return;
+                };
+                if last_snapshot.as_deref() != Some(payload.as_str()) {
+                    if socket.send(Message::Text(payload.clone().into())).await.is_err() {
+                        return;
+                    }
+                    last_snapshot = Some(payload);
+                }
+
+                if op.status.is_terminal() {
+                    let _ = socket.send(Message::Close(None)).await;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Sends every event after `last_seq`; returns `false` once the socket or database fails.
+async fn send_events_after(
+    db: &SqlitePool,
+    op_id: &str,
+    last_seq: &mut i64,
+    socket: &mut WebSocket,
+) -> bool {
+    loop {
+        let events = match operations_repo::list_events_after(db, op_id, *last_seq, 200).await {
+            Ok(v) => v,
+            Err(_) => return false,
+        };
+        if events.is_empty() {
+            return true;
+        }
+        for event in &events {
+            *last_seq = (*last_seq).max(event.seq);
+            let payload = match serde_json::to_string(&OperationWsMessage::Event { event }) {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+            if socket.send(Message::Text(payload.into())).await.is_err() {
+                return false;
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! In-memory token buckets for `/api` requests, keyed by client IP and by session.
+//!
+//! Each bucket holds a minute's worth of requests and refills continuously, so short bursts are
+//! fine while a client polling in a tight loop is slowed down to the configured rate. A bucket
+//! that has refilled completely is no different from a new one, so idle buckets are dropped.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use bastion_config::Config;
+
+/// How often buckets that have refilled completely are dropped.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// The per-session limit falls back to the client IP for requests without a valid session, so
+/// made-up cookies can't each get a fresh bucket.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SessionKey {
+    Session(String),
+    Ip(IpAddr),
+}
+
+#[derive(Debug)]
+struct Entries<K> {
+    buckets: HashMap<K, Bucket>,
+    pruned_at: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct Buckets<K> {
+    capacity: f64,
+    refill_per_sec: f64,
+    entries: Mutex<Entries<K>>,
+}
+
+impl<K: Eq + Hash> Buckets<K> {
+    fn per_minute(limit: u32) -> Option<Self> {
+        (limit > 0).then(|| Self {
+            capacity: f64::from(limit),
+            refill_per_sec: f64::from(limit) / 60.0,
+            entries: Mutex::new(Entries {
+                buckets: HashMap::new(),
+                pruned_at: None,
+            }),
+        })
+    }
+
+    /// Takes one token for `key`, or returns how long to wait until one is available.
+    fn take(&self, key: K, now: Instant) -> Result<(), Duration> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries
+            .pruned_at
+            .is_none_or(|at| now.saturating_duration_since(at) >= PRUNE_INTERVAL)
+        {
+            entries
+                .buckets
+                .retain(|_, bucket| self.refilled(bucket, now) < self.capacity);
+            entries.pruned_at = Some(now);
+        }
+
+        let bucket = entries.buckets.entry(key).or_insert(Bucket {
+            tokens: self.capacity,
+            updated_at: now,
+        });
+        bucket.tokens = self.refilled(bucket, now);
+        bucket.updated_at = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        Err(Duration::from_secs_f64(
+            (1.0 - bucket.tokens) * 60.0 / self.capacity,
+        ))
+    }
+
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now
+            .saturating_duration_since(bucket.updated_at)
+            .as_secs_f64();
+        (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity)
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct ApiRateLimiter {
+    per_ip: Option<Buckets<IpAddr>>,
+    per_session: Option<Buckets<SessionKey>>,
+}
+
+impl ApiRateLimiter {
+    pub(super) fn new(per_ip_per_minute: u32, per_session_per_minute: u32) -> Self {
+        Self {
+            per_ip: Buckets::per_minute(per_ip_per_minute),
+            per_session: Buckets::per_minute(per_session_per_minute),
+        }
+    }
+
+    pub(super) fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.api_rate_limit_per_ip,
+            config.api_rate_limit_per_session,
+        )
+    }
+
+    /// Whether a per-session limit is configured, i.e. whether callers need to look up the session.
+    pub(super) fn limits_sessions(&self) -> bool {
+        self.per_session.is_some()
+    }
+
+    /// Charges one request to the client IP. Returns the number of seconds to wait when the limit
+    /// is exhausted.
+    pub(super) fn check_ip(&self, ip: IpAddr, now: Instant) -> Result<(), u64> {
+        take(self.per_ip.as_ref(), ip, now)
+    }
+
+    /// Charges one request to the session, or to the client IP under the per-session limit when
+    /// `session_id` is `None`. Callers pass only validated session ids. Returns the number of
+    /// seconds to wait when the limit is exhausted.
+    pub(super) fn check_session(
+        &self,
+        ip: IpAddr,
+        session_id: Option<&str>,
+        now: Instant,
+    ) -> Result<(), u64> {
+        let session_key = match session_id {
+            Some(session_id) => SessionKey::Session(session_id.to_string()),
+            None => SessionKey::Ip(ip),
+        };
+        take(self.per_session.as_ref(), session_key, now)
+    }
+}
+
+fn take<K: Eq + Hash>(buckets: Option<&Buckets<K>>, key: K, now: Instant) -> Result<(), u64> {
+    match buckets.map(|buckets| buckets.take(key, now)) {
+        Some(Err(wait)) => Err(wait.as_secs_f64().ceil().max(1.0) as u64),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::{Duration, Instant};
+
+    use super::ApiRateLimiter;
+
+    const IP_A: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    const IP_B: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+    #[test]
+    fn ip_bucket_allows_a_burst_then_refills() {
+        let limiter = ApiRateLimiter::new(60, 0);
+        let start = Instant::now();
+        for _ in 0..60 {
+            assert!(limiter.check_ip(IP_A, start).is_ok());
+        }
+        assert_eq!(limiter.check_ip(IP_A, start), Err(1));
+        assert!(limiter.check_ip(IP_B, start).is_ok());
+
+        let later = start + Duration::from_secs(2);
+        assert!(limiter.check_ip(IP_A, later).is_ok());
+        assert!(limiter.check_ip(IP_A, later).is_ok());
+        assert!(limiter.check_ip(IP_A, later).is_err());
+    }
+
+    #[test]
+    fn session_bucket_is_shared_across_ips() {
+        let limiter = ApiRateLimiter::new(0, 2);
+        let now = Instant::now();
+        assert!(limiter.check_session(IP_A, Some("s1"), now).is_ok());
+        assert!(limiter.check_session(IP_B, Some("s1"), now).is_ok());
+        assert_eq!(limiter.check_session(IP_A, Some("s1"), now), Err(30));
+        assert!(limiter.check_session(IP_A, Some("s2"), now).is_ok());
+        assert!(limiter.check_session(IP_A, None, now).is_ok());
+        assert!(limiter.check_session(IP_A, None, now).is_ok());
+        assert!(limiter.check_session(IP_A, None, now).is_err());
+        assert!(limiter.check_session(IP_B, None, now).is_ok());
+    }
+
+    #[test]
+    fn idle_buckets_are_dropped() {
+        let limiter = ApiRateLimiter::new(60, 60);
+        let start = Instant::now();
+        for i in 0..100u8 {
+            let ip = IpAddr::V4(Ipv4Addr::new(10, 1, 0, i));
+            assert!(limiter.check_ip(ip, start).is_ok());
+            assert!(
+                limiter
+                    .check_session(ip, Some(&format!("s{i}")), start)
+                    .is_ok()
+            );
+        }
+        let tracked = |limiter: &ApiRateLimiter| {
+            let per_ip = limiter.per_ip.as_ref().expect("per ip");
+            let per_session = limiter.per_session.as_ref().expect("per session");
+            (
+                per_ip.entries.lock().expect("lock").buckets.len(),
+                per_session.entries.lock().expect("lock").buckets.len(),
+            )
+        };
+        assert_eq!(tracked(&limiter), (100, 100));
+
+        // Buckets drained shortly before the sweep haven't refilled yet and are kept.
+        for _ in 0..5 {
+            let at = start + Duration::from_secs(59);
+            assert!(limiter.check_ip(IP_A, at).is_ok());
+            assert!(limiter.check_session(IP_A, Some("s1"), at).is_ok());
+        }
+        let at = start + Duration::from_secs(61);
+        assert!(limiter.check_ip(IP_B, at).is_ok());
+        assert!(limiter.check_session(IP_B, None, at).is_ok());
+        assert_eq!(tracked(&limiter), (2, 2));
+    }
+
+    #[test]
+    fn zero_disables_both_limits() {
+        let limiter = ApiRateLimiter::new(0, 0);
+        let now = Instant::now();
+        for _ in 0..1000 {
+            assert!(limiter.check_ip(IP_A, now).is_ok());
+            assert!(limiter.check_session(IP_A, Some("s1"), now).is_ok());
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db};
+
+fn test_config(temp: &TempDir, per_ip: u32, per_session: u32) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: per_ip,
+        api_rate_limit_per_session: per_session,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+async fn start_server(
+    temp: &TempDir,
+    pool: sqlx::SqlitePool,
+    per_ip: u32,
+    per_session: u32,
+) -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
+    let config = test_config(temp, per_ip, per_session);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool,
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    (addr, server)
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn api_requests_over_the_ip_limit_get_429_with_retry_after() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    let (addr, server) = start_server(&temp, pool, 3, 0).await;
+    let client = reqwest::Client::new();
+
+    for _ in 0..3 {
+        let resp = client
+            .get(format!("{}/api/setup/status", base_url(addr)))
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+    let resp = client
+        .get(format!("{}/api/setup/status", base_url(addr)))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after = resp
+        .headers()
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .expect("retry-after");
+    assert!((1..=20).contains(&retry_after));
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("rate_limited"));
+    assert_eq!(body["details"]["reason"].as_str(), Some("api_rate_limit"));
+    assert_eq!(
+        body["details"]["params"]["retry_after_seconds"].as_u64(),
+        Some(retry_after)
+    );
+
+    // Health probes are never limited.
+    let resp = client
+        .get(format!("{}/api/health", base_url(addr)))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn each_session_has_its_own_budget() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    auth::create_user(&pool, "admin", "pw")
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let first = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+    let second = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+    let (addr, server) = start_server(&temp, pool, 0, 2).await;
+    let client = reqwest::Client::new();
+    let session_info = |session: &auth::SessionRow| {
+        client
+            .get(format!("{}/api/session", base_url(addr)))
+            .header("cookie", format!("bastion_session={}", session.id))
+            .send()
+    };
+
+    for _ in 0..2 {
+        let resp = session_info(&first).await.expect("request");
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+    let resp = session_info(&first).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    let resp = session_info(&second).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn made_up_session_cookies_share_the_ip_budget() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    let (addr, server) = start_server(&temp, pool, 0, 2).await;
+    let client = reqwest::Client::new();
+    let session_info = || {
+        client
+            .get(format!("{}/api/session", base_url(addr)))
+            .header(
+                "cookie",
+                format!("bastion_session={}", uuid::Uuid::new_v4()),
+            )
+            .send()
+    };
+
+    for _ in 0..2 {
+        let resp = session_info().await.expect("request");
+        assert_ne!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+    let resp = session_info().await.expect("request");
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn api_token_guesses_are_limited_per_ip() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    let (addr, server) = start_server(&temp, pool, 2, 0).await;
+    let client = reqwest::Client::new();
+    let guess = || {
+        client
+            .get(format!("{}/api/session", base_url(addr)))
+            .bearer_auth(format!(
+                "{}{}",
+                auth::API_TOKEN_PREFIX,
+                uuid::Uuid::new_v4()
+            ))
+            .send()
+    };
+
+    for _ in 0..2 {
+        let resp = guess().await.expect("request");
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+    let resp = guess().await.expect("request");
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    server.abort();
+}
@@ -0,0 +1,289 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db, hub_runtime_config_repo, job_access_repo, jobs_repo, runs_repo};
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+async fn start_server(
+    temp: &TempDir,
+    pool: sqlx::SqlitePool,
+) -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
+    let config = test_config(temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool,
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    (addr, server)
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn restores_to_new_destinations_wait_for_a_second_admin() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    auth::create_first_user(&pool, "admin", "pw")
+        .await
+        .expect("admin");
+    auth::create_user(&pool, "carol", "pw")
+        .await
+        .expect("carol");
+    auth::create_user(&pool, "bob", "pw").await.expect("bob");
+    let session = |name: &'static str| {
+        let pool = pool.clone();
+        async move {
+            let user = auth::find_user_by_username(&pool, name)
+                .await
+                .expect("find user")
+                .expect("user exists");
+            auth::create_session(&pool, user.id)
+                .await
+                .expect("create session")
+        }
+    };
+    let admin = session("admin").await;
+    let carol = session("carol").await;
+    let bob = session("bob").await;
+    assert!(
+        job_access_repo::set_admin(&pool, carol.user_id, true)
+            .await
+            .expect("set admin")
+    );
+    hub_runtime_config_repo::upsert(
+        &pool,
+        &hub_runtime_config_repo::HubRuntimeConfig {
+            restore_approval_required: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("runtime config");
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "job1",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "root": "/" },
+            "target": { "type": "local_dir", "base_dir": "/tmp" }
+        }),
+    )
+    .await
+    .expect("create job");
+    let run = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Success,
+        1,
+        Some(2),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+
+    let (addr, server) = start_server(&temp, pool.clone()).await;
+    let client = reqwest::Client::new();
+    let post = |session: &auth::SessionRow, path: String, body: serde_json::Value| {
+        client
+            .post(format!("{}{path}", base_url(addr)))
+            .header("cookie", format!("bastion_session={}", session.id))
+            .header("x-csrf-token", session.csrf_token.clone())
+            .json(&body)
+            .send()
+    };
+    let restore_body = serde_json::json!({
+        "destination": { "type": "local_fs", "node_id": "hub", "directory": temp.path().join("restore") },
+        "conflict_policy": "overwrite"
+    });
+
+    let resp = post(
+        &admin,
+        format!("/api/runs/{}/restore", run.id),
+        restore_body.clone(),
+    )
+    .await
+    .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["status"].as_str(), Some("pending_approval"));
+    let op_id = body["op_id"].as_str().expect("op_id").to_string();
+    let approve_path = format!("/api/operations/{op_id}/approve");
+
+    let resp = post(&admin, approve_path.clone(), serde_json::json!({}))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("second_admin_required"));
+
+    let resp = post(&bob, approve_path.clone(), serde_json::json!({}))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("admin_required"));
+
+    let resp = post(&carol, approve_path.clone(), serde_json::json!({}))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_ne!(body["status"].as_str(), Some("pending_approval"));
+
+    let resp = post(&carol, approve_path, serde_json::json!({}))
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("operation_not_pending"));
+
+    // The destination is now known, so the same restore starts right away.
+    let resp = post(
+        &admin,
+        format!("/api/runs/{}/restore", run.id),
+        restore_body,
+    )
+    .await
+    .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["status"].as_str(), Some("running"));
+
+    // A pending restore can be canceled instead.
+    let resp = post(
+        &admin,
+        format!("/api/runs/{}/restore", run.id),
+        serde_json::json!({
+            "destination": { "type": "local_fs", "node_id": "hub", "directory": temp.path().join("other") },
+            "conflict_policy": "overwrite"
+        }),
+    )
+    .await
+    .expect("request");
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["status"].as_str(), Some("pending_approval"));
+    let op_id = body["op_id"].as_str().expect("op_id").to_string();
+    let resp = post(
+        &admin,
+        format!("/api/operations/{op_id}/cancel"),
+        serde_json::json!({ "reason": "wrong folder" }),
+    )
+    .await
+    .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["status"].as_str(), Some("canceled"));
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn only_admins_can_change_restore_approval() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    auth::create_first_user(&pool, "admin", "pw")
+        .await
+        .expect("admin");
+    auth::create_user(&pool, "bob", "pw").await.expect("bob");
+    let bob = auth::find_user_by_username(&pool, "bob")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, bob.id)
+        .await
+        .expect("create session");
+    hub_runtime_config_repo::upsert(
+        &pool,
+        &hub_runtime_config_repo::HubRuntimeConfig {
+            restore_approval_required: true,
+            ..Default::default()
+        },
+    )
+    .await
+    .expect("runtime config");
+
+    let (addr, server) = start_server(&temp, pool.clone()).await;
+    let resp = reqwest::Client::new()
+        .put(format!(
+            "{}/api/settings/hub-runtime-config",
+            base_url(addr)
+        ))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .header("x-csrf-token", &session.csrf_token)
+        .json(&serde_json::json!({ "restore_approval_required": false }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "admin_required");
+    assert!(
+        hub_runtime_config_repo::get(&pool)
+            .await
+            .expect("get")
+            .expect("saved")
+            .restore_approval_required
+    );
+
+    server.abort();
+}
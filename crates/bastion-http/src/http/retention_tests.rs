@@ -30,6 +30,11 @@ fn test_config(temp: &TempDir) -> Arc<Config> {
         hub_timezone: "UTC".to_string(),
         run_retention_days: 180,
         incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
         trusted_proxies: vec![
             "127.0.0.1/32".parse().expect("proxy"),
             "::1/128".parse().expect("proxy"),
@@ -119,6 +124,7 @@ async fn create_job_inherits_default_retention_when_missing() {
                 keep_days: Some(30),
                 max_delete_per_tick: 20,
                 max_delete_per_day: 100,
+                lock_days: None,
             },
             ..Default::default()
         },
@@ -12,9 +12,12 @@ use bastion_core::agent_protocol::{HubToAgentMessageV1, PROTOCOL_VERSION};
 use bastion_engine::cancel_registry::global_cancel_registry;
 use bastion_engine::run_events;
 use bastion_storage::agent_tasks_repo;
+use bastion_storage::artifact_delete_repo;
+use bastion_storage::job_access_repo::{self, JobPermission};
+use bastion_storage::run_artifacts_repo;
 use bastion_storage::runs_repo;
 
-use super::shared::{require_csrf, require_session};
+use super::shared::{job_list_viewer, require_csrf, require_run_permission, require_session};
 use super::{AppError, AppState};
 
 fn invalid_kind_error(message: impl Into<String>) -> AppError {
@@ -123,6 +126,56 @@ impl ResolvedRange {
             to: now_ts,
         })
     }
+
+    /// Explicit `from`/`to` bounds (unix seconds) override the preset.
+    fn resolve(
+        raw: Option<&str>,
+        from: Option<i64>,
+        to: Option<i64>,
+        now_ts: i64,
+    ) -> Result<Self, AppError> {
+        if from.is_none() && to.is_none() {
+            return Self::parse(raw, now_ts);
+        }
+
+        let from = from.unwrap_or(0);
+        let to = to.unwrap_or(now_ts);
+        if from > to {
+            return Err(
+                AppError::bad_request("invalid_range", "from must not be after to")
+                    .with_reason("invalid_value")
+                    .with_field("from"),
+            );
+        }
+        Ok(Self {
+            preset: "custom",
+            from,
+            to,
+        })
+    }
+}
+
+/// Resolves a run id cursor (the last item of the previous page) to its keyset position.
+pub(super) async fn resolve_run_cursor(
+    db: &sqlx::SqlitePool,
+    cursor: Option<&str>,
+    job_id: Option<&str>,
+) -> Result<Option<(i64, String)>, AppError> {
+    let Some(cursor) = cursor.map(str::trim).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    let run = runs_repo::get_run(db, cursor)
+        .await?
+        .filter(|run| job_id.is_none_or(|job_id| run.job_id == job_id))
+        .ok_or_else(|| {
+            AppError::bad_request(
+                "invalid_cursor",
+                "cursor does not match a run in this listing",
+            )
+            .with_reason("not_found")
+            .with_field("cursor")
+        })?;
+    Ok(Some((run.started_at, run.id)))
 }
 
 fn scope_string(agent_id: Option<&str>) -> String {
@@ -217,6 +270,8 @@ pub(super) struct RunsWorkspaceListResponse {
     page: i64,
     page_size: i64,
     total: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    next_cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -270,6 +325,10 @@ pub(super) struct RunWorkspaceCapabilities {
 pub(super) struct RunWorkspaceRelatedSummary {
     operations_total: i64,
     artifacts_total: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    artifact_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    artifact_deleted_at: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -332,6 +391,12 @@ pub(super) struct ListRunsWorkspaceQuery {
     #[serde(default)]
     q: Option<String>,
     #[serde(default)]
+    from: Option<i64>,
+    #[serde(default)]
+    to: Option<i64>,
+    #[serde(default)]
+    cursor: Option<String>,
+    #[serde(default)]
     page: Option<i64>,
     #[serde(default)]
     page_size: Option<i64>,
@@ -406,6 +471,7 @@ fn parse_csv_filter(raw: Option<&str>, field: &'static str) -> Result<Vec<String
     Ok(values)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn apply_runs_list_filters<'a>(
     qb: &mut QueryBuilder<'a, sqlx::Sqlite>,
     scope: &'a RequestedScope,
@@ -414,12 +480,19 @@ fn apply_runs_list_filters<'a>(
     job_id: Option<&'a str>,
     kind: Option<&'a str>,
     q: Option<&'a str>,
+    before: Option<&'a (i64, String)>,
+    viewer: Option<i64>,
 ) {
     qb.push(" WHERE r.started_at >= ");
     qb.push_bind(range.from);
     qb.push(" AND r.started_at <= ");
     qb.push_bind(range.to);
 
+    if let Some(user_id) = viewer {
+        qb.push(" AND ");
+        job_access_repo::push_visible_jobs_condition(qb, "j", user_id);
+    }
+
     match scope {
         RequestedScope::All => {}
         RequestedScope::Hub => {
@@ -453,6 +526,15 @@ fn apply_runs_list_filters<'a>(
         qb.push_bind(pattern);
         qb.push(")");
     }
+    if let Some((started_at, id)) = before {
+        qb.push(" AND (r.started_at < ");
+        qb.push_bind(*started_at);
+        qb.push(" OR (r.started_at = ");
+        qb.push_bind(*started_at);
+        qb.push(" AND r.id < ");
+        qb.push_bind(id.as_str());
+        qb.push("))");
+    }
 }
 
 fn apply_run_event_filters<'a>(
@@ -683,7 +765,8 @@ pub(super) async fn get_run(
     cookies: Cookies,
     Path(run_id): Path<String>,
 ) -> Result<Json<RunResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_run_permission(&state, &session, &run_id, JobPermission::View).await?;
 
     let run = get_run_response(&state.db, &run_id)
         .await?
@@ -697,11 +780,12 @@ pub(super) async fn list_runs_workspace(
     cookies: Cookies,
     Query(query): Query<ListRunsWorkspaceQuery>,
 ) -> Result<Json<RunsWorkspaceListResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    let viewer = job_list_viewer(&state, &session).await?;
 
     let requested_scope = RequestedScope::parse(query.scope.as_deref())?;
     let now = OffsetDateTime::now_utc().unix_timestamp();
-    let range = ResolvedRange::parse(query.range.as_deref(), now)?;
+    let range = ResolvedRange::resolve(query.range.as_deref(), query.from, query.to, now)?;
     let page = parse_page(query.page)?;
     let page_size = parse_page_size(query.page_size)?;
     let q = normalize_optional_string(query.q.as_deref());
@@ -715,6 +799,7 @@ pub(super) async fn list_runs_workspace(
                 .with_field("status")
         })?;
     }
+    let before = resolve_run_cursor(&state.db, query.cursor.as_deref(), None).await?;
 
     let mut count_qb: QueryBuilder<'_, sqlx::Sqlite> = QueryBuilder::new(
         r#"
@@ -731,6 +816,8 @@ pub(super) async fn list_runs_workspace(
         job_id.as_deref(),
         kind.as_deref(),
         q.as_deref(),
+        None,
+        viewer,
     );
     let total = count_qb
         .build_query_scalar::<i64>()
@@ -764,11 +851,16 @@ pub(super) async fn list_runs_workspace(
         job_id.as_deref(),
         kind.as_deref(),
         q.as_deref(),
+        before.as_ref(),
+        viewer,
     );
     qb.push(" ORDER BY r.started_at DESC, r.id DESC LIMIT ");
     qb.push_bind(page_size);
-    qb.push(" OFFSET ");
-    qb.push_bind(offset);
+    // With a cursor the keyset clause positions the page; `page` only applies without one.
+    if before.is_none() {
+        qb.push(" OFFSET ");
+        qb.push_bind(offset);
+    }
 
     let rows = qb.build().fetch_all(&state.db).await?;
 
@@ -801,6 +893,10 @@ pub(super) async fn list_runs_workspace(
         });
     }
 
+    let next_cursor = (items.len() as i64 == page_size)
+        .then(|| items.last().map(|item| item.id.clone()))
+        .flatten();
+
     Ok(Json(RunsWorkspaceListResponse {
         scope: RunsWorkspaceScopeEcho {
             requested: requested_scope.as_str(),
@@ -817,6 +913,7 @@ pub(super) async fn list_runs_workspace(
         page,
         page_size,
         total,
+        next_cursor,
     }))
 }
 
@@ -825,7 +922,8 @@ pub(super) async fn get_run_workspace(
     cookies: Cookies,
     Path(run_id): Path<String>,
 ) -> Result<Json<RunWorkspaceResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_run_permission(&state, &session, &run_id, JobPermission::View).await?;
 
     let run = get_run_response(&state.db, &run_id)
         .await?
@@ -847,10 +945,16 @@ pub(super) async fn get_run_workspace(
             .fetch_one(&state.db)
             .await?;
 
+    // The run record can outlive its snapshot data (artifact retention); nothing to restore then.
+    let artifact = run_artifacts_repo::get_run_artifact(&state.db, &run_id).await?;
+    let artifact_gone = artifact
+        .as_ref()
+        .is_some_and(|a| a.status == "deleted" || a.status == "missing");
+
     let capabilities = RunWorkspaceCapabilities {
         can_cancel: run.status.is_cancelable() && run.cancel_requested_at.is_none(),
-        can_restore: run.status == runs_repo::RunStatus::Success,
-        can_verify: run.status == runs_repo::RunStatus::Success,
+        can_restore: run.status == runs_repo::RunStatus::Success && !artifact_gone,
+        can_verify: run.status == runs_repo::RunStatus::Success && !artifact_gone,
     };
     let kind = derive_run_kind(run.progress.as_ref());
 
@@ -878,6 +982,8 @@ pub(super) async fn get_run_workspace(
         related: RunWorkspaceRelatedSummary {
             operations_total,
             artifacts_total,
+            artifact_status: artifact.as_ref().map(|a| a.status.clone()),
+            artifact_deleted_at: artifact.and_then(|a| a.deleted_at),
         },
     }))
 }
@@ -888,7 +994,8 @@ pub(super) async fn list_run_event_console(
     Path(run_id): Path<String>,
     Query(query): Query<RunEventConsoleQuery>,
 ) -> Result<Json<RunEventConsoleResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_run_permission(&state, &session, &run_id, JobPermission::View).await?;
 
     if runs_repo::get_run(&state.db, &run_id).await?.is_none() {
         return Err(AppError::not_found("run_not_found", "Run not found"));
@@ -1044,6 +1151,7 @@ pub(super) async fn cancel_run(
 ) -> Result<Json<RunResponse>, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
+    require_run_permission(&state, &session, &run_id, JobPermission::Trigger).await?;
 
     let before = runs_repo::get_run(&state.db, &run_id)
         .await?
@@ -1102,6 +1210,84 @@ pub(super) async fn cancel_run(
     Ok(Json(response))
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub(super) struct PurgeRunQuery {
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct PurgeRunResponse {
+    run_id: String,
+    /// `purged` when the run is already gone; `pending` while snapshot deletion is in progress.
+    status: &'static str,
+}
+
+pub(super) async fn purge_run(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(run_id): Path<String>,
+    Query(query): Query<PurgeRunQuery>,
+) -> Result<Json<PurgeRunResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_run_permission(&state, &session, &run_id, JobPermission::Edit).await?;
+
+    let run = runs_repo::get_run(&state.db, &run_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("run_not_found", "Run not found"))?;
+    if matches!(
+        run.status,
+        runs_repo::RunStatus::Queued | runs_repo::RunStatus::Running
+    ) {
+        return Err(AppError::conflict(
+            "run_active",
+            "Run is still active; cancel it before purging",
+        ));
+    }
+
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    if let Some(artifact) = run_artifacts_repo::get_run_artifact(&state.db, &run_id).await? {
+        super::jobs::enqueue_snapshot_delete(
+            &state,
+            session.user_id,
+            &artifact.job_id,
+            &run_id,
+            query.force,
+            now,
+        )
+        .await?;
+        // A delete that already gave up would otherwise keep the run forever.
+        if artifact.status == "error"
+            && artifact_delete_repo::retry_now(&state.db, &run_id, now).await?
+        {
+            let _ = artifact_delete_repo::append_event(
+                &state.db,
+                &run_id,
+                "info",
+                "retry_now",
+                "retry requested by run purge",
+                Some(serde_json::json!({ "user_id": session.user_id })),
+                now,
+            )
+            .await;
+            state.artifact_delete_notify.notify_one();
+        }
+    }
+
+    runs_repo::request_run_purge(&state.db, &run_id, session.user_id, now).await?;
+    let purged = runs_repo::purge_requested_runs(&state.db).await?;
+    let status = if purged.iter().any(|id| id == &run_id) {
+        "purged"
+    } else {
+        "pending"
+    };
+    tracing::info!(run_id = %run_id, status, "run purge requested");
+
+    Ok(Json(PurgeRunResponse { run_id, status }))
+}
+
 #[derive(Debug, Deserialize)]
 pub(super) struct ListRunEntriesQuery {
     #[serde(default)]
@@ -1130,7 +1316,8 @@ pub(super) async fn list_run_entries(
     Path(run_id): Path<String>,
     Query(query): Query<ListRunEntriesQuery>,
 ) -> Result<Json<restore::RunEntriesChildrenResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
+    let session = require_session(&state, &cookies).await?;
+    require_run_permission(&state, &session, &run_id, JobPermission::View).await?;
 
     let ListRunEntriesQuery {
         prefix,
@@ -8,7 +8,7 @@ use bastion_config::Config;
 use bastion_core::agent_protocol::{HubToAgentMessageV1, PROTOCOL_VERSION};
 use bastion_engine::agent_manager::AgentManager;
 use bastion_storage::secrets::SecretsCrypto;
-use bastion_storage::{agent_tasks_repo, auth, db, jobs_repo, runs_repo};
+use bastion_storage::{agent_tasks_repo, auth, db, jobs_repo, run_logs_repo, runs_repo};
 
 async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
@@ -27,6 +27,11 @@ fn test_config(temp: &TempDir) -> Arc<Config> {
         hub_timezone: "UTC".to_string(),
         run_retention_days: 180,
         incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
         trusted_proxies: vec![
             "127.0.0.1/32".parse().expect("proxy"),
             "::1/128".parse().expect("proxy"),
@@ -550,3 +555,448 @@ async fn cancel_run_dispatches_agent_cancel_message_when_task_exists() {
 
     server.abort();
 }
+
+#[tokio::test]
+async fn purge_run_deletes_snapshot_then_run() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "job1",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create job");
+    let plain = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Failed,
+        1000,
+        Some(1001),
+        None,
+        Some("boom"),
+    )
+    .await
+    .expect("create plain run");
+    let with_snapshot = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Success,
+        1000,
+        Some(1001),
+        None,
+        None,
+    )
+    .await
+    .expect("create snapshot run");
+    let running = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Running,
+        1000,
+        None,
+        None,
+        None,
+    )
+    .await
+    .expect("create running run");
+
+    sqlx::query(
+        r#"
+        INSERT INTO run_artifacts (
+          run_id, job_id, node_id, target_type, target_snapshot_json,
+          artifact_format, status, started_at, ended_at, created_at, updated_at
+        ) VALUES (?, ?, 'hub', 'local_dir', ?, 'archive_v1', 'present', 1000, 1001, 1001, 1001)
+        "#,
+    )
+    .bind(&with_snapshot.id)
+    .bind(&job.id)
+    .bind(r#"{"node_id":"hub","target":{"type":"local_dir","base_dir":"/tmp"}}"#)
+    .execute(&pool)
+    .await
+    .expect("insert run_artifacts");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let purge = |run_id: String| {
+        client
+            .post(format!("{}/api/runs/{}/purge", base_url(addr), run_id))
+            .header("cookie", format!("bastion_session={}", session.id))
+            .header("x-csrf-token", session.csrf_token.clone())
+            .send()
+    };
+
+    let resp = purge(running.id.clone()).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let resp = purge(plain.id.clone()).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["status"].as_str(), Some("purged"));
+    assert!(
+        runs_repo::get_run(&pool, &plain.id)
+            .await
+            .expect("get run")
+            .is_none()
+    );
+
+    // Runs with a live snapshot stay until the artifact delete task finishes.
+    let resp = purge(with_snapshot.id.clone()).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["status"].as_str(), Some("pending"));
+    let task = bastion_storage::artifact_delete_repo::get_task(&pool, &with_snapshot.id)
+        .await
+        .expect("get task");
+    assert!(task.is_some());
+
+    // A delete that gave up blocks the purge until the run is purged again, which re-queues it.
+    bastion_storage::artifact_delete_repo::mark_abandoned(
+        &pool,
+        &with_snapshot.id,
+        "io",
+        "x",
+        1002,
+    )
+    .await
+    .expect("abandon task");
+    bastion_storage::run_artifacts_repo::mark_run_artifact_error(
+        &pool,
+        &with_snapshot.id,
+        "io",
+        "x",
+        1002,
+        1002,
+    )
+    .await
+    .expect("mark error");
+    assert_eq!(
+        runs_repo::list_blocked_run_purges(&pool)
+            .await
+            .expect("blocked"),
+        vec![with_snapshot.id.clone()]
+    );
+    let resp = purge(with_snapshot.id.clone()).await.expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["status"].as_str(), Some("pending"));
+    let task = bastion_storage::artifact_delete_repo::get_task(&pool, &with_snapshot.id)
+        .await
+        .expect("get task")
+        .expect("task exists");
+    assert_eq!(task.status, "queued");
+    assert!(
+        runs_repo::list_blocked_run_purges(&pool)
+            .await
+            .expect("blocked")
+            .is_empty()
+    );
+
+    sqlx::query("UPDATE run_artifacts SET status = 'deleted' WHERE run_id = ?")
+        .bind(&with_snapshot.id)
+        .execute(&pool)
+        .await
+        .expect("mark deleted");
+    let purged = runs_repo::purge_requested_runs(&pool).await.expect("purge");
+    assert_eq!(purged, vec![with_snapshot.id.clone()]);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn export_run_events_streams_every_event() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "job1",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create job");
+    let run = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Success,
+        1000,
+        Some(2000),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+
+    // More than one export page and more than the 500-event listing cap.
+    for i in 0..520 {
+        runs_repo::append_run_event(
+            &pool,
+            &run.id,
+            "info",
+            "step",
+            &format!("event {i}"),
+            (i == 0).then(|| serde_json::json!({"n": 1})),
+        )
+        .await
+        .expect("append event");
+    }
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .get(format!(
+            "{}/api/runs/{}/events/export",
+            base_url(addr),
+            run.id
+        ))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok()),
+        Some("application/x-ndjson")
+    );
+    let body = resp.text().await.expect("body");
+    let lines = body.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 520);
+    let first: serde_json::Value = serde_json::from_str(lines[0]).expect("json line");
+    assert_eq!(first["seq"].as_i64(), Some(1));
+    assert_eq!(first["fields"]["n"].as_i64(), Some(1));
+    let last: serde_json::Value = serde_json::from_str(lines[519]).expect("json line");
+    assert_eq!(last["message"].as_str(), Some("event 519"));
+
+    let resp = client
+        .get(format!(
+            "{}/api/runs/{}/events/export?format=txt",
+            base_url(addr),
+            run.id
+        ))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = resp.text().await.expect("body");
+    let first = body.lines().next().expect("first line");
+    assert!(
+        first.ends_with(" INFO  [step] event 0 {\"n\":1}"),
+        "{first}"
+    );
+
+    let resp = client
+        .get(format!(
+            "{}/api/runs/{}/events/export?format=csv",
+            base_url(addr),
+            run.id
+        ))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("invalid_format"));
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn download_run_log_returns_stored_chunks() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "job1",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create job");
+    let run = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Success,
+        1000,
+        Some(2000),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let url = format!("{}/api/runs/{}/log", base_url(addr), run.id);
+
+    let resp = client
+        .get(&url)
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"].as_str(), Some("run_log_not_found"));
+
+    for chunk in [&b"warning: a\n"[..], &b"[stderr] b\n"[..]] {
+        run_logs_repo::append_run_log_chunk(
+            &pool,
+            &run.id,
+            chunk,
+            run_logs_repo::MAX_RUN_LOG_BYTES,
+            2000,
+        )
+        .await
+        .expect("append log");
+    }
+
+    let resp = client
+        .get(&url)
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(
+        resp.headers()
+            .get("content-disposition")
+            .and_then(|v| v.to_str().ok()),
+        Some(format!("attachment; filename=\"run-{}.log\"", run.id).as_str())
+    );
+    assert_eq!(resp.text().await.expect("body"), "warning: a\n[stderr] b\n");
+
+    server.abort();
+}
@@ -7,7 +7,7 @@ use bastion_config::Config;
 use bastion_engine::agent_manager::AgentManager;
 use bastion_engine::run_events_bus::RunEventsBus;
 use bastion_storage::secrets::SecretsCrypto;
-use bastion_storage::{auth, db, jobs_repo, runs_repo};
+use bastion_storage::{auth, db, jobs_repo, run_artifacts_repo, runs_repo};
 
 async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
@@ -26,6 +26,11 @@ fn test_config(temp: &TempDir) -> Arc<Config> {
         hub_timezone: "UTC".to_string(),
         run_retention_days: 180,
         incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
         trusted_proxies: vec![
             "127.0.0.1/32".parse().expect("proxy"),
             "::1/128".parse().expect("proxy"),
@@ -304,6 +309,88 @@ async fn get_run_workspace_returns_structured_diagnostics() {
     server.abort();
 }
 
+#[tokio::test]
+async fn get_run_workspace_reports_deleted_snapshot_data() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let job = jobs_repo::create_job(
+        &pool,
+        "Nightly DB",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create job");
+    let run = runs_repo::create_run(
+        &pool,
+        &job.id,
+        runs_repo::RunStatus::Success,
+        1_760_000_000,
+        Some(1_760_000_100),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    sqlx::query(
+        r#"
+        INSERT INTO run_artifacts (
+          run_id, job_id, node_id, target_type, target_snapshot_json,
+          artifact_format, status, started_at, ended_at, created_at, updated_at
+        ) VALUES (?, ?, 'hub', 'local_dir', '{}', 'archive_v1', 'present', ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&run.id)
+    .bind(&job.id)
+    .bind(1_760_000_000_i64)
+    .bind(1_760_000_100_i64)
+    .bind(1_760_000_100_i64)
+    .bind(1_760_000_100_i64)
+    .execute(&pool)
+    .await
+    .expect("insert artifact");
+    run_artifacts_repo::mark_run_artifact_deleted(&pool, &run.id, 1_760_100_000)
+        .await
+        .expect("mark deleted");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+    let app = build_app(pool.clone(), config, secrets);
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let (client, session_id) = create_authed_session(&pool).await;
+    let resp = client
+        .get(format!("{}/api/runs/{}/workspace", base_url(addr), run.id))
+        .header("cookie", format!("bastion_session={session_id}"))
+        .send()
+        .await
+        .expect("request");
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["related"]["artifact_status"].as_str(), Some("deleted"));
+    assert_eq!(
+        body["related"]["artifact_deleted_at"].as_i64(),
+        Some(1_760_100_000)
+    );
+    assert_eq!(body["capabilities"]["can_restore"].as_bool(), Some(false));
+    assert_eq!(body["capabilities"]["can_verify"].as_bool(), Some(false));
+
+    server.abort();
+}
+
 #[tokio::test]
 async fn run_event_console_supports_filters_and_windows() {
     let temp = TempDir::new().expect("tempdir");
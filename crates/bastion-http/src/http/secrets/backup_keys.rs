@@ -0,0 +1,301 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+use bastion_backup::backup_encryption::{
+    BACKUP_AGE_IDENTITY_KIND, BACKUP_AGE_PASSPHRASE_KEY_KIND, create_age_passphrase_key,
+    get_age_identity, get_age_passphrase_recipient, rotate_age_identity,
+};
+use bastion_backup::restore;
+use bastion_core::HUB_NODE_ID;
+use bastion_core::job_spec;
+use bastion_engine::cancel_registry::global_cancel_registry;
+use bastion_storage::{jobs_repo, operations_repo, run_artifacts_repo, secrets_repo};
+
+use super::super::agents::send_node_config_snapshot;
+use super::super::shared::{require_csrf, require_session};
+use super::super::{AppError, AppState};
+use super::SecretListItem;
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+const RUN_ARTIFACTS_PAGE_SIZE: u64 = 200;
+
+pub(in crate::http) async fn list_backup_passphrase_keys(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<SecretListItem>>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    let secrets =
+        secrets_repo::list_secrets(&state.db, HUB_NODE_ID, BACKUP_AGE_PASSPHRASE_KEY_KIND).await?;
+    Ok(Json(
+        secrets
+            .into_iter()
+            .map(|s| SecretListItem {
+                name: s.name,
+                updated_at: s.updated_at,
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Deserialize)]
+pub(in crate::http) struct CreateBackupPassphraseKeyRequest {
+    passphrase: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct BackupPassphraseKeyResponse {
+    name: String,
+    recipient: String,
+}
+
+pub(in crate::http) async fn create_backup_passphrase_key(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(req): Json<CreateBackupPassphraseKeyRequest>,
+) -> Result<(StatusCode, Json<BackupPassphraseKeyResponse>), AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(
+            AppError::bad_request("invalid_name", "Key name is required")
+                .with_reason("required")
+                .with_field("name"),
+        );
+    }
+    if req.passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(AppError::bad_request(
+            "invalid_passphrase",
+            format!("Passphrase must be at least {MIN_PASSPHRASE_LEN} characters"),
+        )
+        .with_reason("too_short")
+        .with_field("passphrase")
+        .with_param("min", MIN_PASSPHRASE_LEN));
+    }
+    for kind in [BACKUP_AGE_IDENTITY_KIND, BACKUP_AGE_PASSPHRASE_KEY_KIND] {
+        if secrets_repo::secret_exists(&state.db, HUB_NODE_ID, kind, name).await? {
+            return Err(AppError::conflict(
+                "backup_key_exists",
+                "A backup key with this name exists",
+            )
+            .with_field("name"));
+        }
+    }
+
+    let recipient =
+        create_age_passphrase_key(&state.db, &state.secrets, name, &req.passphrase).await?;
+    tracing::info!(
+        secret_kind = BACKUP_AGE_PASSPHRASE_KEY_KIND,
+        secret_name = %name,
+        "backup passphrase key created"
+    );
+    Ok((
+        StatusCode::CREATED,
+        Json(BackupPassphraseKeyResponse {
+            name: name.to_string(),
+            recipient,
+        }),
+    ))
+}
+
+pub(in crate::http) async fn get_backup_passphrase_key(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<BackupPassphraseKeyResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+
+    let recipient = get_age_passphrase_recipient(&state.db, &state.secrets, &name)
+        .await?
+        .ok_or_else(|| AppError::not_found("secret_not_found", "Secret not found"))?;
+    Ok(Json(BackupPassphraseKeyResponse { name, recipient }))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(in crate::http) struct RotateBackupKeyRequest {
+    /// Also re-encrypt existing snapshots to the new recipient (background operation).
+    #[serde(default)]
+    reencrypt: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct RotateBackupKeyResponse {
+    name: String,
+    recipient: String,
+    previous_recipient: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    op_id: Option<String>,
+    /// Snapshots queued for re-encryption.
+    runs: usize,
+}
+
+fn uses_backup_key(spec: &job_spec::JobSpecV1, name: &str) -> bool {
+    let pipeline = match spec {
+        job_spec::JobSpecV1::Filesystem { pipeline, .. } => pipeline,
+        job_spec::JobSpecV1::Sqlite { pipeline, .. } => pipeline,
+        job_spec::JobSpecV1::Vaultwarden { pipeline, .. } => pipeline,
+        job_spec::JobSpecV1::SqliteApp { pipeline, .. } => pipeline,
+        job_spec::JobSpecV1::Postgres { pipeline, .. } => pipeline,
+        job_spec::JobSpecV1::VmImage { pipeline, .. } => pipeline,
+        job_spec::JobSpecV1::KubernetesPvc { pipeline, .. } => pipeline,
+        job_spec::JobSpecV1::Imap { pipeline, .. } => pipeline,
+        job_spec::JobSpecV1::Git { pipeline, .. } => pipeline,
+    };
+    matches!(
+        &pipeline.encryption,
+        job_spec::EncryptionV1::AgeX25519 { key_name } if key_name.trim() == name
+    )
+}
+
+pub(in crate::http) async fn rotate_backup_key(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    req: Option<Json<RotateBackupKeyRequest>>,
+) -> Result<Json<RotateBackupKeyResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+
+    let name = name.trim().to_string();
+    if get_age_identity(&state.db, &state.secrets, &name)
+        .await?
+        .is_none()
+    {
+        if get_age_passphrase_recipient(&state.db, &state.secrets, &name)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::bad_request(
+                "unsupported_key",
+                "Passphrase-protected keys cannot be rotated",
+            )
+            .with_reason("passphrase_key"));
+        }
+        return Err(AppError::not_found("secret_not_found", "Secret not found"));
+    }
+
+    let rotated = rotate_age_identity(&state.db, &state.secrets, &name).await?;
+    tracing::info!(
+        secret_kind = BACKUP_AGE_IDENTITY_KIND,
+        secret_name = %name,
+        "backup key rotated"
+    );
+
+    let mut job_ids = Vec::new();
+    let mut agent_ids = std::collections::BTreeSet::new();
+    for job in jobs_repo::list_jobs_including_archived(&state.db).await? {
+        let Ok(spec) = job_spec::parse_value(&job.spec) else {
+            continue;
+        };
+        if !uses_backup_key(&spec, &name) {
+            continue;
+        }
+        if job.archived_at.is_none()
+            && let Some(agent_id) = job.agent_id.clone()
+        {
+            agent_ids.insert(agent_id);
+        }
+        job_ids.push(job.id);
+    }
+
+    // Agents cache job configs (with the recipient) for offline runs.
+    for agent_id in &agent_ids {
+        if let Err(error) = send_node_config_snapshot(
+            &state.db,
+            state.secrets.as_ref(),
+            &state.agent_manager,
+            agent_id,
+        )
+        .await
+        {
+            tracing::warn!(agent_id = %agent_id, error = %error, "failed to send agent config snapshot");
+        }
+    }
+
+    if !req.reencrypt {
+        return Ok(Json(RotateBackupKeyResponse {
+            name,
+            recipient: rotated.recipient,
+            previous_recipient: rotated.previous_recipient,
+            op_id: None,
+            runs: 0,
+        }));
+    }
+
+    // Only snapshots in a Hub-local directory can be rewritten in place.
+    let mut run_ids = Vec::new();
+    for job_id in &job_ids {
+        let mut cursor = 0;
+        loop {
+            let page = run_artifacts_repo::list_run_artifacts_for_job(
+                &state.db,
+                job_id,
+                cursor,
+                RUN_ARTIFACTS_PAGE_SIZE,
+                Some("present"),
+            )
+            .await?;
+            let fetched = page.len() as u64;
+            run_ids.extend(
+                page.into_iter()
+                    .filter(|a| a.node_id == HUB_NODE_ID && a.target_type == "local_dir")
+                    .map(|a| a.run_id),
+            );
+            if fetched < RUN_ARTIFACTS_PAGE_SIZE {
+                break;
+            }
+            cursor += fetched;
+        }
+    }
+
+    let op = operations_repo::create_operation(
+        &state.db,
+        operations_repo::OperationKind::Reencrypt,
+        Some(("backup_key", name.as_str())),
+    )
+    .await?;
+    let _ = operations_repo::append_event(
+        &state.db,
+        &op.id,
+        "info",
+        "requested",
+        "requested",
+        Some(serde_json::json!({ "key_name": name.clone(), "runs": run_ids.len() })),
+    )
+    .await;
+
+    let runs = run_ids.len();
+    restore::spawn_reencrypt_operation(
+        state.db.clone(),
+        state.secrets.clone(),
+        state.config.data_dir.clone(),
+        op.id.clone(),
+        name.clone(),
+        run_ids,
+        global_cancel_registry().register_operation(&op.id),
+        Some(Box::new({
+            let op_id = op.id.clone();
+            move || {
+                global_cancel_registry().unregister_operation(&op_id);
+            }
+        })),
+    )
+    .await;
+
+    tracing::info!(op_id = %op.id, key_name = %name, runs, "re-encrypt requested");
+    Ok(Json(RotateBackupKeyResponse {
+        name,
+        recipient: rotated.recipient,
+        previous_recipient: rotated.previous_recipient,
+        op_id: Some(op.id),
+        runs,
+    }))
+}
@@ -0,0 +1,233 @@
+//! Slack and Discord destinations: Hub secrets holding just a webhook URL.
+
+use axum::Json;
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tower_cookies::Cookies;
+
+use bastion_core::HUB_NODE_ID;
+use bastion_notify::NotificationChannel;
+use bastion_notify::discord::DiscordChannel;
+use bastion_notify::slack::SlackChannel;
+use bastion_notify::webhook::WebhookDestination;
+use bastion_storage::notifications_repo;
+use bastion_storage::secrets_repo;
+
+use super::super::notifications::destination_error;
+use super::super::shared::{require_csrf, require_session};
+use super::super::{AppError, AppState};
+use super::SecretListItem;
+use super::references::{DeleteSecretQuery, ensure_secret_unreferenced};
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct UpsertChatWebhookSecretRequest {
+    webhook_url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct ChatWebhookSecretResponse {
+    name: String,
+    webhook_url: String,
+}
+
+async fn list_secrets(
+    state: &AppState,
+    cookies: &Cookies,
+    channel: &dyn NotificationChannel,
+) -> Result<Json<Vec<SecretListItem>>, AppError> {
+    let _session = require_session(state, cookies).await?;
+    let secrets = secrets_repo::list_secrets(&state.db, HUB_NODE_ID, channel.secret_kind()).await?;
+    Ok(Json(
+        secrets
+            .into_iter()
+            .map(|s| SecretListItem {
+                name: s.name,
+                updated_at: s.updated_at,
+            })
+            .collect(),
+    ))
+}
+
+async fn upsert_secret(
+    state: &AppState,
+    cookies: &Cookies,
+    headers: &HeaderMap,
+    channel: &dyn NotificationChannel,
+    name: &str,
+    req: UpsertChatWebhookSecretRequest,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(state, cookies).await?;
+    require_csrf(headers, &session)?;
+
+    if name.trim().is_empty() {
+        return Err(
+            AppError::bad_request("invalid_name", "Secret name is required")
+                .with_reason("required")
+                .with_field("name"),
+        );
+    }
+
+    let payload = channel
+        .validate_destination(serde_json::json!({ "webhook_url": req.webhook_url }))
+        .map_err(destination_error)?;
+    let bytes = serde_json::to_vec(&payload)?;
+
+    secrets_repo::upsert_secret(
+        &state.db,
+        &state.secrets,
+        HUB_NODE_ID,
+        channel.secret_kind(),
+        name.trim(),
+        &bytes,
+    )
+    .await?;
+    tracing::info!(
+        secret_kind = channel.secret_kind(),
+        secret_name = %name.trim(),
+        "secret upserted"
+    );
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn get_secret(
+    state: &AppState,
+    cookies: &Cookies,
+    channel: &dyn NotificationChannel,
+    name: String,
+) -> Result<Json<ChatWebhookSecretResponse>, AppError> {
+    let _session = require_session(state, cookies).await?;
+
+    let bytes = secrets_repo::get_secret(
+        &state.db,
+        &state.secrets,
+        HUB_NODE_ID,
+        channel.secret_kind(),
+        &name,
+    )
+    .await?
+    .ok_or_else(|| AppError::not_found("secret_not_found", "Secret not found"))?;
+
+    let payload: WebhookDestination = serde_json::from_slice(&bytes)?;
+    Ok(Json(ChatWebhookSecretResponse {
+        name,
+        webhook_url: payload.webhook_url,
+    }))
+}
+
+async fn delete_secret(
+    state: &AppState,
+    cookies: &Cookies,
+    headers: &HeaderMap,
+    channel: &dyn NotificationChannel,
+    name: &str,
+    force: bool,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(state, cookies).await?;
+    require_csrf(headers, &session)?;
+
+    ensure_secret_unreferenced(state, HUB_NODE_ID, channel.secret_kind(), name, force).await?;
+    let deleted =
+        secrets_repo::delete_secret(&state.db, HUB_NODE_ID, channel.secret_kind(), name).await?;
+    if !deleted {
+        return Err(AppError::not_found("secret_not_found", "Secret not found"));
+    }
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let _ = notifications_repo::cancel_queued_for_destination(
+        &state.db,
+        channel.id(),
+        name,
+        "canceled: destination deleted",
+        now,
+    )
+    .await?;
+    tracing::info!(secret_kind = channel.secret_kind(), secret_name = %name, "secret deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(in crate::http) async fn list_slack_secrets(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<SecretListItem>>, AppError> {
+    list_secrets(&state, &cookies, &SlackChannel).await
+}
+
+pub(in crate::http) async fn upsert_slack_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(req): Json<UpsertChatWebhookSecretRequest>,
+) -> Result<StatusCode, AppError> {
+    upsert_secret(&state, &cookies, &headers, &SlackChannel, &name, req).await
+}
+
+pub(in crate::http) async fn get_slack_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<ChatWebhookSecretResponse>, AppError> {
+    get_secret(&state, &cookies, &SlackChannel, name).await
+}
+
+pub(in crate::http) async fn delete_slack_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Query(query): Query<DeleteSecretQuery>,
+) -> Result<StatusCode, AppError> {
+    delete_secret(
+        &state,
+        &cookies,
+        &headers,
+        &SlackChannel,
+        &name,
+        query.force,
+    )
+    .await
+}
+
+pub(in crate::http) async fn list_discord_secrets(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<SecretListItem>>, AppError> {
+    list_secrets(&state, &cookies, &DiscordChannel).await
+}
+
+pub(in crate::http) async fn upsert_discord_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(req): Json<UpsertChatWebhookSecretRequest>,
+) -> Result<StatusCode, AppError> {
+    upsert_secret(&state, &cookies, &headers, &DiscordChannel, &name, req).await
+}
+
+pub(in crate::http) async fn get_discord_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<ChatWebhookSecretResponse>, AppError> {
+    get_secret(&state, &cookies, &DiscordChannel, name).await
+}
+
+pub(in crate::http) async fn delete_discord_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Query(query): Query<DeleteSecretQuery>,
+) -> Result<StatusCode, AppError> {
+    delete_secret(
+        &state,
+        &cookies,
+        &headers,
+        &DiscordChannel,
+        &name,
+        query.force,
+    )
+    .await
+}
@@ -0,0 +1,214 @@
+use axum::Json;
+use axum::extract::Path;
+use axum::http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+use bastion_core::HUB_NODE_ID;
+use bastion_storage::auth;
+use bastion_storage::job_access_repo;
+use bastion_storage::secrets::{ExternalProvider, ExternalSecretRef, ProviderHealth};
+use bastion_storage::secrets_repo;
+
+use super::super::shared::{require_csrf, require_session};
+use super::super::{AppError, AppState};
+use super::node_validation::validate_node_id;
+use super::references::{
+    KIND_DISCORD, KIND_SLACK, KIND_SMS, KIND_SMTP, KIND_WEBDAV, KIND_WECOM_BOT,
+};
+use super::webdav::maybe_send_node_snapshots;
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct ProvidersHealthResponse {
+    enabled: bool,
+    providers: Vec<ProviderHealth>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct UpsertExternalSecretRequest {
+    provider: ExternalProvider,
+    path: String,
+    #[serde(default)]
+    field: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct ExternalSecretResponse {
+    node_id: String,
+    kind: &'static str,
+    name: String,
+    external_ref: ExternalSecretRef,
+}
+
+pub(in crate::http) async fn external_providers_health(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<ProvidersHealthResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    let Some(providers) = state.secrets.external_providers() else {
+        return Ok(Json(ProvidersHealthResponse {
+            enabled: false,
+            providers: Vec::new(),
+        }));
+    };
+    Ok(Json(ProvidersHealthResponse {
+        enabled: true,
+        providers: providers.health().await,
+    }))
+}
+
+/// References are resolved with the Hub's own provider credentials, so only admins may manage them.
+async fn require_admin(state: &AppState, session: &auth::SessionRow) -> Result<(), AppError> {
+    if !job_access_repo::is_admin(&state.db, session.user_id).await? {
+        return Err(AppError::forbidden(
+            "admin_required",
+            "Only admins can manage external secret references",
+        ));
+    }
+    Ok(())
+}
+
+pub(in crate::http) async fn get_external_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path((node_id, kind, name)): Path<(String, String, String)>,
+) -> Result<Json<ExternalSecretResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_admin(&state, &session).await?;
+    let (node_id, kind) = validate_target(&state, &node_id, &kind).await?;
+    let name = name.trim().to_string();
+
+    let Some(external_ref) =
+        secrets_repo::get_external_ref(&state.db, &node_id, kind, &name).await?
+    else {
+        return Err(AppError::not_found(
+            "secret_not_found",
+            "External secret not found",
+        ));
+    };
+    Ok(Json(ExternalSecretResponse {
+        node_id,
+        kind,
+        name,
+        external_ref,
+    }))
+}
+
+/// Points a secret at an external provider path; the reference is only saved once it resolves.
+pub(in crate::http) async fn upsert_external_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path((node_id, kind, name)): Path<(String, String, String)>,
+    Json(req): Json<UpsertExternalSecretRequest>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_admin(&state, &session).await?;
+    let (node_id, kind) = validate_target(&state, &node_id, &kind).await?;
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(
+            AppError::bad_request("invalid_name", "Secret name is required")
+                .with_reason("required")
+                .with_field("name"),
+        );
+    }
+    let path = req.path.trim();
+    if path.is_empty() {
+        return Err(
+            AppError::bad_request("invalid_path", "Provider path is required")
+                .with_reason("required")
+                .with_field("path"),
+        );
+    }
+
+    let Some(providers) = state.secrets.external_providers() else {
+        return Err(AppError::bad_request(
+            "external_provider_not_configured",
+            "External secret providers are not enabled",
+        )
+        .with_field("provider"));
+    };
+    if !providers.is_configured(req.provider) {
+        return Err(AppError::bad_request(
+            "external_provider_not_configured",
+            format!("Provider {} is not configured", req.provider.as_str()),
+        )
+        .with_reason("not_configured")
+        .with_field("provider"));
+    }
+
+    let external_ref = ExternalSecretRef {
+        provider: req.provider,
+        path: path.to_string(),
+        field: req
+            .field
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty()),
+    };
+    if let Err(error) = external_ref.validate() {
+        return Err(AppError::bad_request(
+            "invalid_path",
+            format!("Invalid provider path: {error}"),
+        )
+        .with_reason("invalid_format")
+        .with_field("path"));
+    }
+    // Always ask the provider: a cached value for the same path says nothing about access now.
+    providers.invalidate(&external_ref);
+    if let Err(error) = providers.resolve(&external_ref).await {
+        return Err(AppError::bad_request(
+            "external_secret_unresolvable",
+            format!("Failed to read secret from provider: {error:#}"),
+        )
+        .with_field("path"));
+    }
+
+    secrets_repo::upsert_external_secret(&state.db, &node_id, kind, name, &external_ref).await?;
+    tracing::info!(
+        node_id = %node_id,
+        secret_kind = kind,
+        secret_name = %name,
+        provider = external_ref.provider.as_str(),
+        "external secret reference upserted"
+    );
+    maybe_send_node_snapshots(&state, &node_id).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// WebDAV credentials can live on any node; notification secrets are Hub-only.
+async fn validate_target(
+    state: &AppState,
+    node_id: &str,
+    kind: &str,
+) -> Result<(String, &'static str), AppError> {
+    let kind = match kind.trim() {
+        KIND_WEBDAV => KIND_WEBDAV,
+        KIND_SMTP => KIND_SMTP,
+        KIND_WECOM_BOT => KIND_WECOM_BOT,
+        KIND_SLACK => KIND_SLACK,
+        KIND_DISCORD => KIND_DISCORD,
+        KIND_SMS => KIND_SMS,
+        _ => {
+            return Err(
+                AppError::bad_request("invalid_kind", "Unsupported secret kind")
+                    .with_reason("unsupported")
+                    .with_field("kind"),
+            );
+        }
+    };
+    validate_node_id(&state.db, node_id).await?;
+    let node_id = node_id.trim();
+    if kind != KIND_WEBDAV && node_id != HUB_NODE_ID {
+        return Err(AppError::bad_request(
+            "invalid_node_id",
+            "Notification secrets are stored on the Hub",
+        )
+        .with_reason("hub_only")
+        .with_field("node_id"));
+    }
+    Ok((node_id.to_string(), kind))
+}
@@ -0,0 +1,321 @@
+use axum::Json;
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, StatusCode};
+use serde::Serialize;
+use tower_cookies::Cookies;
+
+use bastion_core::HUB_NODE_ID;
+use bastion_core::job_spec::ImapCredentialsV1;
+use bastion_storage::secrets_repo;
+
+use super::super::shared::{require_csrf, require_session};
+use super::super::{AppError, AppState};
+use super::SecretListItem;
+use super::node_validation::validate_node_id;
+use super::references::{DeleteSecretQuery, KIND_IMAP, ensure_secret_unreferenced};
+use super::webdav::maybe_send_node_config_snapshot;
+
+pub(in crate::http) async fn list_imap_secrets(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<SecretListItem>>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    list_imap_secrets_for_node(&state, HUB_NODE_ID).await
+}
+
+pub(in crate::http) async fn list_imap_secrets_node(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(node_id): Path<String>,
+) -> Result<Json<Vec<SecretListItem>>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    validate_node_id(&state.db, &node_id).await?;
+
+    list_imap_secrets_for_node(&state, node_id.trim()).await
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct ImapSecretResponse {
+    name: String,
+    #[serde(flatten)]
+    credentials: ImapCredentialsV1,
+}
+
+pub(in crate::http) async fn upsert_imap_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(req): Json<ImapCredentialsV1>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    upsert_imap_secret_for_node(&state, HUB_NODE_ID, &name, req).await?;
+    tracing::info!(secret_kind = "imap", secret_name = %name.trim(), "secret upserted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(in crate::http) async fn get_imap_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<ImapSecretResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    let credentials = load_imap_secret_payload(&state, HUB_NODE_ID, &name).await?;
+    Ok(Json(ImapSecretResponse { name, credentials }))
+}
+
+pub(in crate::http) async fn delete_imap_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Query(query): Query<DeleteSecretQuery>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    delete_imap_secret_for_node(&state, HUB_NODE_ID, &name, query.force).await?;
+    tracing::info!(secret_kind = "imap", secret_name = %name, "secret deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(in crate::http) async fn upsert_imap_secret_node(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path((node_id, name)): Path<(String, String)>,
+    Json(req): Json<ImapCredentialsV1>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    validate_node_id(&state.db, &node_id).await?;
+
+    let node_id_trimmed = node_id.trim();
+    upsert_imap_secret_for_node(&state, node_id_trimmed, &name, req).await?;
+    tracing::info!(
+        node_id = %node_id_trimmed,
+        secret_kind = "imap",
+        secret_name = %name.trim(),
+        "secret upserted"
+    );
+    // Agents receive IMAP credentials inside their resolved job specs.
+    maybe_send_node_config_snapshot(&state, node_id_trimmed).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(in crate::http) async fn get_imap_secret_node(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path((node_id, name)): Path<(String, String)>,
+) -> Result<Json<ImapSecretResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    validate_node_id(&state.db, &node_id).await?;
+    let credentials = load_imap_secret_payload(&state, node_id.trim(), &name).await?;
+    Ok(Json(ImapSecretResponse { name, credentials }))
+}
+
+pub(in crate::http) async fn delete_imap_secret_node(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path((node_id, name)): Path<(String, String)>,
+    Query(query): Query<DeleteSecretQuery>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    validate_node_id(&state.db, &node_id).await?;
+
+    let node_id_trimmed = node_id.trim();
+    delete_imap_secret_for_node(&state, node_id_trimmed, &name, query.force).await?;
+    tracing::info!(
+        node_id = %node_id_trimmed,
+        secret_kind = "imap",
+        secret_name = %name,
+        "secret deleted"
+    );
+    maybe_send_node_config_snapshot(&state, node_id_trimmed).await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_imap_secrets_for_node(
+    state: &AppState,
+    node_id: &str,
+) -> Result<Json<Vec<SecretListItem>>, AppError> {
+    let secrets = secrets_repo::list_secrets(&state.db, node_id, "imap").await?;
+    Ok(Json(
+        secrets
+            .into_iter()
+            .map(|s| SecretListItem {
+                name: s.name,
+                updated_at: s.updated_at,
+            })
+            .collect(),
+    ))
+}
+
+fn required(
+    value: &str,
+    code: &'static str,
+    message: &'static str,
+    field: &'static str,
+) -> Result<String, AppError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(AppError::bad_request(code, message)
+            .with_reason("required")
+            .with_field(field));
+    }
+    Ok(value.to_string())
+}
+
+fn normalize_imap_credentials(req: ImapCredentialsV1) -> Result<ImapCredentialsV1, AppError> {
+    match req {
+        ImapCredentialsV1::Password { username, password } => {
+            let username = required(
+                &username,
+                "invalid_username",
+                "Username is required",
+                "username",
+            )?;
+            if password.is_empty() {
+                return Err(
+                    AppError::bad_request("invalid_password", "Password is required")
+                        .with_reason("required")
+                        .with_field("password"),
+                );
+            }
+            Ok(ImapCredentialsV1::Password { username, password })
+        }
+        ImapCredentialsV1::Oauth2 {
+            username,
+            token_url,
+            client_id,
+            client_secret,
+            refresh_token,
+        } => {
+            let username = required(
+                &username,
+                "invalid_username",
+                "Username is required",
+                "username",
+            )?;
+            let token_url = required(
+                &token_url,
+                "invalid_token_url",
+                "Token URL is required",
+                "token_url",
+            )?;
+            let scheme_ok = url::Url::parse(&token_url)
+                .map(|url| matches!(url.scheme(), "http" | "https"))
+                .unwrap_or(false);
+            if !scheme_ok {
+                return Err(
+                    AppError::bad_request("invalid_token_url", "Token URL is invalid")
+                        .with_reason("invalid_format")
+                        .with_field("token_url"),
+                );
+            }
+            let client_id = required(
+                &client_id,
+                "invalid_client_id",
+                "Client ID is required",
+                "client_id",
+            )?;
+            let refresh_token = required(
+                &refresh_token,
+                "invalid_refresh_token",
+                "Refresh token is required",
+                "refresh_token",
+            )?;
+            Ok(ImapCredentialsV1::Oauth2 {
+                username,
+                token_url,
+                client_id,
+                client_secret: client_secret.filter(|v| !v.trim().is_empty()),
+                refresh_token,
+            })
+        }
+    }
+}
+
+async fn upsert_imap_secret_for_node(
+    state: &AppState,
+    node_id: &str,
+    name: &str,
+    req: ImapCredentialsV1,
+) -> Result<(), AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(
+            AppError::bad_request("invalid_name", "Secret name is required")
+                .with_reason("required")
+                .with_field("name"),
+        );
+    }
+    let credentials = normalize_imap_credentials(req)?;
+    let bytes = serde_json::to_vec(&credentials)?;
+
+    secrets_repo::upsert_secret(&state.db, &state.secrets, node_id, "imap", name, &bytes).await?;
+    Ok(())
+}
+
+async fn load_imap_secret_payload(
+    state: &AppState,
+    node_id: &str,
+    name: &str,
+) -> Result<ImapCredentialsV1, AppError> {
+    let bytes = secrets_repo::get_secret(&state.db, &state.secrets, node_id, "imap", name)
+        .await?
+        .ok_or_else(|| AppError::not_found("secret_not_found", "Secret not found"))?;
+
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+async fn delete_imap_secret_for_node(
+    state: &AppState,
+    node_id: &str,
+    name: &str,
+    force: bool,
+) -> Result<(), AppError> {
+    ensure_secret_unreferenced(state, node_id, KIND_IMAP, name, force).await?;
+    let deleted = secrets_repo::delete_secret(&state.db, node_id, "imap", name).await?;
+    if !deleted {
+        return Err(AppError::not_found("secret_not_found", "Secret not found"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bastion_core::job_spec::ImapCredentialsV1;
+
+    use super::normalize_imap_credentials;
+
+    #[test]
+    fn oauth2_credentials_require_an_http_token_url() {
+        let oauth2 = |token_url: &str| ImapCredentialsV1::Oauth2 {
+            username: " me@example.com ".to_string(),
+            token_url: token_url.to_string(),
+            client_id: "client".to_string(),
+            client_secret: Some(" ".to_string()),
+            refresh_token: "refresh".to_string(),
+        };
+        assert!(normalize_imap_credentials(oauth2("ftp://example.com/token")).is_err());
+
+        let normalized = normalize_imap_credentials(oauth2("https://oauth2.googleapis.com/token"))
+            .expect("valid");
+        let ImapCredentialsV1::Oauth2 {
+            username,
+            client_secret,
+            ..
+        } = normalized
+        else {
+            panic!("expected oauth2 credentials");
+        };
+        assert_eq!(username, "me@example.com");
+        assert_eq!(client_secret, None);
+    }
+}
@@ -0,0 +1,399 @@
+use axum::Extension;
+use axum::Json;
+use axum::body::Body;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+use bastion_core::HUB_NODE_ID;
+use bastion_engine::keypack_escrow;
+use bastion_storage::keypack_escrow_repo::{
+    self, KeypackEscrowSettings, KeypackEscrowStatus, KeypackEscrowTarget,
+};
+use bastion_storage::{audit_repo, auth, job_access_repo, secrets, secrets_repo};
+
+use super::super::shared::{ClientConnInfo, reauthenticate, require_csrf, require_session};
+use super::super::{AppError, AppState};
+
+const MIN_KEYPACK_PASSWORD_LEN: usize = 8;
+const AUDIT_LIST_LIMIT: u32 = 50;
+
+async fn require_admin(state: &AppState, session: &auth::SessionRow) -> Result<(), AppError> {
+    if !job_access_repo::is_admin(&state.db, session.user_id).await? {
+        return Err(AppError::forbidden(
+            "admin_required",
+            "Only admins can export, import or escrow the keypack",
+        ));
+    }
+    Ok(())
+}
+
+pub(in crate::http) fn validate_keypack_password(password: &str) -> Result<(), AppError> {
+    if password.chars().count() < MIN_KEYPACK_PASSWORD_LEN {
+        return Err(AppError::bad_request(
+            "invalid_password",
+            format!("Keypack password must be at least {MIN_KEYPACK_PASSWORD_LEN} characters"),
+        )
+        .with_reason("too_short")
+        .with_field("password")
+        .with_param("min", MIN_KEYPACK_PASSWORD_LEN));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub(in crate::http) struct ExportKeypackRequest {
+    current_password: String,
+    password: String,
+}
+
+pub(in crate::http) async fn export_keypack(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Json(req): Json<ExportKeypackRequest>,
+) -> Result<Response, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_admin(&state, &session).await?;
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+    reauthenticate(&state, &session, &client_ip, &req.current_password, now).await?;
+    validate_keypack_password(&req.password)?;
+
+    let data_dir = state.config.data_dir.clone();
+    let password = req.password;
+    // Argon2 key derivation is deliberately slow; keep it off the async workers.
+    let bytes =
+        tokio::task::spawn_blocking(move || secrets::export_keypack_bytes(&data_dir, &password))
+            .await??;
+
+    audit_repo::append(
+        &state.db,
+        now,
+        audit_repo::ACTION_KEYPACK_EXPORTED,
+        Some(session.user_id),
+        Some(&client_ip),
+        None,
+    )
+    .await?;
+    tracing::warn!(client_ip = %client_ip, user_id = session.user_id, "keypack exported");
+
+    let filename = format!("bastion-keypack-{now}.json");
+    let resp = Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(
+            header::CONTENT_DISPOSITION,
+            HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+                .unwrap_or_else(|_| HeaderValue::from_static("attachment")),
+        )
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from(bytes))?;
+    Ok(resp)
+}
+
+#[derive(Deserialize)]
+pub(in crate::http) struct ImportKeypackRequest {
+    current_password: String,
+    password: String,
+    keypack: serde_json::Value,
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct ImportKeypackResponse {
+    restart_required: bool,
+}
+
+pub(in crate::http) async fn import_keypack(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Json(req): Json<ImportKeypackRequest>,
+) -> Result<Json<ImportKeypackResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_admin(&state, &session).await?;
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+    reauthenticate(&state, &session, &client_ip, &req.current_password, now).await?;
+    if req.password.is_empty() {
+        return Err(
+            AppError::bad_request("invalid_password", "Keypack password is required")
+                .with_reason("required")
+                .with_field("password"),
+        );
+    }
+    if !req.force {
+        return Err(AppError::conflict(
+            "master_key_exists",
+            "The Hub already has a master key; confirm replacing it",
+        )
+        .with_field("force"));
+    }
+
+    let bytes = serde_json::to_vec(&req.keypack)?;
+    let data_dir = state.config.data_dir.clone();
+    let password = req.password;
+    tokio::task::spawn_blocking(move || {
+        secrets::import_keypack_bytes(&data_dir, &bytes, &password, true)
+    })
+    .await?
+    .map_err(|error| {
+        tracing::debug!(error = %error, "keypack import rejected");
+        AppError::bad_request(
+            "invalid_keypack",
+            "Keypack could not be decrypted (wrong password or damaged file)",
+        )
+        .with_field("keypack")
+    })?;
+
+    audit_repo::append(
+        &state.db,
+        now,
+        audit_repo::ACTION_KEYPACK_IMPORTED,
+        Some(session.user_id),
+        Some(&client_ip),
+        None,
+    )
+    .await?;
+    tracing::warn!(client_ip = %client_ip, user_id = session.user_id, "keypack imported; restart required");
+
+    Ok(Json(ImportKeypackResponse {
+        restart_required: true,
+    }))
+}
+
+pub(in crate::http) async fn keypack_audit(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<audit_repo::AuditEntry>>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    let entries = audit_repo::list_recent(
+        &state.db,
+        &[
+            audit_repo::ACTION_KEYPACK_EXPORTED,
+            audit_repo::ACTION_KEYPACK_IMPORTED,
+            audit_repo::ACTION_KEYPACK_ESCROW_UPDATED,
+        ],
+        AUDIT_LIST_LIMIT,
+    )
+    .await?;
+    Ok(Json(entries))
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct KeypackEscrowResponse {
+    enabled: bool,
+    target: Option<KeypackEscrowTarget>,
+    password_set: bool,
+    status: KeypackEscrowStatus,
+}
+
+async fn load_escrow(state: &AppState) -> Result<KeypackEscrowResponse, AppError> {
+    let settings = keypack_escrow_repo::get_settings(&state.db).await?;
+    let password_set = secrets_repo::secret_exists(
+        &state.db,
+        HUB_NODE_ID,
+        keypack_escrow_repo::PASSWORD_SECRET_KIND,
+        keypack_escrow_repo::PASSWORD_SECRET_NAME,
+    )
+    .await?;
+    let status = keypack_escrow_repo::get_status(&state.db).await?;
+    Ok(KeypackEscrowResponse {
+        enabled: settings.enabled,
+        target: settings.target,
+        password_set,
+        status,
+    })
+}
+
+pub(in crate::http) async fn get_keypack_escrow(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<KeypackEscrowResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    Ok(Json(load_escrow(&state).await?))
+}
+
+#[derive(Deserialize)]
+pub(in crate::http) struct UpdateKeypackEscrowRequest {
+    current_password: String,
+    enabled: bool,
+    #[serde(default)]
+    target: Option<KeypackEscrowTarget>,
+    /// Leave unset to keep the stored password.
+    #[serde(default)]
+    password: Option<String>,
+}
+
+async fn validate_escrow_target(
+    state: &AppState,
+    target: &KeypackEscrowTarget,
+) -> Result<KeypackEscrowTarget, AppError> {
+    match target {
+        KeypackEscrowTarget::LocalDir { base_dir } => {
+            let base_dir = base_dir.trim();
+            if !std::path::Path::new(base_dir).is_absolute() {
+                return Err(AppError::bad_request(
+                    "invalid_base_dir",
+                    "base_dir must be an absolute path",
+                )
+                .with_reason("not_absolute")
+                .with_field("target.base_dir"));
+            }
+            Ok(KeypackEscrowTarget::LocalDir {
+                base_dir: base_dir.to_string(),
+            })
+        }
+        KeypackEscrowTarget::Webdav {
+            base_url,
+            secret_name,
+        } => {
+            let base_url = base_url.trim();
+            let valid =
+                url::Url::parse(base_url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"));
+            if !valid {
+                return Err(
+                    AppError::bad_request("invalid_base_url", "invalid base_url")
+                        .with_reason("invalid_format")
+                        .with_field("target.base_url"),
+                );
+            }
+            let secret_name = secret_name.trim();
+            if !secrets_repo::secret_exists(&state.db, HUB_NODE_ID, "webdav", secret_name).await? {
+                return Err(AppError::bad_request(
+                    "invalid_secret_name",
+                    "WebDAV credential not found",
+                )
+                .with_reason("not_found")
+                .with_field("target.secret_name"));
+            }
+            Ok(KeypackEscrowTarget::Webdav {
+                base_url: base_url.to_string(),
+                secret_name: secret_name.to_string(),
+            })
+        }
+    }
+}
+
+pub(in crate::http) async fn update_keypack_escrow(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<ClientConnInfo>,
+    Json(req): Json<UpdateKeypackEscrowRequest>,
+) -> Result<Json<KeypackEscrowResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_admin(&state, &session).await?;
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+    reauthenticate(&state, &session, &client_ip, &req.current_password, now).await?;
+
+    let target = match &req.target {
+        Some(target) => Some(validate_escrow_target(&state, target).await?),
+        None => None,
+    };
+    let password = req.password.filter(|v| !v.is_empty());
+    if let Some(password) = &password {
+        validate_keypack_password(password)?;
+    }
+    if req.enabled {
+        if target.is_none() {
+            return Err(
+                AppError::bad_request("invalid_target", "An escrow target is required")
+                    .with_reason("required")
+                    .with_field("target"),
+            );
+        }
+        let password_set = secrets_repo::secret_exists(
+            &state.db,
+            HUB_NODE_ID,
+            keypack_escrow_repo::PASSWORD_SECRET_KIND,
+            keypack_escrow_repo::PASSWORD_SECRET_NAME,
+        )
+        .await?;
+        if password.is_none() && !password_set {
+            return Err(AppError::bad_request(
+                "invalid_password",
+                "A keypack password is required",
+            )
+            .with_reason("required")
+            .with_field("password"));
+        }
+    }
+
+    let previous = keypack_escrow_repo::get_settings(&state.db).await?;
+    if let Some(password) = &password {
+        secrets_repo::upsert_secret(
+            &state.db,
+            &state.secrets,
+            HUB_NODE_ID,
+            keypack_escrow_repo::PASSWORD_SECRET_KIND,
+            keypack_escrow_repo::PASSWORD_SECRET_NAME,
+            password.as_bytes(),
+        )
+        .await?;
+    }
+    // A new password or destination needs a fresh keypack even if the keyring is unchanged.
+    if password.is_some() || previous.target != target {
+        let mut status = keypack_escrow_repo::get_status(&state.db).await?;
+        status.escrowed_fingerprint = None;
+        keypack_escrow_repo::upsert_status(&state.db, &status).await?;
+    }
+    keypack_escrow_repo::upsert_settings(
+        &state.db,
+        &KeypackEscrowSettings {
+            enabled: req.enabled,
+            target,
+        },
+    )
+    .await?;
+
+    audit_repo::append(
+        &state.db,
+        now,
+        audit_repo::ACTION_KEYPACK_ESCROW_UPDATED,
+        Some(session.user_id),
+        Some(&client_ip),
+        Some(if req.enabled { "enabled" } else { "disabled" }),
+    )
+    .await?;
+    tracing::info!(client_ip = %client_ip, user_id = session.user_id, enabled = req.enabled, "keypack escrow updated");
+
+    // Failures are recorded in the escrow status, which the response carries.
+    if let Err(error) =
+        keypack_escrow::escrow_keypack(&state.db, &state.secrets, &state.config.data_dir, false)
+            .await
+    {
+        tracing::warn!(error = %error, "keypack escrow failed");
+    }
+    Ok(Json(load_escrow(&state).await?))
+}
+
+pub(in crate::http) async fn run_keypack_escrow(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+) -> Result<Json<KeypackEscrowResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    require_admin(&state, &session).await?;
+
+    if let Err(error) =
+        keypack_escrow::escrow_keypack(&state.db, &state.secrets, &state.config.data_dir, true)
+            .await
+    {
+        tracing::warn!(error = %error, "keypack escrow failed");
+    }
+    Ok(Json(load_escrow(&state).await?))
+}
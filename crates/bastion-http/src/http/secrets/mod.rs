@@ -1,14 +1,47 @@
 use serde::Serialize;
 
+mod backup_keys;
+mod chat_webhook;
+mod external;
+mod imap;
+mod keypack;
 mod node_validation;
+mod references;
+mod sms;
 mod smtp;
 mod webdav;
 mod wecom_bot;
 
+pub(super) use backup_keys::{
+    create_backup_passphrase_key, get_backup_passphrase_key, list_backup_passphrase_keys,
+    rotate_backup_key,
+};
+pub(super) use chat_webhook::{
+    delete_discord_secret, delete_slack_secret, get_discord_secret, get_slack_secret,
+    list_discord_secrets, list_slack_secrets, upsert_discord_secret, upsert_slack_secret,
+};
+pub(super) use external::{external_providers_health, get_external_secret, upsert_external_secret};
+pub(super) use imap::{
+    delete_imap_secret, delete_imap_secret_node, get_imap_secret, get_imap_secret_node,
+    list_imap_secrets, list_imap_secrets_node, upsert_imap_secret, upsert_imap_secret_node,
+};
+pub(super) use keypack::{
+    export_keypack, get_keypack_escrow, import_keypack, keypack_audit, run_keypack_escrow,
+    update_keypack_escrow, validate_keypack_password,
+};
+pub(super) use references::{
+    discord_secret_references, imap_secret_references, imap_secret_references_node,
+    slack_secret_references, sms_secret_references, smtp_secret_references,
+    webdav_secret_references, webdav_secret_references_node, wecom_bot_secret_references,
+};
+pub(super) use sms::{delete_sms_secret, get_sms_secret, list_sms_secrets, upsert_sms_secret};
 pub(super) use smtp::{delete_smtp_secret, get_smtp_secret, list_smtp_secrets, upsert_smtp_secret};
 pub(super) use webdav::{
-    delete_webdav_secret, delete_webdav_secret_node, get_webdav_secret, get_webdav_secret_node,
-    list_webdav_secrets, list_webdav_secrets_node, upsert_webdav_secret, upsert_webdav_secret_node,
+    delete_webdav_secret, delete_webdav_secret_node, discard_webdav_pending,
+    discard_webdav_pending_node, get_webdav_secret, get_webdav_secret_node, list_webdav_secrets,
+    list_webdav_secrets_node, promote_webdav_pending, promote_webdav_pending_node,
+    stage_webdav_pending, stage_webdav_pending_node, upsert_webdav_secret,
+    upsert_webdav_secret_node,
 };
 pub(super) use wecom_bot::{
     delete_wecom_bot_secret, get_wecom_bot_secret, list_wecom_bot_secrets, upsert_wecom_bot_secret,
@@ -0,0 +1,245 @@
+use axum::Json;
+use axum::extract::Path;
+use serde::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+use bastion_core::HUB_NODE_ID;
+use bastion_core::job_spec;
+use bastion_driver_registry::target_runtime;
+use bastion_storage::jobs_repo;
+
+use super::super::shared::require_session;
+use super::super::{AppError, AppState};
+use super::node_validation::validate_node_id;
+
+pub(super) const KIND_WEBDAV: &str = "webdav";
+pub(super) const KIND_SMTP: &str = "smtp";
+pub(super) const KIND_WECOM_BOT: &str = "wecom_bot";
+pub(super) const KIND_SLACK: &str = "slack";
+pub(super) const KIND_DISCORD: &str = "discord";
+pub(super) const KIND_SMS: &str = "sms";
+pub(super) const KIND_IMAP: &str = "imap";
+
+#[derive(Debug, Default, Deserialize)]
+pub(in crate::http) struct DeleteSecretQuery {
+    #[serde(default)]
+    pub(super) force: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct SecretReference {
+    job_id: String,
+    job_name: String,
+    node_id: String,
+    /// `target` for target credentials (e.g. WebDAV), `source` for IMAP credentials, `notifications` for
+    /// explicitly selected destinations.
+    usage: &'static str,
+    archived: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct SecretReferencesResponse {
+    node_id: String,
+    kind: &'static str,
+    name: String,
+    references: Vec<SecretReference>,
+}
+
+/// Jobs (including archived ones) whose spec names this secret.
+///
+/// WebDAV and IMAP secrets are node-scoped, so only jobs on that node can use them. Notification
+/// destinations only count when a job selects them explicitly (`mode: custom`): inherited jobs
+/// keep notifying the remaining destinations.
+pub(super) async fn find_secret_references(
+    state: &AppState,
+    node_id: &str,
+    kind: &'static str,
+    name: &str,
+) -> Result<Vec<SecretReference>, AppError> {
+    let name = name.trim();
+    let mut out = Vec::new();
+    for job in jobs_repo::list_jobs_including_archived(&state.db).await? {
+        let job_node_id = job.agent_id.as_deref().unwrap_or(HUB_NODE_ID);
+        let Ok(spec) = job_spec::parse_value(&job.spec) else {
+            continue;
+        };
+
+        let target_secret = target_runtime::credential_secret_for_job_target(spec_target(&spec))
+            .ok()
+            .flatten()
+            .filter(|secret| secret.kind == kind);
+        let usage = match (target_secret, kind) {
+            (Some(secret), _) => {
+                (job_node_id == node_id && secret.name == name).then_some("target")
+            }
+            (None, KIND_IMAP) => match &spec {
+                job_spec::JobSpecV1::Imap { source, .. }
+                    if job_node_id == node_id && source.secret_name.trim() == name =>
+                {
+                    Some("source")
+                }
+                _ => None,
+            },
+            (None, kind) => match bastion_notify::builtin_channels().for_secret_kind(kind) {
+                Some(channel) => {
+                    let notifications = spec.notifications();
+                    (notifications.mode == job_spec::NotificationsModeV1::Custom
+                        && notifications
+                            .destinations_for(channel.id())
+                            .iter()
+                            .any(|v| v.trim() == name))
+                    .then_some("notifications")
+                }
+                None => None,
+            },
+        };
+
+        if let Some(usage) = usage {
+            out.push(SecretReference {
+                job_id: job.id,
+                job_name: job.name,
+                node_id: job_node_id.to_string(),
+                usage,
+                archived: job.archived_at.is_some(),
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Rejects deleting a referenced secret unless the caller passed `force=true`.
+pub(super) async fn ensure_secret_unreferenced(
+    state: &AppState,
+    node_id: &str,
+    kind: &'static str,
+    name: &str,
+    force: bool,
+) -> Result<(), AppError> {
+    if force {
+        return Ok(());
+    }
+    let references = find_secret_references(state, node_id, kind, name).await?;
+    if references.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::conflict(
+        "secret_in_use",
+        format!(
+            "Secret is referenced by {} job(s); force=true required",
+            references.len()
+        ),
+    )
+    .with_details(serde_json::json!({ "references": references })))
+}
+
+fn spec_target(spec: &job_spec::JobSpecV1) -> &job_spec::TargetV1 {
+    match spec {
+        job_spec::JobSpecV1::Filesystem { target, .. }
+        | job_spec::JobSpecV1::Sqlite { target, .. }
+        | job_spec::JobSpecV1::Vaultwarden { target, .. }
+        | job_spec::JobSpecV1::SqliteApp { target, .. }
+        | job_spec::JobSpecV1::Postgres { target, .. }
+        | job_spec::JobSpecV1::VmImage { target, .. }
+        | job_spec::JobSpecV1::KubernetesPvc { target, .. }
+        | job_spec::JobSpecV1::Imap { target, .. }
+        | job_spec::JobSpecV1::Git { target, .. } => target,
+    }
+}
+
+async fn references_response(
+    state: &AppState,
+    node_id: &str,
+    kind: &'static str,
+    name: String,
+) -> Result<Json<SecretReferencesResponse>, AppError> {
+    let references = find_secret_references(state, node_id, kind, &name).await?;
+    Ok(Json(SecretReferencesResponse {
+        node_id: node_id.to_string(),
+        kind,
+        name,
+        references,
+    }))
+}
+
+pub(in crate::http) async fn webdav_secret_references(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<SecretReferencesResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    references_response(&state, HUB_NODE_ID, KIND_WEBDAV, name).await
+}
+
+pub(in crate::http) async fn webdav_secret_references_node(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path((node_id, name)): Path<(String, String)>,
+) -> Result<Json<SecretReferencesResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    validate_node_id(&state.db, &node_id).await?;
+    references_response(&state, node_id.trim(), KIND_WEBDAV, name).await
+}
+
+pub(in crate::http) async fn smtp_secret_references(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<SecretReferencesResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    references_response(&state, HUB_NODE_ID, KIND_SMTP, name).await
+}
+
+pub(in crate::http) async fn wecom_bot_secret_references(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<SecretReferencesResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    references_response(&state, HUB_NODE_ID, KIND_WECOM_BOT, name).await
+}
+
+pub(in crate::http) async fn slack_secret_references(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<SecretReferencesResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    references_response(&state, HUB_NODE_ID, KIND_SLACK, name).await
+}
+
+pub(in crate::http) async fn discord_secret_references(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<SecretReferencesResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    references_response(&state, HUB_NODE_ID, KIND_DISCORD, name).await
+}
+
+pub(in crate::http) async fn sms_secret_references(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<SecretReferencesResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    references_response(&state, HUB_NODE_ID, KIND_SMS, name).await
+}
+
+pub(in crate::http) async fn imap_secret_references(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<SecretReferencesResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    references_response(&state, HUB_NODE_ID, KIND_IMAP, name).await
+}
+
+pub(in crate::http) async fn imap_secret_references_node(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path((node_id, name)): Path<(String, String)>,
+) -> Result<Json<SecretReferencesResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    validate_node_id(&state.db, &node_id).await?;
+    references_response(&state, node_id.trim(), KIND_IMAP, name).await
+}
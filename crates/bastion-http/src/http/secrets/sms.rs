@@ -0,0 +1,148 @@
+use axum::Json;
+use axum::extract::{Path, Query};
+use axum::http::{HeaderMap, StatusCode};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+use tower_cookies::Cookies;
+
+use bastion_core::HUB_NODE_ID;
+use bastion_notify::NotificationChannel as _;
+use bastion_notify::sms::{SmsChannel, SmsGatewayDestination, SmsGatewayMethod};
+use bastion_storage::notifications_repo;
+use bastion_storage::secrets_repo;
+
+use super::super::notifications::destination_error;
+use super::super::shared::{require_csrf, require_session};
+use super::super::{AppError, AppState};
+use super::SecretListItem;
+use super::references::{DeleteSecretQuery, KIND_SMS, ensure_secret_unreferenced};
+
+pub(in crate::http) async fn list_sms_secrets(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<SecretListItem>>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+    let secrets = secrets_repo::list_secrets(&state.db, HUB_NODE_ID, KIND_SMS).await?;
+    Ok(Json(
+        secrets
+            .into_iter()
+            .map(|s| SecretListItem {
+                name: s.name,
+                updated_at: s.updated_at,
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub(in crate::http) struct UpsertSmsSecretRequest {
+    url_template: String,
+    #[serde(default)]
+    method: SmsGatewayMethod,
+    #[serde(default)]
+    auth_header: Option<String>,
+    #[serde(default)]
+    body_template: Option<String>,
+    #[serde(default)]
+    content_type: Option<String>,
+    to: String,
+    #[serde(default)]
+    failures_only: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct SmsSecretResponse {
+    name: String,
+    #[serde(flatten)]
+    destination: SmsGatewayDestination,
+}
+
+pub(in crate::http) async fn upsert_sms_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(req): Json<UpsertSmsSecretRequest>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    if name.trim().is_empty() {
+        return Err(
+            AppError::bad_request("invalid_name", "Secret name is required")
+                .with_reason("required")
+                .with_field("name"),
+        );
+    }
+
+    let mut destination = serde_json::json!({
+        "url_template": req.url_template,
+        "method": req.method,
+        "auth_header": req.auth_header,
+        "body_template": req.body_template,
+        "content_type": req.content_type,
+        "to": req.to,
+    });
+    if let Some(failures_only) = req.failures_only {
+        destination["failures_only"] = serde_json::json!(failures_only);
+    }
+    let payload = SmsChannel
+        .validate_destination(destination)
+        .map_err(destination_error)?;
+    let bytes = serde_json::to_vec(&payload)?;
+
+    secrets_repo::upsert_secret(
+        &state.db,
+        &state.secrets,
+        HUB_NODE_ID,
+        KIND_SMS,
+        name.trim(),
+        &bytes,
+    )
+    .await?;
+    tracing::info!(secret_kind = KIND_SMS, secret_name = %name.trim(), "secret upserted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(in crate::http) async fn get_sms_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(name): Path<String>,
+) -> Result<Json<SmsSecretResponse>, AppError> {
+    let _session = require_session(&state, &cookies).await?;
+
+    let bytes = secrets_repo::get_secret(&state.db, &state.secrets, HUB_NODE_ID, KIND_SMS, &name)
+        .await?
+        .ok_or_else(|| AppError::not_found("secret_not_found", "Secret not found"))?;
+
+    let destination: SmsGatewayDestination = serde_json::from_slice(&bytes)?;
+    Ok(Json(SmsSecretResponse { name, destination }))
+}
+
+pub(in crate::http) async fn delete_sms_secret(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Query(query): Query<DeleteSecretQuery>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    ensure_secret_unreferenced(&state, HUB_NODE_ID, KIND_SMS, &name, query.force).await?;
+    let deleted = secrets_repo::delete_secret(&state.db, HUB_NODE_ID, KIND_SMS, &name).await?;
+    if !deleted {
+        return Err(AppError::not_found("secret_not_found", "Secret not found"));
+    }
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let _ = notifications_repo::cancel_queued_for_destination(
+        &state.db,
+        notifications_repo::CHANNEL_SMS,
+        &name,
+        "canceled: destination deleted",
+        now,
+    )
+    .await?;
+    tracing::info!(secret_kind = KIND_SMS, secret_name = %name, "secret deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,18 +1,21 @@
 use axum::Json;
-use axum::extract::Path;
+use axum::extract::{Path, Query};
 use axum::http::{HeaderMap, StatusCode};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tower_cookies::Cookies;
 
 use bastion_core::HUB_NODE_ID;
-use bastion_notify::smtp::{SmtpSecretPayload, SmtpTlsMode, is_valid_mailbox};
+use bastion_notify::NotificationChannel as _;
+use bastion_notify::smtp::{EmailChannel, SmtpSecretPayload, SmtpTlsMode};
 use bastion_storage::notifications_repo;
 use bastion_storage::secrets_repo;
 
+use super::super::notifications::destination_error;
 use super::super::shared::{require_csrf, require_session};
 use super::super::{AppError, AppState};
 use super::SecretListItem;
+use super::references::{DeleteSecretQuery, KIND_SMTP, ensure_secret_unreferenced};
 
 pub(in crate::http) async fn list_smtp_secrets(
     state: axum::extract::State<AppState>,
@@ -40,6 +43,10 @@ pub(in crate::http) struct UpsertSmtpSecretRequest {
     from: String,
     to: Vec<String>,
     tls: SmtpTlsMode,
+    #[serde(default)]
+    subject_template: Option<String>,
+    #[serde(default)]
+    body_template: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -52,6 +59,8 @@ pub(in crate::http) struct SmtpSecretResponse {
     from: String,
     to: Vec<String>,
     tls: SmtpTlsMode,
+    subject_template: Option<String>,
+    body_template: Option<String>,
 }
 
 pub(in crate::http) async fn upsert_smtp_secret(
@@ -72,79 +81,19 @@ pub(in crate::http) async fn upsert_smtp_secret(
         );
     }
 
-    let host = req.host.trim();
-    if host.is_empty() {
-        return Err(
-            AppError::bad_request("invalid_host", "SMTP host is required")
-                .with_reason("required")
-                .with_field("host"),
-        );
-    }
-    if req.port == 0 {
-        return Err(
-            AppError::bad_request("invalid_port", "SMTP port is required")
-                .with_reason("required")
-                .with_field("port"),
-        );
-    }
-
-    let from = req.from.trim();
-    if from.is_empty() {
-        return Err(
-            AppError::bad_request("invalid_from", "SMTP from is required")
-                .with_reason("required")
-                .with_field("from"),
-        );
-    }
-    if !is_valid_mailbox(from) {
-        return Err(
-            AppError::bad_request("invalid_from", "Invalid SMTP from address")
-                .with_reason("invalid_format")
-                .with_field("from"),
-        );
-    }
-
-    let mut to = Vec::new();
-    for (index, item) in req.to.into_iter().enumerate() {
-        let addr = item.trim();
-        if addr.is_empty() {
-            continue;
-        }
-        if !is_valid_mailbox(addr) {
-            return Err(
-                AppError::bad_request("invalid_to", "Invalid SMTP recipient address")
-                    .with_reason("invalid_format")
-                    .with_field("to")
-                    .with_param("index", index),
-            );
-        }
-        to.push(addr.to_string());
-    }
-    if to.is_empty() {
-        return Err(AppError::bad_request("invalid_to", "SMTP to is required")
-            .with_reason("required")
-            .with_field("to"));
-    }
-
-    let username = req.username.trim().to_string();
-    if !username.is_empty() && req.password.trim().is_empty() {
-        return Err(AppError::bad_request(
-            "invalid_password",
-            "SMTP password is required when username is set",
-        )
-        .with_reason("required_with_username")
-        .with_field("password"));
-    }
-
-    let payload = SmtpSecretPayload {
-        host: host.to_string(),
-        port: req.port,
-        username,
-        password: req.password,
-        from: from.to_string(),
-        to,
-        tls: req.tls,
-    };
+    let payload = EmailChannel
+        .validate_destination(serde_json::json!({
+            "host": req.host,
+            "port": req.port,
+            "username": req.username,
+            "password": req.password,
+            "from": req.from,
+            "to": req.to,
+            "tls": req.tls,
+            "subject_template": req.subject_template,
+            "body_template": req.body_template,
+        }))
+        .map_err(destination_error)?;
     let bytes = serde_json::to_vec(&payload)?;
 
     secrets_repo::upsert_secret(
@@ -181,6 +130,8 @@ pub(in crate::http) async fn get_smtp_secret(
         from: payload.from,
         to: payload.to,
         tls: payload.tls,
+        subject_template: payload.subject_template,
+        body_template: payload.body_template,
     }))
 }
 
@@ -189,10 +140,12 @@ pub(in crate::http) async fn delete_smtp_secret(
     cookies: Cookies,
     headers: HeaderMap,
     Path(name): Path<String>,
+    Query(query): Query<DeleteSecretQuery>,
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
 
+    ensure_secret_unreferenced(&state, HUB_NODE_ID, KIND_SMTP, &name, query.force).await?;
     let deleted = secrets_repo::delete_secret(&state.db, HUB_NODE_ID, "smtp", &name).await?;
     if !deleted {
         return Err(AppError::not_found("secret_not_found", "Secret not found"));
@@ -1,10 +1,12 @@
 use axum::Json;
-use axum::extract::Path;
+use axum::extract::{Path, Query};
 use axum::http::{HeaderMap, StatusCode};
 use serde::{Deserialize, Serialize};
 use tower_cookies::Cookies;
 
 use bastion_core::HUB_NODE_ID;
+use bastion_engine::agent_snapshots::send_node_secrets_snapshot;
+use bastion_engine::webdav_rotation;
 use bastion_storage::secrets_repo;
 
 use super::super::agents::send_node_config_snapshot;
@@ -12,6 +14,7 @@ use super::super::shared::{require_csrf, require_session};
 use super::super::{AppError, AppState};
 use super::SecretListItem;
 use super::node_validation::validate_node_id;
+use super::references::{DeleteSecretQuery, KIND_WEBDAV, ensure_secret_unreferenced};
 
 pub(in crate::http) async fn list_webdav_secrets(
     state: axum::extract::State<AppState>,
@@ -85,10 +88,11 @@ pub(in crate::http) async fn delete_webdav_secret(
     cookies: Cookies,
     headers: HeaderMap,
     Path(name): Path<String>,
+    Query(query): Query<DeleteSecretQuery>,
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
-    delete_webdav_secret_for_node(&state, HUB_NODE_ID, &name).await?;
+    delete_webdav_secret_for_node(&state, HUB_NODE_ID, &name, query.force).await?;
     tracing::info!(secret_kind = "webdav", secret_name = %name, "secret deleted");
     Ok(StatusCode::NO_CONTENT)
 }
@@ -138,13 +142,14 @@ pub(in crate::http) async fn delete_webdav_secret_node(
     cookies: Cookies,
     headers: HeaderMap,
     Path((node_id, name)): Path<(String, String)>,
+    Query(query): Query<DeleteSecretQuery>,
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
     validate_node_id(&state.db, &node_id).await?;
 
     let node_id_trimmed = node_id.trim();
-    delete_webdav_secret_for_node(&state, node_id_trimmed, &name).await?;
+    delete_webdav_secret_for_node(&state, node_id_trimmed, &name, query.force).await?;
     tracing::info!(
         node_id = %node_id_trimmed,
         secret_kind = "webdav",
@@ -156,6 +161,83 @@ pub(in crate::http) async fn delete_webdav_secret_node(
     Ok(StatusCode::NO_CONTENT)
 }
 
+pub(in crate::http) async fn stage_webdav_pending(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(req): Json<UpsertWebdavSecretRequest>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    stage_webdav_pending_for_node(&state, HUB_NODE_ID, &name, req).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(in crate::http) async fn promote_webdav_pending(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    promote_webdav_pending_for_node(&state, HUB_NODE_ID, &name).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(in crate::http) async fn discard_webdav_pending(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    discard_webdav_pending_for_node(&state, HUB_NODE_ID, &name).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(in crate::http) async fn stage_webdav_pending_node(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path((node_id, name)): Path<(String, String)>,
+    Json(req): Json<UpsertWebdavSecretRequest>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    validate_node_id(&state.db, &node_id).await?;
+    stage_webdav_pending_for_node(&state, node_id.trim(), &name, req).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(in crate::http) async fn promote_webdav_pending_node(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path((node_id, name)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    validate_node_id(&state.db, &node_id).await?;
+    promote_webdav_pending_for_node(&state, node_id.trim(), &name).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub(in crate::http) async fn discard_webdav_pending_node(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Path((node_id, name)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+    validate_node_id(&state.db, &node_id).await?;
+    discard_webdav_pending_for_node(&state, node_id.trim(), &name).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 async fn list_webdav_secrets_for_node(
     state: &AppState,
     node_id: &str,
@@ -231,15 +313,120 @@ async fn delete_webdav_secret_for_node(
     state: &AppState,
     node_id: &str,
     name: &str,
+    force: bool,
 ) -> Result<(), AppError> {
+    ensure_secret_unreferenced(state, node_id, KIND_WEBDAV, name, force).await?;
     let deleted = secrets_repo::delete_secret(&state.db, node_id, "webdav", name).await?;
     if !deleted {
         return Err(AppError::not_found("secret_not_found", "Secret not found"));
     }
+    webdav_rotation::discard_pending(&state.db, node_id, name).await?;
     Ok(())
 }
 
-async fn maybe_send_node_config_snapshot(state: &AppState, node_id: &str) {
+/// Stages a replacement credential; the active one stays in use until a run verifies it.
+async fn stage_webdav_pending_for_node(
+    state: &AppState,
+    node_id: &str,
+    name: &str,
+    req: UpsertWebdavSecretRequest,
+) -> Result<(), AppError> {
+    let name = validate_webdav_secret_name(name)?;
+    let username = validate_webdav_secret_username(&req.username)?;
+    if !secrets_repo::secret_exists(&state.db, node_id, "webdav", name).await? {
+        return Err(AppError::not_found("secret_not_found", "Secret not found"));
+    }
+
+    webdav_rotation::stage_pending(
+        &state.db,
+        &state.secrets,
+        node_id,
+        name,
+        username,
+        &req.password,
+    )
+    .await?;
+    tracing::info!(
+        node_id = %node_id,
+        secret_kind = "webdav",
+        secret_name = %name,
+        "pending secret staged"
+    );
+    maybe_send_node_snapshots(state, node_id).await;
+    Ok(())
+}
+
+async fn promote_webdav_pending_for_node(
+    state: &AppState,
+    node_id: &str,
+    name: &str,
+) -> Result<(), AppError> {
+    let name = name.trim();
+    let promoted =
+        webdav_rotation::promote_pending(&state.db, &state.secrets, node_id, name, None).await?;
+    if !promoted {
+        return Err(AppError::not_found(
+            "pending_secret_not_found",
+            "No pending credential is staged",
+        ));
+    }
+    tracing::info!(
+        node_id = %node_id,
+        secret_kind = "webdav",
+        secret_name = %name,
+        "pending secret promoted"
+    );
+    maybe_send_node_snapshots(state, node_id).await;
+    Ok(())
+}
+
+async fn discard_webdav_pending_for_node(
+    state: &AppState,
+    node_id: &str,
+    name: &str,
+) -> Result<(), AppError> {
+    let name = name.trim();
+    if !webdav_rotation::discard_pending(&state.db, node_id, name).await? {
+        return Err(AppError::not_found(
+            "pending_secret_not_found",
+            "No pending credential is staged",
+        ));
+    }
+    tracing::info!(
+        node_id = %node_id,
+        secret_kind = "webdav",
+        secret_name = %name,
+        "pending secret discarded"
+    );
+    maybe_send_node_snapshots(state, node_id).await;
+    Ok(())
+}
+
+/// Pushes both snapshots: the secrets snapshot carries the pending credential and the config
+/// snapshot carries the resolved job targets the agent runs with.
+pub(super) async fn maybe_send_node_snapshots(state: &AppState, node_id: &str) {
+    if node_id == HUB_NODE_ID {
+        return;
+    }
+
+    if let Err(error) = send_node_secrets_snapshot(
+        &state.db,
+        state.secrets.as_ref(),
+        &state.agent_manager,
+        node_id,
+    )
+    .await
+    {
+        tracing::warn!(
+            node_id = %node_id,
+            error = %error,
+            "failed to send agent secrets snapshot"
+        );
+    }
+    maybe_send_node_config_snapshot(state, node_id).await;
+}
+
+pub(super) async fn maybe_send_node_config_snapshot(state: &AppState, node_id: &str) {
     if node_id == HUB_NODE_ID {
         return;
     }
@@ -1,17 +1,22 @@
 use axum::Json;
-use axum::extract::Path;
+use axum::extract::{Path, Query};
 use axum::http::{HeaderMap, StatusCode};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 use tower_cookies::Cookies;
 
 use bastion_core::HUB_NODE_ID;
+use bastion_notify::NotificationChannel as _;
+use bastion_notify::webhook::WebhookDestination;
+use bastion_notify::wecom::WecomBotChannel;
 use bastion_storage::notifications_repo;
 use bastion_storage::secrets_repo;
 
+use super::super::notifications::destination_error;
 use super::super::shared::{require_csrf, require_session};
 use super::super::{AppError, AppState};
 use super::SecretListItem;
+use super::references::{DeleteSecretQuery, KIND_WECOM_BOT, ensure_secret_unreferenced};
 
 pub(in crate::http) async fn list_wecom_bot_secrets(
     state: axum::extract::State<AppState>,
@@ -41,11 +46,6 @@ pub(in crate::http) struct WecomBotSecretResponse {
     webhook_url: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct WecomBotSecretPayload {
-    webhook_url: String,
-}
-
 pub(in crate::http) async fn upsert_wecom_bot_secret(
     state: axum::extract::State<AppState>,
     cookies: Cookies,
@@ -64,31 +64,9 @@ pub(in crate::http) async fn upsert_wecom_bot_secret(
         );
     }
 
-    let webhook_url = req.webhook_url.trim();
-    if webhook_url.is_empty() {
-        return Err(
-            AppError::bad_request("invalid_webhook_url", "Webhook URL is required")
-                .with_reason("required")
-                .with_field("webhook_url"),
-        );
-    }
-    let url = url::Url::parse(webhook_url).map_err(|_| {
-        AppError::bad_request("invalid_webhook_url", "Webhook URL is invalid")
-            .with_reason("invalid_format")
-            .with_field("webhook_url")
-    })?;
-    if !matches!(url.scheme(), "http" | "https") {
-        return Err(
-            AppError::bad_request("invalid_webhook_url", "Webhook URL must be http(s)")
-                .with_reason("invalid_scheme")
-                .with_field("webhook_url")
-                .with_param("scheme", url.scheme()),
-        );
-    }
-
-    let payload = WecomBotSecretPayload {
-        webhook_url: webhook_url.to_string(),
-    };
+    let payload = WecomBotChannel
+        .validate_destination(serde_json::json!({ "webhook_url": req.webhook_url }))
+        .map_err(destination_error)?;
     let bytes = serde_json::to_vec(&payload)?;
 
     secrets_repo::upsert_secret(
@@ -120,7 +98,7 @@ pub(in crate::http) async fn get_wecom_bot_secret(
             .await?
             .ok_or_else(|| AppError::not_found("secret_not_found", "Secret not found"))?;
 
-    let payload: WecomBotSecretPayload = serde_json::from_slice(&bytes)?;
+    let payload: WebhookDestination = serde_json::from_slice(&bytes)?;
     Ok(Json(WecomBotSecretResponse {
         name,
         webhook_url: payload.webhook_url,
@@ -132,10 +110,12 @@ pub(in crate::http) async fn delete_wecom_bot_secret(
     cookies: Cookies,
     headers: HeaderMap,
     Path(name): Path<String>,
+    Query(query): Query<DeleteSecretQuery>,
 ) -> Result<StatusCode, AppError> {
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
 
+    ensure_secret_unreferenced(&state, HUB_NODE_ID, KIND_WECOM_BOT, &name, query.force).await?;
     let deleted = secrets_repo::delete_secret(&state.db, HUB_NODE_ID, "wecom_bot", &name).await?;
     if !deleted {
         return Err(AppError::not_found("secret_not_found", "Secret not found"));
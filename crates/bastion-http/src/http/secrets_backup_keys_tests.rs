@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn backup_passphrase_keys_validate_and_reject_name_conflicts() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+    bastion_backup::backup_encryption::ensure_age_identity(&pool, &secrets, "default")
+        .await
+        .expect("hub-managed identity");
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+
+    let resp = client
+        .post(format!(
+            "{}/api/secrets/backup-passphrase-keys/offline",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({ "passphrase": "short" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_passphrase");
+
+    // A hub-managed identity already uses this name.
+    let resp = client
+        .post(format!(
+            "{}/api/secrets/backup-passphrase-keys/default",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({ "passphrase": "correct horse battery" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "backup_key_exists");
+
+    let resp = client
+        .get(format!(
+            "{}/api/secrets/backup-passphrase-keys/offline",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = client
+        .get(format!(
+            "{}/api/secrets/backup-passphrase-keys",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body, serde_json::json!([]));
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn rotate_backup_key_replaces_recipient_and_starts_reencrypt() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+    bastion_backup::backup_encryption::ensure_age_identity(&pool, &secrets, "default")
+        .await
+        .expect("hub-managed identity");
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets: secrets.clone(),
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+
+    let resp = client
+        .post(format!(
+            "{}/api/secrets/backup-keys/missing/rotate",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let resp = client
+        .post(format!(
+            "{}/api/secrets/backup-keys/default/rotate",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({ "reencrypt": true }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_ne!(body["recipient"], body["previous_recipient"]);
+    assert_eq!(body["runs"], 0);
+    let op_id = body["op_id"].as_str().expect("op_id").to_string();
+
+    // Old snapshots stay decryptable through the retired identity.
+    let identities = bastion_backup::backup_encryption::list_age_identities(
+        &pool,
+        &secrets,
+        "default",
+        body["previous_recipient"].as_str(),
+    )
+    .await
+    .expect("identities");
+    assert_eq!(identities.len(), 1);
+
+    let mut status = serde_json::Value::Null;
+    for _ in 0..50 {
+        let resp = client
+            .get(format!("{}/api/operations/{}", base_url(addr), op_id))
+            .header("cookie", &cookie)
+            .send()
+            .await
+            .expect("request");
+        let body: serde_json::Value = resp.json().await.expect("json");
+        status = body["status"].clone();
+        if status != "running" {
+            assert_eq!(body["kind"], "reencrypt");
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    assert_eq!(status, "success");
+
+    server.abort();
+}
@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::{ExternalSecretProviders, SecretsCrypto};
+use bastion_storage::{auth, db, secrets_repo};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+/// Minimal Vault KV v2 stand-in: one secret and a token lookup endpoint.
+async fn start_fake_vault() -> (std::net::SocketAddr, tokio::task::JoinHandle<()>) {
+    use axum::routing::get;
+
+    let app = axum::Router::new()
+        .route(
+            "/v1/secret/data/smtp",
+            get(|headers: axum::http::HeaderMap| async move {
+                if headers.get("x-vault-token").and_then(|v| v.to_str().ok()) != Some("root") {
+                    return Err(StatusCode::FORBIDDEN);
+                }
+                Ok(axum::Json(serde_json::json!({
+                    "data": {
+                        "data": { "password": "from-vault" },
+                        "metadata": { "version": 1 }
+                    }
+                })))
+            }),
+        )
+        .route(
+            "/v1/auth/token/lookup-self",
+            get(|| async { axum::Json(serde_json::json!({ "data": {} })) }),
+        );
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("serve vault");
+    });
+    (addr, server)
+}
+
+#[tokio::test]
+async fn external_secret_references_are_validated_and_resolved_through_the_provider() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_first_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    auth::create_user(&pool, "bob", &user_password)
+        .await
+        .expect("create bob");
+    let bob = auth::find_user_by_username(&pool, "bob")
+        .await
+        .expect("find bob")
+        .expect("bob exists");
+    let bob_session = auth::create_session(&pool, bob.id)
+        .await
+        .expect("create bob session");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let (vault_addr, vault) = start_fake_vault().await;
+    let vault_url = base_url(vault_addr);
+    let providers = ExternalSecretProviders::from_lookup(|key| match key {
+        "VAULT_ADDR" => Some(vault_url.clone()),
+        "VAULT_TOKEN" => Some("root".to_string()),
+        _ => None,
+    });
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(
+        SecretsCrypto::load_or_create(&config.data_dir)
+            .expect("secrets")
+            .with_external_providers(providers),
+    );
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets: secrets.clone(),
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+
+    let resp = client
+        .get(format!("{}/api/secrets/providers/health", base_url(addr)))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["enabled"], true);
+    assert_eq!(body["providers"][0]["provider"], "vault");
+    assert_eq!(body["providers"][0]["ok"], true);
+    assert_eq!(body["providers"][1]["provider"], "aws_secrets_manager");
+    assert_eq!(body["providers"][1]["configured"], false);
+
+    // Only admins may point secrets at the Hub's provider credentials.
+    let resp = client
+        .put(format!(
+            "{}/api/secrets/external/hub/smtp/ops",
+            base_url(addr)
+        ))
+        .header("cookie", format!("bastion_session={}", bob_session.id))
+        .header("x-csrf-token", bob_session.csrf_token.clone())
+        .json(&serde_json::json!({ "provider": "vault", "path": "secret/data/smtp" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    // Non-KV Vault paths are rejected before the provider is asked.
+    let resp = client
+        .put(format!(
+            "{}/api/secrets/external/hub/smtp/ops",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({
+            "provider": "vault",
+            "path": "auth/token/lookup-self",
+            "field": "id"
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_path");
+
+    // Unknown kinds and unconfigured providers are rejected before anything is stored.
+    let resp = client
+        .put(format!(
+            "{}/api/secrets/external/hub/ssh/ops",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({ "provider": "vault", "path": "secret/data/smtp" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_kind");
+
+    let resp = client
+        .put(format!(
+            "{}/api/secrets/external/hub/smtp/ops",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({ "provider": "aws_secrets_manager", "path": "prod/smtp" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "external_provider_not_configured");
+
+    let resp = client
+        .put(format!(
+            "{}/api/secrets/external/hub/smtp/ops",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({ "provider": "vault", "path": "secret/data/missing" }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "external_secret_unresolvable");
+
+    let resp = client
+        .put(format!(
+            "{}/api/secrets/external/hub/smtp/ops",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({
+            "provider": "vault",
+            "path": "secret/data/smtp",
+            "field": "password"
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+    let resp = client
+        .get(format!(
+            "{}/api/secrets/external/hub/smtp/ops",
+            base_url(addr)
+        ))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["external_ref"]["path"], "secret/data/smtp");
+    assert_eq!(body["external_ref"]["field"], "password");
+
+    let value = secrets_repo::get_secret(&pool, &secrets, "hub", "smtp", "ops")
+        .await
+        .expect("get secret")
+        .expect("present");
+    assert_eq!(value, b"from-vault");
+
+    server.abort();
+    vault.abort();
+}
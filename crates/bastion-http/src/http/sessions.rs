@@ -0,0 +1,107 @@
+use axum::Extension;
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use serde::Serialize;
+use tower_cookies::Cookies;
+
+use super::shared::{self, require_csrf, require_session};
+use super::{AppError, AppState};
+use bastion_storage::{audit_repo, auth};
+
+#[derive(Debug, Serialize)]
+pub(super) struct SessionListItem {
+    #[serde(flatten)]
+    session: auth::SessionInfo,
+    /// Whether this is the session making the request.
+    current: bool,
+}
+
+pub(super) async fn list_sessions(
+    state: State<AppState>,
+    cookies: Cookies,
+) -> Result<Json<Vec<SessionListItem>>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let sessions = auth::list_user_sessions(&state.db, session.user_id, now).await?;
+    Ok(Json(
+        sessions
+            .into_iter()
+            .map(|s| SessionListItem {
+                current: session.public_id.as_deref() == Some(s.id.as_str()),
+                session: s,
+            })
+            .collect(),
+    ))
+}
+
+pub(super) async fn revoke_session(
+    state: State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<shared::ClientConnInfo>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    if !auth::delete_user_session(&state.db, session.user_id, &id).await? {
+        return Err(AppError::not_found(
+            "session_not_found",
+            "Session not found",
+        ));
+    }
+
+    audit_revoked(&state, &client, session.user_id, &id).await?;
+    tracing::info!(user_id = session.user_id, session = %id, "session revoked");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct RevokeOthersResponse {
+    revoked: u64,
+}
+
+/// Signs out every other browser session of the caller. API tokens are not affected.
+pub(super) async fn revoke_other_sessions(
+    state: State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    Extension(client): Extension<shared::ClientConnInfo>,
+) -> Result<Json<RevokeOthersResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_csrf(&headers, &session)?;
+
+    let revoked = auth::delete_other_user_sessions(&state.db, session.user_id, &session.id).await?;
+    if revoked > 0 {
+        audit_revoked(
+            &state,
+            &client,
+            session.user_id,
+            &format!("others:{revoked}"),
+        )
+        .await?;
+    }
+    tracing::info!(user_id = session.user_id, revoked, "other sessions revoked");
+    Ok(Json(RevokeOthersResponse { revoked }))
+}
+
+async fn audit_revoked(
+    state: &AppState,
+    client: &shared::ClientConnInfo,
+    user_id: i64,
+    detail: &str,
+) -> Result<(), anyhow::Error> {
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let client_ip = client.ip.to_string();
+    audit_repo::append(
+        &state.db,
+        now,
+        audit_repo::ACTION_SESSIONS_REVOKED,
+        Some(user_id),
+        Some(&client_ip),
+        Some(detail),
+    )
+    .await?;
+    Ok(())
+}
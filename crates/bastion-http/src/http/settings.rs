@@ -4,7 +4,7 @@ use axum::http::{HeaderMap, StatusCode};
 use serde::Serialize;
 use tower_cookies::Cookies;
 
-use bastion_storage::hub_runtime_config_repo;
+use bastion_storage::{hub_runtime_config_repo, job_access_repo};
 
 use super::shared::{require_csrf, require_session};
 use super::{AppError, AppState, ConfigValueSource, normalize_public_base_url};
@@ -67,7 +67,10 @@ pub(in crate::http) struct HubRuntimeConfigGetResponse {
 fn is_overridden(source: ConfigValueSource) -> bool {
     matches!(
         source,
-        ConfigValueSource::Cli | ConfigValueSource::Env | ConfigValueSource::EnvRustLog
+        ConfigValueSource::Cli
+            | ConfigValueSource::Env
+            | ConfigValueSource::EnvRustLog
+            | ConfigValueSource::File
     )
 }
 
@@ -85,7 +88,17 @@ pub(in crate::http) async fn get_hub_runtime_config(
         .await?
         .unwrap_or_default();
 
-    let sources = &state.hub_runtime_config.sources;
+    let reloaded = &state.hub_runtime_config.reloaded;
+    let reloaded_trusted_proxies = reloaded.trusted_proxies();
+    let reloaded_log_filter = reloaded.log_filter();
+
+    let mut sources = state.hub_runtime_config.sources.clone();
+    if let Some((_, source)) = &reloaded_trusted_proxies {
+        sources.trusted_proxies = *source;
+    }
+    if let Some((_, source)) = &reloaded_log_filter {
+        sources.log_filter = *source;
+    }
 
     let fields = HubRuntimeConfigFieldsMeta {
         bind_host: HubRuntimeConfigFieldMeta {
@@ -162,12 +175,15 @@ pub(in crate::http) async fn get_hub_runtime_config(
 
     let bind_host = state.config.bind.ip().to_string();
     let bind_port = state.config.bind.port();
-    let trusted_proxies = state
-        .config
-        .trusted_proxies
-        .iter()
-        .map(ToString::to_string)
-        .collect::<Vec<_>>();
+    let trusted_proxies = match &reloaded_trusted_proxies {
+        Some((proxies, _)) => proxies.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        None => state
+            .config
+            .trusted_proxies
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+    };
 
     let effective = HubRuntimeConfigEffective {
         bind_host,
@@ -180,7 +196,9 @@ pub(in crate::http) async fn get_hub_runtime_config(
         run_retention_days: state.config.run_retention_days,
         incomplete_cleanup_days: state.config.incomplete_cleanup_days,
         public_base_url: state.hub_runtime_config.public_base_url.clone(),
-        log_filter: state.hub_runtime_config.logging.filter.clone(),
+        log_filter: reloaded_log_filter
+            .map(|(filter, _)| filter)
+            .unwrap_or_else(|| state.hub_runtime_config.logging.filter.clone()),
         log_file: state.hub_runtime_config.logging.file.clone(),
         log_rotation: state.hub_runtime_config.logging.rotation.clone(),
         log_keep_files: state.hub_runtime_config.logging.keep_files,
@@ -236,6 +254,21 @@ pub(in crate::http) async fn put_hub_runtime_config(
     let session = require_session(&state, &cookies).await?;
     require_csrf(&headers, &session)?;
 
+    // The approval gate guards restores against a single compromised account, so only an admin
+    // may turn it on or off.
+    let saved_approval_required = hub_runtime_config_repo::get(&state.db)
+        .await?
+        .is_some_and(|saved| saved.restore_approval_required);
+    if req.restore_approval_required != saved_approval_required
+        && !job_access_repo::is_admin(&state.db, session.user_id).await?
+    {
+        return Err(AppError::forbidden(
+            "admin_required",
+            "Only admins can change restore approval",
+        )
+        .with_field("restore_approval_required"));
+    }
+
     if let Some(v) = req.run_retention_days
         && v <= 0
     {
@@ -342,6 +375,110 @@ pub(in crate::http) async fn put_hub_runtime_config(
         }
     }
 
+    {
+        const MAX_SCHEDULE_STAGGER_SECONDS: u32 = 3600;
+
+        if let Some(v) = req.schedule_stagger_seconds
+            && v > MAX_SCHEDULE_STAGGER_SECONDS
+        {
+            return Err(AppError::bad_request(
+                "invalid_schedule_stagger_seconds",
+                format!("schedule_stagger_seconds must be <= {MAX_SCHEDULE_STAGGER_SECONDS}"),
+            )
+            .with_reason("max_exceeded")
+            .with_field("schedule_stagger_seconds")
+            .with_param("max", MAX_SCHEDULE_STAGGER_SECONDS));
+        }
+    }
+
+    {
+        const MAX_ARTIFACT_RETENTION_DAYS: u32 = 36_500;
+
+        if let Some(v) = req.artifact_retention_days
+            && (v == 0 || v > MAX_ARTIFACT_RETENTION_DAYS)
+        {
+            return Err(AppError::bad_request(
+                "invalid_artifact_retention_days",
+                format!("artifact_retention_days must be within 1..={MAX_ARTIFACT_RETENTION_DAYS}"),
+            )
+            .with_reason("out_of_range")
+            .with_field("artifact_retention_days")
+            .with_param("min", 1)
+            .with_param("max", MAX_ARTIFACT_RETENTION_DAYS));
+        }
+    }
+
+    {
+        const MAX_EVENT_RETENTION_DAYS: u32 = 36_500;
+
+        if let Some(v) = req.event_retention_days
+            && (v == 0 || v > MAX_EVENT_RETENTION_DAYS)
+        {
+            return Err(AppError::bad_request(
+                "invalid_event_retention_days",
+                format!("event_retention_days must be within 1..={MAX_EVENT_RETENTION_DAYS}"),
+            )
+            .with_reason("out_of_range")
+            .with_field("event_retention_days")
+            .with_param("min", 1)
+            .with_param("max", MAX_EVENT_RETENTION_DAYS));
+        }
+    }
+
+    {
+        const MIN_EVENTS_PER_RUN: u32 = 100;
+        const MAX_EVENTS_PER_RUN: u32 = 1_000_000;
+
+        if let Some(v) = req.max_events_per_run
+            && !(MIN_EVENTS_PER_RUN..=MAX_EVENTS_PER_RUN).contains(&v)
+        {
+            return Err(AppError::bad_request(
+                "invalid_max_events_per_run",
+                format!(
+                    "max_events_per_run must be within {MIN_EVENTS_PER_RUN}..={MAX_EVENTS_PER_RUN}"
+                ),
+            )
+            .with_reason("out_of_range")
+            .with_field("max_events_per_run")
+            .with_param("min", MIN_EVENTS_PER_RUN)
+            .with_param("max", MAX_EVENTS_PER_RUN));
+        }
+    }
+
+    {
+        const MAX_SESSION_MAX_AGE_HOURS: u32 = 24 * 365;
+        const MIN_SESSION_IDLE_TIMEOUT_MINUTES: u32 = 5;
+        const MAX_SESSION_IDLE_TIMEOUT_MINUTES: u32 = 60 * 24 * 30;
+
+        if let Some(v) = req.session_max_age_hours
+            && (v == 0 || v > MAX_SESSION_MAX_AGE_HOURS)
+        {
+            return Err(AppError::bad_request(
+                "invalid_session_max_age_hours",
+                format!("session_max_age_hours must be within 1..={MAX_SESSION_MAX_AGE_HOURS}"),
+            )
+            .with_reason("out_of_range")
+            .with_field("session_max_age_hours")
+            .with_param("min", 1)
+            .with_param("max", MAX_SESSION_MAX_AGE_HOURS));
+        }
+
+        if let Some(v) = req.session_idle_timeout_minutes
+            && !(MIN_SESSION_IDLE_TIMEOUT_MINUTES..=MAX_SESSION_IDLE_TIMEOUT_MINUTES).contains(&v)
+        {
+            return Err(AppError::bad_request(
+                "invalid_session_idle_timeout_minutes",
+                format!(
+                    "session_idle_timeout_minutes must be within {MIN_SESSION_IDLE_TIMEOUT_MINUTES}..={MAX_SESSION_IDLE_TIMEOUT_MINUTES}"
+                ),
+            )
+            .with_reason("out_of_range")
+            .with_field("session_idle_timeout_minutes")
+            .with_param("min", MIN_SESSION_IDLE_TIMEOUT_MINUTES)
+            .with_param("max", MAX_SESSION_IDLE_TIMEOUT_MINUTES));
+        }
+    }
+
     req.hub_timezone = validate_timezone(req.hub_timezone.as_deref())?;
     req.public_base_url =
         normalize_public_base_url(req.public_base_url.as_deref()).map_err(|reason| {
@@ -4,6 +4,7 @@ use tower_cookies::cookie::{Cookie, SameSite};
 
 use super::{AppError, AppState};
 use bastion_storage::auth;
+use bastion_storage::job_access_repo::{self, JobPermission};
 
 pub(in crate::http) const SESSION_COOKIE_NAME: &str = "bastion_session";
 pub(in crate::http) const LOCALE_COOKIE_NAME: &str = "bastion_locale";
@@ -25,6 +26,15 @@ pub(in crate::http) async fn require_session(
     Ok(session)
 }
 
+/// Opaque per-session id sent with agent list requests so one session's browsing never
+/// supersedes another's. Derived from the session id without revealing it.
+pub(in crate::http) fn list_requester_id(session: &auth::SessionRow) -> String {
+    use sha2::Digest as _;
+
+    let digest = sha2::Sha256::digest(session.id.as_bytes());
+    hex::encode(&digest[..8])
+}
+
 pub(in crate::http) fn require_csrf(
     headers: &HeaderMap,
     session: &auth::SessionRow,
@@ -39,35 +49,202 @@ pub(in crate::http) fn require_csrf(
     Ok(())
 }
 
-pub(in crate::http) fn set_session_cookie(
+/// Checks the session's user may act on a job. Jobs they can't see answer 404 like missing ones.
+pub(in crate::http) async fn require_job_permission(
     state: &AppState,
-    headers: &HeaderMap,
-    peer_ip: std::net::IpAddr,
+    session: &auth::SessionRow,
+    job_id: &str,
+    needed: JobPermission,
+) -> Result<(), AppError> {
+    let permission = job_access_repo::permission_for(&state.db, job_id, session.user_id).await?;
+    check_permission(permission, needed)
+        .map_err(|e| e.unwrap_or_else(|| AppError::not_found("job_not_found", "Job not found")))
+}
+
+/// Like [`require_job_permission`], for the job a run belongs to.
+pub(in crate::http) async fn require_run_permission(
+    state: &AppState,
+    session: &auth::SessionRow,
+    run_id: &str,
+    needed: JobPermission,
+) -> Result<(), AppError> {
+    let permission =
+        job_access_repo::permission_for_run(&state.db, run_id, session.user_id).await?;
+    check_permission(permission, needed)
+        .map_err(|e| e.unwrap_or_else(|| AppError::not_found("run_not_found", "Run not found")))
+}
+
+/// Like [`require_job_permission`], for the job or run an operation was started on.
+pub(in crate::http) async fn require_operation_permission(
+    state: &AppState,
+    session: &auth::SessionRow,
+    op_id: &str,
+    needed: JobPermission,
+) -> Result<(), AppError> {
+    let permission =
+        job_access_repo::permission_for_operation(&state.db, op_id, session.user_id).await?;
+    check_permission(permission, needed).map_err(|e| {
+        e.unwrap_or_else(|| AppError::not_found("operation_not_found", "Operation not found"))
+    })
+}
+
+/// `Err(None)` when the job isn't visible at all.
+fn check_permission(
+    permission: Option<JobPermission>,
+    needed: JobPermission,
+) -> Result<(), Option<AppError>> {
+    match permission {
+        None => Err(None),
+        Some(p) if p < needed => Err(Some(
+            AppError::forbidden("job_permission_required", "Not allowed for this job")
+                .with_param("required", needed.as_str()),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Re-checks the session user's login password before a sensitive action.
+///
+/// Failures count towards the same per-IP throttle as the login endpoint.
+pub(in crate::http) async fn reauthenticate(
+    state: &AppState,
+    session: &auth::SessionRow,
+    client_ip: &str,
+    current_password: &str,
+    now: i64,
+) -> Result<(), AppError> {
+    if let Some(retry_after) =
+        auth::login_throttle_retry_after_seconds(&state.db, client_ip, now).await?
+    {
+        return Err(AppError::too_many_requests(
+            "rate_limited",
+            format!("Too many attempts. Retry after {retry_after}s."),
+        )
+        .with_reason("throttled")
+        .with_param("retry_after_seconds", retry_after));
+    }
+
+    let user = auth::find_user_by_id(&state.db, session.user_id).await?;
+    let verified = match &user {
+        Some(user) => auth::verify_password(&user.password_hash, current_password)?,
+        None => false,
+    };
+    if !verified {
+        let _ = auth::record_login_failure(&state.db, client_ip, now).await;
+        tracing::warn!(client_ip = %client_ip, user_id = session.user_id, "re-authentication failed");
+        // 403 rather than 401: the session itself is still valid.
+        return Err(
+            AppError::forbidden("invalid_credentials", "Current password is incorrect")
+                .with_field("current_password"),
+        );
+    }
+    let _ = auth::clear_login_throttle(&state.db, client_ip).await;
+    Ok(())
+}
+
+/// The user whose job visibility lists must be limited to; `None` for admins.
+pub(in crate::http) async fn job_list_viewer(
+    state: &AppState,
+    session: &auth::SessionRow,
+) -> Result<Option<i64>, AppError> {
+    if job_access_repo::is_admin(&state.db, session.user_id).await? {
+        return Ok(None);
+    }
+    Ok(Some(session.user_id))
+}
+
+/// Ids of the jobs the session's user can see; `None` for admins, who see every job.
+pub(in crate::http) async fn visible_job_ids(
+    state: &AppState,
+    session: &auth::SessionRow,
+) -> Result<Option<std::collections::HashSet<String>>, AppError> {
+    match job_list_viewer(state, session).await? {
+        Some(user_id) => Ok(Some(
+            job_access_repo::visible_job_ids(&state.db, user_id).await?,
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Where a request came from, resolved once per request by the client info middleware.
+/// `X-Forwarded-*` headers are only honored when the peer is a trusted proxy.
+#[derive(Debug, Clone)]
+pub(in crate::http) struct ClientConnInfo {
+    /// Address of the TCP peer (the proxy, when there is one).
+    pub(in crate::http) peer_ip: std::net::IpAddr,
+    /// Real client IP: the first untrusted hop of `X-Forwarded-For`, or the peer.
+    pub(in crate::http) ip: std::net::IpAddr,
+    pub(in crate::http) trusted_proxy: bool,
+    /// Whether the client reached the Hub over HTTPS.
+    pub(in crate::http) https: bool,
+    /// First `X-Forwarded-Proto` value, normalized to `http`/`https`.
+    pub(in crate::http) forwarded_proto: Option<String>,
+    /// First `X-Forwarded-Host` value.
+    pub(in crate::http) forwarded_host: Option<String>,
+    /// Client certificate verified by the Hub's own TLS listener.
+    pub(in crate::http) tls_peer_cert: Option<Vec<u8>>,
+}
+
+impl ClientConnInfo {
+    pub(in crate::http) fn resolve(
+        state: &AppState,
+        headers: &HeaderMap,
+        peer_ip: std::net::IpAddr,
+    ) -> Self {
+        let trusted_proxy = is_trusted_proxy(state, peer_ip);
+        let forwarded = |name: &str| {
+            if !trusted_proxy {
+                return None;
+            }
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.split(",").next())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+
+        Self {
+            peer_ip,
+            ip: effective_client_ip_from_forwarded(headers, peer_ip, |ip| {
+                is_trusted_proxy(state, ip)
+            }),
+            trusted_proxy,
+            https: request_is_https(state, headers, trusted_proxy),
+            forwarded_proto: forwarded("x-forwarded-proto")
+                .and_then(|v| normalize_origin_scheme(&v)),
+            forwarded_host: forwarded("x-forwarded-host"),
+            tls_peer_cert: None,
+        }
+    }
+}
+
+pub(in crate::http) fn set_session_cookie(
+    client: &ClientConnInfo,
     cookies: &Cookies,
     session_id: &str,
 ) -> Result<(), anyhow::Error> {
-    let is_secure = request_is_https(state, headers, peer_ip);
-
     let mut cookie = Cookie::new(SESSION_COOKIE_NAME, session_id.to_string());
     cookie.set_http_only(true);
     cookie.set_same_site(SameSite::Lax);
     cookie.set_path("/");
-    cookie.set_secure(is_secure);
+    cookie.set_secure(client.https);
 
     cookies.add(cookie);
     Ok(())
 }
 
-pub(in crate::http) fn request_is_https(
-    state: &AppState,
-    headers: &HeaderMap,
-    peer_ip: std::net::IpAddr,
-) -> bool {
+fn request_is_https(state: &AppState, headers: &HeaderMap, trusted_proxy: bool) -> bool {
+    if state.hub_runtime_config.tls_enabled {
+        return true;
+    }
+
     if state.config.insecure_http {
         return false;
     }
 
-    if !is_trusted_proxy(state, peer_ip) {
+    if !trusted_proxy {
         return false;
     }
 
@@ -81,7 +258,10 @@ pub(in crate::http) fn request_is_https(
     proto.eq_ignore_ascii_case("https")
 }
 
-pub(in crate::http) fn is_trusted_proxy(state: &AppState, peer_ip: std::net::IpAddr) -> bool {
+fn is_trusted_proxy(state: &AppState, peer_ip: std::net::IpAddr) -> bool {
+    if let Some(trusted) = state.hub_runtime_config.reloaded.trusts_proxy(peer_ip) {
+        return trusted;
+    }
     state
         .config
         .trusted_proxies
@@ -130,25 +310,163 @@ fn effective_client_ip_from_forwarded(
     peer_ip
 }
 
-pub(in crate::http) fn effective_client_ip(
-    state: &AppState,
+/// Rejects WebSocket upgrades whose `Origin` does not match the (proxy-aware) request origin.
+pub(in crate::http) fn require_ws_same_origin(
+    client: &ClientConnInfo,
     headers: &HeaderMap,
-    peer_ip: std::net::IpAddr,
-) -> std::net::IpAddr {
-    effective_client_ip_from_forwarded(headers, peer_ip, |ip| {
-        state
-            .config
-            .trusted_proxies
-            .iter()
-            .any(|net| net.contains(&ip))
-    })
+) -> Result<(), AppError> {
+    let origin = headers
+        .get(axum::http::header::ORIGIN)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| AppError::unauthorized("invalid_origin", "Invalid origin"))?;
+
+    let origin = match url::Url::parse(origin) {
+        Ok(url) => url,
+        Err(_) => return Err(AppError::unauthorized("invalid_origin", "Invalid origin")),
+    };
+
+    let expected_scheme = client.forwarded_proto.clone().unwrap_or_else(|| {
+        if client.https {
+            "https".to_string()
+        } else {
+            "http".to_string()
+        }
+    });
+
+    let expected_authority_raw = client
+        .forwarded_host
+        .as_deref()
+        .or_else(|| {
+            headers
+                .get(axum::http::header::HOST)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        })
+        .ok_or_else(|| AppError::unauthorized("invalid_origin", "Invalid origin"))?;
+
+    let expected_authority = expected_authority_raw
+        .parse::<axum::http::uri::Authority>()
+        .map_err(|_| AppError::unauthorized("invalid_origin", "Invalid origin"))?;
+
+    let expected_host = expected_authority.host().to_ascii_lowercase();
+    let expected_port = expected_authority
+        .port_u16()
+        .unwrap_or_else(|| default_port_for_scheme(&expected_scheme));
+
+    let origin_scheme = normalize_origin_scheme(origin.scheme())
+        .ok_or_else(|| AppError::unauthorized("invalid_origin", "Invalid origin"))?;
+    let origin_host = origin
+        .host_str()
+        .map(|v| v.to_ascii_lowercase())
+        .ok_or_else(|| AppError::unauthorized("invalid_origin", "Invalid origin"))?;
+    let origin_port = origin
+        .port_or_known_default()
+        .unwrap_or_else(|| default_port_for_scheme(&origin_scheme));
+
+    if origin_scheme != expected_scheme
+        || origin_host != expected_host
+        || origin_port != expected_port
+    {
+        return Err(AppError::unauthorized("invalid_origin", "Invalid origin"));
+    }
+
+    Ok(())
+}
+
+fn normalize_origin_scheme(raw: &str) -> Option<String> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "http" | "ws" => Some("http".to_string()),
+        "https" | "wss" => Some("https".to_string()),
+        _ => None,
+    }
+}
+
+fn default_port_for_scheme(scheme: &str) -> u16 {
+    if scheme.eq_ignore_ascii_case("https") {
+        443
+    } else {
+        80
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use axum::http::{HeaderMap, HeaderValue};
 
-    use super::effective_client_ip_from_forwarded;
+    use super::{ClientConnInfo, effective_client_ip_from_forwarded};
+
+    async fn state_trusting(temp: &tempfile::TempDir, proxy: &str) -> crate::http::AppState {
+        use std::sync::Arc;
+
+        let pool = bastion_storage::db::init(temp.path())
+            .await
+            .expect("db init");
+        let config = Arc::new(bastion_config::Config {
+            bind: "127.0.0.1:0".parse().expect("bind"),
+            data_dir: temp.path().to_path_buf(),
+            insecure_http: false,
+            debug_errors: false,
+            hub_timezone: "UTC".to_string(),
+            run_retention_days: 180,
+            incomplete_cleanup_days: 7,
+            staging_dir: None,
+            staging_min_free_bytes: 0,
+            ui_dev_proxy: None,
+            api_rate_limit_per_ip: 0,
+            api_rate_limit_per_session: 0,
+            trusted_proxies: vec![proxy.parse().expect("proxy")],
+        });
+        let secrets = Arc::new(
+            bastion_storage::secrets::SecretsCrypto::load_or_create(&config.data_dir)
+                .expect("secrets"),
+        );
+        crate::http::AppState {
+            config,
+            db: pool,
+            secrets,
+            agent_manager: Default::default(),
+            run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+            incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+            artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+            jobs_notify: Arc::new(tokio::sync::Notify::new()),
+            notifications_notify: Arc::new(tokio::sync::Notify::new()),
+            bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+            run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+            hub_runtime_config: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_conn_info_honors_forwarded_headers_only_from_trusted_proxies() {
+        let temp = tempfile::TempDir::new().expect("tempdir");
+        let state = state_trusting(&temp, "10.0.0.0/8").await;
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("203.0.113.7"));
+        headers.insert("x-forwarded-proto", HeaderValue::from_static("https"));
+        headers.insert(
+            "x-forwarded-host",
+            HeaderValue::from_static("hub.example.com, proxy.internal"),
+        );
+
+        let proxied = ClientConnInfo::resolve(&state, &headers, "10.0.0.10".parse().expect("peer"));
+        assert_eq!(
+            proxied.ip,
+            "203.0.113.7".parse::<std::net::IpAddr>().expect("ip")
+        );
+        assert!(proxied.trusted_proxy);
+        assert!(proxied.https);
+        assert_eq!(proxied.forwarded_proto.as_deref(), Some("https"));
+        assert_eq!(proxied.forwarded_host.as_deref(), Some("hub.example.com"));
+
+        let peer = "198.51.100.30".parse().expect("peer");
+        let direct = ClientConnInfo::resolve(&state, &headers, peer);
+        assert_eq!(direct.ip, peer);
+        assert!(!direct.trusted_proxy);
+        assert!(!direct.https);
+        assert_eq!(direct.forwarded_proto, None);
+        assert_eq!(direct.forwarded_host, None);
+    }
 
     #[test]
     fn forwarded_leftmost_spoof_is_ignored_with_trusted_hops() {
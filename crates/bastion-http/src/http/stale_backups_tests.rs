@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db, runs_repo};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn stale_backups_lists_jobs_past_their_success_interval() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(bastion_engine::run_events_bus::RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let cookie = format!("bastion_session={}", session.id);
+    let spec = serde_json::json!({
+        "v": 1,
+        "type": "filesystem",
+        "source": { "root": "/" },
+        "target": { "type": "local_dir", "base_dir": "/tmp" }
+    });
+
+    let resp = client
+        .get(format!("{}/api/alerts/stale-backups", base_url(addr)))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    let resp = client
+        .post(format!("{}/api/jobs", base_url(addr)))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({
+            "name": "too-lenient",
+            "max_success_interval_seconds": 365 * 24 * 3600,
+            "overlap_policy": "queue",
+            "spec": spec,
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    assert_eq!(body["error"], "invalid_max_success_interval");
+
+    let mut job_ids = Vec::new();
+    for name in ["nightly", "fresh"] {
+        let resp = client
+            .post(format!("{}/api/jobs", base_url(addr)))
+            .header("cookie", &cookie)
+            .header("x-csrf-token", session.csrf_token.clone())
+            .json(&serde_json::json!({
+                "name": name,
+                "schedule": "0 2 * * *",
+                "max_success_interval_seconds": 26 * 3600,
+                "overlap_policy": "queue",
+                "spec": spec,
+            }))
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let job: serde_json::Value = resp.json().await.expect("json");
+        assert_eq!(job["max_success_interval_seconds"], 26 * 3600);
+        job_ids.push(job["id"].as_str().expect("id").to_string());
+    }
+
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    // The last success of "nightly" is two days old; "fresh" succeeded an hour ago.
+    for (job_id, ended_at) in [(&job_ids[0], now - 48 * 3600), (&job_ids[1], now - 3600)] {
+        runs_repo::create_run(
+            &pool,
+            job_id,
+            runs_repo::RunStatus::Success,
+            ended_at - 60,
+            Some(ended_at),
+            None,
+            None,
+        )
+        .await
+        .expect("create run");
+    }
+
+    let resp = client
+        .get(format!("{}/api/alerts/stale-backups", base_url(addr)))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+    let items = body["items"].as_array().expect("items");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["job_id"].as_str(), Some(job_ids[0].as_str()));
+    assert_eq!(items[0]["job_name"].as_str(), Some("nightly"));
+    assert_eq!(items[0]["last_success_at"].as_i64(), Some(now - 48 * 3600));
+    assert!(items[0]["overdue_seconds"].as_i64().expect("overdue") >= 22 * 3600);
+
+    // Disabling the interval stops monitoring the job.
+    let resp = client
+        .put(format!("{}/api/jobs/{}", base_url(addr), job_ids[0]))
+        .header("cookie", &cookie)
+        .header("x-csrf-token", session.csrf_token.clone())
+        .json(&serde_json::json!({
+            "name": "nightly",
+            "schedule": "0 2 * * *",
+            "max_success_interval_seconds": 0,
+            "overlap_policy": "queue",
+            "spec": spec,
+        }))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let body: serde_json::Value = client
+        .get(format!("{}/api/alerts/stale-backups", base_url(addr)))
+        .header("cookie", &cookie)
+        .send()
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("json");
+    assert!(body["items"].as_array().expect("items").is_empty());
+
+    server.abort();
+}
@@ -0,0 +1,116 @@
+//! What a warm standby Hub serves while it follows the primary: health, its sync status, and
+//! `503 hub_standby` for everything else, so agents and load balancers stay on the primary.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+
+use bastion_engine::hub_standby::{self, StandbyState};
+
+use super::AppError;
+
+#[derive(Debug, Serialize)]
+struct StandbyStatusResponse {
+    standby: bool,
+    #[serde(flatten)]
+    state: StandbyState,
+}
+
+pub fn standby_router(data_dir: PathBuf) -> Router {
+    Router::new()
+        .route("/api/health", get(health))
+        .route("/api/ready", get(ready))
+        .route("/api/standby", get(status))
+        .fallback(unavailable)
+        .with_state(Arc::new(data_dir))
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "ok": true }))
+}
+
+/// Not ready: a standby doesn't serve the API.
+async fn ready() -> (StatusCode, Json<serde_json::Value>) {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(serde_json::json!({ "ok": false })),
+    )
+}
+
+async fn status(State(data_dir): State<Arc<PathBuf>>) -> Json<StandbyStatusResponse> {
+    Json(StandbyStatusResponse {
+        standby: true,
+        state: hub_standby::read_state(&data_dir).await.unwrap_or_default(),
+    })
+}
+
+async fn unavailable() -> Response {
+    AppError::service_unavailable(
+        "hub_standby",
+        "This Hub is a standby; use the primary or promote this one",
+    )
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt as _;
+
+    use bastion_engine::hub_standby::{StandbyState, write_state};
+
+    use super::standby_router;
+
+    #[tokio::test]
+    async fn standby_serves_status_and_refuses_everything_else() {
+        let temp = tempfile::TempDir::new().expect("tempdir");
+        write_state(
+            temp.path(),
+            &StandbyState {
+                primary_url: "https://primary.example.com/".to_string(),
+                last_sync_at: Some(1_700_000_000),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("state");
+
+        let call = |uri: &'static str| {
+            standby_router(temp.path().to_path_buf())
+                .oneshot(Request::get(uri).body(Body::empty()).expect("request"))
+        };
+
+        assert_eq!(
+            call("/api/health").await.expect("health").status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            call("/api/ready").await.expect("ready").status(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+
+        let resp = call("/api/standby").await.expect("status");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let body: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+        assert_eq!(body["standby"], true);
+        assert_eq!(body["last_sync_at"], 1_700_000_000);
+
+        let resp = call("/agent/ws").await.expect("agent ws");
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let body: serde_json::Value = serde_json::from_slice(&bytes).expect("json");
+        assert_eq!(body["error"], "hub_standby");
+    }
+}
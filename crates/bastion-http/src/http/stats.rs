@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+
+use axum::Json;
+use axum::extract::{Path, Query};
+use serde::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+use bastion_core::run_failure::RunErrorCode;
+use bastion_storage::job_access_repo::JobPermission;
+use bastion_storage::jobs_repo;
+use bastion_storage::run_stats_repo::{self, ErrorCodeRow, JobRunStatsRow, WeeklyBytesRow};
+
+use super::shared::{require_job_permission, require_session, visible_job_ids};
+use super::{AppError, AppState};
+
+#[derive(Debug, Default, Deserialize)]
+pub(in crate::http) struct RunStatsQuery {
+    #[serde(default)]
+    refresh: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(in crate::http) struct RunStatsSummary {
+    runs_total: u64,
+    runs_success: u64,
+    runs_failed: u64,
+    /// `success / (success + failed)`; `None` when no run finished in the window.
+    success_rate: Option<f64>,
+    avg_duration_secs: Option<f64>,
+    #[serde(skip)]
+    duration_total_secs: u64,
+    #[serde(skip)]
+    duration_runs: u64,
+}
+
+impl RunStatsSummary {
+    fn add(&mut self, row: &JobRunStatsRow) {
+        self.runs_total = self.runs_total.saturating_add(row.runs_total);
+        self.runs_success = self.runs_success.saturating_add(row.runs_success);
+        self.runs_failed = self.runs_failed.saturating_add(row.runs_failed);
+        self.duration_total_secs = self
+            .duration_total_secs
+            .saturating_add(row.duration_total_secs);
+        self.duration_runs = self.duration_runs.saturating_add(row.duration_runs);
+        self.derive();
+    }
+
+    fn derive(&mut self) {
+        let finished = self.runs_success.saturating_add(self.runs_failed);
+        self.success_rate = (finished > 0).then(|| self.runs_success as f64 / finished as f64);
+        self.avg_duration_secs = (self.duration_runs > 0)
+            .then(|| self.duration_total_secs as f64 / self.duration_runs as f64);
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct RunStatsWeek {
+    week_start: String,
+    runs: u64,
+    source_bytes: u64,
+    transfer_bytes: u64,
+}
+
+/// Failed runs in the window for one error code of the run failure catalog.
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct RunStatsErrorCode {
+    error_code: RunErrorCode,
+    runs: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct RunStatsJob {
+    job_id: String,
+    job_name: Option<String>,
+    last_ended_at: Option<i64>,
+    #[serde(flatten)]
+    summary: RunStatsSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct RunStatsResponse {
+    refreshed_at: Option<i64>,
+    window_days: i64,
+    totals: RunStatsSummary,
+    errors: Vec<RunStatsErrorCode>,
+    weekly: Vec<RunStatsWeek>,
+    jobs: Vec<RunStatsJob>,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct JobRunStatsResponse {
+    refreshed_at: Option<i64>,
+    window_days: i64,
+    #[serde(flatten)]
+    job: RunStatsJob,
+    errors: Vec<RunStatsErrorCode>,
+    weekly: Vec<RunStatsWeek>,
+}
+
+pub(in crate::http) async fn get_run_stats(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Query(query): Query<RunStatsQuery>,
+) -> Result<Json<RunStatsResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    let visible = visible_job_ids(&state, &session).await?;
+
+    let refreshed_at = ensure_refreshed(&state, query.refresh).await?;
+    let mut rows = run_stats_repo::list_job_run_stats(&state.db).await?;
+    let mut weekly = run_stats_repo::list_weekly_bytes(&state.db, None).await?;
+    let mut errors = run_stats_repo::list_error_codes(&state.db, None).await?;
+    if let Some(visible) = &visible {
+        rows.retain(|row| visible.contains(&row.job_id));
+        weekly.retain(|row| visible.contains(&row.job_id));
+        errors.retain(|row| visible.contains(&row.job_id));
+    }
+
+    let mut totals = RunStatsSummary::default();
+    let jobs = rows
+        .into_iter()
+        .map(|row| {
+            totals.add(&row);
+            job_stats(row)
+        })
+        .collect();
+
+    Ok(Json(RunStatsResponse {
+        refreshed_at,
+        window_days: run_stats_repo::WINDOW_DAYS,
+        totals,
+        errors: merge_error_codes(errors),
+        weekly: merge_weeks(weekly),
+        jobs,
+    }))
+}
+
+pub(in crate::http) async fn get_job_run_stats(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Path(job_id): Path<String>,
+    Query(query): Query<RunStatsQuery>,
+) -> Result<Json<JobRunStatsResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    require_job_permission(&state, &session, &job_id, JobPermission::View).await?;
+
+    let job = jobs_repo::get_job(&state.db, &job_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("job_not_found", "Job not found"))?;
+
+    let refreshed_at = ensure_refreshed(&state, query.refresh).await?;
+    let row = run_stats_repo::list_job_run_stats(&state.db)
+        .await?
+        .into_iter()
+        .find(|row| row.job_id == job.id);
+    let weekly = run_stats_repo::list_weekly_bytes(&state.db, Some(&job.id)).await?;
+    let errors = run_stats_repo::list_error_codes(&state.db, Some(&job.id)).await?;
+
+    let job = match row {
+        Some(row) => job_stats(row),
+        // No finished runs in the window.
+        None => RunStatsJob {
+            job_id: job.id,
+            job_name: Some(job.name),
+            last_ended_at: None,
+            summary: RunStatsSummary::default(),
+        },
+    };
+
+    Ok(Json(JobRunStatsResponse {
+        refreshed_at,
+        window_days: run_stats_repo::WINDOW_DAYS,
+        job,
+        errors: merge_error_codes(errors),
+        weekly: merge_weeks(weekly),
+    }))
+}
+
+// The background loop keeps the rollups warm; an explicit refresh (or a cold cache)
+// recomputes synchronously so just-finished runs show up right away.
+async fn ensure_refreshed(state: &AppState, refresh: bool) -> Result<Option<i64>, AppError> {
+    let refreshed_at = run_stats_repo::last_refreshed_at(&state.db).await?;
+    if !refresh && refreshed_at.is_some() {
+        return Ok(refreshed_at);
+    }
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    run_stats_repo::refresh_run_stats(&state.db, now).await?;
+    Ok(Some(now))
+}
+
+fn job_stats(row: JobRunStatsRow) -> RunStatsJob {
+    let mut summary = RunStatsSummary::default();
+    summary.add(&row);
+    RunStatsJob {
+        job_id: row.job_id,
+        job_name: row.job_name,
+        last_ended_at: row.last_ended_at,
+        summary,
+    }
+}
+
+fn merge_weeks(rows: Vec<WeeklyBytesRow>) -> Vec<RunStatsWeek> {
+    let mut weeks: BTreeMap<String, RunStatsWeek> = BTreeMap::new();
+    for row in rows {
+        let week = weeks
+            .entry(row.week_start.clone())
+            .or_insert_with(|| RunStatsWeek {
+                week_start: row.week_start,
+                runs: 0,
+                source_bytes: 0,
+                transfer_bytes: 0,
+            });
+        week.runs = week.runs.saturating_add(row.runs);
+        week.source_bytes = week.source_bytes.saturating_add(row.source_bytes);
+        week.transfer_bytes = week.transfer_bytes.saturating_add(row.transfer_bytes);
+    }
+    weeks.into_values().collect()
+}
+
+// Codes outside the catalog (for example from older agents) are counted as `unknown`.
+fn merge_error_codes(rows: Vec<ErrorCodeRow>) -> Vec<RunStatsErrorCode> {
+    let mut counts: BTreeMap<&'static str, (RunErrorCode, u64)> = BTreeMap::new();
+    for row in rows {
+        let code = RunErrorCode::from_code(&row.error_code).unwrap_or(RunErrorCode::Unknown);
+        let entry = counts.entry(code.as_str()).or_insert((code, 0));
+        entry.1 = entry.1.saturating_add(row.runs);
+    }
+    let mut out: Vec<RunStatsErrorCode> = counts
+        .into_values()
+        .map(|(error_code, runs)| RunStatsErrorCode { error_code, runs })
+        .collect();
+    out.sort_by_key(|e| std::cmp::Reverse(e.runs));
+    out
+}
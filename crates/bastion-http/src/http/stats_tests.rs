@@ -0,0 +1,314 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_engine::run_events_bus::RunEventsBus;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db, jobs_repo, runs_repo};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+async fn insert_run(
+    pool: &sqlx::SqlitePool,
+    job_id: &str,
+    status: runs_repo::RunStatus,
+    duration_secs: i64,
+    transfer_bytes: Option<i64>,
+) {
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let run = runs_repo::create_run(
+        pool,
+        job_id,
+        status,
+        now - duration_secs,
+        Some(now),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+
+    let Some(transfer_bytes) = transfer_bytes else {
+        return;
+    };
+    sqlx::query(
+        r#"
+        INSERT INTO run_artifacts (
+          run_id, job_id, node_id, target_type, target_snapshot_json,
+          artifact_format, status, started_at, ended_at,
+          source_bytes, transfer_bytes, created_at, updated_at
+        ) VALUES (?, ?, 'hub', 'local_dir', '{}', 'archive_v1', 'present', ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&run.id)
+    .bind(job_id)
+    .bind(now - duration_secs)
+    .bind(now)
+    .bind(transfer_bytes)
+    .bind(transfer_bytes)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .expect("insert artifact");
+}
+
+fn test_app(temp: &TempDir, pool: sqlx::SqlitePool) -> axum::Router {
+    let config = test_config(temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    super::router(super::AppState {
+        config,
+        db: pool,
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    })
+}
+
+#[tokio::test]
+async fn run_stats_requires_auth() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+    let app = test_app(&temp, pool);
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .get(format!("{}/api/stats", base_url(addr)))
+        .send()
+        .await
+        .expect("request");
+
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn run_stats_roll_up_per_job_and_globally() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let flaky = jobs_repo::create_job(
+        &pool,
+        "Flaky Job",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create flaky job");
+    let steady = jobs_repo::create_job(
+        &pool,
+        "Steady Job",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create steady job");
+    let idle = jobs_repo::create_job(
+        &pool,
+        "Idle Job",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create idle job");
+
+    insert_run(
+        &pool,
+        &flaky.id,
+        runs_repo::RunStatus::Success,
+        10,
+        Some(100),
+    )
+    .await;
+    insert_run(&pool, &flaky.id, runs_repo::RunStatus::Failed, 30, None).await;
+    insert_run(
+        &pool,
+        &steady.id,
+        runs_repo::RunStatus::Success,
+        20,
+        Some(50),
+    )
+    .await;
+
+    let app = test_app(&temp, pool.clone());
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .get(format!("{}/api/stats", base_url(addr)))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+
+    assert!(body["refreshed_at"].as_i64().is_some());
+    assert_eq!(body["window_days"].as_i64(), Some(30));
+    assert_eq!(body["totals"]["runs_total"].as_u64(), Some(3));
+    assert_eq!(body["totals"]["runs_failed"].as_u64(), Some(1));
+    assert!((body["totals"]["success_rate"].as_f64().expect("rate") - 2.0 / 3.0).abs() < 1e-9);
+    assert_eq!(body["totals"]["avg_duration_secs"].as_f64(), Some(20.0));
+
+    // A failed run without a recorded code counts as `unknown`.
+    let errors = body["errors"].as_array().expect("errors");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["error_code"].as_str(), Some("unknown"));
+    assert_eq!(errors[0]["runs"].as_u64(), Some(1));
+
+    let weekly = body["weekly"].as_array().expect("weekly");
+    assert_eq!(weekly.len(), 1);
+    assert_eq!(weekly[0]["runs"].as_u64(), Some(2));
+    assert_eq!(weekly[0]["transfer_bytes"].as_u64(), Some(150));
+
+    let jobs = body["jobs"].as_array().expect("jobs");
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0]["job_id"].as_str(), Some(flaky.id.as_str()));
+    assert_eq!(jobs[0]["job_name"].as_str(), Some("Flaky Job"));
+    assert_eq!(jobs[0]["success_rate"].as_f64(), Some(0.5));
+    assert_eq!(jobs[0]["avg_duration_secs"].as_f64(), Some(20.0));
+    assert_eq!(jobs[1]["success_rate"].as_f64(), Some(1.0));
+
+    // Per-job view, including a job without runs in the window.
+    let body: serde_json::Value = client
+        .get(format!("{}/api/stats/jobs/{}", base_url(addr), steady.id))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("json");
+    assert_eq!(body["job_name"].as_str(), Some("Steady Job"));
+    assert_eq!(body["runs_total"].as_u64(), Some(1));
+    assert_eq!(body["weekly"][0]["transfer_bytes"].as_u64(), Some(50));
+
+    let body: serde_json::Value = client
+        .get(format!("{}/api/stats/jobs/{}", base_url(addr), idle.id))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("json");
+    assert_eq!(body["runs_total"].as_u64(), Some(0));
+    assert!(body["success_rate"].is_null());
+    assert!(body["weekly"].as_array().expect("weekly").is_empty());
+
+    let resp = client
+        .get(format!("{}/api/stats/jobs/missing", base_url(addr)))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // New runs are not visible until the rollups are refreshed.
+    insert_run(&pool, &steady.id, runs_repo::RunStatus::Failed, 5, None).await;
+    let body: serde_json::Value = client
+        .get(format!("{}/api/stats", base_url(addr)))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("json");
+    assert_eq!(body["totals"]["runs_total"].as_u64(), Some(3));
+
+    let body: serde_json::Value = client
+        .get(format!("{}/api/stats?refresh=true", base_url(addr)))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("json");
+    assert_eq!(body["totals"]["runs_total"].as_u64(), Some(4));
+
+    server.abort();
+}
@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+
+use axum::Json;
+use axum::extract::Query;
+use serde::{Deserialize, Serialize};
+use tower_cookies::Cookies;
+
+use bastion_storage::storage_usage_repo::{self, StorageUsageRow};
+
+use super::shared::{require_session, visible_job_ids};
+use super::{AppError, AppState};
+
+#[derive(Debug, Default, Deserialize)]
+pub(in crate::http) struct StorageUsageQuery {
+    #[serde(default)]
+    refresh: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(in crate::http) struct StorageUsageTotals {
+    snapshots: u64,
+    pinned_snapshots: u64,
+    source_bytes: u64,
+    transfer_bytes: u64,
+}
+
+impl StorageUsageTotals {
+    fn add(&mut self, row: &StorageUsageRow) {
+        self.snapshots = self.snapshots.saturating_add(row.snapshots);
+        self.pinned_snapshots = self.pinned_snapshots.saturating_add(row.pinned_snapshots);
+        self.source_bytes = self.source_bytes.saturating_add(row.source_bytes);
+        self.transfer_bytes = self.transfer_bytes.saturating_add(row.transfer_bytes);
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct StorageUsageJob {
+    job_id: String,
+    job_name: Option<String>,
+    #[serde(flatten)]
+    totals: StorageUsageTotals,
+    targets: Vec<StorageUsageRow>,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct StorageUsageTarget {
+    node_id: String,
+    target_type: String,
+    target_key: String,
+    jobs_total: u64,
+    #[serde(flatten)]
+    totals: StorageUsageTotals,
+}
+
+#[derive(Debug, Serialize)]
+pub(in crate::http) struct StorageUsageResponse {
+    refreshed_at: Option<i64>,
+    totals: StorageUsageTotals,
+    jobs: Vec<StorageUsageJob>,
+    targets: Vec<StorageUsageTarget>,
+}
+
+pub(in crate::http) async fn get_storage_usage(
+    state: axum::extract::State<AppState>,
+    cookies: Cookies,
+    Query(query): Query<StorageUsageQuery>,
+) -> Result<Json<StorageUsageResponse>, AppError> {
+    let session = require_session(&state, &cookies).await?;
+    let visible = visible_job_ids(&state, &session).await?;
+
+    // The background loop keeps the cache warm; an explicit refresh (or a cold cache)
+    // recomputes synchronously so freshly created snapshots show up right away.
+    let mut refreshed_at = storage_usage_repo::last_refreshed_at(&state.db).await?;
+    if query.refresh || refreshed_at.is_none() {
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        storage_usage_repo::refresh_storage_usage(&state.db, now).await?;
+        refreshed_at = Some(now);
+    }
+
+    let mut rows = storage_usage_repo::list_storage_usage(&state.db).await?;
+    if let Some(visible) = &visible {
+        rows.retain(|row| visible.contains(&row.job_id));
+    }
+    Ok(Json(build_response(refreshed_at, rows)))
+}
+
+fn build_response(refreshed_at: Option<i64>, rows: Vec<StorageUsageRow>) -> StorageUsageResponse {
+    let mut totals = StorageUsageTotals::default();
+    let mut jobs: Vec<StorageUsageJob> = Vec::new();
+    let mut job_index: BTreeMap<String, usize> = BTreeMap::new();
+    let mut targets: BTreeMap<(String, String, String), StorageUsageTarget> = BTreeMap::new();
+
+    for row in rows {
+        totals.add(&row);
+
+        let target = targets
+            .entry((
+                row.node_id.clone(),
+                row.target_type.clone(),
+                row.target_key.clone(),
+            ))
+            .or_insert_with(|| StorageUsageTarget {
+                node_id: row.node_id.clone(),
+                target_type: row.target_type.clone(),
+                target_key: row.target_key.clone(),
+                jobs_total: 0,
+                totals: StorageUsageTotals::default(),
+            });
+        target.jobs_total = target.jobs_total.saturating_add(1);
+        target.totals.add(&row);
+
+        let idx = *job_index.entry(row.job_id.clone()).or_insert_with(|| {
+            jobs.push(StorageUsageJob {
+                job_id: row.job_id.clone(),
+                job_name: row.job_name.clone(),
+                totals: StorageUsageTotals::default(),
+                targets: Vec::new(),
+            });
+            jobs.len() - 1
+        });
+        let job = &mut jobs[idx];
+        job.totals.add(&row);
+        job.targets.push(row);
+    }
+
+    // Largest consumers first: this is what users look at before tuning retention.
+    jobs.sort_by(|a, b| {
+        b.totals
+            .transfer_bytes
+            .cmp(&a.totals.transfer_bytes)
+            .then_with(|| a.job_id.cmp(&b.job_id))
+    });
+    let mut targets = targets.into_values().collect::<Vec<_>>();
+    targets.sort_by_key(|t| std::cmp::Reverse(t.totals.transfer_bytes));
+
+    StorageUsageResponse {
+        refreshed_at,
+        totals,
+        jobs,
+        targets,
+    }
+}
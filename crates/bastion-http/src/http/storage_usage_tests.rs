@@ -0,0 +1,266 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use tempfile::TempDir;
+
+use bastion_config::Config;
+use bastion_engine::agent_manager::AgentManager;
+use bastion_engine::run_events_bus::RunEventsBus;
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{auth, db, jobs_repo, runs_repo};
+
+async fn start_test_server() -> (tokio::net::TcpListener, std::net::SocketAddr) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    (listener, addr)
+}
+
+fn test_config(temp: &TempDir) -> Arc<Config> {
+    Arc::new(Config {
+        bind: "127.0.0.1:0".parse().expect("bind"),
+        data_dir: temp.path().to_path_buf(),
+        insecure_http: true,
+        debug_errors: false,
+        hub_timezone: "UTC".to_string(),
+        run_retention_days: 180,
+        incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
+        trusted_proxies: vec![
+            "127.0.0.1/32".parse().expect("proxy"),
+            "::1/128".parse().expect("proxy"),
+        ],
+    })
+}
+
+fn base_url(addr: std::net::SocketAddr) -> String {
+    format!("http://{addr}")
+}
+
+async fn insert_artifact(
+    pool: &sqlx::SqlitePool,
+    job_id: &str,
+    base_url: &str,
+    status: &str,
+    transfer_bytes: i64,
+) {
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    let run = runs_repo::create_run(
+        pool,
+        job_id,
+        runs_repo::RunStatus::Success,
+        now - 10,
+        Some(now),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+
+    sqlx::query(
+        r#"
+        INSERT INTO run_artifacts (
+          run_id, job_id, node_id, target_type, target_snapshot_json,
+          artifact_format, status, started_at, ended_at,
+          source_bytes, transfer_bytes, created_at, updated_at
+        ) VALUES (?, ?, 'hub', 'webdav', ?, 'archive_v1', ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&run.id)
+    .bind(job_id)
+    .bind(
+        serde_json::json!({
+            "node_id": "hub",
+            "target": { "type": "webdav", "base_url": base_url, "secret_name": "s" }
+        })
+        .to_string(),
+    )
+    .bind(status)
+    .bind(now - 10)
+    .bind(now)
+    .bind(transfer_bytes)
+    .bind(transfer_bytes)
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .expect("insert artifact");
+}
+
+#[tokio::test]
+async fn storage_usage_requires_auth() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .get(format!("{}/api/storage/usage", base_url(addr)))
+        .send()
+        .await
+        .expect("request");
+
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+    server.abort();
+}
+
+#[tokio::test]
+async fn storage_usage_groups_bytes_by_job_and_target() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let user_password = uuid::Uuid::new_v4().to_string();
+    auth::create_user(&pool, "admin", &user_password)
+        .await
+        .expect("create user");
+    let user = auth::find_user_by_username(&pool, "admin")
+        .await
+        .expect("find user")
+        .expect("user exists");
+    let session = auth::create_session(&pool, user.id)
+        .await
+        .expect("create session");
+
+    let big = jobs_repo::create_job(
+        &pool,
+        "Big Job",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create big job");
+    let small = jobs_repo::create_job(
+        &pool,
+        "Small Job",
+        None,
+        None,
+        Some("UTC"),
+        jobs_repo::OverlapPolicy::Queue,
+        serde_json::json!({"v":1,"type":"filesystem"}),
+    )
+    .await
+    .expect("create small job");
+
+    insert_artifact(&pool, &big.id, "https://dav-a/", "present", 1000).await;
+    insert_artifact(&pool, &big.id, "https://dav-b/", "present", 500).await;
+    insert_artifact(&pool, &big.id, "https://dav-a/", "deleted", 9999).await;
+    insert_artifact(&pool, &small.id, "https://dav-a/", "present", 10).await;
+
+    let config = test_config(&temp);
+    let secrets = Arc::new(SecretsCrypto::load_or_create(&config.data_dir).expect("secrets"));
+
+    let app = super::router(super::AppState {
+        config,
+        db: pool.clone(),
+        secrets,
+        agent_manager: AgentManager::default(),
+        run_queue_notify: Arc::new(tokio::sync::Notify::new()),
+        incomplete_cleanup_notify: Arc::new(tokio::sync::Notify::new()),
+        artifact_delete_notify: Arc::new(tokio::sync::Notify::new()),
+        jobs_notify: Arc::new(tokio::sync::Notify::new()),
+        notifications_notify: Arc::new(tokio::sync::Notify::new()),
+        bulk_ops_notify: Arc::new(tokio::sync::Notify::new()),
+        run_events_bus: Arc::new(RunEventsBus::new()),
+        hub_runtime_config: Default::default(),
+    });
+
+    let (listener, addr) = start_test_server().await;
+    let server = tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+        )
+        .await
+        .expect("serve");
+    });
+
+    let client = reqwest::Client::new();
+    let resp = client
+        .get(format!("{}/api/storage/usage", base_url(addr)))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request");
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: serde_json::Value = resp.json().await.expect("json");
+
+    assert!(body["refreshed_at"].as_i64().is_some());
+    assert_eq!(body["totals"]["snapshots"].as_u64(), Some(3));
+    assert_eq!(body["totals"]["transfer_bytes"].as_u64(), Some(1510));
+
+    let jobs = body["jobs"].as_array().expect("jobs");
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0]["job_id"].as_str(), Some(big.id.as_str()));
+    assert_eq!(jobs[0]["job_name"].as_str(), Some("Big Job"));
+    assert_eq!(jobs[0]["transfer_bytes"].as_u64(), Some(1500));
+    assert_eq!(jobs[0]["targets"].as_array().expect("targets").len(), 2);
+    assert_eq!(jobs[1]["transfer_bytes"].as_u64(), Some(10));
+
+    let targets = body["targets"].as_array().expect("targets");
+    assert_eq!(targets.len(), 2);
+    assert_eq!(targets[0]["target_key"].as_str(), Some("https://dav-a/"));
+    assert_eq!(targets[0]["jobs_total"].as_u64(), Some(2));
+    assert_eq!(targets[0]["transfer_bytes"].as_u64(), Some(1010));
+
+    // New snapshots are not visible until the cache is refreshed.
+    insert_artifact(&pool, &small.id, "https://dav-b/", "present", 5).await;
+    let body: serde_json::Value = client
+        .get(format!("{}/api/storage/usage", base_url(addr)))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("json");
+    assert_eq!(body["totals"]["snapshots"].as_u64(), Some(3));
+
+    let body: serde_json::Value = client
+        .get(format!("{}/api/storage/usage?refresh=true", base_url(addr)))
+        .header("cookie", format!("bastion_session={}", session.id))
+        .send()
+        .await
+        .expect("request")
+        .json()
+        .await
+        .expect("json");
+    assert_eq!(body["totals"]["snapshots"].as_u64(), Some(4));
+
+    server.abort();
+}
@@ -0,0 +1,256 @@
+//! `--ui-dev-proxy`: forwards UI requests to a Vite dev server instead of serving `ui/dist`, so
+//! frontend changes (including hot module reload over WebSocket) show up without a rebuild.
+
+use axum::body::Body;
+use axum::extract::ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{FromRequestParts as _, Request};
+use axum::http::{HeaderMap, HeaderName, StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use futures_util::{SinkExt as _, StreamExt as _};
+use tokio_tungstenite::tungstenite;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest as _;
+
+/// Request bodies the UI sends to the dev server are tiny; this only guards against abuse.
+const MAX_REQUEST_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub(super) struct UiDevProxy {
+    /// Dev server origin without a trailing slash, e.g. `http://127.0.0.1:5173`.
+    base: String,
+    client: reqwest::Client,
+}
+
+impl UiDevProxy {
+    pub(super) fn new(base: &str) -> Self {
+        let client = reqwest::Client::builder()
+            // Redirects belong to the browser, not the Hub.
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap_or_default();
+        Self {
+            base: base.trim_end_matches('/').to_string(),
+            client,
+        }
+    }
+
+    pub(super) fn base(&self) -> &str {
+        &self.base
+    }
+
+    pub(super) async fn handle(self, req: Request) -> Response {
+        let path = req
+            .uri()
+            .path_and_query()
+            .map(|v| v.as_str())
+            .unwrap_or("/");
+        let target = format!("{}{path}", self.base);
+
+        let is_websocket = req
+            .headers()
+            .get(header::UPGRADE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+        let result = if is_websocket {
+            self.proxy_websocket(&target, req).await
+        } else {
+            self.proxy_http(&target, req).await
+        };
+        result.unwrap_or_else(|error| {
+            tracing::warn!(target = %target, error = %error, "ui dev proxy request failed");
+            (
+                StatusCode::BAD_GATEWAY,
+                format!("ui dev proxy: {}: {error}", self.base),
+            )
+                .into_response()
+        })
+    }
+
+    async fn proxy_http(&self, target: &str, req: Request) -> Result<Response, anyhow::Error> {
+        let (parts, body) = req.into_parts();
+        let body = axum::body::to_bytes(body, MAX_REQUEST_BODY_BYTES).await?;
+
+        let upstream = self
+            .client
+            .request(parts.method, target)
+            .headers(forwarded_headers(&parts.headers))
+            .body(body)
+            .send()
+            .await?;
+
+        let mut resp = Response::builder().status(upstream.status());
+        if let Some(headers) = resp.headers_mut() {
+            *headers = forwarded_headers(upstream.headers());
+        }
+        Ok(resp.body(Body::from_stream(upstream.bytes_stream()))?)
+    }
+
+    async fn proxy_websocket(&self, target: &str, req: Request) -> Result<Response, anyhow::Error> {
+        let (mut parts, _body) = req.into_parts();
+        let ws = WebSocketUpgrade::from_request_parts(&mut parts, &())
+            .await
+            .map_err(|rejection| anyhow::anyhow!("{}", rejection.body_text()))?;
+
+        let ws_target = if let Some(rest) = target.strip_prefix("https://") {
+            format!("wss://{rest}")
+        } else if let Some(rest) = target.strip_prefix("http://") {
+            format!("ws://{rest}")
+        } else {
+            target.to_string()
+        };
+        let mut upstream_req = ws_target.as_str().into_client_request()?;
+        // Vite only accepts its HMR socket with the `vite-hmr` subprotocol.
+        if let Some(protocols) = parts.headers.get(header::SEC_WEBSOCKET_PROTOCOL) {
+            upstream_req
+                .headers_mut()
+                .insert(header::SEC_WEBSOCKET_PROTOCOL, protocols.clone());
+        }
+
+        // Connect first so the client is offered the subprotocol the dev server picked.
+        let (upstream, upstream_resp) = tokio_tungstenite::connect_async(upstream_req).await?;
+        let ws = match upstream_resp
+            .headers()
+            .get(header::SEC_WEBSOCKET_PROTOCOL)
+            .and_then(|v| v.to_str().ok())
+        {
+            Some(protocol) => ws.protocols([protocol.to_string()]),
+            None => ws,
+        };
+        Ok(ws.on_upgrade(move |socket| bridge(socket, upstream)))
+    }
+}
+
+async fn bridge(
+    socket: WebSocket,
+    upstream: tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >,
+) {
+    let (mut client_tx, mut client_rx) = socket.split();
+    let (mut upstream_tx, mut upstream_rx) = upstream.split();
+
+    let to_upstream = async {
+        while let Some(Ok(msg)) = client_rx.next().await {
+            let Some(msg) = to_tungstenite(msg) else {
+                continue;
+            };
+            let close = matches!(msg, tungstenite::Message::Close(_));
+            if upstream_tx.send(msg).await.is_err() || close {
+                break;
+            }
+        }
+    };
+    let to_client = async {
+        while let Some(Ok(msg)) = upstream_rx.next().await {
+            let Some(msg) = to_axum(msg) else {
+                continue;
+            };
+            let close = matches!(msg, Message::Close(_));
+            if client_tx.send(msg).await.is_err() || close {
+                break;
+            }
+        }
+    };
+
+    // Either side closing ends the session; dropping the other half closes it too.
+    tokio::select! {
+        _ = to_upstream => {}
+        _ = to_client => {}
+    }
+}
+
+/// Pings and pongs are answered by each side's own WebSocket stack, so only data and close
+/// frames cross the bridge.
+fn to_tungstenite(msg: Message) -> Option<tungstenite::Message> {
+    match msg {
+        Message::Text(text) => Some(tungstenite::Message::Text(text.as_str().to_string().into())),
+        Message::Binary(bytes) => Some(tungstenite::Message::Binary(bytes)),
+        Message::Close(frame) => Some(tungstenite::Message::Close(frame.map(|f| {
+            tungstenite::protocol::CloseFrame {
+                code: f.code.into(),
+                reason: f.reason.as_str().to_string().into(),
+            }
+        }))),
+        Message::Ping(_) | Message::Pong(_) => None,
+    }
+}
+
+fn to_axum(msg: tungstenite::Message) -> Option<Message> {
+    match msg {
+        tungstenite::Message::Text(text) => Some(Message::Text(text.as_str().to_string().into())),
+        tungstenite::Message::Binary(bytes) => Some(Message::Binary(bytes)),
+        tungstenite::Message::Close(frame) => Some(Message::Close(frame.map(|f| CloseFrame {
+            code: f.code.into(),
+            reason: f.reason.as_str().to_string().into(),
+        }))),
+        tungstenite::Message::Ping(_)
+        | tungstenite::Message::Pong(_)
+        | tungstenite::Message::Frame(_) => None,
+    }
+}
+
+fn forwarded_headers(headers: &HeaderMap) -> HeaderMap {
+    headers
+        .iter()
+        .filter(|(name, _)| !is_hop_by_hop(name))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+fn is_hop_by_hop(name: &HeaderName) -> bool {
+    // `Host` is set from the target URL; `Content-Length` is recomputed for the forwarded body.
+    matches!(
+        name.as_str(),
+        "connection"
+            | "keep-alive"
+            | "proxy-authenticate"
+            | "proxy-authorization"
+            | "te"
+            | "trailer"
+            | "transfer-encoding"
+            | "upgrade"
+            | "host"
+            | "content-length"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Router;
+    use axum::routing::get;
+
+    use super::UiDevProxy;
+
+    #[tokio::test]
+    async fn forwards_requests_and_status_to_the_dev_server() {
+        let upstream = Router::new().route(
+            "/src/main.ts",
+            get(|uri: axum::http::Uri| async move {
+                (
+                    [("content-type", "text/javascript")],
+                    format!("// {}", uri.query().unwrap_or_default()),
+                )
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, upstream).await.unwrap();
+        });
+
+        let proxy = UiDevProxy::new(&format!("http://{addr}/"));
+        let req = axum::http::Request::get("/src/main.ts?t=1")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let resp = proxy.clone().handle(req).await;
+        assert_eq!(resp.status(), axum::http::StatusCode::OK);
+        assert_eq!(resp.headers()["content-type"], "text/javascript");
+        let body = axum::body::to_bytes(resp.into_body(), 1024).await.unwrap();
+        assert_eq!(&body[..], b"// t=1");
+
+        let req = axum::http::Request::get("/missing")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let resp = proxy.handle(req).await;
+        assert_eq!(resp.status(), axum::http::StatusCode::NOT_FOUND);
+    }
+}
@@ -21,7 +21,7 @@ use super::list_paging::{
     invalid_cursor_error, invalid_sort_by_error, invalid_sort_dir_error, parse_sort_by,
     parse_sort_dir, rank_kind,
 };
-use super::shared::require_session;
+use super::shared::{list_requester_id, require_session};
 use super::{AppError, AppState};
 
 #[derive(Debug, Deserialize)]
@@ -77,8 +77,8 @@ pub(super) async fn webdav_list(
     Path(node_id): Path<String>,
     Query(req): Query<WebdavListRequest>,
 ) -> Result<Json<WebdavListResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
-    webdav_list_impl(&state, node_id, req).await
+    let session = require_session(&state, &cookies).await?;
+    webdav_list_impl(&state, node_id, req, list_requester_id(&session)).await
 }
 
 pub(super) async fn webdav_list_post(
@@ -87,8 +87,8 @@ pub(super) async fn webdav_list_post(
     Path(node_id): Path<String>,
     Json(req): Json<WebdavListRequest>,
 ) -> Result<Json<WebdavListResponse>, AppError> {
-    let _session = require_session(&state, &cookies).await?;
-    webdav_list_impl(&state, node_id, req).await
+    let session = require_session(&state, &cookies).await?;
+    webdav_list_impl(&state, node_id, req, list_requester_id(&session)).await
 }
 
 fn invalid_webdav_secret_error(
@@ -139,6 +139,7 @@ async fn webdav_list_impl(
     state: &AppState,
     node_id: String,
     req: WebdavListRequest,
+    requester: String,
 ) -> Result<Json<WebdavListResponse>, AppError> {
     let base_url = req.base_url.trim().to_string();
     if base_url.is_empty() {
@@ -202,6 +203,7 @@ async fn webdav_list_impl(
         sort_dir: req.sort_dir,
         size_min_bytes: req.size_min_bytes,
         size_max_bytes: req.size_max_bytes,
+        requester: Some(requester),
     };
 
     let page = state
@@ -257,9 +259,8 @@ fn map_agent_webdav_list_error(path: &str, error: anyhow::Error) -> AppError {
 
         let (mapped_code, mapped_from_legacy_message) = match remote_code.as_str() {
             "permission_denied" | "path_not_found" | "not_directory" | "invalid_cursor"
-            | "invalid_path" | "invalid_sort_by" | "invalid_sort_dir" => {
-                (remote_code.as_str(), false)
-            }
+            | "invalid_path" | "invalid_sort_by" | "invalid_sort_dir" | "rate_limited"
+            | "superseded" => (remote_code.as_str(), false),
             _ => match classify_legacy_remote_webdav_error(&message) {
                 Some(mapped) => (mapped, true),
                 None => ("agent_webdav_list_failed", false),
@@ -274,6 +275,14 @@ fn map_agent_webdav_list_error(path: &str, error: anyhow::Error) -> AppError {
             "invalid_path" => invalid_path_error("required", "path is required"),
             "invalid_sort_by" => invalid_sort_by_error("unsupported_value", "invalid sort_by"),
             "invalid_sort_dir" => invalid_sort_dir_error("unsupported_value", "invalid sort_dir"),
+            "rate_limited" => AppError::too_many_requests(
+                "agent_list_rate_limited",
+                "Agent is throttling directory listings; retry shortly",
+            ),
+            "superseded" => AppError::conflict(
+                "agent_list_superseded",
+                "Listing was superseded by a newer request",
+            ),
             _ => agent_webdav_list_failed_error(
                 "remote_error",
                 format!("Agent WebDAV list failed: {message}"),
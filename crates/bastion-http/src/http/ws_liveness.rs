@@ -0,0 +1,68 @@
+//! Liveness for Hub→browser WebSockets: the Hub pings every socket and drops the ones whose peer
+//! stopped answering (sleeping laptops, dead proxies), so they don't hold bus subscriptions forever.
+
+use std::time::Duration;
+
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+pub(super) const PING_INTERVAL: Duration = Duration::from_secs(20);
+/// Browsers answer pings on their own, so three missed pongs mean the peer is gone.
+pub(super) const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub(super) struct WsLiveness {
+    interval: Interval,
+    idle_timeout: Duration,
+    last_seen: Instant,
+}
+
+impl WsLiveness {
+    pub(super) fn new() -> Self {
+        Self::with_timing(PING_INTERVAL, IDLE_TIMEOUT)
+    }
+
+    pub(super) fn with_timing(ping_interval: Duration, idle_timeout: Duration) -> Self {
+        let start = Instant::now();
+        let mut interval = tokio::time::interval_at(start + ping_interval, ping_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self {
+            interval,
+            idle_timeout,
+            last_seen: start,
+        }
+    }
+
+    /// Records a frame from the peer (data, pong or ping).
+    pub(super) fn seen(&mut self) {
+        self.last_seen = Instant::now();
+    }
+
+    /// Waits for the next ping; `false` when the peer has been silent past the idle timeout and
+    /// the socket should be closed.
+    pub(super) async fn tick(&mut self) -> bool {
+        let now = self.interval.tick().await;
+        now.saturating_duration_since(self.last_seen) < self.idle_timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::WsLiveness;
+
+    #[tokio::test]
+    async fn silent_peers_time_out_and_answering_peers_do_not() {
+        let mut liveness =
+            WsLiveness::with_timing(Duration::from_millis(20), Duration::from_millis(50));
+        assert!(liveness.tick().await);
+        liveness.seen();
+        assert!(liveness.tick().await);
+        assert!(liveness.tick().await);
+
+        let mut alive = true;
+        for _ in 0..4 {
+            alive = liveness.tick().await;
+        }
+        assert!(!alive);
+    }
+}
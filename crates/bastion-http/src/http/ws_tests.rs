@@ -7,7 +7,7 @@ use tokio_tungstenite::tungstenite::Error as WsError;
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
 
 use bastion_storage::secrets::SecretsCrypto;
-use bastion_storage::{auth, db, jobs_repo, runs_repo};
+use bastion_storage::{auth, db, jobs_repo, operations_repo, runs_repo};
 
 use bastion_config::Config;
 use bastion_engine::agent_manager::AgentManager;
@@ -81,6 +81,11 @@ async fn setup_ws_harness(insecure_http: bool) -> WsHarness {
         hub_timezone: "UTC".to_string(),
         run_retention_days: 180,
         incomplete_cleanup_days: 7,
+        staging_dir: None,
+        staging_min_free_bytes: 0,
+        ui_dev_proxy: None,
+        api_rate_limit_per_ip: 0,
+        api_rate_limit_per_session: 0,
         trusted_proxies: vec![
             "127.0.0.1/32".parse().expect("proxy"),
             "::1/128".parse().expect("proxy"),
@@ -248,3 +253,129 @@ async fn run_events_ws_rejects_origin_with_mismatched_scheme() {
 
     harness.server.abort();
 }
+
+async fn next_json(
+    socket: &mut tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >,
+) -> serde_json::Value {
+    let msg = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+        .await
+        .expect("recv timeout")
+        .expect("recv some")
+        .expect("recv ok");
+    let text = msg.into_text().expect("text");
+    serde_json::from_str(&text).expect("json")
+}
+
+#[tokio::test]
+async fn operation_events_ws_streams_events_and_progress_until_done() {
+    let harness = setup_ws_harness(true).await;
+
+    let op = operations_repo::create_operation(
+        &harness.pool,
+        operations_repo::OperationKind::Verify,
+        Some(("run", harness.run_id.as_str())),
+    )
+    .await
+    .expect("create operation");
+    operations_repo::append_event(&harness.pool, &op.id, "info", "start", "start", None)
+        .await
+        .expect("event1");
+
+    let url = format!("ws://{}/api/operations/{}/events/ws", harness.addr, op.id);
+    let mut req = url.into_client_request().expect("ws request");
+    req.headers_mut().insert(
+        "origin",
+        format!("http://{}", harness.addr).parse().expect("origin"),
+    );
+    req.headers_mut().insert(
+        "cookie",
+        format!("bastion_session={}", harness.session_id)
+            .parse()
+            .expect("cookie"),
+    );
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(req)
+        .await
+        .expect("ws connect");
+
+    let first = next_json(&mut socket).await;
+    assert_eq!(first["type"], "event");
+    assert_eq!(first["event"]["seq"].as_i64().unwrap_or_default(), 1);
+
+    let snapshot = next_json(&mut socket).await;
+    assert_eq!(snapshot["type"], "snapshot");
+    assert_eq!(snapshot["status"], "running");
+    assert!(snapshot["progress"].is_null());
+
+    operations_repo::set_operation_progress(
+        &harness.pool,
+        &op.id,
+        Some(serde_json::json!({ "stage": "verify", "done": { "files": 1 } })),
+    )
+    .await
+    .expect("progress");
+
+    let progress = next_json(&mut socket).await;
+    assert_eq!(progress["type"], "snapshot");
+    assert_eq!(progress["progress"]["stage"], "verify");
+
+    operations_repo::append_event(&harness.pool, &op.id, "info", "complete", "complete", None)
+        .await
+        .expect("event2");
+    operations_repo::complete_operation(
+        &harness.pool,
+        &op.id,
+        operations_repo::OperationStatus::Success,
+        None,
+        None,
+    )
+    .await
+    .expect("complete");
+
+    let last_event = next_json(&mut socket).await;
+    assert_eq!(last_event["type"], "event");
+    assert_eq!(last_event["event"]["seq"].as_i64().unwrap_or_default(), 2);
+
+    let done = next_json(&mut socket).await;
+    assert_eq!(done["status"], "success");
+
+    let closed = tokio::time::timeout(std::time::Duration::from_secs(5), socket.next())
+        .await
+        .expect("close timeout");
+    assert!(matches!(
+        closed,
+        None | Some(Ok(tokio_tungstenite::tungstenite::Message::Close(_)))
+    ));
+
+    harness.server.abort();
+}
+
+#[tokio::test]
+async fn operation_events_ws_rejects_unknown_operation() {
+    let harness = setup_ws_harness(true).await;
+
+    let url = format!("ws://{}/api/operations/missing/events/ws", harness.addr);
+    let mut req = url.into_client_request().expect("ws request");
+    req.headers_mut().insert(
+        "origin",
+        format!("http://{}", harness.addr).parse().expect("origin"),
+    );
+    req.headers_mut().insert(
+        "cookie",
+        format!("bastion_session={}", harness.session_id)
+            .parse()
+            .expect("cookie"),
+    );
+
+    let err = tokio_tungstenite::connect_async(req)
+        .await
+        .expect_err("expected not found");
+    match err {
+        WsError::Http(resp) => assert_eq!(resp.status(), axum::http::StatusCode::NOT_FOUND),
+        other => panic!("unexpected ws error: {other:?}"),
+    }
+
+    harness.server.abort();
+}
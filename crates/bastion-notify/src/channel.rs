@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+
+pub type ChannelFuture<'a> = Pin<Box<dyn Future<Output = Result<(), anyhow::Error>> + Send + 'a>>;
+
+/// A rendered notification, ready to send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderedMessage {
+    /// Only set by channels with a subject line (email).
+    pub subject: Option<String>,
+    pub body: String,
+}
+
+/// Message templates as seen by a channel.
+///
+/// Slots name the hub-wide templates (`wecom_markdown`, `email_subject`, `email_body`); chat
+/// channels without a template of their own reuse the email slots. A channel passes a
+/// destination's own template as `template_override` to use it instead of the slot.
+pub trait MessageTemplates {
+    fn render(&self, slot: &str, template_override: Option<&str>) -> String;
+}
+
+/// Why a destination config was rejected; maps onto the API's field errors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DestinationError {
+    pub code: &'static str,
+    pub field: &'static str,
+    pub reason: &'static str,
+    pub message: String,
+    pub params: Vec<(&'static str, serde_json::Value)>,
+}
+
+impl DestinationError {
+    pub fn new(
+        code: &'static str,
+        field: &'static str,
+        reason: &'static str,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            code,
+            field,
+            reason,
+            message: message.into(),
+            params: Vec::new(),
+        }
+    }
+
+    pub fn with_param(mut self, name: &'static str, value: impl Into<serde_json::Value>) -> Self {
+        self.params.push((name, value.into()));
+        self
+    }
+}
+
+impl std::fmt::Display for DestinationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl std::error::Error for DestinationError {}
+
+/// A way of delivering notifications (WeCom bot, email, ...).
+///
+/// Destinations are Hub secrets of kind [`NotificationChannel::secret_kind`]; their JSON payload
+/// is what [`NotificationChannel::validate_destination`] returns.
+pub trait NotificationChannel: Send + Sync {
+    /// Channel id used in the queue, job specs and the API (`wecom_bot`, `email`, ...).
+    fn id(&self) -> &'static str;
+
+    /// Secret kind destinations of this channel are stored under.
+    fn secret_kind(&self) -> &'static str;
+
+    /// Wire protocol, reported in error envelopes (`http`, `smtp`, ...).
+    fn protocol(&self) -> &'static str;
+
+    /// Checks and normalizes a destination config before it is stored.
+    fn validate_destination(
+        &self,
+        destination: serde_json::Value,
+    ) -> Result<serde_json::Value, DestinationError>;
+
+    /// Whether a destination takes the notification of a run that ended with `status`
+    /// (`success`, `failed`, ...). A digest counts as `failed` when any run of its period failed;
+    /// test messages are always sent.
+    fn accepts_run_status(&self, _destination: &serde_json::Value, _status: &str) -> bool {
+        true
+    }
+
+    fn render(
+        &self,
+        destination: &serde_json::Value,
+        templates: &dyn MessageTemplates,
+    ) -> Result<RenderedMessage, anyhow::Error>;
+
+    fn send<'a>(
+        &'a self,
+        destination: &'a serde_json::Value,
+        message: &'a RenderedMessage,
+    ) -> ChannelFuture<'a>;
+}
+
+/// Notification channels by id, so a new channel only needs to register itself.
+#[derive(Default, Clone)]
+pub struct ChannelRegistry {
+    channels: BTreeMap<&'static str, Arc<dyn NotificationChannel>>,
+}
+
+impl ChannelRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the built-in `wecom_bot`, `email`, `slack`, `discord` and `sms` channels.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register(Arc::new(crate::wecom::WecomBotChannel))
+            .expect("builtin channel ids are unique");
+        registry
+            .register(Arc::new(crate::smtp::EmailChannel))
+            .expect("builtin channel ids are unique");
+        registry
+            .register(Arc::new(crate::slack::SlackChannel))
+            .expect("builtin channel ids are unique");
+        registry
+            .register(Arc::new(crate::discord::DiscordChannel))
+            .expect("builtin channel ids are unique");
+        registry
+            .register(Arc::new(crate::sms::SmsChannel))
+            .expect("builtin channel ids are unique");
+        registry
+    }
+
+    pub fn register(&mut self, channel: Arc<dyn NotificationChannel>) -> Result<(), anyhow::Error> {
+        let id = channel.id();
+        if self.channels.contains_key(id) {
+            anyhow::bail!("notification channel already registered: {id}");
+        }
+        if self
+            .channels
+            .values()
+            .any(|c| c.secret_kind() == channel.secret_kind())
+        {
+            anyhow::bail!(
+                "notification secret kind already registered: {}",
+                channel.secret_kind()
+            );
+        }
+        self.channels.insert(id, channel);
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Arc<dyn NotificationChannel>> {
+        self.channels.get(id)
+    }
+
+    pub fn for_secret_kind(&self, kind: &str) -> Option<&Arc<dyn NotificationChannel>> {
+        self.channels.values().find(|c| c.secret_kind() == kind)
+    }
+
+    /// Registered channels, sorted by id.
+    pub fn channels(&self) -> impl Iterator<Item = &Arc<dyn NotificationChannel>> {
+        self.channels.values()
+    }
+
+    /// `(channel id, secret kind)` pairs, as used by the destination queries.
+    pub fn destination_kinds(&self) -> Vec<(&'static str, &'static str)> {
+        self.channels
+            .values()
+            .map(|c| (c.id(), c.secret_kind()))
+            .collect()
+    }
+}
+
+/// The registry of built-in channels.
+pub fn builtin_channels() -> &'static ChannelRegistry {
+    static BUILTINS: OnceLock<ChannelRegistry> = OnceLock::new();
+    BUILTINS.get_or_init(ChannelRegistry::with_builtins)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{ChannelRegistry, MessageTemplates, builtin_channels};
+
+    struct Slots;
+
+    impl MessageTemplates for Slots {
+        fn render(&self, slot: &str, template_override: Option<&str>) -> String {
+            template_override.unwrap_or(slot).to_string()
+        }
+    }
+
+    #[test]
+    fn registry_rejects_duplicates_and_maps_secret_kinds() {
+        let mut registry = ChannelRegistry::with_builtins();
+        assert_eq!(
+            registry.destination_kinds(),
+            vec![
+                ("discord", "discord"),
+                ("email", "smtp"),
+                ("slack", "slack"),
+                ("sms", "sms"),
+                ("wecom_bot", "wecom_bot")
+            ]
+        );
+        assert_eq!(
+            registry.for_secret_kind("smtp").map(|c| c.id()),
+            Some("email")
+        );
+        assert!(registry.get("telegram").is_none());
+
+        let err = registry
+            .register(Arc::new(crate::wecom::WecomBotChannel))
+            .expect_err("duplicate");
+        assert!(err.to_string().contains("already registered"));
+    }
+
+    #[test]
+    fn channels_render_their_own_templates() {
+        let wecom = builtin_channels().get("wecom_bot").unwrap();
+        let message = wecom
+            .render(&serde_json::json!({ "webhook_url": "https://x" }), &Slots)
+            .unwrap();
+        assert_eq!(message.subject, None);
+        assert_eq!(message.body, "wecom_markdown");
+
+        let email = builtin_channels().get("email").unwrap();
+        let destination = serde_json::json!({
+            "host": "smtp.example.com",
+            "port": 587,
+            "username": "",
+            "password": "",
+            "from": "bastion@example.com",
+            "to": ["ops@example.com"],
+            "tls": "starttls",
+            "body_template": "custom body",
+        });
+        let message = email.render(&destination, &Slots).unwrap();
+        assert_eq!(message.subject.as_deref(), Some("email_subject"));
+        assert_eq!(message.body, "custom body");
+
+        let slack = builtin_channels().get("slack").unwrap();
+        let message = slack
+            .render(&serde_json::json!({ "webhook_url": "https://x" }), &Slots)
+            .unwrap();
+        assert_eq!(message.subject.as_deref(), Some("email_subject"));
+        assert_eq!(message.body, "email_body");
+
+        let sms = builtin_channels().get("sms").unwrap();
+        let message = sms.render(&serde_json::json!({}), &Slots).unwrap();
+        assert_eq!(message.subject, None);
+        assert_eq!(message.body, "Bastion {{status_text}}: {{job_name}}");
+    }
+}
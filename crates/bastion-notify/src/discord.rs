@@ -0,0 +1,110 @@
+use serde::Deserialize as _;
+
+use crate::channel::{
+    ChannelFuture, DestinationError, MessageTemplates, NotificationChannel, RenderedMessage,
+};
+use crate::webhook::{WebhookDestination, post_json, truncate};
+
+/// Discord limits embed titles to 256 and descriptions to 4096 characters.
+const TITLE_MAX_CHARS: usize = 256;
+const DESCRIPTION_MAX_CHARS: usize = 4096;
+
+fn discord_payload(message: &RenderedMessage) -> serde_json::Value {
+    let title = message.subject.as_deref().unwrap_or("Bastion");
+    serde_json::json!({
+        "username": "Bastion",
+        "embeds": [{
+            "title": truncate(title, TITLE_MAX_CHARS),
+            "description": truncate(&message.body, DESCRIPTION_MAX_CHARS),
+        }],
+        // Run output must never ping @everyone or roles.
+        "allowed_mentions": { "parse": [] },
+    })
+}
+
+fn validate_discord_webhook_response(
+    status: reqwest::StatusCode,
+    body: &str,
+) -> Result<(), anyhow::Error> {
+    if !status.is_success() {
+        anyhow::bail!("discord webhook http {status}: {}", body.trim());
+    }
+    Ok(())
+}
+
+/// Discord channel webhook, sent as an embed.
+pub struct DiscordChannel;
+
+impl NotificationChannel for DiscordChannel {
+    fn id(&self) -> &'static str {
+        "discord"
+    }
+
+    fn secret_kind(&self) -> &'static str {
+        "discord"
+    }
+
+    fn protocol(&self) -> &'static str {
+        "http"
+    }
+
+    fn validate_destination(
+        &self,
+        destination: serde_json::Value,
+    ) -> Result<serde_json::Value, DestinationError> {
+        crate::webhook::validate_destination(&destination)
+    }
+
+    fn render(
+        &self,
+        _destination: &serde_json::Value,
+        templates: &dyn MessageTemplates,
+    ) -> Result<RenderedMessage, anyhow::Error> {
+        Ok(RenderedMessage {
+            subject: Some(templates.render("email_subject", None)),
+            body: templates.render("email_body", None),
+        })
+    }
+
+    fn send<'a>(
+        &'a self,
+        destination: &'a serde_json::Value,
+        message: &'a RenderedMessage,
+    ) -> ChannelFuture<'a> {
+        Box::pin(async move {
+            let destination = WebhookDestination::deserialize(destination)?;
+            let (status, body) =
+                post_json(&destination.webhook_url, &discord_payload(message)).await?;
+            validate_discord_webhook_response(status, &body)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channel::RenderedMessage;
+
+    use super::{discord_payload, validate_discord_webhook_response};
+
+    #[test]
+    fn payload_is_a_single_embed_without_mentions() {
+        let payload = discord_payload(&RenderedMessage {
+            subject: Some("Backup succeeded".to_string()),
+            body: "Job: nightly".to_string(),
+        });
+        assert_eq!(payload["embeds"][0]["title"], "Backup succeeded");
+        assert_eq!(payload["embeds"][0]["description"], "Job: nightly");
+        assert_eq!(payload["allowed_mentions"]["parse"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn non_success_status_fails() {
+        assert!(validate_discord_webhook_response(reqwest::StatusCode::NO_CONTENT, "").is_ok());
+        let err = validate_discord_webhook_response(
+            reqwest::StatusCode::BAD_REQUEST,
+            r#"{"message":"Invalid Webhook Token"}"#,
+        )
+        .expect_err("expected error");
+        assert!(err.to_string().contains("discord webhook http 400"));
+    }
+}
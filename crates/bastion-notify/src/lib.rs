@@ -1,2 +1,13 @@
+pub mod channel;
+pub mod discord;
+pub mod ping;
+pub mod slack;
+pub mod sms;
 pub mod smtp;
+pub mod webhook;
 pub mod wecom;
+
+pub use channel::{
+    ChannelRegistry, DestinationError, MessageTemplates, NotificationChannel, RenderedMessage,
+    builtin_channels,
+};
@@ -0,0 +1,38 @@
+fn validate_ping_response(status: reqwest::StatusCode) -> Result<(), anyhow::Error> {
+    if !status.is_success() {
+        anyhow::bail!("ping http {status}");
+    }
+    Ok(())
+}
+
+/// Pings a monitoring URL (Healthchecks.io style) with a plain GET request.
+pub async fn send_ping(url: &str) -> Result<(), anyhow::Error> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()?;
+
+    // reqwest errors carry the request URL, which embeds the check's secret id.
+    let res = client
+        .get(url)
+        .send()
+        .await
+        .map_err(reqwest::Error::without_url)?;
+    validate_ping_response(res.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_ping_response;
+
+    #[test]
+    fn validate_success_status_passes() -> Result<(), anyhow::Error> {
+        validate_ping_response(reqwest::StatusCode::OK)
+    }
+
+    #[test]
+    fn validate_non_success_status_fails() {
+        let err =
+            validate_ping_response(reqwest::StatusCode::NOT_FOUND).expect_err("expected error");
+        assert!(err.to_string().contains("ping http 404"));
+    }
+}
@@ -0,0 +1,126 @@
+use serde::Deserialize as _;
+
+use crate::channel::{
+    ChannelFuture, DestinationError, MessageTemplates, NotificationChannel, RenderedMessage,
+};
+use crate::webhook::{WebhookDestination, post_json, truncate};
+
+/// Slack limits header blocks to 150 and section text to 3000 characters.
+const HEADER_MAX_CHARS: usize = 150;
+const SECTION_MAX_CHARS: usize = 3000;
+
+/// Slack `mrkdwn` treats `&`, `<` and `>` as control characters.
+fn escape_mrkdwn(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn slack_payload(message: &RenderedMessage) -> serde_json::Value {
+    let title = message.subject.as_deref().unwrap_or("Bastion");
+    serde_json::json!({
+        // Shown in push notifications and clients without block support.
+        "text": truncate(title, SECTION_MAX_CHARS),
+        "blocks": [
+            {
+                "type": "header",
+                "text": { "type": "plain_text", "text": truncate(title, HEADER_MAX_CHARS) },
+            },
+            {
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": truncate(&escape_mrkdwn(&message.body), SECTION_MAX_CHARS),
+                },
+            },
+        ],
+    })
+}
+
+fn validate_slack_webhook_response(
+    status: reqwest::StatusCode,
+    body: &str,
+) -> Result<(), anyhow::Error> {
+    if !status.is_success() {
+        anyhow::bail!("slack webhook http {status}: {}", body.trim());
+    }
+    Ok(())
+}
+
+/// Slack incoming webhook, sent as a header block plus a `mrkdwn` section.
+pub struct SlackChannel;
+
+impl NotificationChannel for SlackChannel {
+    fn id(&self) -> &'static str {
+        "slack"
+    }
+
+    fn secret_kind(&self) -> &'static str {
+        "slack"
+    }
+
+    fn protocol(&self) -> &'static str {
+        "http"
+    }
+
+    fn validate_destination(
+        &self,
+        destination: serde_json::Value,
+    ) -> Result<serde_json::Value, DestinationError> {
+        crate::webhook::validate_destination(&destination)
+    }
+
+    fn render(
+        &self,
+        _destination: &serde_json::Value,
+        templates: &dyn MessageTemplates,
+    ) -> Result<RenderedMessage, anyhow::Error> {
+        Ok(RenderedMessage {
+            subject: Some(templates.render("email_subject", None)),
+            body: templates.render("email_body", None),
+        })
+    }
+
+    fn send<'a>(
+        &'a self,
+        destination: &'a serde_json::Value,
+        message: &'a RenderedMessage,
+    ) -> ChannelFuture<'a> {
+        Box::pin(async move {
+            let destination = WebhookDestination::deserialize(destination)?;
+            let (status, body) =
+                post_json(&destination.webhook_url, &slack_payload(message)).await?;
+            validate_slack_webhook_response(status, &body)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channel::RenderedMessage;
+
+    use super::{slack_payload, validate_slack_webhook_response};
+
+    #[test]
+    fn payload_uses_header_and_escaped_mrkdwn_section() {
+        let payload = slack_payload(&RenderedMessage {
+            subject: Some("Backup failed".to_string()),
+            body: "Job: <nightly> & co".to_string(),
+        });
+        assert_eq!(payload["text"], "Backup failed");
+        assert_eq!(payload["blocks"][0]["type"], "header");
+        assert_eq!(payload["blocks"][0]["text"]["text"], "Backup failed");
+        assert_eq!(
+            payload["blocks"][1]["text"]["text"],
+            "Job: &lt;nightly&gt; &amp; co"
+        );
+    }
+
+    #[test]
+    fn non_success_status_fails() {
+        assert!(validate_slack_webhook_response(reqwest::StatusCode::OK, "ok").is_ok());
+        let err = validate_slack_webhook_response(reqwest::StatusCode::NOT_FOUND, "no_team")
+            .expect_err("expected error");
+        assert!(err.to_string().contains("slack webhook http 404"));
+    }
+}
@@ -0,0 +1,367 @@
+//! SMS through a generic HTTP gateway (Twilio, Aliyun, an in-house paging service, ...).
+
+use serde::Deserialize as _;
+
+use crate::channel::{
+    ChannelFuture, DestinationError, MessageTemplates, NotificationChannel, RenderedMessage,
+};
+use crate::webhook::truncate;
+
+/// One SMS segment; longer texts are cut rather than split.
+const MESSAGE_MAX_CHARS: usize = 160;
+
+/// Short on purpose: job name and status are all an on-call phone needs.
+const MESSAGE_TEMPLATE: &str = "Bastion {{status_text}}: {{job_name}}";
+
+const DEFAULT_BODY_TEMPLATE: &str = r#"{"to":"{{to}}","message":"{{message}}"}"#;
+const DEFAULT_CONTENT_TYPE: &str = "application/json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmsGatewayMethod {
+    Get,
+    #[default]
+    Post,
+}
+
+/// Destination payload of an `sms` secret.
+///
+/// `url_template` and `body_template` may use `{{to}}` and `{{message}}`; values are URL-encoded in
+/// the URL and escaped for the body's content type.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SmsGatewayDestination {
+    pub url_template: String,
+    #[serde(default)]
+    pub method: SmsGatewayMethod,
+    /// Sent as is, e.g. `Authorization: Bearer <token>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_header: Option<String>,
+    /// POST body; defaults to `{"to":"{{to}}","message":"{{message}}"}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_template: Option<String>,
+    /// POST content type; defaults to `application/json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// Phone number (or gateway recipient id).
+    pub to: String,
+    /// Skip notifications for runs that did not fail.
+    #[serde(default = "default_failures_only")]
+    pub failures_only: bool,
+}
+
+fn default_failures_only() -> bool {
+    true
+}
+
+impl SmsGatewayDestination {
+    fn content_type(&self) -> &str {
+        self.content_type.as_deref().unwrap_or(DEFAULT_CONTENT_TYPE)
+    }
+}
+
+fn url_encode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+/// Escapes a value for use inside a body of `content_type`.
+fn escape_body_value(value: &str, content_type: &str) -> String {
+    let content_type = content_type.to_ascii_lowercase();
+    if content_type.contains("x-www-form-urlencoded") {
+        url_encode(value)
+    } else if content_type.contains("json") {
+        let quoted = serde_json::Value::String(value.to_string()).to_string();
+        quoted[1..quoted.len() - 1].to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn fill(template: &str, to: &str, message: &str) -> String {
+    template
+        .replace("{{to}}", to)
+        .replace("{{message}}", message)
+}
+
+/// The URL and (for POST) body sent to the gateway.
+fn gateway_request(destination: &SmsGatewayDestination, message: &str) -> (String, Option<String>) {
+    let url = fill(
+        &destination.url_template,
+        &url_encode(&destination.to),
+        &url_encode(message),
+    );
+    let body = match destination.method {
+        SmsGatewayMethod::Get => None,
+        SmsGatewayMethod::Post => {
+            let content_type = destination.content_type();
+            Some(fill(
+                destination
+                    .body_template
+                    .as_deref()
+                    .unwrap_or(DEFAULT_BODY_TEMPLATE),
+                &escape_body_value(&destination.to, content_type),
+                &escape_body_value(message, content_type),
+            ))
+        }
+    };
+    (url, body)
+}
+
+fn parse_auth_header(value: &str) -> Option<(reqwest::header::HeaderName, &str)> {
+    let (name, value) = value.split_once(':')?;
+    let name = reqwest::header::HeaderName::from_bytes(name.trim().as_bytes()).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then_some((name, value))
+}
+
+fn validate_sms_gateway_response(
+    status: reqwest::StatusCode,
+    body: &str,
+) -> Result<(), anyhow::Error> {
+    if !status.is_success() {
+        anyhow::bail!("sms gateway http {status}: {}", truncate(body.trim(), 200));
+    }
+    Ok(())
+}
+
+pub async fn send_sms(
+    destination: &SmsGatewayDestination,
+    message: &str,
+) -> Result<(), anyhow::Error> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()?;
+
+    let (url, body) = gateway_request(destination, message);
+    let mut req = match destination.method {
+        SmsGatewayMethod::Get => client.get(&url),
+        SmsGatewayMethod::Post => client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, destination.content_type())
+            .body(body.unwrap_or_default()),
+    };
+    if let Some(header) = destination.auth_header.as_deref() {
+        let (name, value) = parse_auth_header(header)
+            .ok_or_else(|| anyhow::anyhow!("invalid sms gateway auth header"))?;
+        req = req.header(name, value);
+    }
+
+    let res = req.send().await?;
+    let status = res.status();
+    let body = res.text().await?;
+    validate_sms_gateway_response(status, &body)
+}
+
+/// Short text messages through an HTTP SMS gateway.
+pub struct SmsChannel;
+
+impl NotificationChannel for SmsChannel {
+    fn id(&self) -> &'static str {
+        "sms"
+    }
+
+    fn secret_kind(&self) -> &'static str {
+        "sms"
+    }
+
+    fn protocol(&self) -> &'static str {
+        "http"
+    }
+
+    fn validate_destination(
+        &self,
+        destination: serde_json::Value,
+    ) -> Result<serde_json::Value, DestinationError> {
+        let mut payload = SmsGatewayDestination::deserialize(destination).map_err(|error| {
+            DestinationError::new(
+                "invalid_destination",
+                "destination",
+                "invalid_format",
+                error.to_string(),
+            )
+        })?;
+
+        payload.url_template = payload.url_template.trim().to_string();
+        if payload.url_template.is_empty() {
+            return Err(DestinationError::new(
+                "invalid_webhook_url",
+                "url_template",
+                "required",
+                "Gateway URL is required",
+            ));
+        }
+        let url = url::Url::parse(&fill(&payload.url_template, "to", "message")).map_err(|_| {
+            DestinationError::new(
+                "invalid_webhook_url",
+                "url_template",
+                "invalid_format",
+                "Gateway URL is invalid",
+            )
+        })?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(DestinationError::new(
+                "invalid_webhook_url",
+                "url_template",
+                "invalid_scheme",
+                "Gateway URL must be http(s)",
+            )
+            .with_param("scheme", url.scheme()));
+        }
+
+        payload.auth_header = payload
+            .auth_header
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        if let Some(header) = payload.auth_header.as_deref()
+            && parse_auth_header(header).is_none()
+        {
+            return Err(DestinationError::new(
+                "invalid_auth_header",
+                "auth_header",
+                "invalid_format",
+                "Auth header must look like `Name: value`",
+            ));
+        }
+
+        payload.body_template = payload.body_template.filter(|v| !v.trim().is_empty());
+        payload.content_type = payload
+            .content_type
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        payload.to = payload.to.trim().to_string();
+        if payload.to.is_empty() {
+            return Err(DestinationError::new(
+                "invalid_to",
+                "to",
+                "required",
+                "SMS recipient is required",
+            ));
+        }
+
+        Ok(serde_json::json!(payload))
+    }
+
+    fn accepts_run_status(&self, destination: &serde_json::Value, status: &str) -> bool {
+        let failures_only = destination
+            .get("failures_only")
+            .and_then(|v| v.as_bool())
+            .unwrap_or_else(default_failures_only);
+        !failures_only || status == "failed"
+    }
+
+    fn render(
+        &self,
+        _destination: &serde_json::Value,
+        templates: &dyn MessageTemplates,
+    ) -> Result<RenderedMessage, anyhow::Error> {
+        let text = templates.render("email_subject", Some(MESSAGE_TEMPLATE));
+        Ok(RenderedMessage {
+            subject: None,
+            body: truncate(text.trim(), MESSAGE_MAX_CHARS),
+        })
+    }
+
+    fn send<'a>(
+        &'a self,
+        destination: &'a serde_json::Value,
+        message: &'a RenderedMessage,
+    ) -> ChannelFuture<'a> {
+        Box::pin(async move {
+            let destination = SmsGatewayDestination::deserialize(destination)?;
+            send_sms(&destination, &message.body).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channel::NotificationChannel as _;
+
+    use super::{SmsChannel, SmsGatewayDestination, SmsGatewayMethod, gateway_request};
+
+    fn destination(method: SmsGatewayMethod) -> SmsGatewayDestination {
+        SmsGatewayDestination {
+            url_template: "https://sms.example.com/send?to={{to}}&text={{message}}".to_string(),
+            method,
+            auth_header: None,
+            body_template: None,
+            content_type: None,
+            to: "+86 138".to_string(),
+            failures_only: true,
+        }
+    }
+
+    #[test]
+    fn get_requests_url_encode_placeholders() {
+        let (url, body) =
+            gateway_request(&destination(SmsGatewayMethod::Get), "Bastion Failed: a&b");
+        assert_eq!(
+            url,
+            "https://sms.example.com/send?to=%2B86+138&text=Bastion+Failed%3A+a%26b"
+        );
+        assert!(body.is_none());
+    }
+
+    #[test]
+    fn post_bodies_escape_for_their_content_type() {
+        let mut dest = destination(SmsGatewayMethod::Post);
+        let (_, body) = gateway_request(&dest, r#"job "db""#);
+        assert_eq!(
+            body.as_deref(),
+            Some(r#"{"to":"+86 138","message":"job \"db\""}"#)
+        );
+
+        dest.content_type = Some("application/x-www-form-urlencoded".to_string());
+        dest.body_template = Some("To={{to}}&Body={{message}}".to_string());
+        let (_, body) = gateway_request(&dest, "a b");
+        assert_eq!(body.as_deref(), Some("To=%2B86+138&Body=a+b"));
+    }
+
+    #[test]
+    fn destination_validation_requires_url_and_recipient() {
+        let err = SmsChannel
+            .validate_destination(serde_json::json!({ "url_template": "", "to": "1" }))
+            .unwrap_err();
+        assert_eq!((err.code, err.reason), ("invalid_webhook_url", "required"));
+
+        let err = SmsChannel
+            .validate_destination(serde_json::json!({
+                "url_template": "https://sms.example.com/{{to}}",
+                "to": "1",
+                "auth_header": "Bearer abc",
+            }))
+            .unwrap_err();
+        assert_eq!(err.code, "invalid_auth_header");
+
+        let err = SmsChannel
+            .validate_destination(serde_json::json!({
+                "url_template": "https://sms.example.com/{{to}}",
+                "to": " ",
+            }))
+            .unwrap_err();
+        assert_eq!(err.code, "invalid_to");
+
+        let ok = SmsChannel
+            .validate_destination(serde_json::json!({
+                "url_template": " https://sms.example.com/{{to}} ",
+                "to": "+1 555",
+                "auth_header": "Authorization: Bearer abc",
+                "body_template": "",
+            }))
+            .unwrap();
+        assert_eq!(ok["url_template"], "https://sms.example.com/{{to}}");
+        assert_eq!(ok["method"], "post");
+        assert_eq!(ok["failures_only"], true);
+        assert!(ok.get("body_template").is_none());
+    }
+
+    #[test]
+    fn failures_only_destinations_skip_other_runs() {
+        let paging = serde_json::json!({ "failures_only": true });
+        assert!(SmsChannel.accepts_run_status(&paging, "failed"));
+        assert!(!SmsChannel.accepts_run_status(&paging, "success"));
+
+        let all = serde_json::json!({ "failures_only": false });
+        assert!(SmsChannel.accepts_run_status(&all, "success"));
+    }
+}
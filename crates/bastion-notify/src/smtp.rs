@@ -1,6 +1,11 @@
 use lettre::message::header::ContentType;
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::Deserialize as _;
+
+use crate::channel::{
+    ChannelFuture, DestinationError, MessageTemplates, NotificationChannel, RenderedMessage,
+};
 
 #[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -19,6 +24,12 @@ pub struct SmtpSecretPayload {
     pub from: String,
     pub to: Vec<String>,
     pub tls: SmtpTlsMode,
+    /// Optional per-destination subject template (falls back to the hub-wide template).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject_template: Option<String>,
+    /// Optional per-destination body template (falls back to the hub-wide template).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_template: Option<String>,
 }
 
 pub async fn send_plain_text(
@@ -66,9 +77,153 @@ pub fn is_valid_mailbox(addr: &str) -> bool {
     addr.parse::<lettre::message::Mailbox>().is_ok()
 }
 
+/// Email over SMTP, sent as plain text.
+pub struct EmailChannel;
+
+impl NotificationChannel for EmailChannel {
+    fn id(&self) -> &'static str {
+        "email"
+    }
+
+    fn secret_kind(&self) -> &'static str {
+        "smtp"
+    }
+
+    fn protocol(&self) -> &'static str {
+        "smtp"
+    }
+
+    fn validate_destination(
+        &self,
+        destination: serde_json::Value,
+    ) -> Result<serde_json::Value, DestinationError> {
+        let mut payload = SmtpSecretPayload::deserialize(destination).map_err(|error| {
+            DestinationError::new(
+                "invalid_destination",
+                "destination",
+                "invalid_format",
+                error.to_string(),
+            )
+        })?;
+
+        payload.host = payload.host.trim().to_string();
+        if payload.host.is_empty() {
+            return Err(DestinationError::new(
+                "invalid_host",
+                "host",
+                "required",
+                "SMTP host is required",
+            ));
+        }
+        if payload.port == 0 {
+            return Err(DestinationError::new(
+                "invalid_port",
+                "port",
+                "required",
+                "SMTP port is required",
+            ));
+        }
+
+        payload.from = payload.from.trim().to_string();
+        if payload.from.is_empty() {
+            return Err(DestinationError::new(
+                "invalid_from",
+                "from",
+                "required",
+                "SMTP from is required",
+            ));
+        }
+        if !is_valid_mailbox(&payload.from) {
+            return Err(DestinationError::new(
+                "invalid_from",
+                "from",
+                "invalid_format",
+                "Invalid SMTP from address",
+            ));
+        }
+
+        let mut to = Vec::new();
+        for (index, item) in payload.to.iter().enumerate() {
+            let addr = item.trim();
+            if addr.is_empty() {
+                continue;
+            }
+            if !is_valid_mailbox(addr) {
+                return Err(DestinationError::new(
+                    "invalid_to",
+                    "to",
+                    "invalid_format",
+                    "Invalid SMTP recipient address",
+                )
+                .with_param("index", index));
+            }
+            to.push(addr.to_string());
+        }
+        if to.is_empty() {
+            return Err(DestinationError::new(
+                "invalid_to",
+                "to",
+                "required",
+                "SMTP to is required",
+            ));
+        }
+        payload.to = to;
+
+        payload.username = payload.username.trim().to_string();
+        if !payload.username.is_empty() && payload.password.trim().is_empty() {
+            return Err(DestinationError::new(
+                "invalid_password",
+                "password",
+                "required_with_username",
+                "SMTP password is required when username is set",
+            ));
+        }
+
+        // Blank templates mean "use the hub-wide default".
+        payload.subject_template = payload.subject_template.filter(|v| !v.trim().is_empty());
+        payload.body_template = payload.body_template.filter(|v| !v.trim().is_empty());
+
+        serde_json::to_value(payload).map_err(|error| {
+            DestinationError::new(
+                "invalid_destination",
+                "destination",
+                "invalid_format",
+                error.to_string(),
+            )
+        })
+    }
+
+    fn render(
+        &self,
+        destination: &serde_json::Value,
+        templates: &dyn MessageTemplates,
+    ) -> Result<RenderedMessage, anyhow::Error> {
+        // Per-destination templates override the hub-wide defaults.
+        let payload = SmtpSecretPayload::deserialize(destination)?;
+        Ok(RenderedMessage {
+            subject: Some(templates.render("email_subject", payload.subject_template.as_deref())),
+            body: templates.render("email_body", payload.body_template.as_deref()),
+        })
+    }
+
+    fn send<'a>(
+        &'a self,
+        destination: &'a serde_json::Value,
+        message: &'a RenderedMessage,
+    ) -> ChannelFuture<'a> {
+        Box::pin(async move {
+            let payload = SmtpSecretPayload::deserialize(destination)?;
+            let subject = message.subject.as_deref().unwrap_or_default();
+            send_plain_text(&payload, subject, &message.body).await
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::is_valid_mailbox;
+    use crate::channel::NotificationChannel as _;
+
+    use super::{EmailChannel, is_valid_mailbox};
 
     #[test]
     fn mailbox_valid_simple_address() {
@@ -85,4 +240,35 @@ mod tests {
         assert!(!is_valid_mailbox("not-an-email"));
         assert!(!is_valid_mailbox("a@b.com\nBcc: evil@example.com"));
     }
+
+    #[test]
+    fn destination_validation_normalizes_recipients_and_templates() {
+        let destination = serde_json::json!({
+            "host": " smtp.example.com ",
+            "port": 587,
+            "username": "",
+            "password": "",
+            "from": "bastion@example.com",
+            "to": ["", " ops@example.com "],
+            "tls": "starttls",
+            "subject_template": "  ",
+        });
+        let out = EmailChannel.validate_destination(destination).unwrap();
+        assert_eq!(out["host"], "smtp.example.com");
+        assert_eq!(out["to"], serde_json::json!(["ops@example.com"]));
+        assert!(out.get("subject_template").is_none());
+
+        let err = EmailChannel
+            .validate_destination(serde_json::json!({
+                "host": "smtp.example.com",
+                "port": 587,
+                "username": "bastion",
+                "password": "",
+                "from": "bastion@example.com",
+                "to": ["ops@example.com"],
+                "tls": "starttls",
+            }))
+            .expect_err("password");
+        assert_eq!(err.code, "invalid_password");
+    }
 }
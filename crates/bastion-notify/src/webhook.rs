@@ -0,0 +1,97 @@
+//! Helpers shared by the chat webhook channels (WeCom, Slack, Discord).
+
+use serde::{Deserialize, Serialize};
+
+use crate::channel::DestinationError;
+
+/// Destination payload of a webhook secret (`wecom_bot`, `slack`, `discord`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDestination {
+    pub webhook_url: String,
+}
+
+/// Checks the `webhook_url` of a destination config and returns the normalized payload.
+pub(crate) fn validate_destination(
+    destination: &serde_json::Value,
+) -> Result<serde_json::Value, DestinationError> {
+    let webhook_url = destination
+        .get("webhook_url")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .trim();
+    if webhook_url.is_empty() {
+        return Err(DestinationError::new(
+            "invalid_webhook_url",
+            "webhook_url",
+            "required",
+            "Webhook URL is required",
+        ));
+    }
+    let url = url::Url::parse(webhook_url).map_err(|_| {
+        DestinationError::new(
+            "invalid_webhook_url",
+            "webhook_url",
+            "invalid_format",
+            "Webhook URL is invalid",
+        )
+    })?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(DestinationError::new(
+            "invalid_webhook_url",
+            "webhook_url",
+            "invalid_scheme",
+            "Webhook URL must be http(s)",
+        )
+        .with_param("scheme", url.scheme()));
+    }
+
+    Ok(serde_json::json!(WebhookDestination {
+        webhook_url: webhook_url.to_string(),
+    }))
+}
+
+/// Posts `payload` as JSON and returns the response status and body.
+pub(crate) async fn post_json(
+    webhook_url: &str,
+    payload: &serde_json::Value,
+) -> Result<(reqwest::StatusCode, String), anyhow::Error> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()?;
+
+    // reqwest errors carry the request URL, and webhook URLs embed their token.
+    let res = client
+        .post(webhook_url)
+        .json(payload)
+        .send()
+        .await
+        .map_err(reqwest::Error::without_url)?;
+    let status = res.status();
+    let body = res.text().await.map_err(reqwest::Error::without_url)?;
+    Ok((status, body))
+}
+
+/// Cuts `text` to at most `max_chars` characters, marking the cut with an ellipsis.
+pub(crate) fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut out = text
+        .chars()
+        .take(max_chars.saturating_sub(1))
+        .collect::<String>();
+    out.push('…');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::truncate;
+
+    #[test]
+    fn truncate_keeps_short_text_and_marks_cuts() {
+        assert_eq!(truncate("hello", 5), "hello");
+        assert_eq!(truncate("hello world", 6), "hello…");
+        assert_eq!(truncate("日本語テキスト", 3), "日本…");
+    }
+}
@@ -1,5 +1,10 @@
 use serde::Deserialize;
 
+use crate::channel::{
+    ChannelFuture, DestinationError, MessageTemplates, NotificationChannel, RenderedMessage,
+};
+use crate::webhook::{WebhookDestination, post_json};
+
 #[derive(Debug, Deserialize)]
 struct WecomWebhookResponse {
     errcode: i64,
@@ -23,29 +28,67 @@ fn validate_wecom_webhook_response(
 }
 
 pub async fn send_markdown(webhook_url: &str, content: &str) -> Result<(), anyhow::Error> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()?;
-
-    let res = client
-        .post(webhook_url)
-        .json(&serde_json::json!({
-            "msgtype": "markdown",
-            "markdown": { "content": content },
-        }))
-        .send()
-        .await?;
-
-    let status = res.status();
-    let body = res.text().await?;
+    let payload = serde_json::json!({
+        "msgtype": "markdown",
+        "markdown": { "content": content },
+    });
+    let (status, body) = post_json(webhook_url, &payload).await?;
     validate_wecom_webhook_response(status, &body)?;
 
     Ok(())
 }
 
+/// WeCom group bot webhook, sent as markdown.
+pub struct WecomBotChannel;
+
+impl NotificationChannel for WecomBotChannel {
+    fn id(&self) -> &'static str {
+        "wecom_bot"
+    }
+
+    fn secret_kind(&self) -> &'static str {
+        "wecom_bot"
+    }
+
+    fn protocol(&self) -> &'static str {
+        "http"
+    }
+
+    fn validate_destination(
+        &self,
+        destination: serde_json::Value,
+    ) -> Result<serde_json::Value, DestinationError> {
+        crate::webhook::validate_destination(&destination)
+    }
+
+    fn render(
+        &self,
+        _destination: &serde_json::Value,
+        templates: &dyn MessageTemplates,
+    ) -> Result<RenderedMessage, anyhow::Error> {
+        Ok(RenderedMessage {
+            subject: None,
+            body: templates.render("wecom_markdown", None),
+        })
+    }
+
+    fn send<'a>(
+        &'a self,
+        destination: &'a serde_json::Value,
+        message: &'a RenderedMessage,
+    ) -> ChannelFuture<'a> {
+        Box::pin(async move {
+            let destination = WebhookDestination::deserialize(destination)?;
+            send_markdown(&destination.webhook_url, &message.body).await
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::validate_wecom_webhook_response;
+    use crate::channel::NotificationChannel as _;
+
+    use super::{WecomBotChannel, validate_wecom_webhook_response};
 
     #[test]
     fn validate_ok_response_passes() -> Result<(), anyhow::Error> {
@@ -73,4 +116,21 @@ mod tests {
     fn validate_invalid_json_fails() {
         assert!(validate_wecom_webhook_response(reqwest::StatusCode::OK, "not json").is_err());
     }
+
+    #[test]
+    fn destination_requires_http_webhook_url() {
+        let ok = WecomBotChannel
+            .validate_destination(serde_json::json!({ "webhook_url": " https://x/hook " }))
+            .unwrap();
+        assert_eq!(ok["webhook_url"], "https://x/hook");
+
+        let err = WecomBotChannel
+            .validate_destination(serde_json::json!({ "webhook_url": "ftp://x" }))
+            .expect_err("scheme");
+        assert_eq!(err.reason, "invalid_scheme");
+        let err = WecomBotChannel
+            .validate_destination(serde_json::json!({}))
+            .expect_err("required");
+        assert_eq!(err.reason, "required");
+    }
 }
@@ -0,0 +1,22 @@
+-- Storage usage rollups ("which jobs consume space").
+--
+-- Aggregated from run_artifacts by a background loop so the usage dashboard does not
+-- scan every snapshot row on each request.
+
+CREATE TABLE IF NOT EXISTS storage_usage (
+  job_id TEXT NOT NULL,
+  node_id TEXT NOT NULL,
+  target_type TEXT NOT NULL,
+  target_key TEXT NOT NULL,
+  snapshots INTEGER NOT NULL,
+  pinned_snapshots INTEGER NOT NULL,
+  source_bytes INTEGER NOT NULL,
+  transfer_bytes INTEGER NOT NULL,
+  oldest_ended_at INTEGER,
+  newest_ended_at INTEGER,
+  refreshed_at INTEGER NOT NULL,
+  PRIMARY KEY (job_id, node_id, target_type, target_key),
+  FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_storage_usage_target ON storage_usage(target_type, target_key);
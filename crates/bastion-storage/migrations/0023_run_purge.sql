@@ -0,0 +1,9 @@
+-- One-shot "delete this run everywhere".
+--
+-- A purge request marks the run; the row (and its events via cascade) is removed once
+-- the run's snapshot artifacts are confirmed gone.
+ALTER TABLE runs ADD COLUMN purge_requested_at INTEGER;
+ALTER TABLE runs ADD COLUMN purge_requested_by_user_id INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_runs_purge_requested_at
+  ON runs(purge_requested_at) WHERE purge_requested_at IS NOT NULL;
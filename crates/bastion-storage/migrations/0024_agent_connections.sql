@@ -0,0 +1,18 @@
+-- Agent connectivity history: one row per hub <-> agent WebSocket session.
+--
+-- Open sessions have disconnected_at = NULL; sessions still open when the Hub stops are
+-- closed on the next startup with reason 'hub_restart'.
+CREATE TABLE IF NOT EXISTS agent_connections (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  agent_id TEXT NOT NULL,
+  connected_at INTEGER NOT NULL,
+  disconnected_at INTEGER,
+  peer_ip TEXT,
+  disconnect_reason TEXT,
+  FOREIGN KEY (agent_id) REFERENCES agents(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_agent_connections_agent_connected
+  ON agent_connections(agent_id, connected_at DESC);
+CREATE INDEX IF NOT EXISTS idx_agent_connections_disconnected_at
+  ON agent_connections(disconnected_at);
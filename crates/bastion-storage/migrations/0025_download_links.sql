@@ -0,0 +1,34 @@
+-- Pre-signed, time-limited download links.
+--
+-- Only the SHA-256 of the link token is stored. `kind` selects what `subject_id`/`path` refer to:
+-- 'snapshot_artifact' (run id + artifact file name) or 'restored_file' (restore operation id +
+-- absolute path on the Hub).
+CREATE TABLE IF NOT EXISTS download_links (
+  id TEXT PRIMARY KEY,
+  token_hash BLOB NOT NULL UNIQUE,
+  kind TEXT NOT NULL,
+  subject_id TEXT NOT NULL,
+  path TEXT NOT NULL,
+  created_by_user_id INTEGER,
+  created_at INTEGER NOT NULL,
+  expires_at INTEGER NOT NULL,
+  revoked_at INTEGER,
+  download_count INTEGER NOT NULL DEFAULT 0,
+  last_downloaded_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_download_links_created_at ON download_links(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_download_links_expires_at ON download_links(expires_at);
+
+-- Audit trail for download links (created / downloaded / revoked).
+CREATE TABLE IF NOT EXISTS download_link_audit (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  link_id TEXT NOT NULL,
+  ts INTEGER NOT NULL,
+  action TEXT NOT NULL,
+  user_id INTEGER,
+  peer_ip TEXT,
+  FOREIGN KEY (link_id) REFERENCES download_links(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_download_link_audit_link_ts ON download_link_audit(link_id, ts);
@@ -0,0 +1,8 @@
+-- Agent update channel: per-agent update policy and the last update offered by the Hub.
+--
+-- update_policy is one of 'auto' (follow the newest hosted release), 'manual' (only when an
+-- operator triggers it) or 'pinned' (converge on update_pinned_version).
+ALTER TABLE agents ADD COLUMN update_policy TEXT NOT NULL DEFAULT 'auto';
+ALTER TABLE agents ADD COLUMN update_pinned_version TEXT;
+ALTER TABLE agents ADD COLUMN last_update_offered_version TEXT;
+ALTER TABLE agents ADD COLUMN last_update_offered_at INTEGER;
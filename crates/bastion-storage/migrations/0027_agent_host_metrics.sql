@@ -0,0 +1,10 @@
+-- Latest host metrics sample per agent (load, memory, free space on staging/source paths).
+--
+-- Only the most recent sample is kept; it is replaced on every report.
+CREATE TABLE IF NOT EXISTS agent_host_metrics (
+  agent_id TEXT PRIMARY KEY,
+  collected_at INTEGER NOT NULL,
+  received_at INTEGER NOT NULL,
+  metrics_json TEXT NOT NULL,
+  FOREIGN KEY (agent_id) REFERENCES agents(id) ON DELETE CASCADE
+);
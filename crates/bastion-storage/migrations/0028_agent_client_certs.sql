@@ -0,0 +1,8 @@
+-- Per-agent client certificates issued by the Hub's agent CA.
+--
+-- client_cert_sha256 pins the currently valid certificate (hex SHA-256 of its DER); it is
+-- replaced on key rotation. When client_cert_required = 1, agent-authenticated requests must
+-- present that certificate in addition to the bearer key.
+ALTER TABLE agents ADD COLUMN client_cert_sha256 TEXT;
+ALTER TABLE agents ADD COLUMN client_cert_expires_at INTEGER;
+ALTER TABLE agents ADD COLUMN client_cert_required INTEGER NOT NULL DEFAULT 0;
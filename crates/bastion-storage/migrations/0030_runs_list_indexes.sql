@@ -0,0 +1,4 @@
+-- Keyset pagination for run listings orders by (started_at DESC, id DESC).
+DROP INDEX IF EXISTS idx_runs_job_id_started_at;
+CREATE INDEX IF NOT EXISTS idx_runs_job_id_started_at_id ON runs(job_id, started_at, id);
+CREATE INDEX IF NOT EXISTS idx_runs_started_at_id ON runs(started_at, id);
@@ -0,0 +1,27 @@
+-- Full-text index over run event messages and fields (external content: rows live in run_events).
+CREATE VIRTUAL TABLE IF NOT EXISTS run_events_fts USING fts5(
+  message,
+  fields_json,
+  content = 'run_events',
+  content_rowid = 'rowid'
+);
+
+CREATE TRIGGER IF NOT EXISTS run_events_fts_ai AFTER INSERT ON run_events BEGIN
+  INSERT INTO run_events_fts(rowid, message, fields_json)
+  VALUES (new.rowid, new.message, new.fields_json);
+END;
+
+CREATE TRIGGER IF NOT EXISTS run_events_fts_ad AFTER DELETE ON run_events BEGIN
+  INSERT INTO run_events_fts(run_events_fts, rowid, message, fields_json)
+  VALUES ('delete', old.rowid, old.message, old.fields_json);
+END;
+
+CREATE TRIGGER IF NOT EXISTS run_events_fts_au AFTER UPDATE ON run_events BEGIN
+  INSERT INTO run_events_fts(run_events_fts, rowid, message, fields_json)
+  VALUES ('delete', old.rowid, old.message, old.fields_json);
+  INSERT INTO run_events_fts(rowid, message, fields_json)
+  VALUES (new.rowid, new.message, new.fields_json);
+END;
+
+-- Index events written before this migration.
+INSERT INTO run_events_fts(run_events_fts) VALUES ('rebuild');
@@ -0,0 +1,2 @@
+-- Random delay (0..=seconds) added to each scheduled trigger of a job.
+ALTER TABLE jobs ADD COLUMN schedule_jitter_seconds INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Job a bulk deploy/sync instantiated this job from, so re-applying the template updates it.
+ALTER TABLE jobs ADD COLUMN template_job_id TEXT;
+CREATE INDEX IF NOT EXISTS idx_jobs_template_agent ON jobs(template_job_id, agent_id);
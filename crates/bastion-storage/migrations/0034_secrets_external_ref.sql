@@ -0,0 +1,2 @@
+-- JSON reference to an external provider (Vault, AWS Secrets Manager); such rows hold no ciphertext.
+ALTER TABLE secrets ADD COLUMN external_ref TEXT;
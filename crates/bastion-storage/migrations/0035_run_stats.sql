@@ -0,0 +1,29 @@
+-- Run statistics rollups (success rate, duration, bytes per week).
+--
+-- Aggregated from runs and run_artifacts by a background loop so the dashboard does not
+-- scan every run on each load. Rates and averages are derived from the stored sums on read.
+
+CREATE TABLE IF NOT EXISTS run_stats (
+  job_id TEXT PRIMARY KEY,
+  window_days INTEGER NOT NULL,
+  runs_total INTEGER NOT NULL,
+  runs_success INTEGER NOT NULL,
+  runs_failed INTEGER NOT NULL,
+  duration_total_secs INTEGER NOT NULL,
+  duration_runs INTEGER NOT NULL,
+  last_ended_at INTEGER,
+  refreshed_at INTEGER NOT NULL,
+  FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS run_stats_weekly (
+  job_id TEXT NOT NULL,
+  -- YYYY-MM-DD of the Monday (UTC) starting the week.
+  week_start TEXT NOT NULL,
+  runs INTEGER NOT NULL,
+  source_bytes INTEGER NOT NULL,
+  transfer_bytes INTEGER NOT NULL,
+  refreshed_at INTEGER NOT NULL,
+  PRIMARY KEY (job_id, week_start),
+  FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE
+);
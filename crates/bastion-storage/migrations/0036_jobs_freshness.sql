@@ -0,0 +1,12 @@
+-- Expected max interval between successful runs of a job (0 disables freshness monitoring).
+ALTER TABLE jobs ADD COLUMN max_success_interval_seconds INTEGER NOT NULL DEFAULT 0;
+
+-- One row per job while it is stale, so each stale episode alerts once.
+CREATE TABLE IF NOT EXISTS stale_backup_alerts (
+  job_id TEXT PRIMARY KEY,
+  -- Failed run recorded for the alert (feeds notifications and run history).
+  run_id TEXT,
+  last_success_at INTEGER,
+  detected_at INTEGER NOT NULL,
+  FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE
+);
@@ -0,0 +1,18 @@
+-- Digest notifications: one message per destination per period instead of one per run.
+CREATE TABLE IF NOT EXISTS notification_digests (
+  id TEXT PRIMARY KEY,
+  period_start INTEGER NOT NULL,
+  period_end INTEGER NOT NULL,
+  channel TEXT NOT NULL,
+  secret_name TEXT NOT NULL,
+  status TEXT NOT NULL,
+  attempts INTEGER NOT NULL,
+  next_attempt_at INTEGER NOT NULL,
+  created_at INTEGER NOT NULL,
+  updated_at INTEGER NOT NULL,
+  last_error TEXT,
+  UNIQUE(period_end, channel, secret_name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_digests_due
+  ON notification_digests(status, next_attempt_at);
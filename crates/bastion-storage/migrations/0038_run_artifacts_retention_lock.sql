@@ -0,0 +1,6 @@
+-- Retention locks: snapshots that must not be deleted before a point in time.
+--
+-- Set from the job's `retention.lock_days` when the snapshot is indexed. Delete tasks
+-- for locked snapshots stay blocked until the lock expires.
+
+ALTER TABLE run_artifacts ADD COLUMN locked_until INTEGER;
@@ -0,0 +1,11 @@
+-- Audit log for sensitive Hub-wide actions (for example keypack export/import).
+CREATE TABLE IF NOT EXISTS audit_log (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  ts INTEGER NOT NULL,
+  action TEXT NOT NULL,
+  user_id INTEGER,
+  peer_ip TEXT,
+  detail TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_ts ON audit_log(ts);
@@ -0,0 +1,8 @@
+-- When the stored snapshot data went away (deleted by retention/an operator, or found missing).
+--
+-- The run and its artifact row outlive the data so run history stays searchable; listings use
+-- this column to show that the snapshot itself is gone.
+
+ALTER TABLE run_artifacts ADD COLUMN deleted_at INTEGER;
+
+UPDATE run_artifacts SET deleted_at = updated_at WHERE status IN ('deleted', 'missing');
@@ -0,0 +1,27 @@
+-- Run failures use the error code catalog (`network_target`, `disk_full`, ...).
+--
+-- Older runs stored free-form codes in runs.error; map them onto the catalog. The detailed code
+-- stays in summary_json.error_code.
+
+UPDATE runs
+SET error = CASE error
+  WHEN 'run_failed' THEN 'unknown'
+  WHEN 'agent_failed' THEN 'unknown'
+  WHEN 'fs_issues' THEN 'source_permission'
+  WHEN 'staging_disk_full' THEN 'disk_full'
+  WHEN 'snapshot_unavailable' THEN 'config'
+  WHEN 'unsupported_driver' THEN 'config'
+  WHEN 'driver_capability_mismatch' THEN 'config'
+  ELSE error
+END
+WHERE status = 'failed';
+
+-- Failed runs per job and error code over the run statistics window.
+CREATE TABLE IF NOT EXISTS run_stats_errors (
+  job_id TEXT NOT NULL,
+  error_code TEXT NOT NULL,
+  runs INTEGER NOT NULL,
+  refreshed_at INTEGER NOT NULL,
+  PRIMARY KEY (job_id, error_code),
+  FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+-- Run event compaction and retention.
+--
+-- events_compacted_at: progress snapshots collapsed and the per-run event cap applied.
+-- events_pruned_at: events older than the event retention removed (error events are kept).
+
+ALTER TABLE runs ADD COLUMN events_compacted_at INTEGER;
+ALTER TABLE runs ADD COLUMN events_pruned_at INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_runs_events_compaction_pending
+  ON runs(ended_at) WHERE events_compacted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_runs_events_prune_pending
+  ON runs(ended_at) WHERE events_pruned_at IS NULL;
@@ -0,0 +1,12 @@
+-- Raw per-run task log (stdout/stderr of external tools, walker debug logs) streamed by agents.
+--
+-- Chunks are appended in arrival order; the log is their concatenation.
+
+CREATE TABLE IF NOT EXISTS run_log_chunks (
+  run_id TEXT NOT NULL,
+  seq INTEGER NOT NULL,
+  ts INTEGER NOT NULL,
+  data BLOB NOT NULL,
+  PRIMARY KEY (run_id, seq),
+  FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+);
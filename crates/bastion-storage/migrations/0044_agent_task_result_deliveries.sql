@@ -0,0 +1,2 @@
+-- How many times an agent delivered the result of a task; only the first delivery completes it.
+ALTER TABLE agent_tasks ADD COLUMN result_deliveries INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,13 @@
+-- Per-username login throttling, next to the per-IP table. `lockouts` counts consecutive
+-- lockouts so each one lasts longer than the last.
+CREATE TABLE IF NOT EXISTS login_user_throttle (
+  username TEXT PRIMARY KEY,
+  failures INTEGER NOT NULL,
+  first_failed_at INTEGER NOT NULL,
+  last_failed_at INTEGER NOT NULL,
+  locked_until INTEGER,
+  lockouts INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_user_throttle_locked_until ON login_user_throttle(locked_until);
+CREATE INDEX IF NOT EXISTS idx_login_user_throttle_last_failed_at ON login_user_throttle(last_failed_at);
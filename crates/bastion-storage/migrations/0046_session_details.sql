@@ -0,0 +1,15 @@
+-- Details shown on the sessions page, plus the idle timeout a session was created with.
+-- `public_id` identifies a session in the API without exposing the cookie value; API token
+-- sessions leave it NULL so they never appear there.
+ALTER TABLE sessions ADD COLUMN public_id TEXT;
+ALTER TABLE sessions ADD COLUMN last_used_at INTEGER;
+ALTER TABLE sessions ADD COLUMN idle_timeout_seconds INTEGER;
+ALTER TABLE sessions ADD COLUMN client_ip TEXT;
+ALTER TABLE sessions ADD COLUMN user_agent TEXT;
+
+UPDATE sessions
+SET public_id = lower(hex(randomblob(8)))
+WHERE id NOT IN (SELECT session_id FROM api_tokens);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_sessions_public_id ON sessions(public_id);
+CREATE INDEX IF NOT EXISTS idx_sessions_user_id ON sessions(user_id);
@@ -0,0 +1,18 @@
+-- Job ownership and per-job grants. Admins see every job. Jobs without an owner (those created
+-- before ownership existed) stay visible to every user until an owner is set.
+ALTER TABLE users ADD COLUMN is_admin INTEGER NOT NULL DEFAULT 0;
+UPDATE users SET is_admin = 1 WHERE id = (SELECT MIN(id) FROM users);
+
+ALTER TABLE jobs ADD COLUMN owner_user_id INTEGER REFERENCES users(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_jobs_owner_user_id ON jobs(owner_user_id);
+
+CREATE TABLE IF NOT EXISTS job_acl (
+  job_id TEXT NOT NULL,
+  user_id INTEGER NOT NULL,
+  permission TEXT NOT NULL CHECK (permission IN ('view', 'trigger', 'edit')),
+  PRIMARY KEY (job_id, user_id),
+  FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+  FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_acl_user_id ON job_acl(user_id);
@@ -0,0 +1,21 @@
+-- Restores waiting for a second admin's approval, and the destinations each job has restored to.
+CREATE TABLE IF NOT EXISTS restore_approvals (
+  op_id TEXT PRIMARY KEY,
+  run_id TEXT NOT NULL,
+  job_id TEXT NOT NULL,
+  destination_key TEXT NOT NULL,
+  request_json TEXT NOT NULL,
+  requested_by_user_id INTEGER,
+  requested_at INTEGER NOT NULL,
+  approved_by_user_id INTEGER,
+  approved_at INTEGER,
+  FOREIGN KEY (op_id) REFERENCES operations(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS restore_destinations (
+  job_id TEXT NOT NULL,
+  destination_key TEXT NOT NULL,
+  first_used_at INTEGER NOT NULL,
+  PRIMARY KEY (job_id, destination_key),
+  FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE
+);
@@ -0,0 +1,175 @@
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+
+pub const DISCONNECT_REASON_CLOSED: &str = "closed";
+pub const DISCONNECT_REASON_CONNECTION_LOST: &str = "connection_lost";
+pub const DISCONNECT_REASON_HUB_RESTART: &str = "hub_restart";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentConnection {
+    pub id: i64,
+    pub agent_id: String,
+    pub connected_at: i64,
+    pub disconnected_at: Option<i64>,
+    pub peer_ip: Option<String>,
+    pub disconnect_reason: Option<String>,
+}
+
+pub async fn record_connected(
+    db: &SqlitePool,
+    agent_id: &str,
+    peer_ip: Option<&str>,
+    now: i64,
+) -> Result<i64, anyhow::Error> {
+    let row = sqlx::query(
+        "INSERT INTO agent_connections (agent_id, connected_at, peer_ip) VALUES (?, ?, ?) RETURNING id",
+    )
+    .bind(agent_id)
+    .bind(now)
+    .bind(peer_ip)
+    .fetch_one(db)
+    .await?;
+    Ok(row.get::<i64, _>("id"))
+}
+
+pub async fn record_disconnected(
+    db: &SqlitePool,
+    connection_id: i64,
+    reason: &str,
+    now: i64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE agent_connections SET disconnected_at = ?, disconnect_reason = ? WHERE id = ? AND disconnected_at IS NULL",
+    )
+    .bind(now)
+    .bind(reason)
+    .bind(connection_id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Closes sessions left open by a previous Hub process (called once at startup).
+pub async fn close_dangling(db: &SqlitePool, reason: &str, now: i64) -> Result<u64, anyhow::Error> {
+    let result = sqlx::query(
+        "UPDATE agent_connections SET disconnected_at = ?, disconnect_reason = ? WHERE disconnected_at IS NULL",
+    )
+    .bind(now)
+    .bind(reason)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Lists sessions overlapping `[since, now]`, newest first.
+pub async fn list_for_agent(
+    db: &SqlitePool,
+    agent_id: &str,
+    since: Option<i64>,
+    limit: u32,
+) -> Result<Vec<AgentConnection>, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, agent_id, connected_at, disconnected_at, peer_ip, disconnect_reason
+        FROM agent_connections
+        WHERE agent_id = ?
+          AND (? IS NULL OR disconnected_at IS NULL OR disconnected_at >= ?)
+        ORDER BY connected_at DESC, id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(agent_id)
+    .bind(since)
+    .bind(since)
+    .bind(limit as i64)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| AgentConnection {
+            id: row.get::<i64, _>("id"),
+            agent_id: row.get::<String, _>("agent_id"),
+            connected_at: row.get::<i64, _>("connected_at"),
+            disconnected_at: row.get::<Option<i64>, _>("disconnected_at"),
+            peer_ip: row.get::<Option<String>, _>("peer_ip"),
+            disconnect_reason: row.get::<Option<String>, _>("disconnect_reason"),
+        })
+        .collect())
+}
+
+pub async fn prune_disconnected_before(db: &SqlitePool, cutoff: i64) -> Result<u64, anyhow::Error> {
+    let result = sqlx::query(
+        "DELETE FROM agent_connections WHERE disconnected_at IS NOT NULL AND disconnected_at < ?",
+    )
+    .bind(cutoff)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::db;
+
+    use super::{
+        DISCONNECT_REASON_CLOSED, DISCONNECT_REASON_HUB_RESTART, close_dangling, list_for_agent,
+        prune_disconnected_before, record_connected, record_disconnected,
+    };
+
+    #[tokio::test]
+    async fn connection_sessions_round_trip_and_prune() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+
+        sqlx::query(
+            "INSERT INTO agents (id, name, key_hash, created_at) VALUES ('a', 'a', X'00', 0)",
+        )
+        .execute(&pool)
+        .await
+        .expect("insert agent");
+
+        let first = record_connected(&pool, "a", Some("10.0.0.1"), 100)
+            .await
+            .expect("connect 1");
+        record_disconnected(&pool, first, DISCONNECT_REASON_CLOSED, 200)
+            .await
+            .expect("disconnect 1");
+        // A second disconnect must not overwrite the recorded end.
+        record_disconnected(&pool, first, "other", 300)
+            .await
+            .expect("disconnect 1 again");
+        let second = record_connected(&pool, "a", None, 400)
+            .await
+            .expect("connect 2");
+
+        let items = list_for_agent(&pool, "a", None, 10).await.expect("list");
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id, second);
+        assert_eq!(items[0].disconnected_at, None);
+        assert_eq!(items[1].disconnected_at, Some(200));
+        assert_eq!(items[1].disconnect_reason.as_deref(), Some("closed"));
+        assert_eq!(items[1].peer_ip.as_deref(), Some("10.0.0.1"));
+
+        let recent = list_for_agent(&pool, "a", Some(250), 10)
+            .await
+            .expect("list since");
+        assert_eq!(recent.len(), 1);
+
+        assert_eq!(
+            close_dangling(&pool, DISCONNECT_REASON_HUB_RESTART, 500)
+                .await
+                .expect("close dangling"),
+            1
+        );
+        assert_eq!(
+            prune_disconnected_before(&pool, 300).await.expect("prune"),
+            1
+        );
+        let items = list_for_agent(&pool, "a", None, 10).await.expect("list");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].disconnect_reason.as_deref(), Some("hub_restart"));
+    }
+}
@@ -0,0 +1,117 @@
+use bastion_core::agent_protocol::HostMetricsV1;
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentHostMetrics {
+    pub received_at: i64,
+    #[serde(flatten)]
+    pub metrics: HostMetricsV1,
+}
+
+pub async fn upsert_latest(
+    db: &SqlitePool,
+    agent_id: &str,
+    metrics: &HostMetricsV1,
+    received_at: i64,
+) -> Result<(), anyhow::Error> {
+    let metrics_json = serde_json::to_string(metrics)?;
+    sqlx::query(
+        r#"
+        INSERT INTO agent_host_metrics (agent_id, collected_at, received_at, metrics_json)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(agent_id) DO UPDATE SET
+          collected_at = excluded.collected_at,
+          received_at = excluded.received_at,
+          metrics_json = excluded.metrics_json
+        "#,
+    )
+    .bind(agent_id)
+    .bind(metrics.collected_at)
+    .bind(received_at)
+    .bind(metrics_json)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn get_latest(
+    db: &SqlitePool,
+    agent_id: &str,
+) -> Result<Option<AgentHostMetrics>, anyhow::Error> {
+    let row = sqlx::query(
+        "SELECT received_at, metrics_json FROM agent_host_metrics WHERE agent_id = ? LIMIT 1",
+    )
+    .bind(agent_id)
+    .fetch_optional(db)
+    .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let metrics_json = row.get::<String, _>("metrics_json");
+    Ok(Some(AgentHostMetrics {
+        received_at: row.get::<i64, _>("received_at"),
+        metrics: serde_json::from_str(&metrics_json)?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use bastion_core::agent_protocol::{DiskUsageV1, HostMetricsV1};
+    use tempfile::TempDir;
+
+    use crate::db;
+
+    use super::{get_latest, upsert_latest};
+
+    #[tokio::test]
+    async fn latest_sample_replaces_previous_one() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+
+        sqlx::query(
+            "INSERT INTO agents (id, name, key_hash, created_at) VALUES ('a', 'a', X'00', 0)",
+        )
+        .execute(&pool)
+        .await
+        .expect("insert agent");
+
+        assert!(get_latest(&pool, "a").await.expect("get").is_none());
+
+        let first = HostMetricsV1 {
+            collected_at: 100,
+            load_avg: Some([1.0, 0.5, 0.25]),
+            ..Default::default()
+        };
+        upsert_latest(&pool, "a", &first, 101)
+            .await
+            .expect("upsert 1");
+
+        let second = HostMetricsV1 {
+            collected_at: 200,
+            mem_total_bytes: Some(4096),
+            mem_available_bytes: Some(1024),
+            disks: vec![DiskUsageV1 {
+                role: "staging".to_string(),
+                path: "/var/lib/bastion".to_string(),
+                total_bytes: 1000,
+                available_bytes: 10,
+            }],
+            ..Default::default()
+        };
+        upsert_latest(&pool, "a", &second, 202)
+            .await
+            .expect("upsert 2");
+
+        let latest = get_latest(&pool, "a").await.expect("get").expect("exists");
+        assert_eq!(latest.received_at, 202);
+        assert_eq!(latest.metrics, second);
+
+        sqlx::query("DELETE FROM agents WHERE id = 'a'")
+            .execute(&pool)
+            .await
+            .expect("delete agent");
+        assert!(get_latest(&pool, "a").await.expect("get").is_none());
+    }
+}
@@ -15,6 +15,19 @@ pub struct AgentTask {
     pub completed_at: Option<i64>,
     pub result: Option<serde_json::Value>,
     pub error: Option<String>,
+    /// Result messages received for this task, replays included.
+    pub result_deliveries: i64,
+}
+
+/// Outcome of [`record_task_result`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskResultDelivery {
+    /// First result for the task: it is now completed and the result should be applied.
+    First,
+    /// The task was already completed (a replay after reconnect); the result must be ignored.
+    Duplicate,
+    /// No such task (dispatched before tasks were tracked, or deleted).
+    UnknownTask,
 }
 
 pub async fn upsert_task(
@@ -99,9 +112,51 @@ pub async fn complete_task(
     Ok(result.rows_affected() > 0)
 }
 
+/// Records a result message for a task. Deliveries are counted in a single statement, so exactly
+/// one of several concurrent deliveries of the same result sees [`TaskResultDelivery::First`].
+pub async fn record_task_result(
+    db: &SqlitePool,
+    id: &str,
+    result: Option<&serde_json::Value>,
+    error: Option<&str>,
+) -> Result<TaskResultDelivery, anyhow::Error> {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let result_json = match result {
+        Some(v) => Some(serde_json::to_string(v)?),
+        None => None,
+    };
+
+    let deliveries = sqlx::query_scalar::<_, i64>(
+        r#"
+        UPDATE agent_tasks
+        SET result_deliveries = result_deliveries + 1,
+            status = 'completed',
+            updated_at = ?,
+            result_json = CASE WHEN completed_at IS NULL THEN ? ELSE result_json END,
+            error = CASE WHEN completed_at IS NULL THEN ? ELSE error END,
+            completed_at = COALESCE(completed_at, ?)
+        WHERE id = ?
+        RETURNING result_deliveries
+        "#,
+    )
+    .bind(now)
+    .bind(result_json)
+    .bind(error)
+    .bind(now)
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(match deliveries {
+        None => TaskResultDelivery::UnknownTask,
+        Some(1) => TaskResultDelivery::First,
+        Some(_) => TaskResultDelivery::Duplicate,
+    })
+}
+
 pub async fn get_task(db: &SqlitePool, id: &str) -> Result<Option<AgentTask>, anyhow::Error> {
     let row = sqlx::query(
-        "SELECT id, agent_id, run_id, status, payload_json, created_at, updated_at, acked_at, completed_at, result_json, error
+        "SELECT id, agent_id, run_id, status, payload_json, created_at, updated_at, acked_at, completed_at, result_json, error, result_deliveries
          FROM agent_tasks
          WHERE id = ?
          LIMIT 1",
@@ -135,6 +190,7 @@ pub async fn get_task(db: &SqlitePool, id: &str) -> Result<Option<AgentTask>, an
         completed_at: row.get::<Option<i64>, _>("completed_at"),
         result,
         error: row.get::<Option<String>, _>("error"),
+        result_deliveries: row.get::<i64, _>("result_deliveries"),
     }))
 }
 
@@ -145,7 +201,7 @@ pub async fn list_open_tasks_for_agent(
 ) -> Result<Vec<AgentTask>, anyhow::Error> {
     let rows = sqlx::query(
         r#"
-        SELECT t.id, t.agent_id, t.run_id, t.status, t.payload_json, t.created_at, t.updated_at, t.acked_at, t.completed_at, t.result_json, t.error
+        SELECT t.id, t.agent_id, t.run_id, t.status, t.payload_json, t.created_at, t.updated_at, t.acked_at, t.completed_at, t.result_json, t.error, t.result_deliveries
         FROM agent_tasks t
         LEFT JOIN runs r ON r.id = t.run_id
         LEFT JOIN operations o ON o.id = t.id
@@ -187,6 +243,7 @@ pub async fn list_open_tasks_for_agent(
             completed_at: row.get::<Option<i64>, _>("completed_at"),
             result,
             error: row.get::<Option<String>, _>("error"),
+            result_deliveries: row.get::<i64, _>("result_deliveries"),
         });
     }
 
@@ -199,7 +256,10 @@ mod tests {
 
     use crate::db;
 
-    use super::{ack_task, complete_task, get_task, list_open_tasks_for_agent, upsert_task};
+    use super::{
+        TaskResultDelivery, ack_task, complete_task, get_task, list_open_tasks_for_agent,
+        record_task_result, upsert_task,
+    };
 
     #[tokio::test]
     async fn tasks_round_trip() {
@@ -306,4 +366,41 @@ mod tests {
         assert_eq!(tasks.len(), 1);
         assert_eq!(tasks[0].id, "op1");
     }
+
+    #[tokio::test]
+    async fn only_the_first_task_result_completes_the_task() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let payload = serde_json::json!({"v":1,"type":"task","task_id":"t1"});
+        upsert_task(&pool, "t1", "agent1", "run1", "sent", &payload)
+            .await
+            .unwrap();
+
+        let first = serde_json::json!({"attempt": 1});
+        assert_eq!(
+            record_task_result(&pool, "t1", Some(&first), None)
+                .await
+                .unwrap(),
+            TaskResultDelivery::First
+        );
+        assert_eq!(
+            record_task_result(&pool, "t1", None, Some("replayed"))
+                .await
+                .unwrap(),
+            TaskResultDelivery::Duplicate
+        );
+        assert_eq!(
+            record_task_result(&pool, "missing", None, None)
+                .await
+                .unwrap(),
+            TaskResultDelivery::UnknownTask
+        );
+
+        let task = get_task(&pool, "t1").await.unwrap().unwrap();
+        assert_eq!(task.status, "completed");
+        assert_eq!(task.result, Some(first));
+        assert_eq!(task.error, None);
+        assert_eq!(task.result_deliveries, 2);
+    }
 }
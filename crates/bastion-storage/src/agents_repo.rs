@@ -3,6 +3,23 @@ use time::OffsetDateTime;
 
 use bastion_core::agent;
 
+pub const UPDATE_POLICY_AUTO: &str = "auto";
+pub const UPDATE_POLICY_MANUAL: &str = "manual";
+pub const UPDATE_POLICY_PINNED: &str = "pinned";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentClientCert {
+    pub sha256: Option<String>,
+    pub expires_at: Option<i64>,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentUpdatePolicy {
+    pub policy: String,
+    pub pinned_version: Option<String>,
+}
+
 pub async fn rotate_agent_key(
     db: &SqlitePool,
     agent_id: &str,
@@ -158,6 +175,106 @@ pub async fn clear_config_sync_error(db: &SqlitePool, agent_id: &str) -> Result<
     Ok(())
 }
 
+pub async fn get_update_policy(
+    db: &SqlitePool,
+    agent_id: &str,
+) -> Result<Option<AgentUpdatePolicy>, anyhow::Error> {
+    let row =
+        sqlx::query("SELECT update_policy, update_pinned_version FROM agents WHERE id = ? LIMIT 1")
+            .bind(agent_id)
+            .fetch_optional(db)
+            .await?;
+
+    Ok(row.map(|row| AgentUpdatePolicy {
+        policy: row.get::<String, _>("update_policy"),
+        pinned_version: row.get::<Option<String>, _>("update_pinned_version"),
+    }))
+}
+
+/// Returns false when the agent does not exist.
+pub async fn set_update_policy(
+    db: &SqlitePool,
+    agent_id: &str,
+    policy: &str,
+    pinned_version: Option<&str>,
+) -> Result<bool, anyhow::Error> {
+    let result =
+        sqlx::query("UPDATE agents SET update_policy = ?, update_pinned_version = ? WHERE id = ?")
+            .bind(policy)
+            .bind(pinned_version)
+            .bind(agent_id)
+            .execute(db)
+            .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn get_client_cert(
+    db: &SqlitePool,
+    agent_id: &str,
+) -> Result<Option<AgentClientCert>, anyhow::Error> {
+    let row = sqlx::query(
+        "SELECT client_cert_sha256, client_cert_expires_at, client_cert_required FROM agents WHERE id = ? LIMIT 1",
+    )
+    .bind(agent_id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.map(|row| AgentClientCert {
+        sha256: row.get::<Option<String>, _>("client_cert_sha256"),
+        expires_at: row.get::<Option<i64>, _>("client_cert_expires_at"),
+        required: row.get::<i64, _>("client_cert_required") != 0,
+    }))
+}
+
+/// Pins a newly issued client certificate. Returns false when the agent does not exist or is revoked.
+pub async fn set_client_cert(
+    db: &SqlitePool,
+    agent_id: &str,
+    sha256: &str,
+    expires_at: i64,
+) -> Result<bool, anyhow::Error> {
+    let result = sqlx::query(
+        "UPDATE agents SET client_cert_sha256 = ?, client_cert_expires_at = ? WHERE id = ? AND revoked_at IS NULL",
+    )
+    .bind(sha256)
+    .bind(expires_at)
+    .bind(agent_id)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Returns false when the agent does not exist.
+pub async fn set_client_cert_required(
+    db: &SqlitePool,
+    agent_id: &str,
+    required: bool,
+) -> Result<bool, anyhow::Error> {
+    let result = sqlx::query("UPDATE agents SET client_cert_required = ? WHERE id = ?")
+        .bind(if required { 1_i64 } else { 0_i64 })
+        .bind(agent_id)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn record_update_offered(
+    db: &SqlitePool,
+    agent_id: &str,
+    version: &str,
+    now: i64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE agents SET last_update_offered_version = ?, last_update_offered_at = ? WHERE id = ?",
+    )
+    .bind(version)
+    .bind(now)
+    .bind(agent_id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use sqlx::Row;
@@ -167,8 +284,10 @@ mod tests {
     use bastion_core::agent;
 
     use super::{
-        record_applied_config_snapshot, record_config_sync_error, rotate_agent_key,
-        set_desired_config_snapshot,
+        AgentClientCert, AgentUpdatePolicy, UPDATE_POLICY_AUTO, UPDATE_POLICY_PINNED,
+        get_client_cert, get_update_policy, record_applied_config_snapshot,
+        record_config_sync_error, record_update_offered, rotate_agent_key, set_client_cert,
+        set_client_cert_required, set_desired_config_snapshot, set_update_policy,
     };
 
     #[tokio::test]
@@ -437,4 +556,120 @@ mod tests {
             Some(123i64)
         );
     }
+
+    #[tokio::test]
+    async fn update_policy_defaults_to_auto_and_can_be_pinned() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let agent_id = "agent1";
+        let agent_key = agent::generate_token_b64_urlsafe(32);
+        let key_hash = agent::sha256_urlsafe_token(&agent_key).unwrap();
+
+        sqlx::query("INSERT INTO agents (id, name, key_hash, created_at) VALUES (?, NULL, ?, ?)")
+            .bind(agent_id)
+            .bind(key_hash)
+            .bind(1i64)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_update_policy(&pool, agent_id).await.unwrap(),
+            Some(AgentUpdatePolicy {
+                policy: UPDATE_POLICY_AUTO.to_string(),
+                pinned_version: None,
+            })
+        );
+
+        assert!(
+            set_update_policy(&pool, agent_id, UPDATE_POLICY_PINNED, Some("1.2.3"))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !set_update_policy(&pool, "missing", UPDATE_POLICY_AUTO, None)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            get_update_policy(&pool, agent_id).await.unwrap(),
+            Some(AgentUpdatePolicy {
+                policy: UPDATE_POLICY_PINNED.to_string(),
+                pinned_version: Some("1.2.3".to_string()),
+            })
+        );
+
+        record_update_offered(&pool, agent_id, "1.2.3", 50)
+            .await
+            .unwrap();
+        let row = sqlx::query(
+            "SELECT last_update_offered_version, last_update_offered_at FROM agents WHERE id = ? LIMIT 1",
+        )
+        .bind(agent_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            row.get::<Option<String>, _>("last_update_offered_version")
+                .as_deref(),
+            Some("1.2.3")
+        );
+        assert_eq!(
+            row.get::<Option<i64>, _>("last_update_offered_at"),
+            Some(50)
+        );
+    }
+
+    #[tokio::test]
+    async fn client_cert_is_pinned_and_can_be_required() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let agent_id = "agent1";
+        let key_hash = agent::sha256_urlsafe_token(&agent::generate_token_b64_urlsafe(32)).unwrap();
+        sqlx::query("INSERT INTO agents (id, name, key_hash, created_at) VALUES (?, NULL, ?, ?)")
+            .bind(agent_id)
+            .bind(key_hash)
+            .bind(1i64)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_client_cert(&pool, agent_id).await.unwrap(),
+            Some(AgentClientCert {
+                sha256: None,
+                expires_at: None,
+                required: false,
+            })
+        );
+
+        assert!(set_client_cert(&pool, agent_id, "abc", 100).await.unwrap());
+        assert!(
+            set_client_cert_required(&pool, agent_id, true)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !set_client_cert_required(&pool, "missing", true)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            get_client_cert(&pool, agent_id).await.unwrap(),
+            Some(AgentClientCert {
+                sha256: Some("abc".to_string()),
+                expires_at: Some(100),
+                required: true,
+            })
+        );
+
+        sqlx::query("UPDATE agents SET revoked_at = 2 WHERE id = ?")
+            .bind(agent_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(!set_client_cert(&pool, agent_id, "def", 200).await.unwrap());
+    }
 }
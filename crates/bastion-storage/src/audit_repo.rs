@@ -0,0 +1,140 @@
+use serde::Serialize;
+use sqlx::{QueryBuilder, Row, SqlitePool};
+
+pub const ACTION_KEYPACK_EXPORTED: &str = "keypack_exported";
+pub const ACTION_KEYPACK_IMPORTED: &str = "keypack_imported";
+pub const ACTION_KEYPACK_ESCROW_UPDATED: &str = "keypack_escrow_updated";
+pub const ACTION_ORPHANS_DELETED: &str = "orphans_deleted";
+pub const ACTION_LOGIN_UNLOCKED: &str = "login_unlocked";
+pub const ACTION_SESSIONS_REVOKED: &str = "sessions_revoked";
+pub const ACTION_PASSWORD_CHANGED: &str = "password_changed";
+pub const ACTION_PASSWORD_RESET: &str = "password_reset";
+pub const ACTION_JOB_ACCESS_CHANGED: &str = "job_access_changed";
+pub const ACTION_USER_ADMIN_CHANGED: &str = "user_admin_changed";
+pub const ACTION_RESTORE_APPROVED: &str = "restore_approved";
+pub const ACTION_CONFIG_EXPORTED: &str = "config_exported";
+pub const ACTION_CONFIG_IMPORTED: &str = "config_imported";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub ts: i64,
+    pub action: String,
+    pub user_id: Option<i64>,
+    pub peer_ip: Option<String>,
+    pub detail: Option<String>,
+}
+
+pub async fn append(
+    db: &SqlitePool,
+    ts: i64,
+    action: &str,
+    user_id: Option<i64>,
+    peer_ip: Option<&str>,
+    detail: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "INSERT INTO audit_log (ts, action, user_id, peer_ip, detail) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(ts)
+    .bind(action)
+    .bind(user_id)
+    .bind(peer_ip)
+    .bind(detail)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Lists the newest entries first, optionally restricted to the given actions.
+pub async fn list_recent(
+    db: &SqlitePool,
+    actions: &[&str],
+    limit: u32,
+) -> Result<Vec<AuditEntry>, anyhow::Error> {
+    let mut qb: QueryBuilder<sqlx::Sqlite> =
+        QueryBuilder::new("SELECT id, ts, action, user_id, peer_ip, detail FROM audit_log");
+    if !actions.is_empty() {
+        qb.push(" WHERE action IN (");
+        let mut sep = qb.separated(", ");
+        for action in actions {
+            sep.push_bind(*action);
+        }
+        sep.push_unseparated(")");
+    }
+    qb.push(" ORDER BY ts DESC, id DESC LIMIT ");
+    qb.push_bind(i64::from(limit));
+
+    let rows = qb.build().fetch_all(db).await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| AuditEntry {
+            id: row.get::<i64, _>("id"),
+            ts: row.get::<i64, _>("ts"),
+            action: row.get::<String, _>("action"),
+            user_id: row.get::<Option<i64>, _>("user_id"),
+            peer_ip: row.get::<Option<String>, _>("peer_ip"),
+            detail: row.get::<Option<String>, _>("detail"),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::db;
+
+    use super::{ACTION_KEYPACK_EXPORTED, ACTION_KEYPACK_IMPORTED, append, list_recent};
+
+    #[tokio::test]
+    async fn list_recent_filters_by_action_newest_first() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+
+        append(
+            &pool,
+            10,
+            ACTION_KEYPACK_EXPORTED,
+            Some(1),
+            Some("10.0.0.1"),
+            None,
+        )
+        .await
+        .expect("append");
+        append(&pool, 20, "other", None, None, None)
+            .await
+            .expect("append");
+        append(
+            &pool,
+            30,
+            ACTION_KEYPACK_IMPORTED,
+            Some(1),
+            None,
+            Some("force"),
+        )
+        .await
+        .expect("append");
+
+        let entries = list_recent(
+            &pool,
+            &[ACTION_KEYPACK_EXPORTED, ACTION_KEYPACK_IMPORTED],
+            10,
+        )
+        .await
+        .expect("list");
+        let actions = entries
+            .iter()
+            .map(|e| e.action.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            vec![ACTION_KEYPACK_IMPORTED, ACTION_KEYPACK_EXPORTED]
+        );
+        assert_eq!(entries[1].peer_ip.as_deref(), Some("10.0.0.1"));
+
+        let all = list_recent(&pool, &[], 2).await.expect("list");
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].ts, 30);
+    }
+}
@@ -0,0 +1,299 @@
+use sqlx::Row;
+use sqlx::SqlitePool;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use bastion_core::agent;
+
+use super::SessionRow;
+use super::sessions::LAST_USED_RESOLUTION_SECONDS;
+
+/// Prefix of API token secrets, so they are recognizable in configs and secret scanners.
+pub const API_TOKEN_PREFIX: &str = "bst_";
+
+// Token sessions have no idle expiry; `expires_at` on the token (if any) bounds them instead.
+const NO_EXPIRY: i64 = i64::MAX;
+
+#[derive(Debug, Clone)]
+pub struct ApiTokenRow {
+    pub id: String,
+    pub user_id: i64,
+    pub name: String,
+    pub created_at: i64,
+    pub expires_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreatedApiToken {
+    pub row: ApiTokenRow,
+    /// Token secret; only available at creation time.
+    pub token: String,
+}
+
+pub async fn create_api_token(
+    db: &SqlitePool,
+    user_id: i64,
+    name: &str,
+    expires_at: Option<i64>,
+) -> Result<CreatedApiToken, anyhow::Error> {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let id = Uuid::new_v4().to_string();
+    let session_id = Uuid::new_v4().to_string();
+    let csrf_token = agent::generate_token_b64_urlsafe(32);
+    let secret = agent::generate_token_b64_urlsafe(32);
+    let token_hash = agent::sha256_urlsafe_token(&secret)?;
+
+    let mut tx = db.begin().await?;
+    sqlx::query(
+        "INSERT INTO sessions (id, user_id, csrf_token, created_at, expires_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&session_id)
+    .bind(user_id)
+    .bind(&csrf_token)
+    .bind(now)
+    .bind(expires_at.unwrap_or(NO_EXPIRY))
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "INSERT INTO api_tokens (id, user_id, name, token_hash, session_id, created_at, expires_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(user_id)
+    .bind(name)
+    .bind(token_hash)
+    .bind(&session_id)
+    .bind(now)
+    .bind(expires_at)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(CreatedApiToken {
+        row: ApiTokenRow {
+            id,
+            user_id,
+            name: name.to_string(),
+            created_at: now,
+            expires_at,
+            last_used_at: None,
+        },
+        token: format!("{API_TOKEN_PREFIX}{secret}"),
+    })
+}
+
+pub async fn list_api_tokens(
+    db: &SqlitePool,
+    user_id: i64,
+) -> Result<Vec<ApiTokenRow>, anyhow::Error> {
+    let rows = sqlx::query(
+        "SELECT id, user_id, name, created_at, expires_at, last_used_at FROM api_tokens WHERE user_id = ? ORDER BY created_at DESC",
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ApiTokenRow {
+            id: row.get::<String, _>("id"),
+            user_id: row.get::<i64, _>("user_id"),
+            name: row.get::<String, _>("name"),
+            created_at: row.get::<i64, _>("created_at"),
+            expires_at: row.get::<Option<i64>, _>("expires_at"),
+            last_used_at: row.get::<Option<i64>, _>("last_used_at"),
+        })
+        .collect())
+}
+
+/// Deletes a token and its backing session. Returns false when no such token exists.
+pub async fn delete_api_token(
+    db: &SqlitePool,
+    user_id: i64,
+    token_id: &str,
+) -> Result<bool, anyhow::Error> {
+    let session_id = sqlx::query_scalar::<_, String>(
+        "SELECT session_id FROM api_tokens WHERE id = ? AND user_id = ? LIMIT 1",
+    )
+    .bind(token_id)
+    .bind(user_id)
+    .fetch_optional(db)
+    .await?;
+    let Some(session_id) = session_id else {
+        return Ok(false);
+    };
+
+    // Cascades to the token row.
+    sqlx::query("DELETE FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .execute(db)
+        .await?;
+    Ok(true)
+}
+
+/// Resolves a token secret to its backing session and records the use.
+pub async fn resolve_api_token(
+    db: &SqlitePool,
+    token: &str,
+) -> Result<Option<SessionRow>, anyhow::Error> {
+    let Some(secret) = token.strip_prefix(API_TOKEN_PREFIX) else {
+        return Ok(None);
+    };
+    let Ok(token_hash) = agent::sha256_urlsafe_token(secret) else {
+        return Ok(None);
+    };
+
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let row = sqlx::query(
+        "SELECT id, session_id, last_used_at FROM api_tokens WHERE token_hash = ? AND (expires_at IS NULL OR expires_at > ?) LIMIT 1",
+    )
+    .bind(token_hash)
+    .bind(now)
+    .fetch_optional(db)
+    .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let session = super::get_session(db, &row.get::<String, _>("session_id")).await?;
+    let stale = row
+        .get::<Option<i64>, _>("last_used_at")
+        .is_none_or(|last_used_at| now - last_used_at >= LAST_USED_RESOLUTION_SECONDS);
+    if session.is_some() && stale {
+        sqlx::query("UPDATE api_tokens SET last_used_at = ? WHERE id = ?")
+            .bind(now)
+            .bind(row.get::<String, _>("id"))
+            .execute(db)
+            .await?;
+    }
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::auth::{create_user, find_user_by_username, get_session};
+    use crate::db;
+
+    use super::{create_api_token, delete_api_token, list_api_tokens, resolve_api_token};
+
+    #[tokio::test]
+    async fn api_token_resolves_to_session_until_deleted() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        create_user(&pool, "admin", "pw").await.expect("user");
+        let user = find_user_by_username(&pool, "admin")
+            .await
+            .expect("find")
+            .expect("user");
+
+        let created = create_api_token(&pool, user.id, "ci", None)
+            .await
+            .expect("create");
+        assert!(created.token.starts_with("bst_"));
+
+        let session = resolve_api_token(&pool, &created.token)
+            .await
+            .expect("resolve")
+            .expect("session");
+        assert_eq!(session.user_id, user.id);
+
+        let tokens = list_api_tokens(&pool, user.id).await.expect("list");
+        assert_eq!(tokens.len(), 1);
+        assert!(tokens[0].last_used_at.is_some());
+
+        assert!(
+            resolve_api_token(&pool, "bst_not-a-token")
+                .await
+                .expect("resolve bad")
+                .is_none()
+        );
+
+        assert!(
+            delete_api_token(&pool, user.id, &created.row.id)
+                .await
+                .expect("delete")
+        );
+        assert!(
+            resolve_api_token(&pool, &created.token)
+                .await
+                .expect("resolve deleted")
+                .is_none()
+        );
+        assert!(
+            get_session(&pool, &session.id)
+                .await
+                .expect("session")
+                .is_none()
+        );
+        assert!(
+            list_api_tokens(&pool, user.id)
+                .await
+                .expect("list")
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn api_token_last_used_is_written_at_most_once_a_minute() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        create_user(&pool, "admin", "pw").await.expect("user");
+        let user = find_user_by_username(&pool, "admin")
+            .await
+            .expect("find")
+            .expect("user");
+        let created = create_api_token(&pool, user.id, "ci", None)
+            .await
+            .expect("create");
+
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let resolve_with_last_used = |last_used_at: i64| {
+            let pool = pool.clone();
+            let token = created.token.clone();
+            let id = created.row.id.clone();
+            async move {
+                sqlx::query("UPDATE api_tokens SET last_used_at = ? WHERE id = ?")
+                    .bind(last_used_at)
+                    .bind(&id)
+                    .execute(&pool)
+                    .await
+                    .expect("set last_used_at");
+                resolve_api_token(&pool, &token)
+                    .await
+                    .expect("resolve")
+                    .expect("session");
+                list_api_tokens(&pool, user.id).await.expect("list")[0].last_used_at
+            }
+        };
+
+        assert_eq!(resolve_with_last_used(now - 10).await, Some(now - 10));
+        assert!(
+            resolve_with_last_used(now - 120)
+                .await
+                .is_some_and(|at| at >= now)
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_api_token_is_rejected() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        create_user(&pool, "admin", "pw").await.expect("user");
+        let user = find_user_by_username(&pool, "admin")
+            .await
+            .expect("find")
+            .expect("user");
+
+        let created = create_api_token(&pool, user.id, "old", Some(1))
+            .await
+            .expect("create");
+        assert!(
+            resolve_api_token(&pool, &created.token)
+                .await
+                .expect("resolve")
+                .is_none()
+        );
+    }
+}
@@ -1,14 +1,25 @@
+mod api_tokens;
 mod password;
 mod sessions;
 mod throttle;
 mod users;
 
+pub use api_tokens::{
+    API_TOKEN_PREFIX, ApiTokenRow, CreatedApiToken, create_api_token, delete_api_token,
+    list_api_tokens, resolve_api_token,
+};
 pub use password::{hash_password, verify_password};
-pub use sessions::{SessionRow, create_session, delete_session, get_session};
+pub use sessions::{
+    DEFAULT_SESSION_MAX_AGE_SECONDS, SessionInfo, SessionOptions, SessionRow, create_session,
+    create_session_with, delete_other_user_sessions, delete_session, delete_user_session,
+    get_session, list_user_sessions,
+};
 pub use throttle::{
-    clear_login_throttle, login_throttle_retry_after_seconds, record_login_failure,
+    LoginUserLock, clear_login_throttle, clear_login_user_throttle, list_login_user_locks,
+    login_throttle_retry_after_seconds, login_user_throttle_retry_after_seconds,
+    record_login_failure, record_login_user_failure,
 };
 pub use users::{
-    CreateFirstUserResult, UserRow, create_first_user, create_user, find_user_by_username,
-    users_count,
+    CreateFirstUserResult, UserRow, create_first_user, create_user, find_user_by_id,
+    find_user_by_username, set_password, users_count,
 };
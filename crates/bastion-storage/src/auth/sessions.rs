@@ -1,11 +1,19 @@
 use base64::Engine as _;
 use rand::RngCore;
+use serde::Serialize;
 use sqlx::Row;
 use sqlx::SqlitePool;
 use time::Duration;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
+/// Absolute lifetime of a browser session unless configured otherwise.
+pub const DEFAULT_SESSION_MAX_AGE_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+// `last_used_at` is only written when it is at least this stale, so busy pages don't turn every
+// request into a write.
+pub(super) const LAST_USED_RESOLUTION_SECONDS: i64 = 60;
+
 #[derive(Debug, Clone)]
 pub struct SessionRow {
     pub id: String,
@@ -15,24 +23,74 @@ pub struct SessionRow {
     #[allow(dead_code)]
     pub created_at: i64,
     pub expires_at: i64,
+    /// `None` for sessions backing API tokens.
+    pub public_id: Option<String>,
+}
+
+/// How a new browser session is recorded and bounded.
+#[derive(Debug, Clone)]
+pub struct SessionOptions {
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub max_age_seconds: i64,
+    /// Ends the session after this long without a request; `None` keeps it until `expires_at`.
+    pub idle_timeout_seconds: Option<i64>,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            client_ip: None,
+            user_agent: None,
+            max_age_seconds: DEFAULT_SESSION_MAX_AGE_SECONDS,
+            idle_timeout_seconds: None,
+        }
+    }
+}
+
+/// An active browser session, as listed to its user.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub created_at: i64,
+    pub last_used_at: i64,
+    pub expires_at: i64,
+    /// When the session ends if it stays unused.
+    pub idle_expires_at: Option<i64>,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
 }
 
 pub async fn create_session(db: &SqlitePool, user_id: i64) -> Result<SessionRow, anyhow::Error> {
+    create_session_with(db, user_id, &SessionOptions::default()).await
+}
+
+pub async fn create_session_with(
+    db: &SqlitePool,
+    user_id: i64,
+    options: &SessionOptions,
+) -> Result<SessionRow, anyhow::Error> {
     let now = OffsetDateTime::now_utc();
     let created_at = now.unix_timestamp();
-    let expires_at = (now + Duration::days(7)).unix_timestamp();
+    let expires_at = (now + Duration::seconds(options.max_age_seconds)).unix_timestamp();
 
     let id = Uuid::new_v4().to_string();
     let csrf_token = random_token_b64_urlsafe(32);
+    let public_id = format!("{:016x}", rand::rng().next_u64());
 
     sqlx::query(
-        "INSERT INTO sessions (id, user_id, csrf_token, created_at, expires_at) VALUES (?, ?, ?, ?, ?)",
+        "INSERT INTO sessions (id, user_id, csrf_token, created_at, expires_at, public_id, last_used_at, idle_timeout_seconds, client_ip, user_agent) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
     )
     .bind(&id)
     .bind(user_id)
     .bind(&csrf_token)
     .bind(created_at)
     .bind(expires_at)
+    .bind(&public_id)
+    .bind(created_at)
+    .bind(options.idle_timeout_seconds)
+    .bind(options.client_ip.as_deref())
+    .bind(options.user_agent.as_deref())
     .execute(db)
     .await?;
 
@@ -42,15 +100,18 @@ pub async fn create_session(db: &SqlitePool, user_id: i64) -> Result<SessionRow,
         csrf_token,
         created_at,
         expires_at,
+        public_id: Some(public_id),
     })
 }
 
+/// Looks up a live session and records the use. Sessions past `expires_at`, or idle for longer
+/// than their idle timeout, are treated as missing.
 pub async fn get_session(
     db: &SqlitePool,
     session_id: &str,
 ) -> Result<Option<SessionRow>, sqlx::Error> {
     let row = sqlx::query(
-        "SELECT id, user_id, csrf_token, created_at, expires_at FROM sessions WHERE id = ? LIMIT 1",
+        "SELECT id, user_id, csrf_token, created_at, expires_at, public_id, last_used_at, idle_timeout_seconds FROM sessions WHERE id = ? LIMIT 1",
     )
     .bind(session_id)
     .fetch_optional(db)
@@ -66,16 +127,106 @@ pub async fn get_session(
         csrf_token: row.get::<String, _>("csrf_token"),
         created_at: row.get::<i64, _>("created_at"),
         expires_at: row.get::<i64, _>("expires_at"),
+        public_id: row.get::<Option<String>, _>("public_id"),
     };
+    let last_used_at = row
+        .get::<Option<i64>, _>("last_used_at")
+        .unwrap_or(session.created_at);
+    let idle_timeout = row.get::<Option<i64>, _>("idle_timeout_seconds");
 
     let now = OffsetDateTime::now_utc().unix_timestamp();
     if session.expires_at <= now {
         return Ok(None);
     }
+    if idle_timeout.is_some_and(|idle| last_used_at.saturating_add(idle) <= now) {
+        return Ok(None);
+    }
+
+    if now - last_used_at >= LAST_USED_RESOLUTION_SECONDS {
+        sqlx::query("UPDATE sessions SET last_used_at = ? WHERE id = ?")
+            .bind(now)
+            .bind(&session.id)
+            .execute(db)
+            .await?;
+    }
 
     Ok(Some(session))
 }
 
+/// Lists a user's live browser sessions, most recently used first. API token sessions are left
+/// out.
+pub async fn list_user_sessions(
+    db: &SqlitePool,
+    user_id: i64,
+    now: i64,
+) -> Result<Vec<SessionInfo>, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT public_id, created_at, COALESCE(last_used_at, created_at) AS last_used_at,
+               expires_at, idle_timeout_seconds, client_ip, user_agent
+        FROM sessions
+        WHERE user_id = ? AND public_id IS NOT NULL AND expires_at > ?
+        ORDER BY last_used_at DESC, created_at DESC
+        "#,
+    )
+    .bind(user_id)
+    .bind(now)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|row| {
+            let last_used_at = row.get::<i64, _>("last_used_at");
+            let idle_expires_at = row
+                .get::<Option<i64>, _>("idle_timeout_seconds")
+                .map(|idle| last_used_at.saturating_add(idle));
+            if idle_expires_at.is_some_and(|at| at <= now) {
+                return None;
+            }
+            Some(SessionInfo {
+                id: row.get::<String, _>("public_id"),
+                created_at: row.get::<i64, _>("created_at"),
+                last_used_at,
+                expires_at: row.get::<i64, _>("expires_at"),
+                idle_expires_at,
+                client_ip: row.get::<Option<String>, _>("client_ip"),
+                user_agent: row.get::<Option<String>, _>("user_agent"),
+            })
+        })
+        .collect())
+}
+
+/// Ends one of a user's browser sessions by its public id. Returns false when there is none.
+pub async fn delete_user_session(
+    db: &SqlitePool,
+    user_id: i64,
+    public_id: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM sessions WHERE user_id = ? AND public_id = ?")
+        .bind(user_id)
+        .bind(public_id)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Ends every browser session of a user except `keep_session_id`; API tokens keep working.
+/// Returns how many were ended.
+pub async fn delete_other_user_sessions(
+    db: &SqlitePool,
+    user_id: i64,
+    keep_session_id: &str,
+) -> Result<u64, sqlx::Error> {
+    let result =
+        sqlx::query("DELETE FROM sessions WHERE user_id = ? AND public_id IS NOT NULL AND id <> ?")
+            .bind(user_id)
+            .bind(keep_session_id)
+            .execute(db)
+            .await?;
+    Ok(result.rows_affected())
+}
+
 pub async fn delete_session(db: &SqlitePool, session_id: &str) -> Result<(), sqlx::Error> {
     sqlx::query("DELETE FROM sessions WHERE id = ?")
         .bind(session_id)
@@ -89,3 +240,96 @@ fn random_token_b64_urlsafe(size: usize) -> String {
     rand::rng().fill_bytes(&mut bytes);
     base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
 }
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::auth::{create_api_token, create_user, find_user_by_username};
+    use crate::db;
+
+    use super::{
+        SessionOptions, create_session, create_session_with, delete_other_user_sessions,
+        delete_user_session, get_session, list_user_sessions,
+    };
+
+    #[tokio::test]
+    async fn idle_sessions_expire_and_only_browser_sessions_are_listed() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        create_user(&pool, "admin", "pw").await.expect("user");
+        let user = find_user_by_username(&pool, "admin")
+            .await
+            .expect("find")
+            .expect("user");
+
+        let current = create_session_with(
+            &pool,
+            user.id,
+            &SessionOptions {
+                client_ip: Some("203.0.113.7".to_string()),
+                user_agent: Some("Firefox".to_string()),
+                idle_timeout_seconds: Some(600),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("session");
+        let other = create_session(&pool, user.id).await.expect("other");
+        let token = create_api_token(&pool, user.id, "ci", None)
+            .await
+            .expect("token");
+
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let listed = list_user_sessions(&pool, user.id, now).await.expect("list");
+        assert_eq!(listed.len(), 2);
+        let mine = listed
+            .iter()
+            .find(|s| Some(&s.id) == current.public_id.as_ref())
+            .expect("current listed");
+        assert_eq!(mine.client_ip.as_deref(), Some("203.0.113.7"));
+        assert_eq!(mine.idle_expires_at, Some(mine.last_used_at + 600));
+
+        // Idle for longer than its timeout: gone, even though `expires_at` is days away.
+        sqlx::query("UPDATE sessions SET last_used_at = ? WHERE id = ?")
+            .bind(now - 601)
+            .bind(&current.id)
+            .execute(&pool)
+            .await
+            .expect("age session");
+        assert!(get_session(&pool, &current.id).await.unwrap().is_none());
+        assert_eq!(
+            list_user_sessions(&pool, user.id, now).await.unwrap().len(),
+            1
+        );
+
+        let third = create_session(&pool, user.id).await.expect("third");
+        assert_eq!(
+            delete_other_user_sessions(&pool, user.id, &third.id)
+                .await
+                .unwrap(),
+            2
+        );
+        assert!(get_session(&pool, &other.id).await.unwrap().is_none());
+        assert!(
+            crate::auth::resolve_api_token(&pool, &token.token)
+                .await
+                .unwrap()
+                .is_some(),
+            "API tokens survive revoking other sessions"
+        );
+
+        let third_public = third.public_id.expect("public id");
+        assert!(
+            !delete_user_session(&pool, user.id + 1, &third_public)
+                .await
+                .unwrap()
+        );
+        assert!(
+            delete_user_session(&pool, user.id, &third_public)
+                .await
+                .unwrap()
+        );
+        assert!(get_session(&pool, &third.id).await.unwrap().is_none());
+    }
+}
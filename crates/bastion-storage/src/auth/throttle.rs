@@ -1,3 +1,4 @@
+use serde::Serialize;
 use sqlx::Row;
 use sqlx::SqlitePool;
 
@@ -5,6 +6,33 @@ const LOGIN_WINDOW_SECONDS: i64 = 10 * 60;
 const LOGIN_MAX_FAILURES: i64 = 10;
 const LOGIN_LOCK_SECONDS: i64 = 15 * 60;
 
+// Per-username throttling still works when every login arrives from one (proxy) IP. Each lockout
+// doubles the next one, up to the cap; a quiet day resets the escalation.
+const USER_MAX_FAILURES: i64 = 5;
+const USER_LOCK_BASE_SECONDS: i64 = 60;
+const USER_LOCK_MAX_SECONDS: i64 = 60 * 60;
+const USER_LOCKOUT_RESET_SECONDS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LoginUserLock {
+    pub username: String,
+    pub lockouts: i64,
+    pub last_failed_at: i64,
+    pub locked_until: i64,
+}
+
+/// Usernames are throttled case-insensitively so `Admin` and `admin` share a counter.
+fn throttle_key(username: &str) -> String {
+    username.trim().to_lowercase()
+}
+
+fn user_lock_seconds(lockouts: i64) -> i64 {
+    let factor = 1_i64 << lockouts.clamp(0, 16);
+    USER_LOCK_BASE_SECONDS
+        .saturating_mul(factor)
+        .min(USER_LOCK_MAX_SECONDS)
+}
+
 pub async fn login_throttle_retry_after_seconds(
     db: &SqlitePool,
     ip: &str,
@@ -111,6 +139,138 @@ pub async fn record_login_failure(db: &SqlitePool, ip: &str, now: i64) -> Result
     Ok(())
 }
 
+pub async fn login_user_throttle_retry_after_seconds(
+    db: &SqlitePool,
+    username: &str,
+    now: i64,
+) -> Result<Option<i64>, sqlx::Error> {
+    let locked_until = sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT locked_until FROM login_user_throttle WHERE username = ? LIMIT 1",
+    )
+    .bind(throttle_key(username))
+    .fetch_optional(db)
+    .await?
+    .flatten();
+
+    Ok(locked_until
+        .filter(|t| *t > now)
+        .map(|t| t.saturating_sub(now)))
+}
+
+/// Forgets failures and lockouts for `username`. Returns whether anything was recorded.
+pub async fn clear_login_user_throttle(
+    db: &SqlitePool,
+    username: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM login_user_throttle WHERE username = ?")
+        .bind(throttle_key(username))
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Records a failed login for `username`; returns the lock expiry when this failure locked it.
+pub async fn record_login_user_failure(
+    db: &SqlitePool,
+    username: &str,
+    now: i64,
+) -> Result<Option<i64>, sqlx::Error> {
+    let key = throttle_key(username);
+    let mut tx = db.begin().await?;
+
+    let row = sqlx::query(
+        "SELECT failures, first_failed_at, last_failed_at, locked_until, lockouts FROM login_user_throttle WHERE username = ? LIMIT 1",
+    )
+    .bind(&key)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(row) = row else {
+        sqlx::query(
+            "INSERT INTO login_user_throttle (username, failures, first_failed_at, last_failed_at, locked_until, lockouts) VALUES (?, 1, ?, ?, NULL, 0)",
+        )
+        .bind(&key)
+        .bind(now)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        return Ok(None);
+    };
+
+    let failures = row.get::<i64, _>("failures");
+    let first_failed_at = row.get::<i64, _>("first_failed_at");
+    let last_failed_at = row.get::<i64, _>("last_failed_at");
+    let locked_until = row.get::<Option<i64>, _>("locked_until");
+    let mut lockouts = row.get::<i64, _>("lockouts");
+
+    if locked_until.is_some_and(|t| t > now) {
+        sqlx::query("UPDATE login_user_throttle SET last_failed_at = ? WHERE username = ?")
+            .bind(now)
+            .bind(&key)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        return Ok(None);
+    }
+
+    if now.saturating_sub(last_failed_at) > USER_LOCKOUT_RESET_SECONDS {
+        lockouts = 0;
+    }
+
+    let (failures, first_failed_at) =
+        if now.saturating_sub(first_failed_at) > LOGIN_WINDOW_SECONDS || locked_until.is_some() {
+            // A new window starts after the previous one ran out or a lock expired.
+            (1, now)
+        } else {
+            (failures.saturating_add(1), first_failed_at)
+        };
+
+    let mut new_lock = None;
+    if failures >= USER_MAX_FAILURES {
+        new_lock = Some(now.saturating_add(user_lock_seconds(lockouts)));
+        lockouts = lockouts.saturating_add(1);
+    }
+
+    sqlx::query(
+        "UPDATE login_user_throttle SET failures = ?, first_failed_at = ?, last_failed_at = ?, locked_until = ?, lockouts = ? WHERE username = ?",
+    )
+    .bind(failures)
+    .bind(first_failed_at)
+    .bind(now)
+    .bind(new_lock)
+    .bind(lockouts)
+    .bind(&key)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(new_lock)
+}
+
+/// Usernames that are currently locked, soonest expiry first.
+pub async fn list_login_user_locks(
+    db: &SqlitePool,
+    now: i64,
+) -> Result<Vec<LoginUserLock>, sqlx::Error> {
+    let rows = sqlx::query(
+        "SELECT username, lockouts, last_failed_at, locked_until FROM login_user_throttle WHERE locked_until > ? ORDER BY locked_until ASC",
+    )
+    .bind(now)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| LoginUserLock {
+            username: row.get::<String, _>("username"),
+            lockouts: row.get::<i64, _>("lockouts"),
+            last_failed_at: row.get::<i64, _>("last_failed_at"),
+            locked_until: row.get::<i64, _>("locked_until"),
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::TempDir;
@@ -118,8 +278,10 @@ mod tests {
     use crate::db;
 
     use super::{
-        LOGIN_LOCK_SECONDS, LOGIN_MAX_FAILURES, clear_login_throttle,
-        login_throttle_retry_after_seconds, record_login_failure,
+        LOGIN_LOCK_SECONDS, LOGIN_MAX_FAILURES, USER_LOCK_BASE_SECONDS, USER_MAX_FAILURES,
+        clear_login_throttle, clear_login_user_throttle, list_login_user_locks,
+        login_throttle_retry_after_seconds, login_user_throttle_retry_after_seconds,
+        record_login_failure, record_login_user_failure,
     };
 
     #[tokio::test]
@@ -150,4 +312,65 @@ mod tests {
             .expect("retry3");
         assert!(retry.is_none());
     }
+
+    #[tokio::test]
+    async fn user_lockouts_grow_exponentially_until_cleared() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+
+        let mut now = 1000;
+        let mut lock_seconds = Vec::new();
+        for _ in 0..3 {
+            let mut locked_until = None;
+            for _ in 0..USER_MAX_FAILURES {
+                locked_until = record_login_user_failure(&pool, "Admin", now)
+                    .await
+                    .expect("record");
+            }
+            let locked_until = locked_until.expect("locked");
+            lock_seconds.push(locked_until - now);
+
+            let retry = login_user_throttle_retry_after_seconds(&pool, "admin", now)
+                .await
+                .expect("retry");
+            assert_eq!(retry, Some(locked_until - now));
+            now = locked_until;
+        }
+        assert_eq!(
+            lock_seconds,
+            [
+                USER_LOCK_BASE_SECONDS,
+                USER_LOCK_BASE_SECONDS * 2,
+                USER_LOCK_BASE_SECONDS * 4
+            ]
+        );
+
+        // Lock again, then an admin unlock clears it along with the escalation.
+        for _ in 0..USER_MAX_FAILURES {
+            record_login_user_failure(&pool, "admin", now)
+                .await
+                .expect("record");
+        }
+        let locks = list_login_user_locks(&pool, now).await.expect("list");
+        assert_eq!(locks.len(), 1);
+        assert_eq!(locks[0].username, "admin");
+        assert_eq!(locks[0].lockouts, 4);
+
+        assert!(
+            clear_login_user_throttle(&pool, "ADMIN")
+                .await
+                .expect("clear")
+        );
+        assert!(
+            login_user_throttle_retry_after_seconds(&pool, "admin", now)
+                .await
+                .expect("retry")
+                .is_none()
+        );
+        assert!(
+            !clear_login_user_throttle(&pool, "admin")
+                .await
+                .expect("clear again")
+        );
+    }
 }
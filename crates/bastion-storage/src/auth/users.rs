@@ -47,7 +47,7 @@ pub async fn create_first_user(
     let password_hash = super::hash_password(password)?;
 
     let result = sqlx::query(
-        "INSERT INTO users (username, password_hash, created_at) SELECT ?, ?, ? WHERE NOT EXISTS (SELECT 1 FROM users)",
+        "INSERT INTO users (username, password_hash, created_at, is_admin) SELECT ?, ?, ?, 1 WHERE NOT EXISTS (SELECT 1 FROM users)",
     )
     .bind(username)
     .bind(password_hash)
@@ -78,3 +78,107 @@ pub async fn find_user_by_username(
         password_hash: r.get::<String, _>("password_hash"),
     }))
 }
+
+pub async fn find_user_by_id(
+    db: &SqlitePool,
+    user_id: i64,
+) -> Result<Option<UserRow>, sqlx::Error> {
+    let row = sqlx::query("SELECT id, username, password_hash FROM users WHERE id = ? LIMIT 1")
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?;
+
+    Ok(row.map(|r| UserRow {
+        id: r.get::<i64, _>("id"),
+        username: r.get::<String, _>("username"),
+        password_hash: r.get::<String, _>("password_hash"),
+    }))
+}
+
+/// Replaces a user's password and ends all of their browser sessions except `keep_session_id`.
+/// API tokens keep working. Returns false when the user doesn't exist.
+pub async fn set_password(
+    db: &SqlitePool,
+    user_id: i64,
+    password: &str,
+    keep_session_id: Option<&str>,
+) -> Result<bool, anyhow::Error> {
+    let password_hash = super::hash_password(password)?;
+
+    let mut tx = db.begin().await?;
+    let result = sqlx::query("UPDATE users SET password_hash = ? WHERE id = ?")
+        .bind(password_hash)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+    sqlx::query("DELETE FROM sessions WHERE user_id = ? AND public_id IS NOT NULL AND id IS NOT ?")
+        .bind(user_id)
+        .bind(keep_session_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::auth::{
+        create_api_token, create_session, find_user_by_username, get_session, resolve_api_token,
+        verify_password,
+    };
+    use crate::db;
+
+    use super::{create_user, set_password};
+
+    #[tokio::test]
+    async fn set_password_signs_out_other_browser_sessions() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        create_user(&pool, "admin", "old-password")
+            .await
+            .expect("user");
+        let user = find_user_by_username(&pool, "admin")
+            .await
+            .expect("find")
+            .expect("user");
+        let kept = create_session(&pool, user.id).await.expect("kept");
+        let other = create_session(&pool, user.id).await.expect("other");
+        let token = create_api_token(&pool, user.id, "ci", None)
+            .await
+            .expect("token");
+
+        assert!(
+            set_password(&pool, user.id, "new-password", Some(&kept.id))
+                .await
+                .expect("set")
+        );
+        let user = find_user_by_username(&pool, "admin")
+            .await
+            .expect("find")
+            .expect("user");
+        assert!(verify_password(&user.password_hash, "new-password").unwrap());
+        assert!(!verify_password(&user.password_hash, "old-password").unwrap());
+
+        assert!(get_session(&pool, &kept.id).await.unwrap().is_some());
+        assert!(get_session(&pool, &other.id).await.unwrap().is_none());
+        assert!(
+            resolve_api_token(&pool, &token.token)
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        assert!(
+            set_password(&pool, user.id, "newer-password", None)
+                .await
+                .expect("set")
+        );
+        assert!(get_session(&pool, &kept.id).await.unwrap().is_none());
+        assert!(!set_password(&pool, user.id + 1, "x", None).await.unwrap());
+    }
+}
@@ -0,0 +1,445 @@
+//! Portable export of a Hub's configuration for migrating to another Hub.
+//!
+//! A bundle carries jobs (schedules, retention and the rest of the spec), notification settings
+//! and, when a password is given, every locally stored secret sealed with that password. Run
+//! history and snapshots stay behind; `bastion recover` rebuilds those from the targets.
+
+use std::collections::HashSet;
+
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use time::OffsetDateTime;
+
+use bastion_core::job_spec;
+
+use crate::jobs_repo::{self, OverlapPolicy};
+use crate::notification_destinations_repo;
+use crate::notifications_settings_repo::{self, NotificationsSettings};
+use crate::secrets::{PasswordSealedV1, SecretsCrypto};
+use crate::secrets_repo;
+
+const BUNDLE_VERSION: u32 = 1;
+const SECRETS_AAD: &[u8] = b"bastion-config-bundle-secrets-v1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ConfigBundleFileV1 {
+    version: u32,
+    exported_at: i64,
+    jobs: Vec<BundleJob>,
+    notifications: NotificationsSettings,
+    #[serde(default)]
+    disabled_destinations: Vec<BundleDestination>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    secrets: Option<PasswordSealedV1>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleJob {
+    pub name: String,
+    pub agent_id: Option<String>,
+    pub schedule: Option<String>,
+    pub schedule_timezone: String,
+    #[serde(default)]
+    pub schedule_jitter_seconds: u32,
+    #[serde(default)]
+    pub max_success_interval_seconds: u32,
+    pub overlap_policy: OverlapPolicy,
+    pub spec: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleDestination {
+    pub secret_kind: String,
+    pub secret_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleSecret {
+    pub node_id: String,
+    pub kind: String,
+    pub name: String,
+    value_b64: String,
+}
+
+impl BundleSecret {
+    fn value(&self) -> Result<Vec<u8>, anyhow::Error> {
+        Ok(base64::engine::general_purpose::STANDARD.decode(&self.value_b64)?)
+    }
+}
+
+/// Decrypted contents of a bundle.
+#[derive(Debug, Clone)]
+pub struct ConfigBundle {
+    pub jobs: Vec<BundleJob>,
+    pub notifications: NotificationsSettings,
+    pub disabled_destinations: Vec<BundleDestination>,
+    pub secrets: Vec<BundleSecret>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportSummary {
+    /// Ids of the jobs created on this Hub.
+    pub jobs_created: Vec<String>,
+    /// Names of jobs skipped because a job with the same name already exists.
+    pub jobs_skipped: Vec<String>,
+    /// Names of jobs created archived because their agent is not enrolled here.
+    pub jobs_archived: Vec<String>,
+    pub secrets_imported: usize,
+    /// Secrets left alone because this Hub already has one with the same name.
+    pub secrets_skipped: usize,
+}
+
+/// Reads the active jobs and notification settings, plus every locally stored secret when
+/// `include_secrets` is set (external secret references are skipped).
+pub async fn collect(
+    db: &SqlitePool,
+    crypto: &SecretsCrypto,
+    include_secrets: bool,
+) -> Result<ConfigBundle, anyhow::Error> {
+    let mut jobs = jobs_repo::list_jobs(db).await?;
+    jobs.sort_by_key(|j| j.created_at);
+    let jobs = jobs
+        .into_iter()
+        .map(|job| BundleJob {
+            name: job.name,
+            agent_id: job.agent_id,
+            schedule: job.schedule,
+            schedule_timezone: job.schedule_timezone,
+            schedule_jitter_seconds: job.schedule_jitter_seconds,
+            max_success_interval_seconds: job.max_success_interval_seconds,
+            overlap_policy: job.overlap_policy,
+            spec: job.spec,
+        })
+        .collect();
+
+    let disabled_destinations = notification_destinations_repo::list_disabled(db)
+        .await?
+        .into_iter()
+        .map(|(secret_kind, secret_name)| BundleDestination {
+            secret_kind,
+            secret_name,
+        })
+        .collect();
+
+    let mut secrets = Vec::new();
+    if include_secrets {
+        for (node_id, kind, name) in secrets_repo::list_encrypted_secret_keys(db).await? {
+            let Some(value) = secrets_repo::get_secret(db, crypto, &node_id, &kind, &name).await?
+            else {
+                continue;
+            };
+            secrets.push(BundleSecret {
+                node_id,
+                kind,
+                name,
+                value_b64: base64::engine::general_purpose::STANDARD.encode(value),
+            });
+        }
+    }
+
+    Ok(ConfigBundle {
+        jobs,
+        notifications: notifications_settings_repo::get_or_default(db).await?,
+        disabled_destinations,
+        secrets,
+    })
+}
+
+/// Serializes `bundle`, sealing its secrets with `password`.
+///
+/// Argon2 key derivation is slow by design; async callers should run this on a blocking thread.
+pub fn encode(bundle: &ConfigBundle, password: Option<&str>) -> Result<Vec<u8>, anyhow::Error> {
+    let secrets = match password {
+        Some(password) => {
+            let plaintext = serde_json::to_vec(&bundle.secrets)?;
+            Some(PasswordSealedV1::seal(password, SECRETS_AAD, &plaintext)?)
+        }
+        None if bundle.secrets.is_empty() => None,
+        None => anyhow::bail!("a password is required to export secrets"),
+    };
+
+    let file = ConfigBundleFileV1 {
+        version: BUNDLE_VERSION,
+        exported_at: OffsetDateTime::now_utc().unix_timestamp(),
+        jobs: bundle.jobs.clone(),
+        notifications: bundle.notifications.clone(),
+        disabled_destinations: bundle.disabled_destinations.clone(),
+        secrets,
+    };
+    Ok(serde_json::to_vec_pretty(&file)?)
+}
+
+/// Parses and validates a bundle, opening its secrets with `password`.
+///
+/// Bundles with secrets need the password they were exported with; bundles without ignore it.
+pub fn decode(bytes: &[u8], password: Option<&str>) -> Result<ConfigBundle, anyhow::Error> {
+    let file: ConfigBundleFileV1 = serde_json::from_slice(bytes)?;
+    if file.version != BUNDLE_VERSION {
+        anyhow::bail!("unsupported config bundle version");
+    }
+    for job in &file.jobs {
+        if job.name.trim().is_empty() {
+            anyhow::bail!("job name is required");
+        }
+        job_spec::validate_value(&job.spec)
+            .map_err(|error| anyhow::anyhow!("invalid spec for job {:?}: {error}", job.name))?;
+    }
+
+    let secrets = match (&file.secrets, password) {
+        (None, _) => Vec::new(),
+        (Some(sealed), Some(password)) => {
+            let plaintext = sealed.open(password, SECRETS_AAD)?;
+            let secrets: Vec<BundleSecret> = serde_json::from_slice(&plaintext)?;
+            for secret in &secrets {
+                secret.value()?;
+            }
+            secrets
+        }
+        (Some(_), None) => anyhow::bail!("the bundle contains secrets; a password is required"),
+    };
+
+    Ok(ConfigBundle {
+        jobs: file.jobs,
+        notifications: file.notifications,
+        disabled_destinations: file.disabled_destinations,
+        secrets,
+    })
+}
+
+/// Writes a decoded bundle into this Hub.
+///
+/// Existing jobs and secrets win: a job whose name is taken, or a secret that already exists, is
+/// skipped rather than overwritten. Notification settings are replaced. Jobs bound to an agent
+/// that is not enrolled here are created archived, to be reassigned before they run.
+pub async fn apply(
+    db: &SqlitePool,
+    crypto: &SecretsCrypto,
+    bundle: &ConfigBundle,
+) -> Result<ImportSummary, anyhow::Error> {
+    let mut summary = ImportSummary::default();
+
+    for secret in &bundle.secrets {
+        if secrets_repo::secret_exists(db, &secret.node_id, &secret.kind, &secret.name).await? {
+            summary.secrets_skipped += 1;
+            continue;
+        }
+        secrets_repo::upsert_secret(
+            db,
+            crypto,
+            &secret.node_id,
+            &secret.kind,
+            &secret.name,
+            &secret.value()?,
+        )
+        .await?;
+        summary.secrets_imported += 1;
+    }
+
+    for destination in &bundle.disabled_destinations {
+        notification_destinations_repo::set_enabled(
+            db,
+            &destination.secret_kind,
+            &destination.secret_name,
+            false,
+        )
+        .await?;
+    }
+    notifications_settings_repo::upsert(db, &bundle.notifications).await?;
+
+    let mut taken: HashSet<String> = jobs_repo::list_jobs_including_archived(db)
+        .await?
+        .into_iter()
+        .map(|job| job.name)
+        .collect();
+    for job in &bundle.jobs {
+        if !taken.insert(job.name.clone()) {
+            summary.jobs_skipped.push(job.name.clone());
+            continue;
+        }
+
+        let created = jobs_repo::create_job(
+            db,
+            &job.name,
+            job.agent_id.as_deref(),
+            job.schedule.as_deref(),
+            Some(&job.schedule_timezone),
+            job.overlap_policy,
+            job.spec.clone(),
+        )
+        .await?;
+        if job.schedule_jitter_seconds > 0 {
+            jobs_repo::set_job_schedule_jitter(db, &created.id, job.schedule_jitter_seconds)
+                .await?;
+        }
+        if job.max_success_interval_seconds > 0 {
+            jobs_repo::set_job_max_success_interval(
+                db,
+                &created.id,
+                job.max_success_interval_seconds,
+            )
+            .await?;
+        }
+        if let Some(agent_id) = job.agent_id.as_deref()
+            && !agent_is_enrolled(db, agent_id).await?
+        {
+            jobs_repo::archive_job(db, &created.id).await?;
+            summary.jobs_archived.push(job.name.clone());
+        }
+        summary.jobs_created.push(created.id);
+    }
+
+    Ok(summary)
+}
+
+async fn agent_is_enrolled(db: &SqlitePool, agent_id: &str) -> Result<bool, anyhow::Error> {
+    let row = sqlx::query("SELECT revoked_at FROM agents WHERE id = ? LIMIT 1")
+        .bind(agent_id)
+        .fetch_optional(db)
+        .await?;
+    Ok(row.is_some_and(|row| row.get::<Option<i64>, _>("revoked_at").is_none()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use bastion_core::HUB_NODE_ID;
+
+    use crate::db;
+    use crate::jobs_repo::{self, OverlapPolicy};
+    use crate::notification_destinations_repo;
+    use crate::notifications_settings_repo;
+    use crate::secrets::SecretsCrypto;
+    use crate::secrets_repo;
+
+    use super::{apply, collect, decode, encode};
+
+    fn spec() -> serde_json::Value {
+        serde_json::json!({
+            "v": 1,
+            "type": "filesystem",
+            "source": { "root": "/" },
+            "target": { "type": "local_dir", "base_dir": "/tmp" }
+        })
+    }
+
+    #[tokio::test]
+    async fn bundle_round_trips_jobs_settings_and_secrets() {
+        let src_dir = TempDir::new().expect("tempdir");
+        let src = db::init(src_dir.path()).await.expect("db init");
+        let src_crypto = SecretsCrypto::load_or_create(src_dir.path()).expect("crypto");
+
+        let job = jobs_repo::create_job(
+            &src,
+            "nightly",
+            None,
+            Some("0 2 * * *"),
+            Some("Europe/Berlin"),
+            OverlapPolicy::Queue,
+            spec(),
+        )
+        .await
+        .expect("create job");
+        jobs_repo::set_job_schedule_jitter(&src, &job.id, 120)
+            .await
+            .expect("jitter");
+        jobs_repo::create_job(
+            &src,
+            "remote",
+            Some("gone-agent"),
+            None,
+            None,
+            OverlapPolicy::Reject,
+            spec(),
+        )
+        .await
+        .expect("create agent job");
+
+        let mut settings = notifications_settings_repo::get_or_default(&src)
+            .await
+            .expect("settings");
+        settings.channels.email.enabled = false;
+        notifications_settings_repo::upsert(&src, &settings)
+            .await
+            .expect("upsert settings");
+        secrets_repo::upsert_secret(&src, &src_crypto, HUB_NODE_ID, "smtp", "ops", b"hunter2")
+            .await
+            .expect("secret");
+        notification_destinations_repo::set_enabled(&src, "smtp", "ops", false)
+            .await
+            .expect("disable");
+
+        let bundle = collect(&src, &src_crypto, true).await.expect("collect");
+        assert!(encode(&bundle, None).is_err());
+        let bytes = encode(&bundle, Some("bundle-password")).expect("encode");
+        assert!(!String::from_utf8_lossy(&bytes).contains("hunter2"));
+        assert!(decode(&bytes, None).is_err());
+        assert!(decode(&bytes, Some("wrong-password")).is_err());
+
+        let dst_dir = TempDir::new().expect("tempdir");
+        let dst = db::init(dst_dir.path()).await.expect("db init");
+        let dst_crypto = SecretsCrypto::load_or_create(dst_dir.path()).expect("crypto");
+        jobs_repo::create_job(
+            &dst,
+            "nightly",
+            None,
+            None,
+            None,
+            OverlapPolicy::Queue,
+            spec(),
+        )
+        .await
+        .expect("existing job");
+
+        let decoded = decode(&bytes, Some("bundle-password")).expect("decode");
+        let summary = apply(&dst, &dst_crypto, &decoded).await.expect("apply");
+        assert_eq!(summary.jobs_skipped, vec!["nightly".to_string()]);
+        assert_eq!(summary.jobs_archived, vec!["remote".to_string()]);
+        assert_eq!(summary.jobs_created.len(), 1);
+        assert_eq!(summary.secrets_imported, 1);
+
+        let remote = jobs_repo::get_job(&dst, &summary.jobs_created[0])
+            .await
+            .expect("get job")
+            .expect("job");
+        assert_eq!(remote.agent_id.as_deref(), Some("gone-agent"));
+        assert!(remote.archived_at.is_some());
+
+        let secret = secrets_repo::get_secret(&dst, &dst_crypto, HUB_NODE_ID, "smtp", "ops")
+            .await
+            .expect("get secret");
+        assert_eq!(secret.as_deref(), Some(&b"hunter2"[..]));
+        assert!(
+            !notification_destinations_repo::is_enabled(&dst, "smtp", "ops")
+                .await
+                .expect("enabled")
+        );
+        let imported = notifications_settings_repo::get_or_default(&dst)
+            .await
+            .expect("settings");
+        assert!(!imported.channels.email.enabled);
+
+        let again = apply(&dst, &dst_crypto, &decoded)
+            .await
+            .expect("apply again");
+        assert!(again.jobs_created.is_empty());
+        assert_eq!(again.secrets_skipped, 1);
+    }
+
+    #[tokio::test]
+    async fn bundle_without_secrets_needs_no_password() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        let crypto = SecretsCrypto::load_or_create(temp.path()).expect("crypto");
+        secrets_repo::upsert_secret(&pool, &crypto, HUB_NODE_ID, "smtp", "ops", b"hunter2")
+            .await
+            .expect("secret");
+
+        let bundle = collect(&pool, &crypto, false).await.expect("collect");
+        assert!(bundle.secrets.is_empty());
+        let bytes = encode(&bundle, None).expect("encode");
+        let decoded = decode(&bytes, None).expect("decode");
+        assert!(decoded.secrets.is_empty());
+    }
+}
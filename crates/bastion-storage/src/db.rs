@@ -1,8 +1,8 @@
 use std::path::Path;
 use std::time::Duration;
 
-use sqlx::SqlitePool;
 use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
+use sqlx::{Row, SqlitePool};
 
 pub async fn init(data_dir: &Path) -> Result<SqlitePool, anyhow::Error> {
     let db_path = data_dir.join("bastion.db");
@@ -24,3 +24,50 @@ pub async fn init(data_dir: &Path) -> Result<SqlitePool, anyhow::Error> {
     sqlx::migrate!("./migrations").run(&pool).await?;
     Ok(pool)
 }
+
+/// Runs `PRAGMA integrity_check` and returns the reported problems (empty when the database is
+/// intact). SQLite stops after `max_errors` problems.
+pub async fn integrity_check(
+    db: &SqlitePool,
+    max_errors: u32,
+) -> Result<Vec<String>, anyhow::Error> {
+    let rows: Vec<String> = sqlx::query_scalar(&format!("PRAGMA integrity_check({max_errors})"))
+        .fetch_all(db)
+        .await?;
+    Ok(rows.into_iter().filter(|row| row != "ok").collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct WalCheckpoint {
+    /// A reader or writer kept the checkpoint from completing.
+    pub busy: bool,
+    pub log_frames: i64,
+    pub checkpointed_frames: i64,
+}
+
+/// Copies the WAL back into the database file and truncates it.
+pub async fn wal_checkpoint(db: &SqlitePool) -> Result<WalCheckpoint, anyhow::Error> {
+    let row = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .fetch_one(db)
+        .await?;
+    Ok(WalCheckpoint {
+        busy: row.try_get::<i64, _>(0)? != 0,
+        log_frames: row.try_get::<i64, _>(1)?,
+        checkpointed_frames: row.try_get::<i64, _>(2)?,
+    })
+}
+
+pub async fn optimize(db: &SqlitePool) -> Result<(), anyhow::Error> {
+    sqlx::query("PRAGMA optimize").execute(db).await?;
+    Ok(())
+}
+
+/// Writes a consistent, compacted copy of the database to `dest` while the Hub keeps running.
+/// `dest` must not exist yet.
+pub async fn vacuum_into(db: &SqlitePool, dest: &Path) -> Result<(), anyhow::Error> {
+    let dest = dest
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("backup path is not valid UTF-8"))?;
+    sqlx::query("VACUUM INTO ?").bind(dest).execute(db).await?;
+    Ok(())
+}
@@ -0,0 +1,366 @@
+use serde::Serialize;
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+use uuid::Uuid;
+
+use crate::job_access_repo;
+
+pub const KIND_SNAPSHOT_ARTIFACT: &str = "snapshot_artifact";
+pub const KIND_RESTORED_FILE: &str = "restored_file";
+
+pub const ACTION_CREATED: &str = "created";
+pub const ACTION_DOWNLOADED: &str = "downloaded";
+pub const ACTION_REVOKED: &str = "revoked";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadLink {
+    pub id: String,
+    pub kind: String,
+    pub subject_id: String,
+    pub path: String,
+    pub created_by_user_id: Option<i64>,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub revoked_at: Option<i64>,
+    pub download_count: i64,
+    pub last_downloaded_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadLinkAuditEntry {
+    pub id: i64,
+    pub link_id: String,
+    pub ts: i64,
+    pub action: String,
+    pub user_id: Option<i64>,
+    pub peer_ip: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NewDownloadLink<'a> {
+    pub token_hash: &'a [u8],
+    pub kind: &'a str,
+    pub subject_id: &'a str,
+    pub path: &'a str,
+    pub created_by_user_id: Option<i64>,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+fn parse_link_row(row: &sqlx::sqlite::SqliteRow) -> DownloadLink {
+    DownloadLink {
+        id: row.get::<String, _>("id"),
+        kind: row.get::<String, _>("kind"),
+        subject_id: row.get::<String, _>("subject_id"),
+        path: row.get::<String, _>("path"),
+        created_by_user_id: row.get::<Option<i64>, _>("created_by_user_id"),
+        created_at: row.get::<i64, _>("created_at"),
+        expires_at: row.get::<i64, _>("expires_at"),
+        revoked_at: row.get::<Option<i64>, _>("revoked_at"),
+        download_count: row.get::<i64, _>("download_count"),
+        last_downloaded_at: row.get::<Option<i64>, _>("last_downloaded_at"),
+    }
+}
+
+async fn append_audit(
+    conn: &mut sqlx::SqliteConnection,
+    link_id: &str,
+    ts: i64,
+    action: &str,
+    user_id: Option<i64>,
+    peer_ip: Option<&str>,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "INSERT INTO download_link_audit (link_id, ts, action, user_id, peer_ip) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(link_id)
+    .bind(ts)
+    .bind(action)
+    .bind(user_id)
+    .bind(peer_ip)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn create_link(
+    db: &SqlitePool,
+    link: NewDownloadLink<'_>,
+    peer_ip: Option<&str>,
+) -> Result<DownloadLink, anyhow::Error> {
+    let id = Uuid::new_v4().to_string();
+
+    let mut tx = db.begin().await?;
+    sqlx::query(
+        r#"
+        INSERT INTO download_links (id, token_hash, kind, subject_id, path, created_by_user_id, created_at, expires_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(link.token_hash)
+    .bind(link.kind)
+    .bind(link.subject_id)
+    .bind(link.path)
+    .bind(link.created_by_user_id)
+    .bind(link.created_at)
+    .bind(link.expires_at)
+    .execute(&mut *tx)
+    .await?;
+    append_audit(
+        &mut tx,
+        &id,
+        link.created_at,
+        ACTION_CREATED,
+        link.created_by_user_id,
+        peer_ip,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(DownloadLink {
+        id,
+        kind: link.kind.to_string(),
+        subject_id: link.subject_id.to_string(),
+        path: link.path.to_string(),
+        created_by_user_id: link.created_by_user_id,
+        created_at: link.created_at,
+        expires_at: link.expires_at,
+        revoked_at: None,
+        download_count: 0,
+        last_downloaded_at: None,
+    })
+}
+
+/// Looks up a link that is neither revoked nor expired at `now`.
+pub async fn find_active_by_token_hash(
+    db: &SqlitePool,
+    token_hash: &[u8],
+    now: i64,
+) -> Result<Option<DownloadLink>, anyhow::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, kind, subject_id, path, created_by_user_id, created_at, expires_at, revoked_at, download_count, last_downloaded_at
+        FROM download_links
+        WHERE token_hash = ? AND revoked_at IS NULL AND expires_at > ?
+        LIMIT 1
+        "#,
+    )
+    .bind(token_hash)
+    .bind(now)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.as_ref().map(parse_link_row))
+}
+
+pub async fn get_link(db: &SqlitePool, id: &str) -> Result<Option<DownloadLink>, anyhow::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT id, kind, subject_id, path, created_by_user_id, created_at, expires_at, revoked_at, download_count, last_downloaded_at
+        FROM download_links
+        WHERE id = ?
+        LIMIT 1
+        "#,
+    )
+    .bind(id)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.as_ref().map(parse_link_row))
+}
+
+/// Newest links first. With `viewer` set, only links to runs and restores of jobs that user can
+/// see are returned.
+pub async fn list_links(
+    db: &SqlitePool,
+    limit: u32,
+    viewer: Option<i64>,
+) -> Result<Vec<DownloadLink>, anyhow::Error> {
+    let mut qb = QueryBuilder::<Sqlite>::new(
+        r#"
+        SELECT l.id, l.kind, l.subject_id, l.path, l.created_by_user_id, l.created_at, l.expires_at, l.revoked_at, l.download_count, l.last_downloaded_at
+        FROM download_links l
+        "#,
+    );
+    if let Some(user_id) = viewer {
+        qb.push(format!(
+            r#"
+            LEFT JOIN operations o ON l.kind = '{KIND_RESTORED_FILE}' AND o.id = l.subject_id
+            LEFT JOIN runs r ON r.id = CASE
+              WHEN l.kind = '{KIND_SNAPSHOT_ARTIFACT}' THEN l.subject_id
+              WHEN o.subject_kind = 'run' THEN o.subject_id
+            END
+            JOIN jobs j ON j.id = COALESCE(r.job_id, CASE WHEN o.subject_kind = 'job' THEN o.subject_id END)
+            WHERE "#
+        ));
+        job_access_repo::push_visible_jobs_condition(&mut qb, "j", user_id);
+    }
+    qb.push(" ORDER BY l.created_at DESC, l.id DESC LIMIT ");
+    qb.push_bind(limit as i64);
+
+    let rows = qb.build().fetch_all(db).await?;
+    Ok(rows.iter().map(parse_link_row).collect())
+}
+
+pub async fn record_download(
+    db: &SqlitePool,
+    link_id: &str,
+    peer_ip: Option<&str>,
+    now: i64,
+) -> Result<(), anyhow::Error> {
+    let mut tx = db.begin().await?;
+    sqlx::query(
+        "UPDATE download_links SET download_count = download_count + 1, last_downloaded_at = ? WHERE id = ?",
+    )
+    .bind(now)
+    .bind(link_id)
+    .execute(&mut *tx)
+    .await?;
+    append_audit(&mut tx, link_id, now, ACTION_DOWNLOADED, None, peer_ip).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Revokes a link. Returns false when the link does not exist or is already revoked.
+pub async fn revoke_link(
+    db: &SqlitePool,
+    link_id: &str,
+    user_id: Option<i64>,
+    peer_ip: Option<&str>,
+    now: i64,
+) -> Result<bool, anyhow::Error> {
+    let mut tx = db.begin().await?;
+    let result =
+        sqlx::query("UPDATE download_links SET revoked_at = ? WHERE id = ? AND revoked_at IS NULL")
+            .bind(now)
+            .bind(link_id)
+            .execute(&mut *tx)
+            .await?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+    append_audit(&mut tx, link_id, now, ACTION_REVOKED, user_id, peer_ip).await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
+pub async fn list_audit(
+    db: &SqlitePool,
+    link_id: &str,
+) -> Result<Vec<DownloadLinkAuditEntry>, anyhow::Error> {
+    let rows = sqlx::query(
+        "SELECT id, link_id, ts, action, user_id, peer_ip FROM download_link_audit WHERE link_id = ? ORDER BY ts ASC, id ASC",
+    )
+    .bind(link_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| DownloadLinkAuditEntry {
+            id: row.get::<i64, _>("id"),
+            link_id: row.get::<String, _>("link_id"),
+            ts: row.get::<i64, _>("ts"),
+            action: row.get::<String, _>("action"),
+            user_id: row.get::<Option<i64>, _>("user_id"),
+            peer_ip: row.get::<Option<String>, _>("peer_ip"),
+        })
+        .collect())
+}
+
+/// Deletes links (and their audit trail) that expired before `cutoff`.
+pub async fn prune_expired_before(db: &SqlitePool, cutoff: i64) -> Result<u64, anyhow::Error> {
+    let result = sqlx::query("DELETE FROM download_links WHERE expires_at < ?")
+        .bind(cutoff)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::db;
+
+    use super::{
+        ACTION_CREATED, ACTION_DOWNLOADED, ACTION_REVOKED, KIND_RESTORED_FILE, NewDownloadLink,
+        create_link, find_active_by_token_hash, get_link, list_audit, prune_expired_before,
+        record_download, revoke_link,
+    };
+
+    #[tokio::test]
+    async fn links_expire_revoke_and_keep_audit() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+
+        let link = create_link(
+            &pool,
+            NewDownloadLink {
+                token_hash: &[1, 2, 3],
+                kind: KIND_RESTORED_FILE,
+                subject_id: "op1",
+                path: "/tmp/restore/a.txt",
+                created_by_user_id: Some(1),
+                created_at: 100,
+                expires_at: 200,
+            },
+            Some("10.0.0.1"),
+        )
+        .await
+        .expect("create");
+
+        assert!(
+            find_active_by_token_hash(&pool, &[1, 2, 3], 150)
+                .await
+                .expect("find")
+                .is_some()
+        );
+        assert!(
+            find_active_by_token_hash(&pool, &[1, 2, 3], 200)
+                .await
+                .expect("find expired")
+                .is_none()
+        );
+
+        record_download(&pool, &link.id, Some("10.0.0.2"), 160)
+            .await
+            .expect("download");
+        assert!(
+            revoke_link(&pool, &link.id, Some(1), None, 170)
+                .await
+                .expect("revoke")
+        );
+        assert!(
+            !revoke_link(&pool, &link.id, Some(1), None, 180)
+                .await
+                .expect("revoke again")
+        );
+        assert!(
+            find_active_by_token_hash(&pool, &[1, 2, 3], 175)
+                .await
+                .expect("find revoked")
+                .is_none()
+        );
+
+        let stored = get_link(&pool, &link.id)
+            .await
+            .expect("get")
+            .expect("exists");
+        assert_eq!(stored.download_count, 1);
+        assert_eq!(stored.last_downloaded_at, Some(160));
+        assert_eq!(stored.revoked_at, Some(170));
+
+        let audit = list_audit(&pool, &link.id).await.expect("audit");
+        let actions: Vec<&str> = audit.iter().map(|a| a.action.as_str()).collect();
+        assert_eq!(
+            actions,
+            vec![ACTION_CREATED, ACTION_DOWNLOADED, ACTION_REVOKED]
+        );
+        assert_eq!(audit[1].peer_ip.as_deref(), Some("10.0.0.2"));
+
+        assert_eq!(prune_expired_before(&pool, 200).await.expect("prune"), 0);
+        assert_eq!(prune_expired_before(&pool, 201).await.expect("prune"), 1);
+        assert!(list_audit(&pool, &link.id).await.expect("audit").is_empty());
+    }
+}
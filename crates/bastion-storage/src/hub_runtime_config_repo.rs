@@ -29,6 +29,37 @@ pub struct HubRuntimeConfig {
 
     #[serde(default)]
     pub default_backup_retention: RetentionPolicyV1,
+
+    /// Spreads simultaneous scheduled runs over this many seconds (per-job stable offset).
+    #[serde(default)]
+    pub schedule_stagger_seconds: Option<u32>,
+
+    /// Deletes stored snapshot data this many days after the run ended (pinned and locked
+    /// snapshots excepted). Run history is kept separately, per `run_retention_days`.
+    #[serde(default)]
+    pub artifact_retention_days: Option<u32>,
+
+    /// Removes run events (except errors) this many days after the run ended. The run record is
+    /// kept per `run_retention_days`.
+    #[serde(default)]
+    pub event_retention_days: Option<u32>,
+
+    /// Caps the events kept per finished run; `None` uses the built-in default.
+    #[serde(default)]
+    pub max_events_per_run: Option<u32>,
+
+    /// Absolute lifetime of new browser sessions; `None` uses 7 days.
+    #[serde(default)]
+    pub session_max_age_hours: Option<u32>,
+
+    /// Ends new browser sessions after this long without a request; `None` disables it.
+    #[serde(default)]
+    pub session_idle_timeout_minutes: Option<u32>,
+
+    /// Holds restores to destinations a job hasn't restored to before until a second admin
+    /// approves them.
+    #[serde(default)]
+    pub restore_approval_required: bool,
 }
 
 pub async fn get(db: &SqlitePool) -> Result<Option<HubRuntimeConfig>, anyhow::Error> {
@@ -76,7 +107,15 @@ mod tests {
                 keep_days: Some(30),
                 max_delete_per_tick: 20,
                 max_delete_per_day: 100,
+                lock_days: None,
             },
+            schedule_stagger_seconds: Some(600),
+            artifact_retention_days: Some(14),
+            event_retention_days: Some(30),
+            max_events_per_run: Some(5000),
+            session_max_age_hours: Some(24),
+            session_idle_timeout_minutes: Some(60),
+            restore_approval_required: true,
         };
         upsert(&pool, &cfg).await.expect("upsert");
 
@@ -92,6 +131,13 @@ mod tests {
         assert_eq!(loaded.log_file.as_deref(), Some("/tmp/bastion.log"));
         assert_eq!(loaded.log_rotation.as_deref(), Some("daily"));
         assert_eq!(loaded.log_keep_files, Some(10));
+        assert_eq!(loaded.schedule_stagger_seconds, Some(600));
+        assert_eq!(loaded.artifact_retention_days, Some(14));
+        assert_eq!(loaded.event_retention_days, Some(30));
+        assert_eq!(loaded.max_events_per_run, Some(5000));
+        assert_eq!(loaded.session_max_age_hours, Some(24));
+        assert_eq!(loaded.session_idle_timeout_minutes, Some(60));
+        assert!(loaded.restore_approval_required);
         assert!(loaded.default_backup_retention.enabled);
         assert_eq!(loaded.default_backup_retention.keep_last, Some(7));
         assert_eq!(loaded.default_backup_retention.keep_days, Some(30));
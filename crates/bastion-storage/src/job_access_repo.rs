@@ -0,0 +1,382 @@
+//! Job ownership and per-job grants.
+//!
+//! Admins, a job's owner, and everyone for jobs without an owner get full access. Other users
+//! only see jobs they were granted a permission on.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool};
+
+/// What a user may do with a job. Each level includes the ones below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPermission {
+    /// See the job, its runs and snapshots.
+    View,
+    /// Also run, cancel, verify and restore it, and download from its snapshots.
+    Trigger,
+    /// Also change, archive or delete it and its snapshots.
+    Edit,
+}
+
+impl JobPermission {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::View => "view",
+            Self::Trigger => "trigger",
+            Self::Edit => "edit",
+        }
+    }
+}
+
+impl std::str::FromStr for JobPermission {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "view" => Ok(Self::View),
+            "trigger" => Ok(Self::Trigger),
+            "edit" => Ok(Self::Edit),
+            _ => Err(anyhow::anyhow!("invalid job permission")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobGrant {
+    pub user_id: i64,
+    pub username: String,
+    pub permission: JobPermission,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobAccess {
+    pub owner_user_id: Option<i64>,
+    pub owner_username: Option<String>,
+    pub grants: Vec<JobGrant>,
+}
+
+pub async fn is_admin(db: &SqlitePool, user_id: i64) -> Result<bool, sqlx::Error> {
+    let admin = sqlx::query_scalar::<_, i64>("SELECT is_admin FROM users WHERE id = ? LIMIT 1")
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?;
+    Ok(admin == Some(1))
+}
+
+/// Grants or revokes admin rights. Returns `false` if the user doesn't exist.
+pub async fn set_admin(db: &SqlitePool, user_id: i64, admin: bool) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("UPDATE users SET is_admin = ? WHERE id = ?")
+        .bind(i64::from(admin))
+        .bind(user_id)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// The user's permission on a job; `None` when the job doesn't exist or they can't see it.
+pub async fn permission_for(
+    db: &SqlitePool,
+    job_id: &str,
+    user_id: i64,
+) -> Result<Option<JobPermission>, anyhow::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT
+          j.owner_user_id AS owner_user_id,
+          (SELECT is_admin FROM users WHERE id = ?) AS is_admin,
+          (SELECT permission FROM job_acl WHERE job_id = j.id AND user_id = ?) AS permission
+        FROM jobs j
+        WHERE j.id = ?
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .bind(job_id)
+    .fetch_optional(db)
+    .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let owner = row.get::<Option<i64>, _>("owner_user_id");
+    if row.get::<Option<i64>, _>("is_admin") == Some(1) || owner.is_none_or(|o| o == user_id) {
+        return Ok(Some(JobPermission::Edit));
+    }
+    row.get::<Option<String>, _>("permission")
+        .map(|p| p.parse::<JobPermission>())
+        .transpose()
+}
+
+/// Like [`permission_for`], for the job a run belongs to. `None` also when the run doesn't exist.
+pub async fn permission_for_run(
+    db: &SqlitePool,
+    run_id: &str,
+    user_id: i64,
+) -> Result<Option<JobPermission>, anyhow::Error> {
+    let job_id = sqlx::query_scalar::<_, String>("SELECT job_id FROM runs WHERE id = ? LIMIT 1")
+        .bind(run_id)
+        .fetch_optional(db)
+        .await?;
+    match job_id {
+        Some(job_id) => permission_for(db, &job_id, user_id).await,
+        None => Ok(None),
+    }
+}
+
+/// Like [`permission_for`], for the job or run an operation was started on. Operations not tied
+/// to a job (such as key re-encryption) are unrestricted. `None` also when the operation doesn't
+/// exist.
+pub async fn permission_for_operation(
+    db: &SqlitePool,
+    op_id: &str,
+    user_id: i64,
+) -> Result<Option<JobPermission>, anyhow::Error> {
+    let row = sqlx::query("SELECT subject_kind, subject_id FROM operations WHERE id = ? LIMIT 1")
+        .bind(op_id)
+        .fetch_optional(db)
+        .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    let kind = row.get::<Option<String>, _>("subject_kind");
+    let id = row
+        .get::<Option<String>, _>("subject_id")
+        .unwrap_or_default();
+    match kind.as_deref() {
+        Some("run") => permission_for_run(db, &id, user_id).await,
+        Some("job") => permission_for(db, &id, user_id).await,
+        _ => Ok(Some(JobPermission::Edit)),
+    }
+}
+
+/// Pushes a condition limiting `jobs` aliased as `job_alias` to those `user_id` can see. Callers
+/// skip it for admins.
+pub fn push_visible_jobs_condition(
+    qb: &mut QueryBuilder<'_, Sqlite>,
+    job_alias: &str,
+    user_id: i64,
+) {
+    qb.push(format!(
+        "({job_alias}.owner_user_id IS NULL OR {job_alias}.owner_user_id = "
+    ));
+    qb.push_bind(user_id);
+    qb.push(format!(
+        " OR EXISTS (SELECT 1 FROM job_acl acl WHERE acl.job_id = {job_alias}.id AND acl.user_id = "
+    ));
+    qb.push_bind(user_id);
+    qb.push("))");
+}
+
+/// Ids of the jobs `user_id` can see.
+pub async fn visible_job_ids(
+    db: &SqlitePool,
+    user_id: i64,
+) -> Result<std::collections::HashSet<String>, sqlx::Error> {
+    let mut qb = QueryBuilder::<Sqlite>::new("SELECT j.id FROM jobs j WHERE ");
+    push_visible_jobs_condition(&mut qb, "j", user_id);
+    let ids = qb.build_query_scalar::<String>().fetch_all(db).await?;
+    Ok(ids.into_iter().collect())
+}
+
+pub async fn get_access(db: &SqlitePool, job_id: &str) -> Result<Option<JobAccess>, anyhow::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT j.owner_user_id AS owner_user_id, u.username AS owner_username
+        FROM jobs j
+        LEFT JOIN users u ON u.id = j.owner_user_id
+        WHERE j.id = ?
+        LIMIT 1
+        "#,
+    )
+    .bind(job_id)
+    .fetch_optional(db)
+    .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    let grants = sqlx::query(
+        r#"
+        SELECT a.user_id AS user_id, u.username AS username, a.permission AS permission
+        FROM job_acl a
+        JOIN users u ON u.id = a.user_id
+        WHERE a.job_id = ?
+        ORDER BY u.username ASC
+        "#,
+    )
+    .bind(job_id)
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .map(|r| {
+        Ok(JobGrant {
+            user_id: r.get::<i64, _>("user_id"),
+            username: r.get::<String, _>("username"),
+            permission: r.get::<String, _>("permission").parse()?,
+        })
+    })
+    .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+    Ok(Some(JobAccess {
+        owner_user_id: row.get::<Option<i64>, _>("owner_user_id"),
+        owner_username: row.get::<Option<String>, _>("owner_username"),
+        grants,
+    }))
+}
+
+pub async fn set_owner(
+    db: &SqlitePool,
+    job_id: &str,
+    owner_user_id: Option<i64>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("UPDATE jobs SET owner_user_id = ? WHERE id = ?")
+        .bind(owner_user_id)
+        .bind(job_id)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Replaces a job's owner and grants in one go.
+pub async fn replace_access(
+    db: &SqlitePool,
+    job_id: &str,
+    owner_user_id: Option<i64>,
+    grants: &[(i64, JobPermission)],
+) -> Result<bool, sqlx::Error> {
+    let mut tx = db.begin().await?;
+    let result = sqlx::query("UPDATE jobs SET owner_user_id = ? WHERE id = ?")
+        .bind(owner_user_id)
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+    sqlx::query("DELETE FROM job_acl WHERE job_id = ?")
+        .bind(job_id)
+        .execute(&mut *tx)
+        .await?;
+    for (user_id, permission) in grants {
+        sqlx::query("INSERT INTO job_acl (job_id, user_id, permission) VALUES (?, ?, ?)")
+            .bind(job_id)
+            .bind(user_id)
+            .bind(permission.as_str())
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::QueryBuilder;
+    use tempfile::TempDir;
+
+    use crate::auth::{create_first_user, create_user, find_user_by_username};
+    use crate::{db, jobs_repo};
+
+    use super::{
+        JobPermission, get_access, is_admin, permission_for, push_visible_jobs_condition,
+        replace_access, set_admin, set_owner, visible_job_ids,
+    };
+
+    #[tokio::test]
+    async fn permissions_follow_ownership_grants_and_admin() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        create_first_user(&pool, "admin", "pw")
+            .await
+            .expect("admin");
+        create_user(&pool, "alice", "pw").await.expect("alice");
+        create_user(&pool, "bob", "pw").await.expect("bob");
+        let id = |name: &'static str| {
+            let pool = pool.clone();
+            async move {
+                find_user_by_username(&pool, name)
+                    .await
+                    .expect("find")
+                    .expect("user")
+                    .id
+            }
+        };
+        let (admin, alice, bob) = (id("admin").await, id("alice").await, id("bob").await);
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "job1",
+            None,
+            None,
+            Some("UTC"),
+            jobs_repo::OverlapPolicy::Queue,
+            serde_json::json!({"v": 1, "type": "filesystem"}),
+        )
+        .await
+        .expect("job");
+
+        // Unowned jobs are shared.
+        assert_eq!(
+            permission_for(&pool, &job.id, bob).await.unwrap(),
+            Some(JobPermission::Edit)
+        );
+
+        assert!(set_owner(&pool, &job.id, Some(alice)).await.unwrap());
+        assert_eq!(
+            permission_for(&pool, &job.id, alice).await.unwrap(),
+            Some(JobPermission::Edit)
+        );
+        assert_eq!(
+            permission_for(&pool, &job.id, admin).await.unwrap(),
+            Some(JobPermission::Edit)
+        );
+        assert_eq!(permission_for(&pool, &job.id, bob).await.unwrap(), None);
+
+        let visible = |user_id: i64| {
+            let pool = pool.clone();
+            async move {
+                let mut qb = QueryBuilder::new("SELECT COUNT(*) FROM jobs j WHERE ");
+                push_visible_jobs_condition(&mut qb, "j", user_id);
+                qb.build_query_scalar::<i64>()
+                    .fetch_one(&pool)
+                    .await
+                    .expect("count")
+            }
+        };
+        assert_eq!(visible(bob).await, 0);
+
+        assert!(
+            replace_access(
+                &pool,
+                &job.id,
+                Some(alice),
+                &[(bob, JobPermission::Trigger)]
+            )
+            .await
+            .unwrap()
+        );
+        assert_eq!(
+            permission_for(&pool, &job.id, bob).await.unwrap(),
+            Some(JobPermission::Trigger)
+        );
+        assert_eq!(visible(bob).await, 1);
+        assert!(visible_job_ids(&pool, bob).await.unwrap().contains(&job.id));
+
+        let access = get_access(&pool, &job.id).await.unwrap().expect("access");
+        assert_eq!(access.owner_username.as_deref(), Some("alice"));
+        assert_eq!(access.grants.len(), 1);
+        assert_eq!(access.grants[0].username, "bob");
+
+        assert!(
+            permission_for(&pool, "missing", admin)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(set_admin(&pool, bob, true).await.unwrap());
+        assert!(is_admin(&pool, bob).await.unwrap());
+        assert!(!set_admin(&pool, 9999, true).await.unwrap());
+    }
+}
@@ -2,8 +2,10 @@ mod repo;
 mod types;
 
 pub use repo::{
-    UpdateJobParams, archive_job, create_job, delete_job, get_job, list_jobs, list_jobs_for_agent,
-    list_jobs_including_archived, unarchive_job, update_job,
+    UpdateJobParams, archive_job, create_job, create_job_with_id, delete_job,
+    find_template_instance, get_job, list_jobs, list_jobs_for_agent, list_jobs_including_archived,
+    set_job_max_success_interval, set_job_schedule_jitter, set_job_template, unarchive_job,
+    update_job,
 };
 pub use types::{Job, OverlapPolicy};
 
@@ -23,8 +23,33 @@ pub async fn create_job(
     overlap_policy: OverlapPolicy,
     spec: serde_json::Value,
 ) -> Result<Job, anyhow::Error> {
-    let now = OffsetDateTime::now_utc().unix_timestamp();
     let id = Uuid::new_v4().to_string();
+    create_job_with_id(
+        db,
+        &id,
+        name,
+        agent_id,
+        schedule,
+        schedule_timezone,
+        overlap_policy,
+        spec,
+    )
+    .await
+}
+
+/// Inserts a job under a caller-chosen id (used when recovering jobs from target directories).
+#[allow(clippy::too_many_arguments)]
+pub async fn create_job_with_id(
+    db: &SqlitePool,
+    id: &str,
+    name: &str,
+    agent_id: Option<&str>,
+    schedule: Option<&str>,
+    schedule_timezone: Option<&str>,
+    overlap_policy: OverlapPolicy,
+    spec: serde_json::Value,
+) -> Result<Job, anyhow::Error> {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
     let spec_json = serde_json::to_string(&spec)?;
     let schedule_timezone = schedule_timezone.unwrap_or("UTC");
 
@@ -34,7 +59,7 @@ pub async fn create_job(
         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
-    .bind(&id)
+    .bind(id)
     .bind(name)
     .bind(agent_id)
     .bind(schedule)
@@ -47,11 +72,13 @@ pub async fn create_job(
     .await?;
 
     Ok(Job {
-        id,
+        id: id.to_string(),
         name: name.to_string(),
         agent_id: agent_id.map(|s| s.to_string()),
         schedule: schedule.map(|s| s.to_string()),
         schedule_timezone: schedule_timezone.to_string(),
+        schedule_jitter_seconds: 0,
+        max_success_interval_seconds: 0,
         overlap_policy,
         spec,
         created_at: now,
@@ -62,7 +89,7 @@ pub async fn create_job(
 
 pub async fn get_job(db: &SqlitePool, job_id: &str) -> Result<Option<Job>, anyhow::Error> {
     let row = sqlx::query(
-        "SELECT id, name, agent_id, schedule, schedule_timezone, overlap_policy, spec_json, created_at, updated_at, archived_at FROM jobs WHERE id = ? LIMIT 1",
+        "SELECT id, name, agent_id, schedule, schedule_timezone, schedule_jitter_seconds, max_success_interval_seconds, overlap_policy, spec_json, created_at, updated_at, archived_at FROM jobs WHERE id = ? LIMIT 1",
     )
     .bind(job_id)
     .fetch_optional(db)
@@ -84,6 +111,12 @@ pub async fn get_job(db: &SqlitePool, job_id: &str) -> Result<Option<Job>, anyho
         agent_id: row.get::<Option<String>, _>("agent_id"),
         schedule: row.get::<Option<String>, _>("schedule"),
         schedule_timezone: row.get::<String, _>("schedule_timezone"),
+        schedule_jitter_seconds: u32::try_from(row.get::<i64, _>("schedule_jitter_seconds"))
+            .unwrap_or(0),
+        max_success_interval_seconds: u32::try_from(
+            row.get::<i64, _>("max_success_interval_seconds"),
+        )
+        .unwrap_or(0),
         overlap_policy,
         spec,
         created_at: row.get::<i64, _>("created_at"),
@@ -94,7 +127,7 @@ pub async fn get_job(db: &SqlitePool, job_id: &str) -> Result<Option<Job>, anyho
 
 pub async fn list_jobs(db: &SqlitePool) -> Result<Vec<Job>, anyhow::Error> {
     let rows = sqlx::query(
-        "SELECT id, name, agent_id, schedule, schedule_timezone, overlap_policy, spec_json, created_at, updated_at, archived_at FROM jobs WHERE archived_at IS NULL ORDER BY created_at DESC",
+        "SELECT id, name, agent_id, schedule, schedule_timezone, schedule_jitter_seconds, max_success_interval_seconds, overlap_policy, spec_json, created_at, updated_at, archived_at FROM jobs WHERE archived_at IS NULL ORDER BY created_at DESC",
     )
     .fetch_all(db)
     .await?;
@@ -113,6 +146,12 @@ pub async fn list_jobs(db: &SqlitePool) -> Result<Vec<Job>, anyhow::Error> {
             agent_id: row.get::<Option<String>, _>("agent_id"),
             schedule: row.get::<Option<String>, _>("schedule"),
             schedule_timezone: row.get::<String, _>("schedule_timezone"),
+            schedule_jitter_seconds: u32::try_from(row.get::<i64, _>("schedule_jitter_seconds"))
+                .unwrap_or(0),
+            max_success_interval_seconds: u32::try_from(
+                row.get::<i64, _>("max_success_interval_seconds"),
+            )
+            .unwrap_or(0),
             overlap_policy,
             spec,
             created_at: row.get::<i64, _>("created_at"),
@@ -126,7 +165,7 @@ pub async fn list_jobs(db: &SqlitePool) -> Result<Vec<Job>, anyhow::Error> {
 
 pub async fn list_jobs_including_archived(db: &SqlitePool) -> Result<Vec<Job>, anyhow::Error> {
     let rows = sqlx::query(
-        "SELECT id, name, agent_id, schedule, schedule_timezone, overlap_policy, spec_json, created_at, updated_at, archived_at FROM jobs ORDER BY created_at DESC",
+        "SELECT id, name, agent_id, schedule, schedule_timezone, schedule_jitter_seconds, max_success_interval_seconds, overlap_policy, spec_json, created_at, updated_at, archived_at FROM jobs ORDER BY created_at DESC",
     )
     .fetch_all(db)
     .await?;
@@ -145,6 +184,12 @@ pub async fn list_jobs_including_archived(db: &SqlitePool) -> Result<Vec<Job>, a
             agent_id: row.get::<Option<String>, _>("agent_id"),
             schedule: row.get::<Option<String>, _>("schedule"),
             schedule_timezone: row.get::<String, _>("schedule_timezone"),
+            schedule_jitter_seconds: u32::try_from(row.get::<i64, _>("schedule_jitter_seconds"))
+                .unwrap_or(0),
+            max_success_interval_seconds: u32::try_from(
+                row.get::<i64, _>("max_success_interval_seconds"),
+            )
+            .unwrap_or(0),
             overlap_policy,
             spec,
             created_at: row.get::<i64, _>("created_at"),
@@ -161,7 +206,7 @@ pub async fn list_jobs_for_agent(
     agent_id: &str,
 ) -> Result<Vec<Job>, anyhow::Error> {
     let rows = sqlx::query(
-        "SELECT id, name, agent_id, schedule, schedule_timezone, overlap_policy, spec_json, created_at, updated_at, archived_at FROM jobs WHERE agent_id = ? AND archived_at IS NULL ORDER BY created_at DESC",
+        "SELECT id, name, agent_id, schedule, schedule_timezone, schedule_jitter_seconds, max_success_interval_seconds, overlap_policy, spec_json, created_at, updated_at, archived_at FROM jobs WHERE agent_id = ? AND archived_at IS NULL ORDER BY created_at DESC",
     )
     .bind(agent_id)
     .fetch_all(db)
@@ -181,6 +226,12 @@ pub async fn list_jobs_for_agent(
             agent_id: row.get::<Option<String>, _>("agent_id"),
             schedule: row.get::<Option<String>, _>("schedule"),
             schedule_timezone: row.get::<String, _>("schedule_timezone"),
+            schedule_jitter_seconds: u32::try_from(row.get::<i64, _>("schedule_jitter_seconds"))
+                .unwrap_or(0),
+            max_success_interval_seconds: u32::try_from(
+                row.get::<i64, _>("max_success_interval_seconds"),
+            )
+            .unwrap_or(0),
             overlap_policy,
             spec,
             created_at: row.get::<i64, _>("created_at"),
@@ -230,6 +281,68 @@ pub async fn update_job(
     Ok(result.rows_affected() > 0)
 }
 
+/// Sets the random delay (0..=seconds) added to each scheduled trigger of the job.
+pub async fn set_job_schedule_jitter(
+    db: &SqlitePool,
+    job_id: &str,
+    seconds: u32,
+) -> Result<bool, anyhow::Error> {
+    let result = sqlx::query("UPDATE jobs SET schedule_jitter_seconds = ? WHERE id = ?")
+        .bind(i64::from(seconds))
+        .bind(job_id)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Records the job a bulk deploy/sync instantiated this job from.
+pub async fn set_job_template(
+    db: &SqlitePool,
+    job_id: &str,
+    template_job_id: Option<&str>,
+) -> Result<bool, anyhow::Error> {
+    let result = sqlx::query("UPDATE jobs SET template_job_id = ? WHERE id = ?")
+        .bind(template_job_id)
+        .bind(job_id)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Finds the (non-archived) job instantiated from `template_job_id` on `agent_id`.
+pub async fn find_template_instance(
+    db: &SqlitePool,
+    template_job_id: &str,
+    agent_id: &str,
+) -> Result<Option<Job>, anyhow::Error> {
+    let job_id = sqlx::query_scalar::<_, String>(
+        "SELECT id FROM jobs WHERE template_job_id = ? AND agent_id = ? AND archived_at IS NULL ORDER BY created_at ASC LIMIT 1",
+    )
+    .bind(template_job_id)
+    .bind(agent_id)
+    .fetch_optional(db)
+    .await?;
+
+    match job_id {
+        Some(job_id) => get_job(db, &job_id).await,
+        None => Ok(None),
+    }
+}
+
+/// Sets the expected max interval between successful runs (`0` disables freshness monitoring).
+pub async fn set_job_max_success_interval(
+    db: &SqlitePool,
+    job_id: &str,
+    seconds: u32,
+) -> Result<bool, anyhow::Error> {
+    let result = sqlx::query("UPDATE jobs SET max_success_interval_seconds = ? WHERE id = ?")
+        .bind(i64::from(seconds))
+        .bind(job_id)
+        .execute(db)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
 pub async fn archive_job(db: &SqlitePool, job_id: &str) -> Result<bool, anyhow::Error> {
     let now = OffsetDateTime::now_utc().unix_timestamp();
     let result = sqlx::query(
@@ -2,7 +2,10 @@ use tempfile::TempDir;
 
 use crate::db;
 
-use super::{OverlapPolicy, UpdateJobParams, create_job, get_job, list_jobs, update_job};
+use super::{
+    OverlapPolicy, UpdateJobParams, archive_job, create_job, find_template_instance, get_job,
+    list_jobs, set_job_schedule_jitter, set_job_template, update_job,
+};
 
 #[tokio::test]
 async fn jobs_crud_round_trip() {
@@ -60,3 +63,121 @@ async fn jobs_crud_round_trip() {
     assert!(fetched.schedule.is_none());
     assert_eq!(fetched.schedule_timezone, "Asia/Shanghai");
 }
+
+#[tokio::test]
+async fn schedule_jitter_survives_job_updates() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let spec = serde_json::json!({ "v": 1, "type": "filesystem" });
+    let job = create_job(
+        &pool,
+        "job1",
+        None,
+        Some("0 2 * * *"),
+        Some("UTC"),
+        OverlapPolicy::Queue,
+        spec.clone(),
+    )
+    .await
+    .expect("create");
+    assert_eq!(job.schedule_jitter_seconds, 0);
+
+    assert!(
+        set_job_schedule_jitter(&pool, &job.id, 300)
+            .await
+            .expect("set jitter")
+    );
+    assert!(
+        !set_job_schedule_jitter(&pool, "missing", 300)
+            .await
+            .expect("set jitter missing")
+    );
+
+    update_job(
+        &pool,
+        UpdateJobParams {
+            job_id: &job.id,
+            name: "job1",
+            agent_id: None,
+            schedule: Some("0 3 * * *"),
+            schedule_timezone: Some("UTC"),
+            overlap_policy: OverlapPolicy::Queue,
+            spec,
+        },
+    )
+    .await
+    .expect("update");
+
+    let fetched = get_job(&pool, &job.id)
+        .await
+        .expect("get")
+        .expect("present");
+    assert_eq!(fetched.schedule_jitter_seconds, 300);
+    assert_eq!(
+        list_jobs(&pool).await.expect("list")[0].schedule_jitter_seconds,
+        300
+    );
+}
+
+#[tokio::test]
+async fn template_instance_is_found_per_agent_until_archived() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    let spec = serde_json::json!({ "v": 1, "type": "filesystem" });
+    let template = create_job(
+        &pool,
+        "template",
+        None,
+        None,
+        Some("UTC"),
+        OverlapPolicy::Queue,
+        spec.clone(),
+    )
+    .await
+    .expect("create template");
+    let instance = create_job(
+        &pool,
+        "template (a1)",
+        Some("a1"),
+        None,
+        Some("UTC"),
+        OverlapPolicy::Queue,
+        spec,
+    )
+    .await
+    .expect("create instance");
+
+    assert!(
+        find_template_instance(&pool, &template.id, "a1")
+            .await
+            .expect("find")
+            .is_none()
+    );
+    assert!(
+        set_job_template(&pool, &instance.id, Some(&template.id))
+            .await
+            .expect("link")
+    );
+
+    let found = find_template_instance(&pool, &template.id, "a1")
+        .await
+        .expect("find")
+        .expect("linked");
+    assert_eq!(found.id, instance.id);
+    assert!(
+        find_template_instance(&pool, &template.id, "a2")
+            .await
+            .expect("find other agent")
+            .is_none()
+    );
+
+    archive_job(&pool, &instance.id).await.expect("archive");
+    assert!(
+        find_template_instance(&pool, &template.id, "a1")
+            .await
+            .expect("find archived")
+            .is_none()
+    );
+}
@@ -35,6 +35,9 @@ pub struct Job {
     pub agent_id: Option<String>,
     pub schedule: Option<String>,
     pub schedule_timezone: String,
+    pub schedule_jitter_seconds: u32,
+    /// Expected max interval between successful runs; `0` disables freshness monitoring.
+    pub max_success_interval_seconds: u32,
     pub overlap_policy: OverlapPolicy,
     pub spec: serde_json::Value,
     pub created_at: i64,
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::settings_repo;
+
+const KEY_SETTINGS: &str = "keypack_escrow";
+const KEY_STATUS: &str = "keypack_escrow_status";
+
+/// Secret kind/name (hub node) holding the password escrowed keypacks are encrypted with.
+pub const PASSWORD_SECRET_KIND: &str = "keypack_escrow";
+pub const PASSWORD_SECRET_NAME: &str = "default";
+
+/// Where escrowed keypacks are written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum KeypackEscrowTarget {
+    LocalDir {
+        base_dir: String,
+    },
+    Webdav {
+        base_url: String,
+        secret_name: String,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeypackEscrowSettings {
+    pub enabled: bool,
+    #[serde(default)]
+    pub target: Option<KeypackEscrowTarget>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeypackEscrowStatus {
+    /// Keyring fingerprint of the last keypack that reached the target.
+    #[serde(default)]
+    pub escrowed_fingerprint: Option<String>,
+    #[serde(default)]
+    pub last_success_at: Option<i64>,
+    #[serde(default)]
+    pub last_attempt_at: Option<i64>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Human-readable location of the last escrowed keypack.
+    #[serde(default)]
+    pub location: Option<String>,
+}
+
+pub async fn get_settings(db: &SqlitePool) -> Result<KeypackEscrowSettings, anyhow::Error> {
+    let Some(json) = settings_repo::get_value_json(db, KEY_SETTINGS).await? else {
+        return Ok(KeypackEscrowSettings::default());
+    };
+    Ok(serde_json::from_str(&json)?)
+}
+
+pub async fn upsert_settings(
+    db: &SqlitePool,
+    settings: &KeypackEscrowSettings,
+) -> Result<(), anyhow::Error> {
+    let json = serde_json::to_string(settings)?;
+    settings_repo::upsert_value_json(db, KEY_SETTINGS, &json).await
+}
+
+pub async fn get_status(db: &SqlitePool) -> Result<KeypackEscrowStatus, anyhow::Error> {
+    let Some(json) = settings_repo::get_value_json(db, KEY_STATUS).await? else {
+        return Ok(KeypackEscrowStatus::default());
+    };
+    Ok(serde_json::from_str(&json)?)
+}
+
+pub async fn upsert_status(
+    db: &SqlitePool,
+    status: &KeypackEscrowStatus,
+) -> Result<(), anyhow::Error> {
+    let json = serde_json::to_string(status)?;
+    settings_repo::upsert_value_json(db, KEY_STATUS, &json).await
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::db;
+
+    use super::{
+        KeypackEscrowSettings, KeypackEscrowTarget, get_settings, get_status, upsert_settings,
+    };
+
+    #[tokio::test]
+    async fn settings_default_to_disabled_and_round_trip() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+
+        let settings = get_settings(&pool).await.expect("get");
+        assert!(!settings.enabled);
+        assert!(settings.target.is_none());
+        assert!(
+            get_status(&pool)
+                .await
+                .expect("status")
+                .escrowed_fingerprint
+                .is_none()
+        );
+
+        let target = KeypackEscrowTarget::Webdav {
+            base_url: "https://dav.example/escrow/".to_string(),
+            secret_name: "offsite".to_string(),
+        };
+        upsert_settings(
+            &pool,
+            &KeypackEscrowSettings {
+                enabled: true,
+                target: Some(target.clone()),
+            },
+        )
+        .await
+        .expect("upsert");
+
+        let loaded = get_settings(&pool).await.expect("get");
+        assert!(loaded.enabled);
+        assert_eq!(loaded.target, Some(target));
+    }
+}
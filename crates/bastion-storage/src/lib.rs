@@ -1,19 +1,32 @@
+pub mod agent_connections_repo;
+pub mod agent_host_metrics_repo;
 pub mod agent_labels_repo;
 pub mod agent_tasks_repo;
 pub mod agents_repo;
 pub mod artifact_delete_repo;
+pub mod audit_repo;
 pub mod auth;
 pub mod bulk_operations_repo;
+pub mod config_bundle;
 pub mod db;
+pub mod download_links_repo;
 pub mod hub_runtime_config_repo;
 pub mod incomplete_cleanup_repo;
+pub mod job_access_repo;
 pub mod jobs_repo;
+pub mod keypack_escrow_repo;
 pub mod notification_destinations_repo;
+pub mod notification_digests_repo;
 pub mod notifications_repo;
 pub mod notifications_settings_repo;
 pub mod operations_repo;
+pub mod restore_approvals_repo;
 pub mod run_artifacts_repo;
+pub mod run_logs_repo;
+pub mod run_stats_repo;
 pub mod runs_repo;
 pub mod secrets;
 pub mod secrets_repo;
 pub mod settings_repo;
+pub mod stale_backups_repo;
+pub mod storage_usage_repo;
@@ -2,12 +2,8 @@ use sqlx::Row;
 use sqlx::SqlitePool;
 use time::OffsetDateTime;
 
-use crate::notifications_repo;
 use bastion_core::HUB_NODE_ID;
 
-pub const SECRET_KIND_WECOM_BOT: &str = "wecom_bot";
-pub const SECRET_KIND_SMTP: &str = "smtp";
-
 #[derive(Debug, Clone)]
 pub struct NotificationDestinationListItem {
     pub channel: String,
@@ -16,31 +12,12 @@ pub struct NotificationDestinationListItem {
     pub updated_at: i64,
 }
 
-pub fn secret_kind_for_channel(channel: &str) -> Option<&'static str> {
-    match channel {
-        notifications_repo::CHANNEL_WECOM_BOT => Some(SECRET_KIND_WECOM_BOT),
-        notifications_repo::CHANNEL_EMAIL => Some(SECRET_KIND_SMTP),
-        _ => None,
-    }
-}
-
-pub fn channel_for_secret_kind(kind: &str) -> Option<&'static str> {
-    match kind {
-        SECRET_KIND_WECOM_BOT => Some(notifications_repo::CHANNEL_WECOM_BOT),
-        SECRET_KIND_SMTP => Some(notifications_repo::CHANNEL_EMAIL),
-        _ => None,
-    }
-}
-
+/// Destinations are enabled unless explicitly disabled.
 pub async fn is_enabled(
     db: &SqlitePool,
-    channel: &str,
+    secret_kind: &str,
     secret_name: &str,
 ) -> Result<bool, anyhow::Error> {
-    let Some(secret_kind) = secret_kind_for_channel(channel) else {
-        return Ok(false);
-    };
-
     let row = sqlx::query(
         "SELECT enabled FROM notification_destinations WHERE node_id = ? AND secret_kind = ? AND secret_name = ? LIMIT 1",
     )
@@ -55,14 +32,10 @@ pub async fn is_enabled(
 
 pub async fn set_enabled(
     db: &SqlitePool,
-    channel: &str,
+    secret_kind: &str,
     secret_name: &str,
     enabled: bool,
 ) -> Result<(), anyhow::Error> {
-    let Some(secret_kind) = secret_kind_for_channel(channel) else {
-        anyhow::bail!("unsupported notification channel: {channel}");
-    };
-
     let now = OffsetDateTime::now_utc().unix_timestamp();
     sqlx::query(
         r#"
@@ -83,35 +56,57 @@ pub async fn set_enabled(
     Ok(())
 }
 
-pub async fn list_destinations(
-    db: &SqlitePool,
-) -> Result<Vec<NotificationDestinationListItem>, anyhow::Error> {
+/// Lists `(secret_kind, secret_name)` of every Hub destination that was explicitly disabled.
+pub async fn list_disabled(db: &SqlitePool) -> Result<Vec<(String, String)>, anyhow::Error> {
     let rows = sqlx::query(
-        r#"
-        SELECT 'wecom_bot' AS channel, s.name AS name, COALESCE(d.enabled, 1) AS enabled, s.updated_at AS updated_at
-          FROM secrets s
-          LEFT JOIN notification_destinations d ON d.secret_kind = s.kind AND d.node_id = s.node_id AND d.secret_name = s.name
-         WHERE s.node_id = 'hub' AND s.kind = 'wecom_bot'
-        UNION ALL
-        SELECT 'email' AS channel, s.name AS name, COALESCE(d.enabled, 1) AS enabled, s.updated_at AS updated_at
-          FROM secrets s
-          LEFT JOIN notification_destinations d ON d.secret_kind = s.kind AND d.node_id = s.node_id AND d.secret_name = s.name
-         WHERE s.node_id = 'hub' AND s.kind = 'smtp'
-         ORDER BY updated_at DESC
-        "#,
+        "SELECT secret_kind, secret_name FROM notification_destinations WHERE node_id = ? AND enabled = 0 ORDER BY secret_kind, secret_name",
     )
+    .bind(HUB_NODE_ID)
     .fetch_all(db)
     .await?;
 
-    let mut out = Vec::with_capacity(rows.len());
-    for row in rows {
-        out.push(NotificationDestinationListItem {
-            channel: row.get::<String, _>("channel"),
-            name: row.get::<String, _>("name"),
-            enabled: row.get::<i64, _>("enabled") != 0,
-            updated_at: row.get::<i64, _>("updated_at"),
-        });
+    Ok(rows
+        .into_iter()
+        .map(|r| {
+            (
+                r.get::<String, _>("secret_kind"),
+                r.get::<String, _>("secret_name"),
+            )
+        })
+        .collect())
+}
+
+/// Lists the Hub secrets of each `(channel, secret_kind)` pair as destinations of that channel,
+/// most recently updated first.
+pub async fn list_destinations(
+    db: &SqlitePool,
+    channels: &[(&str, &str)],
+) -> Result<Vec<NotificationDestinationListItem>, anyhow::Error> {
+    let mut out = Vec::new();
+    for (channel, secret_kind) in channels {
+        let rows = sqlx::query(
+            r#"
+            SELECT s.name AS name, COALESCE(d.enabled, 1) AS enabled, s.updated_at AS updated_at
+              FROM secrets s
+              LEFT JOIN notification_destinations d ON d.secret_kind = s.kind AND d.node_id = s.node_id AND d.secret_name = s.name
+             WHERE s.node_id = ? AND s.kind = ?
+            "#,
+        )
+        .bind(HUB_NODE_ID)
+        .bind(secret_kind)
+        .fetch_all(db)
+        .await?;
+
+        for row in rows {
+            out.push(NotificationDestinationListItem {
+                channel: channel.to_string(),
+                name: row.get::<String, _>("name"),
+                enabled: row.get::<i64, _>("enabled") != 0,
+                updated_at: row.get::<i64, _>("updated_at"),
+            });
+        }
     }
+    out.sort_by_key(|d| std::cmp::Reverse(d.updated_at));
     Ok(out)
 }
 
@@ -140,15 +135,16 @@ mod tests {
             .await
             .expect("upsert smtp");
 
-        assert!(is_enabled(&pool, CHANNEL_WECOM_BOT, "w1").await.unwrap());
-        assert!(is_enabled(&pool, CHANNEL_EMAIL, "s1").await.unwrap());
+        assert!(is_enabled(&pool, "wecom_bot", "w1").await.unwrap());
+        assert!(is_enabled(&pool, "smtp", "s1").await.unwrap());
 
-        set_enabled(&pool, CHANNEL_WECOM_BOT, "w1", false)
+        set_enabled(&pool, "wecom_bot", "w1", false)
             .await
             .expect("disable");
-        assert!(!is_enabled(&pool, CHANNEL_WECOM_BOT, "w1").await.unwrap());
+        assert!(!is_enabled(&pool, "wecom_bot", "w1").await.unwrap());
 
-        let list = list_destinations(&pool).await.unwrap();
+        let channels = [(CHANNEL_WECOM_BOT, "wecom_bot"), (CHANNEL_EMAIL, "smtp")];
+        let list = list_destinations(&pool, &channels).await.unwrap();
         assert_eq!(list.len(), 2);
         let w1 = list
             .iter()
@@ -0,0 +1,262 @@
+use sqlx::{Row, SqlitePool};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct DigestRow {
+    pub id: String,
+    pub period_start: i64,
+    pub period_end: i64,
+    pub channel: String,
+    pub secret_name: String,
+    pub attempts: i64,
+}
+
+/// A finished run included in a digest.
+#[derive(Debug, Clone)]
+pub struct DigestRunRow {
+    pub run_id: String,
+    pub job_id: String,
+    pub job_name: String,
+    pub status: String,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub error: Option<String>,
+    pub summary_json: Option<String>,
+}
+
+/// Whether any digest was already enqueued for the period ending at `period_end`.
+pub async fn period_enqueued(db: &SqlitePool, period_end: i64) -> Result<bool, anyhow::Error> {
+    let count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(1) FROM notification_digests WHERE period_end = ?",
+    )
+    .bind(period_end)
+    .fetch_one(db)
+    .await?;
+    Ok(count > 0)
+}
+
+pub async fn enqueue_for_period(
+    db: &SqlitePool,
+    period_start: i64,
+    period_end: i64,
+    channel: &str,
+    secret_names: &[String],
+    now: i64,
+) -> Result<i64, anyhow::Error> {
+    let mut inserted = 0_i64;
+    for name in secret_names {
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO notification_digests (id, period_start, period_end, channel, secret_name, status, attempts, next_attempt_at, created_at, updated_at) VALUES (?, ?, ?, ?, ?, 'queued', 0, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(period_start)
+        .bind(period_end)
+        .bind(channel)
+        .bind(name)
+        .bind(now)
+        .bind(now)
+        .bind(now)
+        .execute(db)
+        .await?;
+        inserted += result.rows_affected() as i64;
+    }
+    Ok(inserted)
+}
+
+pub async fn claim_next_due(db: &SqlitePool, now: i64) -> Result<Option<DigestRow>, anyhow::Error> {
+    let row = sqlx::query(
+        "UPDATE notification_digests SET status = 'sending', updated_at = ? WHERE id = (SELECT id FROM notification_digests WHERE status = 'queued' AND next_attempt_at <= ? ORDER BY next_attempt_at ASC LIMIT 1) RETURNING id, period_start, period_end, channel, secret_name, attempts",
+    )
+    .bind(now)
+    .bind(now)
+    .fetch_optional(db)
+    .await?;
+
+    Ok(row.map(|row| DigestRow {
+        id: row.get::<String, _>("id"),
+        period_start: row.get::<i64, _>("period_start"),
+        period_end: row.get::<i64, _>("period_end"),
+        channel: row.get::<String, _>("channel"),
+        secret_name: row.get::<String, _>("secret_name"),
+        attempts: row.get::<i64, _>("attempts"),
+    }))
+}
+
+/// Moves a claimed digest to a final status (`sent`, `failed` or `canceled`).
+pub async fn finish(
+    db: &SqlitePool,
+    id: &str,
+    status: &str,
+    attempts: i64,
+    last_error: Option<&str>,
+    now: i64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE notification_digests SET status = ?, attempts = ?, updated_at = ?, last_error = ? WHERE id = ? AND status = 'sending'",
+    )
+    .bind(status)
+    .bind(attempts)
+    .bind(now)
+    .bind(last_error)
+    .bind(id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn reschedule(
+    db: &SqlitePool,
+    id: &str,
+    attempts: i64,
+    next_attempt_at: i64,
+    last_error: &str,
+    now: i64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        "UPDATE notification_digests SET status = 'queued', attempts = ?, next_attempt_at = ?, updated_at = ?, last_error = ? WHERE id = ? AND status = 'sending'",
+    )
+    .bind(attempts)
+    .bind(next_attempt_at)
+    .bind(now)
+    .bind(last_error)
+    .bind(id)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Finished runs (any final status) whose `ended_at` falls in `[period_start, period_end)`,
+/// ordered by job name then end time.
+pub async fn list_period_runs(
+    db: &SqlitePool,
+    period_start: i64,
+    period_end: i64,
+) -> Result<Vec<DigestRunRow>, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT r.id, r.job_id, COALESCE(j.name, r.job_id) AS job_name, r.status,
+               r.started_at, r.ended_at, r.error, r.summary_json
+        FROM runs r
+        LEFT JOIN jobs j ON j.id = r.job_id
+        WHERE r.ended_at IS NOT NULL AND r.ended_at >= ? AND r.ended_at < ?
+          AND r.status IN ('success', 'failed', 'rejected', 'canceled')
+        ORDER BY job_name ASC, r.ended_at ASC, r.id ASC
+        "#,
+    )
+    .bind(period_start)
+    .bind(period_end)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| DigestRunRow {
+            run_id: row.get::<String, _>("id"),
+            job_id: row.get::<String, _>("job_id"),
+            job_name: row.get::<String, _>("job_name"),
+            status: row.get::<String, _>("status"),
+            started_at: row.get::<i64, _>("started_at"),
+            ended_at: row.get::<i64, _>("ended_at"),
+            error: row.get::<Option<String>, _>("error"),
+            summary_json: row.get::<Option<String>, _>("summary_json"),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::db;
+    use crate::jobs_repo::{self, OverlapPolicy};
+
+    use super::{
+        claim_next_due, enqueue_for_period, finish, list_period_runs, period_enqueued, reschedule,
+    };
+
+    #[tokio::test]
+    async fn digests_are_enqueued_once_per_period_and_destination() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let names = vec!["ops".to_string(), " ".to_string()];
+        assert!(!period_enqueued(&pool, 200).await.unwrap());
+        assert_eq!(
+            enqueue_for_period(&pool, 100, 200, "email", &names, 200)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            enqueue_for_period(&pool, 100, 200, "email", &names, 201)
+                .await
+                .unwrap(),
+            0
+        );
+        assert!(period_enqueued(&pool, 200).await.unwrap());
+
+        let claimed = claim_next_due(&pool, 200).await.unwrap().expect("due");
+        assert_eq!(claimed.secret_name, "ops");
+        assert_eq!((claimed.period_start, claimed.period_end), (100, 200));
+        assert!(claim_next_due(&pool, 200).await.unwrap().is_none());
+
+        reschedule(&pool, &claimed.id, 1, 260, "smtp down", 200)
+            .await
+            .unwrap();
+        assert!(claim_next_due(&pool, 259).await.unwrap().is_none());
+        let claimed = claim_next_due(&pool, 260).await.unwrap().expect("retry");
+        assert_eq!(claimed.attempts, 1);
+        finish(&pool, &claimed.id, "sent", 2, None, 260)
+            .await
+            .unwrap();
+        assert!(claim_next_due(&pool, 10_000).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn period_runs_include_only_finished_runs_in_range() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "nightly",
+            None,
+            None,
+            Some("UTC"),
+            OverlapPolicy::Queue,
+            serde_json::json!({"v": 1, "type": "filesystem"}),
+        )
+        .await
+        .unwrap();
+
+        for (id, status, ended_at) in [
+            ("before", "success", Some(99)),
+            ("ok", "success", Some(100)),
+            ("bad", "failed", Some(150)),
+            ("running", "running", None),
+            ("after", "success", Some(200)),
+        ] {
+            sqlx::query(
+                "INSERT INTO runs (id, job_id, status, started_at, ended_at) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(&job.id)
+            .bind(status)
+            .bind(90)
+            .bind(ended_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let runs = list_period_runs(&pool, 100, 200).await.unwrap();
+        let ids = runs.iter().map(|r| r.run_id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["ok", "bad"]);
+        assert_eq!(runs[0].job_name, "nightly");
+    }
+}
@@ -1,5 +1,8 @@
 pub const CHANNEL_WECOM_BOT: &str = "wecom_bot";
 pub const CHANNEL_EMAIL: &str = "email";
+pub const CHANNEL_SLACK: &str = "slack";
+pub const CHANNEL_DISCORD: &str = "discord";
+pub const CHANNEL_SMS: &str = "sms";
 
 pub const STATUS_QUEUED: &str = "queued";
 pub const STATUS_SENDING: &str = "sending";
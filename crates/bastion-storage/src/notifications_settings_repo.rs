@@ -22,6 +22,26 @@ pub struct NotificationsChannels {
     pub wecom_bot: NotificationsChannelSettings,
     #[serde(default)]
     pub email: NotificationsChannelSettings,
+    #[serde(default)]
+    pub slack: NotificationsChannelSettings,
+    #[serde(default)]
+    pub discord: NotificationsChannelSettings,
+    #[serde(default)]
+    pub sms: NotificationsChannelSettings,
+}
+
+impl NotificationsChannels {
+    /// Whether `channel` is switched on; channels without a setting here are always on.
+    pub fn is_enabled(&self, channel: &str) -> bool {
+        match channel {
+            "wecom_bot" => self.wecom_bot.enabled,
+            "email" => self.email.enabled,
+            "slack" => self.slack.enabled,
+            "discord" => self.discord.enabled,
+            "sms" => self.sms.enabled,
+            _ => true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,7 +59,7 @@ impl Default for NotificationsTemplates {
 > Run: {{run_id}}
 > Started: {{started_at}}
 > Ended: {{ended_at}}
-{{target_line_wecom}}{{consistency_line_wecom}}{{error_line_wecom}}"#
+{{anomaly_line_wecom}}{{target_line_wecom}}{{consistency_line_wecom}}{{delta_line_wecom}}{{error_line_wecom}}"#
                 .to_string(),
             email_subject: "Bastion {{status_text}} - {{job_name}}".to_string(),
             email_body: r#"Bastion backup
@@ -49,12 +69,55 @@ Run: {{run_id}}
 Status: {{status}}
 Started: {{started_at}}
 Ended: {{ended_at}}
-{{target_line_email}}{{consistency_line_email}}{{error_line_email}}"#
+{{anomaly_line_email}}{{target_line_email}}{{consistency_line_email}}{{delta_line_email}}{{error_line_email}}"#
                 .to_string(),
         }
     }
 }
 
+/// How finished runs are reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationsDigestMode {
+    /// One message per run, filtered by each job's notification rules.
+    #[default]
+    PerRun,
+    /// One message per day summarizing every run that finished in the previous 24 hours.
+    Daily,
+    /// One message per week summarizing every run that finished in the previous 7 days.
+    Weekly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationsDigestSettings {
+    #[serde(default)]
+    pub mode: NotificationsDigestMode,
+    /// Hour of day (0-23, hub timezone) the digest is sent.
+    #[serde(default = "default_digest_hour")]
+    pub hour: u8,
+    /// ISO weekday (1 = Monday .. 7 = Sunday) of the weekly digest.
+    #[serde(default = "default_digest_weekday")]
+    pub weekday: u8,
+}
+
+fn default_digest_hour() -> u8 {
+    8
+}
+
+fn default_digest_weekday() -> u8 {
+    1
+}
+
+impl Default for NotificationsDigestSettings {
+    fn default() -> Self {
+        Self {
+            mode: NotificationsDigestMode::PerRun,
+            hour: default_digest_hour(),
+            weekday: default_digest_weekday(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NotificationsSettings {
     pub enabled: bool,
@@ -62,6 +125,8 @@ pub struct NotificationsSettings {
     pub channels: NotificationsChannels,
     #[serde(default)]
     pub templates: NotificationsTemplates,
+    #[serde(default)]
+    pub digest: NotificationsDigestSettings,
 }
 
 impl Default for NotificationsSettings {
@@ -70,6 +135,7 @@ impl Default for NotificationsSettings {
             enabled: true,
             channels: NotificationsChannels::default(),
             templates: NotificationsTemplates::default(),
+            digest: NotificationsDigestSettings::default(),
         }
     }
 }
@@ -102,7 +168,7 @@ mod tests {
 
     use crate::db;
 
-    use super::{get_or_default, upsert};
+    use super::{NotificationsDigestMode, NotificationsSettings, get_or_default, upsert};
 
     #[tokio::test]
     async fn default_is_inserted_and_loadable() {
@@ -122,5 +188,17 @@ mod tests {
         let s3 = get_or_default(&pool).await.expect("load");
         assert!(!s3.enabled);
         assert!(!s3.channels.email.enabled);
+        assert_eq!(s3.digest.mode, NotificationsDigestMode::PerRun);
+    }
+
+    #[test]
+    fn settings_saved_before_digest_default_to_per_run() {
+        let parsed = serde_json::from_str::<NotificationsSettings>(
+            r#"{"enabled":true,"channels":{},"templates":{"wecom_markdown":"w","email_subject":"s","email_body":"b"}}"#,
+        )
+        .expect("parse");
+        assert_eq!(parsed.digest.mode, NotificationsDigestMode::PerRun);
+        assert_eq!(parsed.digest.hour, 8);
+        assert_eq!(parsed.digest.weekday, 1);
     }
 }
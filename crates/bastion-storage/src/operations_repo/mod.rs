@@ -3,7 +3,8 @@ mod types;
 
 pub use repo::{
     append_event, complete_operation, create_operation, get_operation, list_events,
-    list_operations_by_subject, request_operation_cancel, set_operation_progress,
+    list_events_after, list_operations_by_subject, request_operation_cancel,
+    set_operation_progress,
 };
 pub use types::{Operation, OperationEvent, OperationKind, OperationStatus};
 
@@ -189,11 +189,22 @@ pub async fn list_events(
     db: &SqlitePool,
     op_id: &str,
     limit: u32,
+) -> Result<Vec<OperationEvent>, anyhow::Error> {
+    list_events_after(db, op_id, 0, limit).await
+}
+
+/// Lists events with `seq > after_seq`, so pollers can tail an operation.
+pub async fn list_events_after(
+    db: &SqlitePool,
+    op_id: &str,
+    after_seq: i64,
+    limit: u32,
 ) -> Result<Vec<OperationEvent>, anyhow::Error> {
     let rows = sqlx::query(
-        "SELECT op_id, seq, ts, level, kind, message, fields_json FROM operation_events WHERE op_id = ? ORDER BY seq ASC LIMIT ?",
+        "SELECT op_id, seq, ts, level, kind, message, fields_json FROM operation_events WHERE op_id = ? AND seq > ? ORDER BY seq ASC LIMIT ?",
     )
     .bind(op_id)
+    .bind(after_seq)
     .bind(limit as i64)
     .fetch_all(db)
     .await?;
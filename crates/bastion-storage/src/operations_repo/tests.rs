@@ -4,8 +4,8 @@ use crate::db;
 
 use super::{
     OperationKind, OperationStatus, append_event, complete_operation, create_operation,
-    get_operation, list_events, list_operations_by_subject, request_operation_cancel,
-    set_operation_progress,
+    get_operation, list_events, list_events_after, list_operations_by_subject,
+    request_operation_cancel, set_operation_progress,
 };
 
 #[tokio::test]
@@ -45,6 +45,12 @@ async fn operations_and_events_round_trip() {
     assert_eq!(events.len(), 2);
     assert_eq!(events[0].seq, 1);
     assert_eq!(events[1].seq, 2);
+
+    let tail = list_events_after(&pool, &op.id, 1, 100)
+        .await
+        .expect("list after");
+    assert_eq!(tail.len(), 1);
+    assert_eq!(tail[0].seq, 2);
 }
 
 #[tokio::test]
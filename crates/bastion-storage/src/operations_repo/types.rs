@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 pub enum OperationKind {
     Restore,
     Verify,
+    /// Re-encrypts existing snapshots after a backup key rotation.
+    Reencrypt,
 }
 
 impl OperationKind {
@@ -12,6 +14,7 @@ impl OperationKind {
         match self {
             Self::Restore => "restore",
             Self::Verify => "verify",
+            Self::Reencrypt => "reencrypt",
         }
     }
 }
@@ -23,6 +26,7 @@ impl std::str::FromStr for OperationKind {
         match s {
             "restore" => Ok(Self::Restore),
             "verify" => Ok(Self::Verify),
+            "reencrypt" => Ok(Self::Reencrypt),
             _ => Err(anyhow::anyhow!("invalid operation kind")),
         }
     }
@@ -31,6 +35,8 @@ impl std::str::FromStr for OperationKind {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum OperationStatus {
+    /// Waiting for a second admin to approve it (restores only); nothing has run yet.
+    PendingApproval,
     Running,
     Success,
     Failed,
@@ -40,6 +46,7 @@ pub enum OperationStatus {
 impl OperationStatus {
     pub fn as_str(self) -> &'static str {
         match self {
+            Self::PendingApproval => "pending_approval",
             Self::Running => "running",
             Self::Success => "success",
             Self::Failed => "failed",
@@ -48,11 +55,11 @@ impl OperationStatus {
     }
 
     pub fn is_terminal(self) -> bool {
-        !matches!(self, Self::Running)
+        !matches!(self, Self::PendingApproval | Self::Running)
     }
 
     pub fn is_cancelable(self) -> bool {
-        matches!(self, Self::Running)
+        matches!(self, Self::PendingApproval | Self::Running)
     }
 }
 
@@ -61,6 +68,7 @@ impl std::str::FromStr for OperationStatus {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
+            "pending_approval" => Ok(Self::PendingApproval),
             "running" => Ok(Self::Running),
             "success" => Ok(Self::Success),
             "failed" => Ok(Self::Failed),
@@ -0,0 +1,273 @@
+//! Two-person rule for restores.
+//!
+//! When approval is required, a restore to a destination its job hasn't restored to before is
+//! created as a `pending_approval` operation and its request is kept here until a second admin
+//! approves or cancels it.
+
+use sqlx::{Row, SqlitePool};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::operations_repo::{self, Operation, OperationKind, OperationStatus};
+
+#[derive(Debug, Clone)]
+pub struct PendingRestore {
+    pub op_id: String,
+    pub run_id: String,
+    pub job_id: String,
+    pub destination_key: String,
+    /// The restore request as submitted, without any passphrase.
+    pub request: serde_json::Value,
+    pub requested_by_user_id: Option<i64>,
+    pub requested_at: i64,
+}
+
+pub async fn is_known_destination(
+    db: &SqlitePool,
+    job_id: &str,
+    destination_key: &str,
+) -> Result<bool, sqlx::Error> {
+    let found = sqlx::query_scalar::<_, i64>(
+        "SELECT 1 FROM restore_destinations WHERE job_id = ? AND destination_key = ? LIMIT 1",
+    )
+    .bind(job_id)
+    .bind(destination_key)
+    .fetch_optional(db)
+    .await?;
+    Ok(found.is_some())
+}
+
+pub async fn remember_destination(
+    db: &SqlitePool,
+    job_id: &str,
+    destination_key: &str,
+    now: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT OR IGNORE INTO restore_destinations (job_id, destination_key, first_used_at) VALUES (?, ?, ?)",
+    )
+    .bind(job_id)
+    .bind(destination_key)
+    .bind(now)
+    .execute(db)
+    .await?;
+    Ok(())
+}
+
+/// Creates a restore operation that waits for approval instead of running.
+pub async fn create_pending(
+    db: &SqlitePool,
+    run_id: &str,
+    job_id: &str,
+    destination_key: &str,
+    request: &serde_json::Value,
+    requested_by_user_id: i64,
+) -> Result<Operation, anyhow::Error> {
+    let id = Uuid::new_v4().to_string();
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+
+    let mut tx = db.begin().await?;
+    sqlx::query(
+        "INSERT INTO operations (id, kind, status, created_at, started_at, subject_kind, subject_id) VALUES (?, ?, ?, ?, ?, 'run', ?)",
+    )
+    .bind(&id)
+    .bind(OperationKind::Restore.as_str())
+    .bind(OperationStatus::PendingApproval.as_str())
+    .bind(now)
+    .bind(now)
+    .bind(run_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "INSERT INTO restore_approvals (op_id, run_id, job_id, destination_key, request_json, requested_by_user_id, requested_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(run_id)
+    .bind(job_id)
+    .bind(destination_key)
+    .bind(serde_json::to_string(request)?)
+    .bind(requested_by_user_id)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    operations_repo::get_operation(db, &id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("operation disappeared"))
+}
+
+/// The stored request of a restore that is still waiting for approval.
+pub async fn get_pending(
+    db: &SqlitePool,
+    op_id: &str,
+) -> Result<Option<PendingRestore>, anyhow::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT a.op_id, a.run_id, a.job_id, a.destination_key, a.request_json,
+               a.requested_by_user_id, a.requested_at
+        FROM restore_approvals a
+        JOIN operations o ON o.id = a.op_id
+        WHERE a.op_id = ? AND o.status = 'pending_approval'
+        LIMIT 1
+        "#,
+    )
+    .bind(op_id)
+    .fetch_optional(db)
+    .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    Ok(Some(PendingRestore {
+        op_id: row.get::<String, _>("op_id"),
+        run_id: row.get::<String, _>("run_id"),
+        job_id: row.get::<String, _>("job_id"),
+        destination_key: row.get::<String, _>("destination_key"),
+        request: serde_json::from_str(&row.get::<String, _>("request_json"))?,
+        requested_by_user_id: row.get::<Option<i64>, _>("requested_by_user_id"),
+        requested_at: row.get::<i64, _>("requested_at"),
+    }))
+}
+
+/// Moves a pending restore to `running`. Returns `false` if it was no longer pending, so only
+/// one approval wins.
+pub async fn approve(
+    db: &SqlitePool,
+    op_id: &str,
+    approved_by_user_id: i64,
+    now: i64,
+) -> Result<bool, sqlx::Error> {
+    let mut tx = db.begin().await?;
+    let result = sqlx::query(
+        "UPDATE operations SET status = 'running', started_at = ? WHERE id = ? AND status = 'pending_approval'",
+    )
+    .bind(now)
+    .bind(op_id)
+    .execute(&mut *tx)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+    sqlx::query(
+        "UPDATE restore_approvals SET approved_by_user_id = ?, approved_at = ? WHERE op_id = ?",
+    )
+    .bind(approved_by_user_id)
+    .bind(now)
+    .bind(op_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Cancels a restore that is still waiting for approval.
+pub async fn cancel_pending(
+    db: &SqlitePool,
+    op_id: &str,
+    canceled_by_user_id: i64,
+    reason: Option<&str>,
+    now: i64,
+) -> Result<bool, sqlx::Error> {
+    let reason = reason.map(str::trim).filter(|v| !v.is_empty());
+    let result = sqlx::query(
+        "UPDATE operations
+         SET status = 'canceled',
+             ended_at = ?,
+             cancel_requested_at = ?,
+             cancel_requested_by_user_id = ?,
+             cancel_reason = ?,
+             error = 'canceled'
+         WHERE id = ? AND status = 'pending_approval'",
+    )
+    .bind(now)
+    .bind(now)
+    .bind(canceled_by_user_id)
+    .bind(reason)
+    .bind(op_id)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::operations_repo::{self, OperationStatus};
+    use crate::{db, jobs_repo, runs_repo};
+
+    use super::{
+        approve, cancel_pending, create_pending, get_pending, is_known_destination,
+        remember_destination,
+    };
+
+    #[tokio::test]
+    async fn pending_restores_are_approved_or_canceled_once() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        let job = jobs_repo::create_job(
+            &pool,
+            "job1",
+            None,
+            None,
+            Some("UTC"),
+            jobs_repo::OverlapPolicy::Queue,
+            serde_json::json!({"v": 1, "type": "filesystem"}),
+        )
+        .await
+        .expect("job");
+        let run = runs_repo::create_run(
+            &pool,
+            &job.id,
+            runs_repo::RunStatus::Success,
+            1,
+            Some(2),
+            None,
+            None,
+        )
+        .await
+        .expect("run");
+
+        let key = "local_fs:hub:/restore";
+        assert!(!is_known_destination(&pool, &job.id, key).await.unwrap());
+        remember_destination(&pool, &job.id, key, 10).await.unwrap();
+        remember_destination(&pool, &job.id, key, 20).await.unwrap();
+        assert!(is_known_destination(&pool, &job.id, key).await.unwrap());
+
+        let request = serde_json::json!({"conflict_policy": "overwrite"});
+        let op = create_pending(&pool, &run.id, &job.id, key, &request, 1)
+            .await
+            .expect("pending");
+        assert_eq!(op.status, OperationStatus::PendingApproval);
+        let pending = get_pending(&pool, &op.id).await.unwrap().expect("pending");
+        assert_eq!(pending.request, request);
+        assert_eq!(pending.requested_by_user_id, Some(1));
+
+        assert!(approve(&pool, &op.id, 2, 30).await.unwrap());
+        assert!(!approve(&pool, &op.id, 2, 31).await.unwrap());
+        assert!(get_pending(&pool, &op.id).await.unwrap().is_none());
+        let op = operations_repo::get_operation(&pool, &op.id)
+            .await
+            .unwrap()
+            .expect("op");
+        assert_eq!(op.status, OperationStatus::Running);
+        assert_eq!(op.started_at, 30);
+
+        let other = create_pending(&pool, &run.id, &job.id, key, &request, 1)
+            .await
+            .expect("pending");
+        assert!(
+            cancel_pending(&pool, &other.id, 2, Some("no"), 40)
+                .await
+                .unwrap()
+        );
+        assert!(!approve(&pool, &other.id, 2, 41).await.unwrap());
+        let other = operations_repo::get_operation(&pool, &other.id)
+            .await
+            .unwrap()
+            .expect("op");
+        assert_eq!(other.status, OperationStatus::Canceled);
+        assert_eq!(other.cancel_reason.as_deref(), Some("no"));
+    }
+}
@@ -2,6 +2,7 @@ use serde::Serialize;
 use sqlx::{Row, SqlitePool};
 use time::OffsetDateTime;
 
+use crate::jobs_repo;
 use crate::runs_repo::{self, RunStatus};
 
 #[derive(Debug, Clone, Serialize)]
@@ -17,6 +18,10 @@ pub struct RunArtifact {
     pub ended_at: i64,
     pub pinned_at: Option<i64>,
     pub pinned_by_user_id: Option<i64>,
+    /// Deletion is refused until this time (unix seconds).
+    pub locked_until: Option<i64>,
+    /// When the stored data was deleted or found missing; the row itself is kept for history.
+    pub deleted_at: Option<i64>,
     pub source_files: Option<u64>,
     pub source_dirs: Option<u64>,
     pub source_bytes: Option<u64>,
@@ -31,6 +36,7 @@ pub struct RunArtifactRetentionItem {
     pub run_id: String,
     pub ended_at: i64,
     pub pinned_at: Option<i64>,
+    pub locked_until: Option<i64>,
     pub source_bytes: Option<u64>,
     pub transfer_bytes: Option<u64>,
 }
@@ -72,6 +78,8 @@ fn parse_row(row: &sqlx::sqlite::SqliteRow) -> Result<RunArtifact, anyhow::Error
         ended_at: row.get::<i64, _>("ended_at"),
         pinned_at: row.get::<Option<i64>, _>("pinned_at"),
         pinned_by_user_id: row.get::<Option<i64>, _>("pinned_by_user_id"),
+        locked_until: row.get::<Option<i64>, _>("locked_until"),
+        deleted_at: row.get::<Option<i64>, _>("deleted_at"),
         source_files: row
             .get::<Option<i64>, _>("source_files")
             .and_then(|v| u64::try_from(v).ok()),
@@ -99,7 +107,7 @@ pub async fn get_run_artifact(
         SELECT
           run_id, job_id, node_id, target_type, target_snapshot_json,
           artifact_format, status, started_at, ended_at,
-          pinned_at, pinned_by_user_id,
+          pinned_at, pinned_by_user_id, locked_until, deleted_at,
           source_files, source_dirs, source_bytes, transfer_bytes,
           last_error_kind, last_error, last_attempt_at
         FROM run_artifacts
@@ -129,7 +137,7 @@ pub async fn list_run_artifacts_for_job(
         SELECT
           run_id, job_id, node_id, target_type, target_snapshot_json,
           artifact_format, status, started_at, ended_at,
-          pinned_at, pinned_by_user_id,
+          pinned_at, pinned_by_user_id, locked_until, deleted_at,
           source_files, source_dirs, source_bytes, transfer_bytes,
           last_error_kind, last_error, last_attempt_at
         FROM run_artifacts
@@ -179,7 +187,7 @@ pub async fn list_run_artifacts_for_job_before(
         SELECT
           run_id, job_id, node_id, target_type, target_snapshot_json,
           artifact_format, status, started_at, ended_at,
-          pinned_at, pinned_by_user_id,
+          pinned_at, pinned_by_user_id, locked_until, deleted_at,
           source_files, source_dirs, source_bytes, transfer_bytes,
           last_error_kind, last_error, last_attempt_at
         FROM run_artifacts
@@ -224,6 +232,7 @@ pub async fn list_retention_items_for_job(
           run_id,
           ended_at,
           pinned_at,
+          locked_until,
           source_bytes,
           transfer_bytes
         FROM run_artifacts
@@ -244,6 +253,7 @@ pub async fn list_retention_items_for_job(
             run_id: row.get::<String, _>("run_id"),
             ended_at: row.get::<i64, _>("ended_at"),
             pinned_at: row.get::<Option<i64>, _>("pinned_at"),
+            locked_until: row.get::<Option<i64>, _>("locked_until"),
             source_bytes: row
                 .get::<Option<i64>, _>("source_bytes")
                 .and_then(|v| u64::try_from(v).ok()),
@@ -255,6 +265,92 @@ pub async fn list_retention_items_for_job(
     Ok(out)
 }
 
+/// Lists present, unpinned and unlocked snapshots (any job) that ended before `ended_before`,
+/// oldest first. Used by the Hub-wide artifact retention.
+pub async fn list_expired_artifacts(
+    db: &SqlitePool,
+    ended_before: i64,
+    now: i64,
+    limit: u64,
+) -> Result<Vec<RunArtifact>, anyhow::Error> {
+    let limit = limit.clamp(1, 50_000);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          run_id, job_id, node_id, target_type, target_snapshot_json,
+          artifact_format, status, started_at, ended_at,
+          pinned_at, pinned_by_user_id, locked_until, deleted_at,
+          source_files, source_dirs, source_bytes, transfer_bytes,
+          last_error_kind, last_error, last_attempt_at
+        FROM run_artifacts
+        WHERE status = 'present'
+          AND ended_at < ?
+          AND pinned_at IS NULL
+          AND (locked_until IS NULL OR locked_until <= ?)
+        ORDER BY ended_at ASC, run_id ASC
+        LIMIT ?
+        "#,
+    )
+    .bind(ended_before)
+    .bind(now)
+    .bind(limit as i64)
+    .fetch_all(db)
+    .await?;
+
+    rows.iter().map(parse_row).collect()
+}
+
+/// Run ids the DB knows for a job, with the snapshot status when the run has one.
+pub async fn list_known_runs_for_job(
+    db: &SqlitePool,
+    job_id: &str,
+) -> Result<Vec<(String, Option<String>)>, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT r.id AS run_id, a.status AS artifact_status
+        FROM runs r
+        LEFT JOIN run_artifacts a ON a.run_id = r.id
+        WHERE r.job_id = ?
+        "#,
+    )
+    .bind(job_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| {
+            (
+                row.get::<String, _>("run_id"),
+                row.get::<Option<String>, _>("artifact_status"),
+            )
+        })
+        .collect())
+}
+
+/// Distinct `(job_id, target_snapshot)` pairs recorded for snapshots, i.e. every place a job has
+/// stored data, including targets the job no longer points at.
+pub async fn list_distinct_targets(
+    db: &SqlitePool,
+) -> Result<Vec<(String, serde_json::Value)>, anyhow::Error> {
+    let rows = sqlx::query(
+        "SELECT DISTINCT job_id, target_snapshot_json FROM run_artifacts ORDER BY job_id",
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut out = Vec::with_capacity(rows.len());
+    for row in rows {
+        let json = row.get::<String, _>("target_snapshot_json");
+        let Ok(snapshot) = serde_json::from_str::<serde_json::Value>(&json) else {
+            continue;
+        };
+        out.push((row.get::<String, _>("job_id"), snapshot));
+    }
+    Ok(out)
+}
+
 pub async fn mark_run_artifact_deleting(
     db: &SqlitePool,
     run_id: &str,
@@ -276,9 +372,10 @@ pub async fn mark_run_artifact_deleted(
     now: i64,
 ) -> Result<(), anyhow::Error> {
     sqlx::query(
-        "UPDATE run_artifacts SET status = 'deleted', updated_at = ?, last_error_kind = NULL, last_error = NULL, last_attempt_at = NULL WHERE run_id = ?",
+        "UPDATE run_artifacts SET status = 'deleted', updated_at = ?, deleted_at = COALESCE(deleted_at, ?), last_error_kind = NULL, last_error = NULL, last_attempt_at = NULL WHERE run_id = ?",
     )
     .bind(now)
+    .bind(now)
     .bind(run_id)
     .execute(db)
     .await?;
@@ -312,9 +409,10 @@ pub async fn mark_run_artifact_missing(
     now: i64,
 ) -> Result<(), anyhow::Error> {
     sqlx::query(
-        "UPDATE run_artifacts SET status = 'missing', updated_at = ?, last_error_kind = NULL, last_error = NULL, last_attempt_at = NULL WHERE run_id = ?",
+        "UPDATE run_artifacts SET status = 'missing', updated_at = ?, deleted_at = COALESCE(deleted_at, ?), last_error_kind = NULL, last_error = NULL, last_attempt_at = NULL WHERE run_id = ?",
     )
     .bind(now)
+    .bind(now)
     .bind(run_id)
     .execute(db)
     .await?;
@@ -488,17 +586,23 @@ pub async fn upsert_run_artifact_from_successful_run(
     let (source_files, source_dirs, source_bytes, transfer_bytes) =
         extract_metrics(run.summary.as_ref(), run.progress.as_ref());
 
+    let locked_until = jobs_repo::get_job(db, &run.job_id)
+        .await?
+        .and_then(|job| retention_lock_days(&job.spec))
+        .map(|days| ended_at.saturating_add(i64::from(days).saturating_mul(24 * 60 * 60)));
+
     let now = OffsetDateTime::now_utc().unix_timestamp();
     let snapshot_json = serde_json::to_string(&snapshot)?;
 
+    // A lock, once set, is never shortened by re-indexing.
     sqlx::query(
         r#"
         INSERT INTO run_artifacts (
           run_id, job_id, node_id, target_type, target_snapshot_json,
           artifact_format, status, started_at, ended_at,
           source_files, source_dirs, source_bytes, transfer_bytes,
-          created_at, updated_at
-        ) VALUES (?, ?, ?, ?, ?, ?, 'present', ?, ?, ?, ?, ?, ?, ?, ?)
+          locked_until, created_at, updated_at
+        ) VALUES (?, ?, ?, ?, ?, ?, 'present', ?, ?, ?, ?, ?, ?, ?, ?, ?)
         ON CONFLICT(run_id) DO UPDATE SET
           job_id = excluded.job_id,
           node_id = excluded.node_id,
@@ -511,6 +615,10 @@ pub async fn upsert_run_artifact_from_successful_run(
           source_dirs = excluded.source_dirs,
           source_bytes = excluded.source_bytes,
           transfer_bytes = excluded.transfer_bytes,
+          locked_until = MAX(
+            COALESCE(run_artifacts.locked_until, excluded.locked_until),
+            COALESCE(excluded.locked_until, run_artifacts.locked_until)
+          ),
           updated_at = excluded.updated_at
         "#,
     )
@@ -526,6 +634,7 @@ pub async fn upsert_run_artifact_from_successful_run(
     .bind(source_dirs.map(|v| v as i64))
     .bind(source_bytes.map(|v| v as i64))
     .bind(transfer_bytes.map(|v| v as i64))
+    .bind(locked_until)
     .bind(now)
     .bind(now)
     .execute(db)
@@ -534,6 +643,14 @@ pub async fn upsert_run_artifact_from_successful_run(
     Ok(true)
 }
 
+fn retention_lock_days(spec: &serde_json::Value) -> Option<u32> {
+    spec.get("retention")
+        .and_then(|r| r.get("lock_days"))
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u32::try_from(v).ok())
+        .filter(|v| *v > 0)
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::TempDir;
@@ -545,8 +662,9 @@ mod tests {
     use crate::runs_repo;
 
     use super::{
-        get_run_artifact, list_run_artifacts_for_job, mark_run_artifact_deleted, pin_run_artifact,
-        unpin_run_artifact, upsert_run_artifact_from_successful_run,
+        get_run_artifact, list_expired_artifacts, list_run_artifacts_for_job,
+        mark_run_artifact_deleted, pin_run_artifact, unpin_run_artifact,
+        upsert_run_artifact_from_successful_run,
     };
 
     #[tokio::test]
@@ -746,10 +864,22 @@ mod tests {
         assert!(got.pinned_at.is_some());
         assert_eq!(got.pinned_by_user_id, Some(123));
 
+        // Pinned snapshots are never picked up by the Hub-wide artifact retention.
+        let expired = list_expired_artifacts(&pool, now + 60, now, 10)
+            .await
+            .unwrap();
+        assert!(expired.is_empty());
+
         assert!(unpin_run_artifact(&pool, &run.id, now + 1).await.unwrap());
         let got2 = get_run_artifact(&pool, &run.id).await.unwrap().unwrap();
         assert!(got2.pinned_at.is_none());
         assert!(got2.pinned_by_user_id.is_none());
+
+        let expired = list_expired_artifacts(&pool, now + 60, now, 10)
+            .await
+            .unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].run_id, run.id);
     }
 
     #[tokio::test]
@@ -845,6 +975,7 @@ mod tests {
 
         let got2 = get_run_artifact(&pool, &run.id).await.unwrap().unwrap();
         assert_eq!(got2.status, "deleted");
+        assert_eq!(got2.deleted_at, Some(now));
     }
 
     #[tokio::test]
@@ -1008,4 +1139,58 @@ mod tests {
         assert_eq!(got.source_bytes, Some(123));
         assert_eq!(got.transfer_bytes, Some(456));
     }
+
+    #[tokio::test]
+    async fn upsert_sets_retention_lock_from_job_spec() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "job",
+            None,
+            None,
+            Some("UTC"),
+            OverlapPolicy::Queue,
+            serde_json::json!({
+                "v": 1,
+                "type": "filesystem",
+                "retention": { "lock_days": 7 },
+                "source": { "root": "/" },
+                "target": { "type": "local_dir", "base_dir": "/tmp" }
+            }),
+        )
+        .await
+        .unwrap();
+        let run = runs_repo::create_run(
+            &pool,
+            &job.id,
+            runs_repo::RunStatus::Queued,
+            1,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        runs_repo::set_run_target_snapshot(
+            &pool,
+            &run.id,
+            serde_json::json!({
+                "node_id": "hub",
+                "target": { "type": "local_dir", "base_dir": "/tmp" }
+            }),
+        )
+        .await
+        .unwrap();
+        runs_repo::complete_run(&pool, &run.id, runs_repo::RunStatus::Success, None, None)
+            .await
+            .unwrap();
+
+        upsert_run_artifact_from_successful_run(&pool, &run.id)
+            .await
+            .unwrap();
+        let got = get_run_artifact(&pool, &run.id).await.unwrap().unwrap();
+        assert_eq!(got.locked_until, Some(got.ended_at + 7 * 24 * 60 * 60));
+    }
 }
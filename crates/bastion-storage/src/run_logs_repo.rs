@@ -0,0 +1,125 @@
+use sqlx::{Row, SqlitePool};
+
+/// Upper bound for a run's stored raw log; later chunks are dropped.
+pub const MAX_RUN_LOG_BYTES: u64 = 16 * 1024 * 1024;
+
+const TRUNCATED_MARKER: &[u8] = b"\n[bastion] log truncated: size limit reached\n";
+
+/// Appends a chunk to the run's raw log. Returns the number of bytes stored, which is less than
+/// `data.len()` once the log reaches `max_bytes`.
+pub async fn append_run_log_chunk(
+    db: &SqlitePool,
+    run_id: &str,
+    data: &[u8],
+    max_bytes: u64,
+    now: i64,
+) -> Result<u64, anyhow::Error> {
+    if data.is_empty() {
+        return Ok(0);
+    }
+
+    let mut tx = db.begin().await?;
+    let row = sqlx::query(
+        "SELECT COALESCE(SUM(length(data)), 0) AS size, COALESCE(MAX(seq), 0) AS last_seq FROM run_log_chunks WHERE run_id = ?",
+    )
+    .bind(run_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let size = row.get::<i64, _>("size").max(0) as u64;
+    let last_seq = row.get::<i64, _>("last_seq");
+
+    if size >= max_bytes {
+        return Ok(0);
+    }
+    let room = (max_bytes - size) as usize;
+    let stored = data.len().min(room);
+    let mut chunk = data[..stored].to_vec();
+    if stored < data.len() {
+        chunk.extend_from_slice(TRUNCATED_MARKER);
+    }
+
+    sqlx::query("INSERT INTO run_log_chunks (run_id, seq, ts, data) VALUES (?, ?, ?, ?)")
+        .bind(run_id)
+        .bind(last_seq + 1)
+        .bind(now)
+        .bind(chunk)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(stored as u64)
+}
+
+/// Returns the run's raw log, or `None` when nothing was recorded.
+pub async fn get_run_log(db: &SqlitePool, run_id: &str) -> Result<Option<Vec<u8>>, anyhow::Error> {
+    let rows = sqlx::query("SELECT data FROM run_log_chunks WHERE run_id = ? ORDER BY seq ASC")
+        .bind(run_id)
+        .fetch_all(db)
+        .await?;
+    if rows.is_empty() {
+        return Ok(None);
+    }
+
+    let mut out = Vec::new();
+    for row in rows {
+        out.extend_from_slice(&row.get::<Vec<u8>, _>("data"));
+    }
+    Ok(Some(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::db;
+    use crate::runs_repo::{self, RunStatus};
+
+    use super::{append_run_log_chunk, get_run_log};
+
+    #[tokio::test]
+    async fn chunks_concatenate_and_stop_at_the_size_limit() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+
+        sqlx::query(
+            "INSERT INTO jobs (id, name, schedule, overlap_policy, spec_json, created_at, updated_at) VALUES ('job1', 'job1', NULL, 'queue', '{}', 0, 0)",
+        )
+        .execute(&pool)
+        .await
+        .expect("insert job");
+        let run = runs_repo::create_run(&pool, "job1", RunStatus::Running, 1, None, None, None)
+            .await
+            .expect("create run");
+
+        assert!(get_run_log(&pool, &run.id).await.expect("get").is_none());
+
+        let stored = append_run_log_chunk(&pool, &run.id, b"line 1\n", 16, 10)
+            .await
+            .expect("append");
+        assert_eq!(stored, 7);
+        let stored = append_run_log_chunk(&pool, &run.id, b"line 2\nline 3\n", 16, 11)
+            .await
+            .expect("append");
+        assert_eq!(stored, 9);
+        let stored = append_run_log_chunk(&pool, &run.id, b"line 4\n", 16, 12)
+            .await
+            .expect("append");
+        assert_eq!(stored, 0);
+
+        let log = get_run_log(&pool, &run.id)
+            .await
+            .expect("get")
+            .expect("log");
+        let log = String::from_utf8(log).expect("utf8");
+        assert!(log.starts_with("line 1\nline 2\nli\n"));
+        assert!(log.contains("log truncated"));
+        assert!(!log.contains("line 4"));
+
+        sqlx::query("DELETE FROM runs WHERE id = ?")
+            .bind(&run.id)
+            .execute(&pool)
+            .await
+            .expect("delete run");
+        assert!(get_run_log(&pool, &run.id).await.expect("get").is_none());
+    }
+}
@@ -0,0 +1,397 @@
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+
+/// Days of run history behind the success rate and average duration.
+pub const WINDOW_DAYS: i64 = 30;
+/// Weeks of upload volume kept in the weekly rollup (the current week included).
+pub const WEEKLY_WINDOW_WEEKS: i64 = 12;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRunStatsRow {
+    pub job_id: String,
+    pub job_name: Option<String>,
+    pub window_days: i64,
+    pub runs_total: u64,
+    pub runs_success: u64,
+    pub runs_failed: u64,
+    pub duration_total_secs: u64,
+    /// Finished runs (`success` / `failed`) that contributed to `duration_total_secs`.
+    pub duration_runs: u64,
+    pub last_ended_at: Option<i64>,
+    pub refreshed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyBytesRow {
+    pub job_id: String,
+    /// YYYY-MM-DD of the Monday (UTC) starting the week.
+    pub week_start: String,
+    pub runs: u64,
+    pub source_bytes: u64,
+    pub transfer_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorCodeRow {
+    pub job_id: String,
+    pub error_code: String,
+    pub runs: u64,
+}
+
+/// Rebuilds the run statistics rollups as of `now`.
+///
+/// Returns the number of per-job rows written. Upload volume is taken from snapshot records
+/// regardless of their current status, so later retention deletes don't rewrite history.
+pub async fn refresh_run_stats(db: &SqlitePool, now: i64) -> Result<u64, anyhow::Error> {
+    let window_start = now.saturating_sub(WINDOW_DAYS * 24 * 60 * 60);
+    let weekly_start = now.saturating_sub(WEEKLY_WINDOW_WEEKS * 7 * 24 * 60 * 60);
+
+    let mut tx = db.begin().await?;
+
+    sqlx::query("DELETE FROM run_stats")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM run_stats_weekly")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM run_stats_errors")
+        .execute(&mut *tx)
+        .await?;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO run_stats (
+          job_id, window_days, runs_total, runs_success, runs_failed,
+          duration_total_secs, duration_runs, last_ended_at, refreshed_at
+        )
+        SELECT
+          job_id,
+          ?,
+          COUNT(1),
+          COALESCE(SUM(CASE WHEN status = 'success' THEN 1 ELSE 0 END), 0),
+          COALESCE(SUM(CASE WHEN status = 'failed' THEN 1 ELSE 0 END), 0),
+          COALESCE(SUM(
+            CASE WHEN status IN ('success', 'failed') THEN MAX(ended_at - started_at, 0) ELSE 0 END
+          ), 0),
+          COALESCE(SUM(CASE WHEN status IN ('success', 'failed') THEN 1 ELSE 0 END), 0),
+          MAX(ended_at),
+          ?
+        FROM runs
+        WHERE ended_at IS NOT NULL AND ended_at >= ?
+        GROUP BY job_id
+        "#,
+    )
+    .bind(WINDOW_DAYS)
+    .bind(now)
+    .bind(window_start)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO run_stats_weekly (
+          job_id, week_start, runs, source_bytes, transfer_bytes, refreshed_at
+        )
+        SELECT
+          job_id,
+          date(ended_at, 'unixepoch', 'weekday 0', '-6 days') AS week_start,
+          COUNT(1),
+          COALESCE(SUM(COALESCE(source_bytes, 0)), 0),
+          COALESCE(SUM(COALESCE(transfer_bytes, 0)), 0),
+          ?
+        FROM run_artifacts
+        WHERE ended_at >= ?
+        GROUP BY job_id, week_start
+        "#,
+    )
+    .bind(now)
+    .bind(weekly_start)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO run_stats_errors (job_id, error_code, runs, refreshed_at)
+        SELECT job_id, COALESCE(NULLIF(TRIM(error), ''), 'unknown') AS error_code, COUNT(1), ?
+        FROM runs
+        WHERE status = 'failed' AND ended_at IS NOT NULL AND ended_at >= ?
+        GROUP BY job_id, error_code
+        "#,
+    )
+    .bind(now)
+    .bind(window_start)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn list_job_run_stats(db: &SqlitePool) -> Result<Vec<JobRunStatsRow>, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          s.job_id, j.name AS job_name, s.window_days, s.runs_total, s.runs_success,
+          s.runs_failed, s.duration_total_secs, s.duration_runs, s.last_ended_at, s.refreshed_at
+        FROM run_stats s
+        LEFT JOIN jobs j ON j.id = s.job_id
+        ORDER BY s.runs_failed DESC, s.runs_total DESC, s.job_id ASC
+        "#,
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut out = Vec::with_capacity(rows.len());
+    for row in rows {
+        out.push(JobRunStatsRow {
+            job_id: row.get::<String, _>("job_id"),
+            job_name: row.get::<Option<String>, _>("job_name"),
+            window_days: row.get::<i64, _>("window_days"),
+            runs_total: row.get::<i64, _>("runs_total").max(0) as u64,
+            runs_success: row.get::<i64, _>("runs_success").max(0) as u64,
+            runs_failed: row.get::<i64, _>("runs_failed").max(0) as u64,
+            duration_total_secs: row.get::<i64, _>("duration_total_secs").max(0) as u64,
+            duration_runs: row.get::<i64, _>("duration_runs").max(0) as u64,
+            last_ended_at: row.get::<Option<i64>, _>("last_ended_at"),
+            refreshed_at: row.get::<i64, _>("refreshed_at"),
+        });
+    }
+    Ok(out)
+}
+
+/// Weekly upload volume, oldest week first; `job_id = None` lists every job.
+pub async fn list_weekly_bytes(
+    db: &SqlitePool,
+    job_id: Option<&str>,
+) -> Result<Vec<WeeklyBytesRow>, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT job_id, week_start, runs, source_bytes, transfer_bytes
+        FROM run_stats_weekly
+        WHERE ? IS NULL OR job_id = ?
+        ORDER BY week_start ASC, job_id ASC
+        "#,
+    )
+    .bind(job_id)
+    .bind(job_id)
+    .fetch_all(db)
+    .await?;
+
+    let mut out = Vec::with_capacity(rows.len());
+    for row in rows {
+        out.push(WeeklyBytesRow {
+            job_id: row.get::<String, _>("job_id"),
+            week_start: row.get::<String, _>("week_start"),
+            runs: row.get::<i64, _>("runs").max(0) as u64,
+            source_bytes: row.get::<i64, _>("source_bytes").max(0) as u64,
+            transfer_bytes: row.get::<i64, _>("transfer_bytes").max(0) as u64,
+        });
+    }
+    Ok(out)
+}
+
+/// Failed runs per error code over the stats window; `job_id = None` lists every job.
+pub async fn list_error_codes(
+    db: &SqlitePool,
+    job_id: Option<&str>,
+) -> Result<Vec<ErrorCodeRow>, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT job_id, error_code, runs
+        FROM run_stats_errors
+        WHERE ? IS NULL OR job_id = ?
+        ORDER BY runs DESC, error_code ASC, job_id ASC
+        "#,
+    )
+    .bind(job_id)
+    .bind(job_id)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ErrorCodeRow {
+            job_id: row.get::<String, _>("job_id"),
+            error_code: row.get::<String, _>("error_code"),
+            runs: row.get::<i64, _>("runs").max(0) as u64,
+        })
+        .collect())
+}
+
+/// When the rollups were last rebuilt (`None` until the first refresh that found any runs).
+pub async fn last_refreshed_at(db: &SqlitePool) -> Result<Option<i64>, anyhow::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT MAX(refreshed_at) AS refreshed_at FROM (
+          SELECT refreshed_at FROM run_stats
+          UNION ALL
+          SELECT refreshed_at FROM run_stats_weekly
+        )
+        "#,
+    )
+    .fetch_one(db)
+    .await?;
+    Ok(row.get::<Option<i64>, _>("refreshed_at"))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::db;
+    use crate::jobs_repo::{self, OverlapPolicy};
+
+    use super::{
+        last_refreshed_at, list_error_codes, list_job_run_stats, list_weekly_bytes,
+        refresh_run_stats,
+    };
+
+    async fn insert_run(
+        pool: &sqlx::SqlitePool,
+        run_id: &str,
+        job_id: &str,
+        status: &str,
+        started_at: i64,
+        ended_at: i64,
+    ) {
+        sqlx::query(
+            "INSERT INTO runs (id, job_id, status, started_at, ended_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(run_id)
+        .bind(job_id)
+        .bind(status)
+        .bind(started_at)
+        .bind(ended_at)
+        .execute(pool)
+        .await
+        .expect("insert run");
+    }
+
+    async fn insert_artifact(
+        pool: &sqlx::SqlitePool,
+        run_id: &str,
+        job_id: &str,
+        ended_at: i64,
+        transfer_bytes: i64,
+    ) {
+        sqlx::query(
+            r#"
+            INSERT INTO run_artifacts (
+              run_id, job_id, node_id, target_type, target_snapshot_json,
+              artifact_format, status, started_at, ended_at,
+              source_bytes, transfer_bytes, created_at, updated_at
+            ) VALUES (?, ?, 'hub', 'local_dir', '{}', 'archive_v1', 'present', ?, ?, ?, ?, 1, 1)
+            "#,
+        )
+        .bind(run_id)
+        .bind(job_id)
+        .bind(ended_at)
+        .bind(ended_at)
+        .bind(transfer_bytes * 2)
+        .bind(transfer_bytes)
+        .execute(pool)
+        .await
+        .expect("insert artifact");
+    }
+
+    #[tokio::test]
+    async fn refresh_rolls_up_success_rate_duration_and_weekly_bytes() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "job",
+            None,
+            None,
+            Some("UTC"),
+            OverlapPolicy::Queue,
+            serde_json::json!({"v": 1, "type": "filesystem"}),
+        )
+        .await
+        .unwrap();
+
+        // 2024-01-31 00:00:00 UTC (Wednesday).
+        let now = 1_706_659_200;
+        let day = 24 * 60 * 60;
+        insert_run(&pool, "r1", &job.id, "success", now - 100, now - 40).await;
+        insert_run(&pool, "r2", &job.id, "success", now - day - 20, now - day).await;
+        insert_run(
+            &pool,
+            "r3",
+            &job.id,
+            "failed",
+            now - 2 * day - 10,
+            now - 2 * day,
+        )
+        .await;
+        insert_run(&pool, "r4", &job.id, "canceled", now - 300, now - 200).await;
+        // Outside the 30-day window.
+        insert_run(
+            &pool,
+            "r5",
+            &job.id,
+            "failed",
+            now - 40 * day,
+            now - 40 * day,
+        )
+        .await;
+
+        insert_artifact(&pool, "r1", &job.id, now - 40, 100).await;
+        insert_artifact(&pool, "r2", &job.id, now - day, 50).await;
+        // Previous week (Sunday 2024-01-28).
+        insert_run(
+            &pool,
+            "r6",
+            &job.id,
+            "success",
+            now - 3 * day,
+            now - 3 * day,
+        )
+        .await;
+        insert_artifact(&pool, "r6", &job.id, now - 3 * day, 7).await;
+
+        assert_eq!(last_refreshed_at(&pool).await.unwrap(), None);
+        assert_eq!(refresh_run_stats(&pool, now).await.unwrap(), 1);
+        assert_eq!(last_refreshed_at(&pool).await.unwrap(), Some(now));
+
+        let rows = list_job_run_stats(&pool).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].job_name.as_deref(), Some("job"));
+        assert_eq!(rows[0].runs_total, 5);
+        assert_eq!(rows[0].runs_success, 3);
+        assert_eq!(rows[0].runs_failed, 1);
+        assert_eq!(rows[0].duration_runs, 4);
+        assert_eq!(rows[0].duration_total_secs, 60 + 20 + 10);
+
+        let weeks = list_weekly_bytes(&pool, Some(&job.id)).await.unwrap();
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].week_start, "2024-01-22");
+        assert_eq!(weeks[0].transfer_bytes, 7);
+        assert_eq!(weeks[1].week_start, "2024-01-29");
+        assert_eq!(weeks[1].runs, 2);
+        assert_eq!(weeks[1].transfer_bytes, 150);
+        assert_eq!(weeks[1].source_bytes, 300);
+        assert!(
+            list_weekly_bytes(&pool, Some("other"))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        sqlx::query("UPDATE runs SET error = 'network_target' WHERE id IN ('r3', 'r5')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        refresh_run_stats(&pool, now).await.unwrap();
+        let errors = list_error_codes(&pool, None).await.unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error_code, "network_target");
+        // Only failures inside the window count.
+        assert_eq!(errors[0].runs, 1);
+
+        // Refresh replaces previous rows instead of accumulating.
+        assert_eq!(refresh_run_stats(&pool, now).await.unwrap(), 1);
+        assert_eq!(list_job_run_stats(&pool).await.unwrap()[0].runs_total, 5);
+    }
+}
@@ -1,7 +1,7 @@
 use sqlx::{QueryBuilder, Row, SqlitePool};
 use time::OffsetDateTime;
 
-use super::RunEvent;
+use super::{RunEvent, RunEventSearchHit, RunStatus};
 
 pub async fn append_run_event(
     db: &SqlitePool,
@@ -179,3 +179,69 @@ pub async fn list_latest_run_events_by_kind(
 
     Ok(events)
 }
+
+/// Turns free text into an FTS5 query that matches events containing every word.
+///
+/// Words are quoted so user input cannot inject FTS5 operators or column filters.
+pub fn run_events_fts_query(text: &str) -> Option<String> {
+    let terms = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Full-text search over the events of all runs of a job, newest runs first.
+pub async fn search_job_run_events(
+    db: &SqlitePool,
+    job_id: &str,
+    text: &str,
+    limit: u32,
+) -> Result<Vec<RunEventSearchHit>, anyhow::Error> {
+    let Some(fts_query) = run_events_fts_query(text) else {
+        return Ok(Vec::new());
+    };
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          e.run_id AS run_id,
+          r.status AS run_status,
+          r.started_at AS run_started_at,
+          e.seq AS seq,
+          e.ts AS ts,
+          e.level AS level,
+          e.kind AS kind,
+          e.message AS message,
+          snippet(run_events_fts, -1, '[', ']', '...', 16) AS excerpt
+        FROM run_events_fts
+        JOIN run_events e ON e.rowid = run_events_fts.rowid
+        JOIN runs r ON r.id = e.run_id
+        WHERE run_events_fts MATCH ? AND r.job_id = ?
+        ORDER BY r.started_at DESC, r.id DESC, e.seq ASC
+        LIMIT ?
+        "#,
+    )
+    .bind(fts_query)
+    .bind(job_id)
+    .bind(limit as i64)
+    .fetch_all(db)
+    .await?;
+
+    let mut hits = Vec::with_capacity(rows.len());
+    for row in rows {
+        hits.push(RunEventSearchHit {
+            run_id: row.get::<String, _>("run_id"),
+            run_status: row.get::<String, _>("run_status").parse::<RunStatus>()?,
+            run_started_at: row.get::<i64, _>("run_started_at"),
+            seq: row.get::<i64, _>("seq"),
+            ts: row.get::<i64, _>("ts"),
+            level: row.get::<String, _>("level"),
+            kind: row.get::<String, _>("kind"),
+            message: row.get::<String, _>("message"),
+            excerpt: row.get::<String, _>("excerpt"),
+        });
+    }
+
+    Ok(hits)
+}
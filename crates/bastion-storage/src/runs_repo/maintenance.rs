@@ -1,5 +1,7 @@
 use sqlx::{Row, SqlitePool};
 
+use bastion_core::progress::PROGRESS_SNAPSHOT_EVENT_KIND_V1;
+
 use super::{IncompleteCleanupRun, RunStatus};
 
 pub async fn prune_runs_ended_before(
@@ -28,6 +30,266 @@ pub async fn prune_runs_ended_before(
     Ok(result.rows_affected())
 }
 
+/// Marks a finished run for purge. Returns `false` if the run does not exist or is still active.
+pub async fn request_run_purge(
+    db: &SqlitePool,
+    run_id: &str,
+    requested_by_user_id: i64,
+    now: i64,
+) -> Result<bool, anyhow::Error> {
+    let result = sqlx::query(
+        r#"
+        UPDATE runs
+        SET purge_requested_at = COALESCE(purge_requested_at, ?),
+            purge_requested_by_user_id = COALESCE(purge_requested_by_user_id, ?)
+        WHERE id = ?
+          AND status NOT IN ('queued', 'running')
+        "#,
+    )
+    .bind(now)
+    .bind(requested_by_user_id)
+    .bind(run_id)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Deletes runs with a pending purge request whose snapshot artifacts are gone.
+///
+/// Run events and the artifact/delete-task rows go with the run via `ON DELETE CASCADE`.
+pub async fn purge_requested_runs(db: &SqlitePool) -> Result<Vec<String>, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        DELETE FROM runs
+        WHERE purge_requested_at IS NOT NULL
+          AND NOT EXISTS (
+            SELECT 1 FROM run_artifacts a
+            WHERE a.run_id = runs.id
+              AND a.status IN ('present', 'deleting', 'error')
+          )
+        RETURNING id
+        "#,
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows.iter().map(|r| r.get::<String, _>("id")).collect())
+}
+
+/// Runs with a pending purge request whose snapshot delete gave up (artifact status `error`).
+///
+/// These stay until the delete is retried (purging again re-queues it) or the snapshot is
+/// ignored, so callers surface them instead of waiting forever.
+pub async fn list_blocked_run_purges(db: &SqlitePool) -> Result<Vec<String>, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT r.id FROM runs r
+        JOIN run_artifacts a ON a.run_id = r.id
+        WHERE r.purge_requested_at IS NOT NULL
+          AND a.status = 'error'
+        ORDER BY r.purge_requested_at ASC
+        "#,
+    )
+    .fetch_all(db)
+    .await?;
+    Ok(rows.iter().map(|r| r.get::<String, _>("id")).collect())
+}
+
+/// Per-run event cap applied by compaction when the runtime config does not set one.
+pub const DEFAULT_MAX_EVENTS_PER_RUN: u32 = 10_000;
+
+/// Event kind of the marker that replaces events dropped by the per-run cap.
+pub const EVENTS_TRUNCATED_EVENT_KIND: &str = "events_truncated";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunEventsCompaction {
+    /// Older `progress_snapshot` events removed (the last one is kept).
+    pub progress_removed: u64,
+    /// Events dropped from the middle of the run to stay under the cap.
+    pub truncated: u64,
+}
+
+/// Finished runs (ended before `ended_before`) whose events have not been compacted, oldest first.
+pub async fn list_runs_pending_event_compaction(
+    db: &SqlitePool,
+    ended_before: i64,
+    limit: u32,
+) -> Result<Vec<String>, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id FROM runs
+        WHERE events_compacted_at IS NULL
+          AND ended_at IS NOT NULL
+          AND ended_at < ?
+        ORDER BY ended_at ASC
+        LIMIT ?
+        "#,
+    )
+    .bind(ended_before)
+    .bind(limit as i64)
+    .fetch_all(db)
+    .await?;
+    Ok(rows.iter().map(|r| r.get::<String, _>("id")).collect())
+}
+
+/// Collapses a finished run's progress snapshots into the final one and, when the run still has
+/// more than `max_events` events, keeps the first and last events around a single
+/// `events_truncated` marker. Marks the run as compacted.
+pub async fn compact_run_events(
+    db: &SqlitePool,
+    run_id: &str,
+    max_events: u32,
+    now: i64,
+) -> Result<RunEventsCompaction, anyhow::Error> {
+    let mut out = RunEventsCompaction::default();
+    let mut tx = db.begin().await?;
+
+    let result = sqlx::query(
+        r#"
+        DELETE FROM run_events
+        WHERE run_id = ?
+          AND kind = ?
+          AND seq < (
+            SELECT MAX(seq) FROM run_events WHERE run_id = ? AND kind = ?
+          )
+        "#,
+    )
+    .bind(run_id)
+    .bind(PROGRESS_SNAPSHOT_EVENT_KIND_V1)
+    .bind(run_id)
+    .bind(PROGRESS_SNAPSHOT_EVENT_KIND_V1)
+    .execute(&mut *tx)
+    .await?;
+    out.progress_removed = result.rows_affected();
+
+    let total = sqlx::query("SELECT COUNT(1) AS n FROM run_events WHERE run_id = ?")
+        .bind(run_id)
+        .fetch_one(&mut *tx)
+        .await?
+        .get::<i64, _>("n")
+        .max(0) as u64;
+
+    // One slot goes to the marker; the rest is split between the start and the end of the run.
+    let max_events = u64::from(max_events.max(3));
+    if total > max_events {
+        let head = (max_events - 1) / 2;
+        let tail = max_events - 1 - head;
+
+        let head_last = sqlx::query(
+            "SELECT seq, ts FROM run_events WHERE run_id = ? ORDER BY seq ASC LIMIT 1 OFFSET ?",
+        )
+        .bind(run_id)
+        .bind((head - 1) as i64)
+        .fetch_one(&mut *tx)
+        .await?;
+        let tail_first = sqlx::query(
+            "SELECT seq FROM run_events WHERE run_id = ? ORDER BY seq DESC LIMIT 1 OFFSET ?",
+        )
+        .bind(run_id)
+        .bind((tail - 1) as i64)
+        .fetch_one(&mut *tx)
+        .await?;
+        let head_last_seq = head_last.get::<i64, _>("seq");
+        let tail_first_seq = tail_first.get::<i64, _>("seq");
+
+        let result = sqlx::query("DELETE FROM run_events WHERE run_id = ? AND seq > ? AND seq < ?")
+            .bind(run_id)
+            .bind(head_last_seq)
+            .bind(tail_first_seq)
+            .execute(&mut *tx)
+            .await?;
+        out.truncated = result.rows_affected();
+
+        // The marker takes the first dropped seq, so the event log keeps its order.
+        let fields = serde_json::json!({
+            "dropped": out.truncated,
+            "max_events": max_events,
+            "first_seq": head_last_seq + 1,
+            "last_seq": tail_first_seq - 1,
+        });
+        sqlx::query(
+            r#"
+            INSERT INTO run_events (run_id, seq, ts, level, kind, message, fields_json)
+            VALUES (?, ?, ?, 'warn', ?, ?, ?)
+            "#,
+        )
+        .bind(run_id)
+        .bind(head_last_seq + 1)
+        .bind(head_last.get::<i64, _>("ts"))
+        .bind(EVENTS_TRUNCATED_EVENT_KIND)
+        .bind(format!(
+            "{} events removed to keep the run under {max_events} events",
+            out.truncated
+        ))
+        .bind(serde_json::to_string(&fields)?)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("UPDATE runs SET events_compacted_at = ? WHERE id = ?")
+        .bind(now)
+        .bind(run_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(out)
+}
+
+/// Removes the events of up to `limit` runs that ended before `cutoff_ts`, keeping `error`
+/// events so failures stay explainable, and their raw task logs. The runs themselves are kept (see
+/// run retention).
+///
+/// Returns `(runs, events)` processed.
+pub async fn prune_run_events_ended_before(
+    db: &SqlitePool,
+    cutoff_ts: i64,
+    now: i64,
+    limit: u32,
+) -> Result<(u64, u64), anyhow::Error> {
+    let mut tx = db.begin().await?;
+
+    let run_ids: Vec<String> = sqlx::query(
+        r#"
+        SELECT id FROM runs
+        WHERE events_pruned_at IS NULL
+          AND ended_at IS NOT NULL
+          AND ended_at < ?
+        ORDER BY ended_at ASC
+        LIMIT ?
+        "#,
+    )
+    .bind(cutoff_ts)
+    .bind(limit as i64)
+    .fetch_all(&mut *tx)
+    .await?
+    .iter()
+    .map(|r| r.get::<String, _>("id"))
+    .collect();
+
+    let mut events = 0_u64;
+    for run_id in &run_ids {
+        let result = sqlx::query("DELETE FROM run_events WHERE run_id = ? AND level != 'error'")
+            .bind(run_id)
+            .execute(&mut *tx)
+            .await?;
+        events = events.saturating_add(result.rows_affected());
+
+        sqlx::query("DELETE FROM run_log_chunks WHERE run_id = ?")
+            .bind(run_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE runs SET events_pruned_at = ? WHERE id = ?")
+            .bind(now)
+            .bind(run_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+    Ok((run_ids.len() as u64, events))
+}
+
 pub async fn list_incomplete_cleanup_candidates(
     db: &SqlitePool,
     cutoff_started_at: i64,
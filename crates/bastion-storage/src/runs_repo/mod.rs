@@ -5,14 +5,21 @@ mod types;
 
 pub use events::{
     append_run_event, list_latest_run_events_by_kind, list_run_events, list_run_events_after_seq,
+    run_events_fts_query, search_job_run_events,
+};
+pub use maintenance::{
+    DEFAULT_MAX_EVENTS_PER_RUN, EVENTS_TRUNCATED_EVENT_KIND, RunEventsCompaction,
+    compact_run_events, list_blocked_run_purges, list_incomplete_cleanup_candidates,
+    list_runs_pending_event_compaction, prune_run_events_ended_before, prune_runs_ended_before,
+    purge_requested_runs, request_run_purge,
 };
-pub use maintenance::{list_incomplete_cleanup_candidates, prune_runs_ended_before};
 pub use runs::{
-    claim_next_queued_run, complete_run, create_run, get_run, get_run_progress,
-    get_run_target_snapshot, list_runs_for_job, request_run_cancel, requeue_run, set_run_progress,
-    set_run_target_snapshot,
+    claim_next_queued_run, complete_run, create_run, create_run_with_id,
+    get_previous_finished_run_status, get_previous_successful_run, get_run, get_run_progress,
+    get_run_target_snapshot, list_runs_for_job, list_runs_for_job_filtered, request_run_cancel,
+    requeue_run, set_run_progress, set_run_target_snapshot,
 };
-pub use types::{IncompleteCleanupRun, Run, RunEvent, RunStatus};
+pub use types::{IncompleteCleanupRun, Run, RunEvent, RunEventSearchHit, RunListFilter, RunStatus};
 
 #[cfg(test)]
 mod tests;
@@ -1,8 +1,8 @@
-use sqlx::{Row, SqlitePool};
+use sqlx::{QueryBuilder, Row, SqlitePool};
 use time::OffsetDateTime;
 use uuid::Uuid;
 
-use super::{Run, RunStatus};
+use super::{Run, RunListFilter, RunStatus};
 
 fn parse_run_row(row: &sqlx::sqlite::SqliteRow) -> Result<Run, anyhow::Error> {
     let status = row.get::<String, _>("status").parse::<RunStatus>()?;
@@ -42,6 +42,24 @@ pub async fn create_run(
     error: Option<&str>,
 ) -> Result<Run, anyhow::Error> {
     let id = Uuid::new_v4().to_string();
+    create_run_with_id(
+        db, &id, job_id, status, started_at, ended_at, summary, error,
+    )
+    .await
+}
+
+/// Inserts a run under a caller-chosen id (used when recovering runs from target directories).
+#[allow(clippy::too_many_arguments)]
+pub async fn create_run_with_id(
+    db: &SqlitePool,
+    id: &str,
+    job_id: &str,
+    status: RunStatus,
+    started_at: i64,
+    ended_at: Option<i64>,
+    summary: Option<serde_json::Value>,
+    error: Option<&str>,
+) -> Result<Run, anyhow::Error> {
     let summary_json = match &summary {
         Some(v) => Some(serde_json::to_string(v)?),
         None => None,
@@ -53,7 +71,7 @@ pub async fn create_run(
         VALUES (?, ?, ?, ?, ?, ?, ?)
         "#,
     )
-    .bind(&id)
+    .bind(id)
     .bind(job_id)
     .bind(status.as_str())
     .bind(started_at)
@@ -64,7 +82,7 @@ pub async fn create_run(
     .await?;
 
     Ok(Run {
-        id,
+        id: id.to_string(),
         job_id: job_id.to_string(),
         status,
         started_at,
@@ -99,6 +117,49 @@ pub async fn list_runs_for_job(
     Ok(runs)
 }
 
+pub async fn list_runs_for_job_filtered(
+    db: &SqlitePool,
+    job_id: &str,
+    filter: &RunListFilter,
+    limit: u32,
+) -> Result<Vec<Run>, anyhow::Error> {
+    let mut qb: QueryBuilder<'_, sqlx::Sqlite> = QueryBuilder::new(
+        "SELECT id, job_id, status, started_at, ended_at, cancel_requested_at, cancel_requested_by_user_id, cancel_reason, progress_json, summary_json, error FROM runs WHERE job_id = ",
+    );
+    qb.push_bind(job_id);
+    if let Some(status) = filter.status {
+        qb.push(" AND status = ");
+        qb.push_bind(status.as_str());
+    }
+    if let Some(from) = filter.started_from {
+        qb.push(" AND started_at >= ");
+        qb.push_bind(from);
+    }
+    if let Some(to) = filter.started_to {
+        qb.push(" AND started_at <= ");
+        qb.push_bind(to);
+    }
+    if let Some((started_at, id)) = &filter.before {
+        qb.push(" AND (started_at < ");
+        qb.push_bind(*started_at);
+        qb.push(" OR (started_at = ");
+        qb.push_bind(*started_at);
+        qb.push(" AND id < ");
+        qb.push_bind(id.as_str());
+        qb.push("))");
+    }
+    qb.push(" ORDER BY started_at DESC, id DESC LIMIT ");
+    qb.push_bind(limit as i64);
+
+    let rows = qb.build().fetch_all(db).await?;
+    let mut runs = Vec::with_capacity(rows.len());
+    for row in rows {
+        runs.push(parse_run_row(&row)?);
+    }
+
+    Ok(runs)
+}
+
 pub async fn get_run(db: &SqlitePool, run_id: &str) -> Result<Option<Run>, anyhow::Error> {
     let row = sqlx::query(
         "SELECT id, job_id, status, started_at, ended_at, cancel_requested_at, cancel_requested_by_user_id, cancel_reason, progress_json, summary_json, error FROM runs WHERE id = ? LIMIT 1",
@@ -114,6 +175,71 @@ pub async fn get_run(db: &SqlitePool, run_id: &str) -> Result<Option<Run>, anyho
     Ok(Some(parse_run_row(&row)?))
 }
 
+/// Status of the most recent earlier run of the same job that ended in success or failure.
+///
+/// `started_at` has second resolution, so runs starting in the same second are ordered by
+/// insertion (`rowid`).
+pub async fn get_previous_finished_run_status(
+    db: &SqlitePool,
+    run_id: &str,
+) -> Result<Option<RunStatus>, anyhow::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT prev.status
+        FROM runs cur
+        JOIN runs prev ON prev.job_id = cur.job_id
+        WHERE cur.id = ?
+          AND prev.id != cur.id
+          AND (
+            prev.started_at < cur.started_at
+            OR (prev.started_at = cur.started_at AND prev.rowid < cur.rowid)
+          )
+          AND prev.status IN ('success', 'failed')
+        ORDER BY prev.started_at DESC, prev.rowid DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(run_id)
+    .fetch_optional(db)
+    .await?;
+
+    let Some(row) = row else {
+        return Ok(None);
+    };
+
+    Ok(Some(row.get::<String, _>("status").parse::<RunStatus>()?))
+}
+
+/// The most recent earlier successful run of the same job.
+pub async fn get_previous_successful_run(
+    db: &SqlitePool,
+    run_id: &str,
+) -> Result<Option<Run>, anyhow::Error> {
+    let row = sqlx::query(
+        r#"
+        SELECT prev.id, prev.job_id, prev.status, prev.started_at, prev.ended_at,
+               prev.cancel_requested_at, prev.cancel_requested_by_user_id, prev.cancel_reason,
+               prev.progress_json, prev.summary_json, prev.error
+        FROM runs cur
+        JOIN runs prev ON prev.job_id = cur.job_id
+        WHERE cur.id = ?
+          AND prev.id != cur.id
+          AND (
+            prev.started_at < cur.started_at
+            OR (prev.started_at = cur.started_at AND prev.rowid < cur.rowid)
+          )
+          AND prev.status = 'success'
+        ORDER BY prev.started_at DESC, prev.rowid DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(run_id)
+    .fetch_optional(db)
+    .await?;
+
+    row.as_ref().map(parse_run_row).transpose()
+}
+
 pub async fn get_run_target_snapshot(
     db: &SqlitePool,
     run_id: &str,
@@ -4,9 +4,14 @@ use crate::db;
 use crate::incomplete_cleanup_repo;
 
 use super::{
-    IncompleteCleanupRun, RunStatus, append_run_event, claim_next_queued_run, complete_run,
-    create_run, get_run, get_run_progress, list_incomplete_cleanup_candidates, list_run_events,
-    list_runs_for_job, prune_runs_ended_before, request_run_cancel, requeue_run, set_run_progress,
+    EVENTS_TRUNCATED_EVENT_KIND, IncompleteCleanupRun, RunListFilter, RunStatus, append_run_event,
+    claim_next_queued_run, compact_run_events, complete_run, create_run,
+    get_previous_finished_run_status, get_previous_successful_run, get_run, get_run_progress,
+    list_blocked_run_purges, list_incomplete_cleanup_candidates, list_run_events,
+    list_runs_for_job, list_runs_for_job_filtered, list_runs_pending_event_compaction,
+    prune_run_events_ended_before, prune_runs_ended_before, purge_requested_runs,
+    request_run_cancel, request_run_purge, requeue_run, run_events_fts_query,
+    search_job_run_events, set_run_progress,
 };
 
 #[tokio::test]
@@ -186,6 +191,102 @@ async fn prune_runs_skips_runs_with_live_snapshots() {
     assert_eq!(gone2, 0);
 }
 
+#[tokio::test]
+async fn purge_requested_runs_waits_for_live_snapshots() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    sqlx::query(
+        "INSERT INTO jobs (id, name, schedule, overlap_policy, spec_json, created_at, updated_at) VALUES (?, ?, NULL, 'queue', ?, ?, ?)",
+    )
+    .bind("job1")
+    .bind("job1")
+    .bind(r#"{"v":1,"type":"filesystem"}"#)
+    .bind(1000)
+    .bind(1000)
+    .execute(&pool)
+    .await
+    .expect("insert job");
+
+    let running = create_run(&pool, "job1", RunStatus::Running, 1, None, None, None)
+        .await
+        .expect("running run");
+    let with_snapshot = create_run(&pool, "job1", RunStatus::Success, 1, Some(10), None, None)
+        .await
+        .expect("run with snapshot");
+    let plain = create_run(&pool, "job1", RunStatus::Failed, 1, Some(10), None, None)
+        .await
+        .expect("plain run");
+    append_run_event(&pool, &plain.id, "info", "start", "start", None)
+        .await
+        .expect("event");
+
+    sqlx::query(
+        r#"
+        INSERT INTO run_artifacts (
+          run_id, job_id, node_id, target_type, target_snapshot_json,
+          artifact_format, status, started_at, ended_at, created_at, updated_at
+        ) VALUES (?, 'job1', 'hub', 'local_dir', ?, 'archive_v1', 'deleting', 1, 10, 1000, 1000)
+        "#,
+    )
+    .bind(&with_snapshot.id)
+    .bind(r#"{"node_id":"hub","target":{"type":"local_dir","base_dir":"/tmp"}}"#)
+    .execute(&pool)
+    .await
+    .expect("insert run_artifacts");
+
+    assert!(
+        !request_run_purge(&pool, &running.id, 1, 100)
+            .await
+            .expect("purge running")
+    );
+    assert!(
+        !request_run_purge(&pool, "missing", 1, 100)
+            .await
+            .expect("purge missing")
+    );
+    assert!(
+        request_run_purge(&pool, &with_snapshot.id, 1, 100)
+            .await
+            .expect("purge snapshot")
+    );
+    assert!(
+        request_run_purge(&pool, &plain.id, 1, 100)
+            .await
+            .expect("purge plain")
+    );
+
+    // Only the run without a live snapshot is removed right away.
+    let purged = purge_requested_runs(&pool).await.expect("purge");
+    assert_eq!(purged, vec![plain.id.clone()]);
+    assert!(get_run(&pool, &plain.id).await.expect("get").is_none());
+    let events = list_run_events(&pool, &plain.id, 100)
+        .await
+        .expect("events");
+    assert!(events.is_empty());
+
+    // A delete that gave up keeps the run and is reported as blocked.
+    sqlx::query("UPDATE run_artifacts SET status = 'error' WHERE run_id = ?")
+        .bind(&with_snapshot.id)
+        .execute(&pool)
+        .await
+        .expect("mark error");
+    assert!(purge_requested_runs(&pool).await.expect("purge").is_empty());
+    assert_eq!(
+        list_blocked_run_purges(&pool).await.expect("blocked"),
+        vec![with_snapshot.id.clone()]
+    );
+
+    sqlx::query("UPDATE run_artifacts SET status = 'deleted' WHERE run_id = ?")
+        .bind(&with_snapshot.id)
+        .execute(&pool)
+        .await
+        .expect("mark deleted");
+    let purged = purge_requested_runs(&pool).await.expect("purge");
+    assert_eq!(purged, vec![with_snapshot.id.clone()]);
+    assert!(get_run(&pool, &running.id).await.expect("get").is_some());
+}
+
 #[tokio::test]
 async fn list_incomplete_cleanup_candidates_filters_and_orders() {
     let temp = TempDir::new().expect("tempdir");
@@ -424,3 +525,468 @@ async fn complete_run_is_ignored_after_terminal_cancel() {
         .expect("run exists");
     assert_eq!(final_run.status, RunStatus::Canceled);
 }
+
+#[tokio::test]
+async fn previous_finished_run_status_skips_canceled_and_other_jobs() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    for job_id in ["job1", "job2"] {
+        sqlx::query(
+            "INSERT INTO jobs (id, name, schedule, overlap_policy, spec_json, created_at, updated_at) VALUES (?, ?, NULL, 'queue', ?, ?, ?)",
+        )
+        .bind(job_id)
+        .bind(job_id)
+        .bind(r#"{"v":1,"type":"filesystem","source":{"root":"/"},"target":{"type":"local_dir","base_dir":"/tmp"}}"#)
+        .bind(1000)
+        .bind(1000)
+        .execute(&pool)
+        .await
+        .expect("insert job");
+    }
+
+    let first = create_run(
+        &pool,
+        "job1",
+        RunStatus::Failed,
+        1000,
+        Some(1001),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    assert_eq!(
+        get_previous_finished_run_status(&pool, &first.id)
+            .await
+            .expect("previous"),
+        None
+    );
+
+    let _ = create_run(
+        &pool,
+        "job1",
+        RunStatus::Canceled,
+        2000,
+        Some(2001),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    let _ = create_run(
+        &pool,
+        "job2",
+        RunStatus::Success,
+        2500,
+        Some(2501),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    let current = create_run(
+        &pool,
+        "job1",
+        RunStatus::Success,
+        3000,
+        Some(3001),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+
+    assert_eq!(
+        get_previous_finished_run_status(&pool, &current.id)
+            .await
+            .expect("previous"),
+        Some(RunStatus::Failed)
+    );
+
+    // Runs started in the same second only see the ones inserted before them.
+    let same_second_first = create_run(
+        &pool,
+        "job1",
+        RunStatus::Success,
+        4000,
+        Some(4001),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    let same_second_second = create_run(
+        &pool,
+        "job1",
+        RunStatus::Failed,
+        4000,
+        Some(4001),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    assert_eq!(
+        get_previous_finished_run_status(&pool, &same_second_first.id)
+            .await
+            .expect("previous"),
+        Some(RunStatus::Success)
+    );
+    assert_eq!(
+        get_previous_finished_run_status(&pool, &same_second_second.id)
+            .await
+            .expect("previous"),
+        Some(RunStatus::Success)
+    );
+    let previous_success = get_previous_successful_run(&pool, &same_second_second.id)
+        .await
+        .expect("previous success")
+        .expect("exists");
+    assert_eq!(previous_success.id, same_second_first.id);
+}
+
+#[tokio::test]
+async fn list_runs_for_job_filtered_pages_with_keyset_cursor() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    sqlx::query(
+        "INSERT INTO jobs (id, name, schedule, overlap_policy, spec_json, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind("job1")
+    .bind("job1")
+    .bind(None::<String>)
+    .bind("queue")
+    .bind(r#"{"v":1,"type":"filesystem"}"#)
+    .bind(1000)
+    .bind(1000)
+    .execute(&pool)
+    .await
+    .expect("insert job");
+
+    for (i, status) in [
+        RunStatus::Success,
+        RunStatus::Failed,
+        RunStatus::Success,
+        RunStatus::Success,
+        RunStatus::Failed,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let started_at = 1000 * (i as i64 + 1);
+        create_run(
+            &pool,
+            "job1",
+            status,
+            started_at,
+            Some(started_at + 1),
+            None,
+            None,
+        )
+        .await
+        .expect("create run");
+    }
+
+    let mut filter = RunListFilter {
+        status: Some(RunStatus::Success),
+        ..Default::default()
+    };
+    let page1 = list_runs_for_job_filtered(&pool, "job1", &filter, 2)
+        .await
+        .expect("page1");
+    assert_eq!(
+        page1.iter().map(|r| r.started_at).collect::<Vec<_>>(),
+        vec![4000, 3000]
+    );
+
+    let last = page1.last().expect("last");
+    filter.before = Some((last.started_at, last.id.clone()));
+    let page2 = list_runs_for_job_filtered(&pool, "job1", &filter, 2)
+        .await
+        .expect("page2");
+    assert_eq!(
+        page2.iter().map(|r| r.started_at).collect::<Vec<_>>(),
+        vec![1000]
+    );
+
+    let ranged = list_runs_for_job_filtered(
+        &pool,
+        "job1",
+        &RunListFilter {
+            started_from: Some(2000),
+            started_to: Some(4000),
+            ..Default::default()
+        },
+        10,
+    )
+    .await
+    .expect("ranged");
+    assert_eq!(
+        ranged.iter().map(|r| r.started_at).collect::<Vec<_>>(),
+        vec![4000, 3000, 2000]
+    );
+}
+
+#[test]
+fn run_events_fts_query_quotes_every_word() {
+    assert_eq!(
+        run_events_fts_query(" permission  denied ").as_deref(),
+        Some("\"permission\" \"denied\"")
+    );
+    assert_eq!(
+        run_events_fts_query("a\"b OR c:").as_deref(),
+        Some("\"a\"\"b\" \"OR\" \"c:\"")
+    );
+    assert_eq!(run_events_fts_query("   "), None);
+}
+
+#[tokio::test]
+async fn search_job_run_events_matches_messages_and_fields() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    for job_id in ["job1", "job2"] {
+        sqlx::query(
+            "INSERT INTO jobs (id, name, schedule, overlap_policy, spec_json, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(job_id)
+        .bind(job_id)
+        .bind(None::<String>)
+        .bind("queue")
+        .bind(r#"{"v":1,"type":"filesystem"}"#)
+        .bind(1000)
+        .bind(1000)
+        .execute(&pool)
+        .await
+        .expect("insert job");
+    }
+
+    let old = create_run(
+        &pool,
+        "job1",
+        RunStatus::Failed,
+        1000,
+        Some(1001),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    let new = create_run(
+        &pool,
+        "job1",
+        RunStatus::Success,
+        2000,
+        Some(2001),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    let other = create_run(
+        &pool,
+        "job2",
+        RunStatus::Failed,
+        3000,
+        Some(3001),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+
+    append_run_event(
+        &pool,
+        &old.id,
+        "error",
+        "fs_issues",
+        "Permission denied: /etc/shadow",
+        None,
+    )
+    .await
+    .expect("event");
+    append_run_event(
+        &pool,
+        &new.id,
+        "warn",
+        "fs_issues",
+        "skipped files",
+        Some(serde_json::json!({"error": "permission denied"})),
+    )
+    .await
+    .expect("event");
+    append_run_event(&pool, &new.id, "info", "complete", "complete", None)
+        .await
+        .expect("event");
+    append_run_event(
+        &pool,
+        &other.id,
+        "error",
+        "fs_issues",
+        "permission denied",
+        None,
+    )
+    .await
+    .expect("event");
+
+    let hits = search_job_run_events(&pool, "job1", "permission denied", 50)
+        .await
+        .expect("search");
+    assert_eq!(
+        hits.iter().map(|h| h.run_id.as_str()).collect::<Vec<_>>(),
+        vec![new.id.as_str(), old.id.as_str()]
+    );
+    assert_eq!(hits[1].run_status, RunStatus::Failed);
+    assert!(
+        hits[1].excerpt.contains("[Permission]"),
+        "{}",
+        hits[1].excerpt
+    );
+
+    sqlx::query("DELETE FROM runs WHERE id = ?")
+        .bind(&old.id)
+        .execute(&pool)
+        .await
+        .expect("delete run");
+    let hits = search_job_run_events(&pool, "job1", "permission", 50)
+        .await
+        .expect("search");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].run_id, new.id);
+}
+
+#[tokio::test]
+async fn compaction_collapses_progress_caps_events_and_retention_keeps_errors() {
+    let temp = TempDir::new().expect("tempdir");
+    let pool = db::init(temp.path()).await.expect("db init");
+
+    sqlx::query(
+        "INSERT INTO jobs (id, name, schedule, overlap_policy, spec_json, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind("job1")
+    .bind("job1")
+    .bind(None::<String>)
+    .bind("queue")
+    .bind(r#"{"v":1,"type":"filesystem"}"#)
+    .bind(1000)
+    .bind(1000)
+    .execute(&pool)
+    .await
+    .expect("insert job");
+
+    let run = create_run(
+        &pool,
+        "job1",
+        RunStatus::Failed,
+        1000,
+        Some(2000),
+        None,
+        None,
+    )
+    .await
+    .expect("create run");
+    let running = create_run(&pool, "job1", RunStatus::Running, 1000, None, None, None)
+        .await
+        .expect("create run");
+
+    append_run_event(&pool, &run.id, "info", "start", "start", None)
+        .await
+        .expect("event");
+    for i in 0..5 {
+        append_run_event(
+            &pool,
+            &run.id,
+            "info",
+            "progress_snapshot",
+            "upload",
+            Some(serde_json::json!({ "i": i })),
+        )
+        .await
+        .expect("event");
+    }
+    for i in 0..20 {
+        append_run_event(
+            &pool,
+            &run.id,
+            "warn",
+            "fs_issue",
+            &format!("issue {i}"),
+            None,
+        )
+        .await
+        .expect("event");
+    }
+    append_run_event(&pool, &run.id, "error", "failed", "failed", None)
+        .await
+        .expect("event");
+
+    let pending = list_runs_pending_event_compaction(&pool, 3000, 10)
+        .await
+        .expect("pending");
+    assert_eq!(pending, vec![run.id.clone()]);
+
+    let stats = compact_run_events(&pool, &run.id, 11, 3000)
+        .await
+        .expect("compact");
+    assert_eq!(stats.progress_removed, 4);
+    // 23 events left: 5 kept at the start, 5 at the end, the rest replaced by 1 marker.
+    assert_eq!(stats.truncated, 13);
+
+    let events = list_run_events(&pool, &run.id, 100).await.expect("events");
+    assert_eq!(events.len(), 11);
+    assert_eq!(events[0].kind, "start");
+    assert_eq!(events[1].kind, "progress_snapshot");
+    assert_eq!(
+        events[1].fields.as_ref().and_then(|f| f.get("i")),
+        Some(&serde_json::json!(4))
+    );
+    assert_eq!(events[5].kind, EVENTS_TRUNCATED_EVENT_KIND);
+    assert_eq!(events[5].seq, events[4].seq + 1);
+    assert!(events.windows(2).all(|w| w[0].seq < w[1].seq));
+    assert_eq!(events[10].kind, "failed");
+
+    assert!(
+        list_runs_pending_event_compaction(&pool, 3000, 10)
+            .await
+            .expect("pending")
+            .is_empty()
+    );
+
+    append_run_event(&pool, &running.id, "info", "start", "start", None)
+        .await
+        .expect("event");
+    crate::run_logs_repo::append_run_log_chunk(&pool, &run.id, b"log\n", 1024, 2000)
+        .await
+        .expect("log");
+    let (runs, removed) = prune_run_events_ended_before(&pool, 3000, 3000, 100)
+        .await
+        .expect("prune events");
+    assert_eq!(runs, 1);
+    assert_eq!(removed, 10);
+    let events = list_run_events(&pool, &run.id, 100).await.expect("events");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].level, "error");
+    assert!(
+        crate::run_logs_repo::get_run_log(&pool, &run.id)
+            .await
+            .expect("log")
+            .is_none()
+    );
+    // Unfinished runs and the run rows themselves are untouched.
+    assert_eq!(
+        list_run_events(&pool, &running.id, 100)
+            .await
+            .expect("events")
+            .len(),
+        1
+    );
+    assert!(get_run(&pool, &run.id).await.expect("get").is_some());
+    assert_eq!(
+        prune_run_events_ended_before(&pool, 3000, 3000, 100)
+            .await
+            .expect("prune events"),
+        (0, 0)
+    );
+}
@@ -48,6 +48,16 @@ impl std::str::FromStr for RunStatus {
     }
 }
 
+/// Filters for paged run listings, newest first.
+#[derive(Debug, Clone, Default)]
+pub struct RunListFilter {
+    pub status: Option<RunStatus>,
+    pub started_from: Option<i64>,
+    pub started_to: Option<i64>,
+    /// Keyset cursor: only runs ordered after this `(started_at, id)` are returned.
+    pub before: Option<(i64, String)>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Run {
     pub id: String,
@@ -74,6 +84,20 @@ pub struct RunEvent {
     pub fields: Option<serde_json::Value>,
 }
 
+/// A run event matched by full-text search, with a highlighted excerpt.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunEventSearchHit {
+    pub run_id: String,
+    pub run_status: RunStatus,
+    pub run_started_at: i64,
+    pub seq: i64,
+    pub ts: i64,
+    pub level: String,
+    pub kind: String,
+    pub message: String,
+    pub excerpt: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct IncompleteCleanupRun {
     pub id: String,
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, io, path::Path};
+use std::{collections::HashMap, io, path::Path, sync::Arc};
 
 use chacha20poly1305::aead::{Aead, Payload};
 use chacha20poly1305::{KeyInit, XChaCha20Poly1305, XNonce};
@@ -7,6 +7,7 @@ use rand::RngCore;
 use sha2::Sha256;
 
 use super::MASTER_KEY_FILE;
+use super::external::{ExternalSecretProviders, ExternalSecretRef};
 
 #[derive(Debug, Clone)]
 struct KeyEntry {
@@ -27,6 +28,7 @@ pub struct EncryptedSecret {
 pub struct SecretsCrypto {
     active_kid: u32,
     keys: HashMap<u32, KeyEntry>,
+    external: Option<Arc<ExternalSecretProviders>>,
 }
 
 impl SecretsCrypto {
@@ -60,9 +62,31 @@ impl SecretsCrypto {
         Ok(Self {
             active_kid: keyring.active_kid,
             keys,
+            external: None,
         })
     }
 
+    /// Enables secrets stored as references to an external provider (Vault, AWS).
+    pub fn with_external_providers(mut self, providers: ExternalSecretProviders) -> Self {
+        self.external = Some(Arc::new(providers));
+        self
+    }
+
+    pub fn external_providers(&self) -> Option<&ExternalSecretProviders> {
+        self.external.as_deref()
+    }
+
+    pub async fn resolve_external(
+        &self,
+        secret: &ExternalSecretRef,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let providers = self
+            .external
+            .as_deref()
+            .ok_or_else(|| io::Error::other("external secret providers are not enabled"))?;
+        providers.resolve(secret).await
+    }
+
     pub fn active_kid(&self) -> u32 {
         self.active_kid
     }
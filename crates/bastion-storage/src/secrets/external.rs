@@ -0,0 +1,567 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use base64::Engine as _;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use time::OffsetDateTime;
+
+/// Resolved values are reused for this long before the provider is asked again.
+const CACHE_TTL: Duration = Duration::from_secs(300);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Vault mounts that are not secret engines; the Hub's token must never be used to read them.
+const VAULT_RESERVED_PREFIXES: &[&str] = &["sys", "auth", "identity", "cubbyhole"];
+
+const AWS_SERVICE: &str = "secretsmanager";
+const AWS_JSON_CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExternalProvider {
+    /// HashiCorp Vault KV (v1 or v2); `path` is the API path below `/v1/`, e.g. `secret/data/nas`.
+    Vault,
+    /// AWS Secrets Manager; `path` is the secret id or ARN.
+    AwsSecretsManager,
+}
+
+impl ExternalProvider {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Vault => "vault",
+            Self::AwsSecretsManager => "aws_secrets_manager",
+        }
+    }
+}
+
+/// Where an externally stored secret lives. Stored instead of ciphertext.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalSecretRef {
+    pub provider: ExternalProvider,
+    pub path: String,
+    /// Single key to read from a JSON secret; without it the whole value is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+}
+
+impl ExternalSecretRef {
+    /// Rejects paths that could reach anything but a secret read with the Hub's credentials.
+    ///
+    /// Vault paths must be relative KV paths: no leading `/`, no `..` segments, no query, and
+    /// not below `sys/`, `auth/`, `identity/` or `cubbyhole/`.
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        match self.provider {
+            ExternalProvider::Vault => validate_vault_path(&self.path),
+            ExternalProvider::AwsSecretsManager => Ok(()),
+        }
+    }
+
+    fn cache_key(&self) -> String {
+        format!(
+            "{}:{}#{}",
+            self.provider.as_str(),
+            self.path,
+            self.field.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderHealth {
+    pub provider: ExternalProvider,
+    pub configured: bool,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub checked_at: i64,
+}
+
+#[derive(Clone)]
+struct VaultConfig {
+    addr: String,
+    token: String,
+    namespace: Option<String>,
+}
+
+#[derive(Clone)]
+struct AwsConfig {
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+struct CachedValue {
+    fetched_at: Instant,
+    bytes: Vec<u8>,
+}
+
+/// Clients for the external secret providers configured on this Hub.
+///
+/// Configuration comes from the providers' usual environment variables (`VAULT_ADDR`,
+/// `VAULT_TOKEN`, `VAULT_NAMESPACE`, `AWS_REGION`/`AWS_DEFAULT_REGION`, `AWS_ACCESS_KEY_ID`,
+/// `AWS_SECRET_ACCESS_KEY`, `AWS_SESSION_TOKEN`).
+pub struct ExternalSecretProviders {
+    vault: Option<VaultConfig>,
+    aws: Option<AwsConfig>,
+    http: reqwest::Client,
+    cache: Mutex<HashMap<String, CachedValue>>,
+}
+
+impl std::fmt::Debug for ExternalSecretProviders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExternalSecretProviders")
+            .field("vault", &self.vault.as_ref().map(|v| v.addr.as_str()))
+            .field("aws", &self.aws.as_ref().map(|v| v.region.as_str()))
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExternalSecretProviders {
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Like [`Self::from_env`], reading variables through `lookup` instead.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let get = |key: &str| {
+            lookup(key)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+
+        let vault = match (get("VAULT_ADDR"), get("VAULT_TOKEN")) {
+            (Some(addr), Some(token)) => Some(VaultConfig {
+                addr: addr.trim_end_matches('/').to_string(),
+                token,
+                namespace: get("VAULT_NAMESPACE"),
+            }),
+            _ => None,
+        };
+        let aws = match (
+            get("AWS_REGION").or_else(|| get("AWS_DEFAULT_REGION")),
+            get("AWS_ACCESS_KEY_ID"),
+            get("AWS_SECRET_ACCESS_KEY"),
+        ) {
+            (Some(region), Some(access_key_id), Some(secret_access_key)) => Some(AwsConfig {
+                region,
+                access_key_id,
+                secret_access_key,
+                session_token: get("AWS_SESSION_TOKEN"),
+            }),
+            _ => None,
+        };
+
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            vault,
+            aws,
+            http,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_configured(&self, provider: ExternalProvider) -> bool {
+        match provider {
+            ExternalProvider::Vault => self.vault.is_some(),
+            ExternalProvider::AwsSecretsManager => self.aws.is_some(),
+        }
+    }
+
+    /// Returns the secret bytes, from cache when fetched within the last few minutes.
+    pub async fn resolve(&self, secret: &ExternalSecretRef) -> Result<Vec<u8>, anyhow::Error> {
+        let key = secret.cache_key();
+        if let Ok(cache) = self.cache.lock()
+            && let Some(hit) = cache.get(&key)
+            && hit.fetched_at.elapsed() < CACHE_TTL
+        {
+            return Ok(hit.bytes.clone());
+        }
+
+        secret.validate()?;
+        let value = match secret.provider {
+            ExternalProvider::Vault => self.fetch_vault(&secret.path).await?,
+            ExternalProvider::AwsSecretsManager => self.fetch_aws(&secret.path).await?,
+        };
+        let bytes = select_field(value, secret.field.as_deref())?;
+
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(
+                key,
+                CachedValue {
+                    fetched_at: Instant::now(),
+                    bytes: bytes.clone(),
+                },
+            );
+        }
+        Ok(bytes)
+    }
+
+    pub fn invalidate(&self, secret: &ExternalSecretRef) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.remove(&secret.cache_key());
+        }
+    }
+
+    /// Checks that each configured provider accepts our credentials.
+    pub async fn health(&self) -> Vec<ProviderHealth> {
+        let mut out = Vec::new();
+        for provider in [ExternalProvider::Vault, ExternalProvider::AwsSecretsManager] {
+            let result = match provider {
+                ExternalProvider::Vault if self.vault.is_some() => Some(self.check_vault().await),
+                ExternalProvider::AwsSecretsManager if self.aws.is_some() => {
+                    Some(self.check_aws().await)
+                }
+                _ => None,
+            };
+            out.push(ProviderHealth {
+                provider,
+                configured: result.is_some(),
+                ok: matches!(result, Some(Ok(()))),
+                message: match result {
+                    Some(Err(error)) => Some(format!("{error:#}")),
+                    Some(Ok(())) => None,
+                    None => Some("not configured".to_string()),
+                },
+                checked_at: OffsetDateTime::now_utc().unix_timestamp(),
+            });
+        }
+        out
+    }
+
+    fn vault_request(
+        &self,
+        vault: &VaultConfig,
+        method: reqwest::Method,
+        api_path: &str,
+    ) -> reqwest::RequestBuilder {
+        let url = format!("{}/v1/{}", vault.addr, api_path.trim_start_matches('/'));
+        let mut req = self
+            .http
+            .request(method, url)
+            .header("X-Vault-Token", &vault.token);
+        if let Some(namespace) = &vault.namespace {
+            req = req.header("X-Vault-Namespace", namespace);
+        }
+        req
+    }
+
+    async fn fetch_vault(&self, path: &str) -> Result<serde_json::Value, anyhow::Error> {
+        let vault = self
+            .vault
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("vault provider is not configured"))?;
+        let resp = self
+            .vault_request(vault, reqwest::Method::GET, path)
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            anyhow::bail!("vault returned HTTP {status} for {path}");
+        }
+        let body: serde_json::Value = resp.json().await?;
+        vault_secret_data(body)
+    }
+
+    async fn check_vault(&self) -> Result<(), anyhow::Error> {
+        let Some(vault) = self.vault.as_ref() else {
+            anyhow::bail!("vault provider is not configured");
+        };
+        let resp = self
+            .vault_request(vault, reqwest::Method::GET, "auth/token/lookup-self")
+            .send()
+            .await?;
+        let status = resp.status();
+        if !status.is_success() {
+            anyhow::bail!("vault token lookup returned HTTP {status}");
+        }
+        Ok(())
+    }
+
+    async fn aws_call(
+        &self,
+        aws: &AwsConfig,
+        operation: &str,
+        body: serde_json::Value,
+    ) -> Result<serde_json::Value, anyhow::Error> {
+        let host = format!("{AWS_SERVICE}.{}.amazonaws.com", aws.region);
+        let target = format!("secretsmanager.{operation}");
+        let body = serde_json::to_vec(&body)?;
+        let headers = sign_aws_request(aws, &host, &target, &body, OffsetDateTime::now_utc())?;
+
+        let mut req = self
+            .http
+            .post(format!("https://{host}/"))
+            .header("Content-Type", AWS_JSON_CONTENT_TYPE)
+            .header("X-Amz-Target", &target);
+        for (name, value) in headers {
+            req = req.header(name, value);
+        }
+        let resp = req.body(body).send().await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("aws secrets manager {operation} returned HTTP {status}: {text}");
+        }
+        Ok(resp.json().await?)
+    }
+
+    async fn fetch_aws(&self, secret_id: &str) -> Result<serde_json::Value, anyhow::Error> {
+        let aws = self
+            .aws
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("aws secrets manager provider is not configured"))?;
+        let body = self
+            .aws_call(
+                aws,
+                "GetSecretValue",
+                serde_json::json!({ "SecretId": secret_id }),
+            )
+            .await?;
+
+        if let Some(text) = body.get("SecretString").and_then(|v| v.as_str()) {
+            // JSON secrets expose their keys to `field`; anything else is an opaque string.
+            return Ok(serde_json::from_str(text)
+                .unwrap_or_else(|_| serde_json::Value::String(text.to_string())));
+        }
+        if let Some(b64) = body.get("SecretBinary").and_then(|v| v.as_str()) {
+            let bytes = base64::engine::general_purpose::STANDARD.decode(b64)?;
+            return Ok(serde_json::Value::String(String::from_utf8(bytes)?));
+        }
+        anyhow::bail!("aws secret {secret_id} has no value")
+    }
+
+    async fn check_aws(&self) -> Result<(), anyhow::Error> {
+        let Some(aws) = self.aws.as_ref() else {
+            anyhow::bail!("aws secrets manager provider is not configured");
+        };
+        self.aws_call(aws, "ListSecrets", serde_json::json!({ "MaxResults": 1 }))
+            .await?;
+        Ok(())
+    }
+}
+
+/// KV v2 nests the secret under `data.data` next to `data.metadata`; KV v1 returns it as `data`.
+fn vault_secret_data(body: serde_json::Value) -> Result<serde_json::Value, anyhow::Error> {
+    let Some(mut data) = body.get("data").cloned() else {
+        anyhow::bail!("vault response has no data");
+    };
+    if data.get("metadata").is_some_and(|v| v.is_object())
+        && let Some(inner) = data.get_mut("data").map(serde_json::Value::take)
+    {
+        return Ok(inner);
+    }
+    Ok(data)
+}
+
+fn select_field(value: serde_json::Value, field: Option<&str>) -> Result<Vec<u8>, anyhow::Error> {
+    let value = match field.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(field) => value
+            .get(field)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("external secret has no field {field:?}"))?,
+        None => value,
+    };
+    match value {
+        serde_json::Value::String(text) => Ok(text.into_bytes()),
+        other => Ok(serde_json::to_vec(&other)?),
+    }
+}
+
+/// AWS Signature Version 4 headers for a JSON POST to `/`.
+fn sign_aws_request(
+    aws: &AwsConfig,
+    host: &str,
+    target: &str,
+    body: &[u8],
+    now: OffsetDateTime,
+) -> Result<Vec<(&'static str, String)>, anyhow::Error> {
+    let amz_date = now.format(time::macros::format_description!(
+        "[year][month][day]T[hour][minute][second]Z"
+    ))?;
+    let date_stamp = &amz_date[..8];
+
+    // Canonical headers must be sorted by name.
+    let mut canonical_headers =
+        format!("content-type:{AWS_JSON_CONTENT_TYPE}\nhost:{host}\nx-amz-date:{amz_date}\n");
+    let mut signed_headers = String::from("content-type;host;x-amz-date");
+    if let Some(token) = &aws.session_token {
+        canonical_headers.push_str(&format!("x-amz-security-token:{token}\n"));
+        signed_headers.push_str(";x-amz-security-token");
+    }
+    canonical_headers.push_str(&format!("x-amz-target:{target}\n"));
+    signed_headers.push_str(";x-amz-target");
+
+    let canonical_request = format!(
+        "POST\n/\n\n{canonical_headers}\n{signed_headers}\n{}",
+        hex::encode(Sha256::digest(body))
+    );
+    let scope = format!("{date_stamp}/{}/{AWS_SERVICE}/aws4_request", aws.region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = aws_signing_key(&aws.secret_access_key, date_stamp, &aws.region, AWS_SERVICE)?;
+    let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes())?);
+
+    let mut headers = vec![
+        ("X-Amz-Date", amz_date.clone()),
+        (
+            "Authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+                aws.access_key_id
+            ),
+        ),
+    ];
+    if let Some(token) = &aws.session_token {
+        headers.push(("X-Amz-Security-Token", token.clone()));
+    }
+    Ok(headers)
+}
+
+fn aws_signing_key(
+    secret_access_key: &str,
+    date_stamp: &str,
+    region: &str,
+    service: &str,
+) -> Result<Vec<u8>, anyhow::Error> {
+    let k_date = hmac_sha256(
+        format!("AWS4{secret_access_key}").as_bytes(),
+        date_stamp.as_bytes(),
+    )?;
+    let k_region = hmac_sha256(&k_date, region.as_bytes())?;
+    let k_service = hmac_sha256(&k_region, service.as_bytes())?;
+    hmac_sha256(&k_service, b"aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], msg: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key).map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    mac.update(msg);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn validate_vault_path(path: &str) -> Result<(), anyhow::Error> {
+    if path.starts_with('/') {
+        anyhow::bail!("vault path must be relative to /v1/");
+    }
+    if path.contains(['?', '#', '\\']) {
+        anyhow::bail!("vault path must not contain a query or fragment");
+    }
+    if path
+        .split('/')
+        .any(|segment| segment == ".." || segment == ".")
+    {
+        anyhow::bail!("vault path must not contain '.' or '..' segments");
+    }
+    let mount = path.split('/').next().unwrap_or_default();
+    if VAULT_RESERVED_PREFIXES
+        .iter()
+        .any(|reserved| mount.eq_ignore_ascii_case(reserved))
+    {
+        anyhow::bail!("vault path must point at a secret engine, not {mount}/");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ExternalProvider, ExternalSecretProviders, ExternalSecretRef, aws_signing_key,
+        select_field, validate_vault_path, vault_secret_data,
+    };
+
+    #[test]
+    fn vault_paths_are_limited_to_secret_engines() {
+        assert!(validate_vault_path("secret/data/nas").is_ok());
+        assert!(validate_vault_path("kv/team/smtp").is_ok());
+        for path in [
+            "/secret/data/nas",
+            "secret/../auth/token/lookup-self",
+            "auth/token/lookup-self",
+            "sys/mounts",
+            "Identity/entity/id",
+            "cubbyhole/mine",
+            "secret/data/nas?version=1",
+        ] {
+            assert!(
+                validate_vault_path(path).is_err(),
+                "{path} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn aws_signing_key_matches_reference_vector() {
+        // Example from the AWS SigV4 documentation ("Deriving a signing key").
+        let key = aws_signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn vault_kv_v1_and_v2_payloads_are_unwrapped() {
+        let v2 = serde_json::json!({
+            "data": { "data": { "username": "u", "password": "p" }, "metadata": { "version": 3 } }
+        });
+        let v1 = serde_json::json!({ "data": { "username": "u", "password": "p" } });
+        let expected = serde_json::json!({ "username": "u", "password": "p" });
+        assert_eq!(vault_secret_data(v2).unwrap(), expected);
+        assert_eq!(vault_secret_data(v1).unwrap(), expected);
+    }
+
+    #[test]
+    fn field_selects_a_single_value() {
+        let value = serde_json::json!({ "webhook_url": "https://example.invalid/hook", "n": 1 });
+        assert_eq!(
+            select_field(value.clone(), Some("webhook_url")).unwrap(),
+            b"https://example.invalid/hook"
+        );
+        assert_eq!(select_field(value.clone(), Some("n")).unwrap(), b"1");
+        assert!(select_field(value.clone(), Some("missing")).is_err());
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(
+                &select_field(value.clone(), None).unwrap()
+            )
+            .unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn providers_are_configured_from_environment_names() {
+        let providers = ExternalSecretProviders::from_lookup(|key| match key {
+            "VAULT_ADDR" => Some("https://vault.example.invalid/".to_string()),
+            "VAULT_TOKEN" => Some("t".to_string()),
+            "AWS_DEFAULT_REGION" => Some("eu-west-1".to_string()),
+            "AWS_ACCESS_KEY_ID" => Some("AKID".to_string()),
+            _ => None,
+        });
+        assert!(providers.is_configured(ExternalProvider::Vault));
+        // Missing AWS_SECRET_ACCESS_KEY leaves the provider unconfigured.
+        assert!(!providers.is_configured(ExternalProvider::AwsSecretsManager));
+
+        let reference: ExternalSecretRef = serde_json::from_value(serde_json::json!({
+            "provider": "aws_secrets_manager",
+            "path": "prod/nas",
+        }))
+        .unwrap();
+        assert_eq!(reference.provider, ExternalProvider::AwsSecretsManager);
+        assert_eq!(reference.field, None);
+    }
+}
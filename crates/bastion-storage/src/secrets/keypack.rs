@@ -14,6 +14,14 @@ use super::{KEYPACK_AAD, KEYPACK_VERSION, MASTER_KEY_FILE, SecretsCrypto};
 struct KeypackFileV1 {
     version: u32,
     created_at: i64,
+    #[serde(flatten)]
+    sealed: PasswordSealedV1,
+}
+
+/// A payload encrypted with a key derived from a password (Argon2id + XChaCha20-Poly1305), in
+/// the same format as keypack files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordSealedV1 {
     kdf: KeypackKdfV1,
     cipher: KeypackCipherV1,
 }
@@ -34,11 +42,120 @@ struct KeypackCipherV1 {
     ciphertext_b64: String,
 }
 
+impl PasswordSealedV1 {
+    /// Encrypts `plaintext`; `aad` binds the ciphertext to its purpose.
+    pub fn seal(password: &str, aad: &[u8], plaintext: &[u8]) -> Result<Self, anyhow::Error> {
+        if password.is_empty() {
+            anyhow::bail!("password must not be empty");
+        }
+
+        let mut salt = [0_u8; 16];
+        rand::rng().fill_bytes(&mut salt);
+
+        let params =
+            Params::new(64 * 1024, 3, 1, Some(32)).map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let mem_cost_kib = params.m_cost();
+        let time_cost = params.t_cost();
+        let parallelism = params.p_cost();
+        let mut derived = [0_u8; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_bytes(), &salt, &mut derived)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+        let cipher = XChaCha20Poly1305::new((&derived).into());
+        let mut nonce = [0_u8; 24];
+        rand::rng().fill_bytes(&mut nonce);
+        let ciphertext = cipher
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+        Ok(Self {
+            kdf: KeypackKdfV1 {
+                kind: "argon2id".to_string(),
+                salt_b64: base64::engine::general_purpose::STANDARD.encode(salt),
+                mem_cost_kib,
+                time_cost,
+                parallelism,
+            },
+            cipher: KeypackCipherV1 {
+                kind: "xchacha20poly1305".to_string(),
+                nonce_b64: base64::engine::general_purpose::STANDARD.encode(nonce),
+                ciphertext_b64: base64::engine::general_purpose::STANDARD.encode(ciphertext),
+            },
+        })
+    }
+
+    /// Decrypts the payload; a wrong password and a damaged payload fail the same way.
+    pub fn open(&self, password: &str, aad: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        if password.is_empty() {
+            anyhow::bail!("password must not be empty");
+        }
+        if self.kdf.kind != "argon2id" {
+            anyhow::bail!("unsupported keypack kdf");
+        }
+        if self.cipher.kind != "xchacha20poly1305" {
+            anyhow::bail!("unsupported keypack cipher");
+        }
+
+        let salt = base64::engine::general_purpose::STANDARD.decode(&self.kdf.salt_b64)?;
+        let salt: [u8; 16] = salt
+            .try_into()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid salt length"))?;
+
+        let nonce = base64::engine::general_purpose::STANDARD.decode(&self.cipher.nonce_b64)?;
+        let nonce: [u8; 24] = nonce
+            .try_into()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid nonce length"))?;
+
+        let ciphertext =
+            base64::engine::general_purpose::STANDARD.decode(&self.cipher.ciphertext_b64)?;
+
+        let params = Params::new(
+            self.kdf.mem_cost_kib,
+            self.kdf.time_cost,
+            self.kdf.parallelism,
+            Some(32),
+        )
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+        let mut derived = [0_u8; 32];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_bytes(), &salt, &mut derived)
+            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+        let cipher = XChaCha20Poly1305::new((&derived).into());
+        let plaintext = cipher
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid password or keypack")
+            })?;
+        Ok(plaintext)
+    }
+}
+
 pub fn export_keypack(
     data_dir: &Path,
     out_path: &Path,
     password: &str,
 ) -> Result<(), anyhow::Error> {
+    let bytes = export_keypack_bytes(data_dir, password)?;
+    super::io::write_file_atomic(out_path, &bytes)?;
+    Ok(())
+}
+
+/// Encrypts the master keyring with `password` and returns the keypack JSON.
+pub fn export_keypack_bytes(data_dir: &Path, password: &str) -> Result<Vec<u8>, anyhow::Error> {
     if password.is_empty() {
         anyhow::bail!("password must not be empty");
     }
@@ -50,53 +167,13 @@ pub fn export_keypack(
 
     let plaintext = serde_json::to_vec_pretty(&keyring_file)?;
 
-    let mut salt = [0_u8; 16];
-    rand::rng().fill_bytes(&mut salt);
-
-    let params =
-        Params::new(64 * 1024, 3, 1, Some(32)).map_err(|e| anyhow::anyhow!(e.to_string()))?;
-    let mem_cost_kib = params.m_cost();
-    let time_cost = params.t_cost();
-    let parallelism = params.p_cost();
-    let mut derived = [0_u8; 32];
-    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
-        .hash_password_into(password.as_bytes(), &salt, &mut derived)
-        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
-
-    let cipher = XChaCha20Poly1305::new((&derived).into());
-    let mut nonce = [0_u8; 24];
-    rand::rng().fill_bytes(&mut nonce);
-    let ciphertext = cipher
-        .encrypt(
-            XNonce::from_slice(&nonce),
-            Payload {
-                msg: &plaintext,
-                aad: KEYPACK_AAD,
-            },
-        )
-        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
-
     let pack = KeypackFileV1 {
         version: KEYPACK_VERSION,
         created_at: OffsetDateTime::now_utc().unix_timestamp(),
-        kdf: KeypackKdfV1 {
-            kind: "argon2id".to_string(),
-            salt_b64: base64::engine::general_purpose::STANDARD.encode(salt),
-            mem_cost_kib,
-            time_cost,
-            parallelism,
-        },
-        cipher: KeypackCipherV1 {
-            kind: "xchacha20poly1305".to_string(),
-            nonce_b64: base64::engine::general_purpose::STANDARD.encode(nonce),
-            ciphertext_b64: base64::engine::general_purpose::STANDARD.encode(ciphertext),
-        },
+        sealed: PasswordSealedV1::seal(password, KEYPACK_AAD, &plaintext)?,
     };
 
-    let bytes = serde_json::to_vec_pretty(&pack)?;
-    super::io::write_file_atomic(out_path, &bytes)?;
-
-    Ok(())
+    Ok(serde_json::to_vec_pretty(&pack)?)
 }
 
 pub fn import_keypack(
@@ -104,58 +181,30 @@ pub fn import_keypack(
     in_path: &Path,
     password: &str,
     force: bool,
+) -> Result<(), anyhow::Error> {
+    let in_bytes = fs::read(in_path)?;
+    import_keypack_bytes(data_dir, &in_bytes, password, force)
+}
+
+/// Decrypts keypack JSON with `password` and installs it as the master keyring.
+///
+/// The running process keeps using the keyring it loaded at startup; the imported one takes
+/// effect on the next start.
+pub fn import_keypack_bytes(
+    data_dir: &Path,
+    in_bytes: &[u8],
+    password: &str,
+    force: bool,
 ) -> Result<(), anyhow::Error> {
     if password.is_empty() {
         anyhow::bail!("password must not be empty");
     }
 
-    let in_bytes = fs::read(in_path)?;
-    let pack: KeypackFileV1 = serde_json::from_slice(&in_bytes)?;
+    let pack: KeypackFileV1 = serde_json::from_slice(in_bytes)?;
     if pack.version != KEYPACK_VERSION {
         anyhow::bail!("unsupported keypack version");
     }
-    if pack.kdf.kind != "argon2id" {
-        anyhow::bail!("unsupported keypack kdf");
-    }
-    if pack.cipher.kind != "xchacha20poly1305" {
-        anyhow::bail!("unsupported keypack cipher");
-    }
-
-    let salt = base64::engine::general_purpose::STANDARD.decode(&pack.kdf.salt_b64)?;
-    let salt: [u8; 16] = salt
-        .try_into()
-        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid salt length"))?;
-
-    let nonce = base64::engine::general_purpose::STANDARD.decode(&pack.cipher.nonce_b64)?;
-    let nonce: [u8; 24] = nonce
-        .try_into()
-        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid nonce length"))?;
-
-    let ciphertext =
-        base64::engine::general_purpose::STANDARD.decode(&pack.cipher.ciphertext_b64)?;
-
-    let params = Params::new(
-        pack.kdf.mem_cost_kib,
-        pack.kdf.time_cost,
-        pack.kdf.parallelism,
-        Some(32),
-    )
-    .map_err(|e| anyhow::anyhow!(e.to_string()))?;
-    let mut derived = [0_u8; 32];
-    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
-        .hash_password_into(password.as_bytes(), &salt, &mut derived)
-        .map_err(|e| anyhow::anyhow!(e.to_string()))?;
-
-    let cipher = XChaCha20Poly1305::new((&derived).into());
-    let plaintext = cipher
-        .decrypt(
-            XNonce::from_slice(&nonce),
-            Payload {
-                msg: &ciphertext,
-                aad: KEYPACK_AAD,
-            },
-        )
-        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "invalid password or keypack"))?;
+    let plaintext = pack.sealed.open(password, KEYPACK_AAD)?;
 
     let keyring: super::keyring::KeyringFile = serde_json::from_slice(&plaintext)?;
     super::keyring::validate_keyring(&keyring)?;
@@ -101,6 +101,16 @@ pub(super) fn validate_keyring(keyring: &KeyringFile) -> Result<(), anyhow::Erro
     Ok(())
 }
 
+/// Returns a hex fingerprint of the master keyring that changes whenever keys are added,
+/// replaced, or the active key changes.
+pub fn keyring_fingerprint(data_dir: &Path) -> Result<String, anyhow::Error> {
+    use sha2::Digest as _;
+
+    let keyring = read_keyring(&data_dir.join(MASTER_KEY_FILE))?;
+    let canonical = serde_json::to_vec(&keyring)?;
+    Ok(hex::encode(sha2::Sha256::digest(&canonical)))
+}
+
 pub fn rotate_master_key(data_dir: &Path) -> Result<KeyRotationResult, anyhow::Error> {
     let path = data_dir.join(MASTER_KEY_FILE);
     let mut keyring = if path.exists() {
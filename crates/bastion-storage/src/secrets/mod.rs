@@ -6,13 +6,17 @@ const KEYPACK_VERSION: u32 = 1;
 const KEYPACK_AAD: &[u8] = b"bastion-keypack-v1";
 
 mod crypto;
+mod external;
 mod io;
 mod keypack;
 mod keyring;
 
 pub use crypto::{EncryptedSecret, SecretsCrypto};
-pub use keypack::{export_keypack, import_keypack};
-pub use keyring::{KeyRotationResult, rotate_master_key};
+pub use external::{ExternalProvider, ExternalSecretProviders, ExternalSecretRef, ProviderHealth};
+pub use keypack::{
+    PasswordSealedV1, export_keypack, export_keypack_bytes, import_keypack, import_keypack_bytes,
+};
+pub use keyring::{KeyRotationResult, keyring_fingerprint, rotate_master_key};
 
 #[cfg(test)]
 mod tests;
@@ -1,6 +1,9 @@
 use tempfile::TempDir;
 
-use super::{EncryptedSecret, SecretsCrypto, export_keypack, import_keypack, rotate_master_key};
+use super::{
+    EncryptedSecret, SecretsCrypto, export_keypack, import_keypack, keyring_fingerprint,
+    rotate_master_key,
+};
 
 #[test]
 fn keypack_round_trip() {
@@ -70,3 +73,17 @@ fn rotate_preserves_old_keys() {
         .unwrap();
     assert_eq!(plain, b"secret");
 }
+
+#[test]
+fn keyring_fingerprint_changes_on_rotation_only() {
+    let temp = TempDir::new().unwrap();
+    let data_dir = temp.path();
+
+    let _ = SecretsCrypto::load_or_create(data_dir).unwrap();
+    let first = keyring_fingerprint(data_dir).unwrap();
+    let _ = SecretsCrypto::load_or_create(data_dir).unwrap();
+    assert_eq!(keyring_fingerprint(data_dir).unwrap(), first);
+
+    rotate_master_key(data_dir).unwrap();
+    assert_ne!(keyring_fingerprint(data_dir).unwrap(), first);
+}
@@ -4,7 +4,7 @@ use uuid::Uuid;
 
 use bastion_core::HUB_NODE_ID;
 
-use crate::secrets::{EncryptedSecret, SecretsCrypto};
+use crate::secrets::{EncryptedSecret, ExternalSecretRef, SecretsCrypto};
 
 #[derive(Debug, Clone)]
 pub struct SecretListItem {
@@ -37,6 +37,7 @@ pub async fn upsert_secret(
           kid = excluded.kid,
           nonce = excluded.nonce,
           ciphertext = excluded.ciphertext,
+          external_ref = NULL,
           updated_at = excluded.updated_at
         "#,
     )
@@ -63,7 +64,7 @@ pub async fn get_secret(
     name: &str,
 ) -> Result<Option<Vec<u8>>, anyhow::Error> {
     let row = sqlx::query(
-        "SELECT kid, nonce, ciphertext FROM secrets WHERE node_id = ? AND kind = ? AND name = ? LIMIT 1",
+        "SELECT kid, nonce, ciphertext, external_ref FROM secrets WHERE node_id = ? AND kind = ? AND name = ? LIMIT 1",
     )
     .bind(node_id)
     .bind(kind)
@@ -75,6 +76,11 @@ pub async fn get_secret(
         return Ok(None);
     };
 
+    if let Some(external_ref) = row.get::<Option<String>, _>("external_ref") {
+        let external_ref: ExternalSecretRef = serde_json::from_str(&external_ref)?;
+        return Ok(Some(crypto.resolve_external(&external_ref).await?));
+    }
+
     let kid = row.get::<i64, _>("kid");
     let nonce = row.get::<Vec<u8>, _>("nonce");
     let ciphertext = row.get::<Vec<u8>, _>("ciphertext");
@@ -93,6 +99,69 @@ pub async fn get_secret(
     Ok(Some(plaintext))
 }
 
+/// Stores a secret as a reference to an external provider; the value is fetched on use.
+pub async fn upsert_external_secret(
+    db: &SqlitePool,
+    node_id: &str,
+    kind: &str,
+    name: &str,
+    external_ref: &ExternalSecretRef,
+) -> Result<(), anyhow::Error> {
+    let node_id = node_id.trim();
+    if node_id.is_empty() {
+        anyhow::bail!("node_id is required");
+    }
+
+    let external_ref = serde_json::to_string(external_ref)?;
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO secrets (id, node_id, kind, name, kid, nonce, ciphertext, external_ref, created_at, updated_at)
+        VALUES (?, ?, ?, ?, 0, X'', X'', ?, ?, ?)
+        ON CONFLICT(kind, node_id, name) DO UPDATE SET
+          kid = excluded.kid,
+          nonce = excluded.nonce,
+          ciphertext = excluded.ciphertext,
+          external_ref = excluded.external_ref,
+          updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(id)
+    .bind(node_id)
+    .bind(kind)
+    .bind(name)
+    .bind(external_ref)
+    .bind(now)
+    .bind(now)
+    .execute(db)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_external_ref(
+    db: &SqlitePool,
+    node_id: &str,
+    kind: &str,
+    name: &str,
+) -> Result<Option<ExternalSecretRef>, anyhow::Error> {
+    let raw = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT external_ref FROM secrets WHERE node_id = ? AND kind = ? AND name = ? LIMIT 1",
+    )
+    .bind(node_id)
+    .bind(kind)
+    .bind(name)
+    .fetch_optional(db)
+    .await?
+    .flatten();
+
+    raw.map(|v| serde_json::from_str(&v))
+        .transpose()
+        .map_err(Into::into)
+}
+
 pub async fn list_secrets(
     db: &SqlitePool,
     node_id: &str,
@@ -162,14 +231,40 @@ pub async fn get_secret_hub(
     get_secret(db, crypto, HUB_NODE_ID, kind, name).await
 }
 
+/// Lists `(node_id, kind, name)` of every secret stored as local ciphertext (external references
+/// are skipped).
+pub async fn list_encrypted_secret_keys(
+    db: &SqlitePool,
+) -> Result<Vec<(String, String, String)>, anyhow::Error> {
+    let rows = sqlx::query(
+        "SELECT node_id, kind, name FROM secrets WHERE external_ref IS NULL ORDER BY node_id, kind, name",
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| {
+            (
+                r.get::<String, _>("node_id"),
+                r.get::<String, _>("kind"),
+                r.get::<String, _>("name"),
+            )
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::TempDir;
 
     use crate::db;
-    use crate::secrets::SecretsCrypto;
+    use crate::secrets::{ExternalProvider, ExternalSecretRef, SecretsCrypto};
 
-    use super::{delete_secret, get_secret, list_secrets, upsert_secret};
+    use super::{
+        delete_secret, get_external_ref, get_secret, list_secrets, upsert_external_secret,
+        upsert_secret,
+    };
 
     #[tokio::test]
     async fn secrets_round_trip() {
@@ -209,4 +304,51 @@ mod tests {
             .expect("get missing");
         assert!(missing.is_none());
     }
+
+    #[tokio::test]
+    async fn external_secret_reference_replaces_and_is_replaced_by_ciphertext() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        let crypto = SecretsCrypto::load_or_create(temp.path()).expect("crypto");
+
+        upsert_secret(&pool, &crypto, "hub", "smtp", "ops", b"local")
+            .await
+            .expect("upsert");
+
+        let reference = ExternalSecretRef {
+            provider: ExternalProvider::Vault,
+            path: "secret/data/smtp".to_string(),
+            field: Some("password".to_string()),
+        };
+        upsert_external_secret(&pool, "hub", "smtp", "ops", &reference)
+            .await
+            .expect("upsert external");
+        assert_eq!(
+            get_external_ref(&pool, "hub", "smtp", "ops")
+                .await
+                .expect("get ref"),
+            Some(reference)
+        );
+        // No provider is enabled on this crypto instance, so resolving must fail loudly.
+        assert!(
+            get_secret(&pool, &crypto, "hub", "smtp", "ops")
+                .await
+                .is_err()
+        );
+
+        upsert_secret(&pool, &crypto, "hub", "smtp", "ops", b"local2")
+            .await
+            .expect("upsert local again");
+        assert_eq!(
+            get_external_ref(&pool, "hub", "smtp", "ops")
+                .await
+                .expect("get ref"),
+            None
+        );
+        let v = get_secret(&pool, &crypto, "hub", "smtp", "ops")
+            .await
+            .expect("get")
+            .expect("present");
+        assert_eq!(v, b"local2");
+    }
 }
@@ -0,0 +1,266 @@
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+
+/// A job whose latest successful run is older than its `max_success_interval_seconds`.
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleJob {
+    pub job_id: String,
+    pub job_name: String,
+    pub agent_id: Option<String>,
+    pub max_success_interval_seconds: u32,
+    /// `None` when the job never succeeded; the interval then counts from job creation.
+    pub last_success_at: Option<i64>,
+    /// When the job became stale (reference time + interval).
+    pub stale_since: i64,
+    pub overdue_seconds: i64,
+    /// Failed run recorded when the stale episode was first detected.
+    pub alert_run_id: Option<String>,
+    pub alerted_at: Option<i64>,
+}
+
+/// Lists active jobs with a freshness interval that have no successful run within it, most
+/// overdue first.
+pub async fn list_stale_jobs(db: &SqlitePool, now: i64) -> Result<Vec<StaleJob>, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          j.id, j.name, j.agent_id, j.max_success_interval_seconds, j.created_at,
+          (
+            SELECT MAX(COALESCE(r.ended_at, r.started_at))
+            FROM runs r
+            WHERE r.job_id = j.id AND r.status = 'success'
+          ) AS last_success_at,
+          a.run_id AS alert_run_id,
+          a.detected_at AS alerted_at
+        FROM jobs j
+        LEFT JOIN stale_backup_alerts a ON a.job_id = j.id
+        WHERE j.archived_at IS NULL AND j.max_success_interval_seconds > 0
+        "#,
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut out = Vec::new();
+    for row in rows {
+        let interval =
+            u32::try_from(row.get::<i64, _>("max_success_interval_seconds")).unwrap_or(0);
+        let last_success_at = row.get::<Option<i64>, _>("last_success_at");
+        let reference = last_success_at.unwrap_or_else(|| row.get::<i64, _>("created_at"));
+        let stale_since = reference.saturating_add(i64::from(interval));
+        if now <= stale_since {
+            continue;
+        }
+
+        out.push(StaleJob {
+            job_id: row.get::<String, _>("id"),
+            job_name: row.get::<String, _>("name"),
+            agent_id: row.get::<Option<String>, _>("agent_id"),
+            max_success_interval_seconds: interval,
+            last_success_at,
+            stale_since,
+            overdue_seconds: now - stale_since,
+            alert_run_id: row.get::<Option<String>, _>("alert_run_id"),
+            alerted_at: row.get::<Option<i64>, _>("alerted_at"),
+        });
+    }
+    out.sort_by(|a, b| {
+        b.overdue_seconds
+            .cmp(&a.overdue_seconds)
+            .then_with(|| a.job_id.cmp(&b.job_id))
+    });
+    Ok(out)
+}
+
+/// Opens a stale episode for the job; returns `false` when one is already open.
+pub async fn claim_alert(
+    db: &SqlitePool,
+    job_id: &str,
+    last_success_at: Option<i64>,
+    now: i64,
+) -> Result<bool, anyhow::Error> {
+    let result = sqlx::query(
+        "INSERT OR IGNORE INTO stale_backup_alerts (job_id, last_success_at, detected_at) VALUES (?, ?, ?)",
+    )
+    .bind(job_id)
+    .bind(last_success_at)
+    .bind(now)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn set_alert_run(
+    db: &SqlitePool,
+    job_id: &str,
+    run_id: &str,
+) -> Result<(), anyhow::Error> {
+    sqlx::query("UPDATE stale_backup_alerts SET run_id = ? WHERE job_id = ?")
+        .bind(run_id)
+        .bind(job_id)
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// Closes the stale episodes of every job not in `stale_job_ids` (fresh again, archived, or
+/// no longer monitored).
+pub async fn resolve_alerts_except(
+    db: &SqlitePool,
+    stale_job_ids: &[&str],
+) -> Result<u64, anyhow::Error> {
+    let ids = serde_json::to_string(stale_job_ids)?;
+    let result = sqlx::query(
+        "DELETE FROM stale_backup_alerts WHERE job_id NOT IN (SELECT value FROM json_each(?))",
+    )
+    .bind(ids)
+    .execute(db)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::db;
+    use crate::jobs_repo::{self, OverlapPolicy};
+
+    use super::{claim_alert, list_stale_jobs, resolve_alerts_except, set_alert_run};
+
+    const HOUR: i64 = 60 * 60;
+
+    async fn insert_run(
+        pool: &sqlx::SqlitePool,
+        run_id: &str,
+        job_id: &str,
+        status: &str,
+        at: i64,
+    ) {
+        sqlx::query(
+            "INSERT INTO runs (id, job_id, status, started_at, ended_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(run_id)
+        .bind(job_id)
+        .bind(status)
+        .bind(at - 60)
+        .bind(at)
+        .execute(pool)
+        .await
+        .expect("insert run");
+    }
+
+    #[tokio::test]
+    async fn jobs_without_a_recent_success_are_stale_once_per_episode() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let spec = serde_json::json!({"v": 1, "type": "filesystem"});
+        let monitored = jobs_repo::create_job(
+            &pool,
+            "monitored",
+            None,
+            None,
+            Some("UTC"),
+            OverlapPolicy::Queue,
+            spec.clone(),
+        )
+        .await
+        .unwrap();
+        let unmonitored = jobs_repo::create_job(
+            &pool,
+            "unmonitored",
+            None,
+            None,
+            Some("UTC"),
+            OverlapPolicy::Queue,
+            spec,
+        )
+        .await
+        .unwrap();
+        jobs_repo::set_job_max_success_interval(&pool, &monitored.id, (26 * HOUR) as u32)
+            .await
+            .unwrap();
+
+        let now = monitored.created_at + 100 * HOUR;
+        insert_run(&pool, "ok", &monitored.id, "success", now - 30 * HOUR).await;
+        insert_run(&pool, "bad", &monitored.id, "failed", now - HOUR).await;
+        insert_run(&pool, "other", &unmonitored.id, "failed", now - HOUR).await;
+
+        let stale = list_stale_jobs(&pool, now).await.unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].job_id, monitored.id);
+        assert_eq!(stale[0].last_success_at, Some(now - 30 * HOUR));
+        assert_eq!(stale[0].overdue_seconds, 4 * HOUR);
+        assert!(stale[0].alerted_at.is_none());
+
+        assert!(
+            claim_alert(&pool, &monitored.id, stale[0].last_success_at, now)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !claim_alert(&pool, &monitored.id, stale[0].last_success_at, now)
+                .await
+                .unwrap()
+        );
+        set_alert_run(&pool, &monitored.id, "bad").await.unwrap();
+        let stale = list_stale_jobs(&pool, now).await.unwrap();
+        assert_eq!(stale[0].alert_run_id.as_deref(), Some("bad"));
+        assert_eq!(stale[0].alerted_at, Some(now));
+
+        // A fresh success ends the episode.
+        insert_run(&pool, "ok2", &monitored.id, "success", now).await;
+        let stale = list_stale_jobs(&pool, now + 1).await.unwrap();
+        assert!(stale.is_empty());
+        assert_eq!(resolve_alerts_except(&pool, &[]).await.unwrap(), 1);
+        assert!(
+            claim_alert(&pool, &monitored.id, Some(now), now + 1)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn never_succeeded_jobs_count_from_creation() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "new",
+            None,
+            None,
+            Some("UTC"),
+            OverlapPolicy::Queue,
+            serde_json::json!({"v": 1, "type": "filesystem"}),
+        )
+        .await
+        .unwrap();
+        jobs_repo::set_job_max_success_interval(&pool, &job.id, HOUR as u32)
+            .await
+            .unwrap();
+
+        assert!(
+            list_stale_jobs(&pool, job.created_at + HOUR)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let stale = list_stale_jobs(&pool, job.created_at + HOUR + 1)
+            .await
+            .unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].last_success_at, None);
+        assert_eq!(stale[0].stale_since, job.created_at + HOUR);
+
+        claim_alert(&pool, &job.id, None, job.created_at + HOUR + 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            resolve_alerts_except(&pool, &[job.id.as_str()])
+                .await
+                .unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageUsageRow {
+    pub job_id: String,
+    pub job_name: Option<String>,
+    pub node_id: String,
+    pub target_type: String,
+    /// WebDAV base URL or local base directory recorded in the run-time target snapshot.
+    pub target_key: String,
+    pub snapshots: u64,
+    pub pinned_snapshots: u64,
+    pub source_bytes: u64,
+    pub transfer_bytes: u64,
+    pub oldest_ended_at: Option<i64>,
+    pub newest_ended_at: Option<i64>,
+    pub refreshed_at: i64,
+}
+
+/// Rebuilds the storage usage rollup from snapshot records.
+///
+/// Snapshots count as stored until their artifacts are confirmed gone (`deleted` / `missing`),
+/// so in-progress or failed deletions still show up as consumed space.
+pub async fn refresh_storage_usage(db: &SqlitePool, now: i64) -> Result<u64, anyhow::Error> {
+    let mut tx = db.begin().await?;
+
+    sqlx::query("DELETE FROM storage_usage")
+        .execute(&mut *tx)
+        .await?;
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO storage_usage (
+          job_id, node_id, target_type, target_key,
+          snapshots, pinned_snapshots, source_bytes, transfer_bytes,
+          oldest_ended_at, newest_ended_at, refreshed_at
+        )
+        SELECT
+          job_id,
+          node_id,
+          target_type,
+          COALESCE(
+            json_extract(target_snapshot_json, '$.target.base_url'),
+            json_extract(target_snapshot_json, '$.target.base_dir'),
+            ''
+          ) AS target_key,
+          COUNT(1),
+          COALESCE(SUM(CASE WHEN pinned_at IS NOT NULL THEN 1 ELSE 0 END), 0),
+          COALESCE(SUM(COALESCE(source_bytes, 0)), 0),
+          COALESCE(SUM(COALESCE(transfer_bytes, 0)), 0),
+          MIN(ended_at),
+          MAX(ended_at),
+          ?
+        FROM run_artifacts
+        WHERE status NOT IN ('deleted', 'missing')
+        GROUP BY job_id, node_id, target_type, target_key
+        "#,
+    )
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(result.rows_affected())
+}
+
+pub async fn list_storage_usage(db: &SqlitePool) -> Result<Vec<StorageUsageRow>, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+          u.job_id, j.name AS job_name, u.node_id, u.target_type, u.target_key,
+          u.snapshots, u.pinned_snapshots, u.source_bytes, u.transfer_bytes,
+          u.oldest_ended_at, u.newest_ended_at, u.refreshed_at
+        FROM storage_usage u
+        LEFT JOIN jobs j ON j.id = u.job_id
+        ORDER BY u.transfer_bytes DESC, u.job_id ASC, u.target_key ASC
+        "#,
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut out = Vec::with_capacity(rows.len());
+    for row in rows {
+        out.push(StorageUsageRow {
+            job_id: row.get::<String, _>("job_id"),
+            job_name: row.get::<Option<String>, _>("job_name"),
+            node_id: row.get::<String, _>("node_id"),
+            target_type: row.get::<String, _>("target_type"),
+            target_key: row.get::<String, _>("target_key"),
+            snapshots: row.get::<i64, _>("snapshots").max(0) as u64,
+            pinned_snapshots: row.get::<i64, _>("pinned_snapshots").max(0) as u64,
+            source_bytes: row.get::<i64, _>("source_bytes").max(0) as u64,
+            transfer_bytes: row.get::<i64, _>("transfer_bytes").max(0) as u64,
+            oldest_ended_at: row.get::<Option<i64>, _>("oldest_ended_at"),
+            newest_ended_at: row.get::<Option<i64>, _>("newest_ended_at"),
+            refreshed_at: row.get::<i64, _>("refreshed_at"),
+        });
+    }
+    Ok(out)
+}
+
+pub async fn last_refreshed_at(db: &SqlitePool) -> Result<Option<i64>, anyhow::Error> {
+    let row = sqlx::query("SELECT MAX(refreshed_at) AS refreshed_at FROM storage_usage")
+        .fetch_one(db)
+        .await?;
+    Ok(row.get::<Option<i64>, _>("refreshed_at"))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::db;
+    use crate::jobs_repo::{self, OverlapPolicy};
+
+    use super::{last_refreshed_at, list_storage_usage, refresh_storage_usage};
+
+    async fn insert_artifact(
+        pool: &sqlx::SqlitePool,
+        run_id: &str,
+        job_id: &str,
+        base_dir: &str,
+        status: &str,
+        transfer_bytes: i64,
+    ) {
+        sqlx::query(
+            "INSERT INTO runs (id, job_id, status, started_at, ended_at) VALUES (?, ?, 'success', 1, 2)",
+        )
+        .bind(run_id)
+        .bind(job_id)
+        .execute(pool)
+        .await
+        .expect("insert run");
+
+        sqlx::query(
+            r#"
+            INSERT INTO run_artifacts (
+              run_id, job_id, node_id, target_type, target_snapshot_json,
+              artifact_format, status, started_at, ended_at,
+              source_bytes, transfer_bytes, created_at, updated_at
+            ) VALUES (?, ?, 'hub', 'local_dir', ?, 'archive_v1', ?, 1, 2, ?, ?, 1, 1)
+            "#,
+        )
+        .bind(run_id)
+        .bind(job_id)
+        .bind(
+            serde_json::json!({
+                "node_id": "hub",
+                "target": { "type": "local_dir", "base_dir": base_dir }
+            })
+            .to_string(),
+        )
+        .bind(status)
+        .bind(transfer_bytes * 2)
+        .bind(transfer_bytes)
+        .execute(pool)
+        .await
+        .expect("insert artifact");
+    }
+
+    #[tokio::test]
+    async fn refresh_aggregates_present_snapshots_per_job_and_target() {
+        let tmp = TempDir::new().unwrap();
+        let pool = db::init(tmp.path()).await.unwrap();
+
+        let job = jobs_repo::create_job(
+            &pool,
+            "job",
+            None,
+            None,
+            Some("UTC"),
+            OverlapPolicy::Queue,
+            serde_json::json!({
+                "v": 1,
+                "type": "filesystem",
+                "source": { "root": "/" },
+                "target": { "type": "local_dir", "base_dir": "/a" }
+            }),
+        )
+        .await
+        .unwrap();
+
+        insert_artifact(&pool, "r1", &job.id, "/a", "present", 100).await;
+        insert_artifact(&pool, "r2", &job.id, "/a", "present", 50).await;
+        insert_artifact(&pool, "r3", &job.id, "/b", "present", 7).await;
+        insert_artifact(&pool, "r4", &job.id, "/a", "deleted", 1000).await;
+
+        assert_eq!(last_refreshed_at(&pool).await.unwrap(), None);
+        assert_eq!(refresh_storage_usage(&pool, 42).await.unwrap(), 2);
+        assert_eq!(last_refreshed_at(&pool).await.unwrap(), Some(42));
+
+        let rows = list_storage_usage(&pool).await.unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].target_key, "/a");
+        assert_eq!(rows[0].job_name.as_deref(), Some("job"));
+        assert_eq!(rows[0].snapshots, 2);
+        assert_eq!(rows[0].transfer_bytes, 150);
+        assert_eq!(rows[0].source_bytes, 300);
+        assert_eq!(rows[1].target_key, "/b");
+        assert_eq!(rows[1].transfer_bytes, 7);
+
+        // Refresh replaces previous rows instead of accumulating.
+        assert_eq!(refresh_storage_usage(&pool, 43).await.unwrap(), 2);
+        assert_eq!(list_storage_usage(&pool).await.unwrap()[0].snapshots, 2);
+    }
+}
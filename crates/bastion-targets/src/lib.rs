@@ -8,6 +8,16 @@ pub struct StoreRunProgress {
     pub bytes_total: Option<u64>,
 }
 
+/// A payload part handed to a target while it is still being produced.
+///
+/// The part is complete once the sender side of `chunks` is dropped. An `Err` chunk means the
+/// producer failed mid-part, and the target must not keep the partial part.
+#[derive(Debug)]
+pub struct StreamedPart {
+    pub name: String,
+    pub chunks: tokio::sync::mpsc::Receiver<std::io::Result<Vec<u8>>>,
+}
+
 pub use webdav_client::{
     WebdavClient, WebdavCredentials, WebdavHttpError, WebdavNotDirectoryError, WebdavPropfindEntry,
     WebdavPutDiagnostic, WebdavPutError, WebdavPutErrorKind, WebdavRequestLimits,
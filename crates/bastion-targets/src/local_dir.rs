@@ -7,7 +7,7 @@ use bastion_core::backup_format::{
 };
 use bastion_core::manifest::{ArtifactFormatV1, ManifestV1};
 
-use crate::StoreRunProgress;
+use crate::{StoreRunProgress, StreamedPart};
 
 /// Store `payload.part*` files as they are finalized, deleting the local part file after it has been
 /// successfully written (or skipped via resumability-by-size).
@@ -35,6 +35,42 @@ pub fn store_run_parts_rolling(
     Ok(run_dir)
 }
 
+/// Store `payload.part*` files straight from the archive builder, without local part files.
+///
+/// Each part is written to a `.partial` file and renamed into place once its last chunk arrives,
+/// so an aborted part never looks complete to the final store step.
+pub fn store_run_parts_streaming(
+    base_dir: &Path,
+    job_id: &str,
+    run_id: &str,
+    mut parts_rx: tokio::sync::mpsc::Receiver<StreamedPart>,
+) -> Result<PathBuf, anyhow::Error> {
+    use std::io::Write as _;
+
+    let run_dir = base_dir.join(job_id).join(run_id);
+    std::fs::create_dir_all(&run_dir)?;
+
+    while let Some(mut part) = parts_rx.blocking_recv() {
+        let dst = run_dir.join(&part.name);
+        let tmp = run_dir.join(format!("{}.partial", part.name));
+        let mut file = std::fs::File::create(&tmp)?;
+        while let Some(chunk) = part.chunks.blocking_recv() {
+            let written = chunk.and_then(|buf| file.write_all(&buf));
+            if let Err(error) = written {
+                drop(file);
+                let _ = std::fs::remove_file(&tmp);
+                return Err(error.into());
+            }
+        }
+        file.sync_all()?;
+        drop(file);
+        std::fs::rename(&tmp, &dst)?;
+        debug!(dst = %dst.display(), "stored streamed part");
+    }
+
+    Ok(run_dir)
+}
+
 pub fn store_run(
     base_dir: &Path,
     job_id: &str,
@@ -151,7 +187,9 @@ pub fn store_run(
     Ok(run_dir)
 }
 
-fn copy_if_needed(src: &Path, dst: &Path, expected_size: u64) -> Result<(), anyhow::Error> {
+/// Copies `src` to `dst` through a `.partial` file; skips files that already have `expected_size`
+/// bytes.
+pub fn copy_if_needed(src: &Path, dst: &Path, expected_size: u64) -> Result<(), anyhow::Error> {
     if let Ok(meta) = std::fs::metadata(dst)
         && meta.len() == expected_size
     {
@@ -253,6 +291,184 @@ fn copy_dir_recursive_if_needed(
     Ok(())
 }
 
+/// Files of a new raw-tree snapshot that were replaced by hardlinks into an earlier snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkUnchangedStats {
+    pub previous_run_id: String,
+    pub linked_files: u64,
+    pub linked_bytes: u64,
+}
+
+#[derive(serde::Deserialize)]
+struct LinkEntryRecord {
+    path: String,
+    kind: String,
+    size: u64,
+    #[serde(default)]
+    hash: Option<String>,
+}
+
+/// Hardlinks unchanged `data/` files of a stored raw-tree run against the previous snapshot of the
+/// same job (rsync `--link-dest` style), so every run stays a browsable full tree while only
+/// changed files take new space.
+///
+/// Files count as unchanged when path, size and content hash match in both entries indexes. The
+/// previous snapshot is the complete raw-tree run with the latest `ended_at`. Returns `None` for
+/// non raw-tree runs or when there is no previous snapshot. Files that can't be linked (for
+/// example across filesystems) keep their copy.
+pub fn link_unchanged_from_previous(
+    base_dir: &Path,
+    job_id: &str,
+    run_id: &str,
+) -> Result<Option<LinkUnchangedStats>, anyhow::Error> {
+    let job_dir = base_dir.join(job_id);
+    let run_dir = job_dir.join(run_id);
+    if read_raw_tree_ended_at(&run_dir).is_none() {
+        return Ok(None);
+    }
+
+    let mut previous: Option<(String, String)> = None;
+    for entry in std::fs::read_dir(&job_dir)? {
+        let entry = entry?;
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if name == run_id || !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let dir = entry.path();
+        if !dir.join(COMPLETE_NAME).is_file() {
+            continue;
+        }
+        let Some(ended_at) = read_raw_tree_ended_at(&dir) else {
+            continue;
+        };
+        if previous.as_ref().is_none_or(|(_, best)| ended_at > *best) {
+            previous = Some((name, ended_at));
+        }
+    }
+    let Some((previous_run_id, _)) = previous else {
+        return Ok(None);
+    };
+
+    let previous_dir = job_dir.join(&previous_run_id);
+    let mut previous_files = std::collections::HashMap::<String, (u64, String)>::new();
+    for_each_file_entry(&previous_dir.join(ENTRIES_INDEX_NAME), |rec, hash| {
+        previous_files.insert(rec.path, (rec.size, hash));
+        Ok(())
+    })?;
+
+    let mut stats = LinkUnchangedStats {
+        previous_run_id,
+        linked_files: 0,
+        linked_bytes: 0,
+    };
+    for_each_file_entry(&run_dir.join(ENTRIES_INDEX_NAME), |rec, hash| {
+        if previous_files.get(&rec.path) != Some(&(rec.size, hash)) {
+            return Ok(());
+        }
+        let src = raw_tree_data_path(&previous_dir, &rec.path);
+        let dst = raw_tree_data_path(&run_dir, &rec.path);
+        match replace_with_hard_link(&src, &dst, rec.size) {
+            Ok(true) => {
+                stats.linked_files = stats.linked_files.saturating_add(1);
+                stats.linked_bytes = stats.linked_bytes.saturating_add(rec.size);
+            }
+            Ok(false) => {}
+            Err(error) => {
+                debug!(
+                    src = %src.display(),
+                    dst = %dst.display(),
+                    error = %error,
+                    "keeping copied raw-tree file"
+                );
+            }
+        }
+        Ok(())
+    })?;
+
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        previous_run_id = %stats.previous_run_id,
+        linked_files = stats.linked_files,
+        linked_bytes = stats.linked_bytes,
+        "linked unchanged raw-tree files against previous snapshot"
+    );
+    Ok(Some(stats))
+}
+
+fn read_raw_tree_ended_at(run_dir: &Path) -> Option<String> {
+    let bytes = std::fs::read(run_dir.join(MANIFEST_NAME)).ok()?;
+    let manifest: ManifestV1 = serde_json::from_slice(&bytes).ok()?;
+    (manifest.pipeline.format == ArtifactFormatV1::RawTreeV1).then_some(manifest.ended_at)
+}
+
+fn for_each_file_entry(
+    entries_index_path: &Path,
+    mut f: impl FnMut(LinkEntryRecord, String) -> Result<(), anyhow::Error>,
+) -> Result<(), anyhow::Error> {
+    use std::io::BufRead as _;
+
+    let file = std::fs::File::open(entries_index_path)?;
+    let reader = std::io::BufReader::new(zstd::Decoder::new(file)?);
+    for line in reader.lines() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let mut rec: LinkEntryRecord = serde_json::from_str(trimmed)?;
+        if rec.kind != "file" {
+            continue;
+        }
+        if let Some(hash) = rec.hash.take() {
+            f(rec, hash)?;
+        }
+    }
+    Ok(())
+}
+
+fn raw_tree_data_path(run_dir: &Path, archive_path: &str) -> PathBuf {
+    let mut out = run_dir.join("data");
+    for seg in archive_path.split('/').map(str::trim) {
+        if !seg.is_empty() {
+            out.push(seg);
+        }
+    }
+    out
+}
+
+/// Atomically replaces `dst` with a hardlink to `src`. Returns `false` if either file is missing
+/// or their sizes differ, or if both already share an inode.
+fn replace_with_hard_link(src: &Path, dst: &Path, size: u64) -> Result<bool, std::io::Error> {
+    let (Ok(src_meta), Ok(dst_meta)) = (std::fs::metadata(src), std::fs::metadata(dst)) else {
+        return Ok(false);
+    };
+    if !src_meta.is_file() || src_meta.len() != size || dst_meta.len() != size {
+        return Ok(false);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt as _;
+        if src_meta.dev() == dst_meta.dev() && src_meta.ino() == dst_meta.ino() {
+            return Ok(false);
+        }
+    }
+
+    let Some(file_name) = dst.file_name().and_then(|name| name.to_str()) else {
+        return Ok(false);
+    };
+    let tmp = dst.with_file_name(format!("{file_name}.link"));
+    let _ = std::fs::remove_file(&tmp);
+    std::fs::hard_link(src, &tmp)?;
+    if let Err(error) = std::fs::rename(&tmp, dst) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(error);
+    }
+    Ok(true)
+}
+
 #[cfg(test)]
 mod tests {
     use bastion_core::manifest::HashAlgorithm;
@@ -260,7 +476,9 @@ mod tests {
 
     use bastion_core::backup_format::{LocalArtifact, LocalRunArtifacts};
 
-    use super::{store_run, store_run_parts_rolling};
+    use super::{
+        link_unchanged_from_previous, store_run, store_run_parts_rolling, store_run_parts_streaming,
+    };
 
     #[test]
     fn store_run_copies_files_and_is_resumable() {
@@ -451,4 +669,143 @@ mod tests {
         assert!(run_dir.join("payload.part000001").exists());
         assert!(!part_path.exists());
     }
+
+    #[test]
+    fn store_run_parts_streaming_writes_parts_and_drops_aborted_ones() {
+        let tmp = tempdir().unwrap();
+        let dest_base = tmp.path().join("dest");
+
+        let (parts_tx, parts_rx) = tokio::sync::mpsc::channel::<crate::StreamedPart>(2);
+        let (chunks_tx, chunks_rx) = tokio::sync::mpsc::channel(4);
+        chunks_tx.blocking_send(Ok(b"hel".to_vec())).unwrap();
+        chunks_tx.blocking_send(Ok(b"lo".to_vec())).unwrap();
+        drop(chunks_tx);
+        parts_tx
+            .blocking_send(crate::StreamedPart {
+                name: "payload.part000001".to_string(),
+                chunks: chunks_rx,
+            })
+            .unwrap();
+
+        let (chunks_tx, chunks_rx) = tokio::sync::mpsc::channel(4);
+        chunks_tx.blocking_send(Ok(b"partial".to_vec())).unwrap();
+        chunks_tx
+            .blocking_send(Err(std::io::Error::other("builder failed")))
+            .unwrap();
+        drop(chunks_tx);
+        parts_tx
+            .blocking_send(crate::StreamedPart {
+                name: "payload.part000002".to_string(),
+                chunks: chunks_rx,
+            })
+            .unwrap();
+        drop(parts_tx);
+
+        let err = store_run_parts_streaming(&dest_base, "job1", "run1", parts_rx).unwrap_err();
+        assert!(err.to_string().contains("builder failed"));
+
+        let run_dir = dest_base.join("job1").join("run1");
+        assert_eq!(
+            std::fs::read(run_dir.join("payload.part000001")).unwrap(),
+            b"hello"
+        );
+        assert!(!run_dir.join("payload.part000002").exists());
+        assert!(!run_dir.join("payload.part000002.partial").exists());
+    }
+
+    #[cfg(unix)]
+    fn write_raw_tree_run(run_dir: &std::path::Path, ended_at: &str, files: &[(&str, &[u8])]) {
+        use std::io::Write as _;
+
+        std::fs::create_dir_all(run_dir.join("data")).unwrap();
+        let mut encoder = zstd::Encoder::new(
+            std::fs::File::create(run_dir.join("entries.jsonl.zst")).unwrap(),
+            3,
+        )
+        .unwrap();
+        for (path, bytes) in files {
+            std::fs::write(run_dir.join("data").join(path), bytes).unwrap();
+            let line = serde_json::json!({
+                "path": path,
+                "kind": "file",
+                "size": bytes.len(),
+                "hash_alg": "blake3",
+                "hash": blake3::hash(bytes).to_hex().to_string(),
+            });
+            writeln!(encoder, "{line}").unwrap();
+        }
+        encoder.finish().unwrap();
+
+        std::fs::write(
+            run_dir.join("manifest.json"),
+            serde_json::to_vec(&serde_json::json!({
+              "format_version": 1,
+              "job_id": "00000000-0000-0000-0000-000000000000",
+              "run_id": "00000000-0000-0000-0000-000000000000",
+              "started_at": ended_at,
+              "ended_at": ended_at,
+              "pipeline": {
+                "format": "raw_tree_v1",
+                "tar": "pax",
+                "compression": "none",
+                "encryption": "none",
+                "split_bytes": 0
+              },
+              "artifacts": [],
+              "entry_index": { "name": "entries.jsonl.zst", "count": files.len() }
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+        std::fs::write(run_dir.join("complete.json"), b"{}").unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn link_unchanged_from_previous_hardlinks_identical_files_only() {
+        use std::os::unix::fs::MetadataExt as _;
+
+        let tmp = tempdir().unwrap();
+        let job_dir = tmp.path().join("job1");
+        write_raw_tree_run(
+            &job_dir.join("old"),
+            "2025-12-29T12:00:00Z",
+            &[("same.txt", b"same"), ("changed.txt", b"v1")],
+        );
+        write_raw_tree_run(
+            &job_dir.join("prev"),
+            "2025-12-30T12:00:00Z",
+            &[("same.txt", b"same"), ("changed.txt", b"v2")],
+        );
+        write_raw_tree_run(
+            &job_dir.join("new"),
+            "2025-12-31T12:00:00Z",
+            &[("same.txt", b"same"), ("changed.txt", b"v3")],
+        );
+
+        let stats = link_unchanged_from_previous(tmp.path(), "job1", "new")
+            .unwrap()
+            .expect("previous snapshot");
+        assert_eq!(stats.previous_run_id, "prev");
+        assert_eq!(stats.linked_files, 1);
+        assert_eq!(stats.linked_bytes, 4);
+
+        let ino = |run: &str, name: &str| {
+            std::fs::metadata(job_dir.join(run).join("data").join(name))
+                .unwrap()
+                .ino()
+        };
+        assert_eq!(ino("new", "same.txt"), ino("prev", "same.txt"));
+        assert_ne!(ino("new", "changed.txt"), ino("prev", "changed.txt"));
+        assert_eq!(
+            std::fs::read(job_dir.join("new/data/changed.txt")).unwrap(),
+            b"v3"
+        );
+
+        // Running again is a no-op.
+        let again = link_unchanged_from_previous(tmp.path(), "job1", "new")
+            .unwrap()
+            .unwrap();
+        assert_eq!(again.linked_files, 0);
+    }
 }
@@ -12,8 +12,34 @@ use bastion_core::backup_format::{
 };
 use bastion_core::manifest::{ArtifactFormatV1, ManifestV1};
 
-use crate::StoreRunProgress;
 use crate::webdav_client::{WebdavClient, WebdavCredentials, WebdavRequestLimits, redact_url};
+use crate::{StoreRunProgress, StreamedPart};
+
+/// Checks that `credentials` are accepted at `base_url`.
+///
+/// A missing base collection still passes: runs create it on first upload.
+pub async fn check_credentials(
+    base_url: &str,
+    credentials: WebdavCredentials,
+) -> Result<(), anyhow::Error> {
+    let mut base_url = Url::parse(base_url)?;
+    if !base_url.path().ends_with('/') {
+        base_url.set_path(&format!("{}/", base_url.path()));
+    }
+
+    let client = WebdavClient::new(base_url.clone(), credentials)?;
+    match client.propfind_depth1(&base_url).await {
+        Ok(_) => Ok(()),
+        Err(error)
+            if error
+                .downcast_ref::<crate::WebdavHttpError>()
+                .is_some_and(|e| e.status == reqwest::StatusCode::NOT_FOUND) =>
+        {
+            Ok(())
+        }
+        Err(error) => Err(error),
+    }
+}
 
 pub async fn cleanup_incomplete_run(
     base_url: &str,
@@ -98,11 +124,17 @@ pub async fn cleanup_incomplete_run(
 ///
 /// This is intended to be used with archive builders that emit part-finalized events so large runs
 /// don't require staging all parts locally at once.
+///
+/// Up to `concurrency` parts are uploaded at once. A new part is only taken from `parts_rx` when an
+/// upload slot is free, so at most `concurrency` parts (plus one queued) sit on local disk. The
+/// function returns only after every received part has landed, so callers can write the manifest
+/// afterwards.
 pub async fn store_run_parts_rolling(
     base_url: &str,
     credentials: WebdavCredentials,
     job_id: &str,
     run_id: &str,
+    concurrency: usize,
     mut parts_rx: tokio::sync::mpsc::Receiver<LocalArtifact>,
 ) -> Result<Url, anyhow::Error> {
     let mut base_url = Url::parse(base_url)?;
@@ -114,6 +146,7 @@ pub async fn store_run_parts_rolling(
         job_id = %job_id,
         run_id = %run_id,
         base_url = %redact_url(&base_url),
+        concurrency,
         "starting rolling part upload to webdav"
     );
 
@@ -124,35 +157,104 @@ pub async fn store_run_parts_rolling(
     let run_url = job_url.join(&format!("{run_id}/"))?;
     client.ensure_collection(&run_url).await?;
 
-    while let Some(part) = parts_rx.recv().await {
-        let url = run_url.join(&part.name)?;
-        if let Some(existing) = client.head_size(&url).await?
-            && existing == part.size
-        {
-            debug!(
-                url = %redact_url(&url),
-                size = part.size,
-                "skipping existing webdav part (rolling upload)"
-            );
-        } else {
-            debug!(
-                url = %redact_url(&url),
-                size = part.size,
-                "uploading webdav part (rolling upload)"
-            );
-            client
-                .put_file_with_retries(&url, &part.path, part.size, 0)
-                .await?;
+    let slots = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut uploads = JoinSet::<Result<(), anyhow::Error>>::new();
+    loop {
+        // Take a slot before receiving so the builder is back-pressured while all slots are busy.
+        let permit = slots.clone().acquire_owned().await?;
+        // Surface failed uploads early so the builder stops producing parts.
+        while let Some(result) = uploads.try_join_next() {
+            result??;
         }
+        let Some(part) = parts_rx.recv().await else {
+            break;
+        };
+
+        let client = client.clone();
+        let url = run_url.join(&part.name)?;
+        uploads.spawn(async move {
+            let _permit = permit;
+            upload_rolling_part(&client, &url, &part).await
+        });
+    }
 
-        // Best-effort cleanup; failure here should not fail the run, because the target already has
-        // the data (or we intentionally skipped due to resumability-by-size).
-        let _ = tokio::fs::remove_file(&part.path).await;
+    // The run is only complete once every part has landed.
+    while let Some(result) = uploads.join_next().await {
+        result??;
     }
 
     Ok(run_url)
 }
 
+/// Store `payload.part*` files straight from the archive builder, without local part files.
+///
+/// Parts are uploaded one at a time with chunked PUTs, in the order the builder produces them.
+/// Streamed bodies cannot be replayed, so a failed part fails the run instead of retrying.
+pub async fn store_run_parts_streaming(
+    base_url: &str,
+    credentials: WebdavCredentials,
+    job_id: &str,
+    run_id: &str,
+    mut parts_rx: tokio::sync::mpsc::Receiver<StreamedPart>,
+) -> Result<Url, anyhow::Error> {
+    let mut base_url = Url::parse(base_url)?;
+    if !base_url.path().ends_with('/') {
+        base_url.set_path(&format!("{}/", base_url.path()));
+    }
+
+    info!(
+        job_id = %job_id,
+        run_id = %run_id,
+        base_url = %redact_url(&base_url),
+        "starting streaming part upload to webdav"
+    );
+
+    let client = WebdavClient::new(base_url.clone(), credentials)?;
+    let job_url = base_url.join(&format!("{job_id}/"))?;
+    client.ensure_collection(&job_url).await?;
+
+    let run_url = job_url.join(&format!("{run_id}/"))?;
+    client.ensure_collection(&run_url).await?;
+
+    while let Some(part) = parts_rx.recv().await {
+        let url = run_url.join(&part.name)?;
+        debug!(url = %redact_url(&url), "streaming webdav part");
+        client.put_stream(&url, part.chunks).await?;
+    }
+
+    Ok(run_url)
+}
+
+async fn upload_rolling_part(
+    client: &WebdavClient,
+    url: &Url,
+    part: &LocalArtifact,
+) -> Result<(), anyhow::Error> {
+    if let Some(existing) = client.head_size(url).await?
+        && existing == part.size
+    {
+        debug!(
+            url = %redact_url(url),
+            size = part.size,
+            "skipping existing webdav part (rolling upload)"
+        );
+    } else {
+        debug!(
+            url = %redact_url(url),
+            size = part.size,
+            "uploading webdav part (rolling upload)"
+        );
+        client
+            .put_file_with_retries(url, &part.path, part.size, 0)
+            .await?;
+    }
+
+    // Best-effort cleanup; failure here should not fail the run, because the target already has
+    // the data (or we intentionally skipped due to resumability-by-size).
+    let _ = tokio::fs::remove_file(&part.path).await;
+    Ok(())
+}
+
 pub async fn store_run(
     base_url: &str,
     credentials: WebdavCredentials,
@@ -837,6 +939,7 @@ mod tests {
             },
             "job1",
             "run1",
+            1,
             rx,
         )
         .await
@@ -889,6 +992,7 @@ mod tests {
             },
             "job1",
             "run1",
+            1,
             rx,
         )
         .await
@@ -900,6 +1004,69 @@ mod tests {
         assert_eq!(*counts.get(&existing_path).unwrap_or(&0), 0);
     }
 
+    #[tokio::test]
+    async fn store_run_parts_rolling_uploads_parts_in_parallel_up_to_limit() {
+        let (base_url, state) = start_dav().await;
+        state.put_delay_ms.store(50, Ordering::SeqCst);
+
+        let temp = TempDir::new().expect("tempdir");
+        let stage = temp.path().join("stage");
+        std::fs::create_dir_all(&stage).unwrap();
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<LocalArtifact>(1);
+        let mut part_paths = Vec::new();
+        for i in 1..=6 {
+            let name = format!("payload.part{i:06}");
+            let path = stage.join(&name);
+            std::fs::write(&path, b"hello").unwrap();
+            part_paths.push(path.clone());
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                tx.send(LocalArtifact {
+                    name,
+                    path,
+                    size: 5,
+                    hash_alg: HashAlgorithm::Blake3,
+                    hash: "deadbeef".to_string(),
+                })
+                .await
+                .unwrap();
+            });
+        }
+        drop(tx);
+
+        super::store_run_parts_rolling(
+            &base_url,
+            WebdavCredentials {
+                username: "u".to_string(),
+                password: "p".to_string(),
+            },
+            "job1",
+            "run1",
+            3,
+            rx,
+        )
+        .await
+        .unwrap();
+
+        // Every part has landed (and been cleaned up locally) once the call returns.
+        assert!(part_paths.iter().all(|p| !p.exists()));
+        assert_eq!(
+            state
+                .files
+                .lock()
+                .unwrap()
+                .keys()
+                .filter(|k| k.contains("payload.part"))
+                .count(),
+            6
+        );
+
+        let peak = state.max_inflight.load(Ordering::SeqCst);
+        assert!(peak <= 3, "expected peak <= 3, got {peak}");
+        assert!(peak >= 2, "expected concurrent uploads, got peak={peak}");
+    }
+
     #[tokio::test]
     async fn store_run_raw_tree_upload_writes_complete_last_and_respects_concurrency_limit() {
         let (base_url, state) = start_dav().await;
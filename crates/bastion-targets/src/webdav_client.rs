@@ -6,7 +6,7 @@ use std::time::UNIX_EPOCH;
 
 use futures_util::TryStreamExt as _;
 use percent_encoding::percent_decode_str;
-use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE, RETRY_AFTER};
+use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE, RANGE, RETRY_AFTER};
 use reqwest::{Method, StatusCode};
 use serde::Deserialize;
 use tokio::io::AsyncWriteExt;
@@ -875,6 +875,44 @@ impl WebdavClient {
         }
     }
 
+    /// Upload a body whose size is not known up front, using a chunked PUT.
+    ///
+    /// The body ends when the sender side of `chunks` is dropped; an `Err` chunk aborts the
+    /// request. Streamed bodies cannot be replayed, so this never retries.
+    pub async fn put_stream(
+        &self,
+        url: &Url,
+        chunks: tokio::sync::mpsc::Receiver<std::io::Result<Vec<u8>>>,
+    ) -> Result<(), anyhow::Error> {
+        tracing::debug!(url = %redact_url(url), "webdav put (streamed)");
+        let stream = futures_util::stream::unfold(chunks, |mut rx| async move {
+            rx.recv().await.map(|chunk| (chunk, rx))
+        });
+        let body = reqwest::Body::wrap_stream(stream);
+
+        let req = self
+            .http
+            .put(url.clone())
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .body(body);
+        let res = self.send_limited(WebdavRequestClass::Put, req).await?;
+
+        let status = res.status();
+        match status {
+            StatusCode::OK | StatusCode::CREATED | StatusCode::NO_CONTENT => Ok(()),
+            s => {
+                let retry_after = parse_retry_after(&res);
+                let message = res.text().await.unwrap_or_default();
+                Err(WebdavHttpError {
+                    status: s,
+                    message,
+                    retry_after,
+                }
+                .into())
+            }
+        }
+    }
+
     /// Upload a file while computing a BLAKE3 hash of the uploaded bytes.
     ///
     /// This is used by streaming upload pipelines that want to avoid reading the payload twice
@@ -1247,6 +1285,96 @@ impl WebdavClient {
         }
     }
 
+    /// Reads `len` bytes starting at `offset` using an HTTP range request.
+    ///
+    /// Servers that ignore the `Range` header are only accepted for reads starting at offset 0;
+    /// the body is then truncated to `len`.
+    pub async fn get_range(
+        &self,
+        url: &Url,
+        offset: u64,
+        len: u64,
+        max_attempts: u32,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let mut attempt = 1u32;
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            match self.get_range_once(url, offset, len).await {
+                Ok(bytes) => return Ok(bytes),
+                Err(error) if attempt < max_attempts => {
+                    let (retriable, retry_after) = Self::classify_read_error_retry(&error);
+                    if !retriable {
+                        return Err(error);
+                    }
+                    tracing::debug!(
+                        url = %redact_url(url),
+                        offset,
+                        len,
+                        attempt,
+                        max_attempts,
+                        backoff_seconds = backoff.as_secs(),
+                        error = %error,
+                        "webdav ranged get failed; retrying"
+                    );
+                    if let Some(delay) = retry_after {
+                        tokio::time::sleep(std::cmp::min(delay, Duration::from_secs(60))).await;
+                    } else {
+                        tokio::time::sleep(backoff).await;
+                        backoff = std::cmp::min(backoff * 2, Duration::from_secs(30));
+                    }
+                    attempt += 1;
+                    continue;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    async fn get_range_once(
+        &self,
+        url: &Url,
+        offset: u64,
+        len: u64,
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        let end = offset.saturating_add(len - 1);
+        let res = self
+            .send_limited(
+                WebdavRequestClass::Other,
+                self.http
+                    .get(url.clone())
+                    .header(RANGE, format!("bytes={offset}-{end}")),
+            )
+            .await?;
+
+        let status = res.status();
+        if status == StatusCode::OK && offset != 0 {
+            anyhow::bail!("server does not support range requests");
+        }
+        if status != StatusCode::PARTIAL_CONTENT && status != StatusCode::OK {
+            return Err(Self::response_http_error(res, "ranged GET failed").await);
+        }
+
+        let mut out = Vec::with_capacity(usize::try_from(len).unwrap_or(0));
+        let mut res = res;
+        while let Some(chunk) = res.chunk().await? {
+            out.extend_from_slice(&chunk);
+            if out.len() as u64 >= len {
+                break;
+            }
+        }
+        out.truncate(usize::try_from(len).unwrap_or(usize::MAX));
+        if (out.len() as u64) != len {
+            anyhow::bail!(
+                "ranged GET returned {} bytes, expected {len} at offset {offset}",
+                out.len()
+            );
+        }
+        Ok(out)
+    }
+
     async fn get_to_file_once(
         &self,
         url: &Url,
@@ -2109,4 +2237,60 @@ mod tests {
         assert_eq!(state.gets.load(Ordering::SeqCst), 2);
         assert_eq!(std::fs::read(&dest).unwrap(), b"hello");
     }
+
+    #[tokio::test]
+    async fn get_range_reads_requested_byte_window() {
+        async fn handler(req: Request<Body>) -> impl IntoResponse {
+            let body = b"0123456789";
+            let range = req
+                .headers()
+                .get("range")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("bytes="))
+                .and_then(|v| v.split_once('-'))
+                .and_then(|(start, end)| {
+                    Some((start.parse::<usize>().ok()?, end.parse::<usize>().ok()?))
+                });
+            match range {
+                Some((start, end)) => axum::http::Response::builder()
+                    .status(StatusCode::PARTIAL_CONTENT)
+                    .body(Body::from(body[start..=end.min(body.len() - 1)].to_vec()))
+                    .unwrap(),
+                None => axum::http::Response::builder()
+                    .status(StatusCode::OK)
+                    .body(Body::from(body.to_vec()))
+                    .unwrap(),
+            }
+        }
+
+        let app = Router::new().route("/{*path}", any(handler));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let base = Url::parse(&format!("http://{addr}/")).unwrap();
+        let client = WebdavClient::new(
+            base.clone(),
+            WebdavCredentials {
+                username: "u".to_string(),
+                password: "p".to_string(),
+            },
+        )
+        .unwrap();
+
+        let url = base.join("payload.part000001").unwrap();
+        assert_eq!(client.get_range(&url, 0, 4, 1).await.unwrap(), b"0123");
+        assert_eq!(client.get_range(&url, 6, 4, 1).await.unwrap(), b"6789");
+
+        let err = client
+            .get_range(&url, 8, 4, 1)
+            .await
+            .expect_err("short read");
+        assert!(
+            err.to_string().contains("expected 4"),
+            "unexpected error: {err}"
+        );
+    }
 }
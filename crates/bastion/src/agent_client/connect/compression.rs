@@ -0,0 +1,69 @@
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::http::HeaderMap;
+
+use bastion_core::agent_stream::{
+    WS_COMPRESSION_HEADER, decode_compressed_text_frame_v1, encode_compressed_text_frame_v1,
+    ws_compression_offers_zstd,
+};
+
+/// True when the Hub echoed our zstd offer in the upgrade response.
+pub(super) fn negotiated(response_headers: &HeaderMap) -> bool {
+    ws_compression_offers_zstd(
+        response_headers
+            .get(WS_COMPRESSION_HEADER)
+            .and_then(|v| v.to_str().ok()),
+    )
+}
+
+pub(super) fn compress_outgoing(msg: Message, enabled: bool) -> Message {
+    match msg {
+        Message::Text(text) if enabled => match encode_compressed_text_frame_v1(&text) {
+            Some(frame) => Message::Binary(frame.into()),
+            None => Message::Text(text),
+        },
+        other => other,
+    }
+}
+
+/// Turns compressed JSON frames back into text messages; everything else passes through.
+pub(super) fn decompress_incoming(msg: Message) -> Result<Message, anyhow::Error> {
+    match msg {
+        Message::Binary(bytes) => Ok(match decode_compressed_text_frame_v1(&bytes)? {
+            Some(text) => Message::Text(text.into()),
+            None => Message::Binary(bytes),
+        }),
+        other => Ok(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_text_round_trips_through_compression() -> Result<(), anyhow::Error> {
+        let text = format!(r#"{{"type":"run_event","message":"{}"}}"#, "x".repeat(4096));
+        let sent = compress_outgoing(Message::Text(text.clone().into()), true);
+        assert!(matches!(sent, Message::Binary(_)));
+
+        let Message::Text(received) = decompress_incoming(sent)? else {
+            anyhow::bail!("expected text message");
+        };
+        assert_eq!(received.as_str(), text);
+        Ok(())
+    }
+
+    #[test]
+    fn text_is_untouched_without_negotiation() {
+        let text = "y".repeat(4096);
+        assert!(matches!(
+            compress_outgoing(Message::Text(text.into()), false),
+            Message::Text(_)
+        ));
+
+        let mut headers = HeaderMap::new();
+        assert!(!negotiated(&headers));
+        headers.insert(WS_COMPRESSION_HEADER, "zstd".parse().expect("header"));
+        assert!(negotiated(&headers));
+    }
+}
@@ -1,5 +1,4 @@
 use std::path::Path;
-use std::sync::Arc;
 
 use futures_util::{Sink, SinkExt};
 use tokio_tungstenite::tungstenite;
@@ -10,16 +9,24 @@ use bastion_core::agent_protocol::{
     AgentToHubMessageV1, BackupAgeIdentitySecretV1, BackupRunTaskV1, JobConfigV1,
     OperationResultV1, PROTOCOL_VERSION, RestoreTaskV1, SnapshotDeleteTaskV1, WebdavSecretV1,
 };
-use bastion_core::run_failure::RunFailedWithSummary;
+use bastion_core::run_failure::{RunErrorCode, RunFailedWithSummary};
 
 use super::super::identity::AgentIdentityV1;
+use super::super::list_throttle::{CachedListPage, ListKind, ListThrottle};
 use super::super::managed::{
-    load_cached_operation_result, load_cached_task_result, load_managed_webdav_credentials,
-    save_managed_config_snapshot, save_managed_secrets_snapshot, save_task_result,
+    forget_task_result, load_cached_operation_result, load_cached_task_result,
+    load_managed_webdav_credentials, save_managed_config_snapshot, save_managed_secrets_snapshot,
+    save_task_result,
 };
+use super::super::run_slots::{RunPriority, RunSlots};
+use super::super::task_queue::{Admission, QueuedTaskKind, TaskQueue};
 use super::super::util::is_ws_error;
 use super::cancel_registry::TaskCancelRegistry;
 
+const LIST_RATE_LIMITED_CODE: &str = "rate_limited";
+const LIST_SUPERSEDED_CODE: &str = "superseded";
+const INTERRUPTED_ERROR: &str = "agent stopped while the task was running";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(super) enum HandlerFlow {
     Continue,
@@ -37,6 +44,25 @@ where
     Ok(HandlerFlow::Continue)
 }
 
+/// Acknowledges a task that already has a result and sends that result again.
+async fn replay_result<S>(
+    tx: &mut S,
+    task_id: &str,
+    result: &AgentToHubMessageV1,
+) -> Result<HandlerFlow, anyhow::Error>
+where
+    S: Sink<Message, Error = tungstenite::Error> + Unpin,
+{
+    let ack = AgentToHubMessageV1::Ack {
+        v: PROTOCOL_VERSION,
+        task_id: task_id.to_string(),
+    };
+    if send_json(tx, &ack).await? == HandlerFlow::Reconnect {
+        return Ok(HandlerFlow::Reconnect);
+    }
+    send_json(tx, result).await
+}
+
 pub(super) async fn handle_secrets_snapshot(
     identity: &AgentIdentityV1,
     data_dir: &Path,
@@ -127,7 +153,8 @@ where
 pub(super) async fn handle_task<S>(
     tx: &mut S,
     data_dir: &Path,
-    run_lock: Arc<tokio::sync::Mutex<()>>,
+    run_slots: &RunSlots,
+    task_queue: &TaskQueue,
     cancel_registry: &TaskCancelRegistry,
     task_id: String,
     task: Box<BackupRunTaskV1>,
@@ -155,19 +182,42 @@ where
             run_id = %run_id,
             "replaying cached task result"
         );
-        let ack = AgentToHubMessageV1::Ack {
-            v: PROTOCOL_VERSION,
-            task_id: task_id.clone(),
-        };
-        if send_json(tx, &ack).await? == HandlerFlow::Reconnect {
-            return Ok(HandlerFlow::Reconnect);
-        }
+        return replay_result(tx, &task_id, &cached).await;
+    }
 
-        if send_json(tx, &cached).await? == HandlerFlow::Reconnect {
-            return Ok(HandlerFlow::Reconnect);
+    let mut queued = loop {
+        match task_queue.admit(&task_id, QueuedTaskKind::Backup, &run_id) {
+            Admission::Run(queued) => break queued,
+            Admission::InFlight(mut done) => {
+                debug!(task_id = %task_id, run_id = %run_id, "task already running; waiting for its result");
+                let _ = done.wait_for(|done| *done).await;
+                if let Some(cached) = load_cached_task_result(data_dir, &task_id, &run_id) {
+                    return replay_result(tx, &task_id, &cached).await;
+                }
+                // The earlier delivery gave up without a result (its connection dropped).
+            }
+            Admission::Interrupted => {
+                warn!(task_id = %task_id, run_id = %run_id, "task was running when the agent stopped; reporting it as failed");
+                let result = AgentToHubMessageV1::TaskResult {
+                    v: PROTOCOL_VERSION,
+                    task_id: task_id.clone(),
+                    run_id,
+                    status: "failed".to_string(),
+                    summary: Some(serde_json::json!({
+                        "run_error_code": RunErrorCode::Unknown.as_str(),
+                    })),
+                    error: Some(INTERRUPTED_ERROR.to_string()),
+                };
+                match save_task_result(data_dir, &result) {
+                    Ok(()) => task_queue.remove(&task_id),
+                    Err(error) => {
+                        warn!(task_id = %task_id, error = %error, "failed to persist task result")
+                    }
+                }
+                return replay_result(tx, &task_id, &result).await;
+            }
         }
-        return Ok(HandlerFlow::Continue);
-    }
+    };
 
     let ack = AgentToHubMessageV1::Ack {
         v: PROTOCOL_VERSION,
@@ -177,14 +227,26 @@ where
         return Ok(HandlerFlow::Reconnect);
     }
 
-    let _guard = run_lock.lock().await;
+    let _slot = run_slots
+        .acquire(Some(&task.job_id), RunPriority::Normal)
+        .await;
+    queued.mark_running();
     let cancel_token = cancel_registry.register_run(&run_id);
     let _token_guard = RunTokenGuard {
         registry: cancel_registry.clone(),
         run_id: run_id.clone(),
     };
 
-    match super::super::handle_backup_task(data_dir, tx, &task_id, *task, &cancel_token).await {
+    match super::super::handle_backup_task(
+        data_dir,
+        tx,
+        &task_id,
+        *task,
+        &cancel_token,
+        super::super::UploadFailurePolicy::Fail,
+    )
+    .await
+    {
         Ok(()) => {}
         Err(error) => {
             if is_ws_error(&error) {
@@ -221,9 +283,19 @@ where
             }
 
             warn!(task_id = %task_id, run_id = %run_id, error = %error, "task failed");
-            let summary = error
+            let mut summary = error
                 .downcast_ref::<RunFailedWithSummary>()
-                .map(|e| e.summary.clone());
+                .map(|e| e.summary.clone())
+                .unwrap_or_else(|| serde_json::json!({}));
+            if let Some(obj) = summary.as_object_mut() {
+                obj.insert(
+                    "run_error_code".to_string(),
+                    serde_json::Value::String(
+                        RunErrorCode::classify(error.chain()).as_str().to_string(),
+                    ),
+                );
+            }
+            let summary = Some(summary);
             let result = AgentToHubMessageV1::TaskResult {
                 v: PROTOCOL_VERSION,
                 task_id: task_id.clone(),
@@ -246,10 +318,12 @@ where
     Ok(HandlerFlow::Continue)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn handle_restore_task<S>(
     tx: &mut S,
     data_dir: &Path,
-    run_lock: Arc<tokio::sync::Mutex<()>>,
+    run_slots: &RunSlots,
+    task_queue: &TaskQueue,
     cancel_registry: &TaskCancelRegistry,
     hub_streams: &super::super::hub_stream::HubStreamManager,
     task_id: String,
@@ -275,19 +349,41 @@ where
 
     if let Some(cached) = load_cached_operation_result(data_dir, &op_id) {
         debug!(task_id = %task_id, op_id = %op_id, "replaying cached restore result");
-        let ack = AgentToHubMessageV1::Ack {
-            v: PROTOCOL_VERSION,
-            task_id: task_id.clone(),
-        };
-        if send_json(tx, &ack).await? == HandlerFlow::Reconnect {
-            return Ok(HandlerFlow::Reconnect);
-        }
-        if send_json(tx, &cached).await? == HandlerFlow::Reconnect {
-            return Ok(HandlerFlow::Reconnect);
-        }
-        return Ok(HandlerFlow::Continue);
+        return replay_result(tx, &task_id, &cached).await;
     }
 
+    let mut queued = loop {
+        match task_queue.admit(&task_id, QueuedTaskKind::Restore, &op_id) {
+            Admission::Run(queued) => break queued,
+            Admission::InFlight(mut done) => {
+                debug!(task_id = %task_id, op_id = %op_id, "restore already running; waiting for its result");
+                let _ = done.wait_for(|done| *done).await;
+                if let Some(cached) = load_cached_operation_result(data_dir, &op_id) {
+                    return replay_result(tx, &task_id, &cached).await;
+                }
+            }
+            Admission::Interrupted => {
+                warn!(task_id = %task_id, op_id = %op_id, "restore was running when the agent stopped; reporting it as failed");
+                let result = AgentToHubMessageV1::OperationResult {
+                    v: PROTOCOL_VERSION,
+                    result: OperationResultV1 {
+                        op_id: op_id.clone(),
+                        status: "failed".to_string(),
+                        summary: None,
+                        error: Some(INTERRUPTED_ERROR.to_string()),
+                    },
+                };
+                match save_task_result(data_dir, &result) {
+                    Ok(()) => task_queue.remove(&task_id),
+                    Err(error) => {
+                        warn!(task_id = %task_id, error = %error, "failed to persist restore result")
+                    }
+                }
+                return replay_result(tx, &task_id, &result).await;
+            }
+        }
+    };
+
     let ack = AgentToHubMessageV1::Ack {
         v: PROTOCOL_VERSION,
         task_id: task_id.clone(),
@@ -296,7 +392,9 @@ where
         return Ok(HandlerFlow::Reconnect);
     }
 
-    let _guard = run_lock.lock().await;
+    // Restores don't belong to a job run, so they only count against the Agent-wide limit.
+    let _slot = run_slots.acquire(None, RunPriority::High).await;
+    queued.mark_running();
     let cancel_token = cancel_registry.register_operation(&op_id);
     let _token_guard = OperationTokenGuard {
         registry: cancel_registry.clone(),
@@ -381,6 +479,11 @@ where
     Ok(HandlerFlow::Continue)
 }
 
+pub(super) fn handle_task_result_ack(data_dir: &Path, task_id: String, duplicate: bool) {
+    debug!(task_id = %task_id, duplicate, "hub acknowledged task result");
+    forget_task_result(data_dir, task_id.trim());
+}
+
 pub(super) fn handle_cancel_run_task(cancel_registry: &TaskCancelRegistry, run_id: String) {
     let run_id = run_id.trim();
     if run_id.is_empty() {
@@ -399,7 +502,7 @@ pub(super) fn handle_cancel_operation_task(cancel_registry: &TaskCancelRegistry,
 
 pub(super) async fn handle_snapshot_delete_task<S>(
     tx: &mut S,
-    run_lock: Arc<tokio::sync::Mutex<()>>,
+    run_slots: &RunSlots,
     task: SnapshotDeleteTaskV1,
 ) -> Result<HandlerFlow, anyhow::Error>
 where
@@ -413,7 +516,9 @@ where
         "received snapshot delete task"
     );
 
-    let _guard = run_lock.lock().await;
+    let _slot = run_slots
+        .acquire(Some(&task.job_id), RunPriority::Low)
+        .await;
 
     let base_dir = task.base_dir.clone();
     let job_id = task.job_id.clone();
@@ -518,6 +623,7 @@ pub(super) struct FsListRequest {
     pub(super) sort_dir: Option<String>,
     pub(super) size_min_bytes: Option<u64>,
     pub(super) size_max_bytes: Option<u64>,
+    pub(super) requester: Option<String>,
 }
 
 pub(super) struct WebdavListRequest {
@@ -535,10 +641,12 @@ pub(super) struct WebdavListRequest {
     pub(super) sort_dir: Option<String>,
     pub(super) size_min_bytes: Option<u64>,
     pub(super) size_max_bytes: Option<u64>,
+    pub(super) requester: Option<String>,
 }
 
 pub(super) async fn handle_fs_list<S>(
     tx: &mut S,
+    throttle: &ListThrottle,
     req: FsListRequest,
 ) -> Result<HandlerFlow, anyhow::Error>
 where
@@ -557,6 +665,7 @@ where
         sort_dir,
         size_min_bytes,
         size_max_bytes,
+        requester,
     } = req;
 
     let path = path.trim().to_string();
@@ -598,37 +707,81 @@ where
         size_max_bytes,
     };
 
-    let result = tokio::task::spawn_blocking(move || {
-        super::super::fs_list::fs_list_dir_entries_paged(&path, opts)
-    })
-    .await;
-
-    let (entries, next_cursor, total, error_code, error_details, error) = match result {
-        Ok(Ok(page)) => (
-            page.entries,
-            page.next_cursor,
-            Some(page.total),
-            None,
-            None,
-            None,
-        ),
-        Ok(Err(err)) => (
-            Vec::new(),
-            None,
-            None,
-            Some(err.code),
-            None,
-            Some(err.message),
-        ),
-        Err(error) => (
-            Vec::new(),
-            None,
-            None,
-            Some("error".to_string()),
-            None,
-            Some(format!("fs list task failed: {error}")),
-        ),
-    };
+    let cache_key = format!("{path}\n{opts:?}");
+    let (entries, next_cursor, total, error_code, error_details, error) =
+        if let Some(cached) = throttle.cached(ListKind::Fs, &cache_key) {
+            (
+                cached.entries,
+                cached.next_cursor,
+                Some(cached.total),
+                None,
+                None,
+                None,
+            )
+        } else if !throttle.try_acquire() {
+            (
+                Vec::new(),
+                None,
+                None,
+                Some(LIST_RATE_LIMITED_CODE.to_string()),
+                None,
+                Some("too many list requests; retry shortly".to_string()),
+            )
+        } else {
+            let ticket = throttle.begin(ListKind::Fs, requester.as_deref(), &path);
+            let cancel = ticket.token().clone();
+            let work = tokio::task::spawn_blocking(move || {
+                super::super::fs_list::fs_list_dir_entries_paged(&path, opts, &cancel)
+            });
+
+            tokio::select! {
+                result = work => match result {
+                    Ok(Ok(page)) => {
+                        throttle.store(
+                            ListKind::Fs,
+                            &cache_key,
+                            CachedListPage {
+                                entries: page.entries.clone(),
+                                next_cursor: page.next_cursor.clone(),
+                                total: page.total,
+                            },
+                        );
+                        (
+                            page.entries,
+                            page.next_cursor,
+                            Some(page.total),
+                            None,
+                            None,
+                            None,
+                        )
+                    }
+                    Ok(Err(err)) => (
+                        Vec::new(),
+                        None,
+                        None,
+                        Some(err.code),
+                        None,
+                        Some(err.message),
+                    ),
+                    Err(error) => (
+                        Vec::new(),
+                        None,
+                        None,
+                        Some("error".to_string()),
+                        None,
+                        Some(format!("fs list task failed: {error}")),
+                    ),
+                },
+                _ = ticket.token().cancelled() => (
+                    Vec::new(),
+                    None,
+                    None,
+                    Some(LIST_SUPERSEDED_CODE.to_string()),
+                    None,
+                    Some("superseded by a newer list request".to_string()),
+                ),
+            }
+        };
 
     let msg = AgentToHubMessageV1::FsListResult {
         v: PROTOCOL_VERSION,
@@ -646,6 +799,7 @@ where
 pub(super) async fn handle_webdav_list<S>(
     tx: &mut S,
     data_dir: &Path,
+    throttle: &ListThrottle,
     req: WebdavListRequest,
 ) -> Result<HandlerFlow, anyhow::Error>
 where
@@ -666,6 +820,7 @@ where
         sort_dir,
         size_min_bytes,
         size_max_bytes,
+        requester,
     } = req;
 
     let base_url = base_url.trim().to_string();
@@ -696,42 +851,80 @@ where
         if t.is_empty() { None } else { Some(t) }
     });
 
-    let (entries, next_cursor, total, error_code, error) =
-        match load_managed_webdav_credentials(data_dir, &secret_name)? {
-            Some(credentials) => {
-                let opts = super::super::webdav_list::WebdavListOptions {
-                    cursor,
-                    limit: limit.map(|v| v.max(1)),
-                    q,
-                    kind,
-                    hide_dotfiles: hide_dotfiles.unwrap_or(false),
-                    type_sort,
-                    sort_by,
-                    sort_dir,
-                    size_min_bytes,
-                    size_max_bytes,
-                };
+    let opts = super::super::webdav_list::WebdavListOptions {
+        cursor,
+        limit: limit.map(|v| v.max(1)),
+        q,
+        kind,
+        hide_dotfiles: hide_dotfiles.unwrap_or(false),
+        type_sort,
+        sort_by,
+        sort_dir,
+        size_min_bytes,
+        size_max_bytes,
+    };
+    let cache_key = format!("{base_url}\n{secret_name}\n{path}\n{opts:?}");
 
-                match super::super::webdav_list::webdav_list_dir_entries_paged(
+    let cached = throttle.cached(ListKind::Webdav, &cache_key);
+    let (entries, next_cursor, total, error_code, error) = match (
+        cached,
+        load_managed_webdav_credentials(data_dir, &secret_name)?,
+    ) {
+        (Some(cached), Some(_)) => (
+            cached.entries,
+            cached.next_cursor,
+            Some(cached.total),
+            None,
+            None,
+        ),
+        (None, Some(_)) if !throttle.try_acquire() => (
+            Vec::new(),
+            None,
+            None,
+            Some(LIST_RATE_LIMITED_CODE.to_string()),
+            Some("too many list requests; retry shortly".to_string()),
+        ),
+        (None, Some(credentials)) => {
+            let target = format!("{base_url}\n{path}");
+            let ticket = throttle.begin(ListKind::Webdav, requester.as_deref(), &target);
+            tokio::select! {
+                result = super::super::webdav_list::webdav_list_dir_entries_paged(
                     &base_url,
                     credentials,
                     &path,
                     opts,
-                )
-                .await
-                {
-                    Ok(page) => (page.entries, page.next_cursor, Some(page.total), None, None),
+                ) => match result {
+                    Ok(page) => {
+                        throttle.store(
+                            ListKind::Webdav,
+                            &cache_key,
+                            CachedListPage {
+                                entries: page.entries.clone(),
+                                next_cursor: page.next_cursor.clone(),
+                                total: page.total,
+                            },
+                        );
+                        (page.entries, page.next_cursor, Some(page.total), None, None)
+                    }
                     Err(e) => (Vec::new(), None, None, Some(e.code), Some(e.message)),
-                }
+                },
+                _ = ticket.token().cancelled() => (
+                    Vec::new(),
+                    None,
+                    None,
+                    Some(LIST_SUPERSEDED_CODE.to_string()),
+                    Some("superseded by a newer list request".to_string()),
+                ),
             }
-            None => (
-                Vec::new(),
-                None,
-                None,
-                Some("missing_webdav_secret".to_string()),
-                Some("missing webdav secret for agent".to_string()),
-            ),
-        };
+        }
+        (_, None) => (
+            Vec::new(),
+            None,
+            None,
+            Some("missing_webdav_secret".to_string()),
+            Some("missing webdav secret for agent".to_string()),
+        ),
+    };
 
     let msg = AgentToHubMessageV1::WebdavListResult {
         v: PROTOCOL_VERSION,
@@ -745,6 +938,30 @@ where
     send_json(tx, &msg).await
 }
 
+pub(super) async fn handle_diagnostics<S>(
+    tx: &mut S,
+    data_dir: &Path,
+    agent_id: &str,
+    request_id: String,
+    job_id: Option<String>,
+) -> Result<HandlerFlow, anyhow::Error>
+where
+    S: Sink<Message, Error = tungstenite::Error> + Unpin,
+{
+    let job_id = job_id
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    let report = super::super::diagnostics::run(data_dir, agent_id, job_id.as_deref()).await;
+
+    let msg = AgentToHubMessageV1::DiagnosticsResult {
+        v: PROTOCOL_VERSION,
+        request_id,
+        report: Some(report),
+        error: None,
+    };
+    send_json(tx, &msg).await
+}
+
 #[cfg(test)]
 mod tests {
     use std::pin::Pin;
@@ -810,10 +1027,12 @@ mod tests {
         AgentIdentityV1 {
             v: 1,
             hub_url: "http://localhost:9876/".to_string(),
+            hub_urls: Vec::new(),
             agent_id: agent_id.to_string(),
             agent_key: "k".to_string(),
             name: None,
             enrolled_at: 1,
+            client_cert: None,
         }
     }
 
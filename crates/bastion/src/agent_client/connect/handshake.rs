@@ -1,8 +1,12 @@
 use futures_util::{Sink, SinkExt};
 use tokio_tungstenite::tungstenite;
 use tokio_tungstenite::tungstenite::Message;
+use tracing::{info, warn};
 
-use bastion_core::agent_protocol::{AgentToHubMessageV1, PROTOCOL_VERSION};
+use bastion_core::agent_protocol::{
+    AgentToHubMessageV1, HostMetricsV1, MessageEnvelopeV1, PROTOCOL_REVISION, PROTOCOL_VERSION,
+    ProtocolCapabilitiesV1,
+};
 use bastion_driver_registry::builtins;
 
 use super::super::identity::AgentIdentityV1;
@@ -27,6 +31,12 @@ fn source_driver_entries() -> Vec<serde_json::Value> {
         serde_json::json!({ "kind": "filesystem", "version": 1 }),
         serde_json::json!({ "kind": "sqlite", "version": 1 }),
         serde_json::json!({ "kind": "vaultwarden", "version": 1 }),
+        serde_json::json!({ "kind": "sqlite_app", "version": 1 }),
+        serde_json::json!({ "kind": "postgres", "version": 1 }),
+        serde_json::json!({ "kind": "vm_image", "version": 1 }),
+        serde_json::json!({ "kind": "kubernetes_pvc", "version": 1 }),
+        serde_json::json!({ "kind": "imap", "version": 1 }),
+        serde_json::json!({ "kind": "git", "version": 1 }),
     ]
 }
 
@@ -64,10 +74,16 @@ fn target_driver_entries() -> Vec<serde_json::Value> {
 pub(super) async fn send_hello<S>(
     tx: &mut S,
     identity: &AgentIdentityV1,
+    self_update: bool,
+    metrics: Option<HostMetricsV1>,
 ) -> Result<(), anyhow::Error>
 where
     S: Sink<Message, Error = tungstenite::Error> + Unpin,
 {
+    let mut control = vec!["fs_list"];
+    if self_update {
+        control.push("self_update");
+    }
     let hello = AgentToHubMessageV1::Hello {
         v: PROTOCOL_VERSION,
         agent_id: identity.agent_id.clone(),
@@ -78,19 +94,62 @@ where
             "arch": std::env::consts::ARCH,
         }),
         capabilities: serde_json::json!({
-            "backup": ["filesystem", "sqlite", "vaultwarden"],
-            "control": ["fs_list"],
+            "backup": ["filesystem", "sqlite", "vaultwarden", "sqlite_app", "postgres", "vm_image", "kubernetes_pvc", "imap", "git"],
+            "control": control,
             "drivers": {
                 "source": source_driver_entries(),
                 "target": target_driver_entries(),
             }
         }),
+        metrics,
+        protocol: Some(ProtocolCapabilitiesV1::current()),
     };
     tx.send(Message::Text(serde_json::to_string(&hello)?.into()))
         .await?;
     Ok(())
 }
 
+pub(super) fn log_negotiated_protocol(agent_id: &str, negotiated: &ProtocolCapabilitiesV1) {
+    if negotiated.revision < PROTOCOL_REVISION {
+        info!(
+            agent_id = %agent_id,
+            hub_revision = negotiated.revision,
+            agent_revision = PROTOCOL_REVISION,
+            "hub speaks an older protocol revision; newer features are disabled"
+        );
+    }
+    let missing = ProtocolCapabilitiesV1::current()
+        .task_types
+        .into_iter()
+        .filter(|t| !negotiated.supports_task_type(t))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        warn!(
+            agent_id = %agent_id,
+            task_types = ?missing,
+            "hub does not support some task types of this agent"
+        );
+    }
+}
+
+/// Warns once per connection when the Hub sends messages with an envelope version this agent
+/// can't read, instead of dropping them silently.
+pub(super) fn warn_on_version_mismatch(text: &str, warned: &mut bool) {
+    let Ok(envelope) = serde_json::from_str::<MessageEnvelopeV1>(text) else {
+        return;
+    };
+    if envelope.v == PROTOCOL_VERSION || *warned {
+        return;
+    }
+    *warned = true;
+    warn!(
+        message_type = %envelope.kind,
+        hub_version = envelope.v,
+        agent_version = PROTOCOL_VERSION,
+        "ignoring hub messages with an unsupported protocol version; upgrade the agent"
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use std::pin::Pin;
@@ -139,10 +198,12 @@ mod tests {
         AgentIdentityV1 {
             v: 1,
             hub_url: "http://localhost:9876/".to_string(),
+            hub_urls: Vec::new(),
             agent_id: "agent1".to_string(),
             agent_key: "k".to_string(),
             name: Some("n".to_string()),
             enrolled_at: 1,
+            client_cert: None,
         }
     }
 
@@ -164,7 +225,7 @@ mod tests {
         let id = identity();
         let mut sink = VecSink::default();
 
-        send_hello(&mut sink, &id).await?;
+        send_hello(&mut sink, &id, true, None).await?;
 
         assert_eq!(sink.sent.len(), 1);
         let Message::Text(text) = &sink.sent[0] else {
@@ -179,7 +240,11 @@ mod tests {
                 name,
                 info,
                 capabilities,
+                metrics,
+                protocol,
             } => {
+                assert!(metrics.is_none());
+                assert_eq!(protocol, Some(ProtocolCapabilitiesV1::current()));
                 assert_eq!(v, PROTOCOL_VERSION);
                 assert_eq!(agent_id, "agent1");
                 assert_eq!(name.as_deref(), Some("n"));
@@ -196,7 +261,10 @@ mod tests {
                     Some(std::env::consts::ARCH)
                 );
                 assert!(capabilities.get("backup").is_some());
-                assert!(capabilities.get("control").is_some());
+                assert_eq!(
+                    capabilities.get("control"),
+                    Some(&serde_json::json!(["fs_list", "self_update"]))
+                );
 
                 let source = capabilities
                     .get("drivers")
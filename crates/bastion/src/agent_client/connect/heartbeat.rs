@@ -2,15 +2,23 @@ use std::time::Duration;
 
 use tokio_tungstenite::tungstenite::Message;
 
-use bastion_core::agent_protocol::{AgentToHubMessageV1, PROTOCOL_VERSION};
+use bastion_core::agent_protocol::{AgentToHubMessageV1, HostMetricsV1, PROTOCOL_VERSION};
 
 pub(super) fn pong_timed_out(last_pong: &tokio::time::Instant, pong_timeout: Duration) -> bool {
     last_pong.elapsed() > pong_timeout
 }
 
-pub(super) fn ping_message() -> Result<Message, anyhow::Error> {
+pub(super) fn host_metrics_due(
+    last_sent: Option<tokio::time::Instant>,
+    interval: Duration,
+) -> bool {
+    last_sent.is_none_or(|at| at.elapsed() >= interval)
+}
+
+pub(super) fn ping_message(metrics: Option<HostMetricsV1>) -> Result<Message, anyhow::Error> {
     let ping = AgentToHubMessageV1::Ping {
         v: PROTOCOL_VERSION,
+        metrics,
     };
     Ok(Message::Text(serde_json::to_string(&ping)?.into()))
 }
@@ -39,17 +47,34 @@ mod tests {
 
     #[test]
     fn ping_message_serializes_as_agent_ping() -> Result<(), anyhow::Error> {
-        let Message::Text(text) = ping_message()? else {
+        let Message::Text(text) = ping_message(None)? else {
             anyhow::bail!("expected text message");
         };
         let msg: AgentToHubMessageV1 = serde_json::from_str(&text)?;
         match msg {
-            AgentToHubMessageV1::Ping { v } => assert_eq!(v, PROTOCOL_VERSION),
+            AgentToHubMessageV1::Ping { v, metrics } => {
+                assert_eq!(v, PROTOCOL_VERSION);
+                assert!(metrics.is_none());
+            }
             other => anyhow::bail!("unexpected message: {other:?}"),
         }
         Ok(())
     }
 
+    #[test]
+    fn host_metrics_are_due_once_per_interval() {
+        let interval = Duration::from_secs(60);
+        assert!(host_metrics_due(None, interval));
+        assert!(!host_metrics_due(
+            Some(tokio::time::Instant::now()),
+            interval
+        ));
+        let long_ago = tokio::time::Instant::now()
+            .checked_sub(Duration::from_secs(120))
+            .expect("instant supports checked_sub");
+        assert!(host_metrics_due(Some(long_ago), interval));
+    }
+
     #[test]
     fn close_message_is_close_frame() {
         assert!(matches!(close_message(), Message::Close(None)));
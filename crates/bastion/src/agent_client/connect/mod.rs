@@ -1,4 +1,5 @@
 mod cancel_registry;
+mod compression;
 mod handlers;
 mod handshake;
 mod heartbeat;
@@ -13,8 +14,9 @@ use tokio::sync::mpsc;
 use tokio_tungstenite::tungstenite;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
 use tokio_tungstenite::tungstenite::http::header::AUTHORIZATION;
-use tracing::warn;
+use tracing::{info, warn};
 use url::Url;
 use uuid::Uuid;
 
@@ -23,13 +25,21 @@ use bastion_core::agent_protocol::{
     HubToAgentMessageV1, PROTOCOL_VERSION,
 };
 use bastion_core::agent_stream::{
-    ArtifactChunkFrameV1Flags, decode_artifact_chunk_frame_v1, encode_artifact_chunk_frame_v1,
+    ArtifactChunkFrameV1Flags, WS_COMPRESSION_HEADER, WS_COMPRESSION_ZSTD,
+    decode_artifact_chunk_frame_v1, encode_artifact_chunk_frame_v1,
 };
 
+use super::client_tls;
+use super::host_metrics;
 use super::hub_stream::{HubStreamChunk, HubStreamManager};
 use super::identity::AgentIdentityV1;
+use super::list_throttle::ListThrottle;
 use super::offline;
-use super::util::normalize_base_url;
+use super::run_slots::RunSlots;
+use super::self_update;
+use super::task_queue::TaskQueue;
+use super::util::agent_ws_url;
+use crate::sd_notify;
 use cancel_registry::TaskCancelRegistry;
 
 const AGENT_CONNECT_OUTBOX_CAPACITY: usize = 512;
@@ -38,7 +48,11 @@ const FORCE_RECONNECT_SIGNAL_CAPACITY: usize = 8;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(super) enum LoopAction {
     Reconnect,
+    /// The connection dropped after the Hub announced it is shutting down.
+    HubShutdown,
     Exit,
+    /// A new binary was installed; exit so the service manager starts it.
+    Restart,
 }
 
 #[derive(Clone)]
@@ -83,28 +97,42 @@ impl Sink<Message> for OutboxSink {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn connect_and_run(
-    ws_url: &Url,
+    hub_url: &Url,
     identity: &AgentIdentityV1,
     data_dir: &Path,
     heartbeat: Duration,
     pong_timeout: Duration,
-    run_lock: std::sync::Arc<tokio::sync::Mutex<()>>,
+    run_slots: RunSlots,
+    task_queue: TaskQueue,
     connected_tx: &tokio::sync::watch::Sender<bool>,
+    update_trust: Option<&self_update::UpdateTrust>,
+    liveness: &sd_notify::Liveness,
 ) -> Result<LoopAction, anyhow::Error> {
+    let ws_url = agent_ws_url(hub_url)?;
     let mut req = ws_url.as_str().into_client_request()?;
     req.headers_mut().insert(
         AUTHORIZATION,
         format!("Bearer {}", identity.agent_key).parse()?,
     );
 
-    let (socket, _) = tokio_tungstenite::connect_async(req).await?;
+    req.headers_mut().insert(
+        WS_COMPRESSION_HEADER,
+        HeaderValue::from_static(WS_COMPRESSION_ZSTD),
+    );
+
+    let connector = client_tls::ws_connector(identity.client_cert.as_ref())?;
+    let (socket, response) =
+        tokio_tungstenite::connect_async_tls_with_config(req, None, false, connector).await?;
+    let compress = compression::negotiated(response.headers());
     let (mut ws_tx, mut ws_rx) = socket.split();
 
     // Outbox so long-running tasks can keep the main receive loop responsive (heartbeats + streams).
     let (out_tx, mut out_rx) = mpsc::channel::<Message>(AGENT_CONNECT_OUTBOX_CAPACITY);
     let send_task = tokio::spawn(async move {
         while let Some(msg) = out_rx.recv().await {
+            let msg = compression::compress_outgoing(msg, compress);
             if ws_tx.send(msg).await.is_err() {
                 break;
             }
@@ -115,15 +143,24 @@ pub(super) async fn connect_and_run(
     let data_dir = data_dir.to_path_buf();
     let hub_streams = HubStreamManager::new(out_tx.clone());
     let cancel_registry = TaskCancelRegistry::default();
+    let list_throttle = ListThrottle::default();
     let (force_reconnect_tx, mut force_reconnect_rx) =
         mpsc::channel::<()>(FORCE_RECONNECT_SIGNAL_CAPACITY);
+    let (restart_tx, mut restart_rx) = mpsc::channel::<()>(1);
 
     let _connected_guard = handshake::ConnectedGuard::new(connected_tx.clone());
-    handshake::send_hello(&mut tx, identity).await?;
-
-    if let Ok(base_url) = normalize_base_url(&identity.hub_url)
-        && let Err(error) =
-            offline::sync_offline_runs(&base_url, &identity.agent_key, &data_dir).await
+    let metrics = host_metrics::collect(&data_dir, &identity.agent_id).await;
+    let mut host_metrics_sent_at = metrics.as_ref().map(|_| tokio::time::Instant::now());
+    handshake::send_hello(&mut tx, identity, update_trust.is_some(), metrics).await?;
+
+    if let Err(error) = offline::sync_offline_runs(
+        hub_url,
+        &identity.agent_key,
+        identity.client_cert.as_ref(),
+        &data_dir,
+        &identity.agent_id,
+    )
+    .await
     {
         warn!(
             agent_id = %identity.agent_id,
@@ -134,6 +171,8 @@ pub(super) async fn connect_and_run(
 
     let mut tick = tokio::time::interval(heartbeat);
     let mut last_pong = tokio::time::Instant::now();
+    let mut version_mismatch_warned = false;
+    let mut hub_shutting_down = false;
     let shutdown = tokio::signal::ctrl_c();
     tokio::pin!(shutdown);
 
@@ -147,6 +186,7 @@ pub(super) async fn connect_and_run(
                 break 'main LoopAction::Exit;
             }
             _ = tick.tick() => {
+                liveness.beat();
                 if heartbeat::pong_timed_out(&last_pong, pong_timeout) {
                     warn!(
                         agent_id = %identity.agent_id,
@@ -156,7 +196,16 @@ pub(super) async fn connect_and_run(
                     break 'main LoopAction::Reconnect;
                 }
 
-                let ping = heartbeat::ping_message()?;
+                let metrics = if heartbeat::host_metrics_due(
+                    host_metrics_sent_at,
+                    host_metrics::HOST_METRICS_INTERVAL,
+                ) {
+                    host_metrics_sent_at = Some(tokio::time::Instant::now());
+                    host_metrics::collect(&data_dir, &identity.agent_id).await
+                } else {
+                    None
+                };
+                let ping = heartbeat::ping_message(metrics)?;
                 if tx.send(ping).await.is_err() {
                     break 'main LoopAction::Reconnect;
                 }
@@ -164,10 +213,26 @@ pub(super) async fn connect_and_run(
             Some(_) = force_reconnect_rx.recv() => {
                 break 'main LoopAction::Reconnect;
             }
+            Some(()) = restart_rx.recv() => {
+                let _ = tx.send(heartbeat::close_message()).await;
+                break 'main LoopAction::Restart;
+            }
             msg = ws_rx.next() => {
                 let Some(msg) = msg else {
                     break 'main LoopAction::Reconnect;
                 };
+                let msg = match msg.map(compression::decompress_incoming) {
+                    Ok(Ok(msg)) => Ok(msg),
+                    Ok(Err(error)) => {
+                        warn!(
+                            agent_id = %identity.agent_id,
+                            error = %error,
+                            "invalid binary frame from hub"
+                        );
+                        continue;
+                    }
+                    Err(error) => Err(error),
+                };
 
                 match msg {
                     Ok(Message::Text(text)) => {
@@ -176,6 +241,9 @@ pub(super) async fn connect_and_run(
                             Ok(HubToAgentMessageV1::Pong { .. }) => {
                                 last_pong = tokio::time::Instant::now();
                             }
+                            Ok(HubToAgentMessageV1::TaskResultAck { v, task_id, duplicate }) if v == PROTOCOL_VERSION => {
+                                handlers::handle_task_result_ack(&data_dir, task_id, duplicate);
+                            }
                             Ok(HubToAgentMessageV1::SecretsSnapshot { v, node_id, issued_at, webdav, backup_age_identities })
                                 if v == PROTOCOL_VERSION =>
                             {
@@ -210,7 +278,8 @@ pub(super) async fn connect_and_run(
                             Ok(HubToAgentMessageV1::Task { v, task_id, task }) if v == PROTOCOL_VERSION => {
                                 let out_tx = out_tx.clone();
                                 let data_dir = data_dir.clone();
-                                let run_lock = run_lock.clone();
+                                let run_slots = run_slots.clone();
+                                let task_queue = task_queue.clone();
                                 let cancel_registry = cancel_registry.clone();
                                 let force_reconnect_tx = force_reconnect_tx.clone();
                                 tokio::spawn(async move {
@@ -218,7 +287,8 @@ pub(super) async fn connect_and_run(
                                     let flow = handlers::handle_task(
                                         &mut tx,
                                         &data_dir,
-                                        run_lock,
+                                        &run_slots,
+                                        &task_queue,
                                         &cancel_registry,
                                         task_id,
                                         task,
@@ -242,7 +312,8 @@ pub(super) async fn connect_and_run(
                             Ok(HubToAgentMessageV1::RestoreTask { v, task_id, task }) if v == PROTOCOL_VERSION => {
                                 let out_tx = out_tx.clone();
                                 let data_dir = data_dir.clone();
-                                let run_lock = run_lock.clone();
+                                let run_slots = run_slots.clone();
+                                let task_queue = task_queue.clone();
                                 let hub_streams = hub_streams.clone();
                                 let cancel_registry = cancel_registry.clone();
                                 let force_reconnect_tx = force_reconnect_tx.clone();
@@ -251,7 +322,8 @@ pub(super) async fn connect_and_run(
                                     let flow = handlers::handle_restore_task(
                                         &mut tx,
                                         &data_dir,
-                                        run_lock,
+                                        &run_slots,
+                                        &task_queue,
                                         &cancel_registry,
                                         &hub_streams,
                                         task_id,
@@ -275,11 +347,11 @@ pub(super) async fn connect_and_run(
                             }
                             Ok(HubToAgentMessageV1::SnapshotDeleteTask { v, task }) if v == PROTOCOL_VERSION => {
                                 let out_tx = out_tx.clone();
-                                let run_lock = run_lock.clone();
+                                let run_slots = run_slots.clone();
                                 let force_reconnect_tx = force_reconnect_tx.clone();
                                 tokio::spawn(async move {
                                     let mut tx = OutboxSink { tx: out_tx };
-                                    let flow = handlers::handle_snapshot_delete_task(&mut tx, run_lock, task).await;
+                                    let flow = handlers::handle_snapshot_delete_task(&mut tx, &run_slots, task).await;
                                     match flow {
                                         Ok(handlers::HandlerFlow::Continue) => {}
                                         Ok(handlers::HandlerFlow::Reconnect) => {
@@ -306,13 +378,16 @@ pub(super) async fn connect_and_run(
                                 sort_dir,
                                 size_min_bytes,
                                 size_max_bytes,
+                                requester,
                             }) if v == PROTOCOL_VERSION => {
                                 let out_tx = out_tx.clone();
+                                let list_throttle = list_throttle.clone();
                                 let force_reconnect_tx = force_reconnect_tx.clone();
                                 tokio::spawn(async move {
                                     let mut tx = OutboxSink { tx: out_tx };
                                     let flow = handlers::handle_fs_list(
                                         &mut tx,
+                                        &list_throttle,
                                         handlers::FsListRequest {
                                             request_id,
                                             path,
@@ -326,6 +401,7 @@ pub(super) async fn connect_and_run(
                                             sort_dir,
                                             size_min_bytes,
                                             size_max_bytes,
+                                            requester,
                                         },
                                     )
                                     .await;
@@ -357,15 +433,18 @@ pub(super) async fn connect_and_run(
                                 sort_dir,
                                 size_min_bytes,
                                 size_max_bytes,
+                                requester,
                             }) if v == PROTOCOL_VERSION => {
                                 let out_tx = out_tx.clone();
                                 let data_dir = data_dir.clone();
+                                let list_throttle = list_throttle.clone();
                                 let force_reconnect_tx = force_reconnect_tx.clone();
                                 tokio::spawn(async move {
                                     let mut tx = OutboxSink { tx: out_tx };
                                     let flow = handlers::handle_webdav_list(
                                         &mut tx,
                                         &data_dir,
+                                        &list_throttle,
                                         handlers::WebdavListRequest {
                                             request_id,
                                             base_url,
@@ -381,6 +460,7 @@ pub(super) async fn connect_and_run(
                                             sort_dir,
                                             size_min_bytes,
                                             size_max_bytes,
+                                            requester,
                                         },
                                     )
                                     .await;
@@ -396,6 +476,62 @@ pub(super) async fn connect_and_run(
                                     }
                                 });
                             }
+                            Ok(HubToAgentMessageV1::Diagnostics { v, request_id, job_id })
+                                if v == PROTOCOL_VERSION =>
+                            {
+                                let out_tx = out_tx.clone();
+                                let data_dir = data_dir.clone();
+                                let agent_id = identity.agent_id.clone();
+                                let force_reconnect_tx = force_reconnect_tx.clone();
+                                tokio::spawn(async move {
+                                    let mut tx = OutboxSink { tx: out_tx };
+                                    let flow = handlers::handle_diagnostics(
+                                        &mut tx,
+                                        &data_dir,
+                                        &agent_id,
+                                        request_id,
+                                        job_id,
+                                    )
+                                    .await;
+                                    match flow {
+                                        Ok(handlers::HandlerFlow::Continue) => {}
+                                        Ok(handlers::HandlerFlow::Reconnect) => {
+                                            let _ = force_reconnect_tx.send(()).await;
+                                        }
+                                        Err(error) => {
+                                            warn!(error = %error, "diagnostics handler failed");
+                                            let _ = force_reconnect_tx.send(()).await;
+                                        }
+                                    }
+                                });
+                            }
+                            Ok(HubToAgentMessageV1::UpdateAvailable { v, update }) if v == PROTOCOL_VERSION => {
+                                let Some(trust) = update_trust.cloned() else {
+                                    warn!(
+                                        version = %update.version,
+                                        "ignoring agent update: no update public key configured"
+                                    );
+                                    continue;
+                                };
+                                let base_url = hub_url.clone();
+                                let agent_key = identity.agent_key.clone();
+                                let client_cert = identity.client_cert.clone();
+                                let run_slots = run_slots.clone();
+                                let restart_tx = restart_tx.clone();
+                                tokio::spawn(async move {
+                                    // Wait for running tasks to finish (and hold off new ones) so the
+                                    // restart does not interrupt them.
+                                    let _all = run_slots.acquire_all().await;
+                                    match self_update::download_and_install(&base_url, &agent_key, client_cert.as_ref(), &trust, &update).await {
+                                        Ok(()) => {
+                                            let _ = restart_tx.send(()).await;
+                                        }
+                                        Err(error) => {
+                                            warn!(version = %update.version, error = %error, "agent update failed");
+                                        }
+                                    }
+                                });
+                            }
                             Ok(HubToAgentMessageV1::ArtifactStreamOpenResult { v, res }) if v == PROTOCOL_VERSION => {
                                 hub_streams.complete_open(res).await;
                             }
@@ -441,7 +577,19 @@ pub(super) async fn connect_and_run(
                                     let _ = hub_pull_streams.remove(&stream_id);
                                 }
                             }
-                            _ => {}
+                            Ok(HubToAgentMessageV1::HubShutdown { v, drain_timeout_secs }) if v == PROTOCOL_VERSION => {
+                                // Tasks keep running; their results are replayed after reconnecting.
+                                info!(
+                                    agent_id = %identity.agent_id,
+                                    drain_timeout_seconds = drain_timeout_secs,
+                                    "hub is shutting down"
+                                );
+                                hub_shutting_down = true;
+                            }
+                            Ok(HubToAgentMessageV1::HelloAck { v, protocol }) if v == PROTOCOL_VERSION => {
+                                handshake::log_negotiated_protocol(&identity.agent_id, &protocol);
+                            }
+                            _ => handshake::warn_on_version_mismatch(&text, &mut version_mismatch_warned),
                         }
                     }
                     Ok(Message::Binary(bytes)) => {
@@ -470,6 +618,9 @@ pub(super) async fn connect_and_run(
     };
 
     send_task.abort();
+    if action == LoopAction::Reconnect && hub_shutting_down {
+        return Ok(LoopAction::HubShutdown);
+    }
     Ok(action)
 }
 
@@ -0,0 +1,349 @@
+use std::path::Path;
+use std::time::Duration;
+
+use bastion_core::agent_protocol::{
+    DiagnosticCheckV1, DiagnosticsReportV1, JobConfigV1, JobSpecResolvedV1, TargetResolvedV1,
+};
+use bastion_targets::{WebdavClient, WebdavCredentials, WebdavHttpError};
+use url::Url;
+
+use super::host_metrics::{DISK_ROLE_STAGING, disk_usage, job_source_paths};
+use super::managed::load_managed_config_snapshot;
+
+/// Staging below this much free space is reported as a warning.
+const LOW_STAGING_SPACE_BYTES: u64 = 1024 * 1024 * 1024;
+const TARGET_PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+
+const STATUS_OK: &str = "ok";
+const STATUS_WARN: &str = "warn";
+const STATUS_FAIL: &str = "fail";
+
+/// Runs the fixed diagnostic checks against the managed config (optionally one job).
+///
+/// Nothing here runs caller-supplied commands: the Hub can only pick which managed job to check.
+pub(super) async fn run(
+    data_dir: &Path,
+    agent_id: &str,
+    job_id: Option<&str>,
+) -> DiagnosticsReportV1 {
+    let mut checks = Vec::new();
+
+    let jobs = match load_managed_config_snapshot(data_dir, agent_id) {
+        Ok(v) => v.map(|config| config.jobs).unwrap_or_default(),
+        Err(error) => {
+            checks.push(check(
+                "job_config",
+                None,
+                data_dir.to_string_lossy(),
+                STATUS_FAIL,
+                Some(format!("failed to load managed config: {error}")),
+            ));
+            Vec::new()
+        }
+    };
+    let jobs = match job_id {
+        Some(job_id) => {
+            let selected = jobs
+                .into_iter()
+                .filter(|job| job.job_id == job_id)
+                .collect::<Vec<_>>();
+            if selected.is_empty() {
+                checks.push(check(
+                    "job_config",
+                    Some(job_id),
+                    job_id,
+                    STATUS_FAIL,
+                    Some("job is not in the agent's config yet; sync config and retry".to_string()),
+                ));
+            }
+            selected
+        }
+        None => jobs,
+    };
+
+    let local = {
+        let data_dir = data_dir.to_path_buf();
+        let jobs = jobs.clone();
+        tokio::task::spawn_blocking(move || local_checks(&data_dir, &jobs))
+            .await
+            .unwrap_or_default()
+    };
+    checks.extend(local);
+
+    for job in &jobs {
+        if let TargetResolvedV1::Webdav {
+            base_url,
+            username,
+            password,
+            ..
+        } = job_target(&job.spec)
+        {
+            checks.push(check_webdav_target(&job.job_id, base_url, username, password).await);
+        }
+    }
+
+    DiagnosticsReportV1 {
+        collected_at: time::OffsetDateTime::now_utc().unix_timestamp(),
+        checks,
+    }
+}
+
+fn check(
+    check: &str,
+    job_id: Option<&str>,
+    subject: impl Into<String>,
+    status: &str,
+    message: Option<String>,
+) -> DiagnosticCheckV1 {
+    DiagnosticCheckV1 {
+        check: check.to_string(),
+        job_id: job_id.map(str::to_string),
+        subject: subject.into(),
+        status: status.to_string(),
+        message,
+        available_bytes: None,
+    }
+}
+
+fn job_target(spec: &JobSpecResolvedV1) -> &TargetResolvedV1 {
+    match spec {
+        JobSpecResolvedV1::Filesystem { target, .. }
+        | JobSpecResolvedV1::Sqlite { target, .. }
+        | JobSpecResolvedV1::Vaultwarden { target, .. }
+        | JobSpecResolvedV1::SqliteApp { target, .. }
+        | JobSpecResolvedV1::Postgres { target, .. }
+        | JobSpecResolvedV1::VmImage { target, .. }
+        | JobSpecResolvedV1::KubernetesPvc { target, .. }
+        | JobSpecResolvedV1::Imap { target, .. }
+        | JobSpecResolvedV1::Git { target, .. } => target,
+    }
+}
+
+/// Staging space and writability, source access and local-dir targets.
+fn local_checks(data_dir: &Path, jobs: &[JobConfigV1]) -> Vec<DiagnosticCheckV1> {
+    let mut checks = Vec::new();
+    let staging = data_dir.to_string_lossy().into_owned();
+
+    match disk_usage(DISK_ROLE_STAGING, data_dir) {
+        Some(disk) => {
+            let (status, message) = if disk.available_bytes < LOW_STAGING_SPACE_BYTES {
+                (STATUS_WARN, Some("less than 1 GiB free".to_string()))
+            } else {
+                (STATUS_OK, None)
+            };
+            let mut space = check("staging_space", None, staging.clone(), status, message);
+            space.available_bytes = Some(disk.available_bytes);
+            checks.push(space);
+        }
+        None => checks.push(check(
+            "staging_space",
+            None,
+            staging.clone(),
+            STATUS_WARN,
+            Some("free space is not available on this platform".to_string()),
+        )),
+    }
+
+    checks.push(match probe_writable(data_dir) {
+        Ok(()) => check("staging_writable", None, staging, STATUS_OK, None),
+        Err(error) => check(
+            "staging_writable",
+            None,
+            staging,
+            STATUS_FAIL,
+            Some(io_error_message(&error)),
+        ),
+    });
+
+    for job in jobs {
+        for path in job_source_paths(&job.spec) {
+            let path = path.trim();
+            if path.is_empty() {
+                continue;
+            }
+            checks.push(check_source(&job.job_id, path));
+        }
+        if let TargetResolvedV1::LocalDir { base_dir, .. } = job_target(&job.spec) {
+            checks.push(check_local_dir_target(&job.job_id, base_dir));
+        }
+    }
+
+    checks
+}
+
+fn check_source(job_id: &str, path: &str) -> DiagnosticCheckV1 {
+    let p = Path::new(path);
+    let result = match std::fs::metadata(p) {
+        Ok(meta) if meta.is_dir() => std::fs::read_dir(p).map(|_| ()),
+        Ok(_) => std::fs::File::open(p).map(|_| ()),
+        Err(error) => Err(error),
+    };
+    match result {
+        Ok(()) => check("source_access", Some(job_id), path, STATUS_OK, None),
+        Err(error) => check(
+            "source_access",
+            Some(job_id),
+            path,
+            STATUS_FAIL,
+            Some(io_error_message(&error)),
+        ),
+    }
+}
+
+fn check_local_dir_target(job_id: &str, base_dir: &str) -> DiagnosticCheckV1 {
+    let dir = Path::new(base_dir.trim());
+    if !dir.exists() {
+        return check(
+            "target_reachable",
+            Some(job_id),
+            base_dir,
+            STATUS_WARN,
+            Some("does not exist yet; it is created on the first run".to_string()),
+        );
+    }
+
+    let mut out = match probe_writable(dir) {
+        Ok(()) => check("target_reachable", Some(job_id), base_dir, STATUS_OK, None),
+        Err(error) => check(
+            "target_reachable",
+            Some(job_id),
+            base_dir,
+            STATUS_FAIL,
+            Some(io_error_message(&error)),
+        ),
+    };
+    out.available_bytes = disk_usage(DISK_ROLE_STAGING, dir).map(|disk| disk.available_bytes);
+    out
+}
+
+async fn check_webdav_target(
+    job_id: &str,
+    base_url: &str,
+    username: &str,
+    password: &str,
+) -> DiagnosticCheckV1 {
+    let subject = base_url.trim();
+    let fail = |message: String| {
+        check(
+            "target_reachable",
+            Some(job_id),
+            subject,
+            STATUS_FAIL,
+            Some(message),
+        )
+    };
+
+    let mut url = match Url::parse(subject) {
+        Ok(v) => v,
+        Err(error) => return fail(format!("invalid base_url: {error}")),
+    };
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    let client = match WebdavClient::new(
+        url.clone(),
+        WebdavCredentials {
+            username: username.to_string(),
+            password: password.to_string(),
+        },
+    ) {
+        Ok(v) => v,
+        Err(error) => return fail(format!("failed to init webdav client: {error}")),
+    };
+
+    match tokio::time::timeout(TARGET_PROBE_TIMEOUT, client.propfind_depth1(&url)).await {
+        Err(_) => fail("timed out".to_string()),
+        Ok(Ok(_)) => check("target_reachable", Some(job_id), subject, STATUS_OK, None),
+        Ok(Err(error)) => match error.downcast_ref::<WebdavHttpError>().map(|e| e.status) {
+            Some(reqwest::StatusCode::NOT_FOUND) => check(
+                "target_reachable",
+                Some(job_id),
+                subject,
+                STATUS_WARN,
+                Some("does not exist yet; it is created on the first run".to_string()),
+            ),
+            Some(reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN) => {
+                fail(format!("authentication failed: {error}"))
+            }
+            _ => fail(error.to_string()),
+        },
+    }
+}
+
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!(".bastion-diagnostics-{}", uuid::Uuid::new_v4()));
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)?;
+    std::fs::remove_file(&probe)
+}
+
+fn io_error_message(error: &std::io::Error) -> String {
+    match error.kind() {
+        std::io::ErrorKind::NotFound => "not found".to_string(),
+        std::io::ErrorKind::PermissionDenied => "permission denied".to_string(),
+        _ => error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bastion_core::agent_protocol::{JobConfigV1, OverlapPolicyV1};
+
+    use super::local_checks;
+
+    fn job(spec: serde_json::Value) -> JobConfigV1 {
+        JobConfigV1 {
+            job_id: "j".to_string(),
+            name: "j".to_string(),
+            schedule: None,
+            schedule_timezone: None,
+            overlap_policy: OverlapPolicyV1::Queue,
+            updated_at: 0,
+            spec: serde_json::from_value(spec).expect("spec"),
+        }
+    }
+
+    #[test]
+    fn local_checks_report_sources_and_local_targets() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let source = tmp.path().join("src");
+        std::fs::create_dir_all(&source).expect("mkdir");
+        let missing = tmp.path().join("missing");
+        let target = tmp.path().join("backup");
+
+        let jobs = vec![job(serde_json::json!({
+            "type": "filesystem",
+            "v": 1,
+            "source": { "paths": [source.to_string_lossy(), missing.to_string_lossy()] },
+            "target": { "type": "local_dir", "base_dir": target.to_string_lossy(), "part_size_bytes": 1024 },
+        }))];
+
+        let checks = local_checks(tmp.path(), &jobs);
+        let status = |name: &str, subject: &std::path::Path| {
+            checks
+                .iter()
+                .find(|c| c.check == name && c.subject == subject.to_string_lossy())
+                .map(|c| c.status.as_str())
+        };
+
+        assert_eq!(status("staging_writable", tmp.path()), Some("ok"));
+        assert_eq!(status("source_access", &source), Some("ok"));
+        assert_eq!(status("source_access", &missing), Some("fail"));
+        assert_eq!(status("target_reachable", &target), Some("warn"));
+
+        std::fs::create_dir_all(&target).expect("mkdir target");
+        let checks = local_checks(tmp.path(), &jobs);
+        assert!(checks.iter().any(|c| c.check == "target_reachable"
+            && c.subject == target.to_string_lossy()
+            && c.status == "ok"));
+        assert!(
+            std::fs::read_dir(&target)
+                .expect("read target")
+                .next()
+                .is_none(),
+            "write probe must clean up"
+        );
+    }
+}
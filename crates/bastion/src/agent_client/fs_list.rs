@@ -6,6 +6,7 @@ use std::time::UNIX_EPOCH;
 use base64::Engine as _;
 use bastion_core::agent_protocol::FsDirEntryV1;
 use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone)]
 pub(super) struct FsListOptions {
@@ -46,6 +47,10 @@ impl FsListError {
         Self::new("invalid_cursor", message)
     }
 
+    fn superseded() -> Self {
+        Self::new("superseded", "superseded by a newer list request")
+    }
+
     fn from_io(path: &str, error: std::io::Error) -> Self {
         match error.kind() {
             std::io::ErrorKind::NotFound => {
@@ -245,9 +250,12 @@ fn read_meta(path: &PathBuf) -> (u64, Option<i64>) {
     (size, mtime)
 }
 
+/// Lists `path` one page at a time; `cancel` is checked per directory entry so a superseded
+/// listing stops scanning instead of running to completion in the background.
 pub(super) fn fs_list_dir_entries_paged(
     path: &str,
     opts: FsListOptions,
+    cancel: &CancellationToken,
 ) -> Result<FsListPage, FsListError> {
     const DEFAULT_LIMIT: u32 = 200;
     const MAX_LIMIT: u32 = 2000;
@@ -278,6 +286,9 @@ pub(super) fn fs_list_dir_entries_paged(
         let mut out = Vec::<FsDirEntryV1>::new();
         let entries = std::fs::read_dir(&dir).map_err(|e| FsListError::from_io(path, e))?;
         for entry in entries {
+            if cancel.is_cancelled() {
+                return Err(FsListError::superseded());
+            }
             let entry = entry
                 .map_err(|e| FsListError::new("error", format!("read_dir entry failed: {e}")))?;
             let name = entry.file_name().to_string_lossy().to_string();
@@ -367,6 +378,9 @@ pub(super) fn fs_list_dir_entries_paged(
 
     let entries = std::fs::read_dir(&dir).map_err(|e| FsListError::from_io(path, e))?;
     for entry in entries {
+        if cancel.is_cancelled() {
+            return Err(FsListError::superseded());
+        }
         let entry =
             entry.map_err(|e| FsListError::new("error", format!("read_dir entry failed: {e}")))?;
         let name = entry.file_name().to_string_lossy().to_string();
@@ -550,6 +564,7 @@ mod tests {
                     size_min_bytes: None,
                     size_max_bytes: None,
                 },
+                &CancellationToken::new(),
             )
             .unwrap();
             assert!(full.entries.len() >= 7);
@@ -568,6 +583,7 @@ mod tests {
                     size_min_bytes: None,
                     size_max_bytes: None,
                 },
+                &CancellationToken::new(),
             )
             .unwrap();
             assert_eq!(page1.entries.len(), 3);
@@ -587,6 +603,7 @@ mod tests {
                     size_min_bytes: None,
                     size_max_bytes: None,
                 },
+                &CancellationToken::new(),
             )
             .unwrap();
             assert_eq!(page2.entries.len(), 3);
@@ -594,9 +611,39 @@ mod tests {
             let combined = page1
                 .entries
                 .into_iter()
-                .chain(page2.entries.into_iter())
+                .chain(page2.entries)
                 .collect::<Vec<_>>();
             assert_eq!(&combined[..], &full.entries[..combined.len()]);
         }
     }
+    #[test]
+    fn fs_list_dir_entries_paged_stops_when_cancelled() {
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        for limit in [None, Some(10)] {
+            let err = fs_list_dir_entries_paged(
+                dir.path().to_string_lossy().as_ref(),
+                FsListOptions {
+                    cursor: None,
+                    limit,
+                    q: None,
+                    kind: None,
+                    hide_dotfiles: false,
+                    type_sort: None,
+                    sort_by: None,
+                    sort_dir: None,
+                    size_min_bytes: None,
+                    size_max_bytes: None,
+                },
+                &cancel,
+            )
+            .unwrap_err();
+            assert_eq!(err.code, "superseded");
+        }
+    }
 }
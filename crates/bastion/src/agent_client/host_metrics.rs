@@ -0,0 +1,249 @@
+use std::path::Path;
+use std::time::Duration;
+
+use bastion_core::agent_protocol::{DiskUsageV1, HostMetricsV1, JobConfigV1, JobSpecResolvedV1};
+use bastion_core::job_spec::KubernetesPvcModeV1;
+use tracing::debug;
+
+use super::managed::load_managed_config_snapshot;
+
+/// Host metrics ride along with a heartbeat at most this often.
+pub(super) const HOST_METRICS_INTERVAL: Duration = Duration::from_secs(60);
+
+const MAX_SOURCE_DISKS: usize = 16;
+
+pub(super) const DISK_ROLE_STAGING: &str = "staging";
+pub(super) const DISK_ROLE_SOURCE: &str = "source";
+
+/// Samples load, memory and free space on the staging dir and managed job sources.
+///
+/// Runs on the blocking pool; returns `None` only if that task failed.
+pub(super) async fn collect(data_dir: &Path, agent_id: &str) -> Option<HostMetricsV1> {
+    let data_dir = data_dir.to_path_buf();
+    let agent_id = agent_id.to_string();
+    tokio::task::spawn_blocking(move || collect_blocking(&data_dir, &agent_id))
+        .await
+        .ok()
+}
+
+fn collect_blocking(data_dir: &Path, agent_id: &str) -> HostMetricsV1 {
+    let (mem_total_bytes, mem_available_bytes) = read_memory();
+
+    // Runs are staged under `<data_dir>/runs/<run_id>/staging`.
+    let mut disks = Vec::new();
+    if let Some(disk) = disk_usage(DISK_ROLE_STAGING, data_dir) {
+        disks.push(disk);
+    }
+
+    let jobs = match load_managed_config_snapshot(data_dir, agent_id) {
+        Ok(v) => v.map(|config| config.jobs).unwrap_or_default(),
+        Err(error) => {
+            debug!(error = %error, "failed to load managed config for host metrics");
+            Vec::new()
+        }
+    };
+    for path in source_paths(&jobs) {
+        if let Some(disk) = disk_usage(DISK_ROLE_SOURCE, Path::new(&path)) {
+            disks.push(disk);
+        }
+    }
+
+    HostMetricsV1 {
+        collected_at: time::OffsetDateTime::now_utc().unix_timestamp(),
+        load_avg: read_load_avg(),
+        mem_total_bytes,
+        mem_available_bytes,
+        disks,
+    }
+}
+
+/// Distinct source paths of the managed jobs, in job order, capped at `MAX_SOURCE_DISKS`.
+fn source_paths(jobs: &[JobConfigV1]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for job in jobs {
+        for path in job_source_paths(&job.spec) {
+            let path = path.trim();
+            if path.is_empty() || out.iter().any(|p| p == path) {
+                continue;
+            }
+            if out.len() >= MAX_SOURCE_DISKS {
+                return out;
+            }
+            out.push(path.to_string());
+        }
+    }
+    out
+}
+
+/// The paths a job reads from: the filesystem `paths` (or `root`), the SQLite file or the data dir.
+pub(super) fn job_source_paths(spec: &JobSpecResolvedV1) -> Vec<String> {
+    match spec {
+        JobSpecResolvedV1::Filesystem { source, .. } => {
+            if source.paths.is_empty() {
+                vec![source.root.clone()]
+            } else {
+                source.paths.clone()
+            }
+        }
+        JobSpecResolvedV1::Sqlite { source, .. } => source
+            .database_list()
+            .into_iter()
+            .map(|db| db.path)
+            .collect(),
+        JobSpecResolvedV1::Vaultwarden { source, .. } => vec![source.data_dir.clone()],
+        JobSpecResolvedV1::SqliteApp { source, .. } => vec![source.data_dir.clone()],
+        JobSpecResolvedV1::Postgres { source, .. } => vec![source.wal_spool_dir.clone()],
+        JobSpecResolvedV1::VmImage { .. } => Vec::new(),
+        JobSpecResolvedV1::KubernetesPvc { source, .. } => match source.mode {
+            KubernetesPvcModeV1::Mounted => vec![source.effective_mount_root().to_string()],
+            KubernetesPvcModeV1::CsiSnapshot => Vec::new(),
+        },
+        JobSpecResolvedV1::Imap { .. } | JobSpecResolvedV1::Git { .. } => Vec::new(),
+    }
+}
+
+#[cfg(unix)]
+pub(super) fn disk_usage(role: &str, path: &Path) -> Option<DiskUsageV1> {
+    let stat = rustix::fs::statvfs(path).ok()?;
+    Some(DiskUsageV1 {
+        role: role.to_string(),
+        path: path.to_string_lossy().into_owned(),
+        total_bytes: stat.f_blocks.saturating_mul(stat.f_frsize),
+        available_bytes: stat.f_bavail.saturating_mul(stat.f_frsize),
+    })
+}
+
+#[cfg(not(unix))]
+pub(super) fn disk_usage(_role: &str, _path: &Path) -> Option<DiskUsageV1> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn read_load_avg() -> Option<[f64; 3]> {
+    parse_load_avg(&std::fs::read_to_string("/proc/loadavg").ok()?)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_load_avg() -> Option<[f64; 3]> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn read_memory() -> (Option<u64>, Option<u64>) {
+    match std::fs::read_to_string("/proc/meminfo") {
+        Ok(text) => parse_meminfo(&text),
+        Err(_) => (None, None),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_memory() -> (Option<u64>, Option<u64>) {
+    (None, None)
+}
+
+/// Parses `/proc/loadavg` (`"0.52 0.58 0.59 1/467 12345"`).
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_load_avg(text: &str) -> Option<[f64; 3]> {
+    let mut fields = text.split_whitespace();
+    let mut out = [0.0; 3];
+    for slot in &mut out {
+        *slot = fields.next()?.parse().ok()?;
+    }
+    Some(out)
+}
+
+/// Parses `MemTotal` / `MemAvailable` (reported in kB) from `/proc/meminfo`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_meminfo(text: &str) -> (Option<u64>, Option<u64>) {
+    let field = |name: &str| {
+        text.lines().find_map(|line| {
+            let rest = line.strip_prefix(name)?.strip_prefix(':')?;
+            let kb = rest.trim().trim_end_matches("kB").trim();
+            kb.parse::<u64>().ok().map(|kb| kb.saturating_mul(1024))
+        })
+    };
+    (field("MemTotal"), field("MemAvailable"))
+}
+
+#[cfg(test)]
+mod tests {
+    use bastion_core::agent_protocol::{JobConfigV1, OverlapPolicyV1};
+
+    use super::{DISK_ROLE_STAGING, parse_load_avg, parse_meminfo, source_paths};
+
+    fn job(spec: serde_json::Value) -> JobConfigV1 {
+        JobConfigV1 {
+            job_id: "j".to_string(),
+            name: "j".to_string(),
+            schedule: None,
+            schedule_timezone: None,
+            overlap_policy: OverlapPolicyV1::Queue,
+            updated_at: 0,
+            spec: serde_json::from_value(spec).expect("spec"),
+        }
+    }
+
+    #[test]
+    fn load_avg_parses_first_three_fields() {
+        assert_eq!(
+            parse_load_avg("0.52 0.58 0.59 1/467 12345\n"),
+            Some([0.52, 0.58, 0.59])
+        );
+        assert_eq!(parse_load_avg("0.52 oops"), None);
+        assert_eq!(parse_load_avg(""), None);
+    }
+
+    #[test]
+    fn meminfo_reports_total_and_available_in_bytes() {
+        let text = "MemTotal:        8000000 kB\nMemFree:          100000 kB\nMemAvailable:    2000000 kB\n";
+        assert_eq!(
+            parse_meminfo(text),
+            (Some(8_000_000 * 1024), Some(2_000_000 * 1024))
+        );
+        // Old kernels don't report MemAvailable.
+        assert_eq!(
+            parse_meminfo("MemTotal: 1024 kB\n"),
+            (Some(1024 * 1024), None)
+        );
+    }
+
+    #[test]
+    fn source_paths_are_deduplicated_across_jobs() {
+        let target = serde_json::json!({ "type": "local_dir", "base_dir": "/backup", "part_size_bytes": 1024 });
+        let jobs = vec![
+            job(serde_json::json!({
+                "type": "filesystem",
+                "v": 1,
+                "source": { "paths": ["/srv/a", "/srv/b"] },
+                "target": target,
+            })),
+            job(serde_json::json!({
+                "type": "filesystem",
+                "v": 1,
+                "source": { "root": "/srv/a" },
+                "target": target,
+            })),
+            job(serde_json::json!({
+                "type": "sqlite",
+                "v": 1,
+                "source": { "path": "/var/db/app.sqlite3" },
+                "target": target,
+            })),
+        ];
+
+        assert_eq!(
+            source_paths(&jobs),
+            vec!["/srv/a", "/srv/b", "/var/db/app.sqlite3"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn disk_usage_reports_existing_paths_only() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let disk = super::disk_usage(DISK_ROLE_STAGING, dir.path()).expect("disk usage");
+        assert_eq!(disk.role, DISK_ROLE_STAGING);
+        assert!(disk.total_bytes >= disk.available_bytes);
+        assert!(super::disk_usage(DISK_ROLE_STAGING, &dir.path().join("missing")).is_none());
+    }
+}
@@ -8,11 +8,46 @@ const IDENTITY_FILE_NAME: &str = "agent.json";
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(super) struct AgentIdentityV1 {
     pub(super) v: u32,
+    /// Preferred Hub URL; always the first of [`Self::hub_urls`].
     pub(super) hub_url: String,
+    /// Every Hub URL the agent may connect to, in the order they are tried. Identities written
+    /// before fallbacks existed only have `hub_url`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(super) hub_urls: Vec<String>,
     pub(super) agent_id: String,
     pub(super) agent_key: String,
     pub(super) name: Option<String>,
     pub(super) enrolled_at: i64,
+    /// Client certificate issued by the Hub; presented when the Hub requires mTLS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) client_cert: Option<AgentClientCertV1>,
+}
+
+impl AgentIdentityV1 {
+    /// Stored Hub URLs, preferred first.
+    pub(super) fn hub_urls(&self) -> Vec<&str> {
+        if self.hub_urls.is_empty() {
+            vec![self.hub_url.as_str()]
+        } else {
+            self.hub_urls.iter().map(String::as_str).collect()
+        }
+    }
+
+    /// Replaces the stored Hub URLs; `urls` must not be empty.
+    pub(super) fn set_hub_urls(&mut self, urls: &[Url]) {
+        self.hub_url = urls[0].to_string();
+        self.hub_urls = if urls.len() > 1 {
+            urls.iter().map(Url::to_string).collect()
+        } else {
+            Vec::new()
+        };
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(super) struct AgentClientCertV1 {
+    pub(super) cert_pem: String,
+    pub(super) key_pem: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -26,6 +61,9 @@ struct EnrollRequest<'a> {
 pub(super) struct EnrollResponse {
     pub(super) agent_id: String,
     pub(super) agent_key: String,
+    // Older Hubs don't issue client certificates.
+    #[serde(default)]
+    pub(super) client_cert: Option<AgentClientCertV1>,
 }
 
 pub(super) fn identity_path(data_dir: &Path) -> PathBuf {
@@ -92,14 +130,36 @@ mod tests {
         let id = super::AgentIdentityV1 {
             v: 1,
             hub_url: "http://localhost:9876/".to_string(),
+            hub_urls: Vec::new(),
             agent_id: "a".to_string(),
             agent_key: "k".to_string(),
             name: Some("n".to_string()),
             enrolled_at: 1,
+            client_cert: None,
         };
 
         save_identity(&path, &id).unwrap();
         let saved = std::fs::read_to_string(&path).unwrap();
         assert!(saved.contains("\"agent_id\""));
     }
+
+    #[test]
+    fn hub_urls_fall_back_to_the_single_stored_url() {
+        let legacy = r#"{"v":1,"hub_url":"http://old:9876/","agent_id":"a","agent_key":"k","name":null,"enrolled_at":1}"#;
+        let mut id = serde_json::from_str::<super::AgentIdentityV1>(legacy).unwrap();
+        assert_eq!(id.hub_urls(), ["http://old:9876/"]);
+
+        let urls =
+            ["https://new.example.com/", "http://old:9876/"].map(|v| url::Url::parse(v).unwrap());
+        id.set_hub_urls(&urls);
+        assert_eq!(id.hub_url, "https://new.example.com/");
+        assert_eq!(
+            id.hub_urls(),
+            ["https://new.example.com/", "http://old:9876/"]
+        );
+
+        id.set_hub_urls(&urls[..1]);
+        assert_eq!(id.hub_urls(), ["https://new.example.com/"]);
+        assert!(!serde_json::to_string(&id).unwrap().contains("hub_urls"));
+    }
 }
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bastion_core::agent_protocol::FsDirEntryV1;
+use tokio_util::sync::CancellationToken;
+
+// Browsing in the UI issues bursts of list requests (typing in the filter box, paging, navigating).
+// Keep the burst allowance generous but bounded so a misbehaving client can't hammer the host.
+const LIST_BUCKET_CAPACITY: f64 = 10.0;
+const LIST_BUCKET_REFILL_PER_SEC: f64 = 4.0;
+
+const LIST_CACHE_TTL: Duration = Duration::from_secs(5);
+const LIST_CACHE_MAX_ENTRIES: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum ListKind {
+    Fs,
+    Webdav,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct CachedListPage {
+    pub entries: Vec<FsDirEntryV1>,
+    pub next_cursor: Option<String>,
+    pub total: u64,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(now: Instant) -> Self {
+        Self {
+            tokens: LIST_BUCKET_CAPACITY,
+            updated_at: now,
+        }
+    }
+
+    fn try_take(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * LIST_BUCKET_REFILL_PER_SEC).min(LIST_BUCKET_CAPACITY);
+        self.updated_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    page: CachedListPage,
+    stored_at: Instant,
+}
+
+#[derive(Debug)]
+struct InFlight {
+    seq: u64,
+    token: CancellationToken,
+}
+
+#[derive(Debug)]
+struct Inner {
+    bucket: TokenBucket,
+    cache: HashMap<(ListKind, String), CacheEntry>,
+    in_flight: HashMap<(ListKind, String), InFlight>,
+    next_seq: u64,
+}
+
+/// Per-agent guard for directory listing requests (`fs_list` / `webdav_list`).
+///
+/// Combines a token-bucket rate limit, a short-lived response cache, and "latest wins"
+/// cancellation: a newer listing of the same kind from the same requester supersedes the one
+/// still running. Requests without a requester id (older Hubs) only supersede the same target.
+#[derive(Debug, Clone)]
+pub(super) struct ListThrottle {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Default for ListThrottle {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                bucket: TokenBucket::new(Instant::now()),
+                cache: HashMap::new(),
+                in_flight: HashMap::new(),
+                next_seq: 0,
+            })),
+        }
+    }
+}
+
+/// Registration of an in-flight listing; dropping it releases the slot if still current.
+pub(super) struct ListTicket {
+    throttle: ListThrottle,
+    slot: (ListKind, String),
+    seq: u64,
+    token: CancellationToken,
+}
+
+impl ListTicket {
+    pub(super) fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for ListTicket {
+    fn drop(&mut self) {
+        let mut guard = self
+            .throttle
+            .inner
+            .lock()
+            .expect("list throttle mutex poisoned");
+        if guard
+            .in_flight
+            .get(&self.slot)
+            .is_some_and(|v| v.seq == self.seq)
+        {
+            let _ = guard.in_flight.remove(&self.slot);
+        }
+    }
+}
+
+impl ListThrottle {
+    pub(super) fn cached(&self, kind: ListKind, key: &str) -> Option<CachedListPage> {
+        self.cached_at(kind, key, Instant::now())
+    }
+
+    fn cached_at(&self, kind: ListKind, key: &str, now: Instant) -> Option<CachedListPage> {
+        let mut guard = self.inner.lock().expect("list throttle mutex poisoned");
+        let cache_key = (kind, key.to_string());
+        match guard.cache.get(&cache_key) {
+            Some(entry) if now.saturating_duration_since(entry.stored_at) <= LIST_CACHE_TTL => {
+                Some(entry.page.clone())
+            }
+            Some(_) => {
+                let _ = guard.cache.remove(&cache_key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub(super) fn store(&self, kind: ListKind, key: &str, page: CachedListPage) {
+        self.store_at(kind, key, page, Instant::now());
+    }
+
+    fn store_at(&self, kind: ListKind, key: &str, page: CachedListPage, now: Instant) {
+        let mut guard = self.inner.lock().expect("list throttle mutex poisoned");
+        guard
+            .cache
+            .retain(|_, entry| now.saturating_duration_since(entry.stored_at) <= LIST_CACHE_TTL);
+        if guard.cache.len() >= LIST_CACHE_MAX_ENTRIES
+            && let Some(oldest) = guard
+                .cache
+                .iter()
+                .min_by_key(|(_, entry)| entry.stored_at)
+                .map(|(k, _)| k.clone())
+        {
+            let _ = guard.cache.remove(&oldest);
+        }
+        guard.cache.insert(
+            (kind, key.to_string()),
+            CacheEntry {
+                page,
+                stored_at: now,
+            },
+        );
+    }
+
+    pub(super) fn try_acquire(&self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> bool {
+        let mut guard = self.inner.lock().expect("list throttle mutex poisoned");
+        guard.bucket.try_take(now)
+    }
+
+    /// Registers a new in-flight listing and cancels the previous one in the same slot.
+    ///
+    /// The slot is the requester when the Hub supplied one, otherwise the listed target.
+    pub(super) fn begin(
+        &self,
+        kind: ListKind,
+        requester: Option<&str>,
+        target: &str,
+    ) -> ListTicket {
+        let slot = match requester {
+            Some(requester) => (kind, format!("requester\n{requester}")),
+            None => (kind, format!("target\n{target}")),
+        };
+        let mut guard = self.inner.lock().expect("list throttle mutex poisoned");
+        guard.next_seq = guard.next_seq.wrapping_add(1);
+        let seq = guard.next_seq;
+        let token = CancellationToken::new();
+        if let Some(prev) = guard.in_flight.insert(
+            slot.clone(),
+            InFlight {
+                seq,
+                token: token.clone(),
+            },
+        ) {
+            prev.token.cancel();
+        }
+
+        ListTicket {
+            throttle: self.clone(),
+            slot,
+            seq,
+            token,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{CachedListPage, LIST_CACHE_TTL, ListKind, ListThrottle};
+
+    fn page(total: u64) -> CachedListPage {
+        CachedListPage {
+            entries: Vec::new(),
+            next_cursor: None,
+            total,
+        }
+    }
+
+    #[test]
+    fn bucket_rejects_bursts_and_refills_over_time() {
+        let throttle = ListThrottle::default();
+        let now = Instant::now();
+        let mut granted = 0;
+        for _ in 0..20 {
+            if throttle.try_acquire_at(now) {
+                granted += 1;
+            }
+        }
+        assert_eq!(granted, 10);
+        assert!(!throttle.try_acquire_at(now));
+        assert!(throttle.try_acquire_at(now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn cache_entries_expire_after_ttl() {
+        let throttle = ListThrottle::default();
+        let now = Instant::now();
+        throttle.store_at(ListKind::Fs, "k", page(3), now);
+
+        assert_eq!(
+            throttle.cached_at(ListKind::Fs, "k", now + Duration::from_secs(1)),
+            Some(page(3))
+        );
+        assert_eq!(throttle.cached_at(ListKind::Webdav, "k", now), None);
+        assert_eq!(
+            throttle.cached_at(
+                ListKind::Fs,
+                "k",
+                now + LIST_CACHE_TTL + Duration::from_secs(1)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn newer_listing_supersedes_previous_from_same_requester() {
+        let throttle = ListThrottle::default();
+        let first = throttle.begin(ListKind::Fs, Some("a"), "/one");
+        let other_kind = throttle.begin(ListKind::Webdav, Some("a"), "/one");
+        let other_requester = throttle.begin(ListKind::Fs, Some("b"), "/one");
+        assert!(!first.token().is_cancelled());
+
+        let second = throttle.begin(ListKind::Fs, Some("a"), "/two");
+        assert!(first.token().is_cancelled());
+        assert!(!second.token().is_cancelled());
+        assert!(!other_kind.token().is_cancelled());
+        assert!(!other_requester.token().is_cancelled());
+
+        // Dropping a stale ticket must not release the newer registration.
+        drop(first);
+        let third = throttle.begin(ListKind::Fs, Some("a"), "/three");
+        assert!(second.token().is_cancelled());
+        assert!(!third.token().is_cancelled());
+    }
+
+    #[test]
+    fn listings_without_requester_only_supersede_the_same_target() {
+        let throttle = ListThrottle::default();
+        let first = throttle.begin(ListKind::Fs, None, "/one");
+        let other_target = throttle.begin(ListKind::Fs, None, "/two");
+        assert!(!first.token().is_cancelled());
+
+        let again = throttle.begin(ListKind::Fs, None, "/one");
+        assert!(first.token().is_cancelled());
+        assert!(!again.token().is_cancelled());
+        assert!(!other_target.token().is_cancelled());
+    }
+}
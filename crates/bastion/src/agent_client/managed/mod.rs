@@ -13,7 +13,7 @@ pub(super) use secrets_snapshot::{
     save_managed_secrets_snapshot,
 };
 pub(super) use task_results::load_cached_operation_result;
-pub(super) use task_results::{load_cached_task_result, save_task_result};
+pub(super) use task_results::{forget_task_result, load_cached_task_result, save_task_result};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct ManagedConfigPlainV1 {
@@ -61,11 +61,20 @@ pub(in super::super) fn save_task_result(
     Ok(())
 }
 
+/// Drops a cached result once the Hub acknowledged it; it won't send the task again.
+pub(in super::super) fn forget_task_result(data_dir: &Path, task_id: &str) {
+    if let Some(path) = task_result_path(data_dir, task_id) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use bastion_core::agent_protocol::{AgentToHubMessageV1, OperationResultV1, PROTOCOL_VERSION};
 
-    use super::{load_cached_operation_result, load_cached_task_result, save_task_result};
+    use super::{
+        forget_task_result, load_cached_operation_result, load_cached_task_result, save_task_result,
+    };
 
     #[test]
     fn save_and_load_task_result_roundtrips() {
@@ -104,6 +113,9 @@ mod tests {
         }
 
         assert!(load_cached_task_result(tmp.path(), "task1", "other-run").is_none());
+
+        forget_task_result(tmp.path(), "task1");
+        assert!(load_cached_task_result(tmp.path(), "task1", "run1").is_none());
     }
 
     #[test]
@@ -11,6 +11,7 @@ fn managed_secrets_snapshot_is_persisted_encrypted() {
         username: "user".to_string(),
         password: "pass".to_string(),
         updated_at: 10,
+        pending: None,
     }];
     let backup_age_identities = vec![bastion_core::agent_protocol::BackupAgeIdentitySecretV1 {
         name: "key1".to_string(),
@@ -72,6 +73,12 @@ fn managed_config_snapshot_is_persisted_encrypted() {
                 root: "/".to_string(),
                 include: vec![],
                 exclude: vec![],
+                exclude_presets: Vec::new(),
+                use_ignore_files: false,
+                max_file_size_bytes: None,
+                skip_older_than_secs: None,
+                skip_newer_than_secs: None,
+                anomaly_detection: None,
                 symlink_policy: Default::default(),
                 hardlink_policy: Default::default(),
                 error_policy: bastion_core::job_spec::FsErrorPolicy::FailFast,
@@ -80,10 +87,12 @@ fn managed_config_snapshot_is_persisted_encrypted() {
                 consistency_policy: Default::default(),
                 consistency_fail_threshold: None,
                 upload_on_consistency_failure: None,
+                windows_system_state: false,
             },
             target: bastion_core::agent_protocol::TargetResolvedV1::LocalDir {
                 base_dir: "/tmp".to_string(),
                 part_size_bytes: 1024,
+                link_unchanged: false,
             },
         },
     }];
@@ -129,6 +138,12 @@ fn managed_config_snapshot_load_round_trips_after_save() {
                 root: "/".to_string(),
                 include: vec![],
                 exclude: vec![],
+                exclude_presets: Vec::new(),
+                use_ignore_files: false,
+                max_file_size_bytes: None,
+                skip_older_than_secs: None,
+                skip_newer_than_secs: None,
+                anomaly_detection: None,
                 symlink_policy: Default::default(),
                 hardlink_policy: Default::default(),
                 error_policy: bastion_core::job_spec::FsErrorPolicy::FailFast,
@@ -137,10 +152,12 @@ fn managed_config_snapshot_load_round_trips_after_save() {
                 consistency_policy: Default::default(),
                 consistency_fail_threshold: None,
                 upload_on_consistency_failure: None,
+                windows_system_state: false,
             },
             target: bastion_core::agent_protocol::TargetResolvedV1::LocalDir {
                 base_dir: "/tmp".to_string(),
                 part_size_bytes: 1024,
+                link_unchanged: false,
             },
         },
     }];
@@ -190,6 +207,7 @@ fn managed_secrets_snapshot_load_webdav_round_trips_after_save() {
         username: " user ".to_string(),
         password: "pass".to_string(),
         updated_at: 10,
+        pending: None,
     }];
     super::save_managed_secrets_snapshot(tmp.path(), "a", 123, &webdav, &[]).unwrap();
 
@@ -208,6 +226,7 @@ fn managed_secrets_snapshot_load_rejects_invalid_nonce_length() {
         username: "user".to_string(),
         password: "pass".to_string(),
         updated_at: 10,
+        pending: None,
     }];
     super::save_managed_secrets_snapshot(tmp.path(), "a", 123, &webdav, &[]).unwrap();
 
@@ -3,16 +3,25 @@ use std::time::Duration;
 use tracing::{info, warn};
 
 use crate::config::AgentArgs;
+use crate::sd_notify;
 
+mod client_tls;
 mod connect;
+mod diagnostics;
 mod fs_list;
+mod host_metrics;
 mod hub_stream;
 mod identity;
+mod list_throttle;
 mod managed;
 mod offline;
 mod restore_task;
+mod run_slots;
+mod self_update;
 mod snapshot_delete;
+mod staging;
 mod targets;
+mod task_queue;
 mod tasks;
 mod util;
 mod webdav_list;
@@ -21,33 +30,78 @@ use connect::{LoopAction, connect_and_run};
 use identity::{AgentIdentityV1, enroll, identity_path, load_identity, save_identity};
 use util::{agent_ws_url, jittered_backoff, normalize_base_url};
 
+pub(crate) use self_update::UPDATE_RESTART_EXIT_CODE;
+
 const MANAGED_SECRETS_FILE_NAME: &str = "secrets.json";
 const MANAGED_CONFIG_FILE_NAME: &str = "config.json";
 const MANAGED_CONFIG_KIND: &str = "agent_config_snapshot";
 const MANAGED_CONFIG_NAME: &str = "config";
+/// The watchdog keepalive stops once neither the Hub connection nor the offline scheduler has
+/// made progress for this long. Leaves room for slow connects and the offline-run upload that
+/// follows a reconnect.
+const LIVENESS_MAX_AGE: Duration = Duration::from_secs(10 * 60);
 
 use restore_task::handle_restore_task;
-use tasks::handle_backup_task;
+use tasks::{UploadFailurePolicy, handle_backup_task};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentExit {
+    Shutdown,
+    /// A Hub-provided update was installed; the process should exit with
+    /// [`UPDATE_RESTART_EXIT_CODE`] so it gets restarted on the new binary.
+    RestartForUpdate,
+}
 
-pub async fn run(args: AgentArgs) -> Result<(), anyhow::Error> {
+pub async fn run(args: AgentArgs) -> Result<AgentExit, anyhow::Error> {
     if args.heartbeat_seconds == 0 {
         anyhow::bail!("heartbeat_seconds must be > 0");
     }
+    if args.max_parallel_runs == 0 {
+        anyhow::bail!("max_parallel_runs must be > 0");
+    }
+    let update_trust = args
+        .update_public_key
+        .as_deref()
+        .map(self_update::parse_public_key)
+        .transpose()?
+        .map(|public_key| self_update::UpdateTrust {
+            public_key,
+            allow_downgrade: args.update_allow_downgrade,
+        });
 
-    let data_dir = bastion_config::data_dir::resolve_data_dir(args.data_dir)?;
-    let base_url = normalize_base_url(&args.hub_url)?;
+    let data_dir = bastion_config::data_dir::resolve_data_dir(args.data_dir.clone())?;
+    staging::configure(
+        args.staging_dir.clone(),
+        args.staging_min_free_mb.saturating_mul(1024 * 1024),
+    );
+    let hub_urls = configured_hub_urls(&args)?;
+    let base_url = hub_urls[0].clone();
 
     let identity_path = identity_path(&data_dir);
     let identity = match load_identity(&identity_path)? {
-        Some(v) => {
-            let stored_url = normalize_base_url(&v.hub_url)?;
-            if stored_url != base_url {
+        Some(mut v) => {
+            let stored_urls = v
+                .hub_urls()
+                .into_iter()
+                .map(normalize_base_url)
+                .collect::<Result<Vec<_>, _>>()?;
+            // Any shared URL keeps the enrollment, so a Hub can be added as a fallback, then
+            // made the preferred URL, then the old one dropped.
+            if !stored_urls.iter().any(|url| hub_urls.contains(url)) {
                 anyhow::bail!(
                     "agent is already enrolled for hub_url={}, delete {} to re-enroll",
-                    stored_url,
+                    stored_urls[0],
                     identity_path.display()
                 );
             }
+            if stored_urls != hub_urls {
+                info!(
+                    hub_urls = %join_urls(&hub_urls),
+                    "updating the agent's hub URLs"
+                );
+                v.set_hub_urls(&hub_urls);
+                save_identity(&identity_path, &v)?;
+            }
             v
         }
         None => {
@@ -60,55 +114,104 @@ pub async fn run(args: AgentArgs) -> Result<(), anyhow::Error> {
             info!(hub_url = %base_url, "enrolling agent");
             let resp = enroll(&base_url, token, args.name.as_deref()).await?;
             let now = time::OffsetDateTime::now_utc().unix_timestamp();
-            let identity = AgentIdentityV1 {
+            let mut identity = AgentIdentityV1 {
                 v: 1,
                 hub_url: base_url.to_string(),
+                hub_urls: Vec::new(),
                 agent_id: resp.agent_id,
                 agent_key: resp.agent_key,
                 name: args.name.clone(),
                 enrolled_at: now,
+                client_cert: resp.client_cert,
             };
+            identity.set_hub_urls(&hub_urls);
             save_identity(&identity_path, &identity)?;
             identity
         }
     };
 
-    let ws_url = agent_ws_url(&base_url)?;
     let heartbeat = Duration::from_secs(args.heartbeat_seconds);
     let pong_timeout = Duration::from_secs(args.heartbeat_seconds.saturating_mul(3));
     let mut backoff = Duration::from_secs(1);
     let mut attempt = 0u32;
+    // Index into `hub_urls`; the agent stays on a Hub until connecting to it fails.
+    let mut current = 0usize;
 
-    let run_lock = std::sync::Arc::new(tokio::sync::Mutex::new(()));
+    let run_slots = run_slots::RunSlots::new(args.max_parallel_runs);
+    let task_queue = task_queue::TaskQueue::open(&data_dir);
     let (connected_tx, connected_rx) = tokio::sync::watch::channel(false);
+    // Beaten by the connection loop while online and by the offline scheduler while offline.
+    let liveness = sd_notify::Liveness::new();
 
     tokio::spawn(offline::offline_scheduler_loop(
         data_dir.clone(),
         identity.agent_id.clone(),
-        run_lock.clone(),
+        run_slots.clone(),
         connected_rx,
+        liveness.clone(),
     ));
 
+    // Tasks run from the local queue even while the Hub is unreachable, so the agent is ready
+    // before its first connection.
+    sd_notify::ready(&format!("agent {}", identity.agent_id));
+    sd_notify::spawn_watchdog({
+        let liveness = liveness.clone();
+        move || {
+            let fresh = liveness.is_fresh(LIVENESS_MAX_AGE);
+            async move { fresh }
+        }
+    });
+
     loop {
+        liveness.beat();
         let action = connect_and_run(
-            &ws_url,
+            &hub_urls[current],
             &identity,
             &data_dir,
             heartbeat,
             pong_timeout,
-            run_lock.clone(),
+            run_slots.clone(),
+            task_queue.clone(),
             &connected_tx,
+            update_trust.as_ref(),
+            &liveness,
         )
         .await;
         match action {
-            Ok(LoopAction::Exit) => return Ok(()),
+            Ok(LoopAction::Exit) => {
+                sd_notify::stopping(None);
+                return Ok(AgentExit::Shutdown);
+            }
+            Ok(LoopAction::Restart) => {
+                sd_notify::stopping(None);
+                return Ok(AgentExit::RestartForUpdate);
+            }
+            Ok(LoopAction::HubShutdown) => {
+                // A planned restart: start over with a short delay instead of the grown backoff.
+                info!(hub_url = %hub_urls[current], "hub shut down; reconnecting");
+                attempt = 0;
+                backoff = Duration::from_secs(1);
+                tokio::time::sleep(jittered_backoff(backoff, &identity.agent_id, attempt)).await;
+            }
             Ok(LoopAction::Reconnect) => {
                 attempt = attempt.saturating_add(1);
                 tokio::time::sleep(jittered_backoff(backoff, &identity.agent_id, attempt)).await;
                 backoff = std::cmp::min(backoff * 2, Duration::from_secs(30));
             }
             Err(error) => {
-                warn!(error = %error, "agent connection failed; retrying");
+                warn!(
+                    hub_url = %hub_urls[current],
+                    error = %error,
+                    "agent connection failed; retrying"
+                );
+                if hub_urls.len() > 1 {
+                    current = (current + 1) % hub_urls.len();
+                    info!(hub_url = %hub_urls[current], "failing over to the next hub URL");
+                    // Only back off once every URL has been tried.
+                    if current != 0 {
+                        continue;
+                    }
+                }
                 attempt = attempt.saturating_add(1);
                 tokio::time::sleep(jittered_backoff(backoff, &identity.agent_id, attempt)).await;
                 backoff = std::cmp::min(backoff * 2, Duration::from_secs(30));
@@ -116,3 +219,23 @@ pub async fn run(args: AgentArgs) -> Result<(), anyhow::Error> {
         }
     }
 }
+
+/// `--hub-url` followed by the `--fallback-hub-url`s, normalized and without duplicates.
+fn configured_hub_urls(args: &AgentArgs) -> Result<Vec<url::Url>, anyhow::Error> {
+    let mut urls = Vec::new();
+    for raw in std::iter::once(&args.hub_url).chain(&args.fallback_hub_urls) {
+        let url = normalize_base_url(raw.trim())?;
+        agent_ws_url(&url)?;
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    Ok(urls)
+}
+
+fn join_urls(urls: &[url::Url]) -> String {
+    urls.iter()
+        .map(url::Url::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,371 @@
+//! Offline runs whose target upload failed keep their staged artifacts for a later upload.
+//!
+//! The run's staging directory moves into the offline run directory (`artifacts/`), next to a
+//! `pending_upload.json` that describes the parts. Target credentials are never written here:
+//! sync re-resolves the target from the managed config snapshot when it uploads.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use bastion_backup as backup;
+use bastion_core::agent_protocol::{JobSpecResolvedV1, TargetResolvedV1};
+use bastion_core::manifest::HashAlgorithm;
+
+use super::super::managed::load_managed_config_snapshot;
+use super::super::targets::store_artifacts_to_resolved_target;
+use super::storage::{
+    OfflineRunEventV1, OfflineRunFileV1, OfflineRunStatusV1, append_offline_event_line,
+    offline_run_dir, write_offline_run_file_atomic,
+};
+
+pub(in super::super) const UPLOAD_DEFERRED_EVENT_KIND: &str = "upload_deferred";
+const DEFERRED_UPLOAD_COMPLETE_EVENT_KIND: &str = "deferred_upload_complete";
+const DEFERRED_UPLOAD_FAILED_EVENT_KIND: &str = "deferred_upload_failed";
+const UPLOAD_TARGET_UNAVAILABLE: &str = "upload_target_unavailable";
+
+const PENDING_UPLOAD_NAME: &str = "pending_upload.json";
+const ARTIFACTS_DIR_NAME: &str = "artifacts";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingPartV1 {
+    name: String,
+    path: String,
+    size: u64,
+    hash_alg: HashAlgorithm,
+    hash: String,
+}
+
+/// Artifact layout of a deferred run; all paths are relative to the `artifacts/` directory.
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingUploadV1 {
+    v: u32,
+    error: String,
+    parts: Vec<PendingPartV1>,
+    entries_index_path: String,
+    entries_count: u64,
+    manifest_path: String,
+    complete_path: String,
+}
+
+impl PendingUploadV1 {
+    fn local_artifacts(&self, artifacts_dir: &Path) -> backup::LocalRunArtifacts {
+        backup::LocalRunArtifacts {
+            run_dir: artifacts_dir.to_path_buf(),
+            parts: self
+                .parts
+                .iter()
+                .map(|part| backup::LocalArtifact {
+                    name: part.name.clone(),
+                    path: artifacts_dir.join(&part.path),
+                    size: part.size,
+                    hash_alg: part.hash_alg.clone(),
+                    hash: part.hash.clone(),
+                })
+                .collect(),
+            entries_index_path: artifacts_dir.join(&self.entries_index_path),
+            entries_count: self.entries_count,
+            manifest_path: artifacts_dir.join(&self.manifest_path),
+            complete_path: artifacts_dir.join(&self.complete_path),
+        }
+    }
+}
+
+/// Whether a run summary's `target` is the placeholder written by [`defer_run_upload`].
+pub(in super::super) fn is_deferred_target_summary(target: &serde_json::Value) -> bool {
+    target.get("upload_deferred").and_then(|v| v.as_bool()) == Some(true)
+}
+
+fn relative_to(run_dir: &Path, path: &Path) -> Option<String> {
+    path.strip_prefix(run_dir)
+        .ok()
+        .map(|p| p.to_string_lossy().into_owned())
+}
+
+/// Moves the staged artifacts of an offline run aside and returns a placeholder target summary.
+///
+/// When the artifacts cannot be queued, the original upload error is returned unchanged.
+pub(in super::super) async fn defer_run_upload(
+    data_dir: &Path,
+    run_id: &str,
+    artifacts: &backup::LocalRunArtifacts,
+    error: anyhow::Error,
+) -> Result<serde_json::Value, anyhow::Error> {
+    let run_dir = &artifacts.run_dir;
+    let parts = artifacts
+        .parts
+        .iter()
+        .map(|part| {
+            Some(PendingPartV1 {
+                name: part.name.clone(),
+                path: relative_to(run_dir, &part.path)?,
+                size: part.size,
+                hash_alg: part.hash_alg.clone(),
+                hash: part.hash.clone(),
+            })
+        })
+        .collect::<Option<Vec<_>>>();
+    let (Some(parts), Some(entries_index_path), Some(manifest_path), Some(complete_path)) = (
+        parts,
+        relative_to(run_dir, &artifacts.entries_index_path),
+        relative_to(run_dir, &artifacts.manifest_path),
+        relative_to(run_dir, &artifacts.complete_path),
+    ) else {
+        warn!(run_id = %run_id, "staged artifacts are outside the run directory; not deferring");
+        return Err(error);
+    };
+
+    let message = format!("{error:#}");
+    let pending = PendingUploadV1 {
+        v: 1,
+        error: message.clone(),
+        parts,
+        entries_index_path,
+        entries_count: artifacts.entries_count,
+        manifest_path,
+        complete_path,
+    };
+
+    let offline_dir = offline_run_dir(data_dir, run_id);
+    let artifacts_dir = offline_dir.join(ARTIFACTS_DIR_NAME);
+    if let Err(move_error) = tokio::fs::rename(run_dir, &artifacts_dir).await {
+        warn!(run_id = %run_id, error = %move_error, "failed to move staged artifacts; not deferring");
+        return Err(error);
+    }
+    if let Err(write_error) = write_pending_upload(&offline_dir, &pending).await {
+        warn!(run_id = %run_id, error = %write_error, "failed to record deferred upload");
+        let _ = tokio::fs::remove_dir_all(&artifacts_dir).await;
+        return Err(error);
+    }
+
+    Ok(serde_json::json!({
+        "type": "deferred",
+        "upload_deferred": true,
+        "error": message,
+    }))
+}
+
+async fn write_pending_upload(
+    offline_dir: &Path,
+    pending: &PendingUploadV1,
+) -> Result<(), anyhow::Error> {
+    let path = offline_dir.join(PENDING_UPLOAD_NAME);
+    let tmp = path.with_extension("json.partial");
+    tokio::fs::write(&tmp, serde_json::to_vec_pretty(pending)?).await?;
+    tokio::fs::rename(&tmp, &path).await?;
+    Ok(())
+}
+
+async fn load_pending_upload(offline_dir: &Path) -> Result<Option<PendingUploadV1>, anyhow::Error> {
+    match tokio::fs::read(offline_dir.join(PENDING_UPLOAD_NAME)).await {
+        Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn resolved_target(spec: JobSpecResolvedV1) -> TargetResolvedV1 {
+    match spec {
+        JobSpecResolvedV1::Filesystem { target, .. }
+        | JobSpecResolvedV1::Sqlite { target, .. }
+        | JobSpecResolvedV1::Vaultwarden { target, .. }
+        | JobSpecResolvedV1::SqliteApp { target, .. }
+        | JobSpecResolvedV1::Postgres { target, .. }
+        | JobSpecResolvedV1::VmImage { target, .. }
+        | JobSpecResolvedV1::KubernetesPvc { target, .. }
+        | JobSpecResolvedV1::Imap { target, .. }
+        | JobSpecResolvedV1::Git { target, .. } => target,
+    }
+}
+
+fn set_summary_field(run: &mut OfflineRunFileV1, key: &str, value: serde_json::Value) {
+    let summary = run.summary.get_or_insert_with(|| serde_json::json!({}));
+    if let Some(obj) = summary.as_object_mut() {
+        obj.insert(key.to_string(), value);
+    }
+}
+
+/// Uploads the deferred artifacts of an offline run before it is ingested.
+///
+/// Returns `Ok(false)` when the run has nothing pending. On success `run.json` carries the real
+/// target summary and an event is appended to `events`. If the job was removed from the managed
+/// config meanwhile, the run is marked failed and its staged artifacts are discarded. Upload
+/// errors are returned so the caller can keep the run for the next reconnect.
+pub(super) async fn upload_deferred_artifacts(
+    data_dir: &Path,
+    agent_id: &str,
+    offline_dir: &Path,
+    run: &mut OfflineRunFileV1,
+    events: &mut Vec<OfflineRunEventV1>,
+) -> Result<bool, anyhow::Error> {
+    let Some(pending) = load_pending_upload(offline_dir).await? else {
+        return Ok(false);
+    };
+    let artifacts_dir: PathBuf = offline_dir.join(ARTIFACTS_DIR_NAME);
+    if run.status != OfflineRunStatusV1::Success {
+        // The run failed after deferring; there is no snapshot worth uploading.
+        tokio::fs::remove_file(offline_dir.join(PENDING_UPLOAD_NAME)).await?;
+        let _ = tokio::fs::remove_dir_all(&artifacts_dir).await;
+        return Ok(true);
+    }
+
+    let target = load_managed_config_snapshot(data_dir, agent_id)?
+        .and_then(|config| config.jobs.into_iter().find(|job| job.job_id == run.job_id))
+        .map(|job| resolved_target(job.spec));
+
+    let (level, kind, message) = match target {
+        Some(target) => {
+            let artifacts = pending.local_artifacts(&artifacts_dir);
+            let target_summary = store_artifacts_to_resolved_target(
+                &run.job_id,
+                &run.id,
+                &target,
+                &artifacts,
+                None,
+                None,
+            )
+            .await?;
+            set_summary_field(run, "target", target_summary);
+            (
+                "info",
+                DEFERRED_UPLOAD_COMPLETE_EVENT_KIND,
+                "deferred upload complete",
+            )
+        }
+        None => {
+            run.status = OfflineRunStatusV1::Failed;
+            run.error = Some(UPLOAD_TARGET_UNAVAILABLE.to_string());
+            set_summary_field(
+                run,
+                "error_code",
+                serde_json::Value::String(UPLOAD_TARGET_UNAVAILABLE.to_string()),
+            );
+            (
+                "error",
+                DEFERRED_UPLOAD_FAILED_EVENT_KIND,
+                "job is no longer assigned to this agent; staged artifacts discarded",
+            )
+        }
+    };
+
+    let event = OfflineRunEventV1 {
+        seq: events.iter().map(|e| e.seq).max().unwrap_or(0) + 1,
+        ts: time::OffsetDateTime::now_utc().unix_timestamp(),
+        level: level.to_string(),
+        kind: kind.to_string(),
+        message: message.to_string(),
+        fields: Some(serde_json::json!({ "deferred_error": pending.error })),
+    };
+    append_offline_event_line(&offline_dir.join("events.jsonl"), &event).await?;
+    events.push(event);
+
+    write_offline_run_file_atomic(&offline_dir.join("run.json"), run.clone()).await?;
+
+    tokio::fs::remove_file(offline_dir.join(PENDING_UPLOAD_NAME)).await?;
+    let _ = tokio::fs::remove_dir_all(&artifacts_dir).await;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use bastion_backup as backup;
+    use bastion_core::manifest::HashAlgorithm;
+
+    use super::super::storage::{OfflineRunFileV1, OfflineRunStatusV1, offline_run_dir};
+    use super::{
+        ARTIFACTS_DIR_NAME, PENDING_UPLOAD_NAME, defer_run_upload, is_deferred_target_summary,
+        upload_deferred_artifacts,
+    };
+
+    fn staged_run(data_dir: &std::path::Path, run_id: &str) -> backup::LocalRunArtifacts {
+        let run_dir = data_dir.join("runs").join(run_id);
+        std::fs::create_dir_all(&run_dir).unwrap();
+        for name in [
+            "payload.part000001",
+            "entries.jsonl.zst",
+            "manifest.json",
+            "complete.json",
+        ] {
+            std::fs::write(run_dir.join(name), name).unwrap();
+        }
+        backup::LocalRunArtifacts {
+            run_dir: run_dir.clone(),
+            parts: vec![backup::LocalArtifact {
+                name: "payload.part000001".to_string(),
+                path: run_dir.join("payload.part000001"),
+                size: 18,
+                hash_alg: HashAlgorithm::Blake3,
+                hash: "h".to_string(),
+            }],
+            entries_index_path: run_dir.join("entries.jsonl.zst"),
+            entries_count: 1,
+            manifest_path: run_dir.join("manifest.json"),
+            complete_path: run_dir.join("complete.json"),
+        }
+    }
+
+    #[tokio::test]
+    async fn defer_moves_staged_artifacts_into_offline_run_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let data_dir = tmp.path();
+        std::fs::create_dir_all(offline_run_dir(data_dir, "run1")).unwrap();
+        let artifacts = staged_run(data_dir, "run1");
+
+        let summary = defer_run_upload(data_dir, "run1", &artifacts, anyhow::anyhow!("offline"))
+            .await
+            .unwrap();
+        assert!(is_deferred_target_summary(&summary));
+        assert!(!artifacts.run_dir.exists());
+
+        let offline_dir = offline_run_dir(data_dir, "run1");
+        assert!(offline_dir.join(PENDING_UPLOAD_NAME).exists());
+        assert!(
+            offline_dir
+                .join(ARTIFACTS_DIR_NAME)
+                .join("payload.part000001")
+                .exists()
+        );
+    }
+
+    #[tokio::test]
+    async fn deferred_run_fails_when_job_is_no_longer_configured() {
+        let tmp = tempfile::tempdir().unwrap();
+        let data_dir = tmp.path();
+        let offline_dir = offline_run_dir(data_dir, "run1");
+        std::fs::create_dir_all(&offline_dir).unwrap();
+        let artifacts = staged_run(data_dir, "run1");
+        let target = defer_run_upload(data_dir, "run1", &artifacts, anyhow::anyhow!("offline"))
+            .await
+            .unwrap();
+
+        let mut run = OfflineRunFileV1 {
+            v: 1,
+            id: "run1".to_string(),
+            job_id: "job1".to_string(),
+            job_name: "job".to_string(),
+            status: OfflineRunStatusV1::Success,
+            started_at: 1,
+            ended_at: Some(2),
+            summary: Some(serde_json::json!({ "target": target })),
+            error: None,
+        };
+        let mut events = Vec::new();
+
+        // No managed config snapshot exists, so the job cannot be resolved any more.
+        let handled = upload_deferred_artifacts(data_dir, "a", &offline_dir, &mut run, &mut events)
+            .await
+            .unwrap();
+        assert!(handled);
+        assert_eq!(run.status, OfflineRunStatusV1::Failed);
+        assert_eq!(run.error.as_deref(), Some("upload_target_unavailable"));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, "deferred_upload_failed");
+        assert!(!offline_dir.join(PENDING_UPLOAD_NAME).exists());
+        assert!(!offline_dir.join(ARTIFACTS_DIR_NAME).exists());
+
+        let saved: OfflineRunFileV1 =
+            serde_json::from_slice(&std::fs::read(offline_dir.join("run.json")).unwrap()).unwrap();
+        assert_eq!(saved.status, OfflineRunStatusV1::Failed);
+    }
+}
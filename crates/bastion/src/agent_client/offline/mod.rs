@@ -2,7 +2,16 @@ use std::path::{Path, PathBuf};
 
 use url::Url;
 
+use super::identity::AgentClientCertV1;
+use super::run_slots::RunSlots;
+use crate::sd_notify::Liveness;
+
+pub(super) use deferred::{
+    UPLOAD_DEFERRED_EVENT_KIND, defer_run_upload, is_deferred_target_summary,
+};
+
 mod cron;
+mod deferred;
 mod scheduler;
 mod storage;
 mod sync;
@@ -10,16 +19,19 @@ mod sync;
 pub(super) async fn offline_scheduler_loop(
     data_dir: PathBuf,
     agent_id: String,
-    run_lock: std::sync::Arc<tokio::sync::Mutex<()>>,
+    run_slots: RunSlots,
     connected_rx: tokio::sync::watch::Receiver<bool>,
+    liveness: Liveness,
 ) {
-    scheduler::offline_scheduler_loop(data_dir, agent_id, run_lock, connected_rx).await;
+    scheduler::offline_scheduler_loop(data_dir, agent_id, run_slots, connected_rx, liveness).await;
 }
 
 pub(super) async fn sync_offline_runs(
     base_url: &Url,
     agent_key: &str,
+    client_cert: Option<&AgentClientCertV1>,
     data_dir: &Path,
+    agent_id: &str,
 ) -> Result<(), anyhow::Error> {
-    sync::sync_offline_runs(base_url, agent_key, data_dir).await
+    sync::sync_offline_runs(base_url, agent_key, client_cert, data_dir, agent_id).await
 }
@@ -9,6 +9,7 @@ use bastion_core::agent_protocol::OverlapPolicyV1;
 use super::super::cron::cron_matches_minute_cached;
 use super::super::storage::OfflineRunWriterHandle;
 use super::types::{InFlightCounts, OfflineRunTask};
+use crate::sd_notify::Liveness;
 
 fn allow_due_for_local_minute(
     tz: chrono_tz::Tz,
@@ -113,6 +114,7 @@ pub(super) async fn offline_cron_loop(
     mut connected_rx: tokio::sync::watch::Receiver<bool>,
     tx: tokio::sync::mpsc::Sender<OfflineRunTask>,
     inflight: std::sync::Arc<tokio::sync::Mutex<InFlightCounts>>,
+    liveness: Liveness,
 ) {
     use chrono::{DateTime, Duration as ChronoDuration, Utc};
     use cron::Schedule;
@@ -122,6 +124,7 @@ pub(super) async fn offline_cron_loop(
     let mut last_minute = time::OffsetDateTime::now_utc().unix_timestamp() / 60 - 1;
 
     loop {
+        liveness.beat();
         if *connected_rx.borrow() {
             if connected_rx.changed().await.is_err() {
                 break;
@@ -369,10 +372,12 @@ mod tests {
                 source: bastion_core::job_spec::SqliteSource {
                     path: "/db.sqlite".to_string(),
                     integrity_check: false,
+                    databases: Vec::new(),
                 },
                 target: bastion_core::agent_protocol::TargetResolvedV1::LocalDir {
                     base_dir: "/tmp".to_string(),
                     part_size_bytes: 1024,
+                    link_unchanged: false,
                 },
             },
         }
@@ -389,10 +394,12 @@ mod tests {
                 source: bastion_core::job_spec::SqliteSource {
                     path: "/db.sqlite".to_string(),
                     integrity_check: false,
+                    databases: Vec::new(),
                 },
                 target: bastion_core::agent_protocol::TargetResolvedV1::LocalDir {
                     base_dir: "/tmp".to_string(),
                     part_size_bytes: 1024,
+                    link_unchanged: false,
                 },
             },
         }
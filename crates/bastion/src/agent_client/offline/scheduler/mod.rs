@@ -7,13 +7,17 @@ mod worker_loop;
 
 use types::{InFlightCounts, OfflineRunTask};
 
+use super::super::run_slots::RunSlots;
+use crate::sd_notify::Liveness;
+
 const OFFLINE_TASK_QUEUE_CAPACITY: usize = 256;
 
 pub(super) async fn offline_scheduler_loop(
     data_dir: PathBuf,
     agent_id: String,
-    run_lock: std::sync::Arc<tokio::sync::Mutex<()>>,
+    run_slots: RunSlots,
     connected_rx: tokio::sync::watch::Receiver<bool>,
+    liveness: Liveness,
 ) {
     let (tx, rx) = tokio::sync::mpsc::channel::<OfflineRunTask>(OFFLINE_TASK_QUEUE_CAPACITY);
     let inflight = std::sync::Arc::new(tokio::sync::Mutex::new(InFlightCounts::default()));
@@ -24,7 +28,8 @@ pub(super) async fn offline_scheduler_loop(
         connected_rx,
         tx,
         inflight.clone(),
+        liveness,
     ));
 
-    worker_loop::offline_worker_loop(data_dir, agent_id, run_lock, rx, inflight).await;
+    worker_loop::offline_worker_loop(data_dir, agent_id, run_slots, rx, inflight).await;
 }
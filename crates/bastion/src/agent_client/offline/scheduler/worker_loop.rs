@@ -4,8 +4,9 @@ use std::pin::Pin;
 
 use tracing::warn;
 
-use bastion_core::run_failure::RunFailedWithSummary;
+use bastion_core::run_failure::{RunErrorCode, RunFailedWithSummary};
 
+use super::super::super::run_slots::{RunPriority, RunSlots};
 use super::super::storage::OfflineRunWriterHandle;
 use super::sink::{OfflineSink, mark_summary_executed_offline};
 use super::types::{InFlightCounts, OfflineRunTask};
@@ -47,14 +48,14 @@ fn execute_offline_run_task_boxed<'a>(
 pub(super) async fn offline_worker_loop(
     data_dir: PathBuf,
     agent_id: String,
-    run_lock: std::sync::Arc<tokio::sync::Mutex<()>>,
+    run_slots: RunSlots,
     mut rx: tokio::sync::mpsc::Receiver<OfflineRunTask>,
     inflight: std::sync::Arc<tokio::sync::Mutex<InFlightCounts>>,
 ) {
     offline_worker_loop_with_executor(
         data_dir,
         agent_id,
-        run_lock,
+        run_slots,
         &mut rx,
         inflight,
         &execute_offline_run_task_boxed,
@@ -65,7 +66,7 @@ pub(super) async fn offline_worker_loop(
 async fn offline_worker_loop_with_executor(
     data_dir: PathBuf,
     agent_id: String,
-    run_lock: std::sync::Arc<tokio::sync::Mutex<()>>,
+    run_slots: RunSlots,
     rx: &mut tokio::sync::mpsc::Receiver<OfflineRunTask>,
     inflight: std::sync::Arc<tokio::sync::Mutex<InFlightCounts>>,
     executor: &dyn OfflineTaskExecutor,
@@ -74,7 +75,7 @@ async fn offline_worker_loop_with_executor(
         let job_id = task.job_id.clone();
         let run_id = task.run_id.clone();
 
-        let _guard = run_lock.lock().await;
+        let _slot = run_slots.acquire(Some(&job_id), RunPriority::Normal).await;
         if let Err(error) = executor.execute(&data_dir, &agent_id, &task).await {
             warn!(
                 agent_id = %agent_id,
@@ -131,6 +132,7 @@ async fn execute_offline_run_task(
         &task.run_id,
         run_task,
         &cancel_token,
+        super::super::super::UploadFailurePolicy::Defer,
     )
     .await;
 
@@ -143,7 +145,10 @@ async fn execute_offline_run_task(
         }
         Err(error) => {
             let soft = error.downcast_ref::<RunFailedWithSummary>();
-            let error_code = soft.map(|e| e.code).unwrap_or("run_failed");
+            let detail_code = soft.map(|e| e.code).unwrap_or("run_failed");
+            let error_code = soft
+                .map(|e| e.error_code)
+                .unwrap_or_else(|| RunErrorCode::classify(error.chain()));
 
             let mut summary = soft
                 .map(|e| e.summary.clone())
@@ -151,7 +156,7 @@ async fn execute_offline_run_task(
             summary.as_object_mut().map(|o| {
                 o.insert(
                     "error_code".to_string(),
-                    serde_json::Value::String(error_code.to_string()),
+                    serde_json::Value::String(detail_code.to_string()),
                 )
             });
             mark_summary_executed_offline(&mut summary);
@@ -163,7 +168,7 @@ async fn execute_offline_run_task(
                 &message,
                 Some(serde_json::json!({ "agent_id": agent_id })),
             );
-            writer.finish_failed(error_code, summary).await?;
+            writer.finish_failed(error_code.as_str(), summary).await?;
         }
     }
 
@@ -181,7 +186,7 @@ mod tests {
 
     use super::super::types::InFlightCounts;
     use super::{
-        BoxFuture, OfflineRunTask, OfflineTaskExecutor, offline_worker_loop_with_executor,
+        BoxFuture, OfflineRunTask, OfflineTaskExecutor, RunSlots, offline_worker_loop_with_executor,
     };
 
     fn task(run_id: &str, job_id: &str) -> OfflineRunTask {
@@ -198,6 +203,12 @@ mod tests {
                     root: String::new(),
                     include: vec![],
                     exclude: vec![],
+                    exclude_presets: Vec::new(),
+                    use_ignore_files: false,
+                    max_file_size_bytes: None,
+                    skip_older_than_secs: None,
+                    skip_newer_than_secs: None,
+                    anomaly_detection: None,
                     symlink_policy: FsSymlinkPolicy::Skip,
                     hardlink_policy: FsHardlinkPolicy::Copy,
                     error_policy: FsErrorPolicy::FailFast,
@@ -206,10 +217,12 @@ mod tests {
                     consistency_policy: Default::default(),
                     consistency_fail_threshold: None,
                     upload_on_consistency_failure: None,
+                    windows_system_state: false,
                 },
                 target: TargetResolvedV1::LocalDir {
                     base_dir: "/tmp".to_string(),
                     part_size_bytes: 1024,
+                    link_unchanged: false,
                 },
             },
         }
@@ -220,7 +233,7 @@ mod tests {
         let tmp = tempfile::tempdir().unwrap();
         let data_dir = tmp.path().to_path_buf();
 
-        let run_lock = std::sync::Arc::new(tokio::sync::Mutex::new(()));
+        let run_slots = RunSlots::new(1);
         let (tx, mut rx) = tokio::sync::mpsc::channel::<OfflineRunTask>(4);
         let inflight = std::sync::Arc::new(tokio::sync::Mutex::new(InFlightCounts::default()));
 
@@ -232,7 +245,7 @@ mod tests {
 
         struct OkExecutor {
             called: std::sync::Arc<AtomicUsize>,
-            run_lock: std::sync::Arc<tokio::sync::Mutex<()>>,
+            run_slots: RunSlots,
         }
 
         impl OfflineTaskExecutor for OkExecutor {
@@ -243,10 +256,10 @@ mod tests {
                 _task: &'a OfflineRunTask,
             ) -> BoxFuture<'a> {
                 let called = self.called.clone();
-                let run_lock = self.run_lock.clone();
+                let run_slots = self.run_slots.clone();
                 Box::pin(async move {
-                    // Worker loop should hold the run lock while executing.
-                    assert!(run_lock.try_lock().is_err());
+                    // Worker loop should hold a run slot while executing.
+                    assert_eq!(run_slots.available(), 0);
                     called.fetch_add(1, Ordering::Relaxed);
                     Ok(())
                 })
@@ -256,7 +269,7 @@ mod tests {
         let called = std::sync::Arc::new(AtomicUsize::new(0));
         let exec = OkExecutor {
             called: called.clone(),
-            run_lock: run_lock.clone(),
+            run_slots: run_slots.clone(),
         };
 
         tx.send(task("run1", "job1")).await.unwrap();
@@ -266,7 +279,7 @@ mod tests {
         offline_worker_loop_with_executor(
             data_dir,
             "agent1".to_string(),
-            run_lock.clone(),
+            run_slots.clone(),
             &mut rx,
             inflight.clone(),
             &exec,
@@ -283,7 +296,7 @@ mod tests {
         let tmp = tempfile::tempdir().unwrap();
         let data_dir = tmp.path().to_path_buf();
 
-        let run_lock = std::sync::Arc::new(tokio::sync::Mutex::new(()));
+        let run_slots = RunSlots::new(1);
         let (tx, mut rx) = tokio::sync::mpsc::channel::<OfflineRunTask>(4);
         let inflight = std::sync::Arc::new(tokio::sync::Mutex::new(InFlightCounts::default()));
 
@@ -329,7 +342,7 @@ mod tests {
         offline_worker_loop_with_executor(
             data_dir,
             "agent1".to_string(),
-            run_lock,
+            run_slots,
             &mut rx,
             inflight.clone(),
             &exec,
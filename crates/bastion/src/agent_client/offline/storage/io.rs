@@ -4,7 +4,7 @@ use tokio::io::AsyncWriteExt as _;
 
 use super::types::{OfflineRunEventV1, OfflineRunFileV1};
 
-pub(super) async fn write_offline_run_file_atomic(
+pub(in super::super) async fn write_offline_run_file_atomic(
     path: &Path,
     doc: OfflineRunFileV1,
 ) -> Result<(), anyhow::Error> {
@@ -16,7 +16,7 @@ pub(super) async fn write_offline_run_file_atomic(
     Ok(())
 }
 
-pub(super) async fn append_offline_event_line(
+pub(in super::super) async fn append_offline_event_line(
     path: &Path,
     event: &OfflineRunEventV1,
 ) -> Result<(), anyhow::Error> {
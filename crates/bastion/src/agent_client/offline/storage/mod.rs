@@ -13,5 +13,6 @@ pub(super) fn offline_run_dir(data_dir: &Path, run_id: &str) -> PathBuf {
     paths::offline_run_dir(data_dir, run_id)
 }
 
+pub(super) use io::{append_offline_event_line, write_offline_run_file_atomic};
 pub(super) use types::{OfflineRunEventV1, OfflineRunFileV1, OfflineRunStatusV1};
 pub(super) use writer::OfflineRunWriterHandle;
@@ -9,7 +9,7 @@ pub(in super::super) enum OfflineRunStatusV1 {
     Rejected,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(in super::super) struct OfflineRunFileV1 {
     pub(in super::super) v: u32,
     pub(in super::super) id: String,
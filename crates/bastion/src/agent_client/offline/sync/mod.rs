@@ -4,14 +4,20 @@ mod request;
 
 use std::path::Path;
 
+use tracing::warn;
 use url::Url;
 
+use super::super::client_tls::http_client_builder;
+use super::super::identity::AgentClientCertV1;
+use super::deferred::upload_deferred_artifacts;
 use super::storage::{OfflineRunEventV1, OfflineRunFileV1, OfflineRunStatusV1, offline_runs_dir};
 
 pub(super) async fn sync_offline_runs(
     base_url: &Url,
     agent_key: &str,
+    client_cert: Option<&AgentClientCertV1>,
     data_dir: &Path,
+    agent_id: &str,
 ) -> Result<(), anyhow::Error> {
     let root = offline_runs_dir(data_dir);
     let mut entries = match tokio::fs::read_dir(&root).await {
@@ -29,18 +35,30 @@ pub(super) async fn sync_offline_runs(
     run_dirs.sort();
 
     let ingest_url = base_url.join("agent/runs/ingest")?;
-    let client = reqwest::Client::builder()
+    let client = http_client_builder(client_cert)?
         .timeout(std::time::Duration::from_secs(30))
         .build()?;
 
     for dir in run_dirs {
         let run_path = dir.join("run.json");
         let bytes = tokio::fs::read(&run_path).await?;
-        let run: OfflineRunFileV1 = serde_json::from_slice(&bytes)?;
+        let mut run: OfflineRunFileV1 = serde_json::from_slice(&bytes)?;
 
         if run.status == OfflineRunStatusV1::Running {
             continue;
         }
+
+        let events_path = dir.join("events.jsonl");
+        let mut events = events::load_offline_events(&events_path).await?;
+
+        // Runs whose target upload was deferred are only ingested once their artifacts landed.
+        if let Err(error) =
+            upload_deferred_artifacts(data_dir, agent_id, &dir, &mut run, &mut events).await
+        {
+            warn!(run_id = %run.id, error = %error, "deferred upload failed; retrying on next reconnect");
+            continue;
+        }
+
         let ended_at = run.ended_at.unwrap_or(run.started_at);
         let status = match run.status {
             OfflineRunStatusV1::Success => "success",
@@ -49,9 +67,6 @@ pub(super) async fn sync_offline_runs(
             OfflineRunStatusV1::Running => continue,
         };
 
-        let events_path = dir.join("events.jsonl");
-        let events = events::load_offline_events(&events_path).await?;
-
         let req = request::AgentIngestRunRequestV1::from_offline_run(run, ended_at, status, events);
         ingest::post_offline_run(&client, &ingest_url, agent_key, &req).await?;
 
@@ -108,7 +108,7 @@ async fn sync_offline_runs_ingests_and_removes_dir() {
     });
 
     let base_url = url::Url::parse(&format!("http://{addr}/")).unwrap();
-    sync_offline_runs(&base_url, agent_key, data_dir)
+    sync_offline_runs(&base_url, agent_key, None, data_dir, "a")
         .await
         .unwrap();
     let _ = shutdown_tx.send(());
@@ -181,7 +181,7 @@ async fn sync_offline_runs_returns_error_and_keeps_dir_when_ingest_fails() {
     });
 
     let base_url = url::Url::parse(&format!("http://{addr}/")).unwrap();
-    let err = sync_offline_runs(&base_url, "agent-key", data_dir)
+    let err = sync_offline_runs(&base_url, "agent-key", None, data_dir, "a")
         .await
         .expect_err("expected ingest failure");
     let _ = shutdown_tx.send(());
@@ -250,7 +250,7 @@ async fn sync_offline_runs_returns_error_and_keeps_dir_when_events_invalid() {
     });
 
     let base_url = url::Url::parse(&format!("http://{addr}/")).unwrap();
-    let _ = sync_offline_runs(&base_url, "agent-key", data_dir)
+    let _ = sync_offline_runs(&base_url, "agent-key", None, data_dir, "a")
         .await
         .expect_err("expected invalid events failure");
     let _ = shutdown_tx.send(());
@@ -313,7 +313,7 @@ async fn sync_offline_runs_skips_running_runs_and_keeps_dir() {
     });
 
     let base_url = url::Url::parse(&format!("http://{addr}/")).unwrap();
-    sync_offline_runs(&base_url, "agent-key", data_dir)
+    sync_offline_runs(&base_url, "agent-key", None, data_dir, "a")
         .await
         .unwrap();
     let _ = shutdown_tx.send(());
@@ -403,7 +403,7 @@ async fn sync_offline_runs_defaults_ended_at_to_started_at_and_allows_missing_ev
     });
 
     let base_url = url::Url::parse(&format!("http://{addr}/")).unwrap();
-    sync_offline_runs(&base_url, agent_key, data_dir)
+    sync_offline_runs(&base_url, agent_key, None, data_dir, "a")
         .await
         .unwrap();
     let _ = shutdown_tx.send(());
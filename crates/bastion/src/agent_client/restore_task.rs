@@ -16,7 +16,8 @@ use bastion_core::agent_protocol::{
 use bastion_core::backup_format::MANIFEST_NAME;
 use bastion_core::manifest::ManifestV1;
 use bastion_core::progress::{
-    PROGRESS_SNAPSHOT_EVENT_KIND_V1, ProgressKindV1, ProgressSnapshotV1, ProgressUnitsV1,
+    PROGRESS_SNAPSHOT_EVENT_KIND_V1, ProgressKindV1, ProgressRateEstimator, ProgressSnapshotV1,
+    ProgressUnitsV1,
 };
 
 use super::hub_stream::{HubStreamManager, HubStreamReader};
@@ -52,15 +53,13 @@ fn check_operation_canceled(
 }
 
 struct OpProgressBuilder {
-    last_ts: Option<i64>,
-    last_done_bytes: u64,
+    rate: ProgressRateEstimator,
 }
 
 impl OpProgressBuilder {
     fn new() -> Self {
         Self {
-            last_ts: None,
-            last_done_bytes: 0,
+            rate: ProgressRateEstimator::new(),
         }
     }
 
@@ -68,22 +67,7 @@ impl OpProgressBuilder {
         let stage: &'static str = "restore";
         let now_ts = time::OffsetDateTime::now_utc().unix_timestamp();
 
-        let (rate_bps, eta_seconds) = match self.last_ts {
-            None => (None, None),
-            Some(prev_ts) => {
-                let dt = now_ts.saturating_sub(prev_ts);
-                let delta = done.bytes.saturating_sub(self.last_done_bytes);
-                let rate = if dt > 0 && delta > 0 {
-                    Some(delta.saturating_div(dt as u64).max(1))
-                } else {
-                    None
-                };
-                (rate, None)
-            }
-        };
-
-        self.last_ts = Some(now_ts);
-        self.last_done_bytes = done.bytes;
+        let (rate_bps, eta_seconds) = self.rate.observe(stage, now_ts, done.bytes, None);
 
         ProgressSnapshotV1 {
             v: 1,
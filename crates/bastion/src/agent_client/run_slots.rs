@@ -0,0 +1,261 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::sync::Notify;
+
+/// Limits the runs an Agent executes at once (Hub tasks, restores, snapshot deletes and offline
+/// runs): at most `max_parallel` in total, and one at a time per job.
+///
+/// Waiting runs start by priority, then in arrival order; a run held back only by its own job
+/// doesn't block the ones behind it. Browsing (`fs_list`, `webdav_list`) and diagnostics don't
+/// take a slot.
+#[derive(Debug, Clone)]
+pub(super) struct RunSlots {
+    max_parallel: u32,
+    state: Arc<Mutex<SlotsState>>,
+    changed: Arc<Notify>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum RunPriority {
+    /// Maintenance (snapshot deletes).
+    Low,
+    /// Backups.
+    Normal,
+    /// Restores, which someone is usually waiting for.
+    High,
+}
+
+type WaiterKey = (Reverse<RunPriority>, u64);
+
+#[derive(Debug, Default)]
+struct SlotsState {
+    running: u32,
+    running_jobs: HashSet<String>,
+    next_seq: u64,
+    waiting: BTreeMap<WaiterKey, Waiter>,
+}
+
+#[derive(Debug)]
+struct Waiter {
+    job_id: Option<String>,
+    slots: u32,
+}
+
+/// Held for the duration of a run.
+#[derive(Debug)]
+pub(super) struct RunSlot {
+    owner: RunSlots,
+    slots: u32,
+    job_id: Option<String>,
+}
+
+impl Drop for RunSlot {
+    fn drop(&mut self) {
+        {
+            let mut state = self.owner.lock();
+            state.running -= self.slots;
+            if let Some(job_id) = self.job_id.as_deref() {
+                state.running_jobs.remove(job_id);
+            }
+        }
+        self.owner.changed.notify_waiters();
+    }
+}
+
+/// Removes a waiter whose `acquire` future was dropped before it got a slot.
+struct QueuedWaiter<'a> {
+    owner: &'a RunSlots,
+    key: Option<WaiterKey>,
+}
+
+impl Drop for QueuedWaiter<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.owner.lock().waiting.remove(&key);
+            self.owner.changed.notify_waiters();
+        }
+    }
+}
+
+impl RunSlots {
+    pub(super) fn new(max_parallel: u32) -> Self {
+        Self {
+            max_parallel: max_parallel.max(1),
+            state: Arc::new(Mutex::new(SlotsState::default())),
+            changed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Waits for the previous run of `job_id` to finish and for a free slot.
+    pub(super) async fn acquire(&self, job_id: Option<&str>, priority: RunPriority) -> RunSlot {
+        self.acquire_slots(job_id, priority, 1).await
+    }
+
+    /// Waits until no run is in progress and keeps new ones from starting while held (used
+    /// before a self-update restart).
+    pub(super) async fn acquire_all(&self) -> RunSlot {
+        self.acquire_slots(None, RunPriority::High, self.max_parallel)
+            .await
+    }
+
+    #[cfg(test)]
+    pub(super) fn available(&self) -> u32 {
+        self.max_parallel - self.lock().running
+    }
+
+    async fn acquire_slots(
+        &self,
+        job_id: Option<&str>,
+        priority: RunPriority,
+        slots: u32,
+    ) -> RunSlot {
+        let key = {
+            let mut state = self.lock();
+            let key = (Reverse(priority), state.next_seq);
+            state.next_seq += 1;
+            state.waiting.insert(
+                key,
+                Waiter {
+                    job_id: job_id.map(str::to_string),
+                    slots,
+                },
+            );
+            key
+        };
+        let mut queued = QueuedWaiter {
+            owner: self,
+            key: Some(key),
+        };
+
+        loop {
+            let notified = self.changed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if let Some(waiter) = self.try_start(key) {
+                queued.key = None;
+                // Others may fit into the remaining slots.
+                self.changed.notify_waiters();
+                return RunSlot {
+                    owner: self.clone(),
+                    slots: waiter.slots,
+                    job_id: waiter.job_id,
+                };
+            }
+            notified.await;
+        }
+    }
+
+    /// Starts `key` if it is the first waiter not held back by its job and its slots are free.
+    fn try_start(&self, key: WaiterKey) -> Option<Waiter> {
+        let mut guard = self.lock();
+        let state = &mut *guard;
+        let next = state.waiting.iter().find(|(_, waiter)| {
+            waiter
+                .job_id
+                .as_deref()
+                .is_none_or(|job_id| !state.running_jobs.contains(job_id))
+        });
+        match next {
+            Some((next_key, waiter))
+                if *next_key == key && state.running + waiter.slots <= self.max_parallel => {}
+            _ => return None,
+        }
+
+        let waiter = state.waiting.remove(&key)?;
+        state.running += waiter.slots;
+        if let Some(job_id) = waiter.job_id.as_deref() {
+            state.running_jobs.insert(job_id.to_string());
+        }
+        Some(waiter)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, SlotsState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{RunPriority, RunSlots};
+
+    #[tokio::test]
+    async fn slots_limit_total_runs_and_serialize_each_job() {
+        let slots = RunSlots::new(2);
+
+        let a = slots.acquire(Some("job-a"), RunPriority::Normal).await;
+        let _b = slots.acquire(Some("job-b"), RunPriority::Normal).await;
+        assert_eq!(slots.available(), 0);
+
+        // A third job waits for a free slot.
+        let third = tokio::time::timeout(
+            Duration::from_millis(50),
+            slots.acquire(Some("job-c"), RunPriority::Normal),
+        );
+        assert!(third.await.is_err());
+
+        drop(a);
+        // The same job still runs one at a time while its previous run is in progress.
+        let again = slots.acquire(Some("job-b"), RunPriority::High);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), again)
+                .await
+                .is_err()
+        );
+        let _c = slots.acquire(Some("job-c"), RunPriority::Normal).await;
+        let untracked = tokio::time::timeout(
+            Duration::from_millis(50),
+            slots.acquire(None, RunPriority::Normal),
+        );
+        assert!(untracked.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn waiting_runs_start_by_priority_then_arrival() {
+        let slots = RunSlots::new(1);
+        let running = slots.acquire(None, RunPriority::Normal).await;
+
+        let (order_tx, mut order_rx) = tokio::sync::mpsc::unbounded_channel();
+        for (name, priority) in [
+            ("delete", RunPriority::Low),
+            ("backup-1", RunPriority::Normal),
+            ("backup-2", RunPriority::Normal),
+            ("restore", RunPriority::High),
+        ] {
+            let slots = slots.clone();
+            let order_tx = order_tx.clone();
+            tokio::spawn(async move {
+                let _slot = slots.acquire(None, priority).await;
+                order_tx.send(name).unwrap();
+            });
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        drop(running);
+        let mut order = Vec::new();
+        for _ in 0..4 {
+            order.push(order_rx.recv().await.unwrap());
+        }
+        assert_eq!(order, ["restore", "backup-1", "backup-2", "delete"]);
+    }
+
+    #[tokio::test]
+    async fn acquire_all_waits_for_running_tasks() {
+        let slots = RunSlots::new(3);
+        let run = slots.acquire(None, RunPriority::Normal).await;
+
+        let all = tokio::time::timeout(Duration::from_millis(50), slots.acquire_all());
+        assert!(all.await.is_err());
+
+        drop(run);
+        let _all = slots.acquire_all().await;
+        assert_eq!(slots.available(), 0);
+    }
+}
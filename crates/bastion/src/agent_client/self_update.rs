@@ -0,0 +1,297 @@
+use std::path::{Path, PathBuf};
+
+use base64::Engine as _;
+use bastion_core::agent_protocol::{AgentUpdateV1, parse_release_version};
+use ring::signature::{ED25519, UnparsedPublicKey};
+use tracing::info;
+use url::Url;
+
+use super::client_tls::http_client_builder;
+use super::identity::AgentClientCertV1;
+
+/// Exit code used after a successful binary swap so the service manager starts the new build
+/// (systemd `Restart=on-failure` / `RestartForceExitStatus=`, Windows service recovery).
+pub(crate) const UPDATE_RESTART_EXIT_CODE: i32 = 75;
+
+const ED25519_PUBLIC_KEY_LEN: usize = 32;
+
+/// What an agent trusts when it is offered an update.
+#[derive(Debug, Clone)]
+pub(super) struct UpdateTrust {
+    pub(super) public_key: Vec<u8>,
+    /// Accept offers older than the running build; without it a Hub cannot replay an old,
+    /// validly signed release to roll agents back.
+    pub(super) allow_downgrade: bool,
+}
+
+pub(super) fn current_platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+pub(super) fn parse_public_key(raw: &str) -> Result<Vec<u8>, anyhow::Error> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(raw.trim())
+        .map_err(|_| anyhow::anyhow!("update public key must be base64"))?;
+    if bytes.len() != ED25519_PUBLIC_KEY_LEN {
+        anyhow::bail!(
+            "update public key must be {ED25519_PUBLIC_KEY_LEN} bytes (got {})",
+            bytes.len()
+        );
+    }
+    Ok(bytes)
+}
+
+/// Checks that an offer targets this platform and moves the agent forward (unless downgrades
+/// are allowed). Runs before downloading so a replayed offer costs nothing.
+pub(super) fn check_offer(
+    update: &AgentUpdateV1,
+    trust: &UpdateTrust,
+    current_version: &str,
+) -> Result<(), anyhow::Error> {
+    if update.platform != current_platform() {
+        anyhow::bail!(
+            "update is for platform {}, this agent is {}",
+            update.platform,
+            current_platform()
+        );
+    }
+    let Some(offered) = parse_release_version(&update.version) else {
+        anyhow::bail!(
+            "update version {:?} is not a release version",
+            update.version
+        );
+    };
+    let current = parse_release_version(current_version);
+    if current == Some(offered) {
+        anyhow::bail!("update {} is already running", update.version);
+    }
+    if !trust.allow_downgrade && current.is_none_or(|current| offered < current) {
+        anyhow::bail!(
+            "update {} is not newer than {current_version}; start the agent with --update-allow-downgrade to roll back",
+            update.version
+        );
+    }
+    Ok(())
+}
+
+/// Checks that the downloaded binary is exactly what the Hub announced and that the release key
+/// signed the offer's manifest (version, platform, size and hash).
+pub(super) fn verify_update(
+    update: &AgentUpdateV1,
+    bytes: &[u8],
+    trust: &UpdateTrust,
+    current_version: &str,
+) -> Result<(), anyhow::Error> {
+    check_offer(update, trust, current_version)?;
+    if bytes.len() as u64 != update.size {
+        anyhow::bail!(
+            "update size mismatch: expected {} bytes, got {}",
+            update.size,
+            bytes.len()
+        );
+    }
+    let hash = blake3::hash(bytes).to_hex();
+    if !hash.as_str().eq_ignore_ascii_case(update.hash.trim()) {
+        anyhow::bail!("update hash mismatch");
+    }
+
+    let signature = base64::engine::general_purpose::STANDARD
+        .decode(update.signature.trim())
+        .map_err(|_| anyhow::anyhow!("update signature must be base64"))?;
+    UnparsedPublicKey::new(&ED25519, &trust.public_key)
+        .verify(update.signed_manifest().as_bytes(), &signature)
+        .map_err(|_| anyhow::anyhow!("update signature verification failed"))?;
+    Ok(())
+}
+
+/// Replaces `exe` with `new_binary`, keeping the previous build next to it as `<exe>.old`.
+///
+/// Renaming a running executable is allowed on both Unix and Windows, so the current process
+/// keeps running from the old inode/handle until it exits.
+pub(super) fn swap_binary(exe: &Path, new_binary: &Path) -> Result<PathBuf, anyhow::Error> {
+    let mut backup = exe.as_os_str().to_owned();
+    backup.push(".old");
+    let backup = PathBuf::from(backup);
+
+    let _ = std::fs::remove_file(&backup);
+    std::fs::rename(exe, &backup)?;
+    if let Err(error) = std::fs::rename(new_binary, exe) {
+        let _ = std::fs::rename(&backup, exe);
+        return Err(error.into());
+    }
+    Ok(backup)
+}
+
+/// Downloads, verifies and installs an update. Returns once the new binary is in place; the
+/// caller is responsible for restarting.
+pub(super) async fn download_and_install(
+    base_url: &Url,
+    agent_key: &str,
+    client_cert: Option<&AgentClientCertV1>,
+    trust: &UpdateTrust,
+    update: &AgentUpdateV1,
+) -> Result<(), anyhow::Error> {
+    check_offer(update, trust, env!("CARGO_PKG_VERSION"))?;
+    let url = base_url.join(update.url.trim_start_matches('/'))?;
+    let res = http_client_builder(client_cert)?
+        .build()?
+        .get(url)
+        .bearer_auth(agent_key)
+        .send()
+        .await?;
+    if res.status() != reqwest::StatusCode::OK {
+        let status = res.status();
+        let text = res.text().await.unwrap_or_default();
+        anyhow::bail!("update download failed: HTTP {status}: {text}");
+    }
+    let bytes = res.bytes().await?;
+    verify_update(update, &bytes, trust, env!("CARGO_PKG_VERSION"))?;
+
+    let exe = std::env::current_exe()?;
+    // Stage next to the executable so the final rename stays on one filesystem.
+    let mut staged = exe.as_os_str().to_owned();
+    staged.push(format!(".{}.new", update.version));
+    let staged = PathBuf::from(staged);
+
+    tokio::fs::write(&staged, &bytes).await?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755)).await?;
+    }
+
+    let backup = tokio::task::spawn_blocking({
+        let exe = exe.clone();
+        let staged = staged.clone();
+        move || swap_binary(&exe, &staged)
+    })
+    .await?;
+    let backup = match backup {
+        Ok(v) => v,
+        Err(error) => {
+            let _ = tokio::fs::remove_file(&staged).await;
+            return Err(error);
+        }
+    };
+
+    info!(
+        version = %update.version,
+        exe = %exe.display(),
+        previous = %backup.display(),
+        "agent update installed"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine as _;
+    use bastion_core::agent_protocol::AgentUpdateV1;
+    use ring::signature::{Ed25519KeyPair, KeyPair as _};
+
+    use super::{
+        UpdateTrust, check_offer, current_platform, parse_public_key, swap_binary, verify_update,
+    };
+
+    fn keypair() -> Ed25519KeyPair {
+        let rng = ring::rand::SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).expect("generate");
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).expect("keypair")
+    }
+
+    fn signed_update(key: &Ed25519KeyPair, version: &str, bytes: &[u8]) -> AgentUpdateV1 {
+        let mut update = AgentUpdateV1 {
+            version: version.to_string(),
+            platform: current_platform(),
+            url: format!("/agent/releases/{version}/x"),
+            size: bytes.len() as u64,
+            hash: blake3::hash(bytes).to_hex().to_string(),
+            signature: String::new(),
+        };
+        update.signature = base64::engine::general_purpose::STANDARD
+            .encode(key.sign(update.signed_manifest().as_bytes()));
+        update
+    }
+
+    fn trust(key: &Ed25519KeyPair, allow_downgrade: bool) -> UpdateTrust {
+        UpdateTrust {
+            public_key: key.public_key().as_ref().to_vec(),
+            allow_downgrade,
+        }
+    }
+
+    #[test]
+    fn public_key_must_be_32_bytes_of_base64() {
+        let key = keypair();
+        let encoded = base64::engine::general_purpose::STANDARD.encode(key.public_key());
+        assert_eq!(
+            parse_public_key(&encoded).expect("valid").as_slice(),
+            key.public_key().as_ref()
+        );
+        assert!(parse_public_key("not base64!").is_err());
+        assert!(parse_public_key("AAAA").is_err());
+    }
+
+    #[test]
+    fn verify_accepts_signed_binary_and_rejects_tampering() {
+        let key = keypair();
+        let trust = trust(&key, false);
+        let bytes = b"new agent binary".to_vec();
+        let update = signed_update(&key, "9.9.9", &bytes);
+
+        verify_update(&update, &bytes, &trust, "1.0.0").expect("valid update");
+
+        let mut tampered = bytes.clone();
+        tampered[0] ^= 1;
+        assert!(verify_update(&update, &tampered, &trust, "1.0.0").is_err());
+
+        // Hash and size match, but the signature was made by another key.
+        let other = keypair();
+        let forged = signed_update(&other, "9.9.9", &bytes);
+        let err = verify_update(&forged, &bytes, &trust, "1.0.0").expect_err("forged");
+        assert!(err.to_string().contains("signature"));
+
+        // The signature covers the manifest, so relabelling a signed build fails.
+        let relabelled = AgentUpdateV1 {
+            version: "10.0.0".to_string(),
+            ..update.clone()
+        };
+        let err = verify_update(&relabelled, &bytes, &trust, "1.0.0").expect_err("relabelled");
+        assert!(err.to_string().contains("signature"));
+
+        let wrong_platform = AgentUpdateV1 {
+            platform: "plan9-mips".to_string(),
+            ..update
+        };
+        assert!(verify_update(&wrong_platform, &bytes, &trust, "1.0.0").is_err());
+    }
+
+    #[test]
+    fn offers_must_be_newer_unless_downgrades_are_allowed() {
+        let key = keypair();
+        let bytes = b"old agent binary".to_vec();
+        let old = signed_update(&key, "0.9.0", &bytes);
+
+        let err = check_offer(&old, &trust(&key, false), "1.0.0").expect_err("downgrade");
+        assert!(err.to_string().contains("not newer"));
+        assert!(check_offer(&old, &trust(&key, false), "0.9.0").is_err());
+        assert!(check_offer(&old, &trust(&key, false), "dev").is_err());
+
+        verify_update(&old, &bytes, &trust(&key, true), "1.0.0").expect("allowed rollback");
+        assert!(check_offer(&old, &trust(&key, true), "0.9.0").is_err());
+    }
+
+    #[test]
+    fn swap_keeps_previous_binary_as_backup() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let exe = dir.path().join("bastion");
+        let staged = dir.path().join("bastion.9.9.9.new");
+        std::fs::write(&exe, b"old").expect("write exe");
+        std::fs::write(&staged, b"new").expect("write staged");
+
+        let backup = swap_binary(&exe, &staged).expect("swap");
+        assert_eq!(std::fs::read(&exe).expect("read exe"), b"new");
+        assert_eq!(std::fs::read(&backup).expect("read backup"), b"old");
+        assert!(!staged.exists());
+    }
+}
@@ -0,0 +1,31 @@
+//! Staging settings from the agent command line, shared by Hub-dispatched and offline runs.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use bastion_backup::staging::StagingArea;
+
+#[derive(Debug, Clone, Default)]
+struct StagingSettings {
+    dir: Option<PathBuf>,
+    min_free_bytes: u64,
+}
+
+static SETTINGS: OnceLock<StagingSettings> = OnceLock::new();
+
+/// Records the staging settings once at startup; later calls are ignored.
+pub(super) fn configure(dir: Option<PathBuf>, min_free_bytes: u64) {
+    let _ = SETTINGS.set(StagingSettings {
+        dir,
+        min_free_bytes,
+    });
+}
+
+/// Staging for runs of this agent; staged under `data_dir` unless a staging dir was configured.
+pub(super) fn staging_area(data_dir: &Path) -> StagingArea {
+    let settings = SETTINGS.get().cloned().unwrap_or_default();
+    StagingArea::new(
+        settings.dir.unwrap_or_else(|| data_dir.to_path_buf()),
+        settings.min_free_bytes,
+    )
+}
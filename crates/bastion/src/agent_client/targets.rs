@@ -90,12 +90,15 @@ mod tests {
             username: "u".to_string(),
             password: "p".to_string(),
             part_size_bytes: 123,
+            secret_name: None,
+            pending: None,
         };
         assert_eq!(target_part_size_bytes(&webdav), 123);
 
         let local = TargetResolvedV1::LocalDir {
             base_dir: "/tmp".to_string(),
             part_size_bytes: 456,
+            link_unchanged: false,
         };
         assert_eq!(target_part_size_bytes(&local), 456);
     }
@@ -0,0 +1,284 @@
+//! Hub tasks the Agent has accepted, persisted under `<data_dir>/agent/task_queue/` until their
+//! result is saved.
+//!
+//! The Hub re-sends unfinished tasks after every reconnect; the queue makes sure each one runs
+//! once: a re-sent task that is still executing waits for the first run's result, and a task that
+//! was running when the Agent stopped is reported as interrupted instead of running again.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tracing::warn;
+
+const QUEUE_ENTRY_VERSION: u32 = 1;
+
+/// Entries the Hub never re-sent (the run was finished or removed meanwhile) are dropped after this.
+const STALE_ENTRY_SECS: i64 = 7 * 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum QueuedTaskKind {
+    Backup,
+    Restore,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum QueuedTaskState {
+    /// Accepted and acknowledged, waiting for a run slot.
+    Queued,
+    Running,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QueuedTaskEntryV1 {
+    v: u32,
+    task_id: String,
+    kind: QueuedTaskKind,
+    /// Run id for backups, operation id for restores.
+    run_id: String,
+    state: QueuedTaskState,
+    received_at: i64,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct TaskQueue {
+    dir: PathBuf,
+    /// Tasks executing in this process; the receiver turns `true` when the result is saved.
+    in_flight: Arc<Mutex<HashMap<String, watch::Receiver<bool>>>>,
+}
+
+/// What to do with a task received from the Hub.
+#[derive(Debug)]
+pub(super) enum Admission {
+    /// Not seen before (or still queued from before a restart): run it.
+    Run(QueuedTask),
+    /// Already executing; wait for it to finish, then replay its saved result.
+    InFlight(watch::Receiver<bool>),
+    /// Was running when the Agent stopped; report it as failed instead of running it twice.
+    Interrupted,
+}
+
+/// An accepted task; dropping it removes it from the queue.
+#[derive(Debug)]
+pub(super) struct QueuedTask {
+    queue: TaskQueue,
+    entry: QueuedTaskEntryV1,
+    done: watch::Sender<bool>,
+}
+
+impl TaskQueue {
+    /// Opens the queue and drops stale entries.
+    pub(super) fn open(data_dir: &Path) -> Self {
+        let queue = Self {
+            dir: data_dir.join("agent").join("task_queue"),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        };
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        for (path, entry) in queue.load_entries() {
+            if now - entry.received_at > STALE_ENTRY_SECS {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        queue
+    }
+
+    /// Persisting is best effort, like the result cache: a task whose entry can't be written
+    /// still runs, it just isn't recognized after a restart.
+    pub(super) fn admit(&self, task_id: &str, kind: QueuedTaskKind, run_id: &str) -> Admission {
+        let path = self.entry_path(task_id);
+
+        let mut in_flight = self.lock_in_flight();
+        if let Some(done) = in_flight.get(task_id) {
+            return Admission::InFlight(done.clone());
+        }
+
+        let saved = path
+            .as_deref()
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice::<QueuedTaskEntryV1>(&bytes).ok())
+            .filter(|entry| entry.task_id == task_id && entry.run_id == run_id);
+        if saved
+            .as_ref()
+            .is_some_and(|entry| entry.state == QueuedTaskState::Running)
+        {
+            return Admission::Interrupted;
+        }
+
+        // Keep the original receive time so pruning counts from the first delivery.
+        let received_at = saved
+            .map(|entry| entry.received_at)
+            .unwrap_or_else(|| time::OffsetDateTime::now_utc().unix_timestamp());
+        let entry = QueuedTaskEntryV1 {
+            v: QUEUE_ENTRY_VERSION,
+            task_id: task_id.to_string(),
+            kind,
+            run_id: run_id.to_string(),
+            state: QueuedTaskState::Queued,
+            received_at,
+        };
+        if let Some(path) = path.as_deref()
+            && let Err(error) = write_entry(path, &entry)
+        {
+            warn!(task_id = %task_id, error = %error, "failed to persist task queue entry");
+        }
+
+        let (done, done_rx) = watch::channel(false);
+        in_flight.insert(task_id.to_string(), done_rx);
+        Admission::Run(QueuedTask {
+            queue: self.clone(),
+            entry,
+            done,
+        })
+    }
+
+    /// Forgets an interrupted task once its failure result has been saved.
+    pub(super) fn remove(&self, task_id: &str) {
+        if let Some(path) = self.entry_path(task_id) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    fn entry_path(&self, task_id: &str) -> Option<PathBuf> {
+        let safe = !task_id.is_empty()
+            && task_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        safe.then(|| self.dir.join(format!("{task_id}.json")))
+    }
+
+    fn load_entries(&self) -> Vec<(PathBuf, QueuedTaskEntryV1)> {
+        let Ok(dir) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        dir.filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let bytes = std::fs::read(&path).ok()?;
+                let entry = serde_json::from_slice::<QueuedTaskEntryV1>(&bytes).ok()?;
+                Some((path, entry))
+            })
+            .collect()
+    }
+
+    fn lock_in_flight(&self) -> std::sync::MutexGuard<'_, HashMap<String, watch::Receiver<bool>>> {
+        match self.in_flight.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl QueuedTask {
+    /// Records that execution started; call once the task got its run slot.
+    pub(super) fn mark_running(&mut self) {
+        self.entry.state = QueuedTaskState::Running;
+        if let Some(path) = self.queue.entry_path(&self.entry.task_id)
+            && let Err(error) = write_entry(&path, &self.entry)
+        {
+            warn!(task_id = %self.entry.task_id, error = %error, "failed to update task queue entry");
+        }
+    }
+}
+
+impl Drop for QueuedTask {
+    fn drop(&mut self) {
+        // The handler saved the result (or gave up on a dead connection, in which case the Hub
+        // re-sends the task); either way it no longer counts as running.
+        self.queue.remove(&self.entry.task_id);
+        self.queue.lock_in_flight().remove(&self.entry.task_id);
+        self.done.send_replace(true);
+    }
+}
+
+fn write_entry(path: &Path, entry: &QueuedTaskEntryV1) -> Result<(), anyhow::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.partial");
+    std::fs::write(&tmp, serde_json::to_vec_pretty(entry)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Admission, QueuedTaskKind, TaskQueue};
+
+    #[test]
+    fn duplicate_deliveries_wait_for_the_running_task() {
+        let tmp = tempfile::tempdir().unwrap();
+        let queue = TaskQueue::open(tmp.path());
+
+        let Admission::Run(mut task) = queue.admit("task1", QueuedTaskKind::Backup, "run1") else {
+            panic!("expected a new task");
+        };
+        task.mark_running();
+
+        let Admission::InFlight(done) = queue.admit("task1", QueuedTaskKind::Backup, "run1") else {
+            panic!("expected the in-flight task");
+        };
+        assert!(!*done.borrow());
+
+        drop(task);
+        assert!(*done.borrow());
+        assert!(!tmp.path().join("agent/task_queue/task1.json").exists());
+        assert!(matches!(
+            queue.admit("task1", QueuedTaskKind::Backup, "run1"),
+            Admission::Run(_)
+        ));
+    }
+
+    #[test]
+    fn tasks_running_before_a_restart_are_reported_as_interrupted() {
+        let tmp = tempfile::tempdir().unwrap();
+
+        let queue = TaskQueue::open(tmp.path());
+        let Admission::Run(mut running) = queue.admit("task1", QueuedTaskKind::Backup, "run1")
+        else {
+            panic!("expected a new task");
+        };
+        running.mark_running();
+        let Admission::Run(queued) = queue.admit("task2", QueuedTaskKind::Restore, "op2") else {
+            panic!("expected a new task");
+        };
+        // Simulate a crash: neither guard gets to clean up.
+        std::mem::forget(running);
+        std::mem::forget(queued);
+
+        let restarted = TaskQueue::open(tmp.path());
+        assert!(matches!(
+            restarted.admit("task1", QueuedTaskKind::Backup, "run1"),
+            Admission::Interrupted
+        ));
+        assert!(matches!(
+            restarted.admit("task2", QueuedTaskKind::Restore, "op2"),
+            Admission::Run(_)
+        ));
+
+        restarted.remove("task1");
+        assert!(matches!(
+            restarted.admit("task1", QueuedTaskKind::Backup, "run1"),
+            Admission::Run(_)
+        ));
+    }
+
+    #[test]
+    fn unsafe_task_ids_are_tracked_in_memory_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let queue = TaskQueue::open(tmp.path());
+
+        let Admission::Run(_task) = queue.admit("../x", QueuedTaskKind::Backup, "run1") else {
+            panic!("expected a new task");
+        };
+        assert!(matches!(
+            queue.admit("../x", QueuedTaskKind::Backup, "run1"),
+            Admission::InFlight(_)
+        ));
+        assert!(!tmp.path().join("agent").join("task_queue").exists());
+    }
+}
@@ -8,10 +8,12 @@ use bastion_core::agent_protocol::{PipelineResolvedV1, TargetResolvedV1};
 use bastion_core::job_spec::{
     ConsistencyPolicyV1, FilesystemSource, FsErrorPolicy, SnapshotModeV1,
 };
-use bastion_core::progress::{ProgressKindV1, ProgressSnapshotV1, ProgressUnitsV1};
+use bastion_core::progress::{
+    ProgressKindV1, ProgressRateEstimator, ProgressSnapshotV1, ProgressUnitsV1,
+};
 use bastion_core::run_failure::RunFailedWithSummary;
 
-use super::super::targets::{store_artifacts_to_resolved_target, target_part_size_bytes};
+use super::super::targets::target_part_size_bytes;
 use super::planner::plan_filesystem_execution;
 
 #[cfg(unix)]
@@ -25,18 +27,14 @@ fn create_dir_link(link: &std::path::Path, target: &std::path::Path) -> std::io:
 }
 
 struct BackupProgressBuilder {
-    last_stage: Option<&'static str>,
-    last_ts: Option<i64>,
-    last_done_bytes: u64,
+    rate: ProgressRateEstimator,
     source_total: Option<ProgressUnitsV1>,
 }
 
 impl BackupProgressBuilder {
     fn new() -> Self {
         Self {
-            last_stage: None,
-            last_ts: None,
-            last_done_bytes: 0,
+            rate: ProgressRateEstimator::new(),
             source_total: None,
         }
     }
@@ -61,36 +59,12 @@ impl BackupProgressBuilder {
             self.source_total = Some(total);
         }
 
-        let stage_changed = self.last_stage != Some(stage);
-        let (rate_bps, eta_seconds) = if stage_changed {
-            (None, None)
-        } else {
-            let dt = self
-                .last_ts
-                .map(|ts| now_ts.saturating_sub(ts))
-                .unwrap_or(0);
-            let delta = update.done.bytes.saturating_sub(self.last_done_bytes);
-            let rate = if dt > 0 && delta > 0 {
-                Some(delta.saturating_div(dt as u64).max(1))
-            } else {
-                None
-            };
-
-            let eta = match (rate, update.total.as_ref()) {
-                (Some(rate), Some(total)) if rate > 0 && total.bytes > update.done.bytes => Some(
-                    total
-                        .bytes
-                        .saturating_sub(update.done.bytes)
-                        .saturating_div(rate),
-                ),
-                _ => None,
-            };
-            (rate, eta)
-        };
-
-        self.last_stage = Some(stage);
-        self.last_ts = Some(now_ts);
-        self.last_done_bytes = update.done.bytes;
+        let (rate_bps, eta_seconds) = self.rate.observe(
+            stage,
+            now_ts,
+            update.done.bytes,
+            update.total.map(|t| t.bytes),
+        );
 
         let detail = match stage {
             "upload" => {
@@ -152,6 +126,14 @@ pub(super) async fn run_filesystem_backup(
         }
     }
 
+    ctx.staging
+        .check_free_space()
+        .map_err(|error| backup::staging::classify_build_error(error.into(), &ctx.staging))?;
+
+    let mut run_log = super::run_log::RunLogStream::new(ctx.run_id);
+    let (log_tx, mut log_rx) =
+        tokio::sync::mpsc::channel::<String>(super::run_log::LINE_CHANNEL_CAPACITY);
+
     let snapshot_mode = source.snapshot_mode;
     let snapshot_provider = source.snapshot_provider.clone();
     let mut snapshot_summary = if snapshot_mode == SnapshotModeV1::Off {
@@ -208,17 +190,21 @@ pub(super) async fn run_filesystem_backup(
                 )
                 .await?;
 
-                let run_dir = backup::run_dir(ctx.data_dir, ctx.run_id);
+                let run_dir = backup::run_dir(&ctx.staging.root, ctx.run_id);
                 let snapshot_settings = snapshot_settings.clone();
+                let log_tx_snapshot = log_tx.clone();
                 let attempt = tokio::task::spawn_blocking(move || {
-                    backup::filesystem::source_snapshot::attempt_source_snapshot(
-                        &root,
-                        &run_dir,
-                        provider_override.as_deref(),
-                        &snapshot_settings,
-                    )
+                    backup::task_log::with_sink(Some(log_tx_snapshot), || {
+                        backup::filesystem::source_snapshot::attempt_source_snapshot(
+                            &root,
+                            &run_dir,
+                            provider_override.as_deref(),
+                            &snapshot_settings,
+                        )
+                    })
                 })
                 .await?;
+                run_log.drain(&mut log_rx);
 
                 match attempt {
                     backup::filesystem::source_snapshot::SnapshotAttempt::Ready(handle) => {
@@ -266,6 +252,7 @@ pub(super) async fn run_filesystem_backup(
                         .await?;
 
                         if snapshot_mode == SnapshotModeV1::Required {
+                            run_log.finish(tx).await?;
                             let summary = serde_json::json!({
                                 "artifact_format": pipeline.format,
                                 "filesystem": {
@@ -344,6 +331,8 @@ pub(super) async fn run_filesystem_backup(
         });
     let encryption = super::payload_encryption(pipeline.encryption.clone());
     let artifact_format = pipeline.format.clone();
+    let compression = pipeline.compression;
+    let part_upload_concurrency = pipeline.webdav.effective_part_upload_concurrency();
     let artifact_format_for_summary = artifact_format.clone();
     let started_at = ctx.started_at;
 
@@ -365,15 +354,25 @@ pub(super) async fn run_filesystem_backup(
     )
     .await?;
 
-    let (on_part_finished, parts_uploader) = if planned.plan.allow_rolling_upload {
-        super::prepare_archive_part_uploader(
+    let (on_part_finished, part_stream, parts_uploader) = if planned.plan.stream_payload_parts {
+        let (part_stream, parts_uploader) = super::prepare_archive_part_streamer(
             &target,
             ctx.job_id,
             ctx.run_id,
             artifact_format.clone(),
-        )
+        );
+        (None, part_stream, parts_uploader)
+    } else if planned.plan.allow_rolling_upload {
+        let (on_part_finished, parts_uploader) = super::prepare_archive_part_uploader(
+            &target,
+            ctx.job_id,
+            ctx.run_id,
+            artifact_format.clone(),
+            part_upload_concurrency,
+        );
+        (on_part_finished, None, parts_uploader)
     } else {
-        (None, None)
+        (None, None, None)
     };
 
     let mut raw_tree_webdav_direct_upload: Option<
@@ -411,7 +410,7 @@ pub(super) async fn run_filesystem_backup(
             .join(ctx.job_id)
             .join(ctx.run_id);
         let target_data_dir = target_run_dir.join("data");
-        let stage_data_dir = backup::stage_dir(ctx.data_dir, ctx.run_id).join("data");
+        let stage_data_dir = backup::stage_dir(&ctx.staging.root, ctx.run_id).join("data");
 
         if let Err(error) = std::fs::create_dir_all(&target_data_dir) {
             let fields = serde_json::json!({
@@ -428,7 +427,7 @@ pub(super) async fn run_filesystem_backup(
             )
             .await?;
         } else {
-            let _ = std::fs::create_dir_all(backup::stage_dir(ctx.data_dir, ctx.run_id));
+            let _ = std::fs::create_dir_all(backup::stage_dir(&ctx.staging.root, ctx.run_id));
             if let Ok(meta) = std::fs::symlink_metadata(&stage_data_dir) {
                 let _ = if meta.is_dir() {
                     std::fs::remove_dir_all(&stage_data_dir)
@@ -478,33 +477,40 @@ pub(super) async fn run_filesystem_backup(
         tokio::sync::mpsc::channel::<backup::filesystem::FilesystemBuildProgressUpdate>(8);
     let mut progress = BackupProgressBuilder::new();
 
-    let data_dir_buf = ctx.data_dir.to_path_buf();
+    let staging_root = ctx.staging.root.clone();
+    let staging_min_free_bytes = ctx.staging.min_free_bytes;
     let job_id_clone = ctx.job_id.to_string();
     let run_id_clone = ctx.run_id.to_string();
     let read_mapping_for_build = read_mapping.clone();
     let progress_tx_build = progress_tx.clone();
     let raw_tree_webdav_direct_upload_for_build = raw_tree_webdav_direct_upload.clone();
+    let log_tx_build = log_tx.clone();
     let mut build_handle = tokio::task::spawn_blocking(move || {
         let on_progress = |update: backup::filesystem::FilesystemBuildProgressUpdate| {
             // Pre-scan/packaging is already throttled; blocking send is OK here.
             let _ = progress_tx_build.blocking_send(update);
         };
-        backup::filesystem::build_filesystem_run(
-            &data_dir_buf,
-            &job_id_clone,
-            &run_id_clone,
-            started_at,
-            &source,
-            backup::BuildPipelineOptions {
-                artifact_format,
-                encryption: &encryption,
-                part_size_bytes: part_size,
-            },
-            read_mapping_for_build.as_ref(),
-            Some(&on_progress),
-            on_part_finished,
-            raw_tree_webdav_direct_upload_for_build,
-        )
+        backup::task_log::with_sink(Some(log_tx_build), || {
+            backup::filesystem::build_filesystem_run(
+                &staging_root,
+                &job_id_clone,
+                &run_id_clone,
+                started_at,
+                &source,
+                backup::BuildPipelineOptions {
+                    artifact_format,
+                    compression,
+                    encryption: &encryption,
+                    part_size_bytes: part_size,
+                    staging_min_free_bytes,
+                },
+                read_mapping_for_build.as_ref(),
+                Some(&on_progress),
+                on_part_finished,
+                raw_tree_webdav_direct_upload_for_build,
+                part_stream,
+            )
+        })
     });
 
     let build_join = loop {
@@ -513,10 +519,15 @@ pub(super) async fn run_filesystem_backup(
             maybe_update = progress_rx.recv() => {
                 if let Some(update) = maybe_update {
                     super::send_run_progress_snapshot(tx, ctx.run_id, progress.snapshot(update)).await?;
+                    run_log.flush(tx).await?;
                 }
             }
+            Some(line) = log_rx.recv() => {
+                run_log.write_line(tx, &line).await?;
+            }
         }
     };
+    run_log.drain(&mut log_rx);
 
     let build_res: Result<backup::filesystem::FilesystemRunBuild, anyhow::Error> = match build_join
     {
@@ -526,7 +537,12 @@ pub(super) async fn run_filesystem_backup(
 
     if let Some(handle) = snapshot_handle.take() {
         let provider = handle.provider.clone();
-        let cleanup = tokio::task::spawn_blocking(move || handle.cleanup()).await?;
+        let log_tx_cleanup = log_tx.clone();
+        let cleanup = tokio::task::spawn_blocking(move || {
+            backup::task_log::with_sink(Some(log_tx_cleanup), || handle.cleanup())
+        })
+        .await?;
+        run_log.drain(&mut log_rx);
         if let Err(error) = cleanup {
             if let Some(obj) = snapshot_summary.as_object_mut() {
                 obj.insert(
@@ -555,6 +571,8 @@ pub(super) async fn run_filesystem_backup(
         }
     }
 
+    run_log.finish(tx).await?;
+
     if build_res.is_err() {
         if using_webdav_raw_tree_direct_upload
             && let TargetResolvedV1::Webdav {
@@ -579,11 +597,9 @@ pub(super) async fn run_filesystem_backup(
         }
     }
 
-    let build = build_res?;
-
-    if let Some(handle) = parts_uploader {
-        handle.await??;
-    }
+    let build = super::finish_part_upload(build_res, parts_uploader)
+        .await
+        .map_err(|error| backup::staging::classify_build_error(error, &ctx.staging))?;
 
     if build.issues.warnings_total > 0 || build.issues.errors_total > 0 {
         let level = if build.issues.errors_total > 0 {
@@ -608,6 +624,30 @@ pub(super) async fn run_filesystem_backup(
         .await?;
     }
 
+    if !build.ignore_files.is_empty() {
+        super::send_run_event(
+            tx,
+            ctx.run_id,
+            "info",
+            "fs_ignore_files",
+            "applied ignore files",
+            Some(serde_json::json!({ "files": &build.ignore_files })),
+        )
+        .await?;
+    }
+
+    if let Some(anomaly) = build.anomaly.as_ref().filter(|a| a.triggered) {
+        super::send_run_event(
+            tx,
+            ctx.run_id,
+            "warn",
+            "fs_anomaly",
+            "possible ransomware activity: snapshot differs sharply from the previous one",
+            Some(serde_json::to_value(anomaly)?),
+        )
+        .await?;
+    }
+
     let raw_consistency = build.consistency;
     let consistency_total = raw_consistency.total();
     let consistency_failed =
@@ -627,6 +667,8 @@ pub(super) async fn run_filesystem_backup(
     }
 
     let source_total = build.source_total;
+    let anomaly = build.anomaly;
+    let system_state = build.system_state;
     let issues = build.issues;
     let consistency = if consistency_policy == ConsistencyPolicyV1::Ignore {
         Default::default()
@@ -683,6 +725,8 @@ pub(super) async fn run_filesystem_backup(
                 "errors_total": issues.errors_total,
                 "snapshot": snapshot_summary.clone(),
                 "consistency": consistency,
+                "anomaly": anomaly,
+                "system_state": system_state,
             },
             "planner": planner_summary.clone(),
         });
@@ -765,13 +809,16 @@ pub(super) async fn run_filesystem_backup(
         None
     };
 
-    let mut upload_fut = std::pin::pin!(store_artifacts_to_resolved_target(
-        ctx.job_id,
-        ctx.run_id,
+    // Raw-tree direct uploads already wrote part of the data straight to the target, so only
+    // fully staged runs can be queued for a later upload.
+    let deferrable = !using_webdav_raw_tree_direct_upload && direct_target_run_dir.is_none();
+    let mut upload_fut = std::pin::pin!(super::store_artifacts_or_defer(
+        ctx,
         &target,
         &artifacts,
         webdav_limits_for_store,
         Some(upload_cb),
+        deferrable,
     ));
     let target_summary = loop {
         tokio::select! {
@@ -852,6 +899,8 @@ pub(super) async fn run_filesystem_backup(
             "errors_total": issues.errors_total,
             "snapshot": snapshot_summary.clone(),
             "consistency": consistency,
+            "anomaly": anomaly,
+            "system_state": system_state,
         },
         "planner": planner_summary,
     });
@@ -943,9 +992,11 @@ mod tests {
 
         let ctx = super::super::TaskContext {
             data_dir: tmp.path(),
+            staging: backup::staging::StagingArea::new(tmp.path().to_path_buf(), 0),
             run_id: "run_id",
             job_id: "job_id",
             started_at: time::OffsetDateTime::now_utc(),
+            upload_failure: super::super::UploadFailurePolicy::Fail,
         };
 
         let pipeline = PipelineResolvedV1::default();
@@ -956,6 +1007,12 @@ mod tests {
             root: String::new(),
             include: Vec::new(),
             exclude: Vec::new(),
+            exclude_presets: Vec::new(),
+            use_ignore_files: false,
+            max_file_size_bytes: None,
+            skip_older_than_secs: None,
+            skip_newer_than_secs: None,
+            anomaly_detection: None,
             symlink_policy: bastion_core::job_spec::FsSymlinkPolicy::Keep,
             hardlink_policy: bastion_core::job_spec::FsHardlinkPolicy::Copy,
             error_policy: bastion_core::job_spec::FsErrorPolicy::FailFast,
@@ -964,11 +1021,13 @@ mod tests {
             consistency_policy: bastion_core::job_spec::ConsistencyPolicyV1::Warn,
             consistency_fail_threshold: None,
             upload_on_consistency_failure: None,
+            windows_system_state: false,
         };
 
         let target = TargetResolvedV1::LocalDir {
             base_dir: dest_dir.to_string_lossy().to_string(),
             part_size_bytes: 1024,
+            link_unchanged: false,
         };
 
         let mut sink = RecordingSink::default();
@@ -0,0 +1,221 @@
+use std::time::{Duration, Instant};
+
+use futures_util::Sink;
+use tokio_tungstenite::tungstenite::Message;
+
+use bastion_backup as backup;
+use bastion_core::agent_protocol::PipelineResolvedV1;
+use bastion_core::agent_protocol::TargetResolvedV1;
+use bastion_core::job_spec::KubernetesPvcSource;
+
+use super::super::targets::target_part_size_bytes;
+use super::planner::plan_kubernetes_pvc_execution;
+use super::vaultwarden::UploadProgressBuilder;
+
+pub(super) async fn run_kubernetes_pvc_backup(
+    tx: &mut (impl Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin),
+    ctx: &super::TaskContext<'_>,
+    pipeline: PipelineResolvedV1,
+    source: KubernetesPvcSource,
+    target: TargetResolvedV1,
+) -> Result<serde_json::Value, anyhow::Error> {
+    ctx.staging
+        .check_free_space()
+        .map_err(|error| backup::staging::classify_build_error(error.into(), &ctx.staging))?;
+    super::send_run_event(tx, ctx.run_id, "info", "snapshot", "snapshot", None).await?;
+    let part_size = target_part_size_bytes(&target);
+    let encryption = super::payload_encryption(pipeline.encryption.clone());
+    let artifact_format = pipeline.format.clone();
+    let compression = pipeline.compression;
+    let part_upload_concurrency = pipeline.webdav.effective_part_upload_concurrency();
+    let artifact_format_for_totals = artifact_format.clone();
+    let started_at = ctx.started_at;
+
+    let planned = plan_kubernetes_pvc_execution(&pipeline, &target)
+        .map_err(|error| anyhow::anyhow!("execution planning failed: {error}"))?;
+    let planner_fields = planned
+        .plan
+        .observability_fields(&planned.source_driver, &planned.target_driver);
+    let planner_summary = planned
+        .plan
+        .summary_payload(&planned.source_driver, &planned.target_driver);
+    super::send_run_event(
+        tx,
+        ctx.run_id,
+        "info",
+        "planning",
+        "planning",
+        Some(planner_fields),
+    )
+    .await?;
+
+    let (on_part_finished, part_stream, parts_uploader) = if planned.plan.stream_payload_parts {
+        let (part_stream, parts_uploader) = super::prepare_archive_part_streamer(
+            &target,
+            ctx.job_id,
+            ctx.run_id,
+            artifact_format.clone(),
+        );
+        (None, part_stream, parts_uploader)
+    } else if planned.plan.allow_rolling_upload {
+        let (on_part_finished, parts_uploader) = super::prepare_archive_part_uploader(
+            &target,
+            ctx.job_id,
+            ctx.run_id,
+            artifact_format.clone(),
+            part_upload_concurrency,
+        );
+        (on_part_finished, None, parts_uploader)
+    } else {
+        (None, None, None)
+    };
+
+    let staging_root = ctx.staging.root.clone();
+    let staging_min_free_bytes = ctx.staging.min_free_bytes;
+    let job_id_clone = ctx.job_id.to_string();
+    let run_id_clone = ctx.run_id.to_string();
+    let build = tokio::task::spawn_blocking(move || {
+        backup::kubernetes_pvc::build_kubernetes_pvc_run(
+            &staging_root,
+            &job_id_clone,
+            &run_id_clone,
+            started_at,
+            &source,
+            backup::BuildPipelineOptions {
+                artifact_format,
+                compression,
+                encryption: &encryption,
+                part_size_bytes: part_size,
+                staging_min_free_bytes,
+            },
+            on_part_finished,
+            part_stream,
+        )
+    })
+    .await?;
+    let build = super::finish_part_upload(build, parts_uploader)
+        .await
+        .map_err(|error| backup::staging::classify_build_error(error, &ctx.staging))?;
+
+    super::send_run_event(
+        tx,
+        ctx.run_id,
+        "info",
+        "kubernetes_pvc",
+        "pvcs",
+        Some(serde_json::json!({
+            "label_selector": build.label_selector,
+            "mode": build.mode,
+            "pvcs": build.pvcs.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+        })),
+    )
+    .await?;
+
+    let parts_bytes: u64 = build.artifacts.parts.iter().map(|p| p.size).sum();
+    let entries_size = std::fs::metadata(&build.artifacts.entries_index_path)?.len();
+    let manifest_size = std::fs::metadata(&build.artifacts.manifest_path)?.len();
+    let complete_size = std::fs::metadata(&build.artifacts.complete_path)?.len();
+    let transfer_total_bytes = parts_bytes
+        .saturating_add(entries_size)
+        .saturating_add(manifest_size)
+        .saturating_add(complete_size);
+    let mut last_upload_done_bytes: u64 = 0;
+
+    super::send_run_event(tx, ctx.run_id, "info", "upload", "upload", None).await?;
+
+    struct UploadThrottle {
+        last_emit: Instant,
+        last_done: u64,
+        last_total: Option<u64>,
+    }
+    const UPLOAD_PROGRESS_MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+    let upload_throttle = std::sync::Arc::new(std::sync::Mutex::new(UploadThrottle {
+        last_emit: Instant::now()
+            .checked_sub(UPLOAD_PROGRESS_MIN_INTERVAL)
+            .unwrap_or_else(Instant::now),
+        last_done: 0,
+        last_total: None,
+    }));
+
+    let (progress_tx, mut progress_rx) =
+        tokio::sync::mpsc::channel::<bastion_targets::StoreRunProgress>(8);
+    let upload_cb: std::sync::Arc<dyn Fn(bastion_targets::StoreRunProgress) + Send + Sync> = {
+        let upload_throttle = upload_throttle.clone();
+        std::sync::Arc::new(move |p: bastion_targets::StoreRunProgress| {
+            let now = Instant::now();
+            let mut guard = match upload_throttle.lock() {
+                Ok(g) => g,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+
+            let total_bytes = Some(transfer_total_bytes);
+            let done_bytes = p.bytes_done;
+            let finished = total_bytes.is_some_and(|t| done_bytes >= t);
+            let should_emit =
+                finished || now.duration_since(guard.last_emit) >= UPLOAD_PROGRESS_MIN_INTERVAL;
+            if !should_emit {
+                return;
+            }
+            if done_bytes == guard.last_done && total_bytes == guard.last_total {
+                return;
+            }
+
+            guard.last_emit = now;
+            guard.last_done = done_bytes;
+            guard.last_total = total_bytes;
+
+            let _ = progress_tx.try_send(p);
+        })
+    };
+
+    let mut upload_fut = std::pin::pin!(super::store_artifacts_or_defer(
+        ctx,
+        &target,
+        &build.artifacts,
+        None,
+        Some(upload_cb),
+        true,
+    ));
+
+    let mut progress = UploadProgressBuilder::new();
+    let target_summary = loop {
+        tokio::select! {
+            res = &mut upload_fut => break res?,
+            maybe_update = progress_rx.recv() => {
+                if let Some(p) = maybe_update {
+                    last_upload_done_bytes = p.bytes_done;
+                    super::send_run_progress_snapshot(
+                        tx,
+                        ctx.run_id,
+                        progress.snapshot(p.bytes_done, Some(transfer_total_bytes)),
+                    )
+                    .await?;
+                }
+            }
+        }
+    };
+
+    if transfer_total_bytes > 0 && last_upload_done_bytes < transfer_total_bytes {
+        super::send_run_progress_snapshot(
+            tx,
+            ctx.run_id,
+            progress.snapshot(transfer_total_bytes, Some(transfer_total_bytes)),
+        )
+        .await?;
+    }
+    let _ = tokio::fs::remove_dir_all(&build.artifacts.run_dir).await;
+
+    Ok(serde_json::json!({
+        "target": target_summary,
+        "artifact_format": artifact_format_for_totals,
+        "entries_count": build.artifacts.entries_count,
+        "parts": build.artifacts.parts.len(),
+        "metrics": {
+            "source_total": { "files": build.source_files, "dirs": 0, "bytes": build.source_bytes },
+            "transfer_total_bytes": transfer_total_bytes,
+        },
+        "kubernetes_pvc": build.summary(),
+        "planner": planner_summary,
+    }))
+}
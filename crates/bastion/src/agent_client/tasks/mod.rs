@@ -1,9 +1,17 @@
 mod filesystem;
+mod git;
+mod imap;
+mod kubernetes_pvc;
 mod planner;
+mod postgres;
+mod run_log;
 mod sqlite;
+mod sqlite_app;
 mod vaultwarden;
+mod vm_image;
 
 use std::path::Path;
+use std::sync::Arc;
 
 use futures_util::{Sink, SinkExt};
 use tokio_tungstenite::tungstenite::Message;
@@ -21,12 +29,53 @@ use bastion_driver_api::DriverId;
 use bastion_driver_registry::builtins;
 
 use super::managed::save_task_result;
+use super::offline::{UPLOAD_DEFERRED_EVENT_KIND, defer_run_upload, is_deferred_target_summary};
+use super::targets::store_artifacts_to_resolved_target;
+
+/// What a backup does when uploading the finished artifacts to the target fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum UploadFailurePolicy {
+    Fail,
+    /// Offline runs keep the staged artifacts and retry the upload when the agent reconnects.
+    Defer,
+}
 
 struct TaskContext<'a> {
     data_dir: &'a Path,
     run_id: &'a str,
     job_id: &'a str,
     started_at: time::OffsetDateTime,
+    upload_failure: UploadFailurePolicy,
+    staging: backup::staging::StagingArea,
+}
+
+/// Uploads the staged artifacts, or hands them to the offline queue when the policy allows it.
+///
+/// A deferred upload returns a placeholder target summary (see `is_deferred_target_summary`).
+async fn store_artifacts_or_defer(
+    ctx: &TaskContext<'_>,
+    target: &TargetResolvedV1,
+    artifacts: &backup::LocalRunArtifacts,
+    webdav_limits: Option<bastion_targets::WebdavRequestLimits>,
+    on_progress: Option<Arc<dyn Fn(bastion_targets::StoreRunProgress) + Send + Sync>>,
+    deferrable: bool,
+) -> Result<serde_json::Value, anyhow::Error> {
+    match store_artifacts_to_resolved_target(
+        ctx.job_id,
+        ctx.run_id,
+        target,
+        artifacts,
+        webdav_limits,
+        on_progress,
+    )
+    .await
+    {
+        Err(error) if deferrable && ctx.upload_failure == UploadFailurePolicy::Defer => {
+            warn!(run_id = %ctx.run_id, error = %error, "target upload failed; deferring until reconnect");
+            defer_run_upload(ctx.data_dir, ctx.run_id, artifacts, error).await
+        }
+        other => other,
+    }
 }
 
 #[derive(Debug)]
@@ -60,12 +109,24 @@ type ArchivePartUploader = (
     Option<ArchivePartFinishedHook>,
     Option<ArchivePartUploadHandle>,
 );
+type ArchivePartStreamer = (
+    Option<Box<dyn backup::PartStream>>,
+    Option<ArchivePartUploadHandle>,
+);
+
+const STREAM_CHUNK_BYTES: usize = 1024 * 1024;
 
 fn expected_source_driver(spec: &JobSpecResolvedV1) -> DriverRefV1 {
     let kind = match spec {
         JobSpecResolvedV1::Filesystem { .. } => "filesystem",
         JobSpecResolvedV1::Sqlite { .. } => "sqlite",
         JobSpecResolvedV1::Vaultwarden { .. } => "vaultwarden",
+        JobSpecResolvedV1::SqliteApp { .. } => "sqlite_app",
+        JobSpecResolvedV1::Postgres { .. } => "postgres",
+        JobSpecResolvedV1::VmImage { .. } => "vm_image",
+        JobSpecResolvedV1::KubernetesPvc { .. } => "kubernetes_pvc",
+        JobSpecResolvedV1::Imap { .. } => "imap",
+        JobSpecResolvedV1::Git { .. } => "git",
     };
 
     DriverRefV1 {
@@ -78,7 +139,13 @@ fn expected_target_driver(spec: &JobSpecResolvedV1) -> DriverRefV1 {
     let kind = match spec {
         JobSpecResolvedV1::Filesystem { target, .. }
         | JobSpecResolvedV1::Sqlite { target, .. }
-        | JobSpecResolvedV1::Vaultwarden { target, .. } => match target {
+        | JobSpecResolvedV1::Vaultwarden { target, .. }
+        | JobSpecResolvedV1::SqliteApp { target, .. }
+        | JobSpecResolvedV1::Postgres { target, .. }
+        | JobSpecResolvedV1::VmImage { target, .. }
+        | JobSpecResolvedV1::KubernetesPvc { target, .. }
+        | JobSpecResolvedV1::Imap { target, .. }
+        | JobSpecResolvedV1::Git { target, .. } => match target {
             TargetResolvedV1::Webdav { .. } => "webdav",
             TargetResolvedV1::LocalDir { .. } => "local_dir",
         },
@@ -228,6 +295,7 @@ pub(super) async fn handle_backup_task(
     task_id: &str,
     task: BackupRunTaskV1,
     cancel_token: &CancellationToken,
+    upload_failure: UploadFailurePolicy,
 ) -> Result<(), anyhow::Error> {
     let run_id = task.run_id.clone();
     let job_id = task.job_id.clone();
@@ -239,14 +307,19 @@ pub(super) async fn handle_backup_task(
     send_run_event(tx, &run_id, "info", "start", "start", None).await?;
     check_run_canceled(&run_id, cancel_token)?;
 
+    let mut spec = task.spec;
+    apply_pending_webdav_credential(tx, &run_id, &mut spec).await?;
+
     let ctx = TaskContext {
         data_dir,
         run_id: &run_id,
         job_id: &job_id,
         started_at,
+        upload_failure,
+        staging: super::staging::staging_area(data_dir),
     };
 
-    let summary = match task.spec {
+    let summary = match spec {
         JobSpecResolvedV1::Filesystem {
             pipeline,
             source,
@@ -265,10 +338,61 @@ pub(super) async fn handle_backup_task(
             target,
             ..
         } => vaultwarden::run_vaultwarden_backup(tx, &ctx, pipeline, source, target).await?,
+        JobSpecResolvedV1::SqliteApp {
+            pipeline,
+            source,
+            target,
+            ..
+        } => sqlite_app::run_sqlite_app_backup(tx, &ctx, pipeline, source, target).await?,
+        JobSpecResolvedV1::Postgres {
+            pipeline,
+            source,
+            target,
+            ..
+        } => postgres::run_postgres_backup(tx, &ctx, pipeline, source, target).await?,
+        JobSpecResolvedV1::VmImage {
+            pipeline,
+            source,
+            target,
+            ..
+        } => vm_image::run_vm_image_backup(tx, &ctx, pipeline, source, target).await?,
+        JobSpecResolvedV1::KubernetesPvc {
+            pipeline,
+            source,
+            target,
+            ..
+        } => kubernetes_pvc::run_kubernetes_pvc_backup(tx, &ctx, pipeline, source, target).await?,
+        JobSpecResolvedV1::Imap {
+            pipeline,
+            source,
+            credentials,
+            target,
+            ..
+        } => imap::run_imap_backup(tx, &ctx, pipeline, source, credentials, target).await?,
+        JobSpecResolvedV1::Git {
+            pipeline,
+            source,
+            target,
+            ..
+        } => git::run_git_backup(tx, &ctx, pipeline, source, target).await?,
     };
 
     check_run_canceled(&run_id, cancel_token)?;
 
+    if let Some(target) = summary.get("target")
+        && is_deferred_target_summary(target)
+    {
+        send_run_event(
+            tx,
+            &run_id,
+            "warn",
+            UPLOAD_DEFERRED_EVENT_KIND,
+            "target unreachable; artifacts kept for upload on reconnect",
+            Some(target.clone()),
+        )
+        .await?;
+    }
+
     send_run_event(tx, &run_id, "info", "complete", "complete", None).await?;
 
     let result = AgentToHubMessageV1::TaskResult {
@@ -287,6 +411,79 @@ pub(super) async fn handle_backup_task(
     Ok(())
 }
 
+/// Switches a WebDAV target to its staged credential when the server accepts it.
+///
+/// The Hub promotes the credential once it sees the verified event; a rejected credential is
+/// reported and the run continues with the active one.
+async fn apply_pending_webdav_credential(
+    tx: &mut (impl Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin),
+    run_id: &str,
+    spec: &mut JobSpecResolvedV1,
+) -> Result<(), anyhow::Error> {
+    let target = match spec {
+        JobSpecResolvedV1::Filesystem { target, .. }
+        | JobSpecResolvedV1::Sqlite { target, .. }
+        | JobSpecResolvedV1::Vaultwarden { target, .. }
+        | JobSpecResolvedV1::SqliteApp { target, .. }
+        | JobSpecResolvedV1::Postgres { target, .. }
+        | JobSpecResolvedV1::VmImage { target, .. }
+        | JobSpecResolvedV1::KubernetesPvc { target, .. }
+        | JobSpecResolvedV1::Imap { target, .. }
+        | JobSpecResolvedV1::Git { target, .. } => target,
+    };
+    let TargetResolvedV1::Webdav {
+        base_url,
+        username,
+        password,
+        secret_name,
+        pending,
+        ..
+    } = target
+    else {
+        return Ok(());
+    };
+    let Some(pending) = pending.take() else {
+        return Ok(());
+    };
+
+    let mut fields = serde_json::json!({
+        "secret_name": secret_name,
+        "staged_at": pending.staged_at,
+    });
+    let credentials = bastion_targets::WebdavCredentials {
+        username: pending.username.clone(),
+        password: pending.password.clone(),
+    };
+    match bastion_targets::webdav::check_credentials(base_url, credentials).await {
+        Ok(()) => {
+            *username = pending.username;
+            *password = pending.password;
+            send_run_event(
+                tx,
+                run_id,
+                "info",
+                "webdav_credential_verified",
+                "pending webdav credential verified",
+                Some(fields),
+            )
+            .await?;
+        }
+        Err(error) => {
+            fields["error"] = serde_json::json!(format!("{error:#}"));
+            send_run_event(
+                tx,
+                run_id,
+                "warn",
+                "webdav_credential_rejected",
+                "pending webdav credential rejected; using the active credential",
+                Some(fields),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
 fn payload_encryption(encryption: EncryptionResolvedV1) -> backup::PayloadEncryption {
     match encryption {
         EncryptionResolvedV1::None => backup::PayloadEncryption::None,
@@ -305,6 +502,7 @@ fn prepare_archive_part_uploader(
     job_id: &str,
     run_id: &str,
     artifact_format: bastion_core::manifest::ArtifactFormatV1,
+    part_upload_concurrency: usize,
 ) -> ArchivePartUploader {
     if artifact_format != bastion_core::manifest::ArtifactFormatV1::ArchiveV1 {
         return (None, None);
@@ -332,6 +530,7 @@ fn prepare_archive_part_uploader(
                     credentials,
                     &job_id,
                     &run_id,
+                    part_upload_concurrency,
                     rx,
                 )
                 .await
@@ -363,6 +562,142 @@ fn prepare_archive_part_uploader(
     (Some(on_part_finished), Some(handle))
 }
 
+fn prepare_archive_part_streamer(
+    target: &bastion_core::agent_protocol::TargetResolvedV1,
+    job_id: &str,
+    run_id: &str,
+    artifact_format: bastion_core::manifest::ArtifactFormatV1,
+) -> ArchivePartStreamer {
+    if artifact_format != bastion_core::manifest::ArtifactFormatV1::ArchiveV1 {
+        return (None, None);
+    }
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<bastion_targets::StreamedPart>(1);
+
+    let handle: ArchivePartUploadHandle = match target {
+        bastion_core::agent_protocol::TargetResolvedV1::Webdav {
+            base_url,
+            username,
+            password,
+            ..
+        } => {
+            let credentials = bastion_targets::WebdavCredentials {
+                username: username.clone(),
+                password: password.clone(),
+            };
+            let base_url = base_url.to_string();
+            let job_id = job_id.to_string();
+            let run_id = run_id.to_string();
+            tokio::spawn(async move {
+                bastion_targets::webdav::store_run_parts_streaming(
+                    &base_url,
+                    credentials,
+                    &job_id,
+                    &run_id,
+                    rx,
+                )
+                .await
+                .map(|_| ())
+            })
+        }
+        bastion_core::agent_protocol::TargetResolvedV1::LocalDir { base_dir, .. } => {
+            let base_dir = base_dir.to_string();
+            let job_id = job_id.to_string();
+            let run_id = run_id.to_string();
+            tokio::task::spawn_blocking(move || {
+                bastion_targets::local_dir::store_run_parts_streaming(
+                    std::path::Path::new(&base_dir),
+                    &job_id,
+                    &run_id,
+                    rx,
+                )
+                .map(|_| ())
+            })
+        }
+    };
+
+    let stream = ChannelPartStream {
+        parts_tx: tx,
+        chunk_tx: None,
+        buf: Vec::new(),
+    };
+    (Some(Box::new(stream)), Some(handle))
+}
+
+/// Forwards archive parts from the blocking builder thread to a streaming target upload.
+struct ChannelPartStream {
+    parts_tx: tokio::sync::mpsc::Sender<bastion_targets::StreamedPart>,
+    chunk_tx: Option<tokio::sync::mpsc::Sender<std::io::Result<Vec<u8>>>>,
+    buf: Vec<u8>,
+}
+
+impl ChannelPartStream {
+    fn send_chunk(&mut self) -> std::io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk_tx = self
+            .chunk_tx
+            .as_ref()
+            .ok_or_else(|| std::io::Error::other("no streamed part in progress"))?;
+        chunk_tx
+            .blocking_send(Ok(std::mem::take(&mut self.buf)))
+            .map_err(|_| std::io::Error::other("streaming uploader dropped"))
+    }
+}
+
+impl backup::PartStream for ChannelPartStream {
+    fn begin_part(&mut self, name: &str) -> std::io::Result<()> {
+        let (chunk_tx, chunks) = tokio::sync::mpsc::channel(4);
+        self.parts_tx
+            .blocking_send(bastion_targets::StreamedPart {
+                name: name.to_string(),
+                chunks,
+            })
+            .map_err(|_| std::io::Error::other("streaming uploader dropped"))?;
+        self.chunk_tx = Some(chunk_tx);
+        Ok(())
+    }
+
+    fn write_part(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.buf.extend_from_slice(buf);
+        if self.buf.len() >= STREAM_CHUNK_BYTES {
+            self.send_chunk()?;
+        }
+        Ok(())
+    }
+
+    fn finish_part(&mut self, _part: &bastion_core::manifest::ArtifactPart) -> std::io::Result<()> {
+        self.send_chunk()?;
+        // Dropping the sender ends the part's body.
+        self.chunk_tx = None;
+        Ok(())
+    }
+}
+
+impl Drop for ChannelPartStream {
+    fn drop(&mut self) {
+        // A part still open here means the build failed; make the target discard it.
+        if let Some(chunk_tx) = self.chunk_tx.take() {
+            let _ = chunk_tx.blocking_send(Err(std::io::Error::other("archive build aborted")));
+        }
+    }
+}
+
+/// Waits for the rolling or streaming part upload after the archive build returned.
+///
+/// A failed upload also fails the build (its part channel closes), so the upload error is
+/// reported first as the root cause.
+async fn finish_part_upload<T>(
+    build: Result<T, anyhow::Error>,
+    parts_uploader: Option<ArchivePartUploadHandle>,
+) -> Result<T, anyhow::Error> {
+    if let Some(handle) = parts_uploader {
+        handle.await??;
+    }
+    build
+}
+
 async fn send_run_event(
     tx: &mut (impl Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin),
     run_id: &str,
@@ -457,10 +792,12 @@ mod tests {
                 source: bastion_core::job_spec::SqliteSource {
                     path: "/tmp/db.sqlite3".to_string(),
                     integrity_check: false,
+                    databases: Vec::new(),
                 },
                 target: TargetResolvedV1::LocalDir {
                     base_dir: "/tmp/out".to_string(),
                     part_size_bytes: 1024 * 1024,
+                    link_unchanged: false,
                 },
             },
             source_driver: None,
@@ -553,12 +890,14 @@ mod tests {
         let target = bastion_core::agent_protocol::TargetResolvedV1::LocalDir {
             base_dir: "/tmp".to_string(),
             part_size_bytes: 1024,
+            link_unchanged: false,
         };
         let (hook, handle) = prepare_archive_part_uploader(
             &target,
             "job_id",
             "run_id",
             bastion_core::manifest::ArtifactFormatV1::RawTreeV1,
+            1,
         );
         assert!(hook.is_none());
         assert!(handle.is_none());
@@ -577,6 +916,7 @@ mod tests {
         let target = bastion_core::agent_protocol::TargetResolvedV1::LocalDir {
             base_dir: base_dir.path().to_string_lossy().to_string(),
             part_size_bytes: 1024,
+            link_unchanged: false,
         };
 
         let (hook, handle) = prepare_archive_part_uploader(
@@ -584,6 +924,7 @@ mod tests {
             "job_id",
             "run_id",
             bastion_core::manifest::ArtifactFormatV1::ArchiveV1,
+            1,
         );
         let hook = hook.expect("hook");
         let handle = handle.expect("handle");
@@ -696,4 +1037,44 @@ mod tests {
         }
         Ok(())
     }
+
+    #[tokio::test]
+    async fn run_log_stream_opens_lazily_and_ends_with_eof() -> Result<(), anyhow::Error> {
+        let mut sink = RecordingSink::default();
+        let mut log = run_log::RunLogStream::new("run_id");
+
+        log.finish(&mut sink).await?;
+        assert!(sink.messages.is_empty());
+
+        log.write_line(&mut sink, "[stderr] hello").await?;
+        log.finish(&mut sink).await?;
+        assert_eq!(sink.messages.len(), 3);
+
+        let Message::Text(text) = &sink.messages[0] else {
+            anyhow::bail!("expected text frame");
+        };
+        let AgentToHubMessageV1::RunLogOpen {
+            run_id, stream_id, ..
+        } = serde_json::from_str::<AgentToHubMessageV1>(text.as_ref())?
+        else {
+            anyhow::bail!("expected RunLogOpen");
+        };
+        assert_eq!(run_id, "run_id");
+
+        let Message::Binary(bytes) = &sink.messages[1] else {
+            anyhow::bail!("expected binary frame");
+        };
+        let chunk = bastion_core::agent_stream::decode_artifact_chunk_frame_v1(bytes)?;
+        assert_eq!(chunk.stream_id.to_string(), stream_id);
+        assert!(!chunk.flags.eof);
+        assert!(std::str::from_utf8(chunk.payload)?.ends_with(" [stderr] hello\n"));
+
+        let Message::Binary(bytes) = &sink.messages[2] else {
+            anyhow::bail!("expected binary frame");
+        };
+        let eof = bastion_core::agent_stream::decode_artifact_chunk_frame_v1(bytes)?;
+        assert!(eof.flags.eof);
+        assert!(eof.payload.is_empty());
+        Ok(())
+    }
 }
@@ -66,6 +66,103 @@ pub(super) fn plan_vaultwarden_execution(
     )
 }
 
+pub(super) fn plan_sqlite_app_execution(
+    pipeline: &PipelineResolvedV1,
+    source: &job_spec::SqliteAppSource,
+    target: &TargetResolvedV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("sqlite_app", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        Some(source.consistency_policy),
+        source.upload_on_consistency_failure,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
+pub(super) fn plan_postgres_execution(
+    pipeline: &PipelineResolvedV1,
+    target: &TargetResolvedV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("postgres", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        None,
+        None,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
+pub(super) fn plan_vm_image_execution(
+    pipeline: &PipelineResolvedV1,
+    target: &TargetResolvedV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("vm_image", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        None,
+        None,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
+pub(super) fn plan_kubernetes_pvc_execution(
+    pipeline: &PipelineResolvedV1,
+    target: &TargetResolvedV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("kubernetes_pvc", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        None,
+        None,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
+pub(super) fn plan_git_execution(
+    pipeline: &PipelineResolvedV1,
+    target: &TargetResolvedV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("git", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        None,
+        None,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
+pub(super) fn plan_imap_execution(
+    pipeline: &PipelineResolvedV1,
+    target: &TargetResolvedV1,
+) -> Result<PlannedExecution, anyhow::Error> {
+    let source_driver = PlannerDriverRefV1::new("imap", 1)?;
+    plan_for_target(
+        source_driver,
+        PlannerSourceCapabilitiesV1::default(),
+        pipeline,
+        None,
+        None,
+        DirectUploadPreferenceV1::Off,
+        target,
+    )
+}
+
 fn plan_for_target(
     source_driver: PlannerDriverRefV1,
     source_capabilities: PlannerSourceCapabilitiesV1,
@@ -105,6 +202,7 @@ fn plan_for_target(
         direct_upload_preference,
         consistency_policy,
         upload_on_consistency_failure,
+        stream_payload: pipeline.staging == job_spec::PayloadStagingV1::Stream,
     })?;
 
     Ok(PlannedExecution {
@@ -130,6 +228,12 @@ mod tests {
             root: String::new(),
             include: vec![],
             exclude: vec![],
+            exclude_presets: Vec::new(),
+            use_ignore_files: false,
+            max_file_size_bytes: None,
+            skip_older_than_secs: None,
+            skip_newer_than_secs: None,
+            anomaly_detection: None,
             symlink_policy: Default::default(),
             hardlink_policy: Default::default(),
             error_policy: Default::default(),
@@ -138,12 +242,15 @@ mod tests {
             consistency_policy: bastion_core::job_spec::ConsistencyPolicyV1::Warn,
             consistency_fail_threshold: Some(0),
             upload_on_consistency_failure: Some(true),
+            windows_system_state: false,
         };
         let target = TargetResolvedV1::Webdav {
             base_url: "https://example.com/base/".to_string(),
             username: "u".to_string(),
             password: "p".to_string(),
             part_size_bytes: 1024,
+            secret_name: None,
+            pending: None,
         };
         let pipeline = PipelineResolvedV1 {
             format: ArtifactFormatV1::RawTreeV1,
@@ -152,6 +259,7 @@ mod tests {
                     mode: bastion_core::job_spec::WebdavRawTreeDirectModeV1::Auto,
                     ..Default::default()
                 },
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -165,6 +273,7 @@ mod tests {
         let target = TargetResolvedV1::LocalDir {
             base_dir: "/tmp/out".to_string(),
             part_size_bytes: 1024,
+            link_unchanged: false,
         };
         let pipeline = PipelineResolvedV1 {
             format: ArtifactFormatV1::ArchiveV1,
@@ -188,6 +297,8 @@ mod tests {
             username: "u".to_string(),
             password: "p".to_string(),
             part_size_bytes: 1024,
+            secret_name: None,
+            pending: None,
         };
         let pipeline = PipelineResolvedV1 {
             format: ArtifactFormatV1::ArchiveV1,
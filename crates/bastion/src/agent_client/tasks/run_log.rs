@@ -0,0 +1,118 @@
+use futures_util::{Sink, SinkExt};
+use time::format_description::well_known::Rfc3339;
+use tokio_tungstenite::tungstenite::Message;
+use uuid::Uuid;
+
+use bastion_core::agent_protocol::{AgentToHubMessageV1, PROTOCOL_VERSION};
+use bastion_core::agent_stream::{ArtifactChunkFrameV1Flags, encode_artifact_chunk_frame_v1};
+
+/// Lines buffered before they are flushed as one chunk frame.
+const FLUSH_BYTES: usize = 16 * 1024;
+const MAX_FRAME_BYTES: usize = 64 * 1024;
+/// Capacity of the channel the blocking builders write task log lines to.
+pub(super) const LINE_CHANNEL_CAPACITY: usize = 1024;
+
+/// Raw task log of a run, pushed to the Hub as artifact chunk frames.
+///
+/// The stream is opened lazily, so runs that log nothing never announce one.
+pub(super) struct RunLogStream {
+    run_id: String,
+    stream_id: Uuid,
+    opened: bool,
+    buf: Vec<u8>,
+}
+
+impl RunLogStream {
+    pub(super) fn new(run_id: &str) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            stream_id: Uuid::new_v4(),
+            opened: false,
+            buf: Vec::new(),
+        }
+    }
+
+    fn push_line(&mut self, line: &str) {
+        let ts = time::OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .unwrap_or_default();
+        self.buf.extend_from_slice(ts.as_bytes());
+        self.buf.push(b' ');
+        self.buf.extend_from_slice(line.trim_end().as_bytes());
+        self.buf.push(b'\n');
+    }
+
+    /// Buffers the lines already waiting in `rx` without blocking.
+    pub(super) fn drain(&mut self, rx: &mut tokio::sync::mpsc::Receiver<String>) {
+        while let Ok(line) = rx.try_recv() {
+            self.push_line(&line);
+        }
+    }
+
+    /// Pushes `line` and flushes once enough output is buffered.
+    pub(super) async fn write_line(
+        &mut self,
+        tx: &mut (impl Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin),
+        line: &str,
+    ) -> Result<(), anyhow::Error> {
+        self.push_line(line);
+        if self.buf.len() >= FLUSH_BYTES {
+            self.flush(tx).await?;
+        }
+        Ok(())
+    }
+
+    pub(super) async fn flush(
+        &mut self,
+        tx: &mut (impl Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin),
+    ) -> Result<(), anyhow::Error> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        if !self.opened {
+            let msg = AgentToHubMessageV1::RunLogOpen {
+                v: PROTOCOL_VERSION,
+                run_id: self.run_id.clone(),
+                stream_id: self.stream_id.to_string(),
+            };
+            tx.send(Message::Text(serde_json::to_string(&msg)?.into()))
+                .await?;
+            self.opened = true;
+        }
+
+        let buf = std::mem::take(&mut self.buf);
+        for chunk in buf.chunks(MAX_FRAME_BYTES) {
+            self.send_frame(tx, false, chunk).await?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the remaining output and closes the stream.
+    pub(super) async fn finish(
+        &mut self,
+        tx: &mut (impl Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin),
+    ) -> Result<(), anyhow::Error> {
+        self.flush(tx).await?;
+        if self.opened {
+            self.send_frame(tx, true, &[]).await?;
+            self.opened = false;
+            self.stream_id = Uuid::new_v4();
+        }
+        Ok(())
+    }
+
+    async fn send_frame(
+        &self,
+        tx: &mut (impl Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin),
+        eof: bool,
+        payload: &[u8],
+    ) -> Result<(), anyhow::Error> {
+        let frame = encode_artifact_chunk_frame_v1(
+            &self.stream_id,
+            ArtifactChunkFrameV1Flags { eof },
+            payload,
+        );
+        tx.send(Message::Binary(frame.into())).await?;
+        Ok(())
+    }
+}
@@ -8,21 +8,21 @@ use bastion_core::agent_protocol::PipelineResolvedV1;
 use bastion_core::agent_protocol::TargetResolvedV1;
 use bastion_core::job_spec::SqliteSource;
 use bastion_core::manifest::ArtifactFormatV1;
-use bastion_core::progress::{ProgressKindV1, ProgressSnapshotV1, ProgressUnitsV1};
+use bastion_core::progress::{
+    ProgressKindV1, ProgressRateEstimator, ProgressSnapshotV1, ProgressUnitsV1,
+};
 
-use super::super::targets::{store_artifacts_to_resolved_target, target_part_size_bytes};
+use super::super::targets::target_part_size_bytes;
 use super::planner::plan_sqlite_execution;
 
 struct UploadProgressBuilder {
-    last_ts: Option<i64>,
-    last_done_bytes: u64,
+    rate: ProgressRateEstimator,
 }
 
 impl UploadProgressBuilder {
     fn new() -> Self {
         Self {
-            last_ts: None,
-            last_done_bytes: 0,
+            rate: ProgressRateEstimator::new(),
         }
     }
 
@@ -40,26 +40,7 @@ impl UploadProgressBuilder {
         done_bytes: u64,
         total_bytes: Option<u64>,
     ) -> ProgressSnapshotV1 {
-        let dt = self
-            .last_ts
-            .map(|ts| now_ts.saturating_sub(ts))
-            .unwrap_or(0);
-        let delta = done_bytes.saturating_sub(self.last_done_bytes);
-        let rate = if dt > 0 && delta > 0 {
-            Some(delta.saturating_div(dt as u64).max(1))
-        } else {
-            None
-        };
-
-        let eta = match (rate, total_bytes) {
-            (Some(rate), Some(total)) if rate > 0 && total > done_bytes => {
-                Some(total.saturating_sub(done_bytes).saturating_div(rate))
-            }
-            _ => None,
-        };
-
-        self.last_ts = Some(now_ts);
-        self.last_done_bytes = done_bytes;
+        let (rate, eta) = self.rate.observe("upload", now_ts, done_bytes, total_bytes);
 
         ProgressSnapshotV1 {
             v: 1,
@@ -90,11 +71,20 @@ pub(super) async fn run_sqlite_backup(
     source: SqliteSource,
     target: TargetResolvedV1,
 ) -> Result<serde_json::Value, anyhow::Error> {
+    ctx.staging
+        .check_free_space()
+        .map_err(|error| backup::staging::classify_build_error(error.into(), &ctx.staging))?;
     super::send_run_event(tx, ctx.run_id, "info", "snapshot", "snapshot", None).await?;
-    let sqlite_path = source.path.clone();
+    let sqlite_path = source
+        .database_list()
+        .first()
+        .map(|db| db.path.clone())
+        .unwrap_or_default();
     let part_size = target_part_size_bytes(&target);
     let encryption = super::payload_encryption(pipeline.encryption.clone());
     let artifact_format = pipeline.format.clone();
+    let compression = pipeline.compression;
+    let part_upload_concurrency = pipeline.webdav.effective_part_upload_concurrency();
     let artifact_format_for_totals = artifact_format.clone();
     let started_at = ctx.started_at;
 
@@ -116,43 +106,60 @@ pub(super) async fn run_sqlite_backup(
     )
     .await?;
 
-    let (on_part_finished, parts_uploader) = if planned.plan.allow_rolling_upload {
-        super::prepare_archive_part_uploader(
+    let (on_part_finished, part_stream, parts_uploader) = if planned.plan.stream_payload_parts {
+        let (part_stream, parts_uploader) = super::prepare_archive_part_streamer(
             &target,
             ctx.job_id,
             ctx.run_id,
             artifact_format.clone(),
-        )
+        );
+        (None, part_stream, parts_uploader)
+    } else if planned.plan.allow_rolling_upload {
+        let (on_part_finished, parts_uploader) = super::prepare_archive_part_uploader(
+            &target,
+            ctx.job_id,
+            ctx.run_id,
+            artifact_format.clone(),
+            part_upload_concurrency,
+        );
+        (on_part_finished, None, parts_uploader)
     } else {
-        (None, None)
+        (None, None, None)
     };
 
-    let data_dir_buf = ctx.data_dir.to_path_buf();
+    let staging_root = ctx.staging.root.clone();
+    let staging_min_free_bytes = ctx.staging.min_free_bytes;
     let job_id_clone = ctx.job_id.to_string();
     let run_id_clone = ctx.run_id.to_string();
     let build = tokio::task::spawn_blocking(move || {
         backup::sqlite::build_sqlite_run(
-            &data_dir_buf,
+            &staging_root,
             &job_id_clone,
             &run_id_clone,
             started_at,
             &source,
             backup::BuildPipelineOptions {
                 artifact_format,
+                compression,
                 encryption: &encryption,
                 part_size_bytes: part_size,
+                staging_min_free_bytes,
             },
             on_part_finished,
+            part_stream,
         )
     })
-    .await??;
-
-    if let Some(handle) = parts_uploader {
-        handle.await??;
-    }
+    .await?;
+    let build = super::finish_part_upload(build, parts_uploader)
+        .await
+        .map_err(|error| backup::staging::classify_build_error(error, &ctx.staging))?;
 
-    if let Some(check) = build.integrity_check.as_ref() {
+    for db in &build.databases {
+        let Some(check) = db.integrity_check.as_ref() else {
+            continue;
+        };
         let data = serde_json::json!({
+            "path": db.path,
             "ok": check.ok,
             "truncated": check.truncated,
             "lines": check.lines,
@@ -168,7 +175,7 @@ pub(super) async fn run_sqlite_backup(
         .await?;
         if !check.ok {
             let first = check.lines.first().cloned().unwrap_or_default();
-            anyhow::bail!("sqlite integrity_check failed: {}", first);
+            anyhow::bail!("sqlite integrity_check failed for {}: {}", db.path, first);
         }
     }
 
@@ -177,7 +184,7 @@ pub(super) async fn run_sqlite_backup(
     let manifest_size = std::fs::metadata(&build.artifacts.manifest_path)?.len();
     let complete_size = std::fs::metadata(&build.artifacts.complete_path)?.len();
     let raw_tree_data_bytes = if artifact_format_for_totals == ArtifactFormatV1::RawTreeV1 {
-        build.snapshot_size
+        build.snapshots_size()
     } else {
         0
     };
@@ -236,13 +243,13 @@ pub(super) async fn run_sqlite_backup(
         })
     };
 
-    let mut upload_fut = std::pin::pin!(store_artifacts_to_resolved_target(
-        ctx.job_id,
-        ctx.run_id,
+    let mut upload_fut = std::pin::pin!(super::store_artifacts_or_defer(
+        ctx,
         &target,
         &build.artifacts,
         None,
         Some(upload_cb),
+        true,
     ));
 
     let mut progress = UploadProgressBuilder::new();
@@ -279,18 +286,23 @@ pub(super) async fn run_sqlite_backup(
         "entries_count": build.artifacts.entries_count,
         "parts": build.artifacts.parts.len(),
         "metrics": {
-            "source_total": { "files": 1, "dirs": 0, "bytes": build.snapshot_size },
+            "source_total": {
+                "files": build.databases.len(),
+                "dirs": 0,
+                "bytes": build.snapshots_size(),
+            },
             "transfer_total_bytes": transfer_total_bytes,
         },
         "sqlite": {
             "path": sqlite_path,
             "snapshot_name": build.snapshot_name,
             "snapshot_size": build.snapshot_size,
-            "integrity_check": build.integrity_check.map(|check| serde_json::json!({
+            "integrity_check": build.integrity_check.as_ref().map(|check| serde_json::json!({
                 "ok": check.ok,
                 "truncated": check.truncated,
                 "lines": check.lines,
             })),
+            "databases": build.databases_summary(),
         },
         "planner": planner_summary,
     }))
@@ -317,9 +329,10 @@ mod tests {
         assert_eq!(s1.rate_bps, Some(5));
         assert_eq!(s1.eta_seconds, Some(10));
 
+        // A stall lowers the rolling average instead of dropping the estimate.
         let s2 = builder.snapshot_at(1020, 50, Some(100));
-        assert_eq!(s2.rate_bps, None);
-        assert_eq!(s2.eta_seconds, None);
+        assert_eq!(s2.rate_bps, Some(2));
+        assert_eq!(s2.eta_seconds, Some(25));
     }
 
     #[test]
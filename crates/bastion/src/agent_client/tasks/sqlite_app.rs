@@ -0,0 +1,318 @@
+use std::time::{Duration, Instant};
+
+use futures_util::Sink;
+use tokio_tungstenite::tungstenite::Message;
+
+use bastion_backup as backup;
+use bastion_core::agent_protocol::PipelineResolvedV1;
+use bastion_core::agent_protocol::TargetResolvedV1;
+use bastion_core::job_spec::{ConsistencyPolicyV1, SqliteAppSource};
+use bastion_core::run_failure::RunFailedWithSummary;
+
+use super::super::targets::target_part_size_bytes;
+use super::planner::plan_sqlite_app_execution;
+use super::vaultwarden::UploadProgressBuilder;
+
+pub(super) async fn run_sqlite_app_backup(
+    tx: &mut (impl Sink<Message, Error = tokio_tungstenite::tungstenite::Error> + Unpin),
+    ctx: &super::TaskContext<'_>,
+    pipeline: PipelineResolvedV1,
+    source: SqliteAppSource,
+    target: TargetResolvedV1,
+) -> Result<serde_json::Value, anyhow::Error> {
+    ctx.staging
+        .check_free_space()
+        .map_err(|error| backup::staging::classify_build_error(error.into(), &ctx.staging))?;
+    super::send_run_event(tx, ctx.run_id, "info", "snapshot", "snapshot", None).await?;
+    let app_data_dir = source.data_dir.clone();
+    let db_path = source.db_path.clone();
+    let consistency_policy = source.consistency_policy;
+    let consistency_fail_threshold = source.consistency_fail_threshold.unwrap_or(0);
+    let upload_on_consistency_failure = source.upload_on_consistency_failure.unwrap_or(false);
+    let part_size = target_part_size_bytes(&target);
+    let encryption = super::payload_encryption(pipeline.encryption.clone());
+    let artifact_format = pipeline.format.clone();
+    let compression = pipeline.compression;
+    let part_upload_concurrency = pipeline.webdav.effective_part_upload_concurrency();
+    let artifact_format_for_summary = artifact_format.clone();
+    let started_at = ctx.started_at;
+
+    let planned = plan_sqlite_app_execution(&pipeline, &source, &target)
+        .map_err(|error| anyhow::anyhow!("execution planning failed: {error}"))?;
+    let planner_fields = planned
+        .plan
+        .observability_fields(&planned.source_driver, &planned.target_driver);
+    let planner_summary = planned
+        .plan
+        .summary_payload(&planned.source_driver, &planned.target_driver);
+    super::send_run_event(
+        tx,
+        ctx.run_id,
+        "info",
+        "planning",
+        "planning",
+        Some(planner_fields),
+    )
+    .await?;
+
+    let (on_part_finished, part_stream, parts_uploader) = if planned.plan.stream_payload_parts {
+        let (part_stream, parts_uploader) = super::prepare_archive_part_streamer(
+            &target,
+            ctx.job_id,
+            ctx.run_id,
+            artifact_format.clone(),
+        );
+        (None, part_stream, parts_uploader)
+    } else if planned.plan.allow_rolling_upload {
+        let (on_part_finished, parts_uploader) = super::prepare_archive_part_uploader(
+            &target,
+            ctx.job_id,
+            ctx.run_id,
+            artifact_format.clone(),
+            part_upload_concurrency,
+        );
+        (on_part_finished, None, parts_uploader)
+    } else {
+        (None, None, None)
+    };
+
+    let staging_root = ctx.staging.root.clone();
+    let staging_min_free_bytes = ctx.staging.min_free_bytes;
+    let job_id_clone = ctx.job_id.to_string();
+    let run_id_clone = ctx.run_id.to_string();
+    let (log_tx, mut log_rx) =
+        tokio::sync::mpsc::channel::<String>(super::run_log::LINE_CHANNEL_CAPACITY);
+    let build = tokio::task::spawn_blocking(move || {
+        backup::task_log::with_sink(Some(log_tx), || {
+            backup::sqlite_app::build_sqlite_app_run(
+                &staging_root,
+                &job_id_clone,
+                &run_id_clone,
+                started_at,
+                &source,
+                backup::BuildPipelineOptions {
+                    artifact_format,
+                    compression,
+                    encryption: &encryption,
+                    part_size_bytes: part_size,
+                    staging_min_free_bytes,
+                },
+                on_part_finished,
+                part_stream,
+            )
+        })
+    })
+    .await?;
+    let mut run_log = super::run_log::RunLogStream::new(ctx.run_id);
+    run_log.drain(&mut log_rx);
+    run_log.finish(tx).await?;
+    let build = super::finish_part_upload(build, parts_uploader)
+        .await
+        .map_err(|error| backup::staging::classify_build_error(error, &ctx.staging))?;
+    let raw_consistency = build.consistency;
+    let consistency_total = raw_consistency.total();
+    let consistency_failed =
+        consistency_policy.should_fail(consistency_total, consistency_fail_threshold);
+    let consistency = if consistency_policy == ConsistencyPolicyV1::Ignore {
+        Default::default()
+    } else {
+        raw_consistency
+    };
+    let snapshot_size = build.snapshot_size;
+    let artifacts = build.artifacts;
+
+    if let Some(check) = build.integrity_check.as_ref() {
+        super::send_run_event(
+            tx,
+            ctx.run_id,
+            if check.ok { "info" } else { "error" },
+            "integrity_check",
+            "integrity_check",
+            Some(serde_json::json!({
+                "ok": check.ok,
+                "truncated": check.truncated,
+                "lines": check.lines,
+            })),
+        )
+        .await?;
+
+        if !check.ok {
+            let _ = tokio::fs::remove_dir_all(&artifacts.run_dir).await;
+            let first = check.lines.first().cloned().unwrap_or_default();
+            anyhow::bail!("sqlite integrity_check failed: {}", first);
+        }
+    }
+
+    if consistency_policy.should_emit_warnings() && consistency_total > 0 {
+        let fields = serde_json::to_value(&consistency)?;
+        super::send_run_event(
+            tx,
+            ctx.run_id,
+            "warn",
+            "source_consistency",
+            "source consistency warnings",
+            Some(fields),
+        )
+        .await?;
+    }
+
+    let parts_bytes: u64 = artifacts.parts.iter().map(|p| p.size).sum();
+    let entries_size = std::fs::metadata(&artifacts.entries_index_path)?.len();
+    let manifest_size = std::fs::metadata(&artifacts.manifest_path)?.len();
+    let complete_size = std::fs::metadata(&artifacts.complete_path)?.len();
+    let transfer_total_bytes = parts_bytes
+        .saturating_add(entries_size)
+        .saturating_add(manifest_size)
+        .saturating_add(complete_size);
+    let mut last_upload_done_bytes: u64 = 0;
+
+    if consistency_failed && !upload_on_consistency_failure {
+        let target_summary = serde_json::json!({
+            "type": match target {
+                TargetResolvedV1::Webdav { .. } => "webdav",
+                TargetResolvedV1::LocalDir { .. } => "local_dir",
+            }
+        });
+
+        let summary = serde_json::json!({
+            "target": target_summary,
+            "artifact_format": artifact_format_for_summary,
+            "entries_count": artifacts.entries_count,
+            "parts": artifacts.parts.len(),
+            "metrics": {
+                "transfer_total_bytes": transfer_total_bytes,
+            },
+            "sqlite_app": {
+                "data_dir": app_data_dir,
+                "db_path": db_path,
+                "snapshot_size": snapshot_size,
+                "consistency": consistency,
+            },
+            "planner": planner_summary.clone(),
+        });
+
+        let _ = tokio::fs::remove_dir_all(&artifacts.run_dir).await;
+
+        return Err(anyhow::Error::new(RunFailedWithSummary::new(
+            "source_consistency",
+            format!(
+                "source changed during backup (failed by policy): total {consistency_total} > threshold {consistency_fail_threshold}"
+            ),
+            summary,
+        )));
+    }
+
+    super::send_run_event(tx, ctx.run_id, "info", "upload", "upload", None).await?;
+
+    struct UploadThrottle {
+        last_emit: Instant,
+        last_done: u64,
+        last_total: Option<u64>,
+    }
+    const UPLOAD_PROGRESS_MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+    let upload_throttle = std::sync::Arc::new(std::sync::Mutex::new(UploadThrottle {
+        last_emit: Instant::now()
+            .checked_sub(UPLOAD_PROGRESS_MIN_INTERVAL)
+            .unwrap_or_else(Instant::now),
+        last_done: 0,
+        last_total: None,
+    }));
+
+    let (progress_tx, mut progress_rx) =
+        tokio::sync::mpsc::channel::<bastion_targets::StoreRunProgress>(8);
+    let upload_cb: std::sync::Arc<dyn Fn(bastion_targets::StoreRunProgress) + Send + Sync> = {
+        let upload_throttle = upload_throttle.clone();
+        std::sync::Arc::new(move |p: bastion_targets::StoreRunProgress| {
+            let now = Instant::now();
+            let mut guard = match upload_throttle.lock() {
+                Ok(g) => g,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+
+            let total_bytes = Some(transfer_total_bytes);
+            let done_bytes = p.bytes_done;
+            let finished = total_bytes.is_some_and(|t| done_bytes >= t);
+            let should_emit =
+                finished || now.duration_since(guard.last_emit) >= UPLOAD_PROGRESS_MIN_INTERVAL;
+            if !should_emit {
+                return;
+            }
+            if done_bytes == guard.last_done && total_bytes == guard.last_total {
+                return;
+            }
+
+            guard.last_emit = now;
+            guard.last_done = done_bytes;
+            guard.last_total = total_bytes;
+
+            let _ = progress_tx.try_send(p);
+        })
+    };
+
+    let mut upload_fut = std::pin::pin!(super::store_artifacts_or_defer(
+        ctx,
+        &target,
+        &artifacts,
+        None,
+        Some(upload_cb),
+        true,
+    ));
+
+    let mut progress = UploadProgressBuilder::new();
+    let target_summary = loop {
+        tokio::select! {
+            res = &mut upload_fut => break res?,
+            maybe_update = progress_rx.recv() => {
+                if let Some(p) = maybe_update {
+                    last_upload_done_bytes = p.bytes_done;
+                    super::send_run_progress_snapshot(
+                        tx,
+                        ctx.run_id,
+                        progress.snapshot(p.bytes_done, Some(transfer_total_bytes)),
+                    )
+                    .await?;
+                }
+            }
+        }
+    };
+
+    if transfer_total_bytes > 0 && last_upload_done_bytes < transfer_total_bytes {
+        super::send_run_progress_snapshot(
+            tx,
+            ctx.run_id,
+            progress.snapshot(transfer_total_bytes, Some(transfer_total_bytes)),
+        )
+        .await?;
+    }
+    let _ = tokio::fs::remove_dir_all(&artifacts.run_dir).await;
+
+    let summary = serde_json::json!({
+        "target": target_summary,
+        "artifact_format": artifact_format_for_summary,
+        "entries_count": artifacts.entries_count,
+        "parts": artifacts.parts.len(),
+        "metrics": {
+            "transfer_total_bytes": transfer_total_bytes,
+        },
+        "sqlite_app": {
+            "data_dir": app_data_dir,
+            "db_path": db_path,
+            "snapshot_size": snapshot_size,
+            "consistency": consistency,
+        },
+        "planner": planner_summary,
+    });
+
+    if consistency_failed {
+        return Err(anyhow::Error::new(RunFailedWithSummary::new(
+            "source_consistency",
+            format!(
+                "source changed during backup (failed by policy): total {consistency_total} > threshold {consistency_fail_threshold}"
+            ),
+            summary,
+        )));
+    }
+
+    Ok(summary)
+}
@@ -7,26 +7,30 @@ use bastion_backup as backup;
 use bastion_core::agent_protocol::PipelineResolvedV1;
 use bastion_core::agent_protocol::TargetResolvedV1;
 use bastion_core::job_spec::{ConsistencyPolicyV1, VaultwardenSource};
-use bastion_core::progress::{ProgressKindV1, ProgressSnapshotV1, ProgressUnitsV1};
+use bastion_core::progress::{
+    ProgressKindV1, ProgressRateEstimator, ProgressSnapshotV1, ProgressUnitsV1,
+};
 use bastion_core::run_failure::RunFailedWithSummary;
 
-use super::super::targets::{store_artifacts_to_resolved_target, target_part_size_bytes};
+use super::super::targets::target_part_size_bytes;
 use super::planner::plan_vaultwarden_execution;
 
-struct UploadProgressBuilder {
-    last_ts: Option<i64>,
-    last_done_bytes: u64,
+pub(super) struct UploadProgressBuilder {
+    rate: ProgressRateEstimator,
 }
 
 impl UploadProgressBuilder {
-    fn new() -> Self {
+    pub(super) fn new() -> Self {
         Self {
-            last_ts: None,
-            last_done_bytes: 0,
+            rate: ProgressRateEstimator::new(),
         }
     }
 
-    fn snapshot(&mut self, done_bytes: u64, total_bytes: Option<u64>) -> ProgressSnapshotV1 {
+    pub(super) fn snapshot(
+        &mut self,
+        done_bytes: u64,
+        total_bytes: Option<u64>,
+    ) -> ProgressSnapshotV1 {
         self.snapshot_at(
             time::OffsetDateTime::now_utc().unix_timestamp(),
             done_bytes,
@@ -40,26 +44,7 @@ impl UploadProgressBuilder {
         done_bytes: u64,
         total_bytes: Option<u64>,
     ) -> ProgressSnapshotV1 {
-        let dt = self
-            .last_ts
-            .map(|ts| now_ts.saturating_sub(ts))
-            .unwrap_or(0);
-        let delta = done_bytes.saturating_sub(self.last_done_bytes);
-        let rate = if dt > 0 && delta > 0 {
-            Some(delta.saturating_div(dt as u64).max(1))
-        } else {
-            None
-        };
-
-        let eta = match (rate, total_bytes) {
-            (Some(rate), Some(total)) if rate > 0 && total > done_bytes => {
-                Some(total.saturating_sub(done_bytes).saturating_div(rate))
-            }
-            _ => None,
-        };
-
-        self.last_ts = Some(now_ts);
-        self.last_done_bytes = done_bytes;
+        let (rate, eta) = self.rate.observe("upload", now_ts, done_bytes, total_bytes);
 
         ProgressSnapshotV1 {
             v: 1,
@@ -90,6 +75,9 @@ pub(super) async fn run_vaultwarden_backup(
     source: VaultwardenSource,
     target: TargetResolvedV1,
 ) -> Result<serde_json::Value, anyhow::Error> {
+    ctx.staging
+        .check_free_space()
+        .map_err(|error| backup::staging::classify_build_error(error.into(), &ctx.staging))?;
     super::send_run_event(tx, ctx.run_id, "info", "snapshot", "snapshot", None).await?;
     let vw_data_dir = source.data_dir.clone();
     let consistency_policy = source.consistency_policy;
@@ -98,6 +86,8 @@ pub(super) async fn run_vaultwarden_backup(
     let part_size = target_part_size_bytes(&target);
     let encryption = super::payload_encryption(pipeline.encryption.clone());
     let artifact_format = pipeline.format.clone();
+    let compression = pipeline.compression;
+    let part_upload_concurrency = pipeline.webdav.effective_part_upload_concurrency();
     let artifact_format_for_summary = artifact_format.clone();
     let started_at = ctx.started_at;
 
@@ -119,36 +109,60 @@ pub(super) async fn run_vaultwarden_backup(
     )
     .await?;
 
-    let (on_part_finished, parts_uploader) = if planned.plan.allow_rolling_upload {
-        super::prepare_archive_part_uploader(
+    let (on_part_finished, part_stream, parts_uploader) = if planned.plan.stream_payload_parts {
+        let (part_stream, parts_uploader) = super::prepare_archive_part_streamer(
             &target,
             ctx.job_id,
             ctx.run_id,
             artifact_format.clone(),
-        )
+        );
+        (None, part_stream, parts_uploader)
+    } else if planned.plan.allow_rolling_upload {
+        let (on_part_finished, parts_uploader) = super::prepare_archive_part_uploader(
+            &target,
+            ctx.job_id,
+            ctx.run_id,
+            artifact_format.clone(),
+            part_upload_concurrency,
+        );
+        (on_part_finished, None, parts_uploader)
     } else {
-        (None, None)
+        (None, None, None)
     };
 
-    let data_dir_buf = ctx.data_dir.to_path_buf();
+    let staging_root = ctx.staging.root.clone();
+    let staging_min_free_bytes = ctx.staging.min_free_bytes;
     let job_id_clone = ctx.job_id.to_string();
     let run_id_clone = ctx.run_id.to_string();
+    let (log_tx, mut log_rx) =
+        tokio::sync::mpsc::channel::<String>(super::run_log::LINE_CHANNEL_CAPACITY);
     let build = tokio::task::spawn_blocking(move || {
-        backup::vaultwarden::build_vaultwarden_run(
-            &data_dir_buf,
-            &job_id_clone,
-            &run_id_clone,
-            started_at,
-            &source,
-            backup::BuildPipelineOptions {
-                artifact_format,
-                encryption: &encryption,
-                part_size_bytes: part_size,
-            },
-            on_part_finished,
-        )
+        backup::task_log::with_sink(Some(log_tx), || {
+            backup::vaultwarden::build_vaultwarden_run(
+                &staging_root,
+                &job_id_clone,
+                &run_id_clone,
+                started_at,
+                &source,
+                backup::BuildPipelineOptions {
+                    artifact_format,
+                    compression,
+                    encryption: &encryption,
+                    part_size_bytes: part_size,
+                    staging_min_free_bytes,
+                },
+                on_part_finished,
+                part_stream,
+            )
+        })
     })
-    .await??;
+    .await?;
+    let mut run_log = super::run_log::RunLogStream::new(ctx.run_id);
+    run_log.drain(&mut log_rx);
+    run_log.finish(tx).await?;
+    let build = super::finish_part_upload(build, parts_uploader)
+        .await
+        .map_err(|error| backup::staging::classify_build_error(error, &ctx.staging))?;
     let raw_consistency = build.consistency;
     let consistency_total = raw_consistency.total();
     let consistency_failed =
@@ -158,10 +172,19 @@ pub(super) async fn run_vaultwarden_backup(
     } else {
         raw_consistency
     };
+    let integrity = build.integrity;
     let artifacts = build.artifacts;
 
-    if let Some(handle) = parts_uploader {
-        handle.await??;
+    if integrity.total() > 0 {
+        super::send_run_event(
+            tx,
+            ctx.run_id,
+            "warn",
+            "vaultwarden_integrity",
+            "vaultwarden attachment/send files do not match the database",
+            Some(serde_json::to_value(&integrity)?),
+        )
+        .await?;
     }
 
     if consistency_policy.should_emit_warnings() && consistency_total > 0 {
@@ -207,6 +230,7 @@ pub(super) async fn run_vaultwarden_backup(
                 "data_dir": vw_data_dir,
                 "db": "db.sqlite3",
                 "consistency": consistency,
+                "integrity": integrity,
             },
             "planner": planner_summary.clone(),
         });
@@ -270,13 +294,13 @@ pub(super) async fn run_vaultwarden_backup(
         })
     };
 
-    let mut upload_fut = std::pin::pin!(store_artifacts_to_resolved_target(
-        ctx.job_id,
-        ctx.run_id,
+    let mut upload_fut = std::pin::pin!(super::store_artifacts_or_defer(
+        ctx,
         &target,
         &artifacts,
         None,
         Some(upload_cb),
+        true,
     ));
 
     let mut progress = UploadProgressBuilder::new();
@@ -319,6 +343,7 @@ pub(super) async fn run_vaultwarden_backup(
             "data_dir": vw_data_dir,
             "db": "db.sqlite3",
             "consistency": consistency,
+            "integrity": integrity,
         },
         "planner": planner_summary,
     });
@@ -357,9 +382,10 @@ mod tests {
         assert_eq!(s1.rate_bps, Some(5));
         assert_eq!(s1.eta_seconds, Some(10));
 
+        // A stall lowers the rolling average instead of dropping the estimate.
         let s2 = builder.snapshot_at(1020, 50, Some(100));
-        assert_eq!(s2.rate_bps, None);
-        assert_eq!(s2.eta_seconds, None);
+        assert_eq!(s2.rate_bps, Some(2));
+        assert_eq!(s2.eta_seconds, Some(25));
     }
 
     #[test]
@@ -0,0 +1,82 @@
+use std::path::Path;
+
+use time::OffsetDateTime;
+
+use bastion_storage::auth;
+
+use crate::config::ApiTokenCommand;
+
+pub async fn run(command: ApiTokenCommand, data_dir: &Path) -> Result<(), anyhow::Error> {
+    let db = bastion_storage::db::init(data_dir).await?;
+
+    let username = match &command {
+        ApiTokenCommand::Create(args) => &args.user,
+        ApiTokenCommand::List(args) => &args.user,
+        ApiTokenCommand::Revoke(args) => &args.user,
+    };
+    let user = auth::find_user_by_username(&db, username.trim())
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("user not found: {username}"))?;
+
+    match command {
+        ApiTokenCommand::Create(args) => {
+            let name = args.name.trim();
+            if name.is_empty() {
+                anyhow::bail!("--name must not be empty");
+            }
+            let expires_at = args.expires_in_days.map(|days| {
+                (OffsetDateTime::now_utc() + time::Duration::days(i64::from(days))).unix_timestamp()
+            });
+            let created = auth::create_api_token(&db, user.id, name, expires_at).await?;
+            eprintln!(
+                "created API token {} ({}); it is shown only once:",
+                created.row.id, created.row.name
+            );
+            println!("{}", created.token);
+        }
+        ApiTokenCommand::List(args) => {
+            let tokens = auth::list_api_tokens(&db, user.id).await?;
+            if args.json {
+                let items = tokens
+                    .iter()
+                    .map(|t| {
+                        serde_json::json!({
+                            "id": t.id,
+                            "name": t.name,
+                            "created_at": t.created_at,
+                            "expires_at": t.expires_at,
+                            "last_used_at": t.last_used_at,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                println!("{}", serde_json::to_string_pretty(&items)?);
+                return Ok(());
+            }
+            if tokens.is_empty() {
+                println!("no API tokens");
+            }
+            for t in tokens {
+                println!(
+                    "{}  {}  created={}  expires={}  last_used={}",
+                    t.id,
+                    t.name,
+                    t.created_at,
+                    t.expires_at
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|| "never".to_string()),
+                    t.last_used_at
+                        .map(|v| v.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                );
+            }
+        }
+        ApiTokenCommand::Revoke(args) => {
+            if !auth::delete_api_token(&db, user.id, args.id.trim()).await? {
+                anyhow::bail!("API token not found: {}", args.id);
+            }
+            println!("revoked API token {}", args.id);
+        }
+    }
+
+    Ok(())
+}
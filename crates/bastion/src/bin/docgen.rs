@@ -243,7 +243,7 @@ fn render_config_reference(
         i18n::cli::CliLocale::EnUs => {
             out.push_str("## Resolution precedence\n\n");
             out.push_str(
-                "For Hub runtime settings, effective values resolve as: CLI flags → environment variables → saved runtime config (DB, for editable policy fields only) → defaults. `RUST_LOG` is considered after `--log` / `BASTION_LOG` and before DB/default fallbacks.\n\n",
+                "For Hub runtime settings, effective values resolve as: CLI flags → environment variables → config file (`--config`) → saved runtime config (DB, for editable policy fields only) → defaults. `RUST_LOG` is considered after `--log` / `BASTION_LOG` and before the config file and DB/default fallbacks.\n\n",
             );
             out.push_str("## CLI-backed configuration environment variables\n\n");
             out.push_str(
@@ -255,7 +255,7 @@ fn render_config_reference(
         i18n::cli::CliLocale::ZhCn => {
             out.push_str("## 生效优先级\n\n");
             out.push_str(
-                "对于 Hub 运行时配置，最终生效值的优先级为：CLI 参数 → 环境变量 → 已保存的运行配置（数据库，仅限可编辑策略字段）→ 默认值。`RUST_LOG` 会在 `--log` / `BASTION_LOG` 之后、数据库/默认值之前参与回退。\n\n",
+                "对于 Hub 运行时配置，最终生效值的优先级为：CLI 参数 → 环境变量 → 配置文件（`--config`）→ 已保存的运行配置（数据库，仅限可编辑策略字段）→ 默认值。`RUST_LOG` 会在 `--log` / `BASTION_LOG` 之后、配置文件及数据库/默认值之前参与回退。\n\n",
             );
             out.push_str("## 由 CLI 定义驱动的配置环境变量\n\n");
             out.push_str(
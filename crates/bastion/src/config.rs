@@ -1,5 +1,5 @@
 use std::{
-    net::{IpAddr, Ipv4Addr, SocketAddr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
     path::PathBuf,
 };
 
@@ -19,6 +19,12 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Command>,
 
+    /// TOML config file with `[hub]`, `[logging]` and `[agent]` sections.
+    ///
+    /// Keys are snake_case option names (e.g. `log_file`); list options take arrays under plural keys (`trusted_proxies`, `acme_domains`). CLI flags and environment variables override the file; the file overrides values saved in the Web UI. On SIGHUP the Hub re-reads the log filter and trusted proxies from it.
+    #[arg(long = "config", env = "BASTION_CONFIG", value_name = "FILE")]
+    pub config_file: Option<PathBuf>,
+
     #[command(flatten)]
     pub logging: LoggingArgs,
 
@@ -46,6 +52,34 @@ pub enum Command {
         #[command(subcommand)]
         command: KeypackCommand,
     },
+    /// Rebuild jobs and runs from backups already stored on a target.
+    Recover(RecoverArgs),
+    /// Export snapshots to portable archives and import them back.
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommand,
+    },
+    /// Inspect or promote a warm standby Hub (see `--standby-of`).
+    Standby {
+        #[command(subcommand)]
+        command: StandbyCommand,
+    },
+    /// Manage API tokens in the Hub data directory.
+    ApiToken {
+        #[command(subcommand)]
+        command: ApiTokenCommand,
+    },
+    /// Manage users in the Hub data directory.
+    User {
+        #[command(subcommand)]
+        command: UserCommand,
+    },
+    /// Manage jobs on a running Hub using an API token.
+    Job(JobArgs),
+    /// Restore a run on a running Hub and follow its progress.
+    Restore(RestoreArgs),
+    /// Restore a snapshot from a local run directory without a Hub or database.
+    RestoreOffline(RestoreOfflineArgs),
 }
 
 #[cfg(windows)]
@@ -83,6 +117,47 @@ pub struct ConfigArgs {
     /// Output JSON (useful for scripts/CI).
     #[arg(long)]
     pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Option<ConfigCommand>,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum ConfigCommand {
+    /// Export jobs, notification settings and (with a password) secrets to a bundle file.
+    Export(ConfigExportArgs),
+    /// Import a bundle written by `bastion config export` into this Hub.
+    Import(ConfigImportArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ConfigExportArgs {
+    /// Output path for the config bundle.
+    #[arg(long)]
+    pub out: PathBuf,
+
+    /// Password that seals secrets into the bundle; without one, secrets are left out.
+    #[arg(long)]
+    pub password: Option<String>,
+
+    /// Read the bundle password from stdin (trailing newline is trimmed).
+    #[arg(long)]
+    pub password_stdin: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ConfigImportArgs {
+    /// Input path of the config bundle.
+    #[arg(long)]
+    pub r#in: PathBuf,
+
+    /// Password the bundle was exported with (required when it contains secrets).
+    #[arg(long)]
+    pub password: Option<String>,
+
+    /// Read the bundle password from stdin (trailing newline is trimmed).
+    #[arg(long)]
+    pub password_stdin: bool,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -124,6 +199,16 @@ pub struct HubArgs {
     #[arg(long, default_value_t = 7, env = "BASTION_INCOMPLETE_CLEANUP_DAYS")]
     pub incomplete_cleanup_days: i64,
 
+    /// Directory for run staging (default: the data directory).
+    ///
+    /// Point this at a separate disk to keep large runs off the data disk.
+    #[arg(long, env = "BASTION_STAGING_DIR")]
+    pub staging_dir: Option<PathBuf>,
+
+    /// Free space (MiB) the staging disk must keep; runs fail with `staging_disk_full` below it (default: 512, 0 disables).
+    #[arg(long, default_value_t = 512, env = "BASTION_STAGING_MIN_FREE_MB")]
+    pub staging_min_free_mb: u64,
+
     /// Hub timezone (IANA), used as the default schedule timezone (default: local system timezone).
     ///
     /// Examples: `UTC`, `Asia/Shanghai`, `America/Los_Angeles`.
@@ -141,6 +226,99 @@ pub struct HubArgs {
     /// Can be specified multiple times: `--trusted-proxy 127.0.0.1/32 --trusted-proxy ::1/128`.
     #[arg(long = "trusted-proxy", env = "BASTION_TRUSTED_PROXIES", value_delimiter = ',', num_args = 0..)]
     pub trusted_proxies: Vec<IpNet>,
+
+    /// `/api` requests per minute allowed from one client IP before answering 429 (default: 1200, 0 disables).
+    #[arg(long, default_value_t = 1200, env = "BASTION_API_RATE_LIMIT_PER_IP")]
+    pub api_rate_limit_per_ip: u32,
+
+    /// `/api` requests per minute allowed for one session or API token before answering 429 (default: 600, 0 disables).
+    #[arg(
+        long,
+        default_value_t = 600,
+        env = "BASTION_API_RATE_LIMIT_PER_SESSION"
+    )]
+    pub api_rate_limit_per_session: u32,
+
+    /// TLS certificate chain (PEM) to serve HTTPS directly, without a reverse proxy.
+    ///
+    /// Requires `--tls-key`. Both files are re-read when they change, so renewals don't need a restart.
+    #[arg(
+        long,
+        env = "BASTION_TLS_CERT",
+        requires = "tls_key",
+        conflicts_with = "acme_domains"
+    )]
+    pub tls_cert: Option<PathBuf>,
+
+    /// TLS private key (PEM) for `--tls-cert`.
+    #[arg(long, env = "BASTION_TLS_KEY", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Serve HTTPS with certificates obtained from Let's Encrypt (ACME TLS-ALPN-01) for these domains.
+    ///
+    /// The Hub must be reachable on port 443 under each domain. Certificates and the ACME account are cached under `<data dir>/acme`.
+    #[arg(long = "acme-domain", env = "BASTION_ACME_DOMAINS", value_delimiter = ',', num_args = 0..)]
+    pub acme_domains: Vec<String>,
+
+    /// Contact email for the ACME account (optional).
+    #[arg(long, env = "BASTION_ACME_EMAIL")]
+    pub acme_email: Option<String>,
+
+    /// Use the Let's Encrypt staging directory (testing only; its certificates are not trusted by browsers).
+    #[arg(long, env = "BASTION_ACME_STAGING")]
+    pub acme_staging: bool,
+
+    /// Proxy UI requests to a Vite dev server instead of serving `ui/dist` (frontend development only).
+    ///
+    /// Everything outside `/api`, `/agent` and `/docs` is forwarded, including the HMR WebSocket. Example: `http://127.0.0.1:5173`.
+    #[arg(long, env = "BASTION_UI_DEV_PROXY")]
+    pub ui_dev_proxy: Option<String>,
+
+    /// Run as a warm standby of the Hub at this URL instead of serving.
+    ///
+    /// The standby pulls a copy of the primary's database into `<data dir>/standby` and answers everything except `/api/health` and `/api/standby` with 503. Take over with `bastion standby promote`.
+    #[arg(long, env = "BASTION_STANDBY_OF", requires = "standby_token")]
+    pub standby_of: Option<Url>,
+
+    /// API token of a primary Hub user, used by `--standby-of` to pull database copies.
+    #[arg(long, env = "BASTION_STANDBY_TOKEN", hide_env_values = true)]
+    pub standby_token: Option<String>,
+
+    /// How often (seconds) a standby pulls a database copy from the primary (default: 60).
+    #[arg(long, default_value_t = 60, env = "BASTION_STANDBY_INTERVAL_SECS")]
+    pub standby_interval_secs: u64,
+
+    /// On SIGTERM/Ctrl-C, how long (seconds) to wait for in-flight runs before interrupting and requeueing them (default: 60).
+    ///
+    /// New runs are not started while draining. Keep the service manager's stop timeout above this value.
+    #[arg(
+        long,
+        default_value_t = 60,
+        env = "BASTION_SHUTDOWN_DRAIN_TIMEOUT_SECS"
+    )]
+    pub shutdown_drain_timeout_secs: u64,
+}
+
+/// Where a warm standby follows its primary from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HubStandbyConfig {
+    pub primary_url: Url,
+    pub token: String,
+    pub interval: std::time::Duration,
+}
+
+/// How the Hub terminates TLS itself (instead of behind a reverse proxy).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HubTlsConfig {
+    Files {
+        cert: PathBuf,
+        key: PathBuf,
+    },
+    Acme {
+        domains: Vec<String>,
+        email: Option<String>,
+        staging: bool,
+    },
 }
 
 #[derive(Debug, Args, Clone)]
@@ -179,6 +357,13 @@ pub struct AgentArgs {
     #[arg(long, env = "BASTION_HUB_URL")]
     pub hub_url: String,
 
+    /// Hub URLs to fail over to, in order, when connecting to the current one fails.
+    ///
+    /// Can be specified multiple times. The list is stored with the agent's enrollment, so a
+    /// migrated or standby Hub can take over without re-enrolling.
+    #[arg(long = "fallback-hub-url", env = "BASTION_AGENT_FALLBACK_HUB_URLS", value_delimiter = ',', num_args = 0..)]
+    pub fallback_hub_urls: Vec<String>,
+
     /// Enrollment token (only required for first-time enrollment).
     #[arg(long, env = "BASTION_AGENT_ENROLL_TOKEN")]
     pub enroll_token: Option<String>,
@@ -194,6 +379,26 @@ pub struct AgentArgs {
     /// Heartbeat interval in seconds (default: 15).
     #[arg(long, default_value_t = 15, env = "BASTION_AGENT_HEARTBEAT_SECONDS")]
     pub heartbeat_seconds: u64,
+
+    /// How many runs (backups, restores, snapshot deletes) may execute at once; runs of the same job never overlap (default: 1).
+    #[arg(long, default_value_t = 1, env = "BASTION_AGENT_MAX_PARALLEL_RUNS")]
+    pub max_parallel_runs: u32,
+
+    /// Directory for run staging (default: the data directory).
+    #[arg(long, env = "BASTION_STAGING_DIR")]
+    pub staging_dir: Option<PathBuf>,
+
+    /// Free space (MiB) the staging disk must keep; runs fail with `staging_disk_full` below it (default: 512, 0 disables).
+    #[arg(long, default_value_t = 512, env = "BASTION_STAGING_MIN_FREE_MB")]
+    pub staging_min_free_mb: u64,
+
+    /// Base64 Ed25519 public key for verifying agent updates hosted by the Hub (self-update stays off when unset).
+    #[arg(long, env = "BASTION_AGENT_UPDATE_PUBLIC_KEY")]
+    pub update_public_key: Option<String>,
+
+    /// Accept Hub update offers older than the running build (needed for `pinned` rollbacks; refused by default).
+    #[arg(long, env = "BASTION_AGENT_UPDATE_ALLOW_DOWNGRADE")]
+    pub update_allow_downgrade: bool,
 }
 
 #[derive(Debug, Subcommand)]
@@ -204,6 +409,8 @@ pub enum KeypackCommand {
     Import(KeypackImportArgs),
     /// Rotate the Hub master key (master.key).
     Rotate(KeypackRotateArgs),
+    /// Write the age identities of a backup encryption key to a file (for offline restores).
+    Identity(KeypackIdentityArgs),
 }
 
 #[derive(Debug, Args, Clone)]
@@ -221,6 +428,17 @@ pub struct KeypackExportArgs {
     pub password_stdin: bool,
 }
 
+#[derive(Debug, Args, Clone)]
+pub struct UserSetAdminArgs {
+    /// Username to change.
+    #[arg(long)]
+    pub user: String,
+
+    /// Revoke admin rights instead of granting them.
+    #[arg(long)]
+    pub revoke: bool,
+}
+
 #[derive(Debug, Args, Clone)]
 pub struct KeypackImportArgs {
     /// Input path of the password-encrypted keypack.
@@ -243,7 +461,434 @@ pub struct KeypackImportArgs {
 #[derive(Debug, Args, Clone)]
 pub struct KeypackRotateArgs {}
 
+#[derive(Debug, Args, Clone)]
+pub struct KeypackIdentityArgs {
+    /// Backup encryption key name (as shown in the job's encryption settings).
+    #[arg(long, value_name = "NAME")]
+    pub key: String,
+
+    /// Output path for the identity file (written with owner-only permissions).
+    #[arg(long)]
+    pub out: PathBuf,
+
+    /// Passphrase for a passphrase-protected backup key (prefer --passphrase-stdin).
+    #[arg(long, env = "BASTION_BACKUP_PASSPHRASE", hide_env_values = true)]
+    pub passphrase: Option<String>,
+
+    /// Read the backup key passphrase from stdin (trailing newline is trimmed).
+    #[arg(long)]
+    pub passphrase_stdin: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct RecoverArgs {
+    /// Target to scan: a local directory (path or `file://` URL) or a WebDAV base URL.
+    #[arg(long)]
+    pub target: String,
+
+    /// Hub WebDAV secret holding the credentials for a WebDAV target.
+    #[arg(long, value_name = "NAME")]
+    pub secret: Option<String>,
+
+    /// Only report what would be imported; do not write to the database.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Output JSON (useful for scripts/CI).
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum SnapshotCommand {
+    /// Package a complete snapshot into a single portable archive.
+    Export(SnapshotExportArgs),
+    /// Unpack a snapshot archive into a local directory target.
+    Import(SnapshotImportArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct SnapshotExportArgs {
+    /// Target holding the snapshot: a local directory (path or `file://` URL) or a WebDAV base URL.
+    #[arg(long)]
+    pub target: String,
+
+    /// Hub WebDAV secret holding the credentials for a WebDAV target.
+    #[arg(long, value_name = "NAME")]
+    pub secret: Option<String>,
+
+    /// Run id of the snapshot.
+    #[arg(long)]
+    pub run_id: String,
+
+    /// Job id of the snapshot (default: looked up in the Hub database).
+    #[arg(long)]
+    pub job_id: Option<String>,
+
+    /// Output path of the archive.
+    #[arg(long)]
+    pub out: PathBuf,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct SnapshotImportArgs {
+    /// Input path of the archive.
+    #[arg(long)]
+    pub r#in: PathBuf,
+
+    /// Local directory target to unpack into (created if missing).
+    #[arg(long)]
+    pub target: PathBuf,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum StandbyCommand {
+    /// Show when the standby last synced from its primary.
+    Status(StandbyStatusArgs),
+    /// Make the standby copy this Hub's database; stop the standby first, then start the Hub without `--standby-of`.
+    Promote(StandbyPromoteArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct StandbyStatusArgs {
+    /// Output JSON (useful for scripts/CI).
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct StandbyPromoteArgs {
+    /// Promote even if `master.key` is missing or doesn't match the primary's (secrets won't decrypt).
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum ApiTokenCommand {
+    /// Create an API token for a user and print it once.
+    Create(ApiTokenCreateArgs),
+    /// List a user's API tokens.
+    List(ApiTokenListArgs),
+    /// Revoke an API token.
+    Revoke(ApiTokenRevokeArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ApiTokenCreateArgs {
+    /// Username the token acts as.
+    #[arg(long)]
+    pub user: String,
+
+    /// Token name (shown when listing tokens).
+    #[arg(long)]
+    pub name: String,
+
+    /// Expire the token after N days (default: never).
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub expires_in_days: Option<u32>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ApiTokenListArgs {
+    /// Username whose tokens to list.
+    #[arg(long)]
+    pub user: String,
+
+    /// Output JSON (useful for scripts/CI).
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct ApiTokenRevokeArgs {
+    /// Username owning the token.
+    #[arg(long)]
+    pub user: String,
+
+    /// Token id (from `api-token list`).
+    #[arg(long)]
+    pub id: String,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum UserCommand {
+    /// Set a new password for a user, sign out their browser sessions and lift any login lock.
+    ResetPassword(UserResetPasswordArgs),
+    /// Grant or revoke admin rights (admins see every job and can approve restores).
+    SetAdmin(UserSetAdminArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct UserResetPasswordArgs {
+    /// Username whose password to reset.
+    #[arg(long)]
+    pub user: String,
+
+    /// Read the new password from stdin instead of printing a generated one (trailing newline is trimmed).
+    #[arg(long)]
+    pub password_stdin: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct HubApiArgs {
+    /// Hub base URL (default: http://127.0.0.1:9876).
+    #[arg(
+        long,
+        global = true,
+        default_value = "http://127.0.0.1:9876",
+        env = "BASTION_HUB_URL"
+    )]
+    pub hub_url: Url,
+
+    /// API token (create one with `bastion api-token create`).
+    #[arg(long, global = true, env = "BASTION_API_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct JobArgs {
+    #[command(flatten)]
+    pub api: HubApiArgs,
+
+    #[command(subcommand)]
+    pub command: JobCommand,
+}
+
+#[derive(Debug, Subcommand, Clone)]
+pub enum JobCommand {
+    /// List jobs.
+    List(JobListArgs),
+    /// Create a job from a JSON job spec.
+    Create(JobCreateArgs),
+    /// Trigger a run of a job.
+    Run(JobRunArgs),
+    /// Delete a job and its run history.
+    Delete(JobDeleteArgs),
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct JobListArgs {
+    /// Include archived jobs.
+    #[arg(long)]
+    pub include_archived: bool,
+
+    /// Output JSON (useful for scripts/CI).
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct JobCreateArgs {
+    /// Job name.
+    #[arg(long)]
+    pub name: String,
+
+    /// Job spec JSON file (`-` reads stdin).
+    #[arg(long, value_name = "FILE")]
+    pub spec: PathBuf,
+
+    /// Run on this Agent instead of the Hub.
+    #[arg(long)]
+    pub agent_id: Option<String>,
+
+    /// Cron schedule (unscheduled when omitted).
+    #[arg(long)]
+    pub schedule: Option<String>,
+
+    /// Schedule timezone (IANA; default: Hub timezone).
+    #[arg(long)]
+    pub schedule_timezone: Option<String>,
+
+    /// What to do when a run is triggered while another is active.
+    #[arg(long, value_enum, default_value_t = OverlapPolicyArg::Queue)]
+    pub overlap_policy: OverlapPolicyArg,
+
+    /// Output JSON (useful for scripts/CI).
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OverlapPolicyArg {
+    Queue,
+    Reject,
+}
+
+impl OverlapPolicyArg {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queue => "queue",
+            Self::Reject => "reject",
+        }
+    }
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct JobRunArgs {
+    /// Job id.
+    pub job_id: String,
+
+    /// Output JSON (useful for scripts/CI).
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct JobDeleteArgs {
+    /// Job id.
+    pub job_id: String,
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct RestoreArgs {
+    #[command(flatten)]
+    pub api: HubApiArgs,
+
+    /// Run id to restore.
+    #[arg(long = "run", value_name = "RUN_ID")]
+    pub run_id: String,
+
+    /// Destination directory on the restoring node.
+    #[arg(long, value_name = "DIR")]
+    pub dest: String,
+
+    /// Node that writes the files (`hub` or an Agent id).
+    #[arg(long, value_name = "NODE_ID", default_value = "hub")]
+    pub node: String,
+
+    /// Restore only this file or directory (repeatable; default: everything).
+    #[arg(long = "select", value_name = "PATH")]
+    pub select: Vec<String>,
+
+    /// What to do when a destination file already exists.
+    #[arg(long, value_enum, default_value_t = ConflictPolicyArg::Overwrite)]
+    pub conflict: ConflictPolicyArg,
+
+    /// Passphrase for a passphrase-protected backup key (prefer --passphrase-stdin).
+    #[arg(long, env = "BASTION_BACKUP_PASSPHRASE", hide_env_values = true)]
+    pub passphrase: Option<String>,
+
+    /// Read the backup key passphrase from stdin (trailing newline is trimmed).
+    #[arg(long)]
+    pub passphrase_stdin: bool,
+
+    /// Print the operation id and exit without waiting for completion.
+    #[arg(long)]
+    pub no_wait: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ConflictPolicyArg {
+    Overwrite,
+    Skip,
+    Fail,
+}
+
+impl ConflictPolicyArg {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Overwrite => "overwrite",
+            Self::Skip => "skip",
+            Self::Fail => "fail",
+        }
+    }
+}
+
+#[derive(Debug, Args, Clone)]
+pub struct RestoreOfflineArgs {
+    /// Run directory holding `manifest.json` and the payload (`<target>/<job_id>/<run_id>`).
+    #[arg(long, value_name = "DIR")]
+    pub run_dir: PathBuf,
+
+    /// Destination directory for the restored files.
+    #[arg(long, value_name = "DIR")]
+    pub dest: PathBuf,
+
+    /// age identity file for encrypted snapshots (`-` reads stdin).
+    #[arg(long, value_name = "FILE")]
+    pub identity: Option<PathBuf>,
+
+    /// Restore only this file or directory (repeatable; default: everything).
+    #[arg(long = "select", value_name = "PATH")]
+    pub select: Vec<String>,
+
+    /// What to do when a destination file already exists.
+    #[arg(long, value_enum, default_value_t = ConflictPolicyArg::Fail)]
+    pub conflict: ConflictPolicyArg,
+}
+
 impl HubArgs {
+    pub fn tls_config(&self) -> Result<Option<HubTlsConfig>, anyhow::Error> {
+        if let (Some(cert), Some(key)) = (&self.tls_cert, &self.tls_key) {
+            return Ok(Some(HubTlsConfig::Files {
+                cert: cert.clone(),
+                key: key.clone(),
+            }));
+        }
+        if self.tls_cert.is_some() || self.tls_key.is_some() {
+            anyhow::bail!("tls_cert and tls_key must be set together");
+        }
+
+        let mut domains = Vec::new();
+        for domain in &self.acme_domains {
+            let domain = domain.trim().trim_end_matches('.').to_ascii_lowercase();
+            if domain.is_empty() || domain.contains(['/', ':', ' ']) {
+                anyhow::bail!("invalid acme_domain: {domain:?}");
+            }
+            if !domains.contains(&domain) {
+                domains.push(domain);
+            }
+        }
+        if domains.is_empty() {
+            if self.acme_email.is_some() || self.acme_staging {
+                anyhow::bail!("acme_email/acme_staging require at least one acme_domain");
+            }
+            return Ok(None);
+        }
+
+        let email = self
+            .acme_email
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string);
+        if let Some(email) = email.as_deref()
+            && !email.contains('@')
+        {
+            anyhow::bail!("invalid acme_email: {email:?}");
+        }
+
+        Ok(Some(HubTlsConfig::Acme {
+            domains,
+            email,
+            staging: self.acme_staging,
+        }))
+    }
+
+    pub fn standby_config(&self) -> Result<Option<HubStandbyConfig>, anyhow::Error> {
+        let Some(primary_url) = self.standby_of.clone() else {
+            return Ok(None);
+        };
+        if !matches!(primary_url.scheme(), "http" | "https") || primary_url.host().is_none() {
+            anyhow::bail!("standby_of must be an http(s) URL with a host");
+        }
+        let token = self
+            .standby_token
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("standby_of requires standby_token"))?
+            .to_string();
+        if self.standby_interval_secs == 0 {
+            anyhow::bail!("standby_interval_secs must be > 0");
+        }
+        Ok(Some(HubStandbyConfig {
+            primary_url,
+            token,
+            interval: std::time::Duration::from_secs(self.standby_interval_secs),
+        }))
+    }
+
     pub fn into_config(self) -> Result<Config, anyhow::Error> {
         let data_dir = bastion_config::data_dir::resolve_data_dir(self.data_dir)?;
 
@@ -282,10 +927,14 @@ impl HubArgs {
             validate_public_base_url(public_base_url)?;
         }
 
+        let ui_dev_proxy = match self.ui_dev_proxy.as_deref().map(str::trim) {
+            Some(value) if !value.is_empty() => Some(validate_ui_dev_proxy(value)?),
+            _ => None,
+        };
+
         let mut trusted_proxies = self.trusted_proxies;
         if trusted_proxies.is_empty() {
-            trusted_proxies.push("127.0.0.1/32".parse()?);
-            trusted_proxies.push("::1/128".parse()?);
+            trusted_proxies = default_trusted_proxies();
         }
 
         Ok(Config {
@@ -296,11 +945,24 @@ impl HubArgs {
             hub_timezone,
             run_retention_days: self.run_retention_days,
             incomplete_cleanup_days: self.incomplete_cleanup_days,
+            staging_dir: self.staging_dir,
+            staging_min_free_bytes: self.staging_min_free_mb.saturating_mul(1024 * 1024),
+            ui_dev_proxy,
+            api_rate_limit_per_ip: self.api_rate_limit_per_ip,
+            api_rate_limit_per_session: self.api_rate_limit_per_session,
             trusted_proxies,
         })
     }
 }
 
+/// Proxies trusted when none are configured: loopback only.
+pub fn default_trusted_proxies() -> Vec<IpNet> {
+    vec![
+        IpNet::from(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        IpNet::from(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+    ]
+}
+
 fn validate_public_base_url(value: &str) -> Result<(), anyhow::Error> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
@@ -320,6 +982,22 @@ fn validate_public_base_url(value: &str) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+/// Returns the dev server origin without a trailing slash.
+fn validate_ui_dev_proxy(value: &str) -> Result<String, anyhow::Error> {
+    let parsed = Url::parse(value).map_err(|_| anyhow::anyhow!("invalid ui_dev_proxy"))?;
+    match parsed.scheme() {
+        "http" | "https" => {}
+        _ => anyhow::bail!("ui_dev_proxy must use http or https"),
+    }
+    if parsed.host().is_none() {
+        anyhow::bail!("ui_dev_proxy must include a host");
+    }
+    if parsed.path() != "/" || parsed.query().is_some() || parsed.fragment().is_some() {
+        anyhow::bail!("ui_dev_proxy must be an origin without path, query or fragment");
+    }
+    Ok(value.trim_end_matches('/').to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -333,12 +1011,102 @@ mod tests {
             debug_errors: false,
             run_retention_days: 180,
             incomplete_cleanup_days: 7,
+            staging_dir: None,
+            staging_min_free_mb: 512,
             hub_timezone: None,
             public_base_url: None,
             trusted_proxies: Vec::new(),
+            api_rate_limit_per_ip: 1200,
+            api_rate_limit_per_session: 600,
+            tls_cert: None,
+            tls_key: None,
+            acme_domains: Vec::new(),
+            acme_email: None,
+            acme_staging: false,
+            ui_dev_proxy: None,
+            standby_of: None,
+            standby_token: None,
+            standby_interval_secs: 60,
+            shutdown_drain_timeout_secs: 60,
         }
     }
 
+    #[test]
+    fn tls_config_prefers_files_and_normalizes_acme_domains() -> Result<(), anyhow::Error> {
+        let dir = tempfile::TempDir::new()?;
+
+        let args = base_hub_args(dir.path().to_path_buf());
+        assert_eq!(args.tls_config()?, None);
+
+        let mut args = base_hub_args(dir.path().to_path_buf());
+        args.tls_cert = Some(PathBuf::from("/etc/bastion/tls.crt"));
+        args.tls_key = Some(PathBuf::from("/etc/bastion/tls.key"));
+        assert_eq!(
+            args.tls_config()?,
+            Some(HubTlsConfig::Files {
+                cert: PathBuf::from("/etc/bastion/tls.crt"),
+                key: PathBuf::from("/etc/bastion/tls.key"),
+            })
+        );
+
+        let mut args = base_hub_args(dir.path().to_path_buf());
+        args.tls_cert = Some(PathBuf::from("/etc/bastion/tls.crt"));
+        assert!(args.tls_config().is_err());
+
+        let mut args = base_hub_args(dir.path().to_path_buf());
+        args.acme_domains = vec![
+            "Backup.Example.com.".to_string(),
+            "backup.example.com".to_string(),
+        ];
+        args.acme_email = Some(" ops@example.com ".to_string());
+        assert_eq!(
+            args.tls_config()?,
+            Some(HubTlsConfig::Acme {
+                domains: vec!["backup.example.com".to_string()],
+                email: Some("ops@example.com".to_string()),
+                staging: false,
+            })
+        );
+
+        let mut args = base_hub_args(dir.path().to_path_buf());
+        args.acme_staging = true;
+        assert!(args.tls_config().is_err());
+
+        let mut args = base_hub_args(dir.path().to_path_buf());
+        args.acme_domains = vec!["https://backup.example.com".to_string()];
+        assert!(args.tls_config().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn standby_config_requires_an_http_primary_and_token() -> Result<(), anyhow::Error> {
+        let dir = tempfile::TempDir::new()?;
+
+        let args = base_hub_args(dir.path().to_path_buf());
+        assert_eq!(args.standby_config()?, None);
+
+        let mut args = base_hub_args(dir.path().to_path_buf());
+        args.standby_of = Some(Url::parse("https://primary.example.com/")?);
+        args.standby_token = Some(" bst_token ".to_string());
+        assert_eq!(
+            args.standby_config()?,
+            Some(HubStandbyConfig {
+                primary_url: Url::parse("https://primary.example.com/")?,
+                token: "bst_token".to_string(),
+                interval: std::time::Duration::from_secs(60),
+            })
+        );
+
+        args.standby_token = Some(" ".to_string());
+        assert!(args.standby_config().is_err());
+
+        let mut args = base_hub_args(dir.path().to_path_buf());
+        args.standby_of = Some(Url::parse("ftp://primary.example.com/")?);
+        args.standby_token = Some("bst_token".to_string());
+        assert!(args.standby_config().is_err());
+        Ok(())
+    }
+
     #[test]
     fn into_config_sets_bind_and_default_trusted_proxies() -> Result<(), anyhow::Error> {
         let dir = tempfile::TempDir::new()?;
@@ -424,6 +1192,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn into_config_normalizes_ui_dev_proxy() -> Result<(), anyhow::Error> {
+        let dir = tempfile::TempDir::new()?;
+        let mut args = base_hub_args(dir.path().to_path_buf());
+        args.ui_dev_proxy = Some(" http://127.0.0.1:5173/ ".to_string());
+        let cfg = args.into_config()?;
+        assert_eq!(cfg.ui_dev_proxy.as_deref(), Some("http://127.0.0.1:5173"));
+
+        let mut args = base_hub_args(dir.path().to_path_buf());
+        args.ui_dev_proxy = Some("http://127.0.0.1:5173/app".to_string());
+        let err = args.into_config().expect_err("expected error");
+        assert!(err.to_string().contains("ui_dev_proxy"));
+        Ok(())
+    }
+
     #[test]
     fn into_config_default_timezone_is_always_valid() -> Result<(), anyhow::Error> {
         let dir = tempfile::TempDir::new()?;
@@ -0,0 +1,74 @@
+use std::path::Path;
+
+use bastion_storage::config_bundle;
+use bastion_storage::secrets::SecretsCrypto;
+
+use crate::config::{ConfigCommand, ConfigExportArgs, ConfigImportArgs};
+
+pub async fn run(command: ConfigCommand, data_dir: &Path) -> Result<(), anyhow::Error> {
+    match command {
+        ConfigCommand::Export(args) => export(args, data_dir).await,
+        ConfigCommand::Import(args) => import(args, data_dir).await,
+    }
+}
+
+fn read_bundle_password(
+    password: Option<String>,
+    password_stdin: bool,
+) -> Result<Option<String>, anyhow::Error> {
+    if password.is_none() && !password_stdin {
+        return Ok(None);
+    }
+    crate::read_keypack_password(password, password_stdin).map(Some)
+}
+
+async fn export(args: ConfigExportArgs, data_dir: &Path) -> Result<(), anyhow::Error> {
+    let password = read_bundle_password(args.password, args.password_stdin)?;
+    let db = bastion_storage::db::init(data_dir).await?;
+    let secrets = SecretsCrypto::load_or_create(data_dir)?;
+
+    let bundle = config_bundle::collect(&db, &secrets, password.is_some()).await?;
+    let bytes = config_bundle::encode(&bundle, password.as_deref())?;
+    std::fs::write(&args.out, bytes)?;
+
+    println!(
+        "exported {} job(s) and {} secret(s) to {}",
+        bundle.jobs.len(),
+        bundle.secrets.len(),
+        args.out.display()
+    );
+    if password.is_none() {
+        println!("secrets were left out; pass --password-stdin to include them");
+    }
+    Ok(())
+}
+
+async fn import(args: ConfigImportArgs, data_dir: &Path) -> Result<(), anyhow::Error> {
+    let password = read_bundle_password(args.password, args.password_stdin)?;
+    let bytes = std::fs::read(&args.r#in)?;
+    let bundle = config_bundle::decode(&bytes, password.as_deref())?;
+
+    let db = bastion_storage::db::init(data_dir).await?;
+    let secrets = SecretsCrypto::load_or_create(data_dir)?;
+    let summary = config_bundle::apply(&db, &secrets, &bundle).await?;
+
+    println!(
+        "imported {} job(s) and {} secret(s) into {}",
+        summary.jobs_created.len(),
+        summary.secrets_imported,
+        data_dir.display()
+    );
+    for name in &summary.jobs_skipped {
+        println!("skipped job {name:?}: a job with this name already exists");
+    }
+    for name in &summary.jobs_archived {
+        println!("archived job {name:?}: its agent is not enrolled here; reassign it to unarchive");
+    }
+    if summary.secrets_skipped > 0 {
+        println!(
+            "kept {} existing secret(s) with the same name",
+            summary.secrets_skipped
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,491 @@
+//! Optional TOML config file (`--config` / `BASTION_CONFIG`).
+//!
+//! File values are turned into command-line flags before the real parse, so clap validates them
+//! like any other input. Flags and env vars the operator set explicitly are left alone, which keeps
+//! the precedence CLI > env > file > DB > default.
+
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use clap::parser::ValueSource;
+use clap::{ArgAction, ArgMatches, Args as _};
+use ipnet::IpNet;
+
+use crate::config::{AgentArgs, HubArgs, LoggingArgs};
+
+const CONFIG_FILE_ARG: &str = "config_file";
+const AGENT_SUBCOMMAND: &str = "agent";
+
+const SECTION_HUB: &str = "hub";
+const SECTION_LOGGING: &str = "logging";
+const SECTION_AGENT: &str = "agent";
+
+/// Root-level args whose value came from the config file.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FileArgs {
+    pub(crate) path: Option<PathBuf>,
+    ids: BTreeSet<String>,
+}
+
+impl FileArgs {
+    pub(crate) fn contains(&self, id: &str) -> bool {
+        self.ids.contains(id)
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ConfigFile {
+    hub: toml::Table,
+    logging: toml::Table,
+    agent: toml::Table,
+}
+
+impl ConfigFile {
+    pub(crate) fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    fn parse(text: &str) -> Result<Self, anyhow::Error> {
+        let mut root: toml::Table = toml::from_str(text)?;
+        let mut section = |name: &str| match root.remove(name) {
+            None => Ok(toml::Table::new()),
+            Some(toml::Value::Table(table)) => Ok(table),
+            Some(_) => Err(anyhow::anyhow!("[{name}] must be a table")),
+        };
+        let file = Self {
+            hub: section(SECTION_HUB)?,
+            logging: section(SECTION_LOGGING)?,
+            agent: section(SECTION_AGENT)?,
+        };
+        if let Some(key) = root.keys().next() {
+            anyhow::bail!("unknown top-level key `{key}` (expected [hub], [logging] or [agent])");
+        }
+
+        check_section(
+            SECTION_HUB,
+            &file.hub,
+            &HubArgs::augment_args(section_command()),
+        )?;
+        check_section(
+            SECTION_LOGGING,
+            &file.logging,
+            &LoggingArgs::augment_args(section_command()),
+        )?;
+        check_section(
+            SECTION_AGENT,
+            &file.agent,
+            &AgentArgs::augment_args(section_command()),
+        )?;
+        Ok(file)
+    }
+
+    /// `[logging] log`, if set.
+    pub(crate) fn log_filter(&self) -> Option<String> {
+        self.logging
+            .get("log")
+            .and_then(toml::Value::as_str)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    }
+
+    /// `[hub] trusted_proxies` (array or comma-separated string), if set.
+    pub(crate) fn trusted_proxies(&self) -> Result<Option<Vec<IpNet>>, anyhow::Error> {
+        let Some(value) = self.hub.get("trusted_proxies") else {
+            return Ok(None);
+        };
+        let items: Vec<&str> = match value {
+            toml::Value::String(s) => s.split(',').collect(),
+            toml::Value::Array(items) => items
+                .iter()
+                .map(|item| {
+                    item.as_str()
+                        .ok_or_else(|| anyhow::anyhow!("trusted_proxies must be strings"))
+                })
+                .collect::<Result<_, _>>()?,
+            _ => anyhow::bail!("trusted_proxies must be a list of IPs/CIDRs"),
+        };
+
+        let mut out = Vec::new();
+        for item in items.into_iter().map(str::trim).filter(|v| !v.is_empty()) {
+            out.push(
+                item.parse::<IpNet>()
+                    .with_context(|| format!("invalid trusted proxy: {item}"))?,
+            );
+        }
+        Ok(Some(out))
+    }
+}
+
+/// Adds the values of the config file named by `--config` / `BASTION_CONFIG` to `argv`.
+///
+/// `[hub]` and `[logging]` apply to every command; `[agent]` only to `bastion agent`.
+pub(crate) fn apply(
+    cmd: &clap::Command,
+    argv: Vec<OsString>,
+    rust_log_set: bool,
+) -> Result<(Vec<OsString>, FileArgs), anyhow::Error> {
+    // Parse leniently first: the file may supply required args (e.g. the agent's `--hub-url`).
+    // Help/version requests fall through to the real parse.
+    let Ok(probe) = cmd
+        .clone()
+        .ignore_errors(true)
+        .try_get_matches_from(argv.iter())
+    else {
+        return Ok((argv, FileArgs::default()));
+    };
+    let Some(path) = probe.get_one::<PathBuf>(CONFIG_FILE_ARG).cloned() else {
+        return Ok((argv, FileArgs::default()));
+    };
+    let file = ConfigFile::load(&path)?;
+
+    let mut ids = BTreeSet::new();
+    let mut root_flags = Vec::new();
+    for (table, args) in [
+        (&file.hub, HubArgs::augment_args(section_command())),
+        (&file.logging, LoggingArgs::augment_args(section_command())),
+    ] {
+        for (key, flags) in section_flags(table, &args, &probe)? {
+            // RUST_LOG is an environment override too.
+            if key == "log" && rust_log_set {
+                continue;
+            }
+            root_flags.extend(flags);
+            ids.insert(key);
+        }
+    }
+
+    let mut agent_flags = Vec::new();
+    if let Some(agent) = probe.subcommand_matches(AGENT_SUBCOMMAND) {
+        let args = AgentArgs::augment_args(section_command());
+        for (_, flags) in section_flags(&file.agent, &args, agent)? {
+            agent_flags.extend(flags);
+        }
+    }
+
+    let mut argv = argv.into_iter();
+    let mut out: Vec<OsString> = argv.next().into_iter().collect();
+    out.extend(root_flags.into_iter().map(OsString::from));
+    out.extend(argv);
+    out.extend(agent_flags.into_iter().map(OsString::from));
+
+    Ok((
+        out,
+        FileArgs {
+            path: Some(path),
+            ids,
+        },
+    ))
+}
+
+fn section_command() -> clap::Command {
+    clap::Command::new("bastion")
+}
+
+fn check_section(
+    section: &str,
+    table: &toml::Table,
+    args: &clap::Command,
+) -> Result<(), anyhow::Error> {
+    for (key, value) in table {
+        let arg = find_arg(args, key)
+            .ok_or_else(|| anyhow::anyhow!("unknown key `{key}` in [{section}]"))?;
+        flags_for(arg, key, value)?;
+    }
+    Ok(())
+}
+
+fn find_arg<'a>(args: &'a clap::Command, id: &str) -> Option<&'a clap::Arg> {
+    args.get_arguments()
+        .find(|arg| arg.get_id() == id && arg.get_long().is_some())
+}
+
+/// Flags for the file keys that weren't set on the command line or via env.
+fn section_flags(
+    table: &toml::Table,
+    args: &clap::Command,
+    matches: &ArgMatches,
+) -> Result<Vec<(String, Vec<String>)>, anyhow::Error> {
+    let mut out = Vec::new();
+    for (key, value) in table {
+        let Some(arg) = find_arg(args, key) else {
+            continue;
+        };
+        if matches!(
+            matches.value_source(key),
+            Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+        ) {
+            continue;
+        }
+        let flags = flags_for(arg, key, value)?;
+        if !flags.is_empty() {
+            out.push((key.clone(), flags));
+        }
+    }
+    Ok(out)
+}
+
+fn flags_for(
+    arg: &clap::Arg,
+    key: &str,
+    value: &toml::Value,
+) -> Result<Vec<String>, anyhow::Error> {
+    let long = arg.get_long().unwrap_or(key);
+    if matches!(arg.get_action(), ArgAction::SetTrue) {
+        return match value {
+            toml::Value::Boolean(true) => Ok(vec![format!("--{long}")]),
+            toml::Value::Boolean(false) => Ok(Vec::new()),
+            _ => anyhow::bail!("`{key}` must be true or false"),
+        };
+    }
+
+    let values = match value {
+        toml::Value::Array(items) => {
+            if !matches!(arg.get_action(), ArgAction::Append) {
+                anyhow::bail!("`{key}` takes a single value");
+            }
+            items.iter().collect()
+        }
+        value => vec![value],
+    };
+    values
+        .into_iter()
+        .map(|value| {
+            let value = match value {
+                toml::Value::String(v) => v.clone(),
+                toml::Value::Integer(v) => v.to_string(),
+                toml::Value::Float(v) => v.to_string(),
+                toml::Value::Boolean(v) => v.to_string(),
+                _ => anyhow::bail!("`{key}` must be a string, number or boolean"),
+            };
+            Ok(format!("--{long}={value}"))
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+pub(crate) use reload::{HubReload, spawn_sighup_reload};
+
+#[cfg(unix)]
+mod reload {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use bastion_http::{ConfigValueSource, HubReloadedConfig};
+    use bastion_storage::hub_runtime_config_repo;
+    use tokio_util::sync::CancellationToken;
+    use tracing::{info, warn};
+
+    use super::ConfigFile;
+    use crate::logging::LogFilterHandle;
+
+    /// What the SIGHUP handler needs to re-resolve the log filter and trusted proxies.
+    pub(crate) struct HubReload {
+        pub(crate) path: PathBuf,
+        pub(crate) db: sqlx::SqlitePool,
+        pub(crate) log_filter_source: ConfigValueSource,
+        pub(crate) trusted_proxies_source: ConfigValueSource,
+        pub(crate) log_filter: LogFilterHandle,
+        pub(crate) reloaded: Arc<HubReloadedConfig>,
+    }
+
+    /// Values set on the command line or via env stay pinned until restart.
+    fn is_pinned(source: ConfigValueSource) -> bool {
+        matches!(
+            source,
+            ConfigValueSource::Cli | ConfigValueSource::Env | ConfigValueSource::EnvRustLog
+        )
+    }
+
+    impl HubReload {
+        async fn apply(&self) -> Result<(), anyhow::Error> {
+            let file = ConfigFile::load(&self.path)?;
+
+            // Resolve everything before changing anything, so a bad file keeps the current values.
+            let trusted_proxies = if is_pinned(self.trusted_proxies_source) {
+                None
+            } else {
+                Some(match file.trusted_proxies()? {
+                    Some(proxies) if !proxies.is_empty() => (proxies, ConfigValueSource::File),
+                    _ => (
+                        crate::config::default_trusted_proxies(),
+                        ConfigValueSource::Default,
+                    ),
+                })
+            };
+            let log_filter = if is_pinned(self.log_filter_source) {
+                None
+            } else if let Some(filter) = file.log_filter() {
+                Some((filter, ConfigValueSource::File))
+            } else {
+                let saved = hub_runtime_config_repo::get(&self.db)
+                    .await?
+                    .unwrap_or_default();
+                Some(
+                    match crate::normalize_optional_string(saved.log_filter.as_deref()) {
+                        Some(filter) => (filter, ConfigValueSource::Db),
+                        None => (
+                            crate::DEFAULT_LOG_FILTER.to_string(),
+                            ConfigValueSource::Default,
+                        ),
+                    },
+                )
+            };
+
+            if let Some((filter, source)) = log_filter {
+                self.log_filter.reload(&filter)?;
+                info!(filter = %filter, source = ?source, "log filter reloaded");
+                self.reloaded.set_log_filter(filter, source);
+            }
+            if let Some((proxies, source)) = trusted_proxies {
+                info!(trusted_proxies = ?proxies, source = ?source, "trusted proxies reloaded");
+                self.reloaded.set_trusted_proxies(proxies, source);
+            }
+            Ok(())
+        }
+    }
+
+    pub(crate) fn spawn_sighup_reload(reload: HubReload, shutdown: CancellationToken) {
+        tokio::spawn(async move {
+            use tokio::signal::unix::{SignalKind, signal};
+
+            let mut hangup = match signal(SignalKind::hangup()) {
+                Ok(v) => v,
+                Err(error) => {
+                    warn!(error = %error, "failed to register SIGHUP handler");
+                    return;
+                }
+            };
+
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => return,
+                    received = hangup.recv() => {
+                        if received.is_none() {
+                            return;
+                        }
+                    }
+                }
+
+                info!(path = %reload.path.display(), "SIGHUP received; reloading config file");
+                if let Err(error) = reload.apply().await {
+                    warn!(error = %error, "config reload failed; keeping current settings");
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsString;
+
+    use clap::{CommandFactory as _, FromArgMatches as _};
+
+    use super::{ConfigFile, apply};
+    use crate::config::{Cli, Command};
+
+    fn write_config(dir: &tempfile::TempDir, text: &str) -> String {
+        let path = dir.path().join("bastion.toml");
+        std::fs::write(&path, text).expect("write config");
+        path.display().to_string()
+    }
+
+    fn parse(args: &[&str], rust_log_set: bool) -> (Cli, super::FileArgs) {
+        let cmd = Cli::command();
+        let argv = args.iter().map(OsString::from).collect();
+        let (argv, file_args) = apply(&cmd, argv, rust_log_set).expect("apply");
+        let matches = cmd.try_get_matches_from(argv).expect("matches");
+        (Cli::from_arg_matches(&matches).expect("cli"), file_args)
+    }
+
+    #[test]
+    fn file_values_fill_in_unset_flags_only() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = write_config(
+            &dir,
+            r#"
+[hub]
+host = "0.0.0.0"
+port = 9000
+insecure_http = true
+trusted_proxies = ["10.0.0.0/8", "192.168.0.0/16"]
+
+[logging]
+log = "debug"
+log_keep_files = 5
+"#,
+        );
+
+        let (cli, file_args) = parse(&["bastion", "--config", &path, "--port", "9100"], false);
+        assert_eq!(cli.hub.host.to_string(), "0.0.0.0");
+        assert_eq!(cli.hub.port, 9100);
+        assert!(cli.hub.insecure_http);
+        assert_eq!(cli.hub.trusted_proxies.len(), 2);
+        assert_eq!(cli.logging.log.as_deref(), Some("debug"));
+        assert_eq!(cli.logging.log_keep_files, 5);
+
+        assert!(file_args.contains("host"));
+        assert!(file_args.contains("trusted_proxies"));
+        assert!(!file_args.contains("port"));
+        assert_eq!(file_args.path.as_deref(), Some(std::path::Path::new(&path)));
+    }
+
+    #[test]
+    fn rust_log_overrides_file_log_filter() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = write_config(&dir, "[logging]\nlog = \"debug\"\n");
+
+        let (cli, file_args) = parse(&["bastion", "--config", &path], true);
+        assert_eq!(cli.logging.log, None);
+        assert!(!file_args.contains("log"));
+    }
+
+    #[test]
+    fn agent_section_supplies_required_args() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = write_config(
+            &dir,
+            "[agent]\nhub_url = \"https://hub.example.com\"\nname = \"nas\"\n",
+        );
+
+        let (cli, _) = parse(&["bastion", "--config", &path, "agent"], false);
+        let Some(Command::Agent(agent)) = cli.command else {
+            panic!("expected agent subcommand");
+        };
+        assert_eq!(agent.hub_url, "https://hub.example.com");
+        assert_eq!(agent.name.as_deref(), Some("nas"));
+    }
+
+    #[test]
+    fn unknown_keys_and_bad_values_are_rejected() {
+        assert!(ConfigFile::parse("[hub]\nport = 9000\n").is_ok());
+        assert!(ConfigFile::parse("[hub]\nprot = 9000\n").is_err());
+        assert!(ConfigFile::parse("[server]\nport = 9000\n").is_err());
+        assert!(ConfigFile::parse("port = 9000\n").is_err());
+        assert!(ConfigFile::parse("[hub]\ninsecure_http = \"yes\"\n").is_err());
+        assert!(ConfigFile::parse("[hub]\nhost = [\"a\", \"b\"]\n").is_err());
+    }
+
+    #[test]
+    fn trusted_proxies_accept_list_or_comma_separated_string() {
+        let file =
+            ConfigFile::parse("[hub]\ntrusted_proxies = \"10.0.0.0/8, ::1/128\"\n").expect("parse");
+        let proxies = file.trusted_proxies().expect("proxies").expect("set");
+        assert_eq!(proxies.len(), 2);
+
+        let file = ConfigFile::parse("[hub]\ntrusted_proxies = [\"nope\"]\n").expect("parse");
+        assert!(file.trusted_proxies().is_err());
+
+        assert!(
+            ConfigFile::default()
+                .trusted_proxies()
+                .expect("ok")
+                .is_none()
+        );
+    }
+}
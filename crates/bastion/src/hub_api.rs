@@ -0,0 +1,124 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use url::Url;
+
+use crate::config::HubApiArgs;
+
+/// Minimal client for the Hub HTTP API, authenticated with an API token.
+pub struct HubApiClient {
+    http: reqwest::Client,
+    base_url: Url,
+    token: String,
+}
+
+impl HubApiClient {
+    pub fn new(args: &HubApiArgs) -> Result<Self, anyhow::Error> {
+        let token = args
+            .token
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| {
+                anyhow::anyhow!("an API token is required (--token or BASTION_API_TOKEN)")
+            })?
+            .to_string();
+
+        let mut base_url = args.hub_url.clone();
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .user_agent(format!("bastion-cli/{}", env!("CARGO_PKG_VERSION")))
+                .build()?,
+            base_url,
+            token,
+        })
+    }
+
+    fn url(&self, path: &str) -> Result<Url, anyhow::Error> {
+        Ok(self.base_url.join(path.trim_start_matches('/'))?)
+    }
+
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, anyhow::Error> {
+        let res = self
+            .http
+            .get(self.url(path)?)
+            .query(query)
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+        Ok(check(res).await?.json::<T>().await?)
+    }
+
+    pub async fn post<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<T, anyhow::Error> {
+        let mut req = self.http.post(self.url(path)?).bearer_auth(&self.token);
+        if let Some(body) = body {
+            req = req.json(body);
+        }
+        let res = req.send().await?;
+        Ok(check(res).await?.json::<T>().await?)
+    }
+
+    /// Streams a GET response body into `dest` and returns the response headers.
+    pub async fn download(
+        &self,
+        path: &str,
+        dest: &std::path::Path,
+    ) -> Result<reqwest::header::HeaderMap, anyhow::Error> {
+        use tokio::io::AsyncWriteExt as _;
+
+        let res = self
+            .http
+            .get(self.url(path)?)
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+        let mut res = check(res).await?;
+        let headers = res.headers().clone();
+
+        let mut file = tokio::fs::File::create(dest).await?;
+        while let Some(chunk) = res.chunk().await? {
+            file.write_all(&chunk).await?;
+        }
+        file.sync_all().await?;
+        Ok(headers)
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), anyhow::Error> {
+        let res = self
+            .http
+            .delete(self.url(path)?)
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+        check(res).await?;
+        Ok(())
+    }
+}
+
+async fn check(res: reqwest::Response) -> Result<reqwest::Response, anyhow::Error> {
+    let status = res.status();
+    if status.is_success() {
+        return Ok(res);
+    }
+
+    let body = res.text().await.unwrap_or_default();
+    match serde_json::from_str::<serde_json::Value>(&body) {
+        Ok(v) if v.get("error").is_some() => {
+            let code = v["error"].as_str().unwrap_or_default();
+            let message = v["message"].as_str().unwrap_or_default();
+            anyhow::bail!("hub returned HTTP {status}: {message} ({code})")
+        }
+        _ => anyhow::bail!("hub returned HTTP {status}"),
+    }
+}
@@ -0,0 +1,185 @@
+//! Runs a Hub as a warm standby (`--standby-of`) and the `bastion standby` commands.
+
+use std::path::Path;
+
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use bastion_engine::hub_standby::{self, KEYRING_FINGERPRINT_HEADER, StandbyState};
+
+use crate::config::{HubApiArgs, HubStandbyConfig, HubTlsConfig, StandbyCommand};
+use crate::hub_api::HubApiClient;
+use crate::hub_tls;
+use crate::sd_notify;
+
+const SNAPSHOT_PATH: &str = "/api/maintenance/db/snapshot";
+/// How long a single sync may take before the watchdog considers the follow loop stuck.
+const SYNC_GRACE: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Follows the primary until `shutdown`, serving only health and standby status.
+pub(crate) async fn run(
+    standby: HubStandbyConfig,
+    config: &bastion_config::Config,
+    tls: Option<HubTlsConfig>,
+    shutdown: CancellationToken,
+) -> Result<(), anyhow::Error> {
+    let client = HubApiClient::new(&HubApiArgs {
+        hub_url: standby.primary_url.clone(),
+        token: Some(standby.token.clone()),
+    })?;
+    let liveness = sd_notify::Liveness::new();
+    tokio::spawn(follow(
+        client,
+        standby.clone(),
+        config.data_dir.clone(),
+        shutdown.clone(),
+        liveness.clone(),
+    ));
+
+    let app = bastion_http::standby_router(config.data_dir.clone());
+    let listener = tokio::net::TcpListener::bind(config.bind).await?;
+    info!(
+        bind = %listener.local_addr()?,
+        data_dir = %config.data_dir.display(),
+        primary = %standby.primary_url,
+        interval_secs = standby.interval.as_secs(),
+        "bastion started as a standby"
+    );
+    sd_notify::ready(&format!("standby of {}", standby.primary_url));
+    let max_age = standby.interval + SYNC_GRACE;
+    sd_notify::spawn_watchdog(move || {
+        let fresh = liveness.is_fresh(max_age);
+        async move { fresh }
+    });
+
+    if let Some(tls) = tls {
+        let tls_config = hub_tls::server_config(&tls, &config.data_dir, shutdown.clone())?;
+        return hub_tls::serve(listener, app, tls_config, shutdown).await;
+    }
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move { shutdown.cancelled().await })
+    .await?;
+    Ok(())
+}
+
+async fn follow(
+    client: HubApiClient,
+    standby: HubStandbyConfig,
+    data_dir: std::path::PathBuf,
+    shutdown: CancellationToken,
+    liveness: sd_notify::Liveness,
+) {
+    let mut state = hub_standby::read_state(&data_dir)
+        .await
+        .filter(|s| s.primary_url == standby.primary_url.as_str())
+        .unwrap_or_else(|| StandbyState {
+            primary_url: standby.primary_url.to_string(),
+            ..Default::default()
+        });
+
+    loop {
+        liveness.beat();
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        match sync_once(&client, &data_dir).await {
+            Ok((size, keyring_matches)) => {
+                if !keyring_matches && state.keyring_matches != Some(false) {
+                    warn!(
+                        "master.key differs from the primary's; import its keypack before promoting"
+                    );
+                }
+                state.last_sync_at = Some(now);
+                state.snapshot_bytes = Some(size);
+                state.keyring_matches = Some(keyring_matches);
+                state.last_error = None;
+                state.last_error_at = None;
+            }
+            Err(error) => {
+                warn!(error = %error, "standby sync failed");
+                state.last_error = Some(format!("{error:#}"));
+                state.last_error_at = Some(now);
+            }
+        }
+        if let Err(error) = hub_standby::write_state(&data_dir, &state).await {
+            warn!(error = %error, "failed to write standby state");
+        }
+
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = tokio::time::sleep(standby.interval) => {}
+        }
+    }
+}
+
+/// Pulls one copy of the primary's database; returns its size and whether the keyrings match.
+async fn sync_once(client: &HubApiClient, data_dir: &Path) -> Result<(u64, bool), anyhow::Error> {
+    let dir = data_dir.join(hub_standby::STANDBY_DIR);
+    tokio::fs::create_dir_all(&dir).await?;
+    let partial = dir.join("download.partial");
+
+    let result = async {
+        let headers = client.download(SNAPSHOT_PATH, &partial).await?;
+        let fingerprint = headers
+            .get(KEYRING_FINGERPRINT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let size = hub_standby::install_snapshot(data_dir, &partial).await?;
+        Ok((size, hub_standby::keyring_matches(data_dir, &fingerprint)))
+    }
+    .await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&partial).await;
+    }
+    result
+}
+
+pub async fn run_cli(command: StandbyCommand, data_dir: &Path) -> Result<(), anyhow::Error> {
+    match command {
+        StandbyCommand::Status(args) => {
+            let Some(state) = hub_standby::read_state(data_dir).await else {
+                anyhow::bail!("{} has no standby state", data_dir.display());
+            };
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&state)?);
+                return Ok(());
+            }
+            println!("primary: {}", state.primary_url);
+            println!(
+                "last sync: {}",
+                state
+                    .last_sync_at
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "never".to_string())
+            );
+            if let Some(size) = state.snapshot_bytes {
+                println!("copy size: {size} bytes");
+            }
+            match state.keyring_matches {
+                Some(true) => println!("master.key: matches the primary"),
+                Some(false) => println!("master.key: DIFFERS from the primary"),
+                None => {}
+            }
+            if let Some(error) = state.last_error {
+                println!("last error: {error}");
+            }
+        }
+        StandbyCommand::Promote(args) => {
+            let promotion = hub_standby::promote(data_dir, args.force).await?;
+            println!(
+                "promoted the standby copy (synced at {})",
+                promotion
+                    .last_sync_at
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "unknown".to_string())
+            );
+            if let Some(dir) = promotion.replaced_dir {
+                println!("the previous database was moved to {}", dir.display());
+            }
+            println!("start the Hub without --standby-of to take over");
+        }
+    }
+    Ok(())
+}
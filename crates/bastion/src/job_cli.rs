@@ -0,0 +1,144 @@
+use std::io::Read as _;
+use std::path::Path;
+
+use crate::config::{JobArgs, JobCommand};
+use crate::hub_api::HubApiClient;
+
+pub async fn run(args: JobArgs) -> Result<(), anyhow::Error> {
+    let client = HubApiClient::new(&args.api)?;
+
+    match args.command {
+        JobCommand::List(args) => {
+            let include_archived = if args.include_archived {
+                "true"
+            } else {
+                "false"
+            };
+            let res = client
+                .get::<serde_json::Value>("api/jobs", &[("include_archived", include_archived)])
+                .await?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&res["items"])?);
+                return Ok(());
+            }
+
+            let items = res["items"].as_array().cloned().unwrap_or_default();
+            if items.is_empty() {
+                println!("no jobs");
+                return Ok(());
+            }
+            for item in items {
+                println!("{}", format_job_line(&item));
+            }
+        }
+        JobCommand::Create(args) => {
+            let spec = read_spec(&args.spec)?;
+            let body = serde_json::json!({
+                "name": args.name,
+                "agent_id": args.agent_id,
+                "schedule": args.schedule,
+                "schedule_timezone": args.schedule_timezone,
+                "overlap_policy": args.overlap_policy.as_str(),
+                "spec": spec,
+            });
+            let job = client
+                .post::<_, serde_json::Value>("api/jobs", Some(&body))
+                .await?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&job)?);
+            } else {
+                println!(
+                    "created job {} ({})",
+                    job["id"].as_str().unwrap_or_default(),
+                    job["name"].as_str().unwrap_or_default()
+                );
+            }
+        }
+        JobCommand::Run(args) => {
+            let path = format!("api/jobs/{}/run", parse_job_id(&args.job_id)?);
+            let res = client
+                .post::<serde_json::Value, serde_json::Value>(&path, None)
+                .await?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&res)?);
+            } else {
+                println!(
+                    "run {} {}",
+                    res["run_id"].as_str().unwrap_or_default(),
+                    res["status"].as_str().unwrap_or_default()
+                );
+            }
+        }
+        JobCommand::Delete(args) => {
+            let path = format!("api/jobs/{}", parse_job_id(&args.job_id)?);
+            client.delete(&path).await?;
+            println!("deleted job {}", args.job_id);
+        }
+    }
+
+    Ok(())
+}
+
+fn read_spec(path: &Path) -> Result<serde_json::Value, anyhow::Error> {
+    let raw = if path == Path::new("-") {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        buf
+    } else {
+        std::fs::read_to_string(path)
+            .map_err(|error| anyhow::anyhow!("failed to read {}: {error}", path.display()))?
+    };
+    serde_json::from_str(&raw).map_err(|error| anyhow::anyhow!("invalid job spec JSON: {error}"))
+}
+
+fn parse_job_id(raw: &str) -> Result<uuid::Uuid, anyhow::Error> {
+    uuid::Uuid::parse_str(raw.trim()).map_err(|_| anyhow::anyhow!("invalid job id: {raw}"))
+}
+
+fn format_job_line(item: &serde_json::Value) -> String {
+    let text = |key: &str| item[key].as_str().unwrap_or("-").to_string();
+    let node = item["agent_id"].as_str().unwrap_or("hub");
+    let mut line = format!(
+        "{}  {}  node={}  schedule={}  last={}",
+        text("id"),
+        text("name"),
+        node,
+        text("schedule"),
+        text("latest_run_status")
+    );
+    if item["archived_at"].is_number() {
+        line.push_str("  (archived)");
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_job_line;
+
+    #[test]
+    fn job_line_shows_node_schedule_and_archive_state() {
+        let line = format_job_line(&serde_json::json!({
+            "id": "j1",
+            "name": "nightly",
+            "agent_id": null,
+            "schedule": "0 0 * * *",
+            "latest_run_status": "success",
+            "archived_at": null,
+        }));
+        assert_eq!(
+            line,
+            "j1  nightly  node=hub  schedule=0 0 * * *  last=success"
+        );
+
+        let line = format_job_line(&serde_json::json!({
+            "id": "j2",
+            "name": "nas",
+            "agent_id": "a1",
+            "schedule": null,
+            "latest_run_status": null,
+            "archived_at": 10,
+        }));
+        assert_eq!(line, "j2  nas  node=a1  schedule=-  last=-  (archived)");
+    }
+}
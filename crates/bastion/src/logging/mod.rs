@@ -5,15 +5,39 @@ mod suffix;
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::layer::SubscriberExt as _;
 use tracing_subscriber::util::SubscriberInitExt as _;
+use tracing_subscriber::{EnvFilter, Registry, reload};
 
 use crate::config::LoggingArgs;
 
 pub struct LoggingGuard {
     _file_guard: Option<WorkerGuard>,
+    filter: LogFilterHandle,
+}
+
+// Only the unix SIGHUP handler reloads the filter.
+#[cfg_attr(not(unix), allow(dead_code))]
+impl LoggingGuard {
+    pub fn filter_handle(&self) -> LogFilterHandle {
+        self.filter.clone()
+    }
+}
+
+/// Swaps the active log filter without restarting the process.
+#[derive(Clone)]
+pub struct LogFilterHandle(reload::Handle<EnvFilter, Registry>);
+
+#[cfg_attr(not(unix), allow(dead_code))]
+impl LogFilterHandle {
+    /// Keeps the current filter when `filter` doesn't parse.
+    pub fn reload(&self, filter: &str) -> Result<(), anyhow::Error> {
+        let filter = EnvFilter::try_new(filter)?;
+        self.0.reload(filter)?;
+        Ok(())
+    }
 }
 
 pub fn init(args: &LoggingArgs) -> Result<LoggingGuard, anyhow::Error> {
-    let filter = build_filter(args)?;
+    let (filter, filter_handle) = reload::Layer::new(build_filter(args)?);
 
     use std::io::IsTerminal as _;
     let console_ansi = std::io::stdout().is_terminal();
@@ -56,10 +80,11 @@ pub fn init(args: &LoggingArgs) -> Result<LoggingGuard, anyhow::Error> {
 
     Ok(LoggingGuard {
         _file_guard: file_guard,
+        filter: LogFilterHandle(filter_handle),
     })
 }
 
-fn build_filter(args: &LoggingArgs) -> Result<tracing_subscriber::EnvFilter, anyhow::Error> {
+fn build_filter(args: &LoggingArgs) -> Result<EnvFilter, anyhow::Error> {
     let filter_str = if let Some(filter) = args.log.as_deref() {
         filter.to_string()
     } else {
@@ -67,7 +92,7 @@ fn build_filter(args: &LoggingArgs) -> Result<tracing_subscriber::EnvFilter, any
         "info,tower_http=warn".to_string()
     };
 
-    Ok(tracing_subscriber::EnvFilter::try_new(filter_str)?)
+    Ok(EnvFilter::try_new(filter_str)?)
 }
 
 #[cfg(test)]
@@ -1,7 +1,20 @@
 mod agent_client;
+mod api_token_cli;
 mod config;
+mod config_bundle_cli;
+mod config_file;
+mod hub_api;
+mod hub_standby;
+mod hub_tls;
 mod i18n;
+mod job_cli;
 mod logging;
+mod recover;
+mod restore_cli;
+mod restore_offline_cli;
+mod sd_notify;
+mod snapshot_cli;
+mod user_cli;
 #[cfg(windows)]
 mod win_service;
 #[cfg(windows)]
@@ -15,26 +28,35 @@ use clap::{CommandFactory, FromArgMatches as _};
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 
-use crate::config::{Cli, Command, ConfigArgs, DoctorArgs, KeypackCommand, LogRotation};
+use crate::config::{
+    Cli, Command, ConfigArgs, DoctorArgs, KeypackCommand, KeypackIdentityArgs, LogRotation,
+};
+use bastion_engine::drain::ShutdownDrain;
 use bastion_engine::run_events_bus::RunEventsBus;
-use bastion_engine::{agent_manager, bulk_operations, maintenance, notifications, scheduler};
+use bastion_engine::{
+    agent_manager, bulk_operations, db_backup, drain, keypack_escrow, maintenance, notifications,
+    run_stats, scheduler, stale_backups, storage_usage,
+};
 use bastion_http::{
     AppState, ConfigValueSource, HubRuntimeConfigMeta, HubRuntimeConfigSources,
     HubRuntimeLoggingEffective, normalize_public_base_url,
 };
-use bastion_storage::hub_runtime_config_repo;
+use bastion_storage::{agent_connections_repo, hub_runtime_config_repo};
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     let argv: Vec<_> = std::env::args_os().collect();
     let locale = i18n::cli::resolve_cli_locale();
     let cmd = i18n::cli::localize_command(Cli::command(), locale);
+    let mut runtime_env = RuntimeEnv::capture();
+    let (argv, file_args) = config_file::apply(&cmd, argv, runtime_env.rust_log.is_some())?;
+    runtime_env.file_args = file_args;
     let matches = cmd.get_matches_from(argv);
-    let runtime_env = RuntimeEnv::capture();
     let Cli {
         command,
         hub,
         logging: logging_args,
+        ..
     } = Cli::from_arg_matches(&matches)?;
 
     if let Some(command) = command {
@@ -49,7 +71,21 @@ async fn main() -> Result<(), anyhow::Error> {
                     log_keep_files = effective_logging_args.log_keep_files,
                     "logging initialized"
                 );
-                agent_client::run(args).await?;
+                if agent_client::run(args).await? == agent_client::AgentExit::RestartForUpdate {
+                    info!("agent update installed; exiting for restart");
+                    drop(_logging_guard);
+                    std::process::exit(agent_client::UPDATE_RESTART_EXIT_CODE);
+                }
+            }
+            Command::Config(ConfigArgs {
+                command: Some(command),
+                ..
+            }) => {
+                let effective_logging_args =
+                    apply_runtime_env_logging_fallback(logging_args, &runtime_env);
+                let _logging_guard = logging::init(&effective_logging_args)?;
+                let config = hub.into_config()?;
+                config_bundle_cli::run(command, &config.data_dir).await?;
             }
             Command::Config(args) => {
                 run_config_command(args, hub, logging_args, &matches, &runtime_env).await?;
@@ -118,8 +154,67 @@ async fn main() -> Result<(), anyhow::Error> {
                         );
                         println!("restart the service to use the new active key");
                     }
+                    KeypackCommand::Identity(args) => {
+                        let out = args.out.clone();
+                        let count = export_backup_identity(&config.data_dir, args).await?;
+                        println!("wrote {count} age identity(ies) to {}", out.display());
+                        println!("keep this file secret; it decrypts every backup using the key");
+                    }
                 }
             }
+            Command::Recover(args) => {
+                let effective_logging_args =
+                    apply_runtime_env_logging_fallback(logging_args, &runtime_env);
+                let _logging_guard = logging::init(&effective_logging_args)?;
+                let config = hub.into_config()?;
+                recover::run(args, &config.data_dir).await?;
+            }
+            Command::Snapshot { command } => {
+                let effective_logging_args =
+                    apply_runtime_env_logging_fallback(logging_args, &runtime_env);
+                let _logging_guard = logging::init(&effective_logging_args)?;
+                let config = hub.into_config()?;
+                snapshot_cli::run(command, &config.data_dir).await?;
+            }
+            Command::Standby { command } => {
+                let effective_logging_args =
+                    apply_runtime_env_logging_fallback(logging_args, &runtime_env);
+                let _logging_guard = logging::init(&effective_logging_args)?;
+                let config = hub.into_config()?;
+                hub_standby::run_cli(command, &config.data_dir).await?;
+            }
+            Command::ApiToken { command } => {
+                let effective_logging_args =
+                    apply_runtime_env_logging_fallback(logging_args, &runtime_env);
+                let _logging_guard = logging::init(&effective_logging_args)?;
+                let config = hub.into_config()?;
+                api_token_cli::run(command, &config.data_dir).await?;
+            }
+            Command::User { command } => {
+                let effective_logging_args =
+                    apply_runtime_env_logging_fallback(logging_args, &runtime_env);
+                let _logging_guard = logging::init(&effective_logging_args)?;
+                let config = hub.into_config()?;
+                user_cli::run(command, &config.data_dir).await?;
+            }
+            Command::Job(args) => {
+                let effective_logging_args =
+                    apply_runtime_env_logging_fallback(logging_args, &runtime_env);
+                let _logging_guard = logging::init(&effective_logging_args)?;
+                job_cli::run(args).await?;
+            }
+            Command::Restore(args) => {
+                let effective_logging_args =
+                    apply_runtime_env_logging_fallback(logging_args, &runtime_env);
+                let _logging_guard = logging::init(&effective_logging_args)?;
+                restore_cli::run(args).await?;
+            }
+            Command::RestoreOffline(args) => {
+                let effective_logging_args =
+                    apply_runtime_env_logging_fallback(logging_args, &runtime_env);
+                let _logging_guard = logging::init(&effective_logging_args)?;
+                restore_offline_cli::run(args)?;
+            }
         }
         return Ok(());
     }
@@ -174,32 +269,77 @@ pub(crate) async fn run_hub(
     hub: crate::config::HubArgs,
     logging_args: crate::config::LoggingArgs,
     matches: &clap::ArgMatches,
-    shutdown: CancellationToken,
+    stop_requested: CancellationToken,
     runtime_env: &RuntimeEnv,
 ) -> Result<(), anyhow::Error> {
+    let tls = hub.tls_config()?;
+    let standby = hub.standby_config()?;
+    let drain_timeout = Duration::from_secs(hub.shutdown_drain_timeout_secs);
     let mut config = hub.into_config()?;
+
+    // A standby only follows its primary; it doesn't open (or migrate) a database.
+    if let Some(standby) = standby {
+        let effective_logging_args = apply_runtime_env_logging_fallback(logging_args, runtime_env);
+        let _logging_guard = logging::init(&effective_logging_args)?;
+        return hub_standby::run(standby, &config, tls, stop_requested).await;
+    }
+
+    // A stop request drains in-flight runs first; `shutdown` then stops everything else.
+    let shutdown = CancellationToken::new();
+    let drain = ShutdownDrain::default();
+
     let pool = bastion_storage::db::init(&config.data_dir).await?;
 
     let saved = hub_runtime_config_repo::get(&pool)
         .await?
         .unwrap_or_default();
 
-    let (hub_runtime_config, effective_logging_args) =
+    let (mut hub_runtime_config, effective_logging_args) =
         resolve_hub_runtime_config_meta(&mut config, matches, &saved, logging_args, runtime_env);
+    hub_runtime_config.tls_enabled = tls.is_some();
 
     let _logging_guard = logging::init(&effective_logging_args)?;
     info!(
         log_file = ?effective_logging_args.log_file,
         log_rotation = ?effective_logging_args.log_rotation,
         log_keep_files = effective_logging_args.log_keep_files,
+        config_file = ?runtime_env.file_args.path,
         "logging initialized"
     );
 
+    #[cfg(unix)]
+    if let Some(path) = runtime_env.file_args.path.clone() {
+        config_file::spawn_sighup_reload(
+            config_file::HubReload {
+                path,
+                db: pool.clone(),
+                log_filter_source: hub_runtime_config.sources.log_filter,
+                trusted_proxies_source: hub_runtime_config.sources.trusted_proxies,
+                log_filter: _logging_guard.filter_handle(),
+                reloaded: hub_runtime_config.reloaded.clone(),
+            },
+            shutdown.clone(),
+        );
+    }
+
     let config = Arc::new(config);
-    let secrets = Arc::new(bastion_storage::secrets::SecretsCrypto::load_or_create(
-        &config.data_dir,
-    )?);
+    let secrets = Arc::new(
+        bastion_storage::secrets::SecretsCrypto::load_or_create(&config.data_dir)?
+            .with_external_providers(bastion_storage::secrets::ExternalSecretProviders::from_env()),
+    );
     let master_kid = secrets.active_kid();
+
+    // No agent can be connected yet; close sessions left open by the previous process.
+    let closed = agent_connections_repo::close_dangling(
+        &pool,
+        agent_connections_repo::DISCONNECT_REASON_HUB_RESTART,
+        time::OffsetDateTime::now_utc().unix_timestamp(),
+    )
+    .await?;
+    if closed > 0 {
+        info!(closed, "closed dangling agent connection sessions");
+    }
+
     let agent_manager = agent_manager::AgentManager::default();
     let run_events_bus = Arc::new(RunEventsBus::new());
     let run_queue_notify = Arc::new(tokio::sync::Notify::new());
@@ -211,7 +351,13 @@ pub(crate) async fn run_hub(
 
     scheduler::spawn(scheduler::SchedulerArgs {
         db: pool.clone(),
-        data_dir: config.data_dir.clone(),
+        staging: bastion_backup::staging::StagingArea::new(
+            config
+                .staging_dir
+                .clone()
+                .unwrap_or_else(|| config.data_dir.clone()),
+            config.staging_min_free_bytes,
+        ),
         secrets: secrets.clone(),
         agent_manager: agent_manager.clone(),
         run_retention_days: config.run_retention_days,
@@ -222,6 +368,7 @@ pub(crate) async fn run_hub(
         artifact_delete_notify: artifact_delete_notify.clone(),
         jobs_notify: jobs_notify.clone(),
         notifications_notify: notifications_notify.clone(),
+        drain: drain.clone(),
         shutdown: shutdown.clone(),
     });
     notifications::spawn(
@@ -231,6 +378,12 @@ pub(crate) async fn run_hub(
         notifications_notify.clone(),
         shutdown.clone(),
     );
+    notifications::spawn_digest(notifications::DigestArgs {
+        db: pool.clone(),
+        secrets: secrets.clone(),
+        hub_timezone: config.hub_timezone.clone(),
+        shutdown: shutdown.clone(),
+    });
     bulk_operations::spawn(bulk_operations::BulkOperationsArgs {
         db: pool.clone(),
         secrets: secrets.clone(),
@@ -239,7 +392,47 @@ pub(crate) async fn run_hub(
         shutdown: shutdown.clone(),
     });
     maintenance::spawn(pool.clone(), shutdown.clone());
+    storage_usage::spawn(pool.clone(), shutdown.clone());
+    run_stats::spawn(pool.clone(), shutdown.clone());
+    stale_backups::spawn(stale_backups::StaleBackupsArgs {
+        db: pool.clone(),
+        run_events_bus: run_events_bus.clone(),
+        notifications_notify: notifications_notify.clone(),
+        shutdown: shutdown.clone(),
+    });
+    db_backup::spawn(db_backup::DbBackupArgs {
+        db: pool.clone(),
+        data_dir: config.data_dir.clone(),
+        shutdown: shutdown.clone(),
+    });
+    keypack_escrow::spawn(keypack_escrow::KeypackEscrowArgs {
+        db: pool.clone(),
+        secrets: secrets.clone(),
+        data_dir: config.data_dir.clone(),
+        shutdown: shutdown.clone(),
+    });
 
+    {
+        let agent_manager = agent_manager.clone();
+        let shutdown = shutdown.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = stop_requested.cancelled() => {
+                    sd_notify::stopping(Some(drain::max_duration(drain_timeout)));
+                    drain::run(drain::DrainArgs {
+                        drain,
+                        agent_manager,
+                        timeout: drain_timeout,
+                        shutdown,
+                    })
+                    .await;
+                }
+                _ = shutdown.cancelled() => {}
+            }
+        });
+    }
+
+    let watchdog_db = pool.clone();
     let app = bastion_http::router(AppState {
         config: config.clone(),
         db: pool,
@@ -255,6 +448,15 @@ pub(crate) async fn run_hub(
         hub_runtime_config,
     });
 
+    let tls_config = match &tls {
+        Some(tls) => Some(hub_tls::server_config(
+            tls,
+            &config.data_dir,
+            shutdown.clone(),
+        )?),
+        None => None,
+    };
+
     let listener = tokio::net::TcpListener::bind(config.bind).await?;
     let addr = listener.local_addr()?;
 
@@ -263,8 +465,18 @@ pub(crate) async fn run_hub(
         data_dir = %config.data_dir.display(),
         master_kid,
         insecure_http = config.insecure_http,
+        tls = ?tls,
         "bastion started"
     );
+    sd_notify::ready(&format!("listening on {addr}"));
+    sd_notify::spawn_watchdog(move || {
+        let db = watchdog_db.clone();
+        async move { sqlx::query("SELECT 1").execute(&db).await.is_ok() }
+    });
+
+    if let Some(tls_config) = tls_config {
+        return hub_tls::serve(listener, app, tls_config, shutdown).await;
+    }
 
     axum::serve(
         listener,
@@ -429,6 +641,7 @@ async fn run_doctor_command(
 ) -> Result<(), anyhow::Error> {
     let mut checks: Vec<serde_json::Value> = Vec::new();
 
+    let tls_configured = hub.tls_cert.is_some() || !hub.acme_domains.is_empty();
     let mut config = match hub.into_config() {
         Ok(c) => {
             checks.push(serde_json::json!({
@@ -458,13 +671,13 @@ async fn run_doctor_command(
 
     // Warn when binding to a non-loopback address without explicitly allowing insecure HTTP.
     // This is a common "it works locally but not from LAN" misconfiguration: the operator needs
-    // either `--insecure-http` (LAN/dev) or a TLS-terminating reverse proxy.
-    if !config.bind.ip().is_loopback() && !config.insecure_http {
+    // either `--insecure-http` (LAN/dev), built-in TLS, or a TLS-terminating reverse proxy.
+    if !config.bind.ip().is_loopback() && !config.insecure_http && !tls_configured {
         checks.push(serde_json::json!({
             "id": "bind_security",
             "status": "warn",
             "message": format!(
-                "bind {} without --insecure-http: HTTPS via reverse proxy is required for non-loopback access",
+                "bind {} without --insecure-http: HTTPS via reverse proxy or --tls-cert/--acme-domain is required for non-loopback access",
                 config.bind
             ),
         }));
@@ -519,23 +732,21 @@ async fn run_doctor_command(
         }
     }));
 
-    // Secrets/keyring access.
-    match bastion_storage::secrets::SecretsCrypto::load_or_create(&config.data_dir) {
-        Ok(secrets) => {
-            checks.push(serde_json::json!({
-                "id": "secrets",
-                "status": "ok",
-                "message": "secrets keyring loaded",
-                "active_kid": secrets.active_kid(),
-            }));
-        }
-        Err(e) => {
-            checks.push(serde_json::json!({
-                "id": "secrets",
-                "status": "fail",
-                "message": format!("secrets error: {e}"),
-            }));
+    // Data directory integrity: DB pages, master.key, stored secrets, staging leftovers.
+    let data_dir_checks = match &pool {
+        Some(pool) => {
+            let staging_root = config
+                .staging_dir
+                .clone()
+                .unwrap_or_else(|| config.data_dir.clone());
+            bastion_engine::doctor::check_data_dir(pool, &config.data_dir, &staging_root)
+                .await
+                .checks
         }
+        None => vec![bastion_engine::doctor::check_master_key(&config.data_dir).0],
+    };
+    for check in data_dir_checks {
+        checks.push(serde_json::to_value(check)?);
     }
 
     // UI/docs assets availability.
@@ -574,6 +785,14 @@ async fn run_doctor_command(
             "fail" => println!("[FAIL] {msg}"),
             _ => println!("[{status}] {msg}"),
         }
+        if let Some(details) = c.get("details").and_then(|v| v.as_array()) {
+            for detail in details.iter().filter_map(|v| v.as_str()) {
+                println!("       - {detail}");
+            }
+        }
+        if let Some(hint) = c.get("hint").and_then(|v| v.as_str()) {
+            println!("       hint: {hint}");
+        }
     }
 
     if !ok {
@@ -676,21 +895,22 @@ fn resolve_hub_runtime_config_meta(
     mut effective_logging_args: crate::config::LoggingArgs,
     runtime_env: &RuntimeEnv,
 ) -> (HubRuntimeConfigMeta, crate::config::LoggingArgs) {
+    let source = |id: &str| runtime_env.value_source(matches, id);
     let mut sources = HubRuntimeConfigSources {
-        bind_host: map_value_source(matches.value_source("host")),
-        bind_port: map_value_source(matches.value_source("port")),
-        data_dir: map_value_source(matches.value_source("data_dir")),
-        insecure_http: map_value_source(matches.value_source("insecure_http")),
-        trusted_proxies: map_value_source(matches.value_source("trusted_proxies")),
-        debug_errors: map_value_source(matches.value_source("debug_errors")),
-        hub_timezone: map_value_source(matches.value_source("hub_timezone")),
-        run_retention_days: map_value_source(matches.value_source("run_retention_days")),
-        incomplete_cleanup_days: map_value_source(matches.value_source("incomplete_cleanup_days")),
-        public_base_url: map_value_source(matches.value_source("public_base_url")),
+        bind_host: source("host"),
+        bind_port: source("port"),
+        data_dir: source("data_dir"),
+        insecure_http: source("insecure_http"),
+        trusted_proxies: source("trusted_proxies"),
+        debug_errors: source("debug_errors"),
+        hub_timezone: source("hub_timezone"),
+        run_retention_days: source("run_retention_days"),
+        incomplete_cleanup_days: source("incomplete_cleanup_days"),
+        public_base_url: source("public_base_url"),
         ..HubRuntimeConfigSources::default()
     };
 
-    // Apply DB overrides for safe policy fields when not explicitly set via CLI/ENV.
+    // Apply DB overrides for safe policy fields when not explicitly set via CLI/ENV/file.
     if sources.hub_timezone == ConfigValueSource::Default
         && let Some(tz) = normalize_optional_string(saved.hub_timezone.as_deref())
             .and_then(|v| validate_timezone(&v).ok())
@@ -734,7 +954,7 @@ fn resolve_hub_runtime_config_meta(
     }
 
     let (effective_log_filter, log_filter_source) = resolve_log_filter(
-        matches,
+        source("log"),
         saved.log_filter.as_deref(),
         effective_logging_args.log.as_deref(),
         runtime_env.rust_log.as_deref(),
@@ -748,7 +968,7 @@ fn resolve_hub_runtime_config_meta(
     }
 
     // log file
-    sources.log_file = map_value_source(matches.value_source("log_file"));
+    sources.log_file = source("log_file");
     if sources.log_file == ConfigValueSource::Default
         && let Some(path) = normalize_optional_string(saved.log_file.as_deref())
     {
@@ -757,7 +977,7 @@ fn resolve_hub_runtime_config_meta(
     }
 
     // log rotation
-    sources.log_rotation = map_value_source(matches.value_source("log_rotation"));
+    sources.log_rotation = source("log_rotation");
     if sources.log_rotation == ConfigValueSource::Default
         && let Some(rot) = normalize_optional_string(saved.log_rotation.as_deref())
             .and_then(|v| parse_log_rotation(&v))
@@ -767,7 +987,7 @@ fn resolve_hub_runtime_config_meta(
     }
 
     // log keep files
-    sources.log_keep_files = map_value_source(matches.value_source("log_keep_files"));
+    sources.log_keep_files = source("log_keep_files");
     if sources.log_keep_files == ConfigValueSource::Default
         && let Some(keep) = saved.log_keep_files
     {
@@ -790,6 +1010,7 @@ fn resolve_hub_runtime_config_meta(
             sources,
             logging: runtime_logging,
             public_base_url: effective_public_base_url,
+            ..HubRuntimeConfigMeta::default()
         },
         effective_logging_args,
     )
@@ -800,6 +1021,7 @@ fn source_label(source: ConfigValueSource) -> &'static str {
         ConfigValueSource::Cli => "cli",
         ConfigValueSource::Env => "env",
         ConfigValueSource::EnvRustLog => "env(RUST_LOG)",
+        ConfigValueSource::File => "file",
         ConfigValueSource::Db => "db",
         ConfigValueSource::Default => "default",
     }
@@ -943,16 +1165,15 @@ fn log_rotation_to_string(value: LogRotation) -> &'static str {
 }
 
 fn resolve_log_filter(
-    matches: &clap::ArgMatches,
+    explicit_source: ConfigValueSource,
     saved: Option<&str>,
     explicit_log: Option<&str>,
     rust_log_env: Option<&str>,
 ) -> (String, ConfigValueSource) {
-    // BASTION_LOG / --log
-    let explicit_source = map_value_source(matches.value_source("log"));
+    // BASTION_LOG / --log, or `[logging] log` (only injected when RUST_LOG is unset)
     if explicit_source != ConfigValueSource::Default {
         return (
-            explicit_log.unwrap_or("info,tower_http=warn").to_string(),
+            explicit_log.unwrap_or(DEFAULT_LOG_FILTER).to_string(),
             explicit_source,
         );
     }
@@ -967,24 +1188,32 @@ fn resolve_log_filter(
         return (saved, ConfigValueSource::Db);
     }
 
-    // Default (keep in sync with logging::build_filter)
-    (
-        "info,tower_http=warn".to_string(),
-        ConfigValueSource::Default,
-    )
+    (DEFAULT_LOG_FILTER.to_string(), ConfigValueSource::Default)
 }
 
+// Keep in sync with logging::build_filter.
+const DEFAULT_LOG_FILTER: &str = "info,tower_http=warn";
+
 #[derive(Debug, Clone, Default)]
 pub(crate) struct RuntimeEnv {
     rust_log: Option<String>,
+    file_args: config_file::FileArgs,
 }
 
 impl RuntimeEnv {
     pub(crate) fn capture() -> Self {
         Self {
             rust_log: std::env::var("RUST_LOG").ok(),
+            file_args: config_file::FileArgs::default(),
         }
     }
+
+    fn value_source(&self, matches: &clap::ArgMatches, id: &str) -> ConfigValueSource {
+        if self.file_args.contains(id) {
+            return ConfigValueSource::File;
+        }
+        map_value_source(matches.value_source(id))
+    }
 }
 
 fn apply_runtime_env_logging_fallback(
@@ -999,6 +1228,47 @@ fn apply_runtime_env_logging_fallback(
     logging_args
 }
 
+/// Writes every identity (current, retired, or passphrase-unlocked) of a backup key to `args.out`.
+async fn export_backup_identity(
+    data_dir: &std::path::Path,
+    args: KeypackIdentityArgs,
+) -> Result<usize, anyhow::Error> {
+    use bastion_backup::backup_encryption;
+
+    let db = bastion_storage::db::init(data_dir).await?;
+    let secrets = bastion_storage::secrets::SecretsCrypto::load_or_create(data_dir)?;
+    let passphrase = restore_cli::read_passphrase(args.passphrase, args.passphrase_stdin)?;
+    let key = args.key.trim();
+
+    let mut identities = backup_encryption::list_age_identities(&db, &secrets, key, None).await?;
+    if let Some(passphrase) = passphrase.as_deref()
+        && let Some(identity) =
+            backup_encryption::unlock_age_passphrase_key(&db, &secrets, key, passphrase).await?
+    {
+        identities.push(identity);
+    }
+    if identities.is_empty() {
+        anyhow::bail!(
+            "backup key not found: {key} (passphrase-protected keys need --passphrase or --passphrase-stdin)"
+        );
+    }
+
+    let mut content = format!("# bastion backup key: {key}\n");
+    for identity in &identities {
+        content.push_str(identity);
+        content.push('\n');
+    }
+    let tmp = args.out.with_extension("tmp");
+    std::fs::write(&tmp, content)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600))?;
+    }
+    std::fs::rename(&tmp, &args.out)?;
+    Ok(identities.len())
+}
+
 fn read_keypack_password(
     password: Option<String>,
     password_stdin: bool,
@@ -1179,7 +1449,7 @@ mod tests {
         let (matches, _cli) = parse_cli_from(&["bastion"]);
 
         let (filter, source) = resolve_log_filter(
-            &matches,
+            map_value_source(matches.value_source("log")),
             Some("info,bastion=warn"),
             None,
             Some("trace,bastion=debug"),
@@ -1189,6 +1459,56 @@ mod tests {
         assert_eq!(source, ConfigValueSource::EnvRustLog);
     }
 
+    #[test]
+    fn resolve_hub_runtime_config_meta_prefers_config_file_over_db() {
+        let dir = TempDir::new().expect("tempdir");
+        let config_path = dir.path().join("bastion.toml");
+        std::fs::write(
+            &config_path,
+            "[hub]\nhub_timezone = \"Europe/Berlin\"\n\n[logging]\nlog = \"warn\"\n",
+        )
+        .expect("write config");
+        let data_dir = dir.path().display().to_string();
+        let config_arg = config_path.display().to_string();
+
+        let cmd = Cli::command();
+        let argv = ["bastion", "--config", &config_arg, "--data-dir", &data_dir]
+            .iter()
+            .map(std::ffi::OsString::from)
+            .collect();
+        let (argv, file_args) = config_file::apply(&cmd, argv, false).expect("apply");
+        let matches = cmd.try_get_matches_from(argv).expect("clap matches");
+        let cli = Cli::from_arg_matches(&matches).expect("cli parse");
+        let mut config = cli.hub.into_config().expect("config");
+        let saved = hub_runtime_config_repo::HubRuntimeConfig {
+            hub_timezone: Some("Asia/Shanghai".to_string()),
+            run_retention_days: Some(30),
+            log_filter: Some("debug".to_string()),
+            ..hub_runtime_config_repo::HubRuntimeConfig::default()
+        };
+        let runtime_env = RuntimeEnv {
+            file_args,
+            ..RuntimeEnv::default()
+        };
+
+        let (meta, effective_logging) = resolve_hub_runtime_config_meta(
+            &mut config,
+            &matches,
+            &saved,
+            cli.logging,
+            &runtime_env,
+        );
+
+        assert_eq!(config.hub_timezone, "Europe/Berlin");
+        assert_eq!(meta.sources.hub_timezone, ConfigValueSource::File);
+        assert_eq!(meta.sources.data_dir, ConfigValueSource::Cli);
+        assert_eq!(config.run_retention_days, 30);
+        assert_eq!(meta.sources.run_retention_days, ConfigValueSource::Db);
+        assert_eq!(meta.logging.filter, "warn");
+        assert_eq!(meta.sources.log_filter, ConfigValueSource::File);
+        assert_eq!(effective_logging.log.as_deref(), Some("warn"));
+    }
+
     #[test]
     fn resolve_hub_runtime_config_meta_prefers_explicit_runtime_env_over_db_value() {
         let dir = TempDir::new().expect("tempdir");
@@ -1202,6 +1522,7 @@ mod tests {
 
         let runtime_env = RuntimeEnv {
             rust_log: Some("trace,bastion=debug".to_string()),
+            ..RuntimeEnv::default()
         };
 
         let (meta, effective_logging) = resolve_hub_runtime_config_meta(
@@ -0,0 +1,615 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use url::Url;
+use uuid::Uuid;
+
+use bastion_backup::backup_encryption::{BACKUP_AGE_IDENTITY_KIND, BACKUP_AGE_PASSPHRASE_KEY_KIND};
+use bastion_core::HUB_NODE_ID;
+use bastion_core::backup_format::{COMPLETE_NAME, MANIFEST_NAME};
+use bastion_core::manifest::{ArtifactFormatV1, ManifestV1};
+use bastion_storage::secrets::SecretsCrypto;
+use bastion_storage::{jobs_repo, run_artifacts_repo, runs_repo, secrets_repo};
+use bastion_targets::{WebdavClient, WebdavCredentials, WebdavHttpError};
+
+use crate::config::RecoverArgs;
+
+// Recovered jobs need a valid source to pass job spec validation, but the original source is not
+// recorded on the target. This path is meant to fail loudly if the job is run before it is edited.
+const RECOVERED_SOURCE_PLACEHOLDER: &str = "/bastion-recovered/set-source-paths";
+
+pub(crate) enum TargetScanner {
+    LocalDir {
+        base_dir: PathBuf,
+    },
+    Webdav {
+        client: Box<WebdavClient>,
+        base_url: Url,
+    },
+}
+
+impl TargetScanner {
+    async fn list_dirs(&self, job_id: Option<&str>) -> Result<Vec<String>, anyhow::Error> {
+        match self {
+            Self::LocalDir { base_dir } => {
+                let dir = match job_id {
+                    Some(job_id) => base_dir.join(job_id),
+                    None => base_dir.clone(),
+                };
+                let mut names = Vec::new();
+                for entry in std::fs::read_dir(&dir)? {
+                    let entry = entry?;
+                    if entry.file_type()?.is_dir() {
+                        names.push(entry.file_name().to_string_lossy().into_owned());
+                    }
+                }
+                Ok(names)
+            }
+            Self::Webdav { client, base_url } => {
+                let url = match job_id {
+                    Some(job_id) => base_url.join(&format!("{job_id}/"))?,
+                    None => base_url.clone(),
+                };
+                let entries = client.propfind_depth1(&url).await?;
+                Ok(entries
+                    .into_iter()
+                    .filter(|entry| entry.kind == "dir")
+                    .map(|entry| entry.name.trim_end_matches('/').to_string())
+                    .collect())
+            }
+        }
+    }
+
+    pub(crate) async fn read_file(
+        &self,
+        job_id: &str,
+        run_id: &str,
+        name: &str,
+    ) -> Result<Option<Vec<u8>>, anyhow::Error> {
+        match self {
+            Self::LocalDir { base_dir } => {
+                let path = base_dir.join(job_id).join(run_id).join(name);
+                match std::fs::read(&path) {
+                    Ok(bytes) => Ok(Some(bytes)),
+                    Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                    Err(error) => Err(error.into()),
+                }
+            }
+            Self::Webdav { client, base_url } => {
+                let url = base_url.join(&format!("{job_id}/{run_id}/{name}"))?;
+                match client.get_bytes(&url).await {
+                    Ok(bytes) => Ok(Some(bytes)),
+                    Err(error) => {
+                        if let Some(http) = error.downcast_ref::<WebdavHttpError>()
+                            && http.status == reqwest::StatusCode::NOT_FOUND
+                        {
+                            return Ok(None);
+                        }
+                        Err(error)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Lists every file of a run directory (recursively) with its size, sorted by path.
+    pub(crate) async fn list_run_files(
+        &self,
+        job_id: &str,
+        run_id: &str,
+    ) -> Result<Vec<(String, u64)>, anyhow::Error> {
+        let mut files = Vec::new();
+        let mut pending = vec![String::new()];
+        while let Some(prefix) = pending.pop() {
+            match self {
+                Self::LocalDir { base_dir } => {
+                    let dir = base_dir.join(job_id).join(run_id).join(&prefix);
+                    for entry in std::fs::read_dir(&dir)? {
+                        let entry = entry?;
+                        let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+                        let file_type = entry.file_type()?;
+                        if file_type.is_dir() {
+                            pending.push(format!("{name}/"));
+                        } else if file_type.is_file() {
+                            files.push((name, entry.metadata()?.len()));
+                        }
+                    }
+                }
+                Self::Webdav { client, base_url } => {
+                    let url = base_url.join(&format!("{job_id}/{run_id}/{prefix}"))?;
+                    for entry in client.propfind_depth1(&url).await? {
+                        let name = format!("{prefix}{}", entry.name.trim_end_matches('/'));
+                        if entry.kind == "dir" {
+                            pending.push(format!("{name}/"));
+                        } else {
+                            files.push((name, entry.size.unwrap_or(0)));
+                        }
+                    }
+                }
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Returns a local path holding `name`: the file itself for a local directory target, or a
+    /// download into `scratch_dir` for WebDAV.
+    pub(crate) async fn fetch_file(
+        &self,
+        job_id: &str,
+        run_id: &str,
+        name: &str,
+        size: u64,
+        scratch_dir: &Path,
+    ) -> Result<PathBuf, anyhow::Error> {
+        match self {
+            Self::LocalDir { base_dir } => Ok(base_dir.join(job_id).join(run_id).join(name)),
+            Self::Webdav { client, base_url } => {
+                let url = base_url.join(&format!("{job_id}/{run_id}/{name}"))?;
+                let dest = scratch_dir.join(name.replace('/', "_"));
+                client.get_to_file(&url, &dest, Some(size), 3).await?;
+                Ok(dest)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RecoverReport {
+    dry_run: bool,
+    target: serde_json::Value,
+    jobs_created: Vec<String>,
+    runs_imported: Vec<String>,
+    runs_skipped: Vec<SkippedRun>,
+    missing_encryption_keys: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SkippedRun {
+    job_id: String,
+    run_id: String,
+    reason: String,
+}
+
+pub async fn run(args: RecoverArgs, data_dir: &Path) -> Result<(), anyhow::Error> {
+    let db = bastion_storage::db::init(data_dir).await?;
+    let secrets = SecretsCrypto::load_or_create(data_dir)?;
+
+    let (target, scanner) =
+        open_target(&db, &secrets, &args.target, args.secret.as_deref()).await?;
+    let report = recover(&db, &scanner, target, args.dry_run).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let verb = if report.dry_run {
+        "would import"
+    } else {
+        "imported"
+    };
+    println!(
+        "{verb} {} run(s) into {} new job(s)",
+        report.runs_imported.len(),
+        report.jobs_created.len()
+    );
+    for skipped in &report.runs_skipped {
+        println!(
+            "skipped {}/{}: {}",
+            skipped.job_id, skipped.run_id, skipped.reason
+        );
+    }
+    for key in &report.missing_encryption_keys {
+        println!("missing backup encryption key: {key} (restores of runs using it will fail)");
+    }
+    if !report.jobs_created.is_empty() && !report.dry_run {
+        println!("recovered jobs have no schedule; set their source before running them");
+    }
+    Ok(())
+}
+
+/// Opens a local directory (path or `file://` URL) or WebDAV target for direct reading.
+pub(crate) async fn open_target(
+    db: &SqlitePool,
+    secrets: &SecretsCrypto,
+    target: &str,
+    secret: Option<&str>,
+) -> Result<(serde_json::Value, TargetScanner), anyhow::Error> {
+    let raw = target.trim();
+    if raw.starts_with("http://") || raw.starts_with("https://") {
+        let secret_name = secret
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("--secret is required for a WebDAV target"))?;
+        let cred_bytes = secrets_repo::get_secret(db, secrets, HUB_NODE_ID, "webdav", secret_name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("missing webdav secret: {secret_name}"))?;
+        let credentials = WebdavCredentials::from_json(&cred_bytes)?;
+
+        let mut base_url = Url::parse(raw)?;
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        let client = WebdavClient::new(base_url.clone(), credentials)?;
+        let target = serde_json::json!({
+            "type": "webdav",
+            "base_url": raw,
+            "secret_name": secret_name,
+        });
+        return Ok((
+            target,
+            TargetScanner::Webdav {
+                client: Box::new(client),
+                base_url,
+            },
+        ));
+    }
+
+    let base_dir = if raw.starts_with("file://") {
+        Url::parse(raw)?
+            .to_file_path()
+            .map_err(|_| anyhow::anyhow!("invalid file URL: {raw}"))?
+    } else {
+        PathBuf::from(raw)
+    };
+    if !base_dir.is_dir() {
+        anyhow::bail!("target directory not found: {}", base_dir.display());
+    }
+    let target = serde_json::json!({
+        "type": "local_dir",
+        "base_dir": base_dir.to_string_lossy(),
+    });
+    Ok((target, TargetScanner::LocalDir { base_dir }))
+}
+
+async fn recover(
+    db: &SqlitePool,
+    scanner: &TargetScanner,
+    target: serde_json::Value,
+    dry_run: bool,
+) -> Result<RecoverReport, anyhow::Error> {
+    let mut report = RecoverReport {
+        dry_run,
+        target: target.clone(),
+        jobs_created: Vec::new(),
+        runs_imported: Vec::new(),
+        runs_skipped: Vec::new(),
+        missing_encryption_keys: Vec::new(),
+    };
+
+    let mut job_ids = scanner
+        .list_dirs(None)
+        .await?
+        .into_iter()
+        .filter(|name| Uuid::parse_str(name).is_ok())
+        .collect::<Vec<_>>();
+    job_ids.sort();
+
+    let mut encryption_keys = BTreeSet::<String>::new();
+    for job_id in job_ids {
+        let mut run_ids = scanner
+            .list_dirs(Some(&job_id))
+            .await?
+            .into_iter()
+            .filter(|name| Uuid::parse_str(name).is_ok())
+            .collect::<Vec<_>>();
+        run_ids.sort();
+
+        let mut job_ready = jobs_repo::get_job(db, &job_id).await?.is_some();
+        for run_id in run_ids {
+            let skip = |reason: &str| SkippedRun {
+                job_id: job_id.clone(),
+                run_id: run_id.clone(),
+                reason: reason.to_string(),
+            };
+
+            if runs_repo::get_run(db, &run_id).await?.is_some() {
+                report.runs_skipped.push(skip("run already exists"));
+                continue;
+            }
+            if scanner
+                .read_file(&job_id, &run_id, COMPLETE_NAME)
+                .await?
+                .is_none()
+            {
+                report
+                    .runs_skipped
+                    .push(skip("incomplete (no complete.json)"));
+                continue;
+            }
+            let Some(manifest_bytes) = scanner.read_file(&job_id, &run_id, MANIFEST_NAME).await?
+            else {
+                report.runs_skipped.push(skip("missing manifest.json"));
+                continue;
+            };
+            let manifest = match serde_json::from_slice::<ManifestV1>(&manifest_bytes) {
+                Ok(v) => v,
+                Err(error) => {
+                    report
+                        .runs_skipped
+                        .push(skip(&format!("invalid manifest.json: {error}")));
+                    continue;
+                }
+            };
+            if manifest.job_id.to_string() != job_id || manifest.run_id.to_string() != run_id {
+                report
+                    .runs_skipped
+                    .push(skip("manifest ids do not match the directory"));
+                continue;
+            }
+            let (started_at, ended_at) = match (
+                parse_rfc3339(&manifest.started_at),
+                parse_rfc3339(&manifest.ended_at),
+            ) {
+                (Some(started_at), Some(ended_at)) => (started_at, ended_at),
+                _ => {
+                    report
+                        .runs_skipped
+                        .push(skip("invalid manifest timestamps"));
+                    continue;
+                }
+            };
+
+            if manifest.pipeline.encryption == "age"
+                && let Some(key_name) = manifest.pipeline.encryption_key.as_deref()
+            {
+                encryption_keys.insert(key_name.to_string());
+            }
+
+            if !job_ready {
+                if !dry_run {
+                    jobs_repo::create_job_with_id(
+                        db,
+                        &job_id,
+                        &recovered_job_name(&job_id),
+                        None,
+                        None,
+                        None,
+                        jobs_repo::OverlapPolicy::Queue,
+                        recovered_job_spec(&target),
+                    )
+                    .await?;
+                }
+                report.jobs_created.push(job_id.clone());
+                job_ready = true;
+            }
+
+            if !dry_run {
+                import_run(db, &target, &manifest, started_at, ended_at).await?;
+            }
+            report.runs_imported.push(run_id.clone());
+        }
+    }
+
+    for key_name in encryption_keys {
+        let mut known = false;
+        for kind in [BACKUP_AGE_IDENTITY_KIND, BACKUP_AGE_PASSPHRASE_KEY_KIND] {
+            known |= secrets_repo::secret_exists(db, HUB_NODE_ID, kind, &key_name).await?;
+        }
+        if !known {
+            report.missing_encryption_keys.push(key_name);
+        }
+    }
+
+    Ok(report)
+}
+
+async fn import_run(
+    db: &SqlitePool,
+    target: &serde_json::Value,
+    manifest: &ManifestV1,
+    started_at: i64,
+    ended_at: i64,
+) -> Result<(), anyhow::Error> {
+    let run_id = manifest.run_id.to_string();
+    let artifact_format = match manifest.pipeline.format {
+        ArtifactFormatV1::ArchiveV1 => "archive_v1",
+        ArtifactFormatV1::RawTreeV1 => "raw_tree_v1",
+    };
+    let transfer_total_bytes = manifest.artifacts.iter().map(|a| a.size).sum::<u64>();
+    let summary = serde_json::json!({
+        "artifact_format": artifact_format,
+        "recovered": true,
+        "metrics": { "transfer_total_bytes": transfer_total_bytes },
+    });
+
+    runs_repo::create_run_with_id(
+        db,
+        &run_id,
+        &manifest.job_id.to_string(),
+        runs_repo::RunStatus::Success,
+        started_at,
+        Some(ended_at),
+        Some(summary),
+        None,
+    )
+    .await?;
+    runs_repo::set_run_target_snapshot(
+        db,
+        &run_id,
+        serde_json::json!({ "node_id": HUB_NODE_ID, "target": target }),
+    )
+    .await?;
+    run_artifacts_repo::upsert_run_artifact_from_successful_run(db, &run_id).await?;
+    Ok(())
+}
+
+fn recovered_job_name(job_id: &str) -> String {
+    let short = job_id.get(..8).unwrap_or(job_id);
+    format!("recovered-{short}")
+}
+
+fn recovered_job_spec(target: &serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "v": 1,
+        "type": "filesystem",
+        "source": { "paths": [RECOVERED_SOURCE_PLACEHOLDER] },
+        "target": target,
+    })
+}
+
+fn parse_rfc3339(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|v| v.timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tempfile::TempDir;
+    use uuid::Uuid;
+
+    use bastion_core::backup_format::{COMPLETE_NAME, MANIFEST_NAME};
+    use bastion_core::job_spec;
+    use bastion_core::manifest::{
+        ArtifactFormatV1, ArtifactPart, EntryIndexRef, HashAlgorithm, ManifestV1, PipelineSettings,
+    };
+    use bastion_storage::{db, jobs_repo, run_artifacts_repo, runs_repo};
+
+    use super::{TargetScanner, recover};
+
+    fn write_run(base: &Path, job_id: Uuid, run_id: Uuid, complete: bool) {
+        let dir = base.join(job_id.to_string()).join(run_id.to_string());
+        std::fs::create_dir_all(&dir).expect("run dir");
+        let manifest = ManifestV1 {
+            format_version: ManifestV1::FORMAT_VERSION,
+            job_id,
+            run_id,
+            started_at: "2026-01-02T03:04:05Z".to_string(),
+            ended_at: "2026-01-02T03:05:05Z".to_string(),
+            pipeline: PipelineSettings {
+                format: ArtifactFormatV1::ArchiveV1,
+                tar: "pax".to_string(),
+                compression: "zstd".to_string(),
+                compression_level: None,
+                encryption: "age".to_string(),
+                encryption_key: Some("default".to_string()),
+                encryption_recipient: None,
+                split_bytes: 1024,
+            },
+            artifacts: vec![ArtifactPart {
+                name: "payload.part000001".to_string(),
+                size: 42,
+                hash_alg: HashAlgorithm::Blake3,
+                hash: "00".to_string(),
+            }],
+            entry_index: EntryIndexRef {
+                name: "entries.jsonl.zst".to_string(),
+                count: 1,
+            },
+            source: None,
+        };
+        std::fs::write(
+            dir.join(MANIFEST_NAME),
+            serde_json::to_vec(&manifest).expect("manifest json"),
+        )
+        .expect("write manifest");
+        if complete {
+            std::fs::write(dir.join(COMPLETE_NAME), b"{}").expect("write complete");
+        }
+    }
+
+    #[tokio::test]
+    async fn list_run_files_walks_nested_directories() {
+        let temp = TempDir::new().expect("tempdir");
+        let job_id = Uuid::new_v4();
+        let run_id = Uuid::new_v4();
+        write_run(temp.path(), job_id, run_id, true);
+        let data_dir = temp
+            .path()
+            .join(job_id.to_string())
+            .join(run_id.to_string())
+            .join("data/sub");
+        std::fs::create_dir_all(&data_dir).expect("data dir");
+        std::fs::write(data_dir.join("a.txt"), b"abc").expect("write file");
+
+        let scanner = TargetScanner::LocalDir {
+            base_dir: temp.path().to_path_buf(),
+        };
+        let files = scanner
+            .list_run_files(&job_id.to_string(), &run_id.to_string())
+            .await
+            .expect("list files");
+        let names = files
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec![COMPLETE_NAME, "data/sub/a.txt", MANIFEST_NAME]);
+        assert_eq!(files[1].1, 3);
+    }
+
+    #[tokio::test]
+    async fn recover_rebuilds_jobs_runs_and_snapshots_from_local_dir() {
+        let temp = TempDir::new().expect("tempdir");
+        let pool = db::init(temp.path()).await.expect("db init");
+        let target_dir = temp.path().join("target");
+
+        let job_id = Uuid::new_v4();
+        let run_id = Uuid::new_v4();
+        let incomplete_run_id = Uuid::new_v4();
+        write_run(&target_dir, job_id, run_id, true);
+        write_run(&target_dir, job_id, incomplete_run_id, false);
+        std::fs::create_dir_all(target_dir.join("not-a-job")).expect("noise dir");
+
+        let target = serde_json::json!({
+            "type": "local_dir",
+            "base_dir": target_dir.to_string_lossy(),
+        });
+        let scanner = TargetScanner::LocalDir {
+            base_dir: target_dir.clone(),
+        };
+
+        let dry = recover(&pool, &scanner, target.clone(), true)
+            .await
+            .expect("dry run");
+        assert_eq!(dry.runs_imported, vec![run_id.to_string()]);
+        assert!(
+            jobs_repo::get_job(&pool, &job_id.to_string())
+                .await
+                .expect("get job")
+                .is_none()
+        );
+
+        let report = recover(&pool, &scanner, target.clone(), false)
+            .await
+            .expect("recover");
+        assert_eq!(report.jobs_created, vec![job_id.to_string()]);
+        assert_eq!(report.runs_imported, vec![run_id.to_string()]);
+        assert_eq!(report.runs_skipped.len(), 1);
+        assert_eq!(report.missing_encryption_keys, vec!["default".to_string()]);
+
+        let job = jobs_repo::get_job(&pool, &job_id.to_string())
+            .await
+            .expect("get job")
+            .expect("job");
+        assert!(job.schedule.is_none());
+        let spec = job_spec::parse_value(&job.spec).expect("spec");
+        job_spec::validate(&spec).expect("valid spec");
+
+        let run = runs_repo::get_run(&pool, &run_id.to_string())
+            .await
+            .expect("get run")
+            .expect("run");
+        assert_eq!(run.status, runs_repo::RunStatus::Success);
+        assert_eq!(run.ended_at, Some(1_767_323_105));
+
+        let artifact = run_artifacts_repo::get_run_artifact(&pool, &run_id.to_string())
+            .await
+            .expect("get artifact")
+            .expect("artifact");
+        assert_eq!(artifact.target_type, "local_dir");
+        assert_eq!(artifact.transfer_bytes, Some(42));
+
+        let again = recover(&pool, &scanner, target, false)
+            .await
+            .expect("recover again");
+        assert!(again.jobs_created.is_empty());
+        assert!(again.runs_imported.is_empty());
+        assert_eq!(again.runs_skipped.len(), 2);
+    }
+}
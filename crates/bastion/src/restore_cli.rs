@@ -0,0 +1,257 @@
+use std::io::{IsTerminal as _, Write as _};
+use std::time::Duration;
+
+use bastion_core::progress::ProgressSnapshotV1;
+
+use crate::config::RestoreArgs;
+use crate::hub_api::HubApiClient;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const BAR_WIDTH: usize = 24;
+
+pub async fn run(args: RestoreArgs) -> Result<(), anyhow::Error> {
+    let client = HubApiClient::new(&args.api)?;
+    let run_id = uuid::Uuid::parse_str(args.run_id.trim())
+        .map_err(|_| anyhow::anyhow!("invalid run id: {}", args.run_id))?;
+
+    let passphrase = read_passphrase(args.passphrase, args.passphrase_stdin)?;
+    let selection = (!args.select.is_empty()).then(|| {
+        // Directory entries also match the exact path, so files and directories can share one list.
+        serde_json::json!({ "files": [], "dirs": args.select })
+    });
+    let body = serde_json::json!({
+        "destination": {
+            "type": "local_fs",
+            "node_id": args.node.trim(),
+            "directory": args.dest.trim(),
+        },
+        "conflict_policy": args.conflict.as_str(),
+        "selection": selection,
+        "passphrase": passphrase,
+    });
+    let res = client
+        .post::<_, serde_json::Value>(&format!("api/runs/{run_id}/restore"), Some(&body))
+        .await?;
+    let op_id = res["op_id"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("hub response is missing op_id"))?
+        .to_string();
+
+    if args.no_wait {
+        println!("{op_id}");
+        return Ok(());
+    }
+
+    eprintln!("restore operation {op_id} started");
+    follow_operation(&client, &op_id).await
+}
+
+pub(crate) fn read_passphrase(
+    passphrase: Option<String>,
+    passphrase_stdin: bool,
+) -> Result<Option<String>, anyhow::Error> {
+    match (passphrase, passphrase_stdin) {
+        (Some(_), true) => anyhow::bail!("use either --passphrase or --passphrase-stdin, not both"),
+        (passphrase, false) => Ok(passphrase),
+        (None, true) => {
+            use std::io::Read as _;
+            let mut buf = String::new();
+            std::io::stdin().read_to_string(&mut buf)?;
+            let trimmed = buf.trim_end_matches(&['\r', '\n'][..]).to_string();
+            if trimmed.is_empty() {
+                anyhow::bail!("passphrase from stdin is empty");
+            }
+            Ok(Some(trimmed))
+        }
+    }
+}
+
+async fn follow_operation(client: &HubApiClient, op_id: &str) -> Result<(), anyhow::Error> {
+    let op_path = format!("api/operations/{op_id}");
+    let events_path = format!("{op_path}/events");
+    let mut bar = ProgressLine::new();
+    let mut last_seq = 0_i64;
+
+    loop {
+        // Read the status before the events so the final poll includes every event.
+        let op = client.get::<serde_json::Value>(&op_path, &[]).await?;
+        let after_seq = last_seq.to_string();
+        let events = client
+            .get::<Vec<serde_json::Value>>(&events_path, &[("after_seq", after_seq.as_str())])
+            .await?;
+        for event in &events {
+            bar.clear();
+            println!("{}", format_event(event));
+            last_seq = last_seq.max(event["seq"].as_i64().unwrap_or(last_seq));
+        }
+
+        let status = op["status"].as_str().unwrap_or_default();
+        if status != "running" {
+            bar.clear();
+            return match status {
+                "success" => {
+                    eprintln!("restore operation {op_id} finished");
+                    Ok(())
+                }
+                _ => {
+                    let error = op["error"].as_str().unwrap_or("no error details");
+                    anyhow::bail!("restore operation {op_id} {status}: {error}")
+                }
+            };
+        }
+
+        if let Some(snapshot) = op
+            .get("progress")
+            .and_then(|v| serde_json::from_value::<ProgressSnapshotV1>(v.clone()).ok())
+        {
+            bar.draw(&format_progress(&snapshot));
+        }
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// Single-line progress indicator on stderr; disabled when stderr is not a terminal.
+struct ProgressLine {
+    enabled: bool,
+    drawn: bool,
+}
+
+impl ProgressLine {
+    fn new() -> Self {
+        Self {
+            enabled: std::io::stderr().is_terminal(),
+            drawn: false,
+        }
+    }
+
+    fn draw(&mut self, text: &str) {
+        if !self.enabled {
+            return;
+        }
+        let mut stderr = std::io::stderr();
+        let _ = write!(stderr, "\r\x1b[2K{text}");
+        let _ = stderr.flush();
+        self.drawn = true;
+    }
+
+    fn clear(&mut self) {
+        if !self.drawn {
+            return;
+        }
+        let mut stderr = std::io::stderr();
+        let _ = write!(stderr, "\r\x1b[2K");
+        let _ = stderr.flush();
+        self.drawn = false;
+    }
+}
+
+fn format_event(event: &serde_json::Value) -> String {
+    format!(
+        "[{}] {}",
+        event["level"].as_str().unwrap_or("info"),
+        event["message"].as_str().unwrap_or_default()
+    )
+}
+
+fn format_progress(snapshot: &ProgressSnapshotV1) -> String {
+    let done = snapshot.done.bytes;
+    let mut line = match snapshot.total.filter(|t| t.bytes > 0) {
+        Some(total) => {
+            let ratio = (done as f64 / total.bytes as f64).clamp(0.0, 1.0);
+            let filled = (ratio * BAR_WIDTH as f64).round() as usize;
+            format!(
+                "{} [{}{}] {:>3}% {}/{}",
+                snapshot.stage,
+                "#".repeat(filled),
+                "-".repeat(BAR_WIDTH - filled),
+                (ratio * 100.0).floor() as u64,
+                format_bytes(done),
+                format_bytes(total.bytes)
+            )
+        }
+        None => format!(
+            "{} {} files {}",
+            snapshot.stage,
+            snapshot.done.files,
+            format_bytes(done)
+        ),
+    };
+    if let Some(rate) = snapshot.rate_bps.filter(|v| *v > 0) {
+        line.push_str(&format!("  {}/s", format_bytes(rate)));
+    }
+    if let Some(eta) = snapshot.eta_seconds {
+        line.push_str(&format!("  ETA {}", format_duration(eta)));
+    }
+    line
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+fn format_duration(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{h}h {m}m {s}s")
+    } else if m > 0 {
+        format!("{m}m {s}s")
+    } else {
+        format!("{s}s")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bastion_core::progress::{ProgressKindV1, ProgressSnapshotV1, ProgressUnitsV1};
+
+    use super::format_progress;
+
+    fn snapshot(done: u64, total: Option<u64>) -> ProgressSnapshotV1 {
+        ProgressSnapshotV1 {
+            v: 1,
+            kind: ProgressKindV1::Restore,
+            stage: "restore".to_string(),
+            ts: 0,
+            done: ProgressUnitsV1 {
+                files: 3,
+                dirs: 0,
+                bytes: done,
+            },
+            total: total.map(|bytes| ProgressUnitsV1 {
+                files: 10,
+                dirs: 0,
+                bytes,
+            }),
+            rate_bps: Some(2048),
+            eta_seconds: Some(90),
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn progress_line_shows_bar_when_total_is_known() {
+        assert_eq!(
+            format_progress(&snapshot(512, Some(1024))),
+            "restore [############------------]  50% 512 B/1.0 KiB  2.0 KiB/s  ETA 1m 30s"
+        );
+    }
+
+    #[test]
+    fn progress_line_falls_back_to_counters_without_total() {
+        assert_eq!(
+            format_progress(&snapshot(512, None)),
+            "restore 3 files 512 B  2.0 KiB/s  ETA 1m 30s"
+        );
+    }
+}
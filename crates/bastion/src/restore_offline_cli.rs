@@ -0,0 +1,74 @@
+use std::io::Read as _;
+
+use bastion_backup::restore::{self, ConflictPolicy, PayloadDecryption, RestoreSelection};
+
+use crate::config::RestoreOfflineArgs;
+
+pub fn run(args: RestoreOfflineArgs) -> Result<(), anyhow::Error> {
+    let decryption = match &args.identity {
+        Some(path) => PayloadDecryption::AgeX25519 {
+            identity: read_identity_file(path)?,
+        },
+        None => PayloadDecryption::None,
+    };
+    let selection = (!args.select.is_empty()).then(|| RestoreSelection {
+        // Directory entries also match the exact path, so files and directories can share one list.
+        files: Vec::new(),
+        dirs: args.select.clone(),
+    });
+    let conflict = args.conflict.as_str().parse::<ConflictPolicy>()?;
+
+    let manifest = restore::restore_run_dir_to_local_fs(
+        &args.run_dir,
+        args.dest.clone(),
+        conflict,
+        decryption,
+        selection.as_ref(),
+        None,
+    )?;
+    println!(
+        "restored run {} of job {} to {}",
+        manifest.run_id,
+        manifest.job_id,
+        args.dest.display()
+    );
+    Ok(())
+}
+
+/// Reads age identities (`AGE-SECRET-KEY-...` lines), skipping comments and blank lines.
+fn read_identity_file(path: &std::path::Path) -> Result<String, anyhow::Error> {
+    let raw = if path.as_os_str() == "-" {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        buf
+    } else {
+        std::fs::read_to_string(path)?
+    };
+    let identities = parse_identities(&raw);
+    if identities.is_empty() {
+        anyhow::bail!("no age identity found in {}", path.display());
+    }
+    Ok(identities)
+}
+
+fn parse_identities(raw: &str) -> String {
+    raw.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_identities;
+
+    #[test]
+    fn parse_identities_skips_comments_and_blank_lines() {
+        let raw = "# created: 2026-01-01\n# public key: age1xyz\nAGE-SECRET-KEY-1AAA\n\n  AGE-SECRET-KEY-1BBB  \n";
+        assert_eq!(
+            parse_identities(raw),
+            "AGE-SECRET-KEY-1AAA\nAGE-SECRET-KEY-1BBB"
+        );
+    }
+}
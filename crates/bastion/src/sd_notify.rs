@@ -0,0 +1,210 @@
+//! systemd service notifications for `Type=notify` units: readiness, watchdog keepalives and stop.
+//!
+//! Everything is a no-op unless systemd set `NOTIFY_SOCKET`, so other service managers and
+//! interactive runs are unaffected.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use tracing::{debug, warn};
+
+/// Startup is done (listener bound, migrations applied); `status` shows in `systemctl status`.
+pub(crate) fn ready(status: &str) {
+    notify(&format!("READY=1\nSTATUS={status}"));
+}
+
+/// Shutdown started. `expected` asks systemd to wait at least that long before killing us.
+pub(crate) fn stopping(expected: Option<Duration>) {
+    let mut state = "STOPPING=1\nSTATUS=draining".to_string();
+    if let Some(expected) = expected {
+        state.push_str(&format!("\nEXTEND_TIMEOUT_USEC={}", expected.as_micros()));
+    }
+    notify(&state);
+}
+
+/// Sends a `WATCHDOG=1` keepalive every half `WatchdogSec=` while `probe` reports healthy.
+///
+/// A probe that fails or hangs skips the keepalive, so systemd restarts a wedged process.
+pub(crate) fn spawn_watchdog<F, Fut>(probe: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = bool> + Send,
+{
+    let Some(interval) = watchdog_interval(
+        std::env::var("WATCHDOG_USEC").ok().as_deref(),
+        std::env::var("WATCHDOG_PID").ok().as_deref(),
+        std::process::id(),
+    ) else {
+        return;
+    };
+    debug!(
+        interval_ms = interval.as_millis() as u64,
+        "systemd watchdog enabled"
+    );
+
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(interval);
+        loop {
+            tick.tick().await;
+            match tokio::time::timeout(interval, probe()).await {
+                Ok(true) => notify("WATCHDOG=1"),
+                Ok(false) => warn!("health probe failed; skipping watchdog keepalive"),
+                Err(_) => warn!("health probe timed out; skipping watchdog keepalive"),
+            }
+        }
+    });
+}
+
+/// When a long-running loop last made progress, for watchdog probes of processes without a
+/// database to ping. Clones share the same timestamp.
+#[derive(Debug, Clone)]
+pub(crate) struct Liveness {
+    started: Instant,
+    /// Milliseconds after `started`.
+    last_progress: Arc<AtomicU64>,
+}
+
+impl Liveness {
+    pub(crate) fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            last_progress: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Records progress now.
+    pub(crate) fn beat(&self) {
+        self.beat_at(Instant::now());
+    }
+
+    /// Whether progress was recorded within `max_age`.
+    pub(crate) fn is_fresh(&self, max_age: Duration) -> bool {
+        self.is_fresh_at(Instant::now(), max_age)
+    }
+
+    fn beat_at(&self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.started).as_millis();
+        self.last_progress.fetch_max(
+            u64::try_from(elapsed).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    fn is_fresh_at(&self, now: Instant, max_age: Duration) -> bool {
+        let last = Duration::from_millis(self.last_progress.load(Ordering::Relaxed));
+        now.saturating_duration_since(self.started)
+            .saturating_sub(last)
+            <= max_age
+    }
+}
+
+/// Half the watchdog timeout, when the watchdog is enabled for this process.
+fn watchdog_interval(usec: Option<&str>, pid: Option<&str>, own_pid: u32) -> Option<Duration> {
+    if let Some(pid) = pid
+        && pid.trim().parse::<u32>().ok() != Some(own_pid)
+    {
+        return None;
+    }
+    let usec = usec?.trim().parse::<u64>().ok().filter(|v| *v > 0)?;
+    Some(Duration::from_micros(usec / 2))
+}
+
+fn notify(state: &str) {
+    let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+    if let Err(error) = send(&path, state) {
+        debug!(error = %error, "failed to notify systemd");
+    }
+}
+
+#[cfg(unix)]
+fn send(path: &std::ffi::OsStr, state: &str) -> std::io::Result<()> {
+    use std::os::unix::ffi::OsStrExt as _;
+    use std::os::unix::net::UnixDatagram;
+
+    let socket = UnixDatagram::unbound()?;
+    match path.as_bytes().strip_prefix(b"@") {
+        Some(name) => send_abstract(&socket, name, state),
+        None => socket.send_to(state.as_bytes(), path).map(|_| ()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send_abstract(
+    socket: &std::os::unix::net::UnixDatagram,
+    name: &[u8],
+    state: &str,
+) -> std::io::Result<()> {
+    use std::os::linux::net::SocketAddrExt as _;
+
+    let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+    socket.send_to_addr(state.as_bytes(), &addr).map(|_| ())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn send_abstract(
+    _socket: &std::os::unix::net::UnixDatagram,
+    _name: &[u8],
+    _state: &str,
+) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
+#[cfg(not(unix))]
+fn send(_path: &std::ffi::OsStr, _state: &str) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Liveness, watchdog_interval};
+
+    #[test]
+    fn watchdog_interval_is_half_the_timeout_for_this_process() {
+        assert_eq!(
+            watchdog_interval(Some("30000000"), None, 42),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(
+            watchdog_interval(Some("30000000"), Some("42"), 42),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(watchdog_interval(Some("30000000"), Some("7"), 42), None);
+        assert_eq!(watchdog_interval(Some("0"), None, 42), None);
+        assert_eq!(watchdog_interval(Some("soon"), None, 42), None);
+        assert_eq!(watchdog_interval(None, None, 42), None);
+    }
+
+    #[test]
+    fn liveness_goes_stale_without_beats() {
+        let liveness = Liveness::new();
+        let max_age = Duration::from_secs(60);
+        let start = liveness.started;
+        assert!(liveness.is_fresh_at(start + Duration::from_secs(60), max_age));
+        assert!(!liveness.is_fresh_at(start + Duration::from_secs(61), max_age));
+
+        liveness.clone().beat_at(start + Duration::from_secs(50));
+        assert!(liveness.is_fresh_at(start + Duration::from_secs(110), max_age));
+        assert!(!liveness.is_fresh_at(start + Duration::from_secs(111), max_age));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn send_delivers_one_datagram_per_state() {
+        use std::os::unix::net::UnixDatagram;
+
+        let temp = tempfile::TempDir::new().expect("tempdir");
+        let path = temp.path().join("notify.sock");
+        let receiver = UnixDatagram::bind(&path).expect("bind");
+
+        super::send(path.as_os_str(), "READY=1\nSTATUS=ok").expect("send");
+        let mut buf = [0u8; 64];
+        let n = receiver.recv(&mut buf).expect("recv");
+        assert_eq!(&buf[..n], b"READY=1\nSTATUS=ok");
+    }
+}
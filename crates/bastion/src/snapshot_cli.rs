@@ -0,0 +1,136 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use bastion_backup::snapshot_export::{
+    self, SnapshotExportFile, SnapshotExportMetadata, SnapshotExportWriter,
+};
+use bastion_core::backup_format::{COMPLETE_NAME, MANIFEST_NAME};
+use bastion_core::manifest::ManifestV1;
+use bastion_storage::runs_repo;
+use bastion_storage::secrets::SecretsCrypto;
+
+use crate::config::{SnapshotCommand, SnapshotExportArgs, SnapshotImportArgs};
+use crate::recover::open_target;
+
+pub async fn run(command: SnapshotCommand, data_dir: &Path) -> Result<(), anyhow::Error> {
+    match command {
+        SnapshotCommand::Export(args) => export(args, data_dir).await,
+        SnapshotCommand::Import(args) => import(args),
+    }
+}
+
+async fn export(args: SnapshotExportArgs, data_dir: &Path) -> Result<(), anyhow::Error> {
+    let db = bastion_storage::db::init(data_dir).await?;
+    let secrets = SecretsCrypto::load_or_create(data_dir)?;
+    let (_, scanner) = open_target(&db, &secrets, &args.target, args.secret.as_deref()).await?;
+
+    let run_id = args.run_id.trim();
+    let job_id = match args.job_id.as_deref().map(str::trim) {
+        Some(job_id) if !job_id.is_empty() => job_id.to_string(),
+        _ => runs_repo::get_run(&db, run_id)
+            .await?
+            .map(|run| run.job_id)
+            .ok_or_else(|| anyhow::anyhow!("run not found in the Hub database; pass --job-id"))?,
+    };
+
+    if scanner
+        .read_file(&job_id, run_id, COMPLETE_NAME)
+        .await?
+        .is_none()
+    {
+        anyhow::bail!("run {run_id} is incomplete or missing (no {COMPLETE_NAME})");
+    }
+    let manifest_bytes = scanner
+        .read_file(&job_id, run_id, MANIFEST_NAME)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("run {run_id} has no {MANIFEST_NAME}"))?;
+    let manifest = serde_json::from_slice::<ManifestV1>(&manifest_bytes)?;
+    if manifest.job_id.to_string() != job_id || manifest.run_id.to_string() != run_id {
+        anyhow::bail!("manifest ids do not match the requested run");
+    }
+
+    let files = scanner
+        .list_run_files(&job_id, run_id)
+        .await?
+        .into_iter()
+        .map(|(path, size)| SnapshotExportFile { path, size })
+        .collect::<Vec<_>>();
+    let metadata = SnapshotExportMetadata::new(&manifest, files)?;
+
+    // Write next to the destination and rename at the end so a failed export never looks complete.
+    let partial = sibling_path(&args.out, "partial");
+    let scratch_dir = sibling_path(&args.out, "download");
+    std::fs::create_dir_all(&scratch_dir)?;
+    let result = async {
+        let mut writer =
+            SnapshotExportWriter::new(BufWriter::new(File::create(&partial)?), &metadata)?;
+        for file in &metadata.files {
+            let path = scanner
+                .fetch_file(&job_id, run_id, &file.path, file.size, &scratch_dir)
+                .await?;
+            writer.append(&file.path, file.size, File::open(&path)?)?;
+            if path.starts_with(&scratch_dir) {
+                std::fs::remove_file(&path)?;
+            }
+        }
+        writer
+            .finish()?
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        Ok::<_, anyhow::Error>(())
+    }
+    .await;
+    let _ = std::fs::remove_dir_all(&scratch_dir);
+    if let Err(error) = result {
+        let _ = std::fs::remove_file(&partial);
+        return Err(error);
+    }
+    std::fs::rename(&partial, &args.out)?;
+
+    let total_bytes = metadata.files.iter().map(|f| f.size).sum::<u64>();
+    println!(
+        "exported run {run_id} ({} file(s), {total_bytes} bytes) to {}",
+        metadata.files.len(),
+        args.out.display()
+    );
+    print_decryption_hint(&metadata);
+    Ok(())
+}
+
+fn import(args: SnapshotImportArgs) -> Result<(), anyhow::Error> {
+    std::fs::create_dir_all(&args.target)?;
+    let archive = File::open(&args.r#in)?;
+    let metadata = snapshot_export::import_snapshot_export(BufReader::new(archive), &args.target)?;
+    println!(
+        "imported run {} of job {} into {}",
+        metadata.run_id,
+        metadata.job_id,
+        args.target.display()
+    );
+    println!(
+        "register it with `bastion recover --target {}`",
+        args.target.display()
+    );
+    print_decryption_hint(&metadata);
+    Ok(())
+}
+
+fn print_decryption_hint(metadata: &SnapshotExportMetadata) {
+    if metadata.encryption.kind != "age" {
+        return;
+    }
+    match metadata.encryption.key_name.as_deref() {
+        Some(key_name) => println!(
+            "payload is encrypted; restores need the backup encryption key `{key_name}` (keep a keypack with the archive)"
+        ),
+        None => println!("payload is encrypted; restores need the matching age identity"),
+    }
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{suffix}"));
+    path.with_file_name(name)
+}
@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use bastion_core::agent;
+use bastion_storage::{audit_repo, auth, job_access_repo};
+
+use crate::config::UserCommand;
+
+// Matches the minimum the Web UI enforces for new passwords.
+const MIN_PASSWORD_LEN: usize = 12;
+
+pub async fn run(command: UserCommand, data_dir: &Path) -> Result<(), anyhow::Error> {
+    let db = bastion_storage::db::init(data_dir).await?;
+
+    match command {
+        UserCommand::ResetPassword(args) => {
+            let username = args.user.trim();
+            let user = auth::find_user_by_username(&db, username)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("user not found: {username}"))?;
+
+            let password = if args.password_stdin {
+                read_password_stdin()?
+            } else {
+                agent::generate_token_b64_urlsafe(18)
+            };
+            if password.len() < MIN_PASSWORD_LEN {
+                anyhow::bail!("password must be at least {MIN_PASSWORD_LEN} characters");
+            }
+
+            auth::set_password(&db, user.id, &password, None).await?;
+            auth::clear_login_user_throttle(&db, &user.username).await?;
+
+            let now = time::OffsetDateTime::now_utc().unix_timestamp();
+            audit_repo::append(
+                &db,
+                now,
+                audit_repo::ACTION_PASSWORD_RESET,
+                None,
+                None,
+                Some(&user.username),
+            )
+            .await?;
+
+            if args.password_stdin {
+                println!("reset the password of {}", user.username);
+            } else {
+                eprintln!(
+                    "reset the password of {}; the new password is shown only once:",
+                    user.username
+                );
+                println!("{password}");
+            }
+        }
+        UserCommand::SetAdmin(args) => {
+            let username = args.user.trim();
+            let user = auth::find_user_by_username(&db, username)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("user not found: {username}"))?;
+
+            job_access_repo::set_admin(&db, user.id, !args.revoke).await?;
+
+            let now = time::OffsetDateTime::now_utc().unix_timestamp();
+            let detail = format!("{} admin={}", user.username, !args.revoke);
+            audit_repo::append(
+                &db,
+                now,
+                audit_repo::ACTION_USER_ADMIN_CHANGED,
+                None,
+                None,
+                Some(&detail),
+            )
+            .await?;
+
+            if args.revoke {
+                println!("{} is no longer an admin", user.username);
+            } else {
+                println!("{} is now an admin", user.username);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_password_stdin() -> Result<String, anyhow::Error> {
+    use std::io::Read as _;
+    let mut buf = String::new();
+    std::io::stdin().read_to_string(&mut buf)?;
+    let trimmed = buf.trim_end_matches(&['\r', '\n'][..]).to_string();
+    if trimmed.is_empty() {
+        anyhow::bail!("password from stdin is empty");
+    }
+    Ok(trimmed)
+}
@@ -65,12 +65,20 @@ fn service_main_inner() -> Result<(), anyhow::Error> {
         // We parse `bastion` with no CLI args so clap applies defaults and reads `BASTION_*` env vars.
         let locale = crate::i18n::cli::resolve_cli_locale();
         let cmd = crate::i18n::cli::localize_command(Cli::command(), locale);
-        let matches = cmd.get_matches_from(vec![OsString::from("bastion")]);
-        let runtime_env = crate::RuntimeEnv::capture();
+        // `BASTION_CONFIG` can point at a config file.
+        let mut runtime_env = crate::RuntimeEnv::capture();
+        let (argv, file_args) = crate::config_file::apply(
+            &cmd,
+            vec![OsString::from("bastion")],
+            runtime_env.rust_log.is_some(),
+        )?;
+        runtime_env.file_args = file_args;
+        let matches = cmd.get_matches_from(argv);
         let Cli {
             command,
             hub,
             mut logging,
+            ..
         } = Cli::from_arg_matches(&matches)?;
 
         if command.is_some() {
@@ -82,13 +82,22 @@ export default defineConfig(({ command }) => {
                   { text: 'Logging', link: '/user/operations/logging' },
                   { text: 'Maintenance', link: '/user/operations/maintenance' },
                   { text: 'Data directory', link: '/user/operations/data-directory' },
+                  { text: 'Warm standby', link: '/user/operations/standby' },
                   { text: 'Reverse proxy', link: '/user/operations/reverse-proxy' },
                   { text: 'Observability', link: '/user/operations/observability' },
                 ],
               },
               {
                 text: 'Recipes',
-                items: [{ text: 'Vaultwarden', link: '/user/recipes/vaultwarden' }],
+                items: [
+                  { text: 'Vaultwarden', link: '/user/recipes/vaultwarden' },
+                  { text: 'App directory + SQLite', link: '/user/recipes/sqlite-app' },
+                  { text: 'PostgreSQL point-in-time recovery', link: '/user/recipes/postgres-pitr' },
+                  { text: 'libvirt VM images', link: '/user/recipes/libvirt-vm' },
+                  { text: 'Kubernetes PVCs', link: '/user/recipes/kubernetes-pvc' },
+                  { text: 'IMAP mailboxes', link: '/user/recipes/imap' },
+                  { text: 'Git mirrors', link: '/user/recipes/git-mirror' },
+                ],
               },
               {
                 text: 'Reference',
@@ -173,13 +182,22 @@ export default defineConfig(({ command }) => {
                   { text: '日志', link: '/zh/user/operations/logging' },
                   { text: '不完整运行清理', link: '/zh/user/operations/maintenance' },
                   { text: '数据目录', link: '/zh/user/operations/data-directory' },
+                  { text: '热备', link: '/zh/user/operations/standby' },
                   { text: '反向代理', link: '/zh/user/operations/reverse-proxy' },
                   { text: '可观测性', link: '/zh/user/operations/observability' },
                 ],
               },
               {
                 text: '配方',
-                items: [{ text: 'Vaultwarden', link: '/zh/user/recipes/vaultwarden' }],
+                items: [
+                  { text: 'Vaultwarden', link: '/zh/user/recipes/vaultwarden' },
+                  { text: '应用目录 + SQLite', link: '/zh/user/recipes/sqlite-app' },
+                  { text: 'PostgreSQL 时间点恢复', link: '/zh/user/recipes/postgres-pitr' },
+                  { text: 'libvirt 虚拟机镜像', link: '/zh/user/recipes/libvirt-vm' },
+                  { text: 'Kubernetes PVC', link: '/zh/user/recipes/kubernetes-pvc' },
+                  { text: 'IMAP 邮箱', link: '/zh/user/recipes/imap' },
+                  { text: 'Git 镜像', link: '/zh/user/recipes/git-mirror' },
+                ],
               },
               {
                 text: '参考',
@@ -119,6 +119,41 @@ describe('JobDeployModal', () => {
     expect(routerPush).toHaveBeenCalledWith({ path: '/system/bulk-operations', query: { open: 'op-1' } })
   })
 
+  it('uses the sync kind for create-or-update mode', async () => {
+    bulkOpsApi.previewJobDeploy.mockResolvedValue({
+      items: [
+        {
+          agent_id: 'agent-1',
+          agent_name: 'Agent 1',
+          planned_name: 'Daily Backup (agent-1)',
+          valid: true,
+          error: null,
+          action: 'update',
+          existing_job_id: 'job-2',
+        },
+      ],
+    })
+    bulkOpsApi.create.mockResolvedValue('op-2')
+
+    const wrapper = mount(JobDeployModal)
+    const vm = wrapper.vm as unknown as {
+      open: (jobId: string) => Promise<void>
+      selectedLabels: string[]
+      deployKind: string
+      previewDeploy: () => Promise<void>
+      deploy: () => Promise<void>
+    }
+
+    await vm.open('job-1')
+    vm.deployKind = 'job_sync'
+    vm.selectedLabels = ['prod']
+    await vm.previewDeploy()
+    expect(bulkOpsApi.previewJobDeploy).toHaveBeenCalledWith(expect.objectContaining({ kind: 'job_sync' }))
+
+    await vm.deploy()
+    expect(bulkOpsApi.create).toHaveBeenCalledWith(expect.objectContaining({ kind: 'job_sync' }))
+  })
+
   it('blocks deploy when preview is missing', async () => {
     const wrapper = mount(JobDeployModal)
     const vm = wrapper.vm as unknown as {
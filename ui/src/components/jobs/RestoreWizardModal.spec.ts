@@ -13,6 +13,7 @@ const secretsApi = {
 
 const operationsApi = {
   startRestore: vi.fn(),
+  previewRestore: vi.fn(),
 }
 
 const messageApi = {
@@ -125,4 +126,36 @@ describe('RestoreWizardModal', () => {
     expect(operationsApi.startRestore).toHaveBeenCalled()
     expect(wrapper.emitted('started')?.[0]).toEqual(['op-restore-1'])
   })
+
+  it('estimates restore size with the destination when it is complete', async () => {
+    operationsApi.previewRestore.mockResolvedValue({
+      files: 3,
+      dirs: 1,
+      symlinks: 0,
+      bytes: 2048,
+      space: { status: 'checked', available_bytes: 1024, sufficient: false },
+    })
+
+    const wrapper = mount(RestoreWizardModal)
+    const vm = wrapper.vm as unknown as {
+      open: (runId: string, opts?: { defaultNodeId?: string | null }) => void
+      estimate: () => Promise<void>
+      localFsDirectory: string
+    }
+
+    vm.open('run-3')
+    await vm.estimate()
+    expect(operationsApi.previewRestore).toHaveBeenLastCalledWith('run-3', null, null)
+
+    vm.localFsDirectory = '/restore/dir'
+    await vm.estimate()
+    expect(operationsApi.previewRestore).toHaveBeenLastCalledWith(
+      'run-3',
+      { type: 'local_fs', node_id: 'hub', directory: '/restore/dir' },
+      null,
+    )
+    await wrapper.vm.$nextTick()
+    expect(wrapper.text()).toContain('restore.preview.spaceInsufficient')
+    expect(messageApi.error).not.toHaveBeenCalled()
+  })
 })
@@ -46,6 +46,11 @@ vi.mock('naive-ui', async () => {
   return {
     NAlert: stub('NAlert'),
     NButton: stub('NButton'),
+    NFormItem: stub('NFormItem'),
+    NInput: stub('NInput'),
+    NInputNumber: stub('NInputNumber'),
+    NRadio: stub('NRadio'),
+    NRadioGroup: stub('NRadioGroup'),
     useMessage: () => messageApi,
   }
 })
@@ -70,6 +75,42 @@ describe('VerifyWizardModal', () => {
     expect(wrapper.emitted('started')?.[0]).toEqual(['op-verify-1'])
   })
 
+  it('starts sample verify with the chosen percentage', async () => {
+    operationsApi.startVerify.mockResolvedValue('op-verify-2')
+
+    const wrapper = mount(VerifyWizardModal)
+    const vm = wrapper.vm as unknown as {
+      open: (runId: string) => void
+      start: () => Promise<void>
+      mode: string
+      samplePercent: number
+    }
+
+    vm.open('run-2')
+    vm.mode = 'sample'
+    vm.samplePercent = 25
+    await vm.start()
+
+    expect(operationsApi.startVerify).toHaveBeenCalledWith('run-2', null, { mode: 'sample', samplePercent: 25 })
+  })
+
+  it('starts stream verify', async () => {
+    operationsApi.startVerify.mockResolvedValue('op-verify-3')
+
+    const wrapper = mount(VerifyWizardModal)
+    const vm = wrapper.vm as unknown as {
+      open: (runId: string) => void
+      start: () => Promise<void>
+      mode: string
+    }
+
+    vm.open('run-3')
+    vm.mode = 'stream'
+    await vm.start()
+
+    expect(operationsApi.startVerify).toHaveBeenCalledWith('run-3', null, { mode: 'stream' })
+  })
+
   it('shows error feedback when verify start fails', async () => {
     operationsApi.startVerify.mockRejectedValue(new Error('boom'))
 
@@ -7,6 +7,8 @@ export function createInitialJobEditorForm(): JobEditorForm {
     node: 'hub',
     schedule: '',
     scheduleTimezone: 'UTC',
+    scheduleJitterSeconds: 0,
+    maxSuccessIntervalHours: 0,
     scheduleMode: 'manual',
     simpleScheduleKind: 'daily',
     simpleEveryMinutes: 15,
@@ -17,11 +19,23 @@ export function createInitialJobEditorForm(): JobEditorForm {
     overlapPolicy: 'queue',
     jobType: 'filesystem',
     artifactFormat: 'archive_v1',
+    compressionAlgorithm: 'zstd',
+    compressionLevel: null,
+    compressionAdaptive: false,
     encryptionEnabled: false,
     encryptionKeyName: 'default',
+    encryptionPassphraseKey: false,
     fsPaths: [],
     fsInclude: '',
     fsExclude: '',
+    fsExcludePresets: [],
+    fsUseIgnoreFiles: false,
+    fsWindowsSystemState: false,
+    fsAnomalyDetection: false,
+    fsAnomalyDetectionSettings: {},
+    fsMaxFileSizeMiB: null,
+    fsSkipOlderThanDays: null,
+    fsSkipNewerThanMinutes: null,
     fsPreScan: true,
     fsSymlinkPolicy: 'keep',
     fsHardlinkPolicy: 'copy',
@@ -33,10 +47,36 @@ export function createInitialJobEditorForm(): JobEditorForm {
     fsUploadOnConsistencyFailure: false,
     sqlitePath: '',
     sqliteIntegrityCheck: false,
+    sqliteDatabases: [],
     vaultwardenDataDir: '',
     vaultwardenConsistencyPolicy: 'warn',
     vaultwardenConsistencyFailThreshold: 0,
     vaultwardenUploadOnConsistencyFailure: false,
+    sqliteAppDataDir: '',
+    sqliteAppDbPath: '',
+    sqliteAppConsistencyPolicy: 'warn',
+    sqliteAppConsistencyFailThreshold: 0,
+    sqliteAppUploadOnConsistencyFailure: false,
+    postgresConnection: '',
+    postgresWalSpoolDir: '',
+    postgresBaseBackupIntervalHours: null,
+    vmImageDomain: '',
+    vmImageDisks: '',
+    vmImageQuiesce: false,
+    vmImageConnectUri: '',
+    kubernetesPvcNamespace: '',
+    kubernetesPvcLabelSelector: '',
+    kubernetesPvcMode: 'mounted',
+    kubernetesPvcMountRoot: '',
+    kubernetesPvcSnapshotClass: '',
+    kubernetesPvcReaderImage: '',
+    imapHost: '',
+    imapPort: null,
+    imapSecurity: 'tls',
+    imapSecretName: '',
+    imapIncludeFolders: '',
+    imapExcludeFolders: '',
+    gitRepositories: '',
     targetType: 'webdav',
     webdavBaseUrl: '',
     webdavSecretName: '',
@@ -50,14 +90,20 @@ export function createInitialJobEditorForm(): JobEditorForm {
     webdavRawTreeDirectRequestTimeoutSecs: 60,
     webdavRawTreeDirectConnectTimeoutSecs: null,
     webdavRawTreeDirectMaxPutAttempts: 3,
+    webdavPartUploadConcurrency: 1,
     localBaseDir: '',
+    localLinkUnchanged: false,
     partSizeMiB: 256,
     notifyMode: 'inherit',
     notifyWecomBots: [],
     notifyEmails: [],
+    notifySlack: [],
+    notifyDiscord: [],
+    notifySms: [],
     retentionEnabled: false,
     retentionKeepLast: null,
     retentionKeepDays: null,
+    retentionLockDays: null,
     retentionMaxDeletePerTick: 50,
     retentionMaxDeletePerDay: 200,
   }
@@ -87,6 +133,31 @@ export function createInitialJobEditorFieldErrors(): Record<JobEditorField, stri
     vaultwardenConsistencyPolicy: null,
     vaultwardenConsistencyFailThreshold: null,
     vaultwardenUploadOnConsistencyFailure: null,
+    sqliteAppDataDir: null,
+    sqliteAppDbPath: null,
+    sqliteAppConsistencyPolicy: null,
+    sqliteAppConsistencyFailThreshold: null,
+    sqliteAppUploadOnConsistencyFailure: null,
+    postgresConnection: null,
+    postgresWalSpoolDir: null,
+    postgresBaseBackupIntervalHours: null,
+    vmImageDomain: null,
+    vmImageDisks: null,
+    vmImageQuiesce: null,
+    vmImageConnectUri: null,
+    kubernetesPvcNamespace: null,
+    kubernetesPvcLabelSelector: null,
+    kubernetesPvcMode: null,
+    kubernetesPvcMountRoot: null,
+    kubernetesPvcSnapshotClass: null,
+    kubernetesPvcReaderImage: null,
+    imapHost: null,
+    imapPort: null,
+    imapSecurity: null,
+    imapSecretName: null,
+    imapIncludeFolders: null,
+    imapExcludeFolders: null,
+    gitRepositories: null,
     webdavBaseUrl: null,
     webdavSecretName: null,
     webdavRawTreeDirectMode: null,
@@ -98,8 +169,10 @@ export function createInitialJobEditorFieldErrors(): Record<JobEditorField, stri
     webdavRawTreeDirectRequestTimeoutSecs: null,
     webdavRawTreeDirectConnectTimeoutSecs: null,
     webdavRawTreeDirectMaxPutAttempts: null,
+    webdavPartUploadConcurrency: null,
     localBaseDir: null,
     partSizeMiB: null,
+    compressionLevel: null,
     encryptionKeyName: null,
   }
 }
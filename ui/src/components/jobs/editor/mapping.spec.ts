@@ -70,6 +70,26 @@ describe('jobDetailToEditorForm', () => {
     const form = jobDetailToEditorForm(job)
     expect(form.encryptionEnabled).toBe(true)
     expect(form.encryptionKeyName).toBe('my-key')
+    expect(form.encryptionPassphraseKey).toBe(false)
+  })
+
+  it('round-trips passphrase-protected encryption keys', () => {
+    const job = createJobDetail({
+      v: 1,
+      type: 'filesystem',
+      pipeline: { encryption: { type: 'age_passphrase', key_name: 'offline' } },
+      notifications: { mode: 'inherit' },
+      source: { paths: ['/tmp'] },
+      target: { type: 'local_dir', base_dir: '/backups', part_size_bytes: 256 * 1024 * 1024 },
+    })
+    const form = jobDetailToEditorForm(job)
+    expect(form.encryptionEnabled).toBe(true)
+    expect(form.encryptionPassphraseKey).toBe(true)
+    expect(form.encryptionKeyName).toBe('offline')
+
+    const spec = editorFormToRequest(form).spec as Record<string, unknown>
+    const pipeline = spec['pipeline'] as Record<string, unknown>
+    expect(pipeline['encryption']).toEqual({ type: 'age_passphrase', key_name: 'offline' })
   })
 
   it('parses raw-tree format and forces encryption off', () => {
@@ -234,6 +254,85 @@ describe('editorFormToRequest', () => {
     const spec = req.spec as Record<string, unknown>
     const target = spec['target'] as Record<string, unknown>
     expect(target['part_size_bytes']).toBe(10 * 1024 * 1024)
+    expect(target['link_unchanged']).toBe(false)
+
+    form.localLinkUnchanged = true
+    const linked = editorFormToRequest(form).spec as Record<string, unknown>
+    expect((linked['target'] as Record<string, unknown>)['link_unchanged']).toBe(true)
+    expect(jobDetailToEditorForm(createJobDetail(linked)).localLinkUnchanged).toBe(true)
+  })
+
+  it('round-trips additional sqlite databases', () => {
+    const form = createInitialJobEditorForm()
+    form.name = 'Stack'
+    form.jobType = 'sqlite'
+    form.sqlitePath = '/srv/app/main.db'
+    form.sqliteIntegrityCheck = true
+    form.sqliteDatabases = [
+      { path: ' /srv/app/cache.db ', integrityCheck: false },
+      { path: ' ', integrityCheck: true },
+    ]
+    form.targetType = 'local_dir'
+    form.localBaseDir = '/tmp/backups'
+
+    const spec = editorFormToRequest(form).spec as Record<string, unknown>
+    expect(spec['source']).toEqual({
+      path: '/srv/app/main.db',
+      integrity_check: true,
+      databases: [{ path: '/srv/app/cache.db', integrity_check: false }],
+    })
+
+    const listOnly = { ...spec, source: { databases: [{ path: '/a.db', integrity_check: true }, { path: '/b.db' }] } }
+    const parsed = jobDetailToEditorForm(createJobDetail(listOnly))
+    expect(parsed.sqlitePath).toBe('/a.db')
+    expect(parsed.sqliteIntegrityCheck).toBe(true)
+    expect(parsed.sqliteDatabases).toEqual([{ path: '/b.db', integrityCheck: false }])
+  })
+
+  it('round-trips imap source folders and omits the default port', () => {
+    const form = createInitialJobEditorForm()
+    form.name = 'Mail'
+    form.jobType = 'imap'
+    form.artifactFormat = 'raw_tree_v1'
+    form.imapHost = ' imap.example.com '
+    form.imapSecretName = 'mail'
+    form.imapIncludeFolders = 'INBOX\n\nArchive/*'
+    form.imapExcludeFolders = 'Trash'
+    form.targetType = 'local_dir'
+    form.localBaseDir = '/tmp/backups'
+
+    const spec = editorFormToRequest(form).spec as Record<string, unknown>
+    expect(spec['source']).toEqual({
+      host: 'imap.example.com',
+      security: 'tls',
+      secret_name: 'mail',
+      include_folders: ['INBOX', 'Archive/*'],
+      exclude_folders: ['Trash'],
+    })
+
+    const parsed = jobDetailToEditorForm(
+      createJobDetail({ ...spec, source: { host: 'h', port: 143, security: 'starttls', secret_name: 'm' } }),
+    )
+    expect(parsed.imapPort).toBe(143)
+    expect(parsed.imapSecurity).toBe('starttls')
+    expect(parsed.imapIncludeFolders).toBe('')
+  })
+
+  it('maps git repository lines with optional names', () => {
+    const form = createInitialJobEditorForm()
+    form.name = 'Forge'
+    form.jobType = 'git'
+    form.gitRepositories = 'https://github.com/acme/app.git\n\ngit@git.example.com:ops/app.git  ops-app'
+    form.targetType = 'local_dir'
+    form.localBaseDir = '/tmp/backups'
+
+    const spec = editorFormToRequest(form).spec as Record<string, unknown>
+    expect(spec['source']).toEqual({
+      repositories: [{ url: 'https://github.com/acme/app.git' }, { url: 'git@git.example.com:ops/app.git', name: 'ops-app' }],
+    })
+
+    const parsed = jobDetailToEditorForm(createJobDetail(spec))
+    expect(parsed.gitRepositories).toBe('https://github.com/acme/app.git\ngit@git.example.com:ops/app.git ops-app')
   })
 
   it('forces pipeline.encryption=none for raw-tree', () => {
@@ -254,6 +353,61 @@ describe('editorFormToRequest', () => {
     expect(pipeline['encryption']).toEqual({ type: 'none' })
   })
 
+  it('round-trips compression settings and drops levels for lz4', () => {
+    const job = createJobDetail({
+      v: 1,
+      type: 'filesystem',
+      pipeline: { compression: { algorithm: 'xz', level: 9 }, encryption: { type: 'none' } },
+      notifications: { mode: 'inherit' },
+      source: { paths: ['/tmp'] },
+      target: { type: 'local_dir', base_dir: '/backups', part_size_bytes: 256 * 1024 * 1024 },
+    })
+    const form = jobDetailToEditorForm(job)
+    expect(form.compressionAlgorithm).toBe('xz')
+    expect(form.compressionLevel).toBe(9)
+
+    let pipeline = (editorFormToRequest(form).spec as Record<string, unknown>)['pipeline'] as Record<string, unknown>
+    expect(pipeline['compression']).toEqual({ algorithm: 'xz', level: 9 })
+
+    form.compressionAlgorithm = 'zstd'
+    form.compressionLevel = null
+    form.compressionAdaptive = true
+    pipeline = (editorFormToRequest(form).spec as Record<string, unknown>)['pipeline'] as Record<string, unknown>
+    expect(pipeline['compression']).toEqual({ algorithm: 'zstd', adaptive: true })
+
+    form.compressionAlgorithm = 'lz4'
+    pipeline = (editorFormToRequest(form).spec as Record<string, unknown>)['pipeline'] as Record<string, unknown>
+    expect(pipeline['compression']).toEqual({ algorithm: 'lz4' })
+
+    form.artifactFormat = 'raw_tree_v1'
+    pipeline = (editorFormToRequest(form).spec as Record<string, unknown>)['pipeline'] as Record<string, unknown>
+    expect(pipeline['compression']).toBeUndefined()
+  })
+
+  it('round-trips webdav part upload concurrency for archive jobs', () => {
+    const job = createJobDetail({
+      v: 1,
+      type: 'filesystem',
+      pipeline: { webdav: { part_upload_concurrency: 4 }, encryption: { type: 'none' } },
+      notifications: { mode: 'inherit' },
+      source: { paths: ['/tmp'] },
+      target: { type: 'webdav', base_url: 'https://dav.example/backup', secret_name: 'dav', part_size_bytes: 1024 },
+    })
+    const form = jobDetailToEditorForm(job)
+    expect(form.webdavPartUploadConcurrency).toBe(4)
+
+    let webdav = ((editorFormToRequest(form).spec as Record<string, unknown>)['pipeline'] as Record<string, unknown>)[
+      'webdav'
+    ] as Record<string, unknown>
+    expect(webdav['part_upload_concurrency']).toBe(4)
+
+    form.webdavPartUploadConcurrency = 1
+    webdav = ((editorFormToRequest(form).spec as Record<string, unknown>)['pipeline'] as Record<string, unknown>)[
+      'webdav'
+    ] as Record<string, unknown>
+    expect(webdav['part_upload_concurrency']).toBeUndefined()
+  })
+
   it('includes filesystem pre_scan in request spec', () => {
     const form = createInitialJobEditorForm()
     form.name = 'Demo'
@@ -267,6 +421,39 @@ describe('editorFormToRequest', () => {
     const spec = req.spec as Record<string, unknown>
     const source = spec['source'] as Record<string, unknown>
     expect(source['pre_scan']).toBe(false)
+    expect(source['use_ignore_files']).toBe(false)
+
+    form.fsUseIgnoreFiles = true
+    const withIgnore = editorFormToRequest(form).spec as Record<string, unknown>
+    expect((withIgnore['source'] as Record<string, unknown>)['use_ignore_files']).toBe(true)
+    expect(jobDetailToEditorForm(createJobDetail(withIgnore)).fsUseIgnoreFiles).toBe(true)
+    expect((withIgnore['source'] as Record<string, unknown>)['windows_system_state']).toBeUndefined()
+
+    form.fsWindowsSystemState = true
+    const withState = editorFormToRequest(form).spec as Record<string, unknown>
+    expect((withState['source'] as Record<string, unknown>)['windows_system_state']).toBe(true)
+    expect(jobDetailToEditorForm(createJobDetail(withState)).fsWindowsSystemState).toBe(true)
+  })
+
+  it('round-trips anomaly detection and keeps custom thresholds', () => {
+    const form = createInitialJobEditorForm()
+    form.name = 'Demo'
+    form.jobType = 'filesystem'
+    form.fsPaths = ['/tmp']
+    form.targetType = 'local_dir'
+    form.localBaseDir = '/tmp/backups'
+
+    const plain = editorFormToRequest(form).spec as Record<string, unknown>
+    expect('anomaly_detection' in (plain['source'] as Record<string, unknown>)).toBe(false)
+
+    form.fsAnomalyDetection = true
+    form.fsAnomalyDetectionSettings = { changed_percent: 30 }
+    const spec = editorFormToRequest(form).spec as Record<string, unknown>
+    expect((spec['source'] as Record<string, unknown>)['anomaly_detection']).toEqual({ changed_percent: 30 })
+
+    const back = jobDetailToEditorForm(createJobDetail(spec))
+    expect(back.fsAnomalyDetection).toBe(true)
+    expect(back.fsAnomalyDetectionSettings).toEqual({ changed_percent: 30 })
   })
 
   it('includes snapshot settings in filesystem source spec', () => {
@@ -286,6 +473,33 @@ describe('editorFormToRequest', () => {
     expect(source['snapshot_provider']).toBe('btrfs')
   })
 
+  it('round-trips filesystem size, age and preset filters', () => {
+    const form = createInitialJobEditorForm()
+    form.name = 'Demo'
+    form.jobType = 'filesystem'
+    form.fsPaths = ['/tmp']
+    form.targetType = 'local_dir'
+    form.localBaseDir = '/tmp/backups'
+    form.fsExcludePresets = ['node_modules', 'caches']
+    form.fsMaxFileSizeMiB = 512
+    form.fsSkipOlderThanDays = 30
+    form.fsSkipNewerThanMinutes = 5
+
+    const req = editorFormToRequest(form)
+    const spec = req.spec as Record<string, unknown>
+    const source = spec['source'] as Record<string, unknown>
+    expect(source['exclude_presets']).toEqual(['node_modules', 'caches'])
+    expect(source['max_file_size_bytes']).toBe(512 * 1024 * 1024)
+    expect(source['skip_older_than_secs']).toBe(30 * 86400)
+    expect(source['skip_newer_than_secs']).toBe(300)
+
+    const parsed = jobDetailToEditorForm(createJobDetail(spec))
+    expect(parsed.fsExcludePresets).toEqual(['node_modules', 'caches'])
+    expect(parsed.fsMaxFileSizeMiB).toBe(512)
+    expect(parsed.fsSkipOlderThanDays).toBe(30)
+    expect(parsed.fsSkipNewerThanMinutes).toBe(5)
+  })
+
   it('includes consistency policy fields in filesystem source spec', () => {
     const form = createInitialJobEditorForm()
     form.name = 'Demo'
@@ -368,6 +582,23 @@ describe('editorFormToRequest', () => {
     })
   })
 
+  it('keeps retention lock days even when retention is disabled', () => {
+    const form = createInitialJobEditorForm()
+    form.name = 'Demo'
+    form.jobType = 'filesystem'
+    form.fsPaths = ['/tmp']
+    form.targetType = 'local_dir'
+    form.localBaseDir = '/tmp/backups'
+    form.retentionLockDays = 14
+
+    const req = editorFormToRequest(form)
+    const spec = req.spec as Record<string, unknown>
+    expect(spec.retention).toMatchObject({ enabled: false, lock_days: 14 })
+
+    const back = jobDetailToEditorForm(createJobDetail(req.spec as Record<string, unknown>))
+    expect(back.retentionLockDays).toBe(14)
+  })
+
   it('includes webdav raw-tree direct upload settings in pipeline spec', () => {
     const form = createInitialJobEditorForm()
     form.name = 'Demo'
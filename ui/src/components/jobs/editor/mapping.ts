@@ -2,12 +2,17 @@ import type { CreateOrUpdateJobRequest, JobDetail } from '@/stores/jobs'
 
 import type {
   ArtifactFormat,
+  CompressionAlgorithm,
   ConsistencyPolicy,
   FsErrorPolicy,
+  FsExcludePreset,
   FsHardlinkPolicy,
   FsSymlinkPolicy,
   JobEditorForm,
+  ImapSecurity,
+  KubernetesPvcMode,
   SnapshotMode,
+  SqliteDatabaseEntry,
   WebdavRawTreeDirectMode,
 } from './types'
 import { cronToSimpleSchedule } from './schedule'
@@ -17,6 +22,16 @@ function parseStringArray(value: unknown): string[] {
   return value.filter((v): v is string => typeof v === 'string')
 }
 
+function parseSqliteDatabases(value: unknown): SqliteDatabaseEntry[] {
+  if (!Array.isArray(value)) return []
+  return value
+    .filter((db): db is Record<string, unknown> => !!db && typeof db === 'object')
+    .map((db) => ({
+      path: typeof db.path === 'string' ? db.path : '',
+      integrityCheck: typeof db.integrity_check === 'boolean' ? db.integrity_check : false,
+    }))
+}
+
 function parseLines(text: string): string[] {
   return text
     .split(/\r?\n/g)
@@ -24,6 +39,27 @@ function parseLines(text: string): string[] {
     .filter((line) => line.length > 0)
 }
 
+// Repositories are edited one per line as `<url>` or `<url> <name>`.
+function parseGitRepositories(text: string): Array<{ url: string; name?: string }> {
+  return parseLines(text).map((line) => {
+    const [url = '', name] = line.split(/\s+/, 2)
+    return name ? { url, name } : { url }
+  })
+}
+
+function formatGitRepositories(value: unknown): string {
+  if (!Array.isArray(value)) return ''
+  return value
+    .filter((repo): repo is Record<string, unknown> => !!repo && typeof repo === 'object')
+    .map((repo) => {
+      const url = typeof repo.url === 'string' ? repo.url.trim() : ''
+      const name = typeof repo.name === 'string' ? repo.name.trim() : ''
+      return name ? `${url} ${name}` : url
+    })
+    .filter((line) => line.length > 0)
+    .join('\n')
+}
+
 function normalizeSymlinkPolicy(value: unknown): FsSymlinkPolicy {
   if (value === 'follow') return 'follow'
   if (value === 'skip') return 'skip'
@@ -41,6 +77,14 @@ function normalizeErrorPolicy(value: unknown): FsErrorPolicy {
   return 'fail_fast'
 }
 
+function parseExcludePresets(value: unknown): FsExcludePreset[] {
+  return parseStringArray(value).filter((v): v is FsExcludePreset => v === 'node_modules' || v === 'caches')
+}
+
+function positiveNumberOrNull(value: unknown): number | null {
+  return typeof value === 'number' && value > 0 ? value : null
+}
+
 function normalizeConsistencyPolicy(value: unknown): ConsistencyPolicy {
   if (value === 'fail') return 'fail'
   if (value === 'ignore') return 'ignore'
@@ -53,11 +97,24 @@ function normalizeSnapshotMode(value: unknown): SnapshotMode {
   return 'off'
 }
 
+function normalizeKubernetesPvcMode(value: unknown): KubernetesPvcMode {
+  return value === 'csi_snapshot' ? 'csi_snapshot' : 'mounted'
+}
+
+function normalizeImapSecurity(value: unknown): ImapSecurity {
+  return value === 'starttls' ? 'starttls' : 'tls'
+}
+
 function normalizeArtifactFormat(value: unknown): ArtifactFormat {
   if (value === 'raw_tree_v1') return 'raw_tree_v1'
   return 'archive_v1'
 }
 
+function normalizeCompressionAlgorithm(value: unknown): CompressionAlgorithm {
+  if (value === 'lz4' || value === 'xz' || value === 'none') return value
+  return 'zstd'
+}
+
 function normalizeWebdavRawTreeDirectMode(value: unknown): WebdavRawTreeDirectMode {
   if (value === 'auto') return 'auto'
   if (value === 'on') return 'on'
@@ -75,9 +132,16 @@ export function jobDetailToEditorForm(job: JobDetail): JobEditorForm {
 
   const pipeline = spec.pipeline as Record<string, unknown> | undefined
   const artifactFormat = normalizeArtifactFormat(pipeline?.format)
+  const compression = pipeline?.compression as Record<string, unknown> | undefined
+  const compressionAlgorithm = normalizeCompressionAlgorithm(compression?.algorithm)
+  const compressionLevel =
+    typeof compression?.level === 'number' && Number.isFinite(compression.level) ? Math.floor(compression.level) : null
+  const compressionAdaptive = compressionAlgorithm === 'zstd' && compression?.adaptive === true
   const enc = pipeline?.encryption as Record<string, unknown> | undefined
   const encType = typeof enc?.type === 'string' ? enc.type : 'none'
-  const encryptionEnabled = artifactFormat === 'raw_tree_v1' ? false : encType === 'age_x25519'
+  const encryptionEnabled =
+    artifactFormat === 'raw_tree_v1' ? false : encType === 'age_x25519' || encType === 'age_passphrase'
+  const encryptionPassphraseKey = encryptionEnabled && encType === 'age_passphrase'
   const encryptionKeyName =
     encryptionEnabled && typeof enc?.key_name === 'string' && enc.key_name.trim() ? enc.key_name : 'default'
 
@@ -119,6 +183,10 @@ export function jobDetailToEditorForm(job: JobDetail): JobEditorForm {
     typeof rawTreeDirectLimits?.max_put_attempts === 'number' && rawTreeDirectLimits.max_put_attempts > 0
       ? Math.floor(rawTreeDirectLimits.max_put_attempts)
       : 3
+  const webdavPartUploadConcurrency =
+    typeof pipelineWebdav?.part_upload_concurrency === 'number' && pipelineWebdav.part_upload_concurrency > 0
+      ? Math.floor(pipelineWebdav.part_upload_concurrency)
+      : 1
 
   const target = spec.target as Record<string, unknown> | undefined
   const targetType = target?.type === 'local_dir' ? 'local_dir' : 'webdav'
@@ -145,6 +213,21 @@ export function jobDetailToEditorForm(job: JobDetail): JobEditorForm {
     typeof source?.consistency_fail_threshold === 'number' && source.consistency_fail_threshold >= 0
       ? Math.floor(source.consistency_fail_threshold)
       : 0
+  const sqliteDatabases = parseSqliteDatabases(source?.databases)
+  let sqlitePath = typeof source?.path === 'string' ? source.path : ''
+  let sqliteIntegrityCheck = typeof source?.integrity_check === 'boolean' ? source.integrity_check : false
+  const firstSqliteDatabase = !sqlitePath ? sqliteDatabases.shift() : undefined
+  if (firstSqliteDatabase) {
+    sqlitePath = firstSqliteDatabase.path
+    sqliteIntegrityCheck = firstSqliteDatabase.integrityCheck
+  }
+  const fsMaxFileSizeBytes = positiveNumberOrNull(source?.max_file_size_bytes)
+  const fsSkipOlderThanSecs = positiveNumberOrNull(source?.skip_older_than_secs)
+  const fsSkipNewerThanSecs = positiveNumberOrNull(source?.skip_newer_than_secs)
+  const fsAnomalyDetection =
+    source?.anomaly_detection && typeof source.anomaly_detection === 'object' && !Array.isArray(source.anomaly_detection)
+      ? (source.anomaly_detection as Record<string, unknown>)
+      : null
   const fsUploadOnConsistencyFailure = typeof source?.upload_on_consistency_failure === 'boolean' ? source.upload_on_consistency_failure : false
 
   const notif = spec.notifications as Record<string, unknown> | undefined
@@ -154,6 +237,8 @@ export function jobDetailToEditorForm(job: JobDetail): JobEditorForm {
   const retentionEnabled = typeof retention?.enabled === 'boolean' ? retention.enabled : false
   const retentionKeepLast = typeof retention?.keep_last === 'number' ? retention.keep_last : null
   const retentionKeepDays = typeof retention?.keep_days === 'number' ? retention.keep_days : null
+  const retentionLockDays =
+    typeof retention?.lock_days === 'number' && retention.lock_days > 0 ? retention.lock_days : null
   const retentionMaxDeletePerTick =
     typeof retention?.max_delete_per_tick === 'number' && retention.max_delete_per_tick > 0 ? retention.max_delete_per_tick : 50
   const retentionMaxDeletePerDay =
@@ -161,6 +246,12 @@ export function jobDetailToEditorForm(job: JobDetail): JobEditorForm {
 
   const schedule = job.schedule ?? ''
   const scheduleTimezone = job.schedule_timezone || 'UTC'
+  const scheduleJitterSeconds =
+    typeof job.schedule_jitter_seconds === 'number' && job.schedule_jitter_seconds > 0 ? job.schedule_jitter_seconds : 0
+  const maxSuccessIntervalHours =
+    typeof job.max_success_interval_seconds === 'number' && job.max_success_interval_seconds > 0
+      ? Math.max(1, Math.round(job.max_success_interval_seconds / 3600))
+      : 0
   const simple = schedule.trim() ? cronToSimpleSchedule(schedule) : null
 
   const vaultwardenConsistencyPolicy = normalizeConsistencyPolicy(source?.consistency_policy)
@@ -177,6 +268,8 @@ export function jobDetailToEditorForm(job: JobDetail): JobEditorForm {
     node: job.agent_id ? job.agent_id : 'hub',
     schedule,
     scheduleTimezone,
+    scheduleJitterSeconds,
+    maxSuccessIntervalHours,
     scheduleMode: schedule.trim() ? (simple ? 'simple' : 'cron') : 'manual',
     simpleScheduleKind: simple?.kind ?? 'daily',
     simpleEveryMinutes: simple?.everyMinutes ?? 15,
@@ -187,11 +280,23 @@ export function jobDetailToEditorForm(job: JobDetail): JobEditorForm {
     overlapPolicy: job.overlap_policy,
     jobType: job.spec.type,
     artifactFormat,
+    compressionAlgorithm,
+    compressionLevel,
+    compressionAdaptive,
     encryptionEnabled,
     encryptionKeyName,
+    encryptionPassphraseKey,
     fsPaths,
     fsInclude: parseStringArray(source?.include).join('\n'),
     fsExclude: parseStringArray(source?.exclude).join('\n'),
+    fsExcludePresets: parseExcludePresets(source?.exclude_presets),
+    fsUseIgnoreFiles: typeof source?.use_ignore_files === 'boolean' ? source.use_ignore_files : false,
+    fsWindowsSystemState: source?.windows_system_state === true,
+    fsAnomalyDetection: fsAnomalyDetection !== null,
+    fsAnomalyDetectionSettings: fsAnomalyDetection ?? {},
+    fsMaxFileSizeMiB: fsMaxFileSizeBytes === null ? null : fsMaxFileSizeBytes / (1024 * 1024),
+    fsSkipOlderThanDays: fsSkipOlderThanSecs === null ? null : fsSkipOlderThanSecs / 86400,
+    fsSkipNewerThanMinutes: fsSkipNewerThanSecs === null ? null : fsSkipNewerThanSecs / 60,
     fsPreScan,
     fsSymlinkPolicy: normalizeSymlinkPolicy(source?.symlink_policy),
     fsHardlinkPolicy: normalizeHardlinkPolicy(source?.hardlink_policy),
@@ -201,12 +306,41 @@ export function jobDetailToEditorForm(job: JobDetail): JobEditorForm {
     fsConsistencyPolicy,
     fsConsistencyFailThreshold,
     fsUploadOnConsistencyFailure,
-    sqlitePath: typeof source?.path === 'string' ? source.path : '',
-    sqliteIntegrityCheck: typeof source?.integrity_check === 'boolean' ? source.integrity_check : false,
+    sqlitePath,
+    sqliteIntegrityCheck,
+    sqliteDatabases,
     vaultwardenDataDir: typeof source?.data_dir === 'string' ? source.data_dir : '',
     vaultwardenConsistencyPolicy,
     vaultwardenConsistencyFailThreshold,
     vaultwardenUploadOnConsistencyFailure,
+    sqliteAppDataDir: typeof source?.data_dir === 'string' ? source.data_dir : '',
+    sqliteAppDbPath: typeof source?.db_path === 'string' ? source.db_path : '',
+    sqliteAppConsistencyPolicy: vaultwardenConsistencyPolicy,
+    sqliteAppConsistencyFailThreshold: vaultwardenConsistencyFailThreshold,
+    sqliteAppUploadOnConsistencyFailure: vaultwardenUploadOnConsistencyFailure,
+    postgresConnection: typeof source?.connection === 'string' ? source.connection : '',
+    postgresWalSpoolDir: typeof source?.wal_spool_dir === 'string' ? source.wal_spool_dir : '',
+    postgresBaseBackupIntervalHours:
+      typeof source?.base_backup_interval_hours === 'number' && source.base_backup_interval_hours > 0
+        ? Math.floor(source.base_backup_interval_hours)
+        : null,
+    vmImageDomain: typeof source?.domain === 'string' ? source.domain : '',
+    vmImageDisks: parseStringArray(source?.disks).join('\n'),
+    vmImageQuiesce: typeof source?.quiesce === 'boolean' ? source.quiesce : false,
+    vmImageConnectUri: typeof source?.connect_uri === 'string' ? source.connect_uri : '',
+    kubernetesPvcNamespace: typeof source?.namespace === 'string' ? source.namespace : '',
+    kubernetesPvcLabelSelector: typeof source?.label_selector === 'string' ? source.label_selector : '',
+    kubernetesPvcMode: normalizeKubernetesPvcMode(source?.mode),
+    kubernetesPvcMountRoot: typeof source?.mount_root === 'string' ? source.mount_root : '',
+    kubernetesPvcSnapshotClass: typeof source?.volume_snapshot_class === 'string' ? source.volume_snapshot_class : '',
+    kubernetesPvcReaderImage: typeof source?.reader_image === 'string' ? source.reader_image : '',
+    imapHost: job.spec.type === 'imap' && typeof source?.host === 'string' ? source.host : '',
+    imapPort: job.spec.type === 'imap' && typeof source?.port === 'number' ? source.port : null,
+    imapSecurity: normalizeImapSecurity(source?.security),
+    imapSecretName: job.spec.type === 'imap' && typeof source?.secret_name === 'string' ? source.secret_name : '',
+    imapIncludeFolders: job.spec.type === 'imap' ? parseStringArray(source?.include_folders).join('\n') : '',
+    imapExcludeFolders: job.spec.type === 'imap' ? parseStringArray(source?.exclude_folders).join('\n') : '',
+    gitRepositories: job.spec.type === 'git' ? formatGitRepositories(source?.repositories) : '',
     targetType,
     webdavBaseUrl: typeof target?.base_url === 'string' ? target.base_url : '',
     webdavSecretName: typeof target?.secret_name === 'string' ? target.secret_name : '',
@@ -220,15 +354,21 @@ export function jobDetailToEditorForm(job: JobDetail): JobEditorForm {
     webdavRawTreeDirectRequestTimeoutSecs,
     webdavRawTreeDirectConnectTimeoutSecs,
     webdavRawTreeDirectMaxPutAttempts,
+    webdavPartUploadConcurrency,
     localBaseDir: typeof target?.base_dir === 'string' ? target.base_dir : '',
+    localLinkUnchanged: typeof target?.link_unchanged === 'boolean' ? target.link_unchanged : false,
     partSizeMiB,
     notifyMode,
     notifyWecomBots: parseStringArray(notif?.['wecom_bot']),
     notifyEmails: parseStringArray(notif?.['email']),
+    notifySlack: parseStringArray(notif?.['slack']),
+    notifyDiscord: parseStringArray(notif?.['discord']),
+    notifySms: parseStringArray(notif?.['sms']),
 
     retentionEnabled,
     retentionKeepLast,
     retentionKeepDays,
+    retentionLockDays,
     retentionMaxDeletePerTick,
     retentionMaxDeletePerDay,
   }
@@ -237,6 +377,7 @@ export function jobDetailToEditorForm(job: JobDetail): JobEditorForm {
 export function editorFormToRequest(form: JobEditorForm): CreateOrUpdateJobRequest {
   const partSizeMiB = Math.max(1, Math.floor(form.partSizeMiB || 1))
   const partSizeBytes = partSizeMiB * 1024 * 1024
+  const partUploadConcurrency = Math.max(1, Math.floor(form.webdavPartUploadConcurrency || 1))
   const webdavRawTreeDirectRequestTimeoutSecs = normalizeOptionalPositiveInt(
     form.webdavRawTreeDirectRequestTimeoutSecs,
   )
@@ -247,13 +388,27 @@ export function editorFormToRequest(form: JobEditorForm): CreateOrUpdateJobReque
     form.webdavRawTreeDirectMaxPutAttempts,
   )
 
+  const compressionLevel =
+    form.compressionAlgorithm === 'zstd' || form.compressionAlgorithm === 'xz' ? form.compressionLevel : null
   const pipeline = {
     format: form.artifactFormat,
+    ...(form.artifactFormat === 'archive_v1'
+      ? {
+          compression: {
+            algorithm: form.compressionAlgorithm,
+            ...(compressionLevel != null ? { level: Math.floor(compressionLevel) } : {}),
+            ...(form.compressionAlgorithm === 'zstd' && form.compressionAdaptive ? { adaptive: true } : {}),
+          },
+        }
+      : {}),
     encryption:
       form.artifactFormat === 'raw_tree_v1'
         ? ({ type: 'none' as const } as const)
         : form.encryptionEnabled
-          ? ({ type: 'age_x25519' as const, key_name: form.encryptionKeyName.trim() } as const)
+          ? ({
+              type: form.encryptionPassphraseKey ? ('age_passphrase' as const) : ('age_x25519' as const),
+              key_name: form.encryptionKeyName.trim(),
+            } as const)
           : ({ type: 'none' as const } as const),
     webdav: {
       raw_tree_direct: {
@@ -280,6 +435,9 @@ export function editorFormToRequest(form: JobEditorForm): CreateOrUpdateJobReque
             }
           : {}),
       },
+      ...(form.targetType === 'webdav' && form.artifactFormat === 'archive_v1' && partUploadConcurrency > 1
+        ? { part_upload_concurrency: partUploadConcurrency }
+        : {}),
     },
   }
 
@@ -289,6 +447,9 @@ export function editorFormToRequest(form: JobEditorForm): CreateOrUpdateJobReque
           mode: 'custom' as const,
           wecom_bot: form.notifyWecomBots,
           email: form.notifyEmails,
+          slack: form.notifySlack,
+          discord: form.notifyDiscord,
+          sms: form.notifySms,
         }
       : ({ mode: 'inherit' as const } as const)
 
@@ -299,6 +460,19 @@ export function editorFormToRequest(form: JobEditorForm): CreateOrUpdateJobReque
           paths: form.fsPaths.map((p) => p.trim()).filter((p) => p.length > 0),
           include: parseLines(form.fsInclude),
           exclude: parseLines(form.fsExclude),
+          ...(form.fsExcludePresets.length > 0 ? { exclude_presets: form.fsExcludePresets } : {}),
+          use_ignore_files: form.fsUseIgnoreFiles,
+          ...(form.fsWindowsSystemState ? { windows_system_state: true } : {}),
+          ...(form.fsAnomalyDetection ? { anomaly_detection: { ...form.fsAnomalyDetectionSettings } } : {}),
+          ...(form.fsMaxFileSizeMiB && form.fsMaxFileSizeMiB > 0
+            ? { max_file_size_bytes: Math.round(form.fsMaxFileSizeMiB * 1024 * 1024) }
+            : {}),
+          ...(form.fsSkipOlderThanDays && form.fsSkipOlderThanDays > 0
+            ? { skip_older_than_secs: Math.round(form.fsSkipOlderThanDays * 86400) }
+            : {}),
+          ...(form.fsSkipNewerThanMinutes && form.fsSkipNewerThanMinutes > 0
+            ? { skip_newer_than_secs: Math.round(form.fsSkipNewerThanMinutes * 60) }
+            : {}),
           symlink_policy: form.fsSymlinkPolicy,
           hardlink_policy: form.fsHardlinkPolicy,
           error_policy: form.fsErrorPolicy,
@@ -315,17 +489,83 @@ export function editorFormToRequest(form: JobEditorForm): CreateOrUpdateJobReque
             : {}),
         }
       : form.jobType === 'sqlite'
-        ? { path: form.sqlitePath.trim(), integrity_check: form.sqliteIntegrityCheck }
-        : {
-            data_dir: form.vaultwardenDataDir.trim(),
-            consistency_policy: form.vaultwardenConsistencyPolicy,
-            ...(form.vaultwardenConsistencyPolicy === 'fail'
+        ? {
+            path: form.sqlitePath.trim(),
+            integrity_check: form.sqliteIntegrityCheck,
+            ...(form.sqliteDatabases.some((db) => db.path.trim())
               ? {
-                  consistency_fail_threshold: Math.max(0, Math.floor(form.vaultwardenConsistencyFailThreshold || 0)),
-                  upload_on_consistency_failure: form.vaultwardenUploadOnConsistencyFailure,
+                  databases: form.sqliteDatabases
+                    .filter((db) => db.path.trim())
+                    .map((db) => ({ path: db.path.trim(), integrity_check: db.integrityCheck })),
                 }
               : {}),
           }
+        : form.jobType === 'sqlite_app'
+          ? {
+              data_dir: form.sqliteAppDataDir.trim(),
+              db_path: form.sqliteAppDbPath.trim(),
+              integrity_check: form.sqliteIntegrityCheck,
+              consistency_policy: form.sqliteAppConsistencyPolicy,
+              ...(form.sqliteAppConsistencyPolicy === 'fail'
+                ? {
+                    consistency_fail_threshold: Math.max(0, Math.floor(form.sqliteAppConsistencyFailThreshold || 0)),
+                    upload_on_consistency_failure: form.sqliteAppUploadOnConsistencyFailure,
+                  }
+                : {}),
+            }
+          : form.jobType === 'postgres'
+            ? {
+                connection: form.postgresConnection.trim(),
+                wal_spool_dir: form.postgresWalSpoolDir.trim(),
+                ...(form.postgresBaseBackupIntervalHours && form.postgresBaseBackupIntervalHours > 0
+                  ? { base_backup_interval_hours: Math.floor(form.postgresBaseBackupIntervalHours) }
+                  : {}),
+              }
+            : form.jobType === 'vm_image'
+              ? {
+                  domain: form.vmImageDomain.trim(),
+                  ...(parseLines(form.vmImageDisks).length > 0 ? { disks: parseLines(form.vmImageDisks) } : {}),
+                  quiesce: form.vmImageQuiesce,
+                  ...(form.vmImageConnectUri.trim() ? { connect_uri: form.vmImageConnectUri.trim() } : {}),
+                }
+              : form.jobType === 'kubernetes_pvc'
+                ? {
+                    ...(form.kubernetesPvcNamespace.trim() ? { namespace: form.kubernetesPvcNamespace.trim() } : {}),
+                    label_selector: form.kubernetesPvcLabelSelector.trim(),
+                    mode: form.kubernetesPvcMode,
+                    ...(form.kubernetesPvcMode === 'mounted' && form.kubernetesPvcMountRoot.trim()
+                      ? { mount_root: form.kubernetesPvcMountRoot.trim() }
+                      : {}),
+                    ...(form.kubernetesPvcMode === 'csi_snapshot' && form.kubernetesPvcSnapshotClass.trim()
+                      ? { volume_snapshot_class: form.kubernetesPvcSnapshotClass.trim() }
+                      : {}),
+                    ...(form.kubernetesPvcMode === 'csi_snapshot' && form.kubernetesPvcReaderImage.trim()
+                      ? { reader_image: form.kubernetesPvcReaderImage.trim() }
+                      : {}),
+                  }
+                : form.jobType === 'imap'
+                  ? {
+                      host: form.imapHost.trim(),
+                      ...(form.imapPort ? { port: Math.floor(form.imapPort) } : {}),
+                      security: form.imapSecurity,
+                      secret_name: form.imapSecretName.trim(),
+                      include_folders: parseLines(form.imapIncludeFolders),
+                      exclude_folders: parseLines(form.imapExcludeFolders),
+                    }
+                  : form.jobType === 'git'
+                    ? {
+                        repositories: parseGitRepositories(form.gitRepositories),
+                      }
+                  : {
+                      data_dir: form.vaultwardenDataDir.trim(),
+                      consistency_policy: form.vaultwardenConsistencyPolicy,
+                      ...(form.vaultwardenConsistencyPolicy === 'fail'
+                        ? {
+                            consistency_fail_threshold: Math.max(0, Math.floor(form.vaultwardenConsistencyFailThreshold || 0)),
+                            upload_on_consistency_failure: form.vaultwardenUploadOnConsistencyFailure,
+                          }
+                        : {}),
+                    }
 
   const target =
     form.targetType === 'webdav'
@@ -339,10 +579,12 @@ export function editorFormToRequest(form: JobEditorForm): CreateOrUpdateJobReque
           type: 'local_dir' as const,
           base_dir: form.localBaseDir.trim(),
           part_size_bytes: partSizeBytes,
+          link_unchanged: form.localLinkUnchanged,
         } as const)
 
   const retentionKeepLast = normalizeOptionalPositiveInt(form.retentionKeepLast)
   const retentionKeepDays = normalizeOptionalPositiveInt(form.retentionKeepDays)
+  const retentionLockDays = normalizeOptionalPositiveInt(form.retentionLockDays)
   const retentionMaxDeletePerTick = Math.max(1, Math.floor(form.retentionMaxDeletePerTick || 1))
   const retentionMaxDeletePerDay = Math.max(1, Math.floor(form.retentionMaxDeletePerDay || 1))
   const retention = {
@@ -351,12 +593,14 @@ export function editorFormToRequest(form: JobEditorForm): CreateOrUpdateJobReque
     keep_days: retentionKeepDays,
     max_delete_per_tick: retentionMaxDeletePerTick,
     max_delete_per_day: retentionMaxDeletePerDay,
+    ...(retentionLockDays !== null ? { lock_days: retentionLockDays } : {}),
   }
 
   const includeRetention =
     retention.enabled ||
     retentionKeepLast !== null ||
     retentionKeepDays !== null ||
+    retentionLockDays !== null ||
     retentionMaxDeletePerTick !== 50 ||
     retentionMaxDeletePerDay !== 200
 
@@ -365,6 +609,8 @@ export function editorFormToRequest(form: JobEditorForm): CreateOrUpdateJobReque
     agent_id: form.node === 'hub' ? null : form.node,
     schedule: form.schedule.trim() ? form.schedule.trim() : null,
     schedule_timezone: form.scheduleTimezone.trim() || 'UTC',
+    schedule_jitter_seconds: Math.max(0, Math.floor(form.scheduleJitterSeconds || 0)),
+    max_success_interval_seconds: Math.max(0, Math.floor(form.maxSuccessIntervalHours || 0)) * 3600,
     overlap_policy: form.overlapPolicy,
     spec: {
       v: 1 as const,
@@ -3,15 +3,21 @@ import type { JobType, OverlapPolicy } from '@/stores/jobs'
 export type FsSymlinkPolicy = 'keep' | 'follow' | 'skip'
 export type FsHardlinkPolicy = 'copy' | 'keep'
 export type FsErrorPolicy = 'fail_fast' | 'skip_fail' | 'skip_ok'
+export type FsExcludePreset = 'node_modules' | 'caches'
 export type ConsistencyPolicy = 'warn' | 'fail' | 'ignore'
 export type SnapshotMode = 'off' | 'auto' | 'required'
+export type KubernetesPvcMode = 'mounted' | 'csi_snapshot'
+export type ImapSecurity = 'tls' | 'starttls'
 
 export type JobTargetType = 'webdav' | 'local_dir'
 export type NotifyMode = 'inherit' | 'custom'
 export type NodeIdOrHub = 'hub' | string
 export type ArtifactFormat = 'archive_v1' | 'raw_tree_v1'
+export type CompressionAlgorithm = 'zstd' | 'lz4' | 'xz' | 'none'
 export type WebdavRawTreeDirectMode = 'off' | 'auto' | 'on'
 
+export type SqliteDatabaseEntry = { path: string; integrityCheck: boolean }
+
 export type JobEditorMode = 'create' | 'edit'
 
 export type JobScheduleMode = 'manual' | 'simple' | 'cron'
@@ -36,6 +42,31 @@ export type JobEditorField =
   | 'vaultwardenConsistencyPolicy'
   | 'vaultwardenConsistencyFailThreshold'
   | 'vaultwardenUploadOnConsistencyFailure'
+  | 'sqliteAppDataDir'
+  | 'sqliteAppDbPath'
+  | 'sqliteAppConsistencyPolicy'
+  | 'sqliteAppConsistencyFailThreshold'
+  | 'sqliteAppUploadOnConsistencyFailure'
+  | 'postgresConnection'
+  | 'postgresWalSpoolDir'
+  | 'postgresBaseBackupIntervalHours'
+  | 'vmImageDomain'
+  | 'vmImageDisks'
+  | 'vmImageQuiesce'
+  | 'vmImageConnectUri'
+  | 'kubernetesPvcNamespace'
+  | 'kubernetesPvcLabelSelector'
+  | 'kubernetesPvcMode'
+  | 'kubernetesPvcMountRoot'
+  | 'kubernetesPvcSnapshotClass'
+  | 'kubernetesPvcReaderImage'
+  | 'imapHost'
+  | 'imapPort'
+  | 'imapSecurity'
+  | 'imapSecretName'
+  | 'imapIncludeFolders'
+  | 'imapExcludeFolders'
+  | 'gitRepositories'
   | 'webdavBaseUrl'
   | 'webdavSecretName'
   | 'webdavRawTreeDirectMode'
@@ -47,8 +78,10 @@ export type JobEditorField =
   | 'webdavRawTreeDirectRequestTimeoutSecs'
   | 'webdavRawTreeDirectConnectTimeoutSecs'
   | 'webdavRawTreeDirectMaxPutAttempts'
+  | 'webdavPartUploadConcurrency'
   | 'localBaseDir'
   | 'partSizeMiB'
+  | 'compressionLevel'
   | 'encryptionKeyName'
 
 export type JobEditorForm = {
@@ -57,6 +90,8 @@ export type JobEditorForm = {
   node: NodeIdOrHub
   schedule: string
   scheduleTimezone: string
+  scheduleJitterSeconds: number
+  maxSuccessIntervalHours: number
   scheduleMode: JobScheduleMode
   simpleScheduleKind: JobSimpleScheduleKind
   simpleEveryMinutes: number
@@ -67,11 +102,24 @@ export type JobEditorForm = {
   overlapPolicy: OverlapPolicy
   jobType: JobType
   artifactFormat: ArtifactFormat
+  compressionAlgorithm: CompressionAlgorithm
+  compressionLevel: number | null
+  compressionAdaptive: boolean
   encryptionEnabled: boolean
   encryptionKeyName: string
+  encryptionPassphraseKey: boolean
   fsPaths: string[]
   fsInclude: string
   fsExclude: string
+  fsExcludePresets: FsExcludePreset[]
+  fsUseIgnoreFiles: boolean
+  fsWindowsSystemState: boolean
+  fsAnomalyDetection: boolean
+  // Thresholds set through the API; kept as-is so saving from the editor does not reset them.
+  fsAnomalyDetectionSettings: Record<string, unknown>
+  fsMaxFileSizeMiB: number | null
+  fsSkipOlderThanDays: number | null
+  fsSkipNewerThanMinutes: number | null
   fsPreScan: boolean
   fsSymlinkPolicy: FsSymlinkPolicy
   fsHardlinkPolicy: FsHardlinkPolicy
@@ -83,10 +131,37 @@ export type JobEditorForm = {
   fsUploadOnConsistencyFailure: boolean
   sqlitePath: string
   sqliteIntegrityCheck: boolean
+  sqliteDatabases: SqliteDatabaseEntry[]
   vaultwardenDataDir: string
   vaultwardenConsistencyPolicy: ConsistencyPolicy
   vaultwardenConsistencyFailThreshold: number
   vaultwardenUploadOnConsistencyFailure: boolean
+  sqliteAppDataDir: string
+  sqliteAppDbPath: string
+  sqliteAppConsistencyPolicy: ConsistencyPolicy
+  sqliteAppConsistencyFailThreshold: number
+  sqliteAppUploadOnConsistencyFailure: boolean
+  postgresConnection: string
+  postgresWalSpoolDir: string
+  postgresBaseBackupIntervalHours: number | null
+  vmImageDomain: string
+  vmImageDisks: string
+  vmImageQuiesce: boolean
+  vmImageConnectUri: string
+  kubernetesPvcNamespace: string
+  kubernetesPvcLabelSelector: string
+  kubernetesPvcMode: KubernetesPvcMode
+  kubernetesPvcMountRoot: string
+  kubernetesPvcSnapshotClass: string
+  kubernetesPvcReaderImage: string
+  imapHost: string
+  imapPort: number | null
+  imapSecurity: ImapSecurity
+  imapSecretName: string
+  imapIncludeFolders: string
+  imapExcludeFolders: string
+  /** One repository per line: `<url>` or `<url> <name>`. */
+  gitRepositories: string
   targetType: JobTargetType
   webdavBaseUrl: string
   webdavSecretName: string
@@ -100,15 +175,21 @@ export type JobEditorForm = {
   webdavRawTreeDirectRequestTimeoutSecs: number | null
   webdavRawTreeDirectConnectTimeoutSecs: number | null
   webdavRawTreeDirectMaxPutAttempts: number | null
+  webdavPartUploadConcurrency: number
   localBaseDir: string
+  localLinkUnchanged: boolean
   partSizeMiB: number
   notifyMode: NotifyMode
   notifyWecomBots: string[]
   notifyEmails: string[]
+  notifySlack: string[]
+  notifyDiscord: string[]
+  notifySms: string[]
 
   retentionEnabled: boolean
   retentionKeepLast: number | null
   retentionKeepDays: number | null
+  retentionLockDays: number | null
   retentionMaxDeletePerTick: number
   retentionMaxDeletePerDay: number
 }
@@ -36,6 +36,31 @@ export function stepForJobEditorField(field: JobEditorField): number {
     case 'vaultwardenConsistencyPolicy':
     case 'vaultwardenConsistencyFailThreshold':
     case 'vaultwardenUploadOnConsistencyFailure':
+    case 'sqliteAppDataDir':
+    case 'sqliteAppDbPath':
+    case 'sqliteAppConsistencyPolicy':
+    case 'sqliteAppConsistencyFailThreshold':
+    case 'sqliteAppUploadOnConsistencyFailure':
+    case 'postgresConnection':
+    case 'postgresWalSpoolDir':
+    case 'postgresBaseBackupIntervalHours':
+    case 'vmImageDomain':
+    case 'vmImageDisks':
+    case 'vmImageQuiesce':
+    case 'vmImageConnectUri':
+    case 'kubernetesPvcNamespace':
+    case 'kubernetesPvcLabelSelector':
+    case 'kubernetesPvcMode':
+    case 'kubernetesPvcMountRoot':
+    case 'kubernetesPvcSnapshotClass':
+    case 'kubernetesPvcReaderImage':
+    case 'imapHost':
+    case 'imapPort':
+    case 'imapSecurity':
+    case 'imapSecretName':
+    case 'imapIncludeFolders':
+    case 'imapExcludeFolders':
+    case 'gitRepositories':
       return 2
     case 'webdavBaseUrl':
     case 'webdavSecretName':
@@ -48,9 +73,11 @@ export function stepForJobEditorField(field: JobEditorField): number {
     case 'webdavRawTreeDirectRequestTimeoutSecs':
     case 'webdavRawTreeDirectConnectTimeoutSecs':
     case 'webdavRawTreeDirectMaxPutAttempts':
+    case 'webdavPartUploadConcurrency':
     case 'localBaseDir':
     case 'partSizeMiB':
       return 3
+    case 'compressionLevel':
     case 'encryptionKeyName':
       return 4
   }
@@ -110,6 +137,54 @@ function validateStep(step: number, form: JobEditorForm, t: TranslateFn): JobEdi
       if (!form.sqlitePath.trim()) {
         issues.push({ field: 'sqlitePath', message: t('errors.sqlitePathRequired') })
       }
+    } else if (form.jobType === 'sqlite_app') {
+      if (!form.sqliteAppDataDir.trim()) {
+        issues.push({ field: 'sqliteAppDataDir', message: t('errors.sqliteAppDataDirRequired') })
+      }
+      const dbPath = form.sqliteAppDbPath.trim()
+      if (!dbPath) {
+        issues.push({ field: 'sqliteAppDbPath', message: t('errors.sqliteAppDbPathRequired') })
+      } else if (/^([a-zA-Z]:)?[\\/]/.test(dbPath) || dbPath.split(/[\\/]/g).some((part) => part === '..')) {
+        issues.push({ field: 'sqliteAppDbPath', message: t('errors.sqliteAppDbPathRelative') })
+      }
+      if (form.sqliteAppConsistencyPolicy === 'fail') {
+        if (!Number.isFinite(form.sqliteAppConsistencyFailThreshold) || form.sqliteAppConsistencyFailThreshold < 0) {
+          issues.push({ field: 'sqliteAppConsistencyFailThreshold', message: t('errors.consistencyThresholdInvalid') })
+        }
+      }
+    } else if (form.jobType === 'postgres') {
+      if (!form.postgresConnection.trim()) {
+        issues.push({ field: 'postgresConnection', message: t('errors.postgresConnectionRequired') })
+      }
+      if (!form.postgresWalSpoolDir.trim()) {
+        issues.push({ field: 'postgresWalSpoolDir', message: t('errors.postgresWalSpoolDirRequired') })
+      }
+    } else if (form.jobType === 'vm_image') {
+      if (!form.vmImageDomain.trim()) {
+        issues.push({ field: 'vmImageDomain', message: t('errors.vmImageDomainRequired') })
+      }
+    } else if (form.jobType === 'kubernetes_pvc') {
+      if (!form.kubernetesPvcLabelSelector.trim()) {
+        issues.push({ field: 'kubernetesPvcLabelSelector', message: t('errors.kubernetesPvcLabelSelectorRequired') })
+      }
+      if (
+        form.kubernetesPvcMode === 'mounted' &&
+        form.kubernetesPvcMountRoot.trim() &&
+        !form.kubernetesPvcMountRoot.trim().startsWith('/')
+      ) {
+        issues.push({ field: 'kubernetesPvcMountRoot', message: t('errors.kubernetesPvcMountRootInvalid') })
+      }
+    } else if (form.jobType === 'imap') {
+      if (!form.imapHost.trim()) {
+        issues.push({ field: 'imapHost', message: t('errors.imapHostRequired') })
+      }
+      if (!form.imapSecretName.trim()) {
+        issues.push({ field: 'imapSecretName', message: t('errors.imapSecretRequired') })
+      }
+    } else if (form.jobType === 'git') {
+      if (!form.gitRepositories.trim()) {
+        issues.push({ field: 'gitRepositories', message: t('errors.gitRepositoriesRequired') })
+      }
     } else {
       if (!form.vaultwardenDataDir.trim()) {
         issues.push({ field: 'vaultwardenDataDir', message: t('errors.vaultwardenDataDirRequired') })
@@ -141,6 +216,16 @@ function validateStep(step: number, form: JobEditorForm, t: TranslateFn): JobEdi
       issues.push({ field: 'partSizeMiB', message: t('errors.partSizeInvalid') })
     }
 
+    if (form.targetType === 'webdav' && form.artifactFormat === 'archive_v1') {
+      const concurrency = Math.floor(form.webdavPartUploadConcurrency || 0)
+      if (!Number.isFinite(concurrency) || concurrency < 1 || concurrency > 16) {
+        issues.push({
+          field: 'webdavPartUploadConcurrency',
+          message: t('errors.webdavPartUploadConcurrencyInvalid'),
+        })
+      }
+    }
+
     const showDirect =
       form.jobType === 'filesystem' && form.targetType === 'webdav' && form.artifactFormat === 'raw_tree_v1'
     if (showDirect && form.webdavRawTreeDirectMode !== 'off') {
@@ -193,6 +278,14 @@ function validateStep(step: number, form: JobEditorForm, t: TranslateFn): JobEdi
   }
 
   if (step === 4) {
+    const levelRange =
+      form.compressionAlgorithm === 'zstd' ? [1, 22] : form.compressionAlgorithm === 'xz' ? [0, 9] : null
+    if (form.artifactFormat === 'archive_v1' && levelRange && form.compressionLevel != null) {
+      const [min, max] = levelRange as [number, number]
+      if (!Number.isInteger(form.compressionLevel) || form.compressionLevel < min || form.compressionLevel > max) {
+        issues.push({ field: 'compressionLevel', message: t('errors.compressionLevelInvalid', { min, max }) })
+      }
+    }
     if (form.encryptionEnabled && !form.encryptionKeyName.trim()) {
       issues.push({ field: 'encryptionKeyName', message: t('errors.encryptionKeyNameRequired') })
     }
@@ -405,6 +405,10 @@ const messages = {
       relatedJobsEmptyTitle: 'No jobs are assigned to this agent',
       relatedJobsEmptyDescription: 'Assign jobs to this agent when you need scheduled backup or restore work here.',
       jobMeta: 'Schedule: {schedule} · Updated: {updatedAt}',
+      diagnosticsTitle: 'Diagnostics',
+      diagnosticsSubtitle: 'Check staging space, source permissions and target reachability before the first backup.',
+      diagnosticsCollectedAt: 'Collected at {at}',
+      diagnosticsEmpty: 'No checks were reported',
       activityTitle: 'Recent activity',
       activityEmptyTitle: 'No recent activity',
       activityEmptyDescription: 'Recent runs for this agent will appear here after work has been scheduled or executed.',
@@ -567,6 +571,22 @@ const messages = {
       storage: 'Storage',
       revoke: 'Revoke',
       rotateKey: 'Rotate key',
+      runDiagnostics: 'Run diagnostics',
+    },
+    diagnostics: {
+      available: '{bytes} free',
+      status: {
+        ok: 'OK',
+        warn: 'Warning',
+        fail: 'Failed',
+      },
+      checks: {
+        job_config: 'Job config',
+        staging_space: 'Staging free space',
+        staging_writable: 'Staging writable',
+        source_access: 'Source access',
+        target_reachable: 'Target reachable',
+      },
     },
     filters: {
       labels: 'Labels',
@@ -582,10 +602,13 @@ const messages = {
       title: 'Create enrollment token',
       ttl: 'Expires in (seconds)',
       remainingUses: 'Remaining uses (optional)',
+      agentName: 'Agent name (optional)',
       create: 'Create',
       help: 'Use this token to enroll an agent',
       token: 'Token',
       enrollCommand: 'Enroll command',
+      qrCode: 'QR code',
+      qrHelp: 'Scan on the device to get the Hub URL and token',
       expiresAt: 'Expires at',
     },
     rotateModal: {
@@ -643,6 +666,7 @@ const messages = {
       agent_labels_remove: 'Remove labels',
       sync_config_now: 'Sync config',
       job_deploy: 'Deploy job',
+      job_sync: 'Sync job',
       webdav_secret_distribute: 'Distribute WebDAV credential',
     },
     statuses: {
@@ -924,6 +948,10 @@ const messages = {
       scheduleHelp: 'Standard 5-field cron: min hour dom month dow',
       scheduleTimezoneHelp: 'Uses the selected timezone (independent of Hub/Agent system timezones)',
       scheduleDstHelp: 'DST: missing times are skipped; repeated times run once',
+      scheduleJitterSeconds: 'Jitter (seconds)',
+      scheduleJitterHelp: 'Delays each scheduled run by a random 0–N seconds so jobs sharing a schedule do not all start at once',
+      maxSuccessIntervalHours: 'Expected success within (hours)',
+      maxSuccessIntervalHelp: 'Alert when the last successful run is older than this (0 disables). For a daily job, 26 leaves room for a slow run.',
       scheduleManualHelp: 'Manual only. Start runs manually.',
       simpleSchedule: 'Simple schedule',
       generatedCron: 'Generated cron',
@@ -940,11 +968,26 @@ const messages = {
       artifactFormatArchiveHelp: 'Default. Stores data as an archive stream (supports encryption).',
       artifactFormatRawTreeHelp: 'Stores files under data/<path>; preserves metadata best-effort (no encryption).',
       encryptionEnabled: 'Backup encryption (age)',
+      compression: 'Compression',
+      compressionHelp: 'Applies to archive payloads. lz4 is fastest, xz is smallest, none skips compression.',
+      compressionZstd: 'zstd (balanced)',
+      compressionLz4: 'lz4 (fast)',
+      compressionXz: 'xz (small)',
+      compressionNone: 'None',
+      compressionAdaptive: 'Skip already-compressed files',
+      compressionAdaptiveHelp:
+        'Stores media and archives (detected by extension or content sampling) without recompressing them. Speeds up media-heavy filesystem backups.',
+      compressionLevel: 'Compression level',
+      compressionLevelPlaceholder: 'Default',
+      compressionLevelHelp: 'Optional, {min}–{max}. Leave empty for the algorithm default.',
       encryptionHelp: 'Encrypts backups end-to-end. Keys are stored locally and protected by master.key.',
       encryptionDisabledByRawTreeHelp: 'raw_tree_v1 does not support encryption; this switch is disabled.',
       encryptionKeyName: 'Encryption key name',
       encryptionKeyNamePlaceholder: 'default',
       encryptionKeyNameHelp: 'Label for selecting encryption keys (default: default)',
+      encryptionPassphraseKey: 'Passphrase-protected key',
+      encryptionPassphraseKeyHelp:
+        'Use a backup key whose private half is locked with your passphrase. Create it first; the Hub never stores the passphrase, so restore and verify will ask for it.',
       targetType: 'Target type',
       sourceRoot: 'Source directory',
       sourceRootPlaceholder: '/path/to/data',
@@ -960,6 +1003,24 @@ const messages = {
       fsExclude: 'Exclude patterns (Glob)',
       fsExcludePlaceholder: '**/node_modules/**',
       fsExcludeHelp: 'One glob per line to exclude from backup',
+      fsExcludePresets: 'Exclude presets',
+      fsExcludePresetsHelp: 'Skip well-known directories anywhere under the selected paths',
+      fsExcludePresetNodeModules: 'node_modules',
+      fsExcludePresetCaches: 'Caches (.cache, __pycache__)',
+      fsUseIgnoreFiles: 'Honor .bastionignore files',
+      fsUseIgnoreFilesHelp: 'Skip paths listed in .bastionignore files inside the source tree (.gitignore syntax)',
+      fsWindowsSystemState: 'Windows system state',
+      fsWindowsSystemStateHelp:
+        'On Windows agents, also store registry hives, installed programs and drivers under bastion-system-state/ (archive format only)',
+      fsAnomalyDetection: 'Ransomware anomaly detection',
+      fsAnomalyDetectionHelp:
+        'Compare each snapshot to the previous one and send a high-priority alert on mass changes, ransomware-style extensions or encrypted-looking content',
+      fsMaxFileSizeMiB: 'Max file size (MiB)',
+      fsMaxFileSizeMiBHelp: 'Skip files larger than this; leave empty for no limit',
+      fsSkipOlderThanDays: 'Skip files older than (days)',
+      fsSkipOlderThanDaysHelp: 'Based on modification time; leave empty to keep old files',
+      fsSkipNewerThanMinutes: 'Skip files newer than (minutes)',
+      fsSkipNewerThanMinutesHelp: 'Skips files still being written; leave empty to keep new files',
       fsSymlinkPolicy: 'Symlink policy',
       fsHardlinkPolicy: 'Hardlink policy',
       fsErrorPolicy: 'Error policy',
@@ -981,22 +1042,94 @@ const messages = {
       sqlitePathPlaceholder: '/path/to/db.sqlite3',
       sqliteIntegrityCheck: 'SQLite integrity check',
       sqliteIntegrityCheckHelp: 'Runs PRAGMA integrity_check (may take longer)',
+      sqliteDatabases: 'Additional databases',
+      sqliteDatabasesHelp: 'Back up several databases in one run and one archive. Each one has its own integrity check toggle.',
       vaultwardenDataDir: 'Vaultwarden data directory',
       vaultwardenDataDirPlaceholder: '/path/to/vaultwarden/data',
       vaultwardenDataDirHelp: 'Creates an online snapshot of db.sqlite3 under this directory (no downtime).',
+      sqliteAppDataDir: 'App data directory',
+      sqliteAppDataDirPlaceholder: '/var/lib/gitea',
+      sqliteAppDataDirHelp: 'The whole directory is packaged into one archive.',
+      sqliteAppDbPath: 'Database path',
+      sqliteAppDbPathPlaceholder: 'data/gitea.db',
+      sqliteAppDbPathHelp: 'Relative to the data directory. The live file is replaced by an online snapshot (no downtime).',
+      postgresConnection: 'Connection string',
+      postgresConnectionPlaceholder: 'host=/run/postgresql user=replicator',
+      postgresConnectionHelp: 'Used by pg_basebackup. The role needs the REPLICATION attribute; use ~/.pgpass for the password.',
+      postgresWalSpoolDir: 'WAL spool directory',
+      postgresWalSpoolDirPlaceholder: '/var/lib/postgresql/wal-spool',
+      postgresWalSpoolDirHelp: 'Where archive_command copies finished segments. Shipped segments are removed after a successful upload.',
+      postgresBaseBackupIntervalHours: 'Base backup interval (hours)',
+      postgresBaseBackupIntervalHoursPlaceholder: '24',
+      postgresBaseBackupIntervalHoursHelp: 'A run takes a new base backup once the last one is older than this.',
+      vmImageDomain: 'libvirt domain',
+      vmImageDomainPlaceholder: 'web01',
+      vmImageDomainHelp: 'Domain name or UUID as shown by virsh list --all.',
+      vmImageDisks: 'Disks',
+      vmImageDisksPlaceholder: 'vda',
+      vmImageDisksHelp: 'One disk target per line (see virsh domblklist). Leave empty to back up every file-backed disk.',
+      vmImageDisksAll: 'All file-backed disks',
+      vmImageQuiesce: 'Quiesce guest filesystems',
+      vmImageQuiesceHelp: 'Freeze guest filesystems through the QEMU guest agent while the snapshot is taken. Requires the agent in the guest.',
+      vmImageConnectUri: 'Connection URI',
+      vmImageConnectUriPlaceholder: 'qemu:///system',
+      vmImageConnectUriHelp: 'Optional. Defaults to the virsh default connection.',
+      kubernetesPvcLabelSelector: 'PVC label selector',
+      kubernetesPvcLabelSelectorPlaceholder: 'app=gitea',
+      kubernetesPvcLabelSelectorHelp: 'Resolved at run time; every bound PVC it matches is backed up and recorded in the manifest.',
+      kubernetesPvcNamespace: 'Namespace',
+      kubernetesPvcNamespacePlaceholder: 'default',
+      kubernetesPvcNamespaceHelp: 'Optional. Defaults to the namespace of the agent\'s kubeconfig context.',
+      kubernetesPvcMode: 'Mode',
+      kubernetesPvcModeHelp: 'Mounted reads PVCs mounted into the agent pod; CSI snapshot copies each PVC from a VolumeSnapshot.',
+      kubernetesPvcModeMounted: 'Mounted path per PVC',
+      kubernetesPvcModeCsiSnapshot: 'CSI volume snapshot',
+      kubernetesPvcMountRoot: 'Mount root',
+      kubernetesPvcMountRootPlaceholder: '/pvc',
+      kubernetesPvcMountRootHelp: 'Each PVC must be mounted at <mount root>/<PVC name> in the agent pod.',
+      kubernetesPvcSnapshotClass: 'VolumeSnapshotClass',
+      kubernetesPvcSnapshotClassPlaceholder: 'csi-hostpath-snapclass',
+      kubernetesPvcSnapshotClassHelp: 'Optional. Defaults to the cluster default snapshot class.',
+      kubernetesPvcReaderImage: 'Reader image',
+      kubernetesPvcReaderImagePlaceholder: 'busybox:1.36',
+      kubernetesPvcReaderImageHelp: 'Image of the temporary pod that mounts each restored snapshot; it must provide tar.',
+      imapHost: 'IMAP server',
+      imapHostPlaceholder: 'imap.example.com',
+      imapSecurity: 'Connection security',
+      imapSecurityTls: 'TLS',
+      imapSecurityStarttls: 'STARTTLS',
+      imapPort: 'Port',
+      imapSecret: 'IMAP credential',
+      imapSecretHelp: 'Stored on the node that runs the job (password or OAuth2).',
+      imapIncludeFolders: 'Include folders',
+      imapIncludeFoldersHelp: 'One glob per line, using / between levels. Empty = all folders.',
+      imapExcludeFolders: 'Exclude folders',
+      imapExcludeFoldersHelp: 'One glob per line, for example Trash or Lists/*.',
+      imapIncrementalHint: 'Each run stores only the messages that arrived since the last successful run. Restoring a full mailbox means restoring its snapshots in order, so keep retention long enough to cover every message you care about.',
+      gitRepositories: 'Repositories',
+      gitRepositoriesPlaceholder: "https://github.com/acme/app.git\ngit{'@'}git.example.com:ops/infra.git infra",
+      gitRepositoriesHelp: 'One repository per line: the clone URL, optionally followed by a space and a name for its mirror (default: taken from the URL).',
+      gitAuthHint: 'Mirrors are kept on the node between runs, so each run only fetches new objects. Private repositories authenticate with the node\'s own SSH keys or git credential helper; git never prompts for a password.',
       webdavBaseUrl: 'WebDAV target URL',
       webdavBaseUrlPlaceholder: 'https://dav.example.com/backup',
       webdavSecret: 'WebDAV credential',
       localBaseDir: 'Backup directory',
       localBaseDirPlaceholder: '/path/to/backups',
       localBaseDirHelp: 'Writes to <base_dir>/<job_id>/<run_id>/',
+      localLinkUnchanged: 'Hardlink unchanged files',
+      localLinkUnchangedHelp: 'Raw-tree snapshots only: files identical to the previous snapshot become hardlinks, so each run is a full browsable tree that only uses space for changes',
       partSizeMiB: 'Part size (MiB)',
       partSizeMiBHelp: 'Chunk size for uploads and resuming',
+      webdavPartUploadConcurrency: 'Parallel part uploads',
+      webdavPartUploadConcurrencyHelp: 'Archive parts uploaded at once (1-16). Each in-flight part stays on local disk until it lands.',
       notificationsMode: 'Notification mode',
       notificationsModeHelp: 'Default: inherit global settings',
       notificationsInheritHelp: 'Inherit global settings: all enabled destinations will be used',
       notifyWecomBots: 'WeCom destinations',
       notifyEmails: 'Email destinations',
+      notifySlack: 'Slack destinations',
+      notifyDiscord: 'Discord destinations',
+      notifySms: 'SMS destinations',
       notifySelectPlaceholder: 'Select destinations (multi-select)',
       notifyEmptyMeansDisable: 'Leave empty to disable this channel for the job',
       notifyDisabledSelected: 'Disabled destinations selected: {names}. Enable them to send.',
@@ -1012,11 +1145,36 @@ const messages = {
       filesystem: 'Filesystem backup',
       sqlite: 'SQLite backup',
       vaultwarden: 'Vaultwarden',
+      sqlite_app: 'App (directory + SQLite)',
+      postgres: 'PostgreSQL (WAL archiving)',
+      vm_image: 'VM image (libvirt)',
+      kubernetes_pvc: 'Kubernetes PVCs',
+      imap: 'IMAP mailboxes',
+      git: 'Git mirrors',
+    },
+    imapCredential: {
+      title: 'New IMAP credential',
+      name: 'Name',
+      auth: 'Authentication',
+      authPassword: 'Password',
+      authOauth2: 'OAuth2',
+      username: 'Username',
+      password: 'Password (or app password)',
+      tokenUrl: 'Token URL',
+      clientId: 'Client ID',
+      clientSecret: 'Client secret (optional)',
+      refreshToken: 'Refresh token',
+      oauth2Help: 'A fresh access token is requested from the token URL at the start of every run.',
     },
     overlap: {
       queue: 'Queue (no parallel)',
       reject: 'Reject (if running)',
     },
+    schedulePreview: {
+      action: 'Preview next runs',
+      saveFirst: 'Save the job first to preview its schedule',
+      title: 'Next runs ({tz})',
+    },
     retention: {
       title: 'Retention',
       help: 'Automatically prune snapshots by keep-last and/or keep-days. Pinned snapshots are excluded.',
@@ -1024,6 +1182,9 @@ const messages = {
       keepDays: 'Keep last N days (0 to unset)',
       maxDeletePerTick: 'Max deletes per tick',
       maxDeletePerDay: 'Max deletes per day',
+      lockDays: 'Retention lock (days)',
+      lockDaysHelp:
+        'Snapshots cannot be deleted until N days after they finish, even manually. Applies even when retention is disabled. Leave empty for no lock.',
       preview: 'Preview',
       applyNow: 'Apply now',
       guardrail: 'Warning: Apply queues snapshot deletions (this job only).',
@@ -1119,11 +1280,17 @@ const messages = {
       hideJson: 'Hide JSON',
       copyJson: 'Copy JSON',
       cronPresets: 'Presets',
+      newImapCredential: 'New credential',
       manageWebdavSecrets: 'Manage WebDAV credentials',
       manageNotificationDestinations: 'Manage notification destinations',
     },
     deploy: {
       title: 'Deploy to nodes',
+      mode: 'Mode',
+      modeCopy: 'Create copies',
+      modeSync: 'Create or update',
+      modeCopyHelp: 'Adds a new copy of the job on every target node.',
+      modeSyncHelp: 'Keeps one linked copy per node: creates it if missing, otherwise updates its spec and schedule to match this job.',
       target: 'Target',
       targetLabels: 'By labels',
       targetNodes: 'Select nodes',
@@ -1144,6 +1311,12 @@ const messages = {
         plannedName: 'Planned name',
         status: 'OK',
         error: 'Error',
+        action: 'Action',
+      },
+      actions: {
+        create: 'Create',
+        update: 'Update',
+        unchanged: 'Unchanged',
       },
     },
     cronPresets: {
@@ -1217,6 +1390,7 @@ const messages = {
       maintenance: 'Maintenance',
       runtimeConfig: 'Runtime config',
       bulkOperations: 'Bulk operations',
+      keypack: 'Master key backup',
       about: 'About',
     },
     overview: {
@@ -1227,6 +1401,7 @@ const messages = {
       maintenanceDesc: 'Operational tools for maintenance tasks',
       runtimeConfigDesc: 'Manage Hub runtime policy and logging (restart required)',
       bulkOperationsDesc: 'Track and control bulk operations across agents',
+      keypackDesc: 'Export, restore, or escrow the encrypted master keypack',
       aboutDesc: 'Version and build information',
     },
     appearance: {
@@ -1254,7 +1429,7 @@ const messages = {
     hubRuntimeConfig: {
       title: 'Hub runtime config',
       restartRequired:
-        'Restart the Hub after saving. Fields overridden by CLI/ENV are read-only here.',
+        'Restart the Hub after saving. Fields overridden by CLI/ENV or the config file are read-only here.',
       sections: {
         startup: {
           title: 'Startup (read-only)',
@@ -1283,6 +1458,25 @@ const messages = {
         hubTimezone: 'Hub timezone',
         runRetentionDays: 'Run retention (days)',
         incompleteCleanupDays: 'Incomplete cleanup (days)',
+        scheduleStaggerSeconds: 'Schedule stagger window (seconds)',
+        scheduleStaggerHelp: 'Spreads jobs that fire in the same minute over this window; each job keeps a stable offset. Applies without restart.',
+        artifactRetentionDays: 'Snapshot data retention (days)',
+        artifactRetentionDaysPlaceholder: 'Keep until job retention deletes it',
+        artifactRetentionDaysHelp: 'Deletes stored snapshot data this many days after the run (pinned and locked snapshots are kept). Run history follows run retention, so it can stay searchable longer. Applies without restart.',
+        eventRetentionDays: 'Run event retention (days)',
+        eventRetentionDaysPlaceholder: 'Keep with the run',
+        eventRetentionDaysHelp: 'Removes run events this many days after the run ended; error events and the run record are kept. Applies without restart.',
+        maxEventsPerRun: 'Max events per run',
+        maxEventsPerRunPlaceholder: '10000',
+        maxEventsPerRunHelp: 'Finished runs keep their first and last events up to this cap; progress snapshots collapse into the final one. Applies to runs finishing after the change.',
+        sessionMaxAgeHours: 'Session lifetime (hours)',
+        sessionMaxAgeHoursPlaceholder: '168',
+        sessionMaxAgeHoursHelp: 'Sign-ins expire this long after they start, however active. Applies to sign-ins after the change.',
+        sessionIdleTimeoutMinutes: 'Idle sign-out (minutes)',
+        sessionIdleTimeoutMinutesPlaceholder: 'Disabled',
+        sessionIdleTimeoutMinutesHelp: 'Ends a sign-in after this long without activity. Leave empty to keep sessions until they expire. Applies to sign-ins after the change.',
+        restoreApprovalRequired: 'Restore approval',
+        restoreApprovalRequiredHelp: 'Restores to a destination a job has not restored to before wait until a second admin approves them.',
         publicBaseUrl: 'Public base URL',
         publicBaseUrlPlaceholder: 'https://backup.example.com/bastion',
         backupRetentionEnabled: 'Default retention for new jobs',
@@ -1313,6 +1507,7 @@ const messages = {
         cli: 'CLI',
         env: 'ENV',
         envRustLog: 'RUST_LOG',
+        file: 'Config file',
         db: 'DB',
         default: 'Default',
       },
@@ -1322,6 +1517,65 @@ const messages = {
         never: 'Never',
       },
     },
+    keypack: {
+      warning:
+        'The keypack protects every stored secret. Keep exported files offline and never store the keypack password next to them.',
+      fields: {
+        currentPassword: 'Your login password',
+        keypackPassword: 'Keypack password',
+        keypackPasswordHelp: 'At least 8 characters; required again when importing',
+        file: 'Keypack file',
+      },
+      export: {
+        title: 'Export keypack',
+        subtitle: 'Download the master keyring encrypted with a password of your choice.',
+        action: 'Export and download',
+      },
+      import: {
+        title: 'Import keypack',
+        subtitle: 'Replace the Hub master keyring with one from a keypack file.',
+        confirmReplace: 'Replace the current master key (secrets encrypted with keys missing from the keypack become unreadable)',
+        action: 'Import keypack',
+        invalidFile: 'The selected file is not valid JSON',
+        restartRequired: 'Keypack imported. Restart the Hub to start using it.',
+      },
+      escrow: {
+        title: 'Off-site escrow',
+        subtitle:
+          'Automatically push a password-protected keypack to another location whenever the master keyring changes.',
+        runNow: 'Escrow now',
+        health: {
+          disabled: 'Off',
+          ok: 'Escrowed',
+          pending: 'Pending',
+          failed: 'Failed',
+        },
+        targets: {
+          localDir: 'Local directory',
+          webdav: 'WebDAV',
+        },
+        fields: {
+          enabled: 'Enable escrow',
+          target: 'Destination',
+          baseDir: 'Directory',
+          baseUrl: 'WebDAV URL',
+          secretName: 'WebDAV credential',
+          passwordKeep: 'Leave empty to keep the current password',
+          lastSuccess: 'Last escrowed',
+          lastAttempt: 'Last attempt',
+          location: 'Location',
+        },
+      },
+      audit: {
+        title: 'Recent keypack activity',
+        empty: 'No keypack exports or imports yet',
+        actions: {
+          exported: 'Exported',
+          imported: 'Imported',
+          escrowUpdated: 'Escrow settings changed',
+        },
+      },
+    },
     about: {
       hubTitle: 'Hub',
       uiTitle: 'Web UI',
@@ -1392,12 +1646,64 @@ const messages = {
           unignore: 'Resume automatic retries (returns to Queued; next attempt = now).',
         },
       },
+      orphans: {
+        title: 'Orphaned snapshot directories',
+        open: 'Orphaned directories',
+        help: 'Lists job/run directories on backup targets that no run or snapshot in the database accounts for, such as leftovers from crashed or force-deleted runs. Directories of unknown jobs are only reported, because a target may be shared.',
+        scan: 'Scan targets',
+        summary: '{targets} targets scanned, {orphans} orphaned directories',
+        empty: 'No orphaned directories found',
+        deleteSelected: 'Delete selected ({count})',
+        deleteAll: 'Delete all deletable ({count})',
+        deleteConfirm: 'Permanently delete {count} directories from their targets?',
+        columns: {
+          location: 'Location',
+          reason: 'Reason',
+          state: 'State',
+        },
+        reason: {
+          unknown_run: 'Unknown run',
+          artifact_deleted: 'Snapshot already deleted',
+          unknown_job: 'Unknown job',
+        },
+        state: {
+          deletable: 'Deletable',
+          reportOnly: 'Report only',
+          deleted: 'Deleted',
+          failed: 'Delete failed',
+        },
+        skipped: {
+          agentLocalDir: 'Skipped: local directory on an agent cannot be listed from the Hub',
+        },
+      },
+    },
+    secretInUse: {
+      title: '"{name}" is still in use',
+      help: 'These jobs reference this secret. Deleting it anyway breaks their backups or notifications until they are updated.',
+      archived: 'Archived',
+      deleteAnyway: 'Delete anyway',
+      usage: {
+        target: 'Backup target',
+        source: 'Backup source',
+        notifications: 'Notifications',
+      },
     },
     webdav: {
       title: 'WebDAV credentials',
       new: 'New',
       editorTitle: 'Edit WebDAV credential',
       deleteConfirm: 'Delete this credential?',
+      rotate: {
+        action: 'Rotate',
+        title: 'Rotate "{name}"',
+        help: 'The new credential is staged next to the current one. The next run that reaches the server with it makes it active; until then runs keep using the current credential.',
+        pending: 'Rotation pending',
+        pendingSince: 'Staged {time}',
+        promote: 'Activate now',
+        promoteConfirm: 'Make the staged credential active without waiting for a run?',
+        discard: 'Discard',
+        discardConfirm: 'Discard the staged credential?',
+      },
       distribute: {
         action: 'Distribute to agents',
         title: 'Distribute "{name}" to agents',
@@ -1457,6 +1763,42 @@ const messages = {
         actions: 'Actions',
       },
     },
+    chatWebhook: {
+      title: {
+        slack: 'Slack',
+        discord: 'Discord',
+      },
+      help: {
+        slack: 'Slack incoming webhook URL (https://hooks.slack.com/services/…). Messages use the email templates.',
+        discord: 'Discord channel webhook URL (https://discord.com/api/webhooks/…). Messages use the email templates.',
+      },
+      new: 'New',
+      editorTitle: {
+        slack: 'Edit Slack webhook',
+        discord: 'Edit Discord webhook',
+      },
+      deleteConfirm: 'Delete this webhook?',
+    },
+    sms: {
+      title: 'SMS (HTTP gateway)',
+      help: 'Sends a short "Bastion <status>: <job>" text through any HTTP SMS gateway.',
+      new: 'New',
+      editorTitle: 'Edit SMS destination',
+      deleteConfirm: 'Delete this SMS destination?',
+      fields: {
+        name: 'Name',
+        to: 'Recipient',
+        urlTemplate: 'Gateway URL',
+        placeholdersHelp: '{{to}} and {{message}} are filled in per message (URL-encoded).',
+        method: 'Method',
+        authHeader: 'Auth header',
+        authHeaderPlaceholder: 'Authorization: Bearer …',
+        contentType: 'Content type',
+        bodyTemplate: 'Body template',
+        failuresOnly: 'Failed runs only',
+        failuresOnlyHelp: 'Skip successful runs; digests are sent only when a run in the period failed.',
+      },
+    },
     smtp: {
       title: 'Email notifications (SMTP)',
       new: 'New',
@@ -1492,7 +1834,7 @@ const messages = {
       },
       overview: {
         channelsDesc: 'Global and per-channel toggles',
-        destinationsDesc: 'WeCom and SMTP destinations (with test)',
+        destinationsDesc: 'WeCom, SMTP, Slack, Discord and SMS destinations (with test)',
         templatesDesc: 'Templates and placeholders',
         queueDesc: 'Delivery history, retry, and cancel',
       },
@@ -1502,7 +1844,29 @@ const messages = {
       globalEnabled: 'Notifications',
       wecomEnabled: 'WeCom bot',
       emailEnabled: 'Email (SMTP)',
+      slackEnabled: 'Slack',
+      discordEnabled: 'Discord',
+      smsEnabled: 'SMS',
       globalDisabledHelp: 'Notifications are disabled. Nothing will be queued or sent.',
+      digestMode: 'Delivery',
+      digestModeHelp:
+        'Digest modes send one summary of all finished runs per period instead of one message per run.',
+      digestModes: {
+        perRun: 'One message per run',
+        daily: 'Daily digest',
+        weekly: 'Weekly digest',
+      },
+      digestWeekday: 'Digest day',
+      digestHour: 'Digest hour (hub timezone)',
+      weekdays: {
+        1: 'Monday',
+        2: 'Tuesday',
+        3: 'Wednesday',
+        4: 'Thursday',
+        5: 'Friday',
+        6: 'Saturday',
+        7: 'Sunday',
+      },
       enabled: 'Enabled',
       test: 'Test',
       wecomTemplate: 'WeCom Markdown template',
@@ -1525,6 +1889,9 @@ const messages = {
         all: 'All channels',
         wecom: 'WeCom',
         email: 'Email',
+        slack: 'Slack',
+        discord: 'Discord',
+        sms: 'SMS',
       },
       queue: {
         total: '{total} total',
@@ -1587,6 +1954,8 @@ const messages = {
     },
     deleteTaskExecutor: 'Executor: {node}',
     pinnedTooltip: 'Pinned: excluded from retention. Deleting requires force confirmation.',
+    lockedTooltip: 'Retention lock: cannot be deleted until {until}.',
+    dataGoneTooltip: 'Snapshot data removed at {at}; the run record is kept.',
     selectionDeleteHint: 'Permanent delete: selected snapshots (this job only).',
     columns: {
       endedAt: 'Completed',
@@ -1638,6 +2007,7 @@ const messages = {
     filters: {
       all: 'All',
       search: 'Search runs',
+      searchEvents: 'Search run events',
       status: 'Status',
       kind: 'Run type',
     },
@@ -1660,6 +2030,16 @@ const messages = {
       run_failed: 'The latest run did not complete successfully.',
       run_rejected: 'The latest run was rejected before execution.',
       run_canceled: 'The latest run was canceled before completion.',
+      network_target: 'The backup target could not be reached.',
+      target_auth: 'The backup target rejected the credentials.',
+      source_permission: 'Some source files could not be read.',
+      source_consistency: 'The source changed during the backup.',
+      disk_full: 'The disk or storage quota is full.',
+      encryption_key_missing: 'The backup encryption key is missing.',
+      timeout: 'An operation timed out.',
+      canceled: 'The run was canceled.',
+      config: 'The job or target configuration is invalid.',
+      unknown: 'The run failed with an unclassified error.',
       generic: 'The latest run reported an execution error.',
     },
     badges: {
@@ -1711,6 +2091,7 @@ const messages = {
       firstErrorSeq: 'First error #{seq}',
       relatedOps: 'Related operations',
       relatedArtifacts: 'Related artifacts',
+      dataGone: 'Data removed {at}',
       noFailureHint: 'No structured failure hint is available for this run.',
       eventWindow: 'Event window {first} - {last}',
       loadOlderEvents: 'Load older events',
@@ -1718,7 +2099,17 @@ const messages = {
       fsSnapshot: 'Filesystem snapshot',
       sqlitePath: 'SQLite path',
       sqliteSnapshot: 'SQLite snapshot',
+      sqliteDatabases: 'SQLite databases',
       vaultwardenDataDir: 'Vaultwarden data directory',
+      sqliteAppDataDir: 'App data directory',
+      postgresWal: 'WAL files shipped',
+      postgresBaseBackup: 'with base backup',
+      vmImage: 'VM',
+      vmImageDisks: '{count} disk(s)',
+      vmImageLiveSnapshot: 'live snapshot',
+      kubernetesPvcs: 'PVCs',
+      imapMessages: '{messages} new message(s) in {folders} folder(s)',
+      gitRepositories: 'Git repositories',
       entries: 'Entries: {count}',
       parts: 'Parts: {count}',
       warnings: 'Warnings: {count}',
@@ -1805,6 +2196,9 @@ const messages = {
       cancelConfirm: 'Cancel this run now?',
       restore: 'Restore',
       verify: 'Verify',
+      exportEventsNdjson: 'Export events (NDJSON)',
+      exportEventsText: 'Export events (text)',
+      downloadLog: 'Download task log',
       openRun: 'View run',
       openJob: 'View job',
     },
@@ -1915,6 +2309,8 @@ const messages = {
       conflictPolicy: 'Conflict policy',
       selection: 'Restore content (optional)',
       selectionHelp: 'Leave empty to restore everything. Selecting a directory restores its entire subtree.',
+      preview: 'Size estimate',
+      previewHelp: 'Sums the selection from the snapshot index and checks free space at the destination.',
     },
     conflict: {
       overwrite: 'Overwrite',
@@ -1926,6 +2322,7 @@ const messages = {
       browse: 'Browse',
       pick: 'Select files/directories',
       clearSelection: 'Clear selection',
+      estimate: 'Estimate',
     },
     pick: {
       title: 'Select files/directories to restore',
@@ -1936,6 +2333,16 @@ const messages = {
       confirm: 'Confirm',
     },
     selectionSummary: 'Selected: {files} files, {dirs} directories',
+    preview: {
+      summary: '{files} files, {dirs} directories, {size} to restore',
+      spaceAvailable: '{available} free at the destination',
+      spaceInsufficient: 'Only {available} free at the destination',
+      spaceSkipped: {
+        remote_node: 'Free space is only checked for directories on the Hub',
+        webdav: 'Free space is not checked for WebDAV destinations',
+        unavailable: 'Free space at the destination could not be read',
+      },
+    },
   },
   verify: {
     title: 'Verify',
@@ -1945,15 +2352,39 @@ const messages = {
     actions: {
       start: 'Start verification',
     },
+    mode: {
+      label: 'Mode',
+      full: 'Full',
+      stream: 'Stream',
+      streamHelp:
+        'Decrypts, decompresses and unpacks the whole snapshot and checks every file hash without writing files, so no scratch space is needed. SQLite integrity checks are skipped.',
+      sample: 'Sample',
+      sampleHelp:
+        'Checks the manifest and the size of every part, and the hashes of this share of parts. Files are not restored.',
+    },
+  },
+  postgresPitr: {
+    title: 'Point-in-time restore',
+    help: 'Rebuilds the data directory on the Hub from the latest base backup before the chosen time plus the WAL shipped after it, and configures PostgreSQL to stop replay at that time.',
+    open: 'Restore to a point in time',
+    targetTime: 'Recover to',
+    directory: 'Hub directory',
+    directoryPlaceholder: '/var/lib/bastion-restore/pg',
+    directoryHelp: 'Must be empty. Start PostgreSQL with the base/ subdirectory as its data directory.',
+    start: 'Start restore',
   },
   operations: {
     title: 'Operations',
+    passphrase: 'Backup key passphrase',
+    passphraseHelp: 'Only needed for snapshots encrypted with a passphrase-protected key. It is used for this operation and never stored.',
     kind: 'Type',
     kinds: {
       restore: 'Restore',
       verify: 'Verify',
+      reencrypt: 'Re-encrypt',
     },
     statuses: {
+      pending_approval: 'Awaiting approval',
       running: 'Running',
       canceling: 'Canceling',
       success: 'Success',
@@ -1964,6 +2395,8 @@ const messages = {
       cancel: 'Cancel',
       canceling: 'Canceling',
       cancelConfirm: 'Cancel this operation now?',
+      approve: 'Approve',
+      approveConfirm: 'Approve this restore and start it now?',
     },
     startedAt: 'Started',
     endedAt: 'Ended',
@@ -1997,10 +2430,19 @@ const messages = {
     runCanceled: 'Run canceled',
     operationCancelRequested: 'Operation cancel requested',
     operationCanceled: 'Operation canceled',
+    operationApproved: 'Restore approved',
+    imapSecretSaved: 'IMAP credential saved',
     webdavSecretSaved: 'WebDAV credential saved',
     webdavSecretDeleted: 'WebDAV credential deleted',
+    webdavPendingStaged: 'New credential staged; the next run will verify it',
+    webdavPendingPromoted: 'Staged credential is now active',
+    webdavPendingDiscarded: 'Staged credential discarded',
     wecomBotSaved: 'WeCom bot saved',
     wecomBotDeleted: 'WeCom bot deleted',
+    chatWebhookSaved: 'Webhook saved',
+    chatWebhookDeleted: 'Webhook deleted',
+    smsSaved: 'SMS destination saved',
+    smsDeleted: 'SMS destination deleted',
     smtpSecretSaved: 'SMTP destination saved',
     smtpSecretDeleted: 'SMTP destination deleted',
     notificationSettingsSaved: 'Notification settings saved',
@@ -2011,6 +2453,7 @@ const messages = {
     incompleteCleanupRetryScheduled: 'Retry queued',
     incompleteCleanupIgnored: 'Marked as ignored',
     incompleteCleanupUnignored: 'Ignore removed',
+    orphansDeleted: 'Deleted {count} orphaned directories',
     snapshotDeleteQueued: 'Delete queued',
     snapshotDeleteRetryQueued: 'Retry queued',
     snapshotDeleteIgnored: 'Marked as ignored',
@@ -2022,10 +2465,20 @@ const messages = {
     sourcePathsAdded: 'Added {count}',
     sourcePathsSkipped: 'Skipped {count} (already exists)',
     hubRuntimeConfigSaved: 'Hub runtime config saved (restart required)',
+    keypackExported: 'Keypack exported',
+    keypackImported: 'Keypack imported (restart required)',
+    keypackEscrowSaved: 'Keypack escrow is up to date',
+    keypackEscrowFailed: 'Keypack escrow failed: {error}',
   },
   apiErrors: {
     invalid_credentials: 'Invalid username or password',
-    rate_limited: 'Too many attempts. Retry in {seconds}s',
+    admin_required: 'Only admins can approve restores',
+    second_admin_required: 'A different admin must approve this restore',
+    operation_not_pending: 'This operation is not waiting for approval',
+    rate_limited: {
+      default: 'Too many attempts. Retry in {seconds}s',
+      api_rate_limit: 'Too many requests. Retry in {seconds}s',
+    },
     invalid_base_url: {
       default: 'Invalid WebDAV base URL',
       required: 'WebDAV base URL is required',
@@ -2062,6 +2515,10 @@ const messages = {
       required: 'At least one recipient is required',
       invalid_format: 'Invalid recipient address',
     },
+    invalid_auth_header: {
+      default: 'Invalid auth header',
+      invalid_format: 'Auth header must look like "Name: value"',
+    },
     invalid_token: {
       default: 'Invalid token',
       invalid_format: 'Invalid token format',
@@ -2139,6 +2596,15 @@ const messages = {
       not_found: 'WebDAV credential not found',
       invalid_payload: 'WebDAV credential is invalid',
     },
+    invalid_imap_secret: {
+      default: 'IMAP credential is invalid',
+      not_found: 'IMAP credential not found',
+    },
+    invalid_token_url: {
+      default: 'Token URL is invalid',
+      required: 'Token URL is required',
+      invalid_format: 'Token URL must be an http(s) URL',
+    },
     invalid_cursor: {
       default: 'Invalid pagination cursor',
       invalid_encoding: 'Invalid pagination cursor',
@@ -2220,6 +2686,12 @@ const messages = {
     },
     invalid_run_retention_days: 'Run retention days must be > 0',
     invalid_incomplete_cleanup_days: 'Incomplete cleanup days must be >= 0',
+    invalid_run_ids: 'Select between 1 and 1000 runs',
+    invalid_artifact_retention_days: 'Snapshot data retention must be between {min} and {max} days',
+    invalid_event_retention_days: 'Run event retention must be between {min} and {max} days',
+    invalid_max_events_per_run: 'Max events per run must be between {min} and {max}',
+    invalid_session_max_age_hours: 'Session lifetime must be between {min} and {max} hours',
+    invalid_session_idle_timeout_minutes: 'Idle sign-out must be between {min} and {max} minutes',
     invalid_log_rotation: 'Invalid log rotation',
   },
   diagnostics: {
@@ -2352,6 +2824,7 @@ const messages = {
     rotateAgentKeyFailed: 'Could not rotate agent key',
     updateAgentLabelsFailed: 'Could not update agent labels',
     syncConfigNowFailed: 'Could not trigger config sync',
+    runAgentDiagnosticsFailed: 'Could not run agent diagnostics',
     copyFailed: 'Copy failed',
     previewBulkOperationFailed: 'Could not preview bulk operation',
     fetchBulkOperationsFailed: 'Could not load bulk operations',
@@ -2370,7 +2843,9 @@ const messages = {
     runNowFailed: 'Could not start run',
     cancelRunFailed: 'Could not cancel run',
     fetchRunsFailed: 'Could not load runs',
+    searchRunsFailed: 'Could not search run events',
     cancelOperationFailed: 'Could not cancel operation',
+    approveOperationFailed: 'Could not approve operation',
     fetchSnapshotsFailed: 'Could not load snapshots',
     deleteSnapshotsFailed: 'Could not delete snapshots',
     pinSnapshotFailed: 'Could not pin snapshot',
@@ -2387,18 +2862,27 @@ const messages = {
     retentionLimitInvalid: 'Delete limits must be > 0',
     consistencyThresholdInvalid: 'Consistency threshold must be >= 0',
     snapshotRequiresSingleSourcePath: 'Snapshot mode requires exactly 1 source path',
+    previewScheduleFailed: 'Could not preview schedule',
     previewRetentionFailed: 'Could not preview retention',
     applyRetentionFailed: 'Could not apply retention',
     fetchWebdavSecretsFailed: 'Could not load WebDAV credentials',
     fetchWebdavSecretFailed: 'Could not load WebDAV credential',
     saveWebdavSecretFailed: 'Could not save WebDAV credential',
     deleteWebdavSecretFailed: 'Could not delete WebDAV credential',
+    stageWebdavPendingFailed: 'Could not stage WebDAV credential',
+    promoteWebdavPendingFailed: 'Could not activate staged credential',
+    discardWebdavPendingFailed: 'Could not discard staged credential',
     secretNameOrUsernameRequired: 'Name and username are required',
     fetchWecomBotsFailed: 'Could not load WeCom bots',
     fetchWecomBotFailed: 'Could not load WeCom bot',
     saveWecomBotFailed: 'Could not save WeCom bot',
     deleteWecomBotFailed: 'Could not delete WeCom bot',
     wecomNameOrWebhookRequired: 'Name and webhook URL are required',
+    fetchChatWebhookFailed: 'Could not load webhook',
+    deleteChatWebhookFailed: 'Could not delete webhook',
+    smsRequiredFields: 'Name, gateway URL and recipient are required',
+    fetchSmsFailed: 'Could not load SMS destination',
+    deleteSmsFailed: 'Could not delete SMS destination',
     fetchSmtpSecretsFailed: 'Could not load SMTP destinations',
     fetchSmtpSecretFailed: 'Could not load SMTP destination',
     saveSmtpSecretFailed: 'Could not save SMTP destination',
@@ -2418,8 +2902,15 @@ const messages = {
     incompleteCleanupRetryFailed: 'Could not retry cleanup task',
     incompleteCleanupIgnoreFailed: 'Could not ignore cleanup task',
     incompleteCleanupUnignoreFailed: 'Could not unignore cleanup task',
+    orphanScanFailed: 'Could not scan targets for orphaned directories',
+    orphanDeleteFailed: 'Could not delete orphaned directories',
     fetchHubRuntimeConfigFailed: 'Could not load hub runtime config',
     saveHubRuntimeConfigFailed: 'Could not save hub runtime config',
+    exportKeypackFailed: 'Could not export keypack',
+    importKeypackFailed: 'Could not import keypack',
+    fetchKeypackAuditFailed: 'Could not load keypack activity',
+    fetchKeypackEscrowFailed: 'Could not load keypack escrow settings',
+    saveKeypackEscrowFailed: 'Could not update keypack escrow',
     smtpNameRequired: 'Name is required',
     smtpHostRequired: 'SMTP host is required',
     smtpPortRequired: 'SMTP port is required',
@@ -2431,6 +2922,7 @@ const messages = {
     webdavBrowseRequiresBaseUrlAndSecret: 'Enter WebDAV URL and select a credential first',
     webdavPrefixRootNotAllowed: 'Root is not allowed; select a subdirectory',
     webdavRawTreeDirectConcurrencyInvalid: 'Concurrency must be between 1 and 128',
+    webdavPartUploadConcurrencyInvalid: 'Parallel part uploads must be between 1 and 16',
     webdavRawTreeDirectQpsInvalid: 'QPS must be between 1 and {max} (or empty)',
     webdavRawTreeDirectBurstInvalid: 'Burst must be between 1 and {max} (or empty)',
     webdavRawTreeDirectTimeoutInvalid: 'Timeout must be between 1 and {max} seconds (or empty)',
@@ -2438,14 +2930,28 @@ const messages = {
     localBaseDirRequired: 'Backup directory is required',
     partSizeInvalid: 'Part size must be a positive number',
     encryptionKeyNameRequired: 'Encryption key name is required',
+    compressionLevelInvalid: 'Compression level must be between {min} and {max}',
     sourceRootRequired: 'Source directory is required',
     sourcePathsRequired: 'Source paths are required',
     sqlitePathRequired: 'SQLite path is required',
     vaultwardenDataDirRequired: 'Vaultwarden data directory is required',
+    sqliteAppDataDirRequired: 'App data directory is required',
+    sqliteAppDbPathRequired: 'Database path is required',
+    sqliteAppDbPathRelative: 'Database path must be relative to the data directory',
+    postgresConnectionRequired: 'Connection string is required',
+    postgresWalSpoolDirRequired: 'WAL spool directory is required',
+    vmImageDomainRequired: 'libvirt domain is required',
+    kubernetesPvcLabelSelectorRequired: 'PVC label selector is required',
+    kubernetesPvcMountRootInvalid: 'Mount root must be an absolute path',
+    imapHostRequired: 'IMAP server is required',
+    imapSecretRequired: 'IMAP credential is required',
+    gitRepositoriesRequired: 'At least one repository is required',
     restoreSelectionRequired: 'Select at least one file or directory',
     restoreDestinationRequired: 'Destination directory is required',
     restoreStartFailed: 'Could not start restore',
+    restorePreviewFailed: 'Could not estimate restore size',
     verifyStartFailed: 'Could not start verification',
+    postgresPitrTargetRequired: 'Choose a time and an empty Hub directory',
     fetchRunEventsFailed: 'Could not load run events',
     fsPathRequired: 'Path is required',
     fsListFailed: 'Could not list files',
@@ -404,6 +404,10 @@ const messages = {
       relatedJobsEmptyTitle: '该客户端尚未分配任务',
       relatedJobsEmptyDescription: '当该节点需要承担计划备份或恢复工作时，请将任务分配到此客户端。',
       jobMeta: '计划：{schedule} · 更新时间：{updatedAt}',
+      diagnosticsTitle: '诊断',
+      diagnosticsSubtitle: '在首次备份前检查暂存空间、源路径权限和目标可达性。',
+      diagnosticsCollectedAt: '采集于 {at}',
+      diagnosticsEmpty: '未返回任何检查项',
       activityTitle: '最近活动',
       activityEmptyTitle: '暂无最近活动',
       activityEmptyDescription: '当该客户端开始承接任务后，最近运行记录会显示在这里。',
@@ -564,6 +568,22 @@ const messages = {
       storage: '存储',
       revoke: '撤销',
       rotateKey: '轮换密钥',
+      runDiagnostics: '运行诊断',
+    },
+    diagnostics: {
+      available: '可用 {bytes}',
+      status: {
+        ok: '正常',
+        warn: '警告',
+        fail: '失败',
+      },
+      checks: {
+        job_config: '任务配置',
+        staging_space: '暂存空间',
+        staging_writable: '暂存目录可写',
+        source_access: '源路径访问',
+        target_reachable: '目标可达',
+      },
     },
     filters: {
       labels: '标签',
@@ -579,10 +599,13 @@ const messages = {
       title: '创建接入令牌',
       ttl: '有效期（秒）',
       remainingUses: '可用次数（可选）',
+      agentName: '客户端名称（可选）',
       create: '创建',
       help: '客户端接入时使用该令牌完成绑定',
       token: '令牌',
       enrollCommand: '接入命令',
+      qrCode: '二维码',
+      qrHelp: '在设备上扫描即可获取 Hub 地址和令牌',
       expiresAt: '过期时间',
     },
     rotateModal: {
@@ -640,6 +663,7 @@ const messages = {
       agent_labels_remove: '移除标签',
       sync_config_now: '同步配置',
       job_deploy: '部署任务',
+      job_sync: '同步任务',
       webdav_secret_distribute: '分发 WebDAV 凭据',
     },
     statuses: {
@@ -921,6 +945,10 @@ const messages = {
       scheduleHelp: '支持标准 5 段 Cron（分 时 日 月 周）',
       scheduleTimezoneHelp: '计划将按所选时区的本地时间解释（与 Hub/客户端 系统时区无关）',
       scheduleDstHelp: '夏令时：跳过不存在的时间；重复的时间仅运行一次',
+      scheduleJitterSeconds: '随机延迟（秒）',
+      scheduleJitterHelp: '每次计划触发随机延迟 0–N 秒，避免相同计划的任务同时启动',
+      maxSuccessIntervalHours: '成功间隔上限（小时）',
+      maxSuccessIntervalHelp: '最近一次成功运行早于该时长时发出告警（0 表示关闭）。每日任务可设为 26，为较慢的运行留出余量。',
       scheduleManualHelp: '仅手动触发运行，不进行自动调度',
       simpleSchedule: '常用计划',
       generatedCron: '生成的 Cron',
@@ -937,11 +965,24 @@ const messages = {
       artifactFormatArchiveHelp: '默认。以归档流存储（支持加密）。',
       artifactFormatRawTreeHelp: '以 data/<path> 文件树存储并尽力保留元数据（不支持加密）。',
       encryptionEnabled: '备份加密（age）',
+      compression: '压缩',
+      compressionHelp: '作用于归档载荷。lz4 最快，xz 体积最小，none 不压缩。',
+      compressionZstd: 'zstd（均衡）',
+      compressionLz4: 'lz4（快速）',
+      compressionXz: 'xz（高压缩率）',
+      compressionNone: '不压缩',
+      compressionAdaptive: '跳过已压缩文件',
+      compressionAdaptiveHelp: '对媒体与压缩包（按扩展名或内容采样识别）直接存储而不再压缩，可加快以媒体为主的文件系统备份。',
+      compressionLevel: '压缩级别',
+      compressionLevelPlaceholder: '默认',
+      compressionLevelHelp: '可选，{min}–{max}。留空使用算法默认值。',
       encryptionHelp: '启用后将对备份数据进行端到端加密。密钥保存在本机并由 master.key 保护。',
       encryptionDisabledByRawTreeHelp: 'raw_tree_v1 不支持加密，已禁用。',
       encryptionKeyName: '加密密钥名称',
       encryptionKeyNamePlaceholder: 'default',
       encryptionKeyNameHelp: '用于区分加密密钥（默认：default）',
+      encryptionPassphraseKey: '口令保护的密钥',
+      encryptionPassphraseKeyHelp: '使用私钥由你的口令加密保护的备份密钥。需先创建该密钥；Hub 不保存口令，恢复与校验时需要输入口令。',
       targetType: '备份目标类型',
       sourceRoot: '源目录',
       sourceRootPlaceholder: '/path/to/data',
@@ -957,6 +998,22 @@ const messages = {
       fsExclude: '排除规则（Glob）',
       fsExcludePlaceholder: '**/node_modules/**',
       fsExcludeHelp: '一行一个 Glob；用于排除不需要备份的路径',
+      fsExcludePresets: '排除预设',
+      fsExcludePresetsHelp: '跳过所选路径下任意位置的常见目录',
+      fsExcludePresetNodeModules: 'node_modules',
+      fsExcludePresetCaches: '缓存目录（.cache、__pycache__）',
+      fsUseIgnoreFiles: '遵循 .bastionignore 文件',
+      fsUseIgnoreFilesHelp: '跳过源目录中 .bastionignore 文件列出的路径（.gitignore 语法）',
+      fsWindowsSystemState: 'Windows 系统状态',
+      fsWindowsSystemStateHelp: '在 Windows Agent 上额外将注册表配置单元、已安装程序和驱动列表保存到 bastion-system-state/（仅归档格式）',
+      fsAnomalyDetection: '勒索软件异常检测',
+      fsAnomalyDetectionHelp: '将每次快照与上一次对比，出现大量修改、勒索软件式扩展名或疑似加密内容时发送高优先级告警',
+      fsMaxFileSizeMiB: '最大文件大小（MiB）',
+      fsMaxFileSizeMiBHelp: '跳过大于该值的文件；留空表示不限制',
+      fsSkipOlderThanDays: '跳过早于（天）的文件',
+      fsSkipOlderThanDaysHelp: '按修改时间判断；留空表示保留旧文件',
+      fsSkipNewerThanMinutes: '跳过晚于（分钟）的文件',
+      fsSkipNewerThanMinutesHelp: '用于跳过仍在写入的文件；留空表示保留新文件',
       fsSymlinkPolicy: '符号链接策略',
       fsHardlinkPolicy: '硬链接策略',
       fsErrorPolicy: '错误策略',
@@ -975,22 +1032,94 @@ const messages = {
       sqlitePathPlaceholder: '/path/to/db.sqlite3',
       sqliteIntegrityCheck: 'SQLite 完整性校验',
       sqliteIntegrityCheckHelp: '执行 PRAGMA integrity_check（可能增加耗时）',
+      sqliteDatabases: '更多数据库',
+      sqliteDatabasesHelp: '在一次运行中将多个数据库备份到同一个归档，每个数据库可单独开启完整性校验。',
       vaultwardenDataDir: 'Vaultwarden 数据目录',
       vaultwardenDataDirPlaceholder: '/path/to/vaultwarden/data',
       vaultwardenDataDirHelp: 'Bastion 会对该目录下的 db.sqlite3 进行在线快照（无需停机）',
+      sqliteAppDataDir: '应用数据目录',
+      sqliteAppDataDirPlaceholder: '/var/lib/gitea',
+      sqliteAppDataDirHelp: '整个目录会被打包到同一个归档中',
+      sqliteAppDbPath: '数据库路径',
+      sqliteAppDbPathPlaceholder: 'data/gitea.db',
+      sqliteAppDbPathHelp: '相对于数据目录；运行中的数据库文件会被替换为在线快照（无需停机）',
+      postgresConnection: '连接字符串',
+      postgresConnectionPlaceholder: 'host=/run/postgresql user=replicator',
+      postgresConnectionHelp: '供 pg_basebackup 使用；该角色需要 REPLICATION 权限，密码请放在 ~/.pgpass 中',
+      postgresWalSpoolDir: 'WAL 暂存目录',
+      postgresWalSpoolDirPlaceholder: '/var/lib/postgresql/wal-spool',
+      postgresWalSpoolDirHelp: 'archive_command 将已完成的段复制到这里；上传成功后已发送的段会被删除',
+      postgresBaseBackupIntervalHours: '基础备份间隔（小时）',
+      postgresBaseBackupIntervalHoursPlaceholder: '24',
+      postgresBaseBackupIntervalHoursHelp: '上一次基础备份早于该间隔时，本次运行会重新做一次基础备份',
+      vmImageDomain: 'libvirt 虚拟机',
+      vmImageDomainPlaceholder: 'web01',
+      vmImageDomainHelp: '虚拟机名称或 UUID（见 virsh list --all）',
+      vmImageDisks: '磁盘',
+      vmImageDisksPlaceholder: 'vda',
+      vmImageDisksHelp: '每行一个磁盘目标名（见 virsh domblklist）；留空则备份所有基于文件的磁盘',
+      vmImageDisksAll: '所有基于文件的磁盘',
+      vmImageQuiesce: '静默客户机文件系统',
+      vmImageQuiesceHelp: '创建快照期间通过 QEMU guest agent 冻结客户机文件系统；需要客户机内安装该 agent',
+      vmImageConnectUri: '连接 URI',
+      vmImageConnectUriPlaceholder: 'qemu:///system',
+      vmImageConnectUriHelp: '可选；默认使用 virsh 的默认连接',
+      kubernetesPvcLabelSelector: 'PVC 标签选择器',
+      kubernetesPvcLabelSelectorPlaceholder: 'app=gitea',
+      kubernetesPvcLabelSelectorHelp: '运行时解析；匹配到的每个已绑定 PVC 都会被备份并记录到清单中',
+      kubernetesPvcNamespace: '命名空间',
+      kubernetesPvcNamespacePlaceholder: 'default',
+      kubernetesPvcNamespaceHelp: '可选；默认使用 Agent kubeconfig 当前上下文的命名空间',
+      kubernetesPvcMode: '模式',
+      kubernetesPvcModeHelp: '挂载模式读取挂载到 Agent Pod 中的 PVC；CSI 快照模式从 VolumeSnapshot 复制每个 PVC',
+      kubernetesPvcModeMounted: '每个 PVC 的挂载路径',
+      kubernetesPvcModeCsiSnapshot: 'CSI 卷快照',
+      kubernetesPvcMountRoot: '挂载根目录',
+      kubernetesPvcMountRootPlaceholder: '/pvc',
+      kubernetesPvcMountRootHelp: '每个 PVC 需挂载在 Agent Pod 的 <挂载根目录>/<PVC 名称>',
+      kubernetesPvcSnapshotClass: 'VolumeSnapshotClass',
+      kubernetesPvcSnapshotClassPlaceholder: 'csi-hostpath-snapclass',
+      kubernetesPvcSnapshotClassHelp: '可选；默认使用集群默认的快照类',
+      kubernetesPvcReaderImage: '读取镜像',
+      kubernetesPvcReaderImagePlaceholder: 'busybox:1.36',
+      kubernetesPvcReaderImageHelp: '用于挂载恢复出的快照的临时 Pod 镜像，需包含 tar',
+      imapHost: 'IMAP 服务器',
+      imapHostPlaceholder: 'imap.example.com',
+      imapSecurity: '连接安全',
+      imapSecurityTls: 'TLS',
+      imapSecurityStarttls: 'STARTTLS',
+      imapPort: '端口',
+      imapSecret: 'IMAP 凭据',
+      imapSecretHelp: '保存在运行该作业的节点上（密码或 OAuth2）。',
+      imapIncludeFolders: '包含文件夹',
+      imapIncludeFoldersHelp: '每行一个通配模式，层级之间用 / 分隔。留空表示全部文件夹。',
+      imapExcludeFolders: '排除文件夹',
+      imapExcludeFoldersHelp: '每行一个通配模式，例如 Trash 或 Lists/*。',
+      imapIncrementalHint: '每次运行只保存自上次成功运行以来新到的邮件。恢复完整邮箱需要按顺序恢复各个快照，因此保留策略应足以覆盖所有需要的邮件。',
+      gitRepositories: '仓库',
+      gitRepositoriesPlaceholder: "https://github.com/acme/app.git\ngit{'@'}git.example.com:ops/infra.git infra",
+      gitRepositoriesHelp: '每行一个仓库：克隆地址，可在空格后附加镜像名称（默认取自地址）。',
+      gitAuthHint: '镜像在运行之间保留在节点上，因此每次运行只拉取新对象。私有仓库使用节点自身的 SSH 密钥或 git 凭据助手认证；git 不会提示输入密码。',
       webdavBaseUrl: 'WebDAV 目标 URL',
       webdavBaseUrlPlaceholder: 'https://dav.example.com/backup',
       webdavSecret: 'WebDAV 凭据',
       localBaseDir: '备份目录',
       localBaseDirPlaceholder: '/path/to/backups',
       localBaseDirHelp: '将备份写入 <base_dir>/<job_id>/<run_id>/',
+      localLinkUnchanged: '硬链接未变化的文件',
+      localLinkUnchangedHelp: '仅限 raw-tree 快照：与上一个快照相同的文件会改为硬链接，每次运行都是可直接浏览的完整目录树，只有变化的文件占用新空间',
       partSizeMiB: '分卷大小（MiB）',
       partSizeMiBHelp: '用于大文件分块上传与断点续传',
+      webdavPartUploadConcurrency: '并行分卷上传',
+      webdavPartUploadConcurrencyHelp: '同时上传的归档分卷数（1-16）。上传中的分卷在完成前会保留在本地磁盘。',
       notificationsMode: '通知模式',
       notificationsModeHelp: '默认继承全局通知设置',
       notificationsInheritHelp: '继承全局设置：使用所有已启用的目的地',
       notifyWecomBots: '企业微信目的地',
       notifyEmails: '邮件目的地',
+      notifySlack: 'Slack 目的地',
+      notifyDiscord: 'Discord 目的地',
+      notifySms: '短信目的地',
       notifySelectPlaceholder: '选择目的地（可多选）',
       notifyEmptyMeansDisable: '不选择表示该渠道不发送通知',
       notifyDisabledSelected: '已选择已禁用的目的地：{names}。启用后才会发送。',
@@ -1006,11 +1135,36 @@ const messages = {
       filesystem: '文件备份',
       sqlite: 'SQLite 备份',
       vaultwarden: 'Vaultwarden',
+      sqlite_app: '应用（目录 + SQLite）',
+      postgres: 'PostgreSQL（WAL 归档）',
+      vm_image: '虚拟机镜像（libvirt）',
+      kubernetes_pvc: 'Kubernetes PVC',
+      imap: 'IMAP 邮箱',
+      git: 'Git 镜像',
+    },
+    imapCredential: {
+      title: '新建 IMAP 凭据',
+      name: '名称',
+      auth: '认证方式',
+      authPassword: '密码',
+      authOauth2: 'OAuth2',
+      username: '用户名',
+      password: '密码（或应用专用密码）',
+      tokenUrl: 'Token URL',
+      clientId: 'Client ID',
+      clientSecret: 'Client secret（可选）',
+      refreshToken: 'Refresh token',
+      oauth2Help: '每次运行开始时都会从 Token URL 获取新的访问令牌。',
     },
     overlap: {
       queue: '排队（不并行）',
       reject: '拒绝（运行中则拒绝）',
     },
+    schedulePreview: {
+      action: '预览后续运行时间',
+      saveFirst: '保存任务后可预览调度',
+      title: '后续运行时间（{tz}）',
+    },
     retention: {
       title: '保留策略',
       help: '自动清理快照：保留最近 N 份和/或最近 N 天；已固定的快照不会被自动删除。',
@@ -1018,6 +1172,8 @@ const messages = {
       keepDays: '保留最近 N 天（0 表示不设置）',
       maxDeletePerTick: '每次最多删除（份）',
       maxDeletePerDay: '每天最多删除（份）',
+      lockDays: '保留锁定（天）',
+      lockDaysHelp: '快照完成后 N 天内不可删除（包括手动删除）；即使未启用保留策略也生效。留空表示不锁定。',
       preview: '预览',
       applyNow: '立即执行',
       guardrail: '提示：立即执行将为本任务排队删除符合条件的快照。',
@@ -1112,11 +1268,17 @@ const messages = {
       hideJson: '隐藏 JSON',
       copyJson: '复制 JSON',
       cronPresets: '常用预设',
+      newImapCredential: '新建凭据',
       manageWebdavSecrets: '管理 WebDAV 凭据',
       manageNotificationDestinations: '管理通知目的地',
     },
     deploy: {
       title: '部署到节点',
+      mode: '模式',
+      modeCopy: '创建副本',
+      modeSync: '创建或更新',
+      modeCopyHelp: '在每个目标节点上新增一份任务副本。',
+      modeSyncHelp: '每个节点只保留一份关联副本：不存在时创建，已存在时将其配置与计划更新为与本任务一致。',
       target: '目标',
       targetLabels: '按标签',
       targetNodes: '选择节点',
@@ -1137,6 +1299,12 @@ const messages = {
         plannedName: '计划名称',
         status: '可用',
         error: '错误',
+        action: '操作',
+      },
+      actions: {
+        create: '创建',
+        update: '更新',
+        unchanged: '无变化',
       },
     },
     cronPresets: {
@@ -1210,6 +1378,7 @@ const messages = {
       maintenance: '运维',
       runtimeConfig: '运行配置',
       bulkOperations: '批量操作',
+      keypack: '主密钥备份',
       about: '关于',
     },
     overview: {
@@ -1220,6 +1389,7 @@ const messages = {
       maintenanceDesc: '运维与维护工具',
       runtimeConfigDesc: '查看并配置 Hub 的运行策略与日志（需重启生效）',
       bulkOperationsDesc: '查看与管理跨客户端的批量操作',
+      keypackDesc: '导出、恢复或托管加密的主密钥包',
       aboutDesc: '版本与构建信息',
     },
     appearance: {
@@ -1246,7 +1416,7 @@ const messages = {
     },
     hubRuntimeConfig: {
       title: 'Hub 运行配置',
-      restartRequired: '保存后需重启 Hub 生效；被命令行/环境变量覆盖的字段在此只读。',
+      restartRequired: '保存后需重启 Hub 生效；被命令行/环境变量或配置文件覆盖的字段在此只读。',
       sections: {
         startup: {
           title: '启动参数（只读）',
@@ -1275,6 +1445,25 @@ const messages = {
         hubTimezone: 'Hub 时区',
         runRetentionDays: '运行保留天数',
         incompleteCleanupDays: '不完整运行清理天数',
+        scheduleStaggerSeconds: '计划错峰窗口（秒）',
+        scheduleStaggerHelp: '将同一分钟触发的任务分散到该时间窗口内，每个任务的偏移量固定。无需重启即可生效。',
+        artifactRetentionDays: '快照数据保留天数',
+        artifactRetentionDaysPlaceholder: '保留至任务保留策略删除',
+        artifactRetentionDaysHelp: '运行结束该天数后删除已存储的快照数据（已固定和已锁定的快照除外）。运行历史遵循运行保留天数，可保留更久以便检索。无需重启即可生效。',
+        eventRetentionDays: '运行事件保留天数',
+        eventRetentionDaysPlaceholder: '随运行记录保留',
+        eventRetentionDaysHelp: '运行结束该天数后删除运行事件；错误事件和运行记录会保留。无需重启即可生效。',
+        maxEventsPerRun: '每次运行最多事件数',
+        maxEventsPerRunPlaceholder: '10000',
+        maxEventsPerRunHelp: '已结束的运行只保留开头和结尾的事件，总数不超过该上限；进度快照会合并为最后一条。对修改后结束的运行生效。',
+        sessionMaxAgeHours: '会话有效期（小时）',
+        sessionMaxAgeHoursPlaceholder: '168',
+        sessionMaxAgeHoursHelp: '登录在开始后经过该时长即失效，无论是否活跃。对修改后的新登录生效。',
+        sessionIdleTimeoutMinutes: '空闲自动登出（分钟）',
+        sessionIdleTimeoutMinutesPlaceholder: '不启用',
+        sessionIdleTimeoutMinutesHelp: '登录在无活动超过该时长后结束。留空则会话保持到过期为止。对修改后的新登录生效。',
+        restoreApprovalRequired: '恢复审批',
+        restoreApprovalRequiredHelp: '恢复到任务此前未使用过的目标时，需等待另一位管理员批准后才会执行。',
         publicBaseUrl: '公共访问地址',
         publicBaseUrlPlaceholder: 'https://backup.example.com/bastion',
         backupRetentionEnabled: '新建任务默认保留策略',
@@ -1304,6 +1493,7 @@ const messages = {
         cli: '命令行',
         env: '环境变量',
         envRustLog: 'RUST_LOG',
+        file: '配置文件',
         db: '数据库',
         default: '默认值',
       },
@@ -1313,6 +1503,63 @@ const messages = {
         never: '从不',
       },
     },
+    keypack: {
+      warning: '密钥包保护着所有已存储的凭据。请离线保存导出的文件，且不要把密钥包密码与文件放在一起。',
+      fields: {
+        currentPassword: '当前登录密码',
+        keypackPassword: '密钥包密码',
+        keypackPasswordHelp: '至少 8 个字符；导入时需要再次输入',
+        file: '密钥包文件',
+      },
+      export: {
+        title: '导出密钥包',
+        subtitle: '使用你设置的密码加密主密钥环并下载。',
+        action: '导出并下载',
+      },
+      import: {
+        title: '导入密钥包',
+        subtitle: '用密钥包文件中的主密钥环替换 Hub 当前的主密钥环。',
+        confirmReplace: '替换当前主密钥（使用密钥包中不存在的密钥加密的凭据将无法读取）',
+        action: '导入密钥包',
+        invalidFile: '所选文件不是有效的 JSON',
+        restartRequired: '密钥包已导入。重启 Hub 后生效。',
+      },
+      escrow: {
+        title: '异地托管',
+        subtitle: '主密钥环发生变化时，自动将带密码保护的密钥包推送到另一个位置。',
+        runNow: '立即托管',
+        health: {
+          disabled: '未启用',
+          ok: '已托管',
+          pending: '待托管',
+          failed: '失败',
+        },
+        targets: {
+          localDir: '本地目录',
+          webdav: 'WebDAV',
+        },
+        fields: {
+          enabled: '启用托管',
+          target: '目标位置',
+          baseDir: '目录',
+          baseUrl: 'WebDAV 地址',
+          secretName: 'WebDAV 凭据',
+          passwordKeep: '留空则保留当前密码',
+          lastSuccess: '最近托管时间',
+          lastAttempt: '最近尝试时间',
+          location: '位置',
+        },
+      },
+      audit: {
+        title: '最近的密钥包操作',
+        empty: '暂无密钥包导出或导入记录',
+        actions: {
+          exported: '已导出',
+          imported: '已导入',
+          escrowUpdated: '托管设置已修改',
+        },
+      },
+    },
     about: {
       hubTitle: 'Hub',
       uiTitle: 'Web UI',
@@ -1384,12 +1631,64 @@ const messages = {
           unignore: '取消忽略并恢复自动重试（回到“排队中”，下次尝试=现在）。',
         },
       },
+      orphans: {
+        title: '孤立快照目录',
+        open: '孤立目录',
+        help: '列出备份目标上数据库中没有对应运行或快照的作业/运行目录，例如崩溃或被强制删除的运行留下的残留。未知作业的目录只会报告，因为目标可能被共享。',
+        scan: '扫描目标',
+        summary: '已扫描 {targets} 个目标，发现 {orphans} 个孤立目录',
+        empty: '未发现孤立目录',
+        deleteSelected: '删除所选（{count}）',
+        deleteAll: '删除全部可删除项（{count}）',
+        deleteConfirm: '确定从目标上永久删除 {count} 个目录吗？',
+        columns: {
+          location: '位置',
+          reason: '原因',
+          state: '状态',
+        },
+        reason: {
+          unknown_run: '未知运行',
+          artifact_deleted: '快照已删除',
+          unknown_job: '未知作业',
+        },
+        state: {
+          deletable: '可删除',
+          reportOnly: '仅报告',
+          deleted: '已删除',
+          failed: '删除失败',
+        },
+        skipped: {
+          agentLocalDir: '已跳过：Hub 无法列出 Agent 上的本地目录',
+        },
+      },
+    },
+    secretInUse: {
+      title: '“{name}”仍在使用中',
+      help: '以下任务引用了该密钥。强制删除后，在更新这些任务之前，其备份或通知将无法正常工作。',
+      archived: '已归档',
+      deleteAnyway: '仍然删除',
+      usage: {
+        target: '备份目标',
+        source: '备份源',
+        notifications: '通知',
+      },
     },
     webdav: {
       title: 'WebDAV 凭据',
       new: '新增',
       editorTitle: '编辑 WebDAV 凭据',
       deleteConfirm: '确认删除该凭据？',
+      rotate: {
+        action: '轮换',
+        title: '轮换「{name}」',
+        help: '新凭据会与当前凭据并存。下一次使用新凭据成功连通服务器的运行会将其设为生效；在此之前运行仍使用当前凭据。',
+        pending: '轮换待验证',
+        pendingSince: '暂存于 {time}',
+        promote: '立即生效',
+        promoteConfirm: '不等待运行验证，直接让暂存凭据生效？',
+        discard: '放弃',
+        discardConfirm: '放弃暂存的凭据？',
+      },
       distribute: {
         action: '分发到客户端',
         title: '分发 “{name}” 到客户端',
@@ -1449,6 +1748,42 @@ const messages = {
         actions: '操作',
       },
     },
+    chatWebhook: {
+      title: {
+        slack: 'Slack',
+        discord: 'Discord',
+      },
+      help: {
+        slack: 'Slack Incoming Webhook 地址（https://hooks.slack.com/services/…），消息使用邮件模板。',
+        discord: 'Discord 频道 Webhook 地址（https://discord.com/api/webhooks/…），消息使用邮件模板。',
+      },
+      new: '新增',
+      editorTitle: {
+        slack: '编辑 Slack Webhook',
+        discord: '编辑 Discord Webhook',
+      },
+      deleteConfirm: '确认删除该 Webhook？',
+    },
+    sms: {
+      title: '短信（HTTP 网关）',
+      help: '通过任意 HTTP 短信网关发送简短的“Bastion <状态>: <任务>”短信。',
+      new: '新增',
+      editorTitle: '编辑短信目的地',
+      deleteConfirm: '确认删除该短信目的地？',
+      fields: {
+        name: '名称',
+        to: '接收号码',
+        urlTemplate: '网关 URL',
+        placeholdersHelp: '{{to}} 与 {{message}} 会在发送时填入（URL 编码）。',
+        method: '请求方法',
+        authHeader: '认证请求头',
+        authHeaderPlaceholder: 'Authorization: Bearer …',
+        contentType: 'Content-Type',
+        bodyTemplate: '请求体模板',
+        failuresOnly: '仅失败时发送',
+        failuresOnlyHelp: '跳过成功的运行；汇总仅在周期内有运行失败时发送。',
+      },
+    },
     smtp: {
       title: '邮件通知（SMTP）',
       new: '新增',
@@ -1484,7 +1819,7 @@ const messages = {
       },
       overview: {
         channelsDesc: '全局与渠道开关',
-        destinationsDesc: '企业微信与 SMTP 目的地（含测试）',
+        destinationsDesc: '企业微信、SMTP、Slack、Discord 与短信目的地（含测试）',
         templatesDesc: '模板与占位符',
         queueDesc: '投递记录、重试与取消',
       },
@@ -1494,7 +1829,28 @@ const messages = {
       globalEnabled: '通知',
       wecomEnabled: '企业微信机器人',
       emailEnabled: '邮件（SMTP）',
+      slackEnabled: 'Slack',
+      discordEnabled: 'Discord',
+      smsEnabled: '短信',
       globalDisabledHelp: '通知已关闭，系统不会入队或发送。',
+      digestMode: '发送方式',
+      digestModeHelp: '汇总模式下，每个周期发送一条包含所有已结束运行的汇总消息，而不是每次运行发送一条。',
+      digestModes: {
+        perRun: '每次运行发送一条',
+        daily: '每日汇总',
+        weekly: '每周汇总',
+      },
+      digestWeekday: '汇总日',
+      digestHour: '汇总时间（Hub 时区，小时）',
+      weekdays: {
+        1: '周一',
+        2: '周二',
+        3: '周三',
+        4: '周四',
+        5: '周五',
+        6: '周六',
+        7: '周日',
+      },
       enabled: '启用',
       test: '测试',
       wecomTemplate: '企业微信 Markdown 模板',
@@ -1517,6 +1873,9 @@ const messages = {
         all: '全部渠道',
         wecom: '企业微信',
         email: '邮件',
+        slack: 'Slack',
+        discord: 'Discord',
+        sms: '短信',
       },
       queue: {
         total: '共 {total} 条',
@@ -1579,6 +1938,8 @@ const messages = {
     },
     deleteTaskExecutor: '执行节点：{node}',
     pinnedTooltip: '已固定：不会被保留策略自动清理；删除需要强制确认。',
+    lockedTooltip: '保留锁定：{until} 之前不可删除。',
+    dataGoneTooltip: '快照数据已于 {at} 删除，运行记录仍保留。',
     selectionDeleteHint: '永久删除：所选快照（仅本任务）。',
     columns: {
       endedAt: '完成时间',
@@ -1630,6 +1991,7 @@ const messages = {
     filters: {
       all: '全部',
       search: '搜索运行',
+      searchEvents: '搜索运行事件',
       status: '状态',
       kind: '运行类型',
     },
@@ -1652,6 +2014,16 @@ const messages = {
       run_failed: '最近一次运行未成功完成。',
       run_rejected: '最近一次运行在执行前被拒绝。',
       run_canceled: '最近一次运行在完成前已取消。',
+      network_target: '无法连接备份目标。',
+      target_auth: '备份目标拒绝了凭据。',
+      source_permission: '部分源文件无法读取。',
+      source_consistency: '备份过程中源数据发生了变化。',
+      disk_full: '磁盘或存储配额已满。',
+      encryption_key_missing: '缺少备份加密密钥。',
+      timeout: '操作超时。',
+      canceled: '运行已取消。',
+      config: '作业或目标配置无效。',
+      unknown: '运行失败，错误未能归类。',
       generic: '最近一次运行上报了执行错误。',
     },
     badges: {
@@ -1703,6 +2075,7 @@ const messages = {
       firstErrorSeq: '首个错误事件 #{seq}',
       relatedOps: '关联操作',
       relatedArtifacts: '关联产物',
+      dataGone: '数据已于 {at} 删除',
       noFailureHint: '当前运行没有可用的结构化失败提示。',
       eventWindow: '事件窗口 {first} - {last}',
       loadOlderEvents: '加载更早事件',
@@ -1710,7 +2083,17 @@ const messages = {
       fsSnapshot: '文件系统快照',
       sqlitePath: 'SQLite 路径',
       sqliteSnapshot: 'SQLite 快照',
+      sqliteDatabases: 'SQLite 数据库',
       vaultwardenDataDir: 'Vaultwarden 数据目录',
+      sqliteAppDataDir: '应用数据目录',
+      postgresWal: '已上传的 WAL 文件',
+      postgresBaseBackup: '含基础备份',
+      vmImage: '虚拟机',
+      vmImageDisks: '{count} 个磁盘',
+      vmImageLiveSnapshot: '在线快照',
+      kubernetesPvcs: 'PVC',
+      imapMessages: '{folders} 个文件夹中有 {messages} 封新邮件',
+      gitRepositories: 'Git 仓库',
       entries: '条目：{count}',
       parts: '分片：{count}',
       warnings: '警告：{count}',
@@ -1797,6 +2180,9 @@ const messages = {
       cancelConfirm: '确认要取消本次运行吗？',
       restore: '恢复',
       verify: '校验',
+      exportEventsNdjson: '导出事件（NDJSON）',
+      exportEventsText: '导出事件（文本）',
+      downloadLog: '下载任务日志',
       openRun: '查看运行',
       openJob: '查看任务',
     },
@@ -1907,6 +2293,8 @@ const messages = {
       conflictPolicy: '冲突策略',
       selection: '恢复内容（可选）',
       selectionHelp: '不选择表示恢复全部；选择目录表示恢复其下所有文件。',
+      preview: '大小估算',
+      previewHelp: '根据快照索引汇总所选内容，并检查目的地剩余空间。',
     },
     conflict: {
       overwrite: '覆盖',
@@ -1918,6 +2306,7 @@ const messages = {
       browse: '浏览',
       pick: '选择文件/目录',
       clearSelection: '清空选择',
+      estimate: '估算',
     },
     pick: {
       title: '选择要恢复的文件/目录',
@@ -1928,6 +2317,16 @@ const messages = {
       confirm: '确认选择',
     },
     selectionSummary: '已选：{files} 文件，{dirs} 目录',
+    preview: {
+      summary: '将恢复 {files} 个文件、{dirs} 个目录，共 {size}',
+      spaceAvailable: '目的地剩余 {available}',
+      spaceInsufficient: '目的地仅剩 {available}，空间不足',
+      spaceSkipped: {
+        remote_node: '仅检查 Hub 上目录的剩余空间',
+        webdav: 'WebDAV 目的地不检查剩余空间',
+        unavailable: '无法读取目的地剩余空间',
+      },
+    },
   },
   verify: {
     title: '校验',
@@ -1936,15 +2335,37 @@ const messages = {
     actions: {
       start: '开始校验',
     },
+    mode: {
+      label: '模式',
+      full: '完整',
+      stream: '流式',
+      streamHelp: '完整解密、解压并解包快照，逐个校验文件哈希，但不写入文件，因此不需要临时空间。会跳过 SQLite 完整性检查。',
+      sample: '抽样',
+      sampleHelp: '检查清单和每个分卷的大小，并按该比例抽样校验分卷哈希。不会恢复文件。',
+    },
+  },
+  postgresPitr: {
+    title: '时间点恢复',
+    help: '在 Hub 上用所选时间之前最近的基础备份及其后上传的 WAL 重建数据目录，并配置 PostgreSQL 在该时间点停止重放。',
+    open: '恢复到某个时间点',
+    targetTime: '恢复到',
+    directory: 'Hub 目录',
+    directoryPlaceholder: '/var/lib/bastion-restore/pg',
+    directoryHelp: '必须为空。启动 PostgreSQL 时以其中的 base/ 子目录作为数据目录。',
+    start: '开始恢复',
   },
   operations: {
     title: '操作记录',
+    passphrase: '备份密钥口令',
+    passphraseHelp: '仅当快照使用口令保护的密钥加密时需要填写；口令只用于本次操作，不会被保存。',
     kind: '类型',
     kinds: {
       restore: '恢复',
       verify: '校验',
+      reencrypt: '重新加密',
     },
     statuses: {
+      pending_approval: '待审批',
       running: '运行中',
       canceling: '取消中',
       success: '成功',
@@ -1955,6 +2376,8 @@ const messages = {
       cancel: '取消',
       canceling: '取消中',
       cancelConfirm: '确认要取消该操作吗？',
+      approve: '批准',
+      approveConfirm: '确认批准该恢复并立即开始吗？',
     },
     startedAt: '开始时间',
     endedAt: '结束时间',
@@ -1988,10 +2411,19 @@ const messages = {
     runCanceled: '运行已取消',
     operationCancelRequested: '已请求取消操作',
     operationCanceled: '操作已取消',
+    operationApproved: '恢复已批准',
+    imapSecretSaved: 'IMAP 凭据已保存',
     webdavSecretSaved: 'WebDAV 凭据已保存',
     webdavSecretDeleted: 'WebDAV 凭据已删除',
+    webdavPendingStaged: '新凭据已暂存，下一次运行将进行验证',
+    webdavPendingPromoted: '暂存凭据已生效',
+    webdavPendingDiscarded: '已放弃暂存凭据',
     wecomBotSaved: '企业微信机器人已保存',
     wecomBotDeleted: '企业微信机器人已删除',
+    chatWebhookSaved: 'Webhook 已保存',
+    chatWebhookDeleted: 'Webhook 已删除',
+    smsSaved: '短信目的地已保存',
+    smsDeleted: '短信目的地已删除',
     smtpSecretSaved: 'SMTP 目的地已保存',
     smtpSecretDeleted: 'SMTP 目的地已删除',
     notificationSettingsSaved: '通知设置已保存',
@@ -2002,6 +2434,7 @@ const messages = {
     incompleteCleanupRetryScheduled: '已加入重试队列',
     incompleteCleanupIgnored: '已标记为忽略',
     incompleteCleanupUnignored: '已取消忽略',
+    orphansDeleted: '已删除 {count} 个孤立目录',
     snapshotDeleteQueued: '已加入删除队列',
     snapshotDeleteRetryQueued: '已加入重试队列',
     snapshotDeleteIgnored: '已标记为忽略',
@@ -2013,10 +2446,20 @@ const messages = {
     sourcePathsAdded: '已添加 {count} 项',
     sourcePathsSkipped: '已跳过 {count} 项（已存在）',
     hubRuntimeConfigSaved: 'Hub 运行配置已保存（重启后生效）',
+    keypackExported: '密钥包已导出',
+    keypackImported: '密钥包已导入（重启后生效）',
+    keypackEscrowSaved: '密钥包托管已是最新',
+    keypackEscrowFailed: '密钥包托管失败：{error}',
   },
   apiErrors: {
     invalid_credentials: '用户名或密码错误',
-    rate_limited: '登录过于频繁，请在 {seconds} 秒后重试',
+    admin_required: '只有管理员可以批准恢复',
+    second_admin_required: '需要由另一位管理员批准该恢复',
+    operation_not_pending: '该操作不在待审批状态',
+    rate_limited: {
+      default: '登录过于频繁，请在 {seconds} 秒后重试',
+      api_rate_limit: '请求过于频繁，请在 {seconds} 秒后重试',
+    },
     invalid_base_url: {
       default: 'WebDAV 基础地址无效',
       required: 'WebDAV 基础地址不能为空',
@@ -2053,6 +2496,10 @@ const messages = {
       required: '至少需要一个收件人邮箱',
       invalid_format: '收件人邮箱格式不正确',
     },
+    invalid_auth_header: {
+      default: '认证请求头无效',
+      invalid_format: '认证请求头格式应为“Name: value”',
+    },
     invalid_token: {
       default: '令牌无效',
       invalid_format: '令牌格式不正确',
@@ -2130,6 +2577,15 @@ const messages = {
       not_found: '未找到 WebDAV 凭据',
       invalid_payload: 'WebDAV 凭据内容无效',
     },
+    invalid_imap_secret: {
+      default: 'IMAP 凭据无效',
+      not_found: '未找到 IMAP 凭据',
+    },
+    invalid_token_url: {
+      default: 'Token URL 无效',
+      required: 'Token URL 不能为空',
+      invalid_format: 'Token URL 必须是 http(s) 地址',
+    },
     invalid_cursor: {
       default: '分页游标无效',
       invalid_encoding: '分页游标无效',
@@ -2211,6 +2667,12 @@ const messages = {
     },
     invalid_run_retention_days: '运行保留天数必须大于 0',
     invalid_incomplete_cleanup_days: '不完整运行清理天数必须大于等于 0',
+    invalid_run_ids: '请选择 1 到 1000 个运行',
+    invalid_artifact_retention_days: '快照数据保留天数必须在 {min} 到 {max} 之间',
+    invalid_event_retention_days: '运行事件保留天数必须在 {min} 到 {max} 之间',
+    invalid_max_events_per_run: '每次运行最多事件数必须在 {min} 到 {max} 之间',
+    invalid_session_max_age_hours: '会话有效期必须在 {min} 到 {max} 小时之间',
+    invalid_session_idle_timeout_minutes: '空闲自动登出时间必须在 {min} 到 {max} 分钟之间',
     invalid_log_rotation: '日志轮转值无效',
   },
   diagnostics: {
@@ -2343,6 +2805,7 @@ const messages = {
     rotateAgentKeyFailed: '轮换客户端密钥失败',
     updateAgentLabelsFailed: '更新客户端标签失败',
     syncConfigNowFailed: '触发配置同步失败',
+    runAgentDiagnosticsFailed: '运行客户端诊断失败',
     copyFailed: '复制失败',
     previewBulkOperationFailed: '预览批量操作失败',
     fetchBulkOperationsFailed: '获取批量操作列表失败',
@@ -2361,7 +2824,9 @@ const messages = {
     runNowFailed: '触发运行失败',
     cancelRunFailed: '取消运行失败',
     fetchRunsFailed: '获取运行记录失败',
+    searchRunsFailed: '搜索运行事件失败',
     cancelOperationFailed: '取消操作失败',
+    approveOperationFailed: '批准操作失败',
     fetchSnapshotsFailed: '获取快照失败',
     deleteSnapshotsFailed: '删除快照失败',
     pinSnapshotFailed: '固定快照失败',
@@ -2378,18 +2843,27 @@ const messages = {
     retentionLimitInvalid: '删除上限必须大于 0',
     consistencyThresholdInvalid: '一致性阈值必须为非负数',
     snapshotRequiresSingleSourcePath: '快照模式要求仅选择 1 个源路径',
+    previewScheduleFailed: '预览调度失败',
     previewRetentionFailed: '预览保留策略失败',
     applyRetentionFailed: '执行保留策略失败',
     fetchWebdavSecretsFailed: '获取 WebDAV 凭据列表失败',
     fetchWebdavSecretFailed: '获取 WebDAV 凭据失败',
     saveWebdavSecretFailed: '保存 WebDAV 凭据失败',
     deleteWebdavSecretFailed: '删除 WebDAV 凭据失败',
+    stageWebdavPendingFailed: '暂存 WebDAV 凭据失败',
+    promoteWebdavPendingFailed: '暂存凭据生效失败',
+    discardWebdavPendingFailed: '放弃暂存凭据失败',
     secretNameOrUsernameRequired: '名称和用户名不能为空',
     fetchWecomBotsFailed: '获取企业微信机器人列表失败',
     fetchWecomBotFailed: '获取企业微信机器人失败',
     saveWecomBotFailed: '保存企业微信机器人失败',
     deleteWecomBotFailed: '删除企业微信机器人失败',
     wecomNameOrWebhookRequired: '名称和 Webhook URL 不能为空',
+    fetchChatWebhookFailed: '获取 Webhook 失败',
+    deleteChatWebhookFailed: '删除 Webhook 失败',
+    smsRequiredFields: '名称、网关 URL 和接收号码不能为空',
+    fetchSmsFailed: '获取短信目的地失败',
+    deleteSmsFailed: '删除短信目的地失败',
     fetchSmtpSecretsFailed: '获取 SMTP 目的地列表失败',
     fetchSmtpSecretFailed: '获取 SMTP 目的地失败',
     saveSmtpSecretFailed: '保存 SMTP 目的地失败',
@@ -2409,8 +2883,15 @@ const messages = {
     incompleteCleanupRetryFailed: '重试清理任务失败',
     incompleteCleanupIgnoreFailed: '忽略清理任务失败',
     incompleteCleanupUnignoreFailed: '取消忽略清理任务失败',
+    orphanScanFailed: '扫描孤立目录失败',
+    orphanDeleteFailed: '删除孤立目录失败',
     fetchHubRuntimeConfigFailed: '获取 Hub 运行配置失败',
     saveHubRuntimeConfigFailed: '保存 Hub 运行配置失败',
+    exportKeypackFailed: '导出密钥包失败',
+    importKeypackFailed: '导入密钥包失败',
+    fetchKeypackAuditFailed: '加载密钥包操作记录失败',
+    fetchKeypackEscrowFailed: '加载密钥包托管设置失败',
+    saveKeypackEscrowFailed: '更新密钥包托管失败',
     smtpNameRequired: '名称不能为空',
     smtpHostRequired: 'SMTP 主机不能为空',
     smtpPortRequired: 'SMTP 端口不能为空',
@@ -2422,6 +2903,7 @@ const messages = {
     webdavBrowseRequiresBaseUrlAndSecret: '请先填写 WebDAV 地址并选择 WebDAV 凭据',
     webdavPrefixRootNotAllowed: '根目录不可用，请选择子目录',
     webdavRawTreeDirectConcurrencyInvalid: '并发必须在 1-128 之间',
+    webdavPartUploadConcurrencyInvalid: '并行分卷上传数必须在 1-16 之间',
     webdavRawTreeDirectQpsInvalid: 'QPS 必须在 1-{max} 之间（或留空）',
     webdavRawTreeDirectBurstInvalid: '突发容量必须在 1-{max} 之间（或留空）',
     webdavRawTreeDirectTimeoutInvalid: '超时时间必须在 1-{max} 秒之间（或留空）',
@@ -2429,14 +2911,28 @@ const messages = {
     localBaseDirRequired: '备份目录不能为空',
     partSizeInvalid: '分卷大小必须为正数',
     encryptionKeyNameRequired: '加密密钥名称不能为空',
+    compressionLevelInvalid: '压缩级别必须在 {min} 到 {max} 之间',
     sourceRootRequired: '源目录不能为空',
     sourcePathsRequired: '源路径不能为空',
     sqlitePathRequired: 'SQLite 路径不能为空',
     vaultwardenDataDirRequired: 'Vaultwarden 数据目录不能为空',
+    sqliteAppDataDirRequired: '应用数据目录不能为空',
+    sqliteAppDbPathRequired: '数据库路径不能为空',
+    sqliteAppDbPathRelative: '数据库路径必须是相对于数据目录的路径',
+    postgresConnectionRequired: '连接字符串不能为空',
+    postgresWalSpoolDirRequired: 'WAL 暂存目录不能为空',
+    vmImageDomainRequired: 'libvirt 虚拟机不能为空',
+    kubernetesPvcLabelSelectorRequired: 'PVC 标签选择器不能为空',
+    kubernetesPvcMountRootInvalid: '挂载根目录必须是绝对路径',
+    imapHostRequired: 'IMAP 服务器不能为空',
+    imapSecretRequired: 'IMAP 凭据不能为空',
+    gitRepositoriesRequired: '至少需要一个仓库',
     restoreSelectionRequired: '请选择至少一个文件或目录',
     restoreDestinationRequired: '目标目录不能为空',
     restoreStartFailed: '无法开始恢复',
+    restorePreviewFailed: '无法估算恢复大小',
     verifyStartFailed: '无法开始校验',
+    postgresPitrTargetRequired: '请选择时间并填写一个空的 Hub 目录',
     fetchRunEventsFailed: '加载运行事件失败',
     fsPathRequired: '路径不能为空',
     fsListFailed: '列出文件失败',
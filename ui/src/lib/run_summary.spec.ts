@@ -103,4 +103,85 @@ describe('parseRunSummary', () => {
     })
     expect(parsed.filesystemSnapshot).toBe(null)
   })
+
+  it('lists every database of a multi-database sqlite run', () => {
+    const parsed = parseRunSummary({
+      sqlite: {
+        path: '/a/app.db',
+        snapshot_name: 'app.db',
+        databases: [
+          { path: '/a/app.db', snapshot_name: 'app.db' },
+          { path: '/b/app.db', snapshot_name: 'app-2.db' },
+        ],
+      },
+    })
+    expect(parsed.sqlitePath).toBe('/a/app.db')
+    expect(parsed.sqliteDatabases).toEqual(['/a/app.db', '/b/app.db'])
+  })
+
+  it('parses sqlite_app data directory and database path', () => {
+    const parsed = parseRunSummary({
+      sqlite_app: {
+        data_dir: '/var/lib/gitea',
+        db_path: 'data/gitea.db',
+        consistency: { v: 2, changed_total: 2, replaced_total: 0, deleted_total: 0, read_error_total: 0, sample: [] },
+      },
+    })
+    expect(parsed.sqliteAppDataDir).toBe('/var/lib/gitea')
+    expect(parsed.sqliteAppDbPath).toBe('data/gitea.db')
+    expect(parsed.consistencyChangedTotal).toBe(2)
+  })
+
+  it('parses postgres base backup and WAL counts', () => {
+    const parsed = parseRunSummary({
+      postgres: { base_backup: true, base_backup_label: 'bastion-r1', wal_segments: 3 },
+    })
+    expect(parsed.postgresBaseBackup).toBe(true)
+    expect(parsed.postgresWalSegments).toBe(3)
+    expect(parseRunSummary({}).postgresBaseBackup).toBeNull()
+  })
+
+  it('parses vm image domain and disks', () => {
+    const parsed = parseRunSummary({
+      vm_image: {
+        domain: 'web01',
+        state: 'running',
+        live_snapshot: true,
+        disks: [{ target: 'vda', archive_name: 'disks/vda.qcow2', size: 1024 }],
+      },
+    })
+    expect(parsed.vmImageDomain).toBe('web01')
+    expect(parsed.vmImageDisks).toBe(1)
+    expect(parsed.vmImageLiveSnapshot).toBe(true)
+  })
+
+  it('parses the PVCs a kubernetes label selector resolved to', () => {
+    const parsed = parseRunSummary({
+      kubernetes_pvc: {
+        label_selector: 'app=gitea',
+        mode: 'csi_snapshot',
+        pvcs: [{ name: 'gitea-data', namespace: 'apps' }, { name: 'gitea-db', namespace: 'apps' }, {}],
+      },
+    })
+    expect(parsed.kubernetesPvcSelector).toBe('app=gitea')
+    expect(parsed.kubernetesPvcNames).toEqual(['gitea-data', 'gitea-db'])
+    expect(parseRunSummary({}).kubernetesPvcNames).toEqual([])
+  })
+
+  it('parses the message and folder counts of an imap run', () => {
+    const parsed = parseRunSummary({
+      imap: { messages: 12, folders: [{ name: 'INBOX', messages: 10 }, { name: 'Sent', messages: 2 }] },
+    })
+    expect(parsed.imapMessages).toBe(12)
+    expect(parsed.imapFolders).toBe(2)
+    expect(parseRunSummary({}).imapMessages).toBeNull()
+  })
+
+  it('lists the repositories of a git mirror run', () => {
+    const parsed = parseRunSummary({
+      git: { repositories: [{ name: 'app', refs: 12 }, { name: 'infra', refs: 3 }, {}] },
+    })
+    expect(parsed.gitRepositories).toEqual(['app', 'infra'])
+    expect(parseRunSummary({}).gitRepositories).toEqual([])
+  })
 })
@@ -39,8 +39,21 @@ export type ParsedRunSummary = {
   filesystemSnapshot: ParsedFilesystemSnapshot | null
   sqlitePath: string | null
   sqliteSnapshotName: string | null
+  sqliteDatabases: string[]
   vaultwardenDataDir: string | null
   vaultwardenDb: string | null
+  sqliteAppDataDir: string | null
+  sqliteAppDbPath: string | null
+  postgresBaseBackup: boolean | null
+  postgresWalSegments: number | null
+  vmImageDomain: string | null
+  vmImageDisks: number | null
+  vmImageLiveSnapshot: boolean | null
+  kubernetesPvcSelector: string | null
+  kubernetesPvcNames: string[]
+  imapMessages: number | null
+  imapFolders: number | null
+  gitRepositories: string[]
 }
 
 function asRecord(value: unknown): RecordValue | null {
@@ -126,8 +139,21 @@ export function parseRunSummary(summary: unknown): ParsedRunSummary {
     filesystemSnapshot: null,
     sqlitePath: null,
     sqliteSnapshotName: null,
+    sqliteDatabases: [],
     vaultwardenDataDir: null,
     vaultwardenDb: null,
+    sqliteAppDataDir: null,
+    sqliteAppDbPath: null,
+    postgresBaseBackup: null,
+    postgresWalSegments: null,
+    vmImageDomain: null,
+    vmImageDisks: null,
+    vmImageLiveSnapshot: null,
+    kubernetesPvcSelector: null,
+    kubernetesPvcNames: [],
+    imapMessages: null,
+    imapFolders: null,
+    gitRepositories: [],
   }
 
   const obj = asRecord(summary)
@@ -145,9 +171,24 @@ export function parseRunSummary(summary: unknown): ParsedRunSummary {
 
   const sqlite = asRecord(obj.sqlite)
   const vaultwarden = asRecord(obj.vaultwarden)
+  const sqliteApp = asRecord(obj.sqlite_app)
+  const postgres = asRecord(obj.postgres)
+  const vmImage = asRecord(obj.vm_image)
+  const kubernetesPvc = asRecord(obj.kubernetes_pvc)
+  const kubernetesPvcsRaw = kubernetesPvc?.pvcs
+  const imap = asRecord(obj.imap)
+  const gitRepositoriesRaw = asRecord(obj.git)?.repositories
+  const sqliteDatabasesRaw = sqlite?.databases
+  const sqliteDatabases = Array.isArray(sqliteDatabasesRaw)
+    ? sqliteDatabasesRaw
+        .map((db: unknown) => asString(asRecord(db)?.path))
+        .filter((path): path is string => path !== null)
+    : []
 
   const consistency =
-    parseConsistencyReport(filesystem?.consistency) ?? parseConsistencyReport(vaultwarden?.consistency)
+    parseConsistencyReport(filesystem?.consistency) ??
+    parseConsistencyReport(vaultwarden?.consistency) ??
+    parseConsistencyReport(sqliteApp?.consistency)
   const consistencyChangedTotal = consistency ? consistency.total : null
 
   return {
@@ -162,7 +203,24 @@ export function parseRunSummary(summary: unknown): ParsedRunSummary {
     filesystemSnapshot,
     sqlitePath: asString(sqlite?.path),
     sqliteSnapshotName: asString(sqlite?.snapshot_name),
+    sqliteDatabases,
     vaultwardenDataDir: asString(vaultwarden?.data_dir),
     vaultwardenDb: asString(vaultwarden?.db),
+    sqliteAppDataDir: asString(sqliteApp?.data_dir),
+    sqliteAppDbPath: asString(sqliteApp?.db_path),
+    postgresBaseBackup: typeof postgres?.base_backup === 'boolean' ? postgres.base_backup : null,
+    postgresWalSegments: asNumber(postgres?.wal_segments),
+    vmImageDomain: asString(vmImage?.domain),
+    vmImageDisks: Array.isArray(vmImage?.disks) ? vmImage.disks.length : null,
+    vmImageLiveSnapshot: typeof vmImage?.live_snapshot === 'boolean' ? vmImage.live_snapshot : null,
+    kubernetesPvcSelector: asString(kubernetesPvc?.label_selector),
+    kubernetesPvcNames: Array.isArray(kubernetesPvcsRaw)
+      ? kubernetesPvcsRaw.map((pvc) => asString(asRecord(pvc)?.name)).filter((name): name is string => name != null)
+      : [],
+    imapMessages: asNumber(imap?.messages),
+    imapFolders: Array.isArray(imap?.folders) ? imap.folders.length : null,
+    gitRepositories: Array.isArray(gitRepositoriesRaw)
+      ? gitRepositoriesRaw.map((repo) => asString(asRecord(repo)?.name)).filter((name): name is string => name != null)
+      : [],
   }
 }
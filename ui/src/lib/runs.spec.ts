@@ -5,6 +5,7 @@ import { runErrorLabel } from './runs'
 describe('runErrorLabel', () => {
   const t = (key: string) => {
     if (key === 'runs.errorHints.run_failed') return 'The latest run did not complete successfully.'
+    if (key === 'runs.errorHints.disk_full') return 'The disk or storage quota is full.'
     if (key === 'runs.errorHints.generic') return 'The latest run reported an execution error.'
     return key
   }
@@ -13,6 +14,10 @@ describe('runErrorLabel', () => {
     expect(runErrorLabel(t, 'run_failed')).toBe('The latest run did not complete successfully.')
   })
 
+  it('maps error catalog codes', () => {
+    expect(runErrorLabel(t, 'disk_full')).toBe('The disk or storage quota is full.')
+  })
+
   it('falls back to a generic operator-facing label for opaque error codes', () => {
     expect(runErrorLabel(t, 'worker_crashed')).toBe('The latest run reported an execution error.')
   })
@@ -0,0 +1,21 @@
+import { describe, expect, it } from 'vitest'
+
+import { ApiError } from './api'
+import { secretInUseReferences } from './secretReferences'
+
+describe('secretInUseReferences', () => {
+  it('extracts references from a secret_in_use conflict', () => {
+    const reference = { job_id: 'j1', job_name: 'Nightly', node_id: 'hub', usage: 'target', archived: false }
+    const error = new ApiError(409, 'in use', {
+      error: 'secret_in_use',
+      message: 'in use',
+      details: { references: [reference] },
+    })
+    expect(secretInUseReferences(error)).toEqual([reference])
+  })
+
+  it('ignores other errors', () => {
+    expect(secretInUseReferences(new ApiError(404, 'missing', { error: 'secret_not_found', message: 'missing' }))).toBeNull()
+    expect(secretInUseReferences(new Error('boom'))).toBeNull()
+  })
+})
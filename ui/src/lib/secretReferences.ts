@@ -0,0 +1,10 @@
+import { toApiErrorInfo } from '@/lib/errors'
+import type { SecretReference } from '@/stores/secrets'
+
+// Returns the referencing jobs when a delete was rejected with `secret_in_use`, otherwise null.
+export function secretInUseReferences(error: unknown): SecretReference[] | null {
+  const info = toApiErrorInfo(error)
+  if (info.code !== 'secret_in_use') return null
+  const details = info.details as { references?: unknown } | undefined
+  return Array.isArray(details?.references) ? (details.references as SecretReference[]) : []
+}
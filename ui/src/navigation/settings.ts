@@ -1,5 +1,5 @@
 import type { Component } from 'vue'
-import { CloudOutline, ColorPaletteOutline, ConstructOutline, InformationCircleOutline, NotificationsOutline, OptionsOutline } from '@vicons/ionicons5'
+import { CloudOutline, ColorPaletteOutline, ConstructOutline, InformationCircleOutline, KeyOutline, NotificationsOutline, OptionsOutline } from '@vicons/ionicons5'
 
 export type SettingsNavItem = {
   key: string
@@ -91,6 +91,17 @@ export const SETTINGS_NAV_ITEMS: SettingsNavItem[] = [
     showInSidebar: true,
     order: 37,
   },
+  {
+    key: 'keypack',
+    to: '/system/keypack',
+    titleKey: 'settings.menu.keypack',
+    descriptionKey: 'settings.overview.keypackDesc',
+    icon: KeyOutline,
+    domain: 'system',
+    showInOverview: true,
+    showInSidebar: true,
+    order: 38,
+  },
   {
     key: 'about',
     to: '/system/about',
@@ -19,6 +19,7 @@ const SettingsStorageView = () => import('@/views/settings/SettingsStorageView.v
 const AboutView = () => import('@/views/settings/AboutView.vue')
 const HubRuntimeConfigView = () => import('@/views/settings/HubRuntimeConfigView.vue')
 const BulkOperationsView = () => import('@/views/settings/BulkOperationsView.vue')
+const KeypackView = () => import('@/views/settings/KeypackView.vue')
 const NotificationsShellView = () => import('@/views/settings/notifications/NotificationsShellView.vue')
 const NotificationsIndexView = () => import('@/views/settings/notifications/NotificationsIndexView.vue')
 const NotificationsChannelsView = () => import('@/views/settings/notifications/NotificationsChannelsView.vue')
@@ -26,6 +27,7 @@ const NotificationsDestinationsView = () => import('@/views/settings/notificatio
 const NotificationsTemplatesView = () => import('@/views/settings/notifications/NotificationsTemplatesView.vue')
 const NotificationsQueueView = () => import('@/views/settings/notifications/NotificationsQueueView.vue')
 const MaintenanceCleanupView = () => import('@/views/settings/maintenance/MaintenanceCleanupView.vue')
+const MaintenanceOrphansView = () => import('@/views/settings/maintenance/MaintenanceOrphansView.vue')
 const RunDetailRouteView = () => import('@/views/RunDetailRouteView.vue')
 const RunsView = () => import('@/views/RunsView.vue')
 const SetupView = () => import('@/views/SetupView.vue')
@@ -374,6 +376,15 @@ const router = createRouter({
                 scopeMode: 'none',
               }),
             },
+            {
+              path: 'keypack',
+              component: KeypackView,
+              meta: mobileMeta('settings.menu.keypack', null, {
+                ...systemShellMeta,
+                secondaryNav: 'keypack',
+                scopeMode: 'none',
+              }),
+            },
             {
               path: 'appearance',
               component: AppearanceView,
@@ -405,6 +416,15 @@ const router = createRouter({
                 scopeMode: 'none',
               }),
             },
+            {
+              path: 'maintenance/orphans',
+              component: MaintenanceOrphansView,
+              meta: mobileMeta('settings.maintenance.orphans.title', null, {
+                ...systemShellMeta,
+                secondaryNav: 'maintenance',
+                scopeMode: 'none',
+              }),
+            },
           ],
         },
 
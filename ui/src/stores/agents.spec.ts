@@ -168,6 +168,7 @@ describe('useAgentsStore', () => {
     const body = JSON.parse(init.body as string) as Record<string, unknown>
     expect(body.ttl_seconds).toBe(60)
     expect(body.remaining_uses).toBeNull()
+    expect(body.name).toBeNull()
   })
 
   it('revokes an agent with CSRF header', async () => {
@@ -284,4 +285,39 @@ describe('useAgentsStore', () => {
       expect.objectContaining({ credentials: 'include' }),
     )
   })
+
+  it('runs diagnostics for one job with CSRF header', async () => {
+    const fetchMock = vi.fn().mockResolvedValue(
+      new Response(
+        JSON.stringify({
+          collected_at: 1,
+          checks: [{ check: 'staging_writable', subject: '/data', status: 'ok' }],
+        }),
+        {
+          status: 200,
+          headers: { 'Content-Type': 'application/json' },
+        },
+      ),
+    )
+    vi.stubGlobal('fetch', fetchMock)
+
+    const auth = useAuthStore()
+    auth.status = 'authenticated'
+    auth.csrfToken = 'csrf-123'
+
+    const agents = useAgentsStore()
+    const res = await agents.runDiagnostics('a b', 'job-1')
+    expect(res.checks[0]?.status).toBe('ok')
+
+    const init = fetchMock.mock.calls[0]?.[1] as RequestInit
+    const headers = init.headers as Record<string, string>
+    expect(headers['X-CSRF-Token']).toBe('csrf-123')
+    expect(init.method).toBe('POST')
+    expect(JSON.parse(init.body as string)).toEqual({ job_id: 'job-1' })
+
+    expect(fetchMock).toHaveBeenCalledWith(
+      '/api/agents/a%20b/diagnostics',
+      expect.objectContaining({ credentials: 'include' }),
+    )
+  })
 })
@@ -42,6 +42,9 @@ export type EnrollmentToken = {
   token: string
   expires_at: number
   remaining_uses: number | null
+  hub_url?: string | null
+  install_command?: string | null
+  qr_payload?: string | null
 }
 
 export type RotateAgentKeyResponse = {
@@ -73,6 +76,27 @@ export type SyncConfigNowResponse = {
   outcome: 'sent' | 'unchanged' | 'pending_offline'
 }
 
+export type AgentDiagnosticCheckKind =
+  | 'job_config'
+  | 'staging_space'
+  | 'staging_writable'
+  | 'source_access'
+  | 'target_reachable'
+
+export type AgentDiagnosticCheck = {
+  check: AgentDiagnosticCheckKind
+  job_id?: string | null
+  subject: string
+  status: 'ok' | 'warn' | 'fail'
+  message?: string | null
+  available_bytes?: number | null
+}
+
+export type AgentDiagnosticsReport = {
+  collected_at: number
+  checks: AgentDiagnosticCheck[]
+}
+
 export const useAgentsStore = defineStore('agents', () => {
   const items = ref<AgentListItem[]>([])
   const loading = ref<boolean>(false)
@@ -140,6 +164,7 @@ export const useAgentsStore = defineStore('agents', () => {
   async function createEnrollmentToken(params: {
     ttlSeconds: number
     remainingUses: number | null
+    name?: string | null
   }): Promise<EnrollmentToken> {
     const csrf = await ensureCsrfToken()
 
@@ -152,6 +177,7 @@ export const useAgentsStore = defineStore('agents', () => {
       body: JSON.stringify({
         ttl_seconds: params.ttlSeconds,
         remaining_uses: params.remainingUses,
+        name: params.name?.trim() || null,
       }),
     })
   }
@@ -187,6 +213,18 @@ export const useAgentsStore = defineStore('agents', () => {
     })
   }
 
+  async function runDiagnostics(agentId: string, jobId?: string | null): Promise<AgentDiagnosticsReport> {
+    const csrf = await ensureCsrfToken()
+    return await apiFetch<AgentDiagnosticsReport>(`/api/agents/${encodeURIComponent(agentId)}/diagnostics`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        'X-CSRF-Token': csrf,
+      },
+      body: JSON.stringify({ job_id: jobId ?? null }),
+    })
+  }
+
   return {
     items,
     loading,
@@ -201,5 +239,6 @@ export const useAgentsStore = defineStore('agents', () => {
     rotateAgentKey,
     getAgent,
     syncConfigNow,
+    runDiagnostics,
   }
 })
@@ -62,6 +62,9 @@ export type BulkSelectorRequest =
   | { node_ids: string[] }
   | { labels: string[]; labels_mode?: 'and' | 'or' }
 
+// `job_sync` creates or updates the job linked to the source on each node instead of adding copies.
+export type JobDeployKind = 'job_deploy' | 'job_sync'
+
 export type CreateBulkOperationRequest =
   | {
       kind: 'agent_labels_add' | 'agent_labels_remove'
@@ -74,7 +77,7 @@ export type CreateBulkOperationRequest =
       payload?: unknown
     }
   | {
-      kind: 'job_deploy'
+      kind: JobDeployKind
       selector: BulkSelectorRequest
       payload: { source_job_id: string; name_template?: string }
     }
@@ -104,10 +107,12 @@ export type JobDeployPreviewItem = {
   planned_name: string
   valid: boolean
   error: string | null
+  action?: 'create' | 'update' | 'unchanged'
+  existing_job_id?: string
 }
 
 export type JobDeployPreviewResponse = {
-  kind: 'job_deploy'
+  kind: JobDeployKind
   source_job_id: string
   name_template: string
   items: JobDeployPreviewItem[]
@@ -145,6 +150,7 @@ export const useBulkOperationsStore = defineStore('bulkOperations', () => {
   }
 
   async function previewJobDeploy(params: {
+    kind?: JobDeployKind
     selector: BulkSelectorRequest
     payload: { source_job_id: string; name_template?: string }
   }): Promise<JobDeployPreviewResponse> {
@@ -156,7 +162,7 @@ export const useBulkOperationsStore = defineStore('bulkOperations', () => {
         'X-CSRF-Token': csrf,
       },
       body: JSON.stringify({
-        kind: 'job_deploy',
+        kind: params.kind ?? 'job_deploy',
         selector: params.selector,
         payload: {
           source_job_id: params.payload.source_job_id,
@@ -2,7 +2,7 @@ import { defineStore } from 'pinia'
 
 import { apiFetch } from '@/lib/api'
 
-export type ConfigValueSource = 'cli' | 'env' | 'env_rust_log' | 'db' | 'default'
+export type ConfigValueSource = 'cli' | 'env' | 'env_rust_log' | 'file' | 'db' | 'default'
 
 export type PublicMetadataResponse = {
   public_base_url?: string | null
@@ -3,7 +3,7 @@ import { defineStore } from 'pinia'
 import { apiFetch } from '@/lib/api'
 import { ensureCsrfToken } from '@/stores/csrf'
 
-export type ConfigValueSource = 'cli' | 'env' | 'env_rust_log' | 'db' | 'default'
+export type ConfigValueSource = 'cli' | 'env' | 'env_rust_log' | 'file' | 'db' | 'default'
 
 export type HubRuntimeConfig = {
   hub_timezone?: string | null
@@ -15,6 +15,13 @@ export type HubRuntimeConfig = {
   log_rotation?: string | null
   log_keep_files?: number | null
   default_backup_retention?: BackupRetentionPolicy | null
+  schedule_stagger_seconds?: number | null
+  artifact_retention_days?: number | null
+  event_retention_days?: number | null
+  max_events_per_run?: number | null
+  session_max_age_hours?: number | null
+  session_idle_timeout_minutes?: number | null
+  restore_approval_required?: boolean
 }
 
 export type BackupRetentionPolicy = {
@@ -56,6 +56,8 @@ export type StorageHealthSummary = {
 export type StorageIntegrationItem = {
   name: string
   updated_at: number
+  // Set while a rotated credential is staged and not yet verified by a run.
+  pending_staged_at?: number | null
   usage_total: number
   usage: StorageUsageRef[]
   health: StorageHealthSummary
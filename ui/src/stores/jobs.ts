@@ -14,7 +14,7 @@ import { scopeFromNodeId, type ScopeValue } from '@/lib/scope'
 import { ensureCsrfToken } from '@/stores/csrf'
 
 export type OverlapPolicy = 'reject' | 'queue'
-export type JobType = 'filesystem' | 'sqlite' | 'vaultwarden'
+export type JobType = 'filesystem' | 'sqlite' | 'vaultwarden' | 'sqlite_app' | 'postgres' | 'vm_image' | 'kubernetes_pvc' | 'imap' | 'git'
 export type RunStatus = 'queued' | 'running' | 'success' | 'failed' | 'rejected' | 'canceled'
 
 export type JobListItem = {
@@ -79,6 +79,8 @@ export type JobsWorkspaceListResponse = {
 }
 
 export type JobDetail = JobListItem & {
+  schedule_jitter_seconds?: number
+  max_success_interval_seconds?: number
   spec: { v: 1; type: JobType } & Record<string, unknown>
 }
 
@@ -109,6 +111,8 @@ export type CreateOrUpdateJobRequest = {
   agent_id: string | null
   schedule: string | null
   schedule_timezone: string
+  schedule_jitter_seconds?: number
+  max_success_interval_seconds?: number
   overlap_policy: OverlapPolicy
   spec: { v: 1; type: JobType } & Record<string, unknown>
 }
@@ -134,6 +138,20 @@ export type RunListItem = {
   consistency_signal_total?: number
 }
 
+export type RunSearchResult = {
+  run_id: string
+  status: RunStatus
+  started_at: number
+  match_count: number
+  excerpts: Array<{ seq: number; ts: number; level: string; kind: string; message: string; excerpt: string }>
+}
+
+export type SearchRunsResponse = {
+  q: string
+  runs: RunSearchResult[]
+  truncated: boolean
+}
+
 export type RunEvent = {
   run_id: string
   seq: number
@@ -214,6 +232,8 @@ export type RunArtifact = {
   ended_at: number
   pinned_at?: number | null
   pinned_by_user_id?: number | null
+  locked_until?: number | null
+  deleted_at?: number | null
   source_files?: number | null
   source_dirs?: number | null
   source_bytes?: number | null
@@ -235,6 +255,7 @@ export type RetentionPolicy = {
   keep_days?: number | null
   max_delete_per_tick?: number
   max_delete_per_day?: number
+  lock_days?: number | null
 }
 
 export type RetentionPreviewItem = {
@@ -256,6 +277,17 @@ export type RetentionPreviewResponse = {
   result_truncated: boolean
 }
 
+export type ScheduleFireTime = {
+  at: number
+  local: string
+}
+
+export type SchedulePreviewResponse = {
+  schedule: string | null
+  timezone: string
+  fire_times: ScheduleFireTime[]
+}
+
 export type RetentionApplyResponse = {
   enqueued: string[]
   already_exists: number
@@ -390,6 +422,11 @@ export const useJobsStore = defineStore('jobs', () => {
     })
   }
 
+  async function searchRuns(jobId: string, q: string): Promise<SearchRunsResponse> {
+    const query = new URLSearchParams({ q })
+    return await apiFetch<SearchRunsResponse>(`/api/jobs/${encodeURIComponent(jobId)}/runs/search?${query.toString()}`)
+  }
+
   async function listRunEvents(runId: string): Promise<RunEvent[]> {
     return await apiFetch<RunEvent[]>(`/api/runs/${encodeURIComponent(runId)}/events`)
   }
@@ -466,6 +503,17 @@ export const useJobsStore = defineStore('jobs', () => {
     })
   }
 
+  async function previewJobSchedule(
+    jobId: string,
+    override: { schedule: string; timezone: string },
+    count = 10,
+  ): Promise<SchedulePreviewResponse> {
+    const query = new URLSearchParams({ schedule: override.schedule, timezone: override.timezone, count: String(count) })
+    return await apiFetch<SchedulePreviewResponse>(
+      `/api/jobs/${encodeURIComponent(jobId)}/schedule/preview?${query.toString()}`,
+    )
+  }
+
   async function applyJobRetention(jobId: string, retention: RetentionPolicy): Promise<RetentionApplyResponse> {
     const csrf = await ensureCsrfToken()
     return await apiFetch<RetentionApplyResponse>(`/api/jobs/${encodeURIComponent(jobId)}/retention/apply`, {
@@ -567,6 +615,7 @@ export const useJobsStore = defineStore('jobs', () => {
     unarchiveJob,
     runNow,
     listRuns,
+    searchRuns,
     listRunEvents,
     getRun,
     cancelRun,
@@ -574,6 +623,7 @@ export const useJobsStore = defineStore('jobs', () => {
     getJobRetention,
     putJobRetention,
     previewJobRetention,
+    previewJobSchedule,
     applyJobRetention,
     deleteJobSnapshot,
     deleteJobSnapshotsBulk,
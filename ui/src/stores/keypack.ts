@@ -0,0 +1,104 @@
+import { defineStore } from 'pinia'
+
+import { apiFetch } from '@/lib/api'
+import { ensureCsrfToken } from '@/stores/csrf'
+
+export type KeypackAuditEntry = {
+  id: number
+  ts: number
+  action: 'keypack_exported' | 'keypack_imported' | string
+  user_id: number | null
+  peer_ip: string | null
+  detail: string | null
+}
+
+export type KeypackEscrowTarget =
+  | { type: 'local_dir'; base_dir: string }
+  | { type: 'webdav'; base_url: string; secret_name: string }
+
+export type KeypackEscrowStatus = {
+  escrowed_fingerprint: string | null
+  last_success_at: number | null
+  last_attempt_at: number | null
+  last_error: string | null
+  location: string | null
+}
+
+export type KeypackEscrow = {
+  enabled: boolean
+  target: KeypackEscrowTarget | null
+  password_set: boolean
+  status: KeypackEscrowStatus
+}
+
+export type UpdateKeypackEscrowRequest = {
+  current_password: string
+  enabled: boolean
+  target: KeypackEscrowTarget | null
+  password?: string
+}
+
+export type ImportKeypackResponse = {
+  restart_required: boolean
+}
+
+export const useKeypackStore = defineStore('keypack', () => {
+  async function exportKeypack(currentPassword: string, password: string): Promise<unknown> {
+    const csrf = await ensureCsrfToken()
+    return await apiFetch<unknown>('/api/system/keypack/export', {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        'X-CSRF-Token': csrf,
+      },
+      body: JSON.stringify({ current_password: currentPassword, password }),
+    })
+  }
+
+  async function importKeypack(
+    currentPassword: string,
+    password: string,
+    keypack: unknown,
+    force: boolean,
+  ): Promise<ImportKeypackResponse> {
+    const csrf = await ensureCsrfToken()
+    return await apiFetch<ImportKeypackResponse>('/api/system/keypack/import', {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        'X-CSRF-Token': csrf,
+      },
+      body: JSON.stringify({ current_password: currentPassword, password, keypack, force }),
+    })
+  }
+
+  async function listAudit(): Promise<KeypackAuditEntry[]> {
+    return await apiFetch<KeypackAuditEntry[]>('/api/system/keypack/audit')
+  }
+
+  async function getEscrow(): Promise<KeypackEscrow> {
+    return await apiFetch<KeypackEscrow>('/api/system/keypack/escrow')
+  }
+
+  async function updateEscrow(req: UpdateKeypackEscrowRequest): Promise<KeypackEscrow> {
+    const csrf = await ensureCsrfToken()
+    return await apiFetch<KeypackEscrow>('/api/system/keypack/escrow', {
+      method: 'PUT',
+      headers: {
+        'Content-Type': 'application/json',
+        'X-CSRF-Token': csrf,
+      },
+      body: JSON.stringify(req),
+    })
+  }
+
+  async function runEscrow(): Promise<KeypackEscrow> {
+    const csrf = await ensureCsrfToken()
+    return await apiFetch<KeypackEscrow>('/api/system/keypack/escrow/run', {
+      method: 'POST',
+      headers: { 'X-CSRF-Token': csrf },
+    })
+  }
+
+  return { exportKeypack, importKeypack, listAudit, getEscrow, updateEscrow, runEscrow }
+})
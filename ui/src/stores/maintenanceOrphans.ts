@@ -0,0 +1,55 @@
+import { defineStore } from 'pinia'
+
+import { apiFetch } from '@/lib/api'
+import { ensureCsrfToken } from '@/stores/csrf'
+
+export type OrphanReason = 'unknown_run' | 'artifact_deleted' | 'unknown_job'
+
+export type OrphanDir = {
+  node_id: string
+  target_type: 'local_dir' | 'webdav' | string
+  job_id: string
+  run_id?: string
+  location: string
+  reason: OrphanReason
+  deletable: boolean
+  deleted: boolean
+  error?: string
+}
+
+export type OrphanTargetScan = {
+  node_id: string
+  target_type: 'local_dir' | 'webdav' | string
+  location: string
+  jobs_scanned: number
+  skipped?: string
+  error?: string
+}
+
+export type OrphanScanReport = {
+  targets: OrphanTargetScan[]
+  orphans: OrphanDir[]
+  deleted_total: number
+}
+
+export type OrphanScanRequest = {
+  job_id?: string
+  delete?: boolean
+  run_ids?: string[]
+}
+
+export const useMaintenanceOrphansStore = defineStore('maintenanceOrphans', () => {
+  async function scan(req: OrphanScanRequest = {}): Promise<OrphanScanReport> {
+    const csrf = await ensureCsrfToken()
+    return await apiFetch<OrphanScanReport>('/api/maintenance/orphans/scan', {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        'X-CSRF-Token': csrf,
+      },
+      body: JSON.stringify(req),
+    })
+  }
+
+  return { scan }
+})
@@ -15,8 +15,15 @@ describe('useNotificationsStore', () => {
       new Response(
         JSON.stringify({
           enabled: true,
-          channels: { wecom_bot: { enabled: true }, email: { enabled: true } },
+          channels: {
+            wecom_bot: { enabled: true },
+            email: { enabled: true },
+            slack: { enabled: true },
+            discord: { enabled: true },
+            sms: { enabled: true },
+          },
           templates: { wecom_markdown: 'w', email_subject: 's', email_body: 'b' },
+          digest: { mode: 'per_run', hour: 8, weekday: 1 },
         }),
         { status: 200, headers: { 'Content-Type': 'application/json' } },
       ),
@@ -44,8 +51,15 @@ describe('useNotificationsStore', () => {
     const store = useNotificationsStore()
     await store.saveSettings({
       enabled: true,
-      channels: { wecom_bot: { enabled: true }, email: { enabled: false } },
+      channels: {
+        wecom_bot: { enabled: true },
+        email: { enabled: false },
+        slack: { enabled: true },
+        discord: { enabled: false },
+        sms: { enabled: false },
+      },
       templates: { wecom_markdown: 'w', email_subject: 's', email_body: 'b' },
+      digest: { mode: 'per_run', hour: 8, weekday: 1 },
     })
 
     const init = fetchMock.mock.calls[0]?.[1] as RequestInit
@@ -10,15 +10,27 @@ export type NotificationsSettings = {
   channels: {
     wecom_bot: { enabled: boolean }
     email: { enabled: boolean }
+    slack: { enabled: boolean }
+    discord: { enabled: boolean }
+    sms: { enabled: boolean }
   }
   templates: {
     wecom_markdown: string
     email_subject: string
     email_body: string
   }
+  digest: {
+    mode: NotificationDigestMode
+    // Hour of day (0-23) in the hub timezone.
+    hour: number
+    // ISO weekday: 1 = Monday .. 7 = Sunday.
+    weekday: number
+  }
 }
 
-export type NotificationChannel = 'wecom_bot' | 'email'
+export type NotificationDigestMode = 'per_run' | 'daily' | 'weekly'
+
+export type NotificationChannel = 'wecom_bot' | 'email' | 'slack' | 'discord' | 'sms'
 
 export type NotificationDestinationListItem = {
   channel: NotificationChannel
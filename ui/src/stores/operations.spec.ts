@@ -77,6 +77,36 @@ describe('useOperationsStore', () => {
     )
   })
 
+  it('previews restore size with normalized selection', async () => {
+    const fetchMock = vi.fn().mockResolvedValue(
+      new Response(JSON.stringify({ files: 2, dirs: 1, symlinks: 0, bytes: 10 }), {
+        status: 200,
+        headers: { 'Content-Type': 'application/json' },
+      }),
+    )
+    vi.stubGlobal('fetch', fetchMock)
+
+    const ops = useOperationsStore()
+    const preview = await ops.previewRestore(
+      'run-1',
+      { type: 'local_fs', node_id: 'hub', directory: '/tmp/restore' },
+      { files: [' a '], dirs: ['b/'] },
+    )
+
+    expect(preview.bytes).toBe(10)
+    expect(fetchMock).toHaveBeenCalledWith(
+      '/api/runs/run-1/restore/preview',
+      expect.objectContaining({ method: 'POST' }),
+    )
+    const init = fetchMock.mock.calls[0]?.[1] as RequestInit
+    expect(init.body).toBe(
+      JSON.stringify({
+        destination: { type: 'local_fs', node_id: 'hub', directory: '/tmp/restore' },
+        selection: { files: ['a'], dirs: ['b'] },
+      }),
+    )
+  })
+
   it('starts verify with CSRF header', async () => {
     const fetchMock = vi.fn().mockResolvedValue(
       new Response(JSON.stringify({ op_id: 'op-2' }), { status: 200, headers: { 'Content-Type': 'application/json' } }),
@@ -94,6 +124,43 @@ describe('useOperationsStore', () => {
     const init = fetchMock.mock.calls[0]?.[1] as RequestInit
     const headers = init.headers as Record<string, string>
     expect(headers['X-CSRF-Token']).toBe('csrf-xyz')
+    expect(init.body).toBeUndefined()
+  })
+
+  it('sends the backup key passphrase with verify when provided', async () => {
+    const fetchMock = vi.fn().mockResolvedValue(
+      new Response(JSON.stringify({ op_id: 'op-3' }), { status: 200, headers: { 'Content-Type': 'application/json' } }),
+    )
+    vi.stubGlobal('fetch', fetchMock)
+
+    const auth = useAuthStore()
+    auth.status = 'authenticated'
+    auth.csrfToken = 'csrf-xyz'
+
+    const ops = useOperationsStore()
+    await ops.startVerify('run-3', 'hunter22')
+
+    const init = fetchMock.mock.calls[0]?.[1] as RequestInit
+    const headers = init.headers as Record<string, string>
+    expect(headers['Content-Type']).toBe('application/json')
+    expect(JSON.parse(String(init.body))).toEqual({ passphrase: 'hunter22' })
+  })
+
+  it('sends sample verify mode with percentage', async () => {
+    const fetchMock = vi.fn().mockResolvedValue(
+      new Response(JSON.stringify({ op_id: 'op-4' }), { status: 200, headers: { 'Content-Type': 'application/json' } }),
+    )
+    vi.stubGlobal('fetch', fetchMock)
+
+    const auth = useAuthStore()
+    auth.status = 'authenticated'
+    auth.csrfToken = 'csrf-xyz'
+
+    const ops = useOperationsStore()
+    await ops.startVerify('run-4', null, { mode: 'sample', samplePercent: 20 })
+
+    const init = fetchMock.mock.calls[0]?.[1] as RequestInit
+    expect(JSON.parse(String(init.body))).toEqual({ mode: 'sample', sample_percent: 20 })
   })
 
   it('cancels operation with CSRF header and JSON body', async () => {
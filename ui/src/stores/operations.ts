@@ -3,8 +3,8 @@ import { defineStore } from 'pinia'
 import { apiFetch } from '@/lib/api'
 import { ensureCsrfToken } from '@/stores/csrf'
 
-export type OperationKind = 'restore' | 'verify'
-export type OperationStatus = 'running' | 'success' | 'failed' | 'canceled'
+export type OperationKind = 'restore' | 'verify' | 'reencrypt'
+export type OperationStatus = 'pending_approval' | 'running' | 'success' | 'failed' | 'canceled'
 
 export type Operation = {
   id: string
@@ -39,6 +39,39 @@ export type RestoreDestination =
 
 export type RestoreExecutor = { node_id: string }
 
+export type RestoreSelection = { files: string[]; dirs: string[] }
+
+export type RestorePreviewSpace = {
+  status: 'checked' | 'remote_node' | 'webdav' | 'unavailable'
+  available_bytes?: number
+  sufficient?: boolean
+}
+
+export type RestorePreview = {
+  files: number
+  dirs: number
+  symlinks: number
+  bytes: number
+  space?: RestorePreviewSpace
+}
+
+export type VerifyMode = 'full' | 'stream' | 'sample'
+export type StartVerifyOptions = { mode?: VerifyMode; samplePercent?: number | null }
+
+function normalizeSelection(selection?: RestoreSelection | null): RestoreSelection | null {
+  if (selection == null) return null
+  return {
+    files: Array.from(new Set(selection.files.map((v) => v.trim()).filter((v) => v.length > 0))),
+    dirs: Array.from(
+      new Set(
+        selection.dirs
+          .map((v) => v.trim().replace(/[\\/]+$/, ''))
+          .filter((v) => v.length > 0),
+      ),
+    ),
+  }
+}
+
 export const useOperationsStore = defineStore('operations', () => {
   const cancelOperationInFlight = new Map<string, Promise<Operation>>()
 
@@ -46,23 +79,12 @@ export const useOperationsStore = defineStore('operations', () => {
     runId: string,
     destination: RestoreDestination,
     conflictPolicy: ConflictPolicy,
-    selection?: { files: string[]; dirs: string[] } | null,
+    selection?: RestoreSelection | null,
     executor?: RestoreExecutor | null,
+    passphrase?: string | null,
   ): Promise<string> {
     const csrf = await ensureCsrfToken()
-    const normalizedSelection =
-      selection == null
-        ? null
-        : {
-            files: Array.from(new Set(selection.files.map((v) => v.trim()).filter((v) => v.length > 0))),
-            dirs: Array.from(
-              new Set(
-                selection.dirs
-                  .map((v) => v.trim().replace(/[\\/]+$/, ''))
-                  .filter((v) => v.length > 0),
-              ),
-            ),
-          }
+    const normalizedSelection = normalizeSelection(selection)
     const res = await apiFetch<{ op_id: string }>(`/api/runs/${encodeURIComponent(runId)}/restore`, {
       method: 'POST',
       headers: {
@@ -74,16 +96,74 @@ export const useOperationsStore = defineStore('operations', () => {
         ...(executor?.node_id?.trim() ? { executor: { node_id: executor.node_id.trim() } } : {}),
         conflict_policy: conflictPolicy,
         ...(normalizedSelection ? { selection: normalizedSelection } : {}),
+        ...(passphrase ? { passphrase } : {}),
+      }),
+    })
+    return res.op_id
+  }
+
+  async function startPostgresPitr(
+    jobId: string,
+    targetTime: number,
+    directory: string,
+    passphrase?: string | null,
+  ): Promise<string> {
+    const csrf = await ensureCsrfToken()
+    const res = await apiFetch<{ op_id: string }>(`/api/jobs/${encodeURIComponent(jobId)}/postgres/pitr`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        'X-CSRF-Token': csrf,
+      },
+      body: JSON.stringify({
+        target_time: targetTime,
+        directory,
+        ...(passphrase ? { passphrase } : {}),
       }),
     })
     return res.op_id
   }
 
-  async function startVerify(runId: string): Promise<string> {
+  async function previewRestore(
+    runId: string,
+    destination?: RestoreDestination | null,
+    selection?: RestoreSelection | null,
+  ): Promise<RestorePreview> {
+    const normalizedSelection = normalizeSelection(selection)
+    return await apiFetch<RestorePreview>(`/api/runs/${encodeURIComponent(runId)}/restore/preview`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({
+        ...(destination ? { destination } : {}),
+        ...(normalizedSelection ? { selection: normalizedSelection } : {}),
+      }),
+    })
+  }
+
+  async function startVerify(
+    runId: string,
+    passphrase?: string | null,
+    options?: StartVerifyOptions,
+  ): Promise<string> {
     const csrf = await ensureCsrfToken()
+    const body = {
+      ...(passphrase ? { passphrase } : {}),
+      ...(options?.mode === 'sample'
+        ? {
+            mode: 'sample',
+            ...(options.samplePercent != null ? { sample_percent: options.samplePercent } : {}),
+          }
+        : options?.mode === 'stream'
+          ? { mode: 'stream' }
+          : {}),
+    }
+    const hasBody = Object.keys(body).length > 0
     const res = await apiFetch<{ op_id: string }>(`/api/runs/${encodeURIComponent(runId)}/verify`, {
       method: 'POST',
-      headers: { 'X-CSRF-Token': csrf },
+      headers: hasBody
+        ? { 'Content-Type': 'application/json', 'X-CSRF-Token': csrf }
+        : { 'X-CSRF-Token': csrf },
+      ...(hasBody ? { body: JSON.stringify(body) } : {}),
     })
     return res.op_id
   }
@@ -130,5 +210,27 @@ export const useOperationsStore = defineStore('operations', () => {
     }
   }
 
-  return { startRestore, startVerify, getOperation, listRunOperations, listEvents, cancelOperation }
+  async function approveOperation(opId: string, passphrase?: string | null): Promise<Operation> {
+    const csrf = await ensureCsrfToken()
+    return await apiFetch<Operation>(`/api/operations/${encodeURIComponent(opId)}/approve`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        'X-CSRF-Token': csrf,
+      },
+      body: JSON.stringify(passphrase ? { passphrase } : {}),
+    })
+  }
+
+  return {
+    startRestore,
+    startPostgresPitr,
+    previewRestore,
+    startVerify,
+    getOperation,
+    listRunOperations,
+    listEvents,
+    cancelOperation,
+    approveOperation,
+  }
 })
@@ -39,6 +39,7 @@ export type RunsWorkspaceListResponse = {
   page: number
   page_size: number
   total: number
+  next_cursor?: string | null
 }
 
 export type RunWorkspaceDiagnostics = {
@@ -60,6 +61,8 @@ export type RunWorkspaceCapabilities = {
 export type RunWorkspaceRelatedSummary = {
   operations_total: number
   artifacts_total: number
+  artifact_status?: string | null
+  artifact_deleted_at?: number | null
 }
 
 export type RunWorkspaceDetail = {
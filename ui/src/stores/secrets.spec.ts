@@ -46,6 +46,25 @@ describe('useSecretsStore', () => {
     expect(init.method).toBe('PUT')
   })
 
+  it('upserts slack and discord webhooks under their own kind', async () => {
+    const fetchMock = vi.fn().mockImplementation(async () => new Response(null, { status: 204 }))
+    vi.stubGlobal('fetch', fetchMock)
+
+    const auth = useAuthStore()
+    auth.status = 'authenticated'
+    auth.csrfToken = 'csrf-123'
+
+    const secrets = useSecretsStore()
+    await secrets.upsertChatWebhook('slack', 'ops', 'https://hooks.slack.com/services/x')
+    await secrets.deleteChatWebhook('discord', 'ops', { force: true })
+
+    expect(fetchMock.mock.calls[0]?.[0]).toBe('/api/secrets/slack/ops')
+    const init = fetchMock.mock.calls[0]?.[1] as RequestInit
+    expect(init.method).toBe('PUT')
+    expect(JSON.parse(String(init.body))).toEqual({ webhook_url: 'https://hooks.slack.com/services/x' })
+    expect(fetchMock.mock.calls[1]?.[0]).toBe('/api/secrets/discord/ops?force=true')
+  })
+
   it('refreshes smtp secrets list', async () => {
     const fetchMock = vi.fn().mockResolvedValue(
       new Response(JSON.stringify([{ name: 'primary', updated_at: 1 }]), {
@@ -89,4 +108,63 @@ describe('useSecretsStore', () => {
     expect(headers['X-CSRF-Token']).toBe('csrf-123')
     expect(init.method).toBe('PUT')
   })
+
+  it('deletes webdav secret with force only when requested', async () => {
+    const fetchMock = vi.fn().mockImplementation(async () => new Response(null, { status: 204 }))
+    vi.stubGlobal('fetch', fetchMock)
+
+    const auth = useAuthStore()
+    auth.status = 'authenticated'
+    auth.csrfToken = 'csrf-123'
+
+    const secrets = useSecretsStore()
+    await secrets.deleteWebdav('hub', 'primary')
+    await secrets.deleteWebdav('hub', 'primary', { force: true })
+
+    expect(fetchMock.mock.calls[0]?.[0]).toBe('/api/nodes/hub/secrets/webdav/primary')
+    expect(fetchMock.mock.calls[1]?.[0]).toBe('/api/nodes/hub/secrets/webdav/primary?force=true')
+    expect((fetchMock.mock.calls[1]?.[1] as RequestInit).method).toBe('DELETE')
+  })
+
+  it('stages, promotes and discards a pending webdav credential', async () => {
+    const fetchMock = vi.fn().mockImplementation(async () => new Response(null, { status: 204 }))
+    vi.stubGlobal('fetch', fetchMock)
+
+    const auth = useAuthStore()
+    auth.status = 'authenticated'
+    auth.csrfToken = 'csrf-123'
+
+    const secrets = useSecretsStore()
+    await secrets.stageWebdavPending('agent1', 'nas', 'u', 'new')
+    await secrets.promoteWebdavPending('agent1', 'nas')
+    await secrets.discardWebdavPending('agent1', 'nas')
+
+    expect(fetchMock.mock.calls[0]?.[0]).toBe('/api/nodes/agent1/secrets/webdav/nas/pending')
+    expect((fetchMock.mock.calls[0]?.[1] as RequestInit).method).toBe('PUT')
+    expect((fetchMock.mock.calls[0]?.[1] as RequestInit).body).toBe(JSON.stringify({ username: 'u', password: 'new' }))
+    expect(fetchMock.mock.calls[1]?.[0]).toBe('/api/nodes/agent1/secrets/webdav/nas/pending/promote')
+    expect((fetchMock.mock.calls[1]?.[1] as RequestInit).method).toBe('POST')
+    expect(fetchMock.mock.calls[2]?.[0]).toBe('/api/nodes/agent1/secrets/webdav/nas/pending')
+    expect((fetchMock.mock.calls[2]?.[1] as RequestInit).method).toBe('DELETE')
+  })
+
+  it('upserts imap credentials as the tagged payload', async () => {
+    const fetchMock = vi.fn().mockImplementation(async () => new Response(null, { status: 204 }))
+    vi.stubGlobal('fetch', fetchMock)
+
+    const auth = useAuthStore()
+    auth.status = 'authenticated'
+    auth.csrfToken = 'csrf-123'
+
+    const secrets = useSecretsStore()
+    await secrets.upsertImap('agent1', 'mail', { auth: 'password', username: 'me', password: 'pw' })
+    await secrets.deleteImap('agent1', 'mail', { force: true })
+
+    expect(fetchMock.mock.calls[0]?.[0]).toBe('/api/nodes/agent1/secrets/imap/mail')
+    expect((fetchMock.mock.calls[0]?.[1] as RequestInit).method).toBe('PUT')
+    expect((fetchMock.mock.calls[0]?.[1] as RequestInit).body).toBe(
+      JSON.stringify({ auth: 'password', username: 'me', password: 'pw' }),
+    )
+    expect(fetchMock.mock.calls[1]?.[0]).toBe('/api/nodes/agent1/secrets/imap/mail?force=true')
+  })
 })
@@ -20,6 +20,29 @@ export type WecomBotSecret = {
   webhook_url: string
 }
 
+// Slack and Discord destinations only hold a webhook URL.
+export type ChatWebhookKind = 'slack' | 'discord'
+
+export type ChatWebhookSecret = {
+  name: string
+  webhook_url: string
+}
+
+export type SmsGatewayMethod = 'get' | 'post'
+
+export type SmsGatewayConfig = {
+  // `{{to}}` and `{{message}}` are filled in per message.
+  url_template: string
+  method: SmsGatewayMethod
+  auth_header?: string | null
+  body_template?: string | null
+  content_type?: string | null
+  to: string
+  failures_only: boolean
+}
+
+export type SmsSecret = SmsGatewayConfig & { name: string }
+
 export type SmtpTlsMode = 'none' | 'starttls' | 'implicit'
 
 export type SmtpSecret = {
@@ -33,6 +56,36 @@ export type SmtpSecret = {
   tls: SmtpTlsMode
 }
 
+export type ImapCredentials =
+  | { auth: 'password'; username: string; password: string }
+  | {
+      auth: 'oauth2'
+      username: string
+      token_url: string
+      client_id: string
+      client_secret?: string | null
+      refresh_token: string
+    }
+
+export type ImapSecret = ImapCredentials & { name: string }
+
+export type SecretReference = {
+  job_id: string
+  job_name: string
+  node_id: string
+  usage: 'target' | 'source' | 'notifications'
+  archived: boolean
+}
+
+export type DeleteSecretOptions = {
+  // Delete even when jobs still reference the secret.
+  force?: boolean
+}
+
+function deleteQuery(options?: DeleteSecretOptions): string {
+  return options?.force ? '?force=true' : ''
+}
+
 export const useSecretsStore = defineStore('secrets', () => {
   const webdav = ref<SecretListItem[]>([])
   const loadingWebdav = ref<boolean>(false)
@@ -40,6 +93,8 @@ export const useSecretsStore = defineStore('secrets', () => {
   const loadingWecomBots = ref<boolean>(false)
   const smtp = ref<SecretListItem[]>([])
   const loadingSmtp = ref<boolean>(false)
+  const imap = ref<SecretListItem[]>([])
+  const loadingImap = ref<boolean>(false)
 
   function webdavBase(nodeId: string): string {
     return `/api/nodes/${encodeURIComponent(nodeId)}/secrets/webdav`
@@ -71,9 +126,92 @@ export const useSecretsStore = defineStore('secrets', () => {
     })
   }
 
-  async function deleteWebdav(nodeId: string, name: string): Promise<void> {
+  async function deleteWebdav(nodeId: string, name: string, options?: DeleteSecretOptions): Promise<void> {
     const csrf = await ensureCsrfToken()
-    await apiFetch<void>(`${webdavBase(nodeId)}/${encodeURIComponent(name)}`, {
+    await apiFetch<void>(`${webdavBase(nodeId)}/${encodeURIComponent(name)}${deleteQuery(options)}`, {
+      method: 'DELETE',
+      headers: { 'X-CSRF-Token': csrf },
+      expectedStatus: 204,
+    })
+  }
+
+  async function stageWebdavPending(nodeId: string, name: string, username: string, password: string): Promise<void> {
+    const csrf = await ensureCsrfToken()
+    await apiFetch<void>(`${webdavBase(nodeId)}/${encodeURIComponent(name)}/pending`, {
+      method: 'PUT',
+      headers: {
+        'Content-Type': 'application/json',
+        'X-CSRF-Token': csrf,
+      },
+      body: JSON.stringify({ username, password }),
+      expectedStatus: 204,
+    })
+  }
+
+  async function promoteWebdavPending(nodeId: string, name: string): Promise<void> {
+    const csrf = await ensureCsrfToken()
+    await apiFetch<void>(`${webdavBase(nodeId)}/${encodeURIComponent(name)}/pending/promote`, {
+      method: 'POST',
+      headers: { 'X-CSRF-Token': csrf },
+      expectedStatus: 204,
+    })
+  }
+
+  async function discardWebdavPending(nodeId: string, name: string): Promise<void> {
+    const csrf = await ensureCsrfToken()
+    await apiFetch<void>(`${webdavBase(nodeId)}/${encodeURIComponent(name)}/pending`, {
+      method: 'DELETE',
+      headers: { 'X-CSRF-Token': csrf },
+      expectedStatus: 204,
+    })
+  }
+
+  async function getChatWebhook(kind: ChatWebhookKind, name: string): Promise<ChatWebhookSecret> {
+    return await apiFetch<ChatWebhookSecret>(`/api/secrets/${kind}/${encodeURIComponent(name)}`)
+  }
+
+  async function upsertChatWebhook(kind: ChatWebhookKind, name: string, webhookUrl: string): Promise<void> {
+    const csrf = await ensureCsrfToken()
+    await apiFetch<void>(`/api/secrets/${kind}/${encodeURIComponent(name)}`, {
+      method: 'PUT',
+      headers: {
+        'Content-Type': 'application/json',
+        'X-CSRF-Token': csrf,
+      },
+      body: JSON.stringify({ webhook_url: webhookUrl }),
+      expectedStatus: 204,
+    })
+  }
+
+  async function deleteChatWebhook(kind: ChatWebhookKind, name: string, options?: DeleteSecretOptions): Promise<void> {
+    const csrf = await ensureCsrfToken()
+    await apiFetch<void>(`/api/secrets/${kind}/${encodeURIComponent(name)}${deleteQuery(options)}`, {
+      method: 'DELETE',
+      headers: { 'X-CSRF-Token': csrf },
+      expectedStatus: 204,
+    })
+  }
+
+  async function getSms(name: string): Promise<SmsSecret> {
+    return await apiFetch<SmsSecret>(`/api/secrets/sms/${encodeURIComponent(name)}`)
+  }
+
+  async function upsertSms(name: string, config: SmsGatewayConfig): Promise<void> {
+    const csrf = await ensureCsrfToken()
+    await apiFetch<void>(`/api/secrets/sms/${encodeURIComponent(name)}`, {
+      method: 'PUT',
+      headers: {
+        'Content-Type': 'application/json',
+        'X-CSRF-Token': csrf,
+      },
+      body: JSON.stringify(config),
+      expectedStatus: 204,
+    })
+  }
+
+  async function deleteSms(name: string, options?: DeleteSecretOptions): Promise<void> {
+    const csrf = await ensureCsrfToken()
+    await apiFetch<void>(`/api/secrets/sms/${encodeURIComponent(name)}${deleteQuery(options)}`, {
       method: 'DELETE',
       headers: { 'X-CSRF-Token': csrf },
       expectedStatus: 204,
@@ -106,9 +244,9 @@ export const useSecretsStore = defineStore('secrets', () => {
     })
   }
 
-  async function deleteWecomBot(name: string): Promise<void> {
+  async function deleteWecomBot(name: string, options?: DeleteSecretOptions): Promise<void> {
     const csrf = await ensureCsrfToken()
-    await apiFetch<void>(`/api/secrets/wecom-bot/${encodeURIComponent(name)}`, {
+    await apiFetch<void>(`/api/secrets/wecom-bot/${encodeURIComponent(name)}${deleteQuery(options)}`, {
       method: 'DELETE',
       headers: { 'X-CSRF-Token': csrf },
       expectedStatus: 204,
@@ -144,9 +282,48 @@ export const useSecretsStore = defineStore('secrets', () => {
     })
   }
 
-  async function deleteSmtp(name: string): Promise<void> {
+  async function deleteSmtp(name: string, options?: DeleteSecretOptions): Promise<void> {
     const csrf = await ensureCsrfToken()
-    await apiFetch<void>(`/api/secrets/smtp/${encodeURIComponent(name)}`, {
+    await apiFetch<void>(`/api/secrets/smtp/${encodeURIComponent(name)}${deleteQuery(options)}`, {
+      method: 'DELETE',
+      headers: { 'X-CSRF-Token': csrf },
+      expectedStatus: 204,
+    })
+  }
+
+  function imapBase(nodeId: string): string {
+    return `/api/nodes/${encodeURIComponent(nodeId)}/secrets/imap`
+  }
+
+  async function refreshImap(nodeId: string): Promise<void> {
+    loadingImap.value = true
+    try {
+      imap.value = await apiFetch<SecretListItem[]>(imapBase(nodeId))
+    } finally {
+      loadingImap.value = false
+    }
+  }
+
+  async function getImap(nodeId: string, name: string): Promise<ImapSecret> {
+    return await apiFetch<ImapSecret>(`${imapBase(nodeId)}/${encodeURIComponent(name)}`)
+  }
+
+  async function upsertImap(nodeId: string, name: string, credentials: ImapCredentials): Promise<void> {
+    const csrf = await ensureCsrfToken()
+    await apiFetch<void>(`${imapBase(nodeId)}/${encodeURIComponent(name)}`, {
+      method: 'PUT',
+      headers: {
+        'Content-Type': 'application/json',
+        'X-CSRF-Token': csrf,
+      },
+      body: JSON.stringify(credentials),
+      expectedStatus: 204,
+    })
+  }
+
+  async function deleteImap(nodeId: string, name: string, options?: DeleteSecretOptions): Promise<void> {
+    const csrf = await ensureCsrfToken()
+    await apiFetch<void>(`${imapBase(nodeId)}/${encodeURIComponent(name)}${deleteQuery(options)}`, {
       method: 'DELETE',
       headers: { 'X-CSRF-Token': csrf },
       expectedStatus: 204,
@@ -160,17 +337,32 @@ export const useSecretsStore = defineStore('secrets', () => {
     getWebdav,
     upsertWebdav,
     deleteWebdav,
+    stageWebdavPending,
+    promoteWebdavPending,
+    discardWebdavPending,
     wecomBots,
     loadingWecomBots,
     refreshWecomBots,
     getWecomBot,
     upsertWecomBot,
     deleteWecomBot,
+    getChatWebhook,
+    upsertChatWebhook,
+    deleteChatWebhook,
+    getSms,
+    upsertSms,
+    deleteSms,
     smtp,
     loadingSmtp,
     refreshSmtp,
     getSmtp,
     upsertSmtp,
     deleteSmtp,
+    imap,
+    loadingImap,
+    refreshImap,
+    getImap,
+    upsertImap,
+    deleteImap,
   }
 })
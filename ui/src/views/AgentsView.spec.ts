@@ -134,6 +134,7 @@ vi.mock('naive-ui', async () => {
     NModal: modal,
     NPagination: stub('NPagination'),
     NPopconfirm: stub('NPopconfirm'),
+    NQrCode: createNaiveStub('NQrCode', { props: ['value', 'size'] }),
     NRadioButton: stub('NRadioButton'),
     NRadioGroup: stub('NRadioGroup'),
     NSelect: stub('NSelect'),
@@ -222,6 +223,31 @@ describe('AgentsView enrollment token modal', () => {
     expect(command!).toContain('https://backup.example.com')
   })
 
+  it('prefers the hub-generated install command and renders the enrollment QR code', async () => {
+    agentsApi.createEnrollmentToken.mockResolvedValue({
+      token: 'tok2',
+      expires_at: 1234,
+      remaining_uses: null,
+      hub_url: 'https://backup.example.com',
+      install_command: "bastion agent --hub-url https://backup.example.com --enroll-token tok2 --name 'edge 1'",
+      qr_payload: '{"v":1,"hub_url":"https://backup.example.com","token":"tok2"}',
+    })
+
+    const wrapper = mount(AgentsView)
+    await flushPromises()
+
+    await wrapper.findAll('button').find((b) => b.text() === 'agents.newToken')!.trigger('click')
+    await wrapper.findAll('button').find((b) => b.text() === 'agents.tokenModal.create')!.trigger('click')
+    await flushPromises()
+
+    const values = wrapper
+      .findAll('textarea[data-stub="NInput"], input[data-stub="NInput"]')
+      .map((n) => String((n.element as HTMLInputElement).value))
+    expect(values).toContain("bastion agent --hub-url https://backup.example.com --enroll-token tok2 --name 'edge 1'")
+    expect(wrapper.find('[data-testid="enrollment-qr"]').exists()).toBe(true)
+    expect(wrapper.text()).toContain('agents.tokenModal.qrHelp')
+  })
+
   it('renders mobile progressive disclosure affordance for secondary metadata', async () => {
     mediaState.desktop = false
     fleetApi.list.mockResolvedValue(buildFleetResponse({
@@ -13,6 +13,7 @@ const agentsApi = {
   syncConfigNow: vi.fn(),
   rotateAgentKey: vi.fn(),
   revokeAgent: vi.fn(),
+  runDiagnostics: vi.fn(),
 }
 
 const uiStore = { locale: 'en-US' }
@@ -144,6 +145,13 @@ describe('FleetAgentDetailView', () => {
     agentsApi.syncConfigNow.mockResolvedValue({ outcome: 'sent' })
     agentsApi.rotateAgentKey.mockResolvedValue({ agent_id: 'edge-a', agent_key: 'new-key' })
     agentsApi.revokeAgent.mockResolvedValue(undefined)
+    agentsApi.runDiagnostics.mockResolvedValue({
+      collected_at: 300,
+      checks: [
+        { check: 'staging_space', subject: '/var/lib/bastion', status: 'warn', message: 'less than 1 GiB free', available_bytes: 1024 },
+        { check: 'source_access', job_id: 'job-1', subject: '/srv/data', status: 'fail', message: 'permission denied' },
+      ],
+    })
   })
 
   it('renders detail sections from fleet response', async () => {
@@ -170,4 +178,20 @@ describe('FleetAgentDetailView', () => {
       query: { scope: 'agent:edge-a' },
     })
   })
+
+  it('runs diagnostics and lists the reported checks', async () => {
+    const wrapper = mount(FleetAgentDetailView)
+    await flushPromises()
+
+    const button = wrapper.findAll('button').find((b) => b.text() === 'agents.actions.runDiagnostics')
+    expect(button).toBeTruthy()
+    await button!.trigger('click')
+    await flushPromises()
+
+    expect(agentsApi.runDiagnostics).toHaveBeenCalledWith('edge-a')
+    expect(wrapper.text()).toContain('agents.diagnostics.checks.source_access')
+    expect(wrapper.text()).toContain('/srv/data')
+    expect(wrapper.text()).toContain('permission denied')
+    expect(wrapper.text()).toContain('agents.diagnostics.status.warn')
+  })
 })
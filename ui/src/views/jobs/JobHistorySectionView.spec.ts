@@ -11,6 +11,7 @@ const messageApi = {
 
 const jobsApi = {
   listRuns: vi.fn(),
+  searchRuns: vi.fn(),
 }
 
 const routeApi = {
@@ -81,6 +82,7 @@ vi.mock('naive-ui', async () => {
     NCard: stub('NCard'),
     NDataTable: dataTable,
     NIcon: stub('NIcon'),
+    NInput: stub('NInput'),
     NSpace: stub('NSpace'),
     NTag: stub('NTag'),
     useMessage: () => messageApi,
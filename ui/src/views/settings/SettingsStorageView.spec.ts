@@ -17,6 +17,9 @@ const secretsApi = {
   getWebdav: vi.fn(),
   upsertWebdav: vi.fn(),
   deleteWebdav: vi.fn(),
+  stageWebdavPending: vi.fn(),
+  promoteWebdavPending: vi.fn(),
+  discardWebdavPending: vi.fn(),
 }
 
 const agentsApi = {
@@ -164,4 +167,31 @@ describe('SettingsStorageView', () => {
     expect(wrapper.text()).toContain('integrations.storage.health.attention')
     expect(wrapper.text()).toContain('Nightly DB')
   })
+
+  it('shows a staged rotation and stages a new credential from the rotate dialog', async () => {
+    const storage = await integrationsApi.getStorage()
+    storage.items[0].pending_staged_at = 120
+    integrationsApi.getStorage.mockResolvedValue(storage)
+    secretsApi.getWebdav.mockResolvedValue({ name: 'primary', username: 'backup', password: 'old' })
+    secretsApi.stageWebdavPending.mockResolvedValue(undefined)
+
+    const wrapper = mount(SettingsStorageView)
+    await flushPromises()
+
+    expect(wrapper.text()).toContain('settings.webdav.rotate.pending')
+
+    const rotate = wrapper.findAll('button').find((b) => b.text() === 'settings.webdav.rotate.action')
+    expect(rotate).toBeTruthy()
+    await rotate!.trigger('click')
+    await flushPromises()
+    expect(secretsApi.getWebdav).toHaveBeenCalledWith('hub', 'primary')
+
+    // The editor and rotate dialogs both render a save button; the rotate one comes second.
+    const saves = wrapper.findAll('button').filter((b) => b.text() === 'common.save')
+    await saves[1]!.trigger('click')
+    await flushPromises()
+
+    expect(secretsApi.stageWebdavPending).toHaveBeenCalledWith('hub', 'primary', 'backup', '')
+    expect(messageApi.success).toHaveBeenCalledWith('messages.webdavPendingStaged')
+  })
 })
@@ -20,6 +20,10 @@ vi.mock('@/stores/ui', () => ({
   useUiStore: () => ({ locale: 'en-US' }),
 }))
 
+vi.mock('vue-router', () => ({
+  useRouter: () => ({ push: vi.fn() }),
+}))
+
 vi.mock('vue-i18n', () => ({
   useI18n: () => ({ t: (key: string) => key }),
 }))